@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: UNLICENSED
+
+pragma solidity ^0.7.0;
+
+/// @dev Optional metadata functions of the ERC20 standard.
+interface IERC20Metadata {
+    /// @dev Returns the symbol of the token.
+    function symbol() external view returns (string memory);
+
+    /// @dev Returns the number of decimals used to get the user representation of the token amounts.
+    function decimals() external view returns (uint8);
+}
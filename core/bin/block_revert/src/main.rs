@@ -19,6 +19,14 @@ async fn revert_blocks_in_storage(
 ) -> anyhow::Result<()> {
     let mut transaction = storage.start_transaction().await?;
 
+    // Volume of the reverted blocks is calculated from their executed operations,
+    // so it has to be subtracted before they are returned to the mempool.
+    transaction
+        .chain()
+        .stats_schema()
+        .revert_token_volume(last_block)
+        .await?;
+    println!("`token_volume_stats` table is updated");
     transaction
         .chain()
         .mempool_schema()
@@ -87,6 +87,8 @@ fn create_deposit(from: Address, to: Address, amount: u32) -> ExecutedOperations
         eth_hash: H256::zero(),
         eth_block: 0,
         eth_block_index: None,
+        eth_block_hash: None,
+        l1_surplus: None,
     };
     let executed_deposit_op = ExecutedPriorityOp {
         priority_op: priority_operation,
@@ -518,6 +518,8 @@ impl TreeState {
                 eth_hash: H256::zero(),
                 eth_block: 0,
                 eth_block_index: None,
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             block_index,
             created_at: chrono::Utc::now(),
@@ -36,6 +36,12 @@ where
     let opt = Opt::from_args();
     let worker_name = opt.worker_name;
 
+    // The exporter goes first, since the metrics emitted before the recorder is installed are lost.
+    if run_prometheus {
+        let prom_config = PrometheusConfig::from_env();
+        run_prometheus_exporter(prom_config.bind_addr());
+    }
+
     // used env
     let prover_options = EnvProverConfig::from_env();
     let prover_config = <PROVER as ProverImpl>::Config::from_env();
@@ -68,11 +74,6 @@ where
         .expect("Failed to register ctrlc handler");
     }
 
-    if run_prometheus {
-        let prom_config = PrometheusConfig::from_env();
-        run_prometheus_exporter(prom_config.port);
-    }
-
     prover_work_cycle(
         prover,
         api_client,
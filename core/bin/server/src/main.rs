@@ -6,8 +6,17 @@ use structopt::StructOpt;
 
 use serde::{Deserialize, Serialize};
 
+use zksync_api::api_server::mempool_resync::{
+    start_mempool_resync_checker, SubmittedTxs, SUBMITTED_TXS_CAPACITY,
+};
+use zksync_api::api_server::sender_limiter::SenderRateLimiter;
+use zksync_api::api_server::shutdown::ShutdownController;
 use zksync_api::fee_ticker::{run_updaters, FeeTicker, TickerInfo};
-use zksync_core::{genesis_init, run_core, wait_for_tasks};
+use zksync_api::mempool_analyzer::{run_mempool_analyzer, MempoolAnalyzer};
+use zksync_api::mempool_revalidator::{
+    run_mempool_revalidator, run_subsidy_releaser, MempoolRevalidator, SUBSIDY_RELEASE_INTERVAL,
+};
+use zksync_core::{bootstrap_init, genesis_init, replay_init, run_core, wait_for_tasks};
 use zksync_eth_client::EthereumGateway;
 use zksync_forced_exit_requests::run_forced_exit_requests_actors;
 use zksync_gateway_watcher::run_gateway_watcher_if_multiplexed;
@@ -24,12 +33,15 @@ use zksync_core::rejected_tx_cleaner::run_rejected_tx_cleaner;
 use zksync_mempool::run_mempool_tx_handler;
 use zksync_prometheus_exporter::{run_operation_counter, run_prometheus_exporter};
 use zksync_storage::ConnectionPool;
+use zksync_types::BlockNumber;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
 #[derive(Debug, Clone, Copy)]
 pub enum ServerCommand {
     Genesis,
+    Bootstrap,
+    Replay { from: BlockNumber, to: BlockNumber },
     Launch,
 }
 
@@ -116,6 +128,15 @@ struct Opt {
     /// Generate genesis block for the first contract deployment
     #[structopt(long)]
     genesis: bool,
+    /// Create the genesis state and initial tokens, does nothing if the database is already bootstrapped
+    #[structopt(long, conflicts_with = "genesis")]
+    bootstrap: bool,
+    /// Re-execute the stored blocks starting from this one and compare the results with the stored ones
+    #[structopt(long, conflicts_with_all = &["genesis", "bootstrap"])]
+    replay_from: Option<BlockNumber>,
+    /// Last block to replay, defaults to the first one
+    #[structopt(long, requires = "replay-from")]
+    replay_to: Option<BlockNumber>,
     /// comma-separated list of components to launch
     #[structopt(
         long,
@@ -130,6 +151,13 @@ async fn main() -> anyhow::Result<()> {
     let mut _vlog_guard = None;
     let server_mode = if opt.genesis {
         ServerCommand::Genesis
+    } else if opt.bootstrap {
+        ServerCommand::Bootstrap
+    } else if let Some(from) = opt.replay_from {
+        ServerCommand::Replay {
+            from,
+            to: opt.replay_to.unwrap_or(from),
+        }
     } else {
         _vlog_guard = Some(vlog::init());
         ServerCommand::Launch
@@ -142,6 +170,17 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let ServerCommand::Bootstrap = server_mode {
+        vlog::info!("Performing the server bootstrap");
+        let config = ChainConfig::from_env();
+        return bootstrap_init(&config).await;
+    }
+
+    if let ServerCommand::Replay { from, to } = server_mode {
+        vlog::info!("Replaying the blocks #{}..#{}", from, to);
+        return replay_init(from, to).await;
+    }
+
     // It's a `ServerCommand::Launch`, perform the usual routine.
     vlog::info!("Running the zkSync server");
 
@@ -154,15 +193,31 @@ async fn run_server(components: &ComponentsToRun) {
     let connection_pool = ConnectionPool::new(None);
     let read_only_connection_pool = ConnectionPool::new_readonly_pool(None);
     let (stop_signal_sender, mut stop_signal_receiver) = mpsc::channel(256);
+    // API servers (including the private core API) are stopped gracefully, letting the in-flight requests finish.
+    let api_shutdown = ShutdownController::new();
 
     let mut tasks = vec![];
 
+    if components.0.contains(&Component::Prometheus) {
+        // Run prometheus data exporter.
+        // It goes first, since the metrics emitted before the recorder is installed are lost.
+        let config = PrometheusConfig::from_env();
+        let prometheus_task_handle = run_prometheus_exporter(config.bind_addr());
+        tasks.push(prometheus_task_handle);
+        // We can run them only with active prometheus
+        if components.0.contains(&Component::PrometheusPeriodicMetrics) {
+            let counter_task_handle = run_operation_counter(read_only_connection_pool.clone());
+            tasks.push(counter_task_handle);
+        }
+    }
+
     if components.0.contains(&Component::Web3Api) {
         // Run web3 api
         tasks.push(zksync_api::api_server::web3::start_rpc_server(
             connection_pool.clone(),
             &Web3Config::from_env(),
             &TokenConfig::from_env(),
+            api_shutdown.signal(),
         ));
     }
 
@@ -194,7 +249,7 @@ async fn run_server(components: &ComponentsToRun) {
         // Run signer
         let (sign_check_sender, sign_check_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
         tasks.push(zksync_api::signature_checker::start_sign_checker(
-            eth_gateway,
+            eth_gateway.clone(),
             sign_check_receiver,
         ));
 
@@ -212,7 +267,43 @@ async fn run_server(components: &ComponentsToRun) {
             read_only_connection_pool.clone(),
         );
 
-        if components.0.contains(&Component::RpcWebSocketApi) {
+        if let Some(period) = common_config.mempool_revalidation_interval() {
+            let revalidator =
+                MempoolRevalidator::new(connection_pool.clone(), ticker.clone(), &common_config);
+            tasks.push(run_mempool_revalidator(revalidator, period));
+        }
+        tasks.push(run_subsidy_releaser(
+            connection_pool.clone(),
+            SUBSIDY_RELEASE_INTERVAL,
+        ));
+
+        if let Some(period) = common_config.stuck_accounts_check_interval() {
+            let analyzer = MempoolAnalyzer::new(connection_pool.clone(), &common_config);
+            tasks.push(run_mempool_analyzer(analyzer, period));
+        }
+
+        // Transactions accepted by any of the APIs are reported to the `mempool` subscriptions
+        // of the WebSocket API, if it's running.
+        let (accepted_txs_sender, accepted_txs_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+        let ws_api_enabled = components.0.contains(&Component::RpcWebSocketApi);
+        let accepted_txs = Some(accepted_txs_sender.clone()).filter(|_| ws_api_enabled);
+        let private_config = PrivateApiConfig::from_env();
+
+        // Transactions accepted by any of the APIs are checked to be received by the core.
+        let submitted_txs = private_config.mempool_resync_interval().map(|period| {
+            let submitted_txs = SubmittedTxs::new(SUBMITTED_TXS_CAPACITY);
+            tasks.push(start_mempool_resync_checker(
+                private_config.url.clone(),
+                submitted_txs.clone(),
+                private_config.mempool_resync_reforward,
+                period,
+            ));
+            submitted_txs
+        });
+        // Submissions are throttled per sender, so all the APIs share the same limiter.
+        let sender_limiter = SenderRateLimiter::from_config(&common_config);
+
+        if ws_api_enabled {
             let (mempool_tx_request_sender, mempool_tx_request_receiver) =
                 mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
             tasks.push(run_mempool_tx_handler(
@@ -229,7 +320,13 @@ async fn run_server(components: &ComponentsToRun) {
                 &JsonRpcConfig::from_env(),
                 chain_config.state_keeper.miniblock_iteration_interval(),
                 mempool_tx_request_sender,
+                accepted_txs_sender,
+                accepted_txs_receiver,
+                submitted_txs.clone(),
+                sender_limiter.clone(),
                 eth_watch_config.confirmations_for_eth_event,
+                private_config.url.clone(),
+                api_shutdown.signal(),
             ));
         }
 
@@ -249,7 +346,12 @@ async fn run_server(components: &ComponentsToRun) {
                 &common_config,
                 &token_config,
                 mempool_tx_request_sender,
+                accepted_txs.clone(),
+                submitted_txs.clone(),
+                sender_limiter.clone(),
                 eth_watch_config.confirmations_for_eth_event,
+                private_config.url.clone(),
+                api_shutdown.signal(),
             ));
         }
 
@@ -261,7 +363,6 @@ async fn run_server(components: &ComponentsToRun) {
                 mempool_tx_request_receiver,
                 chain_config.state_keeper.block_chunk_sizes,
             ));
-            let private_config = PrivateApiConfig::from_env();
             tasks.push(zksync_api::api_server::rest::start_server_thread_detached(
                 read_only_connection_pool.clone(),
                 connection_pool.clone(),
@@ -270,7 +371,12 @@ async fn run_server(components: &ComponentsToRun) {
                 ticker,
                 sign_check_sender,
                 mempool_tx_request_sender,
+                accepted_txs,
+                submitted_txs,
+                sender_limiter,
                 private_config.url,
+                eth_gateway,
+                api_shutdown.signal(),
             ));
         }
     }
@@ -288,6 +394,7 @@ async fn run_server(components: &ComponentsToRun) {
                 read_only_connection_pool.clone(),
                 &ZkSyncConfig::from_env(),
                 eth_gateway.clone(),
+                api_shutdown.signal(),
             )
             .await
             .unwrap(),
@@ -298,18 +405,6 @@ async fn run_server(components: &ComponentsToRun) {
         tasks.push(run_witness_generator(connection_pool.clone()))
     }
 
-    if components.0.contains(&Component::Prometheus) {
-        // Run prometheus data exporter.
-        let config = PrometheusConfig::from_env();
-        let prometheus_task_handle = run_prometheus_exporter(config.port);
-        tasks.push(prometheus_task_handle);
-        // We can run them only with active prometheus
-        if components.0.contains(&Component::PrometheusPeriodicMetrics) {
-            let counter_task_handle = run_operation_counter(read_only_connection_pool.clone());
-            tasks.push(counter_task_handle);
-        }
-    }
-
     if components.0.contains(&Component::ForcedExit) {
         tasks.append(&mut run_forced_exit(connection_pool.clone()));
     }
@@ -336,6 +431,20 @@ async fn run_server(components: &ComponentsToRun) {
             vlog::warn!("Stop signal received, shutting down");
         }
     };
+
+    // The servers stop accepting new requests, but the in-flight ones are given time to finish.
+    // Once the servers are stopped, their mempool channels are closed, so the mempool handlers stop as well.
+    let grace_period = CommonApiConfig::from_env().shutdown_grace_period();
+    tokio::select! {
+        stopped = api_shutdown.shutdown(grace_period) => {
+            if !stopped {
+                vlog::warn!("API servers haven't stopped within {:?}, exiting anyway", grace_period);
+            }
+        },
+        _ = async { stop_signal_receiver.next().await } => {
+            vlog::warn!("Second stop signal received, exiting immediately");
+        }
+    };
 }
 
 pub fn run_forced_exit(connection_pool: ConnectionPool) -> Vec<JoinHandle<()>> {
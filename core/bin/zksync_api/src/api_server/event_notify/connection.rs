@@ -0,0 +1,112 @@
+//! Accounting of the subscriptions opened over the WebSocket connections.
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    Arc,
+};
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Identifier of the WebSocket connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConnectionId(pub u64);
+
+/// WebSocket connection along with the number of its active subscriptions.
+#[derive(Debug)]
+pub struct Connection {
+    id: ConnectionId,
+    active_subscriptions: AtomicUsize,
+    /// Whether the connection has ever subscribed to anything.
+    subscribed: AtomicBool,
+    /// Whether the connection is closed, its subscriptions are not accepted anymore then.
+    closed: AtomicBool,
+}
+
+impl Connection {
+    pub fn new() -> Arc<Self> {
+        metrics::increment_gauge!("api.ws.active_connections", 1.0);
+        Arc::new(Self {
+            id: ConnectionId(NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed)),
+            active_subscriptions: AtomicUsize::new(0),
+            subscribed: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+        })
+    }
+
+    pub fn id(&self) -> ConnectionId {
+        self.id
+    }
+
+    #[cfg(test)]
+    pub fn active_subscriptions(&self) -> usize {
+        self.active_subscriptions.load(Ordering::SeqCst)
+    }
+
+    pub fn has_subscribed(&self) -> bool {
+        self.subscribed.load(Ordering::SeqCst)
+    }
+
+    /// Marks the connection as closed. Must be called before the notifier is told
+    /// about the disconnect, so the subscription requests still queued for the notifier
+    /// are refused instead of outliving the connection.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Reserves a slot for the new subscription, unless the connection already has
+    /// `max` active subscriptions.
+    pub fn reserve_slot(self: &Arc<Self>, max: usize) -> Option<SubscriptionSlot> {
+        self.active_subscriptions
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |active| {
+                if active < max {
+                    Some(active + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()?;
+        self.subscribed.store(true, Ordering::SeqCst);
+
+        metrics::increment_gauge!("api.ws.active_subscriptions", 1.0);
+        Some(SubscriptionSlot {
+            connection: self.clone(),
+        })
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        metrics::decrement_gauge!("api.ws.active_connections", 1.0);
+    }
+}
+
+/// Slot taken by the subscription in the limit of its connection.
+///
+/// The slot is stored along with the subscription and released once the subscription is dropped
+/// by the notifier, be it unsubscribed, notified for the last time or closed with the connection.
+#[derive(Debug)]
+pub struct SubscriptionSlot {
+    connection: Arc<Connection>,
+}
+
+impl SubscriptionSlot {
+    pub fn connection_id(&self) -> ConnectionId {
+        self.connection.id()
+    }
+
+    pub fn is_connection_closed(&self) -> bool {
+        self.connection.is_closed()
+    }
+}
+
+impl Drop for SubscriptionSlot {
+    fn drop(&mut self) {
+        self.connection
+            .active_subscriptions
+            .fetch_sub(1, Ordering::SeqCst);
+        metrics::decrement_gauge!("api.ws.active_subscriptions", 1.0);
+    }
+}
@@ -1,4 +1,7 @@
-use super::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp};
+use super::rpc_server::types::{
+    BlockNotification, BlockSubscriptionMode, ETHOpInfoResp, MempoolTxNotification,
+    ResponseAccountState, TransactionInfoResp,
+};
 use futures::{channel::mpsc, select, stream::StreamExt};
 use jsonrpc_pubsub::{
     typed::{Sink, Subscriber},
@@ -9,10 +12,14 @@ use zksync_config::configs::api::TokenConfig;
 use zksync_storage::ConnectionPool;
 use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
-use zksync_types::{block::ExecutedOperations, ActionType, Address};
+use zksync_types::{block::ExecutedOperations, ActionType, Address, ZkSyncTx};
 
+pub use self::connection::{Connection, ConnectionId, SubscriptionSlot};
+#[cfg(test)]
+pub use self::operation_notifier::OperationNotifier;
 use self::{event_fetcher::EventFetcher, operation_notifier::OperationNotifier};
 
+mod connection;
 mod event_fetcher;
 mod operation_notifier;
 mod state;
@@ -42,10 +49,21 @@ pub enum EventSubscribeRequest {
         action: ActionType,
         subscriber: Subscriber<ResponseAccountState>,
     },
+    Blocks {
+        mode: BlockSubscriptionMode,
+        subscriber: Subscriber<BlockNotification>,
+    },
+    Mempool {
+        address: Address,
+        subscriber: Subscriber<MempoolTxNotification>,
+    },
 }
 
 pub enum EventNotifierRequest {
-    Sub(EventSubscribeRequest),
+    Sub {
+        request: EventSubscribeRequest,
+        slot: SubscriptionSlot,
+    },
     Unsub(SubscriptionId),
 }
 
@@ -53,11 +71,14 @@ pub enum EventNotifierRequest {
 struct SubscriptionSender<T> {
     id: SubscriptionId,
     sink: Sink<T>,
+    slot: SubscriptionSlot,
 }
 
 pub fn start_sub_notifier(
     db_pool: ConnectionPool,
     mut subscription_stream: mpsc::Receiver<EventNotifierRequest>,
+    mut disconnect_stream: mpsc::UnboundedReceiver<ConnectionId>,
+    mut accepted_txs_stream: mpsc::Receiver<Vec<ZkSyncTx>>,
     api_requests_caches_size: usize,
     miniblock_interval: Duration,
     token_config: &TokenConfig,
@@ -108,6 +129,16 @@ pub fn start_sub_notifier(
                             .unwrap_or_default();
                     }
                 },
+                connection = disconnect_stream.next() => {
+                    if let Some(connection) = connection {
+                        notifier.handle_disconnect(connection);
+                    }
+                },
+                accepted_txs = accepted_txs_stream.next() => {
+                    if let Some(accepted_txs) = accepted_txs {
+                        notifier.handle_accepted_txs(accepted_txs);
+                    }
+                },
                 complete => break,
             }
         }
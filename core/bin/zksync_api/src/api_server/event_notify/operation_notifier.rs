@@ -1,5 +1,6 @@
 use crate::api_server::rpc_server::types::{
-    BlockInfo, ETHOpInfoResp, ResponseAccountState, TransactionInfoResp,
+    BlockInfo, BlockNotification, BlockSubscriptionMode, ETHOpInfoResp, MempoolTxNotification,
+    ResponseAccountState, TransactionInfoResp, TxInfoStatus,
 };
 use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
 use std::time::{Duration, Instant};
@@ -7,11 +8,14 @@ use zksync_storage::ConnectionPool;
 use zksync_types::aggregated_operations::AggregatedOperation;
 use zksync_types::tx::TxHash;
 use zksync_types::BlockNumber;
-use zksync_types::{block::ExecutedOperations, AccountId, ActionType, Address, PriorityOpId};
+use zksync_types::{
+    block::ExecutedOperations, AccountId, ActionType, Address, PriorityOpId, ZkSyncTx,
+};
 
 use super::{
-    state::NotifierState, sub_store::SubStorage, EventNotifierRequest, EventSubscribeRequest,
-    ExecutedOps,
+    state::NotifierState,
+    sub_store::{BlockSubStorage, MempoolSubStorage, SubStorage},
+    ConnectionId, EventNotifierRequest, EventSubscribeRequest, ExecutedOps, SubscriptionSlot,
 };
 
 pub struct OperationNotifier {
@@ -20,6 +24,8 @@ pub struct OperationNotifier {
     tx_subs: SubStorage<TxHash, TransactionInfoResp>,
     prior_op_subs: SubStorage<PriorityOpId, ETHOpInfoResp>,
     account_subs: SubStorage<AccountId, ResponseAccountState>,
+    block_subs: BlockSubStorage,
+    mempool_subs: MempoolSubStorage,
 }
 
 impl OperationNotifier {
@@ -33,6 +39,8 @@ impl OperationNotifier {
             tx_subs: SubStorage::new(),
             prior_op_subs: SubStorage::new(),
             account_subs: SubStorage::new(),
+            block_subs: BlockSubStorage::new(),
+            mempool_subs: MempoolSubStorage::new(),
         }
     }
 
@@ -42,18 +50,21 @@ impl OperationNotifier {
         new_sub: EventNotifierRequest,
     ) -> Result<(), anyhow::Error> {
         match new_sub {
-            EventNotifierRequest::Sub(event_sub) => match event_sub {
+            EventNotifierRequest::Sub { request, slot } => match request {
                 EventSubscribeRequest::Transaction {
                     hash,
                     action,
                     subscriber,
-                } => self.add_transaction_sub(hash, action, subscriber).await,
+                } => {
+                    self.add_transaction_sub(hash, action, subscriber, slot)
+                        .await
+                }
                 EventSubscribeRequest::PriorityOp {
                     serial_id,
                     action,
                     subscriber,
                 } => {
-                    self.add_priority_op_sub(serial_id, action, subscriber)
+                    self.add_priority_op_sub(serial_id, action, subscriber, slot)
                         .await
                 }
                 EventSubscribeRequest::Account {
@@ -61,9 +72,16 @@ impl OperationNotifier {
                     action,
                     subscriber,
                 } => {
-                    self.add_account_update_sub(address, action, subscriber)
+                    self.add_account_update_sub(address, action, subscriber, slot)
                         .await
                 }
+                EventSubscribeRequest::Blocks { mode, subscriber } => {
+                    self.add_blocks_sub(mode, subscriber, slot)
+                }
+                EventSubscribeRequest::Mempool {
+                    address,
+                    subscriber,
+                } => self.add_mempool_sub(address, subscriber, slot),
             }
             .map_err(|e| anyhow::format_err!("Failed to add sub: {}", e)),
             EventNotifierRequest::Unsub(sub_id) => self
@@ -79,13 +97,31 @@ impl OperationNotifier {
     ) -> anyhow::Result<()> {
         let start = Instant::now();
 
-        let (action, blocks) = match aggregation_operation {
-            AggregatedOperation::CommitBlocks(operation) => (ActionType::COMMIT, operation.blocks),
-            AggregatedOperation::ExecuteBlocks(operation) => (ActionType::VERIFY, operation.blocks),
+        let (action, mode, blocks) = match aggregation_operation {
+            AggregatedOperation::CommitBlocks(operation) => (
+                ActionType::COMMIT,
+                BlockSubscriptionMode::Committed,
+                operation.blocks,
+            ),
+            AggregatedOperation::ExecuteBlocks(operation) => (
+                ActionType::VERIFY,
+                BlockSubscriptionMode::Finalized,
+                operation.blocks,
+            ),
             _ => return Ok(()),
         };
 
         for block in blocks {
+            if self.block_subs.subscriber_exists(mode) {
+                let notification = BlockNotification {
+                    block_number: block.block_number,
+                    new_state_root: block.new_root_hash,
+                    tx_count: block.block_transactions.len(),
+                    timestamp: block.timestamp,
+                };
+                self.block_subs.notify(mode, notification);
+            }
+
             self.handle_executed_operations(
                 block.block_transactions.clone(),
                 action,
@@ -135,6 +171,7 @@ impl OperationNotifier {
                 ExecutedOperations::Tx(tx) => {
                     let hash = tx.signed_tx.hash();
                     let resp = TransactionInfoResp {
+                        status: TxInfoStatus::Executed,
                         executed: true,
                         success: Some(tx.success),
                         fail_reason: tx.fail_reason,
@@ -176,20 +213,61 @@ impl OperationNotifier {
         Ok(())
     }
 
+    /// Notifies the subscribers of the senders and the recipients of the transactions
+    /// accepted into the mempool.
+    pub fn handle_accepted_txs(&mut self, txs: Vec<ZkSyncTx>) {
+        for tx in txs {
+            let mut addresses = vec![tx.account()];
+            // `ChangePubKey` has no recipient, its "recipient" is the new public key hash.
+            if !matches!(tx, ZkSyncTx::ChangePubKey(_)) {
+                addresses.extend(tx.to_account());
+            }
+            addresses.dedup();
+
+            for address in addresses {
+                if self.mempool_subs.subscriber_exists(&address) {
+                    self.mempool_subs
+                        .notify(address, MempoolTxNotification::new(&tx));
+                }
+            }
+        }
+    }
+
     /// Removes provided subscription from the list.
     fn handle_unsub(&mut self, sub_id: SubscriptionId) -> Result<(), anyhow::Error> {
         self.prior_op_subs.remove(sub_id.clone())?;
         self.tx_subs.remove(sub_id.clone())?;
-        self.account_subs.remove(sub_id)?;
+        self.account_subs.remove(sub_id.clone())?;
+        self.block_subs.remove(&sub_id);
+        self.mempool_subs.remove(&sub_id);
         Ok(())
     }
 
+    /// Removes all the subscriptions of the closed connection.
+    pub fn handle_disconnect(&mut self, connection: ConnectionId) {
+        self.prior_op_subs.remove_connection(connection);
+        self.tx_subs.remove_connection(connection);
+        self.account_subs.remove_connection(connection);
+        self.block_subs.remove_connection(connection);
+        self.mempool_subs.remove_connection(connection);
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.prior_op_subs.is_empty()
+            && self.tx_subs.is_empty()
+            && self.account_subs.is_empty()
+            && self.block_subs.is_empty()
+            && self.mempool_subs.is_empty()
+    }
+
     /// Add priority operation subscription.
     async fn add_priority_op_sub(
         &mut self,
         serial_id: u64,
         action: ActionType,
         sub: Subscriber<ETHOpInfoResp>,
+        slot: SubscriptionSlot,
     ) -> Result<(), anyhow::Error> {
         let start = Instant::now();
         let sub_id = self
@@ -231,7 +309,7 @@ impl OperationNotifier {
         }
 
         self.prior_op_subs
-            .insert_new(sub_id, sub, PriorityOpId(serial_id), action)?;
+            .insert_new(sub_id, sub, slot, PriorityOpId(serial_id), action)?;
         metrics::histogram!("api.notifier.add_priority_op_sub", start.elapsed());
         Ok(())
     }
@@ -242,6 +320,7 @@ impl OperationNotifier {
         hash: TxHash,
         action: ActionType,
         sub: Subscriber<TransactionInfoResp>,
+        slot: SubscriptionSlot,
     ) -> Result<(), anyhow::Error> {
         let start = Instant::now();
         let sub_id = self.tx_subs.generate_sub_id(hash, action);
@@ -250,6 +329,7 @@ impl OperationNotifier {
 
         if let Some(receipt) = tx_receipt {
             let tx_info_resp = TransactionInfoResp {
+                status: TxInfoStatus::Executed,
                 executed: true,
                 success: Some(receipt.success),
                 fail_reason: receipt.fail_reason,
@@ -273,7 +353,7 @@ impl OperationNotifier {
             }
         }
 
-        self.tx_subs.insert_new(sub_id, sub, hash, action)?;
+        self.tx_subs.insert_new(sub_id, sub, slot, hash, action)?;
         metrics::histogram!("api.notifier.add_transaction_sub", start.elapsed());
         Ok(())
     }
@@ -284,6 +364,7 @@ impl OperationNotifier {
         address: Address,
         action: ActionType,
         sub: Subscriber<ResponseAccountState>,
+        slot: SubscriptionSlot,
     ) -> Result<(), anyhow::Error> {
         let start = Instant::now();
         let (account_id, _account_state) = self.state.get_account_info(address, action).await?;
@@ -291,8 +372,30 @@ impl OperationNotifier {
         let sub_id = self.account_subs.generate_sub_id(account_id, action);
 
         self.account_subs
-            .insert_new(sub_id, sub, account_id, action)?;
+            .insert_new(sub_id, sub, slot, account_id, action)?;
         metrics::histogram!("api.notifier.add_account_update_sub", start.elapsed());
         Ok(())
     }
+
+    /// Add new blocks subscription.
+    fn add_blocks_sub(
+        &mut self,
+        mode: BlockSubscriptionMode,
+        sub: Subscriber<BlockNotification>,
+        slot: SubscriptionSlot,
+    ) -> Result<(), anyhow::Error> {
+        let sub_id = self.block_subs.generate_sub_id(mode);
+        self.block_subs.insert_new(sub_id, sub, slot, mode)
+    }
+
+    /// Add subscription to the mempool transactions of the address.
+    fn add_mempool_sub(
+        &mut self,
+        address: Address,
+        sub: Subscriber<MempoolTxNotification>,
+        slot: SubscriptionSlot,
+    ) -> Result<(), anyhow::Error> {
+        let sub_id = self.mempool_subs.generate_sub_id(address);
+        self.mempool_subs.insert_new(sub_id, sub, slot, address)
+    }
 }
@@ -1,8 +1,14 @@
 //! Storage for subscription objects.
-use super::SubscriptionSender;
+use super::{ConnectionId, SubscriptionSender, SubscriptionSlot};
+use crate::api_server::rpc_server::{
+    error::RpcErrorCodes,
+    types::{BlockNotification, BlockSubscriptionMode, MempoolTxNotification},
+};
+use serde_json::json;
 use std::{cmp::Ord, collections::BTreeMap, str::FromStr};
-use zksync_types::{tx::TxHash, AccountId, ActionType, PriorityOpId};
+use zksync_types::{tx::TxHash, AccountId, ActionType, Address, BlockNumber, PriorityOpId};
 
+use jsonrpc_core::Error;
 use jsonrpc_pubsub::{
     typed::{Sink, Subscriber},
     SubscriptionId,
@@ -12,6 +18,27 @@ const MAX_LISTENERS_PER_ENTITY: usize = 2048;
 const TX_SUB_PREFIX: &str = "txsub";
 const ETHOP_SUB_PREFIX: &str = "eosub";
 const ACCOUNT_SUB_PREFIX: &str = "acsub";
+const BLOCK_SUB_PREFIX: &str = "blsub";
+const MEMPOOL_SUB_PREFIX: &str = "mpsub";
+
+/// Rejects the subscriber of the entity which already has the maximum number of listeners.
+fn reject_over_limit<T>(sub: Subscriber<T>) -> anyhow::Result<()> {
+    let code = RpcErrorCodes::SubscriptionsLimitReached;
+    sub.reject(Error {
+        code: code.into(),
+        message: format!(
+            "The subscribed entity has reached the limit of {} listeners",
+            MAX_LISTENERS_PER_ENTITY
+        ),
+        data: Some(json!({
+            "code": code as i64,
+            "reason": code.as_str(),
+            "limit": MAX_LISTENERS_PER_ENTITY,
+        })),
+    })
+    .unwrap_or_default();
+    Err(anyhow::format_err!("ListenersLimitReached"))
+}
 
 pub trait ActionId {
     fn sub_type() -> &'static str;
@@ -90,22 +117,30 @@ where
         &mut self,
         sub_id: SubscriptionId,
         sub: Subscriber<RESP>,
+        slot: SubscriptionSlot,
         action_id: ID,
         action_type: ActionType,
     ) -> anyhow::Result<()> {
-        let mut subs = self
-            .storage
-            .remove(&(action_id.clone(), action_type))
-            .unwrap_or_default();
-        if subs.len() < MAX_LISTENERS_PER_ENTITY {
-            let sink = sub
-                .assign_id(sub_id.clone())
-                .map_err(|_| anyhow::format_err!("SubIdAssign"))?;
-            subs.push(SubscriptionSender { id: sub_id, sink });
-        };
-        self.storage.insert((action_id, action_type), subs);
+        if slot.is_connection_closed() {
+            return Err(anyhow::format_err!("ConnectionClosed"));
+        }
+        let subs = self.storage.entry((action_id, action_type)).or_default();
+        if subs.len() >= MAX_LISTENERS_PER_ENTITY {
+            return reject_over_limit(sub);
+        }
+        let result = sub
+            .assign_id(sub_id.clone())
+            .map_err(|_| anyhow::format_err!("SubIdAssign"))
+            .map(|sink| {
+                subs.push(SubscriptionSender {
+                    id: sub_id,
+                    sink,
+                    slot,
+                })
+            });
+        self.storage.retain(|_, subs| !subs.is_empty());
 
-        Ok(())
+        result
     }
 
     pub fn remove(&mut self, sub_id: SubscriptionId) -> anyhow::Result<()> {
@@ -132,6 +167,19 @@ where
         Ok(())
     }
 
+    /// Removes all the subscriptions of the closed connection.
+    pub fn remove_connection(&mut self, connection: ConnectionId) {
+        for subs in self.storage.values_mut() {
+            subs.retain(|sub| sub.slot.connection_id() != connection);
+        }
+        self.storage.retain(|_, subs| !subs.is_empty());
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
     pub fn subscriber_exists(&mut self, action_id: ID, action_type: ActionType) -> bool {
         self.storage.contains_key(&(action_id, action_type))
     }
@@ -158,3 +206,379 @@ where
         Ok(())
     }
 }
+
+/// Subscription to the blocks along with the last block it was notified about.
+#[derive(Debug)]
+struct BlockSubscription {
+    sender: SubscriptionSender<BlockNotification>,
+    last_sent: Option<BlockNumber>,
+}
+
+/// Storage for the `blocks` subscriptions.
+///
+/// Unlike the other subscriptions, these are not removed after the first notification.
+/// The same block may be reported by the several aggregated operations, so every subscription
+/// keeps the watermark of the last sent block and is never notified about the older ones.
+#[derive(Debug, Default)]
+pub struct BlockSubStorage {
+    storage: BTreeMap<BlockSubscriptionMode, Vec<BlockSubscription>>,
+}
+
+impl BlockSubStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn generate_sub_id(&self, mode: BlockSubscriptionMode) -> SubscriptionId {
+        SubscriptionId::String(format!(
+            "{}/{:?}/{}",
+            BLOCK_SUB_PREFIX,
+            mode,
+            zksync_crypto::rand::random::<u64>()
+        ))
+    }
+
+    pub fn insert_new(
+        &mut self,
+        sub_id: SubscriptionId,
+        sub: Subscriber<BlockNotification>,
+        slot: SubscriptionSlot,
+        mode: BlockSubscriptionMode,
+    ) -> anyhow::Result<()> {
+        if slot.is_connection_closed() {
+            return Err(anyhow::format_err!("ConnectionClosed"));
+        }
+        let subs = self.storage.entry(mode).or_default();
+        if subs.len() >= MAX_LISTENERS_PER_ENTITY {
+            return reject_over_limit(sub);
+        }
+        let result = sub
+            .assign_id(sub_id.clone())
+            .map_err(|_| anyhow::format_err!("SubIdAssign"))
+            .map(|sink| {
+                subs.push(BlockSubscription {
+                    sender: SubscriptionSender {
+                        id: sub_id,
+                        sink,
+                        slot,
+                    },
+                    last_sent: None,
+                })
+            });
+        self.storage.retain(|_, subs| !subs.is_empty());
+
+        result
+    }
+
+    pub fn remove(&mut self, sub_id: &SubscriptionId) {
+        for subs in self.storage.values_mut() {
+            subs.retain(|sub| &sub.sender.id != sub_id);
+        }
+        self.storage.retain(|_, subs| !subs.is_empty());
+    }
+
+    /// Removes all the subscriptions of the closed connection.
+    pub fn remove_connection(&mut self, connection: ConnectionId) {
+        for subs in self.storage.values_mut() {
+            subs.retain(|sub| sub.sender.slot.connection_id() != connection);
+        }
+        self.storage.retain(|_, subs| !subs.is_empty());
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    pub fn subscriber_exists(&self, mode: BlockSubscriptionMode) -> bool {
+        self.storage.contains_key(&mode)
+    }
+
+    /// Notifies the subscriptions that haven't seen this block yet. Subscriptions with
+    /// the closed connection are removed.
+    pub fn notify(&mut self, mode: BlockSubscriptionMode, block: BlockNotification) {
+        let subs = match self.storage.remove(&mode) {
+            Some(subs) => subs,
+            None => return,
+        };
+
+        let subs: Vec<_> = subs
+            .into_iter()
+            .filter_map(|mut sub| {
+                if let Some(last_sent) = sub.last_sent {
+                    if last_sent >= block.block_number {
+                        // Already notified about this or a newer block.
+                        return Some(sub);
+                    }
+                }
+                match sub.sender.sink.notify(Ok(block.clone())) {
+                    Ok(()) => {
+                        sub.last_sent = Some(block.block_number);
+                        Some(sub)
+                    }
+                    Err(e) => {
+                        vlog::warn!("{}", e.to_string());
+                        None
+                    }
+                }
+            })
+            .collect();
+        if !subs.is_empty() {
+            self.storage.insert(mode, subs);
+        }
+    }
+}
+
+/// Storage for the `mempool` subscriptions.
+///
+/// Subscriptions are kept until they are removed or their connection is closed, every
+/// transaction of the watched address accepted into the mempool is reported.
+#[derive(Debug, Default)]
+pub struct MempoolSubStorage {
+    storage: BTreeMap<Address, Vec<SubscriptionSender<MempoolTxNotification>>>,
+}
+
+impl MempoolSubStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn generate_sub_id(&self, address: Address) -> SubscriptionId {
+        SubscriptionId::String(format!(
+            "{}/{:?}/{}",
+            MEMPOOL_SUB_PREFIX,
+            address,
+            zksync_crypto::rand::random::<u64>()
+        ))
+    }
+
+    pub fn insert_new(
+        &mut self,
+        sub_id: SubscriptionId,
+        sub: Subscriber<MempoolTxNotification>,
+        slot: SubscriptionSlot,
+        address: Address,
+    ) -> anyhow::Result<()> {
+        if slot.is_connection_closed() {
+            return Err(anyhow::format_err!("ConnectionClosed"));
+        }
+        let subs = self.storage.entry(address).or_default();
+        if subs.len() >= MAX_LISTENERS_PER_ENTITY {
+            return reject_over_limit(sub);
+        }
+        let result = sub
+            .assign_id(sub_id.clone())
+            .map_err(|_| anyhow::format_err!("SubIdAssign"))
+            .map(|sink| {
+                subs.push(SubscriptionSender {
+                    id: sub_id,
+                    sink,
+                    slot,
+                })
+            });
+        self.storage.retain(|_, subs| !subs.is_empty());
+
+        result
+    }
+
+    pub fn remove(&mut self, sub_id: &SubscriptionId) {
+        for subs in self.storage.values_mut() {
+            subs.retain(|sub| &sub.id != sub_id);
+        }
+        self.storage.retain(|_, subs| !subs.is_empty());
+    }
+
+    /// Removes all the subscriptions of the closed connection.
+    pub fn remove_connection(&mut self, connection: ConnectionId) {
+        for subs in self.storage.values_mut() {
+            subs.retain(|sub| sub.slot.connection_id() != connection);
+        }
+        self.storage.retain(|_, subs| !subs.is_empty());
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+
+    pub fn subscriber_exists(&self, address: &Address) -> bool {
+        self.storage.contains_key(address)
+    }
+
+    /// Notifies the subscriptions of the address. Subscriptions with the closed connection
+    /// are removed.
+    pub fn notify(&mut self, address: Address, notification: MempoolTxNotification) {
+        let mut subs = match self.storage.remove(&address) {
+            Some(subs) => subs,
+            None => return,
+        };
+
+        subs.retain(|sub| match sub.sink.notify(Ok(notification.clone())) {
+            Ok(()) => true,
+            Err(e) => {
+                vlog::warn!("{}", e.to_string());
+                false
+            }
+        });
+        if !subs.is_empty() {
+            self.storage.insert(address, subs);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::event_notify::Connection;
+    use futures::StreamExt;
+    use serde_json::Value;
+    use zksync_crypto::{ff::Field, Fr};
+
+    fn notification(block_number: u32) -> BlockNotification {
+        BlockNotification {
+            block_number: BlockNumber(block_number),
+            new_state_root: Fr::zero(),
+            tx_count: 1,
+            timestamp: u64::from(block_number),
+        }
+    }
+
+    #[tokio::test]
+    async fn blocks_notifications_are_ordered() {
+        let mut storage = BlockSubStorage::new();
+        let mode = BlockSubscriptionMode::Committed;
+
+        let connection = Connection::new();
+        let slot = connection.reserve_slot(1).unwrap();
+        let (subscriber, _id, mut notifications) = Subscriber::new_test("blocks");
+        let sub_id = storage.generate_sub_id(mode);
+        storage
+            .insert_new(sub_id.clone(), subscriber, slot, mode)
+            .unwrap();
+        assert!(storage.subscriber_exists(mode));
+        assert!(!storage.subscriber_exists(BlockSubscriptionMode::Finalized));
+
+        // The same block may come within the several aggregated operations,
+        // only the blocks newer than the last sent one are reported.
+        for &block_number in &[1, 2, 1, 2] {
+            storage.notify(mode, notification(block_number));
+        }
+        for &expected in &[1, 2] {
+            let message: Value =
+                serde_json::from_str(&notifications.next().await.unwrap()).unwrap();
+            assert_eq!(message["params"]["result"]["blockNumber"], expected);
+        }
+        assert!(notifications.try_next().is_err());
+
+        assert_eq!(connection.active_subscriptions(), 1);
+        storage.remove(&sub_id);
+        assert!(!storage.subscriber_exists(mode));
+        assert_eq!(connection.active_subscriptions(), 0);
+    }
+
+    #[test]
+    fn closed_blocks_subscriptions_are_removed() {
+        let mut storage = BlockSubStorage::new();
+        let mode = BlockSubscriptionMode::Finalized;
+
+        let connection = Connection::new();
+        let slot = connection.reserve_slot(1).unwrap();
+        let (subscriber, _id, notifications) = Subscriber::new_test("blocks");
+        let sub_id = storage.generate_sub_id(mode);
+        storage.insert_new(sub_id, subscriber, slot, mode).unwrap();
+
+        drop(notifications);
+        storage.notify(mode, notification(1));
+        assert!(!storage.subscriber_exists(mode));
+        assert_eq!(connection.active_subscriptions(), 0);
+    }
+
+    /// Checks that the subscription request handled after the disconnect of its connection
+    /// is refused, so it doesn't outlive the connection.
+    #[test]
+    fn subscriptions_of_closed_connections_are_refused() {
+        let mut storage = BlockSubStorage::new();
+        let mode = BlockSubscriptionMode::Committed;
+
+        let connection = Connection::new();
+        let slot = connection.reserve_slot(1).unwrap();
+        connection.close();
+        storage.remove_connection(connection.id());
+
+        let (subscriber, _id, _notifications) = Subscriber::new_test("blocks");
+        let sub_id = storage.generate_sub_id(mode);
+        assert!(storage.insert_new(sub_id, subscriber, slot, mode).is_err());
+        assert!(!storage.subscriber_exists(mode));
+        assert_eq!(connection.active_subscriptions(), 0);
+    }
+
+    #[tokio::test]
+    async fn listeners_beyond_limit_are_rejected() {
+        let mut storage = BlockSubStorage::new();
+        let mode = BlockSubscriptionMode::Committed;
+
+        let connection = Connection::new();
+        let mut notifications = Vec::new();
+        for _ in 0..MAX_LISTENERS_PER_ENTITY {
+            let slot = connection
+                .reserve_slot(MAX_LISTENERS_PER_ENTITY + 1)
+                .unwrap();
+            let (subscriber, _id, subscriber_notifications) = Subscriber::new_test("blocks");
+            let sub_id = storage.generate_sub_id(mode);
+            storage.insert_new(sub_id, subscriber, slot, mode).unwrap();
+            notifications.push(subscriber_notifications);
+        }
+
+        // The subscriber over the limit gets the error instead of being dropped silently.
+        let slot = connection
+            .reserve_slot(MAX_LISTENERS_PER_ENTITY + 1)
+            .unwrap();
+        let (subscriber, id, _notifications) = Subscriber::new_test("blocks");
+        let sub_id = storage.generate_sub_id(mode);
+        assert!(storage.insert_new(sub_id, subscriber, slot, mode).is_err());
+        let error = id.await.unwrap().unwrap_err();
+        assert_eq!(
+            error.code.code(),
+            RpcErrorCodes::SubscriptionsLimitReached as i64
+        );
+        assert_eq!(connection.active_subscriptions(), MAX_LISTENERS_PER_ENTITY);
+    }
+
+    #[test]
+    fn mempool_subscriptions_are_kept() {
+        let mut storage = MempoolSubStorage::new();
+        let address = Address::repeat_byte(1);
+        let notification = || MempoolTxNotification {
+            tx_hash: TxHash::default(),
+            tx_type: "Transfer".to_string(),
+            token: Default::default(),
+            amount: None,
+            nonce: Default::default(),
+        };
+
+        let connection = Connection::new();
+        let (subscriber, _id, notifications) = Subscriber::new_test("mempool");
+        let sub_id = storage.generate_sub_id(address);
+        let slot = connection.reserve_slot(2).unwrap();
+        storage
+            .insert_new(sub_id, subscriber, slot, address)
+            .unwrap();
+        let (subscriber, _id, _other_notifications) = Subscriber::new_test("mempool");
+        let other_sub_id = storage.generate_sub_id(address);
+        let slot = connection.reserve_slot(2).unwrap();
+        storage
+            .insert_new(other_sub_id.clone(), subscriber, slot, address)
+            .unwrap();
+
+        // Every transaction is reported, closed subscriptions are removed.
+        storage.notify(address, notification());
+        drop(notifications);
+        storage.notify(address, notification());
+        assert!(storage.subscriber_exists(&address));
+        assert_eq!(connection.active_subscriptions(), 1);
+
+        storage.remove(&other_sub_id);
+        assert!(storage.is_empty());
+        assert_eq!(connection.active_subscriptions(), 0);
+    }
+}
@@ -7,14 +7,20 @@ use std::collections::HashMap;
 use num::BigUint;
 
 // Workspace uses
-use zksync_api_types::v02::account::{DepositingAccountBalances, DepositingFunds, OngoingDeposit};
+use zksync_api_types::v02::{
+    account::{
+        DepositingAccountBalances, DepositingFunds, OngoingDeposit, PendingPriorityOp,
+        PendingPriorityOpStatus,
+    },
+    transaction::TxType,
+};
 use zksync_storage::StorageProcessor;
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{Address, H256};
+use zksync_types::{tx::TxHash, Address, TokenId, H256};
 use zksync_utils::remove_prefix;
 
 // Local uses
-use super::rest::v02::error::Error;
+use super::rest::v02::error::{Error, TxError};
 use crate::fee_ticker::PriceError;
 
 pub fn try_parse_hash(query: &str) -> Result<H256, hex::FromHexError> {
@@ -28,10 +34,58 @@ pub fn try_parse_hash(query: &str) -> Result<H256, hex::FromHexError> {
     Ok(H256::from_slice(&slice))
 }
 
+/// Parses the hash of a zkSync transaction or of the Ethereum transaction of a priority operation,
+/// both are 32 bytes long. The hash may be prefixed with `0x` or `sync-tx:`, or have no prefix at all.
+pub fn parse_tx_hash(query: &str) -> Result<TxHash, TxError> {
+    const HASH_HEX_LEN: usize = 64;
+
+    let (prefix, hash_hex) = match query.rfind(':') {
+        Some(pos) => query.split_at(pos + 1),
+        None if matches!(query.get(..2), Some(prefix) if prefix.eq_ignore_ascii_case("0x")) => {
+            query.split_at(2)
+        }
+        None => ("", query),
+    };
+    if !matches!(prefix, "" | "0x" | "sync-tx:") {
+        return Err(TxError::UnsupportedHashPrefix(prefix.to_string()));
+    }
+
+    let len = hash_hex.chars().count();
+    if len != HASH_HEX_LEN {
+        return Err(TxError::InvalidHashLength(len));
+    }
+    if let Some(invalid) = hash_hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(TxError::InvalidHashHex(invalid));
+    }
+
+    let bytes = hex::decode(hash_hex).expect("hash consists of hex digits");
+    Ok(TxHash::from_slice(&bytes).expect("hash is 32 bytes long"))
+}
+
+/// Deposit that is not executed yet.
+struct PendingDeposit {
+    token_id: TokenId,
+    amount: BigUint,
+    received_on_block: u64,
+    confirmations_left: u64,
+}
+
+impl From<OngoingDeposit> for PendingDeposit {
+    fn from(deposit: OngoingDeposit) -> Self {
+        // Deposits are stored in the mempool once they are confirmed.
+        Self {
+            token_id: deposit.token_id,
+            amount: BigUint::from(deposit.amount),
+            received_on_block: deposit.received_on_block,
+            confirmations_left: 0,
+        }
+    }
+}
+
 async fn depositing_from_pending_ops(
     storage: &mut StorageProcessor<'_>,
     tokens: &TokenDBCache,
-    pending_ops: Vec<OngoingDeposit>,
+    pending_ops: Vec<PendingDeposit>,
     confirmations_for_eth_event: u64,
 ) -> Result<DepositingAccountBalances, Error> {
     let mut balances = HashMap::new();
@@ -56,13 +110,14 @@ async fn depositing_from_pending_ops(
             .entry(token_symbol)
             .or_insert_with(DepositingFunds::default);
 
-        balance.amount += BigUint::from(op.amount);
+        balance.amount += op.amount;
 
         // `balance.expected_accept_block` should be the greatest block number among
         // all the deposits for a certain token.
         if expected_accept_block > balance.expected_accept_block {
             balance.expected_accept_block = expected_accept_block;
         }
+        balance.confirmations_left = balance.confirmations_left.max(op.confirmations_left);
     }
 
     Ok(DepositingAccountBalances { balances })
@@ -80,7 +135,100 @@ pub async fn get_depositing(
         .get_pending_deposits(address)
         .await?
         .into_iter()
-        .map(OngoingDeposit::new)
+        .map(|op| OngoingDeposit::new(op).into())
+        .collect();
+    depositing_from_pending_ops(storage, tokens, pending_ops, confirmations_for_eth_event).await
+}
+
+/// Sums up the deposits among the priority operations reported by the Ethereum watcher of the core,
+/// including the ones without enough confirmations yet.
+pub async fn get_depositing_from_eth_watch(
+    storage: &mut StorageProcessor<'_>,
+    tokens: &TokenDBCache,
+    pending_ops: Vec<PendingPriorityOp>,
+    confirmations_for_eth_event: u64,
+) -> Result<DepositingAccountBalances, Error> {
+    let pending_ops = pending_ops
+        .into_iter()
+        .filter(|op| {
+            op.op_type == TxType::Deposit && op.status != PendingPriorityOpStatus::Executed
+        })
+        .map(|op| PendingDeposit {
+            token_id: op.token_id,
+            amount: op.amount.map(|amount| amount.0).unwrap_or_default(),
+            received_on_block: op.eth_block,
+            confirmations_left: op.confirmations_left,
+        })
         .collect();
     depositing_from_pending_ops(storage, tokens, pending_ops, confirmations_for_eth_event).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH_HEX: &str = "8e2c7e3b1d4a0f6e9b5c2d7a1f3e8b4c6d0a9f2e5b7c1d3a8f4e6b2c0d9a7f5e";
+
+    #[test]
+    fn parse_tx_hash_prefixes() {
+        let expected = TxHash::from_slice(&hex::decode(HASH_HEX).unwrap()).unwrap();
+        for query in &[
+            format!("0x{}", HASH_HEX),
+            format!("sync-tx:{}", HASH_HEX),
+            HASH_HEX.to_string(),
+            HASH_HEX.to_uppercase(),
+        ] {
+            assert_eq!(parse_tx_hash(query), Ok(expected), "{}", query);
+        }
+
+        // Hash of the Ethereum transaction of a priority operation is parsed the same way.
+        let eth_hash = H256::random();
+        let tx_hash = parse_tx_hash(&format!("{:?}", eth_hash)).unwrap();
+        assert_eq!(H256::from(tx_hash), eth_hash);
+    }
+
+    #[test]
+    fn parse_malformed_tx_hash() {
+        let malformed = vec![
+            (String::new(), TxError::InvalidHashLength(0)),
+            ("0x".to_string(), TxError::InvalidHashLength(0)),
+            ("sync-tx:".to_string(), TxError::InvalidHashLength(0)),
+            ("0x8e2c".to_string(), TxError::InvalidHashLength(4)),
+            (HASH_HEX[..63].to_string(), TxError::InvalidHashLength(63)),
+            (format!("{}0", HASH_HEX), TxError::InvalidHashLength(65)),
+            (format!("0x{}00", HASH_HEX), TxError::InvalidHashLength(66)),
+            (format!("0x0x{}", HASH_HEX), TxError::InvalidHashLength(66)),
+            (
+                format!("sync-tx:sync-tx:{}", HASH_HEX),
+                TxError::UnsupportedHashPrefix("sync-tx:sync-tx:".to_string()),
+            ),
+            (
+                format!("{}g", &HASH_HEX[..63]),
+                TxError::InvalidHashHex('g'),
+            ),
+            (
+                format!("0x {}", &HASH_HEX[1..]),
+                TxError::InvalidHashHex(' '),
+            ),
+            (
+                format!("sync-tx:{}\u{e4}", &HASH_HEX[..63]),
+                TxError::InvalidHashHex('\u{e4}'),
+            ),
+            (
+                format!("sync-bl:{}", HASH_HEX),
+                TxError::UnsupportedHashPrefix("sync-bl:".to_string()),
+            ),
+            (
+                format!("SYNC-TX:{}", HASH_HEX),
+                TxError::UnsupportedHashPrefix("SYNC-TX:".to_string()),
+            ),
+            (
+                format!("0X{}", HASH_HEX),
+                TxError::UnsupportedHashPrefix("0X".to_string()),
+            ),
+        ];
+        for (query, expected) in &malformed {
+            assert_eq!(parse_tx_hash(query).as_ref(), Err(expected), "{}", query);
+        }
+    }
+}
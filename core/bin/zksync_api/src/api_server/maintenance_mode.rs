@@ -0,0 +1,49 @@
+//! Maintenance mode of the network.
+//!
+//! The flag is stored in the database, so it's shared by all the API servers. Every transaction
+//! submission checks it, so the loaded value is cached for a short time to not hit the database
+//! on each request.
+
+// Built-in uses
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// Workspace uses
+use zksync_api_types::MaintenanceMode;
+use zksync_storage::ConnectionPool;
+
+/// Time during which the loaded maintenance mode is considered actual.
+const CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Cache of the maintenance mode loaded from the database.
+/// Clones share the cached value.
+#[derive(Debug, Clone, Default)]
+pub struct MaintenanceModeCache(Arc<Mutex<Option<(MaintenanceMode, Instant)>>>);
+
+impl MaintenanceModeCache {
+    /// Returns the maintenance mode, loading it from the database if the cached one is expired.
+    pub async fn get(&self, pool: &ConnectionPool) -> anyhow::Result<MaintenanceMode> {
+        if let Some((mode, loaded_at)) = self.0.lock().unwrap().as_ref() {
+            if loaded_at.elapsed() < CACHE_TTL {
+                return Ok(mode.clone());
+            }
+        }
+
+        let mode = pool
+            .access_storage()
+            .await?
+            .maintenance_schema()
+            .load_maintenance_mode()
+            .await?;
+        *self.0.lock().unwrap() = Some((mode.clone(), Instant::now()));
+        Ok(mode)
+    }
+
+    /// Drops the cached value, so the next call loads the actual one.
+    #[cfg(test)]
+    pub fn invalidate(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+}
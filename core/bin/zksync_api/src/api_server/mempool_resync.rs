@@ -0,0 +1,505 @@
+//! Consistency check between the mempool as seen by the API node and by the core.
+//!
+//! In split deployments the API server and the core server access the mempool through
+//! their own database connections, so a replication issue may cause the API to report a
+//! transaction as queued while the core has never received it. The checker periodically
+//! takes the recently submitted transactions and batches and asks the core whether it knows
+//! about them. Divergences are logged and counted, and the missing submissions may optionally
+//! be forwarded to the mempool of the core once again. Re-forwarding is safe, since the core
+//! doesn't add the transactions it already knows about.
+
+// Built-in uses
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// External uses
+use futures::channel::mpsc;
+use tokio::{runtime::Runtime, task::JoinHandle, time};
+
+// Workspace uses
+use zksync_api_types::MempoolSubmission;
+use zksync_types::{
+    tx::{error::TxAddError, TxEthSignature, TxHash},
+    SignedZkSyncTx,
+};
+use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
+
+// Local uses
+use crate::core_api_client::CoreApiClient;
+
+/// Maximum number of recently submitted transactions and batches remembered by the API node.
+pub const SUBMITTED_TXS_CAPACITY: usize = 1000;
+
+/// Source of truth about the transactions received by the core.
+#[async_trait::async_trait]
+pub trait CoreMempoolClient: Send + Sync + 'static {
+    /// Returns the subset of the given hashes that are known to the core.
+    async fn known_txs(&self, tx_hashes: &[TxHash]) -> anyhow::Result<Vec<TxHash>>;
+
+    /// Adds the submission unknown to the core to its mempool.
+    async fn reforward_txs(
+        &self,
+        submission: &MempoolSubmission,
+    ) -> anyhow::Result<Result<(), TxAddError>>;
+}
+
+#[async_trait::async_trait]
+impl CoreMempoolClient for CoreApiClient {
+    async fn known_txs(&self, tx_hashes: &[TxHash]) -> anyhow::Result<Vec<TxHash>> {
+        CoreApiClient::known_txs(self, tx_hashes).await
+    }
+
+    async fn reforward_txs(
+        &self,
+        submission: &MempoolSubmission,
+    ) -> anyhow::Result<Result<(), TxAddError>> {
+        CoreApiClient::reforward_txs(self, submission).await
+    }
+}
+
+/// Bounded ring of the transactions and batches recently accepted by the API node.
+/// When the ring is full, the oldest submission is evicted.
+#[derive(Debug, Clone)]
+pub struct SubmittedTxs {
+    submissions: Arc<Mutex<VecDeque<(MempoolSubmission, Instant)>>>,
+    capacity: usize,
+}
+
+impl SubmittedTxs {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            submissions: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Remembers the transaction accepted by the mempool.
+    pub fn push(&self, tx: SignedZkSyncTx) {
+        self.push_submission(MempoolSubmission::Tx { tx });
+    }
+
+    /// Remembers the batch accepted by the mempool.
+    pub fn push_batch(&self, txs: Vec<SignedZkSyncTx>, eth_signatures: Vec<TxEthSignature>) {
+        self.push_submission(MempoolSubmission::Batch {
+            txs,
+            eth_signatures,
+        });
+    }
+
+    fn push_submission(&self, submission: MempoolSubmission) {
+        let mut submissions = self.submissions.lock().unwrap();
+        if submissions.len() == self.capacity {
+            submissions.pop_front();
+        }
+        submissions.push_back((submission, Instant::now()));
+    }
+
+    /// Returns the submissions that were made at least `min_age` ago.
+    pub fn sample(&self, min_age: Duration) -> Vec<MempoolSubmission> {
+        self.submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, submitted_at)| submitted_at.elapsed() >= min_age)
+            .map(|(submission, _)| submission.clone())
+            .collect()
+    }
+
+    /// Forgets the submissions all the transactions of which have the given hashes.
+    pub fn remove(&self, tx_hashes: &[TxHash]) {
+        self.submissions.lock().unwrap().retain(|(submission, _)| {
+            !submission
+                .tx_hashes()
+                .iter()
+                .all(|tx_hash| tx_hashes.contains(tx_hash))
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.submissions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Outcome of a single consistency check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResyncReport {
+    /// Number of transactions sent to the core for verification.
+    pub checked: usize,
+    /// Number of transactions not acknowledged by the core.
+    pub missing: usize,
+    /// Number of missing transactions successfully added to the mempool of the core again.
+    pub reforwarded: usize,
+}
+
+#[derive(Debug)]
+pub struct MempoolResyncChecker<C> {
+    core: C,
+    submitted: SubmittedTxs,
+    reforward: bool,
+    /// Transactions younger than this are not checked to give the core time to see them.
+    min_age: Duration,
+}
+
+impl<C: CoreMempoolClient> MempoolResyncChecker<C> {
+    pub fn new(core: C, submitted: SubmittedTxs, reforward: bool, min_age: Duration) -> Self {
+        Self {
+            core,
+            submitted,
+            reforward,
+            min_age,
+        }
+    }
+
+    /// Verifies that the core acknowledges the sampled submissions.
+    /// Acknowledged submissions are forgotten, while the missing ones are kept
+    /// to be checked again on the next iteration.
+    pub async fn check(&mut self) -> anyhow::Result<ResyncReport> {
+        let submissions = self.submitted.sample(self.min_age);
+        if submissions.is_empty() {
+            return Ok(ResyncReport::default());
+        }
+
+        let tx_hashes: Vec<TxHash> = submissions
+            .iter()
+            .flat_map(MempoolSubmission::tx_hashes)
+            .collect();
+        let known_hashes = self.core.known_txs(&tx_hashes).await?;
+        self.submitted.remove(&known_hashes);
+
+        let mut report = ResyncReport {
+            checked: tx_hashes.len(),
+            ..Default::default()
+        };
+        for submission in submissions {
+            let tx_hashes = submission.tx_hashes();
+            // Transactions of the batch are stored together, so the batch is either fully known or not at all.
+            if tx_hashes
+                .iter()
+                .all(|tx_hash| known_hashes.contains(tx_hash))
+            {
+                continue;
+            }
+
+            report.missing += tx_hashes.len();
+            metrics::increment_counter!("api.mempool_resync.missing_txs");
+            vlog::warn!(
+                "Transactions {:?} were accepted by the API but are not known to the core",
+                tx_hashes
+            );
+
+            if self.reforward {
+                match self.core.reforward_txs(&submission).await {
+                    Ok(Ok(())) => {
+                        report.reforwarded += tx_hashes.len();
+                        metrics::increment_counter!("api.mempool_resync.reforwarded_txs");
+                    }
+                    Ok(Err(err)) => {
+                        vlog::error!(
+                            "Transactions {:?} were rejected by the core mempool: {}",
+                            tx_hashes,
+                            err
+                        );
+                    }
+                    Err(err) => {
+                        vlog::error!("Failed to re-forward transactions {:?}: {}", tx_hashes, err);
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Runs the checker in a separate thread with the given period between checks.
+    pub fn start_checker_detached(mut self, panic_notify: mpsc::Sender<bool>, period: Duration) {
+        std::thread::Builder::new()
+            .name("mempool-resync-checker".to_string())
+            .spawn(move || {
+                let _panic_sentinel = ThreadPanicNotify(panic_notify.clone());
+
+                let runtime = Runtime::new().expect("tokio runtime creation");
+
+                let check_task = async move {
+                    let mut timer = time::interval(period);
+                    loop {
+                        timer.tick().await;
+                        match self.check().await {
+                            Ok(report) if report.missing > 0 => {
+                                vlog::warn!("Mempool resync check found divergences: {:?}", report)
+                            }
+                            Ok(_) => {}
+                            Err(err) => vlog::error!("Mempool resync check failed: {}", err),
+                        }
+                    }
+                };
+                runtime.block_on(check_task);
+            })
+            .expect("Mempool resync checker thread");
+    }
+}
+
+/// Starts the checker for the submissions accepted by any of the API servers sharing `submitted`.
+#[must_use]
+pub fn start_mempool_resync_checker(
+    core_address: String,
+    submitted: SubmittedTxs,
+    reforward: bool,
+    period: Duration,
+) -> JoinHandle<()> {
+    let (handler, panic_sender) = spawn_panic_handler();
+    MempoolResyncChecker::new(
+        CoreApiClient::new(core_address),
+        submitted,
+        reforward,
+        period,
+    )
+    .start_checker_detached(panic_sender, period);
+    handler
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use futures::{channel::oneshot, SinkExt};
+    use num::BigUint;
+    use zksync_mempool::{run_mempool_tx_handler, MempoolTransactionRequest};
+    use zksync_storage::ConnectionPool;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{AccountId, TokenId, ZkSyncTx};
+
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::TestServerConfig;
+
+    /// Core client backed by the real mempool handler and the database it writes to.
+    #[derive(Debug, Clone)]
+    struct LoopbackCore {
+        pool: ConnectionPool,
+        mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    }
+
+    impl LoopbackCore {
+        fn new(cfg: &TestServerConfig) -> Self {
+            let (mempool_tx_sender, mempool_tx_receiver) = mpsc::channel(16);
+            drop(run_mempool_tx_handler(
+                cfg.pool.clone(),
+                mempool_tx_receiver,
+                cfg.config.chain.state_keeper.block_chunk_sizes.clone(),
+            ));
+            Self {
+                pool: cfg.pool.clone(),
+                mempool_tx_sender,
+            }
+        }
+
+        async fn is_known(&self, tx_hashes: &[TxHash]) -> anyhow::Result<bool> {
+            Ok(self.known_txs(tx_hashes).await?.len() == tx_hashes.len())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl CoreMempoolClient for LoopbackCore {
+        async fn known_txs(&self, tx_hashes: &[TxHash]) -> anyhow::Result<Vec<TxHash>> {
+            let mut storage = self.pool.access_storage().await?;
+            let known_hashes = storage
+                .chain()
+                .mempool_schema()
+                .filter_known_txs(tx_hashes)
+                .await?;
+            Ok(known_hashes)
+        }
+
+        async fn reforward_txs(
+            &self,
+            submission: &MempoolSubmission,
+        ) -> anyhow::Result<Result<(), TxAddError>> {
+            let (sender, receiver) = oneshot::channel();
+            let request = match submission.clone() {
+                MempoolSubmission::Tx { tx } => {
+                    MempoolTransactionRequest::NewTx(Box::new(tx), sender)
+                }
+                MempoolSubmission::Batch {
+                    txs,
+                    eth_signatures,
+                } => MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, sender),
+            };
+            self.mempool_tx_sender.clone().send(request).await?;
+            Ok(receiver.await?)
+        }
+    }
+
+    /// Generates the transfers of a new account with the consecutive nonces.
+    fn gen_transfers(count: usize) -> Vec<SignedZkSyncTx> {
+        let from = ZkSyncAccount::rand();
+        from.set_account_id(Some(AccountId(zksync_crypto::rand::random::<u32>() >> 1)));
+        let to = ZkSyncAccount::rand();
+        (0..count)
+            .map(|_| {
+                let (transfer, _) = from.sign_transfer(
+                    TokenId(0),
+                    "ETH",
+                    BigUint::from(1u32),
+                    BigUint::from(1u32),
+                    &to.address,
+                    None,
+                    true,
+                    Default::default(),
+                );
+                SignedZkSyncTx {
+                    tx: ZkSyncTx::Transfer(Box::new(transfer)),
+                    eth_sign_data: None,
+                    created_at: Utc::now(),
+                }
+            })
+            .collect()
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn lost_txs_are_detected_and_reforwarded() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let core = LoopbackCore::new(&cfg);
+        let submitted = SubmittedTxs::new(SUBMITTED_TXS_CAPACITY);
+        let mut checker = MempoolResyncChecker::new(
+            core.clone(),
+            submitted.clone(),
+            true,
+            Duration::from_secs(0),
+        );
+
+        // The core has received only the first transaction, while the next one
+        // and the batch were lost.
+        let txs = gen_transfers(4);
+        let tx_hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
+        core.reforward_txs(&MempoolSubmission::Tx { tx: txs[0].clone() })
+            .await??;
+        submitted.push(txs[0].clone());
+        submitted.push(txs[1].clone());
+        submitted.push_batch(txs[2..].to_vec(), vec![]);
+        assert!(core.is_known(&tx_hashes[..1]).await?);
+        assert!(!core.is_known(&tx_hashes[1..2]).await?);
+        assert!(!core.is_known(&tx_hashes[2..]).await?);
+
+        let report = checker.check().await?;
+        assert_eq!(
+            report,
+            ResyncReport {
+                checked: 4,
+                missing: 3,
+                reforwarded: 3,
+            }
+        );
+        assert!(core.is_known(&tx_hashes).await?);
+
+        // After the re-forward the core acknowledges the lost transactions.
+        let report = checker.check().await?;
+        assert_eq!(
+            report,
+            ResyncReport {
+                checked: 3,
+                missing: 0,
+                reforwarded: 0,
+            }
+        );
+        assert!(submitted.is_empty());
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn lost_tx_is_only_reported_without_reforward() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let core = LoopbackCore::new(&cfg);
+        let submitted = SubmittedTxs::new(SUBMITTED_TXS_CAPACITY);
+        let mut checker = MempoolResyncChecker::new(
+            core.clone(),
+            submitted.clone(),
+            false,
+            Duration::from_secs(0),
+        );
+
+        let tx = gen_transfers(1).remove(0);
+        submitted.push(tx.clone());
+
+        for _ in 0..2 {
+            let report = checker.check().await?;
+            assert_eq!(report.missing, 1);
+            assert_eq!(report.reforwarded, 0);
+        }
+        assert!(!core.is_known(&[tx.hash()]).await?);
+        assert_eq!(submitted.len(), 1);
+
+        Ok(())
+    }
+
+    /// Checks that the real mempool handler stores a transaction submitted several times
+    /// (e.g. by a client that lost the response) only once, while accepting every submission.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn duplicate_submissions_are_stored_once() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let core = LoopbackCore::new(&cfg);
+
+        let txs = gen_transfers(2);
+        for tx in txs.iter().chain(&txs).chain(&txs[..1]) {
+            core.reforward_txs(&MempoolSubmission::Tx { tx: tx.clone() })
+                .await??;
+        }
+
+        let account_id = txs[0].account_id().unwrap();
+        let queued_nonces = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .get_queued_nonces(account_id)
+            .await?;
+        assert_eq!(
+            queued_nonces,
+            txs.iter().map(|tx| tx.nonce()).collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn submitted_txs_ring_evicts_oldest() {
+        let submitted = SubmittedTxs::new(2);
+        let txs = gen_transfers(4);
+        submitted.push(txs[0].clone());
+        submitted.push(txs[1].clone());
+        submitted.push_batch(txs[2..].to_vec(), vec![]);
+
+        let sampled: Vec<TxHash> = submitted
+            .sample(Duration::from_secs(0))
+            .iter()
+            .flat_map(MempoolSubmission::tx_hashes)
+            .collect();
+        assert_eq!(sampled, vec![txs[1].hash(), txs[2].hash(), txs[3].hash()]);
+
+        // The batch is forgotten only once all of its transactions are known.
+        submitted.remove(&[txs[1].hash(), txs[2].hash()]);
+        assert_eq!(submitted.len(), 1);
+        submitted.remove(&[txs[3].hash()]);
+        assert_eq!(submitted.len(), 1);
+        submitted.remove(&[txs[2].hash(), txs[3].hash()]);
+        assert!(submitted.is_empty());
+    }
+}
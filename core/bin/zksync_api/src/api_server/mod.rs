@@ -7,11 +7,16 @@
 mod event_notify;
 pub mod forced_exit_checker;
 mod helpers;
+mod maintenance_mode;
+pub mod mempool_resync;
 pub mod rest;
 pub mod rpc_server;
 pub mod rpc_subscriptions;
+pub mod sender_limiter;
+pub mod shutdown;
 mod tx_sender;
 pub mod web3;
+mod ws_transport;
 
 /// Amount of threads used by each server to serve requests.
 const THREADS_PER_SERVER: usize = 128;
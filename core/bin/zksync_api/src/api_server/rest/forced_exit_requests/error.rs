@@ -86,6 +86,9 @@ pub enum SumbitErrorCode {
     IncorrectTx = 104,
     TxAdd = 105,
     InappropriateFeeToken = 106,
+    TooManyRequests = 107,
+    MaintenanceMode = 108,
+    AccountBlocked = 109,
 
     Internal = 110,
     CommunicationCoreServer = 111,
@@ -100,7 +103,12 @@ impl SumbitErrorCode {
             SubmitError::UnsupportedFastProcessing => Self::UnsupportedFastProcessing,
             SubmitError::IncorrectTx(_) => Self::IncorrectTx,
             SubmitError::TxAdd(_) => Self::TxAdd,
+            SubmitError::NonceOutOfWindow { .. } => Self::TxAdd,
+            SubmitError::LimitExceeded { .. } => Self::TxAdd,
             SubmitError::InappropriateFeeToken => Self::InappropriateFeeToken,
+            SubmitError::TooManyRequests { .. } => Self::TooManyRequests,
+            SubmitError::MaintenanceMode { .. } => Self::MaintenanceMode,
+            SubmitError::AccountBlocked { .. } => Self::AccountBlocked,
             SubmitError::MempoolCommunication(_) => Self::CommunicationCoreServer,
             SubmitError::Internal(_) => Self::Internal,
             SubmitError::Other(_) => Self::Other,
@@ -118,10 +126,16 @@ impl From<SubmitError> for ApiError {
     fn from(inner: SubmitError) -> Self {
         let internal_code = SumbitErrorCode::from_err(&inner).as_code();
 
-        if let SubmitError::Internal(err) = &inner {
-            ApiError::internal(err)
-        } else {
-            ApiError::bad_request(inner)
+        match &inner {
+            SubmitError::Internal(err) => ApiError::internal(err),
+            SubmitError::TooManyRequests { .. } => {
+                ApiError::with_code(StatusCode::TOO_MANY_REQUESTS, inner)
+            }
+            SubmitError::MaintenanceMode { .. } => {
+                ApiError::with_code(StatusCode::SERVICE_UNAVAILABLE, inner)
+            }
+            SubmitError::AccountBlocked { .. } => ApiError::with_code(StatusCode::FORBIDDEN, inner),
+            _ => ApiError::bad_request(inner),
         }
         .code(internal_code)
     }
@@ -0,0 +1,301 @@
+//! Latency and status metrics of the REST API routes.
+//!
+//! Requests are labeled with the matched route pattern rather than the raw path, since the paths contain
+//! hashes and addresses. The v0.2 errors are returned with the `200` status, so they are counted separately
+//! in `api.http.api_errors`, while `api.http.requests` reflects the HTTP status only.
+
+// Built-in uses
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+    time::Instant,
+};
+
+// External uses
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::StatusCode,
+    HttpMessage,
+};
+use futures::future::LocalBoxFuture;
+
+// Local uses
+use super::v02::error::ErrorCode;
+
+/// Label of the requests which haven't matched any route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Code of the error returned by the v0.2 handler, stored in the request extensions.
+#[derive(Debug, Clone)]
+pub struct ApiErrorCode(pub ErrorCode);
+
+/// Returns the status class label, e.g. `4xx`.
+fn status_class(status: StatusCode) -> String {
+    format!("{}xx", status.as_u16() / 100)
+}
+
+/// Middleware recording the duration and the outcome of every request in the scope.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HttpMetrics;
+
+impl<S> Transform<S, ServiceRequest> for HttpMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Transform = HttpMetricsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HttpMetricsMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct HttpMetricsMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S> Service<ServiceRequest> for HttpMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let route = req
+            .request()
+            .match_pattern()
+            .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+        let method = req.method().to_string();
+        let start = Instant::now();
+
+        Box::pin(async move {
+            let result = service.call(req).await;
+            let status = match &result {
+                Ok(response) => response.status(),
+                Err(err) => err.as_response_error().status_code(),
+            };
+            let status = status_class(status);
+
+            metrics::histogram!(
+                "api.http.duration",
+                start.elapsed(),
+                "route" => route.clone(),
+                "method" => method.clone(),
+                "status" => status.clone()
+            );
+            metrics::increment_counter!(
+                "api.http.requests",
+                "route" => route.clone(),
+                "method" => method.clone(),
+                "status" => status
+            );
+            if let Ok(response) = &result {
+                let code = response
+                    .request()
+                    .extensions()
+                    .get::<ApiErrorCode>()
+                    .map(|ApiErrorCode(code)| code.code());
+                if let Some(code) = code {
+                    metrics::increment_counter!(
+                        "api.http.api_errors",
+                        "route" => route,
+                        "method" => method,
+                        "code" => code.to_string()
+                    );
+                }
+            }
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{error::Error, response::ApiResult, SharedData};
+    use actix_web::{web, App, HttpResponse};
+    use metrics::{GaugeValue, Key, Recorder, Unit};
+    use once_cell::sync::Lazy;
+    use std::{collections::BTreeMap, sync::Mutex};
+    use zksync_api_types::v02::ApiVersion;
+    use zksync_types::network::Network;
+
+    type Labels = BTreeMap<String, String>;
+
+    /// Recorder remembering the names and labels of the metrics.
+    #[derive(Debug, Default)]
+    struct TestRecorder {
+        records: Mutex<Vec<(String, Labels)>>,
+    }
+
+    impl TestRecorder {
+        fn record(&self, key: &Key) {
+            let labels = key
+                .labels()
+                .map(|label| (label.key().to_string(), label.value().to_string()))
+                .collect();
+            self.records
+                .lock()
+                .unwrap()
+                .push((key.name().to_string(), labels));
+        }
+
+        /// Returns the labels of the metrics with the given name recorded for the route.
+        fn recorded(&self, name: &str, route: &str) -> Vec<Labels> {
+            self.records
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(metric, labels)| {
+                    metric == name && labels.get("route").map(String::as_str) == Some(route)
+                })
+                .map(|(_, labels)| labels.clone())
+                .collect()
+        }
+    }
+
+    impl Recorder for TestRecorder {
+        fn register_counter(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+
+        fn register_gauge(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+
+        fn register_histogram(&self, _: &Key, _: Option<Unit>, _: Option<&'static str>) {}
+
+        fn increment_counter(&self, key: &Key, _: u64) {
+            self.record(key)
+        }
+
+        fn update_gauge(&self, key: &Key, _: GaugeValue) {
+            self.record(key)
+        }
+
+        fn record_histogram(&self, key: &Key, _: f64) {
+            self.record(key)
+        }
+    }
+
+    /// The recorder is global, so it's installed once for all the tests.
+    static RECORDER: Lazy<TestRecorder> = Lazy::new(TestRecorder::default);
+
+    fn recorder() -> &'static TestRecorder {
+        // Fails if the recorder is already installed, which is fine.
+        metrics::set_recorder(&*RECORDER).ok();
+        &RECORDER
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    async fn block(_: web::Path<u32>) -> ApiResult<()> {
+        ApiResult::Ok(())
+    }
+
+    async fn fail() -> ApiResult<()> {
+        ApiResult::from(Error::storage("database is down"))
+    }
+
+    async fn crash() -> HttpResponse {
+        HttpResponse::InternalServerError().finish()
+    }
+
+    #[actix_rt::test]
+    async fn route_metrics() -> anyhow::Result<()> {
+        let recorder = recorder();
+        let shared_data = SharedData {
+            net: Network::Localhost,
+            api_version: ApiVersion::V02,
+        };
+        let server = actix_test::start(move || {
+            App::new().app_data(web::Data::new(shared_data)).service(
+                web::scope("/metrics_test")
+                    .wrap(HttpMetrics)
+                    .route("/blocks/{number}", web::get().to(block))
+                    .route("/fail", web::post().to(fail))
+                    .route("/crash", web::get().to(crash)),
+            )
+        });
+
+        for path in ["/blocks/1", "/blocks/2", "/fail", "/crash", "/unknown"] {
+            let request = if path == "/fail" {
+                server.post(format!("/metrics_test{}", path))
+            } else {
+                server.get(format!("/metrics_test{}", path))
+            };
+            request
+                .send()
+                .await
+                .map_err(|err| anyhow::format_err!("{}", err))?;
+        }
+        server.stop().await;
+
+        // Paths are reduced to the route patterns.
+        let ok = labels(&[
+            ("route", "/metrics_test/blocks/{number}"),
+            ("method", "GET"),
+            ("status", "2xx"),
+        ]);
+        assert_eq!(
+            recorder.recorded("api.http.requests", "/metrics_test/blocks/{number}"),
+            vec![ok.clone(), ok.clone()]
+        );
+        assert_eq!(
+            recorder.recorded("api.http.duration", "/metrics_test/blocks/{number}"),
+            vec![ok.clone(), ok]
+        );
+        assert!(recorder
+            .recorded("api.http.api_errors", "/metrics_test/blocks/{number}")
+            .is_empty());
+
+        // API errors are sent with the successful HTTP status, but are counted separately.
+        assert_eq!(
+            recorder.recorded("api.http.requests", "/metrics_test/fail"),
+            vec![labels(&[
+                ("route", "/metrics_test/fail"),
+                ("method", "POST"),
+                ("status", "2xx"),
+            ])]
+        );
+        assert_eq!(
+            recorder.recorded("api.http.api_errors", "/metrics_test/fail"),
+            vec![labels(&[
+                ("route", "/metrics_test/fail"),
+                ("method", "POST"),
+                ("code", &ErrorCode::StorageError.code().to_string()),
+            ])]
+        );
+
+        // Transport-level errors are seen in the status only.
+        assert_eq!(
+            recorder.recorded("api.http.requests", "/metrics_test/crash"),
+            vec![labels(&[
+                ("route", "/metrics_test/crash"),
+                ("method", "GET"),
+                ("status", "5xx"),
+            ])]
+        );
+        assert!(recorder
+            .recorded("api.http.api_errors", "/metrics_test/crash")
+            .is_empty());
+
+        // The unknown paths don't produce the new labels.
+        assert!(recorder
+            .recorded("api.http.requests", "/metrics_test/unknown")
+            .is_empty());
+
+        Ok(())
+    }
+}
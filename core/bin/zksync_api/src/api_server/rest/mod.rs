@@ -1,37 +1,62 @@
 use actix_cors::Cors;
 use actix_web::{web, App, HttpResponse, HttpServer};
 use futures::channel::mpsc;
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 use zksync_storage::ConnectionPool;
-use zksync_types::{SequentialTxId, H160};
+use zksync_types::{SequentialTxId, ZkSyncTx, H160};
 
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 use self::v01::api_decl::ApiV01;
 use crate::signature_checker::VerifySignatureRequest;
 
-use super::tx_sender::TxSender;
+use super::{
+    mempool_resync::SubmittedTxs, sender_limiter::SenderRateLimiter, shutdown::ShutdownSignal,
+    tx_sender::TxSender,
+};
 
 use crate::api_server::rest::network_status::SharedNetworkStatus;
+use crate::core_api_client::CoreApiClient;
 use crate::fee_ticker::FeeTicker;
 use tokio::task::JoinHandle;
 use zksync_config::ZkSyncConfig;
+use zksync_eth_client::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
 
 mod forced_exit_requests;
 mod helpers;
+mod http_metrics;
 pub mod network_status;
 mod v01;
 pub mod v02;
 
+/// Interval of writing the accumulated usage of the API keys to the database.
+const API_KEY_USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[allow(clippy::too_many_arguments)]
 async fn start_server(
     api_v01: ApiV01,
     fee_ticker: FeeTicker,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     bind_to: SocketAddr,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    submitted_txs: Option<SubmittedTxs>,
+    sender_limiter: Option<SenderRateLimiter>,
+    accepted_txs_sender: Option<mpsc::Sender<Vec<ZkSyncTx>>>,
+    core_api_client: CoreApiClient,
+    eth_client: EthereumGateway,
+    shutdown: ShutdownSignal,
 ) {
-    HttpServer::new(move || {
+    let grace_period = api_v01.config.api.common.shutdown_grace_period();
+    // Rate limits are shared between the workers, so the middleware is created once.
+    // API keys are issued via the core, so the main database is used to see them right away.
+    let api_key_auth = v02::api_keys::ApiKeyAuth::new(
+        api_v01.main_database_connection_pool.clone(),
+        api_v01.config.api.rest.ip_requests_per_minute,
+    );
+    let usage_flusher = api_key_auth.spawn_usage_flusher(API_KEY_USAGE_FLUSH_INTERVAL);
+    let server_api_key_auth = api_key_auth.clone();
+    let server = HttpServer::new(move || {
         let api_v01 = api_v01.clone();
         // This api stores forced exit requests, it's necessary to use main database connection
         let forced_exit_requests_api_scope = forced_exit_requests::api_scope(
@@ -46,7 +71,7 @@ async fn start_server(
         );
 
         let api_v02_scope = {
-            let tx_sender = TxSender::new(
+            let mut tx_sender = TxSender::new(
                 api_v01.connection_pool.clone(),
                 sign_verifier.clone(),
                 fee_ticker.clone(),
@@ -54,7 +79,17 @@ async fn start_server(
                 &api_v01.config.api.token_config,
                 mempool_tx_sender.clone(),
             );
-            v02::api_scope(tx_sender, &api_v01.config, api_v01.network_status.clone())
+            tx_sender.submitted_txs = submitted_txs.clone();
+            tx_sender.accepted_txs = accepted_txs_sender.clone();
+            tx_sender.sender_limiter = sender_limiter.clone();
+            v02::api_scope(
+                tx_sender,
+                &api_v01.config,
+                api_v01.network_status.clone(),
+                server_api_key_auth.clone(),
+                core_api_client.clone(),
+                eth_client.clone(),
+            )
         };
         App::new()
             .wrap(
@@ -77,12 +112,29 @@ async fn start_server(
     .workers(super::THREADS_PER_SERVER)
     .bind(bind_to)
     .unwrap()
-    .shutdown_timeout(60)
+    // The server is stopped by the shutdown signal only.
+    .disable_signals()
+    .shutdown_timeout(grace_period.as_secs())
     .keep_alive(10)
     .client_timeout(60000)
-    .run()
-    .await
-    .expect("REST API server has crashed");
+    .run();
+
+    // The graceful stop rejects new connections and waits for the in-flight requests to finish.
+    let server_handle = server.handle();
+    let stop_requested = shutdown.clone().requested();
+    actix_rt::spawn(async move {
+        stop_requested.await;
+        server_handle.stop(true).await;
+    });
+    server.await.expect("REST API server has crashed");
+    // The usage of the requests served since the last periodic flush would be lost otherwise.
+    usage_flusher.abort();
+    if let Err(err) = api_key_auth.flush_usage().await {
+        vlog::warn!("Failed to flush the usage of the API keys: {}", err);
+    }
+    vlog::info!("REST API server has stopped");
+    // The signal is kept until the server is stopped, so the shutdown waits for it.
+    drop(shutdown);
 }
 
 /// Start HTTP REST API
@@ -96,7 +148,12 @@ pub fn start_server_thread_detached(
     fee_ticker: FeeTicker,
     sign_verifier: mpsc::Sender<VerifySignatureRequest>,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    accepted_txs_sender: Option<mpsc::Sender<Vec<ZkSyncTx>>>,
+    submitted_txs: Option<SubmittedTxs>,
+    sender_limiter: Option<SenderRateLimiter>,
     core_address: String,
+    eth_client: EthereumGateway,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
     let (handler, panic_sender) = spawn_panic_handler();
 
@@ -108,7 +165,7 @@ pub fn start_server_thread_detached(
                 // TODO remove this config ZKS-815
                 let config = ZkSyncConfig::from_env();
 
-                let mut network_status = SharedNetworkStatus::new(core_address);
+                let mut network_status = SharedNetworkStatus::new(core_address.clone());
                 // We want to update the network status, as soon as possible, otherwise we can catch the situation,
                 // when the node is started and receiving the request, but the status is still `null` and
                 // monitoring tools spawn the notification that our node is down, though it's just a default status
@@ -132,7 +189,13 @@ pub fn start_server_thread_detached(
                     fee_ticker,
                     sign_verifier,
                     listen_addr,
-                    mempool_tx_sender.clone(),
+                    mempool_tx_sender,
+                    submitted_txs,
+                    sender_limiter,
+                    accepted_txs_sender,
+                    CoreApiClient::new(core_address),
+                    eth_client,
+                    shutdown,
                 )
                 .await;
             });
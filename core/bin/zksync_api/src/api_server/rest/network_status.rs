@@ -4,9 +4,9 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::{runtime::Runtime, time};
-use zksync_api_types::CoreStatus;
-use zksync_storage::ConnectionPool;
-use zksync_types::{BlockNumber, SequentialTxId};
+use zksync_api_types::{v02, CoreStatus};
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{Address, BlockNumber, SequentialTxId};
 use zksync_utils::panic_notify::ThreadPanicNotify;
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
@@ -18,6 +18,34 @@ pub struct NetworkStatus {
     pub outstanding_txs: u32,
     pub mempool_size: u32,
     pub core_status: Option<CoreStatus>,
+    #[serde(skip)]
+    pub contract: Option<ContractInfo>,
+}
+
+impl From<&NetworkStatus> for v02::status::NetworkStatus {
+    fn from(status: &NetworkStatus) -> Self {
+        Self {
+            last_committed: status.last_committed,
+            finalized: status.last_verified,
+            total_transactions: status.total_transactions,
+            mempool_size: status.mempool_size,
+            core_status: status.core_status.clone(),
+        }
+    }
+}
+
+/// Current state of the contracts as stored in the database.
+/// Unlike the server config, it reflects the upgrades that happened after the server start.
+#[derive(Default, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ContractInfo {
+    pub contract_addr: Address,
+    pub gov_contract_addr: Address,
+    /// Version of the contracts set by the latest observed upgrade.
+    pub upgrade_version: Option<u64>,
+    /// Target of the main contract set by the latest observed upgrade.
+    pub contract_target_addr: Option<Address>,
+    /// Default NFT factory, `None` if it's not set yet.
+    pub nft_factory_addr: Option<Address>,
 }
 
 #[derive(Debug, Clone)]
@@ -53,6 +81,34 @@ impl SharedNetworkStatus {
             .await?)
     }
 
+    /// Loads the current state of the contracts.
+    async fn load_contract_info(
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<ContractInfo, anyhow::Error> {
+        let config = storage.config_schema().load_config().await?;
+        let parse_address = |address: String| -> Result<Address, anyhow::Error> {
+            Ok(address.trim_start_matches("0x").parse()?)
+        };
+        let parse_contract_address = |address: Option<String>| -> Result<Address, anyhow::Error> {
+            let address = address.ok_or_else(|| anyhow::anyhow!("Contract address is not set"))?;
+            parse_address(address)
+        };
+        let upgrade_version = storage
+            .config_schema()
+            .load_last_contract_upgrade()
+            .await?
+            .map(|upgrade| upgrade.version_id as u64);
+        let contract_target_addr = storage.config_schema().load_zksync_target().await?;
+
+        Ok(ContractInfo {
+            contract_addr: parse_contract_address(config.contract_addr)?,
+            gov_contract_addr: parse_contract_address(config.gov_contract_addr)?,
+            upgrade_version,
+            contract_target_addr,
+            nft_factory_addr: config.nft_factory_addr.map(parse_address).transpose()?,
+        })
+    }
+
     /// Updates shared network status. We use last_tx_id as a checkpoint
     /// to calculate total number of transactions faster
     pub(crate) async fn update(
@@ -101,6 +157,8 @@ impl SharedNetworkStatus {
             .await
             .unwrap_or(0);
 
+        let contract = Self::load_contract_info(&mut transaction).await.ok();
+
         transaction.commit().await.unwrap_or_default();
 
         let core_status = self.get_core_status().await.ok();
@@ -112,6 +170,7 @@ impl SharedNetworkStatus {
             outstanding_txs,
             mempool_size,
             core_status,
+            contract,
         };
 
         // save status to state
@@ -1,6 +1,7 @@
 //! Declaration of the API structure.
 
-use crate::api_server::rest::{helpers::*, v01::caches::Caches};
+use crate::api_server::rest::{helpers::*, http_metrics::HttpMetrics, v01::caches::Caches};
+use actix_web::dev::HttpServiceFactory;
 use actix_web::error::InternalError;
 use actix_web::{web, HttpResponse, Result as ActixResult};
 use futures::channel::mpsc;
@@ -52,9 +53,10 @@ impl ApiV01 {
     }
 
     /// Creates an actix-web `Scope`, which can be mounted to the Http server.
-    pub fn into_scope(self) -> actix_web::Scope {
+    pub fn into_scope(self) -> impl HttpServiceFactory {
         web::scope("/api/v0.1")
             .app_data(web::Data::new(self))
+            .wrap(HttpMetrics)
             .route("/testnet_config", web::get().to(Self::testnet_config))
             .route("/status", web::get().to(Self::status))
             .route("/tokens", web::get().to(Self::tokens))
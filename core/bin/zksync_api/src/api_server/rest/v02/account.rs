@@ -6,28 +6,257 @@ use std::str::FromStr;
 use std::time::Instant;
 
 // External uses
-use actix_web::{web, Scope};
+use actix_web::{
+    web::{self, Bytes, Json},
+    Either, HttpResponse,
+};
+use futures::{stream, Stream, StreamExt};
+use serde_json::Value;
 
 // Workspace uses
 use zksync_api_types::v02::{
-    account::{Account, AccountAddressOrId, AccountState, IncomingAccountTxsQuery},
+    account::{
+        Account, AccountActivity, AccountAddressOrId, AccountExitData, AccountExportQuery,
+        AccountExportRecord, AccountPendingTxs, AccountState, ExitDataQuery, ExportFormat,
+        IncomingAccountTxsQuery, PendingPriorityOp, PendingPriorityOpStatus, StoredBlockInfo,
+    },
     pagination::{
-        parse_query, AccountTxsRequest, ApiEither, Paginated, PaginationQuery, PendingOpsRequest,
+        AccountActivityRequest, AccountNFTsRequest, AccountQueuedTxsRequest, AccountTxsRequest,
+        ActivityPosition, ApiEither, CountOnlyQuery, Paginated, PaginationDirection,
+        PaginationQuery, PendingOpsRequest, QueuedTxPosition,
     },
-    transaction::{Transaction, TxHashSerializeWrapper},
+    token::NFT,
+    transaction::{AccountToggle2FA, Toggle2FAResponse, Transaction, TxHashSerializeWrapper},
+};
+use zksync_crypto::{
+    circuit::{account::CircuitAccount, CircuitAccountTree},
+    params::{self, MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL},
+    Fr,
 };
-use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenLike};
+use zksync_types::{
+    tx::TxHash, AccountId, AccountMap, Address, BlockNumber, PriorityOp, SerialId, TokenId,
+    TokenLike, ZkSyncOp,
+};
 
 // Local uses
 use super::{
     error::{Error, InvalidDataError},
-    paginate_trait::Paginate,
+    page_limit::parse_query,
+    paginate_trait::{Paginate, PaginateCount},
     response::ApiResult,
+    spec::{ApiScope, Operation},
 };
-use crate::{api_server::helpers::get_depositing, api_try, fee_ticker::PriceError};
+use crate::{
+    api_server::{helpers::get_depositing, tx_sender::TxSender},
+    api_try,
+    core_api_client::CoreApiClient,
+    fee_ticker::PriceError,
+    utils::shared_lru_cache::SharedLruCache,
+};
+
+/// Amount of the activity feed entries loaded from the database per chunk of the export.
+const EXPORT_CHUNK_SIZE: u32 = 100;
+/// Maximum amount of blocks in the range of a single export request.
+pub const MAX_EXPORT_BLOCKS: u32 = 10_000;
+/// Columns of the CSV export, named after the fields of the JSON record.
+const EXPORT_CSV_COLUMNS: [&str; 12] = [
+    "blockNumber",
+    "blockIndex",
+    "kind",
+    "txHash",
+    "timestamp",
+    "direction",
+    "counterparty",
+    "token",
+    "amount",
+    "feeToken",
+    "fee",
+    "status",
+];
+
+/// Formats the export record as a CSV row with the `EXPORT_CSV_COLUMNS` columns.
+fn export_csv_row(record: &AccountExportRecord) -> String {
+    let value = serde_json::to_value(record).expect("Export record should be serializable");
+    let fields: Vec<String> = EXPORT_CSV_COLUMNS
+        .iter()
+        .map(|column| match &value[*column] {
+            Value::Null => String::new(),
+            Value::String(field) if field.contains(|c| matches!(c, ',' | '"' | '\n')) => {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            }
+            Value::String(field) => field.clone(),
+            field => field.to_string(),
+        })
+        .collect();
+    fields.join(",")
+}
+
+/// Export of the account activity feed within the block range, loaded from the database by chunks.
+struct AccountExport {
+    pool: ConnectionPool,
+    address: Address,
+    to_block: BlockNumber,
+    format: ExportFormat,
+    /// Position the next chunk is loaded from, inclusive.
+    cursor: ActivityPosition,
+    /// Whether the entry at the cursor is exported already.
+    cursor_exported: bool,
+    records_exported: usize,
+    finished: bool,
+}
+
+impl AccountExport {
+    fn new(pool: ConnectionPool, address: Address, query: &AccountExportQuery) -> Self {
+        let range_start = ActivityPosition {
+            block_number: query.from_block,
+            ..Default::default()
+        };
+        let (cursor, cursor_exported) = match query.after {
+            Some(after) if after.block_number >= query.from_block => (after, true),
+            _ => (range_start, false),
+        };
+        Self {
+            pool,
+            address,
+            to_block: query.to_block,
+            format: query.format,
+            cursor,
+            cursor_exported,
+            records_exported: 0,
+            finished: false,
+        }
+    }
+
+    fn header(&self) -> String {
+        match self.format {
+            ExportFormat::Json => String::from("["),
+            ExportFormat::Csv => format!("{}\n", EXPORT_CSV_COLUMNS.join(",")),
+        }
+    }
+
+    fn footer(&self) -> &'static str {
+        match self.format {
+            ExportFormat::Json => "]",
+            ExportFormat::Csv => "",
+        }
+    }
+
+    /// Loads and formats the next chunk of the feed. The footer is appended to the last chunk.
+    async fn next_chunk(&mut self) -> Result<String, Error> {
+        let query = PaginationQuery {
+            from: AccountActivityRequest {
+                address: self.address,
+                position: ApiEither::from(self.cursor),
+            },
+            limit: EXPORT_CHUNK_SIZE,
+            direction: PaginationDirection::Newer,
+        };
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let activity = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_activity(&query)
+            .await
+            .map_err(Error::storage)?;
+        self.finished = activity.len() < EXPORT_CHUNK_SIZE as usize;
+
+        let mut chunk = String::new();
+        for entry in &activity {
+            let record = match AccountExportRecord::from_activity(entry, self.address) {
+                Some(record) => record,
+                None => continue,
+            };
+            if record.block_number > self.to_block {
+                self.finished = true;
+                break;
+            }
+            let position = record.position();
+            if self.cursor_exported && position == self.cursor {
+                continue;
+            }
+
+            match self.format {
+                ExportFormat::Json => {
+                    if self.records_exported > 0 {
+                        chunk.push(',');
+                    }
+                    chunk.push_str(
+                        &serde_json::to_string(&record)
+                            .expect("Export record should be serializable"),
+                    );
+                }
+                ExportFormat::Csv => {
+                    chunk.push_str(&export_csv_row(&record));
+                    chunk.push('\n');
+                }
+            }
+            self.records_exported += 1;
+            self.cursor = position;
+            self.cursor_exported = true;
+        }
+        if self.finished {
+            chunk.push_str(self.footer());
+        }
+        Ok(chunk)
+    }
+
+    fn into_stream(self) -> impl Stream<Item = Result<Bytes, actix_web::Error>> {
+        let header = Bytes::from(self.header());
+        let chunks = stream::unfold(self, |mut export| async move {
+            if export.finished {
+                return None;
+            }
+            let chunk = export.next_chunk().await.map(Bytes::from).map_err(|err| {
+                // The response is already started, so the client gets the truncated export
+                // and is expected to resume it from the last received record.
+                export.finished = true;
+                actix_web::error::ErrorInternalServerError(err.message)
+            });
+            Some((chunk, export))
+        });
+        stream::once(async { Ok(header) }).chain(chunks)
+    }
+}
+
+/// Merkle paths of the account token balance: the path of the account in the account tree
+/// and the path of the token in the account balance tree, from the leaf to the root.
+#[derive(Debug, Clone, PartialEq)]
+struct ExitPaths {
+    root_hash: Fr,
+    account_path: Vec<Fr>,
+    balance_path: Vec<Fr>,
+}
+
+/// Restores the account tree from the given state and builds the Merkle paths of the account token balance.
+fn exit_paths(accounts: AccountMap, account_id: AccountId, token_id: TokenId) -> ExitPaths {
+    let mut tree = CircuitAccountTree::new(params::account_tree_depth());
+    for (id, account) in accounts {
+        tree.insert(*id, CircuitAccount::from(account));
+    }
+
+    let account_path = tree
+        .merkle_path(*account_id)
+        .into_iter()
+        .map(|(hash, _)| hash)
+        .collect();
+    let default_account = CircuitAccount::default();
+    let balance_path = tree
+        .get(*account_id)
+        .unwrap_or(&default_account)
+        .subtree
+        .merkle_path(*token_id as u32)
+        .into_iter()
+        .map(|(hash, _)| hash)
+        .collect();
+
+    ExitPaths {
+        root_hash: tree.root_hash(),
+        account_path,
+        balance_path,
+    }
+}
 
 /// Shared data between `api/v02/accounts` endpoints.
 #[derive(Clone)]
@@ -35,14 +264,37 @@ struct ApiAccountData {
     pool: ConnectionPool,
     tokens: TokenDBCache,
     confirmations_for_eth_event: u64,
+    exit_data_enabled: bool,
+    /// Exit data for the last verified block, the account and the token.
+    exit_data_cache: SharedLruCache<(BlockNumber, AccountId, TokenId), AccountExitData>,
+    /// Priority operations that are not executed yet are known to the core only.
+    core_api_client: CoreApiClient,
+    /// 2FA requests are checked by the signature checker.
+    tx_sender: TxSender,
+    /// Maximum number of items on a page of the paginated endpoints.
+    max_page_limit: u32,
 }
 
 impl ApiAccountData {
-    fn new(pool: ConnectionPool, tokens: TokenDBCache, confirmations_for_eth_event: u64) -> Self {
+    fn new(
+        pool: ConnectionPool,
+        tokens: TokenDBCache,
+        confirmations_for_eth_event: u64,
+        exit_data_enabled: bool,
+        caches_size: usize,
+        core_api_client: CoreApiClient,
+        tx_sender: TxSender,
+        max_page_limit: u32,
+    ) -> Self {
         Self {
             pool,
             tokens,
             confirmations_for_eth_event,
+            exit_data_enabled,
+            exit_data_cache: SharedLruCache::new(caches_size),
+            core_api_client,
+            tx_sender,
+            max_page_limit,
         }
     }
 
@@ -295,10 +547,12 @@ impl ApiAccountData {
         query: PaginationQuery<ApiEither<TxHash>>,
         address: Address,
         token_like: Option<TokenLike>,
+        include_fee_token: bool,
         second_address: Option<Address>,
+        count_only: bool,
     ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
         let token = if let Some(token_like) = token_like {
+            let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
             Some(
                 self.tokens
                     .get_token(&mut storage, token_like.clone())
@@ -315,24 +569,204 @@ impl ApiAccountData {
                 tx_hash: query.from,
                 address,
                 token,
+                include_fee_token,
                 second_address,
             },
             limit: query.limit,
             direction: query.direction,
         };
-        storage.paginate_checked(&new_query).await
+        if count_only {
+            self.pool.clone().paginate_count(&new_query).await
+        } else {
+            self.pool.clone().paginate(&new_query).await
+        }
+    }
+
+    async fn account_activity(
+        &self,
+        query: PaginationQuery<ApiEither<ActivityPosition>>,
+        address: Address,
+    ) -> Result<Paginated<AccountActivity, ActivityPosition>, Error> {
+        let new_query = PaginationQuery {
+            from: AccountActivityRequest {
+                address,
+                position: query.from,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        storage.paginate(&new_query).await
+    }
+
+    async fn account_queued_txs(
+        &self,
+        query: PaginationQuery<ApiEither<QueuedTxPosition>>,
+        address: Address,
+    ) -> Result<Paginated<Transaction, QueuedTxPosition>, Error> {
+        let new_query = PaginationQuery {
+            from: AccountQueuedTxsRequest {
+                address,
+                position: query.from,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        storage.paginate(&new_query).await
+    }
+
+    async fn account_nfts(
+        &self,
+        query: PaginationQuery<ApiEither<TokenId>>,
+        account_id: AccountId,
+    ) -> Result<Paginated<NFT, TokenId>, Error> {
+        let new_query = PaginationQuery {
+            from: AccountNFTsRequest {
+                account_id,
+                token_id: query.from,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        storage.paginate(&new_query).await
+    }
+
+    async fn account_export(
+        &self,
+        account_id_or_address: &str,
+        query: AccountExportQuery,
+    ) -> Result<HttpResponse, Error> {
+        if query.to_block < query.from_block {
+            return Err(Error::from(InvalidDataError::InvalidExportRange));
+        }
+        if *query.to_block - *query.from_block >= MAX_EXPORT_BLOCKS {
+            return Err(Error::from(InvalidDataError::ExportRangeTooBig));
+        }
+        let address_or_id = self.parse_account_id_or_address(account_id_or_address)?;
+        let address = self.get_address_by_address_or_id(address_or_id).await?;
+
+        let content_type = match query.format {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv",
+        };
+        let export = AccountExport::new(self.pool.clone(), address, &query);
+        Ok(HttpResponse::Ok()
+            .content_type(content_type)
+            .streaming(Box::pin(export.into_stream())))
+    }
+
+    async fn account_exit_data(
+        &self,
+        account_id: AccountId,
+        token_like: TokenLike,
+    ) -> Result<AccountExitData, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let token = self
+            .tokens
+            .get_token(&mut storage, token_like.clone())
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(PriceError::token_not_found(token_like)))?;
+        // Exiting with NFT requires the paths of the NFT creator and the NFT storage accounts as well.
+        if token.id.0 >= MIN_NFT_TOKEN_ID {
+            return Err(Error::from(InvalidDataError::NFTExitDataUnsupported));
+        }
+
+        let last_verified_block = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
+        if let Some(exit_data) =
+            self.exit_data_cache
+                .get(&(last_verified_block, account_id, token.id))
+        {
+            return Ok(exit_data);
+        }
+
+        let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+        let (block_number, accounts) = transaction
+            .chain()
+            .state_schema()
+            .load_verified_state()
+            .await
+            .map_err(Error::storage)?;
+        let block = transaction
+            .chain()
+            .block_schema()
+            .get_block(block_number)
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(anyhow::anyhow!("Verified block is not stored")))?;
+        // Placeholder creator for the fungible tokens is the account with id 0.
+        let nft_creator_address = transaction
+            .chain()
+            .account_schema()
+            .account_address_by_id(AccountId(0))
+            .await
+            .map_err(Error::storage)?
+            .unwrap_or_default();
+        transaction.commit().await.map_err(Error::storage)?;
+
+        let account = accounts
+            .get(&account_id)
+            .cloned()
+            .ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))?;
+
+        // Restoring the tree is CPU-heavy, so it shouldn't block the server.
+        let token_id = token.id;
+        let paths = tokio::task::spawn_blocking(move || exit_paths(accounts, account_id, token_id))
+            .await
+            .map_err(|err| Error::from(anyhow::Error::from(err)))?;
+        if paths.root_hash != block.new_root_hash {
+            return Err(Error::from(anyhow::anyhow!(
+                "Restored state doesn't match the root hash of the verified block {}",
+                block_number
+            )));
+        }
+
+        let exit_data = AccountExitData {
+            stored_block_info: StoredBlockInfo {
+                block_number,
+                priority_operations: block.number_of_processed_prior_ops(),
+                pending_onchain_operations_hash: block.get_onchain_operations_block_info().1,
+                timestamp: block.timestamp,
+                state_hash: block.get_eth_encoded_root(),
+                commitment: block.block_commitment,
+            },
+            owner: account.address,
+            account_id,
+            token_id,
+            token_address: token.address,
+            amount: account.get_balance(token_id),
+            nonce: account.nonce,
+            pub_key_hash: account.pub_key_hash,
+            nft_creator_id: AccountId(0),
+            nft_creator_address,
+            nft_serial_id: 0,
+            nft_content_hash: Default::default(),
+            account_path: paths.account_path,
+            balance_path: paths.balance_path,
+        };
+        self.exit_data_cache
+            .insert((block_number, account_id, token_id), exit_data.clone());
+        Ok(exit_data)
     }
 
     /// Pending deposits can be matched only with addresses,
     /// while pending full exits can be matched only with account ids.
     /// If the account isn't created yet it doesn't have an id
     /// but we can still find pending deposits for its address that is why account_id is Option.
+    /// If the account is stuck behind an unexecutable transaction, its hash is returned as well.
     async fn account_pending_txs(
         &self,
         query: PaginationQuery<ApiEither<SerialId>>,
         address: Address,
         account_id: Option<AccountId>,
-    ) -> Result<Paginated<Transaction, SerialId>, Error> {
+    ) -> Result<AccountPendingTxs, Error> {
         let new_query = PaginationQuery {
             from: PendingOpsRequest {
                 address,
@@ -343,7 +777,68 @@ impl ApiAccountData {
             direction: query.direction,
         };
         let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        storage.paginate_checked(&new_query).await
+        let txs = storage.paginate(&new_query).await?;
+        let blocked_by = match account_id {
+            Some(account_id) => storage
+                .chain()
+                .mempool_schema()
+                .get_stuck_account(account_id)
+                .await
+                .map_err(Error::storage)?
+                .map(|account| TxHashSerializeWrapper(account.blocking_tx_hash)),
+            None => None,
+        };
+        Ok(AccountPendingTxs { txs, blocked_by })
+    }
+
+    /// Merges the priority operations seen by the Ethereum watcher of the core
+    /// with the ones executed in the blocks that are not finalized yet.
+    async fn account_pending_priority_ops(
+        &self,
+        address: Address,
+    ) -> Result<Vec<PendingPriorityOp>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let last_finalized = storage
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
+        let executed_ops = storage
+            .chain()
+            .operations_schema()
+            .get_executed_priority_ops_after_block(address, last_finalized)
+            .await
+            .map_err(Error::storage)?;
+        let max_executed_serial_id = storage
+            .chain()
+            .operations_schema()
+            .get_max_priority_op_serial_id()
+            .await
+            .map_err(Error::storage)?;
+        let queued_ops = self
+            .core_api_client
+            .pending_priority_ops(address)
+            .await
+            .map_err(Error::core_api)?;
+
+        let executed_ops = executed_ops.into_iter().map(|op| {
+            // Withdrawn amount of the full exit is known only after the execution.
+            let withdrawn_amount = match serde_json::from_value(op.operation.clone()) {
+                Ok(ZkSyncOp::FullExit(full_exit)) => full_exit.withdraw_amount,
+                _ => None,
+            };
+            let mut op =
+                PendingPriorityOp::new(&PriorityOp::from(op), PendingPriorityOpStatus::Executed, 0);
+            op.amount = op.amount.or(withdrawn_amount);
+            op
+        });
+        // Priority operations are executed in the order of their serial ids, so the watcher
+        // may still report the operations which are already executed.
+        let queued_ops = queued_ops
+            .into_iter()
+            .filter(|op| max_executed_serial_id.map_or(true, |serial_id| op.serial_id > serial_id));
+        Ok(executed_ops.chain(queued_ops).collect())
     }
 }
 
@@ -399,14 +894,17 @@ async fn account_txs(
     data: web::Data<ApiAccountData>,
     account_id_or_address: web::Path<String>,
     web::Query(query): web::Query<IncomingAccountTxsQuery>,
+    web::Query(CountOnlyQuery { count_only }): web::Query<CountOnlyQuery>,
 ) -> ApiResult<Paginated<Transaction, TxHashSerializeWrapper>> {
     let start = Instant::now();
-    let pagination = api_try!(parse_query(PaginationQuery {
-        from: query.from,
-        limit: query.limit,
-        direction: query.direction
-    })
-    .map_err(Error::from));
+    let pagination = api_try!(parse_query(
+        PaginationQuery {
+            from: query.from,
+            limit: query.limit,
+            direction: query.direction
+        },
+        data.max_page_limit
+    ));
 
     let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
     let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
@@ -423,20 +921,41 @@ async fn account_txs(
     let token_like = query.token.map(|token| TokenLike::parse(&token));
 
     let res = data
-        .account_txs(pagination, address, token_like, second_address)
+        .account_txs(
+            pagination,
+            address,
+            token_like,
+            query.include_fee_token,
+            second_address,
+            count_only,
+        )
         .await
         .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_txs");
     res
 }
 
+async fn account_activity(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<AccountActivity, ActivityPosition>> {
+    let start = Instant::now();
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.account_activity(query, address).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_activity");
+    res
+}
+
 async fn account_pending_txs(
     data: web::Data<ApiAccountData>,
     account_id_or_address: web::Path<String>,
     web::Query(query): web::Query<PaginationQuery<String>>,
-) -> ApiResult<Paginated<Transaction, SerialId>> {
+) -> ApiResult<AccountPendingTxs> {
     let start = Instant::now();
-    let query = api_try!(parse_query(query).map_err(Error::from));
+    let query = api_try!(parse_query(query, data.max_page_limit));
     let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
     let address = api_try!(
         data.get_address_by_address_or_id(address_or_id.clone())
@@ -451,56 +970,309 @@ async fn account_pending_txs(
     res
 }
 
+/// Lists the deposits and full exits targeting the account that are not finalized yet.
+async fn account_pending_priority_ops(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+) -> ApiResult<Vec<PendingPriorityOp>> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.account_pending_priority_ops(address).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_pending_priority_ops");
+    res
+}
+
+/// Lists the transactions initiated by the account that are waiting in the mempool, ordered by nonce.
+/// Parked transactions are not listed.
+async fn account_queued_txs(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<Transaction, QueuedTxPosition>> {
+    let start = Instant::now();
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let address = api_try!(data.get_address_by_address_or_id(address_or_id).await);
+    let res = data.account_queued_txs(query, address).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_queued_txs");
+    res
+}
+
+/// Lists the NFTs owned by the account in the committed state.
+async fn account_nfts(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<NFT, TokenId>> {
+    let start = Instant::now();
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    // Unknown account id is rejected while resolving the address.
+    api_try!(
+        data.get_address_by_address_or_id(address_or_id.clone())
+            .await
+    );
+    let account_id =
+        api_try!(data
+            .get_id_by_address_or_id(address_or_id)
+            .await
+            .and_then(|account_id| {
+                account_id.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))
+            }));
+    let res = data.account_nfts(query, account_id).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_nfts");
+    res
+}
+
+/// Streams the activity of the account within the block range. Errors that occur
+/// before the export is started are returned in the usual response format.
+async fn account_export(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<AccountExportQuery>,
+) -> Either<HttpResponse, ApiResult<()>> {
+    let start = Instant::now();
+    let res = data.account_export(&account_id_or_address, query).await;
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_export");
+    match res {
+        Ok(response) => Either::Left(response),
+        Err(err) => Either::Right(err.into()),
+    }
+}
+
+async fn account_exit_data(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    web::Query(query): web::Query<ExitDataQuery>,
+) -> ApiResult<AccountExitData> {
+    let start = Instant::now();
+    if !data.exit_data_enabled {
+        return Error::from(InvalidDataError::ExitDataDisabled).into();
+    }
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id =
+        api_try!(data
+            .get_id_by_address_or_id(address_or_id)
+            .await
+            .and_then(|account_id| {
+                account_id.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))
+            }));
+    let token_like = TokenLike::parse(&query.token);
+    let res = data.account_exit_data(account_id, token_like).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_exit_data");
+    res
+}
+
+/// Opts the account into or out of 2FA. The request is signed by the account owner on L1.
+async fn account_toggle_2fa(
+    data: web::Data<ApiAccountData>,
+    account_id_or_address: web::Path<String>,
+    Json(request): Json<AccountToggle2FA>,
+) -> ApiResult<Toggle2FAResponse> {
+    let start = Instant::now();
+    let address_or_id = api_try!(data.parse_account_id_or_address(&account_id_or_address));
+    let account_id =
+        api_try!(data
+            .get_id_by_address_or_id(address_or_id)
+            .await
+            .and_then(|account_id| {
+                account_id.ok_or_else(|| Error::from(InvalidDataError::AccountNotFound))
+            }));
+    let res = data
+        .tx_sender
+        .toggle_2fa(request.into_toggle(account_id))
+        .await
+        .map_err(Error::from)
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "account_toggle_2fa");
+    res
+}
+
+const ACCOUNT_PARAM: &str = "account_id_or_address";
+const ACCOUNT_PARAM_DESCRIPTION: &str = "Id or address of the account";
+
 pub fn api_scope(
     pool: ConnectionPool,
     tokens: TokenDBCache,
     confirmations_for_eth_event: u64,
-) -> Scope {
-    let data = ApiAccountData::new(pool, tokens, confirmations_for_eth_event);
+    exit_data_enabled: bool,
+    caches_size: usize,
+    core_api_client: CoreApiClient,
+    tx_sender: TxSender,
+    max_page_limit: u32,
+) -> ApiScope {
+    let data = ApiAccountData::new(
+        pool,
+        tokens,
+        confirmations_for_eth_event,
+        exit_data_enabled,
+        caches_size,
+        core_api_client,
+        tx_sender,
+        max_page_limit,
+    );
 
-    web::scope("accounts")
+    ApiScope::new("accounts")
         .app_data(web::Data::new(data))
         .route(
-            "{account_id_or_address}/committed",
-            web::get().to(account_committed_info),
+            Operation::get(
+                "{account_id_or_address}/committed",
+                "Committed state of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .result::<Option<Account>>(),
+            account_committed_info,
         )
         .route(
-            "{account_id_or_address}/finalized",
-            web::get().to(account_finalized_info),
+            Operation::get(
+                "{account_id_or_address}/finalized",
+                "Finalized state of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .result::<Option<Account>>(),
+            account_finalized_info,
         )
-        .route("{account_id_or_address}", web::get().to(account_full_info))
         .route(
-            "{account_id_or_address}/transactions",
-            web::get().to(account_txs),
+            Operation::get(
+                "{account_id_or_address}",
+                "Committed and finalized states of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .result::<AccountState>(),
+            account_full_info,
         )
         .route(
-            "{account_id_or_address}/transactions/pending",
-            web::get().to(account_pending_txs),
+            Operation::get(
+                "{account_id_or_address}/transactions",
+                "Transactions of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .query::<IncomingAccountTxsQuery>()
+            .result::<Paginated<Transaction, TxHashSerializeWrapper>>(),
+            account_txs,
         )
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::api_server::rest::v02::{
-        test_utils::{deserialize_response_result, TestServerConfig},
-        SharedData,
-    };
-    use num::BigUint;
-    use serde::Deserialize;
-    use zksync_api_client::rest::client::Client;
-    use zksync_api_types::v02::{
-        account::{DepositingAccountBalances, DepositingFunds},
-        pagination::{PaginationDirection, PaginationQuery},
-        transaction::{L1Transaction, TransactionData},
-        ApiVersion,
-    };
-    use zksync_storage::StorageProcessor;
-    use zksync_types::{AccountId, Address, Deposit, PriorityOp, TokenId, ZkSyncPriorityOp, H256};
-
-    // While the values of the PendingOpsFlattenRequest's fields are never directly
-    // used in the tests, we still need them to specify the JSON format of the `unconfirmed_ops` endpoint input in tests.
-    #[derive(Debug, Deserialize)]
+        .route(
+            Operation::get(
+                "{account_id_or_address}/transactions/pending",
+                "Pending transactions of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .query::<PaginationQuery<String>>()
+            .result::<AccountPendingTxs>(),
+            account_pending_txs,
+        )
+        .route(
+            Operation::get(
+                "{account_id_or_address}/pending",
+                "Transactions of the account queued in the mempool",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .query::<PaginationQuery<String>>()
+            .result::<Paginated<Transaction, QueuedTxPosition>>(),
+            account_queued_txs,
+        )
+        .route(
+            Operation::get(
+                "{account_id_or_address}/pending_priority_ops",
+                "Pending priority operations of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .result::<Vec<PendingPriorityOp>>(),
+            account_pending_priority_ops,
+        )
+        .route(
+            Operation::get(
+                "{account_id_or_address}/activity",
+                "Activity feed of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .query::<PaginationQuery<String>>()
+            .result::<Paginated<AccountActivity, ActivityPosition>>(),
+            account_activity,
+        )
+        .route(
+            Operation::get("{account_id_or_address}/nfts", "NFTs owned by the account")
+                .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+                .query::<PaginationQuery<String>>()
+                .result::<Paginated<NFT, u32>>(),
+            account_nfts,
+        )
+        .route(
+            Operation::get(
+                "{account_id_or_address}/export",
+                "Export of the account history",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .query::<AccountExportQuery>()
+            .stream(&["application/json", "text/csv"]),
+            account_export,
+        )
+        .route(
+            Operation::get(
+                "{account_id_or_address}/exit_data",
+                "Data required to exit the network with the account balance",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .query::<ExitDataQuery>()
+            .result::<AccountExitData>(),
+            account_exit_data,
+        )
+        .route(
+            Operation::post(
+                "{account_id_or_address}/2fa",
+                "Enables or disables 2FA of the account",
+            )
+            .path_param(ACCOUNT_PARAM, ACCOUNT_PARAM_DESCRIPTION)
+            .body::<AccountToggle2FA>()
+            .result::<Toggle2FAResponse>(),
+            account_toggle_2fa,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::{
+        rest::v02::{
+            test_utils::{
+                deserialize_response_result, submit_txs_loopback, test_tx_sender, TestServerConfig,
+                COMMITTED_BLOCKS_COUNT, COMMITTED_OP_SERIAL_ID,
+            },
+            SharedData,
+        },
+        rpc_server::error::RpcErrorCodes,
+        tx_sender::SubmitError,
+    };
+    use crate::mempool_analyzer::MempoolAnalyzer;
+    use chrono::Utc;
+    use num::BigUint;
+    use serde::Deserialize;
+    use tokio::task::JoinHandle;
+    use zksync_api_client::rest::client::Client;
+    use zksync_api_types::v02::{
+        account::{DepositingAccountBalances, DepositingFunds, ExportDirection},
+        error::ErrorCode,
+        pagination::{ActivityKind, PaginationDirection, PaginationQuery, MAX_LIMIT},
+        transaction::{L1Transaction, TransactionData, TxInBlockStatus, TxType},
+        ApiVersion, Response,
+    };
+    use zksync_crypto::{
+        circuit::account::Balance, ff::PrimeField, merkle_tree::hasher::Hasher,
+        primitives::GetBits, Engine,
+    };
+    use zksync_storage::{chain::account::records::EthAccountType, StorageProcessor};
+    use zksync_types::{
+        tx::{
+            error::TxAddError, PackedEthSignature, Transfer, TxEthSignature, TxEthSignatureVariant,
+        },
+        AccountId, AccountUpdate, Address, Deposit, Nonce, PriorityOp, SignedZkSyncTx, TokenId,
+        ZkSyncPriorityOp, ZkSyncTx, H256,
+    };
+
+    // While the values of the PendingOpsFlattenRequest's fields are never directly
+    // used in the tests, we still need them to specify the JSON format of the `unconfirmed_ops` endpoint input in tests.
+    #[derive(Debug, Deserialize)]
     #[allow(dead_code)]
     struct PendingOpsFlattenRequest {
         pub address: Address,
@@ -512,8 +1284,50 @@ mod tests {
 
     struct TestServer {
         api_server: actix_test::TestServer,
+        core_server: actix_test::TestServer,
         pool: ConnectionPool,
         confirmations_for_eth_event: u64,
+        tx_sender: TxSender,
+        mempool_task: JoinHandle<()>,
+    }
+
+    /// Serial id of the deposit which is not confirmed yet according to the mock core.
+    const UNCONFIRMED_SERIAL_ID: SerialId = 1_000_000;
+
+    /// Deposit to the address reported by the mock core.
+    fn queued_deposit(serial_id: SerialId, to: Address) -> PriorityOp {
+        PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Address::repeat_byte(0x11),
+                token: TokenId(0),
+                amount: BigUint::from(100u32),
+                to,
+            }),
+            deadline_block: 0,
+            eth_hash: H256::from_low_u64_be(serial_id),
+            eth_block: 10,
+            eth_block_index: Some(0),
+            eth_block_hash: None,
+            l1_surplus: None,
+        }
+    }
+
+    /// Reports the deposits to any address as the Ethereum watcher of the core does: the first one
+    /// is already executed, the second one doesn't have enough confirmations yet.
+    async fn mock_pending_priority_ops(address: web::Path<Address>) -> HttpResponse {
+        HttpResponse::Ok().json(vec![
+            PendingPriorityOp::new(
+                &queued_deposit(0, *address),
+                PendingPriorityOpStatus::Confirmed,
+                0,
+            ),
+            PendingPriorityOp::new(
+                &queued_deposit(UNCONFIRMED_SERIAL_ID, *address),
+                PendingPriorityOpStatus::Unconfirmed,
+                3,
+            ),
+        ])
     }
 
     impl TestServer {
@@ -523,10 +1337,22 @@ mod tests {
 
             let pool = cfg.pool.clone();
 
+            let core_server = actix_test::start(|| {
+                actix_web::App::new().route(
+                    "/priority_ops/pending/{address}",
+                    web::get().to(mock_pending_priority_ops),
+                )
+            });
+            let core_address = core_server.url("").trim_end_matches('/').to_owned();
+
+            let (mempool_sender, mempool_task) = submit_txs_loopback();
+            let tx_sender = test_tx_sender(&cfg, &cfg.config.api.common, mempool_sender);
+
             let shared_data = SharedData {
                 net: cfg.config.chain.eth.network,
                 api_version: ApiVersion::V02,
             };
+            let scope_tx_sender = tx_sender.clone();
             let (api_client, api_server) = cfg.start_server(
                 move |cfg: &TestServerConfig| {
                     api_scope(
@@ -535,6 +1361,11 @@ mod tests {
                             cfg.config.api.token_config.invalidate_token_cache_period(),
                         ),
                         cfg.config.eth_watch.confirmations_for_eth_event,
+                        cfg.config.api.rest.exit_data_enabled,
+                        cfg.config.api.common.caches_size,
+                        CoreApiClient::new(core_address.clone()),
+                        scope_tx_sender.clone(),
+                        cfg.config.api.rest.max_page_limit,
                     )
                 },
                 Some(shared_data),
@@ -544,12 +1375,30 @@ mod tests {
                 api_client,
                 Self {
                     api_server,
+                    core_server,
                     pool,
                     confirmations_for_eth_event: cfg.config.eth_watch.confirmations_for_eth_event,
+                    tx_sender,
+                    mempool_task,
                 },
             ))
         }
 
+        /// Requests the export of the account activity and returns the response body.
+        async fn export(&self, address: Address, query: &str) -> anyhow::Result<String> {
+            let mut response = self
+                .api_server
+                .get(format!("/api/v0.2/accounts/{:?}/export?{}", address, query))
+                .send()
+                .await
+                .map_err(|err| anyhow::anyhow!("Export request failed: {}", err))?;
+            let body = response
+                .body()
+                .await
+                .map_err(|err| anyhow::anyhow!("Export response is not received: {}", err))?;
+            Ok(String::from_utf8(body.to_vec())?)
+        }
+
         async fn account_id_and_tx_hash(
             storage: &mut StorageProcessor<'_>,
             block: BlockNumber,
@@ -573,7 +1422,71 @@ mod tests {
 
         async fn stop(self) {
             self.api_server.stop().await;
+            self.core_server.stop().await;
+            self.mempool_task.abort();
+        }
+    }
+
+    /// Recomputes the root hash from the leaf hash and the sibling hashes on the path to the root.
+    fn root_from_path(hasher: &impl Hasher<Fr>, leaf_hash: Fr, index: u32, path: &[Fr]) -> Fr {
+        path.iter()
+            .enumerate()
+            .fold(leaf_hash, |hash, (level, sibling)| {
+                if (index >> level) & 1 == 1 {
+                    hasher.compress(sibling, &hash, level)
+                } else {
+                    hasher.compress(&hash, sibling, level)
+                }
+            })
+    }
+
+    #[test]
+    fn exit_paths_lead_to_verified_root() {
+        let account_id = AccountId(5);
+        let token_id = TokenId(2);
+        let mut accounts = AccountMap::default();
+        for id in 0..10u32 {
+            let mut account = zksync_types::Account::default_with_address(&Address::random());
+            account.add_balance(TokenId(0), &BigUint::from(100u32 + id));
+            account.add_balance(token_id, &BigUint::from(1000u32 * id));
+            accounts.insert(AccountId(id), account);
+        }
+        let account = accounts[&account_id].clone();
+
+        // The verified root is calculated independently of the exit paths.
+        let mut tree = CircuitAccountTree::new(params::account_tree_depth());
+        for (id, account) in accounts.clone() {
+            tree.insert(*id, CircuitAccount::from(account));
         }
+        let verified_root = tree.root_hash();
+
+        let paths = exit_paths(accounts, account_id, token_id);
+        assert_eq!(paths.root_hash, verified_root);
+        assert_eq!(paths.account_path.len(), params::account_tree_depth());
+        assert_eq!(paths.balance_path.len(), params::balance_tree_depth());
+
+        // The balance path leads to the root of the account balance tree.
+        let circuit_account = CircuitAccount::from(account.clone());
+        let hasher = &circuit_account.subtree.hasher;
+        let balance = Balance::<Engine> {
+            value: Fr::from_str(&account.get_balance(token_id).to_string()).unwrap(),
+        };
+        let balance_root = root_from_path(
+            hasher,
+            hasher.hash_bits(balance.get_bits_le()),
+            *token_id as u32,
+            &paths.balance_path,
+        );
+        assert_eq!(balance_root, circuit_account.subtree.root_hash());
+
+        // The account path leads to the verified root.
+        let root = root_from_path(
+            hasher,
+            hasher.hash_bits(circuit_account.get_bits_le()),
+            *account_id,
+            &paths.account_path,
+        );
+        assert_eq!(root, verified_root);
     }
 
     #[actix_rt::test]
@@ -630,6 +1543,8 @@ mod tests {
                             eth_hash: H256::from_slice(&[0u8; 32]),
                             eth_block: 25,
                             eth_block_index: Some(1),
+                            eth_block_hash: None,
+                            l1_surplus: None,
                         },
                         PriorityOp {
                             serial_id: 11,
@@ -643,6 +1558,8 @@ mod tests {
                             eth_hash: H256::from_slice(&[0u8; 32]),
                             eth_block: 25,
                             eth_block_index: Some(1),
+                            eth_block_hash: None,
+                            l1_surplus: None,
                         },
                         PriorityOp {
                             serial_id: 12,
@@ -656,6 +1573,8 @@ mod tests {
                             eth_hash: H256::from_slice(&[0u8; 32]),
                             eth_block: 25,
                             eth_block_index: Some(1),
+                            eth_block_hash: None,
+                            l1_surplus: None,
                         },
                     ],
                     false,
@@ -667,6 +1586,7 @@ mod tests {
             DepositingFunds {
                 amount: BigUint::from(301500u32),
                 expected_accept_block: 25 + server.confirmations_for_eth_event,
+                confirmations_left: 0,
             },
         )]
         .into_iter()
@@ -691,6 +1611,52 @@ mod tests {
         let txs: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
         assert_eq!(txs.list[0].tx_hash, tx_hash);
 
+        // The cursor should be a transaction of the account itself.
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: MAX_LIMIT,
+            direction: PaginationDirection::Older,
+        };
+        let response = client.account_txs(&query, &account_id.to_string()).await?;
+        let account_txs: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
+        assert_eq!(account_txs.list.len() as u32, account_txs.pagination.count);
+        let foreign_tx_hash = server
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .get_block_transactions(BlockNumber(1))
+            .await?
+            .into_iter()
+            .map(|tx| TxHash::from_str(&tx.tx_hash).unwrap())
+            .find(|hash| account_txs.list.iter().all(|tx| tx.tx_hash != *hash))
+            .expect("The block should contain transactions of other accounts");
+        let query = PaginationQuery {
+            from: ApiEither::from(foreign_tx_hash),
+            limit: 1,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client.account_txs(&query, &account_id.to_string()).await?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::TransactionNotFound)?
+        );
+
+        // Unknown tokens are rejected instead of producing an empty list.
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: MAX_LIMIT,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_txs_of_token(&query, &account_id.to_string(), "UNKNOWN", false)
+            .await?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::TokenNotFound)?
+        );
+
         let query = PaginationQuery {
             from: ApiEither::from_str("latest").unwrap(),
             limit: 2,
@@ -711,6 +1677,723 @@ mod tests {
             _ => panic!("account_pending_txs returned L2 tx"),
         }
 
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 3,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_activity(&query, &format!("{:?}", address))
+            .await?;
+        let activity: Paginated<AccountActivity, ActivityPosition> =
+            deserialize_response_result(response)?;
+        assert!(!activity.list.is_empty());
+        assert!(activity.pagination.count as usize >= activity.list.len());
+        assert_eq!(activity.list[0].position(), Some(activity.pagination.from));
+
+        // The next page starts from the last element of the current one.
+        let last_position = activity.list.last().unwrap().position().unwrap();
+        let query = PaginationQuery {
+            from: ApiEither::from(last_position),
+            limit: 3,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_activity(&query, &format!("{:?}", address))
+            .await?;
+        let next_page: Paginated<AccountActivity, ActivityPosition> =
+            deserialize_response_result(response)?;
+        assert_eq!(next_page.list[0].position(), Some(last_position));
+        assert_eq!(next_page.pagination.count, activity.pagination.count);
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Checks that the priority operations reported by the core are merged with the ones
+    /// executed in the blocks which are not finalized yet.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn pending_priority_ops() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        // Both the finalized deposit and the committed full exit of the test data target this address.
+        let address = Address::default();
+        let response = client
+            .account_pending_priority_ops(&format!("{:?}", address))
+            .await?;
+        let ops: Vec<PendingPriorityOp> = deserialize_response_result(response)?;
+        let serial_ids: Vec<_> = ops.iter().map(|op| op.serial_id).collect();
+        // Deposit reported by the core as confirmed is already executed, so it's skipped.
+        assert_eq!(
+            serial_ids,
+            vec![COMMITTED_OP_SERIAL_ID, UNCONFIRMED_SERIAL_ID]
+        );
+        assert_eq!(ops[0].op_type, TxType::FullExit);
+        assert_eq!(ops[0].status, PendingPriorityOpStatus::Executed);
+        assert_eq!(ops[0].confirmations_left, 0);
+        assert_eq!(
+            ops[1],
+            PendingPriorityOp::new(
+                &queued_deposit(UNCONFIRMED_SERIAL_ID, address),
+                PendingPriorityOpStatus::Unconfirmed,
+                3
+            )
+        );
+
+        // Operations targeting the address without an account are reported as well.
+        let address = Address::repeat_byte(0x42);
+        let response = client
+            .account_pending_priority_ops(&format!("{:?}", address))
+            .await?;
+        let ops: Vec<PendingPriorityOp> = deserialize_response_result(response)?;
+        assert_eq!(
+            ops,
+            vec![PendingPriorityOp::new(
+                &queued_deposit(UNCONFIRMED_SERIAL_ID, address),
+                PendingPriorityOpStatus::Unconfirmed,
+                3
+            )]
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Checks that the account created in the block which is not finalized yet
+    /// has only the committed state.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_not_finalized_yet() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let updates = server
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .state_schema()
+            .load_state_diff_for_block(BlockNumber(COMMITTED_BLOCKS_COUNT))
+            .await?;
+        let (account_id, address) = updates
+            .iter()
+            .find_map(|(account_id, update)| match update {
+                AccountUpdate::Create { address, .. } => Some((*account_id, *address)),
+                _ => None,
+            })
+            .expect("The last committed block should create accounts");
+
+        for account_id_or_address in [account_id.to_string(), format!("{:?}", address)] {
+            let response = client.account_full_info(&account_id_or_address).await?;
+            let account_full_info: AccountState = deserialize_response_result(response)?;
+            let committed = account_full_info.committed.unwrap();
+            assert_eq!(committed.account_id, account_id);
+            assert_eq!(committed.address, address);
+            assert_eq!(
+                committed.last_update_in_block,
+                BlockNumber(COMMITTED_BLOCKS_COUNT)
+            );
+            assert_eq!(account_full_info.finalized, None);
+
+            let response = client
+                .account_info(&account_id_or_address, "finalized")
+                .await?;
+            let account_finalized_info: Option<Account> = deserialize_response_result(response)?;
+            assert_eq!(account_finalized_info, None);
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Checks that the NFTs owned by the account are paginated and the unknown account
+    /// is told apart from the account without NFTs.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_nfts() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let (account_id, _) = TestServer::account_id_and_tx_hash(
+            &mut server.pool.access_storage().await?,
+            BlockNumber(1),
+        )
+        .await?;
+        let response = client
+            .account_info(&account_id.to_string(), "committed")
+            .await?;
+        let account: Account = deserialize_response_result(response)?;
+        let expected_ids: Vec<TokenId> = account.nfts.keys().copied().collect();
+        assert!(expected_ids.len() > 2);
+
+        let query = PaginationQuery {
+            from: ApiEither::from(TokenId(0)),
+            limit: MAX_LIMIT,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client.account_nfts(&query, &account_id.to_string()).await?;
+        let nfts: Paginated<NFT, TokenId> = deserialize_response_result(response)?;
+        let ids: Vec<TokenId> = nfts.list.iter().map(|nft| nft.id).collect();
+        assert_eq!(ids, expected_ids);
+        assert_eq!(nfts.pagination.count as usize, expected_ids.len());
+        for nft in &nfts.list {
+            assert_eq!(nft.content_hash, account.nfts[&nft.id].content_hash);
+            assert_eq!(nft.creator_address, account.nfts[&nft.id].creator_address);
+            assert_eq!(nft.address, account.nfts[&nft.id].address);
+        }
+
+        // The latest page is the one with the greatest ids.
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 2,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_nfts(&query, &format!("{:?}", account.address))
+            .await?;
+        let nfts: Paginated<NFT, TokenId> = deserialize_response_result(response)?;
+        let ids: Vec<TokenId> = nfts.list.iter().map(|nft| nft.id).collect();
+        let expected_latest: Vec<TokenId> = expected_ids.iter().rev().take(2).copied().collect();
+        assert_eq!(ids, expected_latest);
+        assert_eq!(nfts.pagination.from, expected_ids[expected_ids.len() - 1]);
+
+        // The account created in the last block doesn't have NFTs yet.
+        let updates = server
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .state_schema()
+            .load_state_diff_for_block(BlockNumber(COMMITTED_BLOCKS_COUNT))
+            .await?;
+        let new_account_id = updates
+            .iter()
+            .find_map(|(account_id, update)| match update {
+                AccountUpdate::Create { .. } => Some(*account_id),
+                _ => None,
+            })
+            .unwrap();
+        let response = client
+            .account_nfts(&query, &new_account_id.to_string())
+            .await?;
+        let nfts: Paginated<NFT, TokenId> = deserialize_response_result(response)?;
+        assert!(nfts.list.is_empty());
+        assert_eq!(nfts.pagination.count, 0);
+
+        // Unknown accounts are reported as errors.
+        for unknown_account in [
+            AccountId(1_000_000).to_string(),
+            format!("{:?}", Address::repeat_byte(0xfe)),
+        ] {
+            let response = client.account_nfts(&query, &unknown_account).await?;
+            assert_eq!(
+                response.error.unwrap()["code"],
+                serde_json::to_value(ErrorCode::AccountNotFound)?
+            );
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Checks that the pending operations of the stuck account point to the blocking transaction.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn pending_txs_of_stuck_account() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let mut storage = server.pool.access_storage().await?;
+        let (account_id, _) =
+            TestServer::account_id_and_tx_hash(&mut storage, BlockNumber(1)).await?;
+        // The lowest queued nonce is parked, the next one waits behind it.
+        let txs: Vec<_> = [Nonce(1000), Nonce(1001)]
+            .iter()
+            .map(|nonce| SignedZkSyncTx {
+                tx: ZkSyncTx::Transfer(Box::new(Transfer::new(
+                    account_id,
+                    Address::random(),
+                    Address::random(),
+                    TokenId(0),
+                    100u32.into(),
+                    10u32.into(),
+                    *nonce,
+                    Default::default(),
+                    None,
+                ))),
+                eth_sign_data: None,
+                created_at: chrono::Utc::now(),
+            })
+            .collect();
+        for tx in &txs {
+            storage.chain().mempool_schema().insert_tx(tx).await?;
+        }
+        storage
+            .chain()
+            .mempool_schema()
+            .park_tx(txs[0].hash(), "Transaction fee is too low")
+            .await?;
+        storage
+            .chain()
+            .mempool_schema()
+            .refresh_stuck_accounts(chrono::Utc::now() + chrono::Duration::seconds(1))
+            .await?;
+
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 2,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_pending_txs(&query, &account_id.to_string())
+            .await?;
+        let pending: AccountPendingTxs = deserialize_response_result(response)?;
+        assert_eq!(
+            pending.blocked_by,
+            Some(TxHashSerializeWrapper(txs[0].hash()))
+        );
+
+        // The hint disappears once the account is not stuck anymore.
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_txs(&tx_hashes)
+            .await?;
+        storage
+            .chain()
+            .mempool_schema()
+            .refresh_stuck_accounts(chrono::Utc::now() + chrono::Duration::seconds(1))
+            .await?;
+        let response = client
+            .account_pending_txs(&query, &account_id.to_string())
+            .await?;
+        let pending: AccountPendingTxs = deserialize_response_result(response)?;
+        assert_eq!(pending.blocked_by, None);
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Checks that the account stuck behind a parked transaction is detected by the mempool analyzer,
+    /// and the deadlock is resolved by dead-lettering the blocking transaction if configured.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn nonce_deadlock() -> anyhow::Result<()> {
+        let pool = ConnectionPool::new(Some(1));
+        let mut storage = pool.access_storage().await?;
+        // Changes are rolled back once the test is over.
+        let mut transaction = storage.start_transaction().await?;
+
+        let account_id = AccountId(0xdeadbeef);
+        let [blocker, queued] = [Nonce(0), Nonce(1)].map(|nonce| SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(Transfer::new(
+                account_id,
+                Address::random(),
+                Address::random(),
+                TokenId(0),
+                100u32.into(),
+                10u32.into(),
+                nonce,
+                Default::default(),
+                None,
+            ))),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        });
+        for tx in &[&blocker, &queued] {
+            transaction.chain().mempool_schema().insert_tx(tx).await?;
+        }
+        transaction
+            .chain()
+            .mempool_schema()
+            .park_tx(blocker.hash(), "Transaction fee is too low")
+            .await?;
+
+        // Without the timeout the deadlock is only reported.
+        let analyzer = |dead_letter_timeout| {
+            MempoolAnalyzer::with_timeouts(
+                pool.clone(),
+                std::time::Duration::from_secs(0),
+                dead_letter_timeout,
+            )
+        };
+        let stuck = analyzer(None).analyze(&mut transaction).await?;
+        let stuck: Vec<_> = stuck
+            .into_iter()
+            .filter(|account| account.account_id == account_id)
+            .collect();
+        assert_eq!(stuck.len(), 1);
+        assert_eq!(stuck[0].blocking_tx_hash, blocker.hash());
+        assert_eq!(stuck[0].queued_txs, 1);
+
+        // The blocker is dead-lettered after the timeout, so the account is not stuck anymore.
+        let stuck = analyzer(Some(std::time::Duration::from_secs(0)))
+            .analyze(&mut transaction)
+            .await?;
+        assert!(stuck.iter().all(|account| account.account_id != account_id));
+        let mut mempool = transaction.chain().mempool_schema();
+        assert!(!mempool.contains_tx(blocker.hash()).await?);
+        assert!(mempool
+            .get_dead_lettered_tx(blocker.hash())
+            .await?
+            .is_some());
+        assert!(mempool.contains_tx(queued.hash()).await?);
+
+        Ok(())
+    }
+
+    /// Checks that the queued transactions of the account are listed page by page in the order
+    /// of their nonces, while the parked ones are not listed.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn queued_txs_of_account() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+
+        let mut storage = server.pool.access_storage().await?;
+        let address = Address::random();
+        let received_at = chrono::Utc::now() - chrono::Duration::minutes(1);
+        // Transactions are received in the reverse order of their nonces.
+        let txs: Vec<_> = (0..4u32)
+            .map(|nonce| SignedZkSyncTx {
+                tx: ZkSyncTx::Transfer(Box::new(Transfer::new(
+                    AccountId(1000),
+                    address,
+                    Address::random(),
+                    TokenId(0),
+                    100u32.into(),
+                    10u32.into(),
+                    Nonce(nonce),
+                    Default::default(),
+                    None,
+                ))),
+                eth_sign_data: None,
+                created_at: received_at - chrono::Duration::seconds(nonce.into()),
+            })
+            .collect();
+        for tx in &txs {
+            storage.chain().mempool_schema().insert_tx(tx).await?;
+        }
+        storage
+            .chain()
+            .mempool_schema()
+            .park_tx(txs[3].hash(), "Transaction fee is too low")
+            .await?;
+
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 2,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_queued_txs(&query, &format!("{:?}", address))
+            .await?;
+        let page: Paginated<Transaction, QueuedTxPosition> = deserialize_response_result(response)?;
+        assert_eq!(page.pagination.count, 3);
+        let hashes: Vec<_> = page.list.iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(hashes, vec![txs[2].hash(), txs[1].hash()]);
+        assert!(page
+            .list
+            .iter()
+            .all(|tx| tx.status == TxInBlockStatus::Queued));
+
+        // The next page starts at the transaction following the last one of the previous page.
+        let next = page
+            .pagination
+            .next
+            .expect("there are more queued transactions");
+        assert_eq!(next.nonce, Nonce(0));
+        let query = PaginationQuery {
+            from: ApiEither::from(next),
+            limit: 2,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .account_queued_txs(&query, &format!("{:?}", address))
+            .await?;
+        let page: Paginated<Transaction, QueuedTxPosition> = deserialize_response_result(response)?;
+        let hashes: Vec<_> = page.list.iter().map(|tx| tx.tx_hash).collect();
+        assert_eq!(hashes, vec![txs[0].hash()]);
+        assert_eq!(page.pagination.next, None);
+
+        let tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        storage
+            .chain()
+            .mempool_schema()
+            .remove_txs(&tx_hashes)
+            .await?;
+
+        server.stop().await;
+        Ok(())
+    }
+
+    fn export_positions(records: &[Value]) -> Vec<ActivityPosition> {
+        records
+            .iter()
+            .map(|record| {
+                let position = format!(
+                    "{}:{}:{}",
+                    record["blockNumber"],
+                    record["kind"].as_str().unwrap(),
+                    record["blockIndex"]
+                );
+                ActivityPosition::from_str(&position).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn export_csv_row_follows_columns() {
+        let record = AccountExportRecord {
+            block_number: BlockNumber(3),
+            block_index: 1,
+            kind: ActivityKind::L2Tx,
+            tx_hash: TxHash::default(),
+            timestamp: None,
+            direction: ExportDirection::Outgoing,
+            counterparty: Some(Address::repeat_byte(0x11)),
+            token: TokenId(1),
+            amount: Some(BigUint::from(100u32).into()),
+            fee_token: Some(TokenId(1)),
+            fee: Some(BigUint::from(5u32).into()),
+            status: TxInBlockStatus::Finalized,
+        };
+        assert_eq!(
+            export_csv_row(&record),
+            format!(
+                "3,1,l2Tx,0x{},,outgoing,{:?},1,100,1,5,finalized",
+                "0".repeat(64),
+                Address::repeat_byte(0x11)
+            )
+        );
+    }
+
+    /// Checks that the export contains the activity feed of the account in both formats,
+    /// can be resumed from the last exported record and validates the block range.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_export() -> anyhow::Result<()> {
+        let (_client, server) = TestServer::new().await?;
+
+        let mut storage = server.pool.access_storage().await?;
+        let (account_id, _) =
+            TestServer::account_id_and_tx_hash(&mut storage, BlockNumber(1)).await?;
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await?
+            .unwrap();
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_confirmed_block()
+            .await?;
+        let activity = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_activity(&PaginationQuery {
+                from: AccountActivityRequest {
+                    address,
+                    position: ApiEither::from(ActivityPosition::default()),
+                },
+                limit: 1000,
+                direction: PaginationDirection::Newer,
+            })
+            .await?;
+        drop(storage);
+        let expected: Vec<ActivityPosition> = activity
+            .iter()
+            .filter_map(AccountActivity::position)
+            .filter(|position| position.block_number <= last_block)
+            .collect();
+        assert!(expected.len() >= 2);
+        let range = format!("from_block=1&to_block={}", *last_block);
+
+        let body = server
+            .export(address, &format!("{}&format=json", range))
+            .await?;
+        let records: Vec<Value> = serde_json::from_str(&body)?;
+        assert_eq!(export_positions(&records), expected);
+
+        // The column order is a part of the API.
+        let body = server
+            .export(address, &format!("{}&format=csv", range))
+            .await?;
+        let mut lines = body.lines();
+        assert_eq!(
+            lines.next(),
+            Some("blockNumber,blockIndex,kind,txHash,timestamp,direction,counterparty,token,amount,feeToken,fee,status")
+        );
+        let rows: Vec<Vec<&str>> = lines.map(|line| line.split(',').collect()).collect();
+        assert_eq!(rows.len(), expected.len());
+        for (row, position) in rows.iter().zip(&expected) {
+            assert_eq!(row.len(), EXPORT_CSV_COLUMNS.len());
+            assert_eq!(
+                format!("{}:{}:{}", row[0], row[2], row[1]),
+                position.to_string()
+            );
+        }
+
+        // The resumed export starts right after the last exported record.
+        let body = server
+            .export(address, &format!("{}&after={}", range, expected[0]))
+            .await?;
+        let records: Vec<Value> = serde_json::from_str(&body)?;
+        assert_eq!(export_positions(&records), expected[1..].to_vec());
+
+        let body = server
+            .export(
+                address,
+                &format!("{}&format=csv&after={}", range, expected.last().unwrap()),
+            )
+            .await?;
+        assert_eq!(body.lines().count(), 1);
+
+        let body = server.export(address, "from_block=2&to_block=1").await?;
+        let response: Response = serde_json::from_str(&body)?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::InvalidExportRange)?
+        );
+
+        let body = server
+            .export(
+                address,
+                &format!("from_block=1&to_block={}", MAX_EXPORT_BLOCKS + 1),
+            )
+            .await?;
+        let response: Response = serde_json::from_str(&body)?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::ExportRangeTooBig)?
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_2fa() -> anyhow::Result<()> {
+        let (client, server) = TestServer::new().await?;
+        let mut storage = server.pool.access_storage().await?;
+        let (account_id, _) =
+            TestServer::account_id_and_tx_hash(&mut storage, BlockNumber(1)).await?;
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await?
+            .unwrap();
+
+        // Transfer of the account without the Ethereum signature.
+        let mut transfer = TestServerConfig::gen_zk_txs(100_u64)
+            .txs
+            .into_iter()
+            .find_map(|(tx, _)| match tx {
+                ZkSyncTx::Transfer(tx) => Some(tx),
+                _ => None,
+            })
+            .unwrap();
+        transfer.account_id = account_id;
+        transfer.from = address;
+        let transfer = ZkSyncTx::Transfer(transfer);
+        let missing_signature = Error::from(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+        let submit_error = || async {
+            server
+                .tx_sender
+                .submit_tx(transfer.clone(), TxEthSignatureVariant::Single(None), None)
+                .await
+                .err()
+                .map(Error::from)
+        };
+        assert_eq!(submit_error().await, Some(missing_signature.clone()));
+
+        // The signature checker of the test server accepts any signature.
+        let signature = TxEthSignature::EthereumSignature(PackedEthSignature::sign(
+            &H256::repeat_byte(0x01),
+            b"toggle 2FA",
+        )?);
+        let toggle = |enable, timestamp| AccountToggle2FA {
+            enable,
+            timestamp,
+            signature: signature.clone(),
+            pub_key_hash: None,
+        };
+        let toggle_error = |response: Response| {
+            response
+                .error
+                .map(|error| serde_json::from_value::<Error>(error).unwrap().code)
+        };
+
+        let disabled_at = Utc::now();
+        let response = client
+            .account_toggle_2fa(&account_id.to_string(), &toggle(false, disabled_at))
+            .await?;
+        let response: Toggle2FAResponse = deserialize_response_result(response)?;
+        assert!(response.success);
+        assert!(matches!(
+            storage
+                .chain()
+                .account_schema()
+                .account_type_by_id(account_id)
+                .await?,
+            Some(EthAccountType::No2FA(None))
+        ));
+        assert_ne!(submit_error().await, Some(missing_signature.clone()));
+
+        // 2FA can be enabled by the address as well.
+        let response = client
+            .account_toggle_2fa(&format!("{:?}", address), &toggle(true, Utc::now()))
+            .await?;
+        assert_eq!(toggle_error(response), None);
+        assert_eq!(submit_error().await, Some(missing_signature));
+
+        // The request disabling 2FA can't be replayed.
+        let response = client
+            .account_toggle_2fa(&account_id.to_string(), &toggle(false, disabled_at))
+            .await?;
+        assert_eq!(
+            toggle_error(response),
+            Some(ErrorCode::from(RpcErrorCodes::Toggle2FA))
+        );
+        assert!(matches!(
+            storage
+                .chain()
+                .account_schema()
+                .account_type_by_id(account_id)
+                .await?,
+            Some(EthAccountType::Owned)
+        ));
+
+        let response = client
+            .account_toggle_2fa("1000000", &toggle(false, Utc::now()))
+            .await?;
+        assert_eq!(toggle_error(response), Some(ErrorCode::AccountNotFound));
+
         server.stop().await;
         Ok(())
     }
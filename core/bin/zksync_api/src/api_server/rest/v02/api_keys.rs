@@ -0,0 +1,638 @@
+//! Access control of the v0.2 API for hosted deployments.
+//!
+//! Requests may carry an API key in the `X-Api-Key` header. The key determines the scopes
+//! available to the request and its rate limit, and every accepted request is accounted
+//! in the daily usage of the key. Requests without a key are limited per IP address.
+//!
+//! The usage is accumulated in memory and written to the database periodically, so the requests
+//! accepted since the last flush are not accounted if the server is stopped abruptly.
+//!
+//! Most of the routes are available without a key, the heavier ones opt in to require it
+//! by wrapping themselves with [`RequireApiKey`].
+
+// Built-in uses
+use std::{
+    collections::HashMap,
+    future::{ready, Ready},
+    hash::Hash,
+    net::SocketAddr,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// External uses
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    HttpMessage, Responder,
+};
+use chrono::{NaiveDate, Utc};
+use futures::future::LocalBoxFuture;
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_storage::{ConnectionPool, QueryResult};
+use zksync_types::api_keys::{api_key_hash, ApiKeyScope};
+
+// Local uses
+use super::{
+    error::{AccessError, Error},
+    response::ApiResult,
+};
+
+/// Header the API key is passed in.
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+/// Rate limits are set per minute.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+/// Number of the tracked clients after which the expired windows are dropped.
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// Fixed-window rate limiter.
+#[derive(Debug)]
+struct RateLimiter<K> {
+    windows: Mutex<HashMap<K, (Instant, u32)>>,
+    window: Duration,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    fn new(window: Duration) -> Self {
+        Self {
+            windows: Mutex::default(),
+            window,
+        }
+    }
+
+    /// Accounts the request of the client. Returns `false` if the client exceeded the limit.
+    fn check(&self, client: K, limit: u32) -> bool {
+        self.check_at(client, limit, Instant::now())
+    }
+
+    fn check_at(&self, client: K, limit: u32, now: Instant) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() > MAX_TRACKED_CLIENTS {
+            let window = self.window;
+            windows.retain(|_, (started_at, _)| now.duration_since(*started_at) < window);
+        }
+
+        let (started_at, requests) = windows.entry(client).or_insert((now, 0));
+        if now.duration_since(*started_at) >= self.window {
+            *started_at = now;
+            *requests = 0;
+        }
+        if *requests >= limit {
+            return false;
+        }
+        *requests += 1;
+        true
+    }
+}
+
+/// Outcome of the API key check, stored in the request extensions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ApiKeyAccess {
+    /// ID of the key the request was made with, `None` for the keyless requests.
+    pub key_id: Option<i64>,
+}
+
+#[derive(Debug)]
+struct ApiKeyAuthState {
+    pool: ConnectionPool,
+    key_limiter: RateLimiter<i64>,
+    ip_limiter: RateLimiter<String>,
+    ip_requests_per_minute: Option<u32>,
+    /// Requests accepted since the last flush, per day and key.
+    pending_usage: Mutex<HashMap<NaiveDate, HashMap<i64, u32>>>,
+}
+
+impl ApiKeyAuthState {
+    async fn check_access(&self, req: &ServiceRequest) -> Result<(), Error> {
+        let key = match req.headers().get(API_KEY_HEADER) {
+            Some(key) => key.to_str().map_err(|_| AccessError::InvalidApiKey)?,
+            None => {
+                self.check_keyless_access(req)?;
+                req.extensions_mut().insert(ApiKeyAccess { key_id: None });
+                return Ok(());
+            }
+        };
+
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let key = storage
+            .api_keys_schema()
+            .load_api_key_by_hash(api_key_hash(key))
+            .await
+            .map_err(Error::storage)?
+            .filter(|key| key.revoked_at.is_none())
+            .ok_or(AccessError::InvalidApiKey)?;
+
+        let scope = required_scope(req);
+        if !key.allows(scope) {
+            return Err(AccessError::ScopeNotAllowed(scope).into());
+        }
+        if !self.key_limiter.check(key.id, key.requests_per_minute) {
+            return Err(AccessError::RateLimitExceeded(key.requests_per_minute).into());
+        }
+
+        self.record_request(key.id, Utc::today().naive_utc());
+        req.extensions_mut().insert(ApiKeyAccess {
+            key_id: Some(key.id),
+        });
+        Ok(())
+    }
+
+    fn record_request(&self, key_id: i64, day: NaiveDate) {
+        let mut pending_usage = self.pending_usage.lock().unwrap();
+        *pending_usage
+            .entry(day)
+            .or_default()
+            .entry(key_id)
+            .or_default() += 1;
+    }
+
+    /// Writes the accumulated usage to the database. If the write fails, the usage is kept
+    /// to be written with the next flush.
+    async fn flush_usage(&self) -> QueryResult<()> {
+        let pending_usage = std::mem::take(&mut *self.pending_usage.lock().unwrap());
+        if pending_usage.is_empty() {
+            return Ok(());
+        }
+
+        let result = async {
+            let mut storage = self.pool.access_storage().await?;
+            let mut transaction = storage.start_transaction().await?;
+            for (day, requests) in &pending_usage {
+                let requests: Vec<_> = requests.iter().map(|(id, count)| (*id, *count)).collect();
+                transaction
+                    .api_keys_schema()
+                    .record_api_key_requests(*day, &requests)
+                    .await?;
+            }
+            transaction.commit().await
+        }
+        .await;
+
+        if result.is_err() {
+            let mut usage = self.pending_usage.lock().unwrap();
+            for (day, requests) in pending_usage {
+                for (key_id, count) in requests {
+                    *usage.entry(day).or_default().entry(key_id).or_default() += count;
+                }
+            }
+        }
+        result
+    }
+
+    fn check_keyless_access(&self, req: &ServiceRequest) -> Result<(), Error> {
+        let limit = match self.ip_requests_per_minute {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+        // The API is expected to run behind a proxy, so the forwarding headers are taken into account.
+        let ip = match req.connection_info().realip_remote_addr() {
+            Some(addr) => addr
+                .parse::<SocketAddr>()
+                .map(|addr| addr.ip().to_string())
+                .unwrap_or_else(|_| addr.to_owned()),
+            None => return Ok(()),
+        };
+
+        if self.ip_limiter.check(ip, limit) {
+            Ok(())
+        } else {
+            Err(AccessError::RateLimitExceeded(limit).into())
+        }
+    }
+}
+
+/// Submitting the transactions and toggling 2FA requires the `submit` scope, everything else is available
+/// with the `read` one. Fee requests are `POST` requests as well, but they don't change anything.
+fn required_scope(req: &ServiceRequest) -> ApiKeyScope {
+    if req.method() == Method::POST
+        && (req.path().contains("/transactions") || req.path().ends_with("/2fa"))
+    {
+        ApiKeyScope::Submit
+    } else {
+        ApiKeyScope::Read
+    }
+}
+
+/// Middleware enforcing the API key scopes and rate limits.
+/// Limits are shared between all the workers of the server, so the middleware should be created once.
+#[derive(Debug, Clone)]
+pub struct ApiKeyAuth(Arc<ApiKeyAuthState>);
+
+impl ApiKeyAuth {
+    pub fn new(pool: ConnectionPool, ip_requests_per_minute: Option<u32>) -> Self {
+        Self(Arc::new(ApiKeyAuthState {
+            pool,
+            key_limiter: RateLimiter::new(RATE_LIMIT_WINDOW),
+            ip_limiter: RateLimiter::new(RATE_LIMIT_WINDOW),
+            ip_requests_per_minute,
+            pending_usage: Mutex::default(),
+        }))
+    }
+
+    /// Writes the usage of the keys accumulated since the last flush to the database.
+    pub async fn flush_usage(&self) -> QueryResult<()> {
+        self.0.flush_usage().await
+    }
+
+    /// Spawns the task flushing the usage of the keys with the given interval.
+    pub fn spawn_usage_flusher(&self, interval: Duration) -> JoinHandle<()> {
+        let auth = self.clone();
+        tokio::spawn(async move {
+            let mut timer = tokio::time::interval(interval);
+            loop {
+                timer.tick().await;
+                if let Err(err) = auth.flush_usage().await {
+                    vlog::warn!("Failed to flush the usage of the API keys: {}", err);
+                }
+            }
+        })
+    }
+}
+
+impl<S> Transform<S, ServiceRequest> for ApiKeyAuth
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Transform = ApiKeyAuthMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ApiKeyAuthMiddleware {
+            service: Rc::new(service),
+            state: self.0.clone(),
+        }))
+    }
+}
+
+pub struct ApiKeyAuthMiddleware<S> {
+    service: Rc<S>,
+    state: Arc<ApiKeyAuthState>,
+}
+
+impl<S> Service<ServiceRequest> for ApiKeyAuthMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let state = self.state.clone();
+
+        Box::pin(async move {
+            if let Err(err) = state.check_access(&req).await {
+                let response = ApiResult::<()>::Error(err).respond_to(req.request());
+                return Ok(req.into_response(response));
+            }
+            service.call(req).await
+        })
+    }
+}
+
+/// Middleware rejecting the requests made without an API key. Wraps the routes available
+/// to the registered partners only, must be nested in the scope wrapped with [`ApiKeyAuth`].
+///
+/// Requests that were not checked by [`ApiKeyAuth`] are rejected as well, so the routes
+/// are never exposed by mistake.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireApiKey;
+
+impl<S> Transform<S, ServiceRequest> for RequireApiKey
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Transform = RequireApiKeyMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireApiKeyMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequireApiKeyMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S> Service<ServiceRequest> for RequireApiKeyMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let access = req.extensions().get::<ApiKeyAccess>().copied();
+
+        Box::pin(async move {
+            if !matches!(access, Some(ApiKeyAccess { key_id: Some(_) })) {
+                let response = ApiResult::<()>::Error(AccessError::ApiKeyRequired.into())
+                    .respond_to(req.request());
+                return Ok(req.into_response(response));
+            }
+            service.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        error::ErrorCode, test_utils::TestServerConfig, SharedData,
+    };
+    use actix_web::{web, App};
+    use zksync_api_types::{v02::ApiVersion, v02::Response, ApiKeyUsage};
+    use zksync_types::network::Network;
+
+    async fn ok() -> ApiResult<()> {
+        ApiResult::Ok(())
+    }
+
+    fn start_server(cfg: &TestServerConfig, auth: ApiKeyAuth) -> actix_test::TestServer {
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        actix_test::start(move || {
+            App::new().app_data(web::Data::new(shared_data)).service(
+                web::scope("/api/v0.2")
+                    .wrap(auth.clone())
+                    .route("/tokens", web::get().to(ok))
+                    .route("/fee", web::post().to(ok))
+                    .route("/transactions", web::post().to(ok))
+                    .service(
+                        web::resource("/export")
+                            .wrap(RequireApiKey)
+                            .route(web::get().to(ok)),
+                    ),
+            )
+        })
+    }
+
+    /// Makes a request and returns the error code, if any.
+    async fn request(
+        server: &actix_test::TestServer,
+        method: Method,
+        path: &str,
+        key: Option<&str>,
+    ) -> anyhow::Result<Option<ErrorCode>> {
+        let mut request = server.request(method, server.url(&format!("/api/v0.2{}", path)));
+        if let Some(key) = key {
+            request = request.insert_header((API_KEY_HEADER, key));
+        }
+        let response: Response = request
+            .send()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?
+            .json()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        Ok(response
+            .error
+            .map(serde_json::from_value::<Error>)
+            .transpose()?
+            .map(|error| error.code))
+    }
+
+    /// Checks that the routes requiring a key are not exposed if the requests
+    /// were not checked by `ApiKeyAuth`.
+    #[actix_rt::test]
+    async fn api_key_required_without_auth() -> anyhow::Result<()> {
+        let shared_data = SharedData {
+            net: Network::Localhost,
+            api_version: ApiVersion::V02,
+        };
+        let server = actix_test::start(move || {
+            App::new().app_data(web::Data::new(shared_data)).service(
+                web::scope("/api/v0.2")
+                    .route("/tokens", web::get().to(ok))
+                    .service(
+                        web::resource("/export")
+                            .wrap(RequireApiKey)
+                            .route(web::get().to(ok)),
+                    ),
+            )
+        });
+
+        assert_eq!(request(&server, Method::GET, "/tokens", None).await?, None);
+        assert_eq!(
+            request(&server, Method::GET, "/export", None).await?,
+            Some(ErrorCode::ApiKeyRequired)
+        );
+        // The key is not checked without `ApiKeyAuth`, so it doesn't grant the access either.
+        assert_eq!(
+            request(&server, Method::GET, "/export", Some("unknown")).await?,
+            Some(ErrorCode::ApiKeyRequired)
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[test]
+    fn rate_limiter_windows() {
+        let limiter = RateLimiter::new(RATE_LIMIT_WINDOW);
+        let start = Instant::now();
+
+        assert!(limiter.check_at(1, 2, start));
+        assert!(limiter.check_at(1, 2, start + Duration::from_secs(1)));
+        assert!(!limiter.check_at(1, 2, start + Duration::from_secs(2)));
+        // Clients are limited independently.
+        assert!(limiter.check_at(2, 2, start + Duration::from_secs(2)));
+        // The limit is reset once the window is over.
+        assert!(limiter.check_at(1, 2, start + RATE_LIMIT_WINDOW));
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn api_key_scopes() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let server = start_server(&cfg, ApiKeyAuth::new(cfg.pool.clone(), None));
+        let (read_key, _) = cfg.issue_api_key(vec![ApiKeyScope::Read], 100).await?;
+        let (full_key, full_key_id) = cfg
+            .issue_api_key(vec![ApiKeyScope::Read, ApiKeyScope::Submit], 100)
+            .await?;
+
+        // Read-only key can query the state and the fee, but can't submit the transactions.
+        assert_eq!(
+            request(&server, Method::GET, "/tokens", Some(&read_key)).await?,
+            None
+        );
+        assert_eq!(
+            request(&server, Method::POST, "/fee", Some(&read_key)).await?,
+            None
+        );
+        assert_eq!(
+            request(&server, Method::POST, "/transactions", Some(&read_key)).await?,
+            Some(ErrorCode::ApiKeyScopeNotAllowed)
+        );
+        assert_eq!(
+            request(&server, Method::POST, "/transactions", Some(&full_key)).await?,
+            None
+        );
+
+        // Unknown and revoked keys are rejected.
+        assert_eq!(
+            request(&server, Method::GET, "/tokens", Some("unknown")).await?,
+            Some(ErrorCode::InvalidApiKey)
+        );
+        cfg.pool
+            .access_storage()
+            .await?
+            .api_keys_schema()
+            .revoke_api_key(full_key_id)
+            .await?;
+        assert_eq!(
+            request(&server, Method::GET, "/tokens", Some(&full_key)).await?,
+            Some(ErrorCode::InvalidApiKey)
+        );
+
+        // Keyless traffic is not limited if the IP limit is not set.
+        assert_eq!(request(&server, Method::GET, "/tokens", None).await?, None);
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn api_key_rate_limits_and_usage() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let auth = ApiKeyAuth::new(cfg.pool.clone(), Some(1));
+        let server = start_server(&cfg, auth.clone());
+        let (limited_key, limited_key_id) = cfg.issue_api_key(vec![ApiKeyScope::Read], 2).await?;
+        let (other_key, other_key_id) = cfg.issue_api_key(vec![ApiKeyScope::Read], 2).await?;
+
+        for _ in 0..2 {
+            assert_eq!(
+                request(&server, Method::GET, "/tokens", Some(&limited_key)).await?,
+                None
+            );
+        }
+        assert_eq!(
+            request(&server, Method::GET, "/tokens", Some(&limited_key)).await?,
+            Some(ErrorCode::RateLimitExceeded)
+        );
+        // Limits are applied per key.
+        assert_eq!(
+            request(&server, Method::GET, "/tokens", Some(&other_key)).await?,
+            None
+        );
+        // Keyless traffic falls back to the IP limit.
+        assert_eq!(request(&server, Method::GET, "/tokens", None).await?, None);
+        assert_eq!(
+            request(&server, Method::GET, "/tokens", None).await?,
+            Some(ErrorCode::RateLimitExceeded)
+        );
+
+        // Only the accepted requests are accounted once the usage is flushed.
+        let today = Utc::today().naive_utc();
+        assert!(cfg
+            .pool
+            .access_storage()
+            .await?
+            .api_keys_schema()
+            .load_api_key_usage(limited_key_id, today, today)
+            .await?
+            .is_empty());
+        auth.flush_usage().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        assert_eq!(
+            storage
+                .api_keys_schema()
+                .load_api_key_usage(limited_key_id, today, today)
+                .await?,
+            vec![ApiKeyUsage {
+                day: today,
+                requests: 2
+            }]
+        );
+        assert_eq!(
+            storage
+                .api_keys_schema()
+                .load_api_key_usage(other_key_id, today, today)
+                .await?,
+            vec![ApiKeyUsage {
+                day: today,
+                requests: 1
+            }]
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn api_key_required_routes() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let auth = ApiKeyAuth::new(cfg.pool.clone(), None);
+        let server = start_server(&cfg, auth.clone());
+        let (key, key_id) = cfg.issue_api_key(vec![ApiKeyScope::Read], 1).await?;
+
+        // Public routes stay available without a key.
+        assert_eq!(request(&server, Method::GET, "/tokens", None).await?, None);
+        assert_eq!(
+            request(&server, Method::GET, "/export", None).await?,
+            Some(ErrorCode::ApiKeyRequired)
+        );
+        assert_eq!(
+            request(&server, Method::GET, "/export", Some("unknown")).await?,
+            Some(ErrorCode::InvalidApiKey)
+        );
+        assert_eq!(
+            request(&server, Method::GET, "/export", Some(&key)).await?,
+            None
+        );
+        assert_eq!(
+            request(&server, Method::GET, "/export", Some(&key)).await?,
+            Some(ErrorCode::RateLimitExceeded)
+        );
+
+        auth.flush_usage().await?;
+        let today = Utc::today().naive_utc();
+        assert_eq!(
+            cfg.pool
+                .access_storage()
+                .await?
+                .api_keys_schema()
+                .load_api_key_usage(key_id, today, today)
+                .await?,
+            vec![ApiKeyUsage {
+                day: today,
+                requests: 1
+            }]
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+}
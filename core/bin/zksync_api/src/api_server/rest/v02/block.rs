@@ -5,26 +5,43 @@ use std::str::FromStr;
 use std::time::Instant;
 
 // External uses
-use actix_web::{web, Scope};
+use actix_web::{web, web::Bytes, Either, HttpResponse};
+use futures::{stream, Stream};
 
 // Workspace uses
 use zksync_api_types::v02::{
-    block::{BlockInfo, BlockStatus},
-    pagination::{parse_query, ApiEither, BlockAndTxHash, Paginated, PaginationQuery},
-    transaction::{Transaction, TxData, TxHashSerializeWrapper},
+    block::{
+        BlockAggregatedOperation, BlockAggregatedOperations, BlockExportQuery, BlockInfo,
+        BlockPosition, BlockStatus, BlocksOrder, IncomingBlockTxsQuery, IncomingBlocksQuery,
+    },
+    pagination::{
+        ApiEither, BlockAndTxHash, BlockAndTxIndex, BlockTimestamp, CountOnlyQuery, Paginated,
+        PaginationQuery,
+    },
+    transaction::{Transaction, TxData, TxHashSerializeWrapper, TxInBlockStatus, TxType},
 };
 use zksync_crypto::{convert::FeConvert, Fr};
-use zksync_storage::{chain::block::records::StorageBlockDetails, ConnectionPool, QueryResult};
-use zksync_types::{tx::TxHash, BlockNumber, H256};
+use zksync_storage::{
+    chain::{
+        block::records::StorageBlockDetails, operations::records::StoredBlockAggregatedOperation,
+    },
+    ConnectionPool, QueryResult,
+};
+use zksync_types::{aggregated_operations::AggregatedActionType, tx::TxHash, BlockNumber, H256};
 
 // Local uses
 use super::{
     error::{Error, InvalidDataError},
-    paginate_trait::Paginate,
-    response::ApiResult,
+    page_limit::parse_query,
+    paginate_trait::{Paginate, PaginateCount},
+    response::{ApiResult, CachePolicy, CachedApiResult},
+    spec::{ApiScope, Operation},
 };
 use crate::{api_try, utils::block_details_cache::BlockDetailsCache};
 
+/// Amount of the transactions loaded from the database per chunk of the block range export.
+const EXPORT_CHUNK_SIZE: u32 = 100;
+
 pub fn block_info_from_details(details: StorageBlockDetails) -> BlockInfo {
     let status = if details.is_verified() {
         BlockStatus::Finalized
@@ -48,18 +65,117 @@ pub fn block_info_from_details(details: StorageBlockDetails) -> BlockInfo {
     }
 }
 
+/// Groups the aggregated operations of the block by their stages. The proofs are created
+/// off-chain, so the `CreateProofBlocks` operations are not reported.
+fn block_aggregated_operations(
+    block_number: BlockNumber,
+    operations: Vec<StoredBlockAggregatedOperation>,
+) -> BlockAggregatedOperations {
+    let mut result = BlockAggregatedOperations {
+        block_number,
+        commit_blocks: None,
+        publish_proof_blocks_onchain: None,
+        execute_blocks: None,
+    };
+    for op in operations {
+        let stage = match AggregatedActionType::from_str(&op.action_type) {
+            Ok(AggregatedActionType::CommitBlocks) => &mut result.commit_blocks,
+            Ok(AggregatedActionType::PublishProofBlocksOnchain) => {
+                &mut result.publish_proof_blocks_onchain
+            }
+            Ok(AggregatedActionType::ExecuteBlocks) => &mut result.execute_blocks,
+            Ok(AggregatedActionType::CreateProofBlocks) | Err(_) => continue,
+        };
+        *stage = Some(BlockAggregatedOperation {
+            confirmed: op.confirmed,
+            eth_tx_hash: op.eth_tx_hash.map(|bytes| H256::from_slice(&bytes)),
+            from_block: BlockNumber(op.from_block as u32),
+            to_block: BlockNumber(op.to_block as u32),
+        });
+    }
+    result
+}
+
+/// Export of the transactions of the finalized block range as newline-delimited JSON,
+/// loaded from the database by chunks.
+struct BlockExport {
+    pool: ConnectionPool,
+    from: BlockNumber,
+    to: BlockNumber,
+    /// Sequence number of the last exported transaction.
+    cursor: Option<i64>,
+    finished: bool,
+}
+
+impl BlockExport {
+    fn new(pool: ConnectionPool, query: &BlockExportQuery) -> Self {
+        Self {
+            pool,
+            from: query.from,
+            to: query.to,
+            cursor: None,
+            finished: false,
+        }
+    }
+
+    /// Loads the next chunk of the transactions, one JSON object per line.
+    async fn next_chunk(&mut self) -> Result<String, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let (txs, cursor) = storage
+            .chain()
+            .block_schema()
+            .get_block_range_transactions_chunk(self.from, self.to, self.cursor, EXPORT_CHUNK_SIZE)
+            .await
+            .map_err(Error::storage)?;
+        self.finished = txs.len() < EXPORT_CHUNK_SIZE as usize;
+        self.cursor = cursor;
+
+        let mut chunk = String::new();
+        for tx in &txs {
+            chunk.push_str(&serde_json::to_string(tx).expect("Transaction should be serializable"));
+            chunk.push('\n');
+        }
+        Ok(chunk)
+    }
+
+    fn into_stream(self) -> impl Stream<Item = Result<Bytes, actix_web::Error>> {
+        stream::unfold(self, |mut export| async move {
+            if export.finished {
+                return None;
+            }
+            let chunk = export.next_chunk().await.map(Bytes::from).map_err(|err| {
+                // The response is already started, so the client gets the truncated export.
+                export.finished = true;
+                actix_web::error::ErrorInternalServerError(err.message)
+            });
+            Some((chunk, export))
+        })
+    }
+}
+
 /// Shared data between `api/v0.2/blocks` endpoints.
 #[derive(Debug, Clone)]
 struct ApiBlockData {
     pool: ConnectionPool,
     verified_blocks_cache: BlockDetailsCache,
+    /// Maximum number of blocks in the range of a single export request.
+    max_export_blocks: u32,
+    /// Maximum number of items on a page of the paginated endpoints.
+    max_page_limit: u32,
 }
 
 impl ApiBlockData {
-    fn new(pool: ConnectionPool, verified_blocks_cache: BlockDetailsCache) -> Self {
+    fn new(
+        pool: ConnectionPool,
+        verified_blocks_cache: BlockDetailsCache,
+        max_export_blocks: u32,
+        max_page_limit: u32,
+    ) -> Self {
         Self {
             pool,
             verified_blocks_cache,
+            max_export_blocks,
+            max_page_limit,
         }
     }
 
@@ -83,48 +199,89 @@ impl ApiBlockData {
         &self,
         block_position: &str,
     ) -> Result<BlockNumber, Error> {
-        if let Ok(number) = u32::from_str(block_position) {
-            Ok(BlockNumber(number))
-        } else {
-            match block_position {
-                "lastCommitted" => self
-                    .get_last_committed_block_number()
-                    .await
-                    .map_err(Error::storage),
-                "lastFinalized" => self
-                    .get_last_finalized_block_number()
-                    .await
-                    .map_err(Error::storage),
-                _ => Err(Error::from(InvalidDataError::InvalidBlockPosition)),
-            }
+        let block_position = BlockPosition::from_str(block_position)
+            .map_err(|_| Error::from(InvalidDataError::InvalidBlockPosition))?;
+        match block_position {
+            BlockPosition::Number(block_number) => Ok(block_number),
+            BlockPosition::LastCommitted => self
+                .get_last_committed_block_number()
+                .await
+                .map_err(Error::storage),
+            BlockPosition::LastFinalized => self
+                .get_last_finalized_block_number()
+                .await
+                .map_err(Error::storage),
         }
     }
 
     async fn block_page(
         &self,
         query: PaginationQuery<ApiEither<BlockNumber>>,
+        count_only: bool,
     ) -> Result<Paginated<BlockInfo, BlockNumber>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        storage.paginate_checked(&query).await
+        if count_only {
+            self.pool.clone().paginate_count(&query).await
+        } else {
+            self.pool.clone().paginate(&query).await
+        }
+    }
+
+    async fn block_page_by_timestamp(
+        &self,
+        query: PaginationQuery<ApiEither<BlockTimestamp>>,
+        count_only: bool,
+    ) -> Result<Paginated<BlockInfo, BlockTimestamp>, Error> {
+        if count_only {
+            self.pool.clone().paginate_count(&query).await
+        } else {
+            self.pool.clone().paginate(&query).await
+        }
     }
 
     async fn transaction_page(
         &self,
         block_number: BlockNumber,
         query: PaginationQuery<ApiEither<TxHash>>,
+        tx_type: Option<TxType>,
+        count_only: bool,
     ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-
         let new_query = PaginationQuery {
             from: BlockAndTxHash {
                 block_number,
                 tx_hash: query.from,
+                tx_type,
+            },
+            limit: query.limit,
+            direction: query.direction,
+        };
+
+        if count_only {
+            self.pool.clone().paginate_count(&new_query).await
+        } else {
+            self.pool.clone().paginate(&new_query).await
+        }
+    }
+
+    async fn transaction_page_by_index(
+        &self,
+        block_number: BlockNumber,
+        query: PaginationQuery<ApiEither<u32>>,
+        count_only: bool,
+    ) -> Result<Paginated<Transaction, u32>, Error> {
+        let new_query = PaginationQuery {
+            from: BlockAndTxIndex {
+                block_number,
+                block_index: query.from,
             },
             limit: query.limit,
             direction: query.direction,
         };
 
-        storage.paginate_checked(&new_query).await
+        if count_only {
+            self.pool.clone().paginate_count(&new_query).await
+        } else {
+            self.pool.clone().paginate(&new_query).await
+        }
     }
 
     async fn tx_data(
@@ -141,6 +298,57 @@ impl ApiBlockData {
             .map_err(Error::storage)?)
     }
 
+    async fn aggregated_operations(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<BlockAggregatedOperations, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        let last_committed = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(Error::storage)?;
+        if block_number > last_committed {
+            return Err(Error::from(InvalidDataError::BlockNotCommitted(
+                last_committed,
+            )));
+        }
+        let operations = storage
+            .chain()
+            .operations_schema()
+            .get_block_aggregated_operations(block_number)
+            .await
+            .map_err(Error::storage)?;
+        Ok(block_aggregated_operations(block_number, operations))
+    }
+
+    /// Only the finalized blocks are exported, so the export never changes.
+    async fn block_export(&self, query: BlockExportQuery) -> Result<HttpResponse, Error> {
+        if query.to < query.from {
+            return Err(Error::from(InvalidDataError::InvalidExportRange));
+        }
+        if *query.to - *query.from >= self.max_export_blocks {
+            return Err(Error::from(InvalidDataError::BlockExportRangeTooBig(
+                self.max_export_blocks,
+            )));
+        }
+        let last_finalized = self
+            .get_last_finalized_block_number()
+            .await
+            .map_err(Error::storage)?;
+        if query.to > last_finalized {
+            return Err(Error::from(InvalidDataError::ExportBlocksNotFinalized(
+                last_finalized,
+            )));
+        }
+
+        let export = BlockExport::new(self.pool.clone(), &query);
+        Ok(HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .streaming(Box::pin(export.into_stream())))
+    }
+
     async fn get_last_committed_block_number(&self) -> QueryResult<BlockNumber> {
         let mut storage = self.pool.access_storage().await?;
         storage
@@ -162,68 +370,233 @@ impl ApiBlockData {
 
 // Server implementation
 
+/// Blocks are paginated by their numbers unless the `orderBy` query parameter asks otherwise,
+/// the type of the cursor depends on the order.
 async fn block_pagination(
     data: web::Data<ApiBlockData>,
-    web::Query(query): web::Query<PaginationQuery<String>>,
-) -> ApiResult<Paginated<BlockInfo, BlockNumber>> {
+    web::Query(query): web::Query<IncomingBlocksQuery>,
+    web::Query(CountOnlyQuery { count_only }): web::Query<CountOnlyQuery>,
+) -> Either<
+    ApiResult<Paginated<BlockInfo, BlockNumber>>,
+    ApiResult<Paginated<BlockInfo, BlockTimestamp>>,
+> {
     let start = Instant::now();
-    let query = api_try!(parse_query(query).map_err(Error::from));
-    let res = data.block_page(query).await.into();
+    let order = match query.order_by.as_deref().map(BlocksOrder::from_str) {
+        Some(Ok(order)) => order,
+        Some(Err(err)) => return Either::Left(Error::from(err).into()),
+        None => BlocksOrder::default(),
+    };
+    let query = PaginationQuery {
+        from: query.from,
+        limit: query.limit,
+        direction: query.direction,
+    };
+    let res = match order {
+        BlocksOrder::Number => Either::Left(blocks_by_number(&data, query, count_only).await),
+        BlocksOrder::Timestamp => {
+            Either::Right(blocks_by_timestamp(&data, query, count_only).await)
+        }
+    };
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_pagination");
     res
 }
 
-// TODO: take `block_position` as enum.
-// Currently actix path extractor doesn't work with enums: https://github.com/actix/actix-web/issues/318 (ZKS-628)
+async fn blocks_by_number(
+    data: &ApiBlockData,
+    query: PaginationQuery<String>,
+    count_only: bool,
+) -> ApiResult<Paginated<BlockInfo, BlockNumber>> {
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    data.block_page(query, count_only).await.into()
+}
+
+async fn blocks_by_timestamp(
+    data: &ApiBlockData,
+    query: PaginationQuery<String>,
+    count_only: bool,
+) -> ApiResult<Paginated<BlockInfo, BlockTimestamp>> {
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    data.block_page_by_timestamp(query, count_only).await.into()
+}
+
+// The position is taken as a string and parsed into `BlockPosition` in the handler,
+// so that the malformed position is reported in the API error format.
 async fn block_by_position(
     data: web::Data<ApiBlockData>,
     block_position: web::Path<String>,
-) -> ApiResult<Option<BlockInfo>> {
+) -> CachedApiResult<Option<BlockInfo>> {
     let start = Instant::now();
     let block_number = api_try!(data.get_block_number_by_position(&block_position).await);
-    let res = data.block_info(block_number).await.into();
+    let block_info = api_try!(data.block_info(block_number).await);
+    // `last_committed` and `last_finalized` positions point to the different blocks over time.
+    let policy = match (&block_info, BlockPosition::from_str(&block_position)) {
+        (Some(block_info), Ok(BlockPosition::Number(_))) => CachePolicy::for_block(block_info),
+        _ => CachePolicy::NoCache,
+    };
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_by_position");
-    res
+    ApiResult::Ok(block_info).with_cache_policy(policy)
 }
 
 async fn block_transactions(
     data: web::Data<ApiBlockData>,
     block_position: web::Path<String>,
-    web::Query(query): web::Query<PaginationQuery<String>>,
+    web::Query(query): web::Query<IncomingBlockTxsQuery>,
+    web::Query(CountOnlyQuery { count_only }): web::Query<CountOnlyQuery>,
 ) -> ApiResult<Paginated<Transaction, TxHashSerializeWrapper>> {
     let start = Instant::now();
     let block_number = api_try!(data.get_block_number_by_position(&block_position).await);
-    let query = api_try!(parse_query(query).map_err(Error::from));
-    let res = data.transaction_page(block_number, query).await.into();
+    let tx_type = match query.tx_type {
+        Some(tx_type) => Some(api_try!(TxType::from_str(&tx_type).map_err(Error::from))),
+        None => None,
+    };
+    let query = api_try!(parse_query(
+        PaginationQuery {
+            from: query.from,
+            limit: query.limit,
+            direction: query.direction,
+        },
+        data.max_page_limit
+    ));
+    let res = data
+        .transaction_page(block_number, query, tx_type, count_only)
+        .await
+        .into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_transactions");
     res
 }
 
+/// Same as `block_transactions`, but uses the index of the transaction within the block as a cursor.
+async fn block_transactions_by_index(
+    data: web::Data<ApiBlockData>,
+    block_position: web::Path<String>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+    web::Query(CountOnlyQuery { count_only }): web::Query<CountOnlyQuery>,
+) -> ApiResult<Paginated<Transaction, u32>> {
+    let start = Instant::now();
+    let block_number = api_try!(data.get_block_number_by_position(&block_position).await);
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    let res = data
+        .transaction_page_by_index(block_number, query, count_only)
+        .await
+        .into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_transactions_by_index");
+    res
+}
+
 async fn transaction_in_block(
     data: web::Data<ApiBlockData>,
     path: web::Path<(BlockNumber, u64)>,
-) -> ApiResult<Option<TxData>> {
+) -> CachedApiResult<Option<TxData>> {
     let start = Instant::now();
     let (block_number, block_index) = *path;
     let res = api_try!(data.tx_data(block_number, block_index).await);
+    let policy = match &res {
+        Some(tx_data) if tx_data.tx.status == TxInBlockStatus::Finalized => {
+            let block_info = api_try!(data.block_info(block_number).await);
+            block_info
+                .as_ref()
+                .map_or(CachePolicy::NoCache, CachePolicy::for_block)
+        }
+        _ => CachePolicy::NoCache,
+    };
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "transaction_in_block");
-    ApiResult::Ok(res)
+    ApiResult::Ok(res).with_cache_policy(policy)
+}
+
+async fn block_aggregated_operations_by_position(
+    data: web::Data<ApiBlockData>,
+    block_position: web::Path<String>,
+) -> ApiResult<BlockAggregatedOperations> {
+    let start = Instant::now();
+    let block_number = api_try!(data.get_block_number_by_position(&block_position).await);
+    let res = data.aggregated_operations(block_number).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_aggregated_operations");
+    res
+}
+
+async fn block_export(
+    data: web::Data<ApiBlockData>,
+    web::Query(query): web::Query<BlockExportQuery>,
+) -> Either<HttpResponse, ApiResult<()>> {
+    let start = Instant::now();
+    let res = data.block_export(query).await;
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "block_export");
+    match res {
+        Ok(response) => Either::Left(response),
+        Err(err) => Either::Right(err.into()),
+    }
 }
 
-pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
-    let data = ApiBlockData::new(pool, cache);
+const BLOCK_PARAM: &str = "block_position";
+const BLOCK_PARAM_DESCRIPTION: &str = "Number of the block, `lastCommitted` or `lastFinalized`";
+
+pub fn api_scope(
+    pool: ConnectionPool,
+    cache: BlockDetailsCache,
+    max_export_blocks: u32,
+    max_page_limit: u32,
+) -> ApiScope {
+    let data = ApiBlockData::new(pool, cache, max_export_blocks, max_page_limit);
 
-    web::scope("blocks")
+    ApiScope::new("blocks")
         .app_data(web::Data::new(data))
-        .route("", web::get().to(block_pagination))
-        .route("{block_position}", web::get().to(block_by_position))
         .route(
-            "{block_position}/transactions",
-            web::get().to(block_transactions),
+            Operation::get("", "Blocks of the network")
+                .query::<IncomingBlocksQuery>()
+                .result::<Paginated<BlockInfo, u32>>(),
+            block_pagination,
+        )
+        // Must be registered before the route with the block position.
+        // Exports are heavy, so they are available with an API key only.
+        .route(
+            Operation::get("export", "Export of the block range")
+                .query::<BlockExportQuery>()
+                .stream(&["application/x-ndjson"])
+                .api_key_required(),
+            block_export,
+        )
+        .route(
+            Operation::get("{block_position}", "Block info")
+                .path_param(BLOCK_PARAM, BLOCK_PARAM_DESCRIPTION)
+                .result::<Option<BlockInfo>>(),
+            block_by_position,
+        )
+        .route(
+            Operation::get(
+                "{block_position}/aggregated",
+                "Aggregated operations the block was committed, proven and executed with",
+            )
+            .path_param(BLOCK_PARAM, BLOCK_PARAM_DESCRIPTION)
+            .result::<BlockAggregatedOperations>(),
+            block_aggregated_operations_by_position,
+        )
+        .route(
+            Operation::get("{block_position}/transactions", "Transactions of the block")
+                .path_param(BLOCK_PARAM, BLOCK_PARAM_DESCRIPTION)
+                .query::<IncomingBlockTxsQuery>()
+                .result::<Paginated<Transaction, TxHashSerializeWrapper>>(),
+            block_transactions,
+        )
+        // Must be registered before the route with the transaction index.
+        .route(
+            Operation::get(
+                "{block_position}/transactions/byIndex",
+                "Transactions of the block paginated by their index in the block",
+            )
+            .path_param(BLOCK_PARAM, BLOCK_PARAM_DESCRIPTION)
+            .query::<PaginationQuery<String>>()
+            .result::<Paginated<Transaction, u32>>(),
+            block_transactions_by_index,
         )
         .route(
-            "{block_position}/transactions/{block_index}",
-            web::get().to(transaction_in_block),
+            Operation::get(
+                "{block_position}/transactions/{block_index}",
+                "Transaction by its index in the block",
+            )
+            .path_param(BLOCK_PARAM, "Number of the block")
+            .path_param("block_index", "Index of the transaction in the block")
+            .result::<Option<TxData>>(),
+            transaction_in_block,
         )
 }
 
@@ -231,12 +604,22 @@ pub fn api_scope(pool: ConnectionPool, cache: BlockDetailsCache) -> Scope {
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
-        test_utils::{deserialize_response_result, TestServerConfig},
+        api_keys::{ApiKeyAuth, API_KEY_HEADER},
+        test_utils::{
+            deserialize_response_result, pagination::cursor, TestServerConfig,
+            EXECUTED_BLOCKS_COUNT, VERIFIED_BLOCKS_COUNT,
+        },
         SharedData,
     };
+    use actix_web::http::{
+        header::{CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+        StatusCode,
+    };
     use zksync_api_types::v02::{
-        pagination::PaginationDirection, transaction::TransactionData, ApiVersion,
+        error::ErrorCode, pagination::PaginationDirection, transaction::TransactionData,
+        ApiVersion, Response,
     };
+    use zksync_types::api_keys::ApiKeyScope;
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -252,7 +635,14 @@ mod tests {
             api_version: ApiVersion::V02,
         };
         let (client, server) = cfg.start_server(
-            |cfg: &TestServerConfig| api_scope(cfg.pool.clone(), BlockDetailsCache::new(10)),
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    BlockDetailsCache::new(10),
+                    cfg.config.api.rest.max_export_blocks,
+                    cfg.config.api.rest.max_page_limit,
+                )
+            },
             Some(shared_data),
         );
 
@@ -264,7 +654,7 @@ mod tests {
         let expected_blocks: Paginated<BlockInfo, BlockNumber> = {
             let mut storage = cfg.pool.access_storage().await?;
             storage
-                .paginate_checked(&query)
+                .paginate(&query)
                 .await
                 .map_err(|err| anyhow::anyhow!(err.message))?
         };
@@ -273,6 +663,65 @@ mod tests {
         let block: BlockInfo = deserialize_response_result(response)?;
         assert_eq!(block, expected_blocks.list[1]);
 
+        // Keywords are resolved to the tip of the chain.
+        let (last_committed, last_finalized) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let last_committed = storage
+                .chain()
+                .block_schema()
+                .get_last_committed_confirmed_block()
+                .await?;
+            let last_finalized = storage
+                .chain()
+                .block_schema()
+                .get_last_verified_confirmed_block()
+                .await?;
+            (last_committed, last_finalized)
+        };
+        assert!(last_committed > last_finalized);
+        for (keyword, block_number) in [
+            ("lastCommitted", last_committed),
+            ("lastFinalized", last_finalized),
+        ] {
+            let response = client.block_by_position(keyword).await?;
+            let block: BlockInfo = deserialize_response_result(response)?;
+            assert_eq!(block.block_number, block_number);
+
+            let query = PaginationQuery {
+                from: ApiEither::from_str("latest").unwrap(),
+                limit: 10,
+                direction: PaginationDirection::Older,
+            };
+            let response = client.block_transactions(&query, keyword).await?;
+            let by_keyword: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
+            let response = client
+                .block_transactions(&query, &block_number.to_string())
+                .await?;
+            let by_number: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
+            assert_eq!(by_keyword.pagination, by_number.pagination);
+            assert_eq!(
+                serde_json::to_value(by_keyword.list)?,
+                serde_json::to_value(by_number.list)?
+            );
+        }
+
+        // Malformed positions are rejected.
+        let response = client.block_by_position("lastVerified").await?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::InvalidBlockPosition)?
+        );
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 10,
+            direction: PaginationDirection::Older,
+        };
+        let response = client.block_transactions(&query, "lastVerified").await?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::InvalidBlockPosition)?
+        );
+
         let response = client.block_pagination(&query).await?;
         let paginated: Paginated<BlockInfo, BlockNumber> = deserialize_response_result(response)?;
         assert_eq!(paginated, expected_blocks);
@@ -319,6 +768,62 @@ mod tests {
             }
         }
 
+        let query = PaginationQuery {
+            from: ApiEither::from(1),
+            limit: 2,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client
+            .block_transactions_by_index(&query, &*block_number.to_string())
+            .await?;
+        let paginated: Paginated<Transaction, u32> = deserialize_response_result(response)?;
+        assert_eq!(paginated.pagination.count as usize, expected_txs.len());
+        assert_eq!(paginated.pagination.from, 1);
+        assert_eq!(
+            paginated
+                .list
+                .iter()
+                .map(|tx| tx.block_index)
+                .collect::<Vec<_>>(),
+            vec![Some(1), Some(2)]
+        );
+
+        // Only the operations of the requested type are listed and counted.
+        let expected_transfers: Vec<_> = expected_txs
+            .iter()
+            .filter(|tx| tx.op["type"] == "Transfer")
+            .map(|tx| tx.tx_hash.clone())
+            .collect();
+        let query = PaginationQuery {
+            from: ApiEither::from_str("latest").unwrap(),
+            limit: 100,
+            direction: PaginationDirection::Older,
+        };
+        let response = client
+            .block_transactions_of_type(&query, &*block_number.to_string(), "Transfer")
+            .await?;
+        let paginated: Paginated<Transaction, TxHash> = deserialize_response_result(response)?;
+        assert_eq!(
+            paginated.pagination.count as usize,
+            expected_transfers.len()
+        );
+        assert_eq!(
+            paginated
+                .list
+                .iter()
+                .map(|tx| tx.tx_hash.to_string().replace("sync-tx:", "0x"))
+                .collect::<Vec<_>>(),
+            expected_transfers
+        );
+
+        let response = client
+            .block_transactions_of_type(&query, &*block_number.to_string(), "Teleport")
+            .await?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::InvalidTxType)?
+        );
+
         for expected_tx in expected_txs {
             if !expected_tx.success {
                 continue;
@@ -342,4 +847,562 @@ mod tests {
         server.stop().await;
         Ok(())
     }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn aggregated_operations() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    BlockDetailsCache::new(10),
+                    cfg.config.api.rest.max_export_blocks,
+                    cfg.config.api.rest.max_page_limit,
+                )
+            },
+            Some(shared_data),
+        );
+        let last_committed = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+
+        for block_number in 1..=*last_committed {
+            let response = client
+                .block_aggregated_operations(&block_number.to_string())
+                .await?;
+            let operations: BlockAggregatedOperations = deserialize_response_result(response)?;
+            assert_eq!(operations.block_number, BlockNumber(block_number));
+
+            // The hashes of the operations are the ones reported in the block info.
+            let response = client.block_by_position(&block_number.to_string()).await?;
+            let block: Option<BlockInfo> = deserialize_response_result(response)?;
+            let block = block.unwrap();
+            let commit = operations.commit_blocks.unwrap();
+            assert!(commit.confirmed);
+            assert_eq!(commit.eth_tx_hash, block.commit_tx_hash);
+            assert_eq!(
+                (commit.from_block, commit.to_block),
+                (BlockNumber(block_number), BlockNumber(block_number))
+            );
+            assert_eq!(
+                operations.publish_proof_blocks_onchain.is_some(),
+                block_number <= VERIFIED_BLOCKS_COUNT
+            );
+            match operations.execute_blocks {
+                Some(execute) => {
+                    assert!(block_number <= EXECUTED_BLOCKS_COUNT);
+                    assert!(execute.confirmed);
+                    assert_eq!(execute.eth_tx_hash, block.verify_tx_hash);
+                }
+                None => assert!(block_number > EXECUTED_BLOCKS_COUNT),
+            }
+        }
+
+        // Keywords are resolved as in the other block routes.
+        let response = client.block_aggregated_operations("lastCommitted").await?;
+        let operations: BlockAggregatedOperations = deserialize_response_result(response)?;
+        assert!(operations.commit_blocks.is_some());
+
+        let response = client
+            .block_aggregated_operations(&(*last_committed + 1).to_string())
+            .await?;
+        assert_eq!(
+            response.error.unwrap()["code"],
+            serde_json::to_value(ErrorCode::BlockNotFound)?
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Makes a request and returns its status along with the `ETag` and `Cache-Control` headers.
+    async fn cached_request(
+        server: &actix_test::TestServer,
+        path: &str,
+        if_none_match: Option<&str>,
+    ) -> anyhow::Result<(StatusCode, Option<String>, String)> {
+        let mut request = server.get(format!("/api/v0.2{}", path));
+        if let Some(etag) = if_none_match {
+            request = request.insert_header((IF_NONE_MATCH, etag));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .map(|value| value.to_str().unwrap().to_string())
+        };
+        Ok((
+            response.status(),
+            header(ETAG),
+            header(CACHE_CONTROL).expect("Cache-Control header is missing"),
+        ))
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn finalized_blocks_caching() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    BlockDetailsCache::new(10),
+                    cfg.config.api.rest.max_export_blocks,
+                    cfg.config.api.rest.max_page_limit,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let last_committed = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+        let (mut finalized, mut committed) = (0, 0);
+        for block_number in 1..=*last_committed {
+            let response = client.block_by_position(&block_number.to_string()).await?;
+            let block: BlockInfo = deserialize_response_result(response)?;
+            let response = client.transaction_in_block(block_number, 0).await?;
+            let tx: Option<TxData> = deserialize_response_result(response)?;
+            let mut paths = vec![format!("/blocks/{}", block_number)];
+            // Rejected transactions are not cached even in the finalized blocks.
+            if tx.map_or(false, |tx| tx.tx.status != TxInBlockStatus::Rejected) {
+                paths.push(format!("/blocks/{}/transactions/0", block_number));
+            }
+
+            if block.status != BlockStatus::Finalized {
+                committed += 1;
+                for path in &paths {
+                    let (status, etag, cache_control) = cached_request(&server, path, None).await?;
+                    assert_eq!(status, StatusCode::OK);
+                    assert_eq!(etag, None);
+                    assert_eq!(cache_control, "no-cache");
+                }
+                continue;
+            }
+
+            finalized += 1;
+            let expected_etag = format!(
+                "W/\"{}-{}\"",
+                block_number,
+                hex::encode(block.new_state_root.to_bytes())
+            );
+            for path in &paths {
+                let (status, etag, cache_control) = cached_request(&server, path, None).await?;
+                assert_eq!(status, StatusCode::OK);
+                assert_eq!(etag.as_ref(), Some(&expected_etag));
+                assert!(cache_control.contains("max-age"));
+
+                // The second request with the known ETag isn't answered with the data.
+                let (status, etag, _) = cached_request(&server, path, Some(&expected_etag)).await?;
+                assert_eq!(status, StatusCode::NOT_MODIFIED);
+                assert_eq!(etag.as_ref(), Some(&expected_etag));
+
+                let (status, _, _) = cached_request(&server, path, Some("W/\"0-00\"")).await?;
+                assert_eq!(status, StatusCode::OK);
+            }
+        }
+        assert!(finalized > 0 && committed > 0);
+
+        // The tip of the chain moves, so it's never cached.
+        for keyword in ["lastCommitted", "lastFinalized"] {
+            let (status, etag, cache_control) =
+                cached_request(&server, &format!("/blocks/{}", keyword), None).await?;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(etag, None);
+            assert_eq!(cache_control, "no-cache");
+        }
+
+        // Errors are never cached.
+        let mut response = server
+            .get("/api/v0.2/blocks/lastVerified")
+            .send()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        assert_eq!(response.headers().get(CACHE_CONTROL).unwrap(), "no-cache");
+        let response: Response = response
+            .json()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        assert!(response.error.is_some());
+
+        server.stop().await;
+        Ok(())
+    }
+
+    /// Requests the export of the block range, returns the body of the successful response
+    /// or the error code.
+    async fn export(
+        server: &actix_test::TestServer,
+        key: &str,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Result<String, serde_json::Value>> {
+        let mut response = server
+            .get(format!("/api/v0.2/blocks/export?from={}&to={}", from, to))
+            .insert_header((API_KEY_HEADER, key))
+            .send()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        let body = response
+            .body()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        let body = String::from_utf8(body.to_vec())?;
+        if response.headers().get("content-type").unwrap() == "application/x-ndjson" {
+            Ok(Ok(body))
+        } else {
+            let response: Response = serde_json::from_str(&body)?;
+            Ok(Err(response.error.unwrap()["code"].clone()))
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocks_export() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (_client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                web::scope("")
+                    .wrap(ApiKeyAuth::new(cfg.pool.clone(), None))
+                    .service(api_scope(
+                        cfg.pool.clone(),
+                        BlockDetailsCache::new(10),
+                        cfg.config.api.rest.max_export_blocks,
+                        cfg.config.api.rest.max_page_limit,
+                    ))
+            },
+            Some(shared_data),
+        );
+        let (key, _) = cfg.issue_api_key(vec![ApiKeyScope::Read], 100).await?;
+
+        let last_finalized = cfg
+            .pool
+            .access_storage()
+            .await?
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        assert!(*last_finalized >= 3);
+        let (from, to) = (last_finalized - 2, last_finalized);
+
+        let mut expected_txs = Vec::new();
+        for block_number in *from..=*to {
+            let mut txs = cfg
+                .pool
+                .access_storage()
+                .await?
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(block_number))
+                .await?
+                .into_iter()
+                .map(|tx| TxHash::from_str(&tx.tx_hash).unwrap())
+                .collect();
+            expected_txs.append(&mut txs);
+        }
+        assert!(!expected_txs.is_empty());
+
+        // Every line is a separate transaction of the finalized block range.
+        let body = export(&server, &key, from, to).await?.unwrap();
+        assert!(body.ends_with('\n'));
+        let mut exported_txs = Vec::new();
+        for line in body.lines() {
+            let tx: Transaction = serde_json::from_str(line)?;
+            let block_number = tx.block_number.unwrap();
+            assert!(block_number >= from && block_number <= to);
+            if tx.fail_reason.is_none() {
+                assert_eq!(tx.status, TxInBlockStatus::Finalized);
+            }
+            exported_txs.push(tx.tx_hash);
+        }
+        expected_txs.sort_unstable();
+        exported_txs.sort_unstable();
+        assert_eq!(exported_txs, expected_txs);
+
+        // Only the bounded ranges of the finalized blocks are exported.
+        assert_eq!(
+            export(&server, &key, to, from).await?,
+            Err(serde_json::to_value(ErrorCode::InvalidExportRange)?)
+        );
+        let max_export_blocks = cfg.config.api.rest.max_export_blocks;
+        assert_eq!(
+            export(&server, &key, from, from + max_export_blocks).await?,
+            Err(serde_json::to_value(ErrorCode::ExportRangeTooBig)?)
+        );
+        assert_eq!(
+            export(&server, &key, from, last_finalized + 1).await?,
+            Err(serde_json::to_value(ErrorCode::ExportBlocksNotFinalized)?)
+        );
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocks_by_timestamp() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    BlockDetailsCache::new(10),
+                    cfg.config.api.rest.max_export_blocks,
+                    cfg.config.api.rest.max_page_limit,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let (expected, count) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let blocks = storage
+                .chain()
+                .block_schema()
+                .load_block_page_by_timestamp(&PaginationQuery {
+                    from: BlockTimestamp {
+                        timestamp: u64::MAX,
+                        block_number: None,
+                    },
+                    limit: u32::MAX,
+                    direction: PaginationDirection::Older,
+                })
+                .await?;
+            let blocks: Vec<_> = blocks
+                .into_iter()
+                .map(|(details, _)| block_info_from_details(details))
+                .collect();
+            // The count matches the number of blocks on the pages.
+            let count = blocks.len() as u32;
+            (blocks, count)
+        };
+        assert!(expected.len() >= 2);
+
+        // Walk through all the blocks by the `next` cursors, the blocks with the same
+        // timestamp are split between the pages.
+        let mut walked = Vec::new();
+        let mut from = ApiEither::from_str("latest").unwrap();
+        loop {
+            let query = PaginationQuery {
+                from,
+                limit: 1,
+                direction: PaginationDirection::Older,
+            };
+            let response = client.block_pagination_by_timestamp(&query).await?;
+            let page: Paginated<BlockInfo, BlockTimestamp> = deserialize_response_result(response)?;
+            assert_eq!(page.pagination.count, count);
+            walked.extend(page.list);
+            match page.pagination.next {
+                Some(next) => from = ApiEither::from(next),
+                None => break,
+            }
+        }
+        assert_eq!(walked, expected);
+
+        // The first block points back to the second one in the opposite direction.
+        let query = PaginationQuery {
+            from: ApiEither::from(BlockTimestamp {
+                timestamp: 0,
+                block_number: None,
+            }),
+            limit: 1,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client.block_pagination_by_timestamp(&query).await?;
+        let page: Paginated<BlockInfo, BlockTimestamp> = deserialize_response_result(response)?;
+        assert_eq!(page.list.first(), expected.last());
+        assert_eq!(page.pagination.previous, None);
+        assert_eq!(
+            page.pagination.next.and_then(|next| next.block_number),
+            Some(expected[expected.len() - 2].block_number)
+        );
+
+        // Default order is left intact.
+        let query = PaginationQuery {
+            from: ApiEither::from(BlockNumber(1)),
+            limit: 1,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client.block_pagination(&query).await?;
+        let page: Paginated<BlockInfo, BlockNumber> = deserialize_response_result(response)?;
+        assert_eq!(page.pagination.from, BlockNumber(1));
+
+        server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocks_page_limit() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    BlockDetailsCache::new(10),
+                    cfg.config.api.rest.max_export_blocks,
+                    cfg.config.api.rest.max_page_limit,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let max = cfg.config.api.rest.max_page_limit;
+        let invalid = Some(ErrorCode::PaginationLimitTooBig);
+        for (limit, expected_error) in [(max, None), (max + 1, invalid.clone()), (0, invalid)] {
+            let query = PaginationQuery {
+                from: ApiEither::from(BlockNumber(1)),
+                limit,
+                direction: PaginationDirection::Newer,
+            };
+            let response = client.block_pagination(&query).await?;
+            let error = response
+                .error
+                .map(|error| serde_json::from_value::<Error>(error).unwrap().code);
+            assert_eq!(error, expected_error, "limit {}", limit);
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn block_transactions_count_only() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(
+                    cfg.pool.clone(),
+                    BlockDetailsCache::new(10),
+                    cfg.config.api.rest.max_export_blocks,
+                    cfg.config.api.rest.max_page_limit,
+                )
+            },
+            Some(shared_data),
+        );
+
+        let (count, other_block_tx_hash) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let count = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions_count(BlockNumber(1))
+                .await?;
+            let other_block_txs = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(2))
+                .await?;
+            (
+                count,
+                TxHash::from_str(&other_block_txs[0].tx_hash).unwrap(),
+            )
+        };
+        assert!(count > 0);
+
+        // The limit doesn't affect the count-only response.
+        for limit in [1, 2] {
+            let query = PaginationQuery {
+                from: cursor(None),
+                limit,
+                direction: PaginationDirection::Older,
+            };
+            let response = client.block_transactions_count(&query, "1").await?;
+            let page: Paginated<Transaction, TxHashSerializeWrapper> =
+                deserialize_response_result(response)?;
+            assert!(page.list.is_empty());
+            assert_eq!(page.pagination.count, count);
+            assert_eq!(page.pagination.limit, limit);
+            assert_eq!(page.pagination.next, None);
+        }
+
+        // The cursor from another block is reported the same way as for the regular page.
+        let query = PaginationQuery {
+            from: ApiEither::from(other_block_tx_hash),
+            limit: 1,
+            direction: PaginationDirection::Older,
+        };
+        let error_code = |response: Response| {
+            response
+                .error
+                .map(|error| serde_json::from_value::<Error>(error).unwrap().code)
+        };
+        let page_error = error_code(client.block_transactions(&query, "1").await?);
+        let count_error = error_code(client.block_transactions_count(&query, "1").await?);
+        assert_eq!(page_error, Some(ErrorCode::TransactionNotFound));
+        assert_eq!(count_error, page_error);
+
+        server.stop().await;
+        Ok(())
+    }
 }
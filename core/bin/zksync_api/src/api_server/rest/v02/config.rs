@@ -4,56 +4,88 @@
 
 use std::time::Instant;
 // External uses
-use actix_web::{web, Scope};
-use serde::{Deserialize, Serialize};
+use actix_web::web;
 
 // Workspace uses
-use zksync_api_types::v02::ZksyncVersion;
+use zksync_api_types::v02::{config::ApiConfigData, ZksyncVersion};
 use zksync_config::ZkSyncConfig;
-use zksync_types::{network::Network, Address};
 
 // Local uses
-use super::response::ApiResult;
+use super::{
+    response::ApiResult,
+    spec::{ApiScope, Operation},
+};
+use crate::api_server::{
+    rest::network_status::{ContractInfo, SharedNetworkStatus},
+    tx_sender::submission_limits,
+};
 
-/// Shared data between `api/v0.2/config` endpoints.
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
-pub struct ApiConfigData {
-    network: Network,
-    contract: Address,
-    gov_contract: Address,
-    deposit_confirmations: u64,
-    zksync_version: ZksyncVersion,
-    // TODO: server_version (ZKS-627)
+/// Builds the config response from the server config.
+fn api_config_data(config: &ZkSyncConfig) -> ApiConfigData {
+    ApiConfigData {
+        network: config.chain.eth.network,
+        contract: config.contracts.contract_addr,
+        gov_contract: config.contracts.governance_addr,
+        deposit_confirmations: config.eth_watch.confirmations_for_eth_event,
+        zksync_version: ZksyncVersion::ContractV4,
+        contract_upgrade_version: None,
+        contract_target: config.contracts.contract_target_addr,
+        submission_limits: submission_limits(&config.api.common),
+        nft_factory: None,
+        max_block_chunks: config
+            .chain
+            .state_keeper
+            .block_chunk_sizes
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_default() as u64,
+    }
 }
 
-impl ApiConfigData {
-    pub fn new(config: &ZkSyncConfig) -> Self {
-        Self {
-            network: config.chain.eth.network,
-            contract: config.contracts.contract_addr,
-            gov_contract: config.contracts.governance_addr,
-            deposit_confirmations: config.eth_watch.confirmations_for_eth_event,
-            zksync_version: ZksyncVersion::ContractV4,
+/// Overrides the configured values with the current state of the contracts.
+fn with_contract_info(mut data: ApiConfigData, contract: Option<ContractInfo>) -> ApiConfigData {
+    if let Some(contract) = contract {
+        data.contract = contract.contract_addr;
+        data.gov_contract = contract.gov_contract_addr;
+        data.contract_upgrade_version = contract.upgrade_version;
+        data.nft_factory = contract.nft_factory_addr;
+        if let Some(contract_target) = contract.contract_target_addr {
+            data.contract_target = contract_target;
         }
     }
+    data
+}
+
+/// Shared data between `api/v0.2/config` endpoints.
+#[derive(Debug, Clone)]
+struct ApiConfigState {
+    config: ApiConfigData,
+    network_status: SharedNetworkStatus,
 }
 
 // Server implementation
 
-async fn config_endpoint(data: web::Data<ApiConfigData>) -> ApiResult<ApiConfigData> {
+async fn config_endpoint(data: web::Data<ApiConfigState>) -> ApiResult<ApiConfigData> {
     let start = Instant::now();
-    let res = ApiResult::Ok(*data.into_inner());
+    let contract = data.network_status.read().await.contract;
+    let res = ApiResult::Ok(with_contract_info(data.config, contract));
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "config_endpoint");
     res
 }
 
-pub fn api_scope(config: &ZkSyncConfig) -> Scope {
-    let data = ApiConfigData::new(config);
+pub fn api_scope(config: &ZkSyncConfig, network_status: SharedNetworkStatus) -> ApiScope {
+    let data = ApiConfigState {
+        config: api_config_data(config),
+        network_status,
+    };
 
-    web::scope("config")
+    ApiScope::new("config")
         .app_data(web::Data::new(data))
-        .route("", web::get().to(config_endpoint))
+        .route(
+            Operation::get("", "Configuration of the network").result::<ApiConfigData>(),
+            config_endpoint,
+        )
 }
 
 #[cfg(test)]
@@ -64,6 +96,7 @@ mod tests {
         SharedData,
     };
     use zksync_api_types::v02::ApiVersion;
+    use zksync_types::{Address, ContractUpgradeEvent, SequentialTxId};
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -71,21 +104,126 @@ mod tests {
         ignore = "Use `zk test rust-api` command to perform this test"
     )]
     async fn config_scope() -> anyhow::Result<()> {
-        let cfg = TestServerConfig::default();
+        let mut cfg = TestServerConfig::default();
+        // Priority operations don't wait for confirmations in the dev environment.
+        cfg.config.eth_watch.confirmations_for_eth_event = 10;
+        cfg.fill_database().await?;
 
         let shared_data = SharedData {
             net: cfg.config.chain.eth.network,
             api_version: ApiVersion::V02,
         };
+        let mut status = SharedNetworkStatus::new("0.0.0.0".to_string());
         let (client, server) = cfg.start_server(
-            |cfg: &TestServerConfig| api_scope(&cfg.config),
+            {
+                let status = status.clone();
+                move |cfg: &TestServerConfig| api_scope(&cfg.config, status.clone())
+            },
             Some(shared_data),
         );
         let response = client.config().await?;
         let api_config: ApiConfigData = deserialize_response_result(response)?;
-        assert_eq!(api_config, ApiConfigData::new(&cfg.config));
+        assert_eq!(api_config, api_config_data(&cfg.config));
+
+        // Every value taken from the server config is set.
+        assert_ne!(api_config.contract, Address::zero());
+        assert_ne!(api_config.gov_contract, Address::zero());
+        assert_ne!(api_config.contract_target, Address::zero());
+        assert_eq!(api_config.deposit_confirmations, 10);
+        assert_eq!(
+            api_config.max_block_chunks as usize,
+            *cfg.config
+                .chain
+                .state_keeper
+                .block_chunk_sizes
+                .iter()
+                .max()
+                .unwrap()
+        );
+        let limits = api_config.submission_limits;
+        assert!(limits.max_transactions_per_batch > 0);
+        assert!(limits.max_authors_per_batch > 0);
+        assert!(limits.max_chunks_per_batch > 0);
+        assert!(limits.max_withdrawals_per_batch > 0);
+        assert!(limits.nonce_ahead_window > 0);
+
+        // Contracts are upgraded, the new target is picked up without the server restart.
+        let contract_target = Address::repeat_byte(0x42);
+        cfg.pool
+            .access_storage()
+            .await?
+            .config_schema()
+            .store_contract_upgrade(&ContractUpgradeEvent {
+                version_id: 1,
+                new_targets: vec![Address::zero(), Address::zero(), contract_target],
+                eth_block: 1,
+            })
+            .await?;
+        status.update(&cfg.pool, SequentialTxId(0)).await?;
+
+        let response = client.config().await?;
+        let api_config: ApiConfigData = deserialize_response_result(response)?;
+        assert_eq!(api_config.contract_upgrade_version, Some(1));
+        assert_eq!(api_config.contract_target, contract_target);
+        // The NFT factory is known from the database only.
+        let nft_factory = cfg
+            .pool
+            .access_storage()
+            .await?
+            .config_schema()
+            .load_config()
+            .await?
+            .nft_factory_addr
+            .unwrap();
+        assert_eq!(
+            api_config.nft_factory,
+            Some(nft_factory.trim_start_matches("0x").parse()?)
+        );
+
+        // All the fields are present in the response, so the clients can rely on them.
+        let response = client.config().await?;
+        let fields = response.result.unwrap();
+        for field in [
+            "network",
+            "contract",
+            "govContract",
+            "depositConfirmations",
+            "zksyncVersion",
+            "contractUpgradeVersion",
+            "contractTarget",
+            "submissionLimits",
+            "nftFactory",
+            "maxBlockChunks",
+        ] {
+            assert!(!fields[field].is_null(), "{} is missing", field);
+        }
 
         server.stop().await;
         Ok(())
     }
+
+    #[test]
+    fn contract_info_overrides_config() {
+        let config = ZkSyncConfig::from_env();
+        let api_config = api_config_data(&config);
+        assert_eq!(with_contract_info(api_config, None), api_config);
+
+        let contract = ContractInfo {
+            contract_addr: Address::repeat_byte(1),
+            gov_contract_addr: Address::repeat_byte(2),
+            upgrade_version: Some(3),
+            contract_target_addr: None,
+            nft_factory_addr: Some(Address::repeat_byte(4)),
+        };
+        let updated = with_contract_info(api_config, Some(contract));
+        assert_eq!(updated.contract, contract.contract_addr);
+        assert_eq!(updated.gov_contract, contract.gov_contract_addr);
+        assert_eq!(updated.contract_upgrade_version, Some(3));
+        assert_eq!(updated.nft_factory, contract.nft_factory_addr);
+        // The main contract wasn't upgraded, so the configured target is still valid.
+        assert_eq!(
+            updated.contract_target,
+            config.contracts.contract_target_addr
+        );
+    }
 }
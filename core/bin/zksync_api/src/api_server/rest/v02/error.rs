@@ -1,89 +1,23 @@
-// Built-in uses
-use std::fmt::{Display, Formatter};
-
 // External uses
-use serde::{Deserialize, Serialize};
-use serde_repr::{Deserialize_repr, Serialize_repr};
+use serde_json::Value;
 use thiserror::Error;
 
 // Workspace uses
-use zksync_api_types::v02::pagination::{UnknownFromParameter, MAX_LIMIT};
+pub use zksync_api_types::v02::error::{
+    ApiError, CoreApiError, Error, ErrorCode, StorageError, SUBMIT_ERROR_TYPE,
+};
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+use zksync_types::{api_keys::ApiKeyScope, BlockNumber};
 
 // Local uses
-use crate::{api_server::tx_sender::SubmitError, fee_ticker::PriceError};
-
-#[derive(Serialize_repr, Debug, Deserialize_repr, Clone, PartialEq)]
-#[repr(u16)]
-pub enum ErrorCode {
-    UnreacheableError = 0,
-    CoreApiError = 100,
-    TokenZeroPriceError = 200,
-    InvalidCurrency = 201,
-    InvalidBlockPosition = 202,
-    InvalidAccountIdOrAddress = 203,
-    AccountNotFound = 204,
-    TransactionNotFound = 205,
-    PaginationLimitTooBig = 206,
-    QueryDeserializationError = 207,
-    InvalidNFTTokenId = 208,
-    StorageError = 300,
-    TokenNotFound = 500,
-    ExternalApiError = 501,
-    InternalError = 600,
-    AccountCloseDisabled = 601,
-    InvalidParams = 602,
-    UnsupportedFastProcessing = 603,
-    IncorrectTx = 604,
-    TxAddError = 605,
-    InappropriateFeeToken = 606,
-    CommunicationCoreServer = 607,
-    Toggle2FAError = 608,
-    Other = 60_000,
-}
-
-/// Error object in a response
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "camelCase")]
-pub struct Error {
-    pub error_type: String,
-    pub code: ErrorCode,
-    pub message: String,
-}
-
-/// Trait that can be used to map custom errors to the object.
-pub trait ApiError: std::fmt::Display {
-    fn error_type(&self) -> String;
-
-    fn code(&self) -> ErrorCode;
-
-    fn message(&self) -> String {
-        self.to_string()
-    }
-}
-
-impl<T> From<T> for Error
-where
-    T: ApiError,
-{
-    fn from(t: T) -> Error {
-        Error {
-            error_type: t.error_type(),
-            code: t.code(),
-            message: t.message(),
-        }
-    }
-}
-
-impl Error {
-    pub fn storage(err: impl Display) -> Error {
-        Error::from(StorageError::new(err))
-    }
-
-    pub fn core_api(err: impl Display) -> Error {
-        Error::from(CoreApiError::new(err))
-    }
-}
+use super::{account::MAX_EXPORT_BLOCKS, transaction::MAX_TX_STATUSES_PER_REQUEST};
+use crate::{
+    api_server::{
+        rpc_server::error::{submit_error_data, submit_error_rpc_code, RpcErrorCodes},
+        tx_sender::SubmitError,
+    },
+    fee_ticker::PriceError,
+};
 
 #[derive(Error, Debug)]
 pub enum InvalidDataError {
@@ -95,14 +29,28 @@ pub enum InvalidDataError {
     InvalidAccountIdOrAddress,
     #[error("Account is not found")]
     AccountNotFound,
-    #[error("Cannot parse currency. There are only token_id, usd options")]
+    #[error("Cannot parse currency. There are only usd and token id, symbol or address options")]
     InvalidCurrency,
     #[error("Transaction is not found")]
     TransactionNotFound,
-    #[error("Limit for pagination should be less than or equal to {}", MAX_LIMIT)]
-    PaginationLimitTooBig,
+    #[error("Limit for pagination should be between 1 and {max}")]
+    InvalidPaginationLimit { max: u32 },
     #[error("NFT token ID should be greater than or equal to {}", MIN_NFT_TOKEN_ID)]
     InvalidNFTTokenId,
+    #[error("Exit data endpoint is disabled on this server")]
+    ExitDataDisabled,
+    #[error("Exit data is available only for fungible tokens")]
+    NFTExitDataUnsupported,
+    #[error("Export block range should not end before it starts")]
+    InvalidExportRange,
+    #[error("Export block range should not exceed {} blocks", MAX_EXPORT_BLOCKS)]
+    ExportRangeTooBig,
+    #[error("Export block range should not exceed {0} blocks")]
+    BlockExportRangeTooBig(u32),
+    #[error("Only finalized blocks can be exported, the last finalized block is {0}")]
+    ExportBlocksNotFinalized(BlockNumber),
+    #[error("Block is not committed yet, the last committed block is {0}")]
+    BlockNotCommitted(BlockNumber),
 }
 
 impl ApiError for InvalidDataError {
@@ -118,92 +66,110 @@ impl ApiError for InvalidDataError {
             Self::AccountNotFound => ErrorCode::AccountNotFound,
             Self::InvalidCurrency => ErrorCode::InvalidCurrency,
             Self::TransactionNotFound => ErrorCode::TransactionNotFound,
-            Self::PaginationLimitTooBig => ErrorCode::PaginationLimitTooBig,
+            Self::InvalidPaginationLimit { .. } => ErrorCode::PaginationLimitTooBig,
             Self::InvalidNFTTokenId => ErrorCode::InvalidNFTTokenId,
+            Self::ExitDataDisabled => ErrorCode::ExitDataDisabled,
+            Self::NFTExitDataUnsupported => ErrorCode::NFTExitDataUnsupported,
+            Self::InvalidExportRange => ErrorCode::InvalidExportRange,
+            Self::ExportRangeTooBig => ErrorCode::ExportRangeTooBig,
+            Self::BlockExportRangeTooBig(_) => ErrorCode::ExportRangeTooBig,
+            Self::ExportBlocksNotFinalized(_) => ErrorCode::ExportBlocksNotFinalized,
+            Self::BlockNotCommitted(_) => ErrorCode::BlockNotFound,
         }
     }
 }
 
-#[derive(Debug)]
-pub struct StorageError(String);
-
-impl StorageError {
-    pub fn new(title: impl Display) -> Self {
-        Self(title.to_string())
-    }
-}
-
-impl Display for StorageError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
-    }
+/// Errors of the transaction hash passed to the `transactions` routes.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum TxError {
+    #[error("Transaction hash should consist of 64 hex digits, got {0}")]
+    InvalidHashLength(usize),
+    #[error("Transaction hash contains an invalid hex character '{0}'")]
+    InvalidHashHex(char),
+    #[error("Transaction hash prefix '{0}' is not supported, use '0x', 'sync-tx:' or no prefix")]
+    UnsupportedHashPrefix(String),
+    #[error(
+        "Statuses of at most {} transactions can be requested at once, got {0}",
+        MAX_TX_STATUSES_PER_REQUEST
+    )]
+    TooManyHashes(usize),
+    #[error(
+        "Withdrawal status is only available for Withdraw, WithdrawNFT and ForcedExit transactions"
+    )]
+    NotWithdrawal,
+    #[error("Transaction is not found among the transactions of the account")]
+    TransactionNotFound,
 }
 
-impl ApiError for StorageError {
+impl ApiError for TxError {
     fn error_type(&self) -> String {
-        String::from("storageError")
+        String::from("txError")
     }
 
     fn code(&self) -> ErrorCode {
-        ErrorCode::StorageError
-    }
-}
-
-#[derive(Debug)]
-pub struct CoreApiError(String);
-
-impl CoreApiError {
-    pub fn new(title: impl Display) -> Self {
-        Self(title.to_string())
-    }
-}
-
-impl Display for CoreApiError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&self.0)
+        match self {
+            Self::InvalidHashLength(_) => ErrorCode::InvalidTxHashLength,
+            Self::InvalidHashHex(_) => ErrorCode::InvalidTxHashHex,
+            Self::UnsupportedHashPrefix(_) => ErrorCode::UnsupportedTxHashPrefix,
+            Self::TooManyHashes(_) => ErrorCode::TooManyTxHashes,
+            Self::NotWithdrawal => ErrorCode::NotWithdrawalTx,
+            Self::TransactionNotFound => ErrorCode::TransactionNotFound,
+        }
     }
 }
 
-impl ApiError for CoreApiError {
+#[derive(Error, Debug)]
+pub enum AccessError {
+    #[error("API key is unknown or revoked")]
+    InvalidApiKey,
+    #[error("API key doesn't give access to the {0} scope")]
+    ScopeNotAllowed(ApiKeyScope),
+    #[error("Too many requests, the limit is {0} requests per minute")]
+    RateLimitExceeded(u32),
+    #[error("API key is required to access this endpoint")]
+    ApiKeyRequired,
+}
+
+impl ApiError for AccessError {
     fn error_type(&self) -> String {
-        String::from("coreApiError")
+        String::from("accessError")
     }
 
     fn code(&self) -> ErrorCode {
-        ErrorCode::CoreApiError
+        match self {
+            Self::InvalidApiKey => ErrorCode::InvalidApiKey,
+            Self::ScopeNotAllowed(_) => ErrorCode::ApiKeyScopeNotAllowed,
+            Self::RateLimitExceeded(_) => ErrorCode::RateLimitExceeded,
+            Self::ApiKeyRequired => ErrorCode::ApiKeyRequired,
+        }
     }
 }
 
-impl ApiError for anyhow::Error {
-    fn error_type(&self) -> String {
-        "internalError".to_string()
-    }
-
-    fn code(&self) -> ErrorCode {
-        ErrorCode::Other
+impl From<RpcErrorCodes> for ErrorCode {
+    fn from(code: RpcErrorCodes) -> Self {
+        Self::Submission(code as u16)
     }
 }
 
 impl ApiError for SubmitError {
     fn error_type(&self) -> String {
-        String::from("submitError")
+        String::from(SUBMIT_ERROR_TYPE)
     }
 
+    /// Submission failures are reported with the same codes as in the JSON-RPC API.
     fn code(&self) -> ErrorCode {
-        match self {
-            Self::AccountCloseDisabled => ErrorCode::AccountCloseDisabled,
-            Self::InvalidParams(_) => ErrorCode::InvalidParams,
-            Self::UnsupportedFastProcessing => ErrorCode::UnsupportedFastProcessing,
-            Self::IncorrectTx(_) => ErrorCode::IncorrectTx,
-            Self::TxAdd(_) => ErrorCode::TxAddError,
-            Self::InappropriateFeeToken => ErrorCode::InappropriateFeeToken,
-            Self::MempoolCommunication(_) => ErrorCode::CommunicationCoreServer,
-            Self::Internal(_) => ErrorCode::InternalError,
-            Self::Toggle2FA(_) => ErrorCode::Toggle2FAError,
-            Self::Other(_) => ErrorCode::Other,
-            Self::PriceError(_) => ErrorCode::InternalError,
+        match (self, submit_error_rpc_code(self)) {
+            (_, Some(code)) => code.into(),
+            (Self::InvalidParams(_), None) => ErrorCode::InvalidParams,
+            (Self::Other(_), None) => ErrorCode::Other,
+            (_, None) => ErrorCode::InternalError,
         }
     }
+
+    /// Carries the same code and details as the JSON-RPC error of the submission.
+    fn data(&self) -> Option<Value> {
+        Some(submit_error_data(self))
+    }
 }
 
 impl ApiError for PriceError {
@@ -215,17 +181,8 @@ impl ApiError for PriceError {
         match self {
             Self::TokenNotFound(_) => ErrorCode::TokenNotFound,
             Self::ApiError(_) => ErrorCode::ExternalApiError,
+            Self::PriceNotFound(_) => ErrorCode::TokenPriceUnavailable,
             Self::DBError(_) => ErrorCode::StorageError,
         }
     }
 }
-
-impl ApiError for UnknownFromParameter {
-    fn error_type(&self) -> String {
-        String::from("invalidDataError")
-    }
-
-    fn code(&self) -> ErrorCode {
-        ErrorCode::QueryDeserializationError
-    }
-}
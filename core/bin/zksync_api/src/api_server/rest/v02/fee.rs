@@ -4,19 +4,27 @@
 
 use std::time::Instant;
 // External uses
-use actix_web::{
-    web::{self, Json},
-    Scope,
-};
+use actix_web::web::{self, Json};
+use bigdecimal::BigDecimal;
+use num::BigUint;
 
 // Workspace uses
-use zksync_api_types::v02::fee::{ApiFee, BatchFeeRequest, TxFeeRequest};
+use zksync_api_types::v02::fee::{
+    ApiFee, ApiWithdrawalEstimate, BatchFeeRequest, TxFeeRequest, WithdrawalEstimateQuery,
+};
+use zksync_types::{Address, TokenLike, TxFeeTypes};
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 
 // Local uses
-use super::{error::Error, response::ApiResult};
+use super::{
+    error::Error,
+    response::ApiResult,
+    spec::{ApiScope, Operation},
+};
 use crate::{
     api_server::tx_sender::{SubmitError, TxSender},
     api_try,
+    fee_ticker::TokenPriceRequestType,
 };
 
 /// Shared data between `api/v0.2/fee` endpoints.
@@ -29,6 +37,18 @@ impl ApiFeeData {
     fn new(tx_sender: TxSender) -> Self {
         Self { tx_sender }
     }
+
+    /// Converts the fee in the smallest units of the token to USD.
+    async fn fee_usd(&self, token: TokenLike, fee: &BigUint) -> Result<BigDecimal, Error> {
+        let price = self
+            .tx_sender
+            .ticker
+            .get_token_price(token, TokenPriceRequestType::USDForOneWei)
+            .await
+            .map_err(Error::from)?;
+        let price = big_decimal_to_ratio(&price).map_err(Error::from)?;
+        Ok(ratio_to_big_decimal(&(price * fee.clone()), USD_PRECISION))
+    }
 }
 
 async fn get_tx_fee(
@@ -46,14 +66,17 @@ async fn get_tx_fee(
         return Error::from(SubmitError::InappropriateFeeToken).into();
     }
     // TODO implement subsidies for v02 api ZKS-888
-    let res = data
+    let fee = api_try!(data
         .tx_sender
         .ticker
-        .get_fee_from_ticker_in_wei(body.tx_type.into(), body.token_like, body.address)
+        .get_fee_from_ticker_in_wei(body.tx_type.into(), body.token_like.clone(), body.address)
         .await
-        .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+        .map_err(Error::from));
+    let total_fee_usd = api_try!(
+        data.fee_usd(body.token_like, &fee.normal_fee.total_fee)
+            .await
+    );
+    let res = ApiResult::Ok(ApiFee::new(fee.normal_fee, total_fee_usd));
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_tx_fee");
     res
 }
@@ -77,25 +100,105 @@ async fn get_batch_fee(
         .into_iter()
         .map(|tx| (tx.tx_type.into(), tx.address))
         .collect();
-    let res = data
+    let fee = api_try!(data
         .tx_sender
         .ticker
-        .get_batch_from_ticker_in_wei(body.token_like, txs)
+        .get_batch_from_ticker_in_wei(body.token_like.clone(), txs)
         .await
-        .map(|fee| fee.normal_fee.into())
-        .map_err(Error::from)
-        .into();
+        .map_err(Error::from));
+    // The fee components are summed over all the transactions of the batch.
+    let total_fee_usd = api_try!(
+        data.fee_usd(body.token_like, &fee.normal_fee.total_fee)
+            .await
+    );
+    let res = ApiResult::Ok(ApiFee::from_batch_fee(
+        fee.normal_fee,
+        fee.gas_price_wei,
+        total_fee_usd,
+    ));
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_batch_fee");
     res
 }
 
-pub fn api_scope(tx_sender: TxSender) -> Scope {
+/// Precision of the USD amounts returned by the withdrawal estimate.
+const USD_PRECISION: usize = 18;
+
+async fn get_withdrawal_estimate(
+    data: web::Data<ApiFeeData>,
+    web::Query(query): web::Query<WithdrawalEstimateQuery>,
+) -> ApiResult<ApiWithdrawalEstimate> {
+    let start = Instant::now();
+    let token_like = TokenLike::parse(&query.token);
+    let token_allowed = api_try!(data
+        .tx_sender
+        .ticker
+        .token_allowed_for_fees(token_like.clone())
+        .await
+        .map_err(Error::from));
+    if !token_allowed {
+        return Error::from(SubmitError::InappropriateFeeToken).into();
+    }
+
+    let estimate = api_try!(data
+        .tx_sender
+        .ticker
+        .get_withdrawal_cost_estimate(token_like)
+        .await
+        .map_err(Error::from));
+    // The recipient doesn't affect the fee for withdrawals.
+    let fee = api_try!(data
+        .tx_sender
+        .ticker
+        .get_fee_from_ticker_in_wei(
+            TxFeeTypes::Withdraw,
+            TokenLike::Id(estimate.token.id),
+            Address::zero()
+        )
+        .await
+        .map_err(Error::from));
+    let fee_usd = api_try!(
+        data.fee_usd(TokenLike::Id(estimate.token.id), &fee.normal_fee.total_fee)
+            .await
+    );
+
+    let res = ApiResult::Ok(ApiWithdrawalEstimate {
+        token_id: estimate.token.id,
+        gas_amount: estimate.gas_amount,
+        gas_price_wei: estimate.gas_price_wei,
+        l1_cost: estimate.cost_in_token,
+        l1_cost_usd: ratio_to_big_decimal(&estimate.cost_usd, USD_PRECISION),
+        zksync_fee: ApiFee::new(fee.normal_fee, fee_usd),
+    });
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_withdrawal_estimate");
+    res
+}
+
+pub fn api_scope(tx_sender: TxSender) -> ApiScope {
     let data = ApiFeeData::new(tx_sender);
 
-    web::scope("fee")
+    ApiScope::new("fee")
         .app_data(web::Data::new(data))
-        .route("", web::post().to(get_tx_fee))
-        .route("/batch", web::post().to(get_batch_fee))
+        .route(
+            Operation::post("", "Fee of the transaction")
+                .body::<TxFeeRequest>()
+                .result::<ApiFee>(),
+            get_tx_fee,
+        )
+        .route(
+            Operation::post("/batch", "Fee of the transaction batch")
+                .body::<BatchFeeRequest>()
+                .result::<ApiFee>(),
+            get_batch_fee,
+        )
+        .route(
+            Operation::get(
+                "/withdrawal_estimate",
+                "Estimated time of the withdrawal processing",
+            )
+            .query::<WithdrawalEstimateQuery>()
+            .result::<ApiWithdrawalEstimate>(),
+            get_withdrawal_estimate,
+        )
 }
 
 #[cfg(test)]
@@ -163,6 +266,7 @@ mod tests {
         let cache = TokenInMemoryCache::new()
             .with_tokens(tokens)
             .with_market(market);
+        let fee_ticker = dummy_fee_ticker(&prices, Some(cache.clone()));
         let (client, server) = cfg.start_server(
             move |cfg: &TestServerConfig| {
                 api_scope(TxSender::new(
@@ -197,6 +301,28 @@ mod tests {
         assert_eq!(api_fee.gas_fee, BigUint::from(1u32));
         assert_eq!(api_fee.zkp_fee, BigUint::from(1u32));
         assert_eq!(api_fee.total_fee, BigUint::from(2u32));
+        assert_eq!(api_fee.gas_price_wei, BigUint::from(1u32));
+        // The token has no decimals in the dummy ticker, so its price is per the smallest unit.
+        assert_eq!(api_fee.total_fee_usd, BigDecimal::from(20_000));
+
+        // NFT operations are quoted as themselves rather than as transfers.
+        for (api_tx_type, tx_type) in [
+            (ApiTxFeeTypes::MintNFT, TxFeeTypes::MintNFT),
+            (ApiTxFeeTypes::WithdrawNFT, TxFeeTypes::WithdrawNFT),
+            (ApiTxFeeTypes::FastWithdrawNFT, TxFeeTypes::FastWithdrawNFT),
+        ] {
+            let response = client
+                .get_txs_fee(api_tx_type, address, allowed_token.clone())
+                .await?;
+            let api_fee: ApiFee = deserialize_response_result(response)?;
+            let expected_fee = fee_ticker
+                .get_fee_from_ticker_in_wei(tx_type, allowed_token.clone(), address)
+                .await?
+                .normal_fee;
+            assert_eq!(api_fee.gas_fee, expected_fee.gas_fee);
+            assert_eq!(api_fee.zkp_fee, expected_fee.zkp_fee);
+            assert_eq!(api_fee.total_fee, expected_fee.total_fee);
+        }
 
         let tx = TxInBatchFeeRequest {
             tx_type: ApiTxFeeTypes::Withdraw,
@@ -209,6 +335,21 @@ mod tests {
         assert_eq!(api_batch_fee.gas_fee, BigUint::from(1u32));
         assert_eq!(api_batch_fee.zkp_fee, BigUint::from(1u32));
         assert_eq!(api_batch_fee.total_fee, BigUint::from(2u32));
+        assert_eq!(api_batch_fee.gas_price_wei, BigUint::from(1u32));
+        assert_eq!(api_batch_fee.total_fee_usd, BigDecimal::from(20_000));
+
+        let response = client.get_withdrawal_estimate("1").await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error, expected_error);
+
+        let response = client.get_withdrawal_estimate("2").await?;
+        let estimate: ApiWithdrawalEstimate = deserialize_response_result(response)?;
+        assert_eq!(estimate.token_id, TokenId(2));
+        assert_eq!(estimate.gas_price_wei, BigUint::from(1u32));
+        // L1 cost of a withdrawal is negligible with a gas price of 1 wei.
+        assert_eq!(estimate.l1_cost, BigUint::from(0u32));
+        assert_eq!(estimate.zksync_fee.total_fee, BigUint::from(2u32));
+        assert_eq!(estimate.zksync_fee.total_fee_usd, BigDecimal::from(20_000));
 
         server.stop().await;
         Ok(())
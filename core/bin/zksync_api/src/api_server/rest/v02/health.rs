@@ -0,0 +1,357 @@
+//! Health and readiness probes of the API server.
+//!
+//! Health is reported as long as the process is up, while readiness checks that the dependencies
+//! of the server answer, so that the load balancer can stop routing the requests to a broken node.
+
+// Built-in uses
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+// External uses
+use actix_web::{http::StatusCode, web, HttpRequest, HttpResponse, Responder};
+use chrono::Utc;
+
+// Workspace uses
+use zksync_api_types::v02::status::{Health, Readiness, ReadinessCheck, ReadinessCheckKind};
+use zksync_storage::ConnectionPool;
+
+// Local uses
+use super::{
+    response::ApiResult,
+    spec::{ApiScope, Operation},
+};
+use crate::core_api_client::CoreApiClient;
+
+/// Every dependency is expected to answer within this time.
+const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Shared data of the `api/v0.2/health` endpoint.
+#[derive(Debug, Clone)]
+struct ApiHealthData {
+    started_at: Instant,
+}
+
+/// Shared data of the `api/v0.2/ready` endpoint.
+#[derive(Debug, Clone)]
+struct ApiReadinessData {
+    pool: ConnectionPool,
+    core_api_client: CoreApiClient,
+    /// The server is not ready if the last committed block is older than this.
+    max_block_age: Option<Duration>,
+}
+
+impl ApiReadinessData {
+    fn new(
+        pool: ConnectionPool,
+        core_api_client: CoreApiClient,
+        max_block_age: Option<Duration>,
+    ) -> Self {
+        Self {
+            pool,
+            core_api_client,
+            max_block_age,
+        }
+    }
+
+    async fn check_database(&self) -> anyhow::Result<()> {
+        self.pool
+            .try_access_storage(READINESS_CHECK_TIMEOUT)
+            .await?;
+        Ok(())
+    }
+
+    async fn check_core_api(&self) -> anyhow::Result<()> {
+        tokio::time::timeout(READINESS_CHECK_TIMEOUT, self.core_api_client.status())
+            .await
+            .map_err(|_| anyhow::format_err!("Core API hasn't answered in time"))??;
+        Ok(())
+    }
+
+    async fn check_last_block_age(&self, max_age: Duration) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .try_access_storage(READINESS_CHECK_TIMEOUT)
+            .await?;
+        let last_committed = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await?;
+        let block = storage
+            .chain()
+            .block_schema()
+            .load_block_range_desc(last_committed, 1)
+            .await?
+            .into_iter()
+            .next();
+        // There is nothing to be stale before the first block.
+        if let Some(block) = block {
+            let age = (Utc::now() - block.committed_at)
+                .to_std()
+                .unwrap_or_default();
+            if age > max_age {
+                anyhow::bail!(
+                    "Last committed block {} is {} seconds old",
+                    last_committed,
+                    age.as_secs()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    async fn readiness(&self) -> Readiness {
+        let (database, core_api) = futures::future::join(
+            run_check(ReadinessCheckKind::Database, self.check_database()),
+            run_check(ReadinessCheckKind::CoreApi, self.check_core_api()),
+        )
+        .await;
+        let mut checks = vec![database, core_api];
+        if let Some(max_age) = self.max_block_age {
+            checks.push(
+                run_check(
+                    ReadinessCheckKind::LastBlockAge,
+                    self.check_last_block_age(max_age),
+                )
+                .await,
+            );
+        }
+
+        Readiness {
+            ready: checks.iter().all(|check| check.passed),
+            checks,
+        }
+    }
+}
+
+async fn run_check(
+    check: ReadinessCheckKind,
+    future: impl Future<Output = anyhow::Result<()>>,
+) -> ReadinessCheck {
+    let start = Instant::now();
+    let result = future.await;
+    ReadinessCheck {
+        check,
+        passed: result.is_ok(),
+        latency_ms: start.elapsed().as_millis() as u64,
+        error: result.err().map(|err| err.to_string()),
+    }
+}
+
+// Server implementation
+
+async fn health(data: web::Data<ApiHealthData>) -> ApiResult<Health> {
+    ApiResult::Ok(Health {
+        uptime_secs: data.started_at.elapsed().as_secs(),
+    })
+}
+
+/// Load balancers look at the HTTP status only, so the unready server answers with `503`.
+async fn ready(data: web::Data<ApiReadinessData>, req: HttpRequest) -> HttpResponse {
+    let start = Instant::now();
+    let readiness = data.readiness().await;
+    let ready = readiness.ready;
+    let mut response = ApiResult::Ok(readiness).respond_to(&req);
+    if !ready {
+        *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+    }
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "ready");
+    response
+}
+
+pub fn health_scope() -> ApiScope {
+    let data = ApiHealthData {
+        started_at: Instant::now(),
+    };
+
+    ApiScope::new("health")
+        .app_data(web::Data::new(data))
+        .route(
+            Operation::get("", "Liveness of the server").result::<Health>(),
+            health,
+        )
+}
+
+pub fn readiness_scope(
+    pool: ConnectionPool,
+    core_api_client: CoreApiClient,
+    max_block_age: Option<Duration>,
+) -> ApiScope {
+    let data = ApiReadinessData::new(pool, core_api_client, max_block_age);
+
+    ApiScope::new("ready").app_data(web::Data::new(data)).route(
+        Operation::get("", "Readiness of the server to serve the requests").result::<Readiness>(),
+        ready,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{test_utils::TestServerConfig, SharedData};
+    use actix_web::App;
+    use zksync_api_types::{
+        v02::{ApiVersion, Response},
+        CoreStatus,
+    };
+    use zksync_types::network::Network;
+
+    /// Nothing listens on this port.
+    const CLOSED_ADDRESS: &str = "127.0.0.1:1";
+
+    async fn mock_core_status() -> HttpResponse {
+        HttpResponse::Ok().json(CoreStatus {
+            main_database_available: true,
+            replica_database_available: true,
+            web3_available: true,
+            contract_config_outdated: false,
+            root_hash_mismatch: false,
+            block_time: None,
+        })
+    }
+
+    fn start_core_server() -> actix_test::TestServer {
+        actix_test::start(|| App::new().route("/status", web::get().to(mock_core_status)))
+    }
+
+    fn start_server(
+        pool: ConnectionPool,
+        core_address: String,
+        max_block_age: Option<Duration>,
+    ) -> actix_test::TestServer {
+        let shared_data = SharedData {
+            net: Network::Localhost,
+            api_version: ApiVersion::V02,
+        };
+        actix_test::start(move || {
+            App::new().app_data(web::Data::new(shared_data)).service(
+                web::scope("/api/v0.2")
+                    .service(health_scope())
+                    .service(readiness_scope(
+                        pool.clone(),
+                        CoreApiClient::new(core_address.clone()),
+                        max_block_age,
+                    )),
+            )
+        })
+    }
+
+    async fn request<T: serde::de::DeserializeOwned>(
+        server: &actix_test::TestServer,
+        path: &str,
+    ) -> anyhow::Result<(StatusCode, T)> {
+        let mut response = server
+            .get(format!("/api/v0.2{}", path))
+            .send()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        let status = response.status();
+        let response: Response = response
+            .json()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        Ok((status, serde_json::from_value(response.result.unwrap())?))
+    }
+
+    fn passed_checks(readiness: &Readiness) -> Vec<(ReadinessCheckKind, bool)> {
+        readiness
+            .checks
+            .iter()
+            .map(|check| (check.check, check.passed))
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn database_down() -> anyhow::Result<()> {
+        let core_server = start_core_server();
+        let pool = ConnectionPool::new_with_url(
+            format!("postgres://postgres@{}/plasma", CLOSED_ADDRESS),
+            1,
+        );
+        let server = start_server(
+            pool,
+            core_server.url("").trim_end_matches('/').to_owned(),
+            Some(Duration::from_secs(3600)),
+        );
+
+        // The process is up regardless of its dependencies.
+        let (status, _health): (_, Health) = request(&server, "/health").await?;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, readiness): (_, Readiness) = request(&server, "/ready").await?;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!readiness.ready);
+        assert_eq!(
+            passed_checks(&readiness),
+            vec![
+                (ReadinessCheckKind::Database, false),
+                (ReadinessCheckKind::CoreApi, true),
+                (ReadinessCheckKind::LastBlockAge, false),
+            ]
+        );
+        assert!(readiness.checks[0].error.is_some());
+        assert!(readiness.checks[0].latency_ms <= READINESS_CHECK_TIMEOUT.as_millis() as u64 + 500);
+
+        server.stop().await;
+        core_server.stop().await;
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn readiness_checks() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let core_server = start_core_server();
+        let core_address = core_server.url("").trim_end_matches('/').to_owned();
+
+        // All the dependencies are fine, the age is not checked if not configured.
+        let server = start_server(cfg.pool.clone(), core_address.clone(), None);
+        let (status, readiness): (_, Readiness) = request(&server, "/ready").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert!(readiness.ready);
+        assert_eq!(
+            passed_checks(&readiness),
+            vec![
+                (ReadinessCheckKind::Database, true),
+                (ReadinessCheckKind::CoreApi, true),
+            ]
+        );
+        server.stop().await;
+
+        // The last block is stale.
+        let server = start_server(cfg.pool.clone(), core_address, Some(Duration::from_secs(0)));
+        let (status, readiness): (_, Readiness) = request(&server, "/ready").await?;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            passed_checks(&readiness),
+            vec![
+                (ReadinessCheckKind::Database, true),
+                (ReadinessCheckKind::CoreApi, true),
+                (ReadinessCheckKind::LastBlockAge, false),
+            ]
+        );
+        server.stop().await;
+
+        // The core is down.
+        let server = start_server(cfg.pool.clone(), format!("http://{}", CLOSED_ADDRESS), None);
+        let (status, readiness): (_, Readiness) = request(&server, "/ready").await?;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            passed_checks(&readiness),
+            vec![
+                (ReadinessCheckKind::Database, true),
+                (ReadinessCheckKind::CoreApi, false),
+            ]
+        );
+        server.stop().await;
+
+        core_server.stop().await;
+        Ok(())
+    }
+}
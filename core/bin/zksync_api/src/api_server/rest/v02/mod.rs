@@ -1,25 +1,37 @@
 // External uses
 use actix_web::{
+    dev::HttpServiceFactory,
     web::{self},
-    Scope,
 };
 // Workspace uses
 use crate::api_server::rest::network_status::SharedNetworkStatus;
 use zksync_api_types::v02::ApiVersion;
 use zksync_config::ZkSyncConfig;
+use zksync_eth_client::EthereumGateway;
 use zksync_types::network::Network;
 
 // Local uses
-use crate::api_server::tx_sender::TxSender;
+use self::{api_keys::ApiKeyAuth, request_id::RequestIdTracing};
+use crate::{
+    api_server::{rest::http_metrics::HttpMetrics, tx_sender::TxSender},
+    core_api_client::CoreApiClient,
+};
 
 mod account;
+pub mod api_keys;
 mod block;
 mod config;
 pub mod error;
 mod fee;
+mod health;
+mod page_limit;
 mod paginate_impl;
 mod paginate_trait;
-mod response;
+mod priority_ops;
+pub mod request_id;
+pub mod response;
+mod search;
+mod spec;
 mod status;
 #[cfg(test)]
 pub mod test_utils;
@@ -36,30 +48,60 @@ pub(crate) fn api_scope(
     tx_sender: TxSender,
     zk_config: &ZkSyncConfig,
     network_status: SharedNetworkStatus,
-) -> Scope {
+    api_key_auth: ApiKeyAuth,
+    core_api_client: CoreApiClient,
+    eth_client: EthereumGateway,
+) -> impl HttpServiceFactory {
     let data = SharedData {
         net: zk_config.chain.eth.network,
         api_version: ApiVersion::V02,
     };
-    web::scope("/api/v0.2")
-        .app_data(web::Data::new(data))
-        .service(account::api_scope(
+    let scopes = vec![
+        account::api_scope(
             tx_sender.pool.clone(),
             tx_sender.tokens.clone(),
             zk_config.eth_watch.confirmations_for_eth_event,
-        ))
-        .service(block::api_scope(
+            zk_config.api.rest.exit_data_enabled,
+            zk_config.api.common.caches_size,
+            core_api_client.clone(),
+            tx_sender.clone(),
+            zk_config.api.rest.max_page_limit,
+        ),
+        block::api_scope(
             tx_sender.pool.clone(),
             tx_sender.blocks.clone(),
-        ))
-        .service(config::api_scope(zk_config))
-        .service(fee::api_scope(tx_sender.clone()))
-        .service(status::api_scope(network_status))
-        .service(token::api_scope(
+            zk_config.api.rest.max_export_blocks,
+            zk_config.api.rest.max_page_limit,
+        ),
+        config::api_scope(zk_config, network_status.clone()),
+        fee::api_scope(tx_sender.clone()),
+        health::health_scope(),
+        health::readiness_scope(
+            tx_sender.pool.clone(),
+            core_api_client,
+            zk_config.api.rest.ready_max_block_age(),
+        ),
+        priority_ops::api_scope(tx_sender.pool.clone(), zk_config.api.rest.max_page_limit),
+        search::api_scope(tx_sender.pool.clone()),
+        status::api_scope(network_status),
+        status::mempool_scope(tx_sender.pool.clone()),
+        token::api_scope(
             zk_config,
             tx_sender.pool.clone(),
             tx_sender.tokens.clone(),
             tx_sender.ticker.clone(),
-        ))
-        .service(transaction::api_scope(tx_sender))
+        ),
+        transaction::api_scope(tx_sender, eth_client),
+    ];
+    let spec_scope = spec::api_scope(&scopes);
+
+    let mut api = web::scope("/api/v0.2")
+        .app_data(web::Data::new(data))
+        .wrap(api_key_auth)
+        .wrap(HttpMetrics)
+        .wrap(RequestIdTracing);
+    for scope in scopes {
+        api = api.service(scope);
+    }
+    api.service(spec_scope)
 }
@@ -0,0 +1,75 @@
+//! Validation of the pagination limit of the v0.2 API.
+//!
+//! All the paginated endpoints parse their pagination query with [`parse_query`], so the limit is
+//! checked once the query is deserialized, whatever way it was written in the query string.
+//! Requests asking for an empty page or for more items than the configured maximum are rejected.
+
+// Built-in uses
+use std::str::FromStr;
+
+// External uses
+use serde::Serialize;
+
+// Workspace uses
+use zksync_api_types::v02::pagination::{self, ApiEither, PaginationQuery};
+
+// Local uses
+use super::error::{Error, InvalidDataError};
+
+/// Parses the `from` cursor of the pagination query and checks that the limit is in `1..=max_limit` range.
+pub fn parse_query<T: FromStr + Serialize>(
+    query: PaginationQuery<String>,
+    max_limit: u32,
+) -> Result<PaginationQuery<ApiEither<T>>, Error> {
+    check_limit(query.limit, max_limit)?;
+    pagination::parse_query(query).map_err(Error::from)
+}
+
+fn check_limit(limit: u32, max: u32) -> Result<(), Error> {
+    if limit == 0 || limit > max {
+        return Err(InvalidDataError::InvalidPaginationLimit { max }.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::error::ErrorCode;
+    use actix_web::web;
+    use zksync_api_types::v02::pagination::PaginationDirection;
+    use zksync_types::BlockNumber;
+
+    #[test]
+    fn limit_bounds() {
+        let code = |limit| {
+            let query = PaginationQuery {
+                from: String::from("latest"),
+                limit,
+                direction: PaginationDirection::Older,
+            };
+            parse_query::<BlockNumber>(query, 100)
+                .err()
+                .map(|err| err.code)
+        };
+
+        assert_eq!(code(1), None);
+        assert_eq!(code(100), None);
+        assert_eq!(code(101), Some(ErrorCode::PaginationLimitTooBig));
+        assert_eq!(code(0), Some(ErrorCode::PaginationLimitTooBig));
+        assert_eq!(code(u32::MAX), Some(ErrorCode::PaginationLimitTooBig));
+    }
+
+    /// The limit is checked after the deserialization, so it can't be smuggled past the check
+    /// by the way it's encoded in the query string.
+    #[test]
+    fn encoded_limit_is_checked() {
+        let web::Query(query) = web::Query::<PaginationQuery<String>>::from_query(
+            "from=latest&limit=%2B1%301&direction=older",
+        )
+        .unwrap();
+        assert_eq!(query.limit, 101);
+        let err = parse_query::<BlockNumber>(query, 100).unwrap_err();
+        assert_eq!(err.code, ErrorCode::PaginationLimitTooBig);
+    }
+}
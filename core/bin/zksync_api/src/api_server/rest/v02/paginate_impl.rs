@@ -1,78 +1,326 @@
 // Built-in uses
 
 // External uses
+use serde::Serialize;
 
 // Workspace uses
 use zksync_api_types::{
     v02::{
+        account::AccountActivity,
         block::BlockInfo,
         pagination::{
-            AccountTxsRequest, ApiEither, BlockAndTxHash, Paginated, PaginationQuery,
-            PendingOpsRequest,
+            AccountActivityRequest, AccountNFTsRequest, AccountQueuedTxsRequest, AccountTxsRequest,
+            ActivityPosition, ApiEither, BlockAndTxHash, BlockAndTxIndex, BlockTimestamp,
+            Paginated, PaginationDirection, PaginationQuery, PendingOpsRequest, PriorityOpsRequest,
+            QueuedTxPosition,
         },
-        transaction::{Transaction, TxHashSerializeWrapper},
+        token::NFT,
+        transaction::{PriorityOpInfo, Transaction, TxHashSerializeWrapper},
     },
     Either,
 };
-use zksync_storage::StorageProcessor;
+use zksync_storage::{
+    chain::operations::records::StoredExecutedPriorityOperation, ConnectionPool, StorageProcessor,
+};
 use zksync_types::{BlockNumber, SerialId, Token, TokenId};
 
 // Local uses
 use super::{
     block::block_info_from_details,
-    error::{Error, InvalidDataError},
-    paginate_trait::Paginate,
+    error::{Error, InvalidDataError, TxError},
+    paginate_trait::{Paginate, PaginateCount},
+    priority_ops::priority_op_info_from_stored,
 };
 
 use zksync_api_types::v02::transaction::{L1Transaction, TransactionData, TxInBlockStatus};
 
+/// Number of the items loaded in the opposite direction to find the item preceding the cursor,
+/// the first one is the cursor itself if it exists.
+const PREVIOUS_LOOKUP_LIMIT: u32 = 2;
+
+/// Pages are loaded with one extra item, which is the cursor of the next page.
+fn limit_with_next(limit: u32) -> u32 {
+    limit.saturating_add(1)
+}
+
+/// Splits the page loaded with [`limit_with_next`] into the page itself and the cursor of the next one.
+fn split_next<T, F>(
+    mut items: Vec<T>,
+    limit: u32,
+    cursor: impl Fn(&T) -> Option<F>,
+) -> (Vec<T>, Option<F>) {
+    let next = if limit > 0 && items.len() > limit as usize {
+        items.truncate(limit as usize + 1);
+        items.pop().and_then(|item| cursor(&item))
+    } else {
+        None
+    };
+    (items, next)
+}
+
+/// Returns the cursor of the item preceding `from`, given the items loaded from `from`
+/// in the opposite direction.
+fn previous_cursor<T, F: PartialEq>(
+    items: &[T],
+    from: &F,
+    cursor: impl Fn(&T) -> Option<F>,
+) -> Option<F> {
+    items
+        .iter()
+        .filter_map(cursor)
+        .find(|previous| previous != from)
+}
+
+fn queued_tx_position((position, _): &(QueuedTxPosition, Transaction)) -> Option<QueuedTxPosition> {
+    Some(*position)
+}
+
+/// Pagination request whose page and total count are loaded by independent queries.
+///
+/// Both queries are run within a single storage transaction, so the count matches the page.
 #[async_trait::async_trait]
-impl Paginate<ApiEither<TokenId>> for StorageProcessor<'_> {
-    type OutputObj = Token;
-    type OutputId = TokenId;
+trait CountedPaginate: Serialize + Send + Sync + Sized + 'static {
+    type OutputObj: Serialize + Send;
+    type OutputId: Serialize + Send + Default;
+
+    /// Resolves the cursor of the query, failing in the same way the page query would fail
+    /// for a bogus cursor. `None` means there are no items to start the page from.
+    async fn load_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<Option<Self::OutputId>, Error>;
+
+    /// Loads the page from the resolved cursor with its next and previous cursors,
+    /// the total count of the returned page is left zero.
+    async fn load_page_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+        from: Self::OutputId,
+    ) -> Result<Paginated<Self::OutputObj, Self::OutputId>, Error>;
+
+    /// Loads the total count of the items the request pages through.
+    async fn load_count(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<u32, Error>;
+}
+
+/// Loads the page of the request, the total count of the returned page is left zero.
+async fn load_page<I: CountedPaginate>(
+    storage: &mut StorageProcessor<'_>,
+    query: &PaginationQuery<I>,
+) -> Result<Paginated<I::OutputObj, I::OutputId>, Error> {
+    match I::load_from(storage, query).await? {
+        Some(from) => I::load_page_from(storage, query, from).await,
+        None => Ok(Paginated::new(
+            Vec::new(),
+            Default::default(),
+            query.limit,
+            query.direction,
+            0,
+        )),
+    }
+}
+
+async fn paginate_sequentially<I: CountedPaginate>(
+    storage: &mut StorageProcessor<'_>,
+    query: &PaginationQuery<I>,
+) -> Result<Paginated<I::OutputObj, I::OutputId>, Error> {
+    let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+    let mut page = load_page(&mut transaction, query).await?;
+    page.pagination.count = I::load_count(&mut transaction, query).await?;
+    transaction.commit().await.map_err(Error::storage)?;
+
+    Ok(page)
+}
+
+#[async_trait::async_trait]
+impl<I: CountedPaginate> Paginate<I> for ConnectionPool {
+    type OutputObj = I::OutputObj;
+    type OutputId = I::OutputId;
 
     async fn paginate(
         &mut self,
-        query: &PaginationQuery<ApiEither<TokenId>>,
-    ) -> Result<Paginated<Token, TokenId>, Error> {
-        let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
+        query: &PaginationQuery<I>,
+    ) -> Result<Paginated<I::OutputObj, I::OutputId>, Error> {
+        let mut storage = self.access_storage().await.map_err(Error::storage)?;
+        paginate_sequentially(&mut storage, query).await
+    }
+}
 
+#[async_trait::async_trait]
+impl<I: CountedPaginate> PaginateCount<I> for ConnectionPool {
+    async fn paginate_count(
+        &mut self,
+        query: &PaginationQuery<I>,
+    ) -> Result<Paginated<I::OutputObj, I::OutputId>, Error> {
+        let mut storage = self.access_storage().await.map_err(Error::storage)?;
+        let mut transaction = storage.start_transaction().await.map_err(Error::storage)?;
+        // The cursor is still resolved, so that the bogus one is reported as with the page.
+        let from = I::load_from(&mut transaction, query).await?;
+        let count = I::load_count(&mut transaction, query).await?;
+        transaction.commit().await.map_err(Error::storage)?;
+
+        Ok(Paginated::new(
+            Vec::new(),
+            from.unwrap_or_default(),
+            query.limit,
+            query.direction,
+            count,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl CountedPaginate for ApiEither<TokenId> {
+    type OutputObj = Token;
+    type OutputId = TokenId;
+
+    async fn load_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<Option<TokenId>, Error> {
         let token_id = match query.from.inner {
             Either::Left(token_id) => token_id,
             Either::Right(_) => TokenId(
-                transaction
+                storage
                     .tokens_schema()
                     .get_max_erc20_token_id()
                     .await
                     .map_err(Error::storage)?,
             ),
         };
+        Ok(Some(token_id))
+    }
 
-        let query = PaginationQuery {
-            from: token_id,
-            limit: query.limit,
-            direction: query.direction,
-        };
-
-        let tokens = transaction
+    async fn load_page_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+        token_id: TokenId,
+    ) -> Result<Paginated<Token, TokenId>, Error> {
+        let tokens = storage
             .tokens_schema()
-            .load_token_page(&query)
+            .load_token_page(&PaginationQuery {
+                from: token_id,
+                limit: limit_with_next(query.limit),
+                direction: query.direction,
+            })
             .await
             .map_err(Error::storage)?;
-        let count = transaction
+        let (tokens, next) = split_next(tokens, query.limit, |token| Some(token.id));
+        let preceding = storage
+            .tokens_schema()
+            .load_token_page(&PaginationQuery {
+                from: token_id,
+                limit: PREVIOUS_LOOKUP_LIMIT,
+                direction: query.direction.opposite(),
+            })
+            .await
+            .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &token_id, |token| Some(token.id));
+
+        Ok(
+            Paginated::new(tokens, token_id, query.limit, query.direction, 0)
+                .with_cursors(next, previous),
+        )
+    }
+
+    async fn load_count(
+        storage: &mut StorageProcessor<'_>,
+        _query: &PaginationQuery<Self>,
+    ) -> Result<u32, Error> {
+        storage
             .tokens_schema()
             .get_count()
             .await
+            .map_err(Error::storage)
+    }
+}
+
+#[async_trait::async_trait]
+impl Paginate<ApiEither<TokenId>> for StorageProcessor<'_> {
+    type OutputObj = Token;
+    type OutputId = TokenId;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<ApiEither<TokenId>>,
+    ) -> Result<Paginated<Token, TokenId>, Error> {
+        paginate_sequentially(self, query).await
+    }
+}
+
+#[async_trait::async_trait]
+impl CountedPaginate for ApiEither<BlockNumber> {
+    type OutputObj = BlockInfo;
+    type OutputId = BlockNumber;
+
+    async fn load_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<Option<BlockNumber>, Error> {
+        let block_number = match query.from.inner {
+            Either::Left(block_number) => block_number,
+            Either::Right(_) => storage
+                .chain()
+                .block_schema()
+                .get_last_committed_confirmed_block()
+                .await
+                .map_err(Error::storage)?,
+        };
+        Ok(Some(block_number))
+    }
+
+    async fn load_page_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+        block_number: BlockNumber,
+    ) -> Result<Paginated<BlockInfo, BlockNumber>, Error> {
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_block_page(&PaginationQuery {
+                from: block_number,
+                limit: limit_with_next(query.limit),
+                direction: query.direction,
+            })
+            .await
             .map_err(Error::storage)?;
-        transaction.commit().await.map_err(Error::storage)?;
+        let (blocks, next) = split_next(blocks, query.limit, |block| {
+            Some(BlockNumber(block.block_number as u32))
+        });
+        let blocks: Vec<BlockInfo> = blocks.into_iter().map(block_info_from_details).collect();
+        let preceding = storage
+            .chain()
+            .block_schema()
+            .load_block_page(&PaginationQuery {
+                from: block_number,
+                limit: PREVIOUS_LOOKUP_LIMIT,
+                direction: query.direction.opposite(),
+            })
+            .await
+            .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &block_number, |block| {
+            Some(BlockNumber(block.block_number as u32))
+        });
 
-        Ok(Paginated::new(
-            tokens,
-            query.from,
-            query.limit,
-            query.direction,
-            count,
-        ))
+        Ok(
+            Paginated::new(blocks, block_number, query.limit, query.direction, 0)
+                .with_cursors(next, previous),
+        )
+    }
+
+    async fn load_count(
+        storage: &mut StorageProcessor<'_>,
+        _query: &PaginationQuery<Self>,
+    ) -> Result<u32, Error> {
+        let last_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
+        Ok(*last_block)
     }
 }
 
@@ -85,68 +333,550 @@ impl Paginate<ApiEither<BlockNumber>> for StorageProcessor<'_> {
         &mut self,
         query: &PaginationQuery<ApiEither<BlockNumber>>,
     ) -> Result<Paginated<BlockInfo, BlockNumber>, Error> {
-        let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
+        paginate_sequentially(self, query).await
+    }
+}
 
-        let last_block = transaction
+#[async_trait::async_trait]
+impl CountedPaginate for ApiEither<BlockTimestamp> {
+    type OutputObj = BlockInfo;
+    type OutputId = BlockTimestamp;
+
+    /// Resolves the cursor to the exact one of the first block of the page,
+    /// `latest` points to the last block in any direction.
+    async fn load_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<Option<BlockTimestamp>, Error> {
+        let (from, direction) = match query.from.inner {
+            Either::Left(from) => (from, query.direction),
+            Either::Right(_) => (
+                BlockTimestamp {
+                    timestamp: u64::MAX,
+                    block_number: None,
+                },
+                PaginationDirection::Older,
+            ),
+        };
+        let first = storage
             .chain()
             .block_schema()
-            .get_last_committed_confirmed_block()
+            .load_block_page_by_timestamp(&PaginationQuery {
+                from,
+                limit: 1,
+                direction,
+            })
             .await
             .map_err(Error::storage)?;
+        Ok(first.into_iter().next().map(|(_, cursor)| cursor))
+    }
 
-        let block_number = match query.from.inner {
-            Either::Left(block_number) => block_number,
-            Either::Right(_) => last_block,
-        };
+    async fn load_page_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+        from: BlockTimestamp,
+    ) -> Result<Paginated<BlockInfo, BlockTimestamp>, Error> {
+        let blocks = storage
+            .chain()
+            .block_schema()
+            .load_block_page_by_timestamp(&PaginationQuery {
+                from,
+                limit: limit_with_next(query.limit),
+                direction: query.direction,
+            })
+            .await
+            .map_err(Error::storage)?;
+        let (blocks, next) = split_next(blocks, query.limit, |(_, cursor)| Some(*cursor));
+        let blocks: Vec<BlockInfo> = blocks
+            .into_iter()
+            .map(|(details, _)| block_info_from_details(details))
+            .collect();
+        let preceding = storage
+            .chain()
+            .block_schema()
+            .load_block_page_by_timestamp(&PaginationQuery {
+                from,
+                limit: PREVIOUS_LOOKUP_LIMIT,
+                direction: query.direction.opposite(),
+            })
+            .await
+            .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &from, |(_, cursor)| Some(*cursor));
 
-        let query = PaginationQuery {
-            from: block_number,
-            limit: query.limit,
+        Ok(
+            Paginated::new(blocks, from, query.limit, query.direction, 0)
+                .with_cursors(next, previous),
+        )
+    }
+
+    /// Only the blocks with the timestamp are counted, as the other ones are never on the pages.
+    async fn load_count(
+        storage: &mut StorageProcessor<'_>,
+        _query: &PaginationQuery<Self>,
+    ) -> Result<u32, Error> {
+        storage
+            .chain()
+            .block_schema()
+            .count_timestamped_blocks()
+            .await
+            .map_err(Error::storage)
+    }
+}
+
+#[async_trait::async_trait]
+impl Paginate<ApiEither<BlockTimestamp>> for StorageProcessor<'_> {
+    type OutputObj = BlockInfo;
+    type OutputId = BlockTimestamp;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<ApiEither<BlockTimestamp>>,
+    ) -> Result<Paginated<BlockInfo, BlockTimestamp>, Error> {
+        paginate_sequentially(self, query).await
+    }
+}
+
+/// Returns the first transaction of the block following the requested one in the query direction,
+/// `None` if there is no such block or it has no transactions of the requested type.
+async fn following_block_first_tx(
+    storage: &mut StorageProcessor<'_>,
+    query: &PaginationQuery<BlockAndTxHash>,
+) -> Result<Option<TxHashSerializeWrapper>, Error> {
+    let block_number = match query.direction {
+        PaginationDirection::Newer => query.from.block_number + 1,
+        PaginationDirection::Older if *query.from.block_number > 1 => query.from.block_number - 1,
+        PaginationDirection::Older => return Ok(None),
+    };
+    let mut operations = storage.chain().operations_ext_schema();
+    let edge_tx_hash = match query.direction {
+        PaginationDirection::Newer => operations.get_block_first_tx_hash(block_number).await,
+        PaginationDirection::Older => operations.get_block_last_tx_hash(block_number).await,
+    }
+    .map_err(Error::storage)?;
+    let edge_tx_hash = match edge_tx_hash {
+        Some(tx_hash) => tx_hash,
+        None => return Ok(None),
+    };
+
+    // The edge transaction may be of the other type, so the first one of the requested type is looked up.
+    let txs = storage
+        .chain()
+        .block_schema()
+        .get_block_transactions_page(&PaginationQuery {
+            from: BlockAndTxHash {
+                block_number,
+                tx_hash: ApiEither::from(edge_tx_hash),
+                tx_type: query.from.tx_type,
+            },
+            limit: 1,
             direction: query.direction,
+        })
+        .await
+        .map_err(Error::storage)?
+        .unwrap_or_default();
+    Ok(txs.first().map(|tx| TxHashSerializeWrapper(tx.tx_hash)))
+}
+
+#[async_trait::async_trait]
+impl CountedPaginate for BlockAndTxHash {
+    type OutputObj = Transaction;
+    type OutputId = TxHashSerializeWrapper;
+
+    async fn load_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<Option<TxHashSerializeWrapper>, Error> {
+        let tx_hash = match query.from.tx_hash.inner {
+            Either::Left(tx_hash) => {
+                // The page query fails for the transactions of the other blocks as well.
+                let sequence_number = storage
+                    .chain()
+                    .operations_ext_schema()
+                    .get_tx_sequence_number_for_block(tx_hash, query.from.block_number)
+                    .await
+                    .map_err(Error::storage)?;
+                if sequence_number.is_none() {
+                    return Err(InvalidDataError::TransactionNotFound.into());
+                }
+                Some(tx_hash)
+            }
+            Either::Right(_) => storage
+                .chain()
+                .operations_ext_schema()
+                .get_block_last_tx_hash(query.from.block_number)
+                .await
+                .map_err(Error::storage)?,
+        };
+        Ok(tx_hash.map(TxHashSerializeWrapper))
+    }
+
+    async fn load_page_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+        from: TxHashSerializeWrapper,
+    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        let page_query = |limit, direction| PaginationQuery {
+            from: BlockAndTxHash {
+                block_number: query.from.block_number,
+                tx_hash: ApiEither::from(from.0),
+                tx_type: query.from.tx_type,
+            },
+            limit,
+            direction,
+        };
+
+        let txs = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_page(&page_query(limit_with_next(query.limit), query.direction))
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(InvalidDataError::TransactionNotFound))?;
+        let tx_cursor = |tx: &Transaction| Some(TxHashSerializeWrapper(tx.tx_hash));
+        let (txs, next) = split_next(txs, query.limit, tx_cursor);
+        // The page reaching the end of the block is continued by the following block.
+        let next = match next {
+            Some(next) => Some(next),
+            None => following_block_first_tx(storage, query).await?,
+        };
+        let preceding = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_page(&page_query(
+                PREVIOUS_LOOKUP_LIMIT,
+                query.direction.opposite(),
+            ))
+            .await
+            .map_err(Error::storage)?
+            .unwrap_or_default();
+        let previous = previous_cursor(&preceding, &from, tx_cursor);
+
+        Ok(Paginated::new(txs, from, query.limit, query.direction, 0).with_cursors(next, previous))
+    }
+
+    async fn load_count(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<u32, Error> {
+        if let Some(tx_type) = query.from.tx_type {
+            storage
+                .chain()
+                .block_schema()
+                .get_block_transactions_count_by_type(query.from.block_number, tx_type)
+                .await
+        } else {
+            storage
+                .chain()
+                .block_schema()
+                .get_block_transactions_count(query.from.block_number)
+                .await
+        }
+        .map_err(Error::storage)
+    }
+}
+
+#[async_trait::async_trait]
+impl Paginate<BlockAndTxHash> for StorageProcessor<'_> {
+    type OutputObj = Transaction;
+    type OutputId = TxHashSerializeWrapper;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<BlockAndTxHash>,
+    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        paginate_sequentially(self, query).await
+    }
+}
+
+#[async_trait::async_trait]
+impl CountedPaginate for BlockAndTxIndex {
+    type OutputObj = Transaction;
+    type OutputId = u32;
+
+    async fn load_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<Option<u32>, Error> {
+        match query.from.block_index.inner {
+            Either::Left(block_index) => Ok(Some(block_index)),
+            Either::Right(_) => storage
+                .chain()
+                .operations_ext_schema()
+                .get_block_last_tx_index(query.from.block_number)
+                .await
+                .map_err(Error::storage),
+        }
+    }
+
+    async fn load_page_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+        block_index: u32,
+    ) -> Result<Paginated<Transaction, u32>, Error> {
+        let page_query = |limit, direction| PaginationQuery {
+            from: BlockAndTxIndex {
+                block_number: query.from.block_number,
+                block_index: ApiEither::from(block_index),
+            },
+            limit,
+            direction,
         };
 
-        let blocks = transaction
+        let txs = storage
             .chain()
             .block_schema()
-            .load_block_page(&query)
+            .get_block_transactions_page_by_index(&page_query(
+                limit_with_next(query.limit),
+                query.direction,
+            ))
             .await
             .map_err(Error::storage)?;
-        let blocks: Vec<BlockInfo> = blocks.into_iter().map(block_info_from_details).collect();
+        let tx_cursor = |tx: &Transaction| tx.block_index;
+        let (txs, next) = split_next(txs, query.limit, tx_cursor);
+        let preceding = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_page_by_index(&page_query(
+                PREVIOUS_LOOKUP_LIMIT,
+                query.direction.opposite(),
+            ))
+            .await
+            .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &block_index, tx_cursor);
 
-        transaction.commit().await.map_err(Error::storage)?;
+        Ok(
+            Paginated::new(txs, block_index, query.limit, query.direction, 0)
+                .with_cursors(next, previous),
+        )
+    }
 
-        Ok(Paginated::new(
-            blocks,
-            query.from,
-            query.limit,
-            query.direction,
-            *last_block,
-        ))
+    async fn load_count(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<u32, Error> {
+        storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_count(query.from.block_number)
+            .await
+            .map_err(Error::storage)
+    }
+}
+
+#[async_trait::async_trait]
+impl Paginate<BlockAndTxIndex> for StorageProcessor<'_> {
+    type OutputObj = Transaction;
+    type OutputId = u32;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<BlockAndTxIndex>,
+    ) -> Result<Paginated<Transaction, u32>, Error> {
+        paginate_sequentially(self, query).await
+    }
+}
+
+#[async_trait::async_trait]
+impl CountedPaginate for AccountTxsRequest {
+    type OutputObj = Transaction;
+    type OutputId = TxHashSerializeWrapper;
+
+    async fn load_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<Option<TxHashSerializeWrapper>, Error> {
+        let tx_hash = match query.from.tx_hash.inner {
+            Either::Left(tx_hash) => {
+                // Otherwise the page would silently start from a transaction of another account.
+                let is_account_tx = storage
+                    .chain()
+                    .operations_ext_schema()
+                    .is_account_tx(query.from.address, tx_hash)
+                    .await
+                    .map_err(Error::storage)?;
+                if !is_account_tx {
+                    return Err(TxError::TransactionNotFound.into());
+                }
+                Some(tx_hash)
+            }
+            Either::Right(_) => storage
+                .chain()
+                .operations_ext_schema()
+                .get_account_last_tx_hash(query.from.address)
+                .await
+                .map_err(Error::storage)?,
+        };
+        Ok(tx_hash.map(TxHashSerializeWrapper))
+    }
+
+    async fn load_page_from(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+        from: TxHashSerializeWrapper,
+    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        let page_query = |limit, direction| PaginationQuery {
+            from: AccountTxsRequest {
+                tx_hash: ApiEither::from(from.0),
+                ..query.from
+            },
+            limit,
+            direction,
+        };
+
+        // Pages span over the blocks, so the next page may start in the following block.
+        let txs = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions(&page_query(limit_with_next(query.limit), query.direction))
+            .await
+            .map_err(Error::storage)?
+            .ok_or_else(|| Error::from(InvalidDataError::TransactionNotFound))?;
+        let tx_cursor = |tx: &Transaction| Some(TxHashSerializeWrapper(tx.tx_hash));
+        let (txs, next) = split_next(txs, query.limit, tx_cursor);
+        let preceding = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions(&page_query(
+                PREVIOUS_LOOKUP_LIMIT,
+                query.direction.opposite(),
+            ))
+            .await
+            .map_err(Error::storage)?
+            .unwrap_or_default();
+        let previous = previous_cursor(&preceding, &from, tx_cursor);
+
+        Ok(Paginated::new(txs, from, query.limit, query.direction, 0).with_cursors(next, previous))
+    }
+
+    async fn load_count(
+        storage: &mut StorageProcessor<'_>,
+        query: &PaginationQuery<Self>,
+    ) -> Result<u32, Error> {
+        storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_transactions_count(
+                query.from.address,
+                query.from.token,
+                query.from.include_fee_token,
+                query.from.second_address,
+            )
+            .await
+            .map_err(Error::storage)
+    }
+}
+
+#[async_trait::async_trait]
+impl Paginate<AccountTxsRequest> for StorageProcessor<'_> {
+    type OutputObj = Transaction;
+    type OutputId = TxHashSerializeWrapper;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<AccountTxsRequest>,
+    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        paginate_sequentially(self, query).await
+    }
+}
+
+#[async_trait::async_trait]
+impl Paginate<AccountActivityRequest> for StorageProcessor<'_> {
+    type OutputObj = AccountActivity;
+    type OutputId = ActivityPosition;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<AccountActivityRequest>,
+    ) -> Result<Paginated<AccountActivity, ActivityPosition>, Error> {
+        let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
+
+        let position = match query.from.position.inner {
+            Either::Left(position) => position,
+            Either::Right(_) => {
+                if let Some(position) = transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_account_last_activity_position(query.from.address)
+                    .await
+                    .map_err(Error::storage)?
+                {
+                    position
+                } else {
+                    return Ok(Paginated::new(
+                        Vec::new(),
+                        Default::default(),
+                        query.limit,
+                        query.direction,
+                        0,
+                    ));
+                }
+            }
+        };
+
+        let page_query = |limit, direction| PaginationQuery {
+            from: AccountActivityRequest {
+                address: query.from.address,
+                position: ApiEither::from(position),
+            },
+            limit,
+            direction,
+        };
+
+        let activity = transaction
+            .chain()
+            .operations_ext_schema()
+            .get_account_activity(&page_query(limit_with_next(query.limit), query.direction))
+            .await
+            .map_err(Error::storage)?;
+        let (activity, next) = split_next(activity, query.limit, AccountActivity::position);
+        let preceding = transaction
+            .chain()
+            .operations_ext_schema()
+            .get_account_activity(&page_query(
+                PREVIOUS_LOOKUP_LIMIT,
+                query.direction.opposite(),
+            ))
+            .await
+            .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &position, AccountActivity::position);
+        let count = transaction
+            .chain()
+            .operations_ext_schema()
+            .get_account_activity_count(query.from.address)
+            .await
+            .map_err(Error::storage)?;
+
+        transaction.commit().await.map_err(Error::storage)?;
+
+        Ok(
+            Paginated::new(activity, position, query.limit, query.direction, count)
+                .with_cursors(next, previous),
+        )
     }
 }
 
 #[async_trait::async_trait]
-impl Paginate<BlockAndTxHash> for StorageProcessor<'_> {
+impl Paginate<AccountQueuedTxsRequest> for StorageProcessor<'_> {
     type OutputObj = Transaction;
-    type OutputId = TxHashSerializeWrapper;
+    type OutputId = QueuedTxPosition;
 
     async fn paginate(
         &mut self,
-        query: &PaginationQuery<BlockAndTxHash>,
-    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        query: &PaginationQuery<AccountQueuedTxsRequest>,
+    ) -> Result<Paginated<Transaction, QueuedTxPosition>, Error> {
         let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
 
-        let tx_hash = match query.from.tx_hash.inner {
-            Either::Left(tx_hash) => tx_hash,
+        let position = match query.from.position.inner {
+            Either::Left(position) => position,
             Either::Right(_) => {
-                if let Some(tx_hash) = transaction
+                if let Some(position) = transaction
                     .chain()
-                    .operations_ext_schema()
-                    .get_block_last_tx_hash(query.from.block_number)
+                    .mempool_schema()
+                    .get_account_last_queued_tx_position(query.from.address)
                     .await
                     .map_err(Error::storage)?
                 {
-                    tx_hash
+                    position
                 } else {
                     return Ok(Paginated::new(
                         Vec::new(),
@@ -159,63 +889,71 @@ impl Paginate<BlockAndTxHash> for StorageProcessor<'_> {
             }
         };
 
-        let query = PaginationQuery {
-            from: BlockAndTxHash {
-                block_number: query.from.block_number,
-                tx_hash: ApiEither::from(tx_hash),
-            },
-            limit: query.limit,
-            direction: query.direction,
-        };
-
         let txs = transaction
             .chain()
-            .block_schema()
-            .get_block_transactions_page(&query)
+            .mempool_schema()
+            .get_account_queued_txs(
+                query.from.address,
+                position,
+                limit_with_next(query.limit),
+                query.direction,
+            )
             .await
-            .map_err(Error::storage)?
-            .ok_or_else(|| Error::from(InvalidDataError::TransactionNotFound))?;
+            .map_err(Error::storage)?;
+        let (txs, next) = split_next(txs, query.limit, queued_tx_position);
+        let preceding = transaction
+            .chain()
+            .mempool_schema()
+            .get_account_queued_txs(
+                query.from.address,
+                position,
+                PREVIOUS_LOOKUP_LIMIT,
+                query.direction.opposite(),
+            )
+            .await
+            .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &position, queued_tx_position);
         let count = transaction
             .chain()
-            .block_schema()
-            .get_block_transactions_count(query.from.block_number)
+            .mempool_schema()
+            .get_account_queued_txs_count(query.from.address)
             .await
             .map_err(Error::storage)?;
 
         transaction.commit().await.map_err(Error::storage)?;
 
-        Ok(Paginated::new(
-            txs,
-            TxHashSerializeWrapper(tx_hash),
-            query.limit,
-            query.direction,
-            count,
-        ))
+        let txs: Vec<Transaction> = txs.into_iter().map(|(_, tx)| tx).collect();
+
+        Ok(
+            Paginated::new(txs, position, query.limit, query.direction, count)
+                .with_cursors(next, previous),
+        )
     }
 }
 
 #[async_trait::async_trait]
-impl Paginate<AccountTxsRequest> for StorageProcessor<'_> {
-    type OutputObj = Transaction;
-    type OutputId = TxHashSerializeWrapper;
+impl Paginate<AccountNFTsRequest> for StorageProcessor<'_> {
+    type OutputObj = NFT;
+    type OutputId = TokenId;
 
     async fn paginate(
         &mut self,
-        query: &PaginationQuery<AccountTxsRequest>,
-    ) -> Result<Paginated<Transaction, TxHashSerializeWrapper>, Error> {
+        query: &PaginationQuery<AccountNFTsRequest>,
+    ) -> Result<Paginated<NFT, TokenId>, Error> {
         let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
 
-        let tx_hash = match query.from.tx_hash.inner {
-            Either::Left(tx_hash) => tx_hash,
+        let account_id = query.from.account_id;
+        let (count, last_token_id) = transaction
+            .chain()
+            .account_schema()
+            .get_account_nfts_summary(account_id)
+            .await
+            .map_err(Error::storage)?;
+        let token_id = match query.from.token_id.inner {
+            Either::Left(token_id) => token_id,
             Either::Right(_) => {
-                if let Some(tx_hash) = transaction
-                    .chain()
-                    .operations_ext_schema()
-                    .get_account_last_tx_hash(query.from.address)
-                    .await
-                    .map_err(Error::storage)?
-                {
-                    tx_hash
+                if let Some(last_token_id) = last_token_id {
+                    last_token_id
                 } else {
                     return Ok(Paginated::new(
                         Vec::new(),
@@ -228,42 +966,44 @@ impl Paginate<AccountTxsRequest> for StorageProcessor<'_> {
             }
         };
 
-        let query = PaginationQuery {
-            from: AccountTxsRequest {
-                tx_hash: ApiEither::from(tx_hash),
-                ..query.from
-            },
-            limit: query.limit,
-            direction: query.direction,
-        };
-
-        let txs = transaction
+        let nfts = transaction
             .chain()
-            .operations_ext_schema()
-            .get_account_transactions(&query)
+            .account_schema()
+            .load_account_nft_page(
+                account_id,
+                &PaginationQuery {
+                    from: token_id,
+                    limit: limit_with_next(query.limit),
+                    direction: query.direction,
+                },
+            )
             .await
-            .map_err(Error::storage)?
-            .ok_or_else(|| Error::from(InvalidDataError::TransactionNotFound))?;
-        let count = transaction
+            .map_err(Error::storage)?;
+        let (nfts, next) = split_next(nfts, query.limit, |nft| Some(nft.id));
+        let preceding = transaction
             .chain()
-            .operations_ext_schema()
-            .get_account_transactions_count(
-                query.from.address,
-                query.from.token,
-                query.from.second_address,
+            .account_schema()
+            .load_account_nft_page(
+                account_id,
+                &PaginationQuery {
+                    from: token_id,
+                    limit: PREVIOUS_LOOKUP_LIMIT,
+                    direction: query.direction.opposite(),
+                },
             )
             .await
             .map_err(Error::storage)?;
-
+        let previous = previous_cursor(&preceding, &token_id, |nft| Some(nft.id));
         transaction.commit().await.map_err(Error::storage)?;
 
         Ok(Paginated::new(
-            txs,
-            TxHashSerializeWrapper(tx_hash),
+            nfts.into_iter().map(NFT::from).collect(),
+            token_id,
             query.limit,
             query.direction,
             count,
-        ))
+        )
+        .with_cursors(next, previous))
     }
 }
 
@@ -301,9 +1041,27 @@ impl Paginate<PendingOpsRequest> for StorageProcessor<'_> {
         let result = self
             .chain()
             .mempool_schema()
-            .get_pending_deposits_for(query.from.address, serial_id, query.limit, query.direction)
+            .get_pending_deposits_for(
+                query.from.address,
+                serial_id,
+                limit_with_next(query.limit),
+                query.direction,
+            )
+            .await
+            .map_err(Error::storage)?;
+        let (result, next) = split_next(result, query.limit, |op| Some(op.serial_id));
+        let preceding = self
+            .chain()
+            .mempool_schema()
+            .get_pending_deposits_for(
+                query.from.address,
+                serial_id,
+                PREVIOUS_LOOKUP_LIMIT,
+                query.direction.opposite(),
+            )
             .await
             .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &serial_id, |op| Some(op.serial_id));
 
         let count = result.len() as u32;
         let txs = result
@@ -323,18 +1081,783 @@ impl Paginate<PendingOpsRequest> for StorageProcessor<'_> {
                     op: TransactionData::L1(tx),
                     status: TxInBlockStatus::Queued,
                     fail_reason: None,
+                    fail_reason_code: None,
                     created_at: None,
                     batch_id: None,
                 }
             })
             .collect();
 
-        Ok(Paginated::new(
-            txs,
-            serial_id,
-            query.limit,
-            query.direction,
-            count,
-        ))
+        Ok(
+            Paginated::new(txs, serial_id, query.limit, query.direction, count)
+                .with_cursors(next, previous),
+        )
+    }
+}
+
+#[async_trait::async_trait]
+impl Paginate<PriorityOpsRequest> for StorageProcessor<'_> {
+    type OutputObj = PriorityOpInfo;
+    type OutputId = SerialId;
+
+    async fn paginate(
+        &mut self,
+        query: &PaginationQuery<PriorityOpsRequest>,
+    ) -> Result<Paginated<PriorityOpInfo, SerialId>, Error> {
+        let mut transaction = self.start_transaction().await.map_err(Error::storage)?;
+
+        let serial_id = match query.from.serial_id.inner {
+            Either::Left(serial_id) => serial_id,
+            Either::Right(_) => {
+                if let Some(serial_id) = transaction
+                    .chain()
+                    .operations_schema()
+                    .get_max_priority_op_serial_id()
+                    .await
+                    .map_err(Error::storage)?
+                {
+                    serial_id
+                } else {
+                    return Ok(Paginated::new(
+                        Vec::new(),
+                        Default::default(),
+                        query.limit,
+                        query.direction,
+                        0,
+                    ));
+                }
+            }
+        };
+
+        let ops = transaction
+            .chain()
+            .operations_schema()
+            .load_executed_priority_ops_page(&PaginationQuery {
+                from: serial_id,
+                limit: limit_with_next(query.limit),
+                direction: query.direction,
+            })
+            .await
+            .map_err(Error::storage)?;
+        let op_cursor =
+            |op: &StoredExecutedPriorityOperation| Some(op.priority_op_serialid as SerialId);
+        let (ops, next) = split_next(ops, query.limit, op_cursor);
+        let preceding = transaction
+            .chain()
+            .operations_schema()
+            .load_executed_priority_ops_page(&PaginationQuery {
+                from: serial_id,
+                limit: PREVIOUS_LOOKUP_LIMIT,
+                direction: query.direction.opposite(),
+            })
+            .await
+            .map_err(Error::storage)?;
+        let previous = previous_cursor(&preceding, &serial_id, op_cursor);
+        let count = transaction
+            .chain()
+            .operations_schema()
+            .get_executed_priority_ops_count()
+            .await
+            .map_err(Error::storage)?;
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await
+            .map_err(Error::storage)?;
+
+        transaction.commit().await.map_err(Error::storage)?;
+
+        let ops = ops
+            .into_iter()
+            .map(|op| priority_op_info_from_stored(op, last_finalized))
+            .collect();
+        Ok(
+            Paginated::new(ops, serial_id, query.limit, query.direction, count)
+                .with_cursors(next, previous),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::{
+        dummy_deposit_op,
+        pagination::{commit_test_block, cursor, Page, PaginationCase, PaginationHarness},
+        TestServerConfig,
+    };
+    use zksync_api_types::v02::{error::ErrorCode, pagination::PaginationDirection};
+    use zksync_crypto::rand::Rng;
+    use zksync_storage::test_data::TestRng;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        tx::TxHash, AccountId, Address, ExecutedOperations, ExecutedPriorityOp, TokenKind,
+    };
+
+    fn op_hash(op: &ExecutedOperations) -> TxHash {
+        match op {
+            ExecutedOperations::Tx(tx) => tx.signed_tx.hash(),
+            ExecutedOperations::PriorityOp(op) => op.priority_op.tx_hash(),
+        }
+    }
+
+    /// Deposit to the address, executed at the end of the block.
+    fn deposit(address: Address, serial_id: SerialId) -> ExecutedPriorityOp {
+        let mut op = dummy_deposit_op(address, AccountId(0xbeef), serial_id, 100);
+        // Priority operations are created on L1 long before they are executed,
+        // so their creation time doesn't follow the execution order.
+        op.created_at = chrono::Utc::now() - chrono::Duration::hours(1);
+        op
+    }
+
+    /// Generates the transactions of a new account and a deposit to it.
+    fn account_operations(serial_id: SerialId) -> (Address, Vec<ExecutedOperations>) {
+        let txs = TestServerConfig::gen_zk_txs(1_000);
+        let address = txs.acc.address;
+        let mut ops: Vec<_> = txs.txs.into_iter().map(|(_tx, op)| op).collect();
+        ops.push(ExecutedOperations::PriorityOp(Box::new(deposit(
+            address, serial_id,
+        ))));
+        (address, ops)
+    }
+
+    async fn insert_deposit(
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+        address: Address,
+        serial_id: SerialId,
+    ) -> anyhow::Result<TxHash> {
+        let op = ExecutedOperations::PriorityOp(Box::new(deposit(address, serial_id)));
+        let tx_hash = op_hash(&op);
+        storage
+            .chain()
+            .block_schema()
+            .save_block_transactions(block_number, vec![op])
+            .await?;
+        Ok(tx_hash)
+    }
+
+    struct TokensCase;
+
+    #[async_trait::async_trait]
+    impl PaginationCase for TokensCase {
+        type Key = TokenId;
+
+        async fn load_page(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+            from: Option<TokenId>,
+            limit: u32,
+            direction: PaginationDirection,
+        ) -> anyhow::Result<Page<TokenId>> {
+            let query = PaginationQuery {
+                from: cursor(from),
+                limit,
+                direction,
+            };
+            let page = storage
+                .paginate(&query)
+                .await
+                .map_err(|err| anyhow::anyhow!(err.message))?;
+            Ok(Page::new(page, |token| token.id, |token_id| token_id))
+        }
+
+        async fn insert_newer(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+        ) -> anyhow::Result<TokenId> {
+            let token_id = TokenId(storage.tokens_schema().get_max_erc20_token_id().await? + 1);
+            storage
+                .tokens_schema()
+                .store_or_update_token(Token::new(
+                    token_id,
+                    Address::random(),
+                    &format!("TST{}", *token_id),
+                    18,
+                    TokenKind::ERC20,
+                ))
+                .await?;
+            Ok(token_id)
+        }
+    }
+
+    struct BlocksCase;
+
+    #[async_trait::async_trait]
+    impl PaginationCase for BlocksCase {
+        type Key = BlockNumber;
+
+        async fn load_page(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+            from: Option<BlockNumber>,
+            limit: u32,
+            direction: PaginationDirection,
+        ) -> anyhow::Result<Page<BlockNumber>> {
+            let query = PaginationQuery {
+                from: cursor(from),
+                limit,
+                direction,
+            };
+            let page = storage
+                .paginate(&query)
+                .await
+                .map_err(|err| anyhow::anyhow!(err.message))?;
+            Ok(Page::new(
+                page,
+                |block| block.block_number,
+                |block_number| block_number,
+            ))
+        }
+
+        async fn insert_newer(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+        ) -> anyhow::Result<BlockNumber> {
+            commit_test_block(storage, Vec::new()).await
+        }
+    }
+
+    struct BlockTxsCase {
+        block_number: BlockNumber,
+        next_serial_id: SerialId,
+    }
+
+    #[async_trait::async_trait]
+    impl PaginationCase for BlockTxsCase {
+        type Key = TxHash;
+
+        async fn load_page(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+            from: Option<TxHash>,
+            limit: u32,
+            direction: PaginationDirection,
+        ) -> anyhow::Result<Page<TxHash>> {
+            let query = PaginationQuery {
+                from: BlockAndTxHash {
+                    block_number: self.block_number,
+                    tx_hash: cursor(from),
+                    tx_type: None,
+                },
+                limit,
+                direction,
+            };
+            let page = storage
+                .paginate(&query)
+                .await
+                .map_err(|err| anyhow::anyhow!(err.message))?;
+            let mut page = Page::new(page, |tx| tx.tx_hash, |tx_hash| tx_hash.0);
+            // The last page of the block points to the following block, which is out of the dataset
+            // (see `block_txs_next_cursor_at_block_boundary`).
+            if let Some(next) = page.next {
+                let in_block = storage
+                    .chain()
+                    .operations_ext_schema()
+                    .get_tx_sequence_number_for_block(next, self.block_number)
+                    .await?
+                    .is_some();
+                if !in_block {
+                    page.next = None;
+                }
+            }
+            Ok(page)
+        }
+
+        async fn insert_newer(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+        ) -> anyhow::Result<TxHash> {
+            self.next_serial_id += 1;
+            insert_deposit(
+                storage,
+                self.block_number,
+                Address::random(),
+                self.next_serial_id,
+            )
+            .await
+        }
+    }
+
+    struct AccountTxsCase {
+        address: Address,
+        token: Option<TokenId>,
+        include_fee_token: bool,
+        block_number: BlockNumber,
+        next_serial_id: SerialId,
+    }
+
+    #[async_trait::async_trait]
+    impl PaginationCase for AccountTxsCase {
+        type Key = TxHash;
+
+        async fn load_page(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+            from: Option<TxHash>,
+            limit: u32,
+            direction: PaginationDirection,
+        ) -> anyhow::Result<Page<TxHash>> {
+            let query = PaginationQuery {
+                from: AccountTxsRequest {
+                    address: self.address,
+                    tx_hash: cursor(from),
+                    token: self.token,
+                    include_fee_token: self.include_fee_token,
+                    second_address: None,
+                },
+                limit,
+                direction,
+            };
+            let page = storage
+                .paginate(&query)
+                .await
+                .map_err(|err| anyhow::anyhow!(err.message))?;
+            Ok(Page::new(page, |tx| tx.tx_hash, |tx_hash| tx_hash.0))
+        }
+
+        async fn insert_newer(
+            &mut self,
+            storage: &mut StorageProcessor<'_>,
+        ) -> anyhow::Result<TxHash> {
+            self.next_serial_id += 1;
+            insert_deposit(
+                storage,
+                self.block_number,
+                self.address,
+                self.next_serial_id,
+            )
+            .await
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tokens_pagination() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        // Changes are rolled back once the test is over.
+        let mut transaction = storage.start_transaction().await?;
+
+        let keys = transaction
+            .tokens_schema()
+            .load_tokens_asc(TokenId(0), None)
+            .await?
+            .into_iter()
+            .map(|token| token.id)
+            .collect();
+        PaginationHarness::new(TokensCase, keys)
+            .run(&mut transaction)
+            .await
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocks_pagination() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let last_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_committed_confirmed_block()
+            .await?;
+        let keys = (1..=*last_block).map(BlockNumber).collect();
+        PaginationHarness::new(BlocksCase, keys)
+            .run(&mut transaction)
+            .await
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn block_txs_pagination() -> anyhow::Result<()> {
+        const FIRST_SERIAL_ID: SerialId = 0xb10c_0000;
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let (_address, ops) = account_operations(FIRST_SERIAL_ID);
+        let keys = ops.iter().map(op_hash).collect();
+        let block_number = commit_test_block(&mut transaction, ops).await?;
+        let case = BlockTxsCase {
+            block_number,
+            next_serial_id: FIRST_SERIAL_ID,
+        };
+        PaginationHarness::new(case, keys)
+            .run(&mut transaction)
+            .await
+    }
+
+    /// Returns the `next` cursor of the block transactions page.
+    async fn block_txs_next_cursor(
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+        from: TxHash,
+        limit: u32,
+        direction: PaginationDirection,
+    ) -> anyhow::Result<Option<TxHash>> {
+        let query = PaginationQuery {
+            from: BlockAndTxHash {
+                block_number,
+                tx_hash: cursor(Some(from)),
+                tx_type: None,
+            },
+            limit,
+            direction,
+        };
+        let page = storage
+            .paginate(&query)
+            .await
+            .map_err(|err| anyhow::anyhow!(err.message))?;
+        Ok(page.pagination.next.map(|next| next.0))
+    }
+
+    /// Checks that the page reaching the end of the block points to the edge transaction
+    /// of the following block in the walk direction.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn block_txs_next_cursor_at_block_boundary() -> anyhow::Result<()> {
+        const FIRST_SERIAL_ID: SerialId = 0xb0de_0000;
+        const BLOCK_TXS: u64 = 3;
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let address = Address::random();
+        let mut blocks = Vec::new();
+        for block in 0..2 {
+            let ops: Vec<_> = (0..BLOCK_TXS)
+                .map(|idx| {
+                    let serial_id = FIRST_SERIAL_ID + block * BLOCK_TXS + idx;
+                    ExecutedOperations::PriorityOp(Box::new(deposit(address, serial_id)))
+                })
+                .collect();
+            let hashes: Vec<_> = ops.iter().map(op_hash).collect();
+            let block_number = commit_test_block(&mut transaction, ops).await?;
+            blocks.push((block_number, hashes));
+        }
+        let (first_block, first_hashes) = blocks[0].clone();
+        let (second_block, second_hashes) = blocks[1].clone();
+        let (newer, older) = (PaginationDirection::Newer, PaginationDirection::Older);
+
+        // Page ending in the middle of the block points to the next item of the block.
+        let next =
+            block_txs_next_cursor(&mut transaction, first_block, first_hashes[0], 2, newer).await?;
+        assert_eq!(next, Some(first_hashes[2]));
+        // Page ending exactly at the block boundary continues with the following block.
+        let next =
+            block_txs_next_cursor(&mut transaction, first_block, first_hashes[1], 2, newer).await?;
+        assert_eq!(next, Some(second_hashes[0]));
+        // So does the page running out of the items of the block.
+        let next = block_txs_next_cursor(&mut transaction, first_block, first_hashes[1], 10, newer)
+            .await?;
+        assert_eq!(next, Some(second_hashes[0]));
+        // Towards the older items, the following block is the preceding one.
+        let next =
+            block_txs_next_cursor(&mut transaction, second_block, second_hashes[2], 2, older)
+                .await?;
+        assert_eq!(next, Some(second_hashes[0]));
+        let next =
+            block_txs_next_cursor(&mut transaction, second_block, second_hashes[1], 2, older)
+                .await?;
+        assert_eq!(next, Some(first_hashes[2]));
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_txs_pagination() -> anyhow::Result<()> {
+        const FIRST_SERIAL_ID: SerialId = 0xacc0_0000;
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let (address, ops) = account_operations(FIRST_SERIAL_ID);
+        let keys = ops.iter().map(op_hash).collect();
+        let block_number = commit_test_block(&mut transaction, ops).await?;
+        let case = AccountTxsCase {
+            address,
+            token: None,
+            include_fee_token: false,
+            block_number,
+            next_serial_id: FIRST_SERIAL_ID,
+        };
+        PaginationHarness::new(case, keys)
+            .run(&mut transaction)
+            .await
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_txs_of_token_pagination() -> anyhow::Result<()> {
+        const FIRST_SERIAL_ID: SerialId = 0xacc1_0000;
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        // All the operations use ETH, but some of them only to pay the fee.
+        let (address, ops) = account_operations(FIRST_SERIAL_ID);
+        let pays_fee_only = |op: &ExecutedOperations| match op {
+            ExecutedOperations::Tx(tx) => tx.signed_tx.tx.fee_only_token().is_some(),
+            ExecutedOperations::PriorityOp(_) => false,
+        };
+        assert!(ops.iter().any(pays_fee_only));
+        let all_keys: Vec<_> = ops.iter().map(op_hash).collect();
+        let transfer_keys = ops
+            .iter()
+            .filter(|op| !pays_fee_only(*op))
+            .map(op_hash)
+            .collect();
+        let block_number = commit_test_block(&mut transaction, ops).await?;
+
+        for (include_fee_token, keys) in [(false, transfer_keys), (true, all_keys)] {
+            let mut transaction = transaction.start_transaction().await?;
+            let case = AccountTxsCase {
+                address,
+                token: Some(TokenId(0)),
+                include_fee_token,
+                block_number,
+                next_serial_id: FIRST_SERIAL_ID,
+            };
+            PaginationHarness::new(case, keys)
+                .run(&mut transaction)
+                .await?;
+        }
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_txs_random_walk() -> anyhow::Result<()> {
+        const FIRST_SERIAL_ID: SerialId = 0xacc2_0000;
+        const WALKS: usize = 50;
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        // The history of the account is spread over several blocks, which contain the operations
+        // of other accounts as well.
+        let mut rng = TestRng::from_env();
+        let mut account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(0xf00d)));
+        let address = account.address;
+        let mut keys = Vec::new();
+        let mut serial_id = FIRST_SERIAL_ID;
+        let mut block_number = BlockNumber(0);
+        // Fees below 1000 are packed exactly, so the transactions with different fees never coincide.
+        let mut fee = 0;
+        for _ in 0..rng.gen_range(2, 5) {
+            let (_, mut ops) = account_operations(serial_id);
+            serial_id += 1;
+            for _ in 0..rng.gen_range(1, 3) {
+                fee += 1;
+                let txs = TestServerConfig::gen_zk_txs_from(
+                    account,
+                    AccountId(0xdead),
+                    Address::random(),
+                    fee,
+                );
+                account = txs.acc;
+                let account_ops: Vec<_> = txs.txs.into_iter().map(|(_tx, op)| op).collect();
+                keys.extend(account_ops.iter().map(op_hash));
+                ops.extend(account_ops);
+            }
+            if rng.gen() {
+                let op = ExecutedOperations::PriorityOp(Box::new(deposit(address, serial_id)));
+                serial_id += 1;
+                keys.push(op_hash(&op));
+                ops.push(op);
+            }
+            block_number = commit_test_block(&mut transaction, ops).await?;
+        }
+
+        let case = AccountTxsCase {
+            address,
+            token: None,
+            include_fee_token: false,
+            block_number,
+            next_serial_id: serial_id,
+        };
+        PaginationHarness::new(case, keys)
+            .run_random(&mut transaction, &mut rng, WALKS)
+            .await
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_txs_foreign_cursor() -> anyhow::Result<()> {
+        const FIRST_SERIAL_ID: SerialId = 0xacc3_0000;
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let mut storage = cfg.pool.access_storage().await?;
+        let mut transaction = storage.start_transaction().await?;
+
+        let (address, ops) = account_operations(FIRST_SERIAL_ID);
+        let (_, foreign_ops) = account_operations(FIRST_SERIAL_ID + 1);
+        let foreign_tx_hash = op_hash(foreign_ops.last().unwrap());
+        commit_test_block(
+            &mut transaction,
+            ops.into_iter().chain(foreign_ops).collect(),
+        )
+        .await?;
+
+        for direction in [PaginationDirection::Newer, PaginationDirection::Older] {
+            let query = PaginationQuery {
+                from: AccountTxsRequest {
+                    address,
+                    tx_hash: ApiEither::from(foreign_tx_hash),
+                    token: None,
+                    include_fee_token: false,
+                    second_address: None,
+                },
+                limit: 10,
+                direction,
+            };
+            let err = transaction.paginate(&query).await.unwrap_err();
+            assert_eq!(err.code, ErrorCode::TransactionNotFound);
+        }
+        Ok(())
+    }
+
+    /// Paginates the query over the storage and the connection pool, checking that their
+    /// results are identical, both for the page and for the count only.
+    async fn compare_paginate_paths<I: CountedPaginate>(
+        pool: &ConnectionPool,
+        query: &PaginationQuery<I>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let expected = paginate_sequentially(&mut storage, query).await?;
+        drop(storage);
+
+        let page = pool.clone().paginate(query).await?;
+        let count = pool.clone().paginate_count(query).await?;
+        assert_eq!(count.pagination.count, expected.pagination.count);
+        assert!(count.list.is_empty());
+        assert_eq!(serde_json::to_value(expected)?, serde_json::to_value(page)?);
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn pool_pagination() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let pool = &cfg.pool;
+
+        let address = {
+            let mut storage = pool.access_storage().await?;
+            let txs = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(1))
+                .await?;
+            let op = txs[1].op.as_object().unwrap();
+            let account_id: AccountId = serde_json::from_value(
+                op.get("accountId")
+                    .or_else(|| op.get("creatorId"))
+                    .unwrap()
+                    .clone(),
+            )?;
+            storage
+                .chain()
+                .account_schema()
+                .account_address_by_id(account_id)
+                .await?
+                .unwrap()
+        };
+
+        for direction in [PaginationDirection::Newer, PaginationDirection::Older] {
+            let tokens = PaginationQuery {
+                from: cursor::<TokenId>(None),
+                limit: 10,
+                direction,
+            };
+            compare_paginate_paths(pool, &tokens).await?;
+
+            let blocks = PaginationQuery {
+                from: cursor::<BlockNumber>(None),
+                limit: 10,
+                direction,
+            };
+            compare_paginate_paths(pool, &blocks).await?;
+
+            let block_txs = PaginationQuery {
+                from: BlockAndTxHash {
+                    block_number: BlockNumber(1),
+                    tx_hash: cursor(None),
+                    tx_type: None,
+                },
+                limit: 10,
+                direction,
+            };
+            compare_paginate_paths(pool, &block_txs).await?;
+
+            let block_txs_by_index = PaginationQuery {
+                from: BlockAndTxIndex {
+                    block_number: BlockNumber(1),
+                    block_index: cursor(None),
+                },
+                limit: 10,
+                direction,
+            };
+            compare_paginate_paths(pool, &block_txs_by_index).await?;
+
+            let account_txs = PaginationQuery {
+                from: AccountTxsRequest {
+                    address,
+                    token: None,
+                    include_fee_token: false,
+                    second_address: None,
+                    tx_hash: cursor(None),
+                },
+                limit: 10,
+                direction,
+            };
+            compare_paginate_paths(pool, &account_txs).await?;
+        }
+        Ok(())
     }
 }
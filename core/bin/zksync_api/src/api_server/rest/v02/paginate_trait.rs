@@ -4,11 +4,13 @@
 use serde::Serialize;
 
 // Workspace uses
-use zksync_api_types::v02::pagination::{Paginated, PaginationQuery, MAX_LIMIT};
+use zksync_api_types::v02::pagination::{Paginated, PaginationQuery};
 
 // Local uses
-use super::error::{Error, InvalidDataError};
+use super::error::Error;
 
+/// Loads the pages of the paginated endpoints. The limit is expected to be validated
+/// by [`parse_query`](super::page_limit::parse_query) beforehand.
 #[async_trait::async_trait]
 pub trait Paginate<I: Serialize + Send + Sync + 'static> {
     type OutputObj: Serialize;
@@ -18,15 +20,15 @@ pub trait Paginate<I: Serialize + Send + Sync + 'static> {
         &mut self,
         query: &PaginationQuery<I>,
     ) -> Result<Paginated<Self::OutputObj, Self::OutputId>, Error>;
+}
 
-    async fn paginate_checked(
+/// Loads only the total count of the paginated items, see the `countOnly` flag of the paginated endpoints.
+#[async_trait::async_trait]
+pub trait PaginateCount<I: Serialize + Send + Sync + 'static>: Paginate<I> {
+    /// Skips the page query, the returned page has an empty list and the total count.
+    /// The cursor is still resolved and checked, while the limit doesn't affect the result.
+    async fn paginate_count(
         &mut self,
         query: &PaginationQuery<I>,
-    ) -> Result<Paginated<Self::OutputObj, Self::OutputId>, Error> {
-        if query.limit > MAX_LIMIT {
-            Err(Error::from(InvalidDataError::PaginationLimitTooBig))
-        } else {
-            self.paginate(query).await
-        }
-    }
+    ) -> Result<Paginated<Self::OutputObj, Self::OutputId>, Error>;
 }
@@ -0,0 +1,217 @@
+//! Priority operations part of API implementation.
+
+// Built-in uses
+use std::time::Instant;
+
+// External uses
+use actix_web::web;
+
+// Workspace uses
+use zksync_api_types::v02::{
+    pagination::{Paginated, PaginationQuery, PriorityOpsRequest},
+    transaction::{PriorityOpInfo, TxInBlockStatus, TxType},
+};
+use zksync_storage::{chain::operations::records::StoredExecutedPriorityOperation, ConnectionPool};
+use zksync_types::{tx::TxHash, BlockNumber, SerialId, ZkSyncOp, H256};
+
+// Local uses
+use super::{
+    error::Error,
+    page_limit::parse_query,
+    paginate_trait::Paginate,
+    response::ApiResult,
+    spec::{ApiScope, Operation},
+};
+use crate::api_try;
+
+/// Operations executed in the blocks up to `last_finalized` one are finalized.
+pub fn priority_op_info_from_stored(
+    op: StoredExecutedPriorityOperation,
+    last_finalized: BlockNumber,
+) -> PriorityOpInfo {
+    let block_number = BlockNumber(op.block_number as u32);
+    let status = if block_number <= last_finalized {
+        TxInBlockStatus::Finalized
+    } else {
+        TxInBlockStatus::Committed
+    };
+    let operation: ZkSyncOp =
+        serde_json::from_value(op.operation).expect("Should be correctly stored");
+    let op_type = match operation {
+        ZkSyncOp::Deposit(_) => TxType::Deposit,
+        ZkSyncOp::FullExit(_) => TxType::FullExit,
+        _ => panic!(
+            "Database provided a non-priority operation: {:?}",
+            operation
+        ),
+    };
+    PriorityOpInfo {
+        serial_id: op.priority_op_serialid as SerialId,
+        op_type,
+        eth_hash: H256::from_slice(&op.eth_hash),
+        tx_hash: TxHash::from_slice(&op.tx_hash).unwrap(),
+        block_number,
+        status,
+    }
+}
+
+/// Shared data between `api/v0.2/priorityOps` endpoints.
+#[derive(Debug, Clone)]
+struct ApiPriorityOpsData {
+    pool: ConnectionPool,
+    /// Maximum number of items on a page of the paginated endpoints.
+    max_page_limit: u32,
+}
+
+impl ApiPriorityOpsData {
+    fn new(pool: ConnectionPool, max_page_limit: u32) -> Self {
+        Self {
+            pool,
+            max_page_limit,
+        }
+    }
+
+    async fn priority_op_page(
+        &self,
+        query: PaginationQuery<PriorityOpsRequest>,
+    ) -> Result<Paginated<PriorityOpInfo, SerialId>, Error> {
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+        storage.paginate(&query).await
+    }
+}
+
+// Server implementation
+
+async fn priority_op_pagination(
+    data: web::Data<ApiPriorityOpsData>,
+    web::Query(query): web::Query<PaginationQuery<String>>,
+) -> ApiResult<Paginated<PriorityOpInfo, SerialId>> {
+    let start = Instant::now();
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    let query = PaginationQuery {
+        from: PriorityOpsRequest {
+            serial_id: query.from,
+        },
+        limit: query.limit,
+        direction: query.direction,
+    };
+    let res = data.priority_op_page(query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "priority_op_pagination");
+    res
+}
+
+pub fn api_scope(pool: ConnectionPool, max_page_limit: u32) -> ApiScope {
+    let data = ApiPriorityOpsData::new(pool, max_page_limit);
+
+    ApiScope::new("priorityOps")
+        .app_data(web::Data::new(data))
+        .route(
+            Operation::get("", "Executed priority operations ordered by the serial id")
+                .query::<PaginationQuery<String>>()
+                .result::<Paginated<PriorityOpInfo, SerialId>>(),
+            priority_op_pagination,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{deserialize_response_result, pagination::cursor, TestServerConfig},
+        SharedData,
+    };
+    use zksync_api_types::v02::{
+        pagination::{ApiEither, PaginationDirection},
+        ApiVersion,
+    };
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn priority_ops_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| {
+                api_scope(cfg.pool.clone(), cfg.config.api.rest.max_page_limit)
+            },
+            Some(shared_data),
+        );
+
+        let (expected, count) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let last_finalized = storage
+                .chain()
+                .block_schema()
+                .get_last_verified_confirmed_block()
+                .await?;
+            let ops = storage
+                .chain()
+                .operations_schema()
+                .load_executed_priority_ops_page(&PaginationQuery {
+                    from: 0,
+                    limit: u32::MAX,
+                    direction: PaginationDirection::Newer,
+                })
+                .await?;
+            let count = storage
+                .chain()
+                .operations_schema()
+                .get_executed_priority_ops_count()
+                .await?;
+            let ops: Vec<_> = ops
+                .into_iter()
+                .map(|op| priority_op_info_from_stored(op, last_finalized))
+                .collect();
+            (ops, count)
+        };
+        assert!(expected.len() >= 2);
+        assert_eq!(expected.len() as u32, count);
+        assert!(expected
+            .iter()
+            .any(|op| op.op_type == TxType::FullExit && op.status == TxInBlockStatus::Committed));
+        assert!(expected
+            .iter()
+            .any(|op| op.op_type == TxType::Deposit && op.status == TxInBlockStatus::Finalized));
+
+        // Walk through all the operations by the `next` cursors.
+        let mut walked = Vec::new();
+        let mut from = ApiEither::from(expected[0].serial_id);
+        loop {
+            let query = PaginationQuery {
+                from,
+                limit: 1,
+                direction: PaginationDirection::Newer,
+            };
+            let response = client.priority_ops(&query).await?;
+            let page: Paginated<PriorityOpInfo, SerialId> = deserialize_response_result(response)?;
+            assert_eq!(page.pagination.count, count);
+            walked.extend(page.list);
+            match page.pagination.next {
+                Some(next) => from = ApiEither::from(next),
+                None => break,
+            }
+        }
+        assert_eq!(walked, expected);
+
+        // The latest operation is the one with the greatest serial id.
+        let query = PaginationQuery {
+            from: cursor(None),
+            limit: 1,
+            direction: PaginationDirection::Older,
+        };
+        let response = client.priority_ops(&query).await?;
+        let page: Paginated<PriorityOpInfo, SerialId> = deserialize_response_result(response)?;
+        assert_eq!(page.list.first(), expected.last());
+
+        server.stop().await;
+        Ok(())
+    }
+}
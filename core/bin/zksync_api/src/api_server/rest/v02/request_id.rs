@@ -0,0 +1,222 @@
+//! Correlation of the v0.2 API responses with the server logs.
+//!
+//! Every request gets an id which is either taken from the `X-Request-Id` header or generated.
+//! The id is returned in the response envelope and the response header, and all the log lines
+//! emitted while handling the request are recorded within a span carrying it.
+
+// Built-in uses
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+// External uses
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    HttpMessage, HttpRequest,
+};
+use futures::future::LocalBoxFuture;
+use uuid::Uuid;
+use vlog::__tracing::{info_span, Instrument};
+
+/// Header the request id is passed and returned in.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+/// Longer ids passed by the clients are replaced with the generated ones.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Id of the request being handled, stored in the request extensions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    fn from_header(value: &HeaderValue) -> Option<Self> {
+        let id = value.to_str().ok()?.trim();
+        if id.is_empty() || id.len() > MAX_REQUEST_ID_LEN {
+            return None;
+        }
+        Some(Self(id.to_string()))
+    }
+
+    /// Returns the id assigned to the request by the middleware.
+    /// Requests that didn't pass through it get a new one.
+    pub fn of(req: &HttpRequest) -> Self {
+        req.extensions()
+            .get::<Self>()
+            .cloned()
+            .unwrap_or_else(Self::generate)
+    }
+}
+
+/// Middleware assigning the ids to the requests.
+/// Should be the outermost one in the scope, so that the responses of the other middlewares have the id as well.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequestIdTracing;
+
+impl<S> Transform<S, ServiceRequest> for RequestIdTracing
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Transform = RequestIdTracingMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestIdTracingMiddleware {
+            service: Rc::new(service),
+        }))
+    }
+}
+
+pub struct RequestIdTracingMiddleware<S> {
+    service: Rc<S>,
+}
+
+impl<S> Service<ServiceRequest> for RequestIdTracingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse, Error = actix_web::Error> + 'static,
+{
+    type Response = ServiceResponse;
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(RequestId::from_header)
+            .unwrap_or_else(RequestId::generate);
+        req.extensions_mut().insert(request_id.clone());
+
+        let span = info_span!(
+            "api_request",
+            request_id = %request_id.0,
+            method = %req.method(),
+            path = %req.path()
+        );
+        Box::pin(
+            async move {
+                let mut response = service.call(req).await?;
+                if let Ok(value) = HeaderValue::from_str(&request_id.0) {
+                    response
+                        .headers_mut()
+                        .insert(HeaderName::from_static("x-request-id"), value);
+                }
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        error::Error, response::ApiResult, test_utils::TestServerConfig, SharedData,
+    };
+    use actix_web::{web, App};
+    use zksync_api_types::v02::{ApiVersion, Response};
+
+    async fn ok() -> ApiResult<()> {
+        ApiResult::Ok(())
+    }
+
+    async fn fail() -> ApiResult<()> {
+        ApiResult::from(Error::storage("database is down"))
+    }
+
+    fn start_server(cfg: &TestServerConfig) -> actix_test::TestServer {
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        actix_test::start(move || {
+            App::new().app_data(web::Data::new(shared_data)).service(
+                web::scope("/api/v0.2")
+                    .wrap(RequestIdTracing)
+                    .route("/ok", web::get().to(ok))
+                    .route("/fail", web::get().to(fail)),
+            )
+        })
+    }
+
+    /// Makes a request and returns the id from the response header and the one from the envelope.
+    async fn request(
+        server: &actix_test::TestServer,
+        path: &str,
+        request_id: Option<&str>,
+    ) -> anyhow::Result<(String, String)> {
+        let mut request = server.get(format!("/api/v0.2{}", path));
+        if let Some(request_id) = request_id {
+            request = request.insert_header((REQUEST_ID_HEADER, request_id));
+        }
+        let mut response = request
+            .send()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        let header = response
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .expect("Request id header is missing")
+            .to_str()?
+            .to_string();
+        let response: Response = response
+            .json()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        Ok((header, response.request_id))
+    }
+
+    #[test]
+    fn request_id_from_header() {
+        let parse = |value: &str| RequestId::from_header(&HeaderValue::from_str(value).unwrap());
+
+        assert_eq!(parse(" trace-1 "), Some(RequestId("trace-1".to_string())));
+        assert_eq!(parse(""), None);
+        assert_eq!(parse(&"a".repeat(MAX_REQUEST_ID_LEN + 1)), None);
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn request_ids() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let server = start_server(&cfg);
+
+        // Ids passed by the clients are echoed, errors carry them as well.
+        for path in ["/ok", "/fail"] {
+            let (header, envelope) = request(&server, path, Some("trace-42")).await?;
+            assert_eq!(header, "trace-42");
+            assert_eq!(envelope, "trace-42");
+        }
+
+        // Otherwise every request gets its own id.
+        let (first, second) = futures::future::join(
+            request(&server, "/ok", None),
+            request(&server, "/fail", None),
+        )
+        .await;
+        let (first_header, first_envelope) = first?;
+        let (second_header, second_envelope) = second?;
+        assert_eq!(first_header, first_envelope);
+        assert_eq!(second_header, second_envelope);
+        assert_ne!(first_envelope, second_envelope);
+        Uuid::parse_str(&first_envelope)?;
+        Uuid::parse_str(&second_envelope)?;
+
+        server.stop().await;
+        Ok(())
+    }
+}
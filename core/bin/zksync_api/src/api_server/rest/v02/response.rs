@@ -3,16 +3,29 @@ use std::collections::HashMap;
 use std::convert::From;
 
 // External uses
-use actix_web::{web::Data, HttpRequest, HttpResponse, Responder};
+use actix_web::{
+    http::header::{HeaderValue, CACHE_CONTROL, ETAG, IF_NONE_MATCH},
+    web::Data,
+    HttpMessage, HttpRequest, HttpResponse, Responder,
+};
 use chrono::Utc;
 use qstring::QString;
 use serde::{Deserialize, Serialize};
 
 // Workspace uses
-use zksync_api_types::v02::{Request, Response, ResultStatus};
+use zksync_api_types::v02::{
+    block::{BlockInfo, BlockStatus},
+    Request, Response, ResultStatus,
+};
+use zksync_crypto::convert::FeConvert;
 
 // Local uses
-use super::{error::Error, SharedData};
+use super::{
+    error::{Error, ErrorCode},
+    request_id::RequestId,
+    SharedData,
+};
+use crate::api_server::{rest::http_metrics::ApiErrorCode, rpc_server::error::RpcErrorCodes};
 
 // This struct is needed to wrap all api responses is `Response` struct by implementing `Responder` trait for it.
 // We can't use simple `Result`, because `actix-web` has already `Responder` implementation for it.
@@ -44,16 +57,37 @@ impl<R: Serialize> Responder for ApiResult<R> {
             args,
             timestamp: Utc::now(),
         };
+        let RequestId(request_id) = RequestId::of(req);
+        if let ApiResult::Error(err) = &self {
+            req.extensions_mut().insert(ApiErrorCode(err.code.clone()));
+        }
 
+        // Throttled clients and the ones submitting during the maintenance are told
+        // to back off by the HTTP status as well.
+        let mut http_response = match &self {
+            ApiResult::Error(err)
+                if err.code == ErrorCode::from(RpcErrorCodes::TooManyRequests) =>
+            {
+                HttpResponse::TooManyRequests()
+            }
+            ApiResult::Error(err)
+                if err.code == ErrorCode::from(RpcErrorCodes::MaintenanceMode) =>
+            {
+                HttpResponse::ServiceUnavailable()
+            }
+            _ => HttpResponse::Ok(),
+        };
         let response = match self {
             ApiResult::Ok(res) => Response {
                 request,
+                request_id,
                 status: ResultStatus::Success,
                 result: Some(serde_json::to_value(res).unwrap()),
                 error: None,
             },
             ApiResult::Error(err) => Response {
                 request,
+                request_id,
                 status: ResultStatus::Error,
                 result: None,
                 error: Some(serde_json::to_value(err).unwrap()),
@@ -62,9 +96,17 @@ impl<R: Serialize> Responder for ApiResult<R> {
 
         let body = serde_json::to_string(&response).expect("Should be correct serializable");
 
-        HttpResponse::Ok()
-            .content_type("application/json")
-            .body(body)
+        http_response.content_type("application/json").body(body)
+    }
+}
+
+impl<R: Serialize> ApiResult<R> {
+    /// Attaches the caching directives to the result. Errors are never cached.
+    pub fn with_cache_policy(self, policy: CachePolicy) -> CachedApiResult<R> {
+        CachedApiResult {
+            result: self,
+            policy,
+        }
     }
 }
 
@@ -83,13 +125,108 @@ impl<R: Serialize> From<Result<R, Error>> for ApiResult<R> {
     }
 }
 
+/// `Cache-Control` of the resources that will never change.
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// HTTP caching of the response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CachePolicy {
+    /// Resource may still change, so the clients have to revalidate it every time.
+    NoCache,
+    /// Resource is a part of the finalized block, so it's identified by the block.
+    Immutable { etag: String },
+}
+
+impl CachePolicy {
+    /// Only the finalized blocks are immutable, the weak ETag is derived from the block number and root hash.
+    pub fn for_block(block: &BlockInfo) -> Self {
+        if block.status == BlockStatus::Finalized {
+            Self::Immutable {
+                etag: format!(
+                    "W/\"{}-{}\"",
+                    block.block_number,
+                    hex::encode(block.new_state_root.to_bytes())
+                ),
+            }
+        } else {
+            Self::NoCache
+        }
+    }
+}
+
+/// Checks whether the `If-None-Match` header contains the ETag. Weak comparison is used.
+fn etag_matches(if_none_match: &HeaderValue, etag: &str) -> bool {
+    let if_none_match = match if_none_match.to_str() {
+        Ok(value) => value,
+        Err(_) => return false,
+    };
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_owned();
+    let etag = opaque(etag);
+    if_none_match
+        .split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == etag)
+}
+
+/// `ApiResult` with the caching directives, see `ApiResult::with_cache_policy`.
+#[derive(Debug)]
+pub struct CachedApiResult<R: Serialize> {
+    result: ApiResult<R>,
+    policy: CachePolicy,
+}
+
+impl<R: Serialize> Responder for CachedApiResult<R> {
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse {
+        let policy = match &self.result {
+            ApiResult::Ok(_) => self.policy,
+            ApiResult::Error(_) => CachePolicy::NoCache,
+        };
+        let etag = match policy {
+            CachePolicy::Immutable { etag } => etag,
+            CachePolicy::NoCache => {
+                let mut response = self.result.respond_to(req);
+                response
+                    .headers_mut()
+                    .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+                return response;
+            }
+        };
+
+        let not_modified = req
+            .headers()
+            .get(IF_NONE_MATCH)
+            .map_or(false, |if_none_match| etag_matches(if_none_match, &etag));
+        let mut response = if not_modified {
+            HttpResponse::NotModified().finish()
+        } else {
+            self.result.respond_to(req)
+        };
+        let headers = response.headers_mut();
+        headers.insert(
+            ETAG,
+            HeaderValue::from_str(&etag).expect("ETag consists of the visible ASCII characters"),
+        );
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL),
+        );
+        response
+    }
+}
+
+impl<R: Serialize> From<Error> for CachedApiResult<R> {
+    fn from(err: Error) -> Self {
+        ApiResult::Error(err).with_cache_policy(CachePolicy::NoCache)
+    }
+}
+
+/// Returns the error from the handler, works for both `ApiResult` and `CachedApiResult`.
 #[macro_export]
 macro_rules! api_try {
     ($e:expr) => {
         match $e {
             Ok(res) => res,
             Err(err) => {
-                return ApiResult::from(err);
+                return From::from(err);
             }
         }
     };
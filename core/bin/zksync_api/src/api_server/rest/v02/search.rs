@@ -0,0 +1,241 @@
+//! Search part of API implementation.
+
+// Built-in uses
+use std::str::FromStr;
+use std::time::Instant;
+
+// External uses
+use actix_web::web;
+
+// Workspace uses
+use zksync_api_types::v02::search::{SearchMatch, SearchQuery};
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{AccountId, Address, BlockNumber};
+
+// Local uses
+use super::{
+    error::Error,
+    response::ApiResult,
+    spec::{ApiScope, Operation},
+};
+use crate::api_server::helpers::parse_tx_hash;
+
+/// Shared data between `api/v0.2/search` endpoints.
+#[derive(Debug, Clone)]
+struct ApiSearchData {
+    pool: ConnectionPool,
+}
+
+impl ApiSearchData {
+    fn new(pool: ConnectionPool) -> Self {
+        Self { pool }
+    }
+
+    /// Resolves the query into the known resources. A number can be both a block number
+    /// and an account id, in this case the block goes first.
+    async fn search(&self, query: &str) -> Result<Vec<SearchMatch>, Error> {
+        let query = query.trim();
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+
+        let mut matches = Vec::new();
+        if let Ok(number) = u32::from_str(query) {
+            if let Some(block) = Self::find_block(&mut storage, BlockNumber(number)).await? {
+                matches.push(block);
+            }
+            if let Some(account) = Self::find_account_by_id(&mut storage, AccountId(number)).await?
+            {
+                matches.push(account);
+            }
+        } else if let Ok(tx_hash) = parse_tx_hash(query) {
+            let receipt = storage
+                .chain()
+                .operations_ext_schema()
+                .tx_receipt_api_v02(tx_hash.as_ref())
+                .await
+                .map_err(Error::storage)?;
+            if receipt.is_some() {
+                matches.push(SearchMatch::transaction(tx_hash));
+            }
+        } else if let Ok(address) = Address::from_str(query.strip_prefix("0x").unwrap_or(query)) {
+            let account_id = storage
+                .chain()
+                .account_schema()
+                .account_id_by_address(address)
+                .await
+                .map_err(Error::storage)?;
+            if let Some(account_id) = account_id {
+                matches.push(SearchMatch::account(account_id, address));
+            }
+        }
+        Ok(matches)
+    }
+
+    async fn find_block(
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> Result<Option<SearchMatch>, Error> {
+        let last_committed = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_block()
+            .await
+            .map_err(Error::storage)?;
+        if *block_number > 0 && block_number <= last_committed {
+            Ok(Some(SearchMatch::block(block_number)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn find_account_by_id(
+        storage: &mut StorageProcessor<'_>,
+        account_id: AccountId,
+    ) -> Result<Option<SearchMatch>, Error> {
+        let address = storage
+            .chain()
+            .account_schema()
+            .account_address_by_id(account_id)
+            .await
+            .map_err(Error::storage)?;
+        Ok(address.map(|address| SearchMatch::account(account_id, address)))
+    }
+}
+
+// Server implementation
+
+async fn search(
+    data: web::Data<ApiSearchData>,
+    web::Query(query): web::Query<SearchQuery>,
+) -> ApiResult<Vec<SearchMatch>> {
+    let start = Instant::now();
+    let res = data.search(&query.query).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "search");
+    res
+}
+
+pub fn api_scope(pool: ConnectionPool) -> ApiScope {
+    let data = ApiSearchData::new(pool);
+
+    ApiScope::new("search")
+        .app_data(web::Data::new(data))
+        .route(
+            Operation::get("", "Blocks, transactions and accounts matching the query")
+                .query::<SearchQuery>()
+                .result::<Vec<SearchMatch>>(),
+            search,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rest::v02::{
+        test_utils::{deserialize_response_result, TestServerConfig},
+        SharedData,
+    };
+    use zksync_api_types::v02::ApiVersion;
+    use zksync_types::{tx::TxHash, AccountUpdate};
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn search_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            |cfg: &TestServerConfig| api_scope(cfg.pool.clone()),
+            Some(shared_data),
+        );
+
+        let (last_committed, tx_hash, account_id, address) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let last_committed = storage
+                .chain()
+                .block_schema()
+                .get_last_committed_block()
+                .await?;
+            let transactions = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(1))
+                .await?;
+            let tx_hash = TxHash::from_str(&transactions[0].tx_hash).unwrap();
+            let updates = storage
+                .chain()
+                .state_schema()
+                .load_state_diff_for_block(last_committed)
+                .await?;
+            let (account_id, address) = updates
+                .iter()
+                .find_map(|(account_id, update)| match update {
+                    AccountUpdate::Create { address, .. } => Some((*account_id, *address)),
+                    _ => None,
+                })
+                .expect("The last committed block should create accounts");
+            (last_committed, tx_hash, account_id, address)
+        };
+
+        // A number may be both a block number and an account id, the block goes first.
+        for number in 0..=*last_committed + 1 {
+            let expected = {
+                let mut storage = cfg.pool.access_storage().await?;
+                let mut expected = Vec::new();
+                if number > 0 && number <= *last_committed {
+                    expected.push(SearchMatch::block(BlockNumber(number)));
+                }
+                if let Some(address) = storage
+                    .chain()
+                    .account_schema()
+                    .account_address_by_id(AccountId(number))
+                    .await?
+                {
+                    expected.push(SearchMatch::account(AccountId(number), address));
+                }
+                expected
+            };
+            let response = client.search(&number.to_string()).await?;
+            let matches: Vec<SearchMatch> = deserialize_response_result(response)?;
+            assert_eq!(matches, expected, "{}", number);
+        }
+
+        let response = client.search(&account_id.to_string()).await?;
+        let matches: Vec<SearchMatch> = deserialize_response_result(response)?;
+        assert_eq!(
+            matches.last(),
+            Some(&SearchMatch::account(account_id, address))
+        );
+
+        let hex_hash = hex::encode(tx_hash.as_ref());
+        for query in [format!("0x{}", hex_hash), format!("sync-tx:{}", hex_hash)] {
+            let response = client.search(&query).await?;
+            let matches: Vec<SearchMatch> = deserialize_response_result(response)?;
+            assert_eq!(matches, vec![SearchMatch::transaction(tx_hash)]);
+        }
+
+        let response = client.search(&format!("{:?}", address)).await?;
+        let matches: Vec<SearchMatch> = deserialize_response_result(response)?;
+        assert_eq!(matches, vec![SearchMatch::account(account_id, address)]);
+
+        // Unknown resources are not an error.
+        for query in [
+            format!("0x{}", "00".repeat(32)),
+            format!("{:?}", Address::repeat_byte(0x42)),
+            u32::MAX.to_string(),
+            "not a query".to_string(),
+        ] {
+            let response = client.search(&query).await?;
+            let matches: Vec<SearchMatch> = deserialize_response_result(response)?;
+            assert!(matches.is_empty(), "{}", query);
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,470 @@
+//! OpenAPI description of the v0.2 API.
+//!
+//! Endpoint modules register their routes through [`ApiScope`], which keeps the description of
+//! every route next to the handler it is wired to, so the document can't describe a route that
+//! is not served or miss one that is. Schemas are generated from the serde types of
+//! `zksync_api_types`.
+
+// Built-in uses
+use std::{future::Future, iter};
+
+// External uses
+use actix_web::{
+    dev::{AppService, Handler, HttpServiceFactory},
+    http::Method,
+    web, FromRequest, HttpResponse, Responder, Scope,
+};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::Schema,
+    visit::Visitor,
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+// Workspace uses
+use zksync_api_types::v02::Response;
+
+// Local uses
+use super::api_keys::{RequireApiKey, API_KEY_HEADER};
+
+const SPEC_SCOPE: &str = "spec";
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Schema of the type, components are referenced.
+fn schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+/// Schema of the type itself, used when its properties have to be listed, e.g. query parameters.
+fn inline_schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    T::json_schema(gen)
+}
+
+fn schema_to_json(gen: &mut SchemaGenerator, mut schema: Schema) -> Value {
+    for visitor in gen.visitors_mut() {
+        visitor.visit_schema(&mut schema);
+    }
+    serde_json::to_value(schema).expect("schema is always serializable")
+}
+
+#[derive(Clone, Copy)]
+enum ResponseContent {
+    /// `result` field of the API response.
+    Result(SchemaFn),
+    /// JSON document sent as is.
+    Json(SchemaFn),
+    /// File streamed in one of the given content types.
+    Stream(&'static [&'static str]),
+}
+
+/// Description of a single route.
+#[derive(Clone)]
+pub struct Operation {
+    method: Method,
+    path: &'static str,
+    summary: &'static str,
+    path_params: Vec<(&'static str, &'static str)>,
+    query: Option<SchemaFn>,
+    body: Option<SchemaFn>,
+    response: ResponseContent,
+    api_key_required: bool,
+}
+
+impl Operation {
+    fn new(method: Method, path: &'static str, summary: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            summary,
+            path_params: Vec::new(),
+            query: None,
+            body: None,
+            response: ResponseContent::Result(schema_for::<()>),
+            api_key_required: false,
+        }
+    }
+
+    pub fn get(path: &'static str, summary: &'static str) -> Self {
+        Self::new(Method::GET, path, summary)
+    }
+
+    pub fn post(path: &'static str, summary: &'static str) -> Self {
+        Self::new(Method::POST, path, summary)
+    }
+
+    /// Describes the path parameter. Parameters themselves are taken from the path,
+    /// so the undescribed ones are still listed.
+    pub fn path_param(mut self, name: &'static str, description: &'static str) -> Self {
+        self.path_params.push((name, description));
+        self
+    }
+
+    /// Query parameters are the fields of the given type.
+    pub fn query<T: JsonSchema>(mut self) -> Self {
+        self.query = Some(inline_schema_for::<T>);
+        self
+    }
+
+    pub fn body<T: JsonSchema>(mut self) -> Self {
+        self.body = Some(schema_for::<T>);
+        self
+    }
+
+    /// The route responds with the given type wrapped into the API response.
+    pub fn result<T: JsonSchema>(mut self) -> Self {
+        self.response = ResponseContent::Result(schema_for::<T>);
+        self
+    }
+
+    /// The route responds with the given type as is.
+    pub fn json<T: JsonSchema>(mut self) -> Self {
+        self.response = ResponseContent::Json(schema_for::<T>);
+        self
+    }
+
+    /// The route streams a file in one of the given content types.
+    pub fn stream(mut self, content_types: &'static [&'static str]) -> Self {
+        self.response = ResponseContent::Stream(content_types);
+        self
+    }
+
+    pub fn api_key_required(mut self) -> Self {
+        self.api_key_required = true;
+        self
+    }
+
+    fn describe(&self, path: &str, gen: &mut SchemaGenerator) -> Value {
+        let mut parameters = Vec::new();
+        for name in path_params(path) {
+            let mut parameter = json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            });
+            if let Some((_, description)) = self.path_params.iter().find(|(n, _)| *n == name) {
+                parameter["description"] = (*description).into();
+            }
+            parameters.push(parameter);
+        }
+        if let Some(query) = self.query {
+            let schema = query(gen);
+            let schema = schema_to_json(gen, schema);
+            let required = schema["required"].as_array().cloned().unwrap_or_default();
+            if let Some(properties) = schema["properties"].as_object() {
+                for (name, property) in properties {
+                    let mut property = property.clone();
+                    let description = property
+                        .as_object_mut()
+                        .and_then(|property| property.remove("description"));
+                    let mut parameter = json!({
+                        "name": name,
+                        "in": "query",
+                        "required": required.iter().any(|field| field == name),
+                        "schema": property,
+                    });
+                    if let Some(description) = description {
+                        parameter["description"] = description;
+                    }
+                    parameters.push(parameter);
+                }
+            }
+        }
+
+        let mut operation = json!({
+            "summary": self.summary,
+            "parameters": parameters,
+            "responses": { "200": self.describe_response(gen) },
+        });
+        if let Some(body) = self.body {
+            let schema = body(gen);
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": schema_to_json(gen, schema) } },
+            });
+        }
+        if self.api_key_required {
+            operation["security"] = json!([{ "apiKey": [] }]);
+        }
+        operation
+    }
+
+    fn describe_response(&self, gen: &mut SchemaGenerator) -> Value {
+        match self.response {
+            ResponseContent::Result(result) => {
+                let envelope = gen.subschema_for::<Response>();
+                let result = result(gen);
+                json!({
+                    "description": "API response, `result` is set if the request has succeeded",
+                    "content": { "application/json": { "schema": { "allOf": [
+                        schema_to_json(gen, envelope),
+                        { "type": "object", "properties": { "result": schema_to_json(gen, result) } },
+                    ] } } },
+                })
+            }
+            ResponseContent::Json(document) => {
+                let document = document(gen);
+                json!({
+                    "description": "JSON document",
+                    "content": { "application/json": { "schema": schema_to_json(gen, document) } },
+                })
+            }
+            ResponseContent::Stream(content_types) => {
+                let content: Map<_, _> = content_types
+                    .iter()
+                    .map(|content_type| {
+                        let schema = json!({ "type": "string", "format": "binary" });
+                        (content_type.to_string(), json!({ "schema": schema }))
+                    })
+                    .collect();
+                json!({ "description": "Exported file", "content": content })
+            }
+        }
+    }
+}
+
+/// Names of the `{name}` segments of the path.
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+/// Path of the route relative to the API root, e.g. `/blocks/{block_position}`.
+fn join_path(scope: &str, path: &str) -> String {
+    let path = path.trim_start_matches('/');
+    if path.is_empty() {
+        format!("/{}", scope)
+    } else {
+        format!("/{}/{}", scope, path)
+    }
+}
+
+/// Scope of the API which describes every route it serves.
+pub struct ApiScope {
+    path: &'static str,
+    scope: Scope,
+    operations: Vec<Operation>,
+}
+
+impl ApiScope {
+    pub fn new(path: &'static str) -> Self {
+        Self {
+            path,
+            scope: web::scope(path),
+            operations: Vec::new(),
+        }
+    }
+
+    pub fn app_data<U: 'static>(mut self, data: U) -> Self {
+        self.scope = self.scope.app_data(data);
+        self
+    }
+
+    /// Registers the handler for the route. Routes are matched in the order of registration.
+    pub fn route<F, T, R>(mut self, operation: Operation, handler: F) -> Self
+    where
+        F: Handler<T, R>,
+        T: FromRequest + 'static,
+        R: Future + 'static,
+        R::Output: Responder + 'static,
+    {
+        let route = web::method(operation.method.clone()).to(handler);
+        self.scope = if operation.api_key_required {
+            self.scope.service(
+                web::resource(operation.path)
+                    .wrap(RequireApiKey)
+                    .route(route),
+            )
+        } else {
+            self.scope.route(operation.path, route)
+        };
+        self.operations.push(operation);
+        self
+    }
+
+    fn operations(&self) -> impl Iterator<Item = (String, &Operation)> {
+        self.operations
+            .iter()
+            .map(move |operation| (join_path(self.path, operation.path), operation))
+    }
+}
+
+impl HttpServiceFactory for ApiScope {
+    fn register(self, config: &mut AppService) {
+        self.scope.register(config)
+    }
+}
+
+/// OpenAPI 3.0 document describing the API.
+#[derive(Debug, Clone)]
+pub struct ApiSpec(Value);
+
+impl ApiSpec {
+    pub fn new(scopes: &[ApiScope]) -> Self {
+        let mut gen = SchemaSettings::openapi3().into_generator();
+
+        let spec_operation = spec_operation();
+        let operations = scopes
+            .iter()
+            .flat_map(ApiScope::operations)
+            .chain(iter::once((
+                join_path(SPEC_SCOPE, spec_operation.path),
+                &spec_operation,
+            )));
+        let mut paths = Map::new();
+        for (path, operation) in operations {
+            let description = operation.describe(&path, &mut gen);
+            let method = operation.method.as_str().to_lowercase();
+            paths.entry(path).or_insert_with(|| json!({}))[method.as_str()] = description;
+        }
+
+        let mut schemas = Map::new();
+        for (name, schema) in gen.take_definitions() {
+            schemas.insert(name, schema_to_json(&mut gen, schema));
+        }
+
+        Self(json!({
+            "openapi": "3.0.3",
+            "info": { "title": "zkSync API", "version": "0.2" },
+            "servers": [{ "url": "/api/v0.2" }],
+            "paths": paths,
+            "components": {
+                "schemas": schemas,
+                "securitySchemes": {
+                    "apiKey": { "type": "apiKey", "in": "header", "name": API_KEY_HEADER },
+                },
+            },
+        }))
+    }
+}
+
+fn spec_operation() -> Operation {
+    Operation::get("", "OpenAPI description of the API").json::<Value>()
+}
+
+async fn api_spec(spec: web::Data<ApiSpec>) -> HttpResponse {
+    HttpResponse::Ok().json(&spec.0)
+}
+
+/// Scope serving the description of the given scopes along with its own.
+pub fn api_scope(scopes: &[ApiScope]) -> ApiScope {
+    ApiScope::new(SPEC_SCOPE)
+        .app_data(web::Data::new(ApiSpec::new(scopes)))
+        .route(spec_operation(), api_spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        api_server::{
+            rest::{
+                network_status::SharedNetworkStatus,
+                v02::{
+                    api_keys::ApiKeyAuth,
+                    test_utils::{
+                        dummy_fee_ticker, dummy_sign_verifier, submit_txs_loopback,
+                        TestServerConfig,
+                    },
+                    SharedData,
+                },
+            },
+            tx_sender::TxSender,
+        },
+        core_api_client::CoreApiClient,
+    };
+    use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
+
+    #[test]
+    fn paths() {
+        assert_eq!(join_path("blocks", ""), "/blocks");
+        assert_eq!(join_path("fee", "/batch"), "/fee/batch");
+        assert_eq!(
+            path_params("/blocks/{block_position}/transactions/{block_index}").collect::<Vec<_>>(),
+            vec!["block_position", "block_index"]
+        );
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn spec_describes_routes() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let (sender, _handle) = submit_txs_loopback();
+        let (_client, server) = cfg.start_server_with_scope(
+            String::new(),
+            move |cfg: &TestServerConfig| {
+                super::super::api_scope(
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&[], None),
+                        &cfg.config.api.common,
+                        &cfg.config.api.token_config,
+                        sender.clone(),
+                    ),
+                    &cfg.config,
+                    SharedNetworkStatus::new(String::new()),
+                    ApiKeyAuth::new(cfg.pool.clone(), None),
+                    CoreApiClient::new(String::new()),
+                    EthereumGateway::Mock(MockEthereum::default()),
+                )
+            },
+            None::<SharedData>,
+        );
+
+        let spec: Value = server
+            .get("/api/v0.2/spec")
+            .send()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?
+            .json()
+            .await
+            .map_err(|err| anyhow::format_err!("{}", err))?;
+        assert_eq!(spec["openapi"], "3.0.3");
+
+        let submit_tx = &spec["paths"]["/transactions"]["post"];
+        assert_eq!(
+            submit_tx["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/TxWithSignature"
+        );
+        assert_eq!(submit_tx["parameters"], json!([]));
+        assert!(spec["components"]["schemas"]["TxWithSignature"].is_object());
+
+        let block = &spec["paths"]["/blocks/{block_position}"]["get"];
+        assert_eq!(
+            block["parameters"],
+            json!([{
+                "name": "block_position",
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+                "description": "Number of the block, `lastCommitted` or `lastFinalized`",
+            }])
+        );
+        assert!(block.get("requestBody").is_none());
+
+        // Query parameters are taken from the fields of the query type.
+        let blocks = &spec["paths"]["/blocks"]["get"]["parameters"];
+        let names: Vec<_> = blocks
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|parameter| (parameter["name"].as_str().unwrap(), parameter["in"].clone()))
+            .collect();
+        assert!(names.contains(&("from", json!("query"))));
+        assert!(names.contains(&("limit", json!("query"))));
+
+        assert_eq!(
+            spec["paths"]["/blocks/export"]["get"]["security"],
+            json!([{ "apiKey": [] }])
+        );
+        assert!(spec["paths"]["/spec"]["get"].is_object());
+
+        Ok(())
+    }
+}
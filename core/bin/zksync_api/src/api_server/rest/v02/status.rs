@@ -2,17 +2,31 @@
 
 // Built-in uses
 
-use std::time::Instant;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 // External uses
-use actix_web::{web, Scope};
+use actix_web::web;
+use chrono::Utc;
+use tokio::sync::Mutex;
 
 // Workspace uses
-use zksync_api_types::v02::status::NetworkStatus;
+use zksync_api_types::v02::status::{MempoolStatus, NetworkStatus};
+use zksync_storage::{chain::mempool::records::MempoolTxTypeStats, ConnectionPool};
 
 // Local uses
-use super::response::ApiResult;
+use super::{
+    error::Error,
+    response::ApiResult,
+    spec::{ApiScope, Operation},
+};
 use crate::api_server::rest::network_status::SharedNetworkStatus;
 
+/// Mempool statistics are reused for this long, so that the dashboards polling them
+/// don't query the database on every request.
+const MEMPOOL_STATS_CACHE_TTL: Duration = Duration::from_secs(5);
+
 /// Shared data between `api/v0.2/networkStatus` endpoints.
 #[derive(Debug, Clone)]
 pub struct ApiStatusData {
@@ -25,29 +39,100 @@ impl ApiStatusData {
     }
 }
 
+/// Shared data of the `api/v0.2/status` endpoints.
+#[derive(Debug, Clone)]
+struct ApiMempoolStatusData {
+    pool: ConnectionPool,
+    /// Statistics along with the time they were loaded at.
+    cache: Arc<Mutex<Option<(Instant, Vec<MempoolTxTypeStats>)>>>,
+}
+
+impl ApiMempoolStatusData {
+    fn new(pool: ConnectionPool) -> Self {
+        Self {
+            pool,
+            cache: Arc::default(),
+        }
+    }
+
+    async fn mempool_status(&self) -> Result<MempoolStatus, Error> {
+        // The lock is held while the statistics are loaded, so that the concurrent
+        // requests wait for them instead of querying the database as well.
+        let mut cache = self.cache.lock().await;
+        let expired = cache.as_ref().map_or(true, |(loaded_at, _)| {
+            loaded_at.elapsed() >= MEMPOOL_STATS_CACHE_TTL
+        });
+        if expired {
+            let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+            let stats = storage
+                .chain()
+                .mempool_schema()
+                .get_mempool_stats()
+                .await
+                .map_err(Error::storage)?;
+            *cache = Some((Instant::now(), stats));
+        }
+        let (_, stats) = cache.as_ref().expect("statistics are loaded above");
+        Ok(mempool_status_from_stats(stats))
+    }
+}
+
+/// The age of the oldest transaction is counted at the time of the request,
+/// even if the statistics are taken from the cache.
+fn mempool_status_from_stats(stats: &[MempoolTxTypeStats]) -> MempoolStatus {
+    let now = Utc::now();
+    MempoolStatus {
+        size: stats.iter().map(|stats| stats.count as u32).sum(),
+        oldest_tx_age_secs: stats
+            .iter()
+            .map(|stats| stats.oldest_created_at)
+            .min()
+            .map(|created_at| (now - created_at).num_seconds().max(0) as u64),
+        tx_counts: stats
+            .iter()
+            .map(|stats| (stats.tx_type.clone(), stats.count as u32))
+            .collect(),
+    }
+}
+
 // Server implementation
 
 async fn get_status(data: web::Data<ApiStatusData>) -> ApiResult<NetworkStatus> {
     let start = Instant::now();
 
-    let status = data.status.read().await;
-    let network_status = NetworkStatus {
-        last_committed: status.last_committed,
-        finalized: status.last_verified,
-        total_transactions: status.total_transactions,
-        mempool_size: status.mempool_size,
-        core_status: status.core_status,
-    };
+    let network_status = NetworkStatus::from(&data.status.read().await);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "get_status");
     Ok(network_status).into()
 }
 
-pub fn api_scope(shared_status: SharedNetworkStatus) -> Scope {
+async fn mempool_status(data: web::Data<ApiMempoolStatusData>) -> ApiResult<MempoolStatus> {
+    let start = Instant::now();
+    let res = data.mempool_status().await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "mempool_status");
+    res
+}
+
+pub fn api_scope(shared_status: SharedNetworkStatus) -> ApiScope {
     let data = ApiStatusData::new(shared_status);
 
-    web::scope("networkStatus")
+    ApiScope::new("networkStatus")
+        .app_data(web::Data::new(data))
+        .route(
+            Operation::get("", "Status of the network").result::<NetworkStatus>(),
+            get_status,
+        )
+}
+
+pub fn mempool_scope(pool: ConnectionPool) -> ApiScope {
+    let data = ApiMempoolStatusData::new(pool);
+
+    ApiScope::new("status")
         .app_data(web::Data::new(data))
-        .route("", web::get().to(get_status))
+        .route(
+            Operation::get("mempool", "Depth of the mempool and its transaction types")
+                .result::<MempoolStatus>(),
+            mempool_status,
+        )
 }
 
 #[cfg(test)]
@@ -58,7 +143,7 @@ mod tests {
         SharedData,
     };
     use zksync_api_types::v02::ApiVersion;
-    use zksync_types::SequentialTxId;
+    use zksync_types::{SequentialTxId, SignedZkSyncTx, ZkSyncTx};
 
     #[actix_rt::test]
     #[cfg_attr(
@@ -118,4 +203,69 @@ mod tests {
         server.stop().await;
         Ok(())
     }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn mempool_status_scope() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let insert_txs = |txs: Vec<ZkSyncTx>| {
+            let pool = cfg.pool.clone();
+            async move {
+                let mut storage = pool.access_storage().await?;
+                for tx in txs {
+                    storage
+                        .chain()
+                        .mempool_schema()
+                        .insert_tx(&SignedZkSyncTx {
+                            tx,
+                            eth_sign_data: None,
+                            created_at: Utc::now(),
+                        })
+                        .await?;
+                }
+                Ok::<_, anyhow::Error>(())
+            }
+        };
+        // Change pubkey, 3 transfers and mint NFT.
+        let txs = TestServerConfig::gen_zk_txs(1_u64).txs;
+        insert_txs(txs.into_iter().map(|(tx, _)| tx).collect()).await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| mempool_scope(cfg.pool.clone()),
+            Some(shared_data),
+        );
+
+        let expected_status = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let stats = storage.chain().mempool_schema().get_mempool_stats().await?;
+            mempool_status_from_stats(&stats)
+        };
+        let response = client.mempool_status().await?;
+        let status: MempoolStatus = deserialize_response_result(response)?;
+        assert_eq!(status.size, expected_status.size);
+        assert_eq!(status.tx_counts, expected_status.tx_counts);
+        assert!(status.oldest_tx_age_secs.is_some());
+        assert!(status.tx_counts["Transfer"] >= 3);
+        assert!(status.tx_counts["ChangePubKey"] >= 1);
+        assert!(status.tx_counts["MintNFT"] >= 1);
+
+        // Statistics are cached, so the new transactions are not counted right away.
+        let txs = TestServerConfig::gen_zk_txs(1_u64).txs;
+        insert_txs(txs.into_iter().map(|(tx, _)| tx).collect()).await?;
+        let response = client.mempool_status().await?;
+        let cached_status: MempoolStatus = deserialize_response_result(response)?;
+        assert_eq!(cached_status.size, status.size);
+
+        server.stop().await;
+        Ok(())
+    }
 }
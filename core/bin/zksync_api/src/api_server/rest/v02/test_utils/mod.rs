@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 // External uses
-use actix_web::{web, App, Scope};
+use actix_web::{dev::HttpServiceFactory, web, App};
 use anyhow::Error;
 use bigdecimal::{BigDecimal, Zero};
 use chrono::Utc;
@@ -13,13 +13,14 @@ use futures::{channel::mpsc, StreamExt};
 use num::{rational::Ratio, BigUint};
 use once_cell::sync::Lazy;
 use serde::de::DeserializeOwned;
-use tokio::sync::Mutex;
+use tokio::{sync::Mutex, task::JoinHandle};
 
 // Workspace uses
 use zksync_api_client::rest::client::Client;
-use zksync_api_types::v02::Response;
-use zksync_config::ZkSyncConfig;
-use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
+use zksync_api_types::{v02::Response, NewApiKey, SubsidyCampaign};
+use zksync_config::{configs::api::CommonApiConfig, ZkSyncConfig};
+use zksync_crypto::rand::Rng;
+use zksync_mempool::MempoolTransactionRequest;
 use zksync_storage::{
     chain::operations::records::NewExecutedPriorityOperation,
     chain::operations::OperationsSchema,
@@ -27,16 +28,18 @@ use zksync_storage::{
     test_data::{
         dummy_ethereum_tx_hash, gen_acc_random_updates, gen_sample_block,
         gen_unique_aggregated_operation_with_txs, generate_nft, get_sample_aggregated_proof,
-        get_sample_single_proof, BLOCK_SIZE_CHUNKS,
+        get_sample_single_proof, TestRng, BLOCK_SIZE_CHUNKS,
     },
-    ConnectionPool,
+    ConnectionPool, StorageProcessor,
 };
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
+    api_keys::{api_key_hash, generate_api_key, ApiKeyScope},
     helpers::{apply_updates, closest_packable_fee_amount, closest_packable_token_amount},
-    operations::{ChangePubKeyOp, TransferToNewOp},
+    operations::{ChangePubKeyOp, TransferToNewOp, WithdrawOp},
     prover::ProverJobType,
+    tokens::TokenMarketVolume,
     tx::ChangePubKeyType,
     AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Deposit, DepositOp,
     ExecutedOperations, ExecutedPriorityOp, ExecutedTx, FullExit, FullExitOp, MintNFTOp, Nonce,
@@ -46,6 +49,7 @@ use zksync_types::{
 use zksync_utils::{big_decimal_to_ratio, scaled_u64_to_ratio, UnsignedRatioSerializeAsDecimal};
 
 // Local uses
+use crate::api_server::tx_sender::TxSender;
 use crate::fee_ticker::{
     tests::TestToken,
     ticker_info::BlocksInFutureAggregatedOperations,
@@ -55,6 +59,8 @@ use crate::fee_ticker::{
 use crate::signature_checker::{VerifiedTx, VerifySignatureRequest};
 use std::any::Any;
 
+pub mod pagination;
+
 /// Serial ID of the verified priority operation.
 pub const VERIFIED_OP_SERIAL_ID: u64 = 10;
 /// Serial ID of the committed priority operation.
@@ -65,6 +71,8 @@ pub const COMMITTED_BLOCKS_COUNT: u32 = 8;
 pub const VERIFIED_BLOCKS_COUNT: u32 = 5;
 /// Number of executed blocks.
 pub const EXECUTED_BLOCKS_COUNT: u32 = 3;
+/// Number of the executed block containing the withdrawal.
+pub const EXECUTED_WITHDRAWAL_BLOCK: u32 = 2;
 
 #[derive(Debug, Clone)]
 pub struct TestServerConfig {
@@ -88,14 +96,39 @@ pub struct TestTransactions {
 }
 
 impl TestServerConfig {
-    pub fn start_server_with_scope<F, D>(
+    /// Issues an API key with the given scopes, returns the key along with its ID.
+    pub async fn issue_api_key(
+        &self,
+        scopes: Vec<ApiKeyScope>,
+        requests_per_minute: u32,
+    ) -> anyhow::Result<(String, i64)> {
+        let key = generate_api_key();
+        let info = self
+            .pool
+            .access_storage()
+            .await?
+            .api_keys_schema()
+            .create_api_key(
+                api_key_hash(&key),
+                &NewApiKey {
+                    name: "test".to_string(),
+                    scopes,
+                    requests_per_minute,
+                },
+            )
+            .await?;
+        Ok((key, info.id))
+    }
+
+    pub fn start_server_with_scope<F, S, D>(
         &self,
         scope: String,
         scope_factory: F,
         shared_data: Option<D>,
     ) -> (Client, actix_test::TestServer)
     where
-        F: Fn(&TestServerConfig) -> Scope + Clone + Send + 'static,
+        F: Fn(&TestServerConfig) -> S + Clone + Send + 'static,
+        S: HttpServiceFactory + 'static,
         D: Clone + Send + 'static,
     {
         let this = self.clone();
@@ -117,13 +150,14 @@ impl TestServerConfig {
         (client, server)
     }
 
-    pub fn start_server<F, D>(
+    pub fn start_server<F, S, D>(
         &self,
         scope_factory: F,
         shared_data: Option<D>,
     ) -> (Client, actix_test::TestServer)
     where
-        F: Fn(&TestServerConfig) -> Scope + Clone + Send + 'static,
+        F: Fn(&TestServerConfig) -> S + Clone + Send + 'static,
+        S: HttpServiceFactory + 'static,
         D: Clone + Send + 'static,
     {
         self.start_server_with_scope(String::from("/api/v0.2"), scope_factory, shared_data)
@@ -143,7 +177,17 @@ impl TestServerConfig {
     ) -> TestTransactions {
         let from = ZkSyncAccount::rand();
         from.set_account_id(Some(AccountId(0xf00d)));
+        Self::gen_zk_txs_from(from, account_id, address, fee)
+    }
 
+    /// Creates several transactions of the `from` account to the specified account
+    /// and the corresponding executed operations.
+    pub fn gen_zk_txs_from(
+        from: ZkSyncAccount,
+        account_id: AccountId,
+        address: Address,
+        fee: u64,
+    ) -> TestTransactions {
         let mut to = ZkSyncAccount::rand();
         to.set_account_id(Some(account_id));
         to.address = address;
@@ -365,7 +409,7 @@ impl TestServerConfig {
             .await?;
 
         // Below lies the initialization of the data for the test.
-        let mut rng = XorShiftRng::from_seed([0, 1, 2, 3]);
+        let mut rng = TestRng::from_seed([0, 1, 2, 3]);
 
         // Required since we use `EthereumSchema` in this test.
         storage.ethereum_schema().initialize_eth_data().await?;
@@ -473,43 +517,13 @@ impl TestServerConfig {
 
             // Store & confirm the operation in the ethereum schema, as it's used for obtaining
             // commit/verify/execute hashes.
-            let aggregated_operation = gen_unique_aggregated_operation_with_txs(
+            confirm_aggregated_operation(
+                &mut storage,
                 block_number,
                 AggregatedActionType::CommitBlocks,
-                BLOCK_SIZE_CHUNKS,
                 txs.clone(),
-            );
-            OperationsSchema(&mut storage)
-                .store_aggregated_action(aggregated_operation)
-                .await?;
-            let (id, op) = OperationsSchema(&mut storage)
-                .get_aggregated_op_that_affects_block(
-                    AggregatedActionType::CommitBlocks,
-                    block_number,
-                )
-                .await?
-                .unwrap();
-
-            // Store the Ethereum transaction.
-            let eth_tx_hash = dummy_ethereum_tx_hash(id);
-            let response = storage
-                .ethereum_schema()
-                .save_new_eth_tx(
-                    AggregatedActionType::CommitBlocks,
-                    Some((id, op)),
-                    100,
-                    100u32.into(),
-                    Default::default(),
-                )
-                .await?;
-            storage
-                .ethereum_schema()
-                .add_hash_entry(response.id, &eth_tx_hash)
-                .await?;
-            storage
-                .ethereum_schema()
-                .confirm_eth_tx(&eth_tx_hash)
-                .await?;
+            )
+            .await?;
 
             // Add verification for the block if required.
             if *block_number <= VERIFIED_BLOCKS_COUNT {
@@ -561,82 +575,23 @@ impl TestServerConfig {
                     )
                     .await?;
 
-                let aggregated_operation = gen_unique_aggregated_operation_with_txs(
+                confirm_aggregated_operation(
+                    &mut storage,
                     block_number,
                     AggregatedActionType::PublishProofBlocksOnchain,
-                    BLOCK_SIZE_CHUNKS,
                     txs.clone(),
-                );
-                OperationsSchema(&mut storage)
-                    .store_aggregated_action(aggregated_operation)
-                    .await?;
-                let (id, op) = OperationsSchema(&mut storage)
-                    .get_aggregated_op_that_affects_block(
-                        AggregatedActionType::PublishProofBlocksOnchain,
-                        block_number,
-                    )
-                    .await?
-                    .unwrap();
-
-                let response = storage
-                    .ethereum_schema()
-                    .save_new_eth_tx(
-                        AggregatedActionType::PublishProofBlocksOnchain,
-                        Some((id, op)),
-                        100,
-                        100u32.into(),
-                        Default::default(),
-                    )
-                    .await?;
-                let eth_tx_hash = dummy_ethereum_tx_hash(id);
-                storage
-                    .ethereum_schema()
-                    .add_hash_entry(response.id, &eth_tx_hash)
-                    .await?;
-                storage
-                    .ethereum_schema()
-                    .confirm_eth_tx(&eth_tx_hash)
-                    .await?;
+                )
+                .await?;
             }
 
             if *block_number <= EXECUTED_BLOCKS_COUNT {
-                let aggregated_operation = gen_unique_aggregated_operation_with_txs(
+                confirm_aggregated_operation(
+                    &mut storage,
                     block_number,
                     AggregatedActionType::ExecuteBlocks,
-                    BLOCK_SIZE_CHUNKS,
                     txs.clone(),
-                );
-                OperationsSchema(&mut storage)
-                    .store_aggregated_action(aggregated_operation)
-                    .await?;
-                let (id, op) = OperationsSchema(&mut storage)
-                    .get_aggregated_op_that_affects_block(
-                        AggregatedActionType::ExecuteBlocks,
-                        block_number,
-                    )
-                    .await?
-                    .unwrap();
-
-                // Store the Ethereum transaction.
-                let eth_tx_hash = dummy_ethereum_tx_hash(id);
-                let response = storage
-                    .ethereum_schema()
-                    .save_new_eth_tx(
-                        AggregatedActionType::ExecuteBlocks,
-                        Some((id, op)),
-                        100,
-                        100u32.into(),
-                        Default::default(),
-                    )
-                    .await?;
-                storage
-                    .ethereum_schema()
-                    .add_hash_entry(response.id, &eth_tx_hash)
-                    .await?;
-                storage
-                    .ethereum_schema()
-                    .confirm_eth_tx(&eth_tx_hash)
-                    .await?;
+                )
+                .await?;
                 storage
                     .chain()
                     .state_schema()
@@ -645,6 +600,43 @@ impl TestServerConfig {
             }
         }
 
+        // Store the withdrawal executed on L1 for the withdrawal tests.
+        let withdraw = {
+            let account = ZkSyncAccount::rand();
+            account.set_account_id(Some(AccountId(0xf00d)));
+            let (tx, _) = account.sign_withdraw(
+                TokenId(0),
+                "ETH",
+                100_u64.into(),
+                1_u64.into(),
+                &account.address,
+                None,
+                true,
+                Default::default(),
+            );
+            let zksync_op = ZkSyncOp::Withdraw(Box::new(WithdrawOp {
+                tx,
+                account_id: account.get_account_id().unwrap(),
+            }));
+            ExecutedTx {
+                signed_tx: zksync_op.try_get_tx().unwrap().into(),
+                success: true,
+                op: Some(zksync_op),
+                fail_reason: None,
+                block_index: Some(3),
+                created_at: chrono::Utc::now(),
+                batch_id: None,
+            }
+        };
+        storage
+            .chain()
+            .block_schema()
+            .save_block_transactions(
+                BlockNumber(EXECUTED_WITHDRAWAL_BLOCK),
+                vec![ExecutedOperations::Tx(Box::new(withdraw))],
+            )
+            .await?;
+
         // Store priority operations for some tests.
         let ops = vec![
             // Verified priority operation.
@@ -655,8 +647,8 @@ impl TestServerConfig {
                     dummy_deposit_op(Address::default(), AccountId(3), VERIFIED_OP_SERIAL_ID, 2).op,
                 )
                 .unwrap(),
-                from_account: Default::default(),
-                to_account: Default::default(),
+                from_account: Address::default().as_bytes().to_vec(),
+                to_account: Address::default().as_bytes().to_vec(),
                 priority_op_serialid: VERIFIED_OP_SERIAL_ID as i64,
                 deadline_block: 100,
                 eth_hash: dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64)
@@ -665,6 +657,8 @@ impl TestServerConfig {
                 eth_block: 10,
                 created_at: chrono::Utc::now(),
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
                 tx_hash: dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64)
                     .as_bytes()
                     .to_vec(),
@@ -680,8 +674,8 @@ impl TestServerConfig {
                         .op,
                 )
                 .unwrap(),
-                from_account: Default::default(),
-                to_account: Default::default(),
+                from_account: Address::default().as_bytes().to_vec(),
+                to_account: Address::default().as_bytes().to_vec(),
                 priority_op_serialid: COMMITTED_OP_SERIAL_ID as i64,
                 deadline_block: 200,
                 eth_hash: dummy_ethereum_tx_hash(COMMITTED_OP_SERIAL_ID as i64)
@@ -690,6 +684,8 @@ impl TestServerConfig {
                 eth_block: 14,
                 created_at: chrono::Utc::now(),
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
                 tx_hash: dummy_ethereum_tx_hash(COMMITTED_OP_SERIAL_ID as i64)
                     .as_bytes()
                     .to_vec(),
@@ -729,6 +725,47 @@ impl TestServerConfig {
     }
 }
 
+/// Stores the aggregated operation of the given type for the block
+/// and confirms the corresponding Ethereum transaction.
+pub async fn confirm_aggregated_operation(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+    action_type: AggregatedActionType,
+    txs: Vec<ExecutedOperations>,
+) -> anyhow::Result<()> {
+    let aggregated_operation =
+        gen_unique_aggregated_operation_with_txs(block_number, action_type, BLOCK_SIZE_CHUNKS, txs);
+    OperationsSchema(storage)
+        .store_aggregated_action(aggregated_operation)
+        .await?;
+    let (id, op) = OperationsSchema(storage)
+        .get_aggregated_op_that_affects_block(action_type, block_number)
+        .await?
+        .unwrap();
+
+    // Store the Ethereum transaction.
+    let eth_tx_hash = dummy_ethereum_tx_hash(id);
+    let response = storage
+        .ethereum_schema()
+        .save_new_eth_tx(
+            action_type,
+            Some((id, op)),
+            100,
+            100u32.into(),
+            Default::default(),
+        )
+        .await?;
+    storage
+        .ethereum_schema()
+        .add_hash_entry(response.id, &eth_tx_hash)
+        .await?;
+    storage
+        .ethereum_schema()
+        .confirm_eth_tx(&eth_tx_hash)
+        .await?;
+    Ok(())
+}
+
 /// Creates dummy deposit priority operation.
 pub fn dummy_deposit_op(
     address: Address,
@@ -754,6 +791,8 @@ pub fn dummy_deposit_op(
             eth_hash: H256::default(),
             eth_block: 10,
             eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: None,
         },
         op: deposit_op,
         block_index,
@@ -790,6 +829,8 @@ pub fn dummy_full_exit_op(
             eth_hash: H256::default(),
             eth_block: 10,
             eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: None,
         },
         op: deposit_op,
         block_index,
@@ -829,6 +870,30 @@ pub fn dummy_sign_verifier() -> mpsc::Sender<VerifySignatureRequest> {
     sender
 }
 
+/// Mempool accepting all the submitted transactions.
+pub fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
+    let (mempool_tx_request_sender, mut mempool_tx_request_receiver) = mpsc::channel(100);
+
+    let task = tokio::spawn(async move {
+        while let Some(tx) = mempool_tx_request_receiver.next().await {
+            match tx {
+                MempoolTransactionRequest::NewTx(_, resp) => resp.send(Ok(())).unwrap_or_default(),
+                MempoolTransactionRequest::ReplaceTx(_, _, resp) => {
+                    resp.send(Ok(())).unwrap_or_default()
+                }
+                MempoolTransactionRequest::NewPriorityOps(_, _, resp) => {
+                    resp.send(Ok(())).unwrap_or_default()
+                }
+                MempoolTransactionRequest::NewTxsBatch(_, _, resp) => {
+                    resp.send(Ok(())).unwrap_or_default()
+                }
+            }
+        }
+    });
+
+    (mempool_tx_request_sender, task)
+}
+
 #[derive(Debug, Clone)]
 pub struct DummyFeeTickerInfo {
     prices: HashMap<TokenLike, BigDecimal>,
@@ -889,6 +954,10 @@ impl FeeTickerInfo for DummyFeeTickerInfo {
         })
     }
 
+    async fn get_active_subsidy_campaigns(&self) -> anyhow::Result<Vec<SubsidyCampaign>> {
+        Ok(Vec::new())
+    }
+
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
     }
@@ -932,3 +1001,47 @@ pub fn dummy_fee_ticker(
         validator,
     )
 }
+
+/// Creates the transaction sender of the tests submitting transactions.
+/// ETH is accepted for the fees, and the tokens of the test transactions have prices.
+pub fn test_tx_sender(
+    cfg: &TestServerConfig,
+    common_config: &CommonApiConfig,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+) -> TxSender {
+    let mut tokens = HashMap::new();
+    tokens.insert(
+        TokenLike::Id(TokenId(0)),
+        Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
+    );
+    let mut market = HashMap::new();
+    market.insert(
+        TokenId(0),
+        TokenMarketVolume {
+            market_volume: Ratio::from_integer(BigUint::from(400u32)),
+            last_updated: Utc::now(),
+        },
+    );
+    let cache = TokenInMemoryCache::new()
+        .with_tokens(tokens)
+        .with_market(market);
+
+    let prices = vec![
+        (TokenLike::Id(TokenId(0)), 10500_u64.into()),
+        (TokenLike::Id(TokenId(1)), 10500_u64.into()),
+        (TokenLike::Id(TokenId(2)), 10500_u64.into()),
+        (TokenLike::Id(TokenId(3)), 10500_u64.into()),
+        (TokenLike::Symbol(String::from("PHNX")), 10_u64.into()),
+        (TokenLike::Id(TokenId(15)), 10_500_u64.into()),
+        (Address::default().into(), 100000_u64.into()),
+    ];
+
+    TxSender::new(
+        cfg.pool.clone(),
+        dummy_sign_verifier(),
+        dummy_fee_ticker(&prices, Some(cache)),
+        common_config,
+        &cfg.config.api.token_config,
+        mempool_tx_sender,
+    )
+}
@@ -0,0 +1,408 @@
+//! Harness checking the invariants of the `Paginate` implementations.
+//!
+//! Given the keys of a seeded dataset, the harness walks the pages in both directions from
+//! several cursors and with several page sizes, and checks that no item is skipped or duplicated,
+//! the total count matches the dataset, and the walk is not broken by the items inserted
+//! between the page fetches. Pages are walked both by the last item of the page and by following
+//! the `next` cursor reported by the paginator.
+
+// Built-in uses
+use std::fmt::Debug;
+
+// External uses
+use serde::Serialize;
+
+// Workspace uses
+use zksync_api_types::{
+    v02::pagination::{ApiEither, Latest, Paginated, PaginationDirection},
+    Either,
+};
+use zksync_crypto::rand::Rng;
+use zksync_storage::{
+    test_data::{gen_sample_block, BLOCK_SIZE_CHUNKS},
+    StorageProcessor,
+};
+use zksync_types::{aggregated_operations::AggregatedActionType, BlockNumber, ExecutedOperations};
+
+// Local uses
+use super::confirm_aggregated_operation;
+
+/// Maximum number of the items inserted during a single walk.
+const MAX_INSERTS_PER_WALK: usize = 3;
+
+/// Keys of the loaded page along with the pagination details.
+#[derive(Debug)]
+pub struct Page<K> {
+    pub keys: Vec<K>,
+    pub count: u32,
+    pub next: Option<K>,
+    pub previous: Option<K>,
+}
+
+impl<K> Page<K> {
+    pub fn new<T: Serialize, F: Serialize>(
+        page: Paginated<T, F>,
+        key: impl Fn(&T) -> K,
+        cursor: impl Fn(F) -> K,
+    ) -> Self {
+        Self {
+            keys: page.list.iter().map(key).collect(),
+            count: page.pagination.count,
+            next: page.pagination.next.map(&cursor),
+            previous: page.pagination.previous.map(&cursor),
+        }
+    }
+}
+
+/// Paginator under test, bound to the seeded dataset.
+#[async_trait::async_trait]
+pub trait PaginationCase: Send {
+    /// Cursor of the paginated item.
+    type Key: Clone + PartialEq + Debug + Send + Sync;
+
+    /// Loads the page, `None` stands for the `latest` cursor.
+    async fn load_page(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        from: Option<Self::Key>,
+        limit: u32,
+        direction: PaginationDirection,
+    ) -> anyhow::Result<Page<Self::Key>>;
+
+    /// Inserts an item that is newer than all the existing ones.
+    async fn insert_newer(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Self::Key>;
+}
+
+/// Converts the optional cursor into the `from` query parameter, `None` stands for `latest`.
+pub fn cursor<T: Serialize>(from: Option<T>) -> ApiEither<T> {
+    match from {
+        Some(from) => ApiEither::from(from),
+        None => ApiEither {
+            inner: Either::Right(Latest),
+        },
+    }
+}
+
+/// Saves the block with the given operations next to the last committed one and confirms its commitment.
+pub async fn commit_test_block(
+    storage: &mut StorageProcessor<'_>,
+    txs: Vec<ExecutedOperations>,
+) -> anyhow::Result<BlockNumber> {
+    let block_number = storage
+        .chain()
+        .block_schema()
+        .get_last_committed_confirmed_block()
+        .await?
+        + 1;
+    storage
+        .chain()
+        .block_schema()
+        .save_full_block(gen_sample_block(
+            block_number,
+            BLOCK_SIZE_CHUNKS,
+            txs.clone(),
+        ))
+        .await?;
+    confirm_aggregated_operation(
+        storage,
+        block_number,
+        AggregatedActionType::CommitBlocks,
+        txs,
+    )
+    .await?;
+    Ok(block_number)
+}
+
+#[derive(Debug)]
+pub struct PaginationHarness<C: PaginationCase> {
+    case: C,
+    /// Keys of the dataset from the oldest to the newest.
+    keys: Vec<C::Key>,
+}
+
+impl<C: PaginationCase> PaginationHarness<C> {
+    pub fn new(case: C, keys: Vec<C::Key>) -> Self {
+        assert!(
+            keys.len() >= 3,
+            "Dataset is too small to check the pagination: {:?}",
+            keys
+        );
+        Self { case, keys }
+    }
+
+    /// Performs all the checks. Items inserted by the checks are left in the storage,
+    /// so it's expected to be a transaction which is never committed.
+    pub async fn run(mut self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        let len = self.keys.len();
+        let limits = [1, 2, 3, len - 1, len, len + 1];
+        let positions = [0, 1, len / 2, len - 2, len - 1];
+        let directions = [PaginationDirection::Newer, PaginationDirection::Older];
+
+        for &limit in &limits {
+            for &direction in &directions {
+                for &position in &positions {
+                    let from = Some(self.keys[position].clone());
+                    let expected = self.expected(from.clone(), direction);
+                    let walked = self
+                        .walk(storage, from.clone(), limit as u32, direction, false)
+                        .await?;
+                    assert_eq!(
+                        walked, expected,
+                        "Walk {:?} from {:?} with limit {}",
+                        direction, from, limit
+                    );
+                    let walked = self
+                        .walk_next(storage, from.clone(), limit as u32, direction)
+                        .await?;
+                    assert_eq!(
+                        walked, expected,
+                        "Walk {:?} by the next cursors from {:?} with limit {}",
+                        direction, from, limit
+                    );
+                }
+
+                let expected = self.expected(None, direction);
+                let walked = self
+                    .walk(storage, None, limit as u32, direction, false)
+                    .await?;
+                assert_eq!(
+                    walked, expected,
+                    "Walk {:?} from the latest with limit {}",
+                    direction, limit
+                );
+                let walked = self
+                    .walk_next(storage, None, limit as u32, direction)
+                    .await?;
+                assert_eq!(
+                    walked, expected,
+                    "Walk {:?} by the next cursors from the latest with limit {}",
+                    direction, limit
+                );
+            }
+        }
+
+        // Items inserted after the walk has started are newer, so they must not appear when walking
+        // towards the older items, and must be reached when walking towards the newer ones.
+        let from = Some(self.keys[len / 2].clone());
+        let expected = self.expected(from.clone(), PaginationDirection::Older);
+        let walked = self
+            .walk(storage, from, 2, PaginationDirection::Older, true)
+            .await?;
+        assert_eq!(walked, expected, "Walk to the older items with inserts");
+
+        let expected = self.expected(None, PaginationDirection::Older);
+        let walked = self
+            .walk(storage, None, 2, PaginationDirection::Older, true)
+            .await?;
+        assert_eq!(walked, expected, "Walk from the latest with inserts");
+
+        let from = Some(self.keys[0].clone());
+        let walked = self
+            .walk(storage, from.clone(), 2, PaginationDirection::Newer, true)
+            .await?;
+        let expected = self.expected(from, PaginationDirection::Newer);
+        assert_eq!(walked, expected, "Walk to the newer items with inserts");
+
+        Ok(())
+    }
+
+    /// Walks the pages in `rounds` random directions from random cursors with random page sizes,
+    /// so the walks not covered by [`PaginationHarness::run`] are checked across the test runs.
+    pub async fn run_random(
+        mut self,
+        storage: &mut StorageProcessor<'_>,
+        rng: &mut impl Rng,
+        rounds: usize,
+    ) -> anyhow::Result<()> {
+        let len = self.keys.len();
+        for _ in 0..rounds {
+            // The last position stands for the `latest` cursor.
+            let position = rng.gen_range(0, len + 1);
+            let from = self.keys.get(position).cloned();
+            let limit = rng.gen_range(1, len as u32 + 2);
+            let direction = if rng.gen() {
+                PaginationDirection::Newer
+            } else {
+                PaginationDirection::Older
+            };
+
+            let expected = self.expected(from.clone(), direction);
+            let walked = self
+                .walk(storage, from.clone(), limit, direction, false)
+                .await?;
+            assert_eq!(
+                walked, expected,
+                "Walk {:?} from {:?} with limit {}",
+                direction, from, limit
+            );
+            let walked = self
+                .walk_next(storage, from.clone(), limit, direction)
+                .await?;
+            assert_eq!(
+                walked, expected,
+                "Walk {:?} by the next cursors from {:?} with limit {}",
+                direction, from, limit
+            );
+        }
+        Ok(())
+    }
+
+    fn position(&self, from: Option<&C::Key>) -> usize {
+        match from {
+            Some(from) => self
+                .keys
+                .iter()
+                .position(|key| key == from)
+                .expect("Cursor is not in the dataset"),
+            // Walk towards the newer items from the `latest` cursor yields only the latest item.
+            None => self.keys.len() - 1,
+        }
+    }
+
+    /// Returns the keys expected to be walked through from the given cursor.
+    fn expected(&self, from: Option<C::Key>, direction: PaginationDirection) -> Vec<C::Key> {
+        let position = self.position(from.as_ref());
+        match direction {
+            PaginationDirection::Newer => self.keys[position..].to_vec(),
+            PaginationDirection::Older => self.keys[..=position].iter().rev().cloned().collect(),
+        }
+    }
+
+    /// Walks the pages until the end and checks the invariants of every page.
+    /// If `insert` is set, new items are inserted between the page fetches.
+    async fn walk(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        from: Option<C::Key>,
+        limit: u32,
+        direction: PaginationDirection,
+        insert: bool,
+    ) -> anyhow::Result<Vec<C::Key>> {
+        let mut walked: Vec<C::Key> = Vec::new();
+        let mut cursor = from;
+        let mut page_limit = limit;
+        let mut inserted = 0;
+        loop {
+            let Page {
+                keys: page, count, ..
+            } = self
+                .case
+                .load_page(storage, cursor.clone(), page_limit, direction)
+                .await?;
+            assert!(
+                page.len() <= page_limit as usize,
+                "Page of {} items exceeds the limit {}",
+                page.len(),
+                page_limit
+            );
+            assert_eq!(
+                count as usize,
+                self.keys.len(),
+                "Count doesn't match the dataset size"
+            );
+
+            let mut page = page.into_iter();
+            if let Some(last) = walked.last() {
+                // The cursor is inclusive, so the page must start exactly at the last walked item.
+                assert_eq!(
+                    page.next().as_ref(),
+                    Some(last),
+                    "Page doesn't start at the cursor"
+                );
+            }
+            let len_before = walked.len();
+            walked.extend(page);
+            if walked.len() == len_before {
+                break;
+            }
+
+            // The next page is requested from the last walked item, which is returned again,
+            // so the limit is increased to keep the amount of the new items per page.
+            cursor = walked.last().cloned();
+            page_limit = limit + 1;
+
+            if insert && inserted < MAX_INSERTS_PER_WALK {
+                let key = self.case.insert_newer(storage).await?;
+                self.keys.push(key);
+                inserted += 1;
+            }
+        }
+
+        for (idx, key) in walked.iter().enumerate() {
+            assert!(
+                !walked[..idx].contains(key),
+                "Item {:?} is walked through twice",
+                key
+            );
+        }
+        Ok(walked)
+    }
+    /// Walks the pages by following the `next` cursors until the last page, which has none.
+    /// Every page must point back to the last item of the preceding one with the `previous` cursor.
+    async fn walk_next(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        from: Option<C::Key>,
+        limit: u32,
+        direction: PaginationDirection,
+    ) -> anyhow::Result<Vec<C::Key>> {
+        // Item preceding the cursor in the walk direction, if any.
+        let position = self.position(from.as_ref());
+        let mut expected_previous = match direction {
+            PaginationDirection::Newer => position.checked_sub(1),
+            PaginationDirection::Older => Some(position + 1).filter(|&idx| idx < self.keys.len()),
+        }
+        .map(|idx| self.keys[idx].clone());
+
+        let mut walked: Vec<C::Key> = Vec::new();
+        let mut cursor = from;
+        loop {
+            let page = self
+                .case
+                .load_page(storage, cursor.clone(), limit, direction)
+                .await?;
+            assert!(
+                page.keys.len() <= limit as usize,
+                "Page of {} items exceeds the limit {}",
+                page.keys.len(),
+                limit
+            );
+            assert_eq!(
+                page.count as usize,
+                self.keys.len(),
+                "Count doesn't match the dataset size"
+            );
+            if let Some(cursor) = &cursor {
+                assert_eq!(
+                    page.keys.first(),
+                    Some(cursor),
+                    "Page doesn't start at the cursor"
+                );
+            }
+            assert_eq!(
+                page.previous, expected_previous,
+                "Page from {:?} points back to the wrong item",
+                cursor
+            );
+
+            expected_previous = page.keys.last().cloned();
+            walked.extend(page.keys);
+            match page.next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        for (idx, key) in walked.iter().enumerate() {
+            assert!(
+                !walked[..idx].contains(key),
+                "Item {:?} is walked through twice",
+                key
+            );
+        }
+        Ok(walked)
+    }
+}
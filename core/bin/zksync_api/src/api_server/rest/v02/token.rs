@@ -1,39 +1,42 @@
 //! Tokens part of API implementation.
 
 // Built-in uses
-use std::str::FromStr;
 use std::time::Instant;
 
 // External uses
-use actix_web::{
-    web::{self},
-    Scope,
-};
+use actix_web::web::{self};
 use bigdecimal::{BigDecimal, Zero};
+use chrono::Utc;
 use num::{rational::Ratio, BigUint, FromPrimitive};
 
 // Workspace uses
 use zksync_api_types::v02::{
-    pagination::{parse_query, ApiEither, Paginated, PaginationQuery},
-    token::{ApiNFT, ApiToken, TokenPrice},
+    pagination::{ApiEither, CountOnlyQuery, Paginated, PaginationQuery},
+    token::{ApiNFT, ApiToken, TokenPrice, TokenVolume, TokenVolumeQuery, VolumeWindow},
 };
 use zksync_config::ZkSyncConfig;
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{tx::TxHash, AccountId, Token, TokenId, TokenLike};
+use zksync_utils::ratio_to_big_decimal;
 
 // Local uses
 use super::{
-    error::{Error, InvalidDataError},
-    paginate_trait::Paginate,
+    error::{Error, ErrorCode, InvalidDataError},
+    page_limit::parse_query,
+    paginate_trait::{Paginate, PaginateCount},
     response::ApiResult,
+    spec::{ApiScope, Operation},
 };
 use crate::{
     api_try,
     fee_ticker::{FeeTicker, PriceError, TokenPriceRequestType},
 };
 
+/// Precision of the USD value of the token volume.
+const USD_VALUE_PRECISION: usize = 6;
+
 /// Shared data between `api/v0.2/tokens` endpoints.
 #[derive(Clone)]
 struct ApiTokenData {
@@ -41,6 +44,8 @@ struct ApiTokenData {
     fee_ticker: FeeTicker,
     tokens: TokenDBCache,
     pool: ConnectionPool,
+    /// Maximum number of items on a page of the paginated endpoints.
+    max_page_limit: u32,
 }
 
 impl ApiTokenData {
@@ -58,6 +63,7 @@ impl ApiTokenData {
             pool,
             tokens,
             fee_ticker,
+            max_page_limit: config.api.rest.max_page_limit,
         }
     }
 }
@@ -79,12 +85,16 @@ impl ApiTokenData {
     async fn token_page(
         &self,
         query: PaginationQuery<ApiEither<TokenId>>,
+        count_only: bool,
     ) -> Result<Paginated<ApiToken, TokenId>, Error> {
-        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
-        let paginated_tokens: Result<Paginated<Token, TokenId>, Error> =
-            storage.paginate_checked(&query).await;
+        let paginated_tokens: Result<Paginated<Token, TokenId>, Error> = if count_only {
+            self.pool.clone().paginate_count(&query).await
+        } else {
+            self.pool.clone().paginate(&query).await
+        };
         match paginated_tokens {
             Ok(paginated_tokens) => {
+                let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
                 let tokens_to_check: Vec<TokenId> =
                     paginated_tokens.list.iter().map(|token| token.id).collect();
                 let tokens_enabled_for_fees = storage
@@ -100,13 +110,10 @@ impl ApiTokenData {
                         ApiToken::from_token_and_eligibility(token, eligibility)
                     })
                     .collect();
-                Ok(Paginated::new(
+                Ok(Paginated {
                     list,
-                    paginated_tokens.pagination.from,
-                    paginated_tokens.pagination.limit,
-                    paginated_tokens.pagination.direction,
-                    paginated_tokens.pagination.count,
-                ))
+                    pagination: paginated_tokens.pagination,
+                })
             }
             Err(err) => Err(err),
         }
@@ -152,38 +159,84 @@ impl ApiTokenData {
         ))
     }
 
+    async fn token_volume(
+        &self,
+        token_like: TokenLike,
+        window: VolumeWindow,
+    ) -> Result<TokenVolume, Error> {
+        let token = self.token(token_like).await?;
+        let mut storage = self.pool.access_storage().await.map_err(Error::storage)?;
+
+        let volume = storage
+            .chain()
+            .stats_schema()
+            .load_token_volume(token.id, Utc::now() - window.duration())
+            .await
+            .map_err(Error::storage)?;
+        let price = storage
+            .tokens_schema()
+            .get_historical_ticker_price(token.id)
+            .await
+            .map_err(Error::storage)?;
+
+        let amount = &volume.transfer + &volume.withdraw;
+        // The price is stored for the whole token, while the amount is in the smallest units.
+        let usd_value = price.map(|price| {
+            let token_amount = Ratio::new(
+                amount.clone(),
+                BigUint::from(10u32).pow(token.decimals as u32),
+            );
+            ratio_to_big_decimal(&(price.usd_price * token_amount), USD_VALUE_PRECISION)
+        });
+
+        Ok(TokenVolume {
+            token_id: token.id,
+            token_symbol: token.symbol,
+            decimals: token.decimals,
+            window,
+            transfer_amount: volume.transfer,
+            withdraw_amount: volume.withdraw,
+            amount,
+            usd_value,
+        })
+    }
+
     async fn token_price_usd(&self, token: TokenLike) -> Result<BigDecimal, Error> {
         self.fee_ticker
             .get_token_price(token, TokenPriceRequestType::USDForOneToken)
             .await
-            .map_err(Error::storage)
+            .map_err(Error::from)
     }
+
+    /// Returns the price of the token either in USD or in another token.
+    ///
+    /// The ticker rounds USD prices to 100 fractional digits. The price in another token is
+    /// the quotient of the two USD prices, which `BigDecimal` division computes with
+    /// 100 significant digits, so it is not rounded any further.
     // TODO: take `currency` as enum. (ZKS-628)
     async fn token_price_in(
         &self,
         first_token: TokenLike,
         currency: &str,
     ) -> Result<BigDecimal, Error> {
-        if let Ok(second_token_id) = u32::from_str(currency) {
-            let second_token = TokenLike::from(TokenId(second_token_id));
-            let first_usd_price = self.token_price_usd(first_token).await;
-            let second_usd_price = self.token_price_usd(second_token).await;
-            match (first_usd_price, second_usd_price) {
-                (Ok(first_usd_price), Ok(second_usd_price)) => {
-                    if second_usd_price.is_zero() {
-                        Err(Error::from(InvalidDataError::TokenZeroPriceError))
-                    } else {
-                        Ok(first_usd_price / second_usd_price)
-                    }
-                }
-                (Err(err), _) => Err(err),
-                (_, Err(err)) => Err(err),
-            }
+        if currency == "usd" {
+            return self.token_price_usd(first_token).await;
+        }
+
+        let second_token = TokenLike::parse(currency);
+        if let Err(err) = self.token(second_token.clone()).await {
+            return Err(if err.code == ErrorCode::TokenNotFound {
+                Error::from(InvalidDataError::InvalidCurrency)
+            } else {
+                err
+            });
+        }
+        let first_usd_price = self.token_price_usd(first_token).await?;
+        let second_usd_price = self.token_price_usd(second_token).await?;
+        if second_usd_price.is_zero() {
+            Err(Error::from(InvalidDataError::TokenZeroPriceError))
         } else {
-            match currency {
-                "usd" => self.token_price_usd(first_token).await,
-                _ => Err(Error::from(InvalidDataError::InvalidCurrency)),
-            }
+            Ok(first_usd_price / second_usd_price)
         }
     }
 }
@@ -193,10 +246,11 @@ impl ApiTokenData {
 async fn token_pagination(
     data: web::Data<ApiTokenData>,
     web::Query(query): web::Query<PaginationQuery<String>>,
+    web::Query(CountOnlyQuery { count_only }): web::Query<CountOnlyQuery>,
 ) -> ApiResult<Paginated<ApiToken, TokenId>> {
     let start = Instant::now();
-    let query = api_try!(parse_query(query).map_err(Error::from));
-    let res = data.token_page(query).await.into();
+    let query = api_try!(parse_query(query, data.max_page_limit));
+    let res = data.token_page(query, count_only).await.into();
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_pagination");
     res
 }
@@ -235,6 +289,18 @@ async fn token_price(
     })
 }
 
+async fn token_volume(
+    data: web::Data<ApiTokenData>,
+    token_like_string: web::Path<String>,
+    web::Query(query): web::Query<TokenVolumeQuery>,
+) -> ApiResult<TokenVolume> {
+    let start = Instant::now();
+    let token_like = TokenLike::parse(&token_like_string);
+    let res = data.token_volume(token_like, query.window).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "token_volume");
+    res
+}
+
 async fn get_nft(
     data: web::Data<ApiTokenData>,
     id: web::Path<TokenId>,
@@ -288,27 +354,68 @@ async fn get_nft_id_by_tx_hash(
     ApiResult::Ok(nft_id)
 }
 
+const TOKEN_PARAM: &str = "token_like";
+const TOKEN_PARAM_DESCRIPTION: &str = "Id, address or symbol of the token";
+
 pub fn api_scope(
     config: &ZkSyncConfig,
     pool: ConnectionPool,
     tokens_db: TokenDBCache,
     fee_ticker: FeeTicker,
-) -> Scope {
+) -> ApiScope {
     let data = ApiTokenData::new(config, pool, tokens_db, fee_ticker);
 
-    web::scope("tokens")
+    ApiScope::new("tokens")
         .app_data(web::Data::new(data))
-        .route("", web::get().to(token_pagination))
-        .route("{token_like}", web::get().to(token_info))
         .route(
-            "{token_like}/priceIn/{currency}",
-            web::get().to(token_price),
+            Operation::get("", "Tokens of the network")
+                .query::<PaginationQuery<String>>()
+                .result::<Paginated<ApiToken, u32>>(),
+            token_pagination,
+        )
+        .route(
+            Operation::get("{token_like}", "Token info")
+                .path_param(TOKEN_PARAM, TOKEN_PARAM_DESCRIPTION)
+                .result::<ApiToken>(),
+            token_info,
+        )
+        .route(
+            Operation::get("{token_like}/priceIn/{currency}", "Price of the token")
+                .path_param(TOKEN_PARAM, TOKEN_PARAM_DESCRIPTION)
+                .path_param(
+                    "currency",
+                    "`usd` or id of the token to express the price in",
+                )
+                .result::<TokenPrice>(),
+            token_price,
+        )
+        .route(
+            Operation::get("{token_like}/volume", "Traded volume of the token")
+                .path_param(TOKEN_PARAM, TOKEN_PARAM_DESCRIPTION)
+                .query::<TokenVolumeQuery>()
+                .result::<TokenVolume>(),
+            token_volume,
+        )
+        .route(
+            Operation::get("nft/{id}", "NFT info")
+                .path_param("id", "Id of the NFT")
+                .result::<Option<ApiNFT>>(),
+            get_nft,
+        )
+        .route(
+            Operation::get("nft/{id}/owner", "Id of the account owning the NFT")
+                .path_param("id", "Id of the NFT")
+                .result::<Option<u32>>(),
+            get_nft_owner,
         )
-        .route("nft/{id}", web::get().to(get_nft))
-        .route("nft/{id}/owner", web::get().to(get_nft_owner))
         .route(
-            "nft_id_by_tx_hash/{tx_hash}",
-            web::get().to(get_nft_id_by_tx_hash),
+            Operation::get(
+                "nft_id_by_tx_hash/{tx_hash}",
+                "Id of the NFT minted by the transaction",
+            )
+            .path_param("tx_hash", "Hash of the mint transaction")
+            .result::<Option<u32>>(),
+            get_nft_id_by_tx_hash,
         )
 }
 
@@ -403,7 +510,7 @@ mod tests {
         let expected_pagination = {
             let mut storage = cfg.pool.access_storage().await?;
             let paginated_tokens: Paginated<Token, TokenId> = storage
-                .paginate_checked(&query)
+                .paginate(&query)
                 .await
                 .map_err(|err| anyhow::anyhow!(err.message))?;
             let mut list = Vec::new();
@@ -415,13 +522,10 @@ mod tests {
                     enabled_for_fees,
                 ));
             }
-            Paginated::new(
+            Paginated {
                 list,
-                paginated_tokens.pagination.from,
-                paginated_tokens.pagination.limit,
-                paginated_tokens.pagination.direction,
-                paginated_tokens.pagination.count,
-            )
+                pagination: paginated_tokens.pagination,
+            }
         };
         assert_eq!(pagination, expected_pagination);
 
@@ -453,8 +557,36 @@ mod tests {
         let price_in_usd: TokenPrice = deserialize_response_result(response)?;
         assert_eq!(price_in_usd, expected_token_price);
 
-        let response = client.token_price(&token_like, "333").await?;
-        assert!(response.error.is_some());
+        // The currency can be set by the symbol of the token as well.
+        let token_like = TokenLike::Id(TokenId(15));
+        let token = {
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .tokens_schema()
+                .get_token(token_like.clone())
+                .await?
+                .unwrap()
+        };
+        let response = client.token_price(&token_like, "PHNX").await?;
+        let price_in_symbol: TokenPrice = deserialize_response_result(response)?;
+        assert_eq!(
+            price_in_symbol,
+            TokenPrice {
+                token_id: token.id,
+                token_symbol: token.symbol,
+                price_in: String::from("PHNX"),
+                decimals: token.decimals,
+                price: BigDecimal::from_u32(10_500).unwrap() / BigDecimal::from_u32(10).unwrap(),
+            }
+        );
+
+        for currency in ["333", "UNKNOWN", "eur"] {
+            let response = client.token_price(&token_like, currency).await?;
+            assert_eq!(
+                response.error.unwrap()["code"],
+                serde_json::to_value(ErrorCode::InvalidCurrency)?
+            );
+        }
 
         let nft_id = TokenId(65542);
         let response = client.nft_by_id(nft_id).await?;
@@ -500,6 +632,95 @@ mod tests {
         let nft_id: Option<TokenId> = deserialize_response_result(response)?;
         assert!(nft_id.is_some());
 
+        server.stop().await;
+        Ok(())
+    }
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tokens_page_limit() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let fee_ticker = dummy_fee_ticker(&[], None);
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    &cfg.config,
+                    cfg.pool.clone(),
+                    TokenDBCache::new(cfg.config.api.token_config.invalidate_token_cache_period()),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let max = cfg.config.api.rest.max_page_limit;
+        let invalid = Some(ErrorCode::PaginationLimitTooBig);
+        for (limit, expected_error) in [(max, None), (max + 1, invalid.clone()), (0, invalid)] {
+            let query = PaginationQuery {
+                from: ApiEither::from(TokenId(0)),
+                limit,
+                direction: PaginationDirection::Newer,
+            };
+            let response = client.token_pagination(&query).await?;
+            let error = response
+                .error
+                .map(|error| serde_json::from_value::<Error>(error).unwrap().code);
+            assert_eq!(error, expected_error, "limit {}", limit);
+        }
+
+        server.stop().await;
+        Ok(())
+    }
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tokens_count_only() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let fee_ticker = dummy_fee_ticker(&[], None);
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    &cfg.config,
+                    cfg.pool.clone(),
+                    TokenDBCache::new(cfg.config.api.token_config.invalidate_token_cache_period()),
+                    fee_ticker.clone(),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let query = PaginationQuery {
+            from: ApiEither::from(TokenId(0)),
+            limit: 2,
+            direction: PaginationDirection::Newer,
+        };
+        let response = client.token_pagination(&query).await?;
+        let page: Paginated<ApiToken, TokenId> = deserialize_response_result(response)?;
+        assert!(!page.list.is_empty());
+
+        let response = client.token_count(&query).await?;
+        let count_only: Paginated<ApiToken, TokenId> = deserialize_response_result(response)?;
+        assert!(count_only.list.is_empty());
+        assert_eq!(count_only.pagination.count, page.pagination.count);
+        assert_eq!(count_only.pagination.from, TokenId(0));
+        assert_eq!(count_only.pagination.next, None);
+
         server.stop().await;
         Ok(())
     }
@@ -1,37 +1,75 @@
 //! Transactions part of API implementation.
 
 // Built-in uses
-use std::time::Instant;
-// External uses
-use actix_web::{
-    web::{self, Json},
-    Scope,
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Instant,
 };
+// External uses
+use actix_web::web::{self, Json};
 
 // Workspace uses
 use zksync_api_types::{
     v02::transaction::{
-        ApiTxBatch, IncomingTxBatch, L1Receipt, L1Transaction, Receipt, SubmitBatchResponse,
-        Toggle2FA, Toggle2FAResponse, Transaction, TransactionData, TxData, TxHashSerializeWrapper,
-        TxInBlockStatus,
+        ApiTxBatch, FinalityStage, IncomingTxBatch, L1Receipt, L1Transaction, L1TxContext,
+        L2Transaction, Receipt, SubmissionLimits, SubmitBatchResponse, SubmitTxResponse, Toggle2FA,
+        Toggle2FAResponse, Transaction, TransactionData, TxData, TxFinality,
+        TxHashSerializeWrapper, TxInBlockStatus, WithdrawalStatus,
     },
     TxWithSignature,
 };
-use zksync_types::{tx::TxHash, EthBlockId};
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, tx::TxHash, BlockNumber, EthBlockId, PriorityOp,
+    H256,
+};
 
 // Local uses
-use super::{error::Error, response::ApiResult};
-use crate::api_server::tx_sender::{SubmitError, TxSender};
+use super::{
+    block::block_info_from_details,
+    error::{Error, TxError},
+    response::{ApiResult, CachePolicy, CachedApiResult},
+    spec::{ApiScope, Operation},
+};
+use crate::{
+    api_server::{
+        helpers::parse_tx_hash,
+        tx_sender::{SubmitError, TxSender},
+    },
+    api_try,
+};
+
+/// Maximum amount of the transactions in a single status request.
+pub const MAX_TX_STATUSES_PER_REQUEST: usize = 100;
+
+/// Receipt of the priority operation waiting in the mempool.
+fn pending_op_receipt(op: PriorityOp) -> Receipt {
+    Receipt::L1(L1Receipt {
+        status: TxInBlockStatus::Queued,
+        eth_block: EthBlockId(op.eth_block),
+        rollup_block: None,
+        id: op.serial_id,
+        eth_sender: Some(op.data.from_account()),
+        eth_block_hash: op.eth_block_hash,
+        l1_surplus: op.l1_surplus,
+    })
+}
 
 /// Shared data between `api/v0.2/transactions` endpoints.
 #[derive(Clone)]
 struct ApiTransactionData {
     tx_sender: TxSender,
+    /// Used to count the confirmations of the Ethereum transactions of the operations.
+    eth_client: EthereumGateway,
 }
 
 impl ApiTransactionData {
-    fn new(tx_sender: TxSender) -> Self {
-        Self { tx_sender }
+    fn new(tx_sender: TxSender, eth_client: EthereumGateway) -> Self {
+        Self {
+            tx_sender,
+            eth_client,
+        }
     }
 
     async fn tx_status(&self, tx_hash: TxHash) -> Result<Option<Receipt>, Error> {
@@ -61,12 +99,7 @@ impl ApiTransactionData {
             .await
             .map_err(Error::core_api)?
         {
-            Ok(Some(Receipt::L1(L1Receipt {
-                status: TxInBlockStatus::Queued,
-                eth_block: EthBlockId(op.eth_block),
-                rollup_block: None,
-                id: op.serial_id,
-            })))
+            Ok(Some(pending_op_receipt(op)))
         }
         // 3. No operation found, return nothing.
         else {
@@ -74,6 +107,54 @@ impl ApiTransactionData {
         }
     }
 
+    /// Same as `tx_status`, but for several transactions loaded with a fixed number of queries.
+    async fn tx_statuses(
+        &self,
+        mut tx_hashes: Vec<TxHash>,
+    ) -> Result<BTreeMap<TxHashSerializeWrapper, Option<Receipt>>, Error> {
+        tx_hashes.sort_unstable();
+        tx_hashes.dedup();
+        let mut storage = self
+            .tx_sender
+            .pool
+            .access_storage()
+            .await
+            .map_err(Error::storage)?;
+
+        let mut receipts = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipts_api_v02(&tx_hashes)
+            .await
+            .map_err(Error::storage)?;
+        let not_executed: Vec<H256> = tx_hashes
+            .iter()
+            .filter(|tx_hash| !receipts.contains_key(tx_hash))
+            .map(|tx_hash| (*tx_hash).into())
+            .collect();
+        let mut pending_ops = storage
+            .chain()
+            .mempool_schema()
+            .get_pending_operations_by_hashes(&not_executed)
+            .await
+            .map_err(Error::core_api)?
+            .into_iter()
+            .map(|op| (op.eth_hash, op))
+            .collect::<HashMap<_, _>>();
+
+        Ok(tx_hashes
+            .into_iter()
+            .map(|tx_hash| {
+                let receipt = receipts.remove(&tx_hash).or_else(|| {
+                    pending_ops
+                        .remove(&H256::from(tx_hash))
+                        .map(pending_op_receipt)
+                });
+                (TxHashSerializeWrapper(tx_hash), receipt)
+            })
+            .collect())
+    }
+
     async fn tx_data(&self, tx_hash: TxHash) -> Result<Option<TxData>, Error> {
         let mut storage = self
             .tx_sender
@@ -97,6 +178,7 @@ impl ApiTransactionData {
             .map_err(Error::core_api)?
         {
             let tx_hash = op.tx_hash();
+            let l1_context = L1TxContext::from_pending_op(&op);
             let tx = Transaction {
                 tx_hash,
                 block_index: None,
@@ -109,6 +191,7 @@ impl ApiTransactionData {
                 )),
                 status: TxInBlockStatus::Queued,
                 fail_reason: None,
+                fail_reason_code: None,
                 created_at: None,
                 batch_id: None,
             };
@@ -116,12 +199,190 @@ impl ApiTransactionData {
             Ok(Some(TxData {
                 tx,
                 eth_signature: None,
+                l1_context: Some(l1_context),
+                nft: None,
             }))
         } else {
             Ok(None)
         }
     }
 
+    async fn tx_finality(&self, tx_hash: TxHash) -> Result<Option<TxFinality>, Error> {
+        let rollup_block = match self.tx_status(tx_hash).await? {
+            Some(Receipt::L1(receipt)) => receipt.rollup_block,
+            // Failed transaction doesn't become a part of the block it's stored with.
+            Some(Receipt::L2(receipt)) if receipt.status == TxInBlockStatus::Rejected => None,
+            Some(Receipt::L2(receipt)) => receipt.rollup_block,
+            None => return Ok(None),
+        };
+        let mut finality = TxFinality {
+            tx_hash,
+            rollup_block,
+            commit: None,
+            prove: None,
+            execute: None,
+        };
+
+        if let Some(block_number) = rollup_block {
+            let mut storage = self
+                .tx_sender
+                .pool
+                .access_storage()
+                .await
+                .map_err(Error::storage)?;
+            // Confirmations of all the stages are counted against the same L1 block.
+            let current_eth_block = self
+                .eth_client
+                .block_number()
+                .await
+                .map_err(Error::from)?
+                .as_u64();
+            finality.commit = self
+                .finality_stage(
+                    &mut storage,
+                    block_number,
+                    AggregatedActionType::CommitBlocks,
+                    current_eth_block,
+                )
+                .await?;
+            finality.prove = self
+                .finality_stage(
+                    &mut storage,
+                    block_number,
+                    AggregatedActionType::PublishProofBlocksOnchain,
+                    current_eth_block,
+                )
+                .await?;
+            finality.execute = self
+                .finality_stage(
+                    &mut storage,
+                    block_number,
+                    AggregatedActionType::ExecuteBlocks,
+                    current_eth_block,
+                )
+                .await?;
+        }
+        Ok(Some(finality))
+    }
+
+    async fn withdrawal_status(&self, tx_hash: TxHash) -> Result<Option<WithdrawalStatus>, Error> {
+        let tx = match self.tx_data(tx_hash).await? {
+            Some(data) => data.tx,
+            None => return Ok(None),
+        };
+        match tx.op {
+            TransactionData::L2(L2Transaction::Withdraw(_))
+            | TransactionData::L2(L2Transaction::WithdrawNFT(_))
+            | TransactionData::L2(L2Transaction::ForcedExit(_)) => {}
+            _ => return Err(Error::from(TxError::NotWithdrawal)),
+        }
+        // Failed transaction doesn't become a part of the block it's stored with.
+        let rollup_block = if tx.status == TxInBlockStatus::Rejected {
+            None
+        } else {
+            tx.block_number
+        };
+        let mut status = WithdrawalStatus {
+            tx_hash,
+            rollup_block,
+            executed_on_l1: false,
+            eth_tx_hash: None,
+            eth_tx_sent_at: None,
+        };
+
+        if let Some(block_number) = rollup_block {
+            let mut storage = self
+                .tx_sender
+                .pool
+                .access_storage()
+                .await
+                .map_err(Error::storage)?;
+            if let Some(eth_op) = storage
+                .ethereum_schema()
+                .confirmed_execute_operation(block_number)
+                .await
+                .map_err(Error::storage)?
+            {
+                status.executed_on_l1 = true;
+                status.eth_tx_hash = eth_op.final_hash.map(|hash| H256::from_slice(&hash));
+                status.eth_tx_sent_at = eth_op.created_at;
+            } else if let Some(eth_tx_hash) = storage
+                .chain()
+                .operations_schema()
+                .eth_tx_for_withdrawal(&tx_hash)
+                .await
+                .map_err(Error::storage)?
+            {
+                // Blocks executed by the old contracts had their withdrawals completed
+                // by a separate `CompleteWithdrawals` operation.
+                status.executed_on_l1 = true;
+                status.eth_tx_hash = Some(eth_tx_hash);
+            }
+        }
+        Ok(Some(status))
+    }
+
+    /// Returns the confirmed Ethereum transaction of the aggregated operation covering the block,
+    /// along with the number of the L1 blocks mined since the one including it.
+    async fn finality_stage(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+        action_type: AggregatedActionType,
+        current_eth_block: u64,
+    ) -> Result<Option<FinalityStage>, Error> {
+        let op = match storage
+            .chain()
+            .operations_schema()
+            .get_stored_aggregated_operation(block_number, action_type)
+            .await
+        {
+            Some(op) => op,
+            None => return Ok(None),
+        };
+        let eth_tx_hash = match storage
+            .ethereum_schema()
+            .get_aggregated_op_confirmed_hash(op.id)
+            .await
+            .map_err(Error::storage)?
+        {
+            Some(eth_tx_hash) => eth_tx_hash,
+            None => return Ok(None),
+        };
+        // The transaction unknown to the Ethereum node (e.g. reorganized out) has no confirmations.
+        let confirmations = self
+            .eth_client
+            .get_tx_status(eth_tx_hash, Some(current_eth_block))
+            .await
+            .map_err(Error::from)?
+            .map_or(0, |status| status.confirmations);
+        Ok(Some(FinalityStage {
+            eth_tx_hash,
+            confirmations,
+        }))
+    }
+
+    /// Transactions of the finalized blocks never change, so they are cached along with the block.
+    async fn cache_policy(
+        &self,
+        finalized_block: Option<BlockNumber>,
+    ) -> Result<CachePolicy, Error> {
+        let block_number = match finalized_block {
+            Some(block_number) => block_number,
+            None => return Ok(CachePolicy::NoCache),
+        };
+        let block_info = self
+            .tx_sender
+            .blocks
+            .get(&self.tx_sender.pool, block_number)
+            .await
+            .map_err(Error::storage)?
+            .map(block_info_from_details);
+        Ok(block_info
+            .as_ref()
+            .map_or(CachePolicy::NoCache, CachePolicy::for_block))
+    }
+
     async fn get_batch(&self, batch_hash: TxHash) -> Result<Option<ApiTxBatch>, Error> {
         let mut storage = self
             .tx_sender
@@ -142,35 +403,94 @@ impl ApiTransactionData {
 
 async fn tx_status(
     data: web::Data<ApiTransactionData>,
-    tx_hash: web::Path<TxHash>,
-) -> ApiResult<Option<Receipt>> {
+    tx_hash: web::Path<String>,
+) -> CachedApiResult<Option<Receipt>> {
     let start = Instant::now();
-    let res = data.tx_status(*tx_hash).await.into();
+    let tx_hash = api_try!(parse_tx_hash(&tx_hash).map_err(Error::from));
+    let receipt = api_try!(data.tx_status(tx_hash).await);
+    let finalized_block = match &receipt {
+        Some(Receipt::L1(receipt)) if receipt.status == TxInBlockStatus::Finalized => {
+            receipt.rollup_block
+        }
+        Some(Receipt::L2(receipt)) if receipt.status == TxInBlockStatus::Finalized => {
+            receipt.rollup_block
+        }
+        _ => None,
+    };
+    let policy = api_try!(data.cache_policy(finalized_block).await);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_status");
+    ApiResult::Ok(receipt).with_cache_policy(policy)
+}
+
+/// Statuses of several transactions at once, keyed by the requested hashes.
+/// Unknown transactions have no receipt.
+async fn tx_statuses(
+    data: web::Data<ApiTransactionData>,
+    Json(tx_hashes): Json<Vec<String>>,
+) -> ApiResult<BTreeMap<TxHashSerializeWrapper, Option<Receipt>>> {
+    let start = Instant::now();
+    if tx_hashes.len() > MAX_TX_STATUSES_PER_REQUEST {
+        return Error::from(TxError::TooManyHashes(tx_hashes.len())).into();
+    }
+    let tx_hashes = api_try!(tx_hashes
+        .iter()
+        .map(|tx_hash| parse_tx_hash(tx_hash))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::from));
+    let res = data.tx_statuses(tx_hashes).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_statuses");
     res
 }
 
 async fn tx_data(
     data: web::Data<ApiTransactionData>,
-    tx_hash: web::Path<TxHash>,
-) -> ApiResult<Option<TxData>> {
+    tx_hash: web::Path<String>,
+) -> CachedApiResult<Option<TxData>> {
     let start = Instant::now();
-    let res = data.tx_data(*tx_hash).await.into();
+    let tx_hash = api_try!(parse_tx_hash(&tx_hash).map_err(Error::from));
+    let tx_data = api_try!(data.tx_data(tx_hash).await);
+    let finalized_block = match &tx_data {
+        Some(tx_data) if tx_data.tx.status == TxInBlockStatus::Finalized => tx_data.tx.block_number,
+        _ => None,
+    };
+    let policy = api_try!(data.cache_policy(finalized_block).await);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_data");
+    ApiResult::Ok(tx_data).with_cache_policy(policy)
+}
+
+async fn tx_finality(
+    data: web::Data<ApiTransactionData>,
+    tx_hash: web::Path<String>,
+) -> ApiResult<Option<TxFinality>> {
+    let start = Instant::now();
+    let tx_hash = api_try!(parse_tx_hash(&tx_hash).map_err(Error::from));
+    let res = data.tx_finality(tx_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "tx_finality");
+    res
+}
+
+async fn withdrawal_status(
+    data: web::Data<ApiTransactionData>,
+    tx_hash: web::Path<String>,
+) -> ApiResult<Option<WithdrawalStatus>> {
+    let start = Instant::now();
+    let tx_hash = api_try!(parse_tx_hash(&tx_hash).map_err(Error::from));
+    let res = data.withdrawal_status(tx_hash).await.into();
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "withdrawal_status");
     res
 }
 
 async fn submit_tx(
     data: web::Data<ApiTransactionData>,
     Json(body): Json<TxWithSignature>,
-) -> ApiResult<TxHashSerializeWrapper> {
+) -> ApiResult<SubmitTxResponse> {
     let start = Instant::now();
-    let tx_hash = data
+    let response = data
         .tx_sender
         .submit_tx(body.tx, body.signature, None)
         .await;
 
-    if let Err(err) = &tx_hash {
+    if let Err(err) = &response {
         let err_label = match err {
             SubmitError::IncorrectTx(err) => err.clone(),
             SubmitError::TxAdd(err) => err.to_string(),
@@ -180,9 +500,9 @@ async fn submit_tx(
         metrics::increment_counter!("rejected_txs", &labels);
     }
 
-    let tx_hash = tx_hash.map_err(Error::from);
+    let response = response.map_err(Error::from);
     metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "submit_tx");
-    tx_hash.map(TxHashSerializeWrapper).into()
+    response.into()
 }
 
 async fn submit_batch(
@@ -210,6 +530,15 @@ async fn submit_batch(
     response.into()
 }
 
+/// Limits the transactions and batches are checked against on submission,
+/// so that the clients can check them before signing.
+async fn submission_limits(data: web::Data<ApiTransactionData>) -> ApiResult<SubmissionLimits> {
+    let start = Instant::now();
+    let res = ApiResult::Ok(data.tx_sender.limits);
+    metrics::histogram!("api", start.elapsed(), "type" => "v02", "endpoint_name" => "submission_limits");
+    res
+}
+
 async fn toggle_2fa(
     data: web::Data<ApiTransactionData>,
     Json(toggle_2fa): Json<Toggle2FA>,
@@ -235,65 +564,166 @@ async fn get_batch(
     res
 }
 
-pub fn api_scope(tx_sender: TxSender) -> Scope {
-    let data = ApiTransactionData::new(tx_sender);
+const TX_HASH_PARAM: &str = "tx_hash";
+const TX_HASH_PARAM_DESCRIPTION: &str = "Hash of the transaction or the priority operation";
 
-    web::scope("transactions")
+pub fn api_scope(tx_sender: TxSender, eth_client: EthereumGateway) -> ApiScope {
+    let data = ApiTransactionData::new(tx_sender, eth_client);
+
+    ApiScope::new("transactions")
         .app_data(web::Data::new(data))
-        .route("", web::post().to(submit_tx))
-        .route("{tx_hash}", web::get().to(tx_status))
-        .route("{tx_hash}/data", web::get().to(tx_data))
-        .route("/batches", web::post().to(submit_batch))
-        .route("/batches/{batch_hash}", web::get().to(get_batch))
-        .route("/toggle2FA", web::post().to(toggle_2fa))
+        .route(
+            Operation::post("", "Submits the transaction")
+                .body::<TxWithSignature>()
+                .result::<SubmitTxResponse>(),
+            submit_tx,
+        )
+        // Registered before `{tx_hash}`, otherwise it would be taken for the transaction hash.
+        .route(
+            Operation::get("limits", "Limits of the transaction submission")
+                .result::<SubmissionLimits>(),
+            submission_limits,
+        )
+        .route(
+            Operation::post("status", "Receipts of the transactions")
+                .body::<Vec<String>>()
+                .result::<BTreeMap<TxHashSerializeWrapper, Option<Receipt>>>(),
+            tx_statuses,
+        )
+        .route(
+            Operation::get("{tx_hash}", "Receipt of the transaction")
+                .path_param(TX_HASH_PARAM, TX_HASH_PARAM_DESCRIPTION)
+                .result::<Option<Receipt>>(),
+            tx_status,
+        )
+        .route(
+            Operation::get("{tx_hash}/data", "Transaction along with its receipt")
+                .path_param(TX_HASH_PARAM, TX_HASH_PARAM_DESCRIPTION)
+                .result::<Option<TxData>>(),
+            tx_data,
+        )
+        .route(
+            Operation::get("{tx_hash}/finality", "Finality stage of the transaction")
+                .path_param(TX_HASH_PARAM, TX_HASH_PARAM_DESCRIPTION)
+                .result::<Option<TxFinality>>(),
+            tx_finality,
+        )
+        .route(
+            Operation::get(
+                "{tx_hash}/withdrawalStatus",
+                "Execution of the withdrawal on L1",
+            )
+            .path_param(TX_HASH_PARAM, TX_HASH_PARAM_DESCRIPTION)
+            .result::<Option<WithdrawalStatus>>(),
+            withdrawal_status,
+        )
+        .route(
+            Operation::post("/batches", "Submits the transaction batch")
+                .body::<IncomingTxBatch>()
+                .result::<SubmitBatchResponse>(),
+            submit_batch,
+        )
+        .route(
+            Operation::get("/batches/{batch_hash}", "Transaction batch")
+                .path_param("batch_hash", "Hash of the batch")
+                .result::<Option<ApiTxBatch>>(),
+            get_batch,
+        )
+        .route(
+            Operation::post("/toggle2FA", "Enables or disables 2FA of the account")
+                .body::<Toggle2FA>()
+                .result::<Toggle2FAResponse>(),
+            toggle_2fa,
+        )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::api_server::rest::v02::{
+        error::ErrorCode,
         test_utils::{
-            deserialize_response_result, dummy_fee_ticker, dummy_sign_verifier, TestServerConfig,
-            TestTransactions,
+            deserialize_response_result, dummy_fee_ticker, dummy_sign_verifier,
+            pagination::commit_test_block, submit_txs_loopback, test_tx_sender, TestServerConfig,
+            TestTransactions, COMMITTED_BLOCKS_COUNT, EXECUTED_WITHDRAWAL_BLOCK,
+            VERIFIED_OP_SERIAL_ID,
         },
         SharedData,
     };
-    use crate::fee_ticker::validator::cache::TokenInMemoryCache;
-    use chrono::Utc;
+    use crate::api_server::{
+        maintenance_mode::MaintenanceModeCache, rpc_server::error::RpcErrorCodes,
+        tx_sender::submission_limits,
+    };
+    use chrono::{TimeZone, Utc};
     use futures::{channel::mpsc, StreamExt};
-    use num::{rational::Ratio, BigUint};
-    use std::collections::HashMap;
+    use std::collections::HashSet;
     use std::str::FromStr;
     use tokio::task::JoinHandle;
-    use zksync_api_types::v02::{
-        transaction::{L2Receipt, TxHashSerializeWrapper},
-        ApiVersion,
+    use zksync_api_types::{
+        v02::{
+            transaction::{EthSignatureType, L2Receipt, TxHashSerializeWrapper},
+            ApiVersion,
+        },
+        MaintenanceMode,
     };
+    use zksync_eth_client::{clients::mock::MockEthereum, ethereum_gateway::ExecutedTxStatus};
     use zksync_mempool::MempoolTransactionRequest;
+    use zksync_storage::{test_data::dummy_ethereum_tx_hash, ConnectionPool};
+    use zksync_test_account::ZkSyncAccount;
     use zksync_types::{
-        tokens::{Token, TokenMarketVolume},
+        tokens::Token,
+        tx::error::TxAddError,
         tx::{
             EthBatchSignData, EthBatchSignatures, PackedEthSignature, TxEthSignature,
             TxEthSignatureVariant,
         },
-        Address, BlockNumber, SignedZkSyncTx, TokenId, TokenKind, TokenLike,
+        AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx, SignedZkSyncTx, TokenId,
+        TokenKind, ZkSyncTx,
     };
 
-    fn submit_txs_loopback() -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
+    /// Mempool storing the accepted transactions, which rejects the transaction
+    /// if the one with the same account and nonce is already accepted.
+    fn submit_txs_to_storage(
+        pool: ConnectionPool,
+    ) -> (mpsc::Sender<MempoolTransactionRequest>, JoinHandle<()>) {
         let (mempool_tx_request_sender, mut mempool_tx_request_receiver) = mpsc::channel(100);
 
         let task = tokio::spawn(async move {
-            while let Some(tx) = mempool_tx_request_receiver.next().await {
-                match tx {
-                    MempoolTransactionRequest::NewTx(_, resp) => {
-                        resp.send(Ok(())).unwrap_or_default()
-                    }
-                    MempoolTransactionRequest::NewPriorityOps(_, _, resp) => {
-                        resp.send(Ok(())).unwrap_or_default()
+            let mut accepted = HashSet::new();
+            while let Some(request) = mempool_tx_request_receiver.next().await {
+                match request {
+                    MempoolTransactionRequest::NewTx(tx, resp) => {
+                        let result =
+                            if accepted.insert((tx.tx.account_id().unwrap(), tx.tx.nonce())) {
+                                let mut storage = pool.access_storage().await.unwrap();
+                                storage
+                                    .chain()
+                                    .mempool_schema()
+                                    .insert_tx(&tx)
+                                    .await
+                                    .unwrap();
+                                Ok(())
+                            } else {
+                                Err(TxAddError::NonceMismatch)
+                            };
+                        resp.send(result).unwrap_or_default();
                     }
-                    MempoolTransactionRequest::NewTxsBatch(_, _, resp) => {
-                        resp.send(Ok(())).unwrap_or_default()
+                    MempoolTransactionRequest::ReplaceTx(tx, replaced_tx_hash, resp) => {
+                        let mut storage = pool.access_storage().await.unwrap();
+                        let replaced = storage
+                            .chain()
+                            .mempool_schema()
+                            .replace_tx(replaced_tx_hash, &tx)
+                            .await
+                            .unwrap();
+                        let result = if replaced {
+                            Ok(())
+                        } else {
+                            Err(TxAddError::ReplacedTxNotQueued)
+                        };
+                        resp.send(result).unwrap_or_default();
                     }
+                    _ => {}
                 }
             }
         });
@@ -317,53 +747,29 @@ mod tests {
             api_version: ApiVersion::V02,
         };
 
-        let mut tokens = HashMap::new();
-        tokens.insert(
-            TokenLike::Id(TokenId(0)),
-            Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20),
-        );
-        let mut market = HashMap::new();
-        market.insert(
-            TokenId(0),
-            TokenMarketVolume {
-                market_volume: Ratio::from_integer(BigUint::from(400u32)),
-                last_updated: Utc::now(),
-            },
-        );
-        let cache = TokenInMemoryCache::new()
-            .with_tokens(tokens)
-            .with_market(market);
-
-        let prices = vec![
-            (TokenLike::Id(TokenId(0)), 10500_u64.into()),
-            (TokenLike::Id(TokenId(1)), 10500_u64.into()),
-            (TokenLike::Id(TokenId(2)), 10500_u64.into()),
-            (TokenLike::Id(TokenId(3)), 10500_u64.into()),
-            (TokenLike::Symbol(String::from("PHNX")), 10_u64.into()),
-            (TokenLike::Id(TokenId(15)), 10_500_u64.into()),
-            (Address::default().into(), 100000_u64.into()),
-        ];
-
+        let eth_client = MockEthereum::default();
+        let server_eth_client = eth_client.clone();
         let (client, server) = cfg.start_server(
             move |cfg: &TestServerConfig| {
-                api_scope(TxSender::new(
-                    cfg.pool.clone(),
-                    dummy_sign_verifier(),
-                    dummy_fee_ticker(&prices, Some(cache.clone())),
-                    &cfg.config.api.common,
-                    &cfg.config.api.token_config,
-                    sender.clone(),
-                ))
+                api_scope(
+                    test_tx_sender(cfg, &cfg.config.api.common, sender.clone()),
+                    EthereumGateway::Mock(server_eth_client.clone()),
+                )
             },
             Some(shared_data),
         );
 
+        let response = client.submission_limits().await?;
+        let limits: SubmissionLimits = deserialize_response_result(response)?;
+        assert_eq!(limits, submission_limits(&cfg.config.api.common));
+
         let tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
         let response = client
             .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
             .await?;
-        let tx_hash: TxHash = deserialize_response_result(response)?;
-        assert_eq!(tx.hash(), tx_hash);
+        let response: SubmitTxResponse = deserialize_response_result(response)?;
+        assert_eq!(tx.hash(), response.tx_hash);
+        assert_eq!(response.replaced_tx_hash, None);
 
         let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
         let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
@@ -436,8 +842,9 @@ mod tests {
             submit_batch_response.transaction_hashes
         );
         assert_eq!(batch.batch_status.last_state, TxInBlockStatus::Queued);
+        assert_eq!(batch.block_number, None);
 
-        let tx_hash = {
+        let (tx_hash, block_index) = {
             let mut storage = cfg.pool.access_storage().await?;
 
             let transactions = storage
@@ -446,15 +853,25 @@ mod tests {
                 .get_block_transactions(BlockNumber(1))
                 .await?;
 
-            TxHash::from_str(&transactions[0].tx_hash).unwrap()
+            (
+                TxHash::from_str(&transactions[0].tx_hash).unwrap(),
+                transactions[0].block_index.map(|index| index as u32),
+            )
         };
         let response = client.tx_status(tx_hash).await?;
         let tx_status: Receipt = deserialize_response_result(response)?;
         let expected_tx_status = Receipt::L2(L2Receipt {
             tx_hash,
             rollup_block: Some(BlockNumber(1)),
+            block_index,
             status: TxInBlockStatus::Finalized,
             fail_reason: None,
+            fail_reason_code: None,
+            attempts_exhausted: false,
+            batch_id: None,
+            batch_index: None,
+            created_at: None,
+            eth_signature_type: None,
         });
         assert_eq!(tx_status, expected_tx_status);
 
@@ -462,6 +879,76 @@ mod tests {
         let tx_data: Option<TxData> = deserialize_response_result(response)?;
         assert_eq!(tx_data.unwrap().tx.tx_hash, tx_hash);
 
+        // Every stage of the finalized transaction is confirmed on L1. The Ethereum transactions
+        // of the stages are reported by the node as mined with different number of confirmations.
+        let expected_stage = |block_number, action_type, confirmations| {
+            let pool = cfg.pool.clone();
+            let mut eth_client = eth_client.clone();
+            async move {
+                let mut storage = pool.access_storage().await?;
+                let op = storage
+                    .chain()
+                    .operations_schema()
+                    .get_stored_aggregated_operation(block_number, action_type)
+                    .await
+                    .unwrap();
+                let eth_tx_hash = dummy_ethereum_tx_hash(op.id);
+                eth_client
+                    .add_execution(
+                        &eth_tx_hash,
+                        &ExecutedTxStatus {
+                            confirmations,
+                            success: true,
+                            receipt: None,
+                        },
+                    )
+                    .await;
+                Ok::<_, anyhow::Error>(Some(FinalityStage {
+                    eth_tx_hash,
+                    confirmations,
+                }))
+            }
+        };
+        let expected_finality = TxFinality {
+            tx_hash,
+            rollup_block: Some(BlockNumber(1)),
+            commit: expected_stage(BlockNumber(1), AggregatedActionType::CommitBlocks, 30).await?,
+            prove: expected_stage(
+                BlockNumber(1),
+                AggregatedActionType::PublishProofBlocksOnchain,
+                20,
+            )
+            .await?,
+            execute: expected_stage(BlockNumber(1), AggregatedActionType::ExecuteBlocks, 10)
+                .await?,
+        };
+        let response = client.tx_finality(tx_hash).await?;
+        let finality: Option<TxFinality> = deserialize_response_result(response)?;
+        assert_eq!(finality, Some(expected_finality));
+
+        // The transaction of the block that is only committed is neither proven nor executed.
+        let committed_block = BlockNumber(COMMITTED_BLOCKS_COUNT - 1);
+        let committed_tx_hash = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let transactions = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(committed_block)
+                .await?;
+            TxHash::from_str(&transactions[0].tx_hash).unwrap()
+        };
+        let expected_finality = TxFinality {
+            tx_hash: committed_tx_hash,
+            rollup_block: Some(committed_block),
+            commit: expected_stage(committed_block, AggregatedActionType::CommitBlocks, 1).await?,
+            prove: None,
+            execute: None,
+        };
+        let response = client.tx_finality(committed_tx_hash).await?;
+        let finality: Option<TxFinality> = deserialize_response_result(response)?;
+        assert_eq!(finality, Some(expected_finality));
+
+        let pending_tx_created_at = Utc.timestamp(1_660_730_400, 0);
         let pending_tx_hash = {
             let mut storage = cfg.pool.access_storage().await?;
 
@@ -473,7 +960,7 @@ mod tests {
                 .insert_tx(&SignedZkSyncTx {
                     tx,
                     eth_sign_data: None,
-                    created_at: Utc::now(),
+                    created_at: pending_tx_created_at,
                 })
                 .await?;
 
@@ -484,8 +971,15 @@ mod tests {
         let expected_tx_status = Receipt::L2(L2Receipt {
             tx_hash: pending_tx_hash,
             rollup_block: None,
+            block_index: None,
             status: TxInBlockStatus::Queued,
             fail_reason: None,
+            fail_reason_code: None,
+            attempts_exhausted: false,
+            batch_id: None,
+            batch_index: None,
+            created_at: Some(pending_tx_created_at),
+            eth_signature_type: Some(EthSignatureType::None),
         });
         assert_eq!(tx_status, expected_tx_status);
 
@@ -498,6 +992,714 @@ mod tests {
         let tx_data: Option<TxData> = deserialize_response_result(response)?;
         assert!(tx_data.is_none());
 
+        // Withdrawal status is only reported for the withdrawals.
+        let response = client.withdrawal_status(tx_hash).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error, Error::from(TxError::NotWithdrawal));
+
+        let response = client.withdrawal_status(tx.hash()).await?;
+        let status: Option<WithdrawalStatus> = deserialize_response_result(response)?;
+        assert!(status.is_none());
+
+        let pending_withdrawal_hash = {
+            let account = ZkSyncAccount::rand();
+            account.set_account_id(Some(AccountId(0xf00d)));
+            let (withdraw, _) = account.sign_withdraw(
+                TokenId(0),
+                "ETH",
+                100_u64.into(),
+                1_u64.into(),
+                &Address::zero(),
+                None,
+                true,
+                Default::default(),
+            );
+            let tx = ZkSyncTx::Withdraw(Box::new(withdraw));
+            let tx_hash = tx.hash();
+            let mut storage = cfg.pool.access_storage().await?;
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_tx(&SignedZkSyncTx {
+                    tx,
+                    eth_sign_data: None,
+                    created_at: Utc::now(),
+                })
+                .await?;
+            tx_hash
+        };
+        let response = client.withdrawal_status(pending_withdrawal_hash).await?;
+        let status: Option<WithdrawalStatus> = deserialize_response_result(response)?;
+        assert_eq!(
+            status,
+            Some(WithdrawalStatus {
+                tx_hash: pending_withdrawal_hash,
+                rollup_block: None,
+                executed_on_l1: false,
+                eth_tx_hash: None,
+                eth_tx_sent_at: None,
+            })
+        );
+
+        // Withdrawal of the executed block is reported along with the `ExecuteBlocks` operation.
+        let withdrawal_block = BlockNumber(EXECUTED_WITHDRAWAL_BLOCK);
+        let (executed_withdrawal_hash, execute_op) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let tx_hash = storage
+                .chain()
+                .block_schema()
+                .get_block_executed_ops(withdrawal_block)
+                .await?
+                .into_iter()
+                .filter_map(|op| op.get_executed_tx().map(|tx| tx.signed_tx.tx.clone()))
+                .find(|tx| matches!(tx, ZkSyncTx::Withdraw(_)))
+                .unwrap()
+                .hash();
+            let execute_op = storage
+                .ethereum_schema()
+                .confirmed_execute_operation(withdrawal_block)
+                .await?
+                .unwrap();
+            (tx_hash, execute_op)
+        };
+        let response = client.withdrawal_status(executed_withdrawal_hash).await?;
+        let status: Option<WithdrawalStatus> = deserialize_response_result(response)?;
+        assert_eq!(
+            status,
+            Some(WithdrawalStatus {
+                tx_hash: executed_withdrawal_hash,
+                rollup_block: Some(withdrawal_block),
+                executed_on_l1: true,
+                eth_tx_hash: Some(H256::from_slice(&execute_op.final_hash.unwrap())),
+                eth_tx_sent_at: execute_op.created_at,
+            })
+        );
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the maintenance account may submit transactions without the fee,
+    /// and such transactions are journaled.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn maintenance_account_fee_exemption() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        // Zero-fee `ChangePubKey` transactions of two different accounts.
+        let maintenance_tx = TestServerConfig::gen_zk_txs(0).txs[0].0.clone();
+        let regular_tx = TestServerConfig::gen_zk_txs(0).txs[0].0.clone();
+
+        let mut common_config = cfg.config.api.common.clone();
+        common_config.enforce_pubkey_change_fee = true;
+        common_config.maintenance_accounts = vec![maintenance_tx.account()];
+
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    test_tx_sender(cfg, &common_config, sender.clone()),
+                    EthereumGateway::Mock(MockEthereum::default()),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let response = client
+            .submit_tx(regular_tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::TxFeeTooLow))
+        );
+        // The submission error carries the same code as the JSON-RPC one.
+        assert_eq!(error.code, ErrorCode::from(RpcErrorCodes::FeeTooLow));
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], RpcErrorCodes::FeeTooLow as i64);
+        assert_eq!(data["reason"], "feeTooLow");
+
+        let response = client
+            .submit_tx(maintenance_tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let response: SubmitTxResponse = deserialize_response_result(response)?;
+        assert_eq!(response.tx_hash, maintenance_tx.hash());
+
+        let exempted = cfg
+            .pool
+            .access_storage()
+            .await?
+            .maintenance_schema()
+            .filter_fee_exempted_txs(&[maintenance_tx.hash(), regular_tx.hash()])
+            .await?;
+        assert_eq!(exempted, vec![maintenance_tx.hash()]);
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the transaction resubmitted after being accepted returns the same hash,
+    /// while the conflicting transaction with the same nonce is still rejected.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn resubmitted_tx_is_idempotent() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let (sender, task) = submit_txs_to_storage(cfg.pool.clone());
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    test_tx_sender(cfg, &cfg.config.api.common, sender.clone()),
+                    EthereumGateway::Mock(MockEthereum::default()),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        for _ in 0..2 {
+            let response = client
+                .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
+                .await?;
+            let response: SubmitTxResponse = deserialize_response_result(response)?;
+            assert_eq!(response.tx_hash, tx.hash());
+        }
+
+        // Same account and nonce, but a different transaction.
+        let conflicting_tx = TestServerConfig::gen_zk_txs(200_u64).txs[0].0.clone();
+        assert_eq!(conflicting_tx.account_id().ok(), tx.account_id().ok());
+        assert_eq!(conflicting_tx.nonce(), tx.nonce());
+        let response = client
+            .submit_tx(conflicting_tx, TxEthSignatureVariant::Single(None))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::NonceMismatch))
+        );
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that no transactions are accepted while the network is under maintenance,
+    /// while the reads keep working.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn maintenance_mode() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        // The cache is shared with the server, so the test doesn't have to wait for it to expire.
+        let maintenance_mode = MaintenanceModeCache::default();
+        let server_maintenance_mode = maintenance_mode.clone();
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                let mut tx_sender = test_tx_sender(cfg, &cfg.config.api.common, sender.clone());
+                tx_sender.maintenance_mode = server_maintenance_mode.clone();
+                api_scope(tx_sender, EthereumGateway::Mock(MockEthereum::default()))
+            },
+            Some(shared_data),
+        );
+
+        async fn set_maintenance_mode(
+            pool: &ConnectionPool,
+            cache: &MaintenanceModeCache,
+            mode: &MaintenanceMode,
+        ) -> anyhow::Result<()> {
+            pool.access_storage()
+                .await?
+                .maintenance_schema()
+                .set_maintenance_mode(mode)
+                .await?;
+            cache.invalidate();
+            Ok(())
+        }
+
+        let mode = MaintenanceMode {
+            enabled: true,
+            reason: Some("Contract upgrade".to_string()),
+        };
+        set_maintenance_mode(&cfg.pool, &maintenance_mode, &mode).await?;
+
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        let response = client
+            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::MaintenanceMode {
+                reason: mode.reason.clone()
+            })
+        );
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], RpcErrorCodes::MaintenanceMode as i64);
+        assert_eq!(data["details"], "Contract upgrade");
+
+        let batch = vec![TxWithSignature {
+            tx: tx.clone(),
+            signature: TxEthSignatureVariant::Single(None),
+        }];
+        let response = client.submit_batch(batch, None).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::from(RpcErrorCodes::MaintenanceMode));
+
+        // Reads are served as usual.
+        let response = client.tx_status(tx.hash()).await?;
+        let receipt: Option<Receipt> = deserialize_response_result(response)?;
+        assert!(receipt.is_none());
+
+        set_maintenance_mode(&cfg.pool, &maintenance_mode, &MaintenanceMode::default()).await?;
+        let response = client
+            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let response: SubmitTxResponse = deserialize_response_result(response)?;
+        assert_eq!(response.tx_hash, tx.hash());
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the transactions of the blocked accounts are rejected, alone or in a batch.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocked_account_submissions() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        let blocked_address = tx.account();
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                let mut tx_sender = test_tx_sender(cfg, &cfg.config.api.common, sender.clone());
+                tx_sender.blocked_accounts.insert(blocked_address);
+                api_scope(tx_sender, EthereumGateway::Mock(MockEthereum::default()))
+            },
+            Some(shared_data),
+        );
+
+        let response = client
+            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::AccountBlocked {
+                address: blocked_address
+            })
+        );
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], RpcErrorCodes::AccountBlocked as i64);
+
+        let batch = vec![TxWithSignature {
+            tx,
+            signature: TxEthSignatureVariant::Single(None),
+        }];
+        let response = client.submit_batch(batch, None).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(error.code, ErrorCode::from(RpcErrorCodes::AccountBlocked));
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the batch may be authorized by the signatures of its transactions,
+    /// either alongside the batch signature or instead of it.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn batch_with_individual_signatures() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    test_tx_sender(cfg, &cfg.config.api.common, sender.clone()),
+                    EthereumGateway::Mock(MockEthereum::default()),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let TestTransactions { acc, txs } = TestServerConfig::gen_zk_txs(1_00);
+        let eth = Token::new(TokenId(0), Default::default(), "ETH", 18, TokenKind::ERC20);
+        let eth_private_key = acc
+            .try_get_eth_private_key()
+            .expect("Should have ETH private key");
+        let txs: Vec<_> = txs.into_iter().map(|(tx, _op)| tx).collect();
+        let expected_tx_hashes: Vec<_> = txs.iter().map(|tx| tx.hash()).collect();
+        let expected_response = SubmitBatchResponse {
+            transaction_hashes: expected_tx_hashes
+                .iter()
+                .copied()
+                .map(TxHashSerializeWrapper)
+                .collect(),
+            batch_hash: TxHash::batch_hash(&expected_tx_hashes),
+        };
+
+        // Transactions which don't require an Ethereum signature are left unsigned.
+        let signed_batch = |signed: &dyn Fn(usize) -> bool| -> Vec<TxWithSignature> {
+            txs.iter()
+                .enumerate()
+                .map(|(idx, tx)| {
+                    let signature = tx
+                        .get_ethereum_sign_message(eth.clone())
+                        .filter(|_| signed(idx))
+                        .map(|message| {
+                            let eth_sig =
+                                PackedEthSignature::sign(eth_private_key, message.as_bytes())
+                                    .unwrap();
+                            TxEthSignature::EthereumSignature(eth_sig)
+                        });
+                    TxWithSignature {
+                        tx: tx.clone(),
+                        signature: TxEthSignatureVariant::Single(signature),
+                    }
+                })
+                .collect()
+        };
+        let batch_signature = {
+            let batch_txs = txs
+                .iter()
+                .map(|tx| (tx.clone(), eth.clone(), tx.account()))
+                .collect::<Vec<_>>();
+            let batch_message = EthBatchSignData::get_batch_sign_message(batch_txs);
+            let eth_sig = PackedEthSignature::sign(eth_private_key, &batch_message).unwrap();
+            EthBatchSignatures::Single(TxEthSignature::EthereumSignature(eth_sig))
+        };
+
+        // Signed and unsigned transactions along with the batch signature.
+        let mixed_batch = signed_batch(&|idx| idx % 2 == 0);
+        assert!(mixed_batch.iter().any(|tx| tx.signature.exists()));
+        assert!(mixed_batch.iter().any(|tx| !tx.signature.exists()));
+        let response = client
+            .submit_batch(mixed_batch, Some(batch_signature))
+            .await?;
+        let submit_batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
+        assert_eq!(submit_batch_response, expected_response);
+
+        // Only the signatures of the transactions.
+        let response = client.submit_batch(signed_batch(&|_| true), None).await?;
+        let submit_batch_response: SubmitBatchResponse = deserialize_response_result(response)?;
+        assert_eq!(submit_batch_response, expected_response);
+
+        // Without the batch signature every transaction has to be signed.
+        let last_signed = txs
+            .iter()
+            .rposition(|tx| tx.get_ethereum_sign_message(eth.clone()).is_some())
+            .unwrap();
+        let response = client
+            .submit_batch(signed_batch(&|idx| idx != last_signed), None)
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::MissingEthSignature))
+        );
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    /// Checks that the queued transaction is replaced by the one with the same nonce paying a higher fee,
+    /// unless the queued transaction is already picked into the pending block or executed.
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn queued_tx_replacement() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let (sender, task) = submit_txs_to_storage(cfg.pool.clone());
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+
+        let mut common_config = cfg.config.api.common.clone();
+        common_config.tx_replacement_enabled = true;
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    test_tx_sender(cfg, &common_config, sender.clone()),
+                    EthereumGateway::Mock(MockEthereum::default()),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        let response = client
+            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let response: SubmitTxResponse = deserialize_response_result(response)?;
+        assert_eq!(response.replaced_tx_hash, None);
+
+        // The same fee is not enough for the replacement.
+        let same_fee_tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        assert_eq!(same_fee_tx.nonce(), tx.nonce());
+        assert_ne!(same_fee_tx.hash(), tx.hash());
+        let response = client
+            .submit_tx(same_fee_tx, TxEthSignatureVariant::Single(None))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::ReplacementFeeTooLow))
+        );
+
+        let replacement = TestServerConfig::gen_zk_txs(200_u64).txs[0].0.clone();
+        let response = client
+            .submit_tx(replacement.clone(), TxEthSignatureVariant::Single(None))
+            .await?;
+        let response: SubmitTxResponse = deserialize_response_result(response)?;
+        assert_eq!(
+            response,
+            SubmitTxResponse {
+                tx_hash: replacement.hash(),
+                replaced_tx_hash: Some(TxHashSerializeWrapper(tx.hash())),
+            }
+        );
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            let mut mempool = storage.chain().mempool_schema();
+            assert!(!mempool.contains_tx(tx.hash()).await?);
+            assert!(mempool.contains_tx(replacement.hash()).await?);
+        }
+
+        // The state keeper has picked the queued transaction into the pending block.
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .mark_txs_picked(&[replacement.hash()])
+            .await?;
+        let picked_replacement = TestServerConfig::gen_zk_txs(300_u64).txs[0].0.clone();
+        let response = client
+            .submit_tx(picked_replacement, TxEthSignatureVariant::Single(None))
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::ReplacedTxNotQueued))
+        );
+        // Picked markers are reset on restart, unlike the executed transactions.
+        cfg.pool
+            .access_storage()
+            .await?
+            .chain()
+            .mempool_schema()
+            .reset_picked_txs()
+            .await?;
+
+        // The queued transaction gets executed after the API has found it, but before it's replaced.
+        {
+            let mut storage = cfg.pool.access_storage().await?;
+            let executed_tx = ExecutedTx {
+                signed_tx: replacement.clone().into(),
+                success: true,
+                op: None,
+                fail_reason: None,
+                block_index: Some(0),
+                created_at: Utc::now(),
+                batch_id: None,
+            };
+            commit_test_block(
+                &mut storage,
+                vec![ExecutedOperations::Tx(Box::new(executed_tx))],
+            )
+            .await?;
+        }
+        let late_replacement = TestServerConfig::gen_zk_txs(300_u64).txs[0].0.clone();
+        let response = client
+            .submit_tx(
+                late_replacement.clone(),
+                TxEthSignatureVariant::Single(None),
+            )
+            .await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(SubmitError::TxAdd(TxAddError::ReplacedTxNotQueued))
+        );
+        let mut storage = cfg.pool.access_storage().await?;
+        assert!(
+            !storage
+                .chain()
+                .mempool_schema()
+                .contains_tx(late_replacement.hash())
+                .await?
+        );
+
+        server.stop().await;
+        task.abort();
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tx_statuses() -> anyhow::Result<()> {
+        let (sender, task) = submit_txs_loopback();
+
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+
+        let shared_data = SharedData {
+            net: cfg.config.chain.eth.network,
+            api_version: ApiVersion::V02,
+        };
+        let (client, server) = cfg.start_server(
+            move |cfg: &TestServerConfig| {
+                api_scope(
+                    TxSender::new(
+                        cfg.pool.clone(),
+                        dummy_sign_verifier(),
+                        dummy_fee_ticker(&[], None),
+                        &cfg.config.api.common,
+                        &cfg.config.api.token_config,
+                        sender.clone(),
+                    ),
+                    EthereumGateway::Mock(MockEthereum::default()),
+                )
+            },
+            Some(shared_data),
+        );
+
+        let (executed_tx_hash, queued_tx_hash) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let transactions = storage
+                .chain()
+                .block_schema()
+                .get_block_transactions(BlockNumber(1))
+                .await?;
+            let executed_tx_hash = TxHash::from_str(&transactions[0].tx_hash).unwrap();
+
+            let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+            let queued_tx_hash = tx.hash();
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_tx(&SignedZkSyncTx {
+                    tx,
+                    eth_sign_data: None,
+                    created_at: Utc::now(),
+                })
+                .await?;
+            (executed_tx_hash, queued_tx_hash)
+        };
+        let priority_op_hash =
+            TxHash::from_slice(dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64).as_bytes())
+                .unwrap();
+        let unknown_tx_hash = TxHash::from_slice(&[0xff; 32]).unwrap();
+
+        let tx_hashes = vec![
+            executed_tx_hash,
+            queued_tx_hash,
+            priority_op_hash,
+            unknown_tx_hash,
+            // Duplicates are reported once.
+            executed_tx_hash,
+        ];
+        let response = client.tx_statuses(&tx_hashes).await?;
+        let statuses: BTreeMap<TxHashSerializeWrapper, Option<Receipt>> =
+            deserialize_response_result(response)?;
+        assert_eq!(statuses.len(), 4);
+        // Statuses are the same as the ones reported for the single transactions.
+        for tx_hash in &tx_hashes {
+            let response = client.tx_status(*tx_hash).await?;
+            let expected_status: Option<Receipt> = deserialize_response_result(response)?;
+            assert_eq!(statuses[&TxHashSerializeWrapper(*tx_hash)], expected_status);
+        }
+        assert!(matches!(
+            statuses[&TxHashSerializeWrapper(executed_tx_hash)],
+            Some(Receipt::L2(L2Receipt {
+                status: TxInBlockStatus::Finalized,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            statuses[&TxHashSerializeWrapper(queued_tx_hash)],
+            Some(Receipt::L2(L2Receipt {
+                status: TxInBlockStatus::Queued,
+                ..
+            }))
+        ));
+        assert!(matches!(
+            statuses[&TxHashSerializeWrapper(priority_op_hash)],
+            Some(Receipt::L1(_))
+        ));
+        assert_eq!(statuses[&TxHashSerializeWrapper(unknown_tx_hash)], None);
+
+        let too_many_hashes = vec![unknown_tx_hash; MAX_TX_STATUSES_PER_REQUEST + 1];
+        let response = client.tx_statuses(&too_many_hashes).await?;
+        let error = serde_json::from_value::<Error>(response.error.unwrap()).unwrap();
+        assert_eq!(
+            error,
+            Error::from(TxError::TooManyHashes(MAX_TX_STATUSES_PER_REQUEST + 1))
+        );
+
         server.stop().await;
         task.abort();
         Ok(())
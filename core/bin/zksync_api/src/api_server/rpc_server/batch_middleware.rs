@@ -0,0 +1,93 @@
+// Built-in uses
+use std::future::Future;
+
+// External uses
+use futures::{
+    future::{self, BoxFuture, Either},
+    stream, FutureExt, StreamExt,
+};
+use jsonrpc_core::{
+    middleware::NoopCallFuture, Error, ErrorCode, Failure, Id, Metadata, Middleware, Output,
+    Request, Response, Version,
+};
+
+/// Middleware enforcing the limits of the JSON-RPC batch requests.
+///
+/// Batches with more than `max_batch_size` calls are rejected as a whole with the standard
+/// "Invalid request" error. Calls of the accepted batches are processed concurrently, but no more
+/// than `parallelism` of them at once, and the responses are returned in the order of the calls.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLimitMiddleware {
+    max_batch_size: usize,
+    parallelism: usize,
+}
+
+impl BatchLimitMiddleware {
+    pub fn new(max_batch_size: usize, parallelism: usize) -> Self {
+        Self {
+            max_batch_size,
+            parallelism: parallelism.max(1),
+        }
+    }
+}
+
+impl<M: Metadata> Middleware<M> for BatchLimitMiddleware {
+    type Future = BoxFuture<'static, Option<Response>>;
+    type CallFuture = NoopCallFuture;
+
+    fn on_request<F, X>(&self, request: Request, meta: M, next: F) -> Either<Self::Future, X>
+    where
+        F: Fn(Request, M) -> X + Send + Sync,
+        X: Future<Output = Option<Response>> + Send + 'static,
+    {
+        let calls = match request {
+            // Empty batches are rejected by the handler itself.
+            Request::Batch(calls) if !calls.is_empty() => calls,
+            request => return Either::Right(next(request, meta)),
+        };
+
+        if calls.len() > self.max_batch_size {
+            let error = Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!(
+                    "Batch is too big, it should contain at most {} calls",
+                    self.max_batch_size
+                ),
+                data: None,
+            };
+            let output = Output::Failure(Failure {
+                jsonrpc: Some(Version::V2),
+                error,
+                id: Id::Null,
+            });
+            return Either::Left(future::ready(Some(Response::Single(output))).boxed());
+        }
+
+        // Every call is handled as a separate request, so the batch can be polled
+        // with the limited concurrency. Notifications have no output.
+        let responses: Vec<_> = calls
+            .into_iter()
+            .map(|call| next(Request::Single(call), meta.clone()))
+            .collect();
+        let parallelism = self.parallelism;
+        let batch = async move {
+            let outputs: Vec<Output> = stream::iter(responses)
+                .buffered(parallelism)
+                .filter_map(|response| async move {
+                    match response {
+                        Some(Response::Single(output)) => Some(output),
+                        _ => None,
+                    }
+                })
+                .collect()
+                .await;
+
+            if outputs.is_empty() {
+                None
+            } else {
+                Some(Response::Batch(outputs))
+            }
+        };
+        Either::Left(batch.boxed())
+    }
+}
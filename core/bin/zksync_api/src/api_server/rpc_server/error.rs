@@ -1,46 +1,107 @@
 // External uses
 use jsonrpc_core::ErrorCode;
+use serde_json::{json, Map, Value};
 use zksync_types::tx::error::TxAddError;
 // Workspace uses
+use zksync_api_types::v02::transaction::{SubmissionLimit, SubmissionLimitExceeded};
 // Local uses
 use crate::api_server::tx_sender::SubmitError;
 
-#[derive(Debug, Clone, Copy)]
+/// Codes of the transaction submission failures.
+///
+/// The codes are stable, so the clients should match on them (or on the `reason` field of
+/// the error data) instead of the error messages. The same codes are reported by the REST API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RpcErrorCodes {
     NonceMismatch = 101,
+    NonceOutOfWindow = 102,
     IncorrectTx = 103,
     FeeTooLow = 104,
     InappropriateFeeToken = 105,
+    SubmissionLimitExceeded = 106,
+    TooManyRequests = 107,
+    InvalidSignature = 108,
+    BatchTooBig = 109,
+    AttemptsExhausted = 110,
+    /// The sender of the transaction is not allowed to submit transactions.
+    AccountBlocked = 111,
 
     MissingEthSignature = 200,
     EIP1271SignatureVerificationFail = 201,
     IncorrectEthSignature = 202,
+    /// The account has to be unlocked with the `ChangePubKey` authorized on-chain.
     ChangePkNotAuthorized = 203,
+    /// There is no smart contract wallet at the address to check the EIP-1271 signature yet.
+    EIP1271ContractNotDeployed = 204,
 
     Other = 300,
     AccountCloseDisabled = 301,
     OperationsLimitReached = 302,
     UnsupportedFastProcessing = 303,
     Toggle2FA = 304,
+    /// The WebSocket connection has reached the limit of the active subscriptions.
+    SubscriptionsLimitReached = 305,
+    /// The server is shutting down and doesn't accept new calls.
+    ShuttingDown = 306,
+    /// The network is under maintenance and doesn't accept new transactions.
+    MaintenanceMode = 307,
+}
+
+impl RpcErrorCodes {
+    /// Machine-readable name of the failure, reported as the `reason` of the error data.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::NonceMismatch => "nonceMismatch",
+            Self::NonceOutOfWindow => "nonceOutOfWindow",
+            Self::IncorrectTx => "incorrectTx",
+            Self::FeeTooLow => "feeTooLow",
+            Self::InappropriateFeeToken => "inappropriateFeeToken",
+            Self::SubmissionLimitExceeded => "submissionLimitExceeded",
+            Self::TooManyRequests => "tooManyRequests",
+            Self::InvalidSignature => "invalidSignature",
+            Self::BatchTooBig => "batchTooBig",
+            Self::AttemptsExhausted => "attemptsExhausted",
+            Self::AccountBlocked => "accountBlocked",
+            Self::MissingEthSignature => "missingEthSignature",
+            Self::EIP1271SignatureVerificationFail => "eip1271SignatureVerificationFail",
+            Self::IncorrectEthSignature => "incorrectEthSignature",
+            Self::ChangePkNotAuthorized => "changePkNotAuthorized",
+            Self::EIP1271ContractNotDeployed => "eip1271ContractNotDeployed",
+            Self::Other => "other",
+            Self::AccountCloseDisabled => "accountCloseDisabled",
+            Self::OperationsLimitReached => "operationsLimitReached",
+            Self::UnsupportedFastProcessing => "unsupportedFastProcessing",
+            Self::Toggle2FA => "toggle2FA",
+            Self::SubscriptionsLimitReached => "subscriptionsLimitReached",
+            Self::ShuttingDown => "shuttingDown",
+            Self::MaintenanceMode => "maintenanceMode",
+        }
+    }
 }
 
 impl From<TxAddError> for RpcErrorCodes {
     fn from(error: TxAddError) -> Self {
         match error {
             TxAddError::NonceMismatch => Self::NonceMismatch,
+            TxAddError::IncorrectTx(err) if err.is_wrong_signature() => Self::InvalidSignature,
+            TxAddError::IncorrectTx(err) if err.is_wrong_fee_token() => Self::InappropriateFeeToken,
             TxAddError::IncorrectTx(_) => Self::IncorrectTx,
             TxAddError::TxFeeTooLow => Self::FeeTooLow,
             TxAddError::TxBatchFeeTooLow => Self::FeeTooLow,
             TxAddError::MissingEthSignature => Self::MissingEthSignature,
             TxAddError::EIP1271SignatureVerificationFail => Self::EIP1271SignatureVerificationFail,
+            TxAddError::EIP1271ContractNotDeployed => Self::EIP1271ContractNotDeployed,
             TxAddError::IncorrectEthSignature => Self::IncorrectEthSignature,
             TxAddError::ChangePkNotAuthorized => Self::ChangePkNotAuthorized,
             TxAddError::Other => Self::Other,
             TxAddError::DbError => Self::Other,
-            TxAddError::EmptyBatch => Self::Other,
-            TxAddError::BatchTooBig => Self::Other,
-            TxAddError::BatchWithdrawalsOverload => Self::Other,
-            TxAddError::EthSignaturesLimitExceeded => Self::Other,
+            TxAddError::EmptyBatch => Self::IncorrectTx,
+            TxAddError::BatchTooBig => Self::BatchTooBig,
+            TxAddError::BatchWithdrawalsOverload => Self::BatchTooBig,
+            TxAddError::EthSignaturesLimitExceeded => Self::BatchTooBig,
+            TxAddError::AttemptsExhausted => Self::AttemptsExhausted,
+            TxAddError::ReplacementFeeTooLow => Self::FeeTooLow,
+            TxAddError::ReplacedTxNotQueued => Self::NonceMismatch,
         }
     }
 }
@@ -51,62 +112,204 @@ impl From<RpcErrorCodes> for ErrorCode {
     }
 }
 
+/// Code of the submission error shared by the JSON-RPC and REST APIs, `None` for the failures
+/// reported with the standard codes of the invalid params and the internal errors.
+pub fn submit_error_rpc_code(error: &SubmitError) -> Option<RpcErrorCodes> {
+    let code = match error {
+        SubmitError::AccountCloseDisabled => RpcErrorCodes::AccountCloseDisabled,
+        SubmitError::UnsupportedFastProcessing => RpcErrorCodes::UnsupportedFastProcessing,
+        SubmitError::IncorrectTx(_) => RpcErrorCodes::IncorrectTx,
+        SubmitError::TxAdd(err) => RpcErrorCodes::from(*err),
+        SubmitError::Toggle2FA(_) => RpcErrorCodes::Toggle2FA,
+        SubmitError::NonceOutOfWindow { .. } => RpcErrorCodes::NonceOutOfWindow,
+        SubmitError::LimitExceeded { .. } => RpcErrorCodes::SubmissionLimitExceeded,
+        SubmitError::InappropriateFeeToken => RpcErrorCodes::InappropriateFeeToken,
+        SubmitError::TooManyRequests { .. } => RpcErrorCodes::TooManyRequests,
+        SubmitError::MaintenanceMode { .. } => RpcErrorCodes::MaintenanceMode,
+        SubmitError::AccountBlocked { .. } => RpcErrorCodes::AccountBlocked,
+        SubmitError::MempoolCommunication(_) => RpcErrorCodes::Other,
+        SubmitError::InvalidParams(_)
+        | SubmitError::Internal(_)
+        | SubmitError::Other(_)
+        | SubmitError::PriceError(_) => return None,
+    };
+    Some(code)
+}
+
+/// Code of the submission error along with its machine-readable name.
+fn submit_error_code(error: &SubmitError) -> (ErrorCode, &'static str) {
+    match (error, submit_error_rpc_code(error)) {
+        (_, Some(code)) => (code.into(), code.as_str()),
+        (SubmitError::InvalidParams(_), None) => (ErrorCode::InvalidParams, "invalidParams"),
+        (_, None) => (ErrorCode::InternalError, "internalError"),
+    }
+}
+
+/// Details of the submission error, if there are any besides the code.
+fn submit_error_details(error: &SubmitError) -> Option<Value> {
+    match error {
+        SubmitError::TxAdd(TxAddError::IncorrectTx(err)) => Some(json!({ "txError": err })),
+        SubmitError::TxAdd(err) => Some(json!({ "txAddError": err })),
+        SubmitError::Toggle2FA(err) => Some(json!({ "toggle2FAError": err })),
+        SubmitError::NonceOutOfWindow {
+            nonce,
+            next_nonce,
+            max_nonce,
+        } => {
+            let mut details = limit_details(
+                SubmissionLimit::NonceAheadWindow,
+                u64::from(**nonce - **next_nonce),
+                u64::from(**max_nonce - **next_nonce),
+            );
+            details["nonce"] = json!(nonce);
+            details["nextNonce"] = json!(next_nonce);
+            details["maxNonce"] = json!(max_nonce);
+            Some(details)
+        }
+        SubmitError::LimitExceeded { limit, value, max } => {
+            Some(limit_details(*limit, *value, *max))
+        }
+        SubmitError::TooManyRequests { retry_after_ms, .. } => {
+            Some(json!({ "retryAfterMs": retry_after_ms }))
+        }
+        SubmitError::MempoolCommunication(reason) => Some(json!({ "details": reason })),
+        SubmitError::MaintenanceMode {
+            reason: Some(reason),
+        } => Some(json!({ "details": reason })),
+        _ => None,
+    }
+}
+
+fn limit_details(limit: SubmissionLimit, value: u64, max: u64) -> Value {
+    serde_json::to_value(SubmissionLimitExceeded { limit, value, max })
+        .expect("limit details are always serializable")
+}
+
+/// Machine-readable payload of the submission error: its numeric `code`, the `reason` naming
+/// the code and the details of the particular failure. Both JSON-RPC and REST APIs report it.
+pub fn submit_error_data(error: &SubmitError) -> Value {
+    let (code, reason) = submit_error_code(error);
+    let mut data = Map::new();
+    data.insert("code".to_string(), code.code().into());
+    data.insert("reason".to_string(), reason.into());
+    if let Some(Value::Object(details)) = submit_error_details(error) {
+        data.extend(details);
+    }
+    Value::Object(data)
+}
+
 impl From<SubmitError> for jsonrpc_core::Error {
     fn from(inner: SubmitError) -> Self {
-        match inner {
-            SubmitError::AccountCloseDisabled => Self {
-                code: RpcErrorCodes::AccountCloseDisabled.into(),
-                message: "Account close tx is disabled.".to_string(),
-                data: None,
-            },
-
-            SubmitError::InvalidParams(msg) => Self::invalid_params(msg),
-            SubmitError::UnsupportedFastProcessing => Self {
-                code: RpcErrorCodes::UnsupportedFastProcessing.into(),
-                message: "Fast processing available only for 'withdraw' operation type."
-                    .to_string(),
-                data: None,
-            },
-            SubmitError::IncorrectTx(message) => Self {
-                code: RpcErrorCodes::IncorrectTx.into(),
-                message,
-                data: None,
-            },
-            SubmitError::TxAdd(inner) => Self {
-                code: RpcErrorCodes::from(inner).into(),
-                message: inner.to_string(),
-                data: None,
-            },
-            SubmitError::Toggle2FA(inner) => Self {
-                code: RpcErrorCodes::Toggle2FA.into(),
-                message: inner.to_string(),
-                data: None,
-            },
-            SubmitError::InappropriateFeeToken => Self {
-                code: RpcErrorCodes::InappropriateFeeToken.into(),
-                message: inner.to_string(),
-                data: None,
-            },
-            SubmitError::MempoolCommunication(reason) => Self {
-                code: RpcErrorCodes::Other.into(),
-                message: "Error communicating core server".to_string(),
-                data: Some(reason.into()),
-            },
-            SubmitError::Internal(msg) => Self {
-                code: ErrorCode::InternalError,
-                message: msg.to_string(),
-                data: None,
-            },
-            SubmitError::Other(message) => Self {
-                code: ErrorCode::InternalError,
-                message,
-                data: None,
-            },
-            SubmitError::PriceError(error) => Self {
-                code: ErrorCode::InternalError,
-                message: error.to_string(),
-                data: None,
-            },
+        let (code, _) = submit_error_code(&inner);
+        let data = Some(submit_error_data(&inner));
+        let message = match inner {
+            SubmitError::AccountCloseDisabled => "Account close tx is disabled.".to_string(),
+            SubmitError::InvalidParams(msg) => msg,
+            SubmitError::UnsupportedFastProcessing => {
+                "Fast processing available only for 'withdraw' operation type.".to_string()
+            }
+            SubmitError::IncorrectTx(message) => message,
+            SubmitError::TxAdd(inner) => inner.to_string(),
+            SubmitError::Toggle2FA(inner) => inner.to_string(),
+            SubmitError::MempoolCommunication(_) => "Error communicating core server".to_string(),
+            SubmitError::Internal(msg) => msg.to_string(),
+            SubmitError::Other(message) => message,
+            SubmitError::PriceError(error) => error.to_string(),
+            SubmitError::NonceOutOfWindow { .. }
+            | SubmitError::LimitExceeded { .. }
+            | SubmitError::InappropriateFeeToken
+            | SubmitError::TooManyRequests { .. }
+            | SubmitError::MaintenanceMode { .. }
+            | SubmitError::AccountBlocked { .. } => inner.to_string(),
+        };
+        Self {
+            code,
+            message,
+            data,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::Nonce;
+
+    #[test]
+    fn submit_error_codes() {
+        let error = jsonrpc_core::Error::from(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+        assert_eq!(error.code, RpcErrorCodes::FeeTooLow.into());
+        assert_eq!(
+            error.data,
+            Some(json!({
+                "code": 104,
+                "reason": "feeTooLow",
+                "txAddError": "TxFeeTooLow",
+            }))
+        );
+
+        let error = jsonrpc_core::Error::from(SubmitError::TxAdd(TxAddError::BatchTooBig));
+        assert_eq!(error.code, RpcErrorCodes::BatchTooBig.into());
+
+        // The nonce window is reported as the other submission limits, along with the nonces.
+        let error = jsonrpc_core::Error::from(SubmitError::NonceOutOfWindow {
+            nonce: Nonce(9),
+            next_nonce: Nonce(5),
+            max_nonce: Nonce(8),
+        });
+        assert_eq!(error.code, RpcErrorCodes::NonceOutOfWindow.into());
+        assert_eq!(
+            error.data,
+            Some(json!({
+                "code": 102,
+                "reason": "nonceOutOfWindow",
+                "limit": "nonceAheadWindow",
+                "value": 4,
+                "max": 3,
+                "nonce": 9,
+                "nextNonce": 5,
+                "maxNonce": 8,
+            }))
+        );
+        let error = jsonrpc_core::Error::from(SubmitError::LimitExceeded {
+            limit: SubmissionLimit::ChunksPerBatch,
+            value: 120,
+            max: 100,
+        });
+        let data = error.data.unwrap();
+        assert_eq!(data["reason"], "submissionLimitExceeded");
+        let details: SubmissionLimitExceeded = serde_json::from_value(data).unwrap();
+        assert_eq!(
+            details,
+            SubmissionLimitExceeded {
+                limit: SubmissionLimit::ChunksPerBatch,
+                value: 120,
+                max: 100,
+            }
+        );
+
+        let error = jsonrpc_core::Error::from(SubmitError::MaintenanceMode {
+            reason: Some("Contract upgrade".to_string()),
+        });
+        assert_eq!(error.code, RpcErrorCodes::MaintenanceMode.into());
+        assert_eq!(
+            error.message,
+            "Network is under maintenance, new transactions are not accepted: Contract upgrade."
+        );
+        assert_eq!(
+            error.data,
+            Some(json!({
+                "code": 307,
+                "reason": "maintenanceMode",
+                "details": "Contract upgrade",
+            }))
+        );
+
+        // The standard JSON-RPC codes are kept for the invalid params and the internal errors.
+        let error = jsonrpc_core::Error::from(SubmitError::invalid_params("Invalid token"));
+        assert_eq!(error.code, ErrorCode::InvalidParams);
+        assert_eq!(error.data.unwrap()["reason"], "invalidParams");
+        let error = jsonrpc_core::Error::from(SubmitError::other("Token not found in the DB"));
+        assert_eq!(error.code, ErrorCode::InternalError);
+    }
+}
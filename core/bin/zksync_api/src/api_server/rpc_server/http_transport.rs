@@ -0,0 +1,302 @@
+//! HTTP transport of the JSON-RPC server.
+//!
+//! `jsonrpc_http_server` doesn't expose the address of the connected peer, while the calls
+//! have to be throttled by the client IP, so the requests are served by `hyper` directly.
+
+// Built-in uses
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+// External uses
+use futures::channel::oneshot;
+use hyper::{
+    header::{self, HeaderValue},
+    server::conn::AddrStream,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use jsonrpc_core::{MetaIoHandler, Middleware};
+use jsonrpc_http_server::{RequestMiddleware, RequestMiddlewareAction};
+
+// Local uses
+use super::ip_limit_middleware::HttpMeta;
+
+/// Maximum size of the request body, same as the default one of `jsonrpc_http_server`.
+const MAX_REQUEST_BODY_SIZE: usize = 5 * 1024 * 1024;
+
+/// Serves the JSON-RPC calls over HTTP on `addr` until `close` is resolved.
+/// Blocks the current thread, the requests are processed by `threads` worker threads.
+pub fn serve_http<S, R>(
+    io: MetaIoHandler<HttpMeta, S>,
+    request_middleware: R,
+    addr: SocketAddr,
+    threads: usize,
+    trust_forwarded_headers: bool,
+    close: oneshot::Receiver<()>,
+) -> anyhow::Result<()>
+where
+    S: Middleware<HttpMeta>,
+    R: RequestMiddleware,
+{
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(threads)
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(async move {
+        let io = Arc::new(io);
+        let request_middleware = Arc::new(request_middleware);
+        let make_service = make_service_fn(move |conn: &AddrStream| {
+            let peer = conn.remote_addr();
+            let io = io.clone();
+            let request_middleware = request_middleware.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    handle_request(
+                        io.clone(),
+                        request_middleware.clone(),
+                        peer,
+                        trust_forwarded_headers,
+                        request,
+                    )
+                }))
+            }
+        });
+
+        Server::try_bind(&addr)?
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                close.await.ok();
+            })
+            .await?;
+        Ok::<(), anyhow::Error>(())
+    })
+}
+
+async fn handle_request<S, R>(
+    io: Arc<MetaIoHandler<HttpMeta, S>>,
+    request_middleware: Arc<R>,
+    peer: SocketAddr,
+    trust_forwarded_headers: bool,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible>
+where
+    S: Middleware<HttpMeta>,
+    R: RequestMiddleware,
+{
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    let mut response = match *request.method() {
+        Method::POST => {
+            let meta = HttpMeta::from_request(&request, peer, trust_forwarded_headers);
+            handle_rpc_request(&io, request_middleware.as_ref(), meta, request).await
+        }
+        // Preflight requests of the browsers.
+        Method::OPTIONS => Response::new(Body::empty()),
+        _ => text_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "Used HTTP Method is not allowed. POST or OPTIONS is required\n",
+        ),
+    };
+
+    // Any origin is allowed, as `jsonrpc_http_server` does with the CORS validation disabled.
+    if let Some(origin) = origin {
+        let headers = response.headers_mut();
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("OPTIONS, POST"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static("origin, content-type, accept"),
+        );
+    }
+    Ok(response)
+}
+
+async fn handle_rpc_request<S, R>(
+    io: &MetaIoHandler<HttpMeta, S>,
+    request_middleware: &R,
+    meta: HttpMeta,
+    request: Request<Body>,
+) -> Response<Body>
+where
+    S: Middleware<HttpMeta>,
+    R: RequestMiddleware,
+{
+    let request = match request_middleware.on_request(request) {
+        RequestMiddlewareAction::Proceed { request, .. } => request,
+        RequestMiddlewareAction::Respond { response, .. } => {
+            return response.await.unwrap_or_else(|err| {
+                text_response(StatusCode::INTERNAL_SERVER_ERROR, &format!("{}\n", err))
+            });
+        }
+    };
+
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if content_length.map_or(false, |length| length > MAX_REQUEST_BODY_SIZE) {
+        return text_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large\n");
+    }
+
+    let body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(body) if body.len() <= MAX_REQUEST_BODY_SIZE => body,
+        Ok(_) => {
+            return text_response(StatusCode::PAYLOAD_TOO_LARGE, "Request body is too large\n")
+        }
+        Err(err) => return text_response(StatusCode::BAD_REQUEST, &format!("{}\n", err)),
+    };
+    let body = match std::str::from_utf8(&body) {
+        Ok(body) => body,
+        Err(_) => return text_response(StatusCode::BAD_REQUEST, "Request body is not UTF-8\n"),
+    };
+
+    match io.handle_request(body, meta).await {
+        Some(output) => {
+            let mut response = Response::new(Body::from(output + "\n"));
+            response.headers_mut().insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json; charset=utf-8"),
+            );
+            response
+        }
+        // Notifications have no response.
+        None => Response::new(Body::empty()),
+    }
+}
+
+fn text_response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(Body::from(message.to_owned()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::rpc_server::error::RpcErrorCodes;
+    use crate::api_server::rpc_server::{
+        ip_insert_middleware::IpInsertMiddleWare,
+        ip_limit_middleware::{IpRateLimitMiddleware, LIMIT_EXCEEDED_CODE},
+    };
+    use crate::api_server::sender_limiter::RateLimiter;
+    use crate::api_server::shutdown::{close_on_shutdown, InFlightCalls, ShutdownController};
+    use serde_json::{json, Value};
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+
+    /// Returns the address of the free local port.
+    fn free_addr() -> SocketAddr {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap()
+    }
+
+    async fn call(
+        client: &hyper::Client<hyper::client::HttpConnector>,
+        addr: SocketAddr,
+        method: &str,
+    ) -> hyper::Result<Value> {
+        let request = Request::post(format!("http://{}", addr))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] }).to_string(),
+            ))
+            .unwrap();
+        let response = client.request(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        Ok(serde_json::from_slice(&body).unwrap())
+    }
+
+    /// Checks that the clients are throttled by the address of the peer
+    /// when the requests carry no forwarding headers.
+    #[tokio::test]
+    async fn requests_without_headers_are_throttled_by_peer() {
+        let addr = free_addr();
+        let middleware = IpRateLimitMiddleware::with_limiters(Some(RateLimiter::new(1, 2)), None);
+        let mut io = MetaIoHandler::with_middleware(middleware);
+        io.add_method("tokens", |_| async { Ok(Value::from("tokens")) });
+
+        let (close_sender, close) = oneshot::channel();
+        let server =
+            std::thread::spawn(move || serve_http(io, IpInsertMiddleWare, addr, 1, false, close));
+        // Give the server some time to start.
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        let client = hyper::Client::new();
+        let mut codes = Vec::new();
+        for _ in 0..3 {
+            let response = call(&client, addr, "tokens").await.unwrap();
+            codes.push(response["error"]["code"].clone());
+        }
+        assert_eq!(
+            codes,
+            vec![Value::Null, Value::Null, Value::from(LIMIT_EXCEEDED_CODE)]
+        );
+
+        close_sender.send(()).unwrap();
+        server.join().unwrap().unwrap();
+    }
+
+    /// Checks that the server started the same way as the JSON-RPC HTTP API lets the in-flight
+    /// calls finish once the shutdown is requested.
+    #[tokio::test]
+    async fn in_flight_calls_are_finished_on_shutdown() {
+        let addr = free_addr();
+        let controller = ShutdownController::new();
+        let shutdown = controller.signal();
+        let calls = InFlightCalls::default();
+        // The slow call is finished only once the permit is added.
+        let release = Arc::new(Semaphore::new(0));
+
+        let mut io = MetaIoHandler::with_middleware(calls.clone());
+        let slow_call_release = release.clone();
+        io.add_method("slow", move |_| {
+            let release = slow_call_release.clone();
+            async move {
+                release.acquire().await.unwrap().forget();
+                Ok(Value::from("done"))
+            }
+        });
+        let (close_sender, close) = oneshot::channel();
+        close_on_shutdown(
+            &shutdown,
+            &tokio::runtime::Handle::current(),
+            calls.clone(),
+            move || close_sender.send(()).unwrap_or_default(),
+        );
+        let server = std::thread::spawn(move || {
+            let result = serve_http(io, IpInsertMiddleWare, addr, 1, false, close);
+            // The signal is kept until the server is stopped, as the API servers do.
+            drop(shutdown);
+            result
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let client = hyper::Client::new();
+        let slow_call = {
+            let client = client.clone();
+            tokio::spawn(async move { call(&client, addr, "slow").await })
+        };
+        while calls.active() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let stopped = tokio::spawn(controller.shutdown(Duration::from_secs(10)));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // The server waits for the slow call before closing, new calls are rejected meanwhile.
+        let response = call(&client, addr, "slow").await.unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            RpcErrorCodes::ShuttingDown as i64
+        );
+
+        release.add_permits(1);
+        let response = slow_call.await.unwrap().unwrap();
+        assert_eq!(response["result"], "done");
+        assert!(stopped.await.unwrap(), "Server should stop in time");
+        server.join().unwrap().unwrap();
+        assert!(call(&client, addr, "slow").await.is_err());
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::HashMap, iter::FromIterator};
 
 // External uses
 use futures::{FutureExt, StreamExt};
-use jsonrpc_core::Params;
+use jsonrpc_core::{Call, Params, Request};
 use jsonrpc_http_server::{RequestMiddleware, RequestMiddlewareAction};
 
 use super::types::RequestMetadata;
@@ -49,10 +49,10 @@ fn get_call_with_ip_if_needed(
     let methods_with_ip: HashMap<&'static str, MethodWithIpDescription> = HashMap::from_iter([
         ("tx_submit", MethodWithIpDescription::new(1, 4)),
         ("submit_txs_batch", MethodWithIpDescription::new(1, 3)),
-        ("get_tx_fee", MethodWithIpDescription::new(3, 4)),
+        ("get_tx_fee", MethodWithIpDescription::new(3, 5)),
         (
             "get_txs_batch_fee_in_wei",
-            MethodWithIpDescription::new(3, 4),
+            MethodWithIpDescription::new(3, 5),
         ),
     ]);
 
@@ -108,8 +108,29 @@ fn get_call_with_ip_if_needed(
     }
 }
 
+/// Same as `get_call_with_ip_if_needed`, but for any call of the request.
+fn get_request_call_with_ip_if_needed(call: Call, ip: Option<String>) -> Call {
+    match call {
+        Call::MethodCall(call) => Call::MethodCall(get_call_with_ip_if_needed(call, ip)),
+        call => call,
+    }
+}
+
+/// Inserts the IP of the user into every call of the request, batches included.
+fn get_request_with_ip_if_needed(request: Request, ip: Option<String>) -> Request {
+    match request {
+        Request::Single(call) => Request::Single(get_request_call_with_ip_if_needed(call, ip)),
+        Request::Batch(calls) => Request::Batch(
+            calls
+                .into_iter()
+                .map(|call| get_request_call_with_ip_if_needed(call, ip.clone()))
+                .collect(),
+        ),
+    }
+}
+
 /// Given the HTTP body of the JSON-RPC request and the IP of the user, inserts the information about it
-/// in the call (if needed) and returns the bytes of the new body.
+/// in the calls (if needed) and returns the bytes of the new body.
 /// If the IP supplied is None, the method makes sure that the user could not pass the IP
 async fn insert_ip_if_needed(body: hyper::Body, ip: Option<String>) -> hyper::Result<Vec<u8>> {
     let body_stream: Vec<_> = body.collect().await;
@@ -119,12 +140,11 @@ async fn insert_ip_if_needed(body: hyper::Body, ip: Option<String>) -> hyper::Re
         body_bytes.extend(bytes?.into_iter());
     }
 
-    let call: std::result::Result<jsonrpc_core::MethodCall, _> =
-        serde_json::from_slice(&body_bytes);
+    let request: std::result::Result<Request, _> = serde_json::from_slice(&body_bytes);
 
-    if let Ok(call) = call {
-        let new_call = get_call_with_ip_if_needed(call, ip);
-        let new_body_bytes = serde_json::to_vec(&new_call);
+    if let Ok(request) = request {
+        let new_request = get_request_with_ip_if_needed(request, ip);
+        let new_body_bytes = serde_json::to_vec(&new_request);
         if let Ok(s) = new_body_bytes {
             body_bytes = s;
         }
@@ -221,6 +241,42 @@ mod tests {
             expected_result_params,
             Some(IP.to_owned()),
         );
+
+        // The optional fee tokens of the batch are filled with null as well.
+        let params = Params::Array(vec![
+            json!(["Transfer", "Withdraw"]),
+            json!(["address_1", "address_2"]),
+            json!("ETH"),
+        ]);
+        let expected_result_params = Params::Array(vec![
+            json!(["Transfer", "Withdraw"]),
+            json!(["address_1", "address_2"]),
+            json!("ETH"),
+            Value::Null,
+            json!({ "ip": IP }),
+        ]);
+        test_call_ip_insertion(
+            "get_txs_batch_fee_in_wei".to_string(),
+            params,
+            expected_result_params,
+            Some(IP.to_owned()),
+        );
+
+        // So is the optional fee token of the single transaction.
+        let params = Params::Array(vec![json!("Transfer"), json!("address"), json!("ETH")]);
+        let expected_result_params = Params::Array(vec![
+            json!("Transfer"),
+            json!("address"),
+            json!("ETH"),
+            Value::Null,
+            json!({ "ip": IP }),
+        ]);
+        test_call_ip_insertion(
+            "get_tx_fee".to_string(),
+            params,
+            expected_result_params,
+            Some(IP.to_owned()),
+        );
     }
 
     #[test]
@@ -331,4 +387,42 @@ mod tests {
             Some(IP.to_owned()),
         );
     }
+
+    #[test]
+    fn insert_ip_into_batch() {
+        let submit = get_method_call(
+            "tx_submit".to_owned(),
+            Params::Array(vec![
+                Value::String("serialized_transfer".to_owned()),
+                Value::String("some_signature".to_owned()),
+                Value::Bool(true),
+                Value::String("override_ip".to_owned()),
+            ]),
+        );
+        let other = get_method_call("tokens".to_owned(), Params::Array(vec![]));
+        let batch = Request::Batch(vec![
+            Call::MethodCall(submit),
+            Call::MethodCall(other.clone()),
+        ]);
+
+        // Every call of the batch is processed as a single one would be.
+        let calls = match get_request_with_ip_if_needed(batch, Some(IP.to_owned())) {
+            Request::Batch(calls) => calls,
+            request => panic!("Batch is expected, got {:?}", request),
+        };
+        assert_eq!(calls.len(), 2);
+        match &calls[0] {
+            Call::MethodCall(call) => assert_eq!(
+                call.params,
+                Params::Array(vec![
+                    Value::String("serialized_transfer".to_owned()),
+                    Value::String("some_signature".to_owned()),
+                    Value::Bool(true),
+                    json!({ "ip": IP }),
+                ])
+            ),
+            call => panic!("Method call is expected, got {:?}", call),
+        }
+        assert_eq!(calls[1], Call::MethodCall(other));
+    }
 }
@@ -0,0 +1,233 @@
+// Built-in uses
+use std::{
+    future::Future,
+    net::{IpAddr, SocketAddr},
+};
+
+// External uses
+use futures::future::{self, Either};
+use hyper::header::HeaderMap;
+use jsonrpc_core::{
+    middleware::NoopFuture, Call, Error, ErrorCode, Failure, Metadata, Middleware, Output,
+};
+use serde_json::json;
+
+// Workspace uses
+use zksync_config::configs::api::JsonRpcConfig;
+
+// Local uses
+use crate::api_server::sender_limiter::RateLimiter;
+
+/// Code of the error returned for the throttled calls, the "Limit exceeded" code of EIP-1474.
+pub const LIMIT_EXCEEDED_CODE: i64 = -32005;
+
+const FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+const CLOUDFLARE_CONNECTING_IP_HEADER: &str = "CF-Connecting-IP";
+
+/// Methods submitting transactions, they are throttled separately from the read ones.
+const SUBMIT_METHODS: &[&str] = &["tx_submit", "submit_txs_batch"];
+
+/// Metadata of the HTTP request handled by the JSON-RPC server.
+#[derive(Debug, Clone)]
+pub struct HttpMeta {
+    /// IP of the client.
+    pub ip: IpAddr,
+}
+
+impl Metadata for HttpMeta {}
+
+impl HttpMeta {
+    /// Determines the IP of the client that sent the HTTP request from the connected `peer`.
+    ///
+    /// The forwarding headers (the leftmost address of `X-Forwarded-For`, then `CF-Connecting-IP`)
+    /// are used only if the proxy in front of the server is trusted, since otherwise the clients
+    /// could set them to anything.
+    pub fn from_request(
+        request: &hyper::Request<hyper::Body>,
+        peer: SocketAddr,
+        trust_forwarded_headers: bool,
+    ) -> Self {
+        let forwarded_ip = if trust_forwarded_headers {
+            let headers = request.headers();
+            header_value(headers, FORWARDED_FOR_HEADER)
+                .and_then(|ips| ips.split(',').next())
+                .or_else(|| header_value(headers, CLOUDFLARE_CONNECTING_IP_HEADER))
+                .and_then(|ip| ip.trim().parse().ok())
+        } else {
+            None
+        };
+
+        Self {
+            ip: forwarded_ip.unwrap_or_else(|| peer.ip()),
+        }
+    }
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// Middleware throttling the calls of a single IP.
+///
+/// Read and submission calls are limited separately, and every call of a batch is counted.
+/// Throttled calls fail with the `LIMIT_EXCEEDED_CODE` error telling when the call can be retried.
+#[derive(Debug, Clone, Default)]
+pub struct IpRateLimitMiddleware {
+    read: Option<RateLimiter<IpAddr>>,
+    submit: Option<RateLimiter<IpAddr>>,
+}
+
+impl IpRateLimitMiddleware {
+    pub fn new(config: &JsonRpcConfig) -> Self {
+        Self::with_limiters(
+            config
+                .ip_read_requests_per_sec
+                .map(|rate_per_sec| RateLimiter::new(rate_per_sec, config.ip_read_requests_burst)),
+            config.ip_submit_requests_per_sec.map(|rate_per_sec| {
+                RateLimiter::new(rate_per_sec, config.ip_submit_requests_burst)
+            }),
+        )
+    }
+
+    pub fn with_limiters(
+        read: Option<RateLimiter<IpAddr>>,
+        submit: Option<RateLimiter<IpAddr>>,
+    ) -> Self {
+        Self { read, submit }
+    }
+
+    fn limiter(&self, method: &str) -> Option<&RateLimiter<IpAddr>> {
+        if SUBMIT_METHODS.contains(&method) {
+            self.submit.as_ref()
+        } else {
+            self.read.as_ref()
+        }
+    }
+}
+
+impl Middleware<HttpMeta> for IpRateLimitMiddleware {
+    type Future = NoopFuture;
+    type CallFuture = future::Ready<Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: HttpMeta, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, HttpMeta) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        if let Call::MethodCall(method_call) = &call {
+            let limiter = self.limiter(&method_call.method);
+            if let Some(Err(retry_after)) = limiter.map(|limiter| limiter.acquire(meta.ip, 1)) {
+                metrics::increment_counter!(
+                    "api.rpc.throttled_calls",
+                    "method" => method_call.method.clone()
+                );
+                let error = Error {
+                    code: ErrorCode::ServerError(LIMIT_EXCEEDED_CODE),
+                    message: "Too many requests, retry later".to_string(),
+                    data: Some(json!({ "retry_after_ms": retry_after.as_millis() as u64 })),
+                };
+                let output = Output::Failure(Failure {
+                    jsonrpc: method_call.jsonrpc,
+                    error,
+                    id: method_call.id.clone(),
+                });
+                return Either::Left(future::ready(Some(output)));
+            }
+        }
+
+        Either::Right(next(call, meta))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::MetaIoHandler;
+    use serde_json::Value;
+
+    async fn call(
+        io: &MetaIoHandler<HttpMeta, IpRateLimitMiddleware>,
+        method: &str,
+        ip: &str,
+    ) -> Value {
+        let call = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] });
+        let meta = HttpMeta {
+            ip: ip.parse().unwrap(),
+        };
+        let response = io.handle_request(&call.to_string(), meta).await.unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn calls_are_throttled_per_ip() {
+        let middleware = IpRateLimitMiddleware {
+            read: Some(RateLimiter::new(1, 3)),
+            submit: Some(RateLimiter::new(1, 1)),
+        };
+        let mut io = MetaIoHandler::with_middleware(middleware);
+        io.add_method("tokens", |_| async { Ok(Value::from("tokens")) });
+        io.add_method("tx_submit", |_| async { Ok(Value::from("hash")) });
+
+        for _ in 0..3 {
+            let response = call(&io, "tokens", "1.1.1.1").await;
+            assert_eq!(response["result"], "tokens");
+        }
+        let response = call(&io, "tokens", "1.1.1.1").await;
+        assert_eq!(response["error"]["code"], LIMIT_EXCEEDED_CODE);
+        assert!(
+            response["error"]["data"]["retry_after_ms"]
+                .as_u64()
+                .unwrap()
+                > 0
+        );
+        assert_eq!(response["id"], 1);
+
+        // Submissions have their own limit.
+        let response = call(&io, "tx_submit", "1.1.1.1").await;
+        assert_eq!(response["result"], "hash");
+        let response = call(&io, "tx_submit", "1.1.1.1").await;
+        assert_eq!(response["error"]["code"], LIMIT_EXCEEDED_CODE);
+
+        // Other clients are not affected.
+        let response = call(&io, "tokens", "2.2.2.2").await;
+        assert_eq!(response["result"], "tokens");
+        let response = call(&io, "tx_submit", "2.2.2.2").await;
+        assert_eq!(response["result"], "hash");
+    }
+
+    #[test]
+    fn client_ip_extraction() {
+        let peer: SocketAddr = "3.3.3.3:4000".parse().unwrap();
+        let ip = |request: &hyper::Request<hyper::Body>, trusted: bool| {
+            HttpMeta::from_request(request, peer, trusted)
+                .ip
+                .to_string()
+        };
+
+        let request = hyper::Request::builder()
+            .header(FORWARDED_FOR_HEADER, "1.1.1.1, 10.0.0.1")
+            .header(CLOUDFLARE_CONNECTING_IP_HEADER, "2.2.2.2")
+            .body(hyper::Body::empty())
+            .unwrap();
+        assert_eq!(ip(&request, true), "1.1.1.1");
+        // Forwarding headers are ignored unless the proxy is trusted.
+        assert_eq!(ip(&request, false), "3.3.3.3");
+
+        let request = hyper::Request::builder()
+            .header(CLOUDFLARE_CONNECTING_IP_HEADER, "2.2.2.2")
+            .body(hyper::Body::empty())
+            .unwrap();
+        assert_eq!(ip(&request, true), "2.2.2.2");
+        assert_eq!(ip(&request, false), "3.3.3.3");
+
+        // Malformed headers fall back to the peer address.
+        let request = hyper::Request::builder()
+            .header(FORWARDED_FOR_HEADER, "unknown")
+            .body(hyper::Body::empty())
+            .unwrap();
+        assert_eq!(ip(&request, true), "3.3.3.3");
+
+        let request = hyper::Request::new(hyper::Body::empty());
+        assert_eq!(ip(&request, true), "3.3.3.3");
+    }
+}
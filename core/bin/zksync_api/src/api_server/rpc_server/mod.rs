@@ -2,9 +2,8 @@
 use std::time::Instant;
 
 // External uses
-use futures::channel::mpsc;
-use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
-use jsonrpc_http_server::ServerBuilder;
+use futures::channel::{mpsc, oneshot};
+use jsonrpc_core::{Error, MetaIoHandler, Metadata, Middleware, Result};
 use tokio::task::JoinHandle;
 
 // Workspace uses
@@ -16,25 +15,42 @@ use zksync_storage::{
     },
     ConnectionPool, StorageProcessor,
 };
-use zksync_types::{tx::TxHash, Address, BlockNumber};
+use zksync_types::{tx::TxHash, Address, BlockNumber, ZkSyncTx};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 
 // Local uses
-use crate::{signature_checker::VerifySignatureRequest, utils::shared_lru_cache::AsyncLruCache};
+use crate::{
+    core_api_client::CoreApiClient, signature_checker::VerifySignatureRequest,
+    utils::shared_lru_cache::AsyncLruCache,
+};
 
+mod batch_middleware;
 pub mod error;
+mod http_transport;
 mod ip_insert_middleware;
+mod ip_limit_middleware;
 mod rpc_impl;
 mod rpc_trait;
 pub mod types;
 
 pub use self::rpc_trait::Rpc;
 use self::types::*;
-use super::tx_sender::TxSender;
+use super::{
+    mempool_resync::SubmittedTxs,
+    sender_limiter::SenderRateLimiter,
+    shutdown::{close_on_shutdown, InFlightCalls, ShutdownSignal},
+    tx_sender::TxSender,
+};
 use crate::fee_ticker::FeeTicker;
+use batch_middleware::BatchLimitMiddleware;
+use http_transport::serve_http;
 use ip_insert_middleware::IpInsertMiddleWare;
+use ip_limit_middleware::IpRateLimitMiddleware;
 use zksync_mempool::MempoolTransactionRequest;
 
+/// Types of the operations moving funds out of zkSync.
+const WITHDRAWAL_TX_TYPES: &[&str] = &["Withdraw", "WithdrawNFT", "ForcedExit", "FullExit"];
+
 #[derive(Clone)]
 pub struct RpcApp {
     cache_of_executed_priority_operations: AsyncLruCache<u32, StoredExecutedPriorityOperation>,
@@ -44,6 +60,9 @@ pub struct RpcApp {
     pub confirmations_for_eth_event: u64,
 
     tx_sender: TxSender,
+    core_api_client: CoreApiClient,
+    /// Whether the state of the core's pending block is exposed.
+    pending_block_info_enabled: bool,
 }
 
 impl RpcApp {
@@ -55,6 +74,7 @@ impl RpcApp {
         token_config: &TokenConfig,
         confirmations_for_eth_event: u64,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+        core_api_client: CoreApiClient,
     ) -> Self {
         let api_requests_caches_size = config.caches_size;
 
@@ -75,9 +95,31 @@ impl RpcApp {
             confirmations_for_eth_event,
 
             tx_sender,
+            core_api_client,
+            pending_block_info_enabled: false,
         }
     }
 
+    /// Sets the channel receiving the transactions accepted into the mempool through this API.
+    pub fn set_accepted_txs_sender(&mut self, accepted_txs: Option<mpsc::Sender<Vec<ZkSyncTx>>>) {
+        self.tx_sender.accepted_txs = accepted_txs;
+    }
+
+    /// Sets the ring of the submitted transactions checked against the core's mempool.
+    pub fn set_submitted_txs(&mut self, submitted_txs: Option<SubmittedTxs>) {
+        self.tx_sender.submitted_txs = submitted_txs;
+    }
+
+    /// Sets the throttling of the transactions submitted by a single sender.
+    pub fn set_sender_limiter(&mut self, sender_limiter: Option<SenderRateLimiter>) {
+        self.tx_sender.sender_limiter = sender_limiter;
+    }
+
+    /// Enables the `pending_block_info` method, which is unavailable by default.
+    pub fn set_pending_block_info_enabled(&mut self, enabled: bool) {
+        self.pending_block_info_enabled = enabled;
+    }
+
     pub fn extend<T: Metadata, S: Middleware<T>>(self, io: &mut MetaIoHandler<T, S>) {
         io.extend_with(self.to_delegate())
     }
@@ -228,6 +270,31 @@ impl RpcApp {
             Some(complete_withdrawals_tx_hash)
         } else {
             let mut storage = self.access_storage().await?;
+            // Every executed transaction has the Ethereum transaction executing its block,
+            // so the hashes of the operations that don't withdraw funds are rejected beforehand.
+            let tx_type = storage
+                .chain()
+                .operations_ext_schema()
+                .get_tx_by_hash(withdrawal_hash.as_ref())
+                .await
+                .map_err(|err| {
+                    vlog::warn!(
+                        "Internal Server Error: '{}'; input: {:?}",
+                        err,
+                        withdrawal_hash,
+                    );
+                    Error::internal_error()
+                })?
+                .map(|tx| tx.tx_type);
+            if let Some(tx_type) = tx_type {
+                if !WITHDRAWAL_TX_TYPES.contains(&tx_type.as_str()) {
+                    return Err(Error::invalid_params(format!(
+                        "{} transaction is not a withdrawal",
+                        tx_type
+                    )));
+                }
+            }
+
             let complete_withdrawals_tx_hash = storage
                 .chain()
                 .operations_schema()
@@ -265,10 +332,19 @@ pub fn start_rpc_server(
     common_api_config: &CommonApiConfig,
     token_config: &TokenConfig,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    accepted_txs_sender: Option<mpsc::Sender<Vec<ZkSyncTx>>>,
+    submitted_txs: Option<SubmittedTxs>,
+    sender_limiter: Option<SenderRateLimiter>,
     confirmations_for_eth_event: u64,
+    core_address: String,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
     let addr = config.http_bind_addr();
-    let rpc_app = RpcApp::new(
+    let batch_limits = BatchLimitMiddleware::new(config.max_batch_size, config.batch_parallelism);
+    // Limiters are shared by all the threads of the server.
+    let ip_limits = IpRateLimitMiddleware::new(config);
+    let trusted_proxy = config.trusted_proxy;
+    let mut rpc_app = RpcApp::new(
         connection_pool,
         sign_verify_request_sender,
         ticker,
@@ -276,28 +352,839 @@ pub fn start_rpc_server(
         token_config,
         confirmations_for_eth_event,
         mempool_tx_sender,
+        CoreApiClient::new(core_address),
     );
+    rpc_app.set_accepted_txs_sender(accepted_txs_sender);
+    rpc_app.set_submitted_txs(submitted_txs);
+    rpc_app.set_sender_limiter(sender_limiter);
+    rpc_app.set_pending_block_info_enabled(config.pending_block_info_enabled);
+
+    let in_flight_calls = InFlightCalls::default();
+    let runtime = tokio::runtime::Handle::current();
 
     let (handler, panic_sender) = spawn_panic_handler();
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
-        let mut io = IoHandler::new();
+        let mut io =
+            MetaIoHandler::with_middleware((batch_limits, ip_limits, in_flight_calls.clone()));
         rpc_app.extend(&mut io);
 
-        let server = ServerBuilder::new(io)
-            .threads(super::THREADS_PER_SERVER)
-            .request_middleware(IpInsertMiddleWare {})
-            .start_http(&addr)
-            .unwrap();
-        server.wait();
+        let (close_sender, close) = oneshot::channel();
+        close_on_shutdown(&shutdown, &runtime, in_flight_calls, move || {
+            close_sender.send(()).unwrap_or_default();
+        });
+        serve_http(
+            io,
+            IpInsertMiddleWare {},
+            addr,
+            super::THREADS_PER_SERVER,
+            trusted_proxy,
+            close,
+        )
+        .expect("JSON-RPC HTTP server failed");
+        vlog::info!("JSON-RPC HTTP server has stopped");
     });
     handler
 }
 
 #[cfg(test)]
 mod test {
+    use actix_web::{web, HttpResponse};
+    use jsonrpc_core::ErrorCode;
     use serde::{Deserialize, Serialize};
-    use zksync_types::TxFeeTypes;
+    use serde_json::{json, Value};
+    use zksync_api_types::{
+        v02::{
+            account::{PendingPriorityOp, PendingPriorityOpStatus},
+            token::ApiNFT,
+            transaction::TxType,
+        },
+        PendingBlockInfo,
+    };
+    use zksync_crypto::params::MIN_NFT_TOKEN_ID;
+    use zksync_storage::test_data::dummy_ethereum_tx_hash;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        AccountId, ContractUpgradeEvent, Deposit, FullExit, Nonce, PriorityOp, SerialId,
+        SignedZkSyncTx, TokenId, TxFeeTypes, ZkSyncPriorityOp, ZkSyncTx, H256,
+    };
+
+    use super::*;
+    use crate::api_server::rest::v02::test_utils::{
+        dummy_fee_ticker, dummy_sign_verifier, TestServerConfig, COMMITTED_BLOCKS_COUNT,
+        COMMITTED_OP_SERIAL_ID, EXECUTED_WITHDRAWAL_BLOCK, VERIFIED_OP_SERIAL_ID,
+    };
+
+    type RpcHandler = MetaIoHandler<(), BatchLimitMiddleware>;
+
+    /// Performs the JSON-RPC request and returns the whole response.
+    async fn request(io: &RpcHandler, request: Value) -> Value {
+        let response = io
+            .handle_request(&request.to_string(), ())
+            .await
+            .expect("Request should have a response");
+        serde_json::from_str(&response).unwrap()
+    }
+
+    /// Performs the JSON-RPC call and returns the whole response.
+    async fn call(io: &RpcHandler, method: &str, params: Value) -> Value {
+        let call = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        request(io, call).await
+    }
+
+    fn rpc_app(cfg: &TestServerConfig) -> RpcApp {
+        rpc_app_with_core(cfg, String::new())
+    }
+
+    fn rpc_app_with_core(cfg: &TestServerConfig, core_address: String) -> RpcApp {
+        RpcApp::new(
+            cfg.pool.clone(),
+            dummy_sign_verifier(),
+            dummy_fee_ticker(&[], None),
+            &cfg.config.api.common,
+            &cfg.config.api.token_config,
+            cfg.config.eth_watch.confirmations_for_eth_event,
+            mpsc::channel(1).0,
+            CoreApiClient::new(core_address),
+        )
+    }
+
+    fn rpc_handler(cfg: &TestServerConfig) -> RpcHandler {
+        let config = &cfg.config.api.json_rpc;
+        let mut io = MetaIoHandler::with_middleware(BatchLimitMiddleware::new(
+            config.max_batch_size,
+            config.batch_parallelism,
+        ));
+        rpc_app(cfg).extend(&mut io);
+        io
+    }
+
+    /// Hash of the first transaction executed in the block.
+    async fn first_tx_hash(cfg: &TestServerConfig, block: BlockNumber) -> anyhow::Result<TxHash> {
+        let mut storage = cfg.pool.access_storage().await?;
+        let hash = storage
+            .chain()
+            .block_schema()
+            .get_block_executed_ops(block)
+            .await?
+            .into_iter()
+            .find_map(|op| op.get_executed_tx().map(|tx| tx.signed_tx.hash()))
+            .unwrap();
+        Ok(hash)
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn nft_methods() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        // The test data contains NFTs minted by several accounts.
+        cfg.fill_database().await?;
+        let io = rpc_handler(&cfg);
+
+        let nft_id = TokenId(65542);
+        let (expected_nft, expected_owner) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let nft = storage.tokens_schema().get_nft(nft_id).await?.unwrap();
+            let owner = storage
+                .chain()
+                .account_schema()
+                .get_nft_owner(nft_id)
+                .await?;
+            (nft, owner)
+        };
+        assert!(expected_owner.is_some());
+
+        let response = call(&io, "get_nft", json!([nft_id])).await;
+        let nft: ApiNFT = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(nft.id, expected_nft.id);
+        assert_eq!(nft.serial_id, expected_nft.serial_id);
+        assert_eq!(nft.creator_id, expected_nft.creator_id);
+        assert_eq!(nft.creator_address, expected_nft.creator_address);
+        assert_eq!(nft.content_hash, expected_nft.content_hash);
+        assert_eq!(nft.address, expected_nft.address);
+
+        let response = call(&io, "get_nft_owner", json!([nft_id])).await;
+        let owner: Option<AccountId> = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(owner, expected_owner);
+
+        // NFTs that weren't minted yet are reported as missing.
+        let unknown_id = TokenId(MIN_NFT_TOKEN_ID + 0xffff);
+        let response = call(&io, "get_nft", json!([unknown_id])).await;
+        assert_eq!(response["result"], Value::Null);
+        let response = call(&io, "get_nft_owner", json!([unknown_id])).await;
+        assert_eq!(response["result"], Value::Null);
+
+        // Ids of the fungible tokens are rejected.
+        for method in ["get_nft", "get_nft_owner"] {
+            let response = call(&io, method, json!([TokenId(0)])).await;
+            assert_eq!(response["error"]["code"], ErrorCode::InvalidParams.code());
+        }
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn eth_tx_for_withdrawal() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let io = rpc_handler(&cfg);
+
+        let (transfer_hash, withdrawal_hash, execute_tx_hash) = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let transfer_hash = storage
+                .chain()
+                .block_schema()
+                .get_block_executed_ops(BlockNumber(1))
+                .await?
+                .into_iter()
+                .filter_map(|op| op.get_executed_tx().map(|tx| tx.signed_tx.tx.clone()))
+                .find(|tx| matches!(tx, ZkSyncTx::Transfer(_)))
+                .unwrap()
+                .hash();
+            let withdrawal_hash = storage
+                .chain()
+                .block_schema()
+                .get_block_executed_ops(BlockNumber(EXECUTED_WITHDRAWAL_BLOCK))
+                .await?
+                .into_iter()
+                .filter_map(|op| op.get_executed_tx().map(|tx| tx.signed_tx.tx.clone()))
+                .find(|tx| matches!(tx, ZkSyncTx::Withdraw(_)))
+                .unwrap()
+                .hash();
+            let execute_tx_hash = storage
+                .ethereum_schema()
+                .aggregated_op_final_hash(BlockNumber(EXECUTED_WITHDRAWAL_BLOCK))
+                .await?
+                .unwrap();
+            (transfer_hash, withdrawal_hash, execute_tx_hash)
+        };
+
+        // Funds of the withdrawal are sent by the transaction executing its block.
+        let response = call(&io, "get_eth_tx_for_withdrawal", json!([withdrawal_hash])).await;
+        assert_eq!(
+            response["result"],
+            Value::from(format!("{:?}", execute_tx_hash))
+        );
+
+        let full_exit_hash = dummy_ethereum_tx_hash(COMMITTED_OP_SERIAL_ID as i64);
+        let deposit_hash = dummy_ethereum_tx_hash(VERIFIED_OP_SERIAL_ID as i64);
+
+        // The full exit is committed, but its block isn't executed yet.
+        let response = call(
+            &io,
+            "get_eth_tx_for_withdrawal",
+            json!([TxHash::from_slice(full_exit_hash.as_bytes()).unwrap()]),
+        )
+        .await;
+        assert_eq!(response["result"], Value::Null);
+
+        // Unknown transactions may still be queued, so they are not rejected.
+        let response = call(&io, "get_eth_tx_for_withdrawal", json!([TxHash::default()])).await;
+        assert_eq!(response["result"], Value::Null);
+
+        // Operations that don't withdraw funds are rejected even if their blocks are executed.
+        for hash in [
+            transfer_hash,
+            TxHash::from_slice(deposit_hash.as_bytes()).unwrap(),
+        ] {
+            let response = call(&io, "get_eth_tx_for_withdrawal", json!([hash])).await;
+            assert_eq!(response["error"]["code"], ErrorCode::InvalidParams.code());
+        }
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn contract_address() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let io = rpc_handler(&cfg);
+
+        let config = cfg
+            .pool
+            .access_storage()
+            .await?
+            .config_schema()
+            .load_config()
+            .await?;
+        let response = call(&io, "contract_address", json!([])).await;
+        let contracts: ContractAddressResp = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(Some(contracts.main_contract), config.contract_addr);
+        assert_eq!(Some(contracts.gov_contract), config.gov_contract_addr);
+        assert_eq!(contracts.nft_factory_contract, config.nft_factory_addr);
+        assert!(contracts.nft_factory_contract.is_some());
+
+        // The upgrade observed on-chain is reported without the server restart.
+        // The same upgrade is stored by the config endpoint test, so the tests can run in any order.
+        let contract_target = Address::repeat_byte(0x42);
+        cfg.pool
+            .access_storage()
+            .await?
+            .config_schema()
+            .store_contract_upgrade(&ContractUpgradeEvent {
+                version_id: 1,
+                new_targets: vec![Address::zero(), Address::zero(), contract_target],
+                eth_block: 1,
+            })
+            .await?;
+        let response = call(&io, "contract_address", json!([])).await;
+        let contracts: ContractAddressResp = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(
+            contracts.contract_target,
+            Some(format!("{:?}", contract_target))
+        );
+        assert_eq!(contracts.contract_upgrade_version, Some(1));
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tx_info_cache() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let rpc_app = rpc_app(&cfg);
+        let is_cached = |hash: TxHash| {
+            let cache = rpc_app.cache_of_transaction_receipts.clone();
+            async move { cache.get(&hash.as_ref().to_vec()).await.is_some() }
+        };
+
+        // Only the transactions from the finalized blocks are cached.
+        let finalized_hash = first_tx_hash(&cfg, BlockNumber(1)).await?;
+        let tx_info = rpc_app.clone()._impl_tx_info(finalized_hash).await?;
+        assert!(tx_info.block.unwrap().verified);
+        assert!(is_cached(finalized_hash).await);
+
+        let committed_hash = first_tx_hash(&cfg, BlockNumber(COMMITTED_BLOCKS_COUNT)).await?;
+        let tx_info = rpc_app.clone()._impl_tx_info(committed_hash).await?;
+        assert!(!tx_info.block.unwrap().verified);
+        assert!(!is_cached(committed_hash).await);
+
+        let queued_hash = TxHash::default();
+        let tx_info = rpc_app.clone()._impl_tx_info(queued_hash).await?;
+        assert!(!tx_info.executed);
+        assert!(!is_cached(queued_hash).await);
+
+        // The cached receipt is returned without querying the database,
+        // which doesn't know anything about this transaction.
+        let cached_hash = TxHash::from_slice(&[0x42; 32]).unwrap();
+        let receipt = TxReceiptResponse {
+            tx_hash: hex::encode(cached_hash.as_ref()),
+            block_number: 1,
+            success: true,
+            verified: true,
+            fail_reason: None,
+            prover_run: None,
+        };
+        rpc_app
+            .cache_of_transaction_receipts
+            .insert(cached_hash.as_ref().to_vec(), receipt)
+            .await;
+        let tx_info = rpc_app.clone()._impl_tx_info(cached_hash).await?;
+        assert!(tx_info.executed);
+        assert!(tx_info.block.unwrap().verified);
+
+        // Priority operations are cached once executed, the finalization of their blocks
+        // is checked by the blocks cache, which keeps only the finalized blocks as well.
+        let op_info = rpc_app
+            .clone()
+            ._impl_ethop_info(VERIFIED_OP_SERIAL_ID as u32)
+            .await?;
+        assert!(op_info.block.unwrap().verified);
+        let op_info = rpc_app
+            .clone()
+            ._impl_ethop_info(COMMITTED_OP_SERIAL_ID as u32)
+            .await?;
+        assert!(!op_info.block.unwrap().verified);
+        let op_info = rpc_app.clone()._impl_ethop_info(u32::MAX).await?;
+        assert!(!op_info.executed);
+        assert!(rpc_app
+            .cache_of_executed_priority_operations
+            .get(&u32::MAX)
+            .await
+            .is_none());
+
+        Ok(())
+    }
+
+    const PENDING_OP_SERIAL_ID: SerialId = 1_000_000;
+
+    /// Reports the deposit with the `PENDING_OP_SERIAL_ID` as the Ethereum watcher of the core does.
+    async fn mock_pending_priority_op(serial_id: web::Path<SerialId>) -> HttpResponse {
+        if *serial_id != PENDING_OP_SERIAL_ID {
+            return HttpResponse::Ok().json(Option::<PendingPriorityOp>::None);
+        }
+        let op = PriorityOp {
+            serial_id: PENDING_OP_SERIAL_ID,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Address::repeat_byte(0x11),
+                token: TokenId(0),
+                amount: 100u32.into(),
+                to: Address::repeat_byte(0x22),
+            }),
+            deadline_block: 0,
+            eth_hash: H256::repeat_byte(0x33),
+            eth_block: 10,
+            eth_block_index: Some(0),
+            eth_block_hash: None,
+            l1_surplus: None,
+        };
+        HttpResponse::Ok().json(Some(PendingPriorityOp::new(
+            &op,
+            PendingPriorityOpStatus::Unconfirmed,
+            3,
+        )))
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn get_priority_op() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let core_server = actix_test::start(|| {
+            actix_web::App::new().route(
+                "/priority_ops/{serial_id}/pending",
+                web::get().to(mock_pending_priority_op),
+            )
+        });
+        let core_address = core_server.url("").trim_end_matches('/').to_owned();
+        let rpc_app = rpc_app_with_core(&cfg, core_address);
+
+        let op = rpc_app
+            .clone()
+            ._impl_get_priority_op(VERIFIED_OP_SERIAL_ID as u32)
+            .await?
+            .expect("Operation should be executed");
+        assert!(!op.pending);
+        assert_eq!(op.op_type, TxType::Deposit);
+        assert!(matches!(op.op, Some(ZkSyncPriorityOp::Deposit(_))));
+        assert!(op.block.unwrap().verified);
+
+        let op = rpc_app
+            .clone()
+            ._impl_get_priority_op(COMMITTED_OP_SERIAL_ID as u32)
+            .await?
+            .expect("Operation should be executed");
+        assert_eq!(op.op_type, TxType::FullExit);
+        let block = op.block.unwrap();
+        assert!(block.committed);
+        assert!(!block.verified);
+
+        // Operations waiting on L1 are reported by the core.
+        let op = rpc_app
+            .clone()
+            ._impl_get_priority_op(PENDING_OP_SERIAL_ID as u32)
+            .await?
+            .expect("Operation should be pending");
+        assert!(op.pending);
+        assert_eq!(op.confirmations_left, 3);
+        assert_eq!(op.eth_hash, H256::repeat_byte(0x33));
+        assert!(op.op.is_none());
+        assert!(op.block.is_none());
+
+        let op = rpc_app._impl_get_priority_op(u32::MAX).await?;
+        assert!(op.is_none());
+
+        Ok(())
+    }
+
+    /// Reports the pending priority operations of the `0x22..22` address as the Ethereum watcher
+    /// of the core does.
+    async fn mock_pending_priority_ops(address: web::Path<Address>) -> HttpResponse {
+        if *address != Address::repeat_byte(0x22) {
+            return HttpResponse::Ok().json(Vec::<PendingPriorityOp>::new());
+        }
+        let deposit = |serial_id: SerialId, amount: u32, eth_block: u64| PriorityOp {
+            serial_id,
+            data: ZkSyncPriorityOp::Deposit(Deposit {
+                from: Address::repeat_byte(0x11),
+                token: TokenId(0),
+                amount: amount.into(),
+                to: *address,
+            }),
+            deadline_block: 0,
+            eth_hash: H256::repeat_byte(serial_id as u8),
+            eth_block,
+            eth_block_index: Some(0),
+            eth_block_hash: None,
+            l1_surplus: None,
+        };
+        let full_exit = PriorityOp {
+            data: ZkSyncPriorityOp::FullExit(FullExit {
+                account_id: AccountId(1),
+                eth_address: *address,
+                token: TokenId(0),
+                is_legacy: false,
+            }),
+            ..deposit(4, 0, 10)
+        };
+        HttpResponse::Ok().json(vec![
+            PendingPriorityOp::new(&deposit(1, 100, 10), PendingPriorityOpStatus::Confirmed, 0),
+            PendingPriorityOp::new(&deposit(2, 50, 12), PendingPriorityOpStatus::Unconfirmed, 3),
+            // Executed deposits are already reported in the committed balances.
+            PendingPriorityOp::new(&deposit(3, 1000, 8), PendingPriorityOpStatus::Executed, 0),
+            PendingPriorityOp::new(&full_exit, PendingPriorityOpStatus::Unconfirmed, 3),
+        ])
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_info_depositing() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let core_server = actix_test::start(|| {
+            actix_web::App::new().route(
+                "/priority_ops/pending/{address}",
+                web::get().to(mock_pending_priority_ops),
+            )
+        });
+        let core_address = core_server.url("").trim_end_matches('/').to_owned();
+        let app = rpc_app_with_core(&cfg, core_address);
+        let confirmations = cfg.config.eth_watch.confirmations_for_eth_event;
+
+        let info = app
+            .clone()
+            ._impl_account_info(Address::repeat_byte(0x22))
+            .await?;
+        let depositing = info.depositing.expect("Ethereum watcher is available");
+        assert_eq!(depositing.balances.len(), 1);
+        let eth = &depositing.balances["ETH"];
+        assert_eq!(eth.amount, 150u32.into());
+        assert_eq!(eth.expected_accept_block, 12 + confirmations);
+        assert_eq!(eth.confirmations_left, 3);
+
+        // The section is empty rather than missing when nothing is pending.
+        let info = app
+            .clone()
+            ._impl_account_info(Address::repeat_byte(0x33))
+            .await?;
+        assert_eq!(info.depositing, Some(Default::default()));
+
+        // The account info is returned without the section if the core can't be reached.
+        let info = rpc_app(&cfg)
+            ._impl_account_info(Address::repeat_byte(0x22))
+            .await?;
+        assert!(info.depositing.is_none());
+        let info = serde_json::to_value(info)?;
+        assert_eq!(info["depositing"], Value::Null);
+
+        Ok(())
+    }
+
+    fn mock_pending_block_info() -> PendingBlockInfo {
+        PendingBlockInfo {
+            block_number: BlockNumber(COMMITTED_BLOCKS_COUNT + 1),
+            chunks_used: 12,
+            available_chunk_sizes: vec![10, 32, 72],
+            success_txs: 2,
+            failed_txs: 1,
+            first_tx_timestamp: Some(1_600_000_000),
+        }
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn pending_block_info() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        let core_server = actix_test::start(|| {
+            actix_web::App::new().route(
+                "/state_keeper/pending_block",
+                web::get().to(|| async { HttpResponse::Ok().json(mock_pending_block_info()) }),
+            )
+        });
+        let core_address = core_server.url("").trim_end_matches('/').to_owned();
+        let mut rpc_app = rpc_app_with_core(&cfg, core_address);
+
+        // The method is disabled unless the config says otherwise.
+        let err = rpc_app
+            .clone()
+            ._impl_pending_block_info()
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::MethodNotFound);
+
+        rpc_app.set_pending_block_info_enabled(true);
+        let info = rpc_app._impl_pending_block_info().await?;
+        assert_eq!(info, mock_pending_block_info());
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn tx_info_status() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let rpc_app = rpc_app(&cfg);
+
+        let tx_info = rpc_app.clone()._impl_tx_info(TxHash::default()).await?;
+        assert_eq!(tx_info.status, TxInfoStatus::NotFound);
+        assert!(!tx_info.executed);
+
+        let queued_hash = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let tx = TestServerConfig::gen_zk_txs(1_u64).txs[0].0.clone();
+            let tx_hash = tx.hash();
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_tx(&SignedZkSyncTx {
+                    tx,
+                    eth_sign_data: None,
+                    created_at: chrono::Utc::now(),
+                })
+                .await?;
+            tx_hash
+        };
+        let tx_info = rpc_app.clone()._impl_tx_info(queued_hash).await?;
+        assert_eq!(tx_info.status, TxInfoStatus::Queued);
+        assert!(!tx_info.executed);
+        assert!(tx_info.success.is_none());
+
+        let success_hash = first_tx_hash(&cfg, BlockNumber(1)).await?;
+        let tx_info = rpc_app.clone()._impl_tx_info(success_hash).await?;
+        assert_eq!(tx_info.status, TxInfoStatus::Executed);
+        assert_eq!(tx_info.success, Some(true));
+        assert!(tx_info.fail_reason.is_none());
+
+        let rejected_hash = {
+            let mut storage = cfg.pool.access_storage().await?;
+            let mut rejected = None;
+            for block in 1..=COMMITTED_BLOCKS_COUNT {
+                rejected = storage
+                    .chain()
+                    .block_schema()
+                    .get_block_executed_ops(BlockNumber(block))
+                    .await?
+                    .into_iter()
+                    .filter_map(|op| op.get_executed_tx().cloned())
+                    .find(|tx| !tx.success)
+                    .map(|tx| tx.signed_tx.hash());
+                if rejected.is_some() {
+                    break;
+                }
+            }
+            rejected.unwrap()
+        };
+        let tx_info = rpc_app.clone()._impl_tx_info(rejected_hash).await?;
+        assert_eq!(tx_info.status, TxInfoStatus::Executed);
+        assert!(tx_info.executed);
+        assert_eq!(tx_info.success, Some(false));
+        assert_eq!(tx_info.fail_reason.as_deref(), Some("Unknown token"));
+
+        // The status is reported in snake case.
+        let tx_info = serde_json::to_value(rpc_app._impl_tx_info(TxHash::default()).await?)?;
+        assert_eq!(tx_info["status"], "not_found");
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn account_pending_nonce() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let io = rpc_handler(&cfg);
+
+        let mut storage = cfg.pool.access_storage().await?;
+        let (_, accounts) = storage
+            .chain()
+            .state_schema()
+            .load_committed_state(None)
+            .await?;
+        let (&account_id, account) = accounts.iter().max_by_key(|(id, _)| **id).unwrap();
+        let committed_nonce = storage
+            .chain()
+            .account_schema()
+            .estimate_nonce(account_id)
+            .await?
+            .unwrap_or_default();
+
+        let window = cfg.config.api.common.nonce_ahead_window;
+        let response = call(&io, "get_account_pending_nonce", json!([account.address])).await;
+        let pending: PendingNonce = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(
+            pending,
+            PendingNonce {
+                next_nonce: committed_nonce,
+                remaining_window: window,
+                missing_nonces: vec![],
+            }
+        );
+
+        // Queue the transactions with the nonces N and N + 2.
+        let sender = ZkSyncAccount::rand();
+        sender.set_account_id(Some(account_id));
+        let recipient = ZkSyncAccount::rand().address;
+        for nonce in [committed_nonce, committed_nonce + 2] {
+            let (tx, _) = sender.sign_transfer(
+                TokenId(0),
+                "ETH",
+                10_u64.into(),
+                1_u64.into(),
+                &recipient,
+                Some(nonce),
+                false,
+                Default::default(),
+            );
+            storage
+                .chain()
+                .mempool_schema()
+                .insert_tx(&SignedZkSyncTx {
+                    tx: ZkSyncTx::Transfer(Box::new(tx)),
+                    eth_sign_data: None,
+                    created_at: chrono::Utc::now(),
+                })
+                .await?;
+        }
+
+        let response = call(&io, "get_account_pending_nonce", json!([account.address])).await;
+        assert_eq!(
+            response["result"]["missingNonces"],
+            json!([*committed_nonce + 1])
+        );
+        let pending: PendingNonce = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(
+            pending,
+            PendingNonce {
+                next_nonce: committed_nonce + 3,
+                remaining_window: window - 1,
+                missing_nonces: vec![committed_nonce + 1],
+            }
+        );
+        // The method is an alias of `get_pending_nonce`.
+        let response = call(&io, "get_pending_nonce", json!([account.address])).await;
+        let same_pending: PendingNonce = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(same_pending, pending);
+
+        // Accounts unknown to the server start from the zero nonce.
+        let response = call(
+            &io,
+            "get_account_pending_nonce",
+            json!([ZkSyncAccount::rand().address]),
+        )
+        .await;
+        let pending: PendingNonce = serde_json::from_value(response["result"].clone())?;
+        assert_eq!(
+            pending,
+            PendingNonce {
+                remaining_window: window,
+                ..Default::default()
+            }
+        );
+
+        Ok(())
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn batch_requests() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let io = rpc_handler(&cfg);
+
+        let batch = json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "contract_address", "params": [] },
+            { "jsonrpc": "2.0", "id": 2, "method": "tokens", "params": [] },
+            { "jsonrpc": "2.0", "id": 3, "method": "no_such_method", "params": [] },
+        ]);
+        let response = request(&io, batch).await;
+        let outputs = response.as_array().unwrap();
+        let ids: Vec<_> = outputs.iter().map(|output| output["id"].clone()).collect();
+        assert_eq!(ids, vec![json!(1), json!(2), json!(3)]);
+        assert!(outputs[0]["result"]["mainContract"].is_string());
+        assert!(outputs[1]["result"]["ETH"].is_object());
+        assert_eq!(
+            outputs[2]["error"]["code"],
+            ErrorCode::MethodNotFound.code()
+        );
+
+        // Batches above the limit are rejected as a whole.
+        let max_batch_size = cfg.config.api.json_rpc.max_batch_size;
+        let batch: Vec<_> = (0..=max_batch_size)
+            .map(|id| json!({ "jsonrpc": "2.0", "id": id, "method": "tokens", "params": [] }))
+            .collect();
+        let response = request(&io, json!(batch)).await;
+        assert_eq!(response["error"]["code"], ErrorCode::InvalidRequest.code());
+        assert_eq!(response["id"], Value::Null);
+
+        Ok(())
+    }
+
+    #[test]
+    fn contract_address_serialization() {
+        let mut contracts = ContractAddressResp {
+            main_contract: "0x0101010101010101010101010101010101010101".to_string(),
+            gov_contract: "0x0202020202020202020202020202020202020202".to_string(),
+            nft_factory_contract: None,
+            contract_target: None,
+            contract_upgrade_version: None,
+        };
+
+        // Addresses that aren't known are omitted, so the response is the same as before.
+        let value = serde_json::to_value(&contracts).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "mainContract": contracts.main_contract,
+                "govContract": contracts.gov_contract,
+            })
+        );
+        let de: ContractAddressResp = serde_json::from_value(value).unwrap();
+        assert_eq!(de.nft_factory_contract, None);
+        assert_eq!(de.contract_target, None);
+        assert_eq!(de.contract_upgrade_version, None);
+
+        contracts.nft_factory_contract =
+            Some("0x0303030303030303030303030303030303030303".to_string());
+        contracts.contract_target = Some("0x0404040404040404040404040404040404040404".to_string());
+        let value = serde_json::to_value(&contracts).unwrap();
+        assert_eq!(
+            value["nftFactoryContract"],
+            json!(contracts.nft_factory_contract)
+        );
+        contracts.contract_upgrade_version = Some(2);
+        let value = serde_json::to_value(&contracts).unwrap();
+        assert_eq!(value["contractTarget"], json!(contracts.contract_target));
+        assert_eq!(value["contractUpgradeVersion"], json!(2));
+    }
 
     #[test]
     fn tx_fee_type_serialization() {
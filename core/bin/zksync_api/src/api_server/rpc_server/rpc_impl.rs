@@ -10,7 +10,7 @@ use zksync_api_types::{
         token::ApiNFT,
         transaction::{Toggle2FA, Toggle2FAResponse},
     },
-    TxWithSignature,
+    PendingBlockInfo, TxWithSignature,
 };
 use zksync_crypto::params::MIN_NFT_TOKEN_ID;
 use zksync_types::{
@@ -20,7 +20,8 @@ use zksync_types::{
 // Local uses
 use crate::{
     api_server::{
-        helpers::get_depositing, rpc_server::error::RpcErrorCodes, tx_sender::SubmitError,
+        helpers::get_depositing_from_eth_watch, rpc_server::error::RpcErrorCodes,
+        tx_sender::SubmitError,
     },
     fee_ticker::TokenPriceRequestType,
 };
@@ -34,23 +35,37 @@ impl RpcApp {
         let account_state = self.get_account_state(address).await?;
 
         let mut storage = self.access_storage().await?;
-        let depositing = get_depositing(
-            &mut storage,
-            &self.tx_sender.tokens,
-            address,
-            self.confirmations_for_eth_event,
-        )
-        .await
-        .map_err(|err| {
-            vlog::warn!(
-                "[{}:{}:{}] Internal Server Error: '{:?}'; input: N/A",
-                file!(),
-                line!(),
-                column!(),
-                err
-            );
-            Error::internal_error()
-        })?;
+        // The deposits waiting for confirmations are known only to the Ethereum watcher of the core,
+        // the account info is still returned if it's unavailable.
+        let depositing = match self.core_api_client.pending_priority_ops(address).await {
+            Ok(pending_ops) => Some(
+                get_depositing_from_eth_watch(
+                    &mut storage,
+                    &self.tx_sender.tokens,
+                    pending_ops,
+                    self.confirmations_for_eth_event,
+                )
+                .await
+                .map_err(|err| {
+                    vlog::warn!(
+                        "[{}:{}:{}] Internal Server Error: '{:?}'; input: N/A",
+                        file!(),
+                        line!(),
+                        column!(),
+                        err
+                    );
+                    Error::internal_error()
+                })?,
+            ),
+            Err(err) => {
+                vlog::warn!(
+                    "Failed to get the pending deposits of {:?} from the core: {}",
+                    address,
+                    err
+                );
+                None
+            }
+        };
         let account_type = if let Some(account_id) = account_state.account_id {
             storage
                 .chain()
@@ -107,6 +122,51 @@ impl RpcApp {
         Ok(result)
     }
 
+    pub async fn _impl_get_priority_op(self, serial_id: u32) -> Result<Option<PriorityOpResp>> {
+        let start = Instant::now();
+        let executed_op = self.get_executed_priority_operation(serial_id).await?;
+        let result = if let Some(executed_op) = executed_op {
+            let block = self.get_block_info(executed_op.block_number).await?;
+            let block = BlockInfo {
+                block_number: executed_op.block_number,
+                committed: true,
+                verified: block.map(|b| b.verified_at.is_some()).unwrap_or_default(),
+            };
+            Some(PriorityOpResp::executed(executed_op, block))
+        } else {
+            // Operations that are not executed yet are known only to the Ethereum watcher.
+            self.core_api_client
+                .pending_priority_op(serial_id.into())
+                .await
+                .map_err(|err| {
+                    vlog::warn!("Internal Server Error: '{}'; input: {}", err, serial_id);
+                    Error::internal_error()
+                })?
+                .map(PriorityOpResp::pending)
+        };
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_priority_op");
+        Ok(result)
+    }
+
+    pub async fn _impl_pending_block_info(self) -> Result<PendingBlockInfo> {
+        if !self.pending_block_info_enabled {
+            return Err(Error::method_not_found());
+        }
+        let start = Instant::now();
+        let info = self
+            .core_api_client
+            .pending_block_info()
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+                Error::internal_error()
+            })?;
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "pending_block_info");
+        Ok(info)
+    }
+
     pub async fn _impl_get_confirmations_for_eth_op_amount(self) -> Result<u64> {
         Ok(self.confirmations_for_eth_event)
     }
@@ -114,9 +174,9 @@ impl RpcApp {
     pub async fn _impl_tx_info(self, tx_hash: TxHash) -> Result<TransactionInfoResp> {
         let start = Instant::now();
         let stored_receipt = self.get_tx_receipt(tx_hash).await?;
-        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "tx_info");
-        Ok(if let Some(stored_receipt) = stored_receipt {
+        let result = if let Some(stored_receipt) = stored_receipt {
             TransactionInfoResp {
+                status: TxInfoStatus::Executed,
                 executed: true,
                 success: Some(stored_receipt.success),
                 fail_reason: stored_receipt.fail_reason,
@@ -127,13 +187,34 @@ impl RpcApp {
                 }),
             }
         } else {
+            // Queued transactions have no receipt yet, but unlike the unknown ones
+            // they shouldn't be resubmitted.
+            let mut storage = self.access_storage().await?;
+            let queued = storage
+                .chain()
+                .mempool_schema()
+                .contains_tx(tx_hash)
+                .await
+                .map_err(|err| {
+                    vlog::warn!("Internal Server Error: '{}'; input: {}", err, tx_hash);
+                    Error::internal_error()
+                })?;
+            let status = if queued {
+                TxInfoStatus::Queued
+            } else {
+                TxInfoStatus::NotFound
+            };
             TransactionInfoResp {
+                status,
                 executed: false,
                 success: None,
                 fail_reason: None,
                 block: None,
             }
-        })
+        };
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "tx_info");
+        Ok(result)
     }
 
     #[allow(deprecated)]
@@ -225,16 +306,38 @@ impl RpcApp {
         let gov_contract = config
             .gov_contract_addr
             .expect("Server config doesn't contain the gov contract address");
+        let contract_target = storage
+            .config_schema()
+            .load_zksync_target()
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+                Error::internal_error()
+            })?
+            .map(|target| format!("{:?}", target));
+        let contract_upgrade_version = storage
+            .config_schema()
+            .load_last_contract_upgrade()
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+                Error::internal_error()
+            })?
+            .map(|upgrade| upgrade.version_id as u64);
 
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "contract_address");
         Ok(ContractAddressResp {
             main_contract,
             gov_contract,
+            nft_factory_contract: config.nft_factory_addr,
+            contract_target,
+            contract_upgrade_version,
         })
     }
 
     pub async fn _impl_get_nft(self, id: TokenId) -> Result<Option<ApiNFT>> {
         let start = Instant::now();
+        check_nft_token_id(id)?;
         let mut storage = self.access_storage().await?;
         let nft = storage
             .tokens_schema()
@@ -277,25 +380,17 @@ impl RpcApp {
         tx_type: ApiTxFeeTypes,
         address: Address,
         token: TokenLike,
+        fee_token: Option<TokenLike>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<Fee> {
         let start = Instant::now();
-        let token_allowed = self
-            .tx_sender
-            .ticker
-            .token_allowed_for_fees(token.clone())
-            .await
-            .map_err(SubmitError::Internal)?;
-        if !token_allowed {
-            return Err(SubmitError::InappropriateFeeToken.into());
-        }
-
         let result = self
             .tx_sender
             .ticker
-            .get_fee_from_ticker_in_wei(tx_type.into(), token.clone(), address)
+            .get_tx_fee(tx_type.into(), address, token, fee_token)
             .await
-            .map_err(SubmitError::Internal)?;
+            .map_err(SubmitError::Internal)?
+            .ok_or(SubmitError::InappropriateFeeToken)?;
 
         let should_subsidize_cpk = self
             .tx_sender
@@ -303,6 +398,7 @@ impl RpcApp {
                 &result.normal_fee.total_fee,
                 &result.subsidized_fee.total_fee,
                 &result.subsidy_size_usd,
+                result.subsidy_campaign.as_ref(),
                 extracted_request_metadata,
             )
             .await?;
@@ -322,6 +418,7 @@ impl RpcApp {
         tx_types: Vec<ApiTxFeeTypes>,
         addresses: Vec<Address>,
         token: TokenLike,
+        fee_tokens: Option<Vec<TokenLike>>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<TotalFee> {
         let start = Instant::now();
@@ -332,30 +429,52 @@ impl RpcApp {
                 data: None,
             });
         }
-
-        let token_allowed = self
-            .tx_sender
-            .ticker
-            .token_allowed_for_fees(token.clone())
-            .await
-            .map_err(|_| Error::internal_error())?;
-        if !token_allowed {
-            return Err(SubmitError::InappropriateFeeToken.into());
+        if matches!(&fee_tokens, Some(fee_tokens) if fee_tokens.len() != tx_types.len()) {
+            return Err(Error {
+                code: RpcErrorCodes::IncorrectTx.into(),
+                message: "Number of fee_tokens must be equal to the number of tx_types".to_string(),
+                data: None,
+            });
         }
 
-        let transactions: Vec<(TxFeeTypes, Address)> = (tx_types
-            .iter()
-            .cloned()
-            .map(|fee_type| fee_type.into())
-            .zip(addresses.iter().cloned()))
-        .collect();
+        // Both the settlement token and the tokens transactions pay fees in must be allowed for fees.
+        let mut tokens = vec![token.clone()];
+        tokens.extend(fee_tokens.iter().flatten().cloned());
+        for token in tokens {
+            let token_allowed = self
+                .tx_sender
+                .ticker
+                .token_allowed_for_fees(token)
+                .await
+                .map_err(|_| Error::internal_error())?;
+            if !token_allowed {
+                return Err(SubmitError::InappropriateFeeToken.into());
+            }
+        }
 
-        let result = self
-            .tx_sender
-            .ticker
-            .get_batch_from_ticker_in_wei(token.clone(), transactions)
-            .await
-            .map_err(SubmitError::Internal)?;
+        let transactions = tx_types
+            .into_iter()
+            .map(TxFeeTypes::from)
+            .zip(addresses.into_iter());
+        let result = match fee_tokens {
+            Some(fee_tokens) => {
+                let transactions = transactions
+                    .zip(fee_tokens)
+                    .map(|((tx_type, address), fee_token)| (tx_type, address, fee_token))
+                    .collect();
+                self.tx_sender
+                    .ticker
+                    .get_mixed_batch_fee_in_wei(token.clone(), transactions)
+                    .await
+            }
+            None => {
+                self.tx_sender
+                    .ticker
+                    .get_batch_from_ticker_in_wei(token.clone(), transactions.collect())
+                    .await
+            }
+        }
+        .map_err(SubmitError::Internal)?;
 
         let should_subsidize_cpk = self
             .tx_sender
@@ -363,6 +482,7 @@ impl RpcApp {
                 &result.normal_fee.total_fee,
                 &result.subsidized_fee.total_fee,
                 &result.subsidy_size_usd,
+                result.subsidy_campaign.as_ref(),
                 extracted_request_metadata,
             )
             .await?;
@@ -403,20 +523,17 @@ impl RpcApp {
 
     pub async fn _impl_get_nft_owner(self, id: TokenId) -> Result<Option<AccountId>> {
         let start = Instant::now();
-        let owner_id = if id.0 < MIN_NFT_TOKEN_ID {
-            None
-        } else {
-            let mut storage = self.access_storage().await?;
-            storage
-                .chain()
-                .account_schema()
-                .get_nft_owner(id)
-                .await
-                .map_err(|err| {
-                    vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
-                    Error::internal_error()
-                })?
-        };
+        check_nft_token_id(id)?;
+        let mut storage = self.access_storage().await?;
+        let owner_id = storage
+            .chain()
+            .account_schema()
+            .get_nft_owner(id)
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: N/A", err);
+                Error::internal_error()
+            })?;
 
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_nft_owner");
         Ok(owner_id)
@@ -451,4 +568,47 @@ impl RpcApp {
         metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_nft_id_by_tx_hash");
         Ok(response)
     }
+
+    pub async fn _impl_get_pending_nonce(self, address: Address) -> Result<PendingNonce> {
+        let start = Instant::now();
+
+        let mut storage = self.access_storage().await?;
+        let account_id = storage
+            .chain()
+            .account_schema()
+            .account_id_by_address(address)
+            .await
+            .map_err(|err| {
+                vlog::warn!("Internal Server Error: '{}'; input: {:?}", err, address);
+                Error::internal_error()
+            })?;
+        drop(storage);
+
+        // Account that doesn't exist yet can't have transactions in the mempool.
+        let response = match account_id {
+            Some(account_id) => self
+                .tx_sender
+                .get_pending_nonce(account_id)
+                .await
+                .map_err(Error::from)?,
+            None => PendingNonce {
+                remaining_window: self.tx_sender.limits.nonce_ahead_window,
+                ..Default::default()
+            },
+        };
+
+        metrics::histogram!("api", start.elapsed(), "type" => "rpc", "endpoint_name" => "get_pending_nonce");
+        Ok(response)
+    }
+}
+
+/// Token ids below `MIN_NFT_TOKEN_ID` belong to the fungible tokens, so they can't identify an NFT.
+fn check_nft_token_id(id: TokenId) -> Result<()> {
+    if id.0 < MIN_NFT_TOKEN_ID {
+        return Err(Error::invalid_params(format!(
+            "NFT token ID should be greater than or equal to {}",
+            MIN_NFT_TOKEN_ID
+        )));
+    }
+    Ok(())
 }
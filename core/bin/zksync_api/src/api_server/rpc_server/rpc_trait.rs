@@ -11,7 +11,7 @@ use zksync_api_types::{
         token::ApiNFT,
         transaction::{Toggle2FA, Toggle2FAResponse},
     },
-    TxWithSignature,
+    PendingBlockInfo, TxWithSignature,
 };
 use zksync_crypto::params::ZKSYNC_VERSION;
 use zksync_types::{
@@ -42,6 +42,16 @@ pub trait Rpc {
     #[rpc(name = "tx_info", returns = "ETHOpInfoResp")]
     fn tx_info(&self, hash: TxHash) -> BoxFutureResult<TransactionInfoResp>;
 
+    /// Returns the executed priority operation, or the stub of the one waiting on L1,
+    /// `null` if the serial id is unknown.
+    #[rpc(name = "get_priority_op", returns = "Option<PriorityOpResp>")]
+    fn get_priority_op(&self, serial_id: u32) -> BoxFutureResult<Option<PriorityOpResp>>;
+
+    /// Returns the block currently being filled by the state keeper of the core.
+    /// Unavailable if disabled in the config.
+    #[rpc(name = "pending_block_info", returns = "PendingBlockInfo")]
+    fn pending_block_info(&self) -> BoxFutureResult<PendingBlockInfo>;
+
     #[rpc(name = "tx_submit", returns = "TxHash")]
     fn tx_submit(
         &self,
@@ -67,22 +77,27 @@ pub trait Rpc {
     fn tokens(&self) -> BoxFutureResult<HashMap<String, Token>>;
 
     // _address argument is left for the backward compatibility.
+    // If `fee_token` is provided, the fee is paid and expressed in it instead of the `token_like`.
     #[rpc(name = "get_tx_fee", returns = "Fee")]
     fn get_tx_fee(
         &self,
         tx_type: ApiTxFeeTypes,
         _address: Address,
         token_like: TokenLike,
+        fee_token: Option<TokenLike>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<Fee>;
 
     // _addresses argument is left for the backward compatibility.
+    // If `fee_tokens` are provided, the fee of every transaction is paid in the corresponding token
+    // and the total fee is expressed in the `token_like`.
     #[rpc(name = "get_txs_batch_fee_in_wei", returns = "TotalFee")]
     fn get_txs_batch_fee_in_wei(
         &self,
         tx_types: Vec<ApiTxFeeTypes>,
         _addresses: Vec<Address>,
         token_like: TokenLike,
+        fee_tokens: Option<Vec<TokenLike>>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> BoxFutureResult<TotalFee>;
 
@@ -110,6 +125,13 @@ pub trait Rpc {
 
     #[rpc(name = "get_nft_id_by_tx_hash", returns = "Option<TokenId>")]
     fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> BoxFutureResult<Option<TokenId>>;
+
+    #[rpc(
+        name = "get_pending_nonce",
+        alias("get_account_pending_nonce"),
+        returns = "PendingNonce"
+    )]
+    fn get_pending_nonce(&self, addr: Address) -> BoxFutureResult<PendingNonce>;
 }
 
 impl Rpc for RpcApp {
@@ -125,6 +147,14 @@ impl Rpc for RpcApp {
         spawn!(self._impl_tx_info(hash))
     }
 
+    fn get_priority_op(&self, serial_id: u32) -> BoxFutureResult<Option<PriorityOpResp>> {
+        spawn!(self._impl_get_priority_op(serial_id))
+    }
+
+    fn pending_block_info(&self) -> BoxFutureResult<PendingBlockInfo> {
+        spawn!(self._impl_pending_block_info())
+    }
+
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
     fn tx_submit(
         &self,
@@ -160,9 +190,10 @@ impl Rpc for RpcApp {
         tx_type: ApiTxFeeTypes,
         address: Address,
         token_like: TokenLike,
+        fee_token: Option<TokenLike>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<Fee> {
-        spawn!(self._impl_get_tx_fee(tx_type, address, token_like, meta))
+        spawn!(self._impl_get_tx_fee(tx_type, address, token_like, fee_token, meta))
     }
 
     // Important: the last parameter should have name `meta` and be of type `RequestMetadata`
@@ -171,9 +202,12 @@ impl Rpc for RpcApp {
         tx_types: Vec<ApiTxFeeTypes>,
         addresses: Vec<Address>,
         token_like: TokenLike,
+        fee_tokens: Option<Vec<TokenLike>>,
         meta: Option<RequestMetadata>,
     ) -> BoxFutureResult<TotalFee> {
-        spawn!(self._impl_get_txs_batch_fee_in_wei(tx_types, addresses, token_like, meta))
+        spawn!(
+            self._impl_get_txs_batch_fee_in_wei(tx_types, addresses, token_like, fee_tokens, meta)
+        )
     }
 
     fn get_token_price(&self, token_like: TokenLike) -> BoxFutureResult<BigDecimal> {
@@ -210,4 +244,8 @@ impl Rpc for RpcApp {
     fn get_nft_id_by_tx_hash(&self, tx_hash: TxHash) -> BoxFutureResult<Option<TokenId>> {
         spawn!(self._impl_get_nft_id_by_tx_hash(tx_hash))
     }
+
+    fn get_pending_nonce(&self, addr: Address) -> BoxFutureResult<PendingNonce> {
+        spawn!(self._impl_get_pending_nonce(addr))
+    }
 }
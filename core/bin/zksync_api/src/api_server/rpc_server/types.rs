@@ -6,13 +6,23 @@ use serde::{Deserialize, Serialize};
 
 // Workspace uses
 use zksync_api_types::v02::{
-    account::{DepositingAccountBalances, EthAccountType},
+    account::{DepositingAccountBalances, EthAccountType, PendingPriorityOp},
     token::NFT,
+    transaction::TxType,
+};
+use zksync_crypto::{
+    params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL},
+    serialization::FrSerde,
+    Fr,
+};
+use zksync_storage::{
+    chain::operations::records::StoredExecutedPriorityOperation, StorageProcessor,
 };
-use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID_VAL};
-use zksync_storage::StorageProcessor;
 use zksync_token_db_cache::TokenDBCache;
-use zksync_types::{Account, AccountId, Address, Nonce, PubKeyHash, TokenId};
+use zksync_types::{
+    tx::TxHash, Account, AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, SerialId,
+    TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
+};
 use zksync_utils::BigUintSerdeWrapper;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -89,7 +99,9 @@ pub struct AccountStateInfo {
 pub struct AccountInfoResp {
     pub address: Address,
     pub id: Option<AccountId>,
-    pub depositing: DepositingAccountBalances,
+    /// Deposits seen by the Ethereum watcher, but not executed yet.
+    /// `None` if the Ethereum watcher is unavailable at the moment.
+    pub depositing: Option<DepositingAccountBalances>,
     pub committed: ResponseAccountState,
     pub verified: ResponseAccountState,
     pub account_type: Option<EthAccountType>,
@@ -103,9 +115,22 @@ pub struct BlockInfo {
     pub verified: bool,
 }
 
+/// Whether the transaction is known to the server and how far it has got.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxInfoStatus {
+    /// The transaction was never seen by the server.
+    NotFound,
+    /// The transaction is in the mempool waiting to be executed.
+    Queued,
+    /// The transaction was executed, successfully or not.
+    Executed,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionInfoResp {
+    pub status: TxInfoStatus,
     pub executed: bool,
     pub success: Option<bool>,
     pub fail_reason: Option<String>,
@@ -119,11 +144,143 @@ pub struct ETHOpInfoResp {
     pub block: Option<BlockInfo>,
 }
 
+/// Priority operation found by its serial id.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpResp {
+    pub serial_id: SerialId,
+    pub op_type: TxType,
+    /// Deposit or full exit fields, `None` for the pending operations.
+    pub op: Option<ZkSyncPriorityOp>,
+    /// Amount withdrawn by the executed full exit.
+    pub withdrawn_amount: Option<BigUintSerdeWrapper>,
+    pub eth_hash: H256,
+    pub eth_block: u64,
+    /// Whether the operation is seen on L1, but not executed yet.
+    pub pending: bool,
+    /// Number of Ethereum blocks left until the pending operation is accepted by the server.
+    pub confirmations_left: u64,
+    /// Block the operation is executed in.
+    pub block: Option<BlockInfo>,
+}
+
+impl PriorityOpResp {
+    pub fn executed(op: StoredExecutedPriorityOperation, block: BlockInfo) -> Self {
+        // Withdrawn amount of the full exit is known only after the execution.
+        let withdrawn_amount = match serde_json::from_value(op.operation.clone()) {
+            Ok(ZkSyncOp::FullExit(full_exit)) => full_exit.withdraw_amount,
+            _ => None,
+        };
+        let op = PriorityOp::from(op);
+        let op_type = match &op.data {
+            ZkSyncPriorityOp::Deposit(_) => TxType::Deposit,
+            ZkSyncPriorityOp::FullExit(_) => TxType::FullExit,
+        };
+        Self {
+            serial_id: op.serial_id,
+            op_type,
+            op: Some(op.data),
+            withdrawn_amount,
+            eth_hash: op.eth_hash,
+            eth_block: op.eth_block,
+            pending: false,
+            confirmations_left: 0,
+            block: Some(block),
+        }
+    }
+
+    pub fn pending(op: PendingPriorityOp) -> Self {
+        Self {
+            serial_id: op.serial_id,
+            op_type: op.op_type,
+            op: None,
+            withdrawn_amount: None,
+            eth_hash: op.eth_hash,
+            eth_block: op.eth_block,
+            pending: true,
+            confirmations_left: op.confirmations_left,
+            block: None,
+        }
+    }
+}
+
+/// Stage of the blocks the `blocks` subscription is notified about.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum BlockSubscriptionMode {
+    Committed,
+    Finalized,
+}
+
+/// Notification about the new block sent to the `blocks` subscribers.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockNotification {
+    pub block_number: BlockNumber,
+    #[serde(with = "FrSerde")]
+    pub new_state_root: Fr,
+    pub tx_count: usize,
+    pub timestamp: u64,
+}
+
+/// Notification about the transaction of the watched address accepted into the mempool,
+/// sent to the `mempool` subscribers.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolTxNotification {
+    pub tx_hash: TxHash,
+    pub tx_type: String,
+    /// Token transferred by the transaction, or the fee token if it doesn't transfer any.
+    pub token: TokenId,
+    /// Amount transferred by the transaction, if there is one.
+    pub amount: Option<BigUintSerdeWrapper>,
+    pub nonce: Nonce,
+}
+
+impl MempoolTxNotification {
+    pub fn new(tx: &ZkSyncTx) -> Self {
+        let (token, amount) = match tx {
+            ZkSyncTx::Transfer(tx) => (tx.token, Some(tx.amount.clone())),
+            ZkSyncTx::Withdraw(tx) => (tx.token, Some(tx.amount.clone())),
+            ZkSyncTx::ForcedExit(tx) => (tx.token, None),
+            ZkSyncTx::WithdrawNFT(tx) => (tx.token, None),
+            _ => (tx.token_id(), None),
+        };
+        Self {
+            tx_hash: tx.hash(),
+            tx_type: tx.variance_name(),
+            token,
+            amount: amount.map(BigUintSerdeWrapper),
+            nonce: tx.nonce(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ContractAddressResp {
     pub main_contract: String,
     pub gov_contract: String,
+    /// Address of the default NFT factory, omitted if it's not known to the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nft_factory_contract: Option<String>,
+    /// Current target of the main contract, omitted if the contract has never been upgraded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_target: Option<String>,
+    /// Version of the contracts set by the latest upgrade, omitted if the contracts have never been upgraded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_upgrade_version: Option<u64>,
+}
+
+/// The nonce following the highest one queued for the account and how many nonces
+/// ahead can still be submitted, along with the nonces missing below the highest queued one.
+/// Transactions queued after a gap are stuck until the missing nonces are submitted.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingNonce {
+    pub next_nonce: Nonce,
+    pub remaining_window: u32,
+    pub missing_nonces: Vec<Nonce>,
 }
 
 /// The metadata of the JSON-RPC call retrieved from the HTTP request of the call
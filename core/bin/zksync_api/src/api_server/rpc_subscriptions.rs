@@ -1,26 +1,40 @@
 #![allow(clippy::needless_return)]
 
 // Built-in deps
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
 use std::time::Duration;
 // External uses
 use futures::channel::mpsc;
-use jsonrpc_core::{MetaIoHandler, Result};
+use jsonrpc_core::{Error, MetaIoHandler, Metadata, Result};
 use jsonrpc_derive::rpc;
-use jsonrpc_pubsub::{typed::Subscriber, PubSubHandler, Session, SubscriptionId};
-use jsonrpc_ws_server::RequestContext;
+use jsonrpc_pubsub::{typed::Subscriber, PubSubHandler, PubSubMetadata, Session, SubscriptionId};
+use jsonrpc_ws_server::ws;
+use serde_json::json;
 use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_config::configs::api::{CommonApiConfig, JsonRpcConfig, TokenConfig};
 use zksync_mempool::MempoolTransactionRequest;
 use zksync_storage::ConnectionPool;
-use zksync_types::{tx::TxHash, ActionType, Address};
+use zksync_types::{tx::TxHash, ActionType, Address, ZkSyncTx};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 // Local uses
+use super::{
+    mempool_resync::SubmittedTxs,
+    sender_limiter::SenderRateLimiter,
+    shutdown::{close_on_shutdown, InFlightCalls, ShutdownSignal},
+    ws_transport::{self, KeepAlive},
+};
 use crate::fee_ticker::FeeTicker;
 use crate::{
-    api_server::event_notify::{start_sub_notifier, EventNotifierRequest, EventSubscribeRequest},
-    api_server::rpc_server::types::{ETHOpInfoResp, ResponseAccountState, TransactionInfoResp},
+    api_server::event_notify::{
+        start_sub_notifier, Connection, ConnectionId, EventNotifierRequest, EventSubscribeRequest,
+    },
+    api_server::rpc_server::error::RpcErrorCodes,
+    api_server::rpc_server::types::{
+        BlockNotification, BlockSubscriptionMode, ETHOpInfoResp, MempoolTxNotification,
+        ResponseAccountState, TransactionInfoResp,
+    },
+    core_api_client::CoreApiClient,
     signature_checker::VerifySignatureRequest,
 };
 
@@ -82,31 +96,66 @@ pub trait RpcPubSub {
         meta: Option<Self::Metadata>,
         subscription: SubscriptionId,
     ) -> Result<bool>;
+
+    #[pubsub(
+        subscription = "blocks",
+        subscribe,
+        name = "blocks_subscribe",
+        alias("blocks_sub")
+    )]
+    fn subscribe_blocks(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<BlockNotification>,
+        mode: BlockSubscriptionMode,
+    );
+    #[pubsub(subscription = "blocks", unsubscribe, name = "blocks_unsubscribe")]
+    fn unsubscribe_blocks(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
+
+    #[pubsub(
+        subscription = "mempool",
+        subscribe,
+        name = "mempool_subscribe",
+        alias("mempool_sub")
+    )]
+    fn subscribe_mempool(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<MempoolTxNotification>,
+        address: Address,
+    );
+    #[pubsub(subscription = "mempool", unsubscribe, name = "mempool_unsubscribe")]
+    fn unsubscribe_mempool(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
 }
 
 impl RpcPubSub for RpcSubApp {
-    type Metadata = Arc<Session>;
+    type Metadata = WsSession;
 
     // subscribe - sub id, sink
     // unsub - sub id
 
     fn subscribe_tx(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         subscriber: Subscriber<TransactionInfoResp>,
         hash: TxHash,
         action: ActionType,
     ) {
-        self.event_sub_sender
-            .clone()
-            .try_send(EventNotifierRequest::Sub(
-                EventSubscribeRequest::Transaction {
-                    hash,
-                    action,
-                    subscriber,
-                },
-            ))
-            .unwrap_or_default();
+        self.subscribe(&meta, subscriber, |subscriber| {
+            EventSubscribeRequest::Transaction {
+                hash,
+                action,
+                subscriber,
+            }
+        });
     }
     fn unsubscribe_tx(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
         self.event_sub_sender
@@ -118,21 +167,18 @@ impl RpcPubSub for RpcSubApp {
 
     fn subscribe_ethop(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         subscriber: Subscriber<ETHOpInfoResp>,
         serial_id: u64,
         action: ActionType,
     ) {
-        self.event_sub_sender
-            .clone()
-            .try_send(EventNotifierRequest::Sub(
-                EventSubscribeRequest::PriorityOp {
-                    serial_id,
-                    action,
-                    subscriber,
-                },
-            ))
-            .unwrap_or_default();
+        self.subscribe(&meta, subscriber, |subscriber| {
+            EventSubscribeRequest::PriorityOp {
+                serial_id,
+                action,
+                subscriber,
+            }
+        });
     }
     fn unsubscribe_ethop(&self, _meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
         self.event_sub_sender
@@ -144,19 +190,18 @@ impl RpcPubSub for RpcSubApp {
 
     fn subscribe_account(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         subscriber: Subscriber<ResponseAccountState>,
         address: Address,
         action: ActionType,
     ) {
-        self.event_sub_sender
-            .clone()
-            .try_send(EventNotifierRequest::Sub(EventSubscribeRequest::Account {
+        self.subscribe(&meta, subscriber, |subscriber| {
+            EventSubscribeRequest::Account {
                 address,
                 action,
                 subscriber,
-            }))
-            .unwrap_or_default();
+            }
+        });
     }
 
     fn unsubscribe_account(
@@ -170,10 +215,168 @@ impl RpcPubSub for RpcSubApp {
             .unwrap_or_default();
         Ok(true)
     }
+
+    fn subscribe_blocks(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<BlockNotification>,
+        mode: BlockSubscriptionMode,
+    ) {
+        self.subscribe(&meta, subscriber, |subscriber| {
+            EventSubscribeRequest::Blocks { mode, subscriber }
+        });
+    }
+
+    fn unsubscribe_blocks(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Unsub(id))
+            .unwrap_or_default();
+        Ok(true)
+    }
+
+    fn subscribe_mempool(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<MempoolTxNotification>,
+        address: Address,
+    ) {
+        self.subscribe(&meta, subscriber, |subscriber| {
+            EventSubscribeRequest::Mempool {
+                address,
+                subscriber,
+            }
+        });
+    }
+
+    fn unsubscribe_mempool(
+        &self,
+        _meta: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> Result<bool> {
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Unsub(id))
+            .unwrap_or_default();
+        Ok(true)
+    }
+}
+
+/// Metadata of the WebSocket connection.
+#[derive(Clone)]
+struct WsSession {
+    session: Arc<Session>,
+    connection: Arc<Connection>,
+}
+
+impl WsSession {
+    /// Creates the session of the new connection. Once the connection is closed,
+    /// it's marked as such and its id is sent to the notifier to remove the subscriptions
+    /// of the connection.
+    fn new(
+        transport: jsonrpc_pubsub::TransportSender,
+        disconnect_sender: mpsc::UnboundedSender<ConnectionId>,
+    ) -> Self {
+        let session = Arc::new(Session::new(transport));
+        let connection = Connection::new();
+
+        let closed_connection = connection.clone();
+        session.on_drop(move || {
+            // Subscriptions queued for the notifier may be handled after the disconnect,
+            // they are refused once the connection is marked as closed.
+            closed_connection.close();
+            disconnect_sender
+                .unbounded_send(closed_connection.id())
+                .unwrap_or_default();
+        });
+
+        Self {
+            session,
+            connection,
+        }
+    }
+}
+
+impl Metadata for WsSession {}
+
+impl PubSubMetadata for WsSession {
+    fn session(&self) -> Option<Arc<Session>> {
+        Some(self.session.clone())
+    }
 }
 
 struct RpcSubApp {
     event_sub_sender: mpsc::Sender<EventNotifierRequest>,
+    max_subscriptions_per_connection: usize,
+}
+
+impl RpcSubApp {
+    /// Sends the subscription request to the notifier, unless the connection has already
+    /// reached the limit of the active subscriptions, in which case the subscriber is rejected.
+    fn subscribe<T>(
+        &self,
+        meta: &WsSession,
+        subscriber: Subscriber<T>,
+        request: impl FnOnce(Subscriber<T>) -> EventSubscribeRequest,
+    ) {
+        let slot = match meta
+            .connection
+            .reserve_slot(self.max_subscriptions_per_connection)
+        {
+            Some(slot) => slot,
+            None => {
+                subscriber
+                    .reject(subscriptions_limit_error(
+                        self.max_subscriptions_per_connection,
+                    ))
+                    .unwrap_or_default();
+                return;
+            }
+        };
+
+        self.event_sub_sender
+            .clone()
+            .try_send(EventNotifierRequest::Sub {
+                request: request(subscriber),
+                slot,
+            })
+            .unwrap_or_default();
+    }
+}
+
+/// Closes the connection with `close` unless it subscribes to anything within the timeout.
+///
+/// Closing the connection drops its session, so it's cleaned up the same way as if the client
+/// disconnected by itself.
+async fn close_if_idle(connection: Weak<Connection>, timeout: Duration, close: impl FnOnce()) {
+    tokio::time::sleep(timeout).await;
+    let idle = connection
+        .upgrade()
+        .map_or(false, |connection| !connection.has_subscribed());
+    if idle {
+        metrics::increment_counter!("api.ws.closed_connections", "reason" => "idle_timeout");
+        close();
+    }
+}
+
+fn subscriptions_limit_error(limit: usize) -> Error {
+    let code = RpcErrorCodes::SubscriptionsLimitReached;
+    Error {
+        code: code.into(),
+        message: format!(
+            "The connection has reached the limit of {} active subscriptions",
+            limit
+        ),
+        data: Some(json!({
+            "code": code as i64,
+            "reason": code.as_str(),
+            "limit": limit,
+        })),
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -187,21 +390,38 @@ pub fn start_ws_server(
     config: &JsonRpcConfig,
     miniblock_iteration_interval: Duration,
     mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    accepted_txs_sender: mpsc::Sender<Vec<ZkSyncTx>>,
+    accepted_txs_receiver: mpsc::Receiver<Vec<ZkSyncTx>>,
+    submitted_txs: Option<SubmittedTxs>,
+    sender_limiter: Option<SenderRateLimiter>,
     confirmations_for_eth_event: u64,
+    core_address: String,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
     let addr = config.ws_bind_addr();
 
+    let max_subscriptions_per_connection = config.max_subscriptions_per_connection;
+    let idle_timeout = config.ws_idle_timeout();
+    let keep_alive = config.ws_ping_interval().map(|interval| KeepAlive {
+        interval,
+        max_missed_pongs: config.ws_max_missed_pongs,
+    });
+
     let (event_sub_sender, event_sub_receiver) = mpsc::channel(2048);
+    // Every connection is closed only once, so the channel is bounded by the number of connections.
+    let (disconnect_sender, disconnect_receiver) = mpsc::unbounded();
 
     start_sub_notifier(
         db_pool.clone(),
         event_sub_receiver,
+        disconnect_receiver,
+        accepted_txs_receiver,
         common_config.caches_size,
         miniblock_iteration_interval,
         token_config,
     );
 
-    let req_rpc_app = super::rpc_server::RpcApp::new(
+    let mut req_rpc_app = super::rpc_server::RpcApp::new(
         db_pool,
         sign_verify_request_sender,
         ticker,
@@ -209,29 +429,355 @@ pub fn start_ws_server(
         token_config,
         confirmations_for_eth_event,
         mempool_tx_sender,
+        CoreApiClient::new(core_address),
     );
+    req_rpc_app.set_accepted_txs_sender(Some(accepted_txs_sender));
+    req_rpc_app.set_submitted_txs(submitted_txs);
+    req_rpc_app.set_sender_limiter(sender_limiter);
+    req_rpc_app.set_pending_block_info_enabled(config.pending_block_info_enabled);
 
+    let in_flight_calls = InFlightCalls::default();
+    let runtime = tokio::runtime::Handle::current();
+    let idle_runtime = runtime.clone();
     let (handler, panic_sender) = spawn_panic_handler();
 
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
-        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let mut io = PubSubHandler::new(MetaIoHandler::with_middleware(in_flight_calls.clone()));
 
         req_rpc_app.extend(&mut io);
 
-        let rpc_sub_app = RpcSubApp { event_sub_sender };
+        let rpc_sub_app = RpcSubApp {
+            event_sub_sender,
+            max_subscriptions_per_connection,
+        };
 
         io.extend_with(rpc_sub_app.to_delegate());
 
-        let server = jsonrpc_ws_server::ServerBuilder::with_meta_extractor(
+        let server = ws_transport::bind(
+            &addr,
             io,
-            |context: &RequestContext| Arc::new(Session::new(context.sender())),
+            1000,
+            keep_alive,
+            runtime.clone(),
+            move |out, transport| {
+                let session = WsSession::new(transport, disconnect_sender.clone());
+                if let Some(timeout) = idle_timeout {
+                    let out = out.clone();
+                    idle_runtime.spawn(close_if_idle(
+                        Arc::downgrade(&session.connection),
+                        timeout,
+                        move || out.close(ws::CloseCode::Normal).unwrap_or_default(),
+                    ));
+                }
+                session
+            },
         )
-        .max_connections(1000)
-        .start(&addr)
         .expect("Unable to start RPC ws server");
 
-        server.wait().expect("rpc ws server start");
+        // Shutting down the server sends the close frames to all the active sessions.
+        let broadcaster = server.broadcaster();
+        close_on_shutdown(&shutdown, &runtime, in_flight_calls, move || {
+            broadcaster.shutdown().unwrap_or_default()
+        });
+        server.run().expect("rpc ws server start");
+        vlog::info!("JSON-RPC WebSocket server has stopped");
     });
     handler
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_server::{
+        event_notify::OperationNotifier,
+        rest::v02::test_utils::{submit_txs_loopback, test_tx_sender, TestServerConfig},
+    };
+    use futures::{channel::oneshot, StreamExt};
+    use serde_json::Value;
+    use zksync_storage::test_data::{gen_unique_aggregated_operation_with_txs, BLOCK_SIZE_CHUNKS};
+    use zksync_types::{
+        aggregated_operations::AggregatedActionType, tx::TxEthSignatureVariant, BlockNumber,
+    };
+
+    fn blocks_subscribe(id: usize) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "blocks_subscribe",
+            "params": ["committed"],
+        })
+        .to_string()
+    }
+
+    /// WebSocket client subscribing to the committed blocks once connected,
+    /// the received messages are passed to the test.
+    struct BlocksClient {
+        out: ws::Sender,
+        messages: mpsc::UnboundedSender<Value>,
+    }
+
+    impl ws::Handler for BlocksClient {
+        fn on_open(&mut self, _: ws::Handshake) -> ws::Result<()> {
+            self.out.send(blocks_subscribe(1))
+        }
+
+        fn on_message(&mut self, message: ws::Message) -> ws::Result<()> {
+            let message = serde_json::from_str(&message.into_text()?).unwrap();
+            self.messages.unbounded_send(message).unwrap_or_default();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn blocks_subscription() {
+        let (event_sub_sender, mut event_sub_receiver) = mpsc::channel(2048);
+        let (disconnect_sender, _disconnect_receiver) = mpsc::unbounded();
+        let mut notifier =
+            OperationNotifier::new(10, ConnectionPool::new(Some(1)), Duration::from_secs(10));
+
+        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let rpc_sub_app = RpcSubApp {
+            event_sub_sender,
+            max_subscriptions_per_connection: 10,
+        };
+        io.extend_with(rpc_sub_app.to_delegate());
+
+        let runtime = tokio::runtime::Handle::current();
+        let (server_sender, server_receiver) = std::sync::mpsc::channel();
+        let server = std::thread::spawn(move || {
+            let server = ws_transport::bind(
+                &"127.0.0.1:0".parse().unwrap(),
+                io,
+                10,
+                None,
+                runtime,
+                move |_, transport| WsSession::new(transport, disconnect_sender.clone()),
+            )
+            .unwrap();
+            server_sender
+                .send((server.local_addr().unwrap(), server.broadcaster()))
+                .unwrap();
+            server.run().map(drop)
+        });
+        let (addr, broadcaster) = server_receiver.recv().unwrap();
+
+        let (messages_sender, mut messages) = mpsc::unbounded();
+        let client = std::thread::spawn(move || {
+            ws::connect(format!("ws://{}", addr), |out| BlocksClient {
+                out,
+                messages: messages_sender.clone(),
+            })
+            .unwrap();
+        });
+
+        let request = event_sub_receiver.next().await.unwrap();
+        notifier.handle_notify_req(request).await.unwrap();
+        let response = messages.next().await.unwrap();
+        let sub_id = response["result"].clone();
+        assert!(sub_id.is_string(), "{}", response);
+
+        // The client observes every committed block in order.
+        for block_number in 1..=2 {
+            let operation = gen_unique_aggregated_operation_with_txs(
+                BlockNumber(block_number),
+                AggregatedActionType::CommitBlocks,
+                BLOCK_SIZE_CHUNKS,
+                Vec::new(),
+            );
+            notifier.handle_new_block(operation).await.unwrap();
+        }
+        for expected in 1..=2 {
+            let notification = messages.next().await.unwrap();
+            assert_eq!(notification["method"], "blocks");
+            assert_eq!(notification["params"]["subscription"], sub_id);
+            assert_eq!(notification["params"]["result"]["blockNumber"], expected);
+            assert_eq!(notification["params"]["result"]["txCount"], 0);
+        }
+
+        broadcaster.shutdown().unwrap();
+        client.join().unwrap();
+        server.join().unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn subscriptions_limit_and_disconnect() {
+        const LIMIT: usize = 2;
+
+        let (event_sub_sender, mut event_sub_receiver) = mpsc::channel(2048);
+        let (disconnect_sender, mut disconnect_receiver) = mpsc::unbounded();
+        let mut notifier =
+            OperationNotifier::new(10, ConnectionPool::new(Some(1)), Duration::from_secs(10));
+
+        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let rpc_sub_app = RpcSubApp {
+            event_sub_sender,
+            max_subscriptions_per_connection: LIMIT,
+        };
+        io.extend_with(rpc_sub_app.to_delegate());
+
+        let (transport, _notifications) = mpsc::unbounded();
+        let meta = WsSession::new(transport, disconnect_sender);
+        let connection = meta.connection.clone();
+
+        // Subscriptions up to the limit are passed to the notifier.
+        for id in 0..LIMIT {
+            let (response, _) = futures::join!(
+                io.handle_request(&blocks_subscribe(id), meta.clone()),
+                async {
+                    let request = event_sub_receiver.next().await.unwrap();
+                    notifier.handle_notify_req(request).await.unwrap();
+                }
+            );
+            let response: Value = serde_json::from_str(&response.unwrap()).unwrap();
+            assert!(response["result"].is_string(), "{}", response);
+        }
+        assert_eq!(connection.active_subscriptions(), LIMIT);
+
+        // The next ones are rejected right away.
+        let response = io
+            .handle_request(&blocks_subscribe(LIMIT), meta.clone())
+            .await
+            .unwrap();
+        let response: Value = serde_json::from_str(&response).unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            RpcErrorCodes::SubscriptionsLimitReached as i64
+        );
+        assert_eq!(response["error"]["data"]["limit"], LIMIT);
+        assert!(event_sub_receiver.try_next().is_err());
+
+        // Closing the connection removes all of its subscriptions from the notifier.
+        drop(meta);
+        let closed = disconnect_receiver.next().await.unwrap();
+        assert_eq!(closed, connection.id());
+        assert!(connection.is_closed());
+        notifier.handle_disconnect(closed);
+        assert!(notifier.is_empty());
+        assert_eq!(connection.active_subscriptions(), 0);
+    }
+
+    #[tokio::test]
+    async fn idle_connections_are_closed() {
+        const IDLE_TIMEOUT: Duration = Duration::from_millis(50);
+        let (disconnect_sender, mut disconnect_receiver) = mpsc::unbounded();
+
+        // The connection that never subscribed is closed, the server drops its session then,
+        // so it's cleaned up as if the client disconnected.
+        let (transport, _notifications) = mpsc::unbounded();
+        let idle = WsSession::new(transport, disconnect_sender.clone());
+        let idle_id = idle.connection.id();
+        let connection = Arc::downgrade(&idle.connection);
+        close_if_idle(connection, IDLE_TIMEOUT, move || drop(idle)).await;
+        assert_eq!(disconnect_receiver.next().await.unwrap(), idle_id);
+
+        let (transport, _notifications) = mpsc::unbounded();
+        let active = WsSession::new(transport, disconnect_sender);
+        let _slot = active.connection.reserve_slot(1).unwrap();
+        let (closed_sender, closed_receiver) = oneshot::channel::<()>();
+        let connection = Arc::downgrade(&active.connection);
+        close_if_idle(connection, IDLE_TIMEOUT, move || {
+            closed_sender.send(()).unwrap()
+        })
+        .await;
+        assert!(
+            closed_receiver.await.is_err(),
+            "Connection with subscriptions should stay open"
+        );
+    }
+
+    #[actix_rt::test]
+    #[cfg_attr(
+        not(feature = "api_test"),
+        ignore = "Use `zk test rust-api` command to perform this test"
+    )]
+    async fn mempool_subscription() -> anyhow::Result<()> {
+        let cfg = TestServerConfig::default();
+        cfg.fill_database().await?;
+        let (mempool_sender, mempool_task) = submit_txs_loopback();
+
+        let (event_sub_sender, mut event_sub_receiver) = mpsc::channel(2048);
+        let (disconnect_sender, _disconnect_receiver) = mpsc::unbounded();
+        let mut notifier = OperationNotifier::new(10, cfg.pool.clone(), Duration::from_secs(10));
+
+        let mut io = PubSubHandler::new(MetaIoHandler::default());
+        let rpc_sub_app = RpcSubApp {
+            event_sub_sender,
+            max_subscriptions_per_connection: 10,
+        };
+        io.extend_with(rpc_sub_app.to_delegate());
+
+        let (transport, mut notifications) = mpsc::unbounded();
+        let meta = WsSession::new(transport, disconnect_sender);
+
+        let tx = TestServerConfig::gen_zk_txs(100_u64)
+            .txs
+            .into_iter()
+            .map(|(tx, _op)| tx)
+            .find(|tx| matches!(tx, ZkSyncTx::Transfer(_)))
+            .unwrap();
+        let recipient = tx.to_account().unwrap();
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "mempool_subscribe",
+            "params": [recipient],
+        });
+        let (response, _) = futures::join!(
+            io.handle_request(&request.to_string(), meta.clone()),
+            async {
+                let request = event_sub_receiver.next().await.unwrap();
+                notifier.handle_notify_req(request).await.unwrap();
+            }
+        );
+        let response: Value = serde_json::from_str(&response.unwrap())?;
+        let sub_id = response["result"].clone();
+        assert!(sub_id.is_string(), "{}", response);
+
+        // Submit the transfer to the recipient, the accepted transaction is reported
+        // by the transaction sender.
+        let (accepted_txs_sender, mut accepted_txs_receiver) = mpsc::channel(16);
+        let mut tx_sender = test_tx_sender(&cfg, &cfg.config.api.common, mempool_sender);
+        tx_sender.accepted_txs = Some(accepted_txs_sender);
+        tx_sender
+            .submit_tx(tx.clone(), TxEthSignatureVariant::Single(None), None)
+            .await?;
+
+        let accepted_txs = accepted_txs_receiver.next().await.unwrap();
+        assert_eq!(accepted_txs.len(), 1);
+        assert_eq!(accepted_txs[0].hash(), tx.hash());
+        notifier.handle_accepted_txs(accepted_txs);
+
+        let notification: Value = serde_json::from_str(&notifications.next().await.unwrap())?;
+        assert_eq!(notification["method"], "mempool");
+        assert_eq!(notification["params"]["subscription"], sub_id);
+        let expected = match &tx {
+            ZkSyncTx::Transfer(transfer) => json!({
+                "txHash": tx.hash(),
+                "txType": "Transfer",
+                "token": transfer.token,
+                "amount": transfer.amount.to_string(),
+                "nonce": transfer.nonce,
+            }),
+            _ => unreachable!(),
+        };
+        assert_eq!(notification["params"]["result"], expected);
+
+        // Transactions of the other addresses are not reported.
+        let other_tx = TestServerConfig::gen_zk_txs(100_u64).txs[0].0.clone();
+        notifier.handle_accepted_txs(vec![other_tx]);
+        assert!(notifications.try_next().is_err());
+
+        mempool_task.abort();
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+//! Throttling of the transactions submitted by a single sender.
+//!
+//! Every sender has a token bucket which is refilled at the configured rate up to the burst size.
+//! Each submitted transaction takes a token, so a sender flooding the API is rejected before
+//! its transactions reach the signature checker. The limiter is created once and shared by all
+//! the APIs accepting the transactions, so a sender can't bypass it by switching between them.
+//! The same buckets keyed by the client IP throttle the requests of the JSON-RPC HTTP server.
+
+// Built-in uses
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_types::Address;
+
+/// Number of the independently locked parts of the senders map.
+const SHARDS_COUNT: usize = 16;
+/// Number of the tracked keys in a shard after which the refilled buckets are dropped.
+const MAX_TRACKED_KEYS_PER_SHARD: usize = 10_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Token-bucket rate limiter keeping a bucket per key.
+/// Clones share the buckets, so the limiter can be used by all the workers of the server.
+#[derive(Debug, Clone)]
+pub struct RateLimiter<K> {
+    shards: Arc<Vec<Mutex<HashMap<K, Bucket>>>>,
+    rate_per_sec: f64,
+    burst: u32,
+}
+
+/// Per-sender limiter of the submitted transactions.
+pub type SenderRateLimiter = RateLimiter<Address>;
+
+impl SenderRateLimiter {
+    /// Creates the limiter if the throttling is enabled in the config.
+    pub fn from_config(config: &CommonApiConfig) -> Option<Self> {
+        config
+            .sender_submission_rate_per_sec
+            .map(|rate_per_sec| Self::new(rate_per_sec, config.sender_submission_burst))
+    }
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    pub fn new(rate_per_sec: u32, burst: u32) -> Self {
+        assert!(rate_per_sec > 0, "Rate limit should be positive");
+        assert!(burst > 0, "Rate limit burst should be positive");
+        Self {
+            shards: Arc::new((0..SHARDS_COUNT).map(|_| Mutex::default()).collect()),
+            rate_per_sec: f64::from(rate_per_sec),
+            burst,
+        }
+    }
+
+    /// Takes `count` tokens from the bucket of the key. If there are not enough tokens,
+    /// nothing is taken and the time after which the request can be retried is returned.
+    ///
+    /// Requests larger than the burst size are accepted once the bucket is full.
+    pub fn acquire(&self, key: K, count: u32) -> Result<(), Duration> {
+        self.acquire_at(key, count, Instant::now())
+    }
+
+    /// Takes the tokens for every key or for none of them. If any of the buckets doesn't have
+    /// enough tokens, the tokens taken from the other ones are returned, and the throttled key
+    /// is returned along with the time after which the request can be retried.
+    pub fn acquire_all(&self, requests: &[(K, u32)]) -> Result<(), (K, Duration)>
+    where
+        K: Clone,
+    {
+        self.acquire_all_at(requests, Instant::now())
+    }
+
+    fn acquire_all_at(&self, requests: &[(K, u32)], now: Instant) -> Result<(), (K, Duration)>
+    where
+        K: Clone,
+    {
+        for (idx, (key, count)) in requests.iter().enumerate() {
+            if let Err(retry_after) = self.acquire_at(key.clone(), *count, now) {
+                for (key, count) in &requests[..idx] {
+                    self.release(key, *count);
+                }
+                return Err((key.clone(), retry_after));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the previously taken tokens to the bucket of the key.
+    fn release(&self, key: &K, count: u32) {
+        let burst = f64::from(self.burst);
+        let count = f64::from(count.min(self.burst));
+        let mut buckets = self.shard(key).lock().unwrap();
+        if let Some(bucket) = buckets.get_mut(key) {
+            bucket.tokens = (bucket.tokens + count).min(burst);
+        }
+    }
+
+    fn shard(&self, key: &K) -> &Mutex<HashMap<K, Bucket>> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.shards[hasher.finish() as usize % SHARDS_COUNT]
+    }
+
+    fn acquire_at(&self, key: K, count: u32, now: Instant) -> Result<(), Duration> {
+        let burst = f64::from(self.burst);
+        let count = f64::from(count.min(self.burst));
+
+        let mut buckets = self.shard(&key).lock().unwrap();
+        if buckets.len() > MAX_TRACKED_KEYS_PER_SHARD {
+            // Full buckets are no different from the ones of the unknown keys.
+            let rate_per_sec = self.rate_per_sec;
+            buckets.retain(|_, bucket| {
+                let elapsed = now.saturating_duration_since(bucket.updated_at);
+                bucket.tokens + elapsed.as_secs_f64() * rate_per_sec < burst
+            });
+        }
+
+        let bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: burst,
+            updated_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * self.rate_per_sec).min(burst);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= count {
+            bucket.tokens -= count;
+            Ok(())
+        } else {
+            let missing = count - bucket.tokens;
+            Err(Duration::from_secs_f64(missing / self.rate_per_sec))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sender_is_throttled_until_refill() {
+        let limiter = SenderRateLimiter::new(10, 5);
+        let sender = Address::repeat_byte(1);
+        let other_sender = Address::repeat_byte(2);
+        let start = Instant::now();
+
+        for _ in 0..5 {
+            limiter.acquire_at(sender, 1, start).unwrap();
+        }
+        let retry_after = limiter.acquire_at(sender, 1, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(100));
+        // Other senders are not affected.
+        limiter.acquire_at(other_sender, 5, start).unwrap();
+
+        // Half of the token is not enough.
+        let now = start + Duration::from_millis(50);
+        let retry_after = limiter.acquire_at(sender, 1, now).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(50));
+
+        let now = start + Duration::from_millis(100);
+        limiter.acquire_at(sender, 1, now).unwrap();
+        limiter.acquire_at(sender, 1, now).unwrap_err();
+
+        // The bucket doesn't grow beyond the burst size.
+        let now = start + Duration::from_secs(10);
+        limiter.acquire_at(sender, 5, now).unwrap();
+        limiter.acquire_at(sender, 1, now).unwrap_err();
+    }
+
+    #[test]
+    fn batch_takes_token_per_tx() {
+        let limiter = SenderRateLimiter::new(2, 4);
+        let sender = Address::repeat_byte(1);
+        let start = Instant::now();
+
+        limiter.acquire_at(sender, 3, start).unwrap();
+        // The rejected batch takes nothing from the bucket.
+        let retry_after = limiter.acquire_at(sender, 3, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(1));
+        limiter.acquire_at(sender, 1, start).unwrap();
+
+        // The batch exceeding the burst size waits for the full bucket.
+        let retry_after = limiter.acquire_at(sender, 10, start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_secs(2));
+        limiter
+            .acquire_at(sender, 10, start + Duration::from_secs(2))
+            .unwrap();
+    }
+
+    #[test]
+    fn throttled_batch_returns_tokens_of_other_senders() {
+        let limiter = SenderRateLimiter::new(1, 4);
+        let sender = Address::repeat_byte(1);
+        let throttled_sender = Address::repeat_byte(2);
+        let start = Instant::now();
+
+        limiter.acquire_at(throttled_sender, 4, start).unwrap();
+        let (key, retry_after) = limiter
+            .acquire_all_at(&[(sender, 3), (throttled_sender, 1)], start)
+            .unwrap_err();
+        assert_eq!(key, throttled_sender);
+        assert_eq!(retry_after, Duration::from_secs(1));
+
+        // The tokens taken for the first sender of the rejected batch are returned.
+        limiter.acquire_at(sender, 4, start).unwrap();
+        limiter.acquire_at(sender, 1, start).unwrap_err();
+
+        let now = start + Duration::from_secs(1);
+        limiter
+            .acquire_all_at(&[(sender, 1), (throttled_sender, 1)], now)
+            .unwrap();
+    }
+}
@@ -0,0 +1,207 @@
+//! Graceful shutdown of the API servers.
+//!
+//! Once the shutdown is requested, every server stops accepting new requests and lets the in-flight
+//! ones finish. The controller waits for the servers to stop, so the process exits only after
+//! the responses are sent (or the grace period is over).
+//!
+//! JSON-RPC servers have no graceful stop of their own, so their in-flight calls are tracked here.
+
+// Built-in uses
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+// External uses
+use futures::{
+    future::{self, BoxFuture, Either},
+    FutureExt,
+};
+use jsonrpc_core::{middleware::NoopFuture, Call, Error, Failure, Metadata, Middleware, Output};
+use tokio::{runtime::Handle, sync::Notify};
+
+// Local uses
+use super::rpc_server::error::RpcErrorCodes;
+
+pub use zksync_utils::shutdown::{ShutdownController, ShutdownSignal};
+
+/// Spawns the task closing the JSON-RPC server with `close` once the shutdown is requested
+/// and the in-flight calls of the server are finished.
+pub fn close_on_shutdown<F>(
+    shutdown: &ShutdownSignal,
+    runtime: &Handle,
+    calls: InFlightCalls,
+    close: F,
+) where
+    F: FnOnce() + Send + 'static,
+{
+    let requested = shutdown.clone().requested();
+    runtime.spawn(async move {
+        requested.await;
+        calls.drain().await;
+        close();
+    });
+}
+
+#[derive(Debug, Default)]
+struct CallsState {
+    active: AtomicUsize,
+    closing: AtomicBool,
+    finished: Notify,
+}
+
+/// Middleware tracking the calls processed by the JSON-RPC server.
+///
+/// Once the server is draining, new calls are rejected with the `ShuttingDown` error,
+/// while the active ones are processed as usual.
+#[derive(Debug, Clone, Default)]
+pub struct InFlightCalls(Arc<CallsState>);
+
+/// Call counted as active until dropped.
+struct ActiveCall(Arc<CallsState>);
+
+impl Drop for ActiveCall {
+    fn drop(&mut self) {
+        if self.0.active.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.finished.notify_one();
+        }
+    }
+}
+
+impl InFlightCalls {
+    #[cfg(test)]
+    pub(crate) fn active(&self) -> usize {
+        self.0.active.load(Ordering::SeqCst)
+    }
+
+    /// Rejects the new calls and waits for the active ones to finish.
+    pub async fn drain(&self) {
+        self.0.closing.store(true, Ordering::SeqCst);
+        while self.0.active.load(Ordering::SeqCst) > 0 {
+            self.0.finished.notified().await;
+        }
+    }
+
+    fn start_call(&self) -> ActiveCall {
+        // The call is counted before checking the flag, so the draining can't miss it.
+        self.0.active.fetch_add(1, Ordering::SeqCst);
+        ActiveCall(self.0.clone())
+    }
+}
+
+impl<M: Metadata> Middleware<M> for InFlightCalls {
+    type Future = NoopFuture;
+    type CallFuture = BoxFuture<'static, Option<Output>>;
+
+    fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let active_call = self.start_call();
+        if !self.0.closing.load(Ordering::SeqCst) {
+            let output = next(call, meta);
+            return Either::Left(
+                async move {
+                    let output = output.await;
+                    drop(active_call);
+                    output
+                }
+                .boxed(),
+            );
+        }
+
+        // Notifications have no output.
+        let output = match call {
+            Call::MethodCall(call) => Some(Output::Failure(Failure {
+                jsonrpc: call.jsonrpc,
+                error: Error {
+                    code: RpcErrorCodes::ShuttingDown.into(),
+                    message: "Server is shutting down".to_string(),
+                    data: None,
+                },
+                id: call.id,
+            })),
+            _ => None,
+        };
+        Either::Left(future::ready(output).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::MetaIoHandler;
+    use jsonrpc_http_server::ServerBuilder;
+    use serde_json::{json, Value};
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+
+    async fn call(client: &reqwest::Client, url: &str, method: &str) -> reqwest::Result<Value> {
+        let call = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": [] });
+        client.post(url).json(&call).send().await?.json().await
+    }
+
+    #[tokio::test]
+    async fn in_flight_calls_are_finished_on_shutdown() {
+        let controller = ShutdownController::new();
+        let shutdown = controller.signal();
+        let calls = InFlightCalls::default();
+        // The slow call is finished only once the permit is added.
+        let release = Arc::new(Semaphore::new(0));
+
+        let runtime = Handle::current();
+        let (addr_sender, addr_receiver) = std::sync::mpsc::channel();
+        let server_calls = calls.clone();
+        let slow_call_release = release.clone();
+        let server_thread = std::thread::spawn(move || {
+            let mut io: MetaIoHandler<(), _> = MetaIoHandler::with_middleware(server_calls.clone());
+            io.add_method("slow", move |_| {
+                let release = slow_call_release.clone();
+                async move {
+                    release.acquire().await.unwrap().forget();
+                    Ok(Value::from("done"))
+                }
+            });
+            let server = ServerBuilder::new(io)
+                .start_http(&"127.0.0.1:0".parse().unwrap())
+                .unwrap();
+            addr_sender.send(*server.address()).unwrap();
+
+            let close_handle = server.close_handle();
+            close_on_shutdown(&shutdown, &runtime, server_calls, move || {
+                close_handle.close()
+            });
+            server.wait();
+        });
+        let url = format!("http://{}", addr_receiver.recv().unwrap());
+
+        let client = reqwest::Client::new();
+        let slow_call = {
+            let (client, url) = (client.clone(), url.clone());
+            tokio::spawn(async move { call(&client, &url, "slow").await })
+        };
+        while calls.active() == 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let stopped = tokio::spawn(controller.shutdown(Duration::from_secs(10)));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // The server waits for the slow call before closing, new calls are rejected meanwhile.
+        let response = call(&client, &url, "slow").await.unwrap();
+        assert_eq!(
+            response["error"]["code"],
+            RpcErrorCodes::ShuttingDown as i64
+        );
+
+        release.add_permits(1);
+        let response = slow_call.await.unwrap().unwrap();
+        assert_eq!(response["result"], "done");
+        assert!(stopped.await.unwrap(), "Server should stop in time");
+        server_thread.join().unwrap();
+        assert!(call(&client, &url, "slow").await.is_err());
+    }
+}
@@ -22,18 +22,22 @@ use thiserror::Error;
 
 // Workspace uses
 use zksync_api_types::{
-    v02::transaction::{SubmitBatchResponse, Toggle2FA, Toggle2FAResponse, TxHashSerializeWrapper},
-    TxWithSignature,
+    v02::transaction::{
+        SubmissionLimit, SubmissionLimits, SubmitBatchResponse, SubmitTxResponse, Toggle2FA,
+        Toggle2FAResponse, TxHashSerializeWrapper,
+    },
+    SubsidyCampaign, TxWithSignature,
 };
 use zksync_storage::misc::records::Subsidy;
 use zksync_storage::{chain::account::records::EthAccountType, ConnectionPool};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
     tx::{
-        EthBatchSignData, EthBatchSignatures, EthSignData, Order, SignedZkSyncTx, TxEthSignature,
-        TxEthSignatureVariant, TxHash,
+        EthBatchSignData, EthBatchSignatures, EthSignData, Order, SignedZkSyncTx, TimeRange,
+        TxEthSignature, TxEthSignatureVariant, TxHash,
     },
-    AccountId, Address, PubKeyHash, Token, TokenId, TokenLike, TxFeeTypes, ZkSyncTx, H160,
+    AccountId, Address, Nonce, PubKeyHash, Token, TokenId, TokenLike, TransferToNewOp, TxFeeTypes,
+    ZkSyncTx, H160,
 };
 use zksync_utils::{
     big_decimal_to_ratio, biguint_to_big_decimal, ratio_to_scaled_u64, scaled_big_decimal_to_ratio,
@@ -41,7 +45,12 @@ use zksync_utils::{
 
 // Local uses
 use crate::{
-    api_server::forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
+    api_server::{
+        forced_exit_checker::{ForcedExitAccountAgeChecker, ForcedExitChecker},
+        maintenance_mode::MaintenanceModeCache,
+        mempool_resync::SubmittedTxs,
+        sender_limiter::SenderRateLimiter,
+    },
     fee_ticker::{ResponseBatchFee, ResponseFee, TokenPriceRequestType},
     signature_checker::{
         BatchRequest, OrderRequest, RequestData, Toggle2FARequest, TxRequest, VerifiedTx,
@@ -54,7 +63,7 @@ use zksync_config::configs::api::{CommonApiConfig, TokenConfig};
 use zksync_mempool::MempoolTransactionRequest;
 use zksync_types::tx::error::TxAddError;
 
-use super::rpc_server::types::RequestMetadata;
+use super::rpc_server::types::{PendingNonce, RequestMetadata};
 use crate::fee_ticker::{FeeTicker, PriceError};
 
 const VALIDNESS_INTERVAL_MINUTES: i64 = 40;
@@ -72,14 +81,33 @@ pub struct TxSender {
     pub blocks: BlockDetailsCache,
     /// List of account IDs that do not have to pay fees for operations.
     pub fee_free_accounts: HashSet<AccountId>,
+    /// Addresses of the maintenance accounts from the config. These accounts are exempted
+    /// from the fee check, same as the ones added to the database through the admin API.
+    pub maintenance_accounts: HashSet<Address>,
+    /// Addresses of the accounts which are not allowed to submit transactions.
+    pub blocked_accounts: HashSet<Address>,
     pub enforce_pubkey_change_fee: bool,
-    // Limit the number of both transactions and Ethereum signatures per batch.
-    pub max_number_of_transactions_per_batch: usize,
-    pub max_number_of_authors_per_batch: usize,
+    /// Limits of the submitted transactions and batches, also reported to the clients.
+    pub limits: SubmissionLimits,
+    /// Maximum time by which `valid_from` of a submitted transaction may be ahead of the current time.
+    pub max_valid_from_horizon: std::time::Duration,
+    /// Whether the queued transactions may be replaced by the ones paying a higher fee.
+    pub tx_replacement_enabled: bool,
 
     pub current_subsidy_type: String,
     pub max_subsidy_usd: Ratio<BigUint>,
     pub subsidized_ips: HashSet<String>,
+
+    /// Ring of the recently submitted transactions used by the mempool resync check.
+    pub submitted_txs: Option<SubmittedTxs>,
+    /// Receives the transactions accepted into the mempool, used by the `mempool`
+    /// WebSocket subscriptions. Nothing is reported if not set.
+    pub accepted_txs: Option<mpsc::Sender<Vec<ZkSyncTx>>>,
+    /// Throttling of the transactions submitted by a single sender, disabled if not set.
+    /// The limiter is shared by all the API servers, so the sender can't bypass it by switching the API.
+    pub sender_limiter: Option<SenderRateLimiter>,
+    /// Maintenance mode shared by all the API servers, no transactions are accepted while it's enabled.
+    pub maintenance_mode: MaintenanceModeCache,
 }
 
 #[derive(Debug, Error)]
@@ -94,8 +122,32 @@ pub enum SubmitError {
     IncorrectTx(String),
     #[error("Transaction adding error: {0}.")]
     TxAdd(#[from] TxAddError),
+    #[error("Nonce {nonce} is too far ahead: the next nonce of the account is {next_nonce}, nonces up to {max_nonce} are accepted.")]
+    NonceOutOfWindow {
+        nonce: Nonce,
+        next_nonce: Nonce,
+        max_nonce: Nonce,
+    },
+    #[error("Submission limit {limit} is exceeded: {value} is more than {max}.")]
+    LimitExceeded {
+        limit: SubmissionLimit,
+        value: u64,
+        max: u64,
+    },
     #[error("Chosen token is not suitable for paying fees.")]
     InappropriateFeeToken,
+    #[error("Too many requests from the account {sender:?}, retry after {retry_after_ms} ms.")]
+    TooManyRequests {
+        sender: Address,
+        retry_after_ms: u64,
+    },
+    #[error(
+        "Network is under maintenance, new transactions are not accepted{}.",
+        .reason.as_ref().map(|reason| format!(": {}", reason)).unwrap_or_default()
+    )]
+    MaintenanceMode { reason: Option<String> },
+    #[error("Account {address:?} is not allowed to submit transactions.")]
+    AccountBlocked { address: Address },
     // Not all TxAddErrors would apply to Toggle2FA, but
     // it is helpful to re-use IncorrectEthSignature and DbError
     #[error("Failed to toggle 2FA: {0}.")]
@@ -150,10 +202,6 @@ impl TxSender {
         token_config: &TokenConfig,
         mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
     ) -> Self {
-        let max_number_of_transactions_per_batch =
-            config.max_number_of_transactions_per_batch as usize;
-        let max_number_of_authors_per_batch = config.max_number_of_authors_per_batch as usize;
-
         Self {
             mempool_tx_sender,
             pool: connection_pool,
@@ -167,11 +215,18 @@ impl TxSender {
             blocks: BlockDetailsCache::new(config.caches_size),
 
             fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
-            max_number_of_transactions_per_batch,
-            max_number_of_authors_per_batch,
+            maintenance_accounts: HashSet::from_iter(config.maintenance_accounts.clone()),
+            blocked_accounts: HashSet::from_iter(config.blocked_accounts.clone()),
+            limits: submission_limits(config),
+            max_valid_from_horizon: config.max_valid_from_horizon(),
+            tx_replacement_enabled: config.tx_replacement_enabled,
             current_subsidy_type: config.subsidy_name.clone(),
             max_subsidy_usd: config.max_subsidy_usd(),
             subsidized_ips: config.subsidized_ips.clone().into_iter().collect(),
+            submitted_txs: None,
+            accepted_txs: None,
+            sender_limiter: None,
+            maintenance_mode: MaintenanceModeCache::default(),
         }
     }
 
@@ -213,6 +268,218 @@ impl TxSender {
             .unwrap_or(EthAccountType::Owned))
     }
 
+    /// Rejects the submission if the network is under maintenance.
+    async fn check_maintenance_mode(&self) -> Result<(), SubmitError> {
+        let mode = self
+            .maintenance_mode
+            .get(&self.pool)
+            .await
+            .map_err(SubmitError::internal)?;
+        if mode.enabled {
+            metrics::increment_counter!("tx_sender.maintenance_rejections");
+            return Err(SubmitError::MaintenanceMode {
+                reason: mode.reason,
+            });
+        }
+        Ok(())
+    }
+
+    /// Rejects the submission if any of the transactions is sent by a blocked account.
+    /// Same as for the rate limits, `ForcedExit` is checked against its initiator.
+    async fn check_blocked_senders<'a>(
+        &self,
+        txs: impl IntoIterator<Item = &'a ZkSyncTx>,
+    ) -> Result<(), SubmitError> {
+        if self.blocked_accounts.is_empty() {
+            return Ok(());
+        }
+        for tx in txs {
+            let sender = self
+                .get_tx_sender(tx)
+                .await
+                .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+            if self.blocked_accounts.contains(&sender) {
+                metrics::increment_counter!("tx_sender.blocked_submissions");
+                return Err(SubmitError::AccountBlocked { address: sender });
+            }
+        }
+        Ok(())
+    }
+
+    /// Accounts the submitted transactions in the rate limits of their senders.
+    /// Every transaction of a batch is counted separately. `ForcedExit` is accounted to its initiator,
+    /// so nobody can get the target account throttled.
+    async fn throttle_senders<'a>(
+        &self,
+        txs: impl IntoIterator<Item = &'a ZkSyncTx>,
+    ) -> Result<(), SubmitError> {
+        let limiter = match &self.sender_limiter {
+            Some(limiter) => limiter,
+            None => return Ok(()),
+        };
+        let mut txs_per_sender = HashMap::<Address, u32>::new();
+        for tx in txs {
+            let sender = self
+                .get_tx_sender(tx)
+                .await
+                .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+            *txs_per_sender.entry(sender).or_default() += 1;
+        }
+        // The batch is rejected as a whole, so the senders of the batch are throttled together.
+        let txs_per_sender: Vec<_> = txs_per_sender.into_iter().collect();
+        limiter
+            .acquire_all(&txs_per_sender)
+            .map_err(|(sender, retry_after)| {
+                metrics::increment_counter!("tx_sender.throttled_submissions");
+                SubmitError::TooManyRequests {
+                    sender,
+                    retry_after_ms: (retry_after.as_secs_f64() * 1000.0).ceil() as u64,
+                }
+            })
+    }
+
+    /// Returns the nonce the next transaction of the account is expected to have, taking into
+    /// account the transactions queued in the mempool, and how many nonces ahead of it can be submitted.
+    pub async fn get_pending_nonce(
+        &self,
+        account_id: AccountId,
+    ) -> Result<PendingNonce, SubmitError> {
+        let (committed_nonce, queued_nonces) = self.load_nonces(account_id).await?;
+        Ok(pending_nonce(
+            committed_nonce,
+            &queued_nonces,
+            self.limits.nonce_ahead_window,
+        ))
+    }
+
+    /// Loads the committed nonce of the account and the sorted nonces of its queued transactions.
+    async fn load_nonces(&self, account_id: AccountId) -> Result<(Nonce, Vec<Nonce>), SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let committed_nonce = storage
+            .chain()
+            .account_schema()
+            .estimate_nonce(account_id)
+            .await
+            .map_err(SubmitError::internal)?
+            .unwrap_or_default();
+        let queued_nonces = storage
+            .chain()
+            .mempool_schema()
+            .get_queued_nonces(account_id)
+            .await
+            .map_err(SubmitError::internal)?;
+        Ok((committed_nonce, queued_nonces))
+    }
+
+    /// Reports the transactions accepted into the mempool to the subscribers, if there are any.
+    /// Notifications are best-effort: they are dropped rather than delaying the submission
+    /// if the channel is full.
+    fn report_accepted_txs(&self, txs: impl FnOnce() -> Vec<ZkSyncTx>) {
+        if let Some(accepted_txs) = &self.accepted_txs {
+            if accepted_txs.clone().try_send(txs()).is_err() {
+                metrics::increment_counter!("tx_sender.accepted_txs_dropped");
+            }
+        }
+    }
+
+    /// Rejects the transactions with nonces too far ahead of the next expected nonce of the account.
+    /// Transactions with nonces inside the window are kept in the mempool until the gap is filled.
+    /// Transactions are checked in order, so the consecutive nonces of one account within
+    /// the batch don't count against the window.
+    /// Checks the time ranges of the transactions. Transactions valid in the future are accepted
+    /// within the horizon and wait in the mempool until they become valid.
+    fn check_time_ranges<'a>(
+        &self,
+        txs: impl IntoIterator<Item = &'a ZkSyncTx>,
+    ) -> Result<(), SubmitError> {
+        let now = Utc::now().timestamp() as u64;
+        for tx in txs {
+            check_time_range(tx.time_range(), now, self.max_valid_from_horizon.as_secs())?;
+        }
+        Ok(())
+    }
+
+    async fn check_nonce_window<'a>(
+        &self,
+        txs: impl IntoIterator<Item = &'a ZkSyncTx>,
+    ) -> Result<(), SubmitError> {
+        let mut next_nonces = HashMap::new();
+        for tx in txs {
+            let account_id = match tx.account_id() {
+                Ok(account_id) => account_id,
+                // Account close transactions are rejected anyway.
+                Err(_) => continue,
+            };
+            let next_nonce = match next_nonces.get(&account_id) {
+                Some(next_nonce) => *next_nonce,
+                None => {
+                    // The window starts after the nonces queued without gaps, so the transactions
+                    // queued after a gap don't move it further.
+                    let (committed_nonce, queued_nonces) = self.load_nonces(account_id).await?;
+                    consecutive_nonce(committed_nonce, &queued_nonces)
+                }
+            };
+            check_nonce_window(tx.nonce(), next_nonce, self.limits.nonce_ahead_window)?;
+            let next_nonce = if tx.nonce() == next_nonce {
+                next_nonce + 1
+            } else {
+                next_nonce
+            };
+            next_nonces.insert(account_id, next_nonce);
+        }
+        Ok(())
+    }
+
+    /// Checks the transactions submitted together against the submission limits.
+    async fn check_submission_limits(
+        &self,
+        txs: &[&ZkSyncTx],
+        authors: usize,
+    ) -> Result<(), SubmitError> {
+        // Too big batches are rejected before the database is queried.
+        check_limit(
+            SubmissionLimit::TransactionsPerBatch,
+            txs.len(),
+            self.limits.max_transactions_per_batch,
+        )?;
+
+        // Transfers to the new accounts take more chunks. The database is queried only if the batch
+        // exceeds the limits when all the transfer recipients are considered new.
+        let recipients: HashSet<Address> = txs
+            .iter()
+            .filter_map(|tx| match tx {
+                ZkSyncTx::Transfer(transfer) => Some(transfer.to),
+                _ => None,
+            })
+            .collect();
+        if check_submission_limits(&self.limits, txs, authors, &recipients).is_ok() {
+            return Ok(());
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let mut new_accounts = HashSet::new();
+        for recipient in recipients {
+            let exists = storage
+                .chain()
+                .account_schema()
+                .does_account_exist(recipient)
+                .await
+                .map_err(SubmitError::internal)?;
+            if !exists {
+                new_accounts.insert(recipient);
+            }
+        }
+        check_submission_limits(&self.limits, txs, authors, &new_accounts)
+    }
+
     pub async fn toggle_2fa(
         &self,
         toggle_2fa: Toggle2FA,
@@ -239,18 +506,25 @@ impl TxSender {
             EthAccountType::No2FA(toggle_2fa.pub_key_hash)
         };
 
+        let timestamp = toggle_2fa.timestamp;
         self.verify_toggle_2fa_request_eth_signature(toggle_2fa)
             .await?;
 
-        self.pool
+        // The signed request stays valid for a while, so the storage makes sure that
+        // the request disabling 2FA is not replayed after the owner has enabled it again.
+        let accepted = self
+            .pool
             .access_storage()
             .await
             .map_err(|_| SubmitError::Toggle2FA(Toggle2FAError::DbError))?
             .chain()
             .account_schema()
-            .set_account_type(account_id, new_type)
+            .toggle_account_type(account_id, new_type, timestamp)
             .await
             .map_err(|_| SubmitError::Toggle2FA(Toggle2FAError::DbError))?;
+        if !accepted {
+            return Err(SubmitError::Toggle2FA(Toggle2FAError::StaleTimestamp));
+        }
 
         Ok(Toggle2FAResponse { success: true })
     }
@@ -381,7 +655,8 @@ impl TxSender {
 
         let result = self
             .submit_tx(tx, signature, extracted_request_metadata)
-            .await;
+            .await
+            .map(|response| response.tx_hash);
 
         if let Err(err) = &result {
             let err_label = match err {
@@ -418,11 +693,16 @@ impl TxSender {
         Ok(result)
     }
 
+    /// Checks whether the subsidized fee can be applied to the request. The subsidies charged to
+    /// a campaign are limited by its budget checked by the fee ticker and are reserved in it by
+    /// `reserve_campaign_subsidy` on submission, the other ones are limited by the budget of
+    /// the current subsidy type.
     pub async fn should_subsidize_cpk(
         &self,
         normal_fee: &BigUint,
         subsidized_fee: &BigUint,
         subsidy_size_usd: &Ratio<BigUint>,
+        subsidy_campaign: Option<&SubsidyCampaign>,
         extracted_request_metadata: Option<RequestMetadata>,
     ) -> Result<bool, SubmitError> {
         let should_subsidize_ip = if let Some(meta) = extracted_request_metadata {
@@ -433,20 +713,80 @@ impl TxSender {
 
         let result = should_subsidize_ip
             && subsidized_fee < normal_fee
-            && self
-                .can_subsidize(subsidy_size_usd.clone())
-                .await
-                .map_err(SubmitError::Internal)?;
+            && (subsidy_campaign.is_some()
+                || self
+                    .can_subsidize(subsidy_size_usd.clone())
+                    .await
+                    .map_err(SubmitError::Internal)?);
 
         Ok(result)
     }
 
+    /// Reserves the subsidy of the submitted request in the campaign budget, so the concurrent
+    /// requests can't overspend it. The subsidy is charged once the transaction with
+    /// `charged_tx_hash` is executed. Returns `false` if the budget left doesn't cover it anymore.
+    async fn reserve_campaign_subsidy(
+        &self,
+        subsidy_campaign: Option<&SubsidyCampaign>,
+        subsidy_size_usd: &Ratio<BigUint>,
+        charged_tx_hash: TxHash,
+    ) -> Result<bool, SubmitError> {
+        let campaign = match subsidy_campaign {
+            Some(campaign) => campaign,
+            None => return Ok(true),
+        };
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let reserved = storage
+            .subsidy_campaigns_schema()
+            .reserve_campaign_charge(
+                &charged_tx_hash,
+                campaign.id,
+                ratio_to_scaled_u64(subsidy_size_usd.clone()),
+            )
+            .await
+            .map_err(SubmitError::internal)?;
+        if !reserved {
+            metrics::increment_counter!("tx_sender.subsidy_campaign_exhausted");
+        }
+
+        Ok(reserved)
+    }
+
+    /// Releases the campaign subsidy reserved for the request which is rejected after all.
+    async fn release_campaign_subsidy(
+        &self,
+        subsidy_campaign: Option<&SubsidyCampaign>,
+        charged_tx_hash: TxHash,
+    ) -> Result<(), SubmitError> {
+        if subsidy_campaign.is_none() {
+            return Ok(());
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        storage
+            .subsidy_campaigns_schema()
+            .release_campaign_charges(&charged_tx_hash)
+            .await
+            .map_err(SubmitError::internal)
+    }
+
+    /// Stores the subsidy of the accepted request.
     pub async fn store_subsidy_data(
         &self,
         hash: TxHash,
         normal_fee: BigUint,
         subsidized_fee: BigUint,
         token_id: TokenId,
+        subsidy_campaign: Option<&SubsidyCampaign>,
     ) -> Result<(), anyhow::Error> {
         let token_price_in_usd = self
             .ticker
@@ -467,7 +807,9 @@ impl TxSender {
             token_id,
             token_amount: biguint_to_big_decimal(subsidized_fee),
             full_cost_token: biguint_to_big_decimal(normal_fee),
-            subsidy_type: self.current_subsidy_type.clone(),
+            subsidy_type: subsidy_campaign
+                .map(|campaign| campaign.partner.clone())
+                .unwrap_or_else(|| self.current_subsidy_type.clone()),
             tx_hash: hash,
         };
 
@@ -481,12 +823,112 @@ impl TxSender {
         Ok(())
     }
 
+    /// Checks whether the address belongs to a maintenance account exempted from the fee check.
+    async fn is_maintenance_account(&self, address: Address) -> Result<bool, SubmitError> {
+        if self.maintenance_accounts.contains(&address) {
+            return Ok(true);
+        }
+
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        storage
+            .maintenance_schema()
+            .is_maintenance_account(address)
+            .await
+            .map_err(SubmitError::internal)
+    }
+
+    /// Checks whether the transaction with exactly the same hash is already accepted to the mempool.
+    async fn is_tx_in_mempool(&self, tx_hash: TxHash) -> Result<bool, SubmitError> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        storage
+            .chain()
+            .mempool_schema()
+            .contains_tx(tx_hash)
+            .await
+            .map_err(SubmitError::internal)
+    }
+
+    /// Journals the transaction of the maintenance account accepted without paying the required fee.
+    async fn store_fee_exemption(
+        &self,
+        tx: &ZkSyncTx,
+        address: Address,
+        required_fee: BigUint,
+    ) -> Result<(), anyhow::Error> {
+        self.pool
+            .access_storage()
+            .await?
+            .maintenance_schema()
+            .record_fee_exemption(tx.hash(), address, &tx.variance_name(), required_fee)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns the queued transaction of the same account with the same nonce, which is replaced
+    /// by the given one. Replacement is accepted only if it pays a strictly higher fee in the same token,
+    /// and the queued transaction is not picked into the pending block yet.
+    /// Nothing is replaced if the replacement is disabled, so the mempool rejects the transaction.
+    async fn find_replaced_tx(&self, tx: &ZkSyncTx) -> Result<Option<TxHash>, SubmitError> {
+        if !self.tx_replacement_enabled {
+            return Ok(None);
+        }
+        let account_id = match tx.account_id() {
+            Ok(account_id) => account_id,
+            Err(_) => return Ok(None),
+        };
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .map_err(SubmitError::internal)?;
+        let queued_tx = storage
+            .chain()
+            .mempool_schema()
+            .get_queued_tx_by_nonce(account_id, tx.nonce())
+            .await
+            .map_err(SubmitError::internal)?;
+        let queued_tx = match queued_tx {
+            Some(queued_tx) => queued_tx,
+            None => return Ok(None),
+        };
+        let picked = storage
+            .chain()
+            .mempool_schema()
+            .is_tx_picked(queued_tx.hash())
+            .await
+            .map_err(SubmitError::internal)?;
+        if picked {
+            return Err(SubmitError::TxAdd(TxAddError::ReplacedTxNotQueued));
+        }
+
+        let fee = |tx: &ZkSyncTx| tx.get_fee_info().map(|(_, token, _, fee)| (token, fee));
+        match (fee(&queued_tx.tx), fee(tx)) {
+            (Some((queued_token, queued_fee)), Some((token, fee)))
+                if queued_token == token && fee > queued_fee =>
+            {
+                Ok(Some(queued_tx.hash()))
+            }
+            _ => Err(SubmitError::TxAdd(TxAddError::ReplacementFeeTooLow)),
+        }
+    }
+
+    /// Submits the transaction to the mempool. The queued transaction with the same nonce
+    /// is replaced if the submitted one pays a higher fee and the replacement is enabled.
     pub async fn submit_tx(
         &self,
         tx: ZkSyncTx,
         signature: TxEthSignatureVariant,
         extracted_request_metadata: Option<RequestMetadata>,
-    ) -> Result<TxHash, SubmitError> {
+    ) -> Result<SubmitTxResponse, SubmitError> {
         let labels = vec![
             ("stage", "api".to_string()),
             ("name", tx.variance_name()),
@@ -498,11 +940,31 @@ impl TxSender {
         if tx.is_close() {
             return Err(SubmitError::AccountCloseDisabled);
         }
+        self.check_maintenance_mode().await?;
+        self.check_blocked_senders(std::iter::once(&tx)).await?;
+        self.throttle_senders(std::iter::once(&tx)).await?;
+
+        // Clients resubmit the transaction if the response to the accepted submission is lost.
+        // Only the exact same transaction is accepted again, the conflicting one with the same
+        // nonce is still rejected by the mempool.
+        let tx_hash = tx.hash();
+        if self.is_tx_in_mempool(tx_hash).await? {
+            metrics::increment_counter!("tx_sender.submit_tx.resubmitted");
+            return Ok(SubmitTxResponse {
+                tx_hash,
+                replaced_tx_hash: None,
+            });
+        }
 
         if let ZkSyncTx::ForcedExit(forced_exit) = &tx {
             self.check_forced_exit(forced_exit).await?;
         }
 
+        self.check_submission_limits(&[&tx], 0).await?;
+        self.check_time_ranges(std::iter::once(&tx))?;
+        self.check_nonce_window(std::iter::once(&tx)).await?;
+        let replaced_tx_hash = self.find_replaced_tx(&tx).await?;
+
         // Resolve the token.
         let token = self.token_info_from_id(tx.token_id()).await?;
         let msg_to_sign = tx
@@ -523,6 +985,8 @@ impl TxSender {
         let sign_verify_channel = self.sign_verify_requests.clone();
 
         let mut fee_data_for_subsidy: Option<ResponseFee> = None;
+        // Sender and the required fee, if the transaction is accepted without paying the fee.
+        let mut fee_exemption: Option<(Address, BigUint)> = None;
 
         if let Some((tx_type, token, address, provided_fee)) = tx_fee_info {
             let should_enforce_fee = !matches!(tx_type, TxFeeTypes::ChangePubKey { .. })
@@ -544,9 +1008,17 @@ impl TxSender {
                     &required_fee_data.normal_fee.total_fee,
                     &required_fee_data.subsidized_fee.total_fee,
                     &required_fee_data.subsidy_size_usd,
+                    required_fee_data.subsidy_campaign.as_ref(),
                     extracted_request_metadata,
                 )
                 .await?
+                && self
+                    .reserve_campaign_subsidy(
+                        required_fee_data.subsidy_campaign.as_ref(),
+                        &required_fee_data.subsidy_size_usd,
+                        tx.hash(),
+                    )
+                    .await?
             {
                 fee_data_for_subsidy = Some(required_fee_data.clone());
                 required_fee_data.subsidized_fee
@@ -560,47 +1032,115 @@ impl TxSender {
             // Scaling the fee required since the price may change between signing the transaction and sending it to the server.
             let scaled_provided_fee = scale_user_fee_up(provided_fee);
             if required_fee >= scaled_provided_fee && should_enforce_fee {
-                return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+                // Maintenance accounts are exempted from the fee check, the signature and the nonce
+                // of their transactions are still checked below.
+                let tx_sender = self
+                    .get_tx_sender(&tx)
+                    .await
+                    .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+                // Nothing is subsidized if the fee is not paid enough or at all.
+                if let Some(fee_data) = fee_data_for_subsidy.take() {
+                    self.release_campaign_subsidy(fee_data.subsidy_campaign.as_ref(), tx.hash())
+                        .await?;
+                }
+                if !self.is_maintenance_account(tx_sender).await? {
+                    return Err(SubmitError::TxAdd(TxAddError::TxFeeTooLow));
+                }
+                fee_exemption = Some((tx_sender, required_fee_data.total_fee));
             }
         }
 
-        let tx_sender = self
-            .get_tx_sender(&tx)
-            .await
-            .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
+        // The reserved campaign subsidy is released if the transaction doesn't make it to the mempool.
+        let sent = async {
+            let tx_sender = self
+                .get_tx_sender(&tx)
+                .await
+                .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?;
 
-        let verified_tx = verify_tx_info_message_signature(
-            &tx,
-            tx_sender,
-            token.clone(),
-            self.get_tx_sender_type(&tx).await?,
-            signature.tx_signature().clone(),
-            msg_to_sign,
-            sign_verify_channel,
-        )
-        .await?
-        .unwrap_tx();
+            // Swaps carry the signatures of the orders along with the transaction one.
+            let tx_signatures = if let ZkSyncTx::Swap(_) = &tx {
+                signature.tx_signature().iter().cloned().collect()
+            } else {
+                signature.signer_signatures()
+            };
+            let verified_tx = verify_tx_info_message_signature(
+                &tx,
+                tx_sender,
+                token.clone(),
+                self.get_tx_sender_type(&tx).await?,
+                tx_signatures,
+                msg_to_sign,
+                sign_verify_channel,
+            )
+            .await?
+            .unwrap_tx();
 
-        if let ZkSyncTx::Swap(tx) = &tx {
-            if signature.is_single() {
-                return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+            if let ZkSyncTx::Swap(tx) = &tx {
+                let signatures = signature
+                    .orders_signatures()
+                    .ok_or(SubmitError::TxAdd(TxAddError::MissingEthSignature))?;
+                self.verify_order_eth_signature(&tx.orders.0, signatures.0.clone())
+                    .await?;
+                self.verify_order_eth_signature(&tx.orders.1, signatures.1.clone())
+                    .await?;
             }
-            let signatures = signature.orders_signatures();
-            self.verify_order_eth_signature(&tx.orders.0, signatures.0.clone())
-                .await?;
-            self.verify_order_eth_signature(&tx.orders.1, signatures.1.clone())
-                .await?;
+
+            let submitted_tx = self.submitted_txs.as_ref().map(|_| verified_tx.clone());
+
+            let (sender, receiver) = oneshot::channel();
+            let item = match replaced_tx_hash {
+                Some(replaced_tx_hash) => MempoolTransactionRequest::ReplaceTx(
+                    Box::new(verified_tx),
+                    replaced_tx_hash,
+                    sender,
+                ),
+                None => MempoolTransactionRequest::NewTx(Box::new(verified_tx), sender),
+            };
+            let mut mempool_sender = self.mempool_tx_sender.clone();
+            mempool_sender
+                .send(item)
+                .await
+                .map_err(SubmitError::internal)?;
+
+            receiver.await.map_err(SubmitError::internal)??;
+            Ok::<_, SubmitError>(submitted_tx)
         }
+        .await;
+        let submitted_tx = match sent {
+            Ok(submitted_tx) => submitted_tx,
+            Err(err) => {
+                if let Some(fee_data) = &fee_data_for_subsidy {
+                    self.release_campaign_subsidy(fee_data.subsidy_campaign.as_ref(), tx.hash())
+                        .await?;
+                }
+                return Err(err);
+            }
+        };
 
-        let (sender, receiver) = oneshot::channel();
-        let item = MempoolTransactionRequest::NewTx(Box::new(verified_tx), sender);
-        let mut mempool_sender = self.mempool_tx_sender.clone();
-        mempool_sender
-            .send(item)
-            .await
-            .map_err(SubmitError::internal)?;
+        if let (Some(submitted_txs), Some(tx)) = (&self.submitted_txs, submitted_tx) {
+            submitted_txs.push(tx);
+        }
+        self.report_accepted_txs(|| vec![tx.clone()]);
 
-        receiver.await.map_err(SubmitError::internal)??;
+        if let Some((address, required_fee)) = fee_exemption {
+            vlog::info!(
+                "Transaction {} of the maintenance account {:?} is accepted without the fee, required fee: {}",
+                tx.hash(),
+                address,
+                required_fee
+            );
+            metrics::increment_counter!("tx_sender.fee_exemptions", "type" => tx.variance_name());
+            // The transaction is already in the mempool, so failing the request would only make
+            // the client resubmit it.
+            if let Err(err) = self.store_fee_exemption(&tx, address, required_fee).await {
+                metrics::increment_counter!("tx_sender.submit_tx.store_fee_exemption_fail");
+                vlog::error!(
+                    "Failed to journal the fee exemption of the transaction {}: {}",
+                    tx.hash(),
+                    err
+                );
+            }
+        }
 
         // fee_data_for_subsidy has Some value only if the batch of transactions is subsidised
         if let Some(fee_data_for_subsidy) = fee_data_for_subsidy {
@@ -615,6 +1155,7 @@ impl TxSender {
                 fee_data_for_subsidy.normal_fee.total_fee,
                 fee_data_for_subsidy.subsidized_fee.total_fee,
                 token.id,
+                fee_data_for_subsidy.subsidy_campaign.as_ref(),
             )
             .await
             .map_err(|e| {
@@ -627,7 +1168,10 @@ impl TxSender {
         }
 
         // if everything is OK, return the transactions hashes.
-        Ok(tx.hash())
+        Ok(SubmitTxResponse {
+            tx_hash: tx.hash(),
+            replaced_tx_hash: replaced_tx_hash.map(TxHashSerializeWrapper),
+        })
     }
 
     pub async fn submit_txs_batch(
@@ -642,12 +1186,16 @@ impl TxSender {
         if txs.is_empty() {
             return Err(SubmitError::TxAdd(TxAddError::EmptyBatch));
         }
-        // Even though this is going to be checked on the Mempool part,
+        self.check_maintenance_mode().await?;
+        self.check_blocked_senders(txs.iter().map(|tx| &tx.tx))
+            .await?;
+        self.throttle_senders(txs.iter().map(|tx| &tx.tx)).await?;
+        // Even though the batch size is going to be checked on the Mempool part,
         // we don't want to verify huge batches as long as this operation
         // is expensive.
-        if txs.len() > self.max_number_of_transactions_per_batch {
-            return Err(SubmitError::TxAdd(TxAddError::BatchTooBig));
-        }
+        let batch: Vec<&ZkSyncTx> = txs.iter().map(|tx| &tx.tx).collect();
+        self.check_submission_limits(&batch, eth_signatures.len())
+            .await?;
 
         for tx in &txs {
             let labels = vec![
@@ -658,15 +1206,13 @@ impl TxSender {
             metrics::increment_counter!("process_tx_count", &labels);
         }
 
-        // Same check but in terms of signatures.
-        if eth_signatures.len() > self.max_number_of_authors_per_batch {
-            return Err(SubmitError::TxAdd(TxAddError::EthSignaturesLimitExceeded));
-        }
-
         if txs.iter().any(|tx| tx.tx.is_close()) {
             return Err(SubmitError::AccountCloseDisabled);
         }
 
+        self.check_time_ranges(txs.iter().map(|tx| &tx.tx))?;
+        self.check_nonce_window(txs.iter().map(|tx| &tx.tx)).await?;
+
         // Checking fees data
         let mut provided_total_usd_fee = BigDecimal::from(0);
         let mut transaction_types = vec![];
@@ -722,6 +1268,9 @@ impl TxSender {
         }
 
         let mut fee_data_for_subsidy: Option<ResponseBatchFee> = None;
+        // The transactions of the batch are executed all together,
+        // so the campaign is charged by the first of them.
+        let charged_tx_hash = txs[0].tx.hash();
 
         // Only one token in batch
         if token_fees.len() == 1 {
@@ -736,9 +1285,17 @@ impl TxSender {
                     &batch_token_fee.normal_fee.total_fee,
                     &batch_token_fee.subsidized_fee.total_fee,
                     &batch_token_fee.subsidy_size_usd,
+                    batch_token_fee.subsidy_campaign.as_ref(),
                     extracted_request_metadata,
                 )
                 .await?
+                && self
+                    .reserve_campaign_subsidy(
+                        batch_token_fee.subsidy_campaign.as_ref(),
+                        &batch_token_fee.subsidy_size_usd,
+                        charged_tx_hash,
+                    )
+                    .await?
             {
                 fee_data_for_subsidy = Some(batch_token_fee.clone());
                 batch_token_fee.subsidized_fee.total_fee
@@ -757,6 +1314,13 @@ impl TxSender {
                     required_normal_fee.to_string(),
                     user_provided_fee.to_string(),
                 );
+                if let Some(fee_data) = &fee_data_for_subsidy {
+                    self.release_campaign_subsidy(
+                        fee_data.subsidy_campaign.as_ref(),
+                        charged_tx_hash,
+                    )
+                    .await?;
+                }
                 return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
             }
         } else {
@@ -771,9 +1335,17 @@ impl TxSender {
                     &required_eth_fee.normal_fee.total_fee,
                     &required_eth_fee.subsidized_fee.total_fee,
                     &required_eth_fee.subsidy_size_usd,
+                    required_eth_fee.subsidy_campaign.as_ref(),
                     extracted_request_metadata,
                 )
                 .await?
+                && self
+                    .reserve_campaign_subsidy(
+                        required_eth_fee.subsidy_campaign.as_ref(),
+                        &required_eth_fee.subsidy_size_usd,
+                        charged_tx_hash,
+                    )
+                    .await?
             {
                 fee_data_for_subsidy = Some(required_eth_fee.clone());
                 required_eth_fee.subsidized_fee.total_fee
@@ -799,94 +1371,134 @@ impl TxSender {
                     scaled_provided_fee_in_usd.to_string(),
                     (&required_total_usd_fee - &scaled_provided_fee_in_usd).to_string(),
                 );
+                if let Some(fee_data) = &fee_data_for_subsidy {
+                    self.release_campaign_subsidy(
+                        fee_data.subsidy_campaign.as_ref(),
+                        charged_tx_hash,
+                    )
+                    .await?;
+                }
                 return Err(SubmitError::TxAdd(TxAddError::TxBatchFeeTooLow));
             }
         }
 
-        for tx in txs.iter() {
-            if let ZkSyncTx::Swap(swap) = &tx.tx {
-                if tx.signature.is_single() {
-                    return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
+        // The reserved campaign subsidy is released if the batch doesn't make it to the mempool.
+        let sent = async {
+            for tx in txs.iter() {
+                if let ZkSyncTx::Swap(swap) = &tx.tx {
+                    let signatures = tx
+                        .signature
+                        .orders_signatures()
+                        .ok_or(SubmitError::TxAdd(TxAddError::MissingEthSignature))?;
+                    self.verify_order_eth_signature(&swap.orders.0, signatures.0.clone())
+                        .await?;
+                    self.verify_order_eth_signature(&swap.orders.1, signatures.1.clone())
+                        .await?;
                 }
-                let signatures = tx.signature.orders_signatures();
-                self.verify_order_eth_signature(&swap.orders.0, signatures.0.clone())
-                    .await?;
-                self.verify_order_eth_signature(&swap.orders.1, signatures.1.clone())
-                    .await?;
             }
-        }
-
-        let mut verified_txs = Vec::with_capacity(txs.len());
-        let mut verified_signatures = Vec::new();
-
-        let mut messages_to_sign = Vec::with_capacity(txs.len());
-        let mut tx_senders = Vec::with_capacity(txs.len());
-        let mut tx_sender_types = Vec::with_capacity(txs.len());
-        let mut tokens = Vec::with_capacity(txs.len());
-        for tx in txs.iter().map(|tx| &tx.tx) {
-            // Resolve the token and save it for constructing the batch message.
-            let token = self.token_info_from_id(tx.token_id()).await?;
-            tokens.push(token.clone());
-
-            messages_to_sign.push(tx.get_ethereum_sign_message(token).map(String::into_bytes));
-            tx_senders.push(
-                self.get_tx_sender(tx)
-                    .await
-                    .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?,
-            );
-            tx_sender_types.push(self.get_tx_sender_type(tx).await?);
-        }
 
-        let batch_sign_data = if !eth_signatures.is_empty() {
-            // User provided at least one signature for the whole batch.
-            // In this case each sender cannot be CREATE2.
-            if tx_sender_types
-                .iter()
-                .any(|_type| matches!(_type, EthAccountType::CREATE2))
-            {
-                return Err(SubmitError::IncorrectTx(
-                    "Eth signature from CREATE2 account not expected".to_string(),
-                ));
+            let mut verified_txs = Vec::with_capacity(txs.len());
+            let mut verified_signatures = Vec::new();
+
+            let mut messages_to_sign = Vec::with_capacity(txs.len());
+            let mut tx_senders = Vec::with_capacity(txs.len());
+            let mut tx_sender_types = Vec::with_capacity(txs.len());
+            let mut tokens = Vec::with_capacity(txs.len());
+            for tx in txs.iter().map(|tx| &tx.tx) {
+                // Resolve the token and save it for constructing the batch message.
+                let token = self.token_info_from_id(tx.token_id()).await?;
+                tokens.push(token.clone());
+
+                messages_to_sign.push(tx.get_ethereum_sign_message(token).map(String::into_bytes));
+                tx_senders.push(
+                    self.get_tx_sender(tx)
+                        .await
+                        .or(Err(SubmitError::TxAdd(TxAddError::DbError)))?,
+                );
+                tx_sender_types.push(self.get_tx_sender_type(tx).await?);
             }
-            let _txs = txs
-                .iter()
-                .zip(tokens.iter().cloned())
-                .zip(tx_senders.iter().cloned())
-                .map(|((tx, token), sender)| (tx.tx.clone(), token, sender))
-                .collect::<Vec<_>>();
-            // Create batch signature data.
-            Some(EthBatchSignData::new(_txs, eth_signatures).map_err(SubmitError::other)?)
-        } else {
-            None
-        };
-        let (verified_batch, sign_data) = verify_txs_batch_signature(
-            txs,
-            tx_senders,
-            tokens,
-            tx_sender_types,
-            batch_sign_data,
-            messages_to_sign,
-            self.sign_verify_requests.clone(),
-        )
-        .await?
-        .unwrap_batch();
-        if let Some(sign_data) = sign_data {
-            verified_signatures.extend(sign_data.signatures.into_iter());
-        }
-        verified_txs.extend(verified_batch.into_iter());
 
-        let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
+            let batch_sign_data = if !eth_signatures.is_empty() {
+                // User provided at least one signature for the whole batch.
+                // In this case each sender cannot be CREATE2.
+                if tx_sender_types
+                    .iter()
+                    .any(|_type| matches!(_type, EthAccountType::CREATE2))
+                {
+                    return Err(SubmitError::IncorrectTx(
+                        "Eth signature from CREATE2 account not expected".to_string(),
+                    ));
+                }
+                let _txs = txs
+                    .iter()
+                    .zip(tokens.iter().cloned())
+                    .zip(tx_senders.iter().cloned())
+                    .map(|((tx, token), sender)| (tx.tx.clone(), token, sender))
+                    .collect::<Vec<_>>();
+                // Create batch signature data.
+                Some(EthBatchSignData::new(_txs, eth_signatures).map_err(SubmitError::other)?)
+            } else {
+                None
+            };
+            let (verified_batch, sign_data) = verify_txs_batch_signature(
+                txs,
+                tx_senders,
+                tokens,
+                tx_sender_types,
+                batch_sign_data,
+                messages_to_sign,
+                self.sign_verify_requests.clone(),
+            )
+            .await?
+            .unwrap_batch();
+            if let Some(sign_data) = sign_data {
+                verified_signatures.extend(sign_data.signatures.into_iter());
+            }
+            verified_txs.extend(verified_batch.into_iter());
 
-        let (sender, receiver) = oneshot::channel();
-        let item =
-            MempoolTransactionRequest::NewTxsBatch(verified_txs, verified_signatures, sender);
-        let mut mempool_sender = self.mempool_tx_sender.clone();
-        mempool_sender
-            .send(item)
-            .await
-            .map_err(SubmitError::mempool_communication)?;
+            let tx_hashes: Vec<TxHash> = verified_txs.iter().map(|tx| tx.tx.hash()).collect();
+            let accepted_txs: Vec<ZkSyncTx> = if self.accepted_txs.is_some() {
+                verified_txs.iter().map(|tx| tx.tx.clone()).collect()
+            } else {
+                Vec::new()
+            };
+            let submitted_batch = self
+                .submitted_txs
+                .as_ref()
+                .map(|_| (verified_txs.clone(), verified_signatures.clone()));
+
+            let (sender, receiver) = oneshot::channel();
+            let item =
+                MempoolTransactionRequest::NewTxsBatch(verified_txs, verified_signatures, sender);
+            let mut mempool_sender = self.mempool_tx_sender.clone();
+            mempool_sender
+                .send(item)
+                .await
+                .map_err(SubmitError::mempool_communication)?;
 
-        receiver.await.map_err(SubmitError::internal)??;
+            receiver.await.map_err(SubmitError::internal)??;
+            Ok::<_, SubmitError>((tx_hashes, accepted_txs, submitted_batch))
+        }
+        .await;
+        let (tx_hashes, accepted_txs, submitted_batch) = match sent {
+            Ok(sent) => sent,
+            Err(err) => {
+                if let Some(fee_data) = &fee_data_for_subsidy {
+                    self.release_campaign_subsidy(
+                        fee_data.subsidy_campaign.as_ref(),
+                        charged_tx_hash,
+                    )
+                    .await?;
+                }
+                return Err(err);
+            }
+        };
+        self.report_accepted_txs(|| accepted_txs);
+        if let (Some(submitted_txs), Some((txs, eth_signatures))) =
+            (&self.submitted_txs, submitted_batch)
+        {
+            submitted_txs.push_batch(txs, eth_signatures);
+        }
 
         let batch_hash = TxHash::batch_hash(&tx_hashes);
 
@@ -913,6 +1525,7 @@ impl TxSender {
                 fee_data.normal_fee.total_fee,
                 fee_data.subsidized_fee.total_fee,
                 subsidy_token_id,
+                fee_data.subsidy_campaign.as_ref(),
             )
             .await
             .map_err(|e| {
@@ -1038,14 +1651,15 @@ async fn verify_tx_info_message_signature(
     tx_sender: Address,
     token: Token,
     account_type: EthAccountType,
-    signature: Option<TxEthSignature>,
+    signatures: Vec<TxEthSignature>,
     msg_to_sign: Option<Vec<u8>>,
     req_channel: mpsc::Sender<VerifySignatureRequest>,
 ) -> Result<VerifiedTx, SubmitError> {
     if matches!(
-        (account_type, signature.clone(), msg_to_sign.clone()),
-        (EthAccountType::CREATE2, Some(_), Some(_))
-    ) {
+        (account_type, msg_to_sign.as_ref()),
+        (EthAccountType::CREATE2, Some(_))
+    ) && !signatures.is_empty()
+    {
         return Err(SubmitError::IncorrectTx(
             "Eth signature from CREATE2 account not expected".to_string(),
         ));
@@ -1067,9 +1681,14 @@ async fn verify_tx_info_message_signature(
         _ => true,
     };
 
+    // Any of the provided signatures may belong to the account owner, the rest are tried
+    // by the signature checker if the first one doesn't match.
+    let mut signatures = signatures.into_iter();
     let eth_sign_data = match (msg_to_sign, should_check_eth_signature) {
         (Some(message), true) => {
-            let signature = signature.ok_or(SubmitError::TxAdd(TxAddError::MissingEthSignature))?;
+            let signature = signatures
+                .next()
+                .ok_or(SubmitError::TxAdd(TxAddError::MissingEthSignature))?;
             Some(EthSignData { signature, message })
         }
         _ => None,
@@ -1086,6 +1705,7 @@ async fn verify_tx_info_message_signature(
             },
             sender: tx_sender,
             token,
+            alternative_signatures: signatures.collect(),
         }),
         response: sender,
     };
@@ -1111,6 +1731,9 @@ async fn verify_txs_batch_signature(
     // from this batch. We save the account type to the db later.
     let mut create2_senders = HashSet::<H160>::new();
     let mut txs = Vec::with_capacity(batch.len());
+    // Whether every transaction that requires an Ethereum signature carries its own one,
+    // so the batch is authorized even without a valid batch signature.
+    let mut all_txs_signed = true;
     for (tx, message, sender, mut sender_type) in
         izip!(batch, msgs_to_sign, senders.iter(), sender_types)
     {
@@ -1140,6 +1763,7 @@ async fn verify_txs_batch_signature(
                     if batch_sign_data.is_none() && !tx.signature.exists() {
                         return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
                     }
+                    all_txs_signed &= tx.signature.exists();
                     tx.signature
                         .tx_signature()
                         .clone()
@@ -1151,6 +1775,7 @@ async fn verify_txs_batch_signature(
                         if batch_sign_data.is_none() && !tx.signature.exists() {
                             return Err(SubmitError::TxAdd(TxAddError::MissingEthSignature));
                         }
+                        all_txs_signed &= tx.signature.exists();
 
                         tx.signature
                             .tx_signature()
@@ -1181,6 +1806,7 @@ async fn verify_txs_batch_signature(
             batch_sign_data,
             senders,
             tokens,
+            all_txs_signed,
         }),
         response: sender,
     };
@@ -1222,9 +1848,147 @@ fn scale_user_fee_up(provided_total_usd_fee: BigDecimal) -> BigDecimal {
     )
 }
 
+/// Computes the pending nonce of the account: the committed nonce is advanced over
+/// the consecutive queued nonces, the rest of the queued nonces take up the window.
+/// `queued_nonces` are expected to be sorted.
+/// Returns the committed nonce advanced over the queued nonces following it without gaps.
+fn consecutive_nonce(committed_nonce: Nonce, queued_nonces: &[Nonce]) -> Nonce {
+    let mut next_nonce = committed_nonce;
+    for nonce in queued_nonces {
+        if *nonce == next_nonce {
+            next_nonce = next_nonce + 1;
+        }
+    }
+    next_nonce
+}
+
+/// The next nonce follows the highest queued one, and the nonces skipped below it are reported
+/// as missing. The window is counted from the nonces queued without gaps.
+fn pending_nonce(committed_nonce: Nonce, queued_nonces: &[Nonce], window: u32) -> PendingNonce {
+    let consecutive_nonce = consecutive_nonce(committed_nonce, queued_nonces);
+    let max_nonce = consecutive_nonce.saturating_add(window);
+    let ahead = queued_nonces
+        .iter()
+        .filter(|nonce| **nonce > consecutive_nonce && ***nonce <= max_nonce)
+        .count() as u32;
+    let next_nonce = queued_nonces
+        .last()
+        .filter(|nonce| **nonce >= committed_nonce)
+        .map_or(committed_nonce, |nonce| *nonce + 1);
+    let missing_nonces = (*consecutive_nonce..*next_nonce)
+        .map(Nonce)
+        .filter(|nonce| queued_nonces.binary_search(nonce).is_err())
+        .collect();
+
+    PendingNonce {
+        next_nonce,
+        remaining_window: window.saturating_sub(ahead),
+        missing_nonces,
+    }
+}
+
+pub fn submission_limits(config: &CommonApiConfig) -> SubmissionLimits {
+    SubmissionLimits {
+        max_transactions_per_batch: config.max_number_of_transactions_per_batch,
+        max_authors_per_batch: config.max_number_of_authors_per_batch,
+        max_chunks_per_batch: config.max_number_of_chunks_per_batch,
+        max_withdrawals_per_batch: config.max_number_of_withdrawals_per_batch,
+        nonce_ahead_window: config.nonce_ahead_window,
+    }
+}
+
+fn check_limit(limit: SubmissionLimit, value: usize, max: u64) -> Result<(), SubmitError> {
+    let value = value as u64;
+    if value > max {
+        return Err(SubmitError::LimitExceeded { limit, value, max });
+    }
+    Ok(())
+}
+
+/// Checks the transactions submitted together against the submission limits, except for the nonce window.
+/// Transfers to the addresses from `new_accounts` create the accounts, so they take more chunks.
+fn check_submission_limits(
+    limits: &SubmissionLimits,
+    txs: &[&ZkSyncTx],
+    authors: usize,
+    new_accounts: &HashSet<Address>,
+) -> Result<(), SubmitError> {
+    check_limit(
+        SubmissionLimit::TransactionsPerBatch,
+        txs.len(),
+        limits.max_transactions_per_batch,
+    )?;
+    check_limit(
+        SubmissionLimit::AuthorsPerBatch,
+        authors,
+        limits.max_authors_per_batch,
+    )?;
+
+    let withdrawals = txs.iter().filter(|tx| tx.is_withdraw()).count();
+    check_limit(
+        SubmissionLimit::WithdrawalsPerBatch,
+        withdrawals,
+        limits.max_withdrawals_per_batch,
+    )?;
+
+    let chunks: usize = txs
+        .iter()
+        .map(|tx| match tx {
+            ZkSyncTx::Transfer(transfer) if new_accounts.contains(&transfer.to) => {
+                TransferToNewOp::CHUNKS
+            }
+            _ => tx.min_chunks(),
+        })
+        .sum();
+    check_limit(
+        SubmissionLimit::ChunksPerBatch,
+        chunks,
+        limits.max_chunks_per_batch,
+    )
+}
+
+/// Rejects the transaction if it has already expired or becomes valid later than `horizon` seconds from now.
+fn check_time_range(time_range: TimeRange, now: u64, horizon: u64) -> Result<(), SubmitError> {
+    if time_range.valid_until < now {
+        return Err(SubmitError::IncorrectTx(format!(
+            "transaction has expired at {}",
+            time_range.valid_until
+        )));
+    }
+    let max_valid_from = now.saturating_add(horizon);
+    if time_range.valid_from > max_valid_from {
+        return Err(SubmitError::IncorrectTx(format!(
+            "transaction becomes valid at {}, only transactions valid until {} are accepted",
+            time_range.valid_from, max_valid_from
+        )));
+    }
+    Ok(())
+}
+
+fn check_nonce_window(nonce: Nonce, next_nonce: Nonce, window: u32) -> Result<(), SubmitError> {
+    let max_nonce = Nonce(next_nonce.saturating_add(window));
+    if nonce > max_nonce {
+        return Err(SubmitError::NonceOutOfWindow {
+            nonce,
+            next_nonce,
+            max_nonce,
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        api_server::rpc_server::error::RpcErrorCodes, signature_checker::start_sign_checker,
+    };
+    use zksync_eth_client::{clients::mock::MockEthereum, EthereumGateway};
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{
+        tx::{PackedEthSignature, Transfer, Withdraw},
+        TokenKind,
+    };
 
     #[test]
     fn test_scaling_user_fee_by_two() {
@@ -1255,4 +2019,320 @@ mod tests {
 
         assert_eq!(provided_fee_scaled_by_five_percent, scaled_fee);
     }
+
+    #[test]
+    fn test_nonce_window() {
+        const WINDOW: u32 = 3;
+        let next_nonce = Nonce(5);
+
+        // N and N + 2 are accepted, N + K + 1 is rejected.
+        assert!(check_nonce_window(next_nonce, next_nonce, WINDOW).is_ok());
+        assert!(check_nonce_window(next_nonce + 2, next_nonce, WINDOW).is_ok());
+        assert!(check_nonce_window(next_nonce + WINDOW, next_nonce, WINDOW).is_ok());
+        match check_nonce_window(next_nonce + WINDOW + 1, next_nonce, WINDOW) {
+            Err(SubmitError::NonceOutOfWindow {
+                nonce,
+                next_nonce,
+                max_nonce,
+            }) => {
+                assert_eq!(nonce, Nonce(9));
+                assert_eq!(next_nonce, Nonce(5));
+                assert_eq!(max_nonce, Nonce(8));
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_pending_nonce() {
+        const WINDOW: u32 = 3;
+
+        // Nothing is queued.
+        let pending = pending_nonce(Nonce(5), &[], WINDOW);
+        assert_eq!(pending.next_nonce, Nonce(5));
+        assert_eq!(pending.remaining_window, WINDOW);
+        assert!(pending.missing_nonces.is_empty());
+
+        // N and N + 2 are queued, N + 1 is missing.
+        let pending = pending_nonce(Nonce(5), &[Nonce(5), Nonce(7)], WINDOW);
+        assert_eq!(pending.next_nonce, Nonce(8));
+        assert_eq!(pending.remaining_window, WINDOW - 1);
+        assert_eq!(pending.missing_nonces, vec![Nonce(6)]);
+
+        // Several gaps, and the outdated nonce below the committed one.
+        let pending = pending_nonce(Nonce(5), &[Nonce(4), Nonce(6), Nonce(9)], WINDOW);
+        assert_eq!(pending.next_nonce, Nonce(10));
+        // N + 4 is beyond the window, so it doesn't take it up.
+        assert_eq!(pending.remaining_window, WINDOW - 1);
+        assert_eq!(pending.missing_nonces, vec![Nonce(5), Nonce(7), Nonce(8)]);
+
+        // The gap is filled.
+        let pending = pending_nonce(Nonce(5), &[Nonce(5), Nonce(6), Nonce(7)], WINDOW);
+        assert_eq!(pending.next_nonce, Nonce(8));
+        assert_eq!(pending.remaining_window, WINDOW);
+        assert!(pending.missing_nonces.is_empty());
+
+        // Only outdated nonces are queued.
+        let pending = pending_nonce(Nonce(5), &[Nonce(3), Nonce(4)], WINDOW);
+        assert_eq!(pending.next_nonce, Nonce(5));
+        assert_eq!(pending.remaining_window, WINDOW);
+        assert!(pending.missing_nonces.is_empty());
+    }
+
+    #[test]
+    fn test_time_range() {
+        const NOW: u64 = 1_600_000_000;
+        const HORIZON: u64 = 3600;
+
+        assert!(check_time_range(TimeRange::default(), NOW, HORIZON).is_ok());
+        // Transaction becoming valid one minute later is accepted and deferred.
+        assert!(check_time_range(TimeRange::new(NOW + 60, NOW + 120), NOW, HORIZON).is_ok());
+        assert!(check_time_range(TimeRange::new(0, NOW), NOW, HORIZON).is_ok());
+        assert!(check_time_range(TimeRange::new(NOW + HORIZON, u64::MAX), NOW, HORIZON).is_ok());
+
+        // The whole window is in the past.
+        let result = check_time_range(TimeRange::new(NOW - 120, NOW - 60), NOW, HORIZON);
+        assert!(
+            matches!(result, Err(SubmitError::IncorrectTx(_))),
+            "{:?}",
+            result
+        );
+        // The window starts beyond the horizon.
+        let result = check_time_range(TimeRange::new(NOW + HORIZON + 1, u64::MAX), NOW, HORIZON);
+        assert!(
+            matches!(result, Err(SubmitError::IncorrectTx(_))),
+            "{:?}",
+            result
+        );
+    }
+
+    fn transfer(to: Address) -> ZkSyncTx {
+        ZkSyncTx::Transfer(Box::new(Transfer::new(
+            AccountId(1),
+            Address::repeat_byte(0x01),
+            to,
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        )))
+    }
+
+    fn withdraw() -> ZkSyncTx {
+        ZkSyncTx::Withdraw(Box::new(Withdraw::new(
+            AccountId(1),
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x01),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        )))
+    }
+
+    fn assert_limit_exceeded(
+        result: Result<(), SubmitError>,
+        expected_limit: SubmissionLimit,
+        expected_value: u64,
+        expected_max: u64,
+    ) {
+        match result {
+            Err(SubmitError::LimitExceeded { limit, value, max }) => {
+                assert_eq!(limit, expected_limit);
+                assert_eq!(value, expected_value);
+                assert_eq!(max, expected_max);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_submission_limits() {
+        let limits = SubmissionLimits {
+            max_transactions_per_batch: 4,
+            max_authors_per_batch: 2,
+            max_chunks_per_batch: 20,
+            max_withdrawals_per_batch: 2,
+            nonce_ahead_window: 3,
+        };
+        let existing = Address::repeat_byte(0x02);
+        let new = Address::repeat_byte(0x03);
+        let no_new_accounts = HashSet::new();
+        let new_accounts: HashSet<_> = std::iter::once(new).collect();
+
+        // Every limit is reached, but not exceeded: 2 + 6 + 6 + 6 chunks.
+        let (to_existing, to_new, withdraw) = (transfer(existing), transfer(new), withdraw());
+        let txs = [&to_existing, &to_new, &withdraw, &withdraw];
+        assert!(check_submission_limits(&limits, &txs, 2, &new_accounts).is_ok());
+
+        let txs = [
+            &to_existing,
+            &to_existing,
+            &to_existing,
+            &to_existing,
+            &to_existing,
+        ];
+        assert_limit_exceeded(
+            check_submission_limits(&limits, &txs, 1, &no_new_accounts),
+            SubmissionLimit::TransactionsPerBatch,
+            5,
+            4,
+        );
+
+        let txs = [&to_existing];
+        assert_limit_exceeded(
+            check_submission_limits(&limits, &txs, 3, &no_new_accounts),
+            SubmissionLimit::AuthorsPerBatch,
+            3,
+            2,
+        );
+
+        let txs = [&withdraw, &withdraw, &withdraw];
+        assert_limit_exceeded(
+            check_submission_limits(&limits, &txs, 1, &no_new_accounts),
+            SubmissionLimit::WithdrawalsPerBatch,
+            3,
+            2,
+        );
+
+        // The same batch fits unless the transfers create the accounts.
+        let txs = [&to_new, &to_new, &to_new, &to_new];
+        assert!(check_submission_limits(&limits, &txs, 1, &no_new_accounts).is_ok());
+        assert_limit_exceeded(
+            check_submission_limits(&limits, &txs, 1, &new_accounts),
+            SubmissionLimit::ChunksPerBatch,
+            24,
+            20,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_several_eth_signatures() {
+        let (request_sender, request_receiver) = mpsc::channel(8);
+        start_sign_checker(
+            EthereumGateway::Mock(MockEthereum::default()),
+            request_receiver,
+        );
+
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(1)));
+        let (transfer, valid_signature) = account.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u32.into(),
+            10u32.into(),
+            &Address::repeat_byte(0x02),
+            None,
+            true,
+            TimeRange::default(),
+        );
+        let message = transfer.get_ethereum_sign_message("ETH", 18).into_bytes();
+        let valid_signature = TxEthSignature::EthereumSignature(valid_signature.unwrap());
+        let invalid_signature = || {
+            let signer = ZkSyncAccount::rand();
+            let signature =
+                PackedEthSignature::sign(signer.try_get_eth_private_key().unwrap(), &message)
+                    .unwrap();
+            TxEthSignature::EthereumSignature(signature)
+        };
+        let token = Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20);
+        let tx = ZkSyncTx::Transfer(Box::new(transfer));
+        let verify = |signatures| {
+            verify_tx_info_message_signature(
+                &tx,
+                account.address,
+                token.clone(),
+                EthAccountType::Owned,
+                signatures,
+                Some(message.clone()),
+                request_sender.clone(),
+            )
+        };
+
+        let result = verify(vec![]).await;
+        assert!(
+            matches!(
+                result,
+                Err(SubmitError::TxAdd(TxAddError::MissingEthSignature))
+            ),
+            "{:?}",
+            result.err()
+        );
+
+        verify(vec![valid_signature.clone()]).await.unwrap();
+
+        // Any of the provided signatures is sufficient, the matching one is kept.
+        let verified_tx = verify(vec![
+            invalid_signature(),
+            invalid_signature(),
+            valid_signature.clone(),
+        ])
+        .await
+        .unwrap()
+        .unwrap_tx();
+        assert_eq!(
+            verified_tx.eth_sign_data.unwrap().signature,
+            valid_signature
+        );
+
+        let result = verify(vec![invalid_signature(), invalid_signature()]).await;
+        assert!(
+            matches!(
+                result,
+                Err(SubmitError::TxAdd(TxAddError::IncorrectEthSignature))
+            ),
+            "{:?}",
+            result.err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_wrong_l2_signature_code() {
+        let (request_sender, request_receiver) = mpsc::channel(8);
+        start_sign_checker(
+            EthereumGateway::Mock(MockEthereum::default()),
+            request_receiver,
+        );
+
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(1)));
+        let (mut transfer, _) = account.sign_transfer(
+            TokenId(0),
+            "ETH",
+            100u32.into(),
+            10u32.into(),
+            &Address::repeat_byte(0x02),
+            None,
+            true,
+            TimeRange::default(),
+        );
+        // The signature doesn't match the transaction once it's changed.
+        transfer.nonce = transfer.nonce + 1;
+        transfer.wipe_signer_cache();
+        let token = Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20);
+
+        let result = verify_tx_info_message_signature(
+            &ZkSyncTx::Transfer(Box::new(transfer)),
+            account.address,
+            token,
+            EthAccountType::No2FA(None),
+            Vec::new(),
+            None,
+            request_sender,
+        )
+        .await;
+        let error = jsonrpc_core::Error::from(
+            result
+                .err()
+                .expect("Transaction with the wrong signature is accepted"),
+        );
+        assert_eq!(error.code, RpcErrorCodes::InvalidSignature.into());
+        let data = error.data.unwrap();
+        assert_eq!(data["code"], RpcErrorCodes::InvalidSignature as i64);
+        assert_eq!(data["reason"], "invalidSignature");
+    }
 }
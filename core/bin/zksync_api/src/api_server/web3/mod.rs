@@ -1,7 +1,7 @@
 // Built-in uses
 // External uses
 
-use jsonrpc_core::{Error, IoHandler, MetaIoHandler, Metadata, Middleware, Result};
+use jsonrpc_core::{Error, MetaIoHandler, Metadata, Middleware, Result};
 use jsonrpc_http_server::ServerBuilder;
 // Workspace uses
 
@@ -9,6 +9,7 @@ use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_utils::panic_notify::{spawn_panic_handler, ThreadPanicNotify};
 // Local uses
 use self::{calls::CallsHelper, logs::LogsHelper, rpc_trait::Web3Rpc};
+use super::shutdown::{close_on_shutdown, InFlightCalls, ShutdownSignal};
 
 use tokio::task::JoinHandle;
 use zksync_config::configs::api::{TokenConfig, Web3Config};
@@ -65,23 +66,31 @@ pub fn start_rpc_server(
     connection_pool: ConnectionPool,
     web3_config: &Web3Config,
     token_config: &TokenConfig,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
     let addr = web3_config.bind_addr();
 
     let rpc_app = Web3RpcApp::new(connection_pool, web3_config, token_config);
+    let in_flight_calls = InFlightCalls::default();
+    let runtime = tokio::runtime::Handle::current();
     let (handler, panic_sender) = spawn_panic_handler();
 
     std::thread::spawn(move || {
         let _panic_sentinel = ThreadPanicNotify(panic_sender);
 
-        let mut io = IoHandler::new();
+        let mut io = MetaIoHandler::<(), _>::with_middleware(in_flight_calls.clone());
         rpc_app.extend(&mut io);
 
         let server = ServerBuilder::new(io)
             .threads(super::THREADS_PER_SERVER)
             .start_http(&addr)
             .unwrap();
+        let close_handle = server.close_handle();
+        close_on_shutdown(&shutdown, &runtime, in_flight_calls, move || {
+            close_handle.close()
+        });
         server.wait();
+        vlog::info!("Web3 server has stopped");
     });
     handler
 }
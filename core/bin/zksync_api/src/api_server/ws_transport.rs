@@ -0,0 +1,251 @@
+//! WebSocket transport of the JSON-RPC pubsub server.
+//!
+//! `jsonrpc-ws-server` only passes the text messages to the handlers, so the server runs its own
+//! `ws` handler to ping the connections and to close the ones that stop answering with pongs.
+
+// Built-in deps
+use std::{io, net::SocketAddr, sync::Arc, time::Duration};
+// External uses
+use futures::{channel::mpsc, StreamExt};
+use jsonrpc_core::{MetaIoHandler, Metadata, Middleware};
+use jsonrpc_ws_server::ws::{self, util::Token};
+use tokio::runtime::Handle;
+
+/// Timer token of the connection pings.
+const PING: Token = Token(1);
+
+/// Server pings of the connections.
+#[derive(Debug, Clone, Copy)]
+pub struct KeepAlive {
+    pub interval: Duration,
+    /// Number of pings in a row the client may leave without a pong before being disconnected.
+    pub max_missed_pongs: u32,
+}
+
+/// Handler of a single connection.
+struct ConnectionHandler<M: Metadata, S: Middleware<M>> {
+    out: ws::Sender,
+    io: Arc<MetaIoHandler<M, S>>,
+    meta: M,
+    runtime: Handle,
+    keep_alive: Option<KeepAlive>,
+    /// Number of pings sent since the last pong of the client.
+    missed_pongs: u32,
+    /// Whether the connection is being closed for the missed pongs.
+    closing: bool,
+}
+
+impl<M: Metadata, S: Middleware<M>> ConnectionHandler<M, S> {
+    fn schedule_ping(&self) -> ws::Result<()> {
+        match self.keep_alive {
+            Some(keep_alive) => self
+                .out
+                .timeout(keep_alive.interval.as_millis() as u64, PING),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<M: Metadata, S: Middleware<M>> ws::Handler for ConnectionHandler<M, S> {
+    fn on_open(&mut self, _: ws::Handshake) -> ws::Result<()> {
+        self.schedule_ping()
+    }
+
+    fn on_message(&mut self, message: ws::Message) -> ws::Result<()> {
+        let request = message.into_text()?;
+        let response = self.io.handle_request(&request, self.meta.clone());
+        let out = self.out.clone();
+        self.runtime.spawn(async move {
+            if let Some(response) = response.await {
+                out.send(response).unwrap_or_default();
+            }
+        });
+        Ok(())
+    }
+
+    fn on_frame(&mut self, frame: ws::Frame) -> ws::Result<Option<ws::Frame>> {
+        if frame.has_rsv1() || frame.has_rsv2() || frame.has_rsv3() {
+            return Err(ws::Error::new(
+                ws::ErrorKind::Protocol,
+                "Encountered frame with reserved bits set.",
+            ));
+        }
+        if frame.opcode() == ws::OpCode::Pong {
+            self.missed_pongs = 0;
+        }
+        Ok(Some(frame))
+    }
+
+    fn on_timeout(&mut self, event: Token) -> ws::Result<()> {
+        let keep_alive = match self.keep_alive {
+            Some(keep_alive) if event == PING => keep_alive,
+            _ => return Ok(()),
+        };
+
+        if self.closing {
+            // The client didn't answer the close frame either, so the connection is dropped.
+            return Err(ws::Error::new(
+                ws::ErrorKind::Io(io::ErrorKind::TimedOut.into()),
+                "Client didn't finish the closing handshake",
+            ));
+        }
+        if self.missed_pongs >= keep_alive.max_missed_pongs {
+            metrics::increment_counter!("api.ws.closed_connections", "reason" => "missed_pongs");
+            self.closing = true;
+            self.out.close(ws::CloseCode::Away)?;
+            return self.schedule_ping();
+        }
+
+        self.missed_pongs += 1;
+        self.out.ping(Vec::new())?;
+        self.schedule_ping()
+    }
+
+    fn on_error(&mut self, err: ws::Error) {
+        if !self.closing {
+            vlog::warn!("WebSocket connection error: {}", err);
+        }
+    }
+}
+
+/// Binds the WebSocket server passing the requests to `io`, to be started with `run`.
+///
+/// `meta_extractor` creates the metadata of every new connection, getting its sender and
+/// the channel of the messages to be sent to it (e.g. the subscription notifications).
+pub fn bind<M, S, E>(
+    addr: &SocketAddr,
+    io: impl Into<MetaIoHandler<M, S>>,
+    max_connections: usize,
+    keep_alive: Option<KeepAlive>,
+    runtime: Handle,
+    mut meta_extractor: E,
+) -> ws::Result<ws::WebSocket<impl ws::Factory>>
+where
+    M: Metadata,
+    S: Middleware<M>,
+    E: FnMut(&ws::Sender, mpsc::UnboundedSender<String>) -> M,
+{
+    // Same limits as `jsonrpc-ws-server` sets by default.
+    const MAX_PAYLOAD_BYTES: usize = 5 * 1024 * 1024;
+    const MAX_BUFFER_CAPACITY: usize = 10 * 1024 * 1024;
+
+    let mut settings = ws::Settings {
+        max_connections,
+        max_fragment_size: MAX_PAYLOAD_BYTES,
+        in_buffer_capacity_hard_limit: MAX_BUFFER_CAPACITY,
+        out_buffer_capacity_hard_limit: MAX_BUFFER_CAPACITY,
+        fragments_grow: false,
+        method_strict: true,
+        masking_strict: true,
+        shutdown_on_interrupt: false,
+        ..Default::default()
+    };
+    settings.fragments_capacity = std::cmp::max(1, MAX_PAYLOAD_BYTES / settings.fragment_size);
+
+    let io = Arc::new(io.into());
+    let factory = move |out: ws::Sender| {
+        let (transport, mut messages) = mpsc::unbounded::<String>();
+        let forward_out = out.clone();
+        runtime.spawn(async move {
+            while let Some(message) = messages.next().await {
+                forward_out.send(message).unwrap_or_default();
+            }
+        });
+
+        ConnectionHandler {
+            meta: meta_extractor(&out, transport),
+            out,
+            io: io.clone(),
+            runtime: runtime.clone(),
+            keep_alive,
+            missed_pongs: 0,
+            closing: false,
+        }
+    };
+
+    ws::Builder::new()
+        .with_settings(settings)
+        .build(factory)?
+        .bind(addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::thread::JoinHandle;
+
+    const MAX_MISSED_PONGS: u32 = 2;
+    const KEEP_ALIVE: KeepAlive = KeepAlive {
+        interval: Duration::from_millis(50),
+        max_missed_pongs: MAX_MISSED_PONGS,
+    };
+
+    /// Client counting the pings of the server, which it may leave unanswered.
+    struct Client {
+        answer_pings: bool,
+        pings: Arc<AtomicU32>,
+    }
+
+    impl ws::Handler for Client {
+        fn on_frame(&mut self, frame: ws::Frame) -> ws::Result<Option<ws::Frame>> {
+            if frame.opcode() == ws::OpCode::Ping {
+                self.pings.fetch_add(1, Ordering::SeqCst);
+                if !self.answer_pings {
+                    // Dropping the frame, so no pong is sent.
+                    return Ok(None);
+                }
+            }
+            Ok(Some(frame))
+        }
+    }
+
+    /// Connects the client, the returned handle resolves to the number of the pings it got
+    /// once the connection is closed.
+    fn connect(url: String, answer_pings: bool) -> JoinHandle<u32> {
+        std::thread::spawn(move || {
+            let pings = Arc::new(AtomicU32::new(0));
+            ws::connect(url, |_| Client {
+                answer_pings,
+                pings: pings.clone(),
+            })
+            .unwrap();
+            pings.load(Ordering::SeqCst)
+        })
+    }
+
+    #[test]
+    fn clients_missing_pongs_are_disconnected() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let handle = runtime.handle().clone();
+        let (server_sender, server_receiver) = std::sync::mpsc::channel();
+        let server = std::thread::spawn(move || {
+            let server = bind(
+                &"127.0.0.1:0".parse().unwrap(),
+                MetaIoHandler::<()>::default(),
+                10,
+                Some(KEEP_ALIVE),
+                handle,
+                |_, _| (),
+            )
+            .unwrap();
+            server_sender
+                .send((server.local_addr().unwrap(), server.broadcaster()))
+                .unwrap();
+            server.run().map(drop)
+        });
+        let (addr, broadcaster) = server_receiver.recv().unwrap();
+        let url = format!("ws://{}", addr);
+
+        let responsive = connect(url.clone(), true);
+        // The client leaving the pings without pongs is closed after `MAX_MISSED_PONGS` of them.
+        let unresponsive = connect(url, false);
+        assert_eq!(unresponsive.join().unwrap(), MAX_MISSED_PONGS);
+
+        // The one answering them stays connected until the server is stopped.
+        std::thread::sleep(KEEP_ALIVE.interval * (MAX_MISSED_PONGS + 2));
+        broadcaster.shutdown().unwrap();
+        assert!(responsive.join().unwrap() > MAX_MISSED_PONGS);
+        server.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,127 @@
+//! Client for the core private API.
+//!
+//! API nodes use it to query the state of the core server which is not
+//! available through the (possibly lagging) read-only database replica.
+
+use zksync_api_types::{
+    v02::account::PendingPriorityOp, CoreStatus, MempoolSubmission, PendingBlockInfo,
+};
+use zksync_types::{
+    tx::{error::TxAddError, TxHash},
+    Address, SerialId,
+};
+
+/// Client for the core private API.
+#[derive(Debug, Clone)]
+pub struct CoreApiClient {
+    client: reqwest::Client,
+    addr: String,
+}
+
+impl CoreApiClient {
+    pub fn new(addr: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            addr,
+        }
+    }
+
+    /// Returns the health check status of the core.
+    pub async fn status(&self) -> anyhow::Result<CoreStatus> {
+        let endpoint = format!("{}/status", self.addr);
+        let status = self
+            .client
+            .get(&endpoint)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(status)
+    }
+
+    /// Returns the subset of the given transaction hashes that are acknowledged by the core,
+    /// i.e. either queued in its mempool or already executed.
+    pub async fn known_txs(&self, tx_hashes: &[TxHash]) -> anyhow::Result<Vec<TxHash>> {
+        let endpoint = format!("{}/mempool/known_txs", self.addr);
+        let known_hashes = self
+            .client
+            .post(&endpoint)
+            .json(tx_hashes)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(known_hashes)
+    }
+
+    /// Forwards the transactions unknown to the core to its mempool once again.
+    /// Returns the result of adding them to the mempool.
+    pub async fn reforward_txs(
+        &self,
+        submission: &MempoolSubmission,
+    ) -> anyhow::Result<Result<(), TxAddError>> {
+        let endpoint = format!("{}/mempool/reforward", self.addr);
+        let result = self
+            .client
+            .post(&endpoint)
+            .json(submission)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(result)
+    }
+
+    /// Returns the deposits and full exits targeting the address that are seen by the Ethereum
+    /// watcher of the core, but not executed yet.
+    pub async fn pending_priority_ops(
+        &self,
+        address: Address,
+    ) -> anyhow::Result<Vec<PendingPriorityOp>> {
+        let endpoint = format!("{}/priority_ops/pending/{:?}", self.addr, address);
+        let ops = self
+            .client
+            .get(&endpoint)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(ops)
+    }
+
+    /// Returns the priority operation with the serial id if it's seen by the Ethereum watcher
+    /// of the core, but not executed yet.
+    pub async fn pending_priority_op(
+        &self,
+        serial_id: SerialId,
+    ) -> anyhow::Result<Option<PendingPriorityOp>> {
+        let endpoint = format!("{}/priority_ops/{}/pending", self.addr, serial_id);
+        let op = self
+            .client
+            .get(&endpoint)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(op)
+    }
+
+    /// Returns the block currently being filled by the state keeper of the core.
+    pub async fn pending_block_info(&self) -> anyhow::Result<PendingBlockInfo> {
+        let endpoint = format!("{}/state_keeper/pending_block", self.addr);
+        let info = self
+            .client
+            .get(&endpoint)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(info)
+    }
+}
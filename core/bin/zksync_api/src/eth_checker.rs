@@ -14,6 +14,16 @@ use zksync_types::{
 /// bytes4(keccak256("isValidSignature(bytes32,bytes)")
 pub const EIP1271_SUCCESS_RETURN_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
 
+/// Result of the EIP1271 signature check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EIP1271SignatureStatus {
+    Correct,
+    Incorrect,
+    /// There is no contract at the address yet, e.g. the smart wallet is going to be
+    /// deployed with CREATE2 later, so the signature can't be checked.
+    ContractNotDeployed,
+}
+
 #[derive(Clone)]
 pub struct EthereumChecker {
     client: EthereumGateway,
@@ -68,6 +78,35 @@ impl EthereumChecker {
         Ok(received == EIP1271_SUCCESS_RETURN_VALUE)
     }
 
+    /// Checks the EIP1271 signature, telling the incorrect signature apart from
+    /// the one of the smart wallet which is not deployed yet.
+    pub async fn check_eip1271_signature(
+        &self,
+        address: Address,
+        message: &[u8],
+        signature: EIP1271Signature,
+    ) -> Result<EIP1271SignatureStatus, anyhow::Error> {
+        if self
+            .is_eip1271_signature_correct(address, message, signature)
+            .await?
+        {
+            return Ok(EIP1271SignatureStatus::Correct);
+        }
+
+        // Calls to the address without code fail as well, so the code is only fetched
+        // once the signature is known to be incorrect.
+        let code = self
+            .client
+            .contract_code(address)
+            .await
+            .map_err(|e| anyhow::format_err!("Failed to query contract code: {}", e))?;
+        if code.is_empty() {
+            Ok(EIP1271SignatureStatus::ContractNotDeployed)
+        } else {
+            Ok(EIP1271SignatureStatus::Incorrect)
+        }
+    }
+
     pub async fn is_new_pubkey_hash_authorized(
         &self,
         address: Address,
@@ -91,12 +130,12 @@ impl EthereumChecker {
 
 #[cfg(test)]
 mod tests {
-    use super::EthereumChecker;
+    use super::{EIP1271SignatureStatus, EthereumChecker, EIP1271_SUCCESS_RETURN_VALUE};
     use std::str::FromStr;
     use zksync_config::test_config::TestConfig;
     use zksync_contracts::zksync_contract;
     use zksync_eth_client::ethereum_gateway::EthereumGateway;
-    use zksync_eth_client::ETHDirectClient;
+    use zksync_eth_client::{clients::mock::MockEthereum, ETHDirectClient};
     use zksync_eth_signer::PrivateKeySigner;
     use zksync_types::{
         tx::{EIP1271Signature, PackedEthSignature},
@@ -144,6 +183,38 @@ mod tests {
         assert!(result, "Signature is incorrect");
     }
 
+    #[tokio::test]
+    async fn eip1271_contract_not_deployed() {
+        let wallet = Address::repeat_byte(1);
+        let signature = EIP1271Signature(vec![0; 65]);
+        let mut client = MockEthereum::default();
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(client.clone()));
+
+        // No code at the address.
+        let status = eth_checker
+            .check_eip1271_signature(wallet, b"hello-world", signature.clone())
+            .await
+            .unwrap();
+        assert_eq!(status, EIP1271SignatureStatus::ContractNotDeployed);
+
+        // The contract is deployed, but it doesn't accept the signature.
+        client.add_contract_code(wallet, vec![0x60, 0x80]).await;
+        let status = eth_checker
+            .check_eip1271_signature(wallet, b"hello-world", signature.clone())
+            .await
+            .unwrap();
+        assert_eq!(status, EIP1271SignatureStatus::Incorrect);
+
+        client
+            .add_contract_call_result(wallet, "isValidSignature", EIP1271_SUCCESS_RETURN_VALUE)
+            .await;
+        let status = eth_checker
+            .check_eip1271_signature(wallet, b"hello-world", signature)
+            .await
+            .unwrap();
+        assert_eq!(status, EIP1271SignatureStatus::Correct);
+    }
+
     /// This test checks that the actual signature data taken from
     /// mainnet / Argent smart wallet is valid in our codebase.
     #[test]
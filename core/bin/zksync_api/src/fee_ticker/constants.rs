@@ -14,10 +14,11 @@ pub(crate) const BASE_TRANSFER_COST: u64 = VerifyCost::TRANSFER_COST
 pub(crate) const BASE_TRANSFER_TO_NEW_COST: u64 = VerifyCost::TRANSFER_TO_NEW_COST
     + CommitCost::TRANSFER_TO_NEW_COST
     + AMORTIZED_COST_PER_CHUNK * (TransferToNewOp::CHUNKS as u64);
-pub(crate) const BASE_WITHDRAW_COST: u64 = VerifyCost::WITHDRAW_COST
-    + VerifyCost::PENDING_WITHDRAW_COST
-    + CommitCost::WITHDRAW_COST
-    + AMORTIZED_COST_PER_CHUNK * (WithdrawOp::CHUNKS as u64);
+/// L1 gas spent on a single withdrawal, as accounted by the `GasCounter` when sealing blocks.
+pub(crate) const WITHDRAW_L1_EXECUTION_COST: u64 =
+    VerifyCost::WITHDRAW_COST + VerifyCost::PENDING_WITHDRAW_COST + CommitCost::WITHDRAW_COST;
+pub(crate) const BASE_WITHDRAW_COST: u64 =
+    WITHDRAW_L1_EXECUTION_COST + AMORTIZED_COST_PER_CHUNK * (WithdrawOp::CHUNKS as u64);
 pub(crate) const BASE_WITHDRAW_NFT_COST: u64 = VerifyCost::WITHDRAW_NFT_COST
     + VerifyCost::PENDING_WITHDRAW_NFT_COST
     + CommitCost::WITHDRAW_NFT_COST
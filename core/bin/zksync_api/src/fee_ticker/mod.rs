@@ -4,7 +4,7 @@
 //! `( zkp cost of chunk * number of chunks + gas price of transaction) * token risk factor / cost of token is usd`
 
 // Built-in deps
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::iter::FromIterator;
@@ -24,6 +24,7 @@ use tokio::time::Instant;
 
 // Workspace deps
 
+use zksync_api_types::SubsidyCampaign;
 use zksync_config::configs::ticker::TokenPriceSource;
 use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
@@ -32,7 +33,7 @@ use zksync_types::{
     BatchFee, ChangePubKeyOp, Fee, MintNFTOp, OutputFeeType, SwapOp, Token, TokenId, TokenLike,
     TransferOp, TransferToNewOp, TxFeeTypes, WithdrawNFTOp, WithdrawOp,
 };
-use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
+use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal, scaled_u64_to_ratio};
 
 // Local deps
 use crate::fee_ticker::constants::AMORTIZED_COST_PER_CHUNK;
@@ -74,7 +75,6 @@ impl GasOperationsCost {
                 OutputFeeType::Transfer,
                 constants::BASE_TRANSFER_COST.into(),
             ),
-            (OutputFeeType::MintNFT, constants::BASE_MINT_NFT_COST.into()),
             (
                 OutputFeeType::TransferToNew,
                 constants::BASE_TRANSFER_TO_NEW_COST.into(),
@@ -156,6 +156,8 @@ pub struct ResponseFee {
     pub normal_fee: Fee,
     pub subsidized_fee: Fee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    /// Campaign the subsidy is charged to, `None` for the default `CREATE2` subsidy.
+    pub subsidy_campaign: Option<SubsidyCampaign>,
 }
 
 #[derive(Debug, Clone)]
@@ -163,6 +165,21 @@ pub struct ResponseBatchFee {
     pub normal_fee: BatchFee,
     pub subsidized_fee: BatchFee,
     pub subsidy_size_usd: Ratio<BigUint>,
+    /// Campaign the subsidy is charged to, `None` for the default `CREATE2` subsidy.
+    pub subsidy_campaign: Option<SubsidyCampaign>,
+    /// Gas price the normal fee was calculated with.
+    pub gas_price_wei: BigUint,
+}
+
+/// Expected L1 cost of executing a withdrawal at the current gas price.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithdrawalCostEstimate {
+    pub token: Token,
+    pub gas_amount: BigUint,
+    pub gas_price_wei: BigUint,
+    /// Cost in the smallest units of the token.
+    pub cost_in_token: BigUint,
+    pub cost_usd: Ratio<BigUint>,
 }
 
 #[derive(Debug, Error)]
@@ -173,6 +190,8 @@ pub enum PriceError {
     ApiError(String),
     #[error("Database error: {0}")]
     DBError(String),
+    #[error("Price is not available: {0}")]
+    PriceNotFound(String),
 }
 
 impl PriceError {
@@ -187,6 +206,10 @@ impl PriceError {
     pub fn db_error(msg: impl Display) -> Self {
         Self::DBError(msg.to_string())
     }
+
+    pub fn price_not_found(msg: impl Display) -> Self {
+        Self::PriceNotFound(msg.to_string())
+    }
 }
 
 #[derive(Clone)]
@@ -362,7 +385,8 @@ impl FeeTicker {
             gas_price_wei.clone(),
         );
 
-        if fee_type == CPK_CREATE2_FEE_TYPE {
+        let subsidy_campaign = self.subsidy_campaign(&[fee_type]).await?;
+        if subsidy_campaign.is_some() || fee_type == CPK_CREATE2_FEE_TYPE {
             let token_price = self
                 .get_token_price(TokenLike::Id(token.id), TokenPriceRequestType::USDForOneWei)
                 .await?;
@@ -390,11 +414,19 @@ impl FeeTicker {
                 Ratio::from(BigUint::from(0u32))
             };
 
-            return Ok(ResponseFee {
-                normal_fee,
-                subsidized_fee,
-                subsidy_size_usd,
+            // The normal fee is charged if the campaign can't afford the subsidy.
+            let within_budget = subsidy_campaign.as_ref().map_or(true, |campaign| {
+                subsidy_size_usd <= scaled_u64_to_ratio(campaign.remaining_usd_scaled())
             });
+            if within_budget {
+                metrics::histogram!("ticker.get_fee_from_ticker_in_wei", start.elapsed());
+                return Ok(ResponseFee {
+                    normal_fee,
+                    subsidized_fee,
+                    subsidy_size_usd,
+                    subsidy_campaign,
+                });
+            }
         }
 
         metrics::histogram!("ticker.get_fee_from_ticker_in_wei", start.elapsed());
@@ -402,9 +434,30 @@ impl FeeTicker {
             normal_fee: normal_fee.clone(),
             subsidized_fee: normal_fee,
             subsidy_size_usd: Ratio::from(BigUint::from(0u32)),
+            subsidy_campaign: None,
         })
     }
 
+    /// Calculates the fee of the transaction made in the `token`, which is paid in the `fee_token`
+    /// if it's provided, e.g. the NFT transfer may pay the fee in any token.
+    ///
+    /// Only the token paying the fee has to be allowed for fees, `None` is returned otherwise.
+    pub async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        recipient: Address,
+        token: TokenLike,
+        fee_token: Option<TokenLike>,
+    ) -> anyhow::Result<Option<ResponseFee>> {
+        let fee_token = fee_token.unwrap_or(token);
+        if !self.token_allowed_for_fees(fee_token.clone()).await? {
+            return Ok(None);
+        }
+        self.get_fee_from_ticker_in_wei(tx_type, fee_token, recipient)
+            .await
+            .map(Some)
+    }
+
     pub async fn get_batch_from_ticker_in_wei(
         &self,
         token: TokenLike,
@@ -460,6 +513,7 @@ impl FeeTicker {
             &self.config.subsidy_cpk_price_usd / denom_part
         };
 
+        let mut txs_gas = Vec::with_capacity(txs.len());
         for (tx_type, recipient) in txs {
             let (output_fee_type, gas_tx_amount, op_chunks) =
                 self.gas_tx_amount(tx_type, recipient).await?;
@@ -475,11 +529,45 @@ impl FeeTicker {
             } else {
                 gas_tx_amount.into()
             };
+            txs_gas.push((output_fee_type, gas_tx_amount, op_chunks));
+        }
 
+        let fee_types: Vec<_> = txs_gas.iter().map(|(fee_type, ..)| *fee_type).collect();
+        let subsidy_campaign = self.subsidy_campaign(&fee_types).await?;
+        let mut remaining_budget_usd = subsidy_campaign
+            .as_ref()
+            .map(|campaign| scaled_u64_to_ratio(campaign.remaining_usd_scaled()))
+            .unwrap_or_else(|| Ratio::from(BigUint::zero()));
+
+        for (output_fee_type, gas_tx_amount, op_chunks) in txs_gas {
             total_normal_gas_tx_amount += &gas_tx_amount;
             total_op_chunks += &op_chunks;
 
-            if output_fee_type == CPK_CREATE2_FEE_TYPE {
+            let subsidized = match &subsidy_campaign {
+                // Transactions are subsidized in order while the campaign budget lasts,
+                // the rest of them pay the normal fee.
+                Some(campaign) if campaign.covers(output_fee_type) => {
+                    let normal_cost = &zkp_cost_chunk * &op_chunks
+                        + &wei_price_usd * &gas_tx_amount * &scale_gas_price;
+                    let subsidized_cost =
+                        &wei_price_usd * &subsidized_gas_amount * &scale_gas_price;
+                    let subsidy_usd = if normal_cost > subsidized_cost {
+                        (normal_cost - subsidized_cost) * &token_usd_risk * &token_price
+                    } else {
+                        Ratio::from(BigUint::zero())
+                    };
+                    if subsidy_usd <= remaining_budget_usd {
+                        remaining_budget_usd -= subsidy_usd;
+                        true
+                    } else {
+                        false
+                    }
+                }
+                // Uncovered `CREATE2` transactions still get the default subsidy.
+                Some(_) | None => output_fee_type == CPK_CREATE2_FEE_TYPE,
+            };
+
+            if subsidized {
                 // The subsidy cost contains only gas cost
                 total_subsidized_gas_tx_amount += &subsidized_gas_amount;
             } else {
@@ -515,6 +603,122 @@ impl FeeTicker {
             normal_fee,
             subsidized_fee,
             subsidy_size_usd,
+            subsidy_campaign,
+            gas_price_wei,
+        })
+    }
+
+    /// Calculates the fee for the batch where transactions pay fees in different tokens.
+    /// The fee of every transaction is calculated in its own fee token and then converted
+    /// to the `settlement_token` using the current prices of the tokens.
+    pub async fn get_mixed_batch_fee_in_wei(
+        &self,
+        settlement_token: TokenLike,
+        txs: Vec<(TxFeeTypes, Address, TokenLike)>,
+    ) -> anyhow::Result<ResponseBatchFee> {
+        let start = Instant::now();
+        let settlement_token = self.info.get_token(settlement_token).await?;
+        let settlement_price = self.token_unit_price_usd(&settlement_token).await?;
+        if settlement_price.is_zero() {
+            anyhow::bail!("The token is not acceptable for fee");
+        }
+
+        let mut txs_by_token: BTreeMap<TokenId, (Token, Vec<(TxFeeTypes, Address)>)> =
+            BTreeMap::new();
+        for (tx_type, recipient, fee_token) in txs {
+            let fee_token = self.info.get_token(fee_token).await?;
+            txs_by_token
+                .entry(fee_token.id)
+                .or_insert_with(|| (fee_token, Vec::new()))
+                .1
+                .push((tx_type, recipient));
+        }
+
+        let zero = || Ratio::from(BigUint::zero());
+        let (mut normal_zkp_fee, mut normal_gas_fee) = (zero(), zero());
+        let (mut subsidized_zkp_fee, mut subsidized_gas_fee) = (zero(), zero());
+        let mut subsidy_size_usd = zero();
+        // The fee is only estimated for the mixed batches, so the campaign budget
+        // is checked for the transactions paying in every token separately.
+        let mut subsidy_campaign = None;
+        for (_, (fee_token, txs)) in txs_by_token {
+            let fee = self
+                .get_batch_from_ticker_in_wei(TokenLike::Id(fee_token.id), txs)
+                .await?;
+            let rate = self.token_unit_price_usd(&fee_token).await? / &settlement_price;
+
+            normal_zkp_fee += Ratio::from(fee.normal_fee.zkp_fee) * &rate;
+            normal_gas_fee += Ratio::from(fee.normal_fee.gas_fee) * &rate;
+            subsidized_zkp_fee += Ratio::from(fee.subsidized_fee.zkp_fee) * &rate;
+            subsidized_gas_fee += Ratio::from(fee.subsidized_fee.gas_fee) * &rate;
+            subsidy_size_usd += fee.subsidy_size_usd;
+            subsidy_campaign = subsidy_campaign.or(fee.subsidy_campaign);
+        }
+
+        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        metrics::histogram!("ticker.get_mixed_batch_fee_in_wei", start.elapsed());
+        Ok(ResponseBatchFee {
+            normal_fee: BatchFee::new(normal_zkp_fee, normal_gas_fee),
+            subsidized_fee: BatchFee::new(subsidized_zkp_fee, subsidized_gas_fee),
+            subsidy_size_usd,
+            subsidy_campaign,
+            gas_price_wei,
+        })
+    }
+
+    /// Returns the first active subsidy campaign covering any of the fee types.
+    /// The fee types covered by a campaign are subsidized only from its budget.
+    async fn subsidy_campaign(
+        &self,
+        fee_types: &[OutputFeeType],
+    ) -> anyhow::Result<Option<SubsidyCampaign>> {
+        let campaigns = self.info.get_active_subsidy_campaigns().await?;
+        Ok(campaigns
+            .into_iter()
+            .find(|campaign| fee_types.iter().any(|fee_type| campaign.covers(*fee_type))))
+    }
+
+    /// Price of the smallest unit of the token in USD.
+    async fn token_unit_price_usd(&self, token: &Token) -> anyhow::Result<Ratio<BigUint>> {
+        let price = self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await?
+            .usd_price;
+        Ok(price / BigUint::from(10u32).pow(u32::from(token.decimals)))
+    }
+
+    /// Estimates the L1 cost of a withdrawal without any risk markups, so that it can
+    /// be compared with the fee charged by zkSync.
+    pub async fn get_withdrawal_cost_estimate(
+        &self,
+        token: TokenLike,
+    ) -> anyhow::Result<WithdrawalCostEstimate> {
+        let start = Instant::now();
+        let token = self.info.get_token(token).await?;
+
+        let gas_price_wei = self.info.get_gas_price_wei().await?;
+        let gas_amount = BigUint::from(constants::WITHDRAW_L1_EXECUTION_COST);
+        let cost_usd = self.wei_price_usd().await? * (&gas_amount * &gas_price_wei);
+
+        let token_price_usd = self
+            .info
+            .get_last_token_price(TokenLike::Id(token.id))
+            .await?
+            .usd_price
+            / BigUint::from(10u32).pow(u32::from(token.decimals));
+        let cost_in_token = cost_usd
+            .checked_div(&token_price_usd)
+            .ok_or_else(|| anyhow::format_err!("Token price is unknown"))?
+            .to_integer();
+
+        metrics::histogram!("ticker.get_withdrawal_cost_estimate", start.elapsed());
+        Ok(WithdrawalCostEstimate {
+            token,
+            gas_amount,
+            gas_price_wei,
+            cost_in_token,
+            cost_usd,
         })
     }
 
@@ -582,18 +786,27 @@ impl FeeTicker {
             TxFeeTypes::MintNFT => (OutputFeeType::MintNFT, MintNFTOp::CHUNKS),
         };
 
-        let gas_tx_amount = if matches!(
-            fee_type,
-            OutputFeeType::FastWithdraw | OutputFeeType::FastWithdrawNFT
-        ) {
-            self.calculate_fast_withdrawal_gas_cost(op_chunks).await?
-        } else {
+        let standard_cost = |fee_type: OutputFeeType| {
             self.config
                 .gas_cost_tx
                 .standard_cost
                 .get(&fee_type)
                 .cloned()
-                .unwrap()
+                .unwrap_or_else(|| panic!("No gas cost for {:?}", fee_type))
+        };
+        let gas_tx_amount = match fee_type {
+            OutputFeeType::FastWithdraw | OutputFeeType::FastWithdrawNFT => {
+                let normal_fee_type = if fee_type == OutputFeeType::FastWithdraw {
+                    OutputFeeType::Withdraw
+                } else {
+                    OutputFeeType::WithdrawNFT
+                };
+                // When most of the future blocks are already paid, the fast processing may get
+                // cheaper than the normal one, which shouldn't happen.
+                let fast_cost = self.calculate_fast_withdrawal_gas_cost(op_chunks).await?;
+                fast_cost.max(standard_cost(normal_fee_type) + 1u32)
+            }
+            _ => standard_cost(fee_type),
         };
 
         // Convert chunks amount to `BigUint`.
@@ -130,6 +130,7 @@ impl FeeTickerAPI for MockApiProvider {
 struct MockTickerInfo {
     pub future_blocks: BlocksInFutureAggregatedOperations,
     pub remaining_chunks: Option<usize>,
+    pub subsidy_campaigns: Vec<SubsidyCampaign>,
 }
 
 impl Default for MockTickerInfo {
@@ -141,6 +142,7 @@ impl Default for MockTickerInfo {
                 blocks_to_execute: 0,
             },
             remaining_chunks: None,
+            subsidy_campaigns: Vec::new(),
         }
     }
 }
@@ -195,6 +197,10 @@ impl FeeTickerInfo for MockTickerInfo {
         unreachable!("incorrect token input")
     }
 
+    async fn get_active_subsidy_campaigns(&self) -> anyhow::Result<Vec<SubsidyCampaign>> {
+        Ok(self.subsidy_campaigns.clone())
+    }
+
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
     }
@@ -446,6 +452,132 @@ fn test_ticker_subsidy() {
     }
 }
 
+fn subsidy_campaign(fee_type: OutputFeeType, budget_usd_scaled: u64) -> SubsidyCampaign {
+    SubsidyCampaign {
+        id: 1,
+        partner: "partner".to_string(),
+        fee_types: vec![fee_type],
+        budget_usd_scaled,
+        spent_usd_scaled: 0,
+        reserved_usd_scaled: 0,
+        expires_at: Utc::now() + chrono::Duration::days(1),
+        enabled: true,
+        created_at: Utc::now(),
+    }
+}
+
+/// Checks that the fee types of the campaign are subsidized while its budget lasts
+/// and the normal fee is charged after it's exhausted, including the middle of the batch.
+#[test]
+fn test_ticker_subsidy_campaign_budget() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+    let mut config = get_test_ticker_config();
+    // The subsidized transactions are free, so the subsidy is the whole normal fee.
+    config.subsidy_cpk_price_usd = Ratio::from(BigUint::zero());
+    let mut ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+    let set_campaigns = |ticker: &mut FeeTicker, campaigns: Vec<SubsidyCampaign>| {
+        let mut info: Box<MockTickerInfo> = ticker.info.clone().into_any().downcast().unwrap();
+        info.subsidy_campaigns = campaigns;
+        ticker.info = info;
+    };
+
+    let fee_type = OutputFeeType::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+        ChangePubKeyType::ECDSA,
+    ));
+    let tx_type = TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+        ChangePubKeyType::ECDSA,
+    ));
+
+    // ECDSA is not subsidized by default.
+    let fee =
+        block_on(ticker.get_fee_from_ticker_in_wei(tx_type, TokenId(0).into(), Address::default()))
+            .unwrap();
+    assert_eq!(fee.normal_fee.total_fee, fee.subsidized_fee.total_fee);
+    assert!(fee.subsidy_campaign.is_none());
+
+    let campaign = subsidy_campaign(fee_type, u64::MAX / 2);
+    set_campaigns(&mut ticker, vec![campaign.clone()]);
+    let fee =
+        block_on(ticker.get_fee_from_ticker_in_wei(tx_type, TokenId(0).into(), Address::default()))
+            .unwrap();
+    assert!(fee.subsidized_fee.total_fee.is_zero());
+    assert_eq!(fee.subsidy_campaign, Some(campaign));
+    let subsidy_usd_scaled = ratio_to_scaled_u64(fee.subsidy_size_usd.clone());
+    assert!(subsidy_usd_scaled > 0);
+
+    // The budget is not enough for the single transaction.
+    set_campaigns(
+        &mut ticker,
+        vec![subsidy_campaign(fee_type, subsidy_usd_scaled / 2)],
+    );
+    let fee =
+        block_on(ticker.get_fee_from_ticker_in_wei(tx_type, TokenId(0).into(), Address::default()))
+            .unwrap();
+    assert_eq!(fee.normal_fee.total_fee, fee.subsidized_fee.total_fee);
+    assert!(fee.subsidy_size_usd.is_zero());
+    assert!(fee.subsidy_campaign.is_none());
+
+    // The budget is enough for two transactions out of three in the batch.
+    let campaign = subsidy_campaign(fee_type, subsidy_usd_scaled * 5 / 2);
+    set_campaigns(&mut ticker, vec![campaign.clone()]);
+    let single_fee =
+        block_on(ticker.get_fee_from_ticker_in_wei(tx_type, TokenId(0).into(), Address::default()))
+            .unwrap();
+    let batch_fee =
+        block_on(ticker.get_batch_from_ticker_in_wei(
+            TokenId(0).into(),
+            vec![(tx_type, Address::default()); 3],
+        ))
+        .unwrap();
+    assert_eq!(
+        batch_fee.subsidized_fee.total_fee,
+        single_fee.normal_fee.total_fee
+    );
+    assert!(batch_fee.normal_fee.total_fee > batch_fee.subsidized_fee.total_fee);
+    let batch_subsidy_usd_scaled = ratio_to_scaled_u64(batch_fee.subsidy_size_usd.clone());
+    assert!(batch_subsidy_usd_scaled <= campaign.budget_usd_scaled);
+    assert!(
+        (batch_subsidy_usd_scaled as i64 - 2 * subsidy_usd_scaled as i64).abs()
+            <= TOLERARED_PRICE_DIFFERENCE_SCALED
+    );
+    assert_eq!(batch_fee.subsidy_campaign, Some(campaign.clone()));
+
+    // Transactions not covered by the campaign pay the normal fee,
+    // except for the `CREATE2` ones which still get the default subsidy.
+    let create2 = TxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+        ChangePubKeyType::CREATE2,
+    ));
+    let batch_fee = block_on(ticker.get_batch_from_ticker_in_wei(
+        TokenId(0).into(),
+        vec![
+            (create2, Address::default()),
+            (tx_type, Address::default()),
+            (TxFeeTypes::Transfer, Address::default()),
+        ],
+    ))
+    .unwrap();
+    let create2_fee =
+        block_on(ticker.get_fee_from_ticker_in_wei(create2, TokenId(0).into(), Address::default()))
+            .unwrap();
+    assert!(create2_fee.subsidized_fee.total_fee.is_zero());
+    let transfer_fee = block_on(ticker.get_fee_from_ticker_in_wei(
+        TxFeeTypes::Transfer,
+        TokenId(0).into(),
+        Address::default(),
+    ))
+    .unwrap();
+    assert_eq!(
+        batch_fee.subsidized_fee.total_fee,
+        transfer_fee.normal_fee.total_fee
+    );
+    assert_eq!(batch_fee.subsidy_campaign, Some(campaign));
+}
+
 #[test]
 fn test_ticker_formula() {
     let validator = FeeTokenValidator::new(
@@ -708,3 +840,223 @@ fn test_zero_price_token_fee() {
     ))
     .unwrap_err();
 }
+
+#[test]
+fn test_withdrawal_cost_estimate() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+
+    let config = get_test_ticker_config();
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+
+    let gas_price_wei = BigUint::from(10u32).pow(7u32);
+    let cost_wei = BigUint::from(constants::WITHDRAW_L1_EXECUTION_COST) * &gas_price_wei;
+    let eth_price = TestToken::eth().price_usd;
+    let expected_cost_usd = eth_price.clone() * &cost_wei / BigUint::from(10u32).pow(18u32);
+
+    // For ETH the cost in token is exactly the cost in wei.
+    let eth_estimate =
+        block_on(ticker.get_withdrawal_cost_estimate(TestToken::eth().id.into())).unwrap();
+    assert_eq!(
+        eth_estimate.gas_amount,
+        BigUint::from(constants::WITHDRAW_L1_EXECUTION_COST)
+    );
+    assert_eq!(eth_estimate.gas_price_wei, gas_price_wei);
+    assert_eq!(eth_estimate.cost_in_token, cost_wei);
+    assert_eq!(eth_estimate.cost_usd, expected_cost_usd);
+
+    // The cheap token costs 1 USD and has 6 decimals.
+    let cheap_estimate =
+        block_on(ticker.get_withdrawal_cost_estimate(TestToken::cheap().id.into())).unwrap();
+    let expected_cost_in_cheap = (eth_price * &cost_wei * BigUint::from(10u32).pow(6u32)
+        / BigUint::from(10u32).pow(18u32))
+    .to_integer();
+    assert_eq!(cheap_estimate.cost_in_token, expected_cost_in_cheap);
+    assert_eq!(cheap_estimate.cost_usd, expected_cost_usd);
+
+    // The estimate is based on the same gas model as the fee, thus it can't exceed the fee gas amount.
+    let fee = block_on(ticker.get_fee_from_ticker_in_wei(
+        TxFeeTypes::Withdraw,
+        TestToken::eth().id.into(),
+        Address::default(),
+    ))
+    .unwrap();
+    assert!(eth_estimate.gas_amount <= fee.normal_fee.gas_tx_amount);
+
+    // Price of the token is zero, so the estimate can't be expressed in it.
+    block_on(ticker.get_withdrawal_cost_estimate(TestToken::zero_price().id.into())).unwrap_err();
+}
+
+#[test]
+fn test_nft_fee_types() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+
+    let config = get_test_ticker_config();
+    let mut ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+    let token = TokenLike::from(TestToken::eth().id);
+
+    // NFT operations are priced with their own gas costs rather than as transfers.
+    for (tx_type, fee_type) in [
+        (TxFeeTypes::MintNFT, OutputFeeType::MintNFT),
+        (TxFeeTypes::WithdrawNFT, OutputFeeType::WithdrawNFT),
+    ] {
+        let fee =
+            block_on(ticker.get_fee_from_ticker_in_wei(tx_type, token.clone(), Address::default()))
+                .unwrap()
+                .normal_fee;
+        assert_eq!(fee.fee_type, fee_type);
+        assert_eq!(
+            fee.gas_tx_amount,
+            ticker.config.gas_cost_tx.standard_cost[&fee_type]
+        );
+    }
+
+    let withdraw_nft_fee = get_normal_and_subsidy_fee(
+        &mut ticker,
+        TxFeeTypes::WithdrawNFT,
+        token.clone(),
+        Address::default(),
+        None,
+        None,
+    )
+    .0;
+    // Fast processing is more expensive however many future blocks are already paid.
+    for blocks in 0..5 {
+        let future_blocks = BlocksInFutureAggregatedOperations {
+            blocks_to_commit: blocks,
+            blocks_to_prove: blocks,
+            blocks_to_execute: blocks,
+        };
+        let fast_withdraw_nft_fee = get_normal_and_subsidy_fee(
+            &mut ticker,
+            TxFeeTypes::FastWithdrawNFT,
+            token.clone(),
+            Address::default(),
+            Some(future_blocks),
+            None,
+        )
+        .0;
+        assert!(
+            fast_withdraw_nft_fee > withdraw_nft_fee,
+            "Fast NFT withdraw fee must be greater than usual NFT withdraw fee"
+        );
+    }
+}
+
+#[test]
+fn test_mixed_batch_fee() {
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new(),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        Default::default(),
+    );
+
+    let config = get_test_ticker_config();
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+    let (eth, cheap) = (TestToken::eth(), TestToken::cheap());
+    let unit_price = |token: &TestToken| {
+        token.price_usd.clone() / BigUint::from(10u32).pow(u32::from(token.precision))
+    };
+
+    // The transfer pays the fee in ETH and the withdrawal pays it in the cheap token.
+    let fee_in_eth = block_on(ticker.get_batch_from_ticker_in_wei(
+        eth.id.into(),
+        vec![(TxFeeTypes::Transfer, Address::default())],
+    ))
+    .unwrap();
+    let fee_in_cheap = block_on(ticker.get_batch_from_ticker_in_wei(
+        cheap.id.into(),
+        vec![(TxFeeTypes::Withdraw, Address::default())],
+    ))
+    .unwrap();
+    let batch = vec![
+        (TxFeeTypes::Transfer, Address::default(), eth.id.into()),
+        (TxFeeTypes::Withdraw, Address::default(), cheap.id.into()),
+    ];
+
+    for (settlement, other, fee_in_settlement, fee_in_other) in [
+        (&eth, &cheap, &fee_in_eth, &fee_in_cheap),
+        (&cheap, &eth, &fee_in_cheap, &fee_in_eth),
+    ] {
+        let rate = unit_price(other) / unit_price(settlement);
+        let expected = BatchFee::new(
+            Ratio::from(fee_in_settlement.normal_fee.zkp_fee.clone())
+                + Ratio::from(fee_in_other.normal_fee.zkp_fee.clone()) * &rate,
+            Ratio::from(fee_in_settlement.normal_fee.gas_fee.clone())
+                + Ratio::from(fee_in_other.normal_fee.gas_fee.clone()) * &rate,
+        );
+
+        let fee = block_on(ticker.get_mixed_batch_fee_in_wei(settlement.id.into(), batch.clone()))
+            .unwrap();
+        assert_eq!(fee.normal_fee.total_fee, expected.total_fee);
+        // Nothing is subsidized in this batch.
+        assert_eq!(fee.subsidized_fee.total_fee, fee.normal_fee.total_fee);
+    }
+
+    // The batch paying fees in a single token costs the same as the usual one.
+    let fee = block_on(ticker.get_mixed_batch_fee_in_wei(
+        eth.id.into(),
+        vec![(TxFeeTypes::Transfer, Address::default(), eth.id.into())],
+    ))
+    .unwrap();
+    assert_eq!(fee.normal_fee.total_fee, fee_in_eth.normal_fee.total_fee);
+
+    // The fee can't be expressed in the token without a price.
+    block_on(ticker.get_mixed_batch_fee_in_wei(TestToken::zero_price().id.into(), batch))
+        .unwrap_err();
+}
+
+#[test]
+fn test_tx_fee_in_other_token() {
+    let (eth, hex) = (TestToken::eth(), TestToken::hex());
+    // Only ETH is allowed for fees, the hex token is unknown to the validator.
+    let tokens = vec![(
+        TokenLike::Id(eth.id),
+        Token::new(eth.id, eth.address, "ETH", eth.precision, TokenKind::ERC20),
+    )];
+    let validator = FeeTokenValidator::new(
+        TokenInMemoryCache::new().with_tokens(tokens.into_iter().collect()),
+        chrono::Duration::seconds(100),
+        BigDecimal::from(100),
+        vec![eth.address].into_iter().collect(),
+    );
+
+    let config = get_test_ticker_config();
+    let ticker = FeeTicker::new(Box::new(MockTickerInfo::default()), config, validator);
+
+    let fee = block_on(ticker.get_tx_fee(
+        TxFeeTypes::Transfer,
+        Address::default(),
+        hex.id.into(),
+        None,
+    ))
+    .unwrap();
+    assert!(fee.is_none(), "Token is not allowed for fees");
+
+    // The transfer of the hex token pays the fee in ETH.
+    let fee = block_on(ticker.get_tx_fee(
+        TxFeeTypes::Transfer,
+        Address::default(),
+        hex.id.into(),
+        Some(eth.id.into()),
+    ))
+    .unwrap()
+    .expect("Fee token is allowed for fees");
+    let fee_in_eth = block_on(ticker.get_fee_from_ticker_in_wei(
+        TxFeeTypes::Transfer,
+        eth.id.into(),
+        Address::default(),
+    ))
+    .unwrap();
+    assert_eq!(fee.normal_fee.total_fee, fee_in_eth.normal_fee.total_fee);
+}
@@ -5,6 +5,7 @@ use async_trait::async_trait;
 use chrono::Utc;
 use num::rational::Ratio;
 
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
 use zksync_storage::ConnectionPool;
@@ -14,6 +15,8 @@ pub mod coingecko;
 pub mod coinmarkercap;
 
 const UPDATE_PRICE_INTERVAL_SECS: u64 = 10 * 60;
+/// Interval of checking for the newly registered tokens, so they get the price before the next full update.
+const NEW_TOKENS_POLL_INTERVAL_SECS: u64 = 10;
 /// The limit of time we are willing to wait for response.
 pub const REQUEST_TIMEOUT: Duration = Duration::from_millis(700);
 /// Configuration parameter of the reqwest Client
@@ -96,26 +99,59 @@ impl<T: TokenPriceAPI> TickerApi<T> {
         metrics::histogram!("ticker.update_price", start.elapsed());
         Ok(())
     }
+
+    /// Updates the prices of the tokens that are not in `priced_tokens`, or of all the tokens
+    /// if `full_update` is set. Tokens are added to `priced_tokens` once their price is stored.
+    async fn update_prices(
+        &self,
+        priced_tokens: &mut HashSet<TokenId>,
+        full_update: bool,
+    ) -> Result<(), PriceError> {
+        let tokens = self.get_all_tokens().await?;
+        for token in &tokens {
+            if !full_update && priced_tokens.contains(&token.id) {
+                continue;
+            }
+            match self.update_price(token).await {
+                Ok(()) => {
+                    priced_tokens.insert(token.id);
+                }
+                Err(e) => {
+                    vlog::error!(
+                        "Can't update price for token {}. Error: {}",
+                        token.symbol,
+                        e
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl<T: TokenPriceAPI + Send + Sync> FeeTickerAPI for TickerApi<T> {
     async fn keep_price_updated(self) {
+        let mut priced_tokens = HashSet::new();
+        let mut last_full_update: Option<Instant> = None;
         loop {
-            if let Ok(tokens) = self.get_all_tokens().await {
-                for token in &tokens {
-                    if let Err(e) = self.update_price(token).await {
-                        vlog::error!(
-                            "Can't update price for token {}. Error: {}",
-                            token.symbol,
-                            e
-                        );
-                    };
+            // Tokens registered since the last iteration are priced right away, so the fees
+            // can be paid in them without waiting for the next full update.
+            let full_update = last_full_update.map_or(true, |updated_at| {
+                updated_at.elapsed() >= Duration::from_secs(UPDATE_PRICE_INTERVAL_SECS)
+            });
+            if self
+                .update_prices(&mut priced_tokens, full_update)
+                .await
+                .is_ok()
+            {
+                if full_update {
+                    last_full_update = Some(Instant::now());
                 }
             } else {
                 vlog::warn!("Can't get info from the database; waiting for the next iteration");
-            };
-            tokio::time::sleep(Duration::from_secs(UPDATE_PRICE_INTERVAL_SECS)).await;
+            }
+            tokio::time::sleep(Duration::from_secs(NEW_TOKENS_POLL_INTERVAL_SECS)).await;
         }
     }
 }
@@ -13,6 +13,7 @@ use chrono::Utc;
 use num::rational::Ratio;
 use num::BigUint;
 // Workspace deps
+use zksync_api_types::SubsidyCampaign;
 use zksync_storage::ConnectionPool;
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType;
@@ -60,6 +61,9 @@ pub trait FeeTickerInfo: FeeTickerClone + Send + Sync + 'static {
 
     async fn get_token(&self, token: TokenLike) -> Result<Token, anyhow::Error>;
 
+    /// Get the subsidy campaigns which are active at the moment.
+    async fn get_active_subsidy_campaigns(&self) -> anyhow::Result<Vec<SubsidyCampaign>>;
+
     /// Make boxed value to any. Helpful for downcasting in tests
     #[cfg(test)]
     fn into_any(self: Box<Self>) -> Box<dyn Any>;
@@ -211,7 +215,10 @@ impl FeeTickerInfo for TickerInfo {
         }
 
         metrics::histogram!("ticker_info.get_last_token_price", start.elapsed(), "type" => "error");
-        Err(PriceError::db_error("No price stored in database"))
+        Err(PriceError::price_not_found(format!(
+            "No price stored in database for token {}",
+            token.symbol
+        )))
     }
 
     /// Get current gas price in ETH
@@ -258,6 +265,19 @@ impl FeeTickerInfo for TickerInfo {
         result
     }
 
+    async fn get_active_subsidy_campaigns(&self) -> anyhow::Result<Vec<SubsidyCampaign>> {
+        let start = Instant::now();
+        let campaigns = self
+            .db
+            .access_storage()
+            .await?
+            .subsidy_campaigns_schema()
+            .load_active_campaigns(Utc::now())
+            .await?;
+        metrics::histogram!("ticker_info.get_active_subsidy_campaigns", start.elapsed());
+        Ok(campaigns)
+    }
+
     #[cfg(test)]
     fn into_any(self: Box<Self>) -> Box<dyn Any> {
         self
@@ -1,8 +1,11 @@
 #![recursion_limit = "256"]
 
 pub mod api_server;
+pub mod core_api_client;
 pub mod eth_checker;
 pub mod fee_ticker;
+pub mod mempool_analyzer;
+pub mod mempool_revalidator;
 pub mod signature_checker;
 pub mod tx_error;
 pub mod utils;
@@ -0,0 +1,140 @@
+//! Periodic detection of the accounts stuck behind an unexecutable transaction.
+//!
+//! Transactions of an account are executed in the nonce order, so if the transaction with the lowest
+//! queued nonce is parked or keeps failing (e.g. the fee is too low or the balance is insufficient),
+//! all the transactions with the higher nonces wait behind it. The analyzer records such accounts
+//! along with the blocking transaction, so they can be listed by the operator and reported to the user.
+//! Optionally, the blocking transaction is dead-lettered once it blocks the account for too long,
+//! so the account can replace it with another transaction with the same nonce.
+
+// Built-in uses
+use std::time::{Duration, Instant};
+
+// External uses
+use chrono::Utc;
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_api_types::StuckAccount;
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+
+#[derive(Debug, Clone)]
+pub struct MempoolAnalyzer {
+    pool: ConnectionPool,
+    stuck_account_threshold: Duration,
+    dead_letter_timeout: Option<Duration>,
+}
+
+impl MempoolAnalyzer {
+    pub fn new(pool: ConnectionPool, config: &CommonApiConfig) -> Self {
+        Self::with_timeouts(
+            pool,
+            config.stuck_account_threshold(),
+            config.stuck_tx_dead_letter_timeout(),
+        )
+    }
+
+    /// Creates the analyzer considering the account stuck once it's blocked for `stuck_account_threshold`.
+    /// The blocking transaction is dead-lettered after `dead_letter_timeout`, if any.
+    pub fn with_timeouts(
+        pool: ConnectionPool,
+        stuck_account_threshold: Duration,
+        dead_letter_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            pool,
+            stuck_account_threshold,
+            dead_letter_timeout,
+        }
+    }
+
+    /// Refreshes the list of the stuck accounts and dead-letters the transactions
+    /// that block their accounts for too long. Returns the accounts that remain stuck.
+    pub async fn analyze(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Vec<StuckAccount>> {
+        let blocked_before = Utc::now() - chrono::Duration::from_std(self.stuck_account_threshold)?;
+        let mut stuck_accounts = storage
+            .chain()
+            .mempool_schema()
+            .refresh_stuck_accounts(blocked_before)
+            .await?;
+        for account in &stuck_accounts {
+            vlog::warn!(
+                "Account {} is blocked by the transaction {} with nonce {} since {}: {}",
+                account.account_id,
+                account.blocking_tx_hash,
+                account.blocking_nonce,
+                account.blocked_since,
+                account.reason
+            );
+        }
+
+        let dead_letter_timeout = match self.dead_letter_timeout {
+            Some(timeout) => timeout,
+            None => return Ok(stuck_accounts),
+        };
+        let dead_letter_before = Utc::now() - chrono::Duration::from_std(dead_letter_timeout)?;
+        let mut resolved = 0;
+        for account in stuck_accounts
+            .iter()
+            .filter(|account| account.blocked_since < dead_letter_before)
+        {
+            let reason = format!(
+                "Transaction blocked the account for too long: {}",
+                account.reason
+            );
+            let dead_lettered = storage
+                .chain()
+                .mempool_schema()
+                .dead_letter_tx(account.blocking_tx_hash, &reason)
+                .await?;
+            if dead_lettered {
+                vlog::info!(
+                    "Transaction {} blocking the account {} was dead-lettered",
+                    account.blocking_tx_hash,
+                    account.account_id
+                );
+                resolved += 1;
+            }
+        }
+
+        if resolved > 0 {
+            metrics::counter!("api.mempool_analyzer.dead_lettered", resolved);
+            stuck_accounts = storage
+                .chain()
+                .mempool_schema()
+                .refresh_stuck_accounts(blocked_before)
+                .await?;
+        }
+        Ok(stuck_accounts)
+    }
+
+    async fn run_iteration(&self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.pool.access_storage().await?;
+        let stuck_accounts = self.analyze(&mut storage).await?;
+
+        metrics::gauge!(
+            "api.mempool_analyzer.stuck_accounts",
+            stuck_accounts.len() as f64
+        );
+        metrics::histogram!("api.mempool_analyzer.analyze", start.elapsed());
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn run_mempool_analyzer(analyzer: MempoolAnalyzer, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut timer = tokio::time::interval(period);
+        loop {
+            timer.tick().await;
+            if let Err(err) = analyzer.run_iteration().await {
+                vlog::error!("Mempool analysis failed: {}", err);
+            }
+        }
+    })
+}
@@ -0,0 +1,394 @@
+//! Periodic re-validation of the queued transactions.
+//!
+//! Requirements for transactions may change while they are waiting in the mempool:
+//! the required fee may rise, the fee token may be no longer suitable for paying fees, or the sender
+//! may be blocked.
+//! Such transactions would fail only at execution, so the re-validator takes the oldest queued
+//! transactions, re-runs the cheap submission-time checks and parks the failing ones, i.e.
+//! excludes them from block proposals. Parked transactions are returned back to the queue once
+//! they satisfy the requirements again, or removed from the mempool after the TTL expiration.
+//! Transactions of the maintenance accounts accepted without the fee are not re-validated.
+//!
+//! Alongside the re-validator, the server always runs a task releasing the subsidies reserved in
+//! the campaign budgets for the transactions which left the mempool without being executed.
+
+// Built-in uses
+use std::{
+    collections::HashSet,
+    iter::FromIterator,
+    time::{Duration, Instant},
+};
+
+// External uses
+use chrono::{DateTime, Utc};
+use num::BigUint;
+use tokio::task::JoinHandle;
+
+// Workspace uses
+use zksync_config::configs::api::CommonApiConfig;
+use zksync_storage::ConnectionPool;
+use zksync_types::{tx::TxHash, AccountId, Address, SignedZkSyncTx, ZkSyncTx};
+
+// Local uses
+use crate::fee_ticker::FeeTicker;
+
+/// Maximum number of transactions re-validated in a single iteration.
+const REVALIDATION_SAMPLE_SIZE: u32 = 500;
+/// Time for the transaction to get to the mempool after its campaign subsidy is reserved.
+const SUBSIDY_RESERVATION_TTL: Duration = Duration::from_secs(10 * 60);
+/// Interval of releasing the stale campaign subsidy reservations.
+pub const SUBSIDY_RELEASE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Changes to be applied to the mempool as a result of re-validation.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RevalidationPlan {
+    /// Queued transactions that don't satisfy the requirements anymore, along with the reasons.
+    pub park: Vec<(TxHash, String)>,
+    /// Parked transactions that satisfy the requirements again.
+    pub unpark: Vec<TxHash>,
+}
+
+#[derive(Clone)]
+pub struct MempoolRevalidator {
+    pool: ConnectionPool,
+    ticker: FeeTicker,
+    fee_free_accounts: HashSet<AccountId>,
+    blocked_accounts: HashSet<Address>,
+    fee_margin_percent: u64,
+    parked_tx_ttl: Duration,
+}
+
+impl MempoolRevalidator {
+    pub fn new(pool: ConnectionPool, ticker: FeeTicker, config: &CommonApiConfig) -> Self {
+        Self {
+            pool,
+            ticker,
+            fee_free_accounts: HashSet::from_iter(config.fee_free_accounts.clone()),
+            blocked_accounts: HashSet::from_iter(config.blocked_accounts.clone()),
+            fee_margin_percent: config.mempool_revalidation_fee_margin_percent,
+            parked_tx_ttl: config.parked_tx_ttl(),
+        }
+    }
+
+    /// Returns the address of the account that sent the transaction. `ForcedExit` is sent by its initiator.
+    async fn tx_sender(&self, tx: &ZkSyncTx) -> anyhow::Result<Address> {
+        match tx {
+            ZkSyncTx::ForcedExit(tx) => self
+                .pool
+                .access_storage()
+                .await?
+                .chain()
+                .account_schema()
+                .account_address_by_id(tx.initiator_account_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Initiator of the forced exit is not found")),
+            _ => Ok(tx.account()),
+        }
+    }
+
+    /// Returns the reason to park the transaction, or `None` if it still satisfies the requirements.
+    async fn check_tx(&self, tx: &ZkSyncTx) -> anyhow::Result<Option<String>> {
+        if !self.blocked_accounts.is_empty() {
+            let sender = self.tx_sender(tx).await?;
+            if self.blocked_accounts.contains(&sender) {
+                return Ok(Some(format!(
+                    "Account {:?} is not allowed to submit transactions",
+                    sender
+                )));
+            }
+        }
+
+        let is_fee_free = tx
+            .account_id()
+            .map(|account_id| self.fee_free_accounts.contains(&account_id))
+            .unwrap_or(false);
+        if is_fee_free {
+            return Ok(None);
+        }
+
+        let (tx_type, token, address, provided_fee) = match tx.get_fee_info() {
+            Some(fee_info) => fee_info,
+            None => return Ok(None),
+        };
+
+        if !self.ticker.token_allowed_for_fees(token.clone()).await? {
+            return Ok(Some(
+                "Chosen token is not suitable for paying fees".to_string(),
+            ));
+        }
+
+        let fee = self
+            .ticker
+            .get_fee_from_ticker_in_wei(tx_type, token, address)
+            .await?;
+        // Subsidized fee is never greater than the normal one, but let's not rely on it.
+        let required_fee = std::cmp::min(fee.normal_fee.total_fee, fee.subsidized_fee.total_fee);
+        let margin = BigUint::from(100 + self.fee_margin_percent);
+        if provided_fee * margin < &required_fee * BigUint::from(100u32) {
+            return Ok(Some(format!(
+                "Transaction fee is too low: required at least {}",
+                required_fee
+            )));
+        }
+
+        Ok(None)
+    }
+
+    /// Decides which of the given transactions should be parked or unparked.
+    /// Transactions that can't be checked at the moment are left as they are.
+    pub async fn plan(
+        &self,
+        txs: Vec<(SignedZkSyncTx, Option<DateTime<Utc>>)>,
+    ) -> RevalidationPlan {
+        let mut plan = RevalidationPlan::default();
+        for (tx, parked_at) in txs {
+            let tx_hash = tx.hash();
+            match self.check_tx(&tx.tx).await {
+                Ok(Some(reason)) if parked_at.is_none() => plan.park.push((tx_hash, reason)),
+                Ok(None) if parked_at.is_some() => plan.unpark.push(tx_hash),
+                Ok(_) => {}
+                Err(err) => {
+                    vlog::warn!("Failed to re-validate transaction {}: {}", tx_hash, err);
+                }
+            }
+        }
+        plan
+    }
+
+    /// Re-validates the oldest queued transactions and removes the expired parked ones.
+    pub async fn revalidate(&self) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let mut storage = self.pool.access_storage().await?;
+
+        let mut txs = storage
+            .chain()
+            .mempool_schema()
+            .load_txs_for_revalidation(REVALIDATION_SAMPLE_SIZE)
+            .await?;
+        let tx_hashes: Vec<_> = txs.iter().map(|(tx, _)| tx.hash()).collect();
+        let fee_exempted: HashSet<_> = storage
+            .maintenance_schema()
+            .filter_fee_exempted_txs(&tx_hashes)
+            .await?
+            .into_iter()
+            .collect();
+        txs.retain(|(tx, _)| !fee_exempted.contains(&tx.hash()));
+        let plan = self.plan(txs).await;
+
+        let mut transaction = storage.start_transaction().await?;
+        for (tx_hash, reason) in &plan.park {
+            vlog::info!("Parking transaction {}: {}", tx_hash, reason);
+            transaction
+                .chain()
+                .mempool_schema()
+                .park_tx(*tx_hash, reason)
+                .await?;
+        }
+        transaction
+            .chain()
+            .mempool_schema()
+            .unpark_txs(&plan.unpark)
+            .await?;
+
+        let parked_before = Utc::now() - chrono::Duration::from_std(self.parked_tx_ttl)?;
+        let expired = transaction
+            .chain()
+            .mempool_schema()
+            .remove_expired_parked_txs(parked_before)
+            .await?;
+        transaction.commit().await?;
+
+        metrics::counter!("api.mempool_revalidator.parked", plan.park.len() as u64);
+        metrics::counter!("api.mempool_revalidator.unparked", plan.unpark.len() as u64);
+        metrics::counter!("api.mempool_revalidator.expired", expired.len() as u64);
+        metrics::histogram!("api.mempool_revalidator.revalidate", start.elapsed());
+        Ok(())
+    }
+}
+
+#[must_use]
+pub fn run_mempool_revalidator(
+    revalidator: MempoolRevalidator,
+    period: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut timer = tokio::time::interval(period);
+        loop {
+            timer.tick().await;
+            if let Err(err) = revalidator.revalidate().await {
+                vlog::error!("Mempool re-validation failed: {}", err);
+            }
+        }
+    })
+}
+
+/// Releases the campaign subsidies reserved for the transactions which are not in the mempool
+/// long after the reservation, i.e. were replaced, expired or never got there.
+pub async fn release_stale_subsidies(pool: &ConnectionPool) -> anyhow::Result<u64> {
+    let reserved_before = Utc::now() - chrono::Duration::from_std(SUBSIDY_RESERVATION_TTL)?;
+    let released = pool
+        .access_storage()
+        .await?
+        .subsidy_campaigns_schema()
+        .release_stale_campaign_charges(reserved_before)
+        .await?;
+    metrics::counter!("api.mempool_revalidator.released_subsidies", released);
+    Ok(released)
+}
+
+/// Runs the release of the stale campaign subsidy reservations. Unlike the re-validation,
+/// it can't be disabled, since otherwise the campaign budgets would leak.
+#[must_use]
+pub fn run_subsidy_releaser(pool: ConnectionPool, period: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut timer = tokio::time::interval(period);
+        loop {
+            timer.tick().await;
+            if let Err(err) = release_stale_subsidies(&pool).await {
+                vlog::error!("Failed to release the stale campaign subsidies: {}", err);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bigdecimal::BigDecimal;
+    use num::rational::Ratio;
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{tokens::TokenMarketVolume, Address, Token, TokenId, TokenKind, TokenLike};
+
+    use super::*;
+    use crate::{
+        api_server::rest::v02::test_utils::dummy_fee_ticker,
+        fee_ticker::validator::cache::TokenInMemoryCache,
+    };
+
+    const FEE_TOKEN: TokenId = TokenId(2);
+
+    /// Creates a ticker where the fee token costs `token_price` and ETH costs `eth_price`.
+    fn ticker(eth_price: &str, token_price: &str) -> FeeTicker {
+        let mut tokens = HashMap::new();
+        tokens.insert(
+            TokenLike::Id(FEE_TOKEN),
+            Token::new(FEE_TOKEN, Address::default(), "", 18, TokenKind::ERC20),
+        );
+        let mut market = HashMap::new();
+        market.insert(
+            FEE_TOKEN,
+            TokenMarketVolume {
+                market_volume: Ratio::from_integer(BigUint::from(400u32)),
+                last_updated: Utc::now(),
+            },
+        );
+        let cache = TokenInMemoryCache::new()
+            .with_tokens(tokens)
+            .with_market(market);
+
+        let prices = vec![
+            (
+                TokenLike::Id(TokenId(0)),
+                eth_price.parse::<BigDecimal>().unwrap(),
+            ),
+            (
+                TokenLike::Id(FEE_TOKEN),
+                token_price.parse::<BigDecimal>().unwrap(),
+            ),
+        ];
+        dummy_fee_ticker(&prices, Some(cache))
+    }
+
+    fn transfer(fee: u32) -> SignedZkSyncTx {
+        let from = ZkSyncAccount::rand();
+        from.set_account_id(Some(AccountId(1)));
+        let (transfer, _) = from.sign_transfer(
+            FEE_TOKEN,
+            "",
+            BigUint::from(1u32),
+            BigUint::from(fee),
+            &ZkSyncAccount::rand().address,
+            None,
+            true,
+            Default::default(),
+        );
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn tx_is_parked_and_unparked_with_fee_changes() {
+        let mut revalidator = MempoolRevalidator::new(
+            ConnectionPool::new(Some(1)),
+            ticker("10", "10000"),
+            &CommonApiConfig::from_env(),
+        );
+        let tx = transfer(10);
+        let tx_hash = tx.hash();
+
+        // The fee is sufficient for the current prices.
+        let plan = revalidator.plan(vec![(tx.clone(), None)]).await;
+        assert_eq!(plan, RevalidationPlan::default());
+
+        // ETH price rises, so does the required fee.
+        revalidator.ticker = ticker("10000000000000000000000", "1");
+        let plan = revalidator.plan(vec![(tx.clone(), None)]).await;
+        assert_eq!(plan.park.len(), 1);
+        assert_eq!(plan.park[0].0, tx_hash);
+        assert!(plan.unpark.is_empty());
+
+        // The transaction stays parked while the fee is insufficient.
+        let plan = revalidator.plan(vec![(tx.clone(), Some(Utc::now()))]).await;
+        assert_eq!(plan, RevalidationPlan::default());
+
+        // Once the required fee is lowered, the transaction is unparked.
+        revalidator.ticker = ticker("10", "10000");
+        let plan = revalidator.plan(vec![(tx, Some(Utc::now()))]).await;
+        assert!(plan.park.is_empty());
+        assert_eq!(plan.unpark, vec![tx_hash]);
+    }
+
+    #[tokio::test]
+    async fn tx_is_parked_for_unsuitable_token() {
+        let revalidator = MempoolRevalidator::new(
+            ConnectionPool::new(Some(1)),
+            dummy_fee_ticker(&[], None),
+            &CommonApiConfig::from_env(),
+        );
+        let tx = transfer(10);
+
+        let plan = revalidator.plan(vec![(tx, None)]).await;
+        assert_eq!(plan.park.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn tx_is_parked_and_unparked_with_blocklist_changes() {
+        let mut revalidator = MempoolRevalidator::new(
+            ConnectionPool::new(Some(1)),
+            ticker("10", "10000"),
+            &CommonApiConfig::from_env(),
+        );
+        let tx = transfer(10);
+        let tx_hash = tx.hash();
+
+        // The sender gets blocked after the transaction is queued.
+        revalidator.blocked_accounts.insert(tx.tx.account());
+        let plan = revalidator.plan(vec![(tx.clone(), None)]).await;
+        assert_eq!(plan.park.len(), 1);
+        assert_eq!(plan.park[0].0, tx_hash);
+        assert!(plan.park[0].1.contains("not allowed"));
+
+        // The transaction stays parked while the sender is blocked.
+        let plan = revalidator.plan(vec![(tx.clone(), Some(Utc::now()))]).await;
+        assert_eq!(plan, RevalidationPlan::default());
+
+        // Once the sender is unblocked, the transaction is unparked.
+        revalidator.blocked_accounts.clear();
+        let plan = revalidator.plan(vec![(tx, Some(Utc::now()))]).await;
+        assert_eq!(plan.unpark, vec![tx_hash]);
+    }
+}
@@ -18,11 +18,13 @@ use tokio::task::JoinHandle;
 // Workspace uses
 use zksync_eth_client::EthereumGateway;
 use zksync_types::{
-    tx::{error::TxAddError, EthBatchSignData, EthSignData, TxEthSignature},
+    tx::{
+        error::TxAddError, ChangePubKeyEthAuthData, EthBatchSignData, EthSignData, TxEthSignature,
+    },
     Address, Order, SignedZkSyncTx, Token, ZkSyncTx,
 };
 // Local uses
-use crate::eth_checker::EthereumChecker;
+use crate::eth_checker::{EIP1271SignatureStatus, EthereumChecker};
 use zksync_types::tx::TransactionError;
 
 /// `TxVariant` is used to form a verify request. It is possible to wrap
@@ -48,10 +50,10 @@ impl VerifiedTx {
     /// Checks the (batch of) transaction(s) correctness by verifying its
     /// Ethereum signature (if required) and `ZKSync` signature.
     pub async fn verify(
-        request_data: RequestData,
+        mut request_data: RequestData,
         eth_checker: &EthereumChecker,
     ) -> Result<Self, TxAddError> {
-        verify_eth_signature(&request_data, eth_checker).await?;
+        verify_eth_signature(&mut request_data, eth_checker).await?;
         let mut tx_variant = request_data.get_tx_variant();
         verify_tx_correctness(&mut tx_variant)?;
 
@@ -87,37 +89,15 @@ impl VerifiedTx {
 
 /// Verifies the Ethereum signature of the (batch of) transaction(s).
 async fn verify_eth_signature(
-    request_data: &RequestData,
+    request_data: &mut RequestData,
     eth_checker: &EthereumChecker,
 ) -> Result<(), TxAddError> {
     match request_data {
         RequestData::Tx(request) => {
-            verify_eth_signature_single_tx(
-                &request.tx,
-                request.sender,
-                request.token.clone(),
-                eth_checker,
-            )
-            .await?;
+            verify_eth_signature_any_signer(request, eth_checker).await?;
         }
         RequestData::Batch(request) => {
-            let accounts = &request.senders;
-            let tokens = &request.tokens;
-            let txs = &request.txs;
-
-            if accounts.len() != request.txs.len() {
-                return Err(TxAddError::Other);
-            }
-            if let Some(batch_sign_data) = &request.batch_sign_data {
-                verify_eth_signature_txs_batch(txs, accounts, batch_sign_data, eth_checker).await?;
-            }
-            // In case there're signatures provided for some of transactions
-            // we still verify them.
-            for ((tx, &account), token) in
-                txs.iter().zip(accounts.iter()).zip(tokens.iter().cloned())
-            {
-                verify_eth_signature_single_tx(tx, account, token, eth_checker).await?;
-            }
+            verify_eth_signature_batch_any_signer(request, eth_checker).await?;
         }
         RequestData::Order(request) => {
             let signature_correct = verify_ethereum_signature(
@@ -126,7 +106,7 @@ async fn verify_eth_signature(
                 request.sender,
                 eth_checker,
             )
-            .await;
+            .await?;
             if !signature_correct {
                 return Err(TxAddError::IncorrectEthSignature);
             }
@@ -138,7 +118,7 @@ async fn verify_eth_signature(
                 request.sender,
                 eth_checker,
             )
-            .await;
+            .await?;
             if !signature_correct {
                 return Err(TxAddError::IncorrectEthSignature);
             }
@@ -150,26 +130,77 @@ async fn verify_eth_signature(
 
 /// Given a single Ethereum signature and a message, checks that it
 /// was signed by an expected address.
+///
+/// Returns the `EIP1271ContractNotDeployed` error if the EIP1271 signature can't be checked,
+/// since there is no smart wallet at the address yet.
 async fn verify_ethereum_signature(
     eth_signature: &TxEthSignature,
     message: &[u8],
     sender_address: Address,
     eth_checker: &EthereumChecker,
-) -> bool {
+) -> Result<bool, TxAddError> {
     let signer_account = match eth_signature {
         TxEthSignature::EthereumSignature(packed_signature) => {
             packed_signature.signature_recover_signer(message)
         }
         TxEthSignature::EIP1271Signature(signature) => {
-            return eth_checker
-                .is_eip1271_signature_correct(sender_address, message, signature.clone())
+            let status = eth_checker
+                .check_eip1271_signature(sender_address, message, signature.clone())
                 .await
-                .expect("Unable to check EIP1271 signature")
+                .map_err(|err| {
+                    vlog::warn!("Unable to check EIP1271 signature: {}", err);
+                    TxAddError::EIP1271SignatureVerificationFail
+                })?;
+            return match status {
+                EIP1271SignatureStatus::Correct => Ok(true),
+                EIP1271SignatureStatus::Incorrect => Ok(false),
+                EIP1271SignatureStatus::ContractNotDeployed => {
+                    Err(TxAddError::EIP1271ContractNotDeployed)
+                }
+            };
         }
     };
     match signer_account {
-        Ok(address) => address == sender_address,
-        Err(_) => false,
+        Ok(address) => Ok(address == sender_address),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Checks whether the signature of the batch was made by the address.
+///
+/// Unlike [`verify_ethereum_signature`], the code at the address isn't fetched for the rejected
+/// EIP1271 signatures, since every signature of the batch is tried against every sender,
+/// so the rejected signature is most likely the one of another sender.
+async fn is_batch_signed_by(
+    eth_signature: &TxEthSignature,
+    message: &[u8],
+    sender_address: Address,
+    eth_checker: &EthereumChecker,
+) -> bool {
+    match eth_signature {
+        TxEthSignature::EthereumSignature(packed_signature) => packed_signature
+            .signature_recover_signer(message)
+            .map_or(false, |address| address == sender_address),
+        TxEthSignature::EIP1271Signature(signature) => eth_checker
+            .is_eip1271_signature_correct(sender_address, message, signature.clone())
+            .await
+            .unwrap_or(false),
+    }
+}
+
+/// Checks whether the transaction is a `ChangePubKey` of the smart wallet which is going
+/// to be deployed with CREATE2 at the account address.
+///
+/// Such wallets can't check the EIP1271 signatures before the deployment, but the CREATE2
+/// data proves that the account is controlled by the owner of the new public key.
+fn is_counterfactual_change_pubkey(tx: &ZkSyncTx) -> bool {
+    match tx {
+        ZkSyncTx::ChangePubKey(change_pk) => matches!(
+            &change_pk.eth_auth_data,
+            Some(ChangePubKeyEthAuthData::CREATE2(create2_data))
+                if create2_data.get_address(&change_pk.new_pk_hash) == change_pk.account
+        ),
+        _ => false,
     }
 }
 
@@ -205,7 +236,7 @@ async fn verify_eth_signature_single_tx(
         let mut signature_correct =
             verify_ethereum_signature(signature, &sign_data.message, sender_address, eth_checker)
                 .await;
-        if !signature_correct {
+        if let Ok(false) = signature_correct {
             let old_message = tx.get_old_ethereum_sign_message(token);
             if let Some(message) = old_message {
                 signature_correct = verify_ethereum_signature(
@@ -217,8 +248,13 @@ async fn verify_eth_signature_single_tx(
                 .await;
             }
         }
-        if !signature_correct {
-            return Err(TxAddError::IncorrectEthSignature);
+        match signature_correct {
+            Ok(true) => {}
+            Ok(false) => return Err(TxAddError::IncorrectEthSignature),
+            // The wallet is deployed by the `ChangePubKey` itself.
+            Err(TxAddError::EIP1271ContractNotDeployed)
+                if is_counterfactual_change_pubkey(&tx.tx) => {}
+            Err(err) => return Err(err),
         }
     }
 
@@ -229,6 +265,38 @@ async fn verify_eth_signature_single_tx(
     Ok(())
 }
 
+/// Verifies the Ethereum signature of the single transaction, trying the alternative signatures
+/// in order if the one provided in the transaction doesn't match the sender.
+/// The matching signature is kept in the transaction.
+async fn verify_eth_signature_any_signer(
+    request: &mut TxRequest,
+    eth_checker: &EthereumChecker,
+) -> Result<(), TxAddError> {
+    let mut result = verify_eth_signature_single_tx(
+        &request.tx,
+        request.sender,
+        request.token.clone(),
+        eth_checker,
+    )
+    .await;
+    for signature in request.alternative_signatures.drain(..) {
+        if !matches!(result, Err(TxAddError::IncorrectEthSignature)) {
+            break;
+        }
+        if let Some(sign_data) = &mut request.tx.eth_sign_data {
+            sign_data.signature = signature;
+        }
+        result = verify_eth_signature_single_tx(
+            &request.tx,
+            request.sender,
+            request.token.clone(),
+            eth_checker,
+        )
+        .await;
+    }
+    result
+}
+
 async fn verify_eth_signature_txs_batch(
     txs: &[SignedZkSyncTx],
     senders: &[Address],
@@ -257,22 +325,13 @@ async fn verify_eth_signature_txs_batch(
         // This block will set the `sender_correct` variable to `true` at the first match.
         let mut sender_correct = false;
         for signature in &batch_sign_data.signatures {
-            let mut signature_correct = verify_ethereum_signature(
-                signature,
-                &batch_sign_data.message,
-                *sender,
-                eth_checker,
-            )
-            .await;
+            let mut signature_correct =
+                is_batch_signed_by(signature, &batch_sign_data.message, *sender, eth_checker).await;
             if !signature_correct {
                 if let Some(old_message) = &old_message {
-                    signature_correct = verify_ethereum_signature(
-                        signature,
-                        old_message.as_slice(),
-                        *sender,
-                        eth_checker,
-                    )
-                    .await;
+                    signature_correct =
+                        is_batch_signed_by(signature, old_message.as_slice(), *sender, eth_checker)
+                            .await;
                 }
             }
             if signature_correct {
@@ -293,6 +352,49 @@ async fn verify_eth_signature_txs_batch(
     Ok(())
 }
 
+/// Verifies the Ethereum signatures of the batch, which is authorized either by the batch signature
+/// or by the signatures of every transaction that requires one.
+///
+/// The signatures of the transactions are verified in any case, so an incorrect one rejects the batch
+/// even if the batch signature is correct. If the batch signature is incorrect, it's dropped,
+/// so only the verified signatures are kept in the request.
+async fn verify_eth_signature_batch_any_signer(
+    request: &mut BatchRequest,
+    eth_checker: &EthereumChecker,
+) -> Result<(), TxAddError> {
+    if request.senders.len() != request.txs.len() {
+        return Err(TxAddError::Other);
+    }
+
+    let batch_signature_correct = match &request.batch_sign_data {
+        Some(batch_sign_data) => verify_eth_signature_txs_batch(
+            &request.txs,
+            &request.senders,
+            batch_sign_data,
+            eth_checker,
+        )
+        .await
+        .is_ok(),
+        None => false,
+    };
+    if !batch_signature_correct {
+        if !request.all_txs_signed {
+            return Err(TxAddError::IncorrectEthSignature);
+        }
+        request.batch_sign_data = None;
+    }
+
+    for ((tx, &sender), token) in request
+        .txs
+        .iter()
+        .zip(request.senders.iter())
+        .zip(request.tokens.iter().cloned())
+    {
+        verify_eth_signature_single_tx(tx, sender, token, eth_checker).await?;
+    }
+    Ok(())
+}
+
 /// Verifies the correctness of the ZKSync transaction(s) (including the
 /// signature check).
 fn verify_tx_correctness(tx: &mut TxVariant) -> Result<(), TxAddError> {
@@ -323,6 +425,9 @@ pub struct TxRequest {
     /// Resolved token might be used to obtain old-formatted 2-FA messages.
     /// Needed for backwards compatibility.
     pub token: Token,
+    /// Signatures tried in order if the one in `tx.eth_sign_data` doesn't belong to the sender,
+    /// so that any signature of the account owner is sufficient.
+    pub alternative_signatures: Vec<TxEthSignature>,
 }
 
 #[derive(Debug)]
@@ -331,6 +436,9 @@ pub struct BatchRequest {
     pub batch_sign_data: Option<EthBatchSignData>,
     pub senders: Vec<Address>,
     pub tokens: Vec<Token>,
+    /// Whether every transaction that requires an Ethereum signature carries its own one.
+    /// If so, the batch is accepted even if the batch signature is missing or incorrect.
+    pub all_txs_signed: bool,
 }
 
 #[derive(Debug)]
@@ -400,3 +508,320 @@ pub fn start_sign_checker(
     }
     tokio::spawn(checker_routine(input, eth_checker))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth_checker::EIP1271_SUCCESS_RETURN_VALUE;
+    use num::BigUint;
+    use zksync_eth_client::clients::mock::MockEthereum;
+    use zksync_test_account::{ZkSyncAccount, ZkSyncETHAccountData};
+    use zksync_types::{
+        tx::{
+            ChangePubKeyCREATE2Data, ChangePubKeyType, EIP1271Signature, PackedEthSignature,
+            TimeRange,
+        },
+        AccountId, Nonce, PubKeyHash, TokenId, TokenKind, H256,
+    };
+
+    /// Creates the account of the smart wallet to be deployed with CREATE2.
+    fn create2_account() -> ZkSyncAccount {
+        let private_key = ZkSyncAccount::rand().private_key;
+        let create2_data = ChangePubKeyCREATE2Data {
+            creator_address: Address::repeat_byte(1),
+            salt_arg: H256::repeat_byte(2),
+            code_hash: H256::repeat_byte(3),
+        };
+        let address = create2_data.get_address(&PubKeyHash::from_privkey(&private_key));
+        let account = ZkSyncAccount::new(
+            private_key,
+            Nonce(0),
+            address,
+            ZkSyncETHAccountData::Create2(create2_data),
+        );
+        account.set_account_id(Some(AccountId(1)));
+        account
+    }
+
+    /// Wraps the transaction into the request, signing it with the EIP1271 signature.
+    fn eip1271_request(account: &ZkSyncAccount, tx: ZkSyncTx) -> RequestData {
+        let tx = SignedZkSyncTx {
+            tx,
+            eth_sign_data: Some(EthSignData {
+                signature: TxEthSignature::EIP1271Signature(EIP1271Signature(vec![0; 65])),
+                message: b"message".to_vec(),
+            }),
+            created_at: chrono::Utc::now(),
+        };
+        RequestData::Tx(TxRequest {
+            tx,
+            sender: account.address,
+            token: Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20),
+            alternative_signatures: vec![],
+        })
+    }
+
+    fn change_pubkey(account: &ZkSyncAccount) -> ZkSyncTx {
+        let tx = account.sign_change_pubkey_tx(
+            None,
+            false,
+            TokenId(0),
+            BigUint::from(0u32),
+            ChangePubKeyType::CREATE2,
+            TimeRange::default(),
+        );
+        ZkSyncTx::ChangePubKey(Box::new(tx))
+    }
+
+    fn transfer(account: &ZkSyncAccount) -> ZkSyncTx {
+        let (tx, _) = account.sign_transfer(
+            TokenId(0),
+            "ETH",
+            BigUint::from(100u32),
+            BigUint::from(0u32),
+            &Address::repeat_byte(5),
+            None,
+            false,
+            TimeRange::default(),
+        );
+        ZkSyncTx::Transfer(Box::new(tx))
+    }
+
+    fn eth_signature(account: &ZkSyncAccount, message: &[u8]) -> TxEthSignature {
+        let private_key = account.eth_account_data.unwrap_eoa_pk();
+        TxEthSignature::EthereumSignature(PackedEthSignature::sign(&private_key, message).unwrap())
+    }
+
+    /// Creates the request for the batch of transfers, each of them signed by the account
+    /// along with the whole batch.
+    fn signed_batch_request(account: &ZkSyncAccount) -> BatchRequest {
+        let token = Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20);
+        let txs: Vec<ZkSyncTx> = (0..2)
+            .map(|nonce| {
+                let (tx, _) = account.sign_transfer(
+                    TokenId(0),
+                    "ETH",
+                    BigUint::from(100u32),
+                    BigUint::from(0u32),
+                    &Address::repeat_byte(5),
+                    Some(Nonce(nonce)),
+                    false,
+                    TimeRange::default(),
+                );
+                ZkSyncTx::Transfer(Box::new(tx))
+            })
+            .collect();
+        let batch_message = EthBatchSignData::get_batch_sign_message(
+            txs.iter()
+                .map(|tx| (tx.clone(), token.clone(), account.address))
+                .collect(),
+        );
+
+        BatchRequest {
+            txs: txs
+                .into_iter()
+                .map(|tx| {
+                    let message = tx
+                        .get_ethereum_sign_message(token.clone())
+                        .unwrap()
+                        .into_bytes();
+                    SignedZkSyncTx {
+                        tx,
+                        eth_sign_data: Some(EthSignData {
+                            signature: eth_signature(account, &message),
+                            message,
+                        }),
+                        created_at: chrono::Utc::now(),
+                    }
+                })
+                .collect(),
+            batch_sign_data: Some(EthBatchSignData {
+                signatures: vec![eth_signature(account, &batch_message)],
+                message: batch_message,
+            }),
+            senders: vec![account.address; 2],
+            tokens: vec![token; 2],
+            all_txs_signed: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_is_authorized_by_any_signatures() {
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(1)));
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(MockEthereum::default()));
+
+        let mut request = signed_batch_request(&account);
+        verify_eth_signature_batch_any_signer(&mut request, &eth_checker)
+            .await
+            .expect("Batch with all the signatures should be accepted");
+        assert!(request.batch_sign_data.is_some());
+
+        // Only the batch signature.
+        let mut request = signed_batch_request(&account);
+        for tx in &mut request.txs {
+            tx.eth_sign_data = None;
+        }
+        request.all_txs_signed = false;
+        verify_eth_signature_batch_any_signer(&mut request, &eth_checker)
+            .await
+            .expect("Batch with the batch signature should be accepted");
+
+        // Only the signatures of the transactions, the incorrect batch signature is dropped.
+        let mut request = signed_batch_request(&account);
+        request.batch_sign_data.as_mut().unwrap().signatures =
+            vec![eth_signature(&account, b"other message")];
+        verify_eth_signature_batch_any_signer(&mut request, &eth_checker)
+            .await
+            .expect("Batch with the signatures of all the transactions should be accepted");
+        assert!(request.batch_sign_data.is_none());
+
+        // Neither the batch nor every transaction is signed.
+        let mut request = signed_batch_request(&account);
+        request.batch_sign_data = None;
+        request.txs[1].eth_sign_data = None;
+        request.all_txs_signed = false;
+        let result = verify_eth_signature_batch_any_signer(&mut request, &eth_checker).await;
+        assert!(
+            matches!(result, Err(TxAddError::IncorrectEthSignature)),
+            "{:?}",
+            result
+        );
+    }
+
+    /// Checks that the incorrect signature of the transaction rejects the batch
+    /// even if the batch signature is correct.
+    #[tokio::test]
+    async fn incorrect_tx_signature_rejects_signed_batch() {
+        let account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(1)));
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(MockEthereum::default()));
+
+        let mut request = signed_batch_request(&account);
+        request.txs[1].eth_sign_data.as_mut().unwrap().signature =
+            eth_signature(&account, b"other message");
+        let result = verify_eth_signature_batch_any_signer(&mut request, &eth_checker).await;
+        assert!(
+            matches!(result, Err(TxAddError::IncorrectEthSignature)),
+            "{:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn eip1271_signature_of_not_deployed_wallet() {
+        let account = create2_account();
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(MockEthereum::default()));
+
+        // The wallet is deployed by the `ChangePubKey`, so it's accepted.
+        let request = eip1271_request(&account, change_pubkey(&account));
+        VerifiedTx::verify(request, &eth_checker)
+            .await
+            .expect("ChangePubKey of the CREATE2 wallet should be accepted");
+
+        // Other transactions require the wallet to be deployed.
+        let request = eip1271_request(&account, transfer(&account));
+        let result = VerifiedTx::verify(request, &eth_checker).await;
+        assert!(
+            matches!(result, Err(TxAddError::EIP1271ContractNotDeployed)),
+            "{:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn eip1271_signature_of_deployed_wallet() {
+        let account = create2_account();
+        let mut client = MockEthereum::default();
+        client
+            .add_contract_code(account.address, vec![0x60, 0x80])
+            .await;
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(client.clone()));
+
+        // Once deployed, the wallet has to accept the signature.
+        let request = eip1271_request(&account, change_pubkey(&account));
+        let result = VerifiedTx::verify(request, &eth_checker).await;
+        assert!(
+            matches!(result, Err(TxAddError::IncorrectEthSignature)),
+            "{:?}",
+            result
+        );
+
+        client
+            .add_contract_call_result(
+                account.address,
+                "isValidSignature",
+                EIP1271_SUCCESS_RETURN_VALUE,
+            )
+            .await;
+        let request = eip1271_request(&account, transfer(&account));
+        VerifiedTx::verify(request, &eth_checker)
+            .await
+            .expect("Signature accepted by the wallet should be correct");
+    }
+
+    #[tokio::test]
+    async fn eip1271_signature_with_unavailable_node() {
+        let account = create2_account();
+        let mut client = MockEthereum::default();
+        client.make_contract_code_unavailable(account.address).await;
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(client));
+
+        // The transaction is rejected instead of panicking, so it can be resubmitted later.
+        let request = eip1271_request(&account, transfer(&account));
+        let result = VerifiedTx::verify(request, &eth_checker).await;
+        assert!(
+            matches!(result, Err(TxAddError::EIP1271SignatureVerificationFail)),
+            "{:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn eip1271_batch_signature_is_checked_without_code() {
+        let wallet = create2_account();
+        let owner = ZkSyncAccount::rand();
+        owner.set_account_id(Some(AccountId(2)));
+        let mut client = MockEthereum::default();
+        client
+            .add_contract_call_result(
+                wallet.address,
+                "isValidSignature",
+                EIP1271_SUCCESS_RETURN_VALUE,
+            )
+            .await;
+        // The EIP1271 signature of the wallet is tried against the owner as well,
+        // which must not lead to the code lookup.
+        client.make_contract_code_unavailable(owner.address).await;
+        let eth_checker = EthereumChecker::new(EthereumGateway::Mock(client));
+
+        let message = b"batch".to_vec();
+        let owner_signature =
+            PackedEthSignature::sign(&owner.eth_account_data.unwrap_eoa_pk(), &message).unwrap();
+        let token = Token::new(TokenId(0), Address::zero(), "ETH", 18, TokenKind::ERC20);
+        let mut request = BatchRequest {
+            txs: vec![transfer(&wallet), transfer(&owner)]
+                .into_iter()
+                .map(|tx| SignedZkSyncTx {
+                    tx,
+                    eth_sign_data: None,
+                    created_at: chrono::Utc::now(),
+                })
+                .collect(),
+            batch_sign_data: Some(EthBatchSignData {
+                signatures: vec![
+                    TxEthSignature::EIP1271Signature(EIP1271Signature(vec![0; 65])),
+                    TxEthSignature::EthereumSignature(owner_signature),
+                ],
+                message,
+            }),
+            senders: vec![wallet.address, owner.address],
+            tokens: vec![token.clone(), token],
+            all_txs_signed: false,
+        };
+        verify_eth_signature_batch_any_signer(&mut request, &eth_checker)
+            .await
+            .expect("Batch signed by both senders should be accepted");
+        assert!(request.batch_sign_data.is_some());
+    }
+}
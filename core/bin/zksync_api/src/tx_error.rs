@@ -14,4 +14,7 @@ pub enum Toggle2FAError {
 
     #[error("Request to enable 2FA should not have PubKeyHash field set")]
     UnusedPubKeyHash,
+
+    #[error("Request to disable 2FA should be newer than the last 2FA change of the account")]
+    StaleTimestamp,
 }
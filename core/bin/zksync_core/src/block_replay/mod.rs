@@ -0,0 +1,333 @@
+//! Deterministic replay of the executed blocks.
+//!
+//! When a state bug is suspected, the replayer loads the committed state preceding the block,
+//! re-executes the stored operations of the block through `ZkSyncState` one by one and compares
+//! the affected accounts and the root hash with the ones recorded in the database.
+//!
+//! Blocks of the range are replayed in order, and the replay stops at the first divergence,
+//! since the following blocks are built on top of the diverged state.
+
+// Built-in uses
+use std::{collections::BTreeSet, fmt};
+// External uses
+use anyhow::format_err;
+// Workspace deps
+use zksync_crypto::{ff, Fr};
+use zksync_state::state::ZkSyncState;
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    block::ExecutedOperations, helpers::apply_updates, Account, AccountId, AccountMap,
+    AccountUpdates, BlockNumber,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Difference of a single account field between the stored and the replayed state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub account_id: AccountId,
+    pub field: String,
+    pub stored: String,
+    pub replayed: String,
+}
+
+impl FieldDiff {
+    fn new(
+        account_id: AccountId,
+        field: impl Into<String>,
+        stored: impl ToString,
+        replayed: impl ToString,
+    ) -> Self {
+        Self {
+            account_id,
+            field: field.into(),
+            stored: stored.to_string(),
+            replayed: replayed.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "account #{} {}: stored {}, replayed {}",
+            *self.account_id, self.field, self.stored, self.replayed
+        )
+    }
+}
+
+/// Difference between the stored and the replayed results of the block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDiff {
+    pub block_number: BlockNumber,
+    /// Operations stored as successful which failed to execute.
+    pub failed_ops: Vec<String>,
+    pub accounts: Vec<FieldDiff>,
+    /// Stored and replayed root hashes, if they differ.
+    pub root_hash: Option<(Fr, Fr)>,
+}
+
+impl BlockDiff {
+    fn new(block_number: BlockNumber) -> Self {
+        Self {
+            block_number,
+            failed_ops: Vec::new(),
+            accounts: Vec::new(),
+            root_hash: None,
+        }
+    }
+
+    /// Whether the block was replayed to exactly the same results as stored.
+    pub fn is_empty(&self) -> bool {
+        self.failed_ops.is_empty() && self.accounts.is_empty() && self.root_hash.is_none()
+    }
+}
+
+impl fmt::Display for BlockDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "Block #{}: no divergence", self.block_number);
+        }
+        write!(f, "Block #{} diverged:", self.block_number)?;
+        for failed_op in &self.failed_ops {
+            write!(f, "\n  failed {}", failed_op)?;
+        }
+        for account in &self.accounts {
+            write!(f, "\n  {}", account)?;
+        }
+        if let Some((stored, replayed)) = &self.root_hash {
+            write!(
+                f,
+                "\n  root hash: stored 0x{}, replayed 0x{}",
+                ff::to_hex(stored),
+                ff::to_hex(replayed)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Replays the stored blocks one by one, starting from the committed state preceding the first one.
+pub struct BlockReplayer {
+    /// State the stored operations are re-executed in.
+    state: ZkSyncState,
+    /// Accounts with the stored updates applied.
+    stored_accounts: AccountMap,
+    last_block: BlockNumber,
+}
+
+impl BlockReplayer {
+    /// Loads the committed state preceding the given block.
+    pub async fn load(
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(*block_number > 0, "Genesis block can't be replayed");
+        let last_block = block_number - 1;
+
+        let (_, accounts) = storage
+            .chain()
+            .state_schema()
+            .load_committed_state(Some(last_block))
+            .await?;
+        let nfts = storage
+            .chain()
+            .state_schema()
+            .load_committed_nft_tokens(Some(last_block))
+            .await?
+            .into_iter()
+            .map(|nft| (nft.id, nft))
+            .collect();
+        let state = ZkSyncState::from_acc_map(accounts.clone());
+        let state = ZkSyncState::new(
+            state.get_balance_tree(),
+            state.get_account_addresses(),
+            nfts,
+        );
+
+        Ok(Self {
+            state,
+            stored_accounts: accounts,
+            last_block,
+        })
+    }
+
+    /// Replays the next block and returns its difference with the stored results.
+    pub async fn replay_block(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<BlockDiff> {
+        let block_number = self.last_block + 1;
+        let block = storage
+            .chain()
+            .block_schema()
+            .get_block(block_number)
+            .await?
+            .ok_or_else(|| format_err!("Block #{} is not found in the database", block_number))?;
+        let stored_updates = storage
+            .chain()
+            .state_schema()
+            .load_state_diff_for_block(block_number)
+            .await?;
+
+        let mut diff = BlockDiff::new(block_number);
+        let mut replayed_updates = AccountUpdates::new();
+        let mut fees = Vec::new();
+        for operation in &block.block_transactions {
+            let success = match operation {
+                // Failed transactions don't affect the state.
+                ExecutedOperations::Tx(tx) if !tx.success => continue,
+                ExecutedOperations::Tx(tx) => {
+                    match self
+                        .state
+                        .execute_tx(tx.signed_tx.tx.clone(), block.timestamp)
+                    {
+                        Ok(success) => success,
+                        Err(err) => {
+                            diff.failed_ops.push(format!(
+                                "transaction {}: {}",
+                                tx.signed_tx.hash(),
+                                err
+                            ));
+                            continue;
+                        }
+                    }
+                }
+                ExecutedOperations::PriorityOp(op) => {
+                    self.state.execute_priority_op(op.priority_op.data.clone())
+                }
+            };
+            fees.extend(success.fee);
+            replayed_updates.extend(success.updates);
+        }
+        replayed_updates.extend(self.state.collect_fee(&fees, block.fee_account));
+
+        let affected_accounts: BTreeSet<AccountId> = stored_updates
+            .iter()
+            .chain(&replayed_updates)
+            .map(|(account_id, _)| *account_id)
+            .collect();
+        apply_updates(&mut self.stored_accounts, stored_updates);
+        for account_id in affected_accounts {
+            diff.accounts.extend(account_diff(
+                account_id,
+                self.stored_accounts.get(&account_id),
+                self.state.get_account(account_id).as_ref(),
+            ));
+        }
+
+        let replayed_root_hash = self.state.root_hash();
+        if replayed_root_hash != block.new_root_hash {
+            diff.root_hash = Some((block.new_root_hash, replayed_root_hash));
+        }
+
+        self.last_block = block_number;
+        Ok(diff)
+    }
+}
+
+/// Replays the blocks of the range in order, stopping at the first divergence.
+/// Returns the difference for the diverged block, `None` if all the blocks are replayed cleanly.
+pub async fn replay_blocks(
+    storage: &mut StorageProcessor<'_>,
+    from: BlockNumber,
+    to: BlockNumber,
+) -> anyhow::Result<Option<BlockDiff>> {
+    anyhow::ensure!(from <= to, "Invalid block range #{}..#{}", from, to);
+
+    let mut replayer = BlockReplayer::load(storage, from).await?;
+    for _ in *from..=*to {
+        let diff = replayer.replay_block(storage).await?;
+        if !diff.is_empty() {
+            return Ok(Some(diff));
+        }
+        vlog::info!("{}", diff);
+    }
+    Ok(None)
+}
+
+/// Replays the blocks of the range and returns the printable report if there is no divergence.
+/// Fails with the report of the diverged block otherwise.
+pub async fn replay_report(
+    storage: &mut StorageProcessor<'_>,
+    from: BlockNumber,
+    to: BlockNumber,
+) -> anyhow::Result<String> {
+    match replay_blocks(storage, from, to).await? {
+        Some(diff) => Err(format_err!(
+            "Block #{} diverged from the stored results\n{}",
+            diff.block_number,
+            diff
+        )),
+        None => Ok(format!(
+            "Blocks #{}..#{} are replayed without divergence",
+            from, to
+        )),
+    }
+}
+
+fn account_diff(
+    account_id: AccountId,
+    stored: Option<&Account>,
+    replayed: Option<&Account>,
+) -> Vec<FieldDiff> {
+    let (stored, replayed) = match (stored, replayed) {
+        (Some(stored), Some(replayed)) => (stored, replayed),
+        (None, None) => return Vec::new(),
+        (stored, replayed) => {
+            return vec![FieldDiff::new(
+                account_id,
+                "exists",
+                stored.is_some(),
+                replayed.is_some(),
+            )]
+        }
+    };
+
+    let mut diffs = Vec::new();
+    if stored.address != replayed.address {
+        diffs.push(FieldDiff::new(
+            account_id,
+            "address",
+            format!("{:?}", stored.address),
+            format!("{:?}", replayed.address),
+        ));
+    }
+    if stored.nonce != replayed.nonce {
+        diffs.push(FieldDiff::new(
+            account_id,
+            "nonce",
+            *stored.nonce,
+            *replayed.nonce,
+        ));
+    }
+    if stored.pub_key_hash != replayed.pub_key_hash {
+        diffs.push(FieldDiff::new(
+            account_id,
+            "pubKeyHash",
+            stored.pub_key_hash.as_hex(),
+            replayed.pub_key_hash.as_hex(),
+        ));
+    }
+    let tokens: BTreeSet<_> = stored
+        .get_nonzero_balances()
+        .into_iter()
+        .chain(replayed.get_nonzero_balances())
+        .map(|(token, _)| token)
+        .collect();
+    for token in tokens {
+        let (stored_balance, replayed_balance) =
+            (stored.get_balance(token), replayed.get_balance(token));
+        if stored_balance != replayed_balance {
+            diffs.push(FieldDiff::new(
+                account_id,
+                format!("balance[{}]", *token),
+                stored_balance,
+                replayed_balance,
+            ));
+        }
+    }
+    diffs
+}
@@ -0,0 +1,340 @@
+// Built-in uses
+use std::mem;
+// External uses
+use chrono::Utc;
+use db_test_macro::test as db_test;
+use num::BigUint;
+// Workspace deps
+use zksync_state::state::{CollectedFee, OpSuccess};
+use zksync_test_account::ZkSyncAccount;
+use zksync_types::{
+    block::Block,
+    tx::{ChangePubKeyType, TimeRange},
+    AccountUpdate, Address, Deposit, ExecutedPriorityOp, ExecutedTx, PriorityOp, TokenId, ZkSyncOp,
+    ZkSyncPriorityOp, ZkSyncTx, H256, U256,
+};
+// Local deps
+use super::*;
+
+const BLOCK_SIZE_CHUNKS: usize = 100;
+const BLOCK_TIMESTAMP: u64 = 1_600_000_000;
+const FEE_ACCOUNT_ID: AccountId = AccountId(0);
+
+/// Executes the operations the way the state keeper does and stores the resulting blocks.
+struct TestChain {
+    state: ZkSyncState,
+    last_block: BlockNumber,
+    next_serial_id: u64,
+    first_serial_id: u64,
+    operations: Vec<ExecutedOperations>,
+    updates: AccountUpdates,
+    fees: Vec<CollectedFee>,
+}
+
+impl TestChain {
+    fn new() -> Self {
+        Self {
+            state: ZkSyncState::empty(),
+            last_block: BlockNumber(0),
+            next_serial_id: 0,
+            first_serial_id: 0,
+            operations: Vec::new(),
+            updates: AccountUpdates::new(),
+            fees: Vec::new(),
+        }
+    }
+
+    fn account_id(&self, address: Address) -> AccountId {
+        self.state
+            .get_account_by_address(&address)
+            .expect("account doesn't exist")
+            .0
+    }
+
+    fn deposit(&mut self, to: Address, amount: u64) {
+        let data = ZkSyncPriorityOp::Deposit(Deposit {
+            from: to,
+            token: TokenId(0),
+            amount: amount.into(),
+            to,
+        });
+        let success = self.state.execute_priority_op(data.clone());
+        let priority_op = PriorityOp {
+            serial_id: self.next_serial_id,
+            data,
+            deadline_block: 0,
+            eth_hash: H256::from_low_u64_be(self.next_serial_id + 1),
+            eth_block: 0,
+            eth_block_index: Some(0),
+            eth_block_hash: None,
+            l1_surplus: None,
+        };
+        self.next_serial_id += 1;
+
+        let block_index = self.operations.len() as u32;
+        let op = self.record(success);
+        self.operations
+            .push(ExecutedOperations::PriorityOp(Box::new(
+                ExecutedPriorityOp {
+                    priority_op,
+                    op,
+                    block_index,
+                    created_at: Utc::now(),
+                },
+            )));
+    }
+
+    fn execute_tx(&mut self, tx: impl Into<ZkSyncTx>) {
+        let tx = tx.into();
+        let success = self
+            .state
+            .execute_tx(tx.clone(), BLOCK_TIMESTAMP)
+            .expect("transaction failed");
+
+        let block_index = self.operations.len() as u32;
+        let op = self.record(success);
+        self.operations
+            .push(ExecutedOperations::Tx(Box::new(ExecutedTx {
+                signed_tx: tx.into(),
+                success: true,
+                op: Some(op),
+                fail_reason: None,
+                block_index: Some(block_index),
+                created_at: Utc::now(),
+                batch_id: None,
+            })));
+    }
+
+    /// Stores the transaction as failed without executing it.
+    fn reject_tx(&mut self, tx: impl Into<ZkSyncTx>) {
+        let tx: ZkSyncTx = tx.into();
+        self.operations
+            .push(ExecutedOperations::Tx(Box::new(ExecutedTx {
+                signed_tx: tx.into(),
+                success: false,
+                op: None,
+                fail_reason: Some("Not enough balance".to_string()),
+                block_index: None,
+                created_at: Utc::now(),
+                batch_id: None,
+            })));
+    }
+
+    fn record(&mut self, success: OpSuccess) -> ZkSyncOp {
+        self.fees.extend(success.fee);
+        self.updates.extend(success.updates);
+        success.executed_op
+    }
+
+    /// Collects the fees and stores the block along with its state updates.
+    /// The updates are passed through `corrupt` before being stored.
+    async fn seal_block(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        corrupt: impl FnOnce(&mut AccountUpdates),
+    ) -> anyhow::Result<()> {
+        let fees = mem::take(&mut self.fees);
+        let fee_updates = self.state.collect_fee(&fees, FEE_ACCOUNT_ID);
+        let mut updates = mem::take(&mut self.updates);
+        updates.extend(fee_updates);
+        corrupt(&mut updates);
+
+        self.last_block = self.last_block + 1;
+        let block = Block::new(
+            self.last_block,
+            self.state.root_hash(),
+            FEE_ACCOUNT_ID,
+            mem::take(&mut self.operations),
+            (self.first_serial_id, self.next_serial_id),
+            BLOCK_SIZE_CHUNKS,
+            U256::from(1_000_000),
+            U256::from(1_500_000),
+            H256::zero(),
+            BLOCK_TIMESTAMP,
+        );
+        self.first_serial_id = self.next_serial_id;
+
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(block)
+            .await?;
+        storage
+            .chain()
+            .state_schema()
+            .commit_state_update(self.last_block, &updates, 0)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Stores 3 blocks with deposits, a `ChangePubKey`, transfers, a withdrawal and a failed transfer.
+/// Updates of the block 2 are passed through `corrupt` before being stored.
+async fn store_blocks(
+    storage: &mut StorageProcessor<'_>,
+    corrupt: impl FnOnce(&mut AccountUpdates),
+) -> anyhow::Result<()> {
+    let mut chain = TestChain::new();
+    let fee_account = ZkSyncAccount::rand();
+    let alice = ZkSyncAccount::rand();
+    let bob = ZkSyncAccount::rand();
+    let fee = || BigUint::from(10u32);
+
+    chain.deposit(fee_account.address, 1_000);
+    chain.deposit(alice.address, 100_000);
+    chain.seal_block(storage, |_| {}).await?;
+    assert_eq!(chain.account_id(fee_account.address), FEE_ACCOUNT_ID);
+    alice.set_account_id(Some(chain.account_id(alice.address)));
+
+    chain.execute_tx(alice.sign_change_pubkey_tx(
+        None,
+        true,
+        TokenId(0),
+        fee(),
+        ChangePubKeyType::Onchain,
+        TimeRange::default(),
+    ));
+    let (transfer, _) = alice.sign_transfer(
+        TokenId(0),
+        "ETH",
+        BigUint::from(5_000u32),
+        fee(),
+        &bob.address,
+        None,
+        true,
+        TimeRange::default(),
+    );
+    chain.execute_tx(transfer);
+    let (transfer, _) = alice.sign_transfer(
+        TokenId(0),
+        "ETH",
+        BigUint::from(1_000u32),
+        fee(),
+        &fee_account.address,
+        None,
+        true,
+        TimeRange::default(),
+    );
+    chain.execute_tx(transfer);
+    chain.seal_block(storage, corrupt).await?;
+
+    let (withdraw, _) = alice.sign_withdraw(
+        TokenId(0),
+        "ETH",
+        BigUint::from(2_000u32),
+        fee(),
+        &alice.address,
+        None,
+        true,
+        TimeRange::default(),
+    );
+    chain.execute_tx(withdraw);
+    let (transfer, _) = alice.sign_transfer(
+        TokenId(0),
+        "ETH",
+        BigUint::from(1_000_000u32),
+        fee(),
+        &bob.address,
+        None,
+        false,
+        TimeRange::default(),
+    );
+    chain.reject_tx(transfer);
+    chain.seal_block(storage, |_| {}).await?;
+
+    Ok(())
+}
+
+/// Checks that the blocks stored as executed are replayed to the same results.
+#[db_test]
+async fn replay_without_divergence(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    store_blocks(&mut storage, |_| {}).await?;
+
+    let diff = replay_blocks(&mut storage, BlockNumber(1), BlockNumber(3)).await?;
+    assert_eq!(diff, None);
+
+    // Replay may start from any block.
+    let diff = replay_blocks(&mut storage, BlockNumber(2), BlockNumber(3)).await?;
+    assert_eq!(diff, None);
+
+    let report = replay_report(&mut storage, BlockNumber(1), BlockNumber(3)).await?;
+    assert_eq!(report, "Blocks #1..#3 are replayed without divergence");
+    // Blocks beyond the stored ones can't be replayed.
+    let err = replay_report(&mut storage, BlockNumber(1), BlockNumber(4))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Block #4 is not found"), "{}", err);
+    Ok(())
+}
+
+/// Checks that a corrupted stored update is reported as the divergence of the exact account field.
+#[db_test]
+async fn replay_detects_corrupted_update(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let mut corrupted_balance = None;
+    store_blocks(&mut storage, |updates| {
+        // The last balance update of the block is the fee collection, so it's not overwritten.
+        let (account_id, update) = updates
+            .iter_mut()
+            .rev()
+            .find(|(_, update)| matches!(update, AccountUpdate::UpdateBalance { .. }))
+            .expect("block has no balance updates");
+        if let AccountUpdate::UpdateBalance { balance_update, .. } = update {
+            balance_update.2 += 1u32;
+            corrupted_balance = Some((*account_id, balance_update.2.clone()));
+        }
+    })
+    .await?;
+    let (account_id, corrupted_balance) = corrupted_balance.unwrap();
+    assert_eq!(account_id, FEE_ACCOUNT_ID);
+
+    let diff = replay_blocks(&mut storage, BlockNumber(1), BlockNumber(3))
+        .await?
+        .expect("divergence is not detected");
+    assert_eq!(diff.block_number, BlockNumber(2));
+    assert!(diff.failed_ops.is_empty());
+    // Root hash of the block was computed from the actual state, only the stored update is corrupted.
+    assert_eq!(diff.root_hash, None);
+    assert_eq!(
+        diff.accounts,
+        vec![FieldDiff::new(
+            account_id,
+            "balance[0]",
+            corrupted_balance.clone(),
+            corrupted_balance - 1u32,
+        )]
+    );
+
+    let mut replayer = BlockReplayer::load(&mut storage, BlockNumber(1)).await?;
+    assert!(replayer.replay_block(&mut storage).await?.is_empty());
+    assert!(!replayer.replay_block(&mut storage).await?.is_empty());
+
+    // The replay tool fails with the report of the diverged block.
+    let err = replay_report(&mut storage, BlockNumber(1), BlockNumber(3))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.starts_with("Block #2 diverged from the stored results\n"),
+        "{}",
+        err
+    );
+    assert!(err.contains("balance[0]"), "{}", err);
+    Ok(())
+}
+
+/// Checks that a stored operation which can't be executed in the replayed state is reported.
+#[db_test]
+async fn replay_reports_failed_operations(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    store_blocks(&mut storage, |_| {}).await?;
+
+    let mut replayer = BlockReplayer::load(&mut storage, BlockNumber(3)).await?;
+    assert!(replayer.replay_block(&mut storage).await?.is_empty());
+
+    // Replaying the block on top of its own results fails the withdrawal because of the nonce.
+    replayer.last_block = BlockNumber(2);
+    let diff = replayer.replay_block(&mut storage).await?;
+    assert_eq!(diff.block_number, BlockNumber(3));
+    assert_eq!(diff.failed_ops.len(), 1, "{}", diff);
+    assert!(diff.failed_ops[0].starts_with("transaction "));
+    Ok(())
+}
@@ -8,10 +8,10 @@ use tokio::{task::JoinHandle, time};
 // Workspace uses
 use zksync_config::ChainConfig;
 use zksync_crypto::Fr;
-use zksync_storage::ConnectionPool;
+use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::{
-    block::{Block, BlockMetadata, ExecutedOperations, IncompleteBlock, PendingBlock},
+    block::{Block, BlockMetadata, ExecutedOperations, ExecutedTx, IncompleteBlock, PendingBlock},
     AccountUpdates, BlockNumber,
 };
 
@@ -54,7 +54,11 @@ pub struct ExecutedOpsNotify {
 
 const PROOF_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
-async fn handle_new_commit_task(mut rx_for_ops: Receiver<CommitRequest>, pool: ConnectionPool) {
+async fn handle_new_commit_task(
+    mut rx_for_ops: Receiver<CommitRequest>,
+    pool: ConnectionPool,
+    max_failed_tx_attempts: u32,
+) {
     vlog::info!("Run committer");
     let mut token_db_cache = TokenDBCache::new(TOKEN_INVALIDATE_CACHE);
     token_db_cache
@@ -68,11 +72,18 @@ async fn handle_new_commit_task(mut rx_for_ops: Receiver<CommitRequest>, pool: C
                     applied_updates_req,
                     &pool,
                     &mut token_db_cache,
+                    max_failed_tx_attempts,
                 )
                 .await;
             }
             CommitRequest::PendingBlock((pending_block, applied_updates_req)) => {
-                save_pending_block(pending_block, applied_updates_req, &pool).await;
+                save_pending_block(
+                    pending_block,
+                    applied_updates_req,
+                    &pool,
+                    max_failed_tx_attempts,
+                )
+                .await;
             }
             CommitRequest::FinishBlock(request) => {
                 finish_block(request, &pool).await;
@@ -99,10 +110,48 @@ async fn remove_reverted_block(block_number: BlockNumber, pool: &ConnectionPool)
     metrics::histogram!("committer.remove_reverted_block", start.elapsed());
 }
 
+/// Records failed execution attempts of the standalone transactions and moves the ones
+/// that failed too many times to the dead-letter state.
+/// Failed transactions from batches are not tracked, since a batch can't be resubmitted partially.
+async fn record_failed_txs<'a>(
+    storage: &mut StorageProcessor<'_>,
+    txs: impl Iterator<Item = &'a ExecutedTx>,
+    max_failed_tx_attempts: u32,
+) {
+    for tx in txs.filter(|tx| !tx.success && tx.batch_id.is_none()) {
+        let fail_reason = tx.fail_reason.as_deref().unwrap_or_default();
+        let dead_lettered = storage
+            .chain()
+            .mempool_schema()
+            .record_failed_execution(
+                &tx.signed_tx,
+                fail_reason,
+                tx.created_at,
+                max_failed_tx_attempts,
+            )
+            .await
+            .expect("committer must record failed execution attempts into db");
+
+        if dead_lettered {
+            vlog::warn!(
+                "Transaction {} failed {} times and was moved to the dead-letter state: {}",
+                tx.signed_tx.hash(),
+                max_failed_tx_attempts,
+                fail_reason
+            );
+            metrics::increment_counter!(
+                "committer.dead_lettered_txs",
+                "reason" => fail_reason.to_string()
+            );
+        }
+    }
+}
+
 async fn save_pending_block(
     pending_block: PendingBlock,
     applied_updates_request: AppliedUpdatesRequest,
     pool: &ConnectionPool,
+    max_failed_tx_attempts: u32,
 ) {
     let start = Instant::now();
     let mut storage = pool
@@ -119,6 +168,13 @@ async fn save_pending_block(
 
     vlog::trace!("persist pending block #{}", block_number);
 
+    record_failed_txs(
+        &mut transaction,
+        pending_block.failed_txs.iter(),
+        max_failed_tx_attempts,
+    )
+    .await;
+
     transaction
         .chain()
         .block_schema()
@@ -150,6 +206,7 @@ async fn seal_incomplete_block(
     applied_updates_request: AppliedUpdatesRequest,
     pool: &ConnectionPool,
     token_db_cache: &mut TokenDBCache,
+    max_failed_tx_attempts: u32,
 ) {
     let start = Instant::now();
     let BlockCommitRequest {
@@ -192,6 +249,13 @@ async fn seal_incomplete_block(
 
     vlog::info!("seal incomplete block #{}", block.block_number);
 
+    // Failed transactions that were already saved with the pending block are not counted twice.
+    let failed_txs = block.block_transactions.iter().filter_map(|op| match op {
+        ExecutedOperations::Tx(tx) => Some(tx.as_ref()),
+        ExecutedOperations::PriorityOp(_) => None,
+    });
+    record_failed_txs(&mut transaction, failed_txs, max_failed_tx_attempts).await;
+
     let block_number = block.block_number;
 
     transaction
@@ -304,6 +368,10 @@ pub fn run_committer(
     pool: ConnectionPool,
     config: ChainConfig,
 ) -> JoinHandle<()> {
-    tokio::spawn(handle_new_commit_task(rx_for_ops, pool.clone()));
+    tokio::spawn(handle_new_commit_task(
+        rx_for_ops,
+        pool.clone(),
+        config.state_keeper.max_failed_tx_attempts,
+    ));
     tokio::spawn(poll_for_new_proofs_task(pool, config))
 }
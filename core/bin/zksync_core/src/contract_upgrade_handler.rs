@@ -0,0 +1,115 @@
+// Built-in deps
+use std::time::Duration;
+
+// External uses
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use tokio::task::JoinHandle;
+// Workspace uses
+use zksync_config::ETHWatchConfig;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::ContractUpgradeEvent;
+// Local uses
+use crate::eth_watch::EthWatchRequest;
+
+/// Handle events about completed upgrades of the contracts
+#[derive(Debug)]
+struct ContractUpgradeHandler {
+    connection_pool: ConnectionPool,
+    poll_interval: Duration,
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    last_eth_block: Option<u64>,
+}
+
+impl ContractUpgradeHandler {
+    fn new(
+        connection_pool: ConnectionPool,
+        eth_watch_req: mpsc::Sender<EthWatchRequest>,
+        config: &ETHWatchConfig,
+    ) -> Self {
+        Self {
+            connection_pool,
+            eth_watch_req,
+            poll_interval: config.contract_upgrade_poll_interval(),
+            last_eth_block: None,
+        }
+    }
+
+    async fn load_contract_upgrade_events(&self) -> Vec<ContractUpgradeEvent> {
+        let (sender, receiver) = oneshot::channel();
+        self.eth_watch_req
+            .clone()
+            .send(EthWatchRequest::GetContractUpgradeEvents {
+                last_eth_block: self.last_eth_block,
+                resp: sender,
+            })
+            .await
+            .expect("ETH watch req receiver dropped");
+
+        receiver.await.expect("Err response from eth watch")
+    }
+
+    async fn save_contract_upgrades(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        contract_upgrade_events: Vec<ContractUpgradeEvent>,
+    ) -> anyhow::Result<()> {
+        let mut transaction = storage.start_transaction().await?;
+        for event in contract_upgrade_events {
+            vlog::info!(
+                "Contracts were upgraded to version {}, new targets: {:?}",
+                event.version_id,
+                event.new_targets
+            );
+            transaction
+                .config_schema()
+                .store_contract_upgrade(&event)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    async fn run(&mut self) {
+        let mut timer = tokio::time::interval(self.poll_interval);
+        loop {
+            timer.tick().await;
+
+            let contract_upgrade_events = self.load_contract_upgrade_events().await;
+            if contract_upgrade_events.is_empty() {
+                continue;
+            }
+
+            self.last_eth_block = contract_upgrade_events
+                .iter()
+                .map(|event| event.eth_block)
+                .max()
+                .or(self.last_eth_block);
+
+            let mut storage = self
+                .connection_pool
+                .access_storage()
+                .await
+                .expect("db connection failed for contract upgrade handler");
+
+            self.save_contract_upgrades(&mut storage, contract_upgrade_events)
+                .await
+                .expect("failed to add contract upgrades to the database");
+        }
+    }
+}
+
+#[must_use]
+pub fn run_contract_upgrade_handler(
+    db_pool: ConnectionPool,
+    eth_watch_req: mpsc::Sender<EthWatchRequest>,
+    config: ETHWatchConfig,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut handler = ContractUpgradeHandler::new(db_pool, eth_watch_req, &config);
+
+        handler.run().await
+    })
+}
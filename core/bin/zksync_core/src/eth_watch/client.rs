@@ -2,28 +2,36 @@ use std::{convert::TryFrom, time::Instant};
 
 use anyhow::format_err;
 use ethabi::Hash;
+use num::{BigUint, CheckedSub};
 use std::fmt::Debug;
 use web3::{
     contract::Options,
     transports::http,
-    types::{BlockNumber, FilterBuilder, Log},
+    types::{BlockNumber, FilterBuilder, Log, Transaction},
     Web3,
 };
 
-use zksync_contracts::{governance_contract, zksync_contract};
+use zksync_contracts::{governance_contract, upgrade_gatekeeper, zksync_contract};
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_types::{
-    Address, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent, H160, U256,
+    Address, ContractUpgradeEvent, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent,
+    TokenId, ZkSyncPriorityOp, H160, U256,
 };
+use zksync_utils::BigUintSerdeWrapper;
 
 struct ContractTopics {
     new_priority_request: Hash,
     new_token: Hash,
     factory_registered: Hash,
+    upgrade_complete: Hash,
 }
 
 impl ContractTopics {
-    fn new(zksync_contract: &ethabi::Contract, governance_contract: &ethabi::Contract) -> Self {
+    fn new(
+        zksync_contract: &ethabi::Contract,
+        governance_contract: &ethabi::Contract,
+        upgrade_gatekeeper: &ethabi::Contract,
+    ) -> Self {
         Self {
             new_priority_request: zksync_contract
                 .event("NewPriorityRequest")
@@ -37,6 +45,10 @@ impl ContractTopics {
                 .event("NFTFactoryRegisteredCreator")
                 .expect("main contract abi error")
                 .signature(),
+            upgrade_complete: upgrade_gatekeeper
+                .event("UpgradeComplete")
+                .expect("upgrade gatekeeper contract abi error")
+                .signature(),
         }
     }
 }
@@ -58,6 +70,11 @@ pub trait EthClient {
         from: BlockNumber,
         to: BlockNumber,
     ) -> anyhow::Result<Vec<NewTokenEvent>>;
+    async fn get_contract_upgrade_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<ContractUpgradeEvent>>;
     async fn block_number(&self) -> anyhow::Result<u64>;
     async fn get_auth_fact(&self, address: Address, nonce: Nonce) -> anyhow::Result<Vec<u8>>;
     async fn get_auth_fact_reset_time(&self, address: Address, nonce: Nonce)
@@ -67,8 +84,10 @@ pub trait EthClient {
 pub struct EthHttpClient {
     client: EthereumGateway,
     topics: ContractTopics,
+    deposit_erc20: ethabi::Function,
     zksync_contract_addr: H160,
     governance_contract_addr: H160,
+    upgrade_gatekeeper_addr: H160,
 }
 
 impl EthHttpClient {
@@ -76,13 +95,25 @@ impl EthHttpClient {
         client: EthereumGateway,
         zksync_contract_addr: H160,
         governance_contract_addr: H160,
+        upgrade_gatekeeper_addr: H160,
     ) -> Self {
-        let topics = ContractTopics::new(&zksync_contract(), &governance_contract());
+        let zksync_contract = zksync_contract();
+        let topics = ContractTopics::new(
+            &zksync_contract,
+            &governance_contract(),
+            &upgrade_gatekeeper(),
+        );
+        let deposit_erc20 = zksync_contract
+            .function("depositERC20")
+            .expect("main contract abi error")
+            .clone();
         Self {
             client,
             topics,
+            deposit_erc20,
             zksync_contract_addr,
             governance_contract_addr,
+            upgrade_gatekeeper_addr,
         }
     }
 
@@ -100,6 +131,7 @@ impl EthHttpClient {
             .address(vec![
                 self.zksync_contract_addr,
                 self.governance_contract_addr,
+                self.upgrade_gatekeeper_addr,
             ])
             .from_block(from)
             .to_block(to)
@@ -124,6 +156,61 @@ impl EthHttpClient {
             })
             .collect()
     }
+
+    /// Derives the surplus of the deposits from their L1 transactions.
+    async fn fill_l1_surplus(&self, ops: &mut [PriorityOp]) -> anyhow::Result<()> {
+        for op in ops
+            .iter_mut()
+            .filter(|op| matches!(op.data, ZkSyncPriorityOp::Deposit(_)))
+        {
+            if let Some(tx) = self.client.get_tx(op.eth_hash).await? {
+                op.l1_surplus =
+                    deposit_l1_surplus(&self.deposit_erc20, self.zksync_contract_addr, op, &tx);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the part of the amount paid by the L1 transaction that was not credited by the deposit,
+/// e.g. because the token charges a fee on transfers.
+/// `None` if the operation is not a deposit or the transaction didn't call the zkSync contract
+/// directly, so the paid amount is not known.
+pub(super) fn deposit_l1_surplus(
+    deposit_erc20: &ethabi::Function,
+    zksync_contract_addr: Address,
+    op: &PriorityOp,
+    tx: &Transaction,
+) -> Option<BigUintSerdeWrapper> {
+    let deposit = match &op.data {
+        ZkSyncPriorityOp::Deposit(deposit) => deposit,
+        ZkSyncPriorityOp::FullExit(_) => return None,
+    };
+    if tx.to != Some(zksync_contract_addr) {
+        return None;
+    }
+
+    let paid = if deposit.token == TokenId(0) {
+        tx.value
+    } else {
+        let input = &tx.input.0;
+        if input.len() < 4 || input[..4] != deposit_erc20.short_signature() {
+            return None;
+        }
+        // The second argument of `depositERC20` is the amount transferred from the sender.
+        deposit_erc20
+            .decode_input(&input[4..])
+            .ok()?
+            .get(1)?
+            .clone()
+            .into_uint()?
+    };
+    let mut paid_bytes = [0u8; 32];
+    paid.to_big_endian(&mut paid_bytes);
+
+    BigUint::from_bytes_be(&paid_bytes)
+        .checked_sub(&deposit.amount)
+        .map(BigUintSerdeWrapper)
 }
 
 #[async_trait::async_trait]
@@ -187,8 +274,11 @@ impl EthClient for EthHttpClient {
             }
         }
 
+        let mut ops = result?;
+        self.fill_l1_surplus(&mut ops).await?;
+
         metrics::histogram!("eth_watcher.get_priority_op_events", start.elapsed());
-        result
+        Ok(ops)
     }
 
     async fn get_new_register_nft_factory_events(
@@ -220,6 +310,20 @@ impl EthClient for EthHttpClient {
         result
     }
 
+    async fn get_contract_upgrade_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<ContractUpgradeEvent>> {
+        let start = Instant::now();
+
+        let result = self
+            .get_events(from, to, vec![self.topics.upgrade_complete])
+            .await;
+        metrics::histogram!("eth_watcher.get_contract_upgrade_events", start.elapsed());
+        result
+    }
+
     async fn block_number(&self) -> anyhow::Result<u64> {
         Ok(self.client.block_number().await?.as_u64())
     }
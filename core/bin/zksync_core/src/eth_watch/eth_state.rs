@@ -2,7 +2,9 @@
 use std::collections::HashMap;
 // External uses
 // Workspace deps
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_types::{
+    ContractUpgradeEvent, NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId,
+};
 // Local deps
 use super::received_ops::ReceivedPriorityOp;
 
@@ -39,6 +41,8 @@ pub struct ETHState {
     new_tokens: Vec<NewTokenEvent>,
     /// List of events denoting registered factories for NFT withdrawing
     register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+    /// List of the completed upgrades of the contracts.
+    contract_upgrade_events: Vec<ContractUpgradeEvent>,
 }
 
 impl ETHState {
@@ -49,6 +53,7 @@ impl ETHState {
         priority_queue: HashMap<SerialId, ReceivedPriorityOp>,
         new_tokens: Vec<NewTokenEvent>,
         register_nft_factory_events: Vec<RegisterNFTFactoryEvent>,
+        contract_upgrade_events: Vec<ContractUpgradeEvent>,
     ) -> Self {
         assert!(
             last_ethereum_block_backup <= last_ethereum_block,
@@ -67,6 +72,7 @@ impl ETHState {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            contract_upgrade_events,
         }
     }
 
@@ -90,6 +96,10 @@ impl ETHState {
         &self.new_tokens
     }
 
+    pub fn contract_upgrade_events(&self) -> &[ContractUpgradeEvent] {
+        &self.contract_upgrade_events
+    }
+
     pub fn next_priority_op_id(&self) -> SerialId {
         self.next_priority_op_id
     }
@@ -1,5 +1,5 @@
 //! Ethereum watcher polls the Ethereum node for new events
-//! such as PriorityQueue events or NewToken events.
+//! such as PriorityQueue events, NewToken events or UpgradeComplete events.
 //! New events are accepted to the zkSync network once they have the sufficient amount of confirmations.
 //!
 //! Poll interval is configured using the `ETH_POLL_INTERVAL` constant.
@@ -21,11 +21,15 @@ use itertools::Itertools;
 use tokio::{task::JoinHandle, time};
 use web3::types::BlockNumber;
 
+use zksync_api_types::v02::account::{PendingPriorityOp, PendingPriorityOpStatus};
 use zksync_config::{ContractsConfig, ETHWatchConfig};
 use zksync_crypto::params::PRIORITY_EXPIRATION;
 use zksync_eth_client::ethereum_gateway::EthereumGateway;
 use zksync_mempool::MempoolTransactionRequest;
-use zksync_types::{NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId};
+use zksync_types::{
+    Address, ContractUpgradeEvent, NewTokenEvent, PriorityOp, RegisterNFTFactoryEvent, SerialId,
+    ZkSyncPriorityOp,
+};
 
 // Local deps
 use self::{client::EthClient, eth_state::ETHState, received_ops::sift_outdated_ops};
@@ -67,6 +71,18 @@ pub enum EthWatchRequest {
         last_eth_block: Option<u64>,
         resp: oneshot::Sender<Vec<RegisterNFTFactoryEvent>>,
     },
+    GetContractUpgradeEvents {
+        last_eth_block: Option<u64>,
+        resp: oneshot::Sender<Vec<ContractUpgradeEvent>>,
+    },
+    GetPendingPriorityOps {
+        address: Address,
+        resp: oneshot::Sender<Vec<PendingPriorityOp>>,
+    },
+    GetPendingPriorityOp {
+        serial_id: SerialId,
+        resp: oneshot::Sender<Option<PendingPriorityOp>>,
+    },
 }
 
 #[derive(Debug, Error)]
@@ -191,6 +207,14 @@ impl<W: EthClient> EthWatch<W> {
         register_nft_factory_events.sort_by_key(|factory_event| factory_event.creator_address);
         register_nft_factory_events.dedup_by_key(|factory_event| factory_event.creator_address);
 
+        let mut contract_upgrade_events = self.eth_state.contract_upgrade_events().to_vec();
+        for event in updated_state.contract_upgrade_events() {
+            contract_upgrade_events.push(event.clone());
+        }
+        // Remove duplicates.
+        contract_upgrade_events.sort_by_key(|upgrade_event| upgrade_event.version_id);
+        contract_upgrade_events.dedup_by_key(|upgrade_event| upgrade_event.version_id);
+
         let new_state = ETHState::new(
             last_ethereum_block,
             previous_ethereum_block,
@@ -198,6 +222,7 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue,
             new_tokens,
             register_nft_factory_events,
+            contract_upgrade_events,
         );
         self.set_new_state(new_state);
         Ok(())
@@ -254,6 +279,14 @@ impl<W: EthClient> EthWatch<W> {
             )
             .await?;
 
+        let new_contract_upgrade_events = self
+            .client
+            .get_contract_upgrade_events(
+                BlockNumber::Number(previous_block_with_accepted_events.into()),
+                BlockNumber::Number(new_block_with_accepted_events.into()),
+            )
+            .await?;
+
         let mut new_priority_op_ids: Vec<_> = priority_queue_map.keys().cloned().collect();
         new_priority_op_ids.sort_unstable();
         vlog::debug!(
@@ -295,6 +328,7 @@ impl<W: EthClient> EthWatch<W> {
             priority_queue_map,
             new_tokens,
             new_register_nft_factory_events,
+            new_contract_upgrade_events,
         );
         Ok(state)
     }
@@ -315,6 +349,69 @@ impl<W: EthClient> EthWatch<W> {
 
         events
     }
+
+    fn get_contract_upgrade_events(
+        &self,
+        last_block_number: Option<u64>,
+    ) -> Vec<ContractUpgradeEvent> {
+        self.eth_state
+            .contract_upgrade_events()
+            .iter()
+            .filter(|event| last_block_number.map_or(true, |block| event.eth_block > block))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the deposits and full exits targeting the address that are known to the watcher,
+    /// but are not executed yet, ordered by the serial id.
+    fn get_pending_priority_ops(&self, address: Address) -> Vec<PendingPriorityOp> {
+        let is_targeting = |op: &PriorityOp| match &op.data {
+            ZkSyncPriorityOp::Deposit(deposit) => deposit.to == address,
+            ZkSyncPriorityOp::FullExit(full_exit) => full_exit.eth_address == address,
+        };
+        let unconfirmed = self
+            .eth_state
+            .unconfirmed_queue()
+            .iter()
+            .filter(|op| is_targeting(op))
+            .map(|op| self.unconfirmed_priority_op(op));
+        let confirmed = self
+            .eth_state
+            .priority_queue()
+            .values()
+            .map(AsRef::<PriorityOp>::as_ref)
+            .filter(|op| is_targeting(op))
+            .map(|op| PendingPriorityOp::new(op, PendingPriorityOpStatus::Confirmed, 0));
+
+        unconfirmed
+            .chain(confirmed)
+            .sorted_by_key(|op| op.serial_id)
+            .collect()
+    }
+
+    /// Returns the priority operation with the serial id if it's known to the watcher,
+    /// but not executed yet.
+    fn get_pending_priority_op(&self, serial_id: SerialId) -> Option<PendingPriorityOp> {
+        if let Some(op) = self.eth_state.priority_queue().get(&serial_id) {
+            return Some(PendingPriorityOp::new(
+                op.as_ref(),
+                PendingPriorityOpStatus::Confirmed,
+                0,
+            ));
+        }
+        self.eth_state
+            .unconfirmed_queue()
+            .iter()
+            .find(|op| op.serial_id == serial_id)
+            .map(|op| self.unconfirmed_priority_op(op))
+    }
+
+    fn unconfirmed_priority_op(&self, op: &PriorityOp) -> PendingPriorityOp {
+        let confirmations_left = (op.eth_block + self.number_of_confirmations_for_event)
+            .saturating_sub(self.eth_state.last_ethereum_block());
+        PendingPriorityOp::new(op, PendingPriorityOpStatus::Unconfirmed, confirmations_left)
+    }
+
     fn get_new_tokens(&self, last_block_number: Option<u64>) -> Vec<NewTokenEvent> {
         let mut new_tokens = self.eth_state.new_tokens().to_vec();
 
@@ -444,6 +541,19 @@ impl<W: EthClient> EthWatch<W> {
                     resp.send(self.get_register_factory_event(last_eth_block))
                         .ok();
                 }
+                EthWatchRequest::GetContractUpgradeEvents {
+                    last_eth_block,
+                    resp,
+                } => {
+                    resp.send(self.get_contract_upgrade_events(last_eth_block))
+                        .ok();
+                }
+                EthWatchRequest::GetPendingPriorityOps { address, resp } => {
+                    resp.send(self.get_pending_priority_ops(address)).ok();
+                }
+                EthWatchRequest::GetPendingPriorityOp { serial_id, resp } => {
+                    resp.send(self.get_pending_priority_op(serial_id)).ok();
+                }
             }
         }
     }
@@ -461,6 +571,7 @@ pub async fn start_eth_watch(
         eth_gateway,
         contract_config.contract_addr,
         contract_config.governance_addr,
+        contract_config.upgrade_gatekeeper_addr,
     );
 
     let mut eth_watch = EthWatch::new(
@@ -2,23 +2,29 @@ use std::cmp::max;
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use web3::types::{Address, BlockNumber};
+use ethabi::Token;
+use web3::types::{Address, BlockNumber, Bytes, Transaction};
+
+use zksync_api_types::v02::account::{PendingPriorityOp, PendingPriorityOpStatus};
+use zksync_contracts::zksync_contract;
 
 use zksync_types::{
-    AccountId, Deposit, FullExit, NewTokenEvent, Nonce, PriorityOp, RegisterNFTFactoryEvent,
-    SerialId, TokenId, ZkSyncPriorityOp, H256,
+    AccountId, ContractUpgradeEvent, Deposit, FullExit, NewTokenEvent, Nonce, PriorityOp,
+    RegisterNFTFactoryEvent, SerialId, TokenId, ZkSyncPriorityOp, H256,
 };
 
 use futures::channel::mpsc;
 use futures::StreamExt;
 use tokio::sync::RwLock;
 use zksync_mempool::MempoolTransactionRequest;
+use zksync_utils::BigUintSerdeWrapper;
 
-use super::is_missing_priority_op_error;
+use super::{client::deposit_l1_surplus, is_missing_priority_op_error};
 use crate::eth_watch::{client::EthClient, EthWatch};
 
 struct FakeEthClientData {
     priority_ops: HashMap<u64, Vec<PriorityOp>>,
+    contract_upgrades: Vec<ContractUpgradeEvent>,
     last_block_number: u64,
 }
 
@@ -26,6 +32,7 @@ impl FakeEthClientData {
     fn new() -> Self {
         Self {
             priority_ops: Default::default(),
+            contract_upgrades: Vec::new(),
             last_block_number: 0,
         }
     }
@@ -57,6 +64,12 @@ impl FakeEthClient {
         self.inner.write().await.add_operations(ops);
     }
 
+    async fn add_contract_upgrade(&mut self, event: ContractUpgradeEvent) {
+        let mut inner = self.inner.write().await;
+        inner.last_block_number = max(event.eth_block, inner.last_block_number);
+        inner.contract_upgrades.push(event);
+    }
+
     async fn block_to_number(&self, block: &BlockNumber) -> u64 {
         match block {
             BlockNumber::Latest => self.inner.read().await.last_block_number,
@@ -107,6 +120,24 @@ impl EthClient for FakeEthClient {
         Ok(Vec::new())
     }
 
+    async fn get_contract_upgrade_events(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+    ) -> anyhow::Result<Vec<ContractUpgradeEvent>> {
+        let from = self.block_to_number(&from).await;
+        let to = self.block_to_number(&to).await;
+        Ok(self
+            .inner
+            .read()
+            .await
+            .contract_upgrades
+            .iter()
+            .filter(|event| (from..=to).contains(&event.eth_block))
+            .cloned()
+            .collect())
+    }
+
     async fn block_number(&self) -> Result<u64, anyhow::Error> {
         Ok(self.inner.read().await.last_block_number)
     }
@@ -152,6 +183,7 @@ async fn fake_mempool(
                 channel.send(Ok(())).unwrap_or_default()
             }
             MempoolTransactionRequest::NewTxsBatch(_, _, _) => unreachable!(),
+            MempoolTransactionRequest::ReplaceTx(_, _, _) => unreachable!(),
         }
     }
 }
@@ -179,6 +211,8 @@ async fn test_operation_queues() {
             eth_hash: [2; 32].into(),
             eth_block: 3,
             eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: None,
         },
         PriorityOp {
             serial_id: 1,
@@ -192,6 +226,8 @@ async fn test_operation_queues() {
             eth_hash: [3; 32].into(),
             eth_block: 4,
             eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: None,
         },
         PriorityOp {
             serial_id: 2,
@@ -205,6 +241,8 @@ async fn test_operation_queues() {
             eth_block: 4,
             eth_hash: [4; 32].into(),
             eth_block_index: Some(2),
+            eth_block_hash: None,
+            l1_surplus: None,
         },
     ];
 
@@ -236,6 +274,54 @@ async fn test_operation_queues() {
     let (op, confirmed) = reader.get(&priority_ops[2].serial_id).unwrap();
     assert!(!confirmed);
     assert_eq!(op.tx_hash(), priority_ops[2].tx_hash());
+
+    // Operations are reported for the address they target, not the one they're sent from.
+    assert_eq!(
+        watcher.get_pending_priority_ops(Address::zero()),
+        vec![PendingPriorityOp::new(
+            &priority_ops[0],
+            PendingPriorityOpStatus::Confirmed,
+            0
+        )]
+    );
+    assert_eq!(
+        watcher.get_pending_priority_ops(to_addr),
+        vec![PendingPriorityOp::new(
+            &priority_ops[1],
+            PendingPriorityOpStatus::Unconfirmed,
+            1
+        )]
+    );
+    assert_eq!(
+        watcher.get_pending_priority_ops(from_addr),
+        vec![PendingPriorityOp::new(
+            &priority_ops[2],
+            PendingPriorityOpStatus::Unconfirmed,
+            1
+        )]
+    );
+    assert!(watcher
+        .get_pending_priority_ops(Address::repeat_byte(0x42))
+        .is_empty());
+
+    // The same operations are found by their serial ids.
+    assert_eq!(
+        watcher.get_pending_priority_op(priority_ops[0].serial_id),
+        Some(PendingPriorityOp::new(
+            &priority_ops[0],
+            PendingPriorityOpStatus::Confirmed,
+            0
+        ))
+    );
+    assert_eq!(
+        watcher.get_pending_priority_op(priority_ops[2].serial_id),
+        Some(PendingPriorityOp::new(
+            &priority_ops[2],
+            PendingPriorityOpStatus::Unconfirmed,
+            1
+        ))
+    );
+    assert_eq!(watcher.get_pending_priority_op(42), None);
 }
 
 /// This test simulates the situation when eth watch module did not poll Ethereum node for some time
@@ -264,6 +350,8 @@ async fn test_operation_queues_time_lag() {
                 eth_hash: [2; 32].into(),
                 eth_block: 1, // <- First operation goes to the first block.
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 1,
@@ -277,6 +365,8 @@ async fn test_operation_queues_time_lag() {
                 eth_hash: [3; 32].into(),
                 eth_block: 100, // <-- Note 100th block, it will set the network block to 100.
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 2,
@@ -290,6 +380,8 @@ async fn test_operation_queues_time_lag() {
                 eth_hash: [3; 32].into(),
                 eth_block: 110, // <-- This operation will get to the unconfirmed queue.
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ])
         .await;
@@ -337,6 +429,8 @@ async fn test_restore_and_poll() {
                 eth_hash: [2; 32].into(),
                 eth_block: 4,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 1,
@@ -350,6 +444,8 @@ async fn test_restore_and_poll() {
                 eth_hash: [3; 32].into(),
                 eth_block: 3,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ])
         .await;
@@ -370,6 +466,8 @@ async fn test_restore_and_poll() {
                 eth_hash: [2; 32].into(),
                 eth_block: 5,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 4,
@@ -383,6 +481,8 @@ async fn test_restore_and_poll() {
                 eth_hash: [3; 32].into(),
                 eth_block: 5,
                 eth_block_index: Some(2),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ])
         .await;
@@ -422,6 +522,8 @@ async fn test_restore_and_poll_time_lag() {
                 eth_hash: [2; 32].into(),
                 eth_block: 1,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 1,
@@ -435,6 +537,8 @@ async fn test_restore_and_poll_time_lag() {
                 eth_hash: [3; 32].into(),
                 eth_block: 100,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ])
         .await;
@@ -470,6 +574,8 @@ async fn test_serial_id_gaps() {
                 eth_hash: [2; 32].into(),
                 eth_block: 1,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 1,
@@ -478,6 +584,8 @@ async fn test_serial_id_gaps() {
                 eth_hash: [3; 32].into(),
                 eth_block: 1,
                 eth_block_index: Some(2),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ])
         .await;
@@ -506,6 +614,8 @@ async fn test_serial_id_gaps() {
                 eth_hash: [2; 32].into(),
                 eth_block: 2,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 4, // Then next id is expected to be 3.
@@ -514,6 +624,8 @@ async fn test_serial_id_gaps() {
                 eth_hash: [3; 32].into(),
                 eth_block: 2,
                 eth_block_index: Some(3),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ])
         .await;
@@ -538,6 +650,8 @@ async fn test_serial_id_gaps() {
             eth_hash: [2; 32].into(),
             eth_block: 2,
             eth_block_index: Some(2),
+            eth_block_hash: None,
+            l1_surplus: None,
         }])
         .await;
     watcher.poll_eth_node().await.unwrap();
@@ -546,3 +660,125 @@ async fn test_serial_id_gaps() {
     assert_eq!(watcher.eth_state.last_ethereum_block_backup(), 0);
     assert_eq!(watcher.eth_state.last_ethereum_block(), 3);
 }
+
+/// Checks that the contract upgrade is reported by the watcher only after it has enough confirmations.
+#[tokio::test]
+async fn test_contract_upgrade_events() {
+    let mut client = FakeEthClient::new();
+    let (sender, receiver) = mpsc::channel(10);
+    tokio::spawn(fake_mempool(receiver, Default::default()));
+
+    let upgrade = ContractUpgradeEvent {
+        version_id: 1,
+        new_targets: vec![Address::zero(), Address::zero(), Address::repeat_byte(0x42)],
+        eth_block: 5,
+    };
+    client.add_contract_upgrade(upgrade.clone()).await;
+
+    let mut watcher = create_watcher(client.clone(), sender);
+    watcher.poll_eth_node().await.unwrap();
+    // The upgrade block is not confirmed yet.
+    assert!(watcher.get_contract_upgrade_events(None).is_empty());
+
+    client.set_last_block_number(6).await;
+    watcher.poll_eth_node().await.unwrap();
+    assert_eq!(
+        watcher.get_contract_upgrade_events(None),
+        vec![upgrade.clone()]
+    );
+    assert_eq!(
+        watcher.get_contract_upgrade_events(Some(4)),
+        vec![upgrade.clone()]
+    );
+    // Upgrades already seen by the handler are not reported again.
+    assert!(watcher
+        .get_contract_upgrade_events(Some(upgrade.eth_block))
+        .is_empty());
+}
+
+fn deposit_op(token: TokenId, amount: u32) -> PriorityOp {
+    PriorityOp {
+        serial_id: 0,
+        data: ZkSyncPriorityOp::Deposit(Deposit {
+            from: Address::repeat_byte(0x11),
+            token,
+            amount: amount.into(),
+            to: Address::repeat_byte(0x22),
+        }),
+        deadline_block: 0,
+        eth_hash: H256::repeat_byte(0x33),
+        eth_block: 1,
+        eth_block_index: Some(0),
+        eth_block_hash: None,
+        l1_surplus: None,
+    }
+}
+
+/// Checks that the part of the paid amount not credited by the deposit is derived from its L1 transaction.
+#[test]
+fn test_deposit_l1_surplus() {
+    let contract = zksync_contract();
+    let deposit_erc20 = contract.function("depositERC20").unwrap();
+    let zksync_contract_addr = Address::repeat_byte(0x55);
+    let erc20_tx = |paid: u32| Transaction {
+        to: Some(zksync_contract_addr),
+        input: Bytes(
+            deposit_erc20
+                .encode_input(&[
+                    Token::Address(Address::repeat_byte(0x44)),
+                    Token::Uint(paid.into()),
+                    Token::Address(Address::repeat_byte(0x22)),
+                ])
+                .unwrap(),
+        ),
+        ..Default::default()
+    };
+
+    // The token charges a fee on transfers, so only a part of the paid amount is credited.
+    let op = deposit_op(TokenId(1), 90);
+    assert_eq!(
+        deposit_l1_surplus(deposit_erc20, zksync_contract_addr, &op, &erc20_tx(100)),
+        Some(BigUintSerdeWrapper(10u32.into()))
+    );
+    // The whole amount is credited.
+    assert_eq!(
+        deposit_l1_surplus(deposit_erc20, zksync_contract_addr, &op, &erc20_tx(90)),
+        Some(BigUintSerdeWrapper(0u32.into()))
+    );
+    // The paid amount is unknown if the deposit was made through another contract.
+    let proxied_tx = Transaction {
+        to: Some(Address::repeat_byte(0x66)),
+        ..erc20_tx(100)
+    };
+    assert_eq!(
+        deposit_l1_surplus(deposit_erc20, zksync_contract_addr, &op, &proxied_tx),
+        None
+    );
+
+    // ETH deposits are credited with the whole transferred value.
+    let op = deposit_op(TokenId(0), 100);
+    let eth_tx = Transaction {
+        to: Some(zksync_contract_addr),
+        value: 100u32.into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        deposit_l1_surplus(deposit_erc20, zksync_contract_addr, &op, &eth_tx),
+        Some(BigUintSerdeWrapper(0u32.into()))
+    );
+
+    // Full exits don't transfer anything on L1.
+    let full_exit = PriorityOp {
+        data: ZkSyncPriorityOp::FullExit(FullExit {
+            account_id: AccountId(1),
+            eth_address: Address::repeat_byte(0x11),
+            token: TokenId(0),
+            is_legacy: false,
+        }),
+        ..op
+    };
+    assert_eq!(
+        deposit_l1_surplus(deposit_erc20, zksync_contract_addr, &full_exit, &eth_tx),
+        None
+    );
+}
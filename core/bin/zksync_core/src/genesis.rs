@@ -1,52 +1,131 @@
 use std::time::Instant;
 
 // External uses
+use anyhow::Context;
 // Workspace uses
 use zksync_crypto::{
+    convert::FeConvert,
     ff,
     params::{MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ADDRESS, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID},
+    Fr,
 };
 use zksync_state::state::ZkSyncState;
-use zksync_storage::ConnectionPool;
-use zksync_types::{Account, AccountId, AccountUpdate, Address, BlockNumber, Token, TokenKind};
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    tokens::TokenInfo, Account, AccountId, AccountMap, AccountUpdate, Address, BlockNumber, Token,
+    TokenId, TokenKind,
+};
 // Local uses
 
-pub async fn create_genesis_block(pool: ConnectionPool, fee_account_address: &Address) {
-    let start = Instant::now();
-    let mut storage = pool
-        .access_storage()
-        .await
-        .expect("db connection failed for statekeeper");
-    let mut transaction = storage
-        .start_transaction()
-        .await
-        .expect("unable to create db transaction in statekeeper");
+/// Parameters of the genesis state requested by the server configuration.
+#[derive(Debug, Clone)]
+pub struct GenesisParams {
+    pub fee_account_address: Address,
+    /// Initial ERC20 tokens, their ids are assigned sequentially starting from 1.
+    pub tokens: Vec<TokenInfo>,
+}
 
-    let (last_committed, mut accounts) = transaction
-        .chain()
-        .state_schema()
-        .load_committed_state(None)
-        .await
-        .expect("db failed");
+/// Result of the [`bootstrap`] call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BootstrapOutcome {
+    /// The genesis state has been created from scratch.
+    Created { genesis_root: Fr },
+    /// The database already contains the requested genesis, nothing was changed.
+    AlreadyBootstrapped { genesis_root: Fr },
+}
 
-    assert!(
-        *last_committed == 0 && accounts.is_empty(),
-        "db should be empty"
-    );
+impl BootstrapOutcome {
+    pub fn genesis_root(&self) -> Fr {
+        match self {
+            Self::Created { genesis_root } | Self::AlreadyBootstrapped { genesis_root } => {
+                *genesis_root
+            }
+        }
+    }
+}
 
-    vlog::info!("Adding special token");
-    transaction
-        .tokens_schema()
-        .store_token(Token::new(
-            NFT_TOKEN_ID,
-            *NFT_STORAGE_ACCOUNT_ADDRESS,
-            "SPECIAL",
-            18,
-            TokenKind::NFT,
-        ))
-        .await
-        .expect("failed to store special token");
-    vlog::info!("Special token added");
+/// Summary of the genesis state used to compare the existing genesis with the requested one.
+#[derive(Debug, Clone)]
+struct GenesisSummary {
+    genesis_root: Fr,
+    fee_account_address: Address,
+    tokens: Vec<TokenInfo>,
+}
+
+impl GenesisSummary {
+    fn requested(params: &GenesisParams) -> Self {
+        Self {
+            genesis_root: genesis_root(&params.fee_account_address),
+            fee_account_address: params.fee_account_address,
+            tokens: params.tokens.clone(),
+        }
+    }
+
+    /// Returns a list of diff-style lines describing how `self` differs from `requested`.
+    /// The list is empty if both summaries describe the same genesis.
+    fn diff(&self, requested: &Self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if self.genesis_root != requested.genesis_root {
+            lines.push(format!(
+                "- genesis root: 0x{}",
+                ff::to_hex(&self.genesis_root)
+            ));
+            lines.push(format!(
+                "+ genesis root: 0x{}",
+                ff::to_hex(&requested.genesis_root)
+            ));
+        }
+        if self.fee_account_address != requested.fee_account_address {
+            lines.push(format!(
+                "- fee account address: {:?}",
+                self.fee_account_address
+            ));
+            lines.push(format!(
+                "+ fee account address: {:?}",
+                requested.fee_account_address
+            ));
+        }
+
+        let tokens_count = self.tokens.len().max(requested.tokens.len());
+        for idx in 0..tokens_count {
+            let existing = self.tokens.get(idx);
+            let requested = requested.tokens.get(idx);
+            let same = match (existing, requested) {
+                (Some(existing), Some(requested)) => {
+                    existing.address == requested.address
+                        && existing.symbol == requested.symbol
+                        && existing.decimals == requested.decimals
+                }
+                _ => false,
+            };
+            if same {
+                continue;
+            }
+            let token_id = idx + 1;
+            if let Some(token) = existing {
+                lines.push(format!("- token {}: {}", token_id, describe_token(token)));
+            }
+            if let Some(token) = requested {
+                lines.push(format!("+ token {}: {}", token_id, describe_token(token)));
+            }
+        }
+
+        lines
+    }
+}
+
+fn describe_token(token: &TokenInfo) -> String {
+    format!(
+        "{} at {:?} ({} decimals)",
+        token.symbol, token.address, token.decimals
+    )
+}
+
+/// Builds the genesis accounts and the account updates that have to be stored for block 0.
+fn genesis_accounts(
+    fee_account_address: &Address,
+) -> (AccountMap, Vec<(AccountId, AccountUpdate)>) {
+    let mut accounts = AccountMap::default();
 
     let fee_account = Account::default_with_address(fee_account_address);
     let db_create_fee_account = AccountUpdate::Create {
@@ -69,48 +148,376 @@ pub async fn create_genesis_block(pool: ConnectionPool, fee_account_address: &Ad
     };
     accounts.insert(NFT_STORAGE_ACCOUNT_ID, special_account);
 
-    transaction
+    let updates = vec![
+        (AccountId(0), db_create_fee_account),
+        db_create_special_account[0].clone(),
+        (NFT_STORAGE_ACCOUNT_ID, db_set_special_account_balance),
+    ];
+    (accounts, updates)
+}
+
+/// Computes the root hash of the genesis state without touching the database.
+pub fn genesis_root(fee_account_address: &Address) -> Fr {
+    let (accounts, _) = genesis_accounts(fee_account_address);
+    ZkSyncState::from_acc_map(accounts).root_hash()
+}
+
+/// Stores the special NFT token, genesis accounts and block 0.
+/// Expects the database to be empty, the caller is responsible for wrapping the call into a transaction.
+async fn store_genesis_state(
+    storage: &mut StorageProcessor<'_>,
+    fee_account_address: &Address,
+) -> anyhow::Result<Fr> {
+    let (last_committed, accounts) = storage
         .chain()
         .state_schema()
-        .commit_state_update(
-            BlockNumber(0),
-            &[
-                (AccountId(0), db_create_fee_account),
-                db_create_special_account[0].clone(),
-                (NFT_STORAGE_ACCOUNT_ID, db_set_special_account_balance),
-            ],
-            0,
-        )
+        .load_committed_state(None)
+        .await?;
+    anyhow::ensure!(
+        *last_committed == 0 && accounts.is_empty(),
+        "db should be empty"
+    );
+
+    vlog::info!("Adding special token");
+    storage
+        .tokens_schema()
+        .store_token(Token::new(
+            NFT_TOKEN_ID,
+            *NFT_STORAGE_ACCOUNT_ADDRESS,
+            "SPECIAL",
+            18,
+            TokenKind::NFT,
+        ))
         .await
-        .expect("db fail");
-    transaction
+        .context("failed to store special token")?;
+    vlog::info!("Special token added");
+
+    let (accounts, updates) = genesis_accounts(fee_account_address);
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(BlockNumber(0), &updates, 0)
+        .await?;
+    storage
         .chain()
         .state_schema()
         .apply_state_update(BlockNumber(0))
-        .await
-        .expect("db fail");
+        .await?;
 
-    let state = ZkSyncState::from_acc_map(accounts);
-    let root_hash = state.root_hash();
-    transaction
+    let root_hash = ZkSyncState::from_acc_map(accounts).root_hash();
+    storage
         .chain()
         .block_schema()
         .save_genesis_block(root_hash)
+        .await?;
+
+    Ok(root_hash)
+}
+
+/// Stores the initial ERC20 tokens with ids starting from 1.
+async fn store_genesis_tokens(
+    storage: &mut StorageProcessor<'_>,
+    tokens: &[TokenInfo],
+) -> anyhow::Result<()> {
+    for (id, token) in (1..).zip(tokens) {
+        vlog::info!(
+            "Adding token: {}, id:{}, address: {}, decimals: {}",
+            token.symbol,
+            id,
+            token.address,
+            token.decimals
+        );
+        storage
+            .tokens_schema()
+            .store_token(Token::new(
+                TokenId(id),
+                token.address,
+                &token.symbol,
+                token.decimals,
+                TokenKind::ERC20,
+            ))
+            .await
+            .context("failed to store token")?;
+    }
+    Ok(())
+}
+
+/// Loads the summary of the genesis created without the bootstrap marker (e.g. by `--genesis`).
+/// Only tokens with ids in the range of the requested genesis tokens are taken into account,
+/// since tokens listed after the genesis are expected to be in the database too.
+async fn load_existing_genesis(
+    storage: &mut StorageProcessor<'_>,
+    requested_tokens_count: usize,
+) -> anyhow::Result<GenesisSummary> {
+    let genesis_block = storage
+        .chain()
+        .block_schema()
+        .get_storage_block(BlockNumber(0))
+        .await?
+        .context("database is not empty, but contains no genesis block")?;
+    let genesis_root = Fr::from_bytes(&genesis_block.root_hash)?;
+    let fee_account_address = storage
+        .chain()
+        .account_schema()
+        .account_address_by_id(AccountId(0))
+        .await?
+        .context("database contains no fee account")?;
+
+    let mut tokens: Vec<_> = storage
+        .tokens_schema()
+        .load_tokens()
+        .await?
+        .into_iter()
+        .filter(|(id, token)| {
+            token.kind == TokenKind::ERC20 && **id > 0 && **id as usize <= requested_tokens_count
+        })
+        .collect();
+    tokens.sort_by_key(|(id, _)| *id);
+    let tokens = tokens
+        .into_iter()
+        .map(|(_, token)| TokenInfo {
+            address: token.address,
+            decimals: token.decimals,
+            symbol: token.symbol,
+        })
+        .collect();
+
+    Ok(GenesisSummary {
+        genesis_root,
+        fee_account_address,
+        tokens,
+    })
+}
+
+fn ensure_same_genesis(
+    existing: &GenesisSummary,
+    requested: &GenesisSummary,
+) -> anyhow::Result<()> {
+    let diff = existing.diff(requested);
+    if !diff.is_empty() {
+        anyhow::bail!(
+            "Existing genesis doesn't match the requested configuration (- existing, + requested):\n{}",
+            diff.join("\n")
+        );
+    }
+    Ok(())
+}
+
+async fn store_marker(
+    storage: &mut StorageProcessor<'_>,
+    summary: &GenesisSummary,
+) -> anyhow::Result<()> {
+    storage
+        .misc_schema()
+        .store_bootstrap_marker(
+            &summary.genesis_root.to_bytes(),
+            summary.fee_account_address,
+            serde_json::to_value(&summary.tokens)?,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Creates the genesis state (fee account, NFT storage account, initial tokens and block 0)
+/// inside one database transaction and records the bootstrap marker.
+///
+/// Running the bootstrap again with the same parameters is a no-op. If the database already
+/// contains a genesis that differs from the requested one, an error describing the difference
+/// is returned and nothing is changed.
+pub async fn bootstrap(
+    storage: &mut StorageProcessor<'_>,
+    params: &GenesisParams,
+) -> anyhow::Result<BootstrapOutcome> {
+    let start = Instant::now();
+    let mut transaction = storage.start_transaction().await?;
+    let requested = GenesisSummary::requested(params);
+
+    if let Some(marker) = transaction.misc_schema().load_bootstrap_marker().await? {
+        let existing = GenesisSummary {
+            genesis_root: Fr::from_bytes(&marker.genesis_root)?,
+            fee_account_address: Address::from_slice(&marker.fee_account_address),
+            tokens: serde_json::from_value(marker.tokens)?,
+        };
+        ensure_same_genesis(&existing, &requested)?;
+        transaction.commit().await?;
+        vlog::info!("Database is already bootstrapped at {}", marker.created_at);
+        return Ok(BootstrapOutcome::AlreadyBootstrapped {
+            genesis_root: existing.genesis_root,
+        });
+    }
+
+    let (last_committed, accounts) = transaction
+        .chain()
+        .state_schema()
+        .load_committed_state(None)
+        .await?;
+    let outcome = if *last_committed == 0 && accounts.is_empty() {
+        vlog::info!("Generating genesis block.");
+        let genesis_root =
+            store_genesis_state(&mut transaction, &params.fee_account_address).await?;
+        vlog::info!("Adding initial tokens to db");
+        store_genesis_tokens(&mut transaction, &params.tokens).await?;
+        BootstrapOutcome::Created { genesis_root }
+    } else {
+        // The genesis was created without the marker, adopt it if it matches the configuration.
+        let existing = load_existing_genesis(&mut transaction, params.tokens.len()).await?;
+        ensure_same_genesis(&existing, &requested)?;
+        vlog::info!("Found the existing genesis matching the configuration");
+        BootstrapOutcome::AlreadyBootstrapped {
+            genesis_root: existing.genesis_root,
+        }
+    };
+    store_marker(&mut transaction, &requested).await?;
+    transaction.commit().await?;
+
+    metrics::histogram!("state_keeper.bootstrap", start.elapsed());
+    Ok(outcome)
+}
+
+pub async fn create_genesis_block(pool: ConnectionPool, fee_account_address: &Address) {
+    let start = Instant::now();
+    let mut storage = pool
+        .access_storage()
+        .await
+        .expect("db connection failed for statekeeper");
+    let mut transaction = storage
+        .start_transaction()
+        .await
+        .expect("unable to create db transaction in statekeeper");
+
+    let root_hash = store_genesis_state(&mut transaction, fee_account_address)
         .await
-        .expect("db fail");
+        .expect("failed to store genesis state");
 
     transaction
         .commit()
         .await
         .expect("Unable to commit transaction in statekeeper");
-    vlog::info!("Genesis block created, state: {}", state.root_hash());
+    vlog::info!("Genesis block created, state: {}", root_hash);
+
+    print_genesis_root(root_hash);
+
+    metrics::histogram!("state_keeper.create_genesis_block", start.elapsed());
+}
 
+pub fn print_genesis_root(root_hash: Fr) {
     // Below we are intentionally using `println`, because during genesis we parse the genesis root from
     // the server output in order to save it into the config file.
     // See `server.genesis()` in the `zk` tool for details.
     // TODO: Find a better and a more intuitive approach (ZKS-816).
     let genesis_root = format!("CONTRACTS_GENESIS_ROOT=0x{}", ff::to_hex(&root_hash));
     println!("{}", &genesis_root);
+}
 
-    metrics::histogram!("state_keeper.create_genesis_block", start.elapsed());
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db_test_macro::test as db_test;
+
+    fn genesis_params(fee_account_address: Address) -> GenesisParams {
+        GenesisParams {
+            fee_account_address,
+            tokens: vec![
+                TokenInfo {
+                    address: Address::repeat_byte(0x01),
+                    decimals: 18,
+                    symbol: "DAI".to_string(),
+                },
+                TokenInfo {
+                    address: Address::repeat_byte(0x02),
+                    decimals: 6,
+                    symbol: "USDC".to_string(),
+                },
+            ],
+        }
+    }
+
+    /// Checks that running the bootstrap twice creates the genesis only once
+    /// and that the stored genesis root matches the computed one.
+    #[db_test]
+    async fn bootstrap_is_idempotent(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+        let params = genesis_params(Address::repeat_byte(0x42));
+        let expected_root = genesis_root(&params.fee_account_address);
+
+        let outcome = bootstrap(&mut storage, &params).await?;
+        assert_eq!(
+            outcome,
+            BootstrapOutcome::Created {
+                genesis_root: expected_root
+            }
+        );
+
+        let genesis_block = storage
+            .chain()
+            .block_schema()
+            .get_storage_block(BlockNumber(0))
+            .await?
+            .expect("genesis block must be stored");
+        assert_eq!(Fr::from_bytes(&genesis_block.root_hash)?, expected_root);
+        let tokens = storage.tokens_schema().load_tokens().await?;
+        assert_eq!(tokens[&TokenId(1)].symbol, "DAI");
+        assert_eq!(tokens[&TokenId(2)].symbol, "USDC");
+
+        let outcome = bootstrap(&mut storage, &params).await?;
+        assert_eq!(
+            outcome,
+            BootstrapOutcome::AlreadyBootstrapped {
+                genesis_root: expected_root
+            }
+        );
+        let (last_committed, accounts) = storage
+            .chain()
+            .state_schema()
+            .load_committed_state(None)
+            .await?;
+        assert_eq!(last_committed, BlockNumber(0));
+        assert_eq!(accounts.len(), 2);
+
+        Ok(())
+    }
+
+    /// Checks that the bootstrap aborts if the requested configuration doesn't match the existing genesis.
+    #[db_test]
+    async fn bootstrap_mismatch_is_rejected(
+        mut storage: StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let params = genesis_params(Address::repeat_byte(0x42));
+        bootstrap(&mut storage, &params).await?;
+
+        let mut other_params = genesis_params(Address::repeat_byte(0x43));
+        other_params.tokens[1].decimals = 18;
+        let err = bootstrap(&mut storage, &other_params)
+            .await
+            .expect_err("mismatched genesis must be rejected")
+            .to_string();
+        assert!(err.contains("- fee account address"));
+        assert!(err.contains("+ fee account address"));
+        assert!(err.contains("+ token 2: USDC"));
+        assert!(!err.contains("token 1"));
+
+        Ok(())
+    }
+
+    /// Checks that the genesis created without the marker is adopted by the bootstrap.
+    #[db_test]
+    async fn bootstrap_adopts_existing_genesis(
+        mut storage: StorageProcessor<'_>,
+    ) -> anyhow::Result<()> {
+        let params = genesis_params(Address::repeat_byte(0x42));
+        let genesis_root = store_genesis_state(&mut storage, &params.fee_account_address).await?;
+        store_genesis_tokens(&mut storage, &params.tokens).await?;
+
+        let outcome = bootstrap(&mut storage, &params).await?;
+        assert_eq!(
+            outcome,
+            BootstrapOutcome::AlreadyBootstrapped { genesis_root }
+        );
+        assert!(storage
+            .misc_schema()
+            .load_bootstrap_marker()
+            .await?
+            .is_some());
+
+        Ok(())
+    }
 }
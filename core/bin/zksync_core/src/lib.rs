@@ -1,9 +1,13 @@
+use crate::contract_upgrade_handler::run_contract_upgrade_handler;
 use crate::register_factory_handler::run_register_factory_handler;
 use crate::state_keeper::ZkSyncStateInitParams;
 use crate::{
     committer::run_committer,
     eth_watch::start_eth_watch,
-    state_keeper::{start_root_hash_calculator, start_state_keeper, ZkSyncStateKeeper},
+    state_keeper::{
+        block_time::BlockTimeController, check_block_proving_keys, start_root_hash_calculator,
+        start_state_keeper, validate_block_chunk_sizes, OffPeakPolicy, ZkSyncStateKeeper,
+    },
     token_handler::run_token_handler,
 };
 use futures::{channel::mpsc, future};
@@ -12,16 +16,21 @@ use zksync_config::{ChainConfig, ZkSyncConfig};
 use zksync_eth_client::EthereumGateway;
 use zksync_mempool::{run_mempool_block_handler, run_mempool_tx_handler};
 use zksync_storage::ConnectionPool;
-use zksync_types::{tokens::get_genesis_token_list, Token, TokenId, TokenKind};
+use zksync_types::{tokens::get_genesis_token_list, BlockNumber, Token, TokenId, TokenKind};
+use zksync_utils::shutdown::ShutdownSignal;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 32_768;
 
+pub mod block_replay;
 pub mod committer;
+pub mod contract_upgrade_handler;
 pub mod eth_watch;
 pub mod register_factory_handler;
 pub mod rejected_tx_cleaner;
+pub mod root_hash_checker;
 pub mod state_keeper;
 pub mod token_handler;
+pub mod token_volume_backfill;
 pub mod tx_event_emitter;
 
 mod genesis;
@@ -79,6 +88,40 @@ pub async fn genesis_init(config: &ChainConfig) {
     }
 }
 
+/// Creates the genesis state and the initial tokens inside one database transaction.
+/// Does nothing if the database is already bootstrapped with the same configuration
+/// and fails if the existing genesis differs from the configured one.
+pub async fn bootstrap_init(config: &ChainConfig) -> anyhow::Result<()> {
+    let pool = ConnectionPool::new(Some(1));
+    let params = genesis::GenesisParams {
+        fee_account_address: config.state_keeper.fee_account_addr,
+        tokens: get_genesis_token_list(&config.eth.network.to_string())?,
+    };
+
+    let mut storage = pool.access_storage().await?;
+    let outcome = genesis::bootstrap(&mut storage, &params).await?;
+    match outcome {
+        genesis::BootstrapOutcome::Created { .. } => vlog::info!("Database is bootstrapped"),
+        genesis::BootstrapOutcome::AlreadyBootstrapped { .. } => {
+            vlog::info!("Database is already bootstrapped, nothing to do")
+        }
+    }
+    genesis::print_genesis_root(outcome.genesis_root());
+
+    Ok(())
+}
+
+/// Re-executes the stored blocks of the range and compares the results with the stored ones.
+/// Prints the difference and fails at the first diverged block.
+pub async fn replay_init(from: BlockNumber, to: BlockNumber) -> anyhow::Result<()> {
+    let pool = ConnectionPool::new(Some(1));
+    let mut storage = pool.access_storage().await?;
+
+    let report = block_replay::replay_report(&mut storage, from, to).await?;
+    println!("{}", report);
+    Ok(())
+}
+
 /// Starts the core application, which has the following sub-modules:
 ///
 /// - Ethereum Watcher, module to monitor on-chain operations.
@@ -86,16 +129,34 @@ pub async fn genesis_init(config: &ChainConfig) {
 /// - mempool, module to organize incoming transactions.
 /// - block proposer, module to create block proposals for state keeper.
 /// - committer, module to store pending and completed blocks into the database.
-/// - private Core API server.
+/// - private Core API server, stopped gracefully by the `shutdown` signal.
 pub async fn run_core(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     config: &ZkSyncConfig,
     eth_gateway: EthereumGateway,
+    shutdown: ShutdownSignal,
 ) -> anyhow::Result<Vec<JoinHandle<()>>> {
+    // Blocks of sizes that can't be proven would stall the whole pipeline, so we'd better fail fast.
+    validate_block_chunk_sizes(
+        &config.chain.state_keeper.block_chunk_sizes,
+        Some(&config.chain.circuit),
+    )?;
+    check_block_proving_keys(
+        &config.chain.state_keeper.block_chunk_sizes,
+        &config.chain.circuit,
+    )?;
+    let off_peak_policy = OffPeakPolicy::from_config(&config.chain.state_keeper)?;
+    if let Some(policy) = &off_peak_policy {
+        validate_block_chunk_sizes(&policy.block_chunk_sizes, Some(&config.chain.circuit))?;
+        check_block_proving_keys(&policy.block_chunk_sizes, &config.chain.circuit)?;
+    }
+
     let (proposed_blocks_sender, proposed_blocks_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
     let (eth_watch_req_sender, eth_watch_req_receiver) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+    let (state_keeper_req_sender, state_keeper_req_receiver) =
+        mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
     let (mempool_tx_request_sender, mempool_tx_request_receiver) =
         mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
     let (mempool_block_request_sender, mempool_block_request_receiver) =
@@ -110,12 +171,24 @@ pub async fn run_core(
         config.chain.state_keeper.block_chunk_sizes.clone(),
     );
 
+    // Seal timeout of the blocks is shared with the private API, so the block time target can be changed at runtime.
+    let block_time_controller =
+        BlockTimeController::from_config(&config.chain.state_keeper).shared();
+
     // Run health check api for core
     let private_api_task = private_api::start_private_core_api(
         connection_pool.clone(),
         read_only_connection_pool,
         eth_gateway.clone(),
         config.api.private.clone(),
+        config.contracts.contract_target_addr,
+        config.contracts.governance_addr,
+        block_time_controller.clone(),
+        eth_watch_req_sender.clone(),
+        state_keeper_req_sender,
+        mempool_tx_request_sender.clone(),
+        config.api.common.shutdown_grace_period(),
+        shutdown,
     );
 
     // Start Ethereum Watcher.
@@ -153,10 +226,15 @@ pub async fn run_core(
 
     // Execute reverted blocks before start
     state_keeper.execute_reverted_blocks().await;
+    state_keeper.set_block_time_controller(block_time_controller);
+    if let Some(policy) = off_peak_policy {
+        state_keeper.set_off_peak_policy(policy);
+    }
 
     let state_keeper_task = start_state_keeper(
         state_keeper,
         config.chain.state_keeper.miniblock_iteration_interval(),
+        state_keeper_req_receiver,
     );
     let root_hash_calculator_task = start_root_hash_calculator(root_hash_calculator);
 
@@ -189,6 +267,24 @@ pub async fn run_core(
         config.token_handler.clone(),
     );
 
+    // Start contract upgrade handler.
+    let contract_upgrade_task = run_contract_upgrade_handler(
+        connection_pool.clone(),
+        eth_watch_req_sender.clone(),
+        config.eth_watch.clone(),
+    );
+
+    // Start the backfill of the token volume stats.
+    let token_volume_backfill_task =
+        token_volume_backfill::run_token_volume_backfill(connection_pool.clone());
+
+    // Start the cross-check of the committed root hashes against L1.
+    let root_hash_checker_task = root_hash_checker::run_root_hash_checker(
+        connection_pool.clone(),
+        eth_gateway.clone(),
+        &config.contracts,
+    );
+
     let tx_event_emitter_task = tx_event_emitter::run_tx_event_emitter_task(
         connection_pool.clone(),
         processed_tx_events_receiver,
@@ -201,6 +297,9 @@ pub async fn run_core(
         committer_task,
         token_handler_task,
         register_factory_task,
+        contract_upgrade_task,
+        token_volume_backfill_task,
+        root_hash_checker_task,
         tx_event_emitter_task,
         mempool_block_handler_task,
         mempool_tx_handler_task,
@@ -10,18 +10,47 @@
 use std::thread;
 use std::time::{Duration, Instant};
 
-use actix_web::{web, App, HttpResponse, HttpServer};
-use futures::{channel::mpsc, StreamExt};
+use actix_web::{dev::Server, web, App, HttpResponse, HttpServer};
+use chrono::{NaiveDate, Utc};
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use serde::Deserialize;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
-use zksync_api_types::CoreStatus;
+use zksync_api_types::{
+    CoreStatus, IssuedApiKey, MaintenanceMode, MempoolSubmission, NewApiKey, NewSubsidyCampaign,
+    RuntimeConfig, SubsidyCampaignTopUp, TokenListingStatus,
+};
 
 use zksync_config::configs::api::PrivateApiConfig;
 use zksync_eth_client::EthereumGateway;
+use zksync_mempool::MempoolTransactionRequest;
 use zksync_storage::ConnectionPool;
-use zksync_utils::panic_notify::ThreadPanicNotify;
+use zksync_types::{
+    api_keys::{api_key_hash, generate_api_key},
+    tx::{error::TxAddError, TxHash},
+    Address, SerialId, TokenId,
+};
+use zksync_utils::{
+    panic_notify::{spawn_panic_handler, ThreadPanicNotify},
+    shutdown::ShutdownSignal,
+};
+
+use crate::{
+    eth_watch::EthWatchRequest,
+    state_keeper::{block_time::SharedBlockTimeController, StateKeeperRequest},
+    token_handler::{add_token, AddTokenError},
+};
 
 const STATUS_INVALIDATION_PERIOD: Duration = Duration::from_secs(60);
+/// Number of days the API key usage is returned for if the range is not specified.
+const DEFAULT_API_KEY_USAGE_DAYS: i64 = 30;
+/// Number of the latest fee exemptions returned if the limit is not specified.
+const DEFAULT_FEE_EXEMPTIONS_LIMIT: u32 = 100;
+/// Number of days the priority operations surplus is returned for if the range is not specified.
+const DEFAULT_PRIORITY_OP_SURPLUS_DAYS: i64 = 30;
 
 #[derive(Debug)]
 struct AppState {
@@ -29,15 +58,28 @@ struct AppState {
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
     status_cache: RwLock<Option<(CoreStatus, Instant)>>,
+    /// Target of the main contract from the server config.
+    contract_target_addr: Address,
+    governance_addr: Address,
+    block_time_controller: SharedBlockTimeController,
+    eth_watch_req_sender: mpsc::Sender<EthWatchRequest>,
+    state_keeper_req_sender: mpsc::Sender<StateKeeperRequest>,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
 }
 
 /// Health check.
-/// The core actor is expected have connection to web3 and both main/replica databases
+/// The core actor is expected have connection to web3 and both main/replica databases.
+/// Mismatch between the configured and on-chain main contract targets marks the core as degraded.
 #[actix_web::get("/status")]
 async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
-    if let Some((status, data)) = data.status_cache.read().await.as_ref() {
-        if data.elapsed() < STATUS_INVALIDATION_PERIOD {
-            return Ok(HttpResponse::Ok().json(status.clone()));
+    // Block time controller state changes with every block, so it's never cached.
+    let block_time = Some(data.block_time_controller.read().await.status());
+    if let Some((status, cached_at)) = data.status_cache.read().await.as_ref() {
+        if cached_at.elapsed() < STATUS_INVALIDATION_PERIOD {
+            return Ok(HttpResponse::Ok().json(CoreStatus {
+                block_time,
+                ..status.clone()
+            }));
         }
     }
 
@@ -52,24 +94,826 @@ async fn status(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
         .await
         .is_ok();
     let eth_status = data.eth_client.block_number().await.is_ok();
+    let contract_config_outdated = match data.connection_pool.access_storage().await {
+        Ok(mut storage) => storage
+            .config_schema()
+            .load_zksync_target()
+            .await
+            .ok()
+            .flatten()
+            .map_or(false, |target| target != data.contract_target_addr),
+        Err(_) => false,
+    };
+    if contract_config_outdated {
+        vlog::warn!("Main contract was upgraded, but the server config still has the old target");
+    }
+    let root_hash_mismatch = match data.connection_pool.access_storage().await {
+        Ok(mut storage) => storage
+            .chain()
+            .block_schema()
+            .load_block_root_mismatches()
+            .await
+            .map_or(false, |blocks| !blocks.is_empty()),
+        Err(_) => false,
+    };
+    if root_hash_mismatch {
+        vlog::warn!("Root hash of some committed blocks differs from the one committed on L1");
+    }
 
     let response = CoreStatus {
         main_database_available: main_database_status,
         replica_database_available: replica_database_status,
         web3_available: eth_status,
+        contract_config_outdated,
+        root_hash_mismatch,
+        block_time,
     };
     *status = Some((response.clone(), Instant::now()));
 
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Returns the server parameters that can be changed at runtime.
+#[actix_web::get("/runtime_config")]
+async fn runtime_config(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let target = data.block_time_controller.read().await.target();
+    Ok(HttpResponse::Ok().json(RuntimeConfig {
+        block_time_target_ms: target.map(|target| target.as_millis() as u64),
+    }))
+}
+
+/// Changes the server parameters at runtime. Changes are lost on restart,
+/// so the config has to be updated as well to keep them.
+#[actix_web::post("/runtime_config")]
+async fn update_runtime_config(
+    data: web::Data<AppState>,
+    config: web::Json<RuntimeConfig>,
+) -> actix_web::Result<HttpResponse> {
+    if config.block_time_target_ms == Some(0) {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Block time target must be positive",
+        ));
+    }
+
+    let target = config.block_time_target_ms.map(Duration::from_millis);
+    let mut controller = data.block_time_controller.write().await;
+    controller.set_target(target);
+    vlog::info!(
+        "Block time target was changed to {:?}, effective seal timeout is {:?}",
+        target,
+        controller.effective_timeout()
+    );
+    Ok(HttpResponse::Ok().json(controller.status()))
+}
+
+/// Returns the subset of the provided transaction hashes that are known to the core,
+/// i.e. either queued in the mempool or already executed.
+/// Used by API nodes to detect transactions that were lost on the way to the mempool.
+#[actix_web::post("/mempool/known_txs")]
+async fn known_txs(
+    data: web::Data<AppState>,
+    tx_hashes: web::Json<Vec<TxHash>>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let known_hashes = storage
+        .chain()
+        .mempool_schema()
+        .filter_known_txs(&tx_hashes)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(known_hashes))
+}
+
+/// Adds the transaction or batch accepted by an API node, but not known to the core, to the mempool.
+/// Already known transactions are not added again, so the same submission may be forwarded several times.
+#[actix_web::post("/mempool/reforward")]
+async fn reforward_txs(
+    data: web::Data<AppState>,
+    submission: web::Json<MempoolSubmission>,
+) -> actix_web::Result<HttpResponse> {
+    let tx_hashes = submission.tx_hashes();
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let known_hashes = storage
+        .chain()
+        .mempool_schema()
+        .filter_known_txs(&tx_hashes)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    drop(storage);
+    if known_hashes.len() == tx_hashes.len() {
+        return Ok(HttpResponse::Ok().json(Ok::<(), TxAddError>(())));
+    }
+
+    let (sender, receiver) = oneshot::channel();
+    let request = match submission.into_inner() {
+        MempoolSubmission::Tx { tx } => MempoolTransactionRequest::NewTx(Box::new(tx), sender),
+        MempoolSubmission::Batch {
+            txs,
+            eth_signatures,
+        } => MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, sender),
+    };
+    data.mempool_tx_sender
+        .clone()
+        .send(request)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let result = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    if result.is_ok() {
+        vlog::info!(
+            "Transactions {:?} were forwarded to the mempool again",
+            tx_hashes
+        );
+    }
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Lists the transactions that failed execution too many times and were moved to the dead-letter state.
+#[actix_web::get("/mempool/dead_letter")]
+async fn dead_lettered_txs(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let txs = storage
+        .chain()
+        .mempool_schema()
+        .load_dead_lettered_txs()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(txs))
+}
+
+/// Returns the dead-lettered transaction back to the mempool with the execution attempts reset.
+#[actix_web::post("/mempool/dead_letter/{tx_hash}/resurrect")]
+async fn resurrect_dead_lettered_tx(
+    data: web::Data<AppState>,
+    tx_hash: web::Path<TxHash>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let resurrected = storage
+        .chain()
+        .mempool_schema()
+        .resurrect_dead_lettered_tx(*tx_hash)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if resurrected {
+        vlog::info!("Dead-lettered transaction {} was resurrected", *tx_hash);
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+/// Lists the accounts whose queued transactions wait behind a transaction that can't be executed.
+#[actix_web::get("/mempool/stuck_accounts")]
+async fn stuck_accounts(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let accounts = storage
+        .chain()
+        .mempool_schema()
+        .load_stuck_accounts()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(accounts))
+}
+
+/// Lists the tokens that were added through the governance contract, but not registered because of the listing policy.
+#[actix_web::get("/token_listings")]
+async fn rejected_token_listings(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let listings = storage
+        .tokens_schema()
+        .load_rejected_token_listings()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(listings))
+}
+
+async fn review_token_listing(
+    data: web::Data<AppState>,
+    token_id: TokenId,
+    status: TokenListingStatus,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let reviewed = storage
+        .tokens_schema()
+        .review_token_listing(token_id, status)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if reviewed {
+        vlog::info!("Listing of the token {} was set to {:?}", token_id, status);
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+/// Approves the token listing, the token will be registered by the token handler.
+#[actix_web::post("/token_listings/{token_id}/approve")]
+async fn approve_token_listing(
+    data: web::Data<AppState>,
+    token_id: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    review_token_listing(data, TokenId(*token_id), TokenListingStatus::Approved).await
+}
+
+/// Rejects the token listing, the token won't be registered.
+#[actix_web::post("/token_listings/{token_id}/reject")]
+async fn reject_token_listing(
+    data: web::Data<AppState>,
+    token_id: web::Path<u32>,
+) -> actix_web::Result<HttpResponse> {
+    review_token_listing(data, TokenId(*token_id), TokenListingStatus::Rejected).await
+}
+
+#[derive(Debug, Deserialize)]
+struct NewToken {
+    address: Address,
+}
+
+/// Registers the ERC20 token added to the governance contract without waiting for the token handler.
+/// Symbol and decimals are queried from the token contract. The API servers don't cache the unknown
+/// tokens, so the fee quotes in the new token are available right away (once its price is known).
+#[actix_web::post("/tokens")]
+async fn register_token(
+    data: web::Data<AppState>,
+    new_token: web::Json<NewToken>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let result = add_token(
+        &mut storage,
+        &data.eth_client,
+        data.governance_addr,
+        new_token.address,
+    )
+    .await;
+
+    let err = match result {
+        Ok(token) => {
+            vlog::info!(
+                "Token {} ({:#x}) was registered through the private API",
+                token.id,
+                token.address
+            );
+            return Ok(HttpResponse::Ok().json(token));
+        }
+        Err(AddTokenError::Internal(err)) => {
+            return Err(actix_web::error::ErrorInternalServerError(err));
+        }
+        Err(err) => err,
+    };
+    let mut response = match err {
+        AddTokenError::DuplicateAddress(_)
+        | AddTokenError::DuplicateSymbol(_)
+        | AddTokenError::IdMismatch { .. } => HttpResponse::Conflict(),
+        _ => HttpResponse::BadRequest(),
+    };
+    Ok(response.json(serde_json::json!({
+        "reason": err.reason(),
+        "message": err.to_string(),
+    })))
+}
+
+/// Issues a new API key. The key is returned only once, only its hash is stored.
+#[actix_web::post("/api_keys")]
+async fn create_api_key(
+    data: web::Data<AppState>,
+    new_key: web::Json<NewApiKey>,
+) -> actix_web::Result<HttpResponse> {
+    if new_key.scopes.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "API key should have at least one scope",
+        ));
+    }
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let key = generate_api_key();
+    let info = storage
+        .api_keys_schema()
+        .create_api_key(api_key_hash(&key), &new_key)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    vlog::info!("API key {} was issued to {}", info.id, info.name);
+    Ok(HttpResponse::Ok().json(IssuedApiKey { key, info }))
+}
+
+/// Lists the issued API keys.
+#[actix_web::get("/api_keys")]
+async fn api_keys(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let keys = storage
+        .api_keys_schema()
+        .load_api_keys()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(keys))
+}
+
+#[actix_web::get("/api_keys/{id}")]
+async fn api_key(data: web::Data<AppState>, id: web::Path<i64>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let key = storage
+        .api_keys_schema()
+        .load_api_key(*id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match key {
+        Some(key) => Ok(HttpResponse::Ok().json(key)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+/// Revokes the API key, the requests made with it are rejected afterwards.
+#[actix_web::post("/api_keys/{id}/revoke")]
+async fn revoke_api_key(
+    data: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let revoked = storage
+        .api_keys_schema()
+        .revoke_api_key(*id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if revoked {
+        vlog::info!("API key {} was revoked", *id);
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DayRangeQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+}
+
+/// Returns the number of requests made with the API key per day (UTC).
+/// By default, the usage for the last 30 days is returned.
+#[actix_web::get("/api_keys/{id}/usage")]
+async fn api_key_usage(
+    data: web::Data<AppState>,
+    id: web::Path<i64>,
+    query: web::Query<DayRangeQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let to = query.to.unwrap_or_else(|| Utc::today().naive_utc());
+    let from = query
+        .from
+        .unwrap_or_else(|| to - chrono::Duration::days(DEFAULT_API_KEY_USAGE_DAYS - 1));
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let usage = storage
+        .api_keys_schema()
+        .load_api_key_usage(*id, from, to)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(usage))
+}
+
+/// Lists the maintenance accounts added through this API.
+/// Accounts from the server config are exempted from the fee check too, but are not listed.
+#[actix_web::get("/maintenance_accounts")]
+async fn maintenance_accounts(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let accounts = storage
+        .maintenance_schema()
+        .load_maintenance_accounts()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(accounts))
+}
+
+/// Exempts the account from the fee check, its transactions may be submitted without fees.
+#[actix_web::post("/maintenance_accounts/{address}")]
+async fn add_maintenance_account(
+    data: web::Data<AppState>,
+    address: web::Path<Address>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let added = storage
+        .maintenance_schema()
+        .add_maintenance_account(*address)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if added {
+        vlog::info!(
+            "Account {:?} was added to the maintenance accounts",
+            *address
+        );
+    }
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Removes the fee exemption of the account added through this API.
+#[actix_web::post("/maintenance_accounts/{address}/remove")]
+async fn remove_maintenance_account(
+    data: web::Data<AppState>,
+    address: web::Path<Address>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let removed = storage
+        .maintenance_schema()
+        .remove_maintenance_account(*address)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if removed {
+        vlog::info!(
+            "Account {:?} was removed from the maintenance accounts",
+            *address
+        );
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().finish())
+    }
+}
+
+/// Returns the maintenance mode of the network.
+#[actix_web::get("/maintenance")]
+async fn maintenance_mode(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mode = storage
+        .maintenance_schema()
+        .load_maintenance_mode()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(mode))
+}
+
+/// Enables or disables the maintenance mode. While it's enabled, the API servers keep serving
+/// the reads but reject the new transactions with the provided reason. The API servers cache
+/// the flag for a few seconds, so the change takes effect with a small delay.
+#[actix_web::post("/maintenance")]
+async fn set_maintenance_mode(
+    data: web::Data<AppState>,
+    mode: web::Json<MaintenanceMode>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    storage
+        .maintenance_schema()
+        .set_maintenance_mode(&mode)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if mode.enabled {
+        vlog::info!(
+            "Maintenance mode was enabled, reason: {}",
+            mode.reason.as_deref().unwrap_or("not specified")
+        );
+    } else {
+        vlog::info!("Maintenance mode was disabled");
+    }
+    Ok(HttpResponse::Ok().json(mode.into_inner()))
+}
+
+/// Lists all the fee subsidy campaigns, including the disabled and the expired ones.
+#[actix_web::get("/subsidy_campaigns")]
+async fn subsidy_campaigns(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let campaigns = storage
+        .subsidy_campaigns_schema()
+        .load_campaigns()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(campaigns))
+}
+
+/// Creates the campaign subsidizing the fees of the partner's transactions with the given
+/// fee types until its budget is spent or it expires.
+#[actix_web::post("/subsidy_campaigns")]
+async fn create_subsidy_campaign(
+    data: web::Data<AppState>,
+    campaign: web::Json<NewSubsidyCampaign>,
+) -> actix_web::Result<HttpResponse> {
+    if campaign.partner.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Partner name should not be empty",
+        ));
+    }
+    if campaign.fee_types.is_empty() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Campaign should subsidize at least one fee type",
+        ));
+    }
+    if campaign.expires_at <= Utc::now() {
+        return Err(actix_web::error::ErrorBadRequest(
+            "Campaign expiration time should be in the future",
+        ));
+    }
+
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let campaign = storage
+        .subsidy_campaigns_schema()
+        .create_campaign(&campaign)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    vlog::info!(
+        "Subsidy campaign #{} was created for {}, budget: {} USD (scaled)",
+        campaign.id,
+        campaign.partner,
+        campaign.budget_usd_scaled
+    );
+    Ok(HttpResponse::Ok().json(campaign))
+}
+
+/// Increases the budget of the subsidy campaign.
+#[actix_web::post("/subsidy_campaigns/{id}/top_up")]
+async fn top_up_subsidy_campaign(
+    data: web::Data<AppState>,
+    id: web::Path<i64>,
+    top_up: web::Json<SubsidyCampaignTopUp>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let campaign = storage
+        .subsidy_campaigns_schema()
+        .top_up_campaign(*id, top_up.amount_usd_scaled)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match campaign {
+        Some(campaign) => {
+            vlog::info!(
+                "Subsidy campaign #{} was topped up by {} USD (scaled)",
+                campaign.id,
+                top_up.amount_usd_scaled
+            );
+            Ok(HttpResponse::Ok().json(campaign))
+        }
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+/// Disables the subsidy campaign, the subsidies of the transactions accepted before
+/// are still charged to its budget once they're executed.
+#[actix_web::post("/subsidy_campaigns/{id}/disable")]
+async fn disable_subsidy_campaign(
+    data: web::Data<AppState>,
+    id: web::Path<i64>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let campaign = storage
+        .subsidy_campaigns_schema()
+        .disable_campaign(*id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match campaign {
+        Some(campaign) => {
+            vlog::info!("Subsidy campaign #{} was disabled", campaign.id);
+            Ok(HttpResponse::Ok().json(campaign))
+        }
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct FeeExemptionsQuery {
+    limit: Option<u32>,
+}
+
+/// Returns the latest transactions accepted without paying the required fee, newest first.
+#[actix_web::get("/maintenance_accounts/fee_exemptions")]
+async fn fee_exemptions(
+    data: web::Data<AppState>,
+    query: web::Query<FeeExemptionsQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let exemptions = storage
+        .maintenance_schema()
+        .load_fee_exemptions(query.limit.unwrap_or(DEFAULT_FEE_EXEMPTIONS_LIMIT))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(exemptions))
+}
+
+/// Returns the part of the amounts paid on L1 that was not credited on L2, summed up per token
+/// and day (UTC) for the reconciliation. By default, the totals for the last 30 days are returned.
+#[actix_web::get("/priority_ops/surplus")]
+async fn priority_op_surplus(
+    data: web::Data<AppState>,
+    query: web::Query<DayRangeQuery>,
+) -> actix_web::Result<HttpResponse> {
+    let to = query.to.unwrap_or_else(|| Utc::today().naive_utc());
+    let from = query
+        .from
+        .unwrap_or_else(|| to - chrono::Duration::days(DEFAULT_PRIORITY_OP_SURPLUS_DAYS - 1));
+    let mut storage = data
+        .connection_pool
+        .access_storage()
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let surplus = storage
+        .chain()
+        .operations_schema()
+        .load_priority_op_surplus(from, to)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(surplus))
+}
+
+/// Lists the deposits and full exits targeting the address that are seen by the Ethereum watcher,
+/// but not executed yet. Operations without enough confirmations are included as well.
+#[actix_web::get("/priority_ops/pending/{address}")]
+async fn pending_priority_ops(
+    data: web::Data<AppState>,
+    address: web::Path<Address>,
+) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    data.eth_watch_req_sender
+        .clone()
+        .send(EthWatchRequest::GetPendingPriorityOps {
+            address: *address,
+            resp: sender,
+        })
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let ops = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(ops))
+}
+
+/// Returns the priority operation with the serial id if it's seen by the Ethereum watcher,
+/// but not executed yet, or `null` otherwise.
+#[actix_web::get("/priority_ops/{serial_id}/pending")]
+async fn pending_priority_op(
+    data: web::Data<AppState>,
+    serial_id: web::Path<SerialId>,
+) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    data.eth_watch_req_sender
+        .clone()
+        .send(EthWatchRequest::GetPendingPriorityOp {
+            serial_id: *serial_id,
+            resp: sender,
+        })
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let op = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(op))
+}
+
+/// Returns the block currently being filled by the state keeper.
+#[actix_web::get("/state_keeper/pending_block")]
+async fn pending_block_info(data: web::Data<AppState>) -> actix_web::Result<HttpResponse> {
+    let (sender, receiver) = oneshot::channel();
+    data.state_keeper_req_sender
+        .clone()
+        .send(StateKeeperRequest::GetPendingBlockInfo(sender))
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    let info = receiver
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(info))
+}
+
+/// Runs the server until the shutdown is requested, then stops it gracefully: new connections
+/// are rejected, while the in-flight requests are given the time to finish.
+async fn run_until_shutdown(server: Server, shutdown: ShutdownSignal) -> std::io::Result<()> {
+    let server_handle = server.handle();
+    let stop_requested = shutdown.clone().requested();
+    actix_rt::spawn(async move {
+        stop_requested.await;
+        server_handle.stop(true).await;
+    });
+    let result = server.await;
+    vlog::info!("Private core API server has stopped");
+    // The signal is kept until the server is stopped, so the shutdown waits for it.
+    drop(shutdown);
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn start_private_core_api(
     connection_pool: ConnectionPool,
     read_only_connection_pool: ConnectionPool,
     eth_client: EthereumGateway,
     config: PrivateApiConfig,
+    contract_target_addr: Address,
+    governance_addr: Address,
+    block_time_controller: SharedBlockTimeController,
+    eth_watch_req_sender: mpsc::Sender<EthWatchRequest>,
+    state_keeper_req_sender: mpsc::Sender<StateKeeperRequest>,
+    mempool_tx_sender: mpsc::Sender<MempoolTransactionRequest>,
+    grace_period: Duration,
+    shutdown: ShutdownSignal,
 ) -> JoinHandle<()> {
-    let (panic_sender, mut panic_receiver) = mpsc::channel(1);
+    let (handler, panic_sender) = spawn_panic_handler();
 
     thread::Builder::new()
         .name("core-private-api".to_string())
@@ -79,12 +923,18 @@ pub fn start_private_core_api(
 
             actix_runtime.block_on(async move {
                 // Start HTTP server.
-                HttpServer::new(move || {
+                let server = HttpServer::new(move || {
                     let app_state = AppState {
                         connection_pool: connection_pool.clone(),
                         read_only_connection_pool: read_only_connection_pool.clone(),
                         eth_client: eth_client.clone(),
                         status_cache: Default::default(),
+                        contract_target_addr,
+                        governance_addr,
+                        block_time_controller: block_time_controller.clone(),
+                        eth_watch_req_sender: eth_watch_req_sender.clone(),
+                        state_keeper_req_sender: state_keeper_req_sender.clone(),
+                        mempool_tx_sender: mempool_tx_sender.clone(),
                     };
 
                     // By calling `register_data` instead of `data` we're avoiding double
@@ -94,15 +944,99 @@ pub fn start_private_core_api(
                         .app_data(web::Data::new(app_state))
                         .app_data(web::JsonConfig::default().limit(2usize.pow(32)))
                         .service(status)
+                        .service(runtime_config)
+                        .service(update_runtime_config)
+                        .service(known_txs)
+                        .service(reforward_txs)
+                        .service(dead_lettered_txs)
+                        .service(resurrect_dead_lettered_tx)
+                        .service(stuck_accounts)
+                        .service(rejected_token_listings)
+                        .service(approve_token_listing)
+                        .service(reject_token_listing)
+                        .service(register_token)
+                        .service(create_api_key)
+                        .service(api_keys)
+                        .service(api_key)
+                        .service(revoke_api_key)
+                        .service(api_key_usage)
+                        .service(fee_exemptions)
+                        .service(priority_op_surplus)
+                        .service(pending_priority_ops)
+                        .service(pending_priority_op)
+                        .service(pending_block_info)
+                        .service(maintenance_accounts)
+                        .service(add_maintenance_account)
+                        .service(remove_maintenance_account)
+                        .service(maintenance_mode)
+                        .service(set_maintenance_mode)
+                        .service(subsidy_campaigns)
+                        .service(create_subsidy_campaign)
+                        .service(top_up_subsidy_campaign)
+                        .service(disable_subsidy_campaign)
                 })
                 .bind(&config.bind_addr())
                 .expect("failed to bind")
-                .run()
-                .await
+                // The server is stopped by the shutdown signal only.
+                .disable_signals()
+                .shutdown_timeout(grace_period.as_secs())
+                .run();
+                run_until_shutdown(server, shutdown).await
             })
         })
         .expect("failed to start prover server");
-    tokio::spawn(async move {
-        panic_receiver.next().await.unwrap();
-    })
+    handler
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::Semaphore;
+    use zksync_utils::shutdown::ShutdownController;
+
+    /// Checks that the in-flight requests are finished once the shutdown is requested.
+    #[actix_rt::test]
+    async fn in_flight_requests_are_finished_on_shutdown() {
+        let controller = ShutdownController::new();
+        // The slow request is finished only once the permit is added.
+        let release = Arc::new(Semaphore::new(0));
+
+        let server_release = release.clone();
+        let server = HttpServer::new(move || {
+            let release = server_release.clone();
+            App::new().route(
+                "/slow",
+                web::get().to(move || {
+                    let release = release.clone();
+                    async move {
+                        release.acquire().await.unwrap().forget();
+                        HttpResponse::Ok().body("done")
+                    }
+                }),
+            )
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let url = format!("http://{}/slow", server.addrs()[0]);
+        let server = server.disable_signals().shutdown_timeout(10).run();
+        let server = actix_rt::spawn(run_until_shutdown(server, controller.signal()));
+
+        let slow_request = actix_rt::spawn(reqwest::get(url));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let mut stopped = actix_rt::spawn(controller.shutdown(Duration::from_secs(10)));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), &mut stopped)
+                .await
+                .is_err(),
+            "Server should wait for the slow request"
+        );
+
+        release.add_permits(1);
+        let response = slow_request.await.unwrap().unwrap();
+        assert_eq!(response.text().await.unwrap(), "done");
+        assert!(stopped.await.unwrap(), "Server should stop in time");
+        server.await.unwrap().unwrap();
+    }
 }
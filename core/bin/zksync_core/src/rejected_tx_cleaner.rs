@@ -5,19 +5,23 @@
 //! which is useful only for a short period of time. Since such transactions are not actually
 //! included in the block and don't affect the state hash, there is no much sense to keep
 //! them forever.
+//!
+//! For the same reason the cleaner moves the payloads of the old confirmed aggregated operations
+//! out of the `aggregate_operations` table: they are only needed until the operation is confirmed on L1.
 
 // External uses
 use tokio::{task::JoinHandle, time};
 
 // Workspace deps
 use zksync_config::DBConfig;
-use zksync_storage::ConnectionPool;
+use zksync_storage::{ConnectionPool, StorageProcessor};
 
 #[must_use]
 pub fn run_rejected_tx_cleaner(config: &DBConfig, db_pool: ConnectionPool) -> JoinHandle<()> {
     let max_age = chrono::Duration::from_std(config.rejected_transactions_max_age()).unwrap();
     let interval = config.rejected_transactions_cleaner_interval();
     let mut timer = time::interval(interval);
+    let config = config.clone();
 
     tokio::spawn(async move {
         loop {
@@ -33,7 +37,37 @@ pub fn run_rejected_tx_cleaner(config: &DBConfig, db_pool: ConnectionPool) -> Jo
             {
                 vlog::error!("Can't delete rejected transactions {:?}", e);
             }
+            if let Err(e) = archive_aggregated_operations(&mut storage, &config).await {
+                vlog::error!("Can't archive aggregated operations {:?}", e);
+            }
             timer.tick().await;
         }
     })
 }
+
+/// Archives the old aggregated operations batch by batch, so no lock is held for long.
+async fn archive_aggregated_operations(
+    storage: &mut StorageProcessor<'_>,
+    config: &DBConfig,
+) -> anyhow::Result<()> {
+    let mut archived = 0;
+    loop {
+        let processed = storage
+            .chain()
+            .operations_schema()
+            .archive_aggregated_operations(
+                config.aggregated_operations_retention_blocks,
+                config.aggregated_operations_archive_batch_size,
+                config.archive_aggregated_operations,
+            )
+            .await?;
+        if processed == 0 {
+            break;
+        }
+        archived += processed;
+    }
+    if archived > 0 {
+        vlog::info!("Archived payloads of {} aggregated operations", archived);
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use std::sync::Mutex;
+
+use anyhow::format_err;
+use ethabi::{Contract, Token};
+use web3::types::{BlockNumber as EthBlockNumber, FilterBuilder, H256 as EthH256, U64};
+
+use zksync_contracts::zksync_contract;
+use zksync_eth_client::ethereum_gateway::EthereumGateway;
+use zksync_types::{Address, BlockNumber, H256, U256};
+
+/// Source of the root hashes the blocks were committed with on L1.
+#[async_trait::async_trait]
+pub trait RootHashClient {
+    /// Returns the state root hash the block was committed with on L1,
+    /// or `None` if there is no commit of the block on L1.
+    async fn committed_root_hash(&self, block_number: BlockNumber) -> anyhow::Result<Option<H256>>;
+}
+
+/// Finds the `BlockCommit` event of the block and takes the root hash
+/// from the input of the `commitBlocks` transaction that emitted it.
+///
+/// Blocks are committed in order, so the events are searched starting from the L1 block
+/// of the last found commit, or from the contract deployment before any commit is found.
+pub struct EthRootHashClient {
+    client: EthereumGateway,
+    zksync_contract: Contract,
+    zksync_contract_addr: Address,
+    block_commit_topic: EthH256,
+    /// Transaction deploying the contract, its block is the earliest one the events can be in.
+    genesis_tx_hash: EthH256,
+    /// L1 block to start the search of the events from, unknown until the deployment is looked up.
+    search_from: Mutex<Option<U64>>,
+}
+
+impl EthRootHashClient {
+    pub fn new(
+        client: EthereumGateway,
+        zksync_contract_addr: Address,
+        genesis_tx_hash: EthH256,
+    ) -> Self {
+        let zksync_contract = zksync_contract();
+        let block_commit_topic = zksync_contract
+            .event("BlockCommit")
+            .expect("main contract abi error")
+            .signature();
+        Self {
+            client,
+            zksync_contract,
+            zksync_contract_addr,
+            block_commit_topic,
+            genesis_tx_hash,
+            search_from: Mutex::new(None),
+        }
+    }
+
+    async fn search_from(&self) -> anyhow::Result<U64> {
+        if let Some(block) = *self.search_from.lock().unwrap() {
+            return Ok(block);
+        }
+        let deployment_block = self
+            .client
+            .tx_receipt(self.genesis_tx_hash)
+            .await?
+            .and_then(|receipt| receipt.block_number)
+            .ok_or_else(|| {
+                format_err!(
+                    "Contract deployment transaction {:?} is not found",
+                    self.genesis_tx_hash
+                )
+            })?;
+        *self.search_from.lock().unwrap() = Some(deployment_block);
+        Ok(deployment_block)
+    }
+}
+
+#[async_trait::async_trait]
+impl RootHashClient for EthRootHashClient {
+    async fn committed_root_hash(&self, block_number: BlockNumber) -> anyhow::Result<Option<H256>> {
+        // Block number is the only indexed field of the event.
+        let block_topic = EthH256::from_low_u64_be(*block_number as u64);
+        let search_from = self.search_from().await?;
+        let filter = FilterBuilder::default()
+            .address(vec![self.zksync_contract_addr])
+            .from_block(EthBlockNumber::Number(search_from))
+            .to_block(EthBlockNumber::Latest)
+            .topics(
+                Some(vec![self.block_commit_topic]),
+                Some(vec![block_topic]),
+                None,
+                None,
+            )
+            .build();
+        let logs = self.client.logs(filter).await?;
+        // The block could be reverted and committed again, only the last commit matters.
+        let (tx_hash, commit_block) = match logs.last() {
+            Some(log) => (
+                log.transaction_hash
+                    .ok_or_else(|| format_err!("BlockCommit event without transaction hash"))?,
+                log.block_number,
+            ),
+            None => return Ok(None),
+        };
+
+        let tx = self
+            .client
+            .get_tx(tx_hash)
+            .await?
+            .ok_or_else(|| format_err!("Commit transaction {:?} is not found", tx_hash))?;
+        // Skip the function selector.
+        let input = tx
+            .input
+            .0
+            .get(4..)
+            .ok_or_else(|| format_err!("Commit transaction {:?} has no input", tx_hash))?;
+        let params = self
+            .zksync_contract
+            .function("commitBlocks")
+            .expect("main contract abi error")
+            .decode_input(input)?;
+
+        let root_hash = commit_blocks_root_hash(&params, block_number).ok_or_else(|| {
+            format_err!(
+                "Block #{} is not found in the commit transaction {:?}",
+                block_number,
+                tx_hash
+            )
+        })?;
+        // The following blocks can't be committed earlier than this one.
+        if let Some(commit_block) = commit_block {
+            let mut search_from = self.search_from.lock().unwrap();
+            *search_from = std::cmp::max(*search_from, Some(commit_block));
+        }
+        Ok(Some(root_hash))
+    }
+}
+
+/// Extracts the root hash of the block from the decoded `commitBlocks` parameters.
+fn commit_blocks_root_hash(params: &[Token], block_number: BlockNumber) -> Option<H256> {
+    // `CommitBlockInfo` fields.
+    const ROOT_HASH_ID: usize = 0;
+    const BLOCK_NUMBER_ID: usize = 4;
+
+    let blocks = match params.get(1)? {
+        Token::Array(blocks) => blocks,
+        _ => return None,
+    };
+    blocks.iter().find_map(|block| match block {
+        Token::Tuple(fields) => match (fields.get(ROOT_HASH_ID)?, fields.get(BLOCK_NUMBER_ID)?) {
+            (Token::FixedBytes(root_hash), Token::Uint(number))
+                if *number == U256::from(*block_number) =>
+            {
+                Some(H256::from_slice(root_hash))
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
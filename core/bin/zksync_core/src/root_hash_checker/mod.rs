@@ -0,0 +1,168 @@
+//! The root hash checker cross-checks the root hashes of the blocks committed on L1
+//! against the locally stored ones.
+//!
+//! A divergence between the server state and the contract state would otherwise be noticed
+//! only when the proof verification fails. For every block which commit is confirmed on L1,
+//! the checker fetches the root hash the block was committed with and records the result
+//! of the comparison. Any mismatch degrades the core health status.
+//!
+//! Blocks are checked in order starting from the one after the last checked block,
+//! so the blocks confirmed while the checker was down are checked on the next run.
+
+// Built-in uses
+use std::time::Duration;
+// External uses
+use anyhow::format_err;
+use tokio::{task::JoinHandle, time};
+// Workspace deps
+use zksync_config::ContractsConfig;
+use zksync_crypto::{convert::FeConvert, Fr};
+use zksync_eth_client::EthereumGateway;
+use zksync_storage::{ConnectionPool, StorageProcessor};
+use zksync_types::{block::Block, BlockNumber, H256};
+// Local deps
+use self::client::{EthRootHashClient, RootHashClient};
+
+pub mod client;
+#[cfg(test)]
+mod tests;
+
+/// Maximum number of blocks checked in one run.
+const MAX_BLOCKS_PER_ITERATION: u32 = 100;
+/// Number of attempts to fetch the root hash of a block from L1.
+const MAX_RETRIES: u32 = 5;
+/// Delay between the attempts to fetch the root hash of a block from L1.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+/// Delay between the checker runs.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct RootHashChecker<C> {
+    client: C,
+    retry_delay: Duration,
+}
+
+impl<C: RootHashClient> RootHashChecker<C> {
+    pub fn new(client: C, retry_delay: Duration) -> Self {
+        Self {
+            client,
+            retry_delay,
+        }
+    }
+
+    /// Checks the blocks confirmed on L1 since the last checked block.
+    /// Returns the number of blocks which root hash doesn't match the L1 one.
+    pub async fn check_confirmed_blocks(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<u32> {
+        let first_block = storage
+            .chain()
+            .block_schema()
+            .get_last_checked_block_root()
+            .await?
+            + 1;
+        let last_confirmed_block = storage
+            .chain()
+            .block_schema()
+            .get_last_committed_confirmed_block()
+            .await?;
+        let last_block = std::cmp::min(
+            *last_confirmed_block,
+            *first_block + MAX_BLOCKS_PER_ITERATION - 1,
+        );
+
+        let mut mismatches = 0;
+        for block_number in *first_block..=last_block {
+            let block_number = BlockNumber(block_number);
+            let local_root_hash = self.local_root_hash(storage, block_number).await?;
+            // If the block can't be checked, the next run starts from it again.
+            let l1_root_hash = self.l1_root_hash(block_number).await?.ok_or_else(|| {
+                format_err!("Commit of the block #{} is not found on L1", block_number)
+            })?;
+
+            storage
+                .chain()
+                .block_schema()
+                .record_block_root_check(block_number, local_root_hash, l1_root_hash)
+                .await?;
+            if local_root_hash != l1_root_hash {
+                mismatches += 1;
+                metrics::increment_counter!("root_hash_checker.mismatch");
+                vlog::error!(
+                    "Root hash of the block #{} differs from the one committed on L1: local {:?}, L1 {:?}",
+                    block_number,
+                    local_root_hash,
+                    l1_root_hash
+                );
+            }
+            metrics::gauge!("root_hash_checker.last_checked_block", *block_number as f64);
+        }
+
+        Ok(mismatches)
+    }
+
+    async fn local_root_hash(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        block_number: BlockNumber,
+    ) -> anyhow::Result<H256> {
+        let block = storage
+            .chain()
+            .block_schema()
+            .get_storage_block(block_number)
+            .await?
+            .ok_or_else(|| format_err!("Block #{} is not found in the database", block_number))?;
+        let root_hash = Fr::from_bytes(&block.root_hash)?;
+        Ok(Block::encode_fr_for_eth(root_hash))
+    }
+
+    async fn l1_root_hash(&self, block_number: BlockNumber) -> anyhow::Result<Option<H256>> {
+        let mut attempt = 1;
+        loop {
+            match self.client.committed_root_hash(block_number).await {
+                Ok(root_hash) => return Ok(root_hash),
+                Err(e) if attempt < MAX_RETRIES => {
+                    vlog::warn!(
+                        "Can't fetch the root hash of the block #{} from L1 (attempt {}): {}",
+                        block_number,
+                        attempt,
+                        e
+                    );
+                    attempt += 1;
+                    time::sleep(self.retry_delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn run_root_hash_checker(
+    db_pool: ConnectionPool,
+    eth_gateway: EthereumGateway,
+    contracts: &ContractsConfig,
+) -> JoinHandle<()> {
+    let checker = RootHashChecker::new(
+        EthRootHashClient::new(
+            eth_gateway,
+            contracts.contract_addr,
+            contracts.genesis_tx_hash,
+        ),
+        RETRY_DELAY,
+    );
+    let mut timer = time::interval(CHECK_INTERVAL);
+
+    tokio::spawn(async move {
+        loop {
+            timer.tick().await;
+            let mut storage = db_pool
+                .access_storage()
+                .await
+                .expect("root hash checker couldn't access the database");
+            if let Err(e) = checker.check_confirmed_blocks(&mut storage).await {
+                vlog::error!("Can't check the committed root hashes {:?}", e);
+            }
+        }
+    })
+}
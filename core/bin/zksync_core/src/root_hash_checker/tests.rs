@@ -0,0 +1,228 @@
+// Built-in uses
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU32, Ordering},
+};
+// External uses
+use db_test_macro::test as db_test;
+// Workspace deps
+use zksync_storage::test_data::{
+    dummy_root_hash_for_block, gen_sample_block, gen_unique_aggregated_operation,
+};
+use zksync_types::aggregated_operations::AggregatedActionType;
+// Local deps
+use super::*;
+
+const BLOCK_SIZE_CHUNKS: usize = 100;
+
+/// Client with the predefined L1 root hashes which fails the requested number of times.
+#[derive(Default)]
+struct FakeRootHashClient {
+    roots: HashMap<BlockNumber, H256>,
+    failures_left: AtomicU32,
+}
+
+#[async_trait::async_trait]
+impl RootHashClient for FakeRootHashClient {
+    async fn committed_root_hash(&self, block_number: BlockNumber) -> anyhow::Result<Option<H256>> {
+        if self.failures_left.load(Ordering::SeqCst) > 0 {
+            self.failures_left.fetch_sub(1, Ordering::SeqCst);
+            anyhow::bail!("Provider is unavailable");
+        }
+        Ok(self.roots.get(&block_number).copied())
+    }
+}
+
+fn local_root_hash(block_number: BlockNumber) -> H256 {
+    Block::encode_fr_for_eth(dummy_root_hash_for_block(block_number))
+}
+
+fn checker(blocks: impl IntoIterator<Item = u32>) -> RootHashChecker<FakeRootHashClient> {
+    let client = FakeRootHashClient {
+        roots: blocks
+            .into_iter()
+            .map(|block| (BlockNumber(block), local_root_hash(BlockNumber(block))))
+            .collect(),
+        ..Default::default()
+    };
+    RootHashChecker::new(client, Duration::from_millis(1))
+}
+
+/// Saves the blocks and confirms their commit operations.
+async fn commit_blocks(
+    storage: &mut StorageProcessor<'_>,
+    blocks: impl IntoIterator<Item = u32>,
+) -> anyhow::Result<()> {
+    for block_number in blocks {
+        let block_number = BlockNumber(block_number);
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(gen_sample_block(block_number, BLOCK_SIZE_CHUNKS, vec![]))
+            .await?;
+        storage
+            .chain()
+            .operations_schema()
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                AggregatedActionType::CommitBlocks,
+                BLOCK_SIZE_CHUNKS,
+            ))
+            .await?;
+        storage
+            .chain()
+            .operations_schema()
+            .confirm_aggregated_operations(
+                block_number,
+                block_number,
+                AggregatedActionType::CommitBlocks,
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Checks that the blocks with the same root hash on L1 are recorded as matching.
+#[db_test]
+async fn root_hashes_match(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    commit_blocks(&mut storage, 1..=3).await?;
+    let checker = checker(1..=3);
+
+    assert_eq!(checker.check_confirmed_blocks(&mut storage).await?, 0);
+    for block in 1..=3 {
+        let check = storage
+            .chain()
+            .block_schema()
+            .get_block_root_check(BlockNumber(block))
+            .await?
+            .expect("block is not checked");
+        assert!(check.matches);
+        assert_eq!(
+            check.l1_root_hash,
+            local_root_hash(BlockNumber(block)).as_bytes()
+        );
+    }
+    assert!(storage
+        .chain()
+        .block_schema()
+        .load_block_root_mismatches()
+        .await?
+        .is_empty());
+    Ok(())
+}
+
+/// Checks that the block with a different root hash on L1 is recorded as a mismatch.
+#[db_test]
+async fn root_hash_mismatch(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    commit_blocks(&mut storage, 1..=3).await?;
+    let mut checker = checker(1..=3);
+    checker
+        .client
+        .roots
+        .insert(BlockNumber(2), H256::repeat_byte(0xff));
+
+    assert_eq!(checker.check_confirmed_blocks(&mut storage).await?, 1);
+    let check = storage
+        .chain()
+        .block_schema()
+        .get_block_root_check(BlockNumber(2))
+        .await?
+        .expect("block is not checked");
+    assert!(!check.matches);
+    assert_eq!(
+        storage
+            .chain()
+            .block_schema()
+            .load_block_root_mismatches()
+            .await?,
+        vec![BlockNumber(2)]
+    );
+    Ok(())
+}
+
+/// Checks that the provider failures are retried.
+#[db_test]
+async fn provider_failures_are_retried(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    commit_blocks(&mut storage, 1..=1).await?;
+    let checker = checker(1..=1);
+
+    // Provider recovers before the retries are exhausted.
+    checker
+        .client
+        .failures_left
+        .store(MAX_RETRIES - 1, Ordering::SeqCst);
+    assert_eq!(checker.check_confirmed_blocks(&mut storage).await?, 0);
+    assert_eq!(
+        storage
+            .chain()
+            .block_schema()
+            .get_last_checked_block_root()
+            .await?,
+        BlockNumber(1)
+    );
+
+    // Provider doesn't recover, the block stays unchecked.
+    commit_blocks(&mut storage, 2..=2).await?;
+    checker
+        .client
+        .failures_left
+        .store(MAX_RETRIES, Ordering::SeqCst);
+    assert!(checker.check_confirmed_blocks(&mut storage).await.is_err());
+    assert_eq!(
+        storage
+            .chain()
+            .block_schema()
+            .get_last_checked_block_root()
+            .await?,
+        BlockNumber(1)
+    );
+    Ok(())
+}
+
+/// Checks that the blocks confirmed while the checker was down or couldn't fetch the root hashes
+/// are checked on the next run.
+#[db_test]
+async fn backfill_after_gap(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    commit_blocks(&mut storage, 1..=2).await?;
+    let mut checker = checker(1..=3);
+    assert_eq!(checker.check_confirmed_blocks(&mut storage).await?, 0);
+
+    // Blocks are confirmed while the checker is down,
+    // and the commit of the block 4 isn't visible to the provider yet.
+    commit_blocks(&mut storage, 3..=5).await?;
+    assert!(checker.check_confirmed_blocks(&mut storage).await.is_err());
+    assert_eq!(
+        storage
+            .chain()
+            .block_schema()
+            .get_last_checked_block_root()
+            .await?,
+        BlockNumber(3)
+    );
+
+    // Once the commit is visible, the remaining blocks are checked.
+    for block in 4..=5 {
+        checker
+            .client
+            .roots
+            .insert(BlockNumber(block), local_root_hash(BlockNumber(block)));
+    }
+    assert_eq!(checker.check_confirmed_blocks(&mut storage).await?, 0);
+    for block in 1..=5 {
+        assert!(storage
+            .chain()
+            .block_schema()
+            .get_block_root_check(BlockNumber(block))
+            .await?
+            .is_some());
+    }
+    assert_eq!(
+        storage
+            .chain()
+            .block_schema()
+            .get_last_checked_block_root()
+            .await?,
+        BlockNumber(5)
+    );
+    Ok(())
+}
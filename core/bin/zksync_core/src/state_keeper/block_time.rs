@@ -0,0 +1,186 @@
+//! Controller of the seal timeout for the blocks that are not full.
+//!
+//! Full blocks (by chunks or by gas) are always sealed immediately. The rest of the blocks are sealed
+//! once the seal timeout expires, so the timeout is the only way to affect the average block time.
+//! The controller keeps track of the recent blocks and picks the timeout so that the average
+//! block time approaches the configured target, within the configured bounds.
+
+// Built-in uses
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+// External uses
+use tokio::sync::RwLock;
+// Workspace uses
+use zksync_api_types::BlockTimeStatus;
+use zksync_config::configs::chain::StateKeeper;
+
+/// Amount of the recent blocks used to estimate the average block time.
+const BLOCK_TIME_WINDOW: usize = 10;
+
+/// Controller shared between the state keeper and the admin API.
+pub type SharedBlockTimeController = Arc<RwLock<BlockTimeController>>;
+
+#[derive(Debug, Clone, Copy)]
+struct SealedBlockTime {
+    block_time: Duration,
+    /// Seal timeout in effect while the block was open.
+    seal_timeout: Duration,
+}
+
+#[derive(Debug)]
+pub struct BlockTimeController {
+    target: Option<Duration>,
+    miniblock_interval: Duration,
+    /// Seal timeout used while no target is set.
+    default_timeout: Duration,
+    min_timeout: Duration,
+    max_timeout: Duration,
+    effective_timeout: Duration,
+    recent_blocks: VecDeque<SealedBlockTime>,
+}
+
+impl BlockTimeController {
+    pub fn new(
+        target: Option<Duration>,
+        miniblock_interval: Duration,
+        default_iterations: usize,
+        min_timeout: Duration,
+        max_timeout: Duration,
+    ) -> Self {
+        assert!(
+            min_timeout <= max_timeout,
+            "Minimum block seal timeout {:?} exceeds the maximum {:?}",
+            min_timeout,
+            max_timeout
+        );
+        let default_timeout = miniblock_interval * default_iterations as u32;
+        let mut controller = Self {
+            target: None,
+            miniblock_interval,
+            default_timeout,
+            min_timeout,
+            max_timeout,
+            effective_timeout: default_timeout,
+            recent_blocks: VecDeque::with_capacity(BLOCK_TIME_WINDOW),
+        };
+        controller.set_target(target);
+        controller
+    }
+
+    pub fn from_config(config: &StateKeeper) -> Self {
+        Self::new(
+            config.block_time_target(),
+            config.miniblock_iteration_interval(),
+            config.miniblock_iterations as usize,
+            config.min_block_seal_timeout(),
+            config.max_block_seal_timeout(),
+        )
+    }
+
+    pub fn shared(self) -> SharedBlockTimeController {
+        Arc::new(RwLock::new(self))
+    }
+
+    /// Changes the target average block time. `None` restores the default seal timeout.
+    pub fn set_target(&mut self, target: Option<Duration>) {
+        self.target = target;
+        self.adjust();
+    }
+
+    pub fn target(&self) -> Option<Duration> {
+        self.target
+    }
+
+    /// Time after which the block that is not full is sealed.
+    pub fn effective_timeout(&self) -> Duration {
+        self.effective_timeout
+    }
+
+    /// Amount of miniblock iterations after which the block that is not full is sealed.
+    pub fn seal_iterations(&self) -> usize {
+        let interval = self.miniblock_interval.as_secs_f64();
+        if interval == 0.0 {
+            return 0;
+        }
+        (self.effective_timeout.as_secs_f64() / interval).ceil() as usize
+    }
+
+    /// Average time of the recent blocks, `None` if no blocks were sealed yet.
+    pub fn average_block_time(&self) -> Option<Duration> {
+        if self.recent_blocks.is_empty() {
+            return None;
+        }
+        let total: Duration = self
+            .recent_blocks
+            .iter()
+            .map(|block| block.block_time)
+            .sum();
+        Some(total / self.recent_blocks.len() as u32)
+    }
+
+    /// Records the time the sealed block was open for and adjusts the seal timeout.
+    pub fn record_block(&mut self, block_time: Duration) {
+        if self.recent_blocks.len() == BLOCK_TIME_WINDOW {
+            self.recent_blocks.pop_front();
+        }
+        self.recent_blocks.push_back(SealedBlockTime {
+            block_time,
+            seal_timeout: self.effective_timeout,
+        });
+        self.adjust();
+    }
+
+    pub fn status(&self) -> BlockTimeStatus {
+        BlockTimeStatus {
+            target_ms: self.target.map(|target| target.as_millis() as u64),
+            effective_timeout_ms: self.effective_timeout.as_millis() as u64,
+            average_block_time_ms: self
+                .average_block_time()
+                .map(|average| average.as_millis() as u64),
+        }
+    }
+
+    fn adjust(&mut self) {
+        self.effective_timeout = match self.target {
+            Some(target) => {
+                // Blocks sealed by the timeout take the whole timeout, while the full blocks are sealed earlier.
+                // Thus the average block time is roughly the timeout multiplied by the average utilization.
+                let utilization = self.average_utilization().max(f64::EPSILON);
+                let timeout = (target.as_secs_f64() / utilization)
+                    .min(self.max_timeout.as_secs_f64())
+                    .max(self.min_timeout.as_secs_f64());
+                Duration::from_secs_f64(timeout)
+            }
+            None => self.default_timeout,
+        };
+        self.report_metrics();
+    }
+
+    /// Average ratio of the block time to the seal timeout in effect, 1 if there are no blocks yet.
+    fn average_utilization(&self) -> f64 {
+        let ratios: Vec<f64> = self
+            .recent_blocks
+            .iter()
+            .filter(|block| block.seal_timeout > Duration::from_secs(0))
+            .map(|block| {
+                (block.block_time.as_secs_f64() / block.seal_timeout.as_secs_f64()).min(1.0)
+            })
+            .collect();
+        if ratios.is_empty() {
+            return 1.0;
+        }
+        ratios.iter().sum::<f64>() / ratios.len() as f64
+    }
+
+    fn report_metrics(&self) {
+        if let Some(target) = self.target {
+            metrics::gauge!("state_keeper.block_time.target", target.as_secs_f64());
+        }
+        metrics::gauge!(
+            "state_keeper.block_time.effective_timeout",
+            self.effective_timeout.as_secs_f64()
+        );
+        if let Some(average) = self.average_block_time() {
+            metrics::gauge!("state_keeper.block_time.average", average.as_secs_f64());
+        }
+    }
+}
@@ -1,15 +1,16 @@
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
 
 // External uses
 use futures::{
     channel::{mpsc, oneshot},
     stream::StreamExt,
-    SinkExt,
+    FutureExt, SinkExt,
 };
 use tokio::task::JoinHandle;
 use tokio::time;
 // Workspace uses
+use zksync_api_types::PendingBlockInfo;
 use zksync_mempool::{GetBlockRequest, MempoolBlocksRequest, ProposedBlock};
 use zksync_state::state::{OpSuccess, ZkSyncState};
 use zksync_types::{
@@ -19,15 +20,16 @@ use zksync_types::{
     },
     gas_counter::GasCounter,
     mempool::SignedTxVariant,
-    tx::ZkSyncTx,
+    tx::{TxHash, ZkSyncTx},
     Address, PriorityOp, SignedZkSyncTx,
 };
 // Local uses
 use self::{
+    block_time::SharedBlockTimeController,
     pending_block::PendingBlock,
     root_hash_calculator::{BlockRootHashJob, RootHashCalculator},
-    types::{ApplyOutcome, StateKeeperConfig},
-    utils::system_time_timestamp,
+    types::{ApplyOutcome, StateKeeperConfig, StateKeeperRequest},
+    utils::{current_utc_hour, system_time_timestamp},
 };
 use crate::{
     committer::{BlockCommitRequest, CommitRequest},
@@ -37,9 +39,13 @@ use crate::{
 pub use self::{
     init_params::ZkSyncStateInitParams,
     root_hash_calculator::{start_root_hash_calculator, BlockRootHashJobQueue},
-    types::StateKeeperTestkitRequest,
+    types::{
+        check_block_proving_keys, validate_block_chunk_sizes, OffPeakPolicy, StateKeeperRequest,
+        StateKeeperTestkitRequest,
+    },
 };
 
+pub mod block_time;
 mod init_params;
 mod pending_block;
 mod root_hash_calculator;
@@ -70,6 +76,15 @@ pub struct ZkSyncStateKeeper {
     /// Queue of reverted blocks
     /// They will be executed before the start of the StateKeeper
     reverted_blocks: VecDeque<IncompleteBlock>,
+
+    /// Controller of the seal timeout for the blocks that are not full.
+    /// If not set, such blocks are sealed after `max_miniblock_iterations`.
+    block_time_controller: Option<SharedBlockTimeController>,
+    /// Moment when the pending block was started.
+    pending_block_started_at: Instant,
+    /// Number of iterations the transactions submitted ahead of time were deferred for,
+    /// keyed by the hash of the transaction (or of the first transaction of the batch).
+    deferred_txs: HashMap<TxHash, usize>,
 }
 
 impl ZkSyncStateKeeper {
@@ -158,12 +173,26 @@ impl ZkSyncStateKeeper {
 
             root_hash_queue,
             reverted_blocks: initial_state.reverted_blocks.clone(),
+
+            block_time_controller: None,
+            pending_block_started_at: Instant::now(),
+            deferred_txs: HashMap::new(),
         };
         keeper.initialize(initial_state.pending_block);
 
         (keeper, root_hash_calculator)
     }
 
+    /// Makes the seal timeout of the blocks that are not full adjustable by the controller.
+    pub fn set_block_time_controller(&mut self, controller: SharedBlockTimeController) {
+        self.block_time_controller = Some(controller);
+    }
+
+    /// Makes the state keeper seal blocks using the off-peak sizes during the off-peak hours.
+    pub fn set_off_peak_policy(&mut self, policy: OffPeakPolicy) {
+        self.config.set_off_peak_policy(policy);
+    }
+
     // TODO (ZKS-821): We should get rid of this function and create state keeper in a ready-to-go state.
     // Currently we partially initialize state keeper, and then finalize initialization when it's actually started
     // which is not a good practice.
@@ -275,11 +304,27 @@ impl ZkSyncStateKeeper {
     }
 
     // Generate and execute new miniblock every miniblock_interval
-    async fn run(mut self, miniblock_interval: Duration) {
+    async fn run(
+        mut self,
+        miniblock_interval: Duration,
+        mut requests: mpsc::Receiver<StateKeeperRequest>,
+    ) {
         let mut timer = time::interval(miniblock_interval);
         loop {
             let start = Instant::now();
-            timer.tick().await;
+            // Requests are only served while waiting for the next miniblock, so they never delay
+            // the block production.
+            loop {
+                futures::select! {
+                    _ = timer.tick().fuse() => break,
+                    // Once the channel is closed, the branch is not polled anymore.
+                    request = requests.next() => {
+                        if let Some(request) = request {
+                            self.handle_request(request);
+                        }
+                    }
+                }
+            }
             // Report timings between two miniblocks.
             // If reported value stays at 0, most likely we have `miniblock_interval` variable too small and
             // spend more time in the loop iteration than this interval.
@@ -299,6 +344,36 @@ impl ZkSyncStateKeeper {
         }
     }
 
+    fn handle_request(&self, request: StateKeeperRequest) {
+        match request {
+            StateKeeperRequest::GetPendingBlockInfo(sender) => {
+                sender.send(self.pending_block_info()).unwrap_or_default();
+            }
+        }
+    }
+
+    /// Summarizes the block currently being filled.
+    fn pending_block_info(&self) -> PendingBlockInfo {
+        let first_tx_timestamp = if self.pending_block.success_operations.is_empty() {
+            // Timestamp of the empty block is renewed on every miniblock.
+            None
+        } else {
+            Some(self.pending_block.timestamp)
+        };
+
+        PendingBlockInfo {
+            block_number: self.pending_block.number,
+            chunks_used: self.config.max_block_size() - self.pending_block.chunks_left,
+            available_chunk_sizes: self
+                .config
+                .block_chunk_sizes_at(current_utc_hour())
+                .to_vec(),
+            success_txs: self.pending_block.success_operations.len(),
+            failed_txs: self.pending_block.failed_txs.len(),
+            first_tx_timestamp,
+        }
+    }
+
     async fn propose_new_block(&mut self, block_timestamp: u64) -> ProposedBlock {
         let start = Instant::now();
 
@@ -403,8 +478,26 @@ impl ZkSyncStateKeeper {
             }
         }
 
+        let mut deferred_txs = HashMap::new();
         let mut tx_queue = proposed_block.txs.into_iter().collect::<VecDeque<_>>();
         while let Some(variant) = tx_queue.pop_front() {
+            // Transactions submitted ahead of time stay in the mempool until the nonce gap is filled,
+            // but no longer than `max_nonce_gap_deferrals` iterations.
+            if self.is_nonce_ahead(&variant.get_transactions()) {
+                let tx_hash = variant.hashes()[0];
+                let deferrals = self.deferred_txs.get(&tx_hash).copied().unwrap_or_default() + 1;
+                if deferrals <= self.config.max_nonce_gap_deferrals {
+                    deferred_txs.insert(tx_hash, deferrals);
+                    continue;
+                }
+                // The gap wasn't filled in time, the transaction is executed to be rejected.
+                vlog::warn!(
+                    "Transaction {} waited for the preceding nonces for too long and is rejected",
+                    tx_hash.to_string()
+                );
+                metrics::increment_counter!("state_keeper.expired_deferred_txs");
+            }
+
             match &variant {
                 SignedTxVariant::Tx(tx) => {
                     match self.apply_tx(tx) {
@@ -439,6 +532,12 @@ impl ZkSyncStateKeeper {
             }
         }
 
+        if !deferred_txs.is_empty() {
+            metrics::counter!("state_keeper.deferred_txs", deferred_txs.len() as u64);
+        }
+        // Only the transactions deferred in this iteration are tracked, so the ones that left the mempool are forgotten.
+        self.deferred_txs = deferred_txs;
+
         // TODO (ZKS-821): We can store events in `committer` (as it's already responsible for applying results of
         // the block execution), there is no need in additional actor for that.
         if !executed_ops.is_empty() {
@@ -454,12 +553,7 @@ impl ZkSyncStateKeeper {
         // Iteration is complete, increment it in the pending block.
         self.pending_block.increment_iteration();
 
-        // If pending block contains withdrawals we seal it faster
-        let max_miniblock_iterations = if self.pending_block.fast_processing_required {
-            self.config.fast_miniblock_iterations
-        } else {
-            self.config.max_miniblock_iterations
-        };
+        let max_miniblock_iterations = self.max_miniblock_iterations().await;
 
         // Check whether we should seal this block and start processing the next one, or we just need
         // to persist the pending block.
@@ -479,6 +573,32 @@ impl ZkSyncStateKeeper {
     }
 
     // Err if there is no space in current block
+    /// Checks whether any of the transactions has a nonce greater than the current nonce of the account,
+    /// i.e. some of the preceding transactions of the account were not executed yet.
+    /// Transactions of the same account are expected to be ordered by nonce.
+    fn is_nonce_ahead(&self, txs: &[SignedZkSyncTx]) -> bool {
+        let mut expected_nonces = HashMap::new();
+        for tx in txs {
+            let account_id = match tx.tx.account_id() {
+                Ok(account_id) => account_id,
+                Err(_) => continue,
+            };
+            let expected_nonce = match expected_nonces.get(&account_id) {
+                Some(nonce) => *nonce,
+                // Transactions of the unknown accounts will fail anyway.
+                None => match self.state.get_account(account_id) {
+                    Some(account) => account.nonce,
+                    None => continue,
+                },
+            };
+            if tx.tx.nonce() > expected_nonce {
+                return true;
+            }
+            expected_nonces.insert(account_id, tx.tx.nonce() + 1);
+        }
+        false
+    }
+
     fn apply_priority_op(&mut self, priority_op: &PriorityOp) -> ApplyOutcome<ExecutedOperations> {
         let start = Instant::now();
         let chunks_needed = priority_op.data.chunks();
@@ -737,10 +857,35 @@ impl ZkSyncStateKeeper {
         ApplyOutcome::Included(exec_result)
     }
 
+    /// Returns the amount of miniblock iterations after which the pending block is sealed even if it's not full.
+    async fn max_miniblock_iterations(&self) -> usize {
+        let fast_processing_required = self.pending_block.fast_processing_required;
+        match &self.block_time_controller {
+            // Fast processing must not be slowed down by the controller.
+            Some(controller) => {
+                let iterations = controller.read().await.seal_iterations();
+                if fast_processing_required {
+                    iterations.min(self.config.fast_miniblock_iterations)
+                } else {
+                    iterations
+                }
+            }
+            // If pending block contains withdrawals we seal it faster
+            None if fast_processing_required => self.config.fast_miniblock_iterations,
+            None => self.config.max_miniblock_iterations,
+        }
+    }
+
     /// Finalizes the pending block, transforming it into a full block.
     async fn seal_pending_block(&mut self) {
         let start = Instant::now();
 
+        let block_time = self.pending_block_started_at.elapsed();
+        self.pending_block_started_at = Instant::now();
+        if let Some(controller) = &self.block_time_controller {
+            controller.write().await.record_block(block_time);
+        }
+
         // Before sealing the block, we need to store the pending block in order to
         // save all the new transactions to the database.
         // Even though it will be removed once the seal request is processed, having a consistent
@@ -783,6 +928,10 @@ impl ZkSyncStateKeeper {
         let commit_gas_limit = self.pending_block.gas_counter.commit_gas_limit();
         let verify_gas_limit = self.pending_block.gas_counter.verify_gas_limit();
 
+        let chunks_used = self.config.max_block_size() - self.pending_block.chunks_left;
+        let block_chunk_sizes = self
+            .config
+            .block_chunk_sizes_for_seal(current_utc_hour(), chunks_used);
         let block = IncompleteBlock::new_from_available_block_sizes(
             self.pending_block.number,
             self.config.fee_account_id,
@@ -791,7 +940,7 @@ impl ZkSyncStateKeeper {
                 self.pending_block.unprocessed_priority_op_before,
                 self.pending_block.unprocessed_priority_op_current,
             ),
-            &self.config.available_block_chunk_sizes,
+            block_chunk_sizes,
             commit_gas_limit,
             verify_gas_limit,
             self.pending_block.timestamp,
@@ -815,6 +964,10 @@ impl ZkSyncStateKeeper {
             block.elapsed(),
             "stage" => "seal"
         );
+        metrics::increment_counter!(
+            "state_keeper.sealed_block_size",
+            "block_size" => block.block_chunks_size.to_string()
+        );
 
         let block_commit_request = BlockCommitRequest {
             block,
@@ -889,6 +1042,10 @@ impl ZkSyncStateKeeper {
 }
 
 #[must_use]
-pub fn start_state_keeper(sk: ZkSyncStateKeeper, miniblock_interval: Duration) -> JoinHandle<()> {
-    tokio::spawn(sk.run(miniblock_interval))
+pub fn start_state_keeper(
+    sk: ZkSyncStateKeeper,
+    miniblock_interval: Duration,
+    requests: mpsc::Receiver<StateKeeperRequest>,
+) -> JoinHandle<()> {
+    tokio::spawn(sk.run(miniblock_interval, requests))
 }
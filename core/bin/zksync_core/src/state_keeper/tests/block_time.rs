@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use super::utils::*;
+use crate::state_keeper::block_time::BlockTimeController;
+
+const MINIBLOCK_INTERVAL: Duration = Duration::from_millis(100);
+const MIN_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_TIMEOUT: Duration = Duration::from_secs(600);
+const TARGET: Duration = Duration::from_secs(120);
+
+fn controller(target: Option<Duration>) -> BlockTimeController {
+    BlockTimeController::new(target, MINIBLOCK_INTERVAL, 300, MIN_TIMEOUT, MAX_TIMEOUT)
+}
+
+/// Seals `count` blocks, each of which gets full after `fill_time` unless the seal timeout expires earlier.
+fn seal_blocks(controller: &mut BlockTimeController, fill_time: Duration, count: usize) {
+    for _ in 0..count {
+        let block_time = fill_time.min(controller.effective_timeout());
+        controller.record_block(block_time);
+        assert!(controller.effective_timeout() >= MIN_TIMEOUT);
+        assert!(controller.effective_timeout() <= MAX_TIMEOUT);
+    }
+}
+
+fn assert_close(actual: Duration, expected: Duration) {
+    let diff = actual.as_secs_f64() - expected.as_secs_f64();
+    assert!(
+        diff.abs() <= expected.as_secs_f64() * 0.05,
+        "{:?} is too far from {:?}",
+        actual,
+        expected
+    );
+}
+
+/// Checks that without the target the default seal timeout is used.
+#[test]
+fn default_timeout() {
+    let mut controller = controller(None);
+    assert_eq!(controller.effective_timeout(), Duration::from_secs(30));
+    assert_eq!(controller.seal_iterations(), 300);
+
+    seal_blocks(&mut controller, Duration::from_secs(1), 20);
+    assert_eq!(controller.effective_timeout(), Duration::from_secs(30));
+    assert_eq!(controller.status().target_ms, None);
+    assert_eq!(controller.status().average_block_time_ms, Some(1_000));
+}
+
+/// Checks that in the low traffic, when all the blocks are sealed by the timeout,
+/// the average block time converges to the target.
+#[test]
+fn low_traffic_converges_to_target() {
+    let mut controller = controller(Some(TARGET));
+    seal_blocks(&mut controller, Duration::from_secs(3_600), 20);
+
+    assert_close(controller.effective_timeout(), TARGET);
+    assert_close(controller.average_block_time().unwrap(), TARGET);
+    assert_eq!(controller.seal_iterations(), 1_200);
+}
+
+/// Checks that in the high traffic the controller stretches the timeout to reach the target
+/// while some blocks are sealed by the timeout, and never exceeds the upper bound.
+#[test]
+fn high_traffic_converges_within_bounds() {
+    let mut controller = controller(Some(TARGET));
+
+    // Half of the blocks get full after a minute, the others would take 10 minutes.
+    for _ in 0..20 {
+        seal_blocks(&mut controller, Duration::from_secs(60), 1);
+        seal_blocks(&mut controller, Duration::from_secs(600), 1);
+    }
+    assert_close(controller.average_block_time().unwrap(), TARGET);
+
+    // Blocks get full faster than the target, so the timeout is stretched to the upper bound.
+    seal_blocks(&mut controller, Duration::from_secs(30), 20);
+    assert_eq!(controller.effective_timeout(), MAX_TIMEOUT);
+    assert_eq!(
+        controller.average_block_time(),
+        Some(Duration::from_secs(30))
+    );
+}
+
+/// Checks that the target can be changed at runtime and is respected within the bounds.
+#[test]
+fn target_change() {
+    let mut controller = controller(Some(TARGET));
+    seal_blocks(&mut controller, Duration::from_secs(3_600), 20);
+
+    controller.set_target(Some(Duration::from_secs(1)));
+    assert_eq!(controller.effective_timeout(), MIN_TIMEOUT);
+    seal_blocks(&mut controller, Duration::from_secs(3_600), 20);
+    assert_eq!(controller.effective_timeout(), MIN_TIMEOUT);
+
+    controller.set_target(Some(Duration::from_secs(60)));
+    seal_blocks(&mut controller, Duration::from_secs(3_600), 20);
+    assert_close(
+        controller.average_block_time().unwrap(),
+        Duration::from_secs(60),
+    );
+
+    controller.set_target(None);
+    assert_eq!(controller.effective_timeout(), Duration::from_secs(30));
+}
+
+/// Checks that the full block is sealed immediately, even if the seal timeout is long.
+#[tokio::test]
+async fn full_block_is_not_delayed() {
+    let mut tester = StateKeeperTester::new(8, 3, 3);
+    let controller = controller(Some(MAX_TIMEOUT)).shared();
+    tester
+        .state_keeper
+        .set_block_time_controller(controller.clone());
+
+    apply_batch_with_two_transfers(&mut tester).await;
+    tester.assert_pending().await;
+    apply_batch_with_two_transfers(&mut tester).await;
+    tester.assert_sealed().await;
+
+    let controller = controller.read().await;
+    assert_eq!(controller.status().target_ms, Some(600_000));
+    assert!(controller.average_block_time().unwrap() < MAX_TIMEOUT);
+}
@@ -565,3 +565,149 @@ async fn transfers_with_different_timestamps() {
         })
         .await;
 }
+
+/// Checks that transactions submitted ahead of time are not executed until the nonce gap is filled.
+#[tokio::test]
+async fn nonces_ahead_are_deferred() {
+    let mut tester = StateKeeperTester::new(20, 5, 5);
+
+    let token_id = TokenId(0);
+    let account_from_id = AccountId(1);
+    let account_to_id = AccountId(2);
+    let (account_from, sk_from) = tester.add_account(account_from_id);
+    let (account_to, _sk_to) = tester.add_account(account_to_id);
+    tester.set_balance(account_from_id, token_id, 999u32);
+
+    let transfer = |nonce: u32| {
+        let transfer = Transfer::new_signed(
+            account_from_id,
+            account_from.address,
+            account_to.address,
+            token_id,
+            100u32.into(),
+            0u32.into(),
+            Nonce(nonce),
+            Default::default(),
+            &sk_from,
+        )
+        .unwrap();
+        SignedZkSyncTx {
+            tx: ZkSyncTx::Transfer(Box::new(transfer)),
+            eth_sign_data: None,
+            created_at: Utc::now(),
+        }
+    };
+    let transfers: Vec<_> = (0..3).map(transfer).collect();
+    let executed_hashes = |block: &zksync_types::block::PendingBlock| -> Vec<_> {
+        block
+            .success_operations
+            .iter()
+            .map(|op| op.get_executed_tx().unwrap().signed_tx.hash())
+            .collect()
+    };
+
+    // Transaction with nonce 2 is deferred since nonce 1 is missing.
+    let proposed_block = ProposedBlock {
+        txs: vec![
+            SignedTxVariant::Tx(transfers[0].clone()),
+            SignedTxVariant::Tx(transfers[2].clone()),
+        ],
+        priority_ops: vec![],
+    };
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+    tester
+        .assert_pending_with(|block| {
+            assert_eq!(executed_hashes(&block), vec![transfers[0].hash()]);
+            assert!(block.failed_txs.is_empty());
+        })
+        .await;
+
+    // Once the gap is filled, the transactions are executed in order.
+    // Only the diff between the pending block updates is sent.
+    let proposed_block = ProposedBlock {
+        txs: vec![
+            SignedTxVariant::Tx(transfers[1].clone()),
+            SignedTxVariant::Tx(transfers[2].clone()),
+        ],
+        priority_ops: vec![],
+    };
+    tester
+        .state_keeper
+        .execute_proposed_block(proposed_block)
+        .await;
+    tester
+        .assert_pending_with(|block| {
+            assert_eq!(
+                executed_hashes(&block),
+                vec![transfers[1].hash(), transfers[2].hash()]
+            );
+            assert!(block.failed_txs.is_empty());
+        })
+        .await;
+}
+
+/// Checks that the transaction waiting for the nonce gap to be filled for too long is rejected.
+#[tokio::test]
+async fn nonce_gap_deferral_is_bounded() {
+    let mut tester = StateKeeperTester::new(20, 5, 5);
+    tester.state_keeper.config.max_nonce_gap_deferrals = 2;
+
+    let token_id = TokenId(0);
+    let account_from_id = AccountId(1);
+    let account_to_id = AccountId(2);
+    let (account_from, sk_from) = tester.add_account(account_from_id);
+    let (account_to, _sk_to) = tester.add_account(account_to_id);
+    tester.set_balance(account_from_id, token_id, 999u32);
+
+    let transfer = Transfer::new_signed(
+        account_from_id,
+        account_from.address,
+        account_to.address,
+        token_id,
+        100u32.into(),
+        0u32.into(),
+        Nonce(1),
+        Default::default(),
+        &sk_from,
+    )
+    .unwrap();
+    let transfer = SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(transfer)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    };
+
+    for iteration in 1..=3 {
+        let proposed_block = ProposedBlock {
+            txs: vec![SignedTxVariant::Tx(transfer.clone())],
+            priority_ops: vec![],
+        };
+        tester
+            .state_keeper
+            .execute_proposed_block(proposed_block)
+            .await;
+
+        if iteration <= 2 {
+            // Nonce 0 is still missing, the transaction is deferred.
+            assert!(tester.state_keeper.pending_block.failed_txs.is_empty());
+            assert_eq!(
+                tester.state_keeper.deferred_txs.get(&transfer.hash()),
+                Some(&iteration)
+            );
+        }
+    }
+
+    // The transaction is rejected after the allowed number of deferrals.
+    let failed_txs = &tester.state_keeper.pending_block.failed_txs;
+    assert_eq!(failed_txs.len(), 1);
+    assert_eq!(failed_txs[0].signed_tx.hash(), transfer.hash());
+    assert!(tester
+        .state_keeper
+        .pending_block
+        .success_operations
+        .is_empty());
+    assert!(tester.state_keeper.deferred_txs.is_empty());
+}
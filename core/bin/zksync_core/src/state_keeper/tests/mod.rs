@@ -4,6 +4,7 @@ use zksync_types::{AccountId, H160, *};
 
 mod apply_priority_op;
 mod apply_tx;
+mod block_time;
 mod execute_proposed_block;
 mod gas_limit;
 mod pending_block;
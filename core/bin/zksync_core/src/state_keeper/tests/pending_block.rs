@@ -1,10 +1,17 @@
 use chrono::Utc;
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
 use num::BigUint;
+use std::time::Duration;
+use zksync_api_types::PendingBlockInfo;
+use zksync_mempool::{MempoolBlocksRequest, ProposedBlock};
 use zksync_types::tx::TimeRange;
-use zksync_types::{AccountId, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx};
+use zksync_types::{AccountId, BlockNumber, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx};
 
 use super::utils::*;
-use crate::state_keeper::utils::system_time_timestamp;
+use crate::state_keeper::{utils::system_time_timestamp, OffPeakPolicy, StateKeeperRequest};
 
 /// Checks if block sealing is done correctly by sealing a block
 /// with 1 priority_op, 1 succeeded tx, 1 failed tx.
@@ -71,6 +78,80 @@ async fn seal_pending_block() {
     );
 }
 
+/// Checks that the sealed block gets the smallest of the configured sizes
+/// that fits all the executed operations.
+#[tokio::test]
+async fn seal_pending_block_with_custom_chunk_sizes() {
+    // Each deposit takes 6 chunks.
+    let test_vector = vec![(1, 6), (2, 12), (3, 20)];
+
+    for (deposits_count, expected_block_size) in test_vector {
+        let mut tester = StateKeeperTester::with_chunk_sizes(vec![6, 12, 20], 3, 3);
+        for _ in 0..deposits_count {
+            let deposit = create_deposit(TokenId(0), 12u32);
+            assert!(tester
+                .state_keeper
+                .apply_priority_op(&deposit)
+                .is_included());
+        }
+        tester.state_keeper.seal_pending_block().await;
+
+        let (block, _) = tester.unwrap_sealed_update().await;
+        assert_eq!(
+            block.block.block_chunks_size, expected_block_size,
+            "Unexpected block size for {} deposits",
+            deposits_count
+        );
+    }
+
+    // Operations that don't fit into the biggest configured block are not included.
+    let mut tester = StateKeeperTester::with_chunk_sizes(vec![6, 12, 20], 3, 3);
+    for _ in 0..3 {
+        let deposit = create_deposit(TokenId(0), 12u32);
+        assert!(tester
+            .state_keeper
+            .apply_priority_op(&deposit)
+            .is_included());
+    }
+    let deposit = create_deposit(TokenId(0), 12u32);
+    assert!(tester
+        .state_keeper
+        .apply_priority_op(&deposit)
+        .is_not_included());
+}
+
+/// Checks that during the off-peak window blocks are sealed using the off-peak sizes
+/// and fall back to the main sizes if they don't fit.
+#[tokio::test]
+async fn seal_pending_block_off_peak() {
+    // Each deposit takes 6 chunks. The window covers the whole day.
+    let test_vector = vec![(1, 6), (2, 12), (3, 20)];
+
+    for (deposits_count, expected_block_size) in test_vector {
+        let mut tester = StateKeeperTester::with_chunk_sizes(vec![12, 20], 3, 3);
+        tester.state_keeper.set_off_peak_policy(OffPeakPolicy {
+            block_chunk_sizes: vec![6, 12],
+            start_hour: 0,
+            end_hour: 24,
+        });
+        for _ in 0..deposits_count {
+            let deposit = create_deposit(TokenId(0), 12u32);
+            assert!(tester
+                .state_keeper
+                .apply_priority_op(&deposit)
+                .is_included());
+        }
+        tester.state_keeper.seal_pending_block().await;
+
+        let (block, _) = tester.unwrap_sealed_update().await;
+        assert_eq!(
+            block.block.block_chunks_size, expected_block_size,
+            "Unexpected block size for {} deposits",
+            deposits_count
+        );
+    }
+}
+
 /// Checks if block storing is done correctly by storing a block
 /// with 1 priority_op, 1 succeeded tx, 1 failed tx
 #[tokio::test]
@@ -254,3 +335,85 @@ async fn correctly_restore_pending_block_timestamp() {
         "Tx with the same valid_until as for previous transaction should've been processed"
     );
 }
+
+/// Starts the state keeper and requests the pending block info through its channel,
+/// the same way the private API does.
+async fn request_pending_block_info(tester: StateKeeperTester) -> PendingBlockInfo {
+    let StateKeeperTester {
+        state_keeper,
+        response_rx: _response_rx,
+        mut mempool_rx,
+        ..
+    } = tester;
+    let (mut requests_sender, requests) = mpsc::channel(1);
+    tokio::spawn(state_keeper.run(Duration::from_secs(3600), requests));
+
+    // The first miniblock is proposed right away, the mempool has nothing to add.
+    let MempoolBlocksRequest::GetBlock(request) = mempool_rx.next().await.unwrap();
+    request
+        .response_sender
+        .send(ProposedBlock::new())
+        .unwrap_or_default();
+
+    // The request is served while the state keeper waits for the next miniblock.
+    let (sender, receiver) = oneshot::channel();
+    requests_sender
+        .send(StateKeeperRequest::GetPendingBlockInfo(sender))
+        .await
+        .unwrap();
+    receiver.await.expect("State keeper should respond")
+}
+
+/// Checks that the pending block info reflects the operations executed in the pending block.
+#[tokio::test]
+async fn pending_block_info() {
+    let tester = StateKeeperTester::with_chunk_sizes(vec![10, 20], 3, 3);
+    let info = request_pending_block_info(tester).await;
+    assert_eq!(
+        info,
+        PendingBlockInfo {
+            block_number: BlockNumber(1),
+            chunks_used: 0,
+            available_chunk_sizes: vec![10, 20],
+            success_txs: 0,
+            failed_txs: 0,
+            first_tx_timestamp: None,
+        }
+    );
+
+    let mut tester = StateKeeperTester::with_chunk_sizes(vec![10, 20], 3, 3);
+    let good_withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(0),
+        AccountId(1),
+        200u32,
+        145u32,
+        Default::default(),
+    );
+    let bad_withdraw = create_account_and_withdrawal(
+        &mut tester,
+        TokenId(2),
+        AccountId(2),
+        100u32,
+        145u32,
+        Default::default(),
+    );
+    let deposit = create_deposit(TokenId(0), 12u32);
+    assert!(tester.state_keeper.apply_tx(&good_withdraw).is_included());
+    assert!(tester.state_keeper.apply_tx(&bad_withdraw).is_included());
+    assert!(tester
+        .state_keeper
+        .apply_priority_op(&deposit)
+        .is_included());
+    let chunks_left = tester.state_keeper.pending_block.chunks_left;
+    let timestamp = tester.state_keeper.pending_block.timestamp;
+
+    // The empty miniblock executed by the running state keeper doesn't change the pending block.
+    let info = request_pending_block_info(tester).await;
+    assert_eq!(info.block_number, BlockNumber(1));
+    assert_eq!(info.chunks_used, 20 - chunks_left);
+    assert!(info.chunks_used > 0);
+    assert_eq!(info.success_txs, 2);
+    assert_eq!(info.failed_txs, 1);
+    assert_eq!(info.first_tx_timestamp, Some(timestamp));
+}
@@ -8,7 +8,7 @@ use zksync_crypto::{
     rand::{Rng, SeedableRng, XorShiftRng},
     PrivateKey,
 };
-use zksync_mempool::ProposedBlock;
+use zksync_mempool::{MempoolBlocksRequest, ProposedBlock};
 use zksync_types::block::{IncompleteBlock, PendingBlock};
 use zksync_types::tx::TimeRange;
 use zksync_types::{
@@ -18,14 +18,23 @@ use zksync_types::{
 pub struct StateKeeperTester {
     pub state_keeper: ZkSyncStateKeeper,
     pub response_rx: mpsc::Receiver<CommitRequest>,
+    pub mempool_rx: mpsc::Receiver<MempoolBlocksRequest>,
     pub fee_collector: AccountId,
 }
 
 impl StateKeeperTester {
     pub fn new(available_chunk_size: usize, max_iterations: usize, fast_iterations: usize) -> Self {
+        Self::with_chunk_sizes(vec![available_chunk_size], max_iterations, fast_iterations)
+    }
+
+    pub fn with_chunk_sizes(
+        available_chunk_sizes: Vec<usize>,
+        max_iterations: usize,
+        fast_iterations: usize,
+    ) -> Self {
         const CHANNEL_SIZE: usize = 32768;
         let (events_sender, _events_receiver) = mpsc::channel(CHANNEL_SIZE);
-        let (request_tx, _request_rx) = mpsc::channel(CHANNEL_SIZE);
+        let (request_tx, mempool_rx) = mpsc::channel(CHANNEL_SIZE);
         let (response_tx, response_rx) = mpsc::channel(CHANNEL_SIZE);
 
         let fee_collector = Account::default_with_address(&H160::random());
@@ -40,7 +49,7 @@ impl StateKeeperTester {
             fee_collector.address,
             response_tx,
             request_tx,
-            vec![available_chunk_size],
+            available_chunk_sizes,
             max_iterations,
             fast_iterations,
             events_sender,
@@ -49,6 +58,7 @@ impl StateKeeperTester {
         Self {
             state_keeper,
             response_rx,
+            mempool_rx,
             fee_collector: AccountId(0),
         }
     }
@@ -304,6 +314,8 @@ pub fn create_deposit(token: TokenId, amount: impl Into<BigUint>) -> PriorityOp
         eth_hash: H256::zero(),
         eth_block: 0,
         eth_block_index: None,
+        eth_block_hash: None,
+        l1_surplus: None,
     }
 }
 
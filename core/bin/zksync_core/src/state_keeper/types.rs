@@ -1,8 +1,15 @@
+// Built-in uses
+use std::collections::HashMap;
 // External uses
 use futures::channel::oneshot;
 use itertools::Itertools;
 // Workspace uses
+use zksync_api_types::PendingBlockInfo;
+use zksync_config::configs::chain::{Circuit, StateKeeper};
 use zksync_mempool::ProposedBlock;
+use zksync_prover_utils::fs_utils::{
+    block_verification_key_path, get_universal_setup_monomial_file_path,
+};
 use zksync_types::{Account, AccountId, Address};
 // Local uses
 use crate::state_keeper::init_params::ZkSyncStateInitParams;
@@ -17,6 +24,12 @@ pub enum StateKeeperTestkitRequest {
     GetCurrentState(oneshot::Sender<ZkSyncStateInitParams>),
 }
 
+/// Requests served by the state keeper in between the miniblocks.
+#[derive(Debug)]
+pub enum StateKeeperRequest {
+    GetPendingBlockInfo(oneshot::Sender<PendingBlockInfo>),
+}
+
 #[derive(Debug)]
 pub enum ApplyOutcome<T> {
     Included(T),
@@ -41,6 +54,10 @@ impl<T> ApplyOutcome<T> {
     }
 }
 
+/// Number of miniblock iterations a transaction may wait for the preceding nonces of its account
+/// (10 minutes with the default 50ms miniblock interval).
+const DEFAULT_MAX_NONCE_GAP_DEFERRALS: usize = 12_000;
+
 /// Constant configuration parameters needed by state keeper to work.
 #[derive(Debug)]
 pub(super) struct StateKeeperConfig {
@@ -48,6 +65,10 @@ pub(super) struct StateKeeperConfig {
     pub(super) available_block_chunk_sizes: Vec<usize>,
     pub(super) max_miniblock_iterations: usize,
     pub(super) fast_miniblock_iterations: usize,
+    pub(super) off_peak_policy: Option<OffPeakPolicy>,
+    /// Number of iterations a transaction submitted ahead of time is deferred for. Once the nonce gap
+    /// isn't filled in time, the transaction is executed and rejected with the nonce mismatch.
+    pub(super) max_nonce_gap_deferrals: usize,
     max_block_size: usize,
 }
 
@@ -59,19 +80,9 @@ impl StateKeeperConfig {
         fast_miniblock_iterations: usize,
     ) -> Self {
         // Ensure that available block chunk sizes are sorted and not empty.
-        assert!(
-            !available_block_chunk_sizes.is_empty(),
-            "Block chunk sizes are empty"
-        );
-        let is_sorted = available_block_chunk_sizes
-            .iter()
-            .tuple_windows()
-            .all(|(a, b)| a < b);
-        assert!(
-            is_sorted,
-            "Block chunk sizes are not in order: {:?}",
-            available_block_chunk_sizes
-        );
+        if let Err(err) = validate_block_chunk_sizes(&available_block_chunk_sizes, None) {
+            panic!("{}", err);
+        }
 
         // Maximum size that block can have.
         let max_block_size = *available_block_chunk_sizes.iter().max().unwrap();
@@ -81,13 +92,166 @@ impl StateKeeperConfig {
             available_block_chunk_sizes,
             max_miniblock_iterations,
             fast_miniblock_iterations,
+            off_peak_policy: None,
+            max_nonce_gap_deferrals: DEFAULT_MAX_NONCE_GAP_DEFERRALS,
             max_block_size,
         }
     }
 
+    pub(super) fn set_off_peak_policy(&mut self, policy: OffPeakPolicy) {
+        // Pending block capacity is determined by the main sizes, so the off-peak sizes can't exceed them.
+        if let Err(err) = validate_block_chunk_sizes(&policy.block_chunk_sizes, None) {
+            panic!("{}", err);
+        }
+        let off_peak_max_block_size = *policy.block_chunk_sizes.last().unwrap();
+        assert!(
+            off_peak_max_block_size <= self.max_block_size,
+            "Off-peak block size {} exceeds the maximum block size {}",
+            off_peak_max_block_size,
+            self.max_block_size
+        );
+        self.off_peak_policy = Some(policy);
+    }
+
     pub(super) fn max_block_size(&self) -> usize {
         self.max_block_size
     }
+
+    /// Returns the block sizes to be used at the given hour of the day (UTC).
+    pub(super) fn block_chunk_sizes_at(&self, hour: u32) -> &[usize] {
+        match &self.off_peak_policy {
+            Some(policy) if policy.is_active(hour) => &policy.block_chunk_sizes,
+            _ => &self.available_block_chunk_sizes,
+        }
+    }
+
+    /// Returns the block sizes to choose from when sealing a block with `chunks_used` chunks
+    /// at the given hour of the day (UTC). Blocks that don't fit into the off-peak sizes
+    /// fall back to the main sizes.
+    pub(super) fn block_chunk_sizes_for_seal(&self, hour: u32, chunks_used: usize) -> &[usize] {
+        let sizes = self.block_chunk_sizes_at(hour);
+        if sizes
+            .last()
+            .map_or(false, |&max_size| max_size >= chunks_used)
+        {
+            sizes
+        } else {
+            &self.available_block_chunk_sizes
+        }
+    }
+}
+
+/// Time-of-day window during which the state keeper seals blocks using a dedicated set of sizes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffPeakPolicy {
+    pub block_chunk_sizes: Vec<usize>,
+    /// Hour of the day (UTC) when the window starts, inclusive.
+    pub start_hour: u32,
+    /// Hour of the day (UTC) when the window ends, exclusive. Wraps around midnight if less than `start_hour`.
+    pub end_hour: u32,
+}
+
+impl OffPeakPolicy {
+    /// Creates the policy from the state keeper config. Returns `None` if the off-peak sizes are not configured.
+    pub fn from_config(config: &StateKeeper) -> anyhow::Result<Option<Self>> {
+        let block_chunk_sizes = match &config.off_peak_block_chunk_sizes {
+            Some(sizes) => sizes.clone(),
+            None => return Ok(None),
+        };
+        let (start_hour, end_hour) = match (config.off_peak_start_hour, config.off_peak_end_hour) {
+            (Some(start_hour), Some(end_hour)) => (start_hour, end_hour),
+            _ => {
+                anyhow::bail!("Off-peak block chunk sizes are set, but the off-peak hours are not")
+            }
+        };
+        anyhow::ensure!(
+            start_hour < 24 && end_hour <= 24,
+            "Off-peak hours are out of range: {}..{}",
+            start_hour,
+            end_hour
+        );
+
+        Ok(Some(Self {
+            block_chunk_sizes,
+            start_hour,
+            end_hour,
+        }))
+    }
+
+    pub fn is_active(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Checks that the block sizes to be generated by the state keeper are not empty,
+/// strictly increasing and (if the circuit configuration is provided) supported by the circuit.
+pub fn validate_block_chunk_sizes(
+    block_chunk_sizes: &[usize],
+    circuit: Option<&Circuit>,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(!block_chunk_sizes.is_empty(), "Block chunk sizes are empty");
+
+    let is_sorted = block_chunk_sizes.iter().tuple_windows().all(|(a, b)| a < b);
+    anyhow::ensure!(
+        is_sorted,
+        "Block chunk sizes are not in order: {:?}",
+        block_chunk_sizes
+    );
+
+    if let Some(circuit) = circuit {
+        let unsupported: Vec<_> = block_chunk_sizes
+            .iter()
+            .filter(|size| !circuit.supported_block_chunks_sizes.contains(size))
+            .collect();
+        anyhow::ensure!(
+            unsupported.is_empty(),
+            "Block chunk sizes {:?} are not supported by the circuit, supported sizes: {:?}",
+            unsupported,
+            circuit.supported_block_chunks_sizes
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that everything needed to prove blocks of every size to be generated is in place:
+/// the verification key and the universal setup the proving key is derived from.
+/// Otherwise sealed blocks will never be proven.
+pub fn check_block_proving_keys(
+    block_chunk_sizes: &[usize],
+    circuit: &Circuit,
+) -> anyhow::Result<()> {
+    let setup_powers: HashMap<_, _> = circuit
+        .supported_block_chunks_sizes_with_setup_pow()
+        .into_iter()
+        .collect();
+
+    let mut missing_keys = Vec::new();
+    for &size in block_chunk_sizes {
+        let verification_key = block_verification_key_path(&circuit.key_dir, size);
+        if !verification_key.exists() {
+            missing_keys.push(verification_key);
+        }
+
+        let setup_power = setup_powers.get(&size).ok_or_else(|| {
+            anyhow::anyhow!("Setup power is not configured for block size {}", size)
+        })?;
+        let universal_setup = get_universal_setup_monomial_file_path(*setup_power)?;
+        if !universal_setup.exists() {
+            missing_keys.push(universal_setup);
+        }
+    }
+    anyhow::ensure!(
+        missing_keys.is_empty(),
+        "Proving keys are missing for the configured block sizes: {:?}",
+        missing_keys
+    );
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -109,6 +273,134 @@ mod tests {
         let _config = StateKeeperConfig::new(AccountId(0), incorrect_chunks, 10, 20);
     }
 
+    /// Checks that block sizes that are not supported by the circuit are rejected.
+    #[test]
+    fn validate_chunks_against_circuit() {
+        let circuit = Circuit {
+            key_dir: "keys".into(),
+            supported_block_chunks_sizes: vec![6, 30, 74],
+            supported_block_chunks_sizes_setup_powers: vec![21, 22, 23],
+            supported_aggregated_proof_sizes: vec![1],
+            supported_aggregated_proof_sizes_setup_power2: vec![22],
+            account_tree_depth: 32,
+            balance_tree_depth: 11,
+        };
+
+        assert!(validate_block_chunk_sizes(&[6, 74], Some(&circuit)).is_ok());
+        assert!(validate_block_chunk_sizes(&[6, 10, 74], Some(&circuit)).is_err());
+        assert!(validate_block_chunk_sizes(&[30, 6], Some(&circuit)).is_err());
+        assert!(validate_block_chunk_sizes(&[], Some(&circuit)).is_err());
+        // Without circuit only the order is checked.
+        assert!(validate_block_chunk_sizes(&[6, 10], None).is_ok());
+    }
+
+    /// Checks that the off-peak sizes are used only within the off-peak window
+    /// and only for the blocks that fit into them.
+    #[test]
+    fn off_peak_block_chunk_sizes() {
+        let mut config = StateKeeperConfig::new(AccountId(0), vec![30, 74], 10, 20);
+        assert_eq!(config.block_chunk_sizes_at(23), &[30, 74]);
+
+        // The window wraps around midnight.
+        config.set_off_peak_policy(OffPeakPolicy {
+            block_chunk_sizes: vec![6, 30],
+            start_hour: 22,
+            end_hour: 6,
+        });
+        for hour in [22, 23, 0, 5] {
+            assert_eq!(config.block_chunk_sizes_at(hour), &[6, 30], "hour {}", hour);
+        }
+        for hour in [6, 12, 21] {
+            assert_eq!(
+                config.block_chunk_sizes_at(hour),
+                &[30, 74],
+                "hour {}",
+                hour
+            );
+        }
+
+        // Synthetic fullness levels within and out of the window.
+        assert_eq!(config.block_chunk_sizes_for_seal(23, 0), &[6, 30]);
+        assert_eq!(config.block_chunk_sizes_for_seal(23, 30), &[6, 30]);
+        assert_eq!(config.block_chunk_sizes_for_seal(23, 31), &[30, 74]);
+        assert_eq!(config.block_chunk_sizes_for_seal(12, 6), &[30, 74]);
+    }
+
+    /// Checks that the off-peak sizes can't exceed the maximum block size.
+    #[test]
+    #[should_panic(expected = "Off-peak block size 150 exceeds the maximum block size 74")]
+    fn off_peak_block_chunk_sizes_too_big() {
+        let mut config = StateKeeperConfig::new(AccountId(0), vec![30, 74], 10, 20);
+        config.set_off_peak_policy(OffPeakPolicy {
+            block_chunk_sizes: vec![6, 150],
+            start_hour: 0,
+            end_hour: 6,
+        });
+    }
+
+    /// Checks that the off-peak policy is created only if it's configured completely.
+    #[test]
+    fn off_peak_policy_from_config() {
+        let mut config = StateKeeper {
+            block_chunk_sizes: vec![30, 74],
+            miniblock_iteration_interval: 200,
+            miniblock_iterations: 10,
+            fast_block_miniblock_iterations: 5,
+            fee_account_addr: Default::default(),
+            aggregated_proof_sizes: vec![1, 5],
+            max_aggregated_blocks_to_commit: 3,
+            max_aggregated_blocks_to_execute: 4,
+            block_commit_deadline: 300,
+            block_prove_deadline: 3_000,
+            block_execute_deadline: 4_000,
+            max_aggregated_tx_gas: 4_000_000,
+            max_failed_tx_attempts: 3,
+            block_time_target: None,
+            min_block_seal_timeout: 1_000,
+            max_block_seal_timeout: 60_000,
+            off_peak_block_chunk_sizes: None,
+            off_peak_start_hour: None,
+            off_peak_end_hour: None,
+        };
+        assert_eq!(OffPeakPolicy::from_config(&config).unwrap(), None);
+
+        config.off_peak_block_chunk_sizes = Some(vec![6]);
+        config.off_peak_start_hour = Some(22);
+        config.off_peak_end_hour = None;
+        assert!(OffPeakPolicy::from_config(&config).is_err());
+
+        config.off_peak_end_hour = Some(25);
+        assert!(OffPeakPolicy::from_config(&config).is_err());
+
+        config.off_peak_end_hour = Some(6);
+        assert_eq!(
+            OffPeakPolicy::from_config(&config).unwrap(),
+            Some(OffPeakPolicy {
+                block_chunk_sizes: vec![6],
+                start_hour: 22,
+                end_hour: 6,
+            })
+        );
+    }
+
+    /// Checks that the missing keys and setup powers are reported instead of panicking.
+    #[test]
+    fn missing_proving_keys() {
+        let circuit = Circuit {
+            key_dir: "keys/nonexistent".into(),
+            supported_block_chunks_sizes: vec![6, 30],
+            supported_block_chunks_sizes_setup_powers: vec![21, 22],
+            supported_aggregated_proof_sizes: vec![1],
+            supported_aggregated_proof_sizes_setup_power2: vec![22],
+            account_tree_depth: 32,
+            balance_tree_depth: 11,
+        };
+
+        assert!(check_block_proving_keys(&[6, 30], &circuit).is_err());
+        // Size without the setup power.
+        assert!(check_block_proving_keys(&[74], &circuit).is_err());
+    }
+
     /// Checks that if chunk sizes are empty, it will panic.
     #[test]
     #[should_panic(expected = "Block chunk sizes are empty")]
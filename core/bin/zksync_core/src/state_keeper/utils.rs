@@ -1,3 +1,4 @@
+use chrono::{Timelike, Utc};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub(super) fn system_time_timestamp() -> u64 {
@@ -6,3 +7,8 @@ pub(super) fn system_time_timestamp() -> u64 {
         .expect("failed to get system time")
         .as_secs()
 }
+
+/// Current hour of the day in UTC.
+pub(super) fn current_utc_hour() -> u32 {
+    Utc::now().hour()
+}
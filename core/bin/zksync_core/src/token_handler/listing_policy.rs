@@ -0,0 +1,182 @@
+//! Token listing policy decides which of the tokens added through the governance contract
+//! are registered by the server.
+//!
+//! - In the open mode every added token is registered.
+//! - In the permissioned mode every added token has to be approved by the operator.
+//! - In the fee-based mode the token is registered only if the listing transaction transferred
+//!   at least the configured fee in the fee token to the designated address.
+//!
+//! Tokens that weren't registered are recorded for the operator review.
+
+// External uses
+use tiny_keccak::keccak256;
+// Workspace uses
+use zksync_config::{configs::token_handler::TokenListingPolicy, TokenHandlerConfig};
+use zksync_types::{tokens::NewTokenEvent, Address, Log, H256, U256};
+
+/// Checks the tokens added through the governance contract against the configured policy.
+#[derive(Debug, Clone)]
+pub struct ListingPolicy {
+    policy: TokenListingPolicy,
+    fee_token: Address,
+    fee_receiver: Address,
+    fee: U256,
+}
+
+impl ListingPolicy {
+    pub fn new(config: &TokenHandlerConfig) -> Self {
+        Self {
+            policy: config.listing_policy,
+            fee_token: config.listing_fee_token,
+            fee_receiver: config.listing_fee_receiver,
+            fee: config.listing_fee(),
+        }
+    }
+
+    /// Whether the logs of the listing transaction are required for the check.
+    pub fn requires_listing_tx_logs(&self) -> bool {
+        self.policy == TokenListingPolicy::FeeBased
+    }
+
+    /// Returns the reason to hold the token back, or `None` if the token can be registered.
+    /// `listing_tx_logs` are the logs of the transaction that listed the token,
+    /// they're required only in the fee-based mode.
+    pub fn check(&self, event: &NewTokenEvent, listing_tx_logs: Option<&[Log]>) -> Option<String> {
+        match self.policy {
+            TokenListingPolicy::Open => None,
+            TokenListingPolicy::Permissioned => {
+                Some("Token listing requires the operator approval".to_string())
+            }
+            TokenListingPolicy::FeeBased => {
+                let logs = match listing_tx_logs {
+                    Some(logs) => logs,
+                    None => {
+                        return Some(format!(
+                            "Listing transaction of the token {} is not found",
+                            event.id
+                        ))
+                    }
+                };
+                let paid_fee = self.paid_fee(logs);
+                if paid_fee < self.fee {
+                    Some(format!(
+                        "Insufficient listing fee: paid {}, required {}",
+                        paid_fee, self.fee
+                    ))
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Sums up the fee token transfers to the fee receiver.
+    fn paid_fee(&self, logs: &[Log]) -> U256 {
+        let transfer_topic = H256::from(keccak256(b"Transfer(address,address,uint256)"));
+        logs.iter()
+            .filter(|log| {
+                log.address == self.fee_token
+                    && log.topics.len() == 3
+                    && log.topics[0] == transfer_topic
+                    && Address::from_slice(&log.topics[2].as_bytes()[12..]) == self.fee_receiver
+            })
+            .fold(U256::zero(), |paid, log| {
+                paid.saturating_add(U256::from_big_endian(&log.data.0))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zksync_types::TokenId;
+
+    const FEE: u64 = 1_000;
+
+    fn policy(policy: TokenListingPolicy) -> ListingPolicy {
+        ListingPolicy {
+            policy,
+            fee_token: Address::repeat_byte(0x01),
+            fee_receiver: Address::repeat_byte(0x02),
+            fee: U256::from(FEE),
+        }
+    }
+
+    fn token_event() -> NewTokenEvent {
+        NewTokenEvent {
+            eth_block_number: 10,
+            address: Address::repeat_byte(0x10),
+            id: TokenId(5),
+            eth_tx_hash: Some(H256::repeat_byte(0x20)),
+        }
+    }
+
+    fn transfer_log(token: Address, to: Address, amount: u64) -> Log {
+        let mut data = [0u8; 32];
+        U256::from(amount).to_big_endian(&mut data);
+        Log {
+            address: token,
+            topics: vec![
+                H256::from(keccak256(b"Transfer(address,address,uint256)")),
+                H256::from(Address::repeat_byte(0x30)),
+                H256::from(to),
+            ],
+            data: data.to_vec().into(),
+            block_hash: None,
+            block_number: Some(10.into()),
+            transaction_hash: Some(H256::repeat_byte(0x20)),
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn open_listing() {
+        let policy = policy(TokenListingPolicy::Open);
+        assert!(!policy.requires_listing_tx_logs());
+        assert_eq!(policy.check(&token_event(), None), None);
+    }
+
+    #[test]
+    fn permissioned_listing() {
+        let policy = policy(TokenListingPolicy::Permissioned);
+        assert!(!policy.requires_listing_tx_logs());
+        assert!(policy.check(&token_event(), None).is_some());
+        // Paying the fee doesn't matter in this mode.
+        let logs = [transfer_log(policy.fee_token, policy.fee_receiver, FEE)];
+        assert!(policy.check(&token_event(), Some(&logs)).is_some());
+    }
+
+    #[test]
+    fn fee_based_listing() {
+        let policy = policy(TokenListingPolicy::FeeBased);
+        let event = token_event();
+        assert!(policy.requires_listing_tx_logs());
+
+        // Listing transaction is unknown.
+        assert!(policy.check(&event, None).is_some());
+        // Fee is not paid.
+        assert!(policy.check(&event, Some(&[])).is_some());
+        // Fee is paid partially.
+        let logs = [transfer_log(policy.fee_token, policy.fee_receiver, FEE - 1)];
+        assert!(policy.check(&event, Some(&logs)).is_some());
+        // Fee is paid in the wrong token or to the wrong address.
+        let logs = [
+            transfer_log(Address::repeat_byte(0x03), policy.fee_receiver, FEE),
+            transfer_log(policy.fee_token, Address::repeat_byte(0x03), FEE),
+        ];
+        assert!(policy.check(&event, Some(&logs)).is_some());
+        // Fee is paid.
+        let logs = [transfer_log(policy.fee_token, policy.fee_receiver, FEE)];
+        assert_eq!(policy.check(&event, Some(&logs)), None);
+        // Fee is paid in several transfers.
+        let logs = [
+            transfer_log(policy.fee_token, policy.fee_receiver, FEE / 2),
+            transfer_log(policy.fee_token, policy.fee_receiver, FEE / 2),
+        ];
+        assert_eq!(policy.check(&event, Some(&logs)), None);
+    }
+}
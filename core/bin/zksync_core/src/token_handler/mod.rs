@@ -0,0 +1,480 @@
+//! Token handler is a crate that receives a notification about adding tokens to the contract
+//! and adds them to the database.
+//!
+//! To set the name and the decimals parameter for the token, a match is searched for with the
+//! token list (which is taken from the environment). If the token address is not found in the
+//! trusted token list, then the default values are used (name = "ERC20-{id}", decimals = 18).
+//!
+//! Tokens that don't satisfy the listing policy are not registered, but recorded for the operator review.
+//! Listings approved by the operator are registered on the next iteration.
+//!
+//! The operator may also register the token added to the governance contract right away through
+//! the private API, in which case its symbol and decimals are taken from the token contract.
+
+// Built-in deps
+use std::collections::HashMap;
+// External uses
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt,
+};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+// Workspace uses
+use zksync_config::TokenHandlerConfig;
+use zksync_notifier::Notifier;
+use zksync_storage::{tokens::StoreTokenError, ConnectionPool, StorageProcessor};
+use zksync_types::{
+    tokens::{NewTokenEvent, Token, TokenInfo},
+    Address, TokenId, TokenKind, TokenLike, U256,
+};
+// Local uses
+use self::listing_policy::ListingPolicy;
+use crate::eth_watch::EthWatchRequest;
+use web3::contract::Options;
+use zksync_contracts::{erc20_contract, erc20_metadata_contract, governance_contract};
+use zksync_eth_client::EthereumGateway;
+
+pub mod listing_policy;
+#[cfg(test)]
+mod tests;
+
+struct TokenHandler {
+    connection_pool: ConnectionPool,
+    poll_interval: std::time::Duration,
+    eth_watcher_req: mpsc::Sender<EthWatchRequest>,
+    eth_client: EthereumGateway,
+    token_list: HashMap<Address, TokenInfo>,
+    listing_policy: ListingPolicy,
+    last_eth_block: Option<u64>,
+    notifier: Option<Notifier>,
+}
+
+impl TokenHandler {
+    fn new(
+        connection_pool: ConnectionPool,
+        eth_watcher_req: mpsc::Sender<EthWatchRequest>,
+        eth_client: EthereumGateway,
+        config: TokenHandlerConfig,
+    ) -> Self {
+        let poll_interval = config.poll_interval();
+        let token_list = config
+            .token_list()
+            .into_iter()
+            .map(|token| (token.address, token))
+            .collect::<HashMap<Address, TokenInfo>>();
+
+        let listing_policy = ListingPolicy::new(&config);
+
+        let webhook_url = reqwest::Url::parse(&config.webhook_url).ok();
+        let notifier = webhook_url.map(Notifier::with_mattermost);
+
+        Self {
+            connection_pool,
+            eth_client,
+            token_list,
+            listing_policy,
+            poll_interval,
+            notifier,
+            last_eth_block: None, // TODO: Maybe load last viewed Ethereum block number for TokenHandler from DB (ZKS-518).
+            eth_watcher_req,
+        }
+    }
+
+    async fn load_new_token_events(&self) -> Vec<NewTokenEvent> {
+        let (sender, receiver) = oneshot::channel();
+        self.eth_watcher_req
+            .clone()
+            .send(EthWatchRequest::GetNewTokens {
+                last_eth_block: self.last_eth_block,
+                resp: sender,
+            })
+            .await
+            .expect("ETH watch req receiver dropped");
+
+        receiver.await.expect("Err response from eth watch")
+    }
+
+    async fn is_contract_erc20(&self, address: Address) -> bool {
+        self.eth_client
+            .call_contract_function::<U256, _, _, _>(
+                "balanceOf",
+                address,
+                None,
+                Options::default(),
+                None,
+                address,
+                erc20_contract(),
+            )
+            .await
+            .is_ok()
+    }
+
+    /// Returns the reason to hold the token back according to the listing policy,
+    /// or `None` if the token can be registered.
+    async fn check_listing(&self, token_event: &NewTokenEvent) -> anyhow::Result<Option<String>> {
+        let listing_tx_logs = match token_event.eth_tx_hash {
+            Some(tx_hash) if self.listing_policy.requires_listing_tx_logs() => self
+                .eth_client
+                .tx_receipt(tx_hash)
+                .await?
+                .map(|receipt| receipt.logs),
+            _ => None,
+        };
+        Ok(self
+            .listing_policy
+            .check(token_event, listing_tx_logs.as_deref()))
+    }
+
+    async fn register_token(
+        &self,
+        transaction: &mut StorageProcessor<'_>,
+        token_event: NewTokenEvent,
+    ) -> anyhow::Result<Token> {
+        // Find a token in the list of trusted tokens
+        // or use default values (name = "ERC20-{id}", decimals = 18).
+        let default_symbol = format!("ERC20-{}", token_event.id);
+        let default_decimals = 18;
+
+        let is_erc20 = self.is_contract_erc20(token_event.address).await;
+        let token_kind = if is_erc20 {
+            TokenKind::ERC20
+        } else {
+            TokenKind::None
+        };
+
+        let token_from_list = {
+            let token_info = self.token_list.get(&token_event.address).cloned();
+
+            if let Some(token_info) = token_info {
+                Some(Token::new(
+                    token_event.id,
+                    token_info.address,
+                    &token_info.symbol,
+                    token_info.decimals,
+                    token_kind,
+                ))
+            } else {
+                None
+            }
+        };
+
+        let token = match token_from_list {
+            Some(token_from_list) => {
+                let try_insert_token = transaction
+                    .tokens_schema()
+                    .store_token(token_from_list.clone())
+                    .await;
+
+                match try_insert_token {
+                    Ok(..) => token_from_list,
+                    Err(StoreTokenError::TokenAlreadyExistsError(..)) => {
+                        // If a token with such parameters already exists in the database
+                        // then try insert token with other symbol.
+                        let token = Token::new(
+                            token_from_list.id,
+                            token_from_list.address,
+                            &default_symbol,
+                            token_from_list.decimals,
+                            token_kind,
+                        );
+                        let try_insert_token =
+                            transaction.tokens_schema().store_token(token.clone()).await;
+                        match try_insert_token {
+                            Ok(..) => (),
+                            Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
+                            Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
+                                vlog::warn!("failed to store token in database: {}", err)
+                            }
+                        }
+
+                        token
+                    }
+                    Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
+                }
+            }
+            None => {
+                // Token with default parameters.
+                let token = Token::new(
+                    token_event.id,
+                    token_event.address,
+                    &default_symbol,
+                    default_decimals,
+                    token_kind,
+                );
+                let try_insert_token = transaction.tokens_schema().store_token(token.clone()).await;
+                match try_insert_token {
+                    Ok(..) => (),
+                    Err(StoreTokenError::Other(anyhow_err)) => return Err(anyhow_err),
+                    Err(StoreTokenError::TokenAlreadyExistsError(err)) => {
+                        vlog::warn!("failed to store token in database: {}", err)
+                    }
+                }
+
+                token
+            }
+        };
+
+        Ok(token)
+    }
+
+    async fn save_new_tokens(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tokens: Vec<NewTokenEvent>,
+    ) -> anyhow::Result<Vec<Token>> {
+        let mut transaction = storage.start_transaction().await?;
+
+        let mut new_tokens = Vec::new();
+
+        for token_event in tokens {
+            // Ids are not registered in order when some of the tokens are held back by the
+            // listing policy or added through the private API, so only the stored ids are skipped.
+            if transaction
+                .tokens_schema()
+                .get_token(TokenLike::Id(token_event.id))
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+
+            if let Some(reason) = self.check_listing(&token_event).await? {
+                vlog::warn!(
+                    "Token {} ({:#x}) was not registered: {}",
+                    token_event.id,
+                    token_event.address,
+                    reason
+                );
+                transaction
+                    .tokens_schema()
+                    .record_rejected_token_listing(&token_event, &reason)
+                    .await?;
+                continue;
+            }
+
+            let token = self.register_token(&mut transaction, token_event).await?;
+            new_tokens.push(token);
+        }
+
+        // Register the listings approved by the operator.
+        let approved_listings = transaction
+            .tokens_schema()
+            .load_approved_token_listings()
+            .await?;
+        for token_event in approved_listings {
+            let token_id = token_event.id;
+            vlog::info!("Registering the approved token {}", token_id);
+            let token = self.register_token(&mut transaction, token_event).await?;
+            transaction
+                .tokens_schema()
+                .remove_token_listing(token_id)
+                .await?;
+            new_tokens.push(token);
+        }
+
+        transaction.commit().await?;
+        Ok(new_tokens)
+    }
+
+    async fn run(&mut self) {
+        let mut timer = tokio::time::interval(self.poll_interval);
+        loop {
+            timer.tick().await;
+
+            let new_tokens_events = self.load_new_token_events().await;
+            let last_eth_block = new_tokens_events
+                .iter()
+                .map(|token| token.eth_block_number)
+                .max()
+                .or(self.last_eth_block);
+
+            let mut storage = self
+                .connection_pool
+                .access_storage()
+                .await
+                .expect("db connection failed for token handler");
+
+            // Tokens are saved in a single database transaction, so the whole range of events
+            // is processed again on the next iteration if e.g. the listing transaction receipt
+            // can't be fetched at the moment.
+            let new_tokens = match self.save_new_tokens(&mut storage, new_tokens_events).await {
+                Ok(new_tokens) => new_tokens,
+                Err(err) => {
+                    vlog::error!(
+                        "Failed to add new tokens, retrying on the next iteration: {}",
+                        err
+                    );
+                    metrics::increment_counter!("token_handler.save_new_tokens_fail");
+                    continue;
+                }
+            };
+            self.last_eth_block = last_eth_block;
+
+            // Send a notification that the token has been successfully added to the database.
+            if let Some(notifier) = &self.notifier {
+                for token in new_tokens {
+                    notifier
+                        .send_new_token_notify(token)
+                        .await
+                        .unwrap_or_else(|e| {
+                            vlog::error!("Failed to send a token insertion notification: {}", e);
+                        });
+                }
+            }
+        }
+    }
+}
+
+/// Reason of the token not being registered through the private API.
+#[derive(Debug, Error)]
+pub enum AddTokenError {
+    #[error("Token with the address {0:#x} is already registered")]
+    DuplicateAddress(Address),
+    #[error("Token with the symbol {0} is already registered")]
+    DuplicateSymbol(String),
+    #[error("Token {0:#x} is not added to the governance contract")]
+    NotInGovernance(Address),
+    #[error(
+        "Token has id {governance} in the governance contract, but the next free id is {next_free}"
+    )]
+    IdMismatch {
+        next_free: TokenId,
+        governance: TokenId,
+    },
+    #[error("Failed to query the token metadata: {0}")]
+    MetadataUnavailable(String),
+    #[error("{0}")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl AddTokenError {
+    /// Machine-readable name of the error.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::DuplicateAddress(_) => "duplicateAddress",
+            Self::DuplicateSymbol(_) => "duplicateSymbol",
+            Self::NotInGovernance(_) => "notInGovernance",
+            Self::IdMismatch { .. } => "idMismatch",
+            Self::MetadataUnavailable(_) => "metadataUnavailable",
+            Self::Internal(_) => "internal",
+        }
+    }
+}
+
+async fn call_token_contract<R>(
+    eth_client: &EthereumGateway,
+    address: Address,
+    func: &str,
+) -> Result<R, AddTokenError>
+where
+    R: web3::contract::tokens::Detokenize + Unpin,
+{
+    eth_client
+        .call_contract_function(
+            func,
+            (),
+            None,
+            Options::default(),
+            None,
+            address,
+            erc20_metadata_contract(),
+        )
+        .await
+        .map_err(|err| AddTokenError::MetadataUnavailable(format!("{}(): {}", func, err)))
+}
+
+/// Registers the ERC20 token added to the governance contract, taking its symbol and decimals
+/// from the token contract. The token gets the next free id, which must match the id assigned
+/// by the governance contract, otherwise the deposits of the token would be credited to another one.
+pub async fn add_token(
+    storage: &mut StorageProcessor<'_>,
+    eth_client: &EthereumGateway,
+    governance_addr: Address,
+    address: Address,
+) -> Result<Token, AddTokenError> {
+    let mut transaction = storage.start_transaction().await?;
+    if transaction
+        .tokens_schema()
+        .get_token(TokenLike::Address(address))
+        .await?
+        .is_some()
+    {
+        return Err(AddTokenError::DuplicateAddress(address));
+    }
+
+    // Tokens that are not added to the governance contract can't be deposited.
+    let token_id: U256 = eth_client
+        .call_contract_function(
+            "tokenIds",
+            address,
+            None,
+            Options::default(),
+            None,
+            governance_addr,
+            governance_contract(),
+        )
+        .await?;
+    if token_id.is_zero() {
+        return Err(AddTokenError::NotInGovernance(address));
+    }
+    let governance_id = TokenId(token_id.as_u32());
+    let token_id = TokenId(transaction.tokens_schema().get_max_token_id().await? + 1);
+    if token_id != governance_id {
+        return Err(AddTokenError::IdMismatch {
+            next_free: token_id,
+            governance: governance_id,
+        });
+    }
+
+    let symbol: String = call_token_contract(eth_client, address, "symbol").await?;
+    let decimals: U256 = call_token_contract(eth_client, address, "decimals").await?;
+    if decimals > U256::from(u8::MAX) {
+        return Err(AddTokenError::MetadataUnavailable(format!(
+            "decimals(): {} is out of range",
+            decimals
+        )));
+    }
+
+    let mut tokens_schema = transaction.tokens_schema();
+    if tokens_schema
+        .get_token(TokenLike::Symbol(symbol.clone()))
+        .await?
+        .is_some()
+    {
+        return Err(AddTokenError::DuplicateSymbol(symbol));
+    }
+
+    let token = Token::new(
+        token_id,
+        address,
+        &symbol,
+        decimals.as_u32() as u8,
+        TokenKind::ERC20,
+    );
+    tokens_schema
+        .store_token(token.clone())
+        .await
+        .map_err(|err| anyhow::format_err!("Failed to store the token: {}", err))?;
+    // The token may have been held back by the listing policy.
+    tokens_schema.remove_token_listing(token_id).await?;
+    transaction.commit().await?;
+
+    Ok(token)
+}
+
+#[must_use]
+pub fn run_token_handler(
+    db_pool: ConnectionPool,
+    eth_client: EthereumGateway,
+    config: &TokenHandlerConfig,
+    eth_watcher_req: mpsc::Sender<EthWatchRequest>,
+) -> JoinHandle<()> {
+    let config = config.clone();
+    tokio::spawn(async move {
+        let mut token_handler =
+            TokenHandler::new(db_pool, eth_watcher_req, eth_client, config.clone());
+
+        token_handler.run().await
+    })
+}
@@ -0,0 +1,210 @@
+// External uses
+use db_test_macro::test as db_test;
+// Workspace uses
+use zksync_config::configs::token_handler::TokenListingPolicy;
+use zksync_eth_client::clients::mock::MockEthereum;
+use zksync_types::H256;
+// Local uses
+use super::*;
+
+fn governance_addr() -> Address {
+    Address::repeat_byte(0xaa)
+}
+
+/// Ethereum client returning the governance token id and the metadata of the token.
+async fn eth_client(token: Address, token_id: u16, symbol: &str, decimals: u8) -> EthereumGateway {
+    let mut client = MockEthereum::default();
+    client
+        .add_contract_call_result(governance_addr(), "tokenIds", U256::from(token_id))
+        .await;
+    client
+        .add_contract_call_result(token, "symbol", symbol.to_string())
+        .await;
+    client
+        .add_contract_call_result(token, "decimals", U256::from(decimals))
+        .await;
+    EthereumGateway::Mock(client)
+}
+
+/// Checks that the token is stored with the metadata queried from L1 and its governance id.
+#[db_test]
+async fn add_token_stores_metadata(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let address = Address::repeat_byte(0x01);
+    let client = eth_client(address, 1, "DAI", 18).await;
+
+    let token = add_token(&mut storage, &client, governance_addr(), address).await?;
+    let expected = Token::new(TokenId(1), address, "DAI", 18, TokenKind::ERC20);
+    assert_eq!(token, expected);
+    let stored = storage
+        .tokens_schema()
+        .get_token(TokenLike::Address(address))
+        .await?;
+    assert_eq!(stored, Some(expected));
+
+    Ok(())
+}
+
+/// Checks that the duplicate tokens and the ones not added to the governance contract are rejected.
+#[db_test]
+async fn add_token_rejects_invalid_tokens(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let address = Address::repeat_byte(0x01);
+    let client = eth_client(address, 1, "DAI", 18).await;
+    add_token(&mut storage, &client, governance_addr(), address).await?;
+
+    let err = add_token(&mut storage, &client, governance_addr(), address)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AddTokenError::DuplicateAddress(addr) if addr == address));
+
+    // Symbols are compared case-insensitively, same as the API looks them up.
+    let other_address = Address::repeat_byte(0x02);
+    let client = eth_client(other_address, 2, "dai", 18).await;
+    let err = add_token(&mut storage, &client, governance_addr(), other_address)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AddTokenError::DuplicateSymbol(symbol) if symbol == "dai"));
+    assert_eq!(err.reason(), "duplicateSymbol");
+
+    let client = eth_client(other_address, 0, "USDC", 6).await;
+    let err = add_token(&mut storage, &client, governance_addr(), other_address)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AddTokenError::NotInGovernance(_)));
+
+    // The token contract without the metadata.
+    let mut client = MockEthereum::default();
+    client
+        .add_contract_call_result(governance_addr(), "tokenIds", U256::from(2u16))
+        .await;
+    let client = EthereumGateway::Mock(client);
+    let err = add_token(&mut storage, &client, governance_addr(), other_address)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, AddTokenError::MetadataUnavailable(_)));
+
+    let stored = storage
+        .tokens_schema()
+        .get_token(TokenLike::Address(other_address))
+        .await?;
+    assert_eq!(stored, None);
+
+    Ok(())
+}
+
+/// Checks that the token whose governance id doesn't match the next free id is rejected.
+#[db_test]
+async fn add_token_requires_next_free_id(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let address = Address::repeat_byte(0x01);
+    let client = eth_client(address, 5, "DAI", 18).await;
+
+    let err = add_token(&mut storage, &client, governance_addr(), address)
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        AddTokenError::IdMismatch {
+            next_free: TokenId(1),
+            governance: TokenId(5),
+        }
+    ));
+    assert_eq!(err.reason(), "idMismatch");
+
+    Ok(())
+}
+
+/// Token handler with the given listing policy, not connected to the Ethereum watcher.
+fn token_handler(listing_policy: TokenListingPolicy) -> TokenHandler {
+    let config = TokenHandlerConfig {
+        token_list_name: String::new(),
+        poll_interval: 1,
+        webhook_url: String::new(),
+        listing_policy,
+        listing_fee_token: Address::zero(),
+        listing_fee_receiver: Address::zero(),
+        listing_fee: String::new(),
+    };
+    TokenHandler {
+        connection_pool: ConnectionPool::new(Some(1)),
+        poll_interval: config.poll_interval(),
+        eth_watcher_req: mpsc::channel(1).0,
+        eth_client: EthereumGateway::Mock(MockEthereum::default()),
+        token_list: HashMap::new(),
+        listing_policy: ListingPolicy::new(&config),
+        last_eth_block: None,
+        notifier: None,
+    }
+}
+
+/// Checks that the handler registers the tokens with ids below the max stored one
+/// and skips only the ids that are already present.
+#[db_test]
+async fn save_new_tokens_fills_id_gaps(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let handler = token_handler(TokenListingPolicy::Open);
+
+    // Token 2 was held back, so token 3 got registered first.
+    for id in [1, 3] {
+        let token = Token::new(
+            TokenId(id),
+            Address::repeat_byte(id as u8),
+            &format!("TKN{}", id),
+            18,
+            TokenKind::ERC20,
+        );
+        storage.tokens_schema().store_token(token).await?;
+    }
+
+    let events = [2, 3]
+        .iter()
+        .map(|&id| NewTokenEvent {
+            eth_block_number: 10,
+            address: Address::repeat_byte(id as u8),
+            id: TokenId(id),
+            eth_tx_hash: None,
+        })
+        .collect();
+    let new_tokens = handler.save_new_tokens(&mut storage, events).await?;
+    assert_eq!(new_tokens.len(), 1);
+    assert_eq!(new_tokens[0].id, TokenId(2));
+
+    let stored = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(TokenId(2)))
+        .await?
+        .expect("token 2 is not registered");
+    assert_eq!(stored.address, Address::repeat_byte(2));
+    let stored = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(TokenId(3)))
+        .await?
+        .expect("token 3 is lost");
+    assert_eq!(stored.symbol, "TKN3");
+
+    Ok(())
+}
+
+/// Checks that the failure to fetch the listing transaction receipt is returned as an error,
+/// so the range of events is processed again, and nothing is stored in the meantime.
+#[db_test]
+async fn save_new_tokens_fails_without_receipt(
+    mut storage: StorageProcessor<'_>,
+) -> anyhow::Result<()> {
+    let handler = token_handler(TokenListingPolicy::FeeBased);
+    let event = NewTokenEvent {
+        eth_block_number: 10,
+        address: Address::repeat_byte(0x01),
+        id: TokenId(1),
+        eth_tx_hash: Some(H256::repeat_byte(0x01)),
+    };
+
+    handler
+        .save_new_tokens(&mut storage, vec![event])
+        .await
+        .unwrap_err();
+    let stored = storage
+        .tokens_schema()
+        .get_token(TokenLike::Id(TokenId(1)))
+        .await?;
+    assert_eq!(stored, None);
+
+    Ok(())
+}
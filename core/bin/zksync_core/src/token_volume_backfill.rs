@@ -0,0 +1,45 @@
+//! The backfill accounts the blocks that are not in the token volume stats yet.
+//!
+//! Normally the stats are updated by the committer as the blocks are committed. The backfill
+//! is responsible for the historical blocks committed before the stats were introduced,
+//! as well as for any block the committer had to skip because the previous blocks weren't accounted yet.
+//! Blocks are processed in bounded chunks, so the database isn't blocked for a long time.
+
+// Built-in uses
+use std::time::Duration;
+// External uses
+use tokio::{task::JoinHandle, time};
+// Workspace deps
+use zksync_storage::ConnectionPool;
+
+/// Maximum number of blocks accounted in one database transaction.
+const BACKFILL_CHUNK_SIZE: u32 = 100;
+/// Delay between the backfill runs once there are no blocks left to account.
+const BACKFILL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[must_use]
+pub fn run_token_volume_backfill(db_pool: ConnectionPool) -> JoinHandle<()> {
+    let mut timer = time::interval(BACKFILL_INTERVAL);
+
+    tokio::spawn(async move {
+        loop {
+            let mut storage = db_pool
+                .access_storage()
+                .await
+                .expect("token volume backfill couldn't access the database");
+            match storage
+                .chain()
+                .stats_schema()
+                .backfill_token_volume(BACKFILL_CHUNK_SIZE)
+                .await
+            {
+                // Keep going while the chunks are full.
+                Ok(aggregated) if aggregated == BACKFILL_CHUNK_SIZE => continue,
+                Ok(_) => {}
+                Err(e) => vlog::error!("Can't backfill the token volume stats {:?}", e),
+            }
+            drop(storage);
+            timer.tick().await;
+        }
+    })
+}
@@ -1,24 +1,32 @@
 //! The `zksync_event_listener` is a stand-alone server-application responsible for
 //! fetching new events that happen in the zkSync network from the database
 //! and streaming them to the connected WebSocket clients.
+//! The account events are also delivered to the registered webhooks,
+//! which are managed through the private API.
 
 // Built-in uses
 // Workspace uses
 use zksync_config::ZkSyncConfig;
+use zksync_storage::ConnectionPool;
 // External uses
 use actix::prelude::*;
 use actix_web::{web, App, Error, HttpRequest, HttpResponse, HttpServer};
 use actix_web_actors::ws;
 // Local uses
 use listener::EventListener;
-use messages::RegisterServerHandle;
+use messages::{RegisterServerHandle, RegisterWebhookNotifier};
 use monitor::ServerMonitor;
 use subscriber::Subscriber;
+use webhooks::{run_webhook_dispatcher, WebhookNotifier};
 
 pub mod listener;
 pub mod messages;
 pub mod monitor;
 pub mod subscriber;
+pub mod webhooks;
+
+/// Size of the connection pool shared by the webhooks components.
+const WEBHOOKS_DB_POOL_SIZE: u32 = 2;
 
 #[derive(Debug)]
 struct AppState {
@@ -35,11 +43,29 @@ async fn ws_index(
 
 pub async fn run_event_server(config: ZkSyncConfig) {
     let monitor = ServerMonitor::new().start();
+    let webhooks_pool = ConnectionPool::new(Some(WEBHOOKS_DB_POOL_SIZE));
+    let webhook_notifier = WebhookNotifier::new(webhooks_pool.clone()).start();
+    monitor
+        .send(RegisterWebhookNotifier(webhook_notifier))
+        .await
+        .unwrap();
+    // The notifier is registered before the listener is started, so no events are missed.
     EventListener::new(monitor.clone(), &config)
         .await
         .unwrap()
         .start();
 
+    let _dispatcher_handle = run_webhook_dispatcher(webhooks_pool.clone(), &config);
+
+    // The webhooks are managed by the operator, so the API is served on a separate private port.
+    let admin_server = HttpServer::new(move || {
+        App::new().service(webhooks::api::api_scope(webhooks_pool.clone()))
+    })
+    .bind(config.event_listener.admin_bind_addr())
+    .unwrap()
+    .run();
+    actix_web::rt::spawn(admin_server);
+
     let state = web::Data::new(AppState {
         server_monitor: monitor.clone(),
     });
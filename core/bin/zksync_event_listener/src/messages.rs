@@ -8,6 +8,7 @@ use zksync_storage::listener::notification::StorageNotification;
 use zksync_types::event::{EventId, ZkSyncEvent};
 // Local uses
 use crate::subscriber::Subscriber;
+use crate::webhooks::WebhookNotifier;
 
 /// Message emitted by the `EventListener` actor, indicates
 /// that an internal error ocurred and the server should stop
@@ -27,6 +28,12 @@ pub struct RegisterServerHandle(pub Server);
 #[rtype(result = "()")]
 pub struct RegisterSubscriber(pub Addr<Subscriber>);
 
+/// Passes the webhook notifier to the monitor on the system start,
+/// so the notifier receives all the new events.
+#[derive(Debug, Message)]
+#[rtype(result = "()")]
+pub struct RegisterWebhookNotifier(pub Addr<WebhookNotifier>);
+
 #[derive(Debug, Message)]
 #[rtype(result = "()")]
 pub struct RemoveSubscriber(pub Addr<Subscriber>);
@@ -7,11 +7,13 @@ use actix_web::dev::Server;
 // Local uses
 use crate::messages::*;
 use crate::subscriber::Subscriber;
+use crate::webhooks::WebhookNotifier;
 
 /// The actor responsible for maintaining the set of connections.
 #[derive(Debug, Default)]
 pub struct ServerMonitor {
     addrs: HashSet<Addr<Subscriber>>,
+    webhook_notifier: Option<Addr<WebhookNotifier>>,
     server_handle: Option<Server>,
 }
 
@@ -49,6 +51,14 @@ impl Handler<RemoveSubscriber> for ServerMonitor {
     }
 }
 
+impl Handler<RegisterWebhookNotifier> for ServerMonitor {
+    type Result = ();
+
+    fn handle(&mut self, request: RegisterWebhookNotifier, _ctx: &mut Self::Context) {
+        self.webhook_notifier.replace(request.0);
+    }
+}
+
 impl Handler<NewEvents> for ServerMonitor {
     type Result = ();
 
@@ -57,6 +67,9 @@ impl Handler<NewEvents> for ServerMonitor {
             vlog::info!("Server monitor received empty array of events");
             return;
         }
+        if let Some(notifier) = &self.webhook_notifier {
+            notifier.do_send(msg.clone());
+        }
         for addr in self.addrs.iter().cloned() {
             addr.send(msg.clone())
                 .into_actor(self)
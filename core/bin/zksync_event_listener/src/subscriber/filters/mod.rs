@@ -10,7 +10,7 @@ use serde::{Deserialize, Deserializer};
 // Local uses
 use self::{account::AccountFilter, block::BlockFilter, transaction::TransactionFilter};
 
-mod account;
+pub(crate) mod account;
 mod block;
 mod transaction;
 
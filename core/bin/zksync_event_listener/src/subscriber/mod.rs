@@ -8,7 +8,7 @@ use crate::messages::{NewEvents, RegisterSubscriber, RemoveSubscriber, Shutdown}
 use crate::monitor::ServerMonitor;
 use filters::SubscriberFilters;
 
-mod filters;
+pub(crate) mod filters;
 
 /// The WebSocket actor. Created for each connected client.
 #[derive(Debug)]
@@ -0,0 +1,174 @@
+//! Private API managing the webhook registrations and exposing the delivery journal.
+
+// Built-in uses
+// External uses
+use actix_web::{error, web, HttpResponse, Result as ActixResult, Scope};
+use serde::Deserialize;
+// Workspace uses
+use zksync_api_types::{NewWebhook, RegisteredWebhook};
+use zksync_storage::ConnectionPool;
+use zksync_types::H256;
+// Local uses
+use crate::subscriber::filters::account::AccountFilter;
+
+/// Maximum number of the deliveries returned at once.
+const MAX_DELIVERIES_LIMIT: u32 = 100;
+
+#[derive(Debug, Clone)]
+struct ApiState {
+    db_pool: ConnectionPool,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeliveriesQuery {
+    limit: Option<u32>,
+}
+
+async fn register_webhook(
+    data: web::Data<ApiState>,
+    params: web::Json<NewWebhook>,
+) -> ActixResult<HttpResponse> {
+    let params = params.into_inner();
+    match reqwest::Url::parse(&params.url) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+        _ => {
+            return Err(error::ErrorBadRequest(format!(
+                "Invalid webhook URL: {}",
+                params.url
+            )))
+        }
+    }
+    serde_json::from_value::<AccountFilter>(params.filter.clone())
+        .map_err(|err| error::ErrorBadRequest(format!("Invalid webhook filter: {}", err)))?;
+
+    let secret = format!("{:x}", H256::random());
+    let mut storage = data
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let info = storage
+        .webhooks_schema()
+        .create_webhook(&params.url, &secret, &params.filter)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    vlog::info!("Registered webhook {} for {}", info.id, info.url);
+
+    Ok(HttpResponse::Ok().json(RegisteredWebhook { secret, info }))
+}
+
+async fn webhooks(data: web::Data<ApiState>) -> ActixResult<HttpResponse> {
+    let mut storage = data
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let webhooks = storage
+        .webhooks_schema()
+        .load_active_webhooks()
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(webhooks))
+}
+
+async fn webhook(data: web::Data<ApiState>, id: web::Path<i64>) -> ActixResult<HttpResponse> {
+    let mut storage = data
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let webhook = storage
+        .webhooks_schema()
+        .load_webhook(id.into_inner())
+        .await
+        .map_err(error::ErrorInternalServerError)?
+        .ok_or_else(|| error::ErrorNotFound("Webhook not found"))?;
+
+    Ok(HttpResponse::Ok().json(webhook))
+}
+
+async fn remove_webhook(
+    data: web::Data<ApiState>,
+    id: web::Path<i64>,
+) -> ActixResult<HttpResponse> {
+    let id = id.into_inner();
+    let mut storage = data
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let removed = storage
+        .webhooks_schema()
+        .remove_webhook(id)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    if !removed {
+        return Err(error::ErrorNotFound("Webhook not found"));
+    }
+    vlog::info!("Removed webhook {}", id);
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+async fn webhook_deliveries(
+    data: web::Data<ApiState>,
+    id: web::Path<i64>,
+    query: web::Query<DeliveriesQuery>,
+) -> ActixResult<HttpResponse> {
+    let limit = query
+        .limit
+        .unwrap_or(MAX_DELIVERIES_LIMIT)
+        .min(MAX_DELIVERIES_LIMIT);
+    let mut storage = data
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let deliveries = storage
+        .webhooks_schema()
+        .load_webhook_deliveries(id.into_inner(), limit)
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(deliveries))
+}
+
+async fn delivery_attempts(
+    data: web::Data<ApiState>,
+    id: web::Path<i64>,
+) -> ActixResult<HttpResponse> {
+    let mut storage = data
+        .db_pool
+        .access_storage()
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+    let attempts = storage
+        .webhooks_schema()
+        .load_webhook_delivery_attempts(id.into_inner())
+        .await
+        .map_err(error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(attempts))
+}
+
+/// Creates the scope with the webhooks management endpoints:
+///
+/// - `POST /webhooks` registers the webhook and returns its secret.
+/// - `GET /webhooks` lists the active webhooks.
+/// - `GET /webhooks/{id}` and `DELETE /webhooks/{id}` load and remove the webhook.
+/// - `GET /webhooks/{id}/deliveries?limit=` returns the latest deliveries of the webhook.
+/// - `GET /webhooks/deliveries/{id}/attempts` returns the journal of the delivery attempts.
+pub fn api_scope(db_pool: ConnectionPool) -> Scope {
+    web::scope("/webhooks")
+        .app_data(web::Data::new(ApiState { db_pool }))
+        .route("", web::post().to(register_webhook))
+        .route("", web::get().to(webhooks))
+        .route(
+            "/deliveries/{id}/attempts",
+            web::get().to(delivery_attempts),
+        )
+        .route("/{id}", web::get().to(webhook))
+        .route("/{id}", web::delete().to(remove_webhook))
+        .route("/{id}/deliveries", web::get().to(webhook_deliveries))
+}
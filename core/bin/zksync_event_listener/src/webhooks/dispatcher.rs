@@ -0,0 +1,183 @@
+// Built-in uses
+use std::time::Duration;
+// External uses
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha256;
+use tokio::{task::JoinHandle, time};
+// Workspace uses
+use zksync_api_types::{WebhookDeliveryAttempt, WebhookDeliveryStatus};
+use zksync_config::ZkSyncConfig;
+use zksync_storage::{
+    webhooks::records::DueWebhookDelivery, ConnectionPool, QueryResult, StorageProcessor,
+};
+// Local uses
+
+/// Header with the hex-encoded HMAC-SHA256 of the request body keyed with the webhook secret.
+pub const SIGNATURE_HEADER: &str = "X-Zksync-Signature";
+/// Header with the id of the delivery, the same for all the attempts of the delivery.
+pub const DELIVERY_ID_HEADER: &str = "X-Zksync-Delivery";
+/// Header with the id of the delivered event.
+pub const EVENT_ID_HEADER: &str = "X-Zksync-Event-Id";
+
+/// How often the dispatcher checks for the due deliveries.
+const DISPATCH_INTERVAL: Duration = Duration::from_secs(1);
+/// Maximum number of the deliveries attempted in one iteration.
+const DELIVERIES_PER_ITERATION: u32 = 100;
+/// Time the receiver has to respond to the callback.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time the claimed deliveries are reserved for the dispatcher, long enough to attempt all of them.
+const CLAIM_DURATION: Duration =
+    Duration::from_secs(DELIVERIES_PER_ITERATION as u64 * REQUEST_TIMEOUT.as_secs() + 60);
+/// Maximum exponent of the retry delay, so the delay doesn't grow indefinitely.
+const MAX_BACKOFF_EXPONENT: u32 = 16;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signs the callback body with the webhook secret.
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Checks the signature of the callback body, meant for the callback receivers.
+pub fn verify_signature(secret: &str, payload: &[u8], signature: &str) -> bool {
+    let signature = match hex::decode(signature) {
+        Ok(signature) => signature,
+        Err(_) => return false,
+    };
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(payload);
+    mac.verify(&signature).is_ok()
+}
+
+/// Sends the queued events to the webhooks.
+#[derive(Debug, Clone)]
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    max_attempts: u32,
+    retry_delay: Duration,
+}
+
+impl WebhookDispatcher {
+    /// Creates the dispatcher that dead-letters the delivery after `max_attempts` failed attempts.
+    /// The delay before the retry is `retry_delay` after the first failure and doubles after each one.
+    pub fn new(max_attempts: u32, retry_delay: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("failed to create HTTP client");
+        Self {
+            client,
+            max_attempts,
+            retry_delay,
+        }
+    }
+
+    /// Attempts all the due deliveries once. Returns the number of the attempted deliveries.
+    pub async fn dispatch_due_deliveries(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> QueryResult<usize> {
+        let now = Utc::now();
+        let claimed_until =
+            now + chrono::Duration::from_std(CLAIM_DURATION).expect("claim duration is too big");
+        let deliveries = storage
+            .webhooks_schema()
+            .claim_due_webhook_deliveries(now, claimed_until, DELIVERIES_PER_ITERATION)
+            .await?;
+
+        for delivery in &deliveries {
+            let attempt = self.deliver(delivery).await;
+            let (status, next_attempt_at) = self.next_state(&attempt);
+            if status == WebhookDeliveryStatus::Dead {
+                vlog::warn!(
+                    "Delivery {} of the event {} to the webhook {} is dead-lettered after {} attempts",
+                    delivery.id,
+                    delivery.event_id,
+                    delivery.webhook_id,
+                    attempt.attempt
+                );
+            }
+            storage
+                .webhooks_schema()
+                .record_webhook_delivery_attempt(delivery.id, &attempt, status, next_attempt_at)
+                .await?;
+        }
+        Ok(deliveries.len())
+    }
+
+    /// Sends the event to the webhook. Any response other than 2xx counts as a failure.
+    async fn deliver(&self, delivery: &DueWebhookDelivery) -> WebhookDeliveryAttempt {
+        let body = serde_json::to_vec(&delivery.payload).expect("couldn't serialize payload");
+        let signature = sign_payload(&delivery.secret, &body);
+        let attempted_at = Utc::now();
+        let response = self
+            .client
+            .post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, signature)
+            .header(DELIVERY_ID_HEADER, delivery.id.to_string())
+            .header(EVENT_ID_HEADER, delivery.event_id.to_string())
+            .body(body)
+            .send()
+            .await;
+
+        let (response_status, error) = match response {
+            Ok(response) if response.status().is_success() => (Some(response.status()), None),
+            Ok(response) => (
+                Some(response.status()),
+                Some(format!("Unexpected response status: {}", response.status())),
+            ),
+            Err(err) => (err.status(), Some(err.to_string())),
+        };
+        WebhookDeliveryAttempt {
+            attempt: delivery.attempts as u32 + 1,
+            response_status: response_status.map(|status| status.as_u16()),
+            error,
+            attempted_at,
+        }
+    }
+
+    /// Returns the status of the delivery after the attempt and the time of the next attempt.
+    pub(crate) fn next_state(
+        &self,
+        attempt: &WebhookDeliveryAttempt,
+    ) -> (WebhookDeliveryStatus, DateTime<Utc>) {
+        if attempt.error.is_none() {
+            (WebhookDeliveryStatus::Delivered, attempt.attempted_at)
+        } else if attempt.attempt >= self.max_attempts {
+            (WebhookDeliveryStatus::Dead, attempt.attempted_at)
+        } else {
+            let exponent = (attempt.attempt - 1).min(MAX_BACKOFF_EXPONENT);
+            let delay = self.retry_delay * 2u32.pow(exponent);
+            let delay = chrono::Duration::from_std(delay).expect("retry delay is too big");
+            (WebhookDeliveryStatus::Pending, attempt.attempted_at + delay)
+        }
+    }
+}
+
+#[must_use]
+pub fn run_webhook_dispatcher(pool: ConnectionPool, config: &ZkSyncConfig) -> JoinHandle<()> {
+    let dispatcher = WebhookDispatcher::new(
+        config.event_listener.webhook_max_attempts,
+        config.event_listener.webhook_retry_delay(),
+    );
+    tokio::spawn(async move {
+        let mut timer = time::interval(DISPATCH_INTERVAL);
+        loop {
+            timer.tick().await;
+
+            let result = match pool.access_storage().await {
+                Ok(mut storage) => dispatcher.dispatch_due_deliveries(&mut storage).await,
+                Err(err) => Err(err.into()),
+            };
+            if let Err(err) = result {
+                vlog::error!("Couldn't dispatch the webhook deliveries: {}", err);
+            }
+        }
+    })
+}
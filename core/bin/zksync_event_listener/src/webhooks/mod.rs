@@ -0,0 +1,115 @@
+//! Webhooks are the HTTP alternative to the WebSocket subscriptions for the account events.
+//!
+//! Every registered webhook has a target URL, a shared secret and a filter in the same format
+//! as the account filter of the WebSocket subscriptions. The events received by the
+//! [`WebhookNotifier`] from the [`ServerMonitor`] are matched against the filters and queued
+//! in the database, and the [`WebhookDispatcher`] sends the queued events to the webhooks
+//! as signed POST requests. Failed deliveries are retried with an exponential backoff and are
+//! dead-lettered after the configured number of attempts. Every attempt is journaled.
+//!
+//! [`ServerMonitor`]: crate::monitor::ServerMonitor
+
+// Built-in uses
+// External uses
+use actix::prelude::*;
+use serde_json::Value;
+// Workspace uses
+use zksync_storage::ConnectionPool;
+use zksync_types::event::{EventId, ZkSyncEvent};
+// Local uses
+use crate::messages::NewEvents;
+use crate::subscriber::filters::account::AccountFilter;
+
+pub use dispatcher::{
+    run_webhook_dispatcher, sign_payload, verify_signature, WebhookDispatcher, DELIVERY_ID_HEADER,
+    EVENT_ID_HEADER, SIGNATURE_HEADER,
+};
+
+pub mod api;
+mod dispatcher;
+
+#[cfg(test)]
+mod tests;
+
+/// The actor responsible for queueing the new events for the delivery to the matching webhooks.
+#[derive(Debug)]
+pub struct WebhookNotifier {
+    db_pool: ConnectionPool,
+}
+
+impl WebhookNotifier {
+    pub fn new(db_pool: ConnectionPool) -> Self {
+        Self { db_pool }
+    }
+
+    async fn enqueue_events(
+        db_pool: ConnectionPool,
+        events: &[ZkSyncEvent],
+    ) -> anyhow::Result<usize> {
+        let mut storage = db_pool.access_storage().await?;
+        let webhooks = storage
+            .webhooks_schema()
+            .load_active_webhooks()
+            .await?
+            .into_iter()
+            .filter_map(
+                |webhook| match serde_json::from_value::<AccountFilter>(webhook.filter) {
+                    Ok(filter) => Some((webhook.id, filter)),
+                    Err(err) => {
+                        vlog::error!("Invalid filter of the webhook {}: {}", webhook.id, err);
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let deliveries = match_deliveries(&webhooks, events);
+        storage
+            .webhooks_schema()
+            .enqueue_webhook_deliveries(&deliveries)
+            .await?;
+        Ok(deliveries.len())
+    }
+}
+
+impl Actor for WebhookNotifier {
+    type Context = Context<Self>;
+
+    fn stopped(&mut self, _ctx: &mut Self::Context) {
+        vlog::warn!("WebhookNotifier actor has stopped");
+    }
+}
+
+impl Handler<NewEvents> for WebhookNotifier {
+    type Result = ();
+
+    fn handle(&mut self, msg: NewEvents, ctx: &mut Self::Context) {
+        let db_pool = self.db_pool.clone();
+        // Events are queued in the order they were received.
+        async move { Self::enqueue_events(db_pool, msg.0.as_ref()).await }
+            .into_actor(self)
+            .map(|result, _, _| {
+                if let Err(err) = result {
+                    vlog::error!("Couldn't queue the events for the webhooks: {}", err);
+                }
+            })
+            .wait(ctx);
+    }
+}
+
+/// Returns the deliveries of the events to the webhooks with the matching filters.
+pub(crate) fn match_deliveries(
+    webhooks: &[(i64, AccountFilter)],
+    events: &[ZkSyncEvent],
+) -> Vec<(i64, EventId, Value)> {
+    let mut deliveries = Vec::new();
+    for event in events {
+        for (webhook_id, filter) in webhooks {
+            if filter.matches(event) {
+                let payload = serde_json::to_value(event).expect("couldn't serialize event");
+                deliveries.push((*webhook_id, event.id, payload));
+            }
+        }
+    }
+    deliveries
+}
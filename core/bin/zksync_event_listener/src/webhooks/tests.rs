@@ -0,0 +1,309 @@
+// Built-in uses
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+// External uses
+use chrono::Utc;
+use db_test_macro::test as db_test;
+use serde_json::{json, Value};
+// Workspace uses
+use zksync_api_types::{WebhookDeliveryAttempt, WebhookDeliveryStatus};
+use zksync_storage::StorageProcessor;
+use zksync_types::{
+    event::{account::AccountStateChangeStatus, test_data::get_account_event, EventId},
+    AccountId, TokenId,
+};
+// Local uses
+use super::{
+    match_deliveries, sign_payload, verify_signature, WebhookDispatcher, DELIVERY_ID_HEADER,
+    SIGNATURE_HEADER,
+};
+use crate::subscriber::filters::account::AccountFilter;
+
+const SECRET: &str = "webhook secret";
+
+#[derive(Debug, Clone)]
+struct ReceivedRequest {
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// HTTP server accepting the callbacks. Responds with the given statuses in order,
+/// the last status is repeated for all the following requests.
+struct MockReceiver {
+    url: String,
+    requests: Arc<Mutex<Vec<ReceivedRequest>>>,
+}
+
+impl MockReceiver {
+    fn start(statuses: Vec<u16>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/callback", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = requests.clone();
+        thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let mut stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => return,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut headers = HashMap::new();
+                // Skip the request line and read the headers up to the empty line.
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    match line.trim_end().split_once(':') {
+                        Some((name, value)) => {
+                            headers.insert(name.to_lowercase(), value.trim().to_string());
+                        }
+                        None => break,
+                    }
+                }
+                let content_length = headers
+                    .get("content-length")
+                    .map(|length| length.parse().unwrap())
+                    .unwrap_or(0);
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                received
+                    .lock()
+                    .unwrap()
+                    .push(ReceivedRequest { headers, body });
+
+                let status = statuses[i.min(statuses.len() - 1)];
+                let response = format!(
+                    "HTTP/1.1 {} Status\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    fn requests(&self) -> Vec<ReceivedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Registers the webhook and queues a single event for it. Returns the webhook id.
+async fn setup_delivery(
+    storage: &mut StorageProcessor<'_>,
+    url: &str,
+    payload: Value,
+) -> anyhow::Result<i64> {
+    let webhook = storage
+        .webhooks_schema()
+        .create_webhook(url, SECRET, &json!({}))
+        .await?;
+    storage
+        .webhooks_schema()
+        .enqueue_webhook_deliveries(&[(webhook.id, EventId(1), payload)])
+        .await?;
+    Ok(webhook.id)
+}
+
+/// Loads the only delivery of the webhook together with its attempts.
+async fn load_delivery(
+    storage: &mut StorageProcessor<'_>,
+    webhook_id: i64,
+) -> anyhow::Result<(WebhookDeliveryStatus, Vec<WebhookDeliveryAttempt>)> {
+    let deliveries = storage
+        .webhooks_schema()
+        .load_webhook_deliveries(webhook_id, 10)
+        .await?;
+    assert_eq!(deliveries.len(), 1);
+    let attempts = storage
+        .webhooks_schema()
+        .load_webhook_delivery_attempts(deliveries[0].id)
+        .await?;
+    Ok((deliveries[0].status, attempts))
+}
+
+#[test]
+fn signature() {
+    let payload = br#"{"type":"account"}"#;
+    let signature = sign_payload(SECRET, payload);
+
+    assert!(verify_signature(SECRET, payload, &signature));
+    assert!(!verify_signature("other secret", payload, &signature));
+    assert!(!verify_signature(
+        SECRET,
+        br#"{"type":"block"}"#,
+        &signature
+    ));
+    assert!(!verify_signature(SECRET, payload, "not a hex"));
+}
+
+#[test]
+fn retry_backoff() {
+    let dispatcher = WebhookDispatcher::new(4, Duration::from_secs(10));
+    let failed_attempt = |attempt| WebhookDeliveryAttempt {
+        attempt,
+        response_status: Some(500),
+        error: Some("Unexpected response status: 500".to_string()),
+        attempted_at: Utc::now(),
+    };
+
+    // The delay doubles after each failure.
+    for &(attempt, delay) in &[(1, 10), (2, 20), (3, 40)] {
+        let attempt = failed_attempt(attempt);
+        let (status, next_attempt_at) = dispatcher.next_state(&attempt);
+        assert_eq!(status, WebhookDeliveryStatus::Pending);
+        assert_eq!(
+            next_attempt_at - attempt.attempted_at,
+            chrono::Duration::seconds(delay)
+        );
+    }
+    // The delivery is dead-lettered after the last attempt.
+    let (status, _) = dispatcher.next_state(&failed_attempt(4));
+    assert_eq!(status, WebhookDeliveryStatus::Dead);
+
+    let succeeded = WebhookDeliveryAttempt {
+        response_status: Some(200),
+        error: None,
+        ..failed_attempt(4)
+    };
+    let (status, _) = dispatcher.next_state(&succeeded);
+    assert_eq!(status, WebhookDeliveryStatus::Delivered);
+}
+
+#[test]
+fn events_matching() {
+    let filter = |accounts: &[u32]| AccountFilter {
+        accounts: Some(accounts.iter().map(|id| AccountId(*id)).collect()),
+        tokens: None,
+        status: None,
+    };
+    let webhooks = vec![(1, filter(&[10])), (2, filter(&[10, 20]))];
+    let mut events = vec![
+        get_account_event(
+            AccountId(10),
+            Some(TokenId(0)),
+            AccountStateChangeStatus::Committed,
+        ),
+        get_account_event(AccountId(20), None, AccountStateChangeStatus::Finalized),
+        get_account_event(AccountId(30), None, AccountStateChangeStatus::Finalized),
+    ];
+    for (id, event) in events.iter_mut().enumerate() {
+        event.id = EventId(id as u64);
+    }
+
+    let deliveries: Vec<_> = match_deliveries(&webhooks, &events)
+        .into_iter()
+        .map(|(webhook_id, event_id, payload)| {
+            assert_eq!(
+                payload,
+                serde_json::to_value(&events[*event_id as usize]).unwrap()
+            );
+            (webhook_id, event_id)
+        })
+        .collect();
+    assert_eq!(
+        deliveries,
+        vec![(1, EventId(0)), (2, EventId(0)), (2, EventId(1))]
+    );
+}
+
+/// Checks that the event is delivered with a valid signature.
+#[db_test]
+async fn successful_delivery(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let receiver = MockReceiver::start(vec![200]);
+    let payload = json!({ "type": "account", "data": { "status": "committed" } });
+    let webhook_id = setup_delivery(&mut storage, &receiver.url, payload.clone()).await?;
+
+    let dispatcher = WebhookDispatcher::new(3, Duration::from_secs(0));
+    assert_eq!(dispatcher.dispatch_due_deliveries(&mut storage).await?, 1);
+
+    let requests = receiver.requests();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(serde_json::from_slice::<Value>(&request.body)?, payload);
+    let signature = &request.headers[&SIGNATURE_HEADER.to_lowercase()];
+    assert!(verify_signature(SECRET, &request.body, signature));
+    assert!(!verify_signature("other secret", &request.body, signature));
+    assert!(request
+        .headers
+        .contains_key(&DELIVERY_ID_HEADER.to_lowercase()));
+
+    let (status, attempts) = load_delivery(&mut storage, webhook_id).await?;
+    assert_eq!(status, WebhookDeliveryStatus::Delivered);
+    assert_eq!(attempts.len(), 1);
+    assert_eq!(attempts[0].response_status, Some(200));
+    assert_eq!(attempts[0].error, None);
+
+    // Delivered events are not sent again.
+    assert_eq!(dispatcher.dispatch_due_deliveries(&mut storage).await?, 0);
+    assert_eq!(receiver.requests().len(), 1);
+
+    Ok(())
+}
+
+/// Checks that the delivery is retried after the receiver responds with an error.
+#[db_test]
+async fn retry_after_server_error(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let receiver = MockReceiver::start(vec![500, 200]);
+    let webhook_id = setup_delivery(&mut storage, &receiver.url, json!({})).await?;
+
+    let dispatcher = WebhookDispatcher::new(3, Duration::from_secs(0));
+    assert_eq!(dispatcher.dispatch_due_deliveries(&mut storage).await?, 1);
+    let (status, attempts) = load_delivery(&mut storage, webhook_id).await?;
+    assert_eq!(status, WebhookDeliveryStatus::Pending);
+    assert_eq!(attempts.len(), 1);
+    assert_eq!(attempts[0].response_status, Some(500));
+    assert!(attempts[0].error.is_some());
+
+    assert_eq!(dispatcher.dispatch_due_deliveries(&mut storage).await?, 1);
+    let (status, attempts) = load_delivery(&mut storage, webhook_id).await?;
+    assert_eq!(status, WebhookDeliveryStatus::Delivered);
+    assert_eq!(attempts.len(), 2);
+    assert_eq!(attempts[1].response_status, Some(200));
+
+    // Both attempts are the same delivery signed the same way.
+    let requests = receiver.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body, requests[1].body);
+    assert_eq!(
+        requests[0].headers[&DELIVERY_ID_HEADER.to_lowercase()],
+        requests[1].headers[&DELIVERY_ID_HEADER.to_lowercase()]
+    );
+
+    Ok(())
+}
+
+/// Checks that the delivery is dead-lettered after the configured number of failures.
+#[db_test]
+async fn dead_letter(mut storage: StorageProcessor<'_>) -> anyhow::Result<()> {
+    let receiver = MockReceiver::start(vec![500]);
+    let webhook_id = setup_delivery(&mut storage, &receiver.url, json!({})).await?;
+
+    let dispatcher = WebhookDispatcher::new(3, Duration::from_secs(0));
+    for _ in 0..3 {
+        assert_eq!(dispatcher.dispatch_due_deliveries(&mut storage).await?, 1);
+    }
+    let (status, attempts) = load_delivery(&mut storage, webhook_id).await?;
+    assert_eq!(status, WebhookDeliveryStatus::Dead);
+    assert_eq!(
+        attempts
+            .iter()
+            .map(|attempt| attempt.attempt)
+            .collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+
+    // Dead deliveries are not retried.
+    assert_eq!(dispatcher.dispatch_due_deliveries(&mut storage).await?, 0);
+    assert_eq!(receiver.requests().len(), 3);
+
+    Ok(())
+}
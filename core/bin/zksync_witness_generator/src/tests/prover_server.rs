@@ -189,6 +189,8 @@ pub async fn get_test_block() -> Block {
                 eth_hash: H256::zero(),
                 eth_block: 10,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             block_index: 1,
             created_at: chrono::Utc::now(),
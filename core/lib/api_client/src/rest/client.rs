@@ -122,6 +122,14 @@ impl ClientRequestBuilder {
             if status == StatusCode::NOT_FOUND {
                 return Err(ClientError::NotFound(self.url));
             }
+            // API v0.2 reports the throttled submissions in the regular response structure.
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                let body = response.bytes().await.map_err(ClientError::Parse)?;
+                return serde_json::from_slice(&body).map_err(|_| ClientError::BadRequest {
+                    http_code: status,
+                    body: serde_json::from_slice(&body).unwrap_or_default(),
+                });
+            }
 
             Err(ClientError::BadRequest {
                 http_code: status,
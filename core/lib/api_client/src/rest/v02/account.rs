@@ -1,10 +1,11 @@
 use crate::rest::client::{Client, Result};
 
 use zksync_api_types::v02::{
-    pagination::{ApiEither, PaginationQuery},
+    pagination::{ActivityPosition, ApiEither, PaginationQuery, QueuedTxPosition},
+    transaction::AccountToggle2FA,
     Response,
 };
-use zksync_types::{tx::TxHash, SerialId};
+use zksync_types::{tx::TxHash, SerialId, TokenId};
 
 impl Client {
     pub async fn account_info(
@@ -43,6 +44,25 @@ impl Client {
         .await
     }
 
+    /// Same as `account_txs`, but lists only the transactions of the given token.
+    pub async fn account_txs_of_token(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<TxHash>>,
+        account_id_or_address: &str,
+        token_like: &str,
+        include_fee_token: bool,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/transactions", account_id_or_address),
+        )
+        .query(&pagination_query)
+        .query(&[("token", token_like)])
+        .query(&[("includeFeeToken", include_fee_token)])
+        .send()
+        .await
+    }
+
     pub async fn account_pending_txs(
         &self,
         pagination_query: &PaginationQuery<ApiEither<SerialId>>,
@@ -56,4 +76,72 @@ impl Client {
         .send()
         .await
     }
+
+    pub async fn account_pending_priority_ops(
+        &self,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/pending_priority_ops", account_id_or_address),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn account_queued_txs(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<QueuedTxPosition>>,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/pending", account_id_or_address),
+        )
+        .query(pagination_query)
+        .send()
+        .await
+    }
+
+    pub async fn account_nfts(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<TokenId>>,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/nfts", account_id_or_address),
+        )
+        .query(pagination_query)
+        .send()
+        .await
+    }
+
+    pub async fn account_activity(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<ActivityPosition>>,
+        account_id_or_address: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/activity", account_id_or_address),
+        )
+        .query(pagination_query)
+        .send()
+        .await
+    }
+
+    pub async fn account_toggle_2fa(
+        &self,
+        account_id_or_address: &str,
+        request: &AccountToggle2FA,
+    ) -> Result<Response> {
+        self.post_with_scope(
+            super::API_V02_SCOPE,
+            &format!("accounts/{}/2fa", account_id_or_address),
+        )
+        .body(request)
+        .send()
+        .await
+    }
 }
@@ -1,7 +1,8 @@
 use crate::rest::client::{Client, Result};
 
 use zksync_api_types::v02::{
-    pagination::{ApiEither, PaginationQuery},
+    block::BlocksOrder,
+    pagination::{ApiEither, BlockTimestamp, CountOnlyQuery, PaginationQuery},
     Response,
 };
 use zksync_types::{tx::TxHash, BlockNumber};
@@ -13,6 +14,15 @@ impl Client {
             .await
     }
 
+    pub async fn block_aggregated_operations(&self, block_position: &str) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/aggregated", block_position),
+        )
+        .send()
+        .await
+    }
+
     pub async fn transaction_in_block(&self, block_number: u32, tx_index: u32) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -36,6 +46,53 @@ impl Client {
         .await
     }
 
+    /// Same as `block_transactions`, but lists only the operations of the given type.
+    pub async fn block_transactions_of_type(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<TxHash>>,
+        block_position: &str,
+        tx_type: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/transactions", block_position),
+        )
+        .query(&pagination_query)
+        .query(&[("txType", tx_type)])
+        .send()
+        .await
+    }
+
+    /// Same as `block_transactions`, but only the total count of the transactions is returned.
+    pub async fn block_transactions_count(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<TxHash>>,
+        block_position: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/transactions", block_position),
+        )
+        .query(&pagination_query)
+        .query(&CountOnlyQuery { count_only: true })
+        .send()
+        .await
+    }
+
+    pub async fn block_transactions_by_index(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<u32>>,
+        block_position: &str,
+    ) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("blocks/{}/transactions/byIndex", block_position),
+        )
+        .query(&pagination_query)
+        .send()
+        .await
+    }
+
     pub async fn block_pagination(
         &self,
         pagination_query: &PaginationQuery<ApiEither<BlockNumber>>,
@@ -45,4 +102,16 @@ impl Client {
             .send()
             .await
     }
+
+    /// Same as `block_pagination`, but the blocks are ordered by their timestamps.
+    pub async fn block_pagination_by_timestamp(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<BlockTimestamp>>,
+    ) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "blocks")
+            .query(pagination_query)
+            .query(&[("orderBy", BlocksOrder::Timestamp.as_str())])
+            .send()
+            .await
+    }
 }
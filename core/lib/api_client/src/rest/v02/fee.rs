@@ -1,7 +1,9 @@
 // Local uses
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::{
-    fee::{ApiTxFeeTypes, BatchFeeRequest, TxFeeRequest, TxInBatchFeeRequest},
+    fee::{
+        ApiTxFeeTypes, BatchFeeRequest, TxFeeRequest, TxInBatchFeeRequest, WithdrawalEstimateQuery,
+    },
     Response,
 };
 use zksync_types::{Address, TokenLike};
@@ -36,4 +38,13 @@ impl Client {
             .send()
             .await
     }
+
+    pub async fn get_withdrawal_estimate(&self, token: &str) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "fee/withdrawal_estimate")
+            .query(&WithdrawalEstimateQuery {
+                token: token.to_owned(),
+            })
+            .send()
+            .await
+    }
 }
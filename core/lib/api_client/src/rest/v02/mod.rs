@@ -2,6 +2,8 @@ pub mod account;
 pub mod block;
 pub mod config;
 pub mod fee;
+pub mod priority_ops;
+pub mod search;
 pub mod status;
 pub mod token;
 pub mod transaction;
@@ -0,0 +1,19 @@
+use crate::rest::client::{Client, Result};
+
+use zksync_api_types::v02::{
+    pagination::{ApiEither, PaginationQuery},
+    Response,
+};
+use zksync_types::SerialId;
+
+impl Client {
+    pub async fn priority_ops(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<SerialId>>,
+    ) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "priorityOps")
+            .query(pagination_query)
+            .send()
+            .await
+    }
+}
@@ -7,4 +7,10 @@ impl Client {
             .send()
             .await
     }
+
+    pub async fn mempool_status(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "status/mempool")
+            .send()
+            .await
+    }
 }
@@ -1,6 +1,6 @@
 use crate::rest::client::{Client, Result};
 use zksync_api_types::v02::{
-    pagination::{ApiEither, PaginationQuery},
+    pagination::{ApiEither, CountOnlyQuery, PaginationQuery},
     Response,
 };
 use zksync_types::{tx::TxHash, TokenId, TokenLike};
@@ -16,16 +16,29 @@ impl Client {
             .await
     }
 
+    /// Same as `token_pagination`, but only the total count of the tokens is returned.
+    pub async fn token_count(
+        &self,
+        pagination_query: &PaginationQuery<ApiEither<TokenId>>,
+    ) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "tokens")
+            .query(&pagination_query)
+            .query(&CountOnlyQuery { count_only: true })
+            .send()
+            .await
+    }
+
     pub async fn token_by_id(&self, token: &TokenLike) -> Result<Response> {
         self.get_with_scope(super::API_V02_SCOPE, &format!("tokens/{}", token))
             .send()
             .await
     }
 
-    pub async fn token_price(&self, token: &TokenLike, token_id_or_usd: &str) -> Result<Response> {
+    /// Price of the token in `currency`, which is either `usd` or an id, symbol or address of a token.
+    pub async fn token_price(&self, token: &TokenLike, currency: &str) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
-            &format!("tokens/{}/priceIn/{}", token, token_id_or_usd),
+            &format!("tokens/{}/priceIn/{}", token, currency),
         )
         .send()
         .await
@@ -5,6 +5,17 @@ use zksync_api_types::{
 };
 use zksync_types::tx::{EthBatchSignatures, TxEthSignatureVariant, TxHash, ZkSyncTx};
 
+/// Response of the `get_batch` method.
+pub use zksync_api_types::v02::transaction::ApiTxBatch;
+/// Category of the fail reason reported in the transaction receipts and data.
+pub use zksync_api_types::v02::transaction::FailReasonCode;
+/// Response of the `submit_batch` method, the batch hash is the one the batch is queried by.
+pub use zksync_api_types::v02::transaction::SubmitBatchResponse;
+/// Response of the `submit_tx` method.
+pub use zksync_api_types::v02::transaction::SubmitTxResponse;
+/// Response of the `tx_finality` method.
+pub use zksync_api_types::v02::transaction::{FinalityStage, TxFinality};
+
 impl Client {
     pub async fn submit_tx(
         &self,
@@ -37,6 +48,13 @@ impl Client {
         .await
     }
 
+    pub async fn tx_statuses(&self, tx_hashes: &[TxHash]) -> Result<Response> {
+        self.post_with_scope(super::API_V02_SCOPE, "transactions/status")
+            .body(tx_hashes)
+            .send()
+            .await
+    }
+
     pub async fn tx_data(&self, tx_hash: TxHash) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -46,6 +64,30 @@ impl Client {
         .await
     }
 
+    pub async fn tx_finality(&self, tx_hash: TxHash) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/finality", tx_hash.to_string()),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn withdrawal_status(&self, tx_hash: TxHash) -> Result<Response> {
+        self.get_with_scope(
+            super::API_V02_SCOPE,
+            &format!("transactions/{}/withdrawalStatus", tx_hash.to_string()),
+        )
+        .send()
+        .await
+    }
+
+    pub async fn submission_limits(&self) -> Result<Response> {
+        self.get_with_scope(super::API_V02_SCOPE, "transactions/limits")
+            .send()
+            .await
+    }
+
     pub async fn get_batch(&self, batch_hash: TxHash) -> Result<Response> {
         self.get_with_scope(
             super::API_V02_SCOPE,
@@ -1,17 +1,26 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, NaiveDate, Utc};
 pub use either::Either;
+use num::BigUint;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use zksync_types::{
-    tx::{TxEthSignatureVariant, TxHash},
-    ZkSyncTx, H256,
+    api_keys::ApiKeyScope,
+    tx::{TxEthSignature, TxEthSignatureVariant, TxHash},
+    AccountId, Address, BlockNumber, Nonce, OutputFeeType, SignedZkSyncTx, TokenId, ZkSyncTx, H256,
 };
+use zksync_utils::BigUintSerdeAsRadix10Str;
 
 pub mod v02;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TxWithSignature {
+    #[schemars(with = "v02::schema::Object")]
     pub tx: ZkSyncTx,
     #[serde(default)]
+    #[schemars(with = "serde_json::Value")]
     pub signature: TxEthSignatureVariant,
 }
 
@@ -29,9 +38,348 @@ pub enum PriorityOpLookupQuery {
 /// Status of core server.
 /// Server should have stable connection to the database (main and replica)
 /// and connection to the ethereum node
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub struct CoreStatus {
     pub main_database_available: bool,
     pub replica_database_available: bool,
     pub web3_available: bool,
+    /// Set if the main contract was upgraded on-chain to a target other than the configured one.
+    /// The server keeps working, but is considered degraded until it's reconfigured.
+    #[serde(default)]
+    pub contract_config_outdated: bool,
+    /// Set if the root hash of some block committed on L1 differs from the locally stored one.
+    #[serde(default)]
+    pub root_hash_mismatch: bool,
+    /// State of the block time controller, if the target average block time is configured.
+    #[serde(default)]
+    pub block_time: Option<BlockTimeStatus>,
+}
+
+/// Current state of the controller steering the average block time towards the target.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct BlockTimeStatus {
+    pub target_ms: Option<u64>,
+    /// Time after which the block that is not full is sealed.
+    pub effective_timeout_ms: u64,
+    /// Average time of the recent blocks, `None` if no blocks were sealed since the start.
+    pub average_block_time_ms: Option<u64>,
+}
+
+/// Server parameters that can be changed at runtime through the admin API.
+/// Changes are not persisted and are lost on restart.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Target average block time, `None` disables the block time controller.
+    pub block_time_target_ms: Option<u64>,
+}
+
+/// Block currently being filled by the state keeper, i.e. the executed operations that
+/// are not committed yet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingBlockInfo {
+    pub block_number: BlockNumber,
+    /// Number of chunks used by the operations of the block.
+    pub chunks_used: usize,
+    /// Sizes (in chunks) the block can be sealed with, the largest one limits the block.
+    pub available_chunk_sizes: Vec<usize>,
+    /// Number of successfully executed transactions and priority operations.
+    pub success_txs: usize,
+    pub failed_txs: usize,
+    /// Timestamp (in seconds) of the first operation of the block, `None` if nothing
+    /// was executed yet.
+    pub first_tx_timestamp: Option<u64>,
+}
+
+/// Transaction or batch accepted to the mempool by the API node.
+/// It's forwarded to the core once again if the core doesn't know about it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum MempoolSubmission {
+    Tx {
+        tx: SignedZkSyncTx,
+    },
+    #[serde(rename_all = "camelCase")]
+    Batch {
+        txs: Vec<SignedZkSyncTx>,
+        eth_signatures: Vec<TxEthSignature>,
+    },
+}
+
+impl MempoolSubmission {
+    pub fn tx_hashes(&self) -> Vec<TxHash> {
+        match self {
+            Self::Tx { tx } => vec![tx.hash()],
+            Self::Batch { txs, .. } => txs.iter().map(|tx| tx.hash()).collect(),
+        }
+    }
+}
+
+/// Transaction that failed execution too many times and is not accepted to the mempool anymore.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeadLetteredTx {
+    pub tx_hash: TxHash,
+    pub tx: ZkSyncTx,
+    pub attempts: u32,
+    pub last_fail_reason: String,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+/// Account whose queued transactions can't be executed because the transaction with
+/// the lowest nonce is parked or keeps failing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StuckAccount {
+    pub account_id: AccountId,
+    pub blocking_tx_hash: TxHash,
+    pub blocking_nonce: Nonce,
+    /// Reason the blocking transaction was parked or failed with.
+    pub reason: String,
+    pub blocked_since: DateTime<Utc>,
+    /// Number of the transactions queued behind the blocking one.
+    pub queued_txs: u32,
+}
+
+/// Review status of the token listing held back by the listing policy.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TokenListingStatus {
+    /// Listing awaits the operator review.
+    Pending,
+    /// Listing was approved, the token is going to be registered.
+    Approved,
+    /// Listing was rejected, the token won't be registered.
+    Rejected,
+}
+
+/// Token added through the governance contract, but not registered because of the listing policy.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedTokenListing {
+    pub token_id: TokenId,
+    pub address: Address,
+    pub eth_block_number: u64,
+    pub eth_tx_hash: Option<H256>,
+    pub reason: String,
+    pub status: TokenListingStatus,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// Parameters of the API key to be issued.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewApiKey {
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub requests_per_minute: u32,
+}
+
+/// API key issued to the customer of the hosted API.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyInfo {
+    pub id: i64,
+    pub name: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub requests_per_minute: u32,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyInfo {
+    /// Whether the key is active and gives access to the scope.
+    pub fn allows(&self, scope: ApiKeyScope) -> bool {
+        self.revoked_at.is_none() && self.scopes.contains(&scope)
+    }
+}
+
+/// Newly issued API key. The key itself is not stored and can't be retrieved later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssuedApiKey {
+    pub key: String,
+    pub info: ApiKeyInfo,
+}
+
+/// Number of requests made with the API key during the day (UTC).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyUsage {
+    pub day: NaiveDate,
+    pub requests: u64,
+}
+
+/// Parameters of the webhook to be registered.
+/// The filter has the same format as the account filter of the event server subscriptions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewWebhook {
+    pub url: String,
+    pub filter: serde_json::Value,
+}
+
+/// HTTP endpoint receiving the account events matching the filter.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookInfo {
+    pub id: i64,
+    pub url: String,
+    pub filter: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+/// Newly registered webhook together with the secret its callbacks are signed with.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredWebhook {
+    pub secret: String,
+    pub info: WebhookInfo,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    /// The delivery has failed too many times and won't be retried.
+    Dead,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::Dead => "dead",
+        }
+    }
+}
+
+impl FromStr for WebhookDeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "delivered" => Ok(Self::Delivered),
+            "dead" => Ok(Self::Dead),
+            _ => Err(format!("Unknown webhook delivery status: {}", s)),
+        }
+    }
+}
+
+/// Delivery of the event to the webhook.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: u64,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Journal entry of the webhook delivery attempt.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryAttempt {
+    pub attempt: u32,
+    /// HTTP status of the response, if the receiver has responded.
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+/// Transaction of the maintenance account accepted without paying the required fee.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeExemption {
+    pub id: i64,
+    pub tx_hash: TxHash,
+    pub address: Address,
+    pub tx_type: String,
+    /// Fee that would be required from a regular account, in the fee token units.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub required_fee: BigUint,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Maintenance mode of the network. New transactions are rejected by the API while it's enabled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceMode {
+    pub enabled: bool,
+    /// Human-readable reason reported to the clients whose transactions are rejected.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Part of the amounts paid on L1 that was not credited on L2, summed up over the priority
+/// operations with the token executed during the day (UTC).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpSurplus {
+    pub day: NaiveDate,
+    pub token_id: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    pub surplus: BigUint,
+    /// Number of the operations the surplus is known for, including the ones without surplus.
+    pub ops_count: u64,
+}
+
+/// Campaign subsidizing the fees of the partner's transactions with the given fee types.
+/// USD amounts are scaled by 10^6, same as the subsidies in the config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsidyCampaign {
+    pub id: i64,
+    pub partner: String,
+    pub fee_types: Vec<OutputFeeType>,
+    pub budget_usd_scaled: u64,
+    /// Subsidies of the executed transactions charged to the budget.
+    pub spent_usd_scaled: u64,
+    /// Subsidies of the accepted transactions which are not executed yet.
+    pub reserved_usd_scaled: u64,
+    pub expires_at: DateTime<Utc>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SubsidyCampaign {
+    pub fn remaining_usd_scaled(&self) -> u64 {
+        self.budget_usd_scaled
+            .saturating_sub(self.spent_usd_scaled)
+            .saturating_sub(self.reserved_usd_scaled)
+    }
+
+    /// Whether the campaign subsidizes the transactions at the given moment.
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.enabled && self.expires_at > now && self.remaining_usd_scaled() > 0
+    }
+
+    pub fn covers(&self, fee_type: OutputFeeType) -> bool {
+        self.fee_types.contains(&fee_type)
+    }
+}
+
+/// Parameters of the new subsidy campaign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSubsidyCampaign {
+    pub partner: String,
+    pub fee_types: Vec<OutputFeeType>,
+    pub budget_usd_scaled: u64,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Amount added to the budget of the subsidy campaign.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsidyCampaignTopUp {
+    pub amount_usd_scaled: u64,
 }
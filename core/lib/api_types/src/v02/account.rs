@@ -1,17 +1,26 @@
 use std::collections::{BTreeMap, HashMap};
 
+use chrono::{DateTime, Utc};
 use num::{BigUint, ToPrimitive};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use zksync_crypto::{serialization::VecFrSerde, Fr};
 use zksync_types::{
-    AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, TokenId, ZkSyncPriorityOp,
+    tx::TxHash, AccountId, Address, BlockNumber, Nonce, PriorityOp, PubKeyHash, SerialId, TokenId,
+    ZkSyncPriorityOp, H256,
 };
-use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
+use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper, ZeroPrefixHexSerde};
 
-use super::pagination::PaginationDirection;
+use super::pagination::{ActivityKind, ActivityPosition, Paginated, PaginationDirection};
+use super::schema;
 use super::token::NFT;
+use super::transaction::{
+    L1Transaction, L2Transaction, Transaction, TransactionData, TxHashSerializeWrapper,
+    TxInBlockStatus, TxType,
+};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountState {
     pub depositing: DepositingAccountBalances,
@@ -19,14 +28,17 @@ pub struct AccountState {
     pub finalized: Option<Account>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Account {
     pub account_id: AccountId,
+    #[schemars(with = "schema::Address")]
     pub address: Address,
     pub nonce: Nonce,
+    #[schemars(with = "schema::PubKeyHash")]
     pub pub_key_hash: PubKeyHash,
     pub last_update_in_block: BlockNumber,
+    #[schemars(with = "BTreeMap<String, schema::BigUint>")]
     pub balances: BTreeMap<String, BigUintSerdeWrapper>,
     pub nfts: BTreeMap<TokenId, NFT>,
     pub minted_nfts: BTreeMap<TokenId, NFT>,
@@ -40,11 +52,11 @@ pub enum AccountAddressOrId {
     Id(AccountId),
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 pub enum EthAccountType {
     Owned,
     CREATE2,
-    No2FA(Option<PubKeyHash>),
+    No2FA(#[schemars(with = "Option<schema::PubKeyHash>")] Option<PubKeyHash>),
 }
 
 /// Information about ongoing deposits for certain recipient address.
@@ -92,26 +104,419 @@ impl OngoingDeposit {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DepositingFunds {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
     pub amount: BigUint,
     pub expected_accept_block: u64,
+    /// Number of Ethereum blocks left until the last of the deposits is accepted by the server.
+    #[serde(default)]
+    pub confirmations_left: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DepositingAccountBalances {
     pub balances: HashMap<String, DepositingFunds>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Stage of the priority operation that is not finalized yet.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PendingPriorityOpStatus {
+    /// Operation is included into an Ethereum block, but doesn't have enough confirmations.
+    Unconfirmed,
+    /// Operation has enough confirmations and waits to be executed.
+    Confirmed,
+    /// Operation is executed in the block which is not finalized yet.
+    Executed,
+}
+
+/// Deposit or full exit targeting the account, which is not finalized yet.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPriorityOp {
+    pub serial_id: SerialId,
+    pub op_type: TxType,
+    pub token_id: TokenId,
+    /// `None` for the full exits that are not executed yet.
+    #[schemars(with = "Option<schema::BigUint>")]
+    pub amount: Option<BigUintSerdeWrapper>,
+    #[schemars(with = "schema::H256")]
+    pub eth_hash: H256,
+    pub eth_block: u64,
+    /// Number of Ethereum blocks left until the operation is accepted by the server.
+    pub confirmations_left: u64,
+    pub status: PendingPriorityOpStatus,
+}
+
+impl PendingPriorityOp {
+    pub fn new(op: &PriorityOp, status: PendingPriorityOpStatus, confirmations_left: u64) -> Self {
+        let (op_type, amount) = match &op.data {
+            ZkSyncPriorityOp::Deposit(deposit) => {
+                (TxType::Deposit, Some(deposit.amount.clone().into()))
+            }
+            ZkSyncPriorityOp::FullExit(_) => (TxType::FullExit, None),
+        };
+        Self {
+            serial_id: op.serial_id,
+            op_type,
+            token_id: op.data.token_id(),
+            amount,
+            eth_hash: op.eth_hash,
+            eth_block: op.eth_block,
+            confirmations_left,
+            status,
+        }
+    }
+}
+
+/// Entry of the account activity feed, which merges executed L2 transactions
+/// with executed priority operations related to the account.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", content = "tx", rename_all = "camelCase")]
+pub enum AccountActivity {
+    L2Tx(Transaction),
+    PriorityOp(Transaction),
+}
+
+impl AccountActivity {
+    pub fn kind(&self) -> ActivityKind {
+        match self {
+            AccountActivity::L2Tx(_) => ActivityKind::L2Tx,
+            AccountActivity::PriorityOp(_) => ActivityKind::PriorityOp,
+        }
+    }
+
+    pub fn tx(&self) -> &Transaction {
+        match self {
+            AccountActivity::L2Tx(tx) | AccountActivity::PriorityOp(tx) => tx,
+        }
+    }
+
+    /// Returns the cursor pointing to this entry of the feed.
+    pub fn position(&self) -> Option<ActivityPosition> {
+        let tx = self.tx();
+        Some(ActivityPosition {
+            block_number: tx.block_number?,
+            kind: self.kind(),
+            block_index: tx.block_index?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl Default for ExportFormat {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+/// Query of the account activity export.
+///
+/// Both bounds of the block range are inclusive. To resume the interrupted export,
+/// `after` is set to the position of the last exported record.
+#[derive(Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct AccountExportQuery {
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    #[serde(default)]
+    pub format: ExportFormat,
+    pub after: Option<ActivityPosition>,
+}
+
+/// Direction of the funds movement relative to the exported account.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportDirection {
+    Incoming,
+    Outgoing,
+    /// Operation of the account with itself, e.g. the transfer to the own address.
+    Internal,
+}
+
+/// Record of the account activity export, one per entry of the activity feed.
+///
+/// `amount` is denominated in `token`, while `fee` is denominated in `fee_token`,
+/// since some operations pay the fee in a different token.
+/// Swaps are exported by the side of the order the account receives the funds with.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountExportRecord {
+    pub block_number: BlockNumber,
+    pub block_index: u32,
+    pub kind: ActivityKind,
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    pub tx_hash: TxHash,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub direction: ExportDirection,
+    pub counterparty: Option<Address>,
+    pub token: TokenId,
+    pub amount: Option<BigUintSerdeWrapper>,
+    pub fee_token: Option<TokenId>,
+    pub fee: Option<BigUintSerdeWrapper>,
+    pub status: TxInBlockStatus,
+}
+
+impl AccountExportRecord {
+    /// Flattens the activity feed entry of the account with the given address.
+    /// Returns `None` if the entry is not included in a block.
+    pub fn from_activity(activity: &AccountActivity, address: Address) -> Option<Self> {
+        let position = activity.position()?;
+        let tx = activity.tx();
+
+        let direction = |from: Address, to: Address| {
+            if from == to {
+                ExportDirection::Internal
+            } else if to == address {
+                ExportDirection::Incoming
+            } else {
+                ExportDirection::Outgoing
+            }
+        };
+        let counterparty = |from: Address, to: Address| {
+            if from == to {
+                None
+            } else if to == address {
+                Some(from)
+            } else {
+                Some(to)
+            }
+        };
+        // The fee is paid by the sender only.
+        let fee = |from: Address, fee_token: TokenId, fee: &BigUint| {
+            if from == address {
+                (Some(fee_token), Some(fee.clone().into()))
+            } else {
+                (None, None)
+            }
+        };
+
+        let (direction, counterparty, token, amount, (fee_token, fee)) = match &tx.op {
+            TransactionData::L1(L1Transaction::Deposit(deposit)) => (
+                direction(deposit.from, deposit.to),
+                counterparty(deposit.from, deposit.to),
+                deposit.token_id,
+                Some(deposit.amount.clone().into()),
+                (None, None),
+            ),
+            // Withdrawn amount is not a part of the operation.
+            TransactionData::L1(L1Transaction::FullExit(full_exit)) => (
+                ExportDirection::Outgoing,
+                None,
+                full_exit.token_id,
+                None,
+                (None, None),
+            ),
+            TransactionData::L2(L2Transaction::Transfer(transfer)) => (
+                direction(transfer.from, transfer.to),
+                counterparty(transfer.from, transfer.to),
+                transfer.token,
+                Some(transfer.amount.clone().into()),
+                fee(transfer.from, transfer.token, &transfer.fee),
+            ),
+            TransactionData::L2(L2Transaction::Withdraw(withdraw)) => {
+                let withdraw = &withdraw.tx;
+                (
+                    direction(withdraw.from, withdraw.to),
+                    counterparty(withdraw.from, withdraw.to),
+                    withdraw.token,
+                    Some(withdraw.amount.clone().into()),
+                    fee(withdraw.from, withdraw.token, &withdraw.fee),
+                )
+            }
+            TransactionData::L2(L2Transaction::WithdrawNFT(withdraw)) => {
+                let withdraw = &withdraw.tx;
+                (
+                    direction(withdraw.from, withdraw.to),
+                    counterparty(withdraw.from, withdraw.to),
+                    withdraw.token,
+                    Some(BigUint::from(1u32).into()),
+                    fee(withdraw.from, withdraw.fee_token, &withdraw.fee),
+                )
+            }
+            TransactionData::L2(L2Transaction::MintNFT(mint)) => (
+                direction(mint.creator_address, mint.recipient),
+                counterparty(mint.creator_address, mint.recipient),
+                mint.fee_token,
+                None,
+                fee(mint.creator_address, mint.fee_token, &mint.fee),
+            ),
+            // Balance of the target is withdrawn in full, the amount is not a part of the transaction.
+            // The initiator is known only by the account ID, so the target can't be told apart from it.
+            TransactionData::L2(L2Transaction::ForcedExit(forced_exit)) => {
+                let forced_exit = &forced_exit.tx;
+                if forced_exit.target == address {
+                    (
+                        ExportDirection::Outgoing,
+                        None,
+                        forced_exit.token,
+                        None,
+                        (None, None),
+                    )
+                } else {
+                    (
+                        ExportDirection::Outgoing,
+                        Some(forced_exit.target),
+                        forced_exit.token,
+                        None,
+                        (
+                            Some(forced_exit.token),
+                            Some(forced_exit.fee.clone().into()),
+                        ),
+                    )
+                }
+            }
+            TransactionData::L2(L2Transaction::ChangePubKey(change_pub_key)) => (
+                ExportDirection::Internal,
+                None,
+                change_pub_key.fee_token,
+                None,
+                fee(
+                    change_pub_key.account,
+                    change_pub_key.fee_token,
+                    &change_pub_key.fee,
+                ),
+            ),
+            TransactionData::L2(L2Transaction::Swap(swap)) => {
+                let (orders, amounts) = (&swap.orders, &swap.amounts);
+                let received = if orders.0.recipient_address == address {
+                    Some((&orders.0, &orders.1, &amounts.1))
+                } else if orders.1.recipient_address == address {
+                    Some((&orders.1, &orders.0, &amounts.0))
+                } else {
+                    None
+                };
+                let fee = fee(swap.submitter_address, swap.fee_token, &swap.fee);
+                match received {
+                    Some((order, counter_order, amount)) => (
+                        ExportDirection::Incoming,
+                        Some(counter_order.recipient_address),
+                        order.token_buy,
+                        Some(amount.clone().into()),
+                        fee,
+                    ),
+                    None => (ExportDirection::Outgoing, None, swap.fee_token, None, fee),
+                }
+            }
+            TransactionData::L2(L2Transaction::Close(_)) => (
+                ExportDirection::Internal,
+                None,
+                TokenId(0),
+                None,
+                (None, None),
+            ),
+        };
+
+        Some(Self {
+            block_number: position.block_number,
+            block_index: position.block_index,
+            kind: position.kind,
+            tx_hash: tx.tx_hash,
+            timestamp: tx.created_at,
+            direction,
+            counterparty,
+            token,
+            amount,
+            fee_token,
+            fee,
+            status: tx.status,
+        })
+    }
+
+    pub fn position(&self) -> ActivityPosition {
+        ActivityPosition {
+            block_number: self.block_number,
+            kind: self.kind,
+            block_index: self.block_index,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IncomingAccountTxsQuery {
     pub from: String,
     pub limit: u32,
     pub direction: PaginationDirection,
     pub token: Option<String>,
+    /// Also list the transactions which use the `token` only to pay the fee.
+    #[serde(default)]
+    pub include_fee_token: bool,
     pub second_account: Option<String>,
 }
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitDataQuery {
+    pub token: String,
+}
+
+/// Block data in the format of the `StoredBlockInfo` structure of the main contract.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredBlockInfo {
+    pub block_number: BlockNumber,
+    pub priority_operations: u64,
+    #[schemars(with = "schema::H256")]
+    pub pending_onchain_operations_hash: H256,
+    pub timestamp: u64,
+    #[schemars(with = "schema::H256")]
+    pub state_hash: H256,
+    #[schemars(with = "schema::H256")]
+    pub commitment: H256,
+}
+
+/// Inputs required to prove the ownership of the funds in exodus mode.
+/// Contains the arguments of the contract exit function, except for the proof itself,
+/// and the Merkle paths of the account in the last verified state needed to generate the proof.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountExitData {
+    pub stored_block_info: StoredBlockInfo,
+    #[schemars(with = "schema::Address")]
+    pub owner: Address,
+    pub account_id: AccountId,
+    pub token_id: TokenId,
+    #[schemars(with = "schema::Address")]
+    pub token_address: Address,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub amount: BigUint,
+    pub nonce: Nonce,
+    #[schemars(with = "schema::PubKeyHash")]
+    pub pub_key_hash: PubKeyHash,
+    pub nft_creator_id: AccountId,
+    #[schemars(with = "schema::Address")]
+    pub nft_creator_address: Address,
+    pub nft_serial_id: u32,
+    #[schemars(with = "schema::H256")]
+    pub nft_content_hash: H256,
+    /// Sibling hashes on the path from the account leaf to the root of the account tree.
+    #[serde(with = "VecFrSerde")]
+    #[schemars(with = "Vec<schema::Fr>")]
+    pub account_path: Vec<Fr>,
+    /// Sibling hashes on the path from the token leaf to the root of the account balance tree.
+    #[serde(with = "VecFrSerde")]
+    #[schemars(with = "Vec<schema::Fr>")]
+    pub balance_path: Vec<Fr>,
+}
+
+/// Pending operations of the account.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountPendingTxs {
+    #[serde(flatten)]
+    pub txs: Paginated<Transaction, SerialId>,
+    /// Queued transaction of the account that is parked or keeps failing, so the transactions
+    /// with the higher nonces can't be executed until it's replaced.
+    pub blocked_by: Option<TxHashSerializeWrapper>,
+}
@@ -1,25 +1,187 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
 use zksync_crypto::{serialization::FrSerde, Fr};
 use zksync_types::{BlockNumber, H256};
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+use super::{pagination::PaginationDirection, schema};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum BlockStatus {
     Committed,
     Finalized,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockInfo {
     pub block_number: BlockNumber,
     #[serde(with = "FrSerde")]
+    #[schemars(with = "schema::Fr")]
     pub new_state_root: Fr,
     pub block_size: u64,
+    #[schemars(with = "Option<schema::H256>")]
     pub commit_tx_hash: Option<H256>,
+    #[schemars(with = "Option<schema::H256>")]
     pub verify_tx_hash: Option<H256>,
     pub committed_at: DateTime<Utc>,
     pub finalized_at: Option<DateTime<Utc>>,
     pub status: BlockStatus,
 }
+
+/// Aggregated operation including the block.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockAggregatedOperation {
+    pub confirmed: bool,
+    /// Hash of the L1 transaction, the last sent one if the operation is not confirmed yet.
+    #[schemars(with = "Option<schema::H256>")]
+    pub eth_tx_hash: Option<H256>,
+    /// Range of the blocks included in the same operation.
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+}
+
+/// Aggregated operations including the block on every stage of its processing on L1,
+/// `None` if the block hasn't reached the stage yet.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockAggregatedOperations {
+    pub block_number: BlockNumber,
+    pub commit_blocks: Option<BlockAggregatedOperation>,
+    pub publish_proof_blocks_onchain: Option<BlockAggregatedOperation>,
+    pub execute_blocks: Option<BlockAggregatedOperation>,
+}
+
+/// Query of the block transactions page, `tx_type` is parsed into `TxType` by the server.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingBlockTxsQuery {
+    pub from: String,
+    pub limit: u32,
+    pub direction: PaginationDirection,
+    pub tx_type: Option<String>,
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Unknown order of the blocks: {0}")]
+pub struct UnknownBlocksOrder(pub String);
+
+/// Order of the blocks page, which also defines the cursor the blocks are paginated by.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlocksOrder {
+    /// Blocks are paginated by their numbers.
+    Number,
+    /// Blocks are paginated by their timestamps, the cursor is `BlockTimestamp`.
+    Timestamp,
+}
+
+impl BlocksOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlocksOrder::Number => "number",
+            BlocksOrder::Timestamp => "timestamp",
+        }
+    }
+}
+
+impl Default for BlocksOrder {
+    fn default() -> Self {
+        BlocksOrder::Number
+    }
+}
+
+impl FromStr for BlocksOrder {
+    type Err = UnknownBlocksOrder;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "number" => Ok(BlocksOrder::Number),
+            "timestamp" => Ok(BlocksOrder::Timestamp),
+            _ => Err(UnknownBlocksOrder(s.to_string())),
+        }
+    }
+}
+
+/// Query of the blocks page, `order_by` is parsed into `BlocksOrder` by the server.
+/// Blocks are ordered by their numbers unless asked otherwise.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomingBlocksQuery {
+    pub from: String,
+    pub limit: u32,
+    pub direction: PaginationDirection,
+    pub order_by: Option<String>,
+}
+
+/// Query of the block range export, both bounds are inclusive.
+#[derive(Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct BlockExportQuery {
+    pub from: BlockNumber,
+    pub to: BlockNumber,
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Cannot parse block position: {0}")]
+pub struct UnknownBlockPosition(pub String);
+
+/// Position of the block in the chain: either the block number or one of the keywords
+/// `lastCommitted` and `lastFinalized`, which are resolved to the tip of the chain.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlockPosition {
+    Number(BlockNumber),
+    LastCommitted,
+    LastFinalized,
+}
+
+impl fmt::Display for BlockPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockPosition::Number(block_number) => write!(f, "{}", block_number),
+            BlockPosition::LastCommitted => write!(f, "lastCommitted"),
+            BlockPosition::LastFinalized => write!(f, "lastFinalized"),
+        }
+    }
+}
+
+impl FromStr for BlockPosition {
+    type Err = UnknownBlockPosition;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lastCommitted" => Ok(BlockPosition::LastCommitted),
+            "lastFinalized" => Ok(BlockPosition::LastFinalized),
+            _ => u32::from_str(s)
+                .map(|number| BlockPosition::Number(BlockNumber(number)))
+                .map_err(|_| UnknownBlockPosition(s.to_string())),
+        }
+    }
+}
+
+impl From<BlockNumber> for BlockPosition {
+    fn from(block_number: BlockNumber) -> Self {
+        BlockPosition::Number(block_number)
+    }
+}
+
+impl Serialize for BlockPosition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockPosition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zksync_types::{network::Network, Address};
+
+use super::{schema, transaction::SubmissionLimits, ZksyncVersion};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiConfigData {
+    pub network: Network,
+    #[schemars(with = "schema::Address")]
+    pub contract: Address,
+    #[schemars(with = "schema::Address")]
+    pub gov_contract: Address,
+    /// Number of the Ethereum confirmations required for the priority operations, deposits included.
+    pub deposit_confirmations: u64,
+    pub zksync_version: ZksyncVersion,
+    /// Version of the contracts set by the latest upgrade, `None` if no upgrades were observed.
+    pub contract_upgrade_version: Option<u64>,
+    #[schemars(with = "schema::Address")]
+    pub contract_target: Address,
+    pub submission_limits: SubmissionLimits,
+    /// Address of the default NFT factory, `None` if it's not known to the server yet.
+    #[schemars(with = "Option<schema::Address>")]
+    pub nft_factory: Option<Address>,
+    /// Maximum number of the pubdata chunks in a block.
+    pub max_block_chunks: u64,
+    // TODO: server_version (ZKS-627)
+}
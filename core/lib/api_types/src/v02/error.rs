@@ -0,0 +1,292 @@
+// Built-in uses
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+// External uses
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+// Local uses
+use super::{
+    block::UnknownBlocksOrder, pagination::UnknownFromParameter, transaction::UnknownTxType,
+};
+
+/// Defines the error codes of the REST API along with their numeric values.
+macro_rules! error_codes {
+    ($($(#[$attr:meta])* $name:ident = $code:literal,)*) => {
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum ErrorCode {
+            $($(#[$attr])* $name,)*
+            /// Code of the transaction submission failure, shared with the JSON-RPC API.
+            Submission(u16),
+        }
+
+        impl ErrorCode {
+            /// Numeric value of the code reported by the API.
+            pub fn code(&self) -> u16 {
+                match self {
+                    $(Self::$name => $code,)*
+                    Self::Submission(code) => *code,
+                }
+            }
+        }
+
+        /// Codes unknown to the REST API are the submission ones.
+        impl From<u16> for ErrorCode {
+            fn from(code: u16) -> Self {
+                match code {
+                    $($code => Self::$name,)*
+                    code => Self::Submission(code),
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    UnreacheableError = 0,
+    CoreApiError = 100,
+    TokenZeroPriceError = 200,
+    InvalidCurrency = 201,
+    InvalidBlockPosition = 202,
+    InvalidAccountIdOrAddress = 203,
+    AccountNotFound = 204,
+    TransactionNotFound = 205,
+    PaginationLimitTooBig = 206,
+    QueryDeserializationError = 207,
+    InvalidNFTTokenId = 208,
+    ExitDataDisabled = 209,
+    NFTExitDataUnsupported = 210,
+    InvalidExportRange = 211,
+    ExportRangeTooBig = 212,
+    InvalidTxHashLength = 213,
+    InvalidTxHashHex = 214,
+    UnsupportedTxHashPrefix = 215,
+    TooManyTxHashes = 216,
+    InvalidTxType = 217,
+    TransactionNotOfAccount = 218,
+    ExportBlocksNotFinalized = 219,
+    BlockNotFound = 220,
+    NotWithdrawalTx = 221,
+    StorageError = 300,
+    InvalidApiKey = 400,
+    ApiKeyScopeNotAllowed = 401,
+    RateLimitExceeded = 402,
+    ApiKeyRequired = 403,
+    TokenNotFound = 500,
+    ExternalApiError = 501,
+    TokenPriceUnavailable = 502,
+    InternalError = 600,
+    InvalidParams = 602,
+    // The rest of the submission failures are reported with the `Submission` codes,
+    // these ones are only kept for the responses of the older servers.
+    AccountCloseDisabled = 601,
+    UnsupportedFastProcessing = 603,
+    IncorrectTx = 604,
+    TxAddError = 605,
+    InappropriateFeeToken = 606,
+    CommunicationCoreServer = 607,
+    Toggle2FAError = 608,
+    Other = 60_000,
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u16(self.code())
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrorCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u16::deserialize(deserializer).map(ErrorCode::from)
+    }
+}
+
+/// Type of the transaction submission errors.
+pub const SUBMIT_ERROR_TYPE: &str = "submitError";
+
+/// Range of the submission codes shared with the JSON-RPC API. Some of them have the same values
+/// as the REST codes, so they're told apart by the type of the error.
+pub const SUBMISSION_CODES: Range<u16> = 100..600;
+
+/// Error object in a response
+#[derive(Clone, Debug, Serialize, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Error {
+    pub error_type: String,
+    #[schemars(with = "u16")]
+    pub code: ErrorCode,
+    pub message: String,
+    /// Machine-readable details of the error, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// Error object as it's sent over the wire.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawError {
+    error_type: String,
+    code: u16,
+    message: String,
+    #[serde(default)]
+    data: Option<Value>,
+}
+
+impl<'de> Deserialize<'de> for Error {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawError::deserialize(deserializer)?;
+        let code = if raw.error_type == SUBMIT_ERROR_TYPE && SUBMISSION_CODES.contains(&raw.code) {
+            ErrorCode::Submission(raw.code)
+        } else {
+            ErrorCode::from(raw.code)
+        };
+        Ok(Self {
+            error_type: raw.error_type,
+            code,
+            message: raw.message,
+            data: raw.data,
+        })
+    }
+}
+
+/// Trait that can be used to map custom errors to the object.
+pub trait ApiError: std::fmt::Display {
+    fn error_type(&self) -> String;
+
+    fn code(&self) -> ErrorCode;
+
+    fn message(&self) -> String {
+        self.to_string()
+    }
+
+    fn data(&self) -> Option<Value> {
+        None
+    }
+}
+
+impl<T> From<T> for Error
+where
+    T: ApiError,
+{
+    fn from(t: T) -> Error {
+        Error {
+            error_type: t.error_type(),
+            code: t.code(),
+            message: t.message(),
+            data: t.data(),
+        }
+    }
+}
+
+impl Error {
+    pub fn new(error_type: impl Into<String>, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            error_type: error_type.into(),
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn storage(err: impl Display) -> Error {
+        Error::from(StorageError::new(err))
+    }
+
+    pub fn core_api(err: impl Display) -> Error {
+        Error::from(CoreApiError::new(err))
+    }
+}
+
+#[derive(Debug)]
+pub struct StorageError(String);
+
+impl StorageError {
+    pub fn new(title: impl Display) -> Self {
+        Self(title.to_string())
+    }
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl ApiError for StorageError {
+    fn error_type(&self) -> String {
+        String::from("storageError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::StorageError
+    }
+}
+
+#[derive(Debug)]
+pub struct CoreApiError(String);
+
+impl CoreApiError {
+    pub fn new(title: impl Display) -> Self {
+        Self(title.to_string())
+    }
+}
+
+impl Display for CoreApiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl ApiError for CoreApiError {
+    fn error_type(&self) -> String {
+        String::from("coreApiError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::CoreApiError
+    }
+}
+
+/// Unexpected errors that can't be classified are reported as the internal ones.
+impl ApiError for anyhow::Error {
+    fn error_type(&self) -> String {
+        "internalError".to_string()
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::Other
+    }
+}
+
+impl ApiError for UnknownFromParameter {
+    fn error_type(&self) -> String {
+        String::from("invalidDataError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::QueryDeserializationError
+    }
+}
+
+impl ApiError for UnknownBlocksOrder {
+    fn error_type(&self) -> String {
+        String::from("invalidDataError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::QueryDeserializationError
+    }
+}
+
+impl ApiError for UnknownTxType {
+    fn error_type(&self) -> String {
+        String::from("invalidDataError")
+    }
+
+    fn code(&self) -> ErrorCode {
+        ErrorCode::InvalidTxType
+    }
+}
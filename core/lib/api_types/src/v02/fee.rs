@@ -1,40 +1,63 @@
+use bigdecimal::BigDecimal;
 use num::BigUint;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use zksync_types::{tokens::ChangePubKeyFeeTypeArg, Address, BatchFee, Fee, TokenLike, TxFeeTypes};
+use zksync_types::{
+    tokens::ChangePubKeyFeeTypeArg, Address, BatchFee, Fee, TokenId, TokenLike, TxFeeTypes,
+};
 use zksync_utils::BigUintSerdeAsRadix10Str;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+use super::schema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiFee {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
     pub gas_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
     pub zkp_fee: BigUint,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
     pub total_fee: BigUint,
+    /// Gas price used by the ticker to calculate the gas part of the fee.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub gas_price_wei: BigUint,
+    /// Value of the total fee in USD at the current price of the token.
+    #[schemars(with = "schema::BigDecimal")]
+    pub total_fee_usd: BigDecimal,
 }
 
-impl From<Fee> for ApiFee {
-    fn from(fee: Fee) -> Self {
-        ApiFee {
+impl ApiFee {
+    pub fn new(fee: Fee, total_fee_usd: BigDecimal) -> Self {
+        Self {
             gas_fee: fee.gas_fee,
             zkp_fee: fee.zkp_fee,
             total_fee: fee.total_fee,
+            gas_price_wei: fee.gas_price_wei,
+            total_fee_usd,
         }
     }
-}
 
-impl From<BatchFee> for ApiFee {
-    fn from(fee: BatchFee) -> Self {
-        ApiFee {
+    /// Unlike the fee for a single transaction, the batch fee doesn't carry the gas price.
+    pub fn from_batch_fee(
+        fee: BatchFee,
+        gas_price_wei: BigUint,
+        total_fee_usd: BigDecimal,
+    ) -> Self {
+        Self {
             gas_fee: fee.gas_fee,
             zkp_fee: fee.zkp_fee,
             total_fee: fee.total_fee,
+            gas_price_wei,
+            total_fee_usd,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub enum ApiTxFeeTypes {
     /// Fee for the `Withdraw` transaction.
     Withdraw,
@@ -71,24 +94,52 @@ impl From<ApiTxFeeTypes> for TxFeeTypes {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TxFeeRequest {
     pub tx_type: ApiTxFeeTypes,
+    #[schemars(with = "schema::Address")]
     pub address: Address,
     pub token_like: TokenLike,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TxInBatchFeeRequest {
     pub tx_type: ApiTxFeeTypes,
+    #[schemars(with = "schema::Address")]
     pub address: Address,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchFeeRequest {
     pub transactions: Vec<TxInBatchFeeRequest>,
     pub token_like: TokenLike,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalEstimateQuery {
+    pub token: String,
+}
+
+/// Expected L1 cost of a withdrawal compared with the current zkSync fee for it.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiWithdrawalEstimate {
+    pub token_id: TokenId,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub gas_amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub gas_price_wei: BigUint,
+    /// Estimated L1 cost in the smallest units of the token.
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub l1_cost: BigUint,
+    #[schemars(with = "schema::BigDecimal")]
+    pub l1_cost_usd: BigDecimal,
+    pub zksync_fee: ApiFee,
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
@@ -6,32 +7,39 @@ use zksync_types::network::Network;
 
 pub mod account;
 pub mod block;
+pub mod config;
+pub mod error;
 pub mod fee;
 pub mod pagination;
+pub mod schema;
+pub mod search;
 pub mod status;
 pub mod token;
 pub mod transaction;
 
-#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg(test)]
+mod tests;
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ZksyncVersion {
     ContractV4,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ApiVersion {
     V02,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum ResultStatus {
     Success,
     Error,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Request {
     pub network: Network,
@@ -41,10 +49,14 @@ pub struct Request {
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub request: Request,
+    /// Id of the request, the log lines of the server related to it carry the same one.
+    /// Empty in the responses of the servers that don't assign the ids.
+    #[serde(default)]
+    pub request_id: String,
     pub status: ResultStatus,
     pub error: Option<Value>,
     pub result: Option<Value>,
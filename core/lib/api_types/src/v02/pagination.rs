@@ -1,18 +1,32 @@
+use chrono::{DateTime, TimeZone, Utc};
 use either::Either;
-use serde::{Deserialize, Serialize, Serializer};
-use std::str::FromStr;
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
 use thiserror::Error;
-use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, SerialId, TokenId};
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber, Nonce, SerialId, TokenId};
 
+use super::transaction::TxType;
+
+/// Default maximum of the pagination limit accepted by the server.
 pub const MAX_LIMIT: u32 = 100;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum PaginationDirection {
     Newer,
     Older,
 }
 
+impl PaginationDirection {
+    pub fn opposite(self) -> Self {
+        match self {
+            PaginationDirection::Newer => PaginationDirection::Older,
+            PaginationDirection::Older => PaginationDirection::Newer,
+        }
+    }
+}
+
 /// The struct for defining `latest` option in pagination query
 #[derive(Debug)]
 pub struct Latest;
@@ -75,7 +89,7 @@ pub fn parse_query<T: FromStr + Serialize>(
     })
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PaginationQuery<Id> {
     pub from: Id,
@@ -83,16 +97,33 @@ pub struct PaginationQuery<Id> {
     pub direction: PaginationDirection,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Flag of the paginated requests that only need the total count of the items.
+///
+/// When set, the page is not loaded: the response has an empty `list` and the accurate `count`.
+/// The `from` cursor is still checked, and the `limit` is still validated but otherwise ignored.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CountOnlyQuery {
+    #[serde(default, alias = "count_only")]
+    pub count_only: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PaginationDetails<F: Serialize> {
     pub from: F,
     pub limit: u32,
     pub direction: PaginationDirection,
     pub count: u32,
+    /// Cursor of the page following this one in the same direction, `None` if this page is the last.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<F>,
+    /// Cursor of the item preceding `from`, i.e. the one to walk back from in the opposite direction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous: Option<F>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Paginated<T: Sized + Serialize, F: Serialize> {
     pub list: Vec<T>,
@@ -114,15 +145,110 @@ impl<T: Sized + Serialize, F: Serialize> Paginated<T, F> {
                 limit,
                 direction,
                 count,
+                next: None,
+                previous: None,
             },
         }
     }
+
+    pub fn with_cursors(mut self, next: Option<F>, previous: Option<F>) -> Self {
+        self.pagination.next = next;
+        self.pagination.previous = previous;
+        self
+    }
 }
 
 #[derive(Debug, Serialize)]
 pub struct BlockAndTxHash {
     pub block_number: BlockNumber,
     pub tx_hash: ApiEither<TxHash>,
+    /// Only the operations of this type are listed.
+    pub tx_type: Option<TxType>,
+}
+
+/// Cursor of the block transactions by the index of the transaction within the block.
+#[derive(Debug, Serialize)]
+pub struct BlockAndTxIndex {
+    pub block_number: BlockNumber,
+    pub block_index: ApiEither<u32>,
+}
+
+/// Cursor of the blocks by the time they were created at.
+///
+/// Blocks are ordered by the timestamp (in seconds since the epoch), the block number is used
+/// to tell apart the blocks created within the same second. In the query string the cursor is
+/// represented as `{timestamp}:{block_number}`, e.g. `1660730400:12`. The block number may be
+/// omitted, then the page starts from the edge of the second in the direction of the page,
+/// e.g. `1660730400` paginated in the `older` direction starts from the last block of that second.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct BlockTimestamp {
+    pub timestamp: u64,
+    pub block_number: Option<BlockNumber>,
+}
+
+impl fmt::Display for BlockTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.block_number {
+            Some(block_number) => write!(f, "{}:{}", self.timestamp, *block_number),
+            None => write!(f, "{}", self.timestamp),
+        }
+    }
+}
+
+impl FromStr for BlockTimestamp {
+    type Err = UnknownFromParameter;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || UnknownFromParameter(s.to_string());
+
+        let mut parts = s.splitn(2, ':');
+        let timestamp = parts.next().ok_or_else(error)?;
+        let timestamp = u64::from_str(timestamp).map_err(|_| error())?;
+        let block_number = match parts.next() {
+            Some(block_number) => Some(BlockNumber(
+                u32::from_str(block_number).map_err(|_| error())?,
+            )),
+            None => None,
+        };
+
+        Ok(Self {
+            timestamp,
+            block_number,
+        })
+    }
+}
+
+impl Serialize for BlockTimestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockTimestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl JsonSchema for BlockTimestamp {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::from("BlockTimestamp")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -132,10 +258,251 @@ pub struct PendingOpsRequest {
     pub serial_id: ApiEither<SerialId>,
 }
 
+/// Cursor of the executed priority operations by the serial id.
+#[derive(Debug, Serialize)]
+pub struct PriorityOpsRequest {
+    pub serial_id: ApiEither<SerialId>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct AccountTxsRequest {
     pub address: Address,
     pub tx_hash: ApiEither<TxHash>,
     pub token: Option<TokenId>,
+    /// Whether the transactions using the `token` only to pay the fee are included.
+    pub include_fee_token: bool,
     pub second_address: Option<Address>,
 }
+
+/// Kind of the operation in the account activity feed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ActivityKind {
+    L2Tx,
+    PriorityOp,
+}
+
+impl ActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityKind::L2Tx => "l2Tx",
+            ActivityKind::PriorityOp => "priorityOp",
+        }
+    }
+}
+
+/// Cursor of the account activity feed.
+///
+/// Operations are ordered by the block number and index within the block,
+/// `kind` is used to tell apart the sources the operation was taken from.
+/// In the query string the cursor is represented as `{block_number}:{kind}:{block_index}`,
+/// e.g. `12:priorityOp:0`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ActivityPosition {
+    pub block_number: BlockNumber,
+    pub kind: ActivityKind,
+    pub block_index: u32,
+}
+
+impl Default for ActivityPosition {
+    fn default() -> Self {
+        Self {
+            block_number: BlockNumber(0),
+            kind: ActivityKind::L2Tx,
+            block_index: 0,
+        }
+    }
+}
+
+impl fmt::Display for ActivityPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}",
+            *self.block_number,
+            self.kind.as_str(),
+            self.block_index
+        )
+    }
+}
+
+impl FromStr for ActivityPosition {
+    type Err = UnknownFromParameter;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || UnknownFromParameter(s.to_string());
+
+        let mut parts = s.split(':');
+        let (block_number, kind, block_index) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(block_number), Some(kind), Some(block_index)) if parts.next().is_none() => {
+                (block_number, kind, block_index)
+            }
+            _ => return Err(error()),
+        };
+        let block_number = u32::from_str(block_number).map_err(|_| error())?;
+        let kind = match kind {
+            "l2Tx" => ActivityKind::L2Tx,
+            "priorityOp" => ActivityKind::PriorityOp,
+            _ => return Err(error()),
+        };
+        let block_index = u32::from_str(block_index).map_err(|_| error())?;
+
+        Ok(Self {
+            block_number: BlockNumber(block_number),
+            kind,
+            block_index,
+        })
+    }
+}
+
+impl Serialize for ActivityPosition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ActivityPosition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl JsonSchema for ActivityPosition {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::from("ActivityPosition")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountActivityRequest {
+    pub address: Address,
+    pub position: ApiEither<ActivityPosition>,
+}
+
+/// Cursor of the queued transactions of the account.
+///
+/// Transactions are ordered by nonce, the time they were received at and the hash are used to tell
+/// apart the transactions with the same nonce. In the query string the cursor is represented
+/// as `{nonce}:{created_at}:{tx_hash}`, where `created_at` is the number of microseconds since
+/// the epoch, e.g. `5:1660730400000000:0x0101...01`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct QueuedTxPosition {
+    pub nonce: Nonce,
+    pub created_at: DateTime<Utc>,
+    pub tx_hash: TxHash,
+}
+
+impl Default for QueuedTxPosition {
+    fn default() -> Self {
+        Self {
+            nonce: Nonce(0),
+            created_at: Utc.timestamp(0, 0),
+            tx_hash: TxHash::default(),
+        }
+    }
+}
+
+impl fmt::Display for QueuedTxPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let created_at = self.created_at.timestamp() * 1_000_000
+            + i64::from(self.created_at.timestamp_subsec_micros());
+        write!(
+            f,
+            "{}:{}:0x{}",
+            self.nonce,
+            created_at,
+            hex::encode(self.tx_hash.as_ref())
+        )
+    }
+}
+
+impl FromStr for QueuedTxPosition {
+    type Err = UnknownFromParameter;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || UnknownFromParameter(s.to_string());
+
+        let mut parts = s.splitn(3, ':');
+        let (nonce, created_at, tx_hash) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(nonce), Some(created_at), Some(tx_hash)) => (nonce, created_at, tx_hash),
+            _ => return Err(error()),
+        };
+        let nonce = u32::from_str(nonce).map_err(|_| error())?;
+        let created_at = i64::from_str(created_at).map_err(|_| error())?;
+        let created_at = Utc
+            .timestamp_opt(
+                created_at.div_euclid(1_000_000),
+                created_at.rem_euclid(1_000_000) as u32 * 1_000,
+            )
+            .single()
+            .ok_or_else(error)?;
+        let tx_hash = TxHash::from_str(tx_hash).map_err(|_| error())?;
+
+        Ok(Self {
+            nonce: Nonce(nonce),
+            created_at,
+            tx_hash,
+        })
+    }
+}
+
+impl Serialize for QueuedTxPosition {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for QueuedTxPosition {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl JsonSchema for QueuedTxPosition {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::from("QueuedTxPosition")
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        String::json_schema(gen)
+    }
+}
+
+/// Cursor of the NFTs owned by the account.
+#[derive(Debug, Serialize)]
+pub struct AccountNFTsRequest {
+    pub account_id: AccountId,
+    pub token_id: ApiEither<TokenId>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountQueuedTxsRequest {
+    pub address: Address,
+    pub position: ApiEither<QueuedTxPosition>,
+}
@@ -0,0 +1,85 @@
+//! JSON schemas of the foreign types which don't describe themselves.
+//!
+//! Types are used in place of the original ones in the `#[schemars(with = "...")]` attributes,
+//! e.g. `#[schemars(with = "schema::Address")]`. Types of `zksync_types`, like `TokenId`,
+//! derive their schemas with the `json_schema` feature and need no replacement.
+
+use std::collections::BTreeMap;
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde_json::Value;
+
+macro_rules! string_schema {
+    ($(#[$attr:meta])* $name:ident, $format:literal) => {
+        $(#[$attr])*
+        pub struct $name;
+
+        impl JsonSchema for $name {
+            fn is_referenceable() -> bool {
+                false
+            }
+
+            fn schema_name() -> String {
+                stringify!($name).to_string()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                SchemaObject {
+                    instance_type: Some(InstanceType::String.into()),
+                    format: Some($format.to_string()),
+                    ..Default::default()
+                }
+                .into()
+            }
+        }
+    };
+}
+
+string_schema!(
+    /// Ethereum address, `0x`-prefixed hex string.
+    Address,
+    "address"
+);
+
+string_schema!(
+    /// 32-byte hash, `0x`-prefixed hex string.
+    H256,
+    "h256"
+);
+
+string_schema!(
+    /// Hash of the zkSync transaction, `sync-tx:` or `0x`-prefixed hex string.
+    TxHash,
+    "tx-hash"
+);
+
+string_schema!(
+    /// Hash of the zkSync public key, `sync:`-prefixed hex string.
+    PubKeyHash,
+    "pub-key-hash"
+);
+
+string_schema!(
+    /// Unsigned integer of arbitrary size, decimal string.
+    BigUint,
+    "biguint"
+);
+
+string_schema!(
+    /// Decimal number, string.
+    BigDecimal,
+    "decimal"
+);
+
+string_schema!(
+    /// Element of the circuit field, `0x`-prefixed hex string.
+    Fr,
+    "fr"
+);
+
+/// Object with arbitrary fields, used for the types of `zksync_types`, e.g. the transactions.
+pub type Object = BTreeMap<String, Value>;
@@ -0,0 +1,61 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use zksync_types::{tx::TxHash, AccountId, Address, BlockNumber};
+
+use super::schema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct SearchQuery {
+    pub query: String,
+}
+
+/// Resource matched by the search query, along with the API path it's available at.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SearchMatch {
+    #[serde(rename_all = "camelCase")]
+    Block {
+        block_number: BlockNumber,
+        path: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Transaction {
+        #[schemars(with = "schema::TxHash")]
+        tx_hash: TxHash,
+        path: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Account {
+        account_id: AccountId,
+        #[schemars(with = "schema::Address")]
+        address: Address,
+        path: String,
+    },
+}
+
+impl SearchMatch {
+    pub fn block(block_number: BlockNumber) -> Self {
+        Self::Block {
+            block_number,
+            path: format!("/api/v0.2/blocks/{}", block_number),
+        }
+    }
+
+    pub fn transaction(tx_hash: TxHash) -> Self {
+        Self::Transaction {
+            tx_hash,
+            path: format!(
+                "/api/v0.2/transactions/0x{}/data",
+                hex::encode(tx_hash.as_ref())
+            ),
+        }
+    }
+
+    pub fn account(account_id: AccountId, address: Address) -> Self {
+        Self::Account {
+            account_id,
+            address,
+            path: format!("/api/v0.2/accounts/{}", account_id),
+        }
+    }
+}
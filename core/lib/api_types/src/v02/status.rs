@@ -1,8 +1,11 @@
+use std::collections::BTreeMap;
+
 use crate::CoreStatus;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use zksync_types::BlockNumber;
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NetworkStatus {
     pub last_committed: BlockNumber,
@@ -11,3 +14,52 @@ pub struct NetworkStatus {
     pub mempool_size: u32,
     pub core_status: Option<CoreStatus>,
 }
+
+/// Current state of the mempool.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MempoolStatus {
+    /// Number of the transactions in the mempool.
+    pub size: u32,
+    /// Age of the oldest transaction in the mempool, `None` if the mempool is empty.
+    pub oldest_tx_age_secs: Option<u64>,
+    /// Number of the transactions of every type, e.g. `Transfer`.
+    pub tx_counts: BTreeMap<String, u32>,
+}
+
+/// Liveness of the API server, reported without checking its dependencies.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    pub uptime_secs: u64,
+}
+
+/// Dependency of the API server checked by the readiness probe.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ReadinessCheckKind {
+    /// Connection can be acquired from the database pool.
+    Database,
+    /// Core server answers the requests of its private API.
+    CoreApi,
+    /// Last committed block is not older than the configured threshold.
+    LastBlockAge,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessCheck {
+    pub check: ReadinessCheckKind,
+    pub passed: bool,
+    pub latency_ms: u64,
+    /// Reason of the failure, `None` if the check has passed.
+    pub error: Option<String>,
+}
+
+/// Result of the readiness probe, the server is ready if all the checks have passed.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<ReadinessCheck>,
+}
@@ -0,0 +1,1639 @@
+//! Snapshots of the JSON representation of the API v0.2 wire types.
+//!
+//! Both the server and the client use the types from this crate, so any change in the JSON shape
+//! must go through these definitions. Every sample is built with an exhaustive struct literal
+//! or `match`, so a new field or variant doesn't compile until its snapshot is updated.
+
+use std::{collections::HashMap, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use zksync_crypto::{ff::Field, Fr};
+use zksync_types::{
+    network::Network, tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, tx::TxHash, AccountId,
+    Address, BlockNumber, EthBlockId, Nonce, PubKeyHash, TokenId, TokenLike, H256,
+};
+use zksync_utils::BigUintSerdeWrapper;
+
+use super::{
+    account::{
+        Account, AccountActivity, AccountExitData, AccountExportQuery, AccountExportRecord,
+        AccountPendingTxs, AccountState, DepositingAccountBalances, DepositingFunds,
+        EthAccountType, ExitDataQuery, ExportDirection, ExportFormat, IncomingAccountTxsQuery,
+        PendingPriorityOp, PendingPriorityOpStatus, StoredBlockInfo,
+    },
+    block::{
+        BlockAggregatedOperation, BlockAggregatedOperations, BlockExportQuery, BlockInfo,
+        BlockPosition, BlockStatus, BlocksOrder, IncomingBlockTxsQuery, IncomingBlocksQuery,
+    },
+    config::ApiConfigData,
+    error::{Error, ErrorCode},
+    fee::{
+        ApiFee, ApiTxFeeTypes, ApiWithdrawalEstimate, BatchFeeRequest, TxFeeRequest,
+        TxInBatchFeeRequest, WithdrawalEstimateQuery,
+    },
+    pagination::{
+        ActivityKind, ActivityPosition, ApiEither, BlockTimestamp, Paginated, PaginationDirection,
+        PaginationQuery, QueuedTxPosition,
+    },
+    search::{SearchMatch, SearchQuery},
+    status::{Health, NetworkStatus, Readiness, ReadinessCheck, ReadinessCheckKind},
+    token::{ApiNFT, ApiToken, TokenPrice, TokenVolume, TokenVolumeQuery, VolumeWindow, NFT},
+    transaction::{
+        ApiDeposit, ApiFullExit, ApiTxBatch, BatchStatus, EthSignatureType, FailReasonCode,
+        L1Receipt, L1Transaction, L1TxContext, L2Receipt, MintedNFT, PriorityOpInfo, Receipt,
+        SubmissionLimit, SubmissionLimitExceeded, SubmissionLimits, SubmitBatchResponse,
+        Toggle2FAResponse, Transaction, TransactionData, TxData, TxHashSerializeWrapper,
+        TxInBlockStatus, TxType,
+    },
+    ApiVersion, Request, Response, ResultStatus, ZksyncVersion,
+};
+use crate::{BlockTimeStatus, CoreStatus};
+
+fn assert_snapshot<T: Serialize>(value: &T, expected: Value) {
+    assert_eq!(serde_json::to_value(value).unwrap(), expected);
+}
+
+/// Checks that the type sent by the client is read by the server as expected.
+fn assert_request_snapshot<T: Serialize + DeserializeOwned>(value: &T, expected: Value) {
+    assert_snapshot(value, expected.clone());
+    let parsed: T = serde_json::from_value(expected.clone()).unwrap();
+    assert_snapshot(&parsed, expected);
+}
+
+fn hex_str(byte: u8, len: usize) -> String {
+    format!("0x{}", hex::encode(vec![byte; len]))
+}
+
+fn tx_hash() -> TxHash {
+    TxHash::from_slice(&[0x22; 32]).unwrap()
+}
+
+fn timestamp() -> DateTime<Utc> {
+    Utc.timestamp(0, 0)
+}
+
+fn fr_zero_hex() -> String {
+    "0".repeat(64)
+}
+
+fn deposit_tx() -> Transaction {
+    Transaction {
+        tx_hash: tx_hash(),
+        block_index: Some(1),
+        block_number: Some(BlockNumber(2)),
+        op: TransactionData::L1(L1Transaction::Deposit(ApiDeposit {
+            from: Address::repeat_byte(0x11),
+            token_id: TokenId(0),
+            amount: 100u32.into(),
+            to: Address::repeat_byte(0x12),
+            account_id: Some(AccountId(3)),
+            eth_hash: H256::repeat_byte(0x33),
+            id: 4,
+            tx_hash: tx_hash(),
+        })),
+        status: TxInBlockStatus::Committed,
+        fail_reason: None,
+        fail_reason_code: None,
+        created_at: Some(timestamp()),
+        batch_id: None,
+    }
+}
+
+fn deposit_tx_json() -> Value {
+    json!({
+        "txHash": hex_str(0x22, 32),
+        "blockIndex": 1,
+        "blockNumber": 2,
+        "op": {
+            "type": "Deposit",
+            "from": hex_str(0x11, 20),
+            "tokenId": 0,
+            "amount": "100",
+            "to": hex_str(0x12, 20),
+            "accountId": 3,
+            "ethHash": hex_str(0x33, 32),
+            "id": 4,
+            "txHash": hex_str(0x22, 32),
+        },
+        "status": "committed",
+        "failReason": null,
+        "failReasonCode": null,
+        "createdAt": "1970-01-01T00:00:00Z",
+        "batchId": null,
+    })
+}
+
+fn block_info() -> BlockInfo {
+    BlockInfo {
+        block_number: BlockNumber(5),
+        new_state_root: Fr::zero(),
+        block_size: 10,
+        commit_tx_hash: Some(H256::repeat_byte(0x44)),
+        verify_tx_hash: None,
+        committed_at: timestamp(),
+        finalized_at: None,
+        status: BlockStatus::Committed,
+    }
+}
+
+fn block_info_json() -> Value {
+    json!({
+        "blockNumber": 5,
+        "newStateRoot": fr_zero_hex(),
+        "blockSize": 10,
+        "commitTxHash": hex_str(0x44, 32),
+        "verifyTxHash": null,
+        "committedAt": "1970-01-01T00:00:00Z",
+        "finalizedAt": null,
+        "status": "committed",
+    })
+}
+
+#[test]
+fn response_snapshot() {
+    let response = Response {
+        request: Request {
+            network: Network::Localhost,
+            api_version: ApiVersion::V02,
+            resource: "/api/v0.2/networkStatus".to_string(),
+            args: HashMap::new(),
+            timestamp: timestamp(),
+        },
+        request_id: "0b3c5b4e-6f4d-4a8e-9c1f-2d7e8a9b0c1d".to_string(),
+        status: ResultStatus::Error,
+        error: Some(
+            serde_json::to_value(Error::new(
+                "invalidDataError",
+                ErrorCode::AccountNotFound,
+                "Account is not found",
+            ))
+            .unwrap(),
+        ),
+        result: None,
+    };
+    assert_request_snapshot(
+        &response,
+        json!({
+            "request": {
+                "network": "localhost",
+                "apiVersion": "v02",
+                "resource": "/api/v0.2/networkStatus",
+                "args": {},
+                "timestamp": "1970-01-01T00:00:00Z",
+            },
+            "requestId": "0b3c5b4e-6f4d-4a8e-9c1f-2d7e8a9b0c1d",
+            "status": "error",
+            "error": {
+                "errorType": "invalidDataError",
+                "code": 204,
+                "message": "Account is not found",
+            },
+            "result": null,
+        }),
+    );
+}
+
+/// Responses of the servers that don't assign the request ids are still accepted.
+#[test]
+fn response_without_request_id() {
+    let response: Response = serde_json::from_value(json!({
+        "request": {
+            "network": "localhost",
+            "apiVersion": "v02",
+            "resource": "/api/v0.2/networkStatus",
+            "args": {},
+            "timestamp": "1970-01-01T00:00:00Z",
+        },
+        "status": "success",
+        "error": null,
+        "result": {},
+    }))
+    .unwrap();
+    assert_eq!(response.request_id, "");
+    assert_eq!(response.result, Some(json!({})));
+}
+
+#[test]
+fn error_codes_snapshot() {
+    fn expected_code(code: &ErrorCode) -> u64 {
+        match code {
+            ErrorCode::UnreacheableError => 0,
+            ErrorCode::CoreApiError => 100,
+            ErrorCode::TokenZeroPriceError => 200,
+            ErrorCode::InvalidCurrency => 201,
+            ErrorCode::InvalidBlockPosition => 202,
+            ErrorCode::InvalidAccountIdOrAddress => 203,
+            ErrorCode::AccountNotFound => 204,
+            ErrorCode::TransactionNotFound => 205,
+            ErrorCode::PaginationLimitTooBig => 206,
+            ErrorCode::QueryDeserializationError => 207,
+            ErrorCode::InvalidNFTTokenId => 208,
+            ErrorCode::ExitDataDisabled => 209,
+            ErrorCode::NFTExitDataUnsupported => 210,
+            ErrorCode::InvalidExportRange => 211,
+            ErrorCode::ExportRangeTooBig => 212,
+            ErrorCode::InvalidTxHashLength => 213,
+            ErrorCode::InvalidTxHashHex => 214,
+            ErrorCode::UnsupportedTxHashPrefix => 215,
+            ErrorCode::TooManyTxHashes => 216,
+            ErrorCode::InvalidTxType => 217,
+            ErrorCode::TransactionNotOfAccount => 218,
+            ErrorCode::ExportBlocksNotFinalized => 219,
+            ErrorCode::BlockNotFound => 220,
+            ErrorCode::NotWithdrawalTx => 221,
+            ErrorCode::StorageError => 300,
+            ErrorCode::InvalidApiKey => 400,
+            ErrorCode::ApiKeyScopeNotAllowed => 401,
+            ErrorCode::RateLimitExceeded => 402,
+            ErrorCode::ApiKeyRequired => 403,
+            ErrorCode::TokenNotFound => 500,
+            ErrorCode::ExternalApiError => 501,
+            ErrorCode::TokenPriceUnavailable => 502,
+            ErrorCode::InternalError => 600,
+            ErrorCode::AccountCloseDisabled => 601,
+            ErrorCode::InvalidParams => 602,
+            ErrorCode::UnsupportedFastProcessing => 603,
+            ErrorCode::IncorrectTx => 604,
+            ErrorCode::TxAddError => 605,
+            ErrorCode::InappropriateFeeToken => 606,
+            ErrorCode::CommunicationCoreServer => 607,
+            ErrorCode::Toggle2FAError => 608,
+            ErrorCode::Other => 60_000,
+            ErrorCode::Submission(code) => *code as u64,
+        }
+    }
+
+    // Keep in sync with the `match` above.
+    let codes = [
+        ErrorCode::UnreacheableError,
+        ErrorCode::CoreApiError,
+        ErrorCode::TokenZeroPriceError,
+        ErrorCode::InvalidCurrency,
+        ErrorCode::InvalidBlockPosition,
+        ErrorCode::InvalidAccountIdOrAddress,
+        ErrorCode::AccountNotFound,
+        ErrorCode::TransactionNotFound,
+        ErrorCode::PaginationLimitTooBig,
+        ErrorCode::QueryDeserializationError,
+        ErrorCode::InvalidNFTTokenId,
+        ErrorCode::ExitDataDisabled,
+        ErrorCode::NFTExitDataUnsupported,
+        ErrorCode::InvalidExportRange,
+        ErrorCode::ExportRangeTooBig,
+        ErrorCode::InvalidTxHashLength,
+        ErrorCode::InvalidTxHashHex,
+        ErrorCode::UnsupportedTxHashPrefix,
+        ErrorCode::TooManyTxHashes,
+        ErrorCode::InvalidTxType,
+        ErrorCode::TransactionNotOfAccount,
+        ErrorCode::ExportBlocksNotFinalized,
+        ErrorCode::BlockNotFound,
+        ErrorCode::NotWithdrawalTx,
+        ErrorCode::StorageError,
+        ErrorCode::InvalidApiKey,
+        ErrorCode::ApiKeyScopeNotAllowed,
+        ErrorCode::RateLimitExceeded,
+        ErrorCode::ApiKeyRequired,
+        ErrorCode::TokenNotFound,
+        ErrorCode::ExternalApiError,
+        ErrorCode::TokenPriceUnavailable,
+        ErrorCode::InternalError,
+        ErrorCode::AccountCloseDisabled,
+        ErrorCode::InvalidParams,
+        ErrorCode::UnsupportedFastProcessing,
+        ErrorCode::IncorrectTx,
+        ErrorCode::TxAddError,
+        ErrorCode::InappropriateFeeToken,
+        ErrorCode::CommunicationCoreServer,
+        ErrorCode::Toggle2FAError,
+        ErrorCode::Other,
+        ErrorCode::Submission(104),
+    ];
+    for code in &codes {
+        assert_request_snapshot(code, json!(expected_code(code)));
+    }
+}
+
+/// Submission codes shared with the JSON-RPC API may have the same values as the REST ones.
+#[test]
+fn submission_error_codes() {
+    let error: Error = serde_json::from_value(json!({
+        "errorType": "submitError",
+        "code": 203,
+        "message": "Not signed ChangePubKey can't be submitted",
+        "data": { "code": 203, "reason": "changePkNotAuthorized" },
+    }))
+    .unwrap();
+    assert_eq!(error.code, ErrorCode::Submission(203));
+    assert_eq!(serde_json::to_value(&error).unwrap()["code"], json!(203));
+
+    let error: Error = serde_json::from_value(json!({
+        "errorType": "invalidDataError",
+        "code": 203,
+        "message": "Cannot parse account id or address",
+    }))
+    .unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidAccountIdOrAddress);
+
+    // The standard codes are kept for the invalid params of the submission.
+    let error: Error = serde_json::from_value(json!({
+        "errorType": "submitError",
+        "code": 602,
+        "message": "Invalid token",
+    }))
+    .unwrap();
+    assert_eq!(error.code, ErrorCode::InvalidParams);
+}
+
+#[test]
+fn config_snapshot() {
+    let config = ApiConfigData {
+        network: Network::Localhost,
+        contract: Address::repeat_byte(0x01),
+        gov_contract: Address::repeat_byte(0x02),
+        deposit_confirmations: 1,
+        zksync_version: ZksyncVersion::ContractV4,
+        contract_upgrade_version: Some(2),
+        contract_target: Address::repeat_byte(0x03),
+        submission_limits: SubmissionLimits {
+            max_transactions_per_batch: 200,
+            max_authors_per_batch: 10,
+            max_chunks_per_batch: 182,
+            max_withdrawals_per_batch: 50,
+            nonce_ahead_window: 10,
+        },
+        nft_factory: Some(Address::repeat_byte(0x04)),
+        max_block_chunks: 690,
+    };
+    assert_request_snapshot(
+        &config,
+        json!({
+            "network": "localhost",
+            "contract": hex_str(0x01, 20),
+            "govContract": hex_str(0x02, 20),
+            "depositConfirmations": 1,
+            "zksyncVersion": "contractV4",
+            "contractUpgradeVersion": 2,
+            "contractTarget": hex_str(0x03, 20),
+            "submissionLimits": {
+                "maxTransactionsPerBatch": 200,
+                "maxAuthorsPerBatch": 10,
+                "maxChunksPerBatch": 182,
+                "maxWithdrawalsPerBatch": 50,
+                "nonceAheadWindow": 10,
+            },
+            "nftFactory": hex_str(0x04, 20),
+            "maxBlockChunks": 690,
+        }),
+    );
+
+    let limits = [
+        SubmissionLimit::TransactionsPerBatch,
+        SubmissionLimit::AuthorsPerBatch,
+        SubmissionLimit::ChunksPerBatch,
+        SubmissionLimit::WithdrawalsPerBatch,
+        SubmissionLimit::NonceAheadWindow,
+    ];
+    for limit in &limits {
+        assert_request_snapshot(limit, json!(limit.as_str()));
+        assert!(json!(config.submission_limits)
+            .get(limit.as_str())
+            .is_some());
+    }
+
+    assert_request_snapshot(
+        &SubmissionLimitExceeded {
+            limit: SubmissionLimit::NonceAheadWindow,
+            value: 4,
+            max: 3,
+        },
+        json!({
+            "limit": "nonceAheadWindow",
+            "value": 4,
+            "max": 3,
+        }),
+    );
+}
+
+#[test]
+fn network_status_snapshot() {
+    let status = NetworkStatus {
+        last_committed: BlockNumber(2),
+        finalized: BlockNumber(1),
+        total_transactions: 10,
+        mempool_size: 3,
+        core_status: Some(CoreStatus {
+            main_database_available: true,
+            replica_database_available: true,
+            web3_available: false,
+            contract_config_outdated: false,
+            root_hash_mismatch: false,
+            block_time: Some(BlockTimeStatus {
+                target_ms: Some(30_000),
+                effective_timeout_ms: 45_000,
+                average_block_time_ms: None,
+            }),
+        }),
+    };
+    assert_request_snapshot(
+        &status,
+        json!({
+            "lastCommitted": 2,
+            "finalized": 1,
+            "totalTransactions": 10,
+            "mempoolSize": 3,
+            "coreStatus": {
+                "main_database_available": true,
+                "replica_database_available": true,
+                "web3_available": false,
+                "contract_config_outdated": false,
+                "root_hash_mismatch": false,
+                "block_time": {
+                    "target_ms": 30000,
+                    "effective_timeout_ms": 45000,
+                    "average_block_time_ms": null,
+                },
+            },
+        }),
+    );
+}
+
+#[test]
+fn health_snapshots() {
+    assert_request_snapshot(&Health { uptime_secs: 60 }, json!({ "uptimeSecs": 60 }));
+
+    let readiness = Readiness {
+        ready: false,
+        checks: vec![
+            ReadinessCheck {
+                check: ReadinessCheckKind::Database,
+                passed: false,
+                latency_ms: 2000,
+                error: Some("Timed out acquiring the connection to db".to_string()),
+            },
+            ReadinessCheck {
+                check: ReadinessCheckKind::CoreApi,
+                passed: true,
+                latency_ms: 3,
+                error: None,
+            },
+            ReadinessCheck {
+                check: ReadinessCheckKind::LastBlockAge,
+                passed: true,
+                latency_ms: 1,
+                error: None,
+            },
+        ],
+    };
+    assert_request_snapshot(
+        &readiness,
+        json!({
+            "ready": false,
+            "checks": [
+                {
+                    "check": "database",
+                    "passed": false,
+                    "latencyMs": 2000,
+                    "error": "Timed out acquiring the connection to db",
+                },
+                {
+                    "check": "coreApi",
+                    "passed": true,
+                    "latencyMs": 3,
+                    "error": null,
+                },
+                {
+                    "check": "lastBlockAge",
+                    "passed": true,
+                    "latencyMs": 1,
+                    "error": null,
+                },
+            ],
+        }),
+    );
+}
+
+#[test]
+fn block_snapshots() {
+    assert_request_snapshot(&block_info(), block_info_json());
+
+    let blocks = Paginated::new(
+        vec![block_info()],
+        BlockNumber(5),
+        1,
+        PaginationDirection::Older,
+        7,
+    );
+    assert_request_snapshot(
+        &blocks,
+        json!({
+            "list": [block_info_json()],
+            "pagination": {
+                "from": 5,
+                "limit": 1,
+                "direction": "older",
+                "count": 7,
+            },
+        }),
+    );
+
+    let blocks = Paginated::new(
+        vec![block_info()],
+        BlockNumber(5),
+        1,
+        PaginationDirection::Older,
+        7,
+    )
+    .with_cursors(Some(BlockNumber(4)), Some(BlockNumber(6)));
+    assert_request_snapshot(
+        &blocks,
+        json!({
+            "list": [block_info_json()],
+            "pagination": {
+                "from": 5,
+                "limit": 1,
+                "direction": "older",
+                "count": 7,
+                "next": 4,
+                "previous": 6,
+            },
+        }),
+    );
+}
+
+#[test]
+fn block_aggregated_operations_snapshot() {
+    let operations = BlockAggregatedOperations {
+        block_number: BlockNumber(5),
+        commit_blocks: Some(BlockAggregatedOperation {
+            confirmed: true,
+            eth_tx_hash: Some(H256::repeat_byte(0x44)),
+            from_block: BlockNumber(5),
+            to_block: BlockNumber(6),
+        }),
+        publish_proof_blocks_onchain: Some(BlockAggregatedOperation {
+            confirmed: false,
+            eth_tx_hash: None,
+            from_block: BlockNumber(4),
+            to_block: BlockNumber(5),
+        }),
+        execute_blocks: None,
+    };
+    assert_request_snapshot(
+        &operations,
+        json!({
+            "blockNumber": 5,
+            "commitBlocks": {
+                "confirmed": true,
+                "ethTxHash": hex_str(0x44, 32),
+                "fromBlock": 5,
+                "toBlock": 6,
+            },
+            "publishProofBlocksOnchain": {
+                "confirmed": false,
+                "ethTxHash": null,
+                "fromBlock": 4,
+                "toBlock": 5,
+            },
+            "executeBlocks": null,
+        }),
+    );
+}
+
+#[test]
+fn block_position_snapshots() {
+    for (position, expected) in [
+        (BlockPosition::Number(BlockNumber(5)), "5"),
+        (BlockPosition::LastCommitted, "lastCommitted"),
+        (BlockPosition::LastFinalized, "lastFinalized"),
+    ] {
+        assert_request_snapshot(&position, json!(expected));
+        assert_eq!(BlockPosition::from_str(expected).unwrap(), position);
+    }
+
+    for malformed in ["latest", "lastcommitted", "-1", "0x05", ""] {
+        assert!(BlockPosition::from_str(malformed).is_err());
+        assert!(serde_json::from_value::<BlockPosition>(json!(malformed)).is_err());
+    }
+}
+
+#[test]
+fn tx_type_snapshots() {
+    for tx_type in [
+        TxType::Transfer,
+        TxType::Withdraw,
+        TxType::ChangePubKey,
+        TxType::Deposit,
+        TxType::FullExit,
+        TxType::ForcedExit,
+        TxType::MintNFT,
+        TxType::WithdrawNFT,
+        TxType::Swap,
+    ] {
+        let expected = match tx_type {
+            TxType::Transfer => "Transfer",
+            TxType::Withdraw => "Withdraw",
+            TxType::ChangePubKey => "ChangePubKey",
+            TxType::Deposit => "Deposit",
+            TxType::FullExit => "FullExit",
+            TxType::ForcedExit => "ForcedExit",
+            TxType::MintNFT => "MintNFT",
+            TxType::WithdrawNFT => "WithdrawNFT",
+            TxType::Swap => "Swap",
+        };
+        assert_request_snapshot(&tx_type, json!(expected));
+        assert_eq!(tx_type.as_str(), expected);
+        assert_eq!(TxType::from_str(expected).unwrap(), tx_type);
+    }
+
+    for malformed in ["transfer", "Close", "Teleport", ""] {
+        assert!(TxType::from_str(malformed).is_err());
+    }
+}
+
+#[test]
+fn pending_priority_op_snapshots() {
+    let op = PendingPriorityOp {
+        serial_id: 12,
+        op_type: TxType::Deposit,
+        token_id: TokenId(1),
+        amount: Some(BigUintSerdeWrapper(100u32.into())),
+        eth_hash: H256::repeat_byte(0x11),
+        eth_block: 25,
+        confirmations_left: 3,
+        status: PendingPriorityOpStatus::Unconfirmed,
+    };
+    assert_snapshot(
+        &op,
+        json!({
+            "serialId": 12,
+            "opType": "Deposit",
+            "tokenId": 1,
+            "amount": "100",
+            "ethHash": hex_str(0x11, 32),
+            "ethBlock": 25,
+            "confirmationsLeft": 3,
+            "status": "unconfirmed",
+        }),
+    );
+
+    for (status, expected) in [
+        (PendingPriorityOpStatus::Unconfirmed, "unconfirmed"),
+        (PendingPriorityOpStatus::Confirmed, "confirmed"),
+        (PendingPriorityOpStatus::Executed, "executed"),
+    ] {
+        assert_request_snapshot(&status, json!(expected));
+    }
+}
+
+#[test]
+fn priority_op_info_snapshot() {
+    let op = PriorityOpInfo {
+        serial_id: 12,
+        op_type: TxType::FullExit,
+        eth_hash: H256::repeat_byte(0x11),
+        tx_hash: tx_hash(),
+        block_number: BlockNumber(5),
+        status: TxInBlockStatus::Finalized,
+    };
+    assert_request_snapshot(
+        &op,
+        json!({
+            "serialId": 12,
+            "opType": "FullExit",
+            "ethHash": hex_str(0x11, 32),
+            "txHash": hex_str(0x22, 32),
+            "blockNumber": 5,
+            "status": "finalized",
+        }),
+    );
+}
+
+#[test]
+fn account_snapshots() {
+    let mut balances = HashMap::new();
+    balances.insert(
+        "ETH".to_string(),
+        DepositingFunds {
+            amount: 10u32.into(),
+            expected_accept_block: 5,
+            confirmations_left: 0,
+        },
+    );
+    let state = AccountState {
+        depositing: DepositingAccountBalances { balances },
+        committed: Some(Account {
+            account_id: AccountId(1),
+            address: Address::repeat_byte(0x11),
+            nonce: Nonce(2),
+            pub_key_hash: PubKeyHash::default(),
+            last_update_in_block: BlockNumber(3),
+            balances: vec![("ETH".to_string(), BigUintSerdeWrapper(100u32.into()))]
+                .into_iter()
+                .collect(),
+            nfts: Default::default(),
+            minted_nfts: Default::default(),
+            account_type: Some(EthAccountType::Owned),
+        }),
+        finalized: None,
+    };
+    assert_request_snapshot(
+        &state,
+        json!({
+            "depositing": {
+                "balances": {
+                    "ETH": {
+                        "amount": "10",
+                        "expectedAcceptBlock": 5,
+                        "confirmationsLeft": 0,
+                    },
+                },
+            },
+            "committed": {
+                "accountId": 1,
+                "address": hex_str(0x11, 20),
+                "nonce": 2,
+                "pubKeyHash": format!("sync:{}", "0".repeat(40)),
+                "lastUpdateInBlock": 3,
+                "balances": { "ETH": "100" },
+                "nfts": {},
+                "mintedNfts": {},
+                "accountType": "Owned",
+            },
+            "finalized": null,
+        }),
+    );
+
+    let exit_data = AccountExitData {
+        stored_block_info: StoredBlockInfo {
+            block_number: BlockNumber(1),
+            priority_operations: 2,
+            pending_onchain_operations_hash: H256::repeat_byte(0x01),
+            timestamp: 3,
+            state_hash: H256::repeat_byte(0x02),
+            commitment: H256::repeat_byte(0x03),
+        },
+        owner: Address::repeat_byte(0x11),
+        account_id: AccountId(4),
+        token_id: TokenId(0),
+        token_address: Address::zero(),
+        amount: 5u32.into(),
+        nonce: Nonce(6),
+        pub_key_hash: PubKeyHash::default(),
+        nft_creator_id: AccountId(0),
+        nft_creator_address: Address::zero(),
+        nft_serial_id: 0,
+        nft_content_hash: H256::zero(),
+        account_path: vec![Fr::zero()],
+        balance_path: vec![],
+    };
+    assert_request_snapshot(
+        &exit_data,
+        json!({
+            "storedBlockInfo": {
+                "blockNumber": 1,
+                "priorityOperations": 2,
+                "pendingOnchainOperationsHash": hex_str(0x01, 32),
+                "timestamp": 3,
+                "stateHash": hex_str(0x02, 32),
+                "commitment": hex_str(0x03, 32),
+            },
+            "owner": hex_str(0x11, 20),
+            "accountId": 4,
+            "tokenId": 0,
+            "tokenAddress": hex_str(0, 20),
+            "amount": "5",
+            "nonce": 6,
+            "pubKeyHash": format!("sync:{}", "0".repeat(40)),
+            "nftCreatorId": 0,
+            "nftCreatorAddress": hex_str(0, 20),
+            "nftSerialId": 0,
+            "nftContentHash": hex_str(0, 32),
+            "accountPath": [fr_zero_hex()],
+            "balancePath": [],
+        }),
+    );
+}
+
+#[test]
+fn account_txs_snapshots() {
+    let pending = AccountPendingTxs {
+        txs: Paginated::new(vec![deposit_tx()], 7, 10, PaginationDirection::Newer, 1),
+        blocked_by: Some(TxHashSerializeWrapper(tx_hash())),
+    };
+    assert_snapshot(
+        &pending,
+        json!({
+            "list": [deposit_tx_json()],
+            "pagination": {
+                "from": 7,
+                "limit": 10,
+                "direction": "newer",
+                "count": 1,
+            },
+            "blockedBy": hex_str(0x22, 32),
+        }),
+    );
+
+    let activity = AccountActivity::PriorityOp(deposit_tx());
+    let position = activity.position().unwrap();
+    let activities = Paginated::new(vec![activity], position, 10, PaginationDirection::Older, 1);
+    assert_snapshot(
+        &activities,
+        json!({
+            "list": [{
+                "kind": "priorityOp",
+                "tx": deposit_tx_json(),
+            }],
+            "pagination": {
+                "from": "2:priorityOp:1",
+                "limit": 10,
+                "direction": "older",
+                "count": 1,
+            },
+        }),
+    );
+}
+
+#[test]
+fn account_export_snapshots() {
+    let activity = AccountActivity::PriorityOp(deposit_tx());
+    let record = AccountExportRecord::from_activity(&activity, Address::repeat_byte(0x12)).unwrap();
+    assert_eq!(
+        record,
+        AccountExportRecord {
+            block_number: BlockNumber(2),
+            block_index: 1,
+            kind: ActivityKind::PriorityOp,
+            tx_hash: tx_hash(),
+            timestamp: Some(timestamp()),
+            direction: ExportDirection::Incoming,
+            counterparty: Some(Address::repeat_byte(0x11)),
+            token: TokenId(0),
+            amount: Some(BigUintSerdeWrapper(100u32.into())),
+            fee_token: None,
+            fee: None,
+            status: TxInBlockStatus::Committed,
+        }
+    );
+    assert_snapshot(
+        &record,
+        json!({
+            "blockNumber": 2,
+            "blockIndex": 1,
+            "kind": "priorityOp",
+            "txHash": hex_str(0x22, 32),
+            "timestamp": "1970-01-01T00:00:00Z",
+            "direction": "incoming",
+            "counterparty": hex_str(0x11, 20),
+            "token": 0,
+            "amount": "100",
+            "feeToken": null,
+            "fee": null,
+            "status": "committed",
+        }),
+    );
+
+    let sender_record =
+        AccountExportRecord::from_activity(&activity, Address::repeat_byte(0x11)).unwrap();
+    assert_eq!(sender_record.direction, ExportDirection::Outgoing);
+    assert_eq!(sender_record.counterparty, Some(Address::repeat_byte(0x12)));
+
+    assert_request_snapshot(
+        &AccountExportQuery {
+            from_block: BlockNumber(1),
+            to_block: BlockNumber(10),
+            format: ExportFormat::Csv,
+            after: Some(record.position()),
+        },
+        json!({
+            "from_block": 1,
+            "to_block": 10,
+            "format": "csv",
+            "after": "2:priorityOp:1",
+        }),
+    );
+}
+
+#[test]
+fn transaction_snapshots() {
+    assert_snapshot(&deposit_tx(), deposit_tx_json());
+
+    let full_exit = L1Transaction::FullExit(ApiFullExit {
+        account_id: AccountId(1),
+        token_id: TokenId(2),
+        eth_hash: H256::repeat_byte(0x33),
+        id: 3,
+        tx_hash: tx_hash(),
+    });
+    assert_snapshot(
+        &full_exit,
+        json!({
+            "type": "FullExit",
+            "accountId": 1,
+            "tokenId": 2,
+            "ethHash": hex_str(0x33, 32),
+            "id": 3,
+            "txHash": hex_str(0x22, 32),
+        }),
+    );
+
+    let tx_data = TxData {
+        tx: deposit_tx(),
+        eth_signature: None,
+        l1_context: Some(L1TxContext {
+            eth_sender: Address::repeat_byte(0x11),
+            eth_block: EthBlockId(10),
+            eth_block_hash: Some(H256::repeat_byte(0x55)),
+            token_id: TokenId(0),
+            amount: Some(BigUintSerdeWrapper(100u32.into())),
+            l1_surplus: Some(BigUintSerdeWrapper(5u32.into())),
+        }),
+        nft: None,
+    };
+    assert_snapshot(
+        &tx_data,
+        json!({
+            "tx": deposit_tx_json(),
+            "ethSignature": null,
+            "l1Context": {
+                "ethSender": hex_str(0x11, 20),
+                "ethBlock": 10,
+                "ethBlockHash": hex_str(0x55, 32),
+                "tokenId": 0,
+                "amount": "100",
+                "l1Surplus": "5",
+            },
+            "nft": null,
+        }),
+    );
+
+    let minted_nft = MintedNFT {
+        token_id: TokenId(65_536),
+        serial_id: 2,
+        content_hash: H256::repeat_byte(0x44),
+        address: Address::repeat_byte(0x55),
+    };
+    assert_snapshot(
+        &minted_nft,
+        json!({
+            "tokenId": 65_536,
+            "serialId": 2,
+            "contentHash": hex_str(0x44, 32),
+            "address": hex_str(0x55, 20),
+        }),
+    );
+}
+
+#[test]
+fn receipt_snapshots() {
+    let l1_receipt = Receipt::L1(L1Receipt {
+        status: TxInBlockStatus::Queued,
+        eth_block: EthBlockId(10),
+        rollup_block: None,
+        id: 3,
+        eth_sender: Some(Address::repeat_byte(0x11)),
+        eth_block_hash: None,
+        l1_surplus: None,
+    });
+    assert_snapshot(
+        &l1_receipt,
+        json!({
+            "status": "queued",
+            "ethBlock": 10,
+            "rollupBlock": null,
+            "id": 3,
+            "ethSender": hex_str(0x11, 20),
+            "ethBlockHash": null,
+            "l1Surplus": null,
+        }),
+    );
+
+    let l2_receipt = Receipt::L2(L2Receipt {
+        tx_hash: tx_hash(),
+        rollup_block: Some(BlockNumber(1)),
+        block_index: Some(0),
+        status: TxInBlockStatus::Parked,
+        fail_reason: None,
+        fail_reason_code: None,
+        attempts_exhausted: false,
+        batch_id: Some(7),
+        batch_index: Some(1),
+        created_at: None,
+        eth_signature_type: None,
+    });
+    assert_snapshot(
+        &l2_receipt,
+        json!({
+            "txHash": hex_str(0x22, 32),
+            "rollupBlock": 1,
+            "blockIndex": 0,
+            "status": "parked",
+            "failReason": null,
+            "failReasonCode": null,
+            "attemptsExhausted": false,
+            "batchId": 7,
+            "batchIndex": 1,
+        }),
+    );
+
+    let queued_l2_receipt = Receipt::L2(L2Receipt {
+        tx_hash: tx_hash(),
+        rollup_block: None,
+        block_index: None,
+        status: TxInBlockStatus::Queued,
+        fail_reason: None,
+        fail_reason_code: None,
+        attempts_exhausted: false,
+        batch_id: None,
+        batch_index: None,
+        created_at: Some(timestamp()),
+        eth_signature_type: Some(EthSignatureType::EIP1271),
+    });
+    assert_snapshot(
+        &queued_l2_receipt,
+        json!({
+            "txHash": hex_str(0x22, 32),
+            "rollupBlock": null,
+            "blockIndex": null,
+            "status": "queued",
+            "failReason": null,
+            "failReasonCode": null,
+            "attemptsExhausted": false,
+            "batchId": null,
+            "batchIndex": null,
+            "createdAt": "1970-01-01T00:00:00Z",
+            "ethSignatureType": "eip1271",
+        }),
+    );
+}
+
+#[test]
+fn fail_reason_codes_snapshot() {
+    fn expected_code(code: &FailReasonCode) -> &'static str {
+        match code {
+            FailReasonCode::InsufficientBalance => "insufficientBalance",
+            FailReasonCode::NonceMismatch => "nonceMismatch",
+            FailReasonCode::InvalidSignature => "invalidSignature",
+            FailReasonCode::ExpiredTimeRange => "expiredTimeRange",
+            FailReasonCode::Other => "other",
+        }
+    }
+
+    // Keep in sync with the `match` above.
+    let codes = [
+        FailReasonCode::InsufficientBalance,
+        FailReasonCode::NonceMismatch,
+        FailReasonCode::InvalidSignature,
+        FailReasonCode::ExpiredTimeRange,
+        FailReasonCode::Other,
+    ];
+    for code in &codes {
+        assert_request_snapshot(code, json!(expected_code(code)));
+    }
+    // Codes added in the later versions are read as `Other`.
+    let code: FailReasonCode = serde_json::from_value(json!("someNewCode")).unwrap();
+    assert_eq!(code, FailReasonCode::Other);
+}
+
+#[test]
+fn fail_reason_codes_mapping() {
+    let reasons = [
+        ("Not enough balance", FailReasonCode::InsufficientBalance),
+        (
+            "Not enough nft balance",
+            FailReasonCode::InsufficientBalance,
+        ),
+        (
+            "Initiator account: Not enough balance to cover fees",
+            FailReasonCode::InsufficientBalance,
+        ),
+        ("Nonce mismatch", FailReasonCode::NonceMismatch),
+        (
+            "Transfer signature is incorrect",
+            FailReasonCode::InvalidSignature,
+        ),
+        (
+            "ChangePubKey Ethereum auth data is incorrect",
+            FailReasonCode::InvalidSignature,
+        ),
+        (
+            "The transaction can't be executed in the block because of an invalid timestamp",
+            FailReasonCode::ExpiredTimeRange,
+        ),
+        (
+            "Batch execution failed, since tx #1 of batch failed with a reason: Nonce mismatch",
+            FailReasonCode::NonceMismatch,
+        ),
+        (
+            "Amount of gas required to process batch is too big",
+            FailReasonCode::Other,
+        ),
+        ("", FailReasonCode::Other),
+    ];
+    for (reason, code) in &reasons {
+        assert_eq!(
+            FailReasonCode::from_fail_reason(reason),
+            *code,
+            "{}",
+            reason
+        );
+    }
+}
+
+#[test]
+fn receipt_without_fail_reason_code() {
+    // Receipts sent by the servers not aware of the fail reason codes are still readable.
+    let receipt: L2Receipt = serde_json::from_value(json!({
+        "txHash": hex_str(0x22, 32),
+        "rollupBlock": 1,
+        "blockIndex": 0,
+        "status": "rejected",
+        "failReason": "Nonce mismatch",
+        "attemptsExhausted": false,
+        "batchId": null,
+        "batchIndex": null,
+    }))
+    .unwrap();
+    assert_eq!(receipt.fail_reason.as_deref(), Some("Nonce mismatch"));
+    assert_eq!(receipt.fail_reason_code, None);
+}
+
+#[test]
+fn batch_snapshots() {
+    let submitted = SubmitBatchResponse {
+        transaction_hashes: vec![TxHashSerializeWrapper(tx_hash())],
+        batch_hash: tx_hash(),
+    };
+    assert_snapshot(
+        &submitted,
+        json!({
+            "transactionHashes": [hex_str(0x22, 32)],
+            "batchHash": hex_str(0x22, 32),
+        }),
+    );
+
+    let batch = ApiTxBatch {
+        batch_hash: tx_hash(),
+        transaction_hashes: vec![TxHashSerializeWrapper(tx_hash())],
+        created_at: timestamp(),
+        batch_status: BatchStatus {
+            updated_at: timestamp(),
+            last_state: TxInBlockStatus::Finalized,
+        },
+        block_number: Some(BlockNumber(5)),
+    };
+    assert_snapshot(
+        &batch,
+        json!({
+            "batchHash": hex_str(0x22, 32),
+            "transactionHashes": [hex_str(0x22, 32)],
+            "createdAt": "1970-01-01T00:00:00Z",
+            "batchStatus": {
+                "updatedAt": "1970-01-01T00:00:00Z",
+                "lastState": "finalized",
+            },
+            "blockNumber": 5,
+        }),
+    );
+
+    assert_request_snapshot(
+        &Toggle2FAResponse { success: true },
+        json!({ "success": true }),
+    );
+}
+
+#[test]
+fn fee_snapshots() {
+    let fee_request = TxFeeRequest {
+        tx_type: ApiTxFeeTypes::ChangePubKey(ChangePubKeyFeeTypeArg::ContractsV4Version(
+            ChangePubKeyType::ECDSA,
+        )),
+        address: Address::repeat_byte(0x11),
+        token_like: TokenLike::Symbol("ETH".to_string()),
+    };
+    assert_request_snapshot(
+        &fee_request,
+        json!({
+            "txType": { "ChangePubKey": "ECDSA" },
+            "address": hex_str(0x11, 20),
+            "tokenLike": "ETH",
+        }),
+    );
+
+    let batch_fee_request = BatchFeeRequest {
+        transactions: vec![TxInBatchFeeRequest {
+            tx_type: ApiTxFeeTypes::Transfer,
+            address: Address::repeat_byte(0x11),
+        }],
+        token_like: TokenLike::Id(TokenId(0)),
+    };
+    assert_request_snapshot(
+        &batch_fee_request,
+        json!({
+            "transactions": [{
+                "txType": "Transfer",
+                "address": hex_str(0x11, 20),
+            }],
+            "tokenLike": 0,
+        }),
+    );
+
+    let fee = ApiFee {
+        gas_fee: 1u32.into(),
+        zkp_fee: 2u32.into(),
+        total_fee: 3u32.into(),
+        gas_price_wei: 4u32.into(),
+        total_fee_usd: BigDecimal::from_str("0.5").unwrap(),
+    };
+    let fee_json = json!({
+        "gasFee": "1",
+        "zkpFee": "2",
+        "totalFee": "3",
+        "gasPriceWei": "4",
+        "totalFeeUsd": "0.5",
+    });
+    assert_request_snapshot(&fee, fee_json.clone());
+
+    assert_request_snapshot(
+        &WithdrawalEstimateQuery {
+            token: "ETH".to_string(),
+        },
+        json!({ "token": "ETH" }),
+    );
+    let estimate = ApiWithdrawalEstimate {
+        token_id: TokenId(0),
+        gas_amount: 10u32.into(),
+        gas_price_wei: 20u32.into(),
+        l1_cost: 200u32.into(),
+        l1_cost_usd: BigDecimal::from_str("1.5").unwrap(),
+        zksync_fee: fee,
+    };
+    assert_request_snapshot(
+        &estimate,
+        json!({
+            "tokenId": 0,
+            "gasAmount": "10",
+            "gasPriceWei": "20",
+            "l1Cost": "200",
+            "l1CostUsd": "1.5",
+            "zksyncFee": fee_json,
+        }),
+    );
+}
+
+#[test]
+fn token_snapshots() {
+    let token = ApiToken {
+        id: TokenId(1),
+        address: Address::repeat_byte(0x11),
+        symbol: "DAI".to_string(),
+        decimals: 18,
+        enabled_for_fees: true,
+    };
+    assert_request_snapshot(
+        &token,
+        json!({
+            "id": 1,
+            "address": hex_str(0x11, 20),
+            "symbol": "DAI",
+            "decimals": 18,
+            "enabledForFees": true,
+        }),
+    );
+
+    let price = TokenPrice {
+        token_id: TokenId(1),
+        token_symbol: "DAI".to_string(),
+        price_in: "usd".to_string(),
+        decimals: 18,
+        price: BigDecimal::from_str("2.5").unwrap(),
+    };
+    assert_request_snapshot(
+        &price,
+        json!({
+            "tokenId": 1,
+            "tokenSymbol": "DAI",
+            "priceIn": "usd",
+            "decimals": 18,
+            "price": "2.5",
+        }),
+    );
+
+    assert_request_snapshot(
+        &TokenVolumeQuery {
+            window: VolumeWindow::Week,
+        },
+        json!({ "window": "7d" }),
+    );
+    let volume = TokenVolume {
+        token_id: TokenId(1),
+        token_symbol: "DAI".to_string(),
+        decimals: 18,
+        window: VolumeWindow::Day,
+        transfer_amount: 1u32.into(),
+        withdraw_amount: 2u32.into(),
+        amount: 3u32.into(),
+        usd_value: None,
+    };
+    assert_request_snapshot(
+        &volume,
+        json!({
+            "tokenId": 1,
+            "tokenSymbol": "DAI",
+            "decimals": 18,
+            "window": "24h",
+            "transferAmount": "1",
+            "withdrawAmount": "2",
+            "amount": "3",
+            "usdValue": null,
+        }),
+    );
+
+    let nft = NFT {
+        id: TokenId(65536),
+        content_hash: H256::repeat_byte(0x01),
+        creator_id: AccountId(2),
+        creator_address: Address::repeat_byte(0x11),
+        serial_id: 3,
+        address: Address::repeat_byte(0x12),
+        symbol: "NFT-65536".to_string(),
+    };
+    assert_request_snapshot(
+        &nft,
+        json!({
+            "id": 65536,
+            "contentHash": hex_str(0x01, 32),
+            "creatorId": 2,
+            "creatorAddress": hex_str(0x11, 20),
+            "serialId": 3,
+            "address": hex_str(0x12, 20),
+            "symbol": "NFT-65536",
+        }),
+    );
+
+    let api_nft = ApiNFT {
+        id: TokenId(65536),
+        content_hash: H256::repeat_byte(0x01),
+        creator_id: AccountId(2),
+        creator_address: Address::repeat_byte(0x11),
+        serial_id: 3,
+        address: Address::repeat_byte(0x12),
+        symbol: "NFT-65536".to_string(),
+        current_factory: Address::repeat_byte(0x13),
+        withdrawn_factory: None,
+    };
+    assert_request_snapshot(
+        &api_nft,
+        json!({
+            "id": 65536,
+            "contentHash": hex_str(0x01, 32),
+            "creatorId": 2,
+            "creatorAddress": hex_str(0x11, 20),
+            "serialId": 3,
+            "address": hex_str(0x12, 20),
+            "symbol": "NFT-65536",
+            "currentFactory": hex_str(0x13, 20),
+            "withdrawnFactory": null,
+        }),
+    );
+}
+
+#[test]
+fn query_snapshots() {
+    let query = PaginationQuery {
+        from: "latest".to_string(),
+        limit: 10,
+        direction: PaginationDirection::Older,
+    };
+    assert_request_snapshot(
+        &query,
+        json!({
+            "from": "latest",
+            "limit": 10,
+            "direction": "older",
+        }),
+    );
+
+    let query = PaginationQuery {
+        from: ApiEither::from(ActivityPosition {
+            block_number: BlockNumber(12),
+            kind: ActivityKind::PriorityOp,
+            block_index: 0,
+        }),
+        limit: 10,
+        direction: PaginationDirection::Newer,
+    };
+    assert_snapshot(
+        &query,
+        json!({
+            "from": "12:priorityOp:0",
+            "limit": 10,
+            "direction": "newer",
+        }),
+    );
+
+    let position = QueuedTxPosition {
+        nonce: Nonce(5),
+        created_at: Utc.timestamp(1_660_730_400, 123_456_000),
+        tx_hash: tx_hash(),
+    };
+    let query = PaginationQuery {
+        from: ApiEither::from(position),
+        limit: 10,
+        direction: PaginationDirection::Older,
+    };
+    assert_snapshot(
+        &query,
+        json!({
+            "from": format!("5:1660730400123456:{}", hex_str(0x22, 32)),
+            "limit": 10,
+            "direction": "older",
+        }),
+    );
+    assert_eq!(
+        QueuedTxPosition::from_str(&position.to_string()).unwrap(),
+        position
+    );
+    assert!(
+        QueuedTxPosition::from_str(&format!("1660730400123456:{}", hex_str(0x22, 32))).is_err()
+    );
+
+    let query = IncomingAccountTxsQuery {
+        from: "latest".to_string(),
+        limit: 10,
+        direction: PaginationDirection::Older,
+        token: Some("ETH".to_string()),
+        include_fee_token: true,
+        second_account: None,
+    };
+    assert_request_snapshot(
+        &query,
+        json!({
+            "from": "latest",
+            "limit": 10,
+            "direction": "older",
+            "token": "ETH",
+            "includeFeeToken": true,
+            "secondAccount": null,
+        }),
+    );
+    // The fee token is excluded unless asked explicitly.
+    let query: IncomingAccountTxsQuery = serde_json::from_value(json!({
+        "from": "latest",
+        "limit": 10,
+        "direction": "older",
+        "token": "ETH",
+    }))
+    .unwrap();
+    assert!(!query.include_fee_token);
+
+    let query = IncomingBlockTxsQuery {
+        from: "latest".to_string(),
+        limit: 10,
+        direction: PaginationDirection::Older,
+        tx_type: Some("Deposit".to_string()),
+    };
+    assert_request_snapshot(
+        &query,
+        json!({
+            "from": "latest",
+            "limit": 10,
+            "direction": "older",
+            "txType": "Deposit",
+        }),
+    );
+
+    let query = IncomingBlocksQuery {
+        from: "1660730400:12".to_string(),
+        limit: 10,
+        direction: PaginationDirection::Older,
+        order_by: Some(BlocksOrder::Timestamp.as_str().to_string()),
+    };
+    assert_request_snapshot(
+        &query,
+        json!({
+            "from": "1660730400:12",
+            "limit": 10,
+            "direction": "older",
+            "orderBy": "timestamp",
+        }),
+    );
+    // Blocks are ordered by their numbers unless asked otherwise.
+    let query: IncomingBlocksQuery = serde_json::from_value(json!({
+        "from": "latest",
+        "limit": 10,
+        "direction": "older",
+    }))
+    .unwrap();
+    assert_eq!(query.order_by, None);
+    assert!(BlocksOrder::from_str("height").is_err());
+
+    let position = BlockTimestamp {
+        timestamp: 1_660_730_400,
+        block_number: Some(BlockNumber(12)),
+    };
+    let query = PaginationQuery {
+        from: ApiEither::from(position),
+        limit: 10,
+        direction: PaginationDirection::Newer,
+    };
+    assert_snapshot(
+        &query,
+        json!({
+            "from": "1660730400:12",
+            "limit": 10,
+            "direction": "newer",
+        }),
+    );
+    assert_eq!(
+        BlockTimestamp::from_str(&position.to_string()).unwrap(),
+        position
+    );
+    assert_eq!(
+        BlockTimestamp::from_str("1660730400").unwrap(),
+        BlockTimestamp {
+            timestamp: 1_660_730_400,
+            block_number: None,
+        }
+    );
+    assert!(BlockTimestamp::from_str("1660730400:latest").is_err());
+
+    assert_request_snapshot(
+        &BlockExportQuery {
+            from: BlockNumber(1),
+            to: BlockNumber(3),
+        },
+        json!({ "from": 1, "to": 3 }),
+    );
+
+    assert_request_snapshot(
+        &ExitDataQuery {
+            token: "ETH".to_string(),
+        },
+        json!({ "token": "ETH" }),
+    );
+
+    assert_request_snapshot(
+        &SearchQuery {
+            query: "sync-tx:22".to_string(),
+        },
+        json!({ "query": "sync-tx:22" }),
+    );
+}
+
+#[test]
+fn search_snapshots() {
+    assert_snapshot(
+        &vec![
+            SearchMatch::block(BlockNumber(5)),
+            SearchMatch::transaction(tx_hash()),
+            SearchMatch::account(AccountId(3), Address::repeat_byte(0x11)),
+        ],
+        json!([
+            {
+                "kind": "block",
+                "blockNumber": 5,
+                "path": "/api/v0.2/blocks/5",
+            },
+            {
+                "kind": "transaction",
+                "txHash": hex_str(0x22, 32),
+                "path": format!("/api/v0.2/transactions/{}/data", hex_str(0x22, 32)),
+            },
+            {
+                "kind": "account",
+                "accountId": 3,
+                "address": hex_str(0x11, 20),
+                "path": "/api/v0.2/accounts/3",
+            },
+        ]),
+    );
+}
+
+/// Schemas of the `zksync_types` fields are derived from the types themselves.
+#[test]
+fn derived_schemas() {
+    let schema = serde_json::to_value(schemars::schema_for!(TxFeeRequest)).unwrap();
+    let definitions = &schema["definitions"];
+    assert_eq!(definitions["TokenId"]["type"], "integer");
+    assert_eq!(definitions["TokenId"]["format"], "uint32");
+    assert_eq!(
+        definitions["TokenLike"]["anyOf"].as_array().unwrap().len(),
+        3
+    );
+    assert!(definitions["ChangePubKeyFeeTypeArg"]["anyOf"].is_array());
+    assert_eq!(
+        definitions["ChangePubKeyType"]["enum"],
+        json!(["Onchain", "ECDSA", "CREATE2"])
+    );
+
+    let schema = serde_json::to_value(schemars::schema_for!(ApiConfigData)).unwrap();
+    assert_eq!(
+        schema["properties"]["network"]["$ref"],
+        "#/definitions/Network"
+    );
+}
@@ -1,27 +1,87 @@
 use bigdecimal::BigDecimal;
+use num::BigUint;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use zksync_types::{AccountId, Address, Token, TokenId, H256};
+use zksync_utils::BigUintSerdeAsRadix10Str;
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+use super::schema;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiToken {
     pub id: TokenId,
+    #[schemars(with = "schema::Address")]
     pub address: Address,
     pub symbol: String,
     pub decimals: u8,
     pub enabled_for_fees: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenPrice {
     pub token_id: TokenId,
     pub token_symbol: String,
     pub price_in: String,
     pub decimals: u8,
+    #[schemars(with = "schema::BigDecimal")]
     pub price: BigDecimal,
 }
 
+/// Trailing period the token volume is calculated over.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+pub enum VolumeWindow {
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+}
+
+impl Default for VolumeWindow {
+    fn default() -> Self {
+        Self::Day
+    }
+}
+
+impl VolumeWindow {
+    pub fn duration(self) -> chrono::Duration {
+        match self {
+            Self::Day => chrono::Duration::hours(24),
+            Self::Week => chrono::Duration::days(7),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenVolumeQuery {
+    #[serde(default)]
+    pub window: VolumeWindow,
+}
+
+/// Transfer and withdraw volume of the token over the trailing window.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenVolume {
+    pub token_id: TokenId,
+    pub token_symbol: String,
+    pub decimals: u8,
+    pub window: VolumeWindow,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub transfer_amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub withdraw_amount: BigUint,
+    #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
+    pub amount: BigUint,
+    /// Value of the `amount` in USD, `None` if the token price is unknown.
+    #[schemars(with = "Option<schema::BigDecimal>")]
+    pub usd_value: Option<BigDecimal>,
+}
+
 impl ApiToken {
     pub fn from_token_and_eligibility(token: Token, eligibility: bool) -> Self {
         ApiToken {
@@ -34,14 +94,17 @@ impl ApiToken {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct NFT {
     pub id: TokenId,
+    #[schemars(with = "schema::H256")]
     pub content_hash: H256,
     pub creator_id: AccountId,
+    #[schemars(with = "schema::Address")]
     pub creator_address: Address,
     pub serial_id: u32,
+    #[schemars(with = "schema::Address")]
     pub address: Address,
     pub symbol: String,
 }
@@ -60,16 +123,21 @@ impl From<zksync_types::NFT> for NFT {
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiNFT {
     pub id: TokenId,
+    #[schemars(with = "schema::H256")]
     pub content_hash: H256,
     pub creator_id: AccountId,
+    #[schemars(with = "schema::Address")]
     pub creator_address: Address,
     pub serial_id: u32,
+    #[schemars(with = "schema::Address")]
     pub address: Address,
     pub symbol: String,
+    #[schemars(with = "schema::Address")]
     pub current_factory: Address,
+    #[schemars(with = "Option<schema::Address>")]
     pub withdrawn_factory: Option<Address>,
 }
@@ -1,34 +1,90 @@
-use crate::{v02::block::BlockStatus, TxWithSignature};
+use std::{fmt, str::FromStr};
+
+use crate::{
+    v02::{block::BlockStatus, schema},
+    TxWithSignature,
+};
 use chrono::serde::ts_milliseconds;
 use chrono::{DateTime, Utc};
 use num::BigUint;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 use zksync_types::{
     tx::{
         ChangePubKey, Close, EthBatchSignatures, ForcedExit, MintNFT, Swap, Transfer,
         TxEthSignature, TxHash, Withdraw, WithdrawNFT,
     },
-    AccountId, Address, BlockNumber, EthBlockId, PubKeyHash, SerialId, TokenId, ZkSyncOp,
-    ZkSyncPriorityOp, H256,
+    AccountId, Address, BlockNumber, EthBlockId, PriorityOp, PubKeyHash, SerialId, TokenId,
+    ZkSyncOp, ZkSyncPriorityOp, H256, NFT,
 };
-use zksync_utils::{BigUintSerdeAsRadix10Str, ZeroPrefixHexSerde};
+use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper, ZeroPrefixHexSerde};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct IncomingTxBatch {
+    /// Transactions may carry their own Ethereum signatures. The batch is authorized either by
+    /// the batch signature or by the signatures of every transaction that requires one.
     pub txs: Vec<TxWithSignature>,
+    #[schemars(with = "Option<schema::Object>")]
     pub signature: Option<EthBatchSignatures>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub enum TxInBlockStatus {
     Queued,
+    /// Transaction is in the mempool, but temporarily excluded from execution
+    /// since it no longer satisfies the submission requirements.
+    Parked,
     Committed,
     Finalized,
     Rejected,
 }
 
+/// Machine-readable category of the reason the transaction has failed with.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum FailReasonCode {
+    InsufficientBalance,
+    NonceMismatch,
+    InvalidSignature,
+    /// Transaction can't be executed at the block timestamp because of its time range.
+    ExpiredTimeRange,
+    /// Any other reason, including the codes unknown to this version.
+    #[serde(other)]
+    Other,
+}
+
+impl FailReasonCode {
+    /// Maps the fail reason stored for the transaction to its code.
+    /// Reasons are the messages of the state handler errors, possibly wrapped into the batch error.
+    pub fn from_fail_reason(fail_reason: &str) -> Self {
+        const REASONS: &[(&str, FailReasonCode)] = &[
+            ("Not enough balance", FailReasonCode::InsufficientBalance),
+            (
+                "Not enough nft balance",
+                FailReasonCode::InsufficientBalance,
+            ),
+            ("Nonce mismatch", FailReasonCode::NonceMismatch),
+            ("signature is incorrect", FailReasonCode::InvalidSignature),
+            (
+                "Ethereum auth data is incorrect",
+                FailReasonCode::InvalidSignature,
+            ),
+            (
+                "because of an invalid timestamp",
+                FailReasonCode::ExpiredTimeRange,
+            ),
+        ];
+        REASONS
+            .iter()
+            .find(|(reason, _)| fail_reason.contains(reason))
+            .map(|(_, code)| *code)
+            .unwrap_or(FailReasonCode::Other)
+    }
+}
+
 impl From<BlockStatus> for TxInBlockStatus {
     fn from(status: BlockStatus) -> Self {
         match status {
@@ -38,99 +94,305 @@ impl From<BlockStatus> for TxInBlockStatus {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct TxData {
     pub tx: Transaction,
     pub eth_signature: Option<String>,
+    /// Context of the originating L1 transaction, `None` for L2 transactions.
+    pub l1_context: Option<L1TxContext>,
+    /// Token produced by the transaction, `None` unless it's an executed `MintNFT`.
+    pub nft: Option<MintedNFT>,
+}
+
+/// NFT produced by the executed `MintNFT` transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MintedNFT {
+    pub token_id: TokenId,
+    /// Number of the NFTs minted by the creator before this one.
+    pub serial_id: u32,
+    #[schemars(with = "schema::H256")]
+    pub content_hash: H256,
+    /// L2 address of the token, derived from the creator id, serial id and content hash.
+    #[schemars(with = "schema::Address")]
+    pub address: Address,
+}
+
+impl From<NFT> for MintedNFT {
+    fn from(nft: NFT) -> Self {
+        Self {
+            token_id: nft.id,
+            serial_id: nft.serial_id,
+            content_hash: nft.content_hash,
+            address: nft.address,
+        }
+    }
+}
+
+/// Context of the L1 transaction that created the priority operation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct L1TxContext {
+    /// Address of the L1 account that sent the operation.
+    #[schemars(with = "schema::Address")]
+    pub eth_sender: Address,
+    pub eth_block: EthBlockId,
+    /// `None` for operations recorded before the block hash was stored.
+    #[schemars(with = "Option<schema::H256>")]
+    pub eth_block_hash: Option<H256>,
+    pub token_id: TokenId,
+    /// Deposited amount or the amount withdrawn by the full exit.
+    /// `None` if the full exit is not executed yet or has failed.
+    #[schemars(with = "Option<schema::BigUint>")]
+    pub amount: Option<BigUintSerdeWrapper>,
+    /// Part of the amount paid on L1 that was not credited on L2.
+    /// `None` if not applicable to the operation or not known.
+    #[schemars(with = "Option<schema::BigUint>")]
+    pub l1_surplus: Option<BigUintSerdeWrapper>,
+}
+
+impl L1TxContext {
+    pub fn from_executed_op(
+        op: &ZkSyncOp,
+        eth_block: EthBlockId,
+        eth_block_hash: Option<H256>,
+        l1_surplus: Option<BigUintSerdeWrapper>,
+    ) -> Option<Self> {
+        match op {
+            ZkSyncOp::Deposit(deposit) => Some(Self {
+                eth_sender: deposit.priority_op.from,
+                eth_block,
+                eth_block_hash,
+                token_id: deposit.priority_op.token,
+                amount: Some(deposit.priority_op.amount.clone().into()),
+                l1_surplus,
+            }),
+            ZkSyncOp::FullExit(full_exit) => Some(Self {
+                eth_sender: full_exit.priority_op.eth_address,
+                eth_block,
+                eth_block_hash,
+                token_id: full_exit.priority_op.token,
+                amount: full_exit.withdraw_amount.clone(),
+                l1_surplus,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn from_pending_op(op: &PriorityOp) -> Self {
+        Self {
+            eth_sender: op.data.from_account(),
+            eth_block: EthBlockId(op.eth_block),
+            eth_block_hash: op.eth_block_hash,
+            token_id: op.data.token_id(),
+            amount: op
+                .data
+                .try_get_deposit()
+                .map(|deposit| deposit.amount.into()),
+            l1_surplus: op.l1_surplus.clone(),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct L1Receipt {
     pub status: TxInBlockStatus,
     pub eth_block: EthBlockId,
     pub rollup_block: Option<BlockNumber>,
     pub id: SerialId,
+    #[schemars(with = "Option<schema::Address>")]
+    pub eth_sender: Option<Address>,
+    /// `None` for operations recorded before the block hash was stored.
+    #[schemars(with = "Option<schema::H256>")]
+    pub eth_block_hash: Option<H256>,
+    /// Part of the amount paid on L1 that was not credited on L2.
+    /// `None` if not applicable to the operation or not known.
+    #[schemars(with = "Option<schema::BigUint>")]
+    pub l1_surplus: Option<BigUintSerdeWrapper>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct L2Receipt {
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
     pub tx_hash: TxHash,
     pub rollup_block: Option<BlockNumber>,
+    /// Index of the transaction within the rollup block.
+    pub block_index: Option<u32>,
     pub status: TxInBlockStatus,
+    /// Fail reason as it was reported by the server, kept for debugging.
     pub fail_reason: Option<String>,
+    /// Category of the fail reason, `None` if the transaction hasn't failed.
+    pub fail_reason_code: Option<FailReasonCode>,
+    /// Whether the transaction was rejected after exhausting its execution attempts.
+    #[serde(default)]
+    pub attempts_exhausted: bool,
+    /// Identifier of the batch the transaction was submitted in.
+    pub batch_id: Option<u32>,
+    /// Index of the transaction within the batch.
+    pub batch_index: Option<u32>,
+    /// Time the transaction was submitted, reported only while it's in the mempool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Kind of the Ethereum signature the transaction was submitted with,
+    /// reported only while it's in the mempool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_signature_type: Option<EthSignatureType>,
+}
+
+/// Kind of the Ethereum signature accompanying the transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum EthSignatureType {
+    Ethereum,
+    #[serde(rename = "eip1271")]
+    EIP1271,
+    /// Transaction was submitted without an Ethereum signature.
+    None,
+}
+
+impl EthSignatureType {
+    pub fn from_signature(signature: Option<&TxEthSignature>) -> Self {
+        match signature {
+            Some(TxEthSignature::EthereumSignature(_)) => Self::Ethereum,
+            Some(TxEthSignature::EIP1271Signature(_)) => Self::EIP1271,
+            None => Self::None,
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+#[error("Unknown transaction type: {0}")]
+pub struct UnknownTxType(pub String);
+
+/// Type of the operation, named the same way as in the `type` field of the operation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, JsonSchema)]
+pub enum TxType {
+    Transfer,
+    Withdraw,
+    ChangePubKey,
+    Deposit,
+    FullExit,
+    ForcedExit,
+    MintNFT,
+    WithdrawNFT,
+    Swap,
+}
+
+impl TxType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TxType::Transfer => "Transfer",
+            TxType::Withdraw => "Withdraw",
+            TxType::ChangePubKey => "ChangePubKey",
+            TxType::Deposit => "Deposit",
+            TxType::FullExit => "FullExit",
+            TxType::ForcedExit => "ForcedExit",
+            TxType::MintNFT => "MintNFT",
+            TxType::WithdrawNFT => "WithdrawNFT",
+            TxType::Swap => "Swap",
+        }
+    }
+}
+
+impl FromStr for TxType {
+    type Err = UnknownTxType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Transfer" => Ok(TxType::Transfer),
+            "Withdraw" => Ok(TxType::Withdraw),
+            "ChangePubKey" => Ok(TxType::ChangePubKey),
+            "Deposit" => Ok(TxType::Deposit),
+            "FullExit" => Ok(TxType::FullExit),
+            "ForcedExit" => Ok(TxType::ForcedExit),
+            "MintNFT" => Ok(TxType::MintNFT),
+            "WithdrawNFT" => Ok(TxType::WithdrawNFT),
+            "Swap" => Ok(TxType::Swap),
+            _ => Err(UnknownTxType(s.to_string())),
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(untagged)]
 pub enum Receipt {
     L1(L1Receipt),
     L2(L2Receipt),
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
     pub tx_hash: TxHash,
     pub block_index: Option<u32>,
     pub block_number: Option<BlockNumber>,
     pub op: TransactionData,
     pub status: TxInBlockStatus,
     pub fail_reason: Option<String>,
+    /// Category of the fail reason, `None` if the transaction hasn't failed.
+    pub fail_reason_code: Option<FailReasonCode>,
     pub created_at: Option<DateTime<Utc>>,
     pub batch_id: Option<u32>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum TransactionData {
     L1(L1Transaction),
     L2(L2Transaction),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type")]
 pub enum L2Transaction {
-    Transfer(Box<Transfer>),
+    Transfer(#[schemars(with = "schema::Object")] Box<Transfer>),
     Withdraw(Box<WithdrawData>),
     #[doc(hidden)]
-    Close(Box<Close>),
-    ChangePubKey(Box<ChangePubKey>),
+    Close(#[schemars(with = "schema::Object")] Box<Close>),
+    ChangePubKey(#[schemars(with = "schema::Object")] Box<ChangePubKey>),
     ForcedExit(Box<ForcedExitData>),
-    MintNFT(Box<MintNFT>),
-    Swap(Box<Swap>),
+    MintNFT(#[schemars(with = "schema::Object")] Box<MintNFT>),
+    Swap(#[schemars(with = "schema::Object")] Box<Swap>),
     WithdrawNFT(Box<WithdrawNFTData>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ForcedExitData {
     #[serde(flatten)]
+    #[schemars(with = "schema::Object")]
     pub tx: ForcedExit,
+    #[schemars(with = "Option<schema::H256>")]
     pub eth_tx_hash: Option<H256>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WithdrawData {
     #[serde(flatten)]
+    #[schemars(with = "schema::Object")]
     pub tx: Withdraw,
+    #[schemars(with = "Option<schema::H256>")]
     pub eth_tx_hash: Option<H256>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct WithdrawNFTData {
     #[serde(flatten)]
+    #[schemars(with = "schema::Object")]
     pub tx: WithdrawNFT,
+    #[schemars(with = "Option<schema::H256>")]
     pub eth_tx_hash: Option<H256>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(tag = "type")]
 pub enum L1Transaction {
     Deposit(ApiDeposit),
@@ -194,72 +456,221 @@ impl L1Transaction {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Priority operation executed in a block, listed by its serial id.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpInfo {
+    pub serial_id: SerialId,
+    /// Either `Deposit` or `FullExit`.
+    pub op_type: TxType,
+    /// Hash of the L1 transaction which has created the operation.
+    #[schemars(with = "schema::H256")]
+    pub eth_hash: H256,
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
+    pub tx_hash: TxHash,
+    pub block_number: BlockNumber,
+    /// Either `committed` or `finalized`.
+    pub status: TxInBlockStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiDeposit {
+    #[schemars(with = "schema::Address")]
     pub from: Address,
     pub token_id: TokenId,
     #[serde(with = "BigUintSerdeAsRadix10Str")]
+    #[schemars(with = "schema::BigUint")]
     pub amount: BigUint,
+    #[schemars(with = "schema::Address")]
     pub to: Address,
     pub account_id: Option<AccountId>,
+    #[schemars(with = "schema::H256")]
     pub eth_hash: H256,
     pub id: SerialId,
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
     pub tx_hash: TxHash,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiFullExit {
     pub account_id: AccountId,
     pub token_id: TokenId,
+    #[schemars(with = "schema::H256")]
     pub eth_hash: H256,
     pub id: SerialId,
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
     pub tx_hash: TxHash,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(
+    Clone, Debug, Serialize, Deserialize, Default, Ord, PartialOrd, Eq, PartialEq, Hash, JsonSchema,
+)]
 pub struct TxHashSerializeWrapper(
-    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")] pub TxHash,
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
+    pub TxHash,
 );
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitTxResponse {
+    #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
+    pub tx_hash: TxHash,
+    /// Queued transaction with the same nonce which is replaced by the submitted one.
+    pub replaced_tx_hash: Option<TxHashSerializeWrapper>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SubmitBatchResponse {
     pub transaction_hashes: Vec<TxHashSerializeWrapper>,
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
     pub batch_hash: TxHash,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiTxBatch {
     #[serde(serialize_with = "ZeroPrefixHexSerde::serialize")]
+    #[schemars(with = "schema::TxHash")]
     pub batch_hash: TxHash,
     pub transaction_hashes: Vec<TxHashSerializeWrapper>,
     pub created_at: DateTime<Utc>,
     pub batch_status: BatchStatus,
+    /// Block the batch is included into, `None` while the batch is queued.
+    pub block_number: Option<BlockNumber>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchStatus {
     pub updated_at: DateTime<Utc>,
     pub last_state: TxInBlockStatus,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// Ethereum transaction that has confirmed a stage of the block finalization.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalityStage {
+    #[schemars(with = "schema::H256")]
+    pub eth_tx_hash: H256,
+    /// Number of the L1 blocks mined since the one including the Ethereum transaction.
+    pub confirmations: u64,
+}
+
+/// Ethereum transactions backing the transaction receipt.
+/// Stages that are not confirmed yet are `None`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TxFinality {
+    #[schemars(with = "schema::TxHash")]
+    pub tx_hash: TxHash,
+    /// Block the transaction is executed in, `None` if it's not executed yet or has failed.
+    pub rollup_block: Option<BlockNumber>,
+    pub commit: Option<FinalityStage>,
+    pub prove: Option<FinalityStage>,
+    pub execute: Option<FinalityStage>,
+}
+
+/// Progress of the withdrawal on L1. Funds are sent to the recipient on L1 by the
+/// `ExecuteBlocks` operation of the block the withdrawal is included into.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalStatus {
+    #[schemars(with = "schema::TxHash")]
+    pub tx_hash: TxHash,
+    /// Block the withdrawal is executed in, `None` if it's not executed yet or has failed.
+    pub rollup_block: Option<BlockNumber>,
+    /// Whether the `ExecuteBlocks` operation of the block is confirmed on L1.
+    pub executed_on_l1: bool,
+    /// L1 transaction which has executed the block and sent the funds.
+    #[schemars(with = "Option<schema::H256>")]
+    pub eth_tx_hash: Option<H256>,
+    /// Time the executing L1 transaction was sent at, the time of its confirmation is not stored.
+    pub eth_tx_sent_at: Option<DateTime<Utc>>,
+}
+
+/// Limits the submitted transactions and batches are checked against.
+/// Single transactions are checked as the batches of one transaction.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionLimits {
+    pub max_transactions_per_batch: u64,
+    /// Maximum number of the Ethereum signatures provided for the batch.
+    pub max_authors_per_batch: u64,
+    /// Maximum number of the block chunks taken by the transactions of the batch.
+    /// Transfers to the new accounts take more chunks than the transfers to the existing ones.
+    pub max_chunks_per_batch: u64,
+    /// Maximum number of the `Withdraw`, `WithdrawNFT` and `ForcedExit` transactions in the batch.
+    pub max_withdrawals_per_batch: u64,
+    /// How far ahead of the next expected nonce of the account a transaction nonce can be.
+    pub nonce_ahead_window: u32,
+}
+
+/// Limit violated by the submitted transaction or batch,
+/// named after the corresponding field of `SubmissionLimits`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionLimit {
+    #[serde(rename = "maxTransactionsPerBatch")]
+    TransactionsPerBatch,
+    #[serde(rename = "maxAuthorsPerBatch")]
+    AuthorsPerBatch,
+    #[serde(rename = "maxChunksPerBatch")]
+    ChunksPerBatch,
+    #[serde(rename = "maxWithdrawalsPerBatch")]
+    WithdrawalsPerBatch,
+    #[serde(rename = "nonceAheadWindow")]
+    NonceAheadWindow,
+}
+
+impl SubmissionLimit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TransactionsPerBatch => "maxTransactionsPerBatch",
+            Self::AuthorsPerBatch => "maxAuthorsPerBatch",
+            Self::ChunksPerBatch => "maxChunksPerBatch",
+            Self::WithdrawalsPerBatch => "maxWithdrawalsPerBatch",
+            Self::NonceAheadWindow => "nonceAheadWindow",
+        }
+    }
+}
+
+impl fmt::Display for SubmissionLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Details of the submission error reported when the transaction or batch exceeds the limit.
+/// For the nonce window, `value` is how far the nonce is ahead of the next expected one.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmissionLimitExceeded {
+    pub limit: SubmissionLimit,
+    pub value: u64,
+    pub max: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Toggle2FA {
     pub enable: bool,
     #[serde(with = "ts_milliseconds")]
+    #[schemars(with = "i64")]
     pub timestamp: DateTime<Utc>,
     pub account_id: AccountId,
+    #[schemars(with = "schema::Object")]
     pub signature: TxEthSignature,
     // If supplied, only transaction signed with this pubkey hash will not
     // have their Ethereum signature checked
+    #[schemars(with = "Option<schema::PubKeyHash>")]
     pub pub_key_hash: Option<PubKeyHash>,
 }
 
@@ -292,7 +703,34 @@ impl Toggle2FA {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Body of the `accounts/{account_id}/2fa` request, which is the same as `Toggle2FA`
+/// except for the account id passed in the path.
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountToggle2FA {
+    pub enable: bool,
+    #[serde(with = "ts_milliseconds")]
+    #[schemars(with = "i64")]
+    pub timestamp: DateTime<Utc>,
+    #[schemars(with = "schema::Object")]
+    pub signature: TxEthSignature,
+    #[schemars(with = "Option<schema::PubKeyHash>")]
+    pub pub_key_hash: Option<PubKeyHash>,
+}
+
+impl AccountToggle2FA {
+    pub fn into_toggle(self, account_id: AccountId) -> Toggle2FA {
+        Toggle2FA {
+            enable: self.enable,
+            timestamp: self.timestamp,
+            account_id,
+            signature: self.signature,
+            pub_key_hash: self.pub_key_hash,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Toggle2FAResponse {
     pub success: bool,
 }
@@ -4,6 +4,7 @@ macro_rules! basic_type {
         #[derive(
             Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash, PartialOrd, Ord, Default
         )]
+        #[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
         pub struct $name(pub $type);
 
         impl Deref for $name {
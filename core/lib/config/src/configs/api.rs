@@ -2,11 +2,11 @@ use num::{rational::Ratio, BigUint};
 /// External uses
 use serde::Deserialize;
 /// Built-in uses
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 use zksync_utils::scaled_u64_to_ratio;
 // Workspace uses
-use zksync_types::AccountId;
+use zksync_types::{AccountId, Address};
 // Local uses
 use crate::envy_load;
 
@@ -53,6 +53,40 @@ impl CommonApiConfig {
         scaled_u64_to_ratio(self.max_subsidy_usd_scaled)
     }
 
+    pub fn mempool_revalidation_interval(&self) -> Option<Duration> {
+        self.mempool_revalidation_interval_sec
+            .map(Duration::from_secs)
+    }
+
+    pub fn parked_tx_ttl(&self) -> Duration {
+        Duration::from_secs(self.parked_tx_ttl_sec)
+    }
+
+    pub fn stuck_accounts_check_interval(&self) -> Option<Duration> {
+        self.stuck_accounts_check_interval_sec
+            .map(Duration::from_secs)
+    }
+
+    pub fn stuck_account_threshold(&self) -> Duration {
+        Duration::from_secs(self.stuck_account_threshold_sec)
+    }
+
+    pub fn stuck_tx_dead_letter_timeout(&self) -> Option<Duration> {
+        self.stuck_tx_dead_letter_sec.map(Duration::from_secs)
+    }
+
+    pub fn max_valid_from_horizon(&self) -> Duration {
+        Duration::from_secs(self.max_valid_from_horizon_sec)
+    }
+
+    fn default_shutdown_grace_period_sec() -> u64 {
+        30
+    }
+
+    pub fn shutdown_grace_period(&self) -> Duration {
+        Duration::from_secs(self.shutdown_grace_period_sec)
+    }
+
     pub fn from_env() -> Self {
         envy_load!("common", "API_COMMON_")
     }
@@ -97,6 +131,11 @@ impl PrometheusConfig {
     pub fn from_env() -> Self {
         envy_load!("prometheus", "API_PROMETHEUS_")
     }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        let ip = self.listen_ip.unwrap_or_else(|| "0.0.0.0".parse().unwrap());
+        SocketAddr::new(ip, self.port)
+    }
 }
 
 // Common configuration options for the API
@@ -109,10 +148,23 @@ pub struct CommonApiConfig {
     pub forced_exit_minimum_account_age_secs: u64,
     /// List of account IDs that do not have to pay fees for operations.
     pub fee_free_accounts: Vec<AccountId>,
+    /// Addresses of the protocol maintenance accounts that are not required to pay fees.
+    /// Signatures and nonces of their transactions are still checked. More accounts can be
+    /// added at runtime through the admin API.
+    #[serde(default)]
+    pub maintenance_accounts: Vec<Address>,
+    /// Addresses of the accounts which are not allowed to submit transactions. Their transactions
+    /// already queued in the mempool are parked by the re-validation.
+    #[serde(default)]
+    pub blocked_accounts: Vec<Address>,
     pub enforce_pubkey_change_fee: bool,
 
     pub max_number_of_transactions_per_batch: u64,
     pub max_number_of_authors_per_batch: u64,
+    /// Maximum number of the block chunks taken by the transactions of a single batch.
+    pub max_number_of_chunks_per_batch: u64,
+    /// Maximum number of the withdrawal transactions (including NFT withdrawals and forced exits) in a single batch.
+    pub max_number_of_withdrawals_per_batch: u64,
 
     /// The IPs which have their CPK (CREATE2) subsidized
     pub subsidized_ips: Vec<String>,
@@ -122,6 +174,41 @@ pub struct CommonApiConfig {
 
     /// The name of current subsidy. It is needed to conveniently fetch historical data regarding subsidies for different partners
     pub subsidy_name: String,
+
+    /// Interval between re-validations of the queued transactions. Re-validation is disabled if not set.
+    pub mempool_revalidation_interval_sec: Option<u64>,
+    /// Margin (in percents) by which the fee of a queued transaction may fall below the required one
+    /// before the transaction is parked.
+    pub mempool_revalidation_fee_margin_percent: u64,
+    /// Time after which a parked transaction is removed from the mempool.
+    pub parked_tx_ttl_sec: u64,
+    /// Maximum distance between the nonce of a submitted transaction and the pending nonce of the account.
+    /// Transactions within the window wait in the mempool until the preceding ones are executed.
+    pub nonce_ahead_window: u32,
+    /// Interval between the checks for the accounts stuck behind an unexecutable transaction.
+    /// The check is disabled if not set.
+    pub stuck_accounts_check_interval_sec: Option<u64>,
+    /// Time after which an account is considered stuck if its lowest queued transaction is parked
+    /// or failing while the transactions with the higher nonces are queued.
+    pub stuck_account_threshold_sec: u64,
+    /// Time after which the transaction blocking the account is dead-lettered, so the account
+    /// can submit another transaction with the same nonce. Blockers are never dead-lettered if not set.
+    pub stuck_tx_dead_letter_sec: Option<u64>,
+    /// Maximum time by which `valid_from` of a submitted transaction may be ahead of the current time.
+    /// Such transactions are deferred in the mempool until they become valid.
+    pub max_valid_from_horizon_sec: u64,
+    /// Rate at which the transactions of a single sender are accepted, in transactions per second.
+    /// Submissions are not throttled if not set.
+    pub sender_submission_rate_per_sec: Option<u32>,
+    /// Number of the transactions a single sender may submit at once before being throttled.
+    pub sender_submission_burst: u32,
+    /// Time given to the in-flight requests to finish once the API servers are shutting down.
+    #[serde(default = "CommonApiConfig::default_shutdown_grace_period_sec")]
+    pub shutdown_grace_period_sec: u64,
+    /// Whether the queued transaction may be replaced by the one with the same nonce paying a higher fee.
+    /// Transactions with the taken nonce are rejected otherwise.
+    #[serde(default)]
+    pub tx_replacement_enabled: bool,
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -178,12 +265,22 @@ pub struct PrivateApiConfig {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// Interval between checks that the transactions accepted by the API server are known to the core.
+    /// The check is disabled if the value is not set.
+    pub mempool_resync_interval_sec: Option<u64>,
+    /// Whether the transactions unknown to the core should be forwarded to the mempool again.
+    #[serde(default)]
+    pub mempool_resync_reforward: bool,
 }
 
 impl PrivateApiConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn mempool_resync_interval(&self) -> Option<Duration> {
+        self.mempool_resync_interval_sec.map(Duration::from_secs)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -192,12 +289,35 @@ pub struct RestApiConfig {
     pub port: u16,
     /// URL to access API server.
     pub url: String,
+    /// Whether the exit data endpoint is enabled.
+    /// Generating exit data requires restoring the whole verified state, so it's disabled by default.
+    #[serde(default)]
+    pub exit_data_enabled: bool,
+    /// Maximum number of requests per minute from a single IP address for the requests without an API key.
+    /// If not set, such requests are not limited.
+    pub ip_requests_per_minute: Option<u32>,
+    /// Maximum number of blocks in the range of a single block export request.
+    pub max_export_blocks: u32,
+    /// Maximum limit of the paginated v0.2 requests, the requests above it are rejected.
+    #[serde(default = "RestApiConfig::default_max_page_limit")]
+    pub max_page_limit: u32,
+    /// The server is not ready if the last committed block is older than this.
+    /// If not set, the age of the last block is not checked.
+    pub ready_max_block_age_sec: Option<u64>,
 }
 
 impl RestApiConfig {
+    fn default_max_page_limit() -> u32 {
+        100
+    }
+
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn ready_max_block_age(&self) -> Option<Duration> {
+        self.ready_max_block_age_sec.map(Duration::from_secs)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -210,9 +330,75 @@ pub struct JsonRpcConfig {
     pub ws_port: u16,
     /// URL to access WebSocket RPC server.
     pub ws_url: String,
+    /// Maximum number of active subscriptions of a single WebSocket connection.
+    /// Subscribe requests above the limit are rejected.
+    #[serde(default = "JsonRpcConfig::default_max_subscriptions_per_connection")]
+    pub max_subscriptions_per_connection: usize,
+    /// Time given to the new WebSocket connection to subscribe to anything, after which
+    /// it's closed. Connections without subscriptions are kept open if not set.
+    pub ws_idle_timeout_sec: Option<u64>,
+    /// Interval at which the server pings the WebSocket connections.
+    /// Connections aren't pinged if not set.
+    pub ws_ping_interval_sec: Option<u64>,
+    /// Number of pings in a row the WebSocket client may leave without a pong,
+    /// after which the connection is closed.
+    #[serde(default = "JsonRpcConfig::default_ws_max_missed_pongs")]
+    pub ws_max_missed_pongs: u32,
+    /// Maximum number of calls in a single batch request of the HTTP RPC server.
+    /// Longer batches are rejected as a whole.
+    #[serde(default = "JsonRpcConfig::default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// Maximum number of calls of a single batch request processed concurrently.
+    #[serde(default = "JsonRpcConfig::default_batch_parallelism")]
+    pub batch_parallelism: usize,
+    /// Rate at which the read calls of a single IP are accepted by the HTTP RPC server,
+    /// in calls per second. Read calls are not throttled if not set.
+    pub ip_read_requests_per_sec: Option<u32>,
+    /// Number of the read calls a single IP may perform at once before being throttled.
+    #[serde(default = "JsonRpcConfig::default_ip_read_requests_burst")]
+    pub ip_read_requests_burst: u32,
+    /// Rate at which the submission calls of a single IP are accepted by the HTTP RPC server,
+    /// in calls per second. Submission calls are not throttled if not set.
+    pub ip_submit_requests_per_sec: Option<u32>,
+    /// Number of the submission calls a single IP may perform at once before being throttled.
+    #[serde(default = "JsonRpcConfig::default_ip_submit_requests_burst")]
+    pub ip_submit_requests_burst: u32,
+    /// Whether the server is behind a trusted proxy, so the IP of the client is taken from the
+    /// `X-Forwarded-For` or `CF-Connecting-IP` headers. Otherwise the address of the connected peer
+    /// is used, since the headers can be set by the clients themselves.
+    #[serde(default)]
+    pub trusted_proxy: bool,
+    /// Whether the `pending_block_info` method exposing the block being filled by the state keeper
+    /// is available. Disabled by default, so public nodes don't expose it unintentionally.
+    #[serde(default)]
+    pub pending_block_info_enabled: bool,
 }
 
 impl JsonRpcConfig {
+    fn default_max_subscriptions_per_connection() -> usize {
+        1000
+    }
+
+    fn default_ws_max_missed_pongs() -> u32 {
+        3
+    }
+
+    fn default_max_batch_size() -> usize {
+        50
+    }
+
+    fn default_batch_parallelism() -> usize {
+        10
+    }
+
+    fn default_ip_read_requests_burst() -> u32 {
+        100
+    }
+
+    fn default_ip_submit_requests_burst() -> u32 {
+        20
+    }
+
     pub fn http_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.http_port)
     }
@@ -220,6 +406,14 @@ impl JsonRpcConfig {
     pub fn ws_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.ws_port)
     }
+
+    pub fn ws_idle_timeout(&self) -> Option<Duration> {
+        self.ws_idle_timeout_sec.map(Duration::from_secs)
+    }
+
+    pub fn ws_ping_interval(&self) -> Option<Duration> {
+        self.ws_ping_interval_sec.map(Duration::from_secs)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -243,12 +437,15 @@ impl Web3Config {
 pub struct PrometheusConfig {
     /// Port to which the Prometheus exporter server is listening.
     pub port: u16,
+    /// IP address to which the Prometheus exporter server is listening.
+    /// If not set, the server listens on all the interfaces.
+    pub listen_ip: Option<IpAddr>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::configs::test_utils::set_env;
+    use crate::configs::test_utils::{addr, set_env};
     use std::net::IpAddr;
 
     fn expected_config() -> ApiConfig {
@@ -259,10 +456,26 @@ mod tests {
                 enforce_pubkey_change_fee: true,
                 max_number_of_transactions_per_batch: 200,
                 max_number_of_authors_per_batch: 10,
+                max_number_of_chunks_per_batch: 182,
+                max_number_of_withdrawals_per_batch: 50,
                 fee_free_accounts: vec![AccountId(4078), AccountId(387)],
+                maintenance_accounts: vec![addr("1111111111111111111111111111111111111111")],
+                blocked_accounts: vec![addr("2222222222222222222222222222222222222222")],
                 subsidized_ips: vec!["127.0.0.1".to_owned()],
                 max_subsidy_usd_scaled: 20000,
                 subsidy_name: String::from("PartnerName"),
+                mempool_revalidation_interval_sec: Some(60),
+                mempool_revalidation_fee_margin_percent: 10,
+                parked_tx_ttl_sec: 3600,
+                nonce_ahead_window: 10,
+                stuck_accounts_check_interval_sec: Some(60),
+                stuck_account_threshold_sec: 600,
+                stuck_tx_dead_letter_sec: Some(86400),
+                max_valid_from_horizon_sec: 86400,
+                sender_submission_rate_per_sec: Some(10),
+                sender_submission_burst: 50,
+                shutdown_grace_period_sec: 30,
+                tx_replacement_enabled: true,
             },
             admin: AdminApiConfig {
                 port: 8080,
@@ -272,12 +485,29 @@ mod tests {
             rest: RestApiConfig {
                 port: 3001,
                 url: "http://127.0.0.1:3001".into(),
+                exit_data_enabled: true,
+                ip_requests_per_minute: Some(600),
+                max_export_blocks: 1000,
+                max_page_limit: 100,
+                ready_max_block_age_sec: Some(3600),
             },
             json_rpc: JsonRpcConfig {
                 http_port: 3030,
                 http_url: "http://127.0.0.1:3030".into(),
                 ws_port: 3031,
                 ws_url: "ws://127.0.0.1:3031".into(),
+                max_subscriptions_per_connection: 1000,
+                ws_idle_timeout_sec: Some(60),
+                ws_ping_interval_sec: Some(20),
+                ws_max_missed_pongs: 3,
+                max_batch_size: 50,
+                batch_parallelism: 10,
+                ip_read_requests_per_sec: Some(50),
+                ip_read_requests_burst: 100,
+                ip_submit_requests_per_sec: Some(5),
+                ip_submit_requests_burst: 20,
+                trusted_proxy: false,
+                pending_block_info_enabled: true,
             },
             web3: Web3Config {
                 port: 3002,
@@ -288,13 +518,18 @@ mod tests {
             private: PrivateApiConfig {
                 port: 8090,
                 url: "http://127.0.0.1:8090".into(),
+                mempool_resync_interval_sec: Some(30),
+                mempool_resync_reforward: true,
             },
             prover: ProverApiConfig {
                 port: 8088,
                 url: "http://127.0.0.1:8088".into(),
                 secret_auth: "sample".into(),
             },
-            prometheus: PrometheusConfig { port: 3312 },
+            prometheus: PrometheusConfig {
+                port: 3312,
+                listen_ip: Some("127.0.0.1".parse().unwrap()),
+            },
             token_config: TokenConfig {
                 invalidate_token_cache_period_sec: 10,
             },
@@ -307,32 +542,68 @@ mod tests {
 API_COMMON_CACHES_SIZE="10000"
 API_COMMON_FORCED_EXIT_MINIMUM_ACCOUNT_AGE_SECS="0"
 API_COMMON_FEE_FREE_ACCOUNTS=4078,387
+API_COMMON_MAINTENANCE_ACCOUNTS="0x1111111111111111111111111111111111111111"
+API_COMMON_BLOCKED_ACCOUNTS="0x2222222222222222222222222222222222222222"
 API_COMMON_ENFORCE_PUBKEY_CHANGE_FEE=true
 API_COMMON_SUBSIDIZED_IPS="127.0.0.1"
 API_COMMON_MAX_SUBSIDY_USD_SCALED=20000
 API_COMMON_SUBSIDY_NAME=PartnerName
 API_COMMON_MAX_NUMBER_OF_TRANSACTIONS_PER_BATCH=200
 API_COMMON_MAX_NUMBER_OF_AUTHORS_PER_BATCH=10
+API_COMMON_MAX_NUMBER_OF_CHUNKS_PER_BATCH=182
+API_COMMON_MAX_NUMBER_OF_WITHDRAWALS_PER_BATCH=50
+API_COMMON_MEMPOOL_REVALIDATION_INTERVAL_SEC="60"
+API_COMMON_MEMPOOL_REVALIDATION_FEE_MARGIN_PERCENT="10"
+API_COMMON_PARKED_TX_TTL_SEC="3600"
+API_COMMON_NONCE_AHEAD_WINDOW="10"
+API_COMMON_STUCK_ACCOUNTS_CHECK_INTERVAL_SEC="60"
+API_COMMON_STUCK_ACCOUNT_THRESHOLD_SEC="600"
+API_COMMON_STUCK_TX_DEAD_LETTER_SEC="86400"
+API_COMMON_MAX_VALID_FROM_HORIZON_SEC="86400"
+API_COMMON_SENDER_SUBMISSION_RATE_PER_SEC="10"
+API_COMMON_SENDER_SUBMISSION_BURST="50"
+API_COMMON_SHUTDOWN_GRACE_PERIOD_SEC="30"
+API_COMMON_TX_REPLACEMENT_ENABLED="true"
 API_TOKEN_INVALIDATE_TOKEN_CACHE_PERIOD_SEC="10"
 API_ADMIN_PORT="8080"
 API_ADMIN_URL="http://127.0.0.1:8080"
 API_ADMIN_SECRET_AUTH="sample"
 API_REST_PORT="3001"
 API_REST_URL="http://127.0.0.1:3001"
+API_REST_EXIT_DATA_ENABLED="true"
+API_REST_IP_REQUESTS_PER_MINUTE="600"
+API_REST_MAX_EXPORT_BLOCKS="1000"
+API_REST_MAX_PAGE_LIMIT="100"
+API_REST_READY_MAX_BLOCK_AGE_SEC="3600"
 API_JSON_RPC_HTTP_PORT="3030"
 API_JSON_RPC_HTTP_URL="http://127.0.0.1:3030"
 API_JSON_RPC_WS_PORT="3031"
 API_JSON_RPC_WS_URL="ws://127.0.0.1:3031"
+API_JSON_RPC_MAX_SUBSCRIPTIONS_PER_CONNECTION="1000"
+API_JSON_RPC_WS_IDLE_TIMEOUT_SEC="60"
+API_JSON_RPC_WS_PING_INTERVAL_SEC="20"
+API_JSON_RPC_WS_MAX_MISSED_PONGS="3"
+API_JSON_RPC_MAX_BATCH_SIZE="50"
+API_JSON_RPC_BATCH_PARALLELISM="10"
+API_JSON_RPC_IP_READ_REQUESTS_PER_SEC="50"
+API_JSON_RPC_IP_READ_REQUESTS_BURST="100"
+API_JSON_RPC_IP_SUBMIT_REQUESTS_PER_SEC="5"
+API_JSON_RPC_IP_SUBMIT_REQUESTS_BURST="20"
+API_JSON_RPC_TRUSTED_PROXY="false"
+API_JSON_RPC_PENDING_BLOCK_INFO_ENABLED="true"
 API_WEB3_PORT="3002"
 API_WEB3_URL="http://127.0.0.1:3002"
 API_WEB3_CHAIN_ID="240"
 API_WEB3_MAX_BLOCK_RANGE="10"
 API_PRIVATE_PORT="8090"
 API_PRIVATE_URL="http://127.0.0.1:8090"
+API_PRIVATE_MEMPOOL_RESYNC_INTERVAL_SEC="30"
+API_PRIVATE_MEMPOOL_RESYNC_REFORWARD="true"
 API_PROVER_PORT="8088"
 API_PROVER_URL="http://127.0.0.1:8088"
 API_PROVER_SECRET_AUTH="sample"
 API_PROMETHEUS_PORT="3312"
+API_PROMETHEUS_LISTEN_IP="127.0.0.1"
         "#;
         set_env(config);
 
@@ -362,6 +633,31 @@ API_PROMETHEUS_PORT="3312"
             config.private.bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.private.port)
         );
+        assert_eq!(
+            config.rest.ready_max_block_age(),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(
+            config.common.mempool_revalidation_interval(),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(config.common.parked_tx_ttl(), Duration::from_secs(3600));
+        assert_eq!(
+            config.common.stuck_accounts_check_interval(),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            config.common.stuck_account_threshold(),
+            Duration::from_secs(600)
+        );
+        assert_eq!(
+            config.common.stuck_tx_dead_letter_timeout(),
+            Some(Duration::from_secs(86400))
+        );
+        assert_eq!(
+            config.private.mempool_resync_interval(),
+            Some(Duration::from_secs(30))
+        );
         assert_eq!(
             config.json_rpc.http_bind_addr(),
             SocketAddr::new(bind_broadcast_addr, config.json_rpc.http_port)
@@ -55,6 +55,18 @@ pub struct Circuit {
 }
 
 impl Circuit {
+    pub fn supported_block_chunks_sizes_with_setup_pow(&self) -> Vec<(usize, u32)> {
+        self.supported_block_chunks_sizes
+            .iter()
+            .cloned()
+            .zip(
+                self.supported_block_chunks_sizes_setup_powers
+                    .iter()
+                    .map(|&power| power as u32),
+            )
+            .collect()
+    }
+
     pub fn supported_aggregated_proof_sizes_with_setup_pow(&self) -> Vec<(usize, u32)> {
         self.supported_aggregated_proof_sizes
             .iter()
@@ -94,6 +106,28 @@ pub struct StateKeeper {
     pub block_prove_deadline: u64,
     pub block_execute_deadline: u64,
     pub max_aggregated_tx_gas: usize,
+    /// Number of failed execution attempts after which the transaction is moved to the dead-letter state
+    /// and is not proposed for execution anymore.
+    pub max_failed_tx_attempts: u32,
+    /// Target average block time (ms). If set, the seal timeout of the non-full blocks is adjusted
+    /// to keep the average block time close to the target. Otherwise, `miniblock_iterations` are used.
+    pub block_time_target: Option<u64>,
+    /// Lower bound of the adjusted seal timeout (ms).
+    pub min_block_seal_timeout: u64,
+    /// Upper bound of the adjusted seal timeout (ms).
+    pub max_block_seal_timeout: u64,
+    /// Block sizes to be generated during the off-peak hours instead of `block_chunk_sizes`.
+    /// Usually contains smaller sizes, so that blocks are proven faster when traffic is low.
+    /// Blocks that don't fit into these sizes are sealed using `block_chunk_sizes`.
+    #[serde(default)]
+    pub off_peak_block_chunk_sizes: Option<Vec<usize>>,
+    /// Hour of the day (UTC, inclusive) when the off-peak window starts.
+    #[serde(default)]
+    pub off_peak_start_hour: Option<u32>,
+    /// Hour of the day (UTC, exclusive) when the off-peak window ends.
+    /// The window wraps around midnight if it's less than `off_peak_start_hour`.
+    #[serde(default)]
+    pub off_peak_end_hour: Option<u32>,
 }
 
 impl StateKeeper {
@@ -102,6 +136,18 @@ impl StateKeeper {
         Duration::from_millis(self.miniblock_iteration_interval)
     }
 
+    pub fn block_time_target(&self) -> Option<Duration> {
+        self.block_time_target.map(Duration::from_millis)
+    }
+
+    pub fn min_block_seal_timeout(&self) -> Duration {
+        Duration::from_millis(self.min_block_seal_timeout)
+    }
+
+    pub fn max_block_seal_timeout(&self) -> Duration {
+        Duration::from_millis(self.max_block_seal_timeout)
+    }
+
     pub fn block_commit_deadline(&self) -> Duration {
         Duration::from_secs(self.block_commit_deadline)
     }
@@ -147,6 +193,13 @@ mod tests {
                 block_prove_deadline: 3_000,
                 block_execute_deadline: 4_000,
                 max_aggregated_tx_gas: 4_000_000,
+                max_failed_tx_attempts: 3,
+                block_time_target: Some(30_000),
+                min_block_seal_timeout: 1_000,
+                max_block_seal_timeout: 60_000,
+                off_peak_block_chunk_sizes: Some(vec![6]),
+                off_peak_start_hour: Some(22),
+                off_peak_end_hour: Some(6),
             },
         }
     }
@@ -175,6 +228,13 @@ CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE="300"
 CHAIN_STATE_KEEPER_BLOCK_PROVE_DEADLINE="3000"
 CHAIN_STATE_KEEPER_BLOCK_EXECUTE_DEADLINE="4000"
 CHAIN_STATE_KEEPER_MAX_AGGREGATED_TX_GAS="4000000"
+CHAIN_STATE_KEEPER_MAX_FAILED_TX_ATTEMPTS="3"
+CHAIN_STATE_KEEPER_BLOCK_TIME_TARGET="30000"
+CHAIN_STATE_KEEPER_MIN_BLOCK_SEAL_TIMEOUT="1000"
+CHAIN_STATE_KEEPER_MAX_BLOCK_SEAL_TIMEOUT="60000"
+CHAIN_STATE_KEEPER_OFF_PEAK_BLOCK_CHUNK_SIZES="6"
+CHAIN_STATE_KEEPER_OFF_PEAK_START_HOUR="22"
+CHAIN_STATE_KEEPER_OFF_PEAK_END_HOUR="6"
         "#;
         set_env(config);
 
@@ -18,6 +18,13 @@ pub struct DBConfig {
     pub rejected_transactions_max_age: u64,
     /// Sleep time (in hours) of the actor responsible for deleting failed transactions from the database.
     pub rejected_transactions_cleaner_interval: u64,
+    /// Payloads of the aggregated operations confirmed more than this amount of blocks ago
+    /// are moved out of the `aggregate_operations` table by the same actor.
+    pub aggregated_operations_retention_blocks: u32,
+    /// Amount of the aggregated operations processed in a single database transaction.
+    pub aggregated_operations_archive_batch_size: u32,
+    /// Whether the old payloads are archived. If not, they are dropped and can't be restored.
+    pub archive_aggregated_operations: bool,
 }
 
 impl DBConfig {
@@ -47,6 +54,9 @@ mod tests {
             url: "postgres://postgres@localhost/plasma".into(),
             rejected_transactions_max_age: 336,
             rejected_transactions_cleaner_interval: 24,
+            aggregated_operations_retention_blocks: 1000,
+            aggregated_operations_archive_batch_size: 100,
+            archive_aggregated_operations: true,
         }
     }
 
@@ -57,6 +67,9 @@ DATABASE_POOL_SIZE="10"
 DATABASE_URL="postgres://postgres@localhost/plasma"
 DATABASE_REJECTED_TRANSACTIONS_MAX_AGE="336"
 DATABASE_REJECTED_TRANSACTIONS_CLEANER_INTERVAL="24"
+DATABASE_AGGREGATED_OPERATIONS_RETENTION_BLOCKS="1000"
+DATABASE_AGGREGATED_OPERATIONS_ARCHIVE_BATCH_SIZE="100"
+DATABASE_ARCHIVE_AGGREGATED_OPERATIONS="true"
         "#;
         set_env(config);
 
@@ -14,9 +14,17 @@ pub struct ETHWatchConfig {
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// How often the contract upgrade events are requested from the Ethereum Watch module.
+    /// Value in seconds.
+    #[serde(default = "ETHWatchConfig::default_contract_upgrade_poll_interval_sec")]
+    pub contract_upgrade_poll_interval_sec: u64,
 }
 
 impl ETHWatchConfig {
+    fn default_contract_upgrade_poll_interval_sec() -> u64 {
+        60
+    }
+
     pub fn from_env() -> Self {
         envy_load!("eth_watch", "ETH_WATCH_")
     }
@@ -25,6 +33,11 @@ impl ETHWatchConfig {
     pub fn poll_interval(&self) -> Duration {
         Duration::from_millis(self.eth_node_poll_interval)
     }
+
+    /// Converts `self.contract_upgrade_poll_interval_sec` into `Duration`.
+    pub fn contract_upgrade_poll_interval(&self) -> Duration {
+        Duration::from_secs(self.contract_upgrade_poll_interval_sec)
+    }
 }
 
 #[cfg(test)]
@@ -36,6 +49,7 @@ mod tests {
         ETHWatchConfig {
             confirmations_for_eth_event: 0,
             eth_node_poll_interval: 300,
+            contract_upgrade_poll_interval_sec: 30,
         }
     }
 
@@ -44,6 +58,7 @@ mod tests {
         let config = r#"
 ETH_WATCH_CONFIRMATIONS_FOR_ETH_EVENT="0"
 ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
+ETH_WATCH_CONTRACT_UPGRADE_POLL_INTERVAL_SEC="30"
         "#;
         set_env(config);
 
@@ -60,5 +75,9 @@ ETH_WATCH_ETH_NODE_POLL_INTERVAL="300"
             config.poll_interval(),
             Duration::from_millis(config.eth_node_poll_interval)
         );
+        assert_eq!(
+            config.contract_upgrade_poll_interval(),
+            Duration::from_secs(30)
+        );
     }
 }
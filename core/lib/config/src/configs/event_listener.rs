@@ -1,5 +1,5 @@
 // Built-in uses
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Duration};
 
 // External uses
 use serde::Deserialize;
@@ -17,6 +17,12 @@ pub struct EventListenerConfig {
     /// PostgreSQL channel name to listen on. Must be equal to the one
     /// hardcoded into database migrations.
     pub channel_name: String,
+    /// The port used by the private server managing the webhooks.
+    pub admin_port: u16,
+    /// Number of failed webhook delivery attempts after which the delivery is dead-lettered.
+    pub webhook_max_attempts: u32,
+    /// Delay before the first webhook delivery retry in milliseconds, doubled after each failure.
+    pub webhook_retry_delay: u64,
 }
 
 impl EventListenerConfig {
@@ -27,6 +33,14 @@ impl EventListenerConfig {
     pub fn ws_bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.ws_port)
     }
+
+    pub fn admin_bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.admin_port)
+    }
+
+    pub fn webhook_retry_delay(&self) -> Duration {
+        Duration::from_millis(self.webhook_retry_delay)
+    }
 }
 
 #[cfg(test)]
@@ -40,6 +54,9 @@ mod tests {
             ws_port: 65535,
             ws_url: "ws://localhost:12345".into(),
             channel_name: "zksync_event_channel".into(),
+            admin_port: 65534,
+            webhook_max_attempts: 10,
+            webhook_retry_delay: 1000,
         }
     }
 
@@ -49,6 +66,9 @@ mod tests {
 EVENT_LISTENER_WS_URL="ws://localhost:12345"
 EVENT_LISTENER_WS_PORT="65535"
 EVENT_LISTENER_CHANNEL_NAME="zksync_event_channel"
+EVENT_LISTENER_ADMIN_PORT="65534"
+EVENT_LISTENER_WEBHOOK_MAX_ATTEMPTS="10"
+EVENT_LISTENER_WEBHOOK_RETRY_DELAY="1000"
         "#;
         set_env(config);
 
@@ -65,5 +85,9 @@ EVENT_LISTENER_CHANNEL_NAME="zksync_event_channel"
             config.ws_bind_addr(),
             SocketAddr::new(bind_addr, config.ws_port)
         );
+        assert_eq!(
+            config.admin_bind_addr(),
+            SocketAddr::new(bind_addr, config.admin_port)
+        );
     }
 }
@@ -4,7 +4,7 @@ use std::time::Duration;
 // External uses
 use serde::Deserialize;
 // Workspace uses
-use zksync_types::TokenInfo;
+use zksync_types::{Address, TokenInfo, U256};
 // Local uses
 use crate::envy_load;
 
@@ -17,6 +17,36 @@ pub struct TokenHandlerConfig {
     pub poll_interval: u64,
     /// Link to MatterMost channel for token list notification.
     pub webhook_url: String,
+    /// Policy of registering the tokens added through the governance contract.
+    #[serde(default)]
+    pub listing_policy: TokenListingPolicy,
+    /// Address of the token in which the listing fee is paid.
+    #[serde(default)]
+    pub listing_fee_token: Address,
+    /// Address that should receive the listing fee.
+    #[serde(default)]
+    pub listing_fee_receiver: Address,
+    /// Minimal listing fee (in the smallest units of the fee token) as a decimal string.
+    #[serde(default)]
+    pub listing_fee: String,
+}
+
+/// Determines which of the tokens added through the governance contract are registered by the server.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenListingPolicy {
+    /// Every added token is registered.
+    Open,
+    /// Added tokens have to be approved by the operator.
+    Permissioned,
+    /// Added tokens are registered only if the listing fee was paid.
+    FeeBased,
+}
+
+impl Default for TokenListingPolicy {
+    fn default() -> Self {
+        Self::Open
+    }
 }
 
 impl TokenHandlerConfig {
@@ -33,6 +63,14 @@ impl TokenHandlerConfig {
         Duration::from_secs(self.poll_interval)
     }
 
+    /// Parses the minimal listing fee, empty value means no fee.
+    pub fn listing_fee(&self) -> U256 {
+        if self.listing_fee.is_empty() {
+            return U256::zero();
+        }
+        U256::from_dec_str(&self.listing_fee).expect("Invalid token listing fee")
+    }
+
     pub fn token_list(&self) -> Vec<TokenInfo> {
         let token_list_name = self.token_list_file();
         let path = format!("./etc/token-lists/{}.json", token_list_name);
@@ -52,6 +90,14 @@ mod tests {
             token_list_name: "localhost".to_string(),
             poll_interval: 1,
             webhook_url: "http://127.0.0.1".to_string(),
+            listing_policy: TokenListingPolicy::FeeBased,
+            listing_fee_token: "0x0000000000000000000000000000000000000001"
+                .parse()
+                .unwrap(),
+            listing_fee_receiver: "0x0000000000000000000000000000000000000002"
+                .parse()
+                .unwrap(),
+            listing_fee: "250000000000000000000".to_string(),
         }
     }
 
@@ -61,11 +107,19 @@ mod tests {
 TOKEN_HANDLER_POLL_INTERVAL=1
 TOKEN_HANDLER_WEBHOOK_URL="http://127.0.0.1"
 TOKEN_HANDLER_TOKEN_LIST_NAME="localhost"
+TOKEN_HANDLER_LISTING_POLICY="fee_based"
+TOKEN_HANDLER_LISTING_FEE_TOKEN="0x0000000000000000000000000000000000000001"
+TOKEN_HANDLER_LISTING_FEE_RECEIVER="0x0000000000000000000000000000000000000002"
+TOKEN_HANDLER_LISTING_FEE="250000000000000000000"
         "#;
         set_env(config);
 
         let actual_config = TokenHandlerConfig::from_env();
         let expected_config = expected_config();
         assert_eq!(actual_config, expected_config);
+        assert_eq!(
+            actual_config.listing_fee(),
+            U256::from_dec_str("250000000000000000000").unwrap()
+        );
     }
 }
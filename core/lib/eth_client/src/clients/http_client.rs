@@ -346,6 +346,15 @@ impl<S: EthereumSigner> ETHDirectClient<S> {
         self.eth_balance(self.inner.sender_account).await
     }
 
+    pub async fn contract_code(&self, address: Address) -> Result<Vec<u8>, anyhow::Error> {
+        #[cfg(feature = "with-metrics")]
+        let start = Instant::now();
+        let code = self.inner.web3.eth().code(address, None).await?;
+        #[cfg(feature = "with-metrics")]
+        metrics::histogram!("eth_client.direct.contract_code", start.elapsed());
+        Ok(code.0)
+    }
+
     pub async fn allowance(
         &self,
         token_address: Address,
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::Error;
-use ethabi::{Address, Contract};
+use ethabi::{Address, Contract, Token};
 use std::collections::{HashMap, HashSet};
 use tokio::sync::RwLock;
 use web3::contract::tokens::{Detokenize, Tokenize};
@@ -22,6 +22,9 @@ struct MockEthereumInner {
     gas_price: U256,
     tx_statuses: Arc<RwLock<HashMap<H256, ExecutedTxStatus>>>,
     sent_txs: Arc<RwLock<HashSet<Vec<u8>>>>,
+    contract_codes: Arc<RwLock<HashMap<Address, Vec<u8>>>>,
+    unavailable_contract_codes: Arc<RwLock<HashSet<Address>>>,
+    contract_call_results: Arc<RwLock<HashMap<(Address, String), Vec<Token>>>>,
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -37,6 +40,9 @@ impl Default for MockEthereumInner {
             gas_price: 100.into(),
             tx_statuses: Default::default(),
             sent_txs: Default::default(),
+            contract_codes: Default::default(),
+            unavailable_contract_codes: Default::default(),
+            contract_call_results: Default::default(),
         }
     }
 }
@@ -87,6 +93,39 @@ impl MockEthereum {
         self.inner.tx_statuses.write().await.insert(tx_hash, status);
     }
 
+    /// Deploys the contract with the provided code to the address.
+    pub async fn add_contract_code(&mut self, address: Address, code: Vec<u8>) {
+        self.inner
+            .contract_codes
+            .write()
+            .await
+            .insert(address, code);
+    }
+
+    /// Makes the requests of the code at the address fail, as if the node was unavailable.
+    pub async fn make_contract_code_unavailable(&mut self, address: Address) {
+        self.inner
+            .unavailable_contract_codes
+            .write()
+            .await
+            .insert(address);
+    }
+
+    /// Adds the result returned by the contract function for any parameters.
+    /// Calls of the functions without the result fail.
+    pub async fn add_contract_call_result<R: Tokenize>(
+        &mut self,
+        address: Address,
+        func: &str,
+        result: R,
+    ) {
+        self.inner
+            .contract_call_results
+            .write()
+            .await
+            .insert((address, func.to_owned()), result.into_tokens());
+    }
+
     /// Same as `add_successfull_execution`, but marks the transaction as a failure.
     pub async fn add_failed_execution(&mut self, hash: &H256, confirmations: u64) {
         Arc::get_mut(&mut self.inner).unwrap().block_number += confirmations;
@@ -185,14 +224,33 @@ impl MockEthereum {
         unreachable!()
     }
 
-    pub async fn tx_receipt(&self, _tx_hash: H256) -> Result<Option<TransactionReceipt>, Error> {
-        unreachable!()
+    /// Returns the receipt of the transaction added through `add_execution`,
+    /// the other receipts are unavailable, as if the node didn't respond.
+    pub async fn tx_receipt(&self, tx_hash: H256) -> Result<Option<TransactionReceipt>, Error> {
+        match self.inner.tx_statuses.read().await.get(&tx_hash) {
+            Some(status) => Ok(status.receipt.clone()),
+            None => anyhow::bail!("Receipt of {:?} is unavailable", tx_hash),
+        }
     }
 
     pub async fn eth_balance(&self, _address: Address) -> Result<U256, Error> {
         unreachable!()
     }
 
+    pub async fn contract_code(&self, address: Address) -> Result<Vec<u8>, Error> {
+        if self
+            .inner
+            .unavailable_contract_codes
+            .read()
+            .await
+            .contains(&address)
+        {
+            anyhow::bail!("Code at {:?} is unavailable", address);
+        }
+        let codes = self.inner.contract_codes.read().await;
+        Ok(codes.get(&address).cloned().unwrap_or_default())
+    }
+
     pub async fn contract_balance(
         &self,
         _token_address: Address,
@@ -241,12 +299,12 @@ impl MockEthereum {
     #[allow(clippy::too_many_arguments)]
     pub async fn call_contract_function<R, A, B, P>(
         &self,
-        _func: &str,
+        func: &str,
         _params: P,
         _from: A,
         _options: Options,
         _block: B,
-        _token_address: Address,
+        token_address: Address,
         _erc20_abi: ethabi::Contract,
     ) -> Result<R, anyhow::Error>
     where
@@ -255,7 +313,14 @@ impl MockEthereum {
         B: Into<Option<BlockId>>,
         P: Tokenize,
     {
-        todo!()
+        let results = self.inner.contract_call_results.read().await;
+        let tokens = results
+            .get(&(token_address, func.to_owned()))
+            .cloned()
+            .ok_or_else(|| {
+                anyhow::format_err!("Call of {} at {:?} reverted", func, token_address)
+            })?;
+        Ok(R::from_tokens(tokens)?)
     }
 
     pub fn create_contract(
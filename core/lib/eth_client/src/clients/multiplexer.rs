@@ -152,6 +152,10 @@ impl MultiplexerEthereumClient {
         multiple_call!(self, eth_balance(address));
     }
 
+    pub async fn contract_code(&self, address: Address) -> Result<Vec<u8>, anyhow::Error> {
+        multiple_call!(self, contract_code(address));
+    }
+
     pub async fn allowance(
         &self,
         token_address: Address,
@@ -178,6 +178,11 @@ impl EthereumGateway {
         delegate_call!(self.eth_balance(address))
     }
 
+    /// Returns the code of the contract at the address, which is empty if there is no contract.
+    pub async fn contract_code(&self, address: Address) -> Result<Vec<u8>, anyhow::Error> {
+        delegate_call!(self.contract_code(address))
+    }
+
     pub async fn allowance(
         &self,
         token_address: Address,
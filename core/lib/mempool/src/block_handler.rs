@@ -64,7 +64,7 @@ impl MempoolBlocksHandler {
             .get_transaction_queue(executed_txs)
             .await?;
 
-        let (txs, priority_ops, chunks_left) = tx_queue
+        let (txs, priority_ops, mut chunks_left) = tx_queue
             .select_transactions(
                 self.max_block_size_chunks,
                 current_unprocessed_priority_op,
@@ -72,6 +72,10 @@ impl MempoolBlocksHandler {
                 &self.mempool_state,
             )
             .await?;
+        // The selected transactions may be replaced after they were loaded,
+        // the replaced ones are not proposed.
+        let (txs, freed_chunks) = self.mempool_state.mark_txs_picked(txs).await?;
+        chunks_left += freed_chunks;
 
         if !priority_ops.is_empty() || !txs.is_empty() {
             vlog::debug!(
@@ -271,6 +271,8 @@ mod tests {
                 eth_hash: Default::default(),
                 eth_block: 0,
                 eth_block_index: None,
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 1,
@@ -284,6 +286,8 @@ mod tests {
                 eth_hash: Default::default(),
                 eth_block: 0,
                 eth_block_index: None,
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 2,
@@ -297,6 +301,8 @@ mod tests {
                 eth_hash: Default::default(),
                 eth_block: 0,
                 eth_block_index: None,
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ]);
         transactions_queue.add_l1_transactions(vec![
@@ -312,6 +318,8 @@ mod tests {
                 eth_hash: Default::default(),
                 eth_block: 0,
                 eth_block_index: None,
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 6,
@@ -325,6 +333,8 @@ mod tests {
                 eth_hash: Default::default(),
                 eth_block: 0,
                 eth_block_index: None,
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             PriorityOp {
                 serial_id: 5,
@@ -338,6 +348,8 @@ mod tests {
                 eth_hash: Default::default(),
                 eth_block: 0,
                 eth_block_index: None,
+                eth_block_hash: None,
+                l1_surplus: None,
             },
         ]);
         let op = transactions_queue.pop_front_l1_transactions().unwrap();
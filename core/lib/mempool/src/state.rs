@@ -1,11 +1,11 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
 use zksync_storage::{ConnectionPool, StorageProcessor};
 use zksync_types::{
     mempool::{SignedTxVariant, SignedTxsBatch},
     tx::{error::TxAddError, TxHash},
-    Address, TransferOp, TransferToNewOp, ZkSyncTx,
+    Address, Nonce, TransferOp, TransferToNewOp, ZkSyncTx,
 };
 
 use crate::MempoolTransactionsQueue;
@@ -108,6 +108,87 @@ impl MempoolState {
             .collect_garbage()
             .await
             .expect("Db error");
+        // Transactions picked before the restart are proposed again.
+        storage
+            .chain()
+            .mempool_schema()
+            .reset_picked_txs()
+            .await
+            .expect("Db error");
+    }
+
+    /// Marks the proposed transactions as picked, so they can't be replaced anymore.
+    /// Returns the transactions that are still in the mempool along with the number of chunks
+    /// freed by the dropped ones. Transactions replaced after being loaded for the proposal are
+    /// dropped together with the following transactions of the same accounts, which would fail
+    /// with the nonce mismatch otherwise.
+    pub async fn mark_txs_picked(
+        &self,
+        txs: Vec<SignedTxVariant>,
+    ) -> Result<(Vec<SignedTxVariant>, usize), TxAddError> {
+        if txs.is_empty() {
+            return Ok((txs, 0));
+        }
+
+        let tx_hashes: Vec<TxHash> = txs.iter().flat_map(|tx| tx.hashes()).collect();
+        let mut storage = self
+            .db_pool
+            .access_storage()
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        let picked: HashSet<TxHash> = storage
+            .chain()
+            .mempool_schema()
+            .mark_txs_picked(&tx_hashes)
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .into_iter()
+            .collect();
+
+        // The lowest nonce of the dropped transactions of each account.
+        let mut nonce_gaps: HashMap<Address, Nonce> = HashMap::new();
+        let mut proposed = Vec::with_capacity(txs.len());
+        let mut dropped = Vec::new();
+        for tx in txs {
+            let still_queued = tx.hashes().iter().all(|tx_hash| picked.contains(tx_hash));
+            let after_gap = tx.get_transactions().iter().any(|tx| {
+                nonce_gaps
+                    .get(&tx.tx.account())
+                    .map_or(false, |nonce| tx.tx.nonce() > *nonce)
+            });
+            if still_queued && !after_gap {
+                proposed.push(tx);
+                continue;
+            }
+
+            for tx in tx.get_transactions() {
+                let nonce = nonce_gaps.entry(tx.tx.account()).or_insert(tx.tx.nonce());
+                *nonce = std::cmp::min(*nonce, tx.tx.nonce());
+            }
+            dropped.push(tx);
+        }
+        if dropped.is_empty() {
+            return Ok((proposed, 0));
+        }
+
+        let dropped_hashes: Vec<TxHash> = dropped.iter().flat_map(|tx| tx.hashes()).collect();
+        vlog::debug!(
+            "Transactions {:?} were replaced before being picked or follow the replaced ones",
+            dropped_hashes
+        );
+        storage
+            .chain()
+            .mempool_schema()
+            .unmark_txs_picked(&dropped_hashes)
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        drop(storage);
+
+        let mut freed_chunks = 0;
+        for tx in &dropped {
+            freed_chunks += self.required_chunks(tx).await?;
+        }
+        Ok((proposed, freed_chunks))
     }
 
     pub fn new(db_pool: ConnectionPool) -> Self {
@@ -4,7 +4,7 @@ use futures::StreamExt;
 use zksync_storage::ConnectionPool;
 use zksync_types::{
     mempool::SignedTxsBatch,
-    tx::{error::TxAddError, TxEthSignature},
+    tx::{error::TxAddError, TxEthSignature, TxHash},
     PriorityOp, SignedZkSyncTx,
 };
 
@@ -17,6 +17,14 @@ pub enum MempoolTransactionRequest {
     /// oneshot is used to receive tx add result.
     NewTx(Box<SignedZkSyncTx>, oneshot::Sender<Result<(), TxAddError>>),
 
+    /// Replace the queued transaction having the given hash with the new one.
+    /// Invariants for the new transaction are the same as in `NewTx` variant of this enum.
+    ReplaceTx(
+        Box<SignedZkSyncTx>,
+        TxHash,
+        oneshot::Sender<Result<(), TxAddError>>,
+    ),
+
     /// Add new priority ops, confirmed or not
     NewPriorityOps(
         Vec<PriorityOp>,
@@ -50,6 +58,30 @@ impl MempoolTransactionsHandler {
             TxAddError::DbError
         })?;
 
+        // The same transaction may be forwarded more than once (e.g. by the API mempool resync),
+        // so an already queued transaction is accepted without being stored again.
+        let already_queued = storage
+            .chain()
+            .mempool_schema()
+            .contains_tx(tx.hash())
+            .await
+            .map_err(|_| TxAddError::DbError)?;
+        if already_queued {
+            return Ok(());
+        }
+
+        // Transactions that repeatedly failed are not accepted until they are resurrected manually.
+        let dead_lettered = storage
+            .chain()
+            .mempool_schema()
+            .get_dead_lettered_tx(tx.hash())
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .is_some();
+        if dead_lettered {
+            return Err(TxAddError::AttemptsExhausted);
+        }
+
         let nonce = storage
             .chain()
             .account_schema()
@@ -83,6 +115,54 @@ impl MempoolTransactionsHandler {
         Ok(())
     }
 
+    async fn replace_tx(
+        &mut self,
+        tx: SignedZkSyncTx,
+        replaced_tx_hash: TxHash,
+    ) -> Result<(), TxAddError> {
+        let mut storage = self.db_pool.access_storage().await.map_err(|err| {
+            vlog::error!("Mempool storage access error: {}", err);
+            TxAddError::DbError
+        })?;
+
+        let nonce = storage
+            .chain()
+            .account_schema()
+            .estimate_nonce(tx.account_id().map_err(|_| TxAddError::Other)?)
+            .await
+            .map_err(|_| TxAddError::DbError)?
+            .unwrap_or_default();
+
+        if tx.nonce() < nonce {
+            return Err(TxAddError::NonceMismatch);
+        }
+
+        // The replaced transaction may get into a block after it was checked by the API,
+        // in this case the replacement is refused.
+        let replaced = storage
+            .chain()
+            .mempool_schema()
+            .replace_tx(replaced_tx_hash, &tx)
+            .await
+            .map_err(|err| {
+                vlog::error!("Mempool storage access error: {}", err);
+                TxAddError::DbError
+            })?;
+        if !replaced {
+            return Err(TxAddError::ReplacedTxNotQueued);
+        }
+
+        let labels = vec![
+            ("stage", "mempool".to_string()),
+            ("name", tx.tx.variance_name()),
+            ("token", tx.tx.token_id().to_string()),
+        ];
+        metrics::histogram!("process_tx", tx.elapsed(), &labels);
+        metrics::increment_counter!("mempool.replaced_txs");
+
+        Ok(())
+    }
+
     /// Add priority operations to the mempool. For a better UX, we save unconfirmed transactions
     /// to the database. And we will move them to the real queue when they are confirmed.
     async fn add_priority_ops(
@@ -201,6 +281,10 @@ impl MempoolTransactionsHandler {
                     let tx_add_result = self.add_tx(*tx).await;
                     resp.send(tx_add_result).unwrap_or_default();
                 }
+                MempoolTransactionRequest::ReplaceTx(tx, replaced_tx_hash, resp) => {
+                    let tx_add_result = self.replace_tx(*tx, replaced_tx_hash).await;
+                    resp.send(tx_add_result).unwrap_or_default();
+                }
                 MempoolTransactionRequest::NewTxsBatch(txs, eth_signatures, resp) => {
                     let tx_add_result = self.add_batch(txs, eth_signatures).await;
                     resp.send(tx_add_result).unwrap_or_default();
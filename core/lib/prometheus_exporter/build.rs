@@ -0,0 +1,30 @@
+//! Exposes the git hash of the build to the crate as the `ZKSYNC_GIT_HASH` environment variable.
+//! The value provided by the build environment (e.g. when building the docker images without the
+//! git repository) takes precedence over the one obtained from git.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=ZKSYNC_GIT_HASH");
+    // Path is relative to the crate root; the hash may be stale for the local builds of the new commits
+    // on the same branch, which is fine since the precise hash matters for the deployed builds only.
+    println!("cargo:rerun-if-changed=../../../.git/HEAD");
+
+    let git_hash = std::env::var("ZKSYNC_GIT_HASH").ok().or_else(|| {
+        let output = Command::new("git")
+            .args(&["rev-parse", "--short", "HEAD"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        String::from_utf8(output.stdout)
+            .ok()
+            .map(|hash| hash.trim().to_string())
+    });
+
+    println!(
+        "cargo:rustc-env=ZKSYNC_GIT_HASH={}",
+        git_hash.as_deref().unwrap_or("unknown")
+    );
+}
@@ -1,20 +1,30 @@
 //! This module handles metric export to the Prometheus server
 
 use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics_util::layers::Layer;
 use num::rational::Ratio;
 use num::{BigUint, ToPrimitive};
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::ops::Add;
 use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
-use tokio::time::sleep;
+use tokio::time::{interval, sleep};
 use zksync_storage::{ConnectionPool, QueryResult, StorageProcessor};
 use zksync_token_db_cache::TokenDBCache;
 use zksync_types::aggregated_operations::AggregatedActionType::*;
 use zksync_types::block::IncompleteBlock;
 use zksync_types::{ExecutedOperations, TokenId};
 
+pub use namespace::{exported_name, MetricKind, NamespaceLayer, METRICS_NAMESPACE};
+
+mod namespace;
+
 const QUERY_INTERVAL: Duration = Duration::from_secs(30);
+/// Interval of updating the process-level gauges.
+const PROCESS_METRICS_INTERVAL: Duration = Duration::from_secs(10);
+/// Git hash of the build, provided by the build script.
+const GIT_HASH: &str = env!("ZKSYNC_GIT_HASH");
 
 pub fn run_operation_counter(connection_pool: ConnectionPool) -> JoinHandle<()> {
     tokio::spawn(async move {
@@ -111,19 +121,40 @@ fn token_amount_to_usd(amount: BigUint, decimals: u8, usd_price: Ratio<BigUint>)
     Ratio::from(amount) / BigUint::from(10u32).pow(u32::from(decimals)) * usd_price
 }
 
-pub fn run_prometheus_exporter(port: u16) -> JoinHandle<()> {
-    let addr = ([0, 0, 0, 0], port);
+/// Installs the Prometheus recorder for the `metrics` facade and runs the HTTP server exposing the
+/// recorded metrics at `/metrics`. The metrics names are converted as described in [`NamespaceLayer`].
+///
+/// Must be called as early as possible, since the metrics emitted before the recorder is installed are lost.
+/// The time of the call is considered the start of the process for the uptime gauge.
+///
+/// Scrapes are served from the in-memory registry only and never touch the storage: metrics requiring
+/// the database are collected in the background by [`run_operation_counter`].
+pub fn run_prometheus_exporter(bind_addr: SocketAddr) -> JoinHandle<()> {
+    let started_at = Instant::now();
     let (recorder, exporter) = PrometheusBuilder::new()
-        .listen_address(addr)
+        .listen_address(bind_addr)
         .build_with_exporter()
         .expect("failed to install Prometheus recorder");
+    let recorder = NamespaceLayer.layer(recorder);
     metrics::set_boxed_recorder(Box::new(recorder)).expect("failed to set metrics recorder");
 
+    metrics::gauge!(
+        "build_info",
+        1.0,
+        "version" => env!("CARGO_PKG_VERSION"),
+        "git_hash" => GIT_HASH
+    );
+    metrics::gauge!("process.uptime_seconds", started_at.elapsed().as_secs_f64());
+
     tokio::spawn(async move {
         tokio::pin!(exporter);
+        let mut process_metrics_timer = interval(PROCESS_METRICS_INTERVAL);
         loop {
             tokio::select! {
                 _ = &mut exporter => {}
+                _ = process_metrics_timer.tick() => {
+                    metrics::gauge!("process.uptime_seconds", started_at.elapsed().as_secs_f64());
+                }
             }
         }
     })
@@ -145,6 +176,11 @@ mod tests {
     };
     use zksync_utils::big_decimal_to_ratio;
 
+    use crate::{exported_name, run_prometheus_exporter, MetricKind};
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpListener, TcpStream};
+    use std::time::Duration;
+
     #[test]
     fn calculate_volume() {
         let usdc_price = big_decimal_to_ratio(&BigDecimal::from_f64(1.032).unwrap()).unwrap();
@@ -267,4 +303,63 @@ mod tests {
         let volume = res.get(&TokenId(33)).unwrap().clone();
         assert_eq!(volume, BigUint::from(20u64));
     }
+
+    #[test]
+    fn metric_names() {
+        assert_eq!(
+            exported_name(MetricKind::Histogram, "state.mint_nft"),
+            "zksync_state_mint_nft_seconds"
+        );
+        assert_eq!(
+            exported_name(MetricKind::Histogram, "tree_cache_size"),
+            "zksync_tree_cache_size"
+        );
+        assert_eq!(
+            exported_name(MetricKind::Counter, "rejected_txs"),
+            "zksync_rejected_txs_total"
+        );
+        assert_eq!(
+            exported_name(MetricKind::Gauge, "mempool_size"),
+            "zksync_mempool_size"
+        );
+    }
+
+    /// Requests the metrics from the exporter, waiting for it to start listening.
+    async fn scrape(addr: SocketAddr) -> String {
+        for _ in 0..50 {
+            if let Ok(mut stream) = TcpStream::connect(addr) {
+                let request = format!(
+                    "GET /metrics HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+                    addr
+                );
+                stream.write_all(request.as_bytes()).unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                return response;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("Prometheus exporter is not listening");
+    }
+
+    /// Checks that the metrics emitted through the facade are exposed with the exported names.
+    /// Note that the recorder is global, so it must be the only test installing the exporter.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn exporter_exposes_metrics() {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let _exporter = run_prometheus_exporter(addr);
+
+        metrics::histogram!("state.mint_nft", Duration::from_millis(10));
+        metrics::increment_counter!("rejected_txs", "stage" => "api");
+
+        let response = scrape(addr).await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.contains("zksync_state_mint_nft_seconds"));
+        assert!(response.contains("zksync_rejected_txs_total{stage=\"api\"} 1"));
+        assert!(response.contains("zksync_build_info{"));
+        assert!(response.contains("zksync_process_uptime_seconds"));
+    }
 }
@@ -0,0 +1,94 @@
+//! Consistent naming of the exported metrics.
+//!
+//! The metrics are emitted across the codebase with the short dot-separated names (e.g. `state.mint_nft`).
+//! Before reaching the Prometheus recorder, the names are converted to the Prometheus conventions:
+//! they get the `zksync_` namespace, the dots are replaced with underscores, counters get the `_total`
+//! suffix and histograms measuring durations get the `_seconds` suffix (e.g. `zksync_state_mint_nft_seconds`).
+
+use metrics::{GaugeValue, Key, Recorder, Unit};
+use metrics_util::layers::Layer;
+
+/// Namespace of all the exported metrics.
+pub const METRICS_NAMESPACE: &str = "zksync";
+
+/// Histograms which record values other than durations.
+const UNITLESS_HISTOGRAMS: &[&str] = &[
+    "committer.priority_ops_per_block",
+    "forced_exit_requests.address_space_overflow",
+    "miniblock_size",
+    "tree_cache_size",
+    "tree_memory_usage",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+/// Returns the name under which the metric is exported.
+pub fn exported_name(kind: MetricKind, name: &str) -> String {
+    let suffix = match kind {
+        MetricKind::Counter => "_total",
+        MetricKind::Gauge => "",
+        MetricKind::Histogram if UNITLESS_HISTOGRAMS.contains(&name) => "",
+        MetricKind::Histogram => "_seconds",
+    };
+    format!("{}_{}{}", METRICS_NAMESPACE, name.replace('.', "_"), suffix)
+}
+
+fn exported_key(kind: MetricKind, key: &Key) -> Key {
+    let name = exported_name(kind, key.name());
+    Key::from_parts(name, key.labels().cloned().collect::<Vec<_>>())
+}
+
+/// Layer renaming the metrics according to the conventions described in the module docs.
+#[derive(Debug, Default)]
+pub struct NamespaceLayer;
+
+impl<R> Layer<R> for NamespaceLayer {
+    type Output = Namespaced<R>;
+
+    fn layer(&self, inner: R) -> Self::Output {
+        Namespaced { inner }
+    }
+}
+
+/// Recorder renaming the metrics before passing them to the wrapped recorder.
+#[derive(Debug)]
+pub struct Namespaced<R> {
+    inner: R,
+}
+
+impl<R: Recorder> Recorder for Namespaced<R> {
+    fn register_counter(&self, key: &Key, unit: Option<Unit>, description: Option<&'static str>) {
+        let key = exported_key(MetricKind::Counter, key);
+        self.inner.register_counter(&key, unit, description)
+    }
+
+    fn register_gauge(&self, key: &Key, unit: Option<Unit>, description: Option<&'static str>) {
+        let key = exported_key(MetricKind::Gauge, key);
+        self.inner.register_gauge(&key, unit, description)
+    }
+
+    fn register_histogram(&self, key: &Key, unit: Option<Unit>, description: Option<&'static str>) {
+        let key = exported_key(MetricKind::Histogram, key);
+        self.inner.register_histogram(&key, unit, description)
+    }
+
+    fn increment_counter(&self, key: &Key, value: u64) {
+        let key = exported_key(MetricKind::Counter, key);
+        self.inner.increment_counter(&key, value)
+    }
+
+    fn update_gauge(&self, key: &Key, value: GaugeValue) {
+        let key = exported_key(MetricKind::Gauge, key);
+        self.inner.update_gauge(&key, value)
+    }
+
+    fn record_histogram(&self, key: &Key, value: f64) {
+        let key = exported_key(MetricKind::Histogram, key);
+        self.inner.record_histogram(&key, value)
+    }
+}
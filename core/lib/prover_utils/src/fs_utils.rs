@@ -9,9 +9,14 @@ use zksync_crypto::proof::PrecomputedSampleProofs;
 use zksync_crypto::Engine;
 
 pub fn get_keys_root_dir() -> PathBuf {
+    keys_root_dir(&std::env::var("CHAIN_CIRCUIT_KEY_DIR").expect("KEY_DIR not set"))
+}
+
+/// Returns the directory with the keys for the given `key_dir` (relative to `$ZKSYNC_HOME`).
+pub fn keys_root_dir(key_dir: &str) -> PathBuf {
     let mut out_dir = PathBuf::new();
     out_dir.push(&std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| "/".to_owned()));
-    out_dir.push(&std::env::var("CHAIN_CIRCUIT_KEY_DIR").expect("KEY_DIR not set"));
+    out_dir.push(key_dir);
     out_dir.push(&format!(
         "account-{}_balance-{}",
         account_tree_depth(),
@@ -38,6 +43,15 @@ fn get_universal_setup_monomial_file_name(power_of_two: u32) -> Result<String, a
     Ok(format!("setup_2^{}.key", power_of_two))
 }
 
+/// Returns the path to the universal setup in the monomial form of the given power of two.
+/// Doesn't check if file exists.
+pub fn get_universal_setup_monomial_file_path(power_of_two: u32) -> Result<PathBuf, anyhow::Error> {
+    let setup_file_name = get_universal_setup_monomial_file_name(power_of_two)?;
+    let mut path = base_universal_setup_dir()?;
+    path.push(&setup_file_name);
+    Ok(path)
+}
+
 pub fn save_universal_setup_monomial_file<R: Read>(
     power_of_two: u32,
     mut reader: R,
@@ -103,6 +117,14 @@ pub fn get_block_verification_key_path(block_chunks: usize) -> PathBuf {
     key
 }
 
+/// Same as `get_block_verification_key_path`, but takes the key directory explicitly
+/// instead of reading it from the environment.
+pub fn block_verification_key_path(key_dir: &str, block_chunks: usize) -> PathBuf {
+    let mut key = keys_root_dir(key_dir);
+    key.push(&format!("verification_block_{}.key", block_chunks));
+    key
+}
+
 pub fn get_verifier_contract_key_path() -> PathBuf {
     let mut contract = get_keys_root_dir();
     contract.push("KeysWithPlonkVerifier.sol");
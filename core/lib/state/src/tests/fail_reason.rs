@@ -0,0 +1,92 @@
+//! Fail reasons of the rejected transactions are reported by the API along with their codes,
+//! so the messages of the state errors have to be recognized by `FailReasonCode`.
+
+use crate::error::{OpError, TxBatchError};
+use crate::handler::error::*;
+use zksync_api_types::v02::transaction::FailReasonCode;
+
+#[test]
+fn fail_reason_codes() {
+    let errors: Vec<(OpError, FailReasonCode)> = vec![
+        (
+            TransferOpError::InsufficientBalance.into(),
+            FailReasonCode::InsufficientBalance,
+        ),
+        (
+            WithdrawNFTOpError::InsufficientNFTBalance.into(),
+            FailReasonCode::InsufficientBalance,
+        ),
+        (
+            ForcedExitOpError::InitiatorInsufficientBalance.into(),
+            FailReasonCode::InsufficientBalance,
+        ),
+        (
+            SwapOpError::InsufficientBalance.into(),
+            FailReasonCode::InsufficientBalance,
+        ),
+        (
+            ChangePubKeyOpError::NonceMismatch.into(),
+            FailReasonCode::NonceMismatch,
+        ),
+        (
+            MintNFTOpError::NonceMismatch.into(),
+            FailReasonCode::NonceMismatch,
+        ),
+        (
+            CloseOpError::NonceMismatch.into(),
+            FailReasonCode::NonceMismatch,
+        ),
+        (
+            WithdrawOpError::InvalidSignature.into(),
+            FailReasonCode::InvalidSignature,
+        ),
+        (
+            ChangePubKeyOpError::InvalidAuthData.into(),
+            FailReasonCode::InvalidSignature,
+        ),
+        (
+            ChangePubKeyOpError::InvalidZksyncSignature.into(),
+            FailReasonCode::InvalidSignature,
+        ),
+        (
+            SwapOpError::OrderInvalidSignature.into(),
+            FailReasonCode::InvalidSignature,
+        ),
+        (
+            ForcedExitOpError::InvalidSignature.into(),
+            FailReasonCode::InvalidSignature,
+        ),
+        (OpError::TimestampError, FailReasonCode::ExpiredTimeRange),
+        (
+            TransferOpError::FromAccountLocked.into(),
+            FailReasonCode::Other,
+        ),
+        (
+            WithdrawOpError::InvalidTokenId.into(),
+            FailReasonCode::Other,
+        ),
+    ];
+
+    for (error, code) in errors {
+        let fail_reason = error.to_string();
+        assert_eq!(
+            FailReasonCode::from_fail_reason(&fail_reason),
+            code,
+            "{}",
+            fail_reason
+        );
+
+        // Failed transactions of the batch are rejected with the reason of the failed one.
+        let fail_reason = TxBatchError {
+            failed_tx_index: 1,
+            reason: error,
+        }
+        .to_string();
+        assert_eq!(
+            FailReasonCode::from_fail_reason(&fail_reason),
+            code,
+            "{}",
+            fail_reason
+        );
+    }
+}
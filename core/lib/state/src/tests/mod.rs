@@ -1,4 +1,5 @@
 mod collect_fee;
+mod fail_reason;
 mod operations;
 mod timestamp;
 
@@ -1,18 +1,19 @@
 use proc_macro::TokenStream;
 use quote::quote;
 
+/// Returns the argument if it has the expected name.
 #[allow(clippy::cmp_owned)] // False positive, `syn::Ident` cannot be dereferenced.
-fn is_arg_storage_processor(arg: Option<&syn::FnArg>) -> bool {
+fn typed_arg<'a>(arg: Option<&'a syn::FnArg>, name: &str) -> Option<&'a syn::PatType> {
     if let Some(syn::FnArg::Typed(arg)) = arg {
-        // For now, we just assume that people writing tests know what they're doing and if method has
-        // exactly one argument and it's named correctly, it is supposed to be the right one.
+        // For now, we just assume that people writing tests know what they're doing and if
+        // the argument is named correctly, it is supposed to be of the right type.
         if let syn::Pat::Ident(ident) = arg.pat.as_ref() {
-            if ident.ident.to_string() == "storage" {
-                return true;
+            if ident.ident.to_string() == name {
+                return Some(arg);
             }
         }
     }
-    false
+    None
 }
 
 fn parse_knobs(mut input: syn::ItemFn) -> Result<TokenStream, syn::Error> {
@@ -28,13 +29,31 @@ fn parse_knobs(mut input: syn::ItemFn) -> Result<TokenStream, syn::Error> {
 
     sig.asyncness = None;
 
-    if sig.inputs.len() != 1 || !is_arg_storage_processor(sig.inputs.first()) {
-        let msg = "the DB test function must take `mut storage: zksync_storage::StorageProcessor<'_>` as a single argument";
+    if sig.inputs.is_empty()
+        || sig.inputs.len() > 2
+        || typed_arg(sig.inputs.first(), "storage").is_none()
+    {
+        let msg = "the DB test function must take `mut storage: zksync_storage::StorageProcessor<'_>` as the first argument";
         return Err(syn::Error::new_spanned(&sig.inputs, msg));
     }
 
-    // Remove argument, as the test function must not have one.
-    sig.inputs.pop();
+    // The optional second argument is the seeded RNG for the test data.
+    // It's created from the environment, so the seed of a failed test can be pinned to reproduce it.
+    let rng_init = if sig.inputs.len() == 2 {
+        let rng_ty = match typed_arg(sig.inputs.last(), "rng") {
+            Some(arg) => &arg.ty,
+            None => {
+                let msg = "the second argument of the DB test function must be `mut rng: zksync_storage::test_data::TestRng`";
+                return Err(syn::Error::new_spanned(&sig.inputs, msg));
+            }
+        };
+        quote! { let mut rng = <#rng_ty>::from_env(); }
+    } else {
+        quote! {}
+    };
+
+    // Remove arguments, as the test function must not have any.
+    sig.inputs.clear();
 
     let rt = quote! { tokio::runtime::Builder::new_current_thread() };
 
@@ -55,6 +74,7 @@ fn parse_knobs(mut input: syn::ItemFn) -> Result<TokenStream, syn::Error> {
                     let mut connection = StorageProcessor::establish_connection().await.unwrap();
                     // `storage` is a transaction, which will be dropped, and thus not committed.
                     let mut storage = connection.start_transaction().await.unwrap();
+                    #rng_init
                     #body
                 })
         }
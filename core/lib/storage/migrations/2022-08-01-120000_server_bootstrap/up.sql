@@ -0,0 +1,9 @@
+-- Marker of the database initialized with the `--bootstrap` server command.
+-- Contains the genesis parameters, so the repeated bootstrap can check them against the requested configuration.
+CREATE TABLE server_bootstrap (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    genesis_root BYTEA NOT NULL,
+    fee_account_address BYTEA NOT NULL,
+    tokens JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
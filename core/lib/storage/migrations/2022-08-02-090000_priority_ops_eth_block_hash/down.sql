@@ -0,0 +1,2 @@
+ALTER TABLE executed_priority_operations DROP COLUMN eth_block_hash;
+ALTER TABLE mempool_priority_operations DROP COLUMN eth_block_hash;
@@ -0,0 +1,2 @@
+ALTER TABLE executed_priority_operations ADD COLUMN eth_block_hash BYTEA;
+ALTER TABLE mempool_priority_operations ADD COLUMN eth_block_hash BYTEA;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS expired_mempool_txs;
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS parked_reason;
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS parked_at;
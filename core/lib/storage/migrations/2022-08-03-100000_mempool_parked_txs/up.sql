@@ -0,0 +1,14 @@
+-- Transactions which no longer satisfy the submission requirements are parked:
+-- they stay in the mempool but are not proposed for execution.
+ALTER TABLE mempool_txs ADD COLUMN parked_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE mempool_txs ADD COLUMN parked_reason TEXT;
+
+-- Parked transactions removed from the mempool after the TTL expiration.
+CREATE TABLE expired_mempool_txs (
+    tx_hash TEXT PRIMARY KEY,
+    tx jsonb NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    eth_sign_data jsonb,
+    fail_reason TEXT NOT NULL,
+    expired_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,6 @@
+CREATE TABLE contract_upgrades (
+    version_id BIGINT PRIMARY KEY,
+    zksync_target_addr TEXT,
+    eth_block BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1,12 @@
+-- Failed execution attempts of L2 transactions.
+-- Once the number of attempts reaches the configured threshold, the transaction is dead-lettered:
+-- it is not accepted to the mempool anymore until it is resurrected manually.
+CREATE TABLE tx_execution_attempts (
+    tx_hash TEXT PRIMARY KEY,
+    tx jsonb NOT NULL,
+    eth_sign_data jsonb,
+    attempts INT NOT NULL,
+    last_fail_reason TEXT NOT NULL,
+    last_failed_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    dead_lettered_at TIMESTAMP WITH TIME ZONE
+);
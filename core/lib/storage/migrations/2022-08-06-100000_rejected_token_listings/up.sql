@@ -0,0 +1,13 @@
+-- Tokens added through the governance contract that were not registered because of the listing policy.
+-- Listings are `pending` until reviewed by the operator; `approved` listings are registered
+-- by the token handler and removed from the table, `rejected` ones are kept for the history.
+CREATE TABLE rejected_token_listings (
+    token_id INTEGER PRIMARY KEY,
+    address TEXT NOT NULL,
+    eth_block_number BIGINT NOT NULL,
+    eth_tx_hash bytea,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    reviewed_at TIMESTAMP WITH TIME ZONE
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS token_volume_stats_progress;
+DROP TABLE IF EXISTS token_volume_stats;
@@ -0,0 +1,17 @@
+-- Transfer and withdraw volume of the tokens aggregated per hour of the block timestamp.
+CREATE TABLE token_volume_stats (
+    token_id INTEGER NOT NULL,
+    hour TIMESTAMP WITH TIME ZONE NOT NULL,
+    transfer_volume NUMERIC NOT NULL,
+    withdraw_volume NUMERIC NOT NULL,
+    PRIMARY KEY (token_id, hour)
+);
+
+-- The last block aggregated into `token_volume_stats`.
+-- Blocks are aggregated strictly in order, so the volume of each block is accounted exactly once.
+CREATE TABLE token_volume_stats_progress (
+    -- enforce single record
+    id bool PRIMARY KEY NOT NULL DEFAULT true,
+    last_block BIGINT NOT NULL
+);
+INSERT INTO token_volume_stats_progress (last_block) VALUES (0);
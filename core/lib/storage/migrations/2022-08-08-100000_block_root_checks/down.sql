@@ -0,0 +1 @@
+DROP TABLE IF EXISTS block_root_checks;
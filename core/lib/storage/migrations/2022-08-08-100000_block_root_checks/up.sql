@@ -0,0 +1,8 @@
+-- Results of cross-checking the root hashes of the blocks committed on L1 against the locally stored ones.
+CREATE TABLE block_root_checks (
+    block_number BIGINT PRIMARY KEY,
+    local_root_hash bytea NOT NULL,
+    l1_root_hash bytea NOT NULL,
+    matches BOOLEAN NOT NULL,
+    checked_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,19 @@
+-- API keys issued to the customers of the hosted API. Only the key hashes are stored.
+CREATE TABLE api_keys (
+    id BIGSERIAL PRIMARY KEY,
+    key_hash bytea NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    can_read BOOLEAN NOT NULL,
+    can_submit BOOLEAN NOT NULL,
+    requests_per_minute INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+-- Number of requests made with the API key per day (UTC).
+CREATE TABLE api_key_usage (
+    key_id BIGINT NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    requests BIGINT NOT NULL,
+    PRIMARY KEY (key_id, day)
+);
@@ -0,0 +1 @@
+-- The backfilled indexes are kept, since they are valid for the previous version as well.
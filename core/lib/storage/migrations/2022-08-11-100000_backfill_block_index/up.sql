@@ -0,0 +1,31 @@
+-- Failed transactions used to be stored without an index in the block.
+-- They are placed after all the indexed operations of their block in the order of execution,
+-- the same way the indexes are assigned when a new block is sealed.
+-- The pending block is skipped, its indexes are assigned once it's sealed.
+WITH next_indexes AS (
+    SELECT block_number, MAX(block_index) + 1 AS next_index
+    FROM (
+        SELECT block_number, block_index FROM executed_transactions
+        UNION ALL
+        SELECT block_number, block_index FROM executed_priority_operations
+    ) indexes
+    GROUP BY block_number
+), missing AS (
+    SELECT
+        executed_transactions.tx_hash,
+        (
+            COALESCE(next_indexes.next_index, 0)
+            + ROW_NUMBER() OVER (
+                PARTITION BY executed_transactions.block_number
+                ORDER BY executed_transactions.sequence_number, executed_transactions.created_at, executed_transactions.tx_hash
+            ) - 1
+        )::INT AS block_index
+    FROM executed_transactions
+    LEFT JOIN next_indexes ON next_indexes.block_number = executed_transactions.block_number
+    WHERE executed_transactions.block_index IS NULL
+        AND executed_transactions.block_number NOT IN (SELECT number FROM pending_block)
+)
+UPDATE executed_transactions
+SET block_index = missing.block_index
+FROM missing
+WHERE executed_transactions.tx_hash = missing.tx_hash;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS fee_exemptions;
+DROP TABLE IF EXISTS maintenance_accounts;
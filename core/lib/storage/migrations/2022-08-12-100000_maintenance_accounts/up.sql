@@ -0,0 +1,16 @@
+-- Addresses of the protocol maintenance accounts that are not required to pay fees.
+-- Complements the list from the server config and is managed through the admin API.
+CREATE TABLE maintenance_accounts (
+    address bytea PRIMARY KEY,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Journal of the transactions accepted without paying the required fee.
+CREATE TABLE fee_exemptions (
+    id BIGSERIAL PRIMARY KEY,
+    tx_hash bytea NOT NULL UNIQUE,
+    address bytea NOT NULL,
+    tx_type TEXT NOT NULL,
+    required_fee NUMERIC NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
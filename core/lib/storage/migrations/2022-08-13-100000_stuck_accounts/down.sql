@@ -0,0 +1 @@
+DROP TABLE IF EXISTS stuck_accounts;
@@ -0,0 +1,11 @@
+-- Accounts whose lowest queued transaction is parked or keeps failing for too long while
+-- the transactions with the higher nonces are queued behind it.
+-- The table is refreshed periodically by the mempool analyzer.
+CREATE TABLE stuck_accounts (
+    account_id BIGINT PRIMARY KEY,
+    blocking_tx_hash TEXT NOT NULL,
+    blocking_nonce BIGINT NOT NULL,
+    reason TEXT NOT NULL,
+    blocked_since TIMESTAMP WITH TIME ZONE NOT NULL,
+    queued_txs BIGINT NOT NULL
+);
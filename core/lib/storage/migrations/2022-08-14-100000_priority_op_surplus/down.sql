@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS priority_op_surplus_daily;
+ALTER TABLE executed_priority_operations DROP COLUMN IF EXISTS l1_surplus;
+ALTER TABLE mempool_priority_operations DROP COLUMN IF EXISTS l1_surplus;
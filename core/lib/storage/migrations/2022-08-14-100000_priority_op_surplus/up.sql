@@ -0,0 +1,13 @@
+-- Part of the amount paid on L1 that was not credited on L2, NULL if not applicable or unknown.
+ALTER TABLE mempool_priority_operations ADD COLUMN l1_surplus NUMERIC;
+ALTER TABLE executed_priority_operations ADD COLUMN l1_surplus NUMERIC;
+
+-- Surplus of the executed priority operations summed up per token and day (UTC) for the reconciliation.
+-- Operations with the zero surplus are counted too.
+CREATE TABLE priority_op_surplus_daily (
+    day DATE NOT NULL,
+    token_id INTEGER NOT NULL,
+    surplus NUMERIC NOT NULL,
+    ops_count BIGINT NOT NULL,
+    PRIMARY KEY (day, token_id)
+);
@@ -0,0 +1,8 @@
+-- Payloads of the confirmed aggregated operations moved out of `aggregate_operations`.
+-- The `arguments` of the archived operation are replaced with the JSON `null`, the metadata is kept.
+-- `arguments` here is the zlib-compressed JSON, NULL if the payload was truncated without archival.
+CREATE TABLE aggregate_operations_archive (
+    op_id BIGINT PRIMARY KEY REFERENCES aggregate_operations (id) ON DELETE CASCADE,
+    arguments BYTEA,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
@@ -0,0 +1 @@
+ALTER TABLE mempool_txs DROP COLUMN IF EXISTS deferred_until;
@@ -0,0 +1,3 @@
+-- Transactions submitted with `valid_from` in the future are deferred:
+-- they stay in the mempool but are not proposed for execution until they become valid.
+ALTER TABLE mempool_txs ADD COLUMN deferred_until TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS mempool_txs_account_nonce_idx;
+DROP FUNCTION IF EXISTS mempool_tx_account(JSONB);
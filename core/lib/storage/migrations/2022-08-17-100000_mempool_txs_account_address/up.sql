@@ -0,0 +1,18 @@
+-- Address of the account that initiated the transaction, the same as `ZkSyncTx::account` returns.
+-- The queued transactions of the account are found by the index built on it, so the whole mempool
+-- doesn't have to be parsed.
+CREATE OR REPLACE FUNCTION mempool_tx_account(tx JSONB) RETURNS BYTEA AS $$
+    SELECT decode(
+        substring(
+            COALESCE(
+                tx->>'from', tx->>'account', tx->>'target', tx->>'submitterAddress', tx->>'creatorAddress'
+            ) FROM 3
+        ),
+        'hex'
+    )
+$$ LANGUAGE SQL IMMUTABLE;
+
+-- Parked transactions are not listed as queued ones, so they are not indexed.
+CREATE INDEX IF NOT EXISTS mempool_txs_account_nonce_idx
+    ON mempool_txs (mempool_tx_account(tx), ((tx->>'nonce')::BIGINT), created_at, tx_hash)
+    WHERE parked_at IS NULL;
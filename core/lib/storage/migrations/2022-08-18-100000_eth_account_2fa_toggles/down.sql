@@ -0,0 +1 @@
+DROP TABLE IF EXISTS eth_account_2fa_toggles;
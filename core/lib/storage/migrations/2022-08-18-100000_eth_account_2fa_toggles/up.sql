@@ -0,0 +1,7 @@
+-- Timestamp of the last accepted request changing the 2FA state of the account.
+-- Requests disabling 2FA must be newer than it, so that an old signed request can't be replayed.
+CREATE TABLE eth_account_2fa_toggles
+(
+    account_id BIGINT PRIMARY KEY,
+    toggled_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
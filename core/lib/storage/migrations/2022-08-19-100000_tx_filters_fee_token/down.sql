@@ -0,0 +1 @@
+ALTER TABLE tx_filters DROP COLUMN IF EXISTS is_fee_token;
@@ -0,0 +1,19 @@
+-- Marks the tokens which are used by the transaction only to pay the fee.
+ALTER TABLE tx_filters ADD COLUMN is_fee_token BOOLEAN NOT NULL DEFAULT false;
+
+UPDATE tx_filters SET is_fee_token = true
+FROM executed_transactions
+WHERE tx_filters.tx_hash = executed_transactions.tx_hash
+    AND tx_filters.token = (executed_transactions.tx->>'feeToken')::integer
+    AND (
+        executed_transactions.tx->>'type' IN ('ChangePubKey', 'MintNFT')
+        OR (
+            executed_transactions.tx->>'type' = 'WithdrawNFT'
+            AND tx_filters.token <> (executed_transactions.tx->>'token')::integer
+        )
+        OR (
+            executed_transactions.tx->>'type' = 'Swap'
+            AND tx_filters.token <> (executed_transactions.tx->'orders'->0->>'tokenBuy')::integer
+            AND tx_filters.token <> (executed_transactions.tx->'orders'->0->>'tokenSell')::integer
+        )
+    );
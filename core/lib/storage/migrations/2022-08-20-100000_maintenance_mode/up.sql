@@ -0,0 +1,7 @@
+-- Maintenance mode of the network, the API servers reject new transactions while it's enabled.
+CREATE TABLE maintenance_mode (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    enabled BOOLEAN NOT NULL,
+    reason TEXT,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS subsidy_campaign_charges;
+DROP TABLE IF EXISTS subsidy_campaigns;
@@ -0,0 +1,26 @@
+-- Campaigns subsidizing the fees of the partners' transactions with the given fee types.
+-- USD amounts are stored scaled by 10^6, same as in the `subsidies` table.
+CREATE TABLE subsidy_campaigns (
+    id BIGSERIAL PRIMARY KEY,
+    partner VARCHAR NOT NULL,
+    -- JSON array of the subsidized `OutputFeeType`s.
+    fee_types JSONB NOT NULL,
+    budget_usd_scale6 BIGINT NOT NULL,
+    spent_usd_scale6 BIGINT NOT NULL DEFAULT 0,
+    -- Subsidies of the accepted transactions which are not executed yet.
+    reserved_usd_scale6 BIGINT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+-- Subsidies reserved in the campaign budget when the transactions are accepted, which are charged
+-- to the budget once the transaction is executed. Batches are charged by the hash of their first
+-- transaction.
+CREATE TABLE subsidy_campaign_charges (
+    tx_hash BYTEA NOT NULL,
+    campaign_id BIGINT NOT NULL REFERENCES subsidy_campaigns (id) ON DELETE CASCADE,
+    usd_amount_scale6 BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    PRIMARY KEY (tx_hash, campaign_id)
+);
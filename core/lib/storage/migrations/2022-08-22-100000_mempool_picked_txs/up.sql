@@ -0,0 +1,3 @@
+-- Transactions proposed to the state keeper are kept in the mempool until the pending block is stored,
+-- the marker prevents their replacement in the meantime.
+ALTER TABLE mempool_txs ADD COLUMN picked BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,7 @@
+-- Queued transactions are looked up by the initiator account id and the nonce, which are stored
+-- under the different keys depending on the transaction type. The expressions have to match the ones
+-- of the queries, so the index is used instead of parsing the whole mempool.
+CREATE INDEX IF NOT EXISTS mempool_txs_account_id_nonce_idx ON mempool_txs (
+    (COALESCE(tx->>'accountId', tx->>'initiatorAccountId', tx->>'creatorId', tx->>'submitterId')),
+    ((tx->>'nonce')::bigint)
+) WHERE reverted = false;
@@ -0,0 +1,160 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::NaiveDate;
+// Workspace imports
+use zksync_api_types::{ApiKeyInfo, ApiKeyUsage, NewApiKey};
+use zksync_types::{api_keys::ApiKeyScope, H256};
+// Local imports
+use self::records::{StorageApiKey, StorageApiKeyUsage};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// API keys schema is capable of storing the API keys issued to the customers of the hosted API
+/// and accounting their usage.
+#[derive(Debug)]
+pub struct ApiKeysSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> ApiKeysSchema<'a, 'c> {
+    /// Stores the new API key under the given hash.
+    pub async fn create_api_key(
+        &mut self,
+        key_hash: H256,
+        new_key: &NewApiKey,
+    ) -> QueryResult<ApiKeyInfo> {
+        let start = Instant::now();
+        let key = sqlx::query_as!(
+            StorageApiKey,
+            r#"
+            INSERT INTO api_keys (key_hash, name, can_read, can_submit, requests_per_minute, created_at)
+            VALUES ($1, $2, $3, $4, $5, now())
+            RETURNING *
+            "#,
+            key_hash.as_bytes(),
+            new_key.name,
+            new_key.scopes.contains(&ApiKeyScope::Read),
+            new_key.scopes.contains(&ApiKeyScope::Submit),
+            new_key.requests_per_minute as i32
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_keys.create_api_key", start.elapsed());
+        Ok(key.into())
+    }
+
+    /// Revokes the API key. Returns `false` if there is no active key with such id.
+    pub async fn revoke_api_key(&mut self, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let revoked = sqlx::query!(
+            "UPDATE api_keys SET revoked_at = now() WHERE id = $1 AND revoked_at IS NULL",
+            id
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.api_keys.revoke_api_key", start.elapsed());
+        Ok(revoked)
+    }
+
+    /// Loads the API key by its id.
+    pub async fn load_api_key(&mut self, id: i64) -> QueryResult<Option<ApiKeyInfo>> {
+        let start = Instant::now();
+        let key = sqlx::query_as!(StorageApiKey, "SELECT * FROM api_keys WHERE id = $1", id)
+            .fetch_optional(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.api_keys.load_api_key", start.elapsed());
+        Ok(key.map(Into::into))
+    }
+
+    /// Loads the API key by its hash. Revoked keys are returned as well.
+    pub async fn load_api_key_by_hash(
+        &mut self,
+        key_hash: H256,
+    ) -> QueryResult<Option<ApiKeyInfo>> {
+        let start = Instant::now();
+        let key = sqlx::query_as!(
+            StorageApiKey,
+            "SELECT * FROM api_keys WHERE key_hash = $1",
+            key_hash.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_keys.load_api_key_by_hash", start.elapsed());
+        Ok(key.map(Into::into))
+    }
+
+    /// Loads all the issued API keys.
+    pub async fn load_api_keys(&mut self) -> QueryResult<Vec<ApiKeyInfo>> {
+        let start = Instant::now();
+        let keys = sqlx::query_as!(StorageApiKey, "SELECT * FROM api_keys ORDER BY id")
+            .fetch_all(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.api_keys.load_api_keys", start.elapsed());
+        Ok(keys.into_iter().map(Into::into).collect())
+    }
+
+    /// Accounts the requests made with the API keys during the day, given as `(key_id, requests)` pairs.
+    /// The usage of all the keys is updated with a single statement.
+    pub async fn record_api_key_requests(
+        &mut self,
+        day: NaiveDate,
+        requests: &[(i64, u32)],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let (ids, counts): (Vec<i64>, Vec<i64>) = requests
+            .iter()
+            .map(|(id, count)| (*id, i64::from(*count)))
+            .unzip();
+        sqlx::query!(
+            r#"
+            INSERT INTO api_key_usage (key_id, day, requests)
+            SELECT u.key_id, $1, u.requests
+                FROM UNNEST ($2::bigint[], $3::bigint[])
+                AS u(key_id, requests)
+            ON CONFLICT (key_id, day)
+            DO UPDATE SET requests = api_key_usage.requests + EXCLUDED.requests
+            "#,
+            day,
+            &ids,
+            &counts
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_keys.record_api_key_requests", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the daily usage of the API key within the given range of days (inclusive).
+    pub async fn load_api_key_usage(
+        &mut self,
+        id: i64,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> QueryResult<Vec<ApiKeyUsage>> {
+        let start = Instant::now();
+        let usage = sqlx::query_as!(
+            StorageApiKeyUsage,
+            r#"
+            SELECT day, requests FROM api_key_usage
+            WHERE key_id = $1 AND day >= $2 AND day <= $3
+            ORDER BY day
+            "#,
+            id,
+            from,
+            to
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.api_keys.load_api_key_usage", start.elapsed());
+        Ok(usage.into_iter().map(Into::into).collect())
+    }
+}
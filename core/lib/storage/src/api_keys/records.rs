@@ -0,0 +1,53 @@
+// External imports
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::FromRow;
+// Workspace imports
+use zksync_api_types::{ApiKeyInfo, ApiKeyUsage};
+use zksync_types::api_keys::ApiKeyScope;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageApiKey {
+    pub id: i64,
+    pub key_hash: Vec<u8>,
+    pub name: String,
+    pub can_read: bool,
+    pub can_submit: bool,
+    pub requests_per_minute: i32,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<StorageApiKey> for ApiKeyInfo {
+    fn from(val: StorageApiKey) -> Self {
+        let scopes = [
+            (ApiKeyScope::Read, val.can_read),
+            (ApiKeyScope::Submit, val.can_submit),
+        ]
+        .iter()
+        .filter_map(|(scope, allowed)| if *allowed { Some(*scope) } else { None })
+        .collect();
+        Self {
+            id: val.id,
+            name: val.name,
+            scopes,
+            requests_per_minute: val.requests_per_minute as u32,
+            created_at: val.created_at,
+            revoked_at: val.revoked_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageApiKeyUsage {
+    pub day: NaiveDate,
+    pub requests: i64,
+}
+
+impl From<StorageApiKeyUsage> for ApiKeyUsage {
+    fn from(val: StorageApiKeyUsage) -> Self {
+        Self {
+            day: val.day,
+            requests: val.requests as u64,
+        }
+    }
+}
@@ -1,12 +1,14 @@
 // Built-in deps
 use std::time::Instant;
 // External imports
+use chrono::{DateTime, Utc};
 use num::{BigUint, Zero};
 use sqlx::{types::BigDecimal, Acquire};
 // Workspace imports
+use zksync_api_types::v02::pagination::{PaginationDirection, PaginationQuery};
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_STORAGE_ACCOUNT_ID, NFT_TOKEN_ID};
 use zksync_types::{
-    Account, AccountId, AccountUpdates, Address, BlockNumber, Nonce, PubKeyHash, TokenId,
+    Account, AccountId, AccountUpdates, Address, BlockNumber, Nonce, PubKeyHash, TokenId, NFT,
 };
 // Local imports
 use self::records::*;
@@ -23,6 +25,34 @@ pub use self::stored_state::StoredAccountState;
 use crate::tokens::records::StorageNFT;
 use num::bigint::ToBigInt;
 
+/// Selects the NFTs owned by the account `$1` in the committed state.
+/// `$2` and `$3` are the bounds of the NFT token ids.
+///
+/// The committed balance is the one set by the latest balance update, or the verified one
+/// if the balance wasn't updated since the verified state was stored.
+const ACCOUNT_NFTS_QUERY: &str = "
+    WITH balance_updates AS (
+        SELECT DISTINCT ON (coin_id) coin_id, new_balance AS balance
+        FROM account_balance_updates
+        WHERE account_id = $1 AND coin_id >= $2 AND coin_id < $3
+        ORDER BY coin_id, block_number DESC, update_order_id DESC
+    ), committed_balances AS (
+        SELECT coin_id, balance FROM balance_updates
+        UNION ALL
+        SELECT coin_id, balance FROM balances
+        WHERE account_id = $1 AND coin_id >= $2 AND coin_id < $3
+            AND coin_id NOT IN (SELECT coin_id FROM balance_updates)
+    ), nfts AS (
+        SELECT mint_nft_updates.token_id, mint_nft_updates.serial_id,
+            mint_nft_updates.creator_account_id, mint_nft_updates.creator_address,
+            mint_nft_updates.address, mint_nft_updates.content_hash, mint_nft_updates.symbol
+        FROM committed_balances
+        INNER JOIN mint_nft_updates
+        ON mint_nft_updates.token_id = committed_balances.coin_id
+        WHERE committed_balances.balance > 0
+    )
+";
+
 /// Account schema contains interfaces to interact with the stored
 /// ZKSync accounts.
 #[derive(Debug)]
@@ -80,6 +110,48 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Stores account type requested by the account owner at the given time.
+    /// Requests disabling 2FA are applied only if they are newer than the last accepted one, so that
+    /// they can't be replayed. Enabling 2FA is always applied.
+    /// Returns `false` if the request was rejected.
+    pub async fn toggle_account_type(
+        &mut self,
+        account_id: AccountId,
+        account_type: EthAccountType,
+        toggled_at: DateTime<Utc>,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+
+        let mut transaction = self.0.start_transaction().await?;
+
+        let disables_2fa = !matches!(account_type, EthAccountType::Owned);
+        let accepted = sqlx::query!(
+            r#"
+            INSERT INTO eth_account_2fa_toggles VALUES ( $1, $2 )
+            ON CONFLICT (account_id) DO UPDATE
+            SET toggled_at = GREATEST(eth_account_2fa_toggles.toggled_at, $2)
+            WHERE NOT $3 OR eth_account_2fa_toggles.toggled_at < $2
+            "#,
+            i64::from(*account_id),
+            toggled_at,
+            disables_2fa
+        )
+        .execute(transaction.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        if accepted {
+            AccountSchema(&mut transaction)
+                .set_account_type(account_id, account_type)
+                .await?;
+            transaction.commit().await?;
+        }
+
+        metrics::histogram!("sql.chain.account.toggle_account_type", start.elapsed());
+        Ok(accepted)
+    }
+
     /// Gets currently committed to the database nonce, if not exist return verified.
     /// After reverting blocks this nonce could be less than actual.
     /// Use this function only for verifying the lower bounds of a nonce.
@@ -575,6 +647,66 @@ impl<'a, 'c> AccountSchema<'a, 'c> {
         Ok(balance)
     }
 
+    /// Loads the page of the NFTs owned by the account in the committed state, ordered by the token id.
+    pub async fn load_account_nft_page(
+        &mut self,
+        account_id: AccountId,
+        query: &PaginationQuery<TokenId>,
+    ) -> QueryResult<Vec<NFT>> {
+        let start = Instant::now();
+        let query_str = match query.direction {
+            PaginationDirection::Newer => format!(
+                "{} SELECT * FROM nfts WHERE token_id >= $4 ORDER BY token_id LIMIT $5",
+                ACCOUNT_NFTS_QUERY
+            ),
+            PaginationDirection::Older => format!(
+                "{} SELECT * FROM nfts WHERE token_id <= $4 ORDER BY token_id DESC LIMIT $5",
+                ACCOUNT_NFTS_QUERY
+            ),
+        };
+        let nfts: Vec<StorageNFT> = sqlx::query_as(query_str.as_str())
+            .bind(i64::from(account_id.0))
+            .bind(MIN_NFT_TOKEN_ID as i32)
+            .bind(NFT_TOKEN_ID.0 as i32)
+            .bind(query.from.0 as i32)
+            .bind(i64::from(query.limit))
+            .fetch_all(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.chain.account.load_account_nft_page", start.elapsed());
+        Ok(nfts.into_iter().map(Into::into).collect())
+    }
+
+    /// Returns the number of the NFTs owned by the account in the committed state
+    /// along with the greatest of their ids.
+    pub async fn get_account_nfts_summary(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<(u32, Option<TokenId>)> {
+        let start = Instant::now();
+        let query_str = format!(
+            "{} SELECT COUNT(*) AS count, MAX(token_id) AS last_token_id FROM nfts",
+            ACCOUNT_NFTS_QUERY
+        );
+        let summary: StorageAccountNFTsSummary = sqlx::query_as(query_str.as_str())
+            .bind(i64::from(account_id.0))
+            .bind(MIN_NFT_TOKEN_ID as i32)
+            .bind(NFT_TOKEN_ID.0 as i32)
+            .fetch_one(self.0.conn())
+            .await?;
+
+        metrics::histogram!(
+            "sql.chain.account.get_account_nfts_summary",
+            start.elapsed()
+        );
+        Ok((
+            summary.count as u32,
+            summary
+                .last_token_id
+                .map(|token_id| TokenId(token_id as u32)),
+        ))
+    }
+
     pub async fn get_nft_owner(&mut self, token_id: TokenId) -> QueryResult<Option<AccountId>> {
         let start = Instant::now();
 
@@ -85,6 +85,13 @@ pub(crate) struct StorageBalance {
     pub balance: BigDecimal,
 }
 
+/// Number of the NFTs owned by the account and the greatest of their ids.
+#[derive(Debug, FromRow)]
+pub(crate) struct StorageAccountNFTsSummary {
+    pub count: i64,
+    pub last_token_id: Option<i32>,
+}
+
 #[derive(Debug, Clone, Copy, sqlx::Type)]
 #[sqlx(type_name = "eth_account_type")]
 pub(crate) enum DbAccountType {
@@ -17,7 +17,8 @@ use zksync_types::{
 };
 // Local imports
 use crate::chain::operations::records::StoredAggregatedOperation;
-use crate::utils::affected_accounts;
+use crate::chain::operations_ext::conversion::fail_reason_code;
+use crate::utils::{affected_accounts, l1_surplus_to_stored, stored_l1_surplus};
 use crate::{
     chain::{
         block::records::TransactionItem,
@@ -69,6 +70,8 @@ impl StoredExecutedPriorityOperation {
                 eth_hash: H256::from_slice(&self.eth_hash),
                 eth_block: self.eth_block as u64,
                 eth_block_index: self.eth_block_index.map(|index| index as u64),
+                eth_block_hash: self.eth_block_hash.map(|hash| H256::from_slice(&hash)),
+                l1_surplus: stored_l1_surplus(self.l1_surplus),
             },
             op: franklin_op,
             block_index: self.block_index as u32,
@@ -122,6 +125,11 @@ impl NewExecutedPriorityOperation {
                 .eth_block_index
                 .map(|index| index as i64),
             tx_hash,
+            eth_block_hash: exec_prior_op
+                .priority_op
+                .eth_block_hash
+                .map(|hash| hash.as_bytes().to_vec()),
+            l1_surplus: l1_surplus_to_stored(&exec_prior_op.priority_op.l1_surplus),
             affected_accounts,
             token,
         }
@@ -195,6 +203,7 @@ impl NewExecutedTransaction {
             .into_iter()
             .map(|id| id.0 as i32)
             .collect();
+        let fee_only_token = exec_tx.signed_tx.tx.fee_only_token().map(|id| id.0 as i32);
         Ok(Self {
             block_number: i64::from(*block),
             tx_hash: exec_tx.signed_tx.hash().as_ref().to_vec(),
@@ -212,6 +221,7 @@ impl NewExecutedTransaction {
             batch_id: exec_tx.batch_id,
             affected_accounts,
             used_tokens,
+            fee_only_token,
         })
     }
 }
@@ -259,6 +269,7 @@ impl TransactionItem {
             block_number,
             op,
             status,
+            fail_reason_code: fail_reason_code(item.fail_reason.as_deref()),
             fail_reason: item.fail_reason,
             created_at: Some(item.created_at),
             batch_id: item.batch_id.map(|id| id as u32),
@@ -1,11 +1,14 @@
 // Built-in deps
+use std::convert::TryFrom;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 // External imports
 // Workspace imports
 use zksync_api_types::{
     v02::{
-        pagination::{BlockAndTxHash, PaginationDirection, PaginationQuery},
-        transaction::Transaction,
+        pagination::{
+            BlockAndTxHash, BlockAndTxIndex, BlockTimestamp, PaginationDirection, PaginationQuery,
+        },
+        transaction::{Transaction, TxType},
     },
     Either,
 };
@@ -19,7 +22,8 @@ use zksync_types::{
 // Local imports
 use self::records::{
     BlockTransactionItem, StorageBlock, StorageBlockDetails, StorageBlockMetadata,
-    StoragePendingBlock, StorageRootHash, TransactionItem,
+    StorageBlockRootCheck, StoragePendingBlock, StorageRootHash, StorageTimestampedBlockDetails,
+    TransactionItem,
 };
 use crate::{
     chain::operations::{
@@ -30,7 +34,7 @@ use crate::{
         OperationsSchema,
     },
     chain::{account::records::EthAccountType, block::records::StorageIncompleteBlock},
-    QueryResult, StorageProcessor,
+    QueryResult, StorageProcessor, MAX_BLOCK_INDEX,
 };
 
 pub(crate) mod conversion;
@@ -494,6 +498,169 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(details)
     }
 
+    /// Loads the block headers for the given pagination query ordered by the block timestamps,
+    /// the blocks with the same timestamp are ordered by their numbers. Every block is returned
+    /// along with its exact cursor. Blocks without the timestamp are skipped.
+    pub async fn load_block_page_by_timestamp(
+        &mut self,
+        query: &PaginationQuery<BlockTimestamp>,
+    ) -> QueryResult<Vec<(StorageBlockDetails, BlockTimestamp)>> {
+        let start = Instant::now();
+        let timestamp = i64::try_from(query.from.timestamp).unwrap_or(i64::MAX);
+        // The cursor without the block number points to the edge of the second
+        // the page starts from.
+        let block_number = match (query.from.block_number, query.direction) {
+            (Some(block_number), _) => i64::from(*block_number),
+            (None, PaginationDirection::Newer) => 0,
+            (None, PaginationDirection::Older) => i64::MAX,
+        };
+        // The query is the same as in `load_block_range_desc`, but the blocks are compared
+        // by the `(timestamp, number)` pairs. The pair with the NULL timestamp is never
+        // compared as true, so such blocks are filtered out.
+        let details = match query.direction {
+            PaginationDirection::Newer => {
+                sqlx::query_as!(
+                    StorageTimestampedBlockDetails,
+                    r#"
+            WITH aggr_comm AS (
+                SELECT 
+                    aggregate_operations.created_at, 
+                    eth_operations.final_hash, 
+                    commit_aggregated_blocks_binding.block_number 
+                FROM aggregate_operations
+                    INNER JOIN commit_aggregated_blocks_binding ON aggregate_operations.id = commit_aggregated_blocks_binding.op_id
+                    INNER JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                    INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                WHERE aggregate_operations.confirmed = true 
+            ),
+            aggr_exec as (
+                 SELECT 
+                    aggregate_operations.created_at, 
+                    eth_operations.final_hash, 
+                    execute_aggregated_blocks_binding.block_number 
+                FROM aggregate_operations
+                    INNER JOIN execute_aggregated_blocks_binding ON aggregate_operations.id = execute_aggregated_blocks_binding.op_id
+                    INNER JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                    INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                WHERE aggregate_operations.confirmed = true 
+            )
+            SELECT
+                blocks.number AS "block_number!",
+                blocks.root_hash AS "new_state_root!",
+                blocks.block_size AS "block_size!",
+                committed.final_hash AS "commit_tx_hash?",
+                verified.final_hash AS "verify_tx_hash?",
+                committed.created_at AS "committed_at!",
+                verified.created_at AS "verified_at?",
+                blocks.timestamp AS "timestamp!"
+            FROM blocks
+                     INNER JOIN aggr_comm committed ON blocks.number = committed.block_number
+                     LEFT JOIN aggr_exec verified ON blocks.number = verified.block_number
+            WHERE
+                (blocks.timestamp, blocks.number) >= ($1, $2)
+            ORDER BY blocks.timestamp ASC, blocks.number ASC
+            LIMIT $3;
+            "#,
+                    timestamp,
+                    block_number,
+                    i64::from(query.limit)
+                )
+                .fetch_all(self.0.conn())
+                .await?
+            }
+            PaginationDirection::Older => {
+                sqlx::query_as!(
+                    StorageTimestampedBlockDetails,
+                    r#"
+            WITH aggr_comm AS (
+                SELECT 
+                    aggregate_operations.created_at, 
+                    eth_operations.final_hash, 
+                    commit_aggregated_blocks_binding.block_number 
+                FROM aggregate_operations
+                    INNER JOIN commit_aggregated_blocks_binding ON aggregate_operations.id = commit_aggregated_blocks_binding.op_id
+                    INNER JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                    INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                WHERE aggregate_operations.confirmed = true 
+            ),
+            aggr_exec as (
+                 SELECT 
+                    aggregate_operations.created_at, 
+                    eth_operations.final_hash, 
+                    execute_aggregated_blocks_binding.block_number 
+                FROM aggregate_operations
+                    INNER JOIN execute_aggregated_blocks_binding ON aggregate_operations.id = execute_aggregated_blocks_binding.op_id
+                    INNER JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                    INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+                WHERE aggregate_operations.confirmed = true 
+            )
+            SELECT
+                blocks.number AS "block_number!",
+                blocks.root_hash AS "new_state_root!",
+                blocks.block_size AS "block_size!",
+                committed.final_hash AS "commit_tx_hash?",
+                verified.final_hash AS "verify_tx_hash?",
+                committed.created_at AS "committed_at!",
+                verified.created_at AS "verified_at?",
+                blocks.timestamp AS "timestamp!"
+            FROM blocks
+                     INNER JOIN aggr_comm committed ON blocks.number = committed.block_number
+                     LEFT JOIN aggr_exec verified ON blocks.number = verified.block_number
+            WHERE
+                (blocks.timestamp, blocks.number) <= ($1, $2)
+            ORDER BY blocks.timestamp DESC, blocks.number DESC
+            LIMIT $3;
+            "#,
+                    timestamp,
+                    block_number,
+                    i64::from(query.limit)
+                )
+                .fetch_all(self.0.conn())
+                .await?
+            }
+        };
+        let details = details
+            .into_iter()
+            .map(|details| {
+                let (details, timestamp) = details.into_parts();
+                let cursor = BlockTimestamp {
+                    timestamp: timestamp as u64,
+                    block_number: Some(BlockNumber(details.block_number as u32)),
+                };
+                (details, cursor)
+            })
+            .collect();
+
+        metrics::histogram!(
+            "sql.chain.block.load_block_page_by_timestamp",
+            start.elapsed()
+        );
+        Ok(details)
+    }
+
+    /// Returns the number of blocks `load_block_page_by_timestamp` can return,
+    /// i.e. the committed blocks with the timestamp.
+    pub async fn count_timestamped_blocks(&mut self) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM blocks
+                INNER JOIN commit_aggregated_blocks_binding ON blocks.number = commit_aggregated_blocks_binding.block_number
+                INNER JOIN aggregate_operations ON aggregate_operations.id = commit_aggregated_blocks_binding.op_id
+                INNER JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            WHERE aggregate_operations.confirmed = true AND blocks.timestamp IS NOT NULL
+            "#
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!("sql.chain.block.count_timestamped_blocks", start.elapsed());
+        Ok(count as u32)
+    }
+
     /// Helper method for `find_block_by_height_or_hash`. It checks whether
     /// provided string can be interpreted like a hash, and if so, returns the
     /// hexadecimal string without prefix.
@@ -933,6 +1100,17 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         .execute(transaction.conn())
         .await?;
 
+        // Account the block in the token volume stats.
+        transaction
+            .chain()
+            .stats_schema()
+            .accumulate_block_token_volume(
+                block.block_number,
+                Some(block.timestamp),
+                &block.block_transactions,
+            )
+            .await?;
+
         transaction.commit().await?;
 
         metrics::histogram!("sql.chain.block.save_block", start.elapsed());
@@ -989,6 +1167,36 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         ).execute(transaction.conn())
         .await?;
 
+        // Failed transactions are executed without an index, so they are placed after
+        // all the successful operations of the block in the order of their execution.
+        // This way every operation in the sealed block has a dense and deterministic index.
+        sqlx::query!(
+            "
+            UPDATE executed_transactions
+            SET block_index = missing.block_index
+            FROM (
+                SELECT
+                    tx_hash,
+                    (
+                        (
+                            SELECT COALESCE(MAX(block_index) + 1, 0)
+                            FROM (
+                                SELECT block_index FROM executed_transactions WHERE block_number = $1
+                                UNION ALL
+                                SELECT block_index FROM executed_priority_operations WHERE block_number = $1
+                            ) indexes
+                        ) + ROW_NUMBER() OVER (ORDER BY sequence_number, tx_hash) - 1
+                    )::INT AS block_index
+                FROM executed_transactions
+                WHERE block_number = $1 AND block_index IS NULL
+            ) missing
+            WHERE executed_transactions.tx_hash = missing.tx_hash
+            ",
+            new_block.number
+        )
+        .execute(transaction.conn())
+        .await?;
+
         transaction.commit().await?;
 
         metrics::histogram!("sql.chain.block.save_incomplete_block", start.elapsed());
@@ -1234,6 +1442,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
             .operations_ext_schema()
             .get_tx_sequence_number_for_block(tx_hash, query.from.block_number)
             .await?;
+        let tx_type = query.from.tx_type.map(|tx_type| tx_type.as_str());
         let block_txs = if let Some(sequence_number) = sequence_number {
             let raw_txs: Vec<TransactionItem> = match query.direction {
                 PaginationDirection::Newer => {
@@ -1255,6 +1464,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                                         batch_id
                                     FROM executed_transactions
                                     WHERE block_number = $1 AND sequence_number >= $2
+                                        AND ($4::text IS NULL OR tx->>'type' = $4)
                                 ), priority_ops AS (
                                     SELECT
                                         sequence_number,
@@ -1270,6 +1480,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                                         Null::bigint as batch_id
                                     FROM executed_priority_operations
                                     WHERE block_number = $1 AND sequence_number >= $2
+                                        AND ($4::text IS NULL OR operation->>'type' = $4)
                                 ), everything AS (
                                     SELECT * FROM transactions
                                     UNION ALL
@@ -1294,6 +1505,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                         i64::from(*query.from.block_number),
                         sequence_number,
                         i64::from(query.limit),
+                        tx_type,
                     )
                     .fetch_all(transaction.conn())
                     .await?
@@ -1317,6 +1529,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                                         batch_id
                                     FROM executed_transactions
                                     WHERE block_number = $1 AND sequence_number <= $2
+                                        AND ($4::text IS NULL OR tx->>'type' = $4)
                                 ), priority_ops AS (
                                     SELECT
                                         sequence_number,
@@ -1332,6 +1545,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                                         Null::bigint as batch_id
                                     FROM executed_priority_operations
                                     WHERE block_number = $1 AND sequence_number <= $2
+                                        AND ($4::text IS NULL OR operation->>'type' = $4)
                                 ), everything AS (
                                     SELECT * FROM transactions
                                     UNION ALL
@@ -1356,6 +1570,7 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
                         i64::from(*query.from.block_number),
                         sequence_number,
                         i64::from(query.limit),
+                        tx_type,
                     )
                     .fetch_all(transaction.conn())
                     .await?
@@ -1383,6 +1598,264 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok(block_txs)
     }
 
+    /// Retrieves both L1 and L2 operations stored in the block for the given pagination query,
+    /// using the index of the operation within the block as a cursor.
+    pub async fn get_block_transactions_page_by_index(
+        &mut self,
+        query: &PaginationQuery<BlockAndTxIndex>,
+    ) -> QueryResult<Vec<Transaction>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let block_index = match query.from.block_index.inner {
+            Either::Left(block_index) => block_index,
+            Either::Right(_) => MAX_BLOCK_INDEX,
+        } as i32;
+        let raw_txs: Vec<TransactionItem> = match query.direction {
+            PaginationDirection::Newer => {
+                sqlx::query_as!(
+                    TransactionItem,
+                    r#"
+                        WITH transactions AS (
+                            SELECT
+                                sequence_number,
+                                tx_hash,
+                                tx as op,
+                                block_number,
+                                created_at,
+                                success,
+                                fail_reason,
+                                Null::bytea as eth_hash,
+                                Null::bigint as priority_op_serialid,
+                                block_index,
+                                batch_id
+                            FROM executed_transactions
+                            WHERE block_number = $1 AND block_index >= $2
+                        ), priority_ops AS (
+                            SELECT
+                                sequence_number,
+                                tx_hash,
+                                operation as op,
+                                block_number,
+                                created_at,
+                                true as success,
+                                Null as fail_reason,
+                                eth_hash,
+                                priority_op_serialid,
+                                block_index,
+                                Null::bigint as batch_id
+                            FROM executed_priority_operations
+                            WHERE block_number = $1 AND block_index >= $2
+                        ), everything AS (
+                            SELECT * FROM transactions
+                            UNION ALL
+                            SELECT * FROM priority_ops
+                        )
+                        SELECT
+                            sequence_number,
+                            tx_hash as "tx_hash!",
+                            block_number as "block_number!",
+                            block_index as "block_index?",
+                            op as "op!",
+                            created_at as "created_at!",
+                            success as "success!",
+                            fail_reason as "fail_reason?",
+                            eth_hash as "eth_hash?",
+                            priority_op_serialid as "priority_op_serialid?",
+                            batch_id as "batch_id?"
+                        FROM everything
+                        ORDER BY block_index ASC
+                        LIMIT $3
+                    "#,
+                    i64::from(*query.from.block_number),
+                    block_index,
+                    i64::from(query.limit),
+                )
+                .fetch_all(transaction.conn())
+                .await?
+            }
+            PaginationDirection::Older => {
+                sqlx::query_as!(
+                    TransactionItem,
+                    r#"
+                        WITH transactions AS (
+                            SELECT
+                                sequence_number,
+                                tx_hash,
+                                tx as op,
+                                block_number,
+                                created_at,
+                                success,
+                                fail_reason,
+                                Null::bytea as eth_hash,
+                                Null::bigint as priority_op_serialid,
+                                block_index,
+                                batch_id
+                            FROM executed_transactions
+                            WHERE block_number = $1 AND block_index <= $2
+                        ), priority_ops AS (
+                            SELECT
+                                sequence_number,
+                                tx_hash,
+                                operation as op,
+                                block_number,
+                                created_at,
+                                true as success,
+                                Null as fail_reason,
+                                eth_hash,
+                                priority_op_serialid,
+                                block_index,
+                                Null::bigint as batch_id
+                            FROM executed_priority_operations
+                            WHERE block_number = $1 AND block_index <= $2
+                        ), everything AS (
+                            SELECT * FROM transactions
+                            UNION ALL
+                            SELECT * FROM priority_ops
+                        )
+                        SELECT
+                            sequence_number,
+                            tx_hash as "tx_hash!",
+                            block_number as "block_number!",
+                            block_index as "block_index?",
+                            op as "op!",
+                            created_at as "created_at!",
+                            success as "success!",
+                            fail_reason as "fail_reason?",
+                            eth_hash as "eth_hash?",
+                            priority_op_serialid as "priority_op_serialid?",
+                            batch_id as "batch_id?"
+                        FROM everything
+                        ORDER BY block_index DESC
+                        LIMIT $3
+                    "#,
+                    i64::from(*query.from.block_number),
+                    block_index,
+                    i64::from(query.limit),
+                )
+                .fetch_all(transaction.conn())
+                .await?
+            }
+        };
+        let is_block_finalized = transaction
+            .chain()
+            .block_schema()
+            .is_block_finalized(query.from.block_number)
+            .await?;
+        let txs = raw_txs
+            .into_iter()
+            .map(|tx| TransactionItem::transaction_from_item(tx, is_block_finalized))
+            .collect();
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.block.get_block_transactions_page_by_index",
+            start.elapsed()
+        );
+        Ok(txs)
+    }
+
+    /// Retrieves both L1 and L2 operations stored in the block range, ordered by their sequence number.
+    /// Operations are loaded starting after the given sequence number, so the range can be read by chunks.
+    /// Returns the operations and the sequence number of the last one, which is the cursor of the next chunk.
+    pub async fn get_block_range_transactions_chunk(
+        &mut self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        after_sequence_number: Option<i64>,
+        limit: u32,
+    ) -> QueryResult<(Vec<Transaction>, Option<i64>)> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let raw_txs: Vec<TransactionItem> = sqlx::query_as!(
+            TransactionItem,
+            r#"
+                WITH transactions AS (
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        tx as op,
+                        block_number,
+                        created_at,
+                        success,
+                        fail_reason,
+                        Null::bytea as eth_hash,
+                        Null::bigint as priority_op_serialid,
+                        block_index,
+                        batch_id
+                    FROM executed_transactions
+                    WHERE block_number BETWEEN $1 AND $2
+                        AND ($3::bigint IS NULL OR sequence_number > $3)
+                ), priority_ops AS (
+                    SELECT
+                        sequence_number,
+                        tx_hash,
+                        operation as op,
+                        block_number,
+                        created_at,
+                        true as success,
+                        Null as fail_reason,
+                        eth_hash,
+                        priority_op_serialid,
+                        block_index,
+                        Null::bigint as batch_id
+                    FROM executed_priority_operations
+                    WHERE block_number BETWEEN $1 AND $2
+                        AND ($3::bigint IS NULL OR sequence_number > $3)
+                ), everything AS (
+                    SELECT * FROM transactions
+                    UNION ALL
+                    SELECT * FROM priority_ops
+                )
+                SELECT
+                    sequence_number,
+                    tx_hash as "tx_hash!",
+                    block_number as "block_number!",
+                    block_index as "block_index?",
+                    op as "op!",
+                    created_at as "created_at!",
+                    success as "success!",
+                    fail_reason as "fail_reason?",
+                    eth_hash as "eth_hash?",
+                    priority_op_serialid as "priority_op_serialid?",
+                    batch_id as "batch_id?"
+                FROM everything
+                ORDER BY sequence_number ASC
+                LIMIT $4
+            "#,
+            i64::from(*from_block),
+            i64::from(*to_block),
+            after_sequence_number,
+            i64::from(limit),
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+        let last_finalized_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let cursor = raw_txs
+            .last()
+            .and_then(|tx| tx.sequence_number)
+            .or(after_sequence_number);
+        let txs = raw_txs
+            .into_iter()
+            .map(|tx| {
+                let is_block_finalized = tx.block_number <= i64::from(*last_finalized_block);
+                TransactionItem::transaction_from_item(tx, is_block_finalized)
+            })
+            .collect();
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.block.get_block_range_transactions_chunk",
+            start.elapsed()
+        );
+        Ok((txs, cursor))
+    }
+
     /// Returns count of both L1 and L2 operations stored in the block
     pub async fn get_block_transactions_count(
         &mut self,
@@ -1414,6 +1887,37 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         Ok((tx_count + priority_op_count) as u32)
     }
 
+    /// Returns count of both L1 and L2 operations of the given type stored in the block
+    pub async fn get_block_transactions_count_by_type(
+        &mut self,
+        block_number: BlockNumber,
+        tx_type: TxType,
+    ) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+                SELECT (
+                    SELECT count(*) FROM executed_transactions
+                    WHERE block_number = $1 AND tx->>'type' = $2
+                ) + (
+                    SELECT count(*) FROM executed_priority_operations
+                    WHERE block_number = $1 AND operation->>'type' = $2
+                ) as "count!"
+            "#,
+            i64::from(*block_number),
+            tx_type.as_str()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!(
+            "sql.chain.block.get_block_transactions_count_by_type",
+            start.elapsed()
+        );
+        Ok(count as u32)
+    }
+
     // Removes blocks with number greater than `last_block`
     pub async fn remove_blocks(&mut self, last_block: BlockNumber) -> QueryResult<()> {
         let start = Instant::now();
@@ -1460,11 +1964,100 @@ impl<'a, 'c> BlockSchema<'a, 'c> {
         )
         .execute(transaction.conn())
         .await?;
+
+        sqlx::query!(
+            "DELETE FROM block_root_checks WHERE block_number > $1",
+            *last_block as i64
+        )
+        .execute(transaction.conn())
+        .await?;
         transaction.commit().await?;
         metrics::histogram!("sql.chain.block.remove_blocks", start.elapsed());
         Ok(())
     }
 
+    /// Stores the result of cross-checking the root hash of the block committed on L1
+    /// against the locally stored one.
+    pub async fn record_block_root_check(
+        &mut self,
+        block_number: BlockNumber,
+        local_root_hash: H256,
+        l1_root_hash: H256,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO block_root_checks (block_number, local_root_hash, l1_root_hash, matches, checked_at)
+            VALUES ($1, $2, $3, $4, now())
+            ON CONFLICT (block_number)
+            DO UPDATE SET local_root_hash = $2, l1_root_hash = $3, matches = $4, checked_at = now()
+            "#,
+            i64::from(*block_number),
+            local_root_hash.as_bytes(),
+            l1_root_hash.as_bytes(),
+            local_root_hash == l1_root_hash
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.record_block_root_check", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the result of cross-checking the root hash of the block, if the block was checked.
+    pub async fn get_block_root_check(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<StorageBlockRootCheck>> {
+        let start = Instant::now();
+        let check = sqlx::query_as!(
+            StorageBlockRootCheck,
+            "SELECT * FROM block_root_checks WHERE block_number = $1",
+            i64::from(*block_number)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain.block.get_block_root_check", start.elapsed());
+        Ok(check)
+    }
+
+    /// Returns the number of the last block which root hash was cross-checked against L1.
+    pub async fn get_last_checked_block_root(&mut self) -> QueryResult<BlockNumber> {
+        let start = Instant::now();
+        let last_block =
+            sqlx::query!(r#"SELECT MAX(block_number) as "max" FROM block_root_checks"#)
+                .fetch_one(self.0.conn())
+                .await?
+                .max
+                .unwrap_or_default();
+
+        metrics::histogram!(
+            "sql.chain.block.get_last_checked_block_root",
+            start.elapsed()
+        );
+        Ok(BlockNumber(last_block as u32))
+    }
+
+    /// Returns the blocks which root hash differs from the one committed on L1.
+    pub async fn load_block_root_mismatches(&mut self) -> QueryResult<Vec<BlockNumber>> {
+        let start = Instant::now();
+        let blocks = sqlx::query!(
+            "SELECT block_number FROM block_root_checks WHERE matches = false ORDER BY block_number"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| BlockNumber(row.block_number as u32))
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain.block.load_block_root_mismatches",
+            start.elapsed()
+        );
+        Ok(blocks)
+    }
+
     // Removes pending block
     pub async fn remove_pending_block(&mut self) -> QueryResult<()> {
         let start = Instant::now();
@@ -39,6 +39,16 @@ pub(crate) struct StorageRootHash {
     pub root_hash: Vec<u8>,
 }
 
+/// Result of cross-checking the root hash of the block committed on L1 against the locally stored one.
+#[derive(Debug, FromRow, PartialEq, Clone)]
+pub struct StorageBlockRootCheck {
+    pub block_number: i64,
+    pub local_root_hash: Vec<u8>,
+    pub l1_root_hash: Vec<u8>,
+    pub matches: bool,
+    pub checked_at: DateTime<Utc>,
+}
+
 #[derive(Debug, FromRow)]
 pub(crate) struct StoragePendingBlock {
     pub number: i64,
@@ -75,6 +85,35 @@ pub struct StorageBlockDetails {
     pub verified_at: Option<DateTime<Utc>>,
 }
 
+/// Block header along with the timestamp of the block, loaded to paginate the blocks by time.
+#[derive(Debug, FromRow, PartialEq, Clone)]
+pub(crate) struct StorageTimestampedBlockDetails {
+    pub block_number: i64,
+    pub new_state_root: Vec<u8>,
+    pub block_size: i64,
+    pub commit_tx_hash: Option<Vec<u8>>,
+    pub verify_tx_hash: Option<Vec<u8>>,
+    pub committed_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub timestamp: i64,
+}
+
+impl StorageTimestampedBlockDetails {
+    /// Splits the record into the block header and the timestamp.
+    pub fn into_parts(self) -> (StorageBlockDetails, i64) {
+        let details = StorageBlockDetails {
+            block_number: self.block_number,
+            new_state_root: self.new_state_root,
+            block_size: self.block_size,
+            commit_tx_hash: self.commit_tx_hash,
+            verify_tx_hash: self.verify_tx_hash,
+            committed_at: self.committed_at,
+            verified_at: self.verified_at,
+        };
+        (details, self.timestamp)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, PartialEq)]
 pub struct BlockTransactionItem {
     pub tx_hash: String,
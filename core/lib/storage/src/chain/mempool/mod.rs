@@ -1,26 +1,32 @@
 // Built-in deps
 use std::{collections::VecDeque, convert::TryFrom, str::FromStr, time::Instant};
 // External imports
+use chrono::{DateTime, TimeZone, Utc};
 use itertools::Itertools;
 // Workspace imports
-use zksync_api_types::v02::pagination::PaginationDirection;
+use zksync_api_types::v02::pagination::{PaginationDirection, QueuedTxPosition};
 use zksync_api_types::v02::transaction::{
-    ApiTxBatch, BatchStatus, TxHashSerializeWrapper, TxInBlockStatus,
+    ApiTxBatch, BatchStatus, Transaction, TxHashSerializeWrapper, TxInBlockStatus,
 };
+use zksync_api_types::{DeadLetteredTx, StuckAccount};
 use zksync_types::{
     block::IncompleteBlock,
     mempool::SignedTxVariant,
     tx::{TxEthSignature, TxHash},
-    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx,
-    PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, H256,
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedPriorityOp, ExecutedTx, Nonce,
+    PriorityOp, SerialId, SignedZkSyncTx, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
 // Local imports
-use self::records::{MempoolPriorityOp, MempoolTx, QueuedBatchTx, RevertedBlock};
-use crate::{QueryResult, StorageProcessor};
+use self::records::{
+    MempoolPriorityOp, MempoolTx, MempoolTxTypeStats, QueuedBatchTx, RevertedBlock,
+    StoredStuckAccount, StoredTxExecutionAttempts,
+};
+use crate::{utils::l1_surplus_to_stored, QueryResult, StorageProcessor};
 
 use crate::chain::operations::records::{
     StoredExecutedPriorityOperation, StoredExecutedTransaction,
 };
+use crate::chain::operations_ext::records::StorageTxData;
 
 pub mod records;
 
@@ -34,7 +40,7 @@ pub mod records;
 pub struct MempoolSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
 impl<'a, 'c> MempoolSchema<'a, 'c> {
-    /// Loads all transactions stored in the mempool schema.
+    /// Loads all transactions stored in the mempool schema, except for the parked, deferred and dead-lettered ones.
     /// We want to exclude txs that have already been processed in memory,
     /// due to asynchronous execution,
     /// these txs may be executed in memory and not yet saved to the database
@@ -47,8 +53,13 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         let excluded_txs: Vec<String> = executed_txs.iter().map(|tx| tx.to_string()).collect();
         let txs: Vec<MempoolTx> = sqlx::query_as!(
             MempoolTx,
-            "SELECT * FROM mempool_txs WHERE reverted = false AND tx_hash NOT IN (
+            "SELECT id, tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id,
+                reverted, parked_at, parked_reason, picked
+            FROM mempool_txs WHERE reverted = false AND parked_at IS NULL
+            AND (deferred_until IS NULL OR deferred_until <= now()) AND tx_hash NOT IN (
                 SELECT u.hashes FROM UNNEST ($1::text[]) as u(hashes)
+            ) AND tx_hash NOT IN (
+                SELECT tx_hash FROM tx_execution_attempts WHERE dead_lettered_at IS NOT NULL
             )
 
             ORDER BY id",
@@ -151,6 +162,8 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         let mut transaction = self.0.start_transaction().await?;
         let tx_hashes: Vec<TxHash> = txs.iter().map(|tx| tx.tx.hash()).collect();
+        // The batch is executed as a whole, so it's deferred until its latest transaction becomes valid.
+        let deferred_until = deferred_until(txs);
 
         // The first transaction of the batch would be inserted manually
         // batch_id of the inserted transaction would be the id of this batch
@@ -167,19 +180,22 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data)
-                VALUES ($1, $2, $3, $4)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, deferred_until)
+                VALUES ($1, $2, $3, $4, $5)",
                 tx_hash,
                 tx,
                 first_tx_data.created_at,
                 eth_sign_data,
+                deferred_until,
             )
             .execute(transaction.conn())
             .await?;
 
             sqlx::query_as!(
                 MempoolTx,
-                "SELECT * FROM mempool_txs
+                "SELECT id, tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id,
+                    reverted, parked_at, parked_reason, picked
+                FROM mempool_txs
                 ORDER BY batch_id DESC
                 LIMIT 1",
             )
@@ -201,13 +217,14 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
 
             sqlx::query!(
-                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-                VALUES ($1, $2, $3, $4, $5)",
+                "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, deferred_until)
+                VALUES ($1, $2, $3, $4, $5, $6)",
                 tx_hash,
                 tx,
                 tx_data.created_at,
                 eth_sign_data,
-                batch_id
+                batch_id,
+                deferred_until,
             )
             .execute(transaction.conn())
             .await?;
@@ -253,13 +270,14 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
 
         sqlx::query!(
-            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
-            VALUES ($1, $2, $3, $4, $5)",
+            "INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id, deferred_until)
+            VALUES ($1, $2, $3, $4, $5, $6)",
             tx_hash,
             tx,
             tx_data.created_at,
             eth_sign_data,
             batch_id,
+            deferred_until(std::slice::from_ref(tx_data)),
         )
         .execute(self.0.conn())
         .await?;
@@ -300,6 +318,129 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Atomically replaces the queued transaction with the given one.
+    /// Returns `false` without changing anything if the replaced transaction is not queued anymore,
+    /// e.g. if it was picked into the pending block in the meantime.
+    pub async fn replace_tx(
+        &mut self,
+        replaced_tx_hash: TxHash,
+        tx_data: &SignedZkSyncTx,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        // The row is locked, so the replaced transaction can't be picked or removed from the mempool
+        // by the block processing until the replacement is done.
+        let queued = sqlx::query!(
+            "SELECT tx_hash FROM mempool_txs
+            WHERE tx_hash = $1 AND batch_id = 0 AND reverted = false AND picked = false
+            FOR UPDATE",
+            hex::encode(replaced_tx_hash.as_ref())
+        )
+        .fetch_optional(transaction.conn())
+        .await?
+        .is_some();
+        // Transactions of the pending block are stored as executed before being removed from the mempool.
+        let executed = sqlx::query!(
+            r#"SELECT EXISTS(SELECT 1 FROM executed_transactions WHERE tx_hash = $1) as "exists!""#,
+            replaced_tx_hash.as_ref()
+        )
+        .fetch_one(transaction.conn())
+        .await?
+        .exists;
+        if !queued || executed {
+            return Ok(false);
+        }
+
+        transaction
+            .chain()
+            .mempool_schema()
+            .remove_tx(replaced_tx_hash.as_ref())
+            .await?;
+        transaction
+            .chain()
+            .mempool_schema()
+            .insert_tx(tx_data)
+            .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "replace_tx");
+        Ok(true)
+    }
+
+    /// Marks the transactions proposed to the state keeper as picked, so they can't be replaced anymore.
+    /// Returns the hashes of the marked transactions, the other ones are not in the mempool,
+    /// e.g. they were replaced after being loaded for the proposal.
+    pub async fn mark_txs_picked(&mut self, tx_hashes: &[TxHash]) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(hex::encode).collect();
+
+        let picked = sqlx::query!(
+            "UPDATE mempool_txs SET picked = true
+            WHERE tx_hash = ANY($1)
+            RETURNING tx_hash",
+            &tx_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            let bytes = hex::decode(row.tx_hash).expect("Stored tx hash must be a valid hex");
+            TxHash::from_slice(&bytes).expect("Stored tx hash must have a correct length")
+        })
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "mark_txs_picked");
+        Ok(picked)
+    }
+
+    /// Resets the picked markers of the transactions dropped from the proposal,
+    /// so they can be replaced again.
+    pub async fn unmark_txs_picked(&mut self, tx_hashes: &[TxHash]) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(hex::encode).collect();
+
+        sqlx::query!(
+            "UPDATE mempool_txs SET picked = false WHERE tx_hash = ANY($1)",
+            &tx_hashes
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "unmark_txs_picked");
+        Ok(())
+    }
+
+    /// Resets the picked markers, since the pending block is lost on restart unless it was stored.
+    /// Transactions of the stored pending block can't be replaced anyway, since they are executed.
+    pub async fn reset_picked_txs(&mut self) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!("UPDATE mempool_txs SET picked = false WHERE picked = true")
+            .execute(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "reset_picked_txs");
+        Ok(())
+    }
+
+    /// Checks whether the queued transaction was picked into a block proposed to the state keeper.
+    pub async fn is_tx_picked(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+
+        let picked = sqlx::query!(
+            "SELECT picked FROM mempool_txs WHERE tx_hash = $1",
+            hex::encode(tx_hash.as_ref())
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.picked)
+        .unwrap_or(false);
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "is_tx_picked");
+        Ok(picked)
+    }
+
     /// Checks if the memory pool contains transaction with the given hash.
     pub async fn contains_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
         let start = Instant::now();
@@ -321,6 +462,616 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(contains)
     }
 
+    /// Returns the hashes from the given list which are either queued in the memory pool
+    /// or already executed.
+    pub async fn filter_known_txs(&mut self, tx_hashes: &[TxHash]) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+
+        let hex_hashes: Vec<String> = tx_hashes
+            .iter()
+            .map(|tx_hash| hex::encode(tx_hash.as_ref()))
+            .collect();
+        let byte_hashes: Vec<Vec<u8>> = tx_hashes
+            .iter()
+            .map(|tx_hash| tx_hash.as_ref().to_vec())
+            .collect();
+
+        let known_hashes = sqlx::query!(
+            r#"
+            SELECT tx_hash as "tx_hash!" FROM mempool_txs
+            WHERE tx_hash = ANY($1)
+            UNION
+            SELECT encode(tx_hash, 'hex') FROM executed_transactions
+            WHERE tx_hash = ANY($2)
+            "#,
+            &hex_hashes,
+            &byte_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            let bytes = hex::decode(row.tx_hash).expect("Stored tx hash must be a valid hex");
+            TxHash::from_slice(&bytes).expect("Stored tx hash must have a correct length")
+        })
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "filter_known_txs");
+        Ok(known_hashes)
+    }
+
+    /// Returns the nonces of the queued transactions initiated by the given account, in ascending order.
+    pub async fn get_queued_nonces(&mut self, account_id: AccountId) -> QueryResult<Vec<Nonce>> {
+        let start = Instant::now();
+
+        // Initiator of the transaction is stored under the different keys depending on the transaction type.
+        let nonces = sqlx::query!(
+            r#"
+            SELECT (tx->>'nonce')::bigint as "nonce!" FROM mempool_txs
+            WHERE reverted = false AND COALESCE(
+                tx->>'accountId', tx->>'initiatorAccountId', tx->>'creatorId', tx->>'submitterId'
+            ) = $1
+            ORDER BY 1
+            "#,
+            account_id.to_string()
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| Nonce(row.nonce as u32))
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_queued_nonces");
+        Ok(nonces)
+    }
+
+    /// Returns the queued transaction initiated by the given account with the given nonce.
+    /// Transactions of the batches are not returned, since they can't be replaced one by one.
+    pub async fn get_queued_tx_by_nonce(
+        &mut self,
+        account_id: AccountId,
+        nonce: Nonce,
+    ) -> QueryResult<Option<SignedZkSyncTx>> {
+        let start = Instant::now();
+
+        let mempool_tx = sqlx::query_as!(
+            MempoolTx,
+            r#"
+            SELECT id, tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id,
+                reverted, parked_at, parked_reason, picked
+            FROM mempool_txs
+            WHERE reverted = false AND batch_id = 0 AND COALESCE(
+                tx->>'accountId', tx->>'initiatorAccountId', tx->>'creatorId', tx->>'submitterId'
+            ) = $1 AND (tx->>'nonce')::bigint = $2
+            ORDER BY id
+            LIMIT 1
+            "#,
+            account_id.to_string(),
+            i64::from(*nonce)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_queued_tx_by_nonce");
+        mempool_tx
+            .map(SignedZkSyncTx::try_from)
+            .transpose()
+            .map_err(anyhow::Error::from)
+    }
+
+    /// Returns the position of the queued transaction of the given account with the highest nonce.
+    pub async fn get_account_last_queued_tx_position(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Option<QueuedTxPosition>> {
+        let start = Instant::now();
+        let position = sqlx::query!(
+            r#"SELECT tx_hash, created_at, (tx->>'nonce')::bigint as "nonce!" FROM mempool_txs
+            WHERE mempool_tx_account(tx) = $1 AND parked_at IS NULL
+            ORDER BY (tx->>'nonce')::bigint DESC, created_at DESC, tx_hash DESC
+            LIMIT 1"#,
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| {
+            let tx_hash = hex::decode(&row.tx_hash).expect("Stored tx hash must be a valid hex");
+            QueuedTxPosition {
+                nonce: Nonce(row.nonce as u32),
+                created_at: row.created_at,
+                tx_hash: TxHash::from_slice(&tx_hash)
+                    .expect("Stored tx hash must have a correct length"),
+            }
+        });
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_account_last_queued_tx_position");
+        Ok(position)
+    }
+
+    /// Loads the page of the queued transactions initiated by the given account, ordered by nonce.
+    /// Parked transactions are not listed. Every transaction is returned along with its position.
+    pub async fn get_account_queued_txs(
+        &mut self,
+        address: Address,
+        position: QueuedTxPosition,
+        limit: u32,
+        direction: PaginationDirection,
+    ) -> QueryResult<Vec<(QueuedTxPosition, Transaction)>> {
+        let start = Instant::now();
+        let query =
+            "SELECT * FROM mempool_txs WHERE mempool_tx_account(tx) = $1 AND parked_at IS NULL";
+        let query = match direction {
+            PaginationDirection::Newer => format!(
+                "{} AND ((tx->>'nonce')::bigint, created_at, tx_hash) >= ($2, $3, $4) \
+                ORDER BY (tx->>'nonce')::bigint, created_at, tx_hash LIMIT $5",
+                query
+            ),
+            PaginationDirection::Older => format!(
+                "{} AND ((tx->>'nonce')::bigint, created_at, tx_hash) <= ($2, $3, $4) \
+                ORDER BY (tx->>'nonce')::bigint DESC, created_at DESC, tx_hash DESC LIMIT $5",
+                query
+            ),
+        };
+        let txs: Vec<MempoolTx> = sqlx::query_as(query.as_str())
+            .bind(address.as_bytes().to_vec())
+            .bind(i64::from(*position.nonce))
+            .bind(position.created_at)
+            .bind(hex::encode(position.tx_hash.as_ref()))
+            .bind(limit as i64)
+            .fetch_all(self.0.conn())
+            .await?;
+
+        let txs = txs
+            .into_iter()
+            .map(|tx| {
+                let tx_hash = hex::decode(&tx.tx_hash).expect("Stored tx hash must be a valid hex");
+                let tx_hash = TxHash::from_slice(&tx_hash)
+                    .expect("Stored tx hash must have a correct length");
+                let zksync_tx: ZkSyncTx = serde_json::from_value(tx.tx)?;
+                let position = QueuedTxPosition {
+                    nonce: zksync_tx.nonce(),
+                    created_at: tx.created_at,
+                    tx_hash,
+                };
+                let tx = Transaction {
+                    tx_hash,
+                    block_index: None,
+                    block_number: None,
+                    op: StorageTxData::tx_data_from_zksync_tx(zksync_tx, None),
+                    status: TxInBlockStatus::Queued,
+                    fail_reason: None,
+                    fail_reason_code: None,
+                    created_at: Some(tx.created_at),
+                    // Special case: batch_id == 0 <==> transaction is not a part of some batch
+                    batch_id: Some(tx.batch_id as u32).filter(|batch_id| *batch_id != 0),
+                };
+                Ok((position, tx))
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_account_queued_txs");
+        Ok(txs)
+    }
+
+    /// Returns the number of the queued transactions initiated by the given account,
+    /// parked transactions are not counted.
+    pub async fn get_account_queued_txs_count(&mut self, address: Address) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"SELECT COUNT(*) as "count!" FROM mempool_txs
+            WHERE mempool_tx_account(tx) = $1 AND parked_at IS NULL"#,
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_account_queued_txs_count");
+        Ok(count as u32)
+    }
+
+    /// Loads the oldest transactions that are not a part of some batch, along with
+    /// the time they were parked at (if they were). Used to re-validate queued transactions.
+    pub async fn load_txs_for_revalidation(
+        &mut self,
+        limit: u32,
+    ) -> QueryResult<Vec<(SignedZkSyncTx, Option<DateTime<Utc>>)>> {
+        let start = Instant::now();
+
+        let txs = sqlx::query_as!(
+            MempoolTx,
+            "SELECT id, tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id,
+                reverted, parked_at, parked_reason, picked
+            FROM mempool_txs
+            WHERE reverted = false AND batch_id = 0
+            ORDER BY id
+            LIMIT $1",
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|mempool_tx| {
+            let parked_at = mempool_tx.parked_at;
+            SignedZkSyncTx::try_from(mempool_tx).map(|tx| (tx, parked_at))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+        metrics::histogram!(
+            "sql.chain",
+            start.elapsed(),
+            "mempool" => "load_txs_for_revalidation"
+        );
+        Ok(txs)
+    }
+
+    /// Parks the transaction, so it won't be proposed for execution until it is unparked.
+    /// Does nothing if the transaction is already parked.
+    pub async fn park_tx(&mut self, tx_hash: TxHash, reason: &str) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        sqlx::query!(
+            "UPDATE mempool_txs SET parked_at = now(), parked_reason = $2
+            WHERE tx_hash = $1 AND parked_at IS NULL",
+            &tx_hash,
+            reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "park_tx");
+        Ok(())
+    }
+
+    /// Returns parked transactions back to the queue.
+    pub async fn unpark_txs(&mut self, tx_hashes: &[TxHash]) -> QueryResult<()> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(hex::encode).collect();
+
+        sqlx::query!(
+            "UPDATE mempool_txs SET parked_at = NULL, parked_reason = NULL
+            WHERE tx_hash = ANY($1)",
+            &tx_hashes
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "unpark_txs");
+        Ok(())
+    }
+
+    /// Removes transactions parked before the given time from the mempool.
+    /// Removed transactions are kept in the `expired_mempool_txs` table along with the fail reason,
+    /// so their status can still be reported.
+    pub async fn remove_expired_parked_txs(
+        &mut self,
+        parked_before: DateTime<Utc>,
+    ) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+
+        let expired_txs = sqlx::query!(
+            "WITH expired AS (
+                DELETE FROM mempool_txs
+                WHERE parked_at < $1
+                RETURNING tx_hash, tx, created_at, eth_sign_data, parked_reason
+            )
+            INSERT INTO expired_mempool_txs (tx_hash, tx, created_at, eth_sign_data, fail_reason, expired_at)
+            SELECT
+                tx_hash, tx, created_at, eth_sign_data,
+                'Transaction was parked for too long: ' || COALESCE(parked_reason, 'unknown reason'),
+                now()
+            FROM expired
+            ON CONFLICT (tx_hash) DO NOTHING
+            RETURNING tx_hash",
+            parked_before
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| {
+            let bytes = hex::decode(row.tx_hash).expect("Stored tx hash must be a valid hex");
+            TxHash::from_slice(&bytes).expect("Stored tx hash must have a correct length")
+        })
+        .collect();
+
+        metrics::histogram!(
+            "sql.chain",
+            start.elapsed(),
+            "mempool" => "remove_expired_parked_txs"
+        );
+        Ok(expired_txs)
+    }
+
+    /// Records a failed execution attempt of the transaction. Once the number of attempts reaches
+    /// `max_attempts`, the transaction is moved to the dead-letter state.
+    /// Recording the same attempt (i.e. with the same `failed_at` time) more than once has no effect.
+    ///
+    /// Returns `true` if the transaction has been dead-lettered as a result of this attempt.
+    pub async fn record_failed_execution(
+        &mut self,
+        tx_data: &SignedZkSyncTx,
+        fail_reason: &str,
+        failed_at: DateTime<Utc>,
+        max_attempts: u32,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_data.tx.hash().as_ref());
+        let tx = serde_json::to_value(&tx_data.tx)?;
+        let eth_sign_data = tx_data
+            .eth_sign_data
+            .as_ref()
+            .map(|sd| serde_json::to_value(sd).expect("failed to encode EthSignData"));
+        let max_attempts = max_attempts as i32;
+
+        let attempts = sqlx::query!(
+            "INSERT INTO tx_execution_attempts (tx_hash, tx, eth_sign_data, attempts, last_fail_reason, last_failed_at, dead_lettered_at)
+            VALUES ($1, $2, $3, 1, $4, $5, CASE WHEN 1 >= $6 THEN now() END)
+            ON CONFLICT (tx_hash)
+            DO UPDATE
+              SET attempts = tx_execution_attempts.attempts + 1, last_fail_reason = $4, last_failed_at = $5,
+                dead_lettered_at = CASE
+                    WHEN tx_execution_attempts.attempts + 1 >= $6
+                    THEN COALESCE(tx_execution_attempts.dead_lettered_at, now())
+                END
+              WHERE tx_execution_attempts.last_failed_at <> $5
+            RETURNING attempts",
+            tx_hash,
+            tx,
+            eth_sign_data,
+            fail_reason,
+            failed_at,
+            max_attempts,
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(|row| row.attempts);
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "record_failed_execution");
+        Ok(attempts == Some(max_attempts))
+    }
+
+    /// Returns the failed execution attempts of the transaction if it is in the dead-letter state.
+    pub async fn get_dead_lettered_tx(
+        &mut self,
+        tx_hash: TxHash,
+    ) -> QueryResult<Option<StoredTxExecutionAttempts>> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let tx = sqlx::query_as!(
+            StoredTxExecutionAttempts,
+            "SELECT * FROM tx_execution_attempts
+            WHERE tx_hash = $1 AND dead_lettered_at IS NOT NULL",
+            &tx_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_dead_lettered_tx");
+        Ok(tx)
+    }
+
+    /// Loads the dead-lettered transactions among the given ones.
+    pub async fn get_dead_lettered_txs(
+        &mut self,
+        tx_hashes: &[TxHash],
+    ) -> QueryResult<Vec<StoredTxExecutionAttempts>> {
+        let start = Instant::now();
+        let tx_hashes: Vec<String> = tx_hashes
+            .iter()
+            .map(|tx_hash| hex::encode(tx_hash.as_ref()))
+            .collect();
+
+        let txs = sqlx::query_as!(
+            StoredTxExecutionAttempts,
+            "SELECT * FROM tx_execution_attempts
+            WHERE tx_hash = ANY($1) AND dead_lettered_at IS NOT NULL",
+            &tx_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_dead_lettered_txs");
+        Ok(txs)
+    }
+
+    /// Loads all the transactions in the dead-letter state, the most recent ones first.
+    pub async fn load_dead_lettered_txs(&mut self) -> QueryResult<Vec<DeadLetteredTx>> {
+        let start = Instant::now();
+
+        let txs = sqlx::query_as!(
+            StoredTxExecutionAttempts,
+            "SELECT * FROM tx_execution_attempts
+            WHERE dead_lettered_at IS NOT NULL
+            ORDER BY dead_lettered_at DESC"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|tx| {
+            let tx_hash = hex::decode(&tx.tx_hash).expect("Stored tx hash must be a valid hex");
+            Ok(DeadLetteredTx {
+                tx_hash: TxHash::from_slice(&tx_hash)
+                    .expect("Stored tx hash must have a correct length"),
+                tx: serde_json::from_value(tx.tx)?,
+                attempts: tx.attempts as u32,
+                last_fail_reason: tx.last_fail_reason,
+                dead_lettered_at: tx.dead_lettered_at.unwrap_or(tx.last_failed_at),
+            })
+        })
+        .collect::<Result<Vec<_>, serde_json::Error>>()?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "load_dead_lettered_txs");
+        Ok(txs)
+    }
+
+    /// Returns the dead-lettered transaction back to the mempool and resets its execution attempts.
+    /// Returns `false` if the transaction is not in the dead-letter state.
+    pub async fn resurrect_dead_lettered_tx(&mut self, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let resurrected = sqlx::query!(
+            "WITH resurrected AS (
+                DELETE FROM tx_execution_attempts
+                WHERE tx_hash = $1 AND dead_lettered_at IS NOT NULL
+                RETURNING tx_hash, tx, eth_sign_data
+            )
+            INSERT INTO mempool_txs (tx_hash, tx, created_at, eth_sign_data, batch_id)
+            SELECT tx_hash, tx, now(), eth_sign_data, 0 FROM resurrected
+            RETURNING tx_hash",
+            &tx_hash
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .is_some();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "resurrect_dead_lettered_tx");
+        Ok(resurrected)
+    }
+
+    /// Moves the queued transaction to the dead-letter state without waiting for it to fail execution.
+    /// Returns `false` if there is no such transaction in the mempool.
+    pub async fn dead_letter_tx(&mut self, tx_hash: TxHash, reason: &str) -> QueryResult<bool> {
+        let start = Instant::now();
+        let tx_hash = hex::encode(tx_hash.as_ref());
+
+        let dead_lettered = sqlx::query!(
+            "WITH removed AS (
+                DELETE FROM mempool_txs
+                WHERE tx_hash = $1
+                RETURNING tx_hash, tx, eth_sign_data
+            )
+            INSERT INTO tx_execution_attempts (tx_hash, tx, eth_sign_data, attempts, last_fail_reason, last_failed_at, dead_lettered_at)
+            SELECT tx_hash, tx, eth_sign_data, 0, $2, now(), now() FROM removed
+            ON CONFLICT (tx_hash)
+            DO UPDATE
+              SET last_fail_reason = $2, dead_lettered_at = now()
+            RETURNING tx_hash",
+            &tx_hash,
+            reason
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .is_some();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "dead_letter_tx");
+        Ok(dead_lettered)
+    }
+
+    /// Re-detects the accounts whose lowest queued transaction has been parked or failing since
+    /// before the given time, while the transactions with the higher nonces are queued behind it.
+    /// The stored list of the stuck accounts is replaced with the detected ones.
+    pub async fn refresh_stuck_accounts(
+        &mut self,
+        blocked_before: DateTime<Utc>,
+    ) -> QueryResult<Vec<StuckAccount>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!("DELETE FROM stuck_accounts")
+            .execute(transaction.conn())
+            .await?;
+        // Transaction is considered failing since it was queued if it has failed execution attempts.
+        let accounts = sqlx::query_as!(
+            StoredStuckAccount,
+            r#"
+            WITH queued AS (
+                SELECT
+                    COALESCE(
+                        tx->>'accountId', tx->>'initiatorAccountId', tx->>'creatorId', tx->>'submitterId'
+                    )::bigint AS account_id,
+                    (tx->>'nonce')::bigint AS nonce,
+                    tx_hash, created_at, parked_at, parked_reason
+                FROM mempool_txs
+                WHERE reverted = false
+            ),
+            lowest AS (
+                SELECT DISTINCT ON (account_id) *
+                FROM queued
+                WHERE account_id IS NOT NULL
+                ORDER BY account_id, nonce
+            ),
+            blockers AS (
+                SELECT
+                    lowest.account_id,
+                    lowest.tx_hash,
+                    lowest.nonce,
+                    COALESCE(lowest.parked_reason, attempts.last_fail_reason, 'unknown reason') AS reason,
+                    COALESCE(
+                        lowest.parked_at,
+                        CASE WHEN attempts.tx_hash IS NOT NULL THEN lowest.created_at END
+                    ) AS blocked_since
+                FROM lowest
+                LEFT JOIN tx_execution_attempts attempts
+                    ON attempts.tx_hash = lowest.tx_hash AND attempts.dead_lettered_at IS NULL
+            )
+            INSERT INTO stuck_accounts (account_id, blocking_tx_hash, blocking_nonce, reason, blocked_since, queued_txs)
+            SELECT
+                blockers.account_id, blockers.tx_hash, blockers.nonce, blockers.reason, blockers.blocked_since,
+                (
+                    SELECT COUNT(*) FROM queued
+                    WHERE queued.account_id = blockers.account_id AND queued.nonce > blockers.nonce
+                )
+            FROM blockers
+            WHERE blockers.blocked_since < $1 AND EXISTS (
+                SELECT 1 FROM queued
+                WHERE queued.account_id = blockers.account_id AND queued.nonce > blockers.nonce
+            )
+            RETURNING *
+            "#,
+            blocked_before
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(StuckAccount::from)
+        .collect();
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "refresh_stuck_accounts");
+        Ok(accounts)
+    }
+
+    /// Loads the accounts detected as stuck by the last check, the longest blocked ones first.
+    pub async fn load_stuck_accounts(&mut self) -> QueryResult<Vec<StuckAccount>> {
+        let start = Instant::now();
+
+        let accounts = sqlx::query_as!(
+            StoredStuckAccount,
+            "SELECT * FROM stuck_accounts ORDER BY blocked_since"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(StuckAccount::from)
+        .collect();
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "load_stuck_accounts");
+        Ok(accounts)
+    }
+
+    /// Returns the transaction blocking the account, if the account was detected as stuck by the last check.
+    pub async fn get_stuck_account(
+        &mut self,
+        account_id: AccountId,
+    ) -> QueryResult<Option<StuckAccount>> {
+        let start = Instant::now();
+
+        let account = sqlx::query_as!(
+            StoredStuckAccount,
+            "SELECT * FROM stuck_accounts WHERE account_id = $1",
+            i64::from(*account_id)
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .map(StuckAccount::from);
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_stuck_account");
+        Ok(account)
+    }
+
     /// Returns zkSync transaction with the given hash.
     pub async fn get_tx(&mut self, tx_hash: &[u8]) -> QueryResult<Option<SignedZkSyncTx>> {
         let start = Instant::now();
@@ -342,7 +1093,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
 
         let mempool_tx = sqlx::query_as!(
             MempoolTx,
-            "SELECT * from mempool_txs
+            "SELECT id, tx_hash, tx, created_at, eth_sign_data, batch_id, next_priority_op_serial_id,
+                reverted, parked_at, parked_reason, picked
+            FROM mempool_txs
             WHERE tx_hash = $1",
             &tx_hash
         )
@@ -424,6 +1177,8 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             let eth_hash = op.eth_hash.as_bytes().to_vec();
             let eth_block = op.eth_block as i64;
             let eth_block_index = op.eth_block_index.map(|v| v as i32).unwrap_or_default();
+            let eth_block_hash = op.eth_block_hash.map(|hash| hash.as_bytes().to_vec());
+            let l1_surplus = l1_surplus_to_stored(&op.l1_surplus);
             let op_type = op.data.variance_name();
             let (l1_address, l2_address) = match &op.data {
                 ZkSyncPriorityOp::Deposit(dep) => {
@@ -439,13 +1194,13 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 "INSERT INTO mempool_priority_operations (
                     serial_id, data, deadline_block, eth_hash, tx_hash,
                     eth_block, eth_block_index, l1_address, 
-                    l2_address, type, created_at, confirmed
+                    l2_address, type, created_at, confirmed, eth_block_hash, l1_surplus
                  )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, now(), $11)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, now(), $11, $12, $13)
                 ON CONFLICT (serial_id) DO UPDATE SET
                 data=$2, deadline_block=$3, eth_hash=$4, tx_hash=$5,
                 eth_block=$6, eth_block_index=$7, l1_address=$8,
-                l2_address=$9, type=$10, confirmed=$11
+                l2_address=$9, type=$10, confirmed=$11, eth_block_hash=$12, l1_surplus=$13
                 ",
                 serial_id,
                 data,
@@ -457,7 +1212,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 l1_address,
                 l2_address,
                 op_type,
-                confirmed
+                confirmed,
+                eth_block_hash,
+                l1_surplus
             )
             .execute(transaction.conn())
             .await?;
@@ -470,7 +1227,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
     pub async fn get_confirmed_priority_ops(&mut self) -> QueryResult<VecDeque<PriorityOp>> {
         let ops = sqlx::query_as!(
             MempoolPriorityOp,
-            "SELECT serial_id,data,deadline_block,eth_hash,tx_hash,eth_block,eth_block_index,created_at FROM mempool_priority_operations WHERE confirmed AND reverted = false ORDER BY serial_id"
+            "SELECT serial_id,data,deadline_block,eth_hash,tx_hash,eth_block,eth_block_index,eth_block_hash,l1_surplus,created_at FROM mempool_priority_operations WHERE confirmed AND reverted = false ORDER BY serial_id"
         )
         .fetch_all(self.0.conn())
         .await?;
@@ -508,7 +1265,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         limit: u32,
         direction: PaginationDirection,
     ) -> QueryResult<Vec<PriorityOp>> {
-        let query = "SELECT serial_id,data,deadline_block,eth_hash,tx_hash,eth_block,eth_block_index,created_at FROM mempool_priority_operations WHERE l2_address = $1";
+        let query = "SELECT serial_id,data,deadline_block,eth_hash,tx_hash,eth_block,eth_block_index,eth_block_hash,l1_surplus,created_at FROM mempool_priority_operations WHERE l2_address = $1";
         let query = match direction {
             PaginationDirection::Newer => {
                 format!("{} AND serial_id >= $2 ORDER BY serial_id LIMIT $3", query)
@@ -537,7 +1294,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             MempoolPriorityOp,
             r#"
                 SELECT serial_id,data,deadline_block,eth_hash,
-                       tx_hash,eth_block,eth_block_index,created_at 
+                       tx_hash,eth_block,eth_block_index,eth_block_hash,l1_surplus,created_at 
                 FROM mempool_priority_operations 
                 WHERE eth_hash = $1
             "#,
@@ -548,12 +1305,37 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         .map(|op| op.into());
         Ok(op)
     }
+
+    /// Loads the pending priority operations with the given Ethereum transaction hashes.
+    pub async fn get_pending_operations_by_hashes(
+        &mut self,
+        tx_hashes: &[H256],
+    ) -> QueryResult<Vec<PriorityOp>> {
+        let tx_hashes: Vec<Vec<u8>> = tx_hashes
+            .iter()
+            .map(|tx_hash| tx_hash.as_bytes().to_vec())
+            .collect();
+        let ops = sqlx::query_as!(
+            MempoolPriorityOp,
+            r#"
+                SELECT serial_id,data,deadline_block,eth_hash,
+                       tx_hash,eth_block,eth_block_index,eth_block_hash,l1_surplus,created_at
+                FROM mempool_priority_operations
+                WHERE eth_hash = ANY($1)
+            "#,
+            &tx_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+        Ok(ops.into_iter().map(|op| op.into()).collect())
+    }
+
     pub async fn get_pending_deposits(&mut self, address: Address) -> QueryResult<Vec<PriorityOp>> {
         let ops = sqlx::query_as!(
             MempoolPriorityOp,
             r#"
             SELECT serial_id,data,deadline_block,eth_hash,
-                   tx_hash,eth_block,eth_block_index,created_at 
+                   tx_hash,eth_block,eth_block_index,eth_block_hash,l1_surplus,created_at 
             FROM mempool_priority_operations 
             WHERE type = 'Deposit' AND l2_address = $1  
             ORDER BY serial_id"#,
@@ -588,6 +1370,31 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(size.unwrap_or(0) as u32)
     }
 
+    /// Counts the mempool transactions of every type along with the creation time of the oldest
+    /// transaction of the type. Unlike `load_txs`, the transactions themselves are not loaded.
+    pub async fn get_mempool_stats(&mut self) -> QueryResult<Vec<MempoolTxTypeStats>> {
+        let start = Instant::now();
+
+        let stats = sqlx::query_as!(
+            MempoolTxTypeStats,
+            r#"
+            SELECT
+                tx->>'type' AS "tx_type!",
+                COUNT(*) AS "count!",
+                MIN(created_at) AS "oldest_created_at!"
+            FROM mempool_txs
+            WHERE reverted = false AND parked_at IS NULL
+            GROUP BY tx->>'type'
+            ORDER BY tx->>'type'
+            "#
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.chain", start.elapsed(), "mempool" => "get_mempool_stats");
+        Ok(stats)
+    }
+
     /// Get info about batch in mempool.
     pub async fn get_queued_batch_info(
         &mut self,
@@ -625,6 +1432,7 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                     updated_at: created_at,
                     last_state: TxInBlockStatus::Queued,
                 },
+                block_number: None,
             })
         } else {
             None
@@ -696,7 +1504,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 mempool_priority_operations.eth_block,
                 mempool_priority_operations.created_at,
                 cast(mempool_priority_operations.eth_block_index as bigint) as "eth_block_index?",
-                mempool_reverted_txs_meta.tx_hash_bytes as tx_hash
+                mempool_reverted_txs_meta.tx_hash_bytes as tx_hash,
+                mempool_priority_operations.eth_block_hash,
+                mempool_priority_operations.l1_surplus
                  FROM mempool_priority_operations INNER JOIN mempool_reverted_txs_meta 
                 ON mempool_priority_operations.tx_hash = mempool_reverted_txs_meta.tx_hash 
                 WHERE mempool_reverted_txs_meta.block_number=$1 AND mempool_reverted_txs_meta.tx_type='L1'"#, 
@@ -953,6 +1763,10 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             let eth_hash = priority_op.eth_hash.as_bytes().to_vec();
             let eth_block = priority_op.eth_block as i64;
             let eth_block_index = priority_op.eth_block_index.map(|a| a as i32);
+            let eth_block_hash = priority_op
+                .eth_block_hash
+                .map(|hash| hash.as_bytes().to_vec());
+            let l1_surplus = l1_surplus_to_stored(&priority_op.l1_surplus);
 
             sqlx::query!(
                 r#"INSERT INTO mempool_reverted_txs_meta (
@@ -977,9 +1791,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 "INSERT INTO mempool_priority_operations (
                     serial_id, data, l1_address, l2_address, 
                     type, deadline_block, eth_hash, tx_hash, eth_block, 
-                    eth_block_index, created_at, confirmed, reverted
+                    eth_block_index, created_at, confirmed, reverted, eth_block_hash, l1_surplus
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, true, true)",
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, true, true, $12, $13)",
                 serial_id,
                 data,
                 l1_address,
@@ -990,7 +1804,9 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
                 tx_hash,
                 eth_block,
                 eth_block_index,
-                created_at
+                created_at,
+                eth_block_hash,
+                l1_surplus
             )
             .execute(transaction.conn())
             .await?;
@@ -1004,6 +1820,11 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
             .await?;
         }
 
+        transaction
+            .chain()
+            .operations_schema()
+            .revert_priority_op_surplus(last_block_number)
+            .await?;
         sqlx::query!(
             r"DELETE FROM executed_priority_operations 
             WHERE block_number > $1",
@@ -1027,3 +1848,13 @@ impl<'a, 'c> MempoolSchema<'a, 'c> {
         Ok(())
     }
 }
+
+/// Returns the time until which the transactions are deferred, i.e. the latest `valid_from`
+/// of the transactions if it's in the future, `None` if all of them are valid already.
+fn deferred_until(txs: &[SignedZkSyncTx]) -> Option<DateTime<Utc>> {
+    let valid_from = txs.iter().map(|tx| tx.tx.valid_from()).max()?;
+    let valid_from = Utc
+        .timestamp_opt(i64::try_from(valid_from).ok()?, 0)
+        .single()?;
+    Some(valid_from).filter(|valid_from| *valid_from > Utc::now())
+}
@@ -3,12 +3,14 @@ use std::convert::TryFrom;
 
 // External imports
 use chrono::{DateTime, Utc};
-use sqlx::FromRow;
+use sqlx::{types::BigDecimal, FromRow};
 
 // Workspace imports
-use zksync_types::{PriorityOp, SignedZkSyncTx, H256};
+use zksync_api_types::StuckAccount;
+use zksync_types::{tx::TxHash, AccountId, Nonce, PriorityOp, SignedZkSyncTx, H256};
 
 // Local imports
+use crate::utils::stored_l1_surplus;
 
 #[derive(Debug, FromRow)]
 pub(crate) struct RevertedBlock {
@@ -33,6 +35,11 @@ pub(crate) struct MempoolTx {
     pub next_priority_op_serial_id: Option<i64>,
     #[allow(dead_code)]
     pub reverted: bool,
+    pub parked_at: Option<DateTime<Utc>>,
+    #[allow(dead_code)]
+    pub parked_reason: Option<String>,
+    #[allow(dead_code)]
+    pub picked: bool,
 }
 
 impl TryFrom<MempoolTx> for SignedZkSyncTx {
@@ -67,7 +74,9 @@ pub(crate) struct MempoolPriorityOp {
     pub created_at: DateTime<Utc>,
     pub eth_block: i64,
     pub eth_block_index: Option<i32>,
+    pub eth_block_hash: Option<Vec<u8>>,
     pub deadline_block: i64,
+    pub l1_surplus: Option<BigDecimal>,
 }
 
 impl From<MempoolPriorityOp> for PriorityOp {
@@ -79,6 +88,57 @@ impl From<MempoolPriorityOp> for PriorityOp {
             eth_hash: H256::from_slice(&value.eth_hash),
             eth_block: value.eth_block as u64,
             eth_block_index: value.eth_block_index.map(|i| i as u64),
+            eth_block_hash: value.eth_block_hash.map(|hash| H256::from_slice(&hash)),
+            l1_surplus: stored_l1_surplus(value.l1_surplus),
+        }
+    }
+}
+
+/// Number of the mempool transactions of the same type, along with the creation time
+/// of the oldest one.
+#[derive(Debug, Clone, FromRow)]
+pub struct MempoolTxTypeStats {
+    pub tx_type: String,
+    pub count: i64,
+    pub oldest_created_at: DateTime<Utc>,
+}
+
+/// Transaction which execution has failed at least once.
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredTxExecutionAttempts {
+    pub tx_hash: String,
+    pub tx: serde_json::Value,
+    pub eth_sign_data: Option<serde_json::Value>,
+    pub attempts: i32,
+    pub last_fail_reason: String,
+    pub last_failed_at: DateTime<Utc>,
+    /// Time the transaction was moved to the dead-letter state, if it was.
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredStuckAccount {
+    pub account_id: i64,
+    pub blocking_tx_hash: String,
+    pub blocking_nonce: i64,
+    pub reason: String,
+    pub blocked_since: DateTime<Utc>,
+    /// Number of the transactions queued behind the blocking one.
+    pub queued_txs: i64,
+}
+
+impl From<StoredStuckAccount> for StuckAccount {
+    fn from(val: StoredStuckAccount) -> Self {
+        let tx_hash =
+            hex::decode(&val.blocking_tx_hash).expect("Stored tx hash must be a valid hex");
+        Self {
+            account_id: AccountId(val.account_id as u32),
+            blocking_tx_hash: TxHash::from_slice(&tx_hash)
+                .expect("Stored tx hash must have a correct length"),
+            blocking_nonce: Nonce(val.blocking_nonce as u32),
+            reason: val.reason,
+            blocked_since: val.blocked_since,
+            queued_txs: val.queued_txs as u32,
         }
     }
 }
@@ -1,21 +1,28 @@
 // Built-in deps
-use std::time::Instant;
+use std::{convert::TryFrom, time::Instant};
 // External imports
-use chrono::{Duration, Utc};
+use chrono::{Duration, NaiveDate, Utc};
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 // Workspace imports
+use zksync_api_types::{
+    v02::pagination::{PaginationDirection, PaginationQuery},
+    PriorityOpSurplus,
+};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     tx::TxHash,
-    BlockNumber, PriorityOp, SerialId, H256,
+    Address, BlockNumber, PriorityOp, SerialId, H256,
 };
 // Local imports
 use self::records::{
     NewExecutedPriorityOperation, NewExecutedTransaction, StoredAggregatedOperation,
-    StoredCompleteWithdrawalsTransaction, StoredExecutedPriorityOperation, StoredPendingWithdrawal,
+    StoredBlockAggregatedOperation, StoredCompleteWithdrawalsTransaction,
+    StoredExecutedPriorityOperation, StoredPendingWithdrawal, StoredPriorityOpSurplus,
 };
 use crate::chain::operations::records::StoredExecutedTransaction;
 use crate::chain::operations_ext::OperationsExtSchema;
 use crate::ethereum::EthereumSchema;
+use crate::subsidy_campaigns::SubsidyCampaignsSchema;
 use crate::{chain::mempool::MempoolSchema, QueryResult, StorageProcessor};
 
 pub mod records;
@@ -76,6 +83,49 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         result
     }
 
+    /// Loads the aggregated operations of every type which cover the block, along with
+    /// the L1 transactions sending them. If the block was aggregated several times
+    /// with the same action type, only the latest operation is returned.
+    pub async fn get_block_aggregated_operations(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Vec<StoredBlockAggregatedOperation>> {
+        let start = Instant::now();
+        let operations = sqlx::query_as!(
+            StoredBlockAggregatedOperation,
+            r#"
+            SELECT DISTINCT ON (aggregate_operations.action_type)
+                aggregate_operations.action_type,
+                aggregate_operations.from_block,
+                aggregate_operations.to_block,
+                aggregate_operations.confirmed,
+                COALESCE(
+                    eth_operations.final_hash,
+                    (
+                        SELECT tx_hash FROM eth_tx_hashes
+                        WHERE eth_tx_hashes.eth_op_id = eth_operations.id
+                        ORDER BY eth_tx_hashes.id DESC
+                        LIMIT 1
+                    )
+                ) AS "eth_tx_hash?"
+            FROM aggregate_operations
+                LEFT JOIN eth_aggregated_ops_binding ON aggregate_operations.id = eth_aggregated_ops_binding.op_id
+                LEFT JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            WHERE $1 BETWEEN aggregate_operations.from_block AND aggregate_operations.to_block
+            ORDER BY aggregate_operations.action_type, aggregate_operations.id DESC
+            "#,
+            i64::from(*block_number)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_block_aggregated_operations",
+            start.elapsed()
+        );
+        Ok(operations)
+    }
+
     /// Retrieves transaction from the database given its hash.
     pub(crate) async fn get_executed_operation(
         &mut self,
@@ -138,6 +188,29 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(op)
     }
 
+    /// Retrieves the priority operations targeting the address that are executed after the given block.
+    pub async fn get_executed_priority_ops_after_block(
+        &mut self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> QueryResult<Vec<StoredExecutedPriorityOperation>> {
+        let start = Instant::now();
+        let ops = sqlx::query_as!(
+            StoredExecutedPriorityOperation,
+            "SELECT * FROM executed_priority_operations WHERE to_account = $1 AND block_number > $2 ORDER BY priority_op_serialid",
+            address.as_bytes(),
+            i64::from(*block_number)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_executed_priority_ops_after_block",
+            start.elapsed()
+        );
+        Ok(ops)
+    }
+
     pub async fn confirm_aggregated_operations(
         &mut self,
         first_block: BlockNumber,
@@ -237,28 +310,36 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         if let Some(seq_no) = sequence_number {
             let mut addresses = Vec::new();
             let mut tokens = Vec::new();
+            let mut fee_token_flags = Vec::new();
             for address in operation.affected_accounts {
                 for token in operation.used_tokens.iter() {
                     addresses.push(address.clone());
                     tokens.push(*token);
+                    fee_token_flags.push(operation.fee_only_token == Some(*token));
                 }
             }
             sqlx::query!(
                 "
-                INSERT INTO tx_filters (address, token, tx_hash, sequence_number, is_priority)
-                SELECT u.address, u.token, $3, $4, false
-                    FROM UNNEST ($1::bytea[], $2::integer[])
-                    AS u(address, token)
+                INSERT INTO tx_filters (address, token, tx_hash, sequence_number, is_priority, is_fee_token)
+                SELECT u.address, u.token, $3, $4, false, u.is_fee_token
+                    FROM UNNEST ($1::bytea[], $2::integer[], $5::boolean[])
+                    AS u(address, token, is_fee_token)
                 ON CONFLICT ON CONSTRAINT tx_filters_pkey DO NOTHING
                 ",
                 &addresses,
                 &tokens,
                 &operation.tx_hash,
-                &seq_no
+                &seq_no,
+                &fee_token_flags
             )
             .execute(transaction.conn())
             .await?;
         }
+        // The subsidy is charged to the campaign budget in the same transaction, so the budget
+        // is spent only by the transactions which are actually executed.
+        SubsidyCampaignsSchema(&mut transaction)
+            .settle_campaign_charges(&operation.tx_hash, operation.success)
+            .await?;
         transaction.commit().await?;
         metrics::histogram!("sql.chain.operations.store_executed_tx", start.elapsed());
         // It's almost impossible situation, but it could be triggered in tests
@@ -269,7 +350,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
-    /// Removes all rejected transactions with an age greater than `max_age` from the database.
+    /// Removes all rejected transactions with an age greater than `max_age` from the database,
+    /// along with the records of their failed execution attempts.
     pub async fn remove_rejected_transactions(&mut self, max_age: Duration) -> QueryResult<()> {
         let start = Instant::now();
 
@@ -300,6 +382,14 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         )
         .execute(transaction.conn())
         .await?;
+        // Failed execution attempts are forgotten along with the rejected transactions,
+        // unless the transaction is dead-lettered and waits for a manual action.
+        sqlx::query!(
+            "DELETE FROM tx_execution_attempts WHERE dead_lettered_at IS NULL AND last_failed_at < $1",
+            offset
+        )
+        .execute(transaction.conn())
+        .await?;
 
         transaction.commit().await?;
 
@@ -310,6 +400,137 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Moves the payloads of the aggregated operations confirmed more than `retention_blocks` blocks
+    /// before the last executed block to `aggregate_operations_archive`, keeping the rest of the operation intact.
+    /// If `archive` is `false`, the payloads are dropped and can't be restored.
+    ///
+    /// At most `batch_size` operations are processed at once to keep the locks short,
+    /// the amount of the processed operations is returned, so the method is expected to be
+    /// called until it returns zero.
+    pub async fn archive_aggregated_operations(
+        &mut self,
+        retention_blocks: u32,
+        batch_size: u32,
+        archive: bool,
+    ) -> QueryResult<usize> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let last_executed_block = OperationsSchema(&mut transaction)
+            .get_last_block_by_aggregated_action(AggregatedActionType::ExecuteBlocks, Some(true))
+            .await?;
+        let max_block = last_executed_block.saturating_sub(retention_blocks);
+        let operations = sqlx::query!(
+            r#"
+            SELECT id, arguments FROM aggregate_operations
+            WHERE confirmed = true AND to_block <= $1
+                AND NOT EXISTS (SELECT 1 FROM aggregate_operations_archive WHERE op_id = aggregate_operations.id)
+            ORDER BY id ASC
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+            i64::from(max_block),
+            i64::from(batch_size)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let ids: Vec<i64> = operations.iter().map(|op| op.id).collect();
+        if archive {
+            let payloads = operations
+                .iter()
+                .map(|op| compress_arguments(&op.arguments))
+                .collect::<QueryResult<Vec<_>>>()?;
+            sqlx::query!(
+                "INSERT INTO aggregate_operations_archive (op_id, arguments)
+                SELECT * FROM UNNEST ($1::bigint[], $2::bytea[])",
+                &ids,
+                &payloads
+            )
+            .execute(transaction.conn())
+            .await?;
+        } else {
+            sqlx::query!(
+                "INSERT INTO aggregate_operations_archive (op_id)
+                SELECT * FROM UNNEST ($1::bigint[])",
+                &ids
+            )
+            .execute(transaction.conn())
+            .await?;
+        }
+        sqlx::query!(
+            "UPDATE aggregate_operations SET arguments = 'null'::jsonb WHERE id = ANY ($1)",
+            &ids
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.archive_aggregated_operations",
+            start.elapsed()
+        );
+        Ok(ids.len())
+    }
+
+    /// Moves the archived payload of the aggregated operation back to `aggregate_operations`.
+    /// Returns `false` if there is nothing to restore, i.e. the payload is not archived or was truncated.
+    pub async fn restore_aggregated_operation(&mut self, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let arguments = match OperationsSchema(&mut transaction)
+            .load_archived_arguments(id)
+            .await?
+        {
+            Some(arguments) => arguments,
+            None => return Ok(false),
+        };
+        sqlx::query!(
+            "UPDATE aggregate_operations SET arguments = $1 WHERE id = $2",
+            arguments,
+            id
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            "DELETE FROM aggregate_operations_archive WHERE op_id = $1",
+            id
+        )
+        .execute(transaction.conn())
+        .await?;
+
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.restore_aggregated_operation",
+            start.elapsed()
+        );
+        Ok(true)
+    }
+
+    /// Loads the archived payload of the aggregated operation, `None` if it's not archived or was truncated.
+    async fn load_archived_arguments(&mut self, id: i64) -> QueryResult<Option<serde_json::Value>> {
+        let start = Instant::now();
+        let payload = sqlx::query!(
+            "SELECT arguments FROM aggregate_operations_archive WHERE op_id = $1",
+            id
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|row| row.arguments);
+        let arguments = payload
+            .map(|payload| decompress_arguments(&payload))
+            .transpose()?;
+
+        metrics::histogram!(
+            "sql.chain.operations.load_archived_arguments",
+            start.elapsed()
+        );
+        Ok(arguments)
+    }
+
     /// Stores executed priority operation in database.
     ///
     /// This method is made public to fill the database for tests, do not use it for
@@ -328,8 +549,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
 
         let sequence_number: Option<i64>= sqlx::query!(
             "INSERT INTO executed_priority_operations (block_number, block_index, operation, from_account, to_account,
-                priority_op_serialid, deadline_block, eth_hash, eth_block, created_at, eth_block_index, tx_hash)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                priority_op_serialid, deadline_block, eth_hash, eth_block, created_at, eth_block_index, tx_hash, eth_block_hash, l1_surplus)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             ON CONFLICT (priority_op_serialid)
             DO NOTHING
             RETURNING sequence_number
@@ -346,6 +567,8 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             operation.created_at,
             operation.eth_block_index,
             operation.tx_hash,
+            operation.eth_block_hash,
+            operation.l1_surplus.clone(),
         )
         .fetch_optional(transaction.conn())
         .await?.map(|a| a.sequence_number).flatten();
@@ -369,6 +592,24 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             )
             .execute(transaction.conn())
             .await?;
+
+            // The surplus is accounted only once, when the operation is stored for the first time.
+            if let Some(surplus) = operation.l1_surplus {
+                sqlx::query!(
+                    "
+                    INSERT INTO priority_op_surplus_daily (day, token_id, surplus, ops_count)
+                    VALUES ($1, $2, $3, 1)
+                    ON CONFLICT (day, token_id)
+                    DO UPDATE SET surplus = priority_op_surplus_daily.surplus + $3,
+                        ops_count = priority_op_surplus_daily.ops_count + 1
+                    ",
+                    operation.created_at.date().naive_utc(),
+                    operation.token,
+                    surplus
+                )
+                .execute(transaction.conn())
+                .await?;
+            }
         }
 
         transaction.commit().await?;
@@ -397,6 +638,63 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(max_serial_id)
     }
 
+    /// Loads the page of the executed priority operations ordered by the serial id.
+    /// The serial id of the cursor is not required to exist, so the gaps left by the reverted
+    /// operations don't break the pagination.
+    pub async fn load_executed_priority_ops_page(
+        &mut self,
+        query: &PaginationQuery<SerialId>,
+    ) -> QueryResult<Vec<StoredExecutedPriorityOperation>> {
+        let start = Instant::now();
+        let from = i64::try_from(query.from).unwrap_or(i64::MAX);
+
+        let ops = match query.direction {
+            PaginationDirection::Newer => {
+                sqlx::query_as!(
+                    StoredExecutedPriorityOperation,
+                    "SELECT * FROM executed_priority_operations WHERE priority_op_serialid >= $1 ORDER BY priority_op_serialid ASC LIMIT $2",
+                    from,
+                    i64::from(query.limit)
+                )
+                .fetch_all(self.0.conn())
+                .await?
+            }
+            PaginationDirection::Older => {
+                sqlx::query_as!(
+                    StoredExecutedPriorityOperation,
+                    "SELECT * FROM executed_priority_operations WHERE priority_op_serialid <= $1 ORDER BY priority_op_serialid DESC LIMIT $2",
+                    from,
+                    i64::from(query.limit)
+                )
+                .fetch_all(self.0.conn())
+                .await?
+            }
+        };
+
+        metrics::histogram!(
+            "sql.chain.operations.load_executed_priority_ops_page",
+            start.elapsed()
+        );
+        Ok(ops)
+    }
+
+    /// Returns the number of the executed priority operations.
+    pub async fn get_executed_priority_ops_count(&mut self) -> QueryResult<u32> {
+        let start = Instant::now();
+
+        let count =
+            sqlx::query!(r#"SELECT COUNT(*) as "count!" FROM executed_priority_operations"#)
+                .fetch_one(self.0.conn())
+                .await?
+                .count;
+
+        metrics::histogram!(
+            "sql.chain.operations.get_executed_priority_ops_count",
+            start.elapsed()
+        );
+        Ok(count as u32)
+    }
+
     /// On old contracts, a separate operation was used to withdraw - `CompleteWithdrawals`.
     ///
     /// NOTE: Currently `CompleteWithdrawals` is deprecated but the information is still stored
@@ -595,13 +893,28 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             i64::from(*block_number)
         )
         .fetch_optional(self.0.conn())
-        .await?
-        .map(|op| {
-            (
-                op.id,
-                serde_json::from_value(op.arguments).expect("unparsable aggregated op"),
-            )
-        });
+        .await?;
+
+        let aggregated_op = match aggregated_op {
+            Some(op) => {
+                // The payloads of the old operations may be moved to the archive.
+                let arguments = if op.arguments.is_null() {
+                    self.load_archived_arguments(op.id).await?.ok_or_else(|| {
+                        anyhow::format_err!(
+                            "Payload of the aggregated operation {} was truncated",
+                            op.id
+                        )
+                    })?
+                } else {
+                    op.arguments
+                };
+                Some((
+                    op.id,
+                    serde_json::from_value(arguments).expect("unparsable aggregated op"),
+                ))
+            }
+            None => None,
+        };
         Ok(aggregated_op)
     }
 
@@ -647,6 +960,9 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
             .execute(transaction.conn())
             .await?;
 
+        OperationsSchema(&mut transaction)
+            .revert_priority_op_surplus(last_block)
+            .await?;
         sqlx::query!(
             "DELETE FROM executed_priority_operations WHERE block_number > $1",
             *last_block as i64
@@ -662,6 +978,70 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 
+    /// Subtracts the surplus of the priority operations executed in blocks with number greater
+    /// than `last_block` from the daily totals. Must be called before such operations are removed.
+    pub(crate) async fn revert_priority_op_surplus(
+        &mut self,
+        last_block: BlockNumber,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "
+            UPDATE priority_op_surplus_daily
+            SET surplus = priority_op_surplus_daily.surplus - reverted.surplus,
+                ops_count = priority_op_surplus_daily.ops_count - reverted.ops_count
+            FROM (
+                SELECT (created_at AT TIME ZONE 'UTC')::date AS day,
+                    (operation -> 'priority_op' ->> 'token')::integer AS token_id,
+                    SUM(l1_surplus) AS surplus,
+                    COUNT(*) AS ops_count
+                FROM executed_priority_operations
+                WHERE block_number > $1 AND l1_surplus IS NOT NULL
+                GROUP BY day, token_id
+            ) AS reverted
+            WHERE priority_op_surplus_daily.day = reverted.day
+                AND priority_op_surplus_daily.token_id = reverted.token_id
+            ",
+            *last_block as i64
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.revert_priority_op_surplus",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the surplus of the executed priority operations summed up per token and day (UTC)
+    /// within the given range of days (inclusive).
+    pub async fn load_priority_op_surplus(
+        &mut self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> QueryResult<Vec<PriorityOpSurplus>> {
+        let start = Instant::now();
+        let surplus = sqlx::query_as!(
+            StoredPriorityOpSurplus,
+            "
+            SELECT * FROM priority_op_surplus_daily
+            WHERE day >= $1 AND day <= $2
+            ORDER BY day, token_id
+            ",
+            from,
+            to
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations.load_priority_op_surplus",
+            start.elapsed()
+        );
+        Ok(surplus.into_iter().map(Into::into).collect())
+    }
+
     // Removes aggregate operations and bindings for blocks with number greater than `last_block`
     pub async fn remove_aggregate_operations(
         &mut self,
@@ -746,3 +1126,14 @@ impl<'a, 'c> OperationsSchema<'a, 'c> {
         Ok(())
     }
 }
+
+/// Compresses the payload of the aggregated operation for `aggregate_operations_archive`.
+fn compress_arguments(arguments: &serde_json::Value) -> QueryResult<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    serde_json::to_writer(&mut encoder, arguments)?;
+    Ok(encoder.finish()?)
+}
+
+fn decompress_arguments(payload: &[u8]) -> QueryResult<serde_json::Value> {
+    Ok(serde_json::from_reader(ZlibDecoder::new(payload))?)
+}
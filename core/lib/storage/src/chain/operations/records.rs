@@ -1,10 +1,13 @@
 // External imports
 use chrono::prelude::*;
+use num::bigint::ToBigInt;
 use serde_json::value::Value;
-use sqlx::FromRow;
-use zksync_types::{PriorityOp, H256};
+use sqlx::{types::BigDecimal, FromRow};
+use zksync_types::{PriorityOp, TokenId, H256};
 // Workspace imports
+use zksync_api_types::PriorityOpSurplus;
 // Local imports
+use crate::utils::stored_l1_surplus;
 
 #[derive(Debug, Clone, FromRow, PartialEq)]
 pub struct StoredExecutedPriorityOperation {
@@ -23,6 +26,9 @@ pub struct StoredExecutedPriorityOperation {
     /// This field must be optional because of backward compatibility.
     pub eth_block_index: Option<i64>,
     pub tx_hash: Vec<u8>,
+    /// This field must be optional because of backward compatibility.
+    pub eth_block_hash: Option<Vec<u8>>,
+    pub l1_surplus: Option<BigDecimal>,
 }
 
 impl From<StoredExecutedPriorityOperation> for PriorityOp {
@@ -34,6 +40,8 @@ impl From<StoredExecutedPriorityOperation> for PriorityOp {
             eth_hash: H256::from_slice(&value.eth_hash),
             eth_block: value.eth_block as u64,
             eth_block_index: Some(value.block_index as u64),
+            eth_block_hash: value.eth_block_hash.map(|hash| H256::from_slice(&hash)),
+            l1_surplus: stored_l1_surplus(value.l1_surplus),
         }
     }
 }
@@ -79,6 +87,9 @@ pub struct NewExecutedPriorityOperation {
     /// This field must be optional because of backward compatibility.
     pub eth_block_index: Option<i64>,
     pub tx_hash: Vec<u8>,
+    /// This field must be optional because of backward compatibility.
+    pub eth_block_hash: Option<Vec<u8>>,
+    pub l1_surplus: Option<BigDecimal>,
     pub affected_accounts: Vec<Vec<u8>>,
     pub token: i32,
 }
@@ -101,6 +112,8 @@ pub(crate) struct NewExecutedTransaction {
     pub batch_id: Option<i64>,
     pub affected_accounts: Vec<Vec<u8>>,
     pub used_tokens: Vec<i32>,
+    /// The token of `used_tokens` which is used only to pay the fee.
+    pub fee_only_token: Option<i32>,
 }
 
 #[derive(Debug, Clone)]
@@ -129,3 +142,39 @@ pub struct StoredAggregatedOperation {
     pub created_at: DateTime<Utc>,
     pub confirmed: bool,
 }
+
+/// Aggregated operation covering a block along with the hash of its L1 transaction.
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredBlockAggregatedOperation {
+    pub action_type: String,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub confirmed: bool,
+    /// Final hash of the confirmed transaction, or the last sent one otherwise.
+    pub eth_tx_hash: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub(crate) struct StoredPriorityOpSurplus {
+    pub day: NaiveDate,
+    pub token_id: i32,
+    pub surplus: BigDecimal,
+    pub ops_count: i64,
+}
+
+impl From<StoredPriorityOpSurplus> for PriorityOpSurplus {
+    fn from(val: StoredPriorityOpSurplus) -> Self {
+        let surplus = val
+            .surplus
+            .to_bigint()
+            .and_then(|int| int.to_biguint())
+            .expect("Invalid priority operations surplus has been stored");
+
+        Self {
+            day: val.day,
+            token_id: TokenId(val.token_id as u32),
+            surplus,
+            ops_count: val.ops_count as u64,
+        }
+    }
+}
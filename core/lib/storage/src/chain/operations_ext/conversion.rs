@@ -2,21 +2,53 @@
 // External imports
 // Workspace imports
 use zksync_api_types::v02::transaction::{
-    ForcedExitData, L1Receipt, L1Transaction, L2Receipt, L2Transaction, Receipt, Transaction,
-    TransactionData, TxData, TxInBlockStatus, WithdrawData, WithdrawNFTData,
+    EthSignatureType, FailReasonCode, ForcedExitData, L1Receipt, L1Transaction, L1TxContext,
+    L2Receipt, L2Transaction, MintedNFT, Receipt, Transaction, TransactionData, TxData,
+    TxInBlockStatus, WithdrawData, WithdrawNFTData,
 };
 use zksync_types::{
     tx::{EthSignData, TxHash},
-    BlockNumber, EthBlockId, ZkSyncOp, ZkSyncTx, H256,
+    Address, BlockNumber, EthBlockId, ZkSyncOp, ZkSyncTx, H256,
 };
 // Local imports
 use super::records::{StorageTxData, StorageTxReceipt};
+use crate::chain::operations::records::StoredExecutedPriorityOperation;
+use crate::utils::stored_l1_surplus;
+
+impl StoredExecutedPriorityOperation {
+    pub(super) fn l1_tx_context(&self) -> Option<L1TxContext> {
+        let op: ZkSyncOp = serde_json::from_value(self.operation.clone()).ok()?;
+        L1TxContext::from_executed_op(
+            &op,
+            EthBlockId(self.eth_block as u64),
+            self.eth_block_hash.as_deref().map(H256::from_slice),
+            stored_l1_surplus(self.l1_surplus.clone()),
+        )
+    }
+}
+
+/// Status of the transaction that is not included into any block.
+/// Expired parked transactions are reported as failed, while parked ones carry the reason of parking.
+fn pending_tx_status(success: Option<bool>, fail_reason: Option<&String>) -> TxInBlockStatus {
+    match (success, fail_reason) {
+        (Some(false), _) => TxInBlockStatus::Rejected,
+        (_, Some(_)) => TxInBlockStatus::Parked,
+        _ => TxInBlockStatus::Queued,
+    }
+}
+
+pub(crate) fn fail_reason_code(fail_reason: Option<&str>) -> Option<FailReasonCode> {
+    fail_reason.map(FailReasonCode::from_fail_reason)
+}
 
 impl StorageTxReceipt {
     pub(super) fn receipt_from_storage_receipt(
         receipt: StorageTxReceipt,
         is_block_finalized: Option<bool>,
+        priority_op: Option<&StoredExecutedPriorityOperation>,
     ) -> Receipt {
+        let batch_id = receipt.batch_id.map(|id| id as u32);
+        let batch_index = batch_id.and(receipt.batch_index).map(|index| index as u32);
         if receipt.block_number.is_some() {
             let status = if receipt.success.unwrap() {
                 if is_block_finalized.unwrap() {
@@ -35,6 +67,11 @@ impl StorageTxReceipt {
                         .block_number
                         .map(|number| BlockNumber(number as u32)),
                     id: receipt.priority_op_serialid.unwrap() as u64,
+                    eth_sender: priority_op.map(|op| Address::from_slice(&op.from_account)),
+                    eth_block_hash: priority_op
+                        .and_then(|op| op.eth_block_hash.as_deref())
+                        .map(H256::from_slice),
+                    l1_surplus: priority_op.and_then(|op| stored_l1_surplus(op.l1_surplus.clone())),
                 })
             } else {
                 Receipt::L2(L2Receipt {
@@ -43,22 +80,46 @@ impl StorageTxReceipt {
                     rollup_block: receipt
                         .block_number
                         .map(|number| BlockNumber(number as u32)),
+                    block_index: receipt.block_index.map(|index| index as u32),
+                    fail_reason_code: fail_reason_code(receipt.fail_reason.as_deref()),
                     fail_reason: receipt.fail_reason,
+                    attempts_exhausted: false,
+                    batch_id,
+                    batch_index,
+                    created_at: None,
+                    eth_signature_type: None,
                 })
             }
         } else {
+            // Only the transactions in the mempool have the submission time stored along with the receipt.
+            let eth_signature_type = receipt.created_at.map(|_| {
+                let eth_sign_data: Option<EthSignData> = receipt
+                    .eth_sign_data
+                    .clone()
+                    .and_then(|sign_data| serde_json::from_value(sign_data).ok());
+                EthSignatureType::from_signature(
+                    eth_sign_data.as_ref().map(|sign_data| &sign_data.signature),
+                )
+            });
             Receipt::L2(L2Receipt {
-                status: TxInBlockStatus::Queued,
+                status: pending_tx_status(receipt.success, receipt.fail_reason.as_ref()),
                 tx_hash: TxHash::from_slice(&receipt.tx_hash).unwrap(),
                 rollup_block: None,
-                fail_reason: None,
+                block_index: None,
+                fail_reason_code: fail_reason_code(receipt.fail_reason.as_deref()),
+                fail_reason: receipt.fail_reason,
+                attempts_exhausted: false,
+                batch_id,
+                batch_index,
+                created_at: receipt.created_at,
+                eth_signature_type,
             })
         }
     }
 }
 
 impl StorageTxData {
-    pub(super) fn tx_data_from_zksync_tx(
+    pub(crate) fn tx_data_from_zksync_tx(
         tx: ZkSyncTx,
         complete_withdrawals_tx_hash: Option<H256>,
     ) -> TransactionData {
@@ -88,6 +149,8 @@ impl StorageTxData {
         data: StorageTxData,
         is_block_finalized: Option<bool>,
         complete_withdrawals_tx_hash: Option<H256>,
+        priority_op: Option<&StoredExecutedPriorityOperation>,
+        nft: Option<MintedNFT>,
     ) -> TxData {
         let tx_hash = TxHash::from_slice(&data.tx_hash).unwrap();
         let batch_id = data.batch_id.map(|id| id as u32);
@@ -122,6 +185,7 @@ impl StorageTxData {
                 block_number,
                 op,
                 status,
+                fail_reason_code: fail_reason_code(data.fail_reason.as_deref()),
                 fail_reason: data.fail_reason,
                 created_at: Some(data.created_at),
                 batch_id,
@@ -136,8 +200,9 @@ impl StorageTxData {
                 block_index: data.block_index.map(|i| i as u32),
                 block_number: None,
                 op: tx_data,
-                status: TxInBlockStatus::Queued,
-                fail_reason: None,
+                status: pending_tx_status(data.success, data.fail_reason.as_ref()),
+                fail_reason_code: fail_reason_code(data.fail_reason.as_deref()),
+                fail_reason: data.fail_reason,
                 created_at: Some(data.created_at),
                 batch_id,
             }
@@ -146,6 +211,12 @@ impl StorageTxData {
             let eth_sign_data: EthSignData = serde_json::from_value(eth_sign_data).unwrap();
             eth_sign_data.signature.to_string()
         });
-        TxData { tx, eth_signature }
+        let l1_context = priority_op.and_then(StoredExecutedPriorityOperation::l1_tx_context);
+        TxData {
+            tx,
+            eth_signature,
+            l1_context,
+            nft,
+        }
     }
 }
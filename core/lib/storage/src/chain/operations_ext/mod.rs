@@ -1,5 +1,5 @@
 // Built-in deps
-use std::time::Instant;
+use std::{collections::HashMap, time::Instant};
 
 // External imports
 use chrono::{DateTime, Utc};
@@ -7,10 +7,14 @@ use chrono::{DateTime, Utc};
 // Workspace imports
 use zksync_api_types::{
     v02::{
-        pagination::{AccountTxsRequest, PaginationDirection, PaginationQuery},
+        account::AccountActivity,
+        pagination::{
+            AccountActivityRequest, AccountTxsRequest, ActivityKind, ActivityPosition,
+            PaginationDirection, PaginationQuery,
+        },
         transaction::{
-            ApiTxBatch, BatchStatus, Receipt, Transaction, TxData, TxHashSerializeWrapper,
-            TxInBlockStatus,
+            ApiTxBatch, BatchStatus, MintedNFT, Receipt, Transaction, TxData,
+            TxHashSerializeWrapper, TxInBlockStatus,
         },
     },
     Either,
@@ -22,6 +26,7 @@ use zksync_types::{
 };
 
 // Local imports
+use self::conversion::fail_reason_code;
 use self::records::{
     AccountCreatedAt, InBlockBatchTx, PriorityOpReceiptResponse, StorageTxData, StorageTxReceipt,
     TransactionsHistoryItem, TxByHashResponse, TxReceiptResponse, Web3TxData, Web3TxReceipt,
@@ -30,6 +35,7 @@ use crate::chain::operations_ext::records::SequenceNumberRecord;
 use crate::{
     chain::{
         block::records::TransactionItem,
+        mempool::MempoolSchema,
         operations::{records::StoredExecutedPriorityOperation, OperationsSchema},
     },
     QueryResult, StorageProcessor,
@@ -90,42 +96,111 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
     }
 
     pub async fn tx_receipt_api_v02(&mut self, hash: &[u8]) -> QueryResult<Option<Receipt>> {
+        let start = Instant::now();
+        // The receipt is looked up the same way as the ones requested together.
+        let result = match TxHash::from_slice(hash) {
+            Some(tx_hash) => self.tx_receipts_api_v02(&[tx_hash]).await?.remove(&tx_hash),
+            None => None,
+        };
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.tx_receipt_api_v02",
+            start.elapsed()
+        );
+        Ok(result)
+    }
+
+    /// Loads the receipts of several transactions at once, using the same number of queries
+    /// regardless of the number of transactions. Executed priority operations are looked up
+    /// by both their zkSync and Ethereum transaction hashes. Unknown hashes are missing from the result.
+    pub async fn tx_receipts_api_v02(
+        &mut self,
+        hashes: &[TxHash],
+    ) -> QueryResult<HashMap<TxHash, Receipt>> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
-        let hash_str = hex::encode(hash);
-        let receipt: Option<StorageTxReceipt> = sqlx::query_as!(
+        let hashes_bytes: Vec<Vec<u8>> = hashes.iter().map(|hash| hash.as_ref().to_vec()).collect();
+        let hashes_str: Vec<String> = hashes
+            .iter()
+            .map(|hash| hex::encode(hash.as_ref()))
+            .collect();
+        let records: Vec<StorageTxReceipt> = sqlx::query_as!(
             StorageTxReceipt,
             r#"
                 WITH transaction AS (
                     SELECT
                         tx_hash,
                         block_number,
+                        block_index,
                         success,
                         fail_reason,
                         Null::bigint as eth_block,
-                        Null::bigint as priority_op_serialid
+                        Null::bigint as priority_op_serialid,
+                        NULLIF(batch_id, 0) as batch_id,
+                        (
+                            SELECT COUNT(*) FROM executed_transactions batch_txs
+                            WHERE batch_txs.batch_id = executed_transactions.batch_id
+                                AND batch_txs.sequence_number < executed_transactions.sequence_number
+                        ) as batch_index,
+                        Null::timestamptz as created_at,
+                        Null::jsonb as eth_sign_data,
+                        1 as source
                     FROM executed_transactions
-                    WHERE tx_hash = $1
+                    WHERE tx_hash = ANY($1)
                 ), priority_op AS (
                     SELECT
-                        tx_hash,
+                        requested.hash as tx_hash,
                         block_number,
+                        block_index,
                         true as success,
                         Null as fail_reason,
                         eth_block,
-                        priority_op_serialid
+                        priority_op_serialid,
+                        Null::bigint as batch_id,
+                        Null::bigint as batch_index,
+                        Null::timestamptz as created_at,
+                        Null::jsonb as eth_sign_data,
+                        2 as source
                     FROM executed_priority_operations
-                    WHERE tx_hash = $1 OR eth_hash = $1
+                    INNER JOIN UNNEST($1::bytea[]) AS requested(hash)
+                        ON executed_priority_operations.tx_hash = requested.hash
+                            OR executed_priority_operations.eth_hash = requested.hash
                 ), mempool_tx AS (
                     SELECT
                         decode(tx_hash, 'hex'),
                         Null::bigint as block_number,
+                        Null::integer as block_index,
                         Null::boolean as success,
-                        Null as fail_reason,
+                        parked_reason as fail_reason,
                         Null::bigint as eth_block,
-                        Null::bigint as priority_op_serialid
+                        Null::bigint as priority_op_serialid,
+                        NULLIF(batch_id, 0) as batch_id,
+                        (
+                            SELECT COUNT(*) FROM mempool_txs batch_txs
+                            WHERE batch_txs.batch_id = mempool_txs.batch_id
+                                AND batch_txs.id < mempool_txs.id
+                        ) as batch_index,
+                        created_at,
+                        eth_sign_data,
+                        3 as source
                     FROM mempool_txs
-                    WHERE tx_hash = $2
+                    WHERE tx_hash = ANY($2)
+                ), expired_tx AS (
+                    SELECT
+                        decode(tx_hash, 'hex'),
+                        Null::bigint as block_number,
+                        Null::integer as block_index,
+                        false as success,
+                        fail_reason,
+                        Null::bigint as eth_block,
+                        Null::bigint as priority_op_serialid,
+                        Null::bigint as batch_id,
+                        Null::bigint as batch_index,
+                        Null::timestamptz as created_at,
+                        Null::jsonb as eth_sign_data,
+                        4 as source
+                    FROM expired_mempool_txs
+                    WHERE tx_hash = ANY($2)
                 ),
                 everything AS (
                     SELECT * FROM transaction
@@ -133,40 +208,111 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     SELECT * FROM priority_op
                     UNION ALL
                     SELECT * FROM mempool_tx
+                    UNION ALL
+                    SELECT * FROM expired_tx
                 )
                 SELECT
                     tx_hash as "tx_hash!",
                     block_number as "block_number?",
+                    block_index as "block_index?",
                     success as "success?",
                     fail_reason as "fail_reason?",
                     eth_block as "eth_block?",
-                    priority_op_serialid as "priority_op_serialid?"
+                    priority_op_serialid as "priority_op_serialid?",
+                    batch_id as "batch_id?",
+                    batch_index as "batch_index?",
+                    created_at as "created_at?",
+                    eth_sign_data as "eth_sign_data?"
                 FROM everything
+                ORDER BY source
             "#,
-            hash,
-            &hash_str
+            &hashes_bytes,
+            &hashes_str
         )
-        .fetch_optional(transaction.conn())
+        .fetch_all(transaction.conn())
         .await?;
 
-        let result = if let Some(receipt) = receipt {
-            let is_block_finalized =
-                is_block_finalized(&mut transaction, receipt.block_number).await?;
+        // A transaction may be found in several tables at once (e.g. it's executed, but not yet
+        // removed from the mempool), the executed one takes precedence.
+        let mut storage_receipts = HashMap::new();
+        for receipt in records {
+            let tx_hash = TxHash::from_slice(&receipt.tx_hash).unwrap();
+            storage_receipts.entry(tx_hash).or_insert(receipt);
+        }
 
-            Some(StorageTxReceipt::receipt_from_storage_receipt(
-                receipt,
-                is_block_finalized,
-            ))
-        } else {
-            None
-        };
+        let last_finalized_block = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        let serial_ids: Vec<i64> = storage_receipts
+            .values()
+            .filter_map(|receipt| receipt.priority_op_serialid)
+            .collect();
+        let priority_ops: HashMap<i64, StoredExecutedPriorityOperation> = sqlx::query_as!(
+            StoredExecutedPriorityOperation,
+            "SELECT * FROM executed_priority_operations WHERE priority_op_serialid = ANY($1)",
+            &serial_ids
+        )
+        .fetch_all(transaction.conn())
+        .await?
+        .into_iter()
+        .map(|op| (op.priority_op_serialid, op))
+        .collect();
+
+        let mut receipts: HashMap<TxHash, Receipt> = storage_receipts
+            .into_iter()
+            .map(|(tx_hash, receipt)| {
+                let is_block_finalized = receipt
+                    .block_number
+                    .map(|block_number| BlockNumber(block_number as u32) <= last_finalized_block);
+                let priority_op = receipt
+                    .priority_op_serialid
+                    .and_then(|serial_id| priority_ops.get(&serial_id));
+                let receipt = StorageTxReceipt::receipt_from_storage_receipt(
+                    receipt,
+                    is_block_finalized,
+                    priority_op,
+                );
+                (tx_hash, receipt)
+            })
+            .collect();
+
+        // Dead-lettered transactions report the reason of the last failed attempt.
+        let rejected: Vec<TxHash> = receipts
+            .iter()
+            .filter_map(|(tx_hash, receipt)| match receipt {
+                Receipt::L2(receipt) if receipt.status == TxInBlockStatus::Rejected => {
+                    Some(*tx_hash)
+                }
+                _ => None,
+            })
+            .collect();
+        if !rejected.is_empty() {
+            let dead_lettered_txs = MempoolSchema(&mut transaction)
+                .get_dead_lettered_txs(&rejected)
+                .await?;
+            for dead_lettered_tx in dead_lettered_txs {
+                let tx_hash = hex::decode(&dead_lettered_tx.tx_hash)
+                    .ok()
+                    .and_then(|tx_hash| TxHash::from_slice(&tx_hash));
+                if let Some(Receipt::L2(receipt)) =
+                    tx_hash.and_then(|tx_hash| receipts.get_mut(&tx_hash))
+                {
+                    receipt.fail_reason_code =
+                        fail_reason_code(Some(&dead_lettered_tx.last_fail_reason));
+                    receipt.fail_reason = Some(dead_lettered_tx.last_fail_reason);
+                    receipt.attempts_exhausted = true;
+                }
+            }
+        }
 
         transaction.commit().await?;
         metrics::histogram!(
-            "sql.chain.operations_ext.tx_receipt_api_v02",
+            "sql.chain.operations_ext.tx_receipts_api_v02",
             start.elapsed()
         );
-        Ok(result)
+        Ok(receipts)
     }
 
     pub async fn tx_data_by_block_and_index_api_v02(
@@ -294,13 +440,28 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         Null::int as block_index,
                         created_at,
                         Null::boolean as success,
-                        Null as fail_reason,
+                        parked_reason as fail_reason,
                         Null::bytea as eth_hash,
                         Null::bigint as priority_op_serialid,
                         batch_id,
                         eth_sign_data
                     FROM mempool_txs
                     WHERE tx_hash = $2
+                ), expired_tx AS (
+                    SELECT
+                        decode(tx_hash, 'hex'),
+                        tx as op,
+                        Null::bigint as block_number,
+                        Null::int as block_index,
+                        created_at,
+                        false as success,
+                        fail_reason,
+                        Null::bytea as eth_hash,
+                        Null::bigint as priority_op_serialid,
+                        Null::bigint as batch_id,
+                        eth_sign_data
+                    FROM expired_mempool_txs
+                    WHERE tx_hash = $2
                 ),
                 everything AS (
                     SELECT * FROM transaction
@@ -308,6 +469,8 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     SELECT * FROM priority_op
                     UNION ALL
                     SELECT * FROM mempool_tx
+                    UNION ALL
+                    SELECT * FROM expired_tx
                 )
                 SELECT
                     tx_hash as "tx_hash!",
@@ -1034,6 +1197,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         query.from.address,
                         address,
                         query.from.token,
+                        query.from.include_fee_token,
                         i64::from(query.limit),
                         id_from,
                         query.direction,
@@ -1048,6 +1212,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                     .get_tx_seq_numbers_for_account(
                         query.from.address,
                         query.from.token,
+                        query.from.include_fee_token,
                         i64::from(query.limit),
                         id_from,
                         query.direction,
@@ -1076,10 +1241,12 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         .await?,
                 );
 
+                // Pages are cut by the sequence number, so the items must be ordered by it as well.
+                // Creation time of the priority operations doesn't follow the execution order.
                 txs.into_iter()
                     .sorted_by(|tx1, tx2| match query.direction {
-                        PaginationDirection::Newer => tx1.created_at.cmp(&tx2.created_at),
-                        PaginationDirection::Older => tx2.created_at.cmp(&tx1.created_at),
+                        PaginationDirection::Newer => tx1.sequence_number.cmp(&tx2.sequence_number),
+                        PaginationDirection::Older => tx2.sequence_number.cmp(&tx1.sequence_number),
                     })
                     .collect()
             };
@@ -1117,6 +1284,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         address: Address,
         second_address: Address,
         token: Option<TokenId>,
+        include_fee_token: bool,
         limit: i64,
         id_from: i64,
         direction: PaginationDirection,
@@ -1135,7 +1303,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
 
         let token_query = if token.is_some() {
-            "AND token = $3"
+            "AND token = $3 AND ($6 OR NOT is_fee_token)"
         } else {
             ""
         };
@@ -1175,6 +1343,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .bind(token.unwrap_or_default().0 as i32)
             .bind(id_from)
             .bind(limit)
+            .bind(include_fee_token)
             .fetch_all(self.0.conn())
             .await?)
     }
@@ -1183,6 +1352,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         &mut self,
         address: Address,
         token: Option<TokenId>,
+        include_fee_token: bool,
         limit: i64,
         id_from: i64,
         direction: PaginationDirection,
@@ -1201,7 +1371,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         };
 
         let token_query = if token.is_some() {
-            "AND token = $2"
+            "AND token = $2 AND ($5 OR NOT is_fee_token)"
         } else {
             ""
         };
@@ -1216,6 +1386,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
             .bind(token.unwrap_or_default().0 as i32)
             .bind(id_from)
             .bind(limit)
+            .bind(include_fee_token)
             .fetch_all(self.0.conn())
             .await?)
     }
@@ -1311,6 +1482,62 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(record.map(|record| TxHash::from_slice(&record.tx_hash).unwrap()))
     }
 
+    /// Checks whether the transaction touches the account, i.e. the account is its sender,
+    /// recipient or target.
+    pub async fn is_account_tx(&mut self, address: Address, tx_hash: TxHash) -> QueryResult<bool> {
+        let start = Instant::now();
+        let exists = sqlx::query!(
+            r#"SELECT EXISTS(SELECT 1 FROM tx_filters WHERE address = $1 AND tx_hash = $2) as "exists!""#,
+            address.as_bytes(),
+            tx_hash.as_ref()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .exists;
+
+        metrics::histogram!("sql.chain.operations_ext.is_account_tx", start.elapsed());
+        Ok(exists)
+    }
+
+    /// Returns the hash of the first operation in the block, `None` if the block is empty.
+    pub async fn get_block_first_tx_hash(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<TxHash>> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            r#"
+                WITH transactions AS (
+                    SELECT tx_hash, sequence_number
+                    FROM executed_transactions
+                    WHERE block_number = $1
+                ), priority_ops AS (
+                    SELECT tx_hash, sequence_number
+                    FROM executed_priority_operations
+                    WHERE block_number = $1
+                ), everything AS (
+                    SELECT * FROM transactions
+                    UNION ALL
+                    SELECT * FROM priority_ops
+                )
+                SELECT
+                    tx_hash as "tx_hash!"
+                FROM everything
+                ORDER BY sequence_number ASC
+                LIMIT 1
+            "#,
+            i64::from(*block_number)
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_block_first_tx_hash",
+            start.elapsed()
+        );
+        Ok(record.map(|record| TxHash::from_slice(&record.tx_hash).unwrap()))
+    }
+
     pub async fn get_block_last_tx_hash(
         &mut self,
         block_number: BlockNumber,
@@ -1349,10 +1576,38 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(record.map(|record| TxHash::from_slice(&record.tx_hash).unwrap()))
     }
 
+    /// Returns the index of the last operation in the block, `None` if the block is empty.
+    pub async fn get_block_last_tx_index(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<u32>> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            r#"
+                SELECT MAX(block_index) as "block_index"
+                FROM (
+                    SELECT block_index FROM executed_transactions WHERE block_number = $1
+                    UNION ALL
+                    SELECT block_index FROM executed_priority_operations WHERE block_number = $1
+                ) indexes
+            "#,
+            i64::from(*block_number)
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_block_last_tx_index",
+            start.elapsed()
+        );
+        Ok(record.block_index.map(|index| index as u32))
+    }
+
     pub async fn get_account_transactions_count(
         &mut self,
         address: Address,
         token: Option<TokenId>,
+        include_fee_token: bool,
         second_address: Option<Address>,
     ) -> QueryResult<u32> {
         let start = Instant::now();
@@ -1362,17 +1617,18 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 r#"
                 WITH tx_hashes AS (
                     SELECT DISTINCT tx_hash FROM tx_filters
-                    WHERE address = $1 AND ($2::boolean OR token = $3)
+                    WHERE address = $1 AND ($2::boolean OR (token = $3 AND ($5::boolean OR NOT is_fee_token)))
                     INTERSECT
                     SELECT DISTINCT tx_hash FROM tx_filters
-                    WHERE address = $4 AND ($2::boolean OR token = $3)
+                    WHERE address = $4 AND ($2::boolean OR (token = $3 AND ($5::boolean OR NOT is_fee_token)))
                 )
                 SELECT COUNT(*) as "count!" FROM tx_hashes
                 "#,
                 address.as_bytes(),
                 token.is_none(),
                 token.unwrap_or_default().0 as i32,
-                second_address.as_bytes()
+                second_address.as_bytes(),
+                include_fee_token
             )
             .fetch_one(self.0.conn())
             .await?
@@ -1382,7 +1638,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 r#"
                 WITH tx_hashes AS (
                     SELECT DISTINCT tx_hash FROM tx_filters 
-                    WHERE address = $1 AND ($2::boolean OR token = $3)
+                    WHERE address = $1 AND ($2::boolean OR (token = $3 AND ($4::boolean OR NOT is_fee_token)))
                 )
                 SELECT COUNT(*) as "count!"
                 FROM tx_hashes
@@ -1390,6 +1646,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 address.as_bytes(),
                 token.is_none(),
                 token.unwrap_or_default().0 as i32,
+                include_fee_token,
             )
             .fetch_one(self.0.conn())
             .await?
@@ -1402,6 +1659,226 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(count as u32)
     }
 
+    /// Returns the activity feed of the account: executed L2 transactions and executed
+    /// priority operations related to the account, ordered by their position in the block.
+    ///
+    /// Rejected transactions are not included, since they don't have a position in the block.
+    pub async fn get_account_activity(
+        &mut self,
+        query: &PaginationQuery<AccountActivityRequest>,
+    ) -> QueryResult<Vec<AccountActivity>> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        let position = match query.from.position.inner {
+            Either::Left(position) => position,
+            Either::Right(_) => {
+                if let Some(position) = transaction
+                    .chain()
+                    .operations_ext_schema()
+                    .get_account_last_activity_position(query.from.address)
+                    .await?
+                {
+                    position
+                } else {
+                    return Ok(Vec::new());
+                }
+            }
+        };
+
+        let query_direction = match query.direction {
+            PaginationDirection::Newer => {
+                "WHERE (block_number, block_index, kind) >= ($2, $3, $4)
+                ORDER BY block_number, block_index, kind
+                LIMIT $5"
+            }
+            PaginationDirection::Older => {
+                "WHERE (block_number, block_index, kind) <= ($2, $3, $4)
+                ORDER BY block_number DESC, block_index DESC, kind DESC
+                LIMIT $5"
+            }
+        };
+        let raw_query = format!(
+            r#"
+                WITH filters AS (
+                    SELECT DISTINCT sequence_number, is_priority FROM tx_filters
+                    WHERE address = $1
+                ), activity AS (
+                    SELECT
+                        executed_transactions.sequence_number,
+                        tx_hash,
+                        tx as op,
+                        block_number,
+                        created_at,
+                        success,
+                        fail_reason,
+                        Null::bytea as eth_hash,
+                        Null::bigint as priority_op_serialid,
+                        block_index,
+                        batch_id,
+                        0 as kind
+                    FROM filters INNER JOIN executed_transactions
+                        ON filters.sequence_number = executed_transactions.sequence_number
+                    WHERE NOT filters.is_priority AND block_index IS NOT NULL
+                    UNION ALL
+                    SELECT
+                        executed_priority_operations.sequence_number,
+                        tx_hash,
+                        operation as op,
+                        block_number,
+                        created_at,
+                        true as success,
+                        Null::text as fail_reason,
+                        eth_hash,
+                        priority_op_serialid,
+                        block_index,
+                        Null::bigint as batch_id,
+                        1 as kind
+                    FROM filters INNER JOIN executed_priority_operations
+                        ON filters.sequence_number = executed_priority_operations.sequence_number
+                    WHERE filters.is_priority
+                )
+                SELECT
+                    sequence_number,
+                    tx_hash,
+                    op,
+                    block_number,
+                    created_at,
+                    success,
+                    fail_reason,
+                    eth_hash,
+                    priority_op_serialid,
+                    block_index,
+                    batch_id
+                FROM activity
+                {}
+            "#,
+            query_direction
+        );
+        let kind: i32 = match position.kind {
+            ActivityKind::L2Tx => 0,
+            ActivityKind::PriorityOp => 1,
+        };
+        let items: Vec<TransactionItem> = sqlx::query_as(&raw_query)
+            .bind(query.from.address.as_bytes())
+            .bind(i64::from(*position.block_number))
+            .bind(position.block_index as i32)
+            .bind(kind)
+            .bind(i64::from(query.limit))
+            .fetch_all(transaction.conn())
+            .await?;
+
+        let last_finalized = transaction
+            .chain()
+            .block_schema()
+            .get_last_verified_confirmed_block()
+            .await?;
+        transaction.commit().await?;
+
+        let activity = items
+            .into_iter()
+            .map(|item| {
+                let is_priority_op = item.eth_hash.is_some();
+                let is_finalized = item.block_number as u32 <= *last_finalized;
+                let tx = TransactionItem::transaction_from_item(item, is_finalized);
+                if is_priority_op {
+                    AccountActivity::PriorityOp(tx)
+                } else {
+                    AccountActivity::L2Tx(tx)
+                }
+            })
+            .collect();
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_activity",
+            start.elapsed()
+        );
+        Ok(activity)
+    }
+
+    /// Returns the position of the latest operation in the activity feed of the account.
+    pub async fn get_account_last_activity_position(
+        &mut self,
+        address: Address,
+    ) -> QueryResult<Option<ActivityPosition>> {
+        let start = Instant::now();
+        let record = sqlx::query!(
+            r#"
+                WITH filters AS (
+                    SELECT DISTINCT sequence_number, is_priority FROM tx_filters
+                    WHERE address = $1
+                ), activity AS (
+                    SELECT block_number, block_index, 0 as kind
+                    FROM filters INNER JOIN executed_transactions
+                        ON filters.sequence_number = executed_transactions.sequence_number
+                    WHERE NOT filters.is_priority AND block_index IS NOT NULL
+                    UNION ALL
+                    SELECT block_number, block_index, 1 as kind
+                    FROM filters INNER JOIN executed_priority_operations
+                        ON filters.sequence_number = executed_priority_operations.sequence_number
+                    WHERE filters.is_priority
+                )
+                SELECT
+                    block_number as "block_number!",
+                    block_index as "block_index!",
+                    kind as "kind!"
+                FROM activity
+                ORDER BY block_number DESC, block_index DESC, kind DESC
+                LIMIT 1
+            "#,
+            address.as_bytes()
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_last_activity_position",
+            start.elapsed()
+        );
+        Ok(record.map(|record| ActivityPosition {
+            block_number: BlockNumber(record.block_number as u32),
+            kind: if record.kind == 0 {
+                ActivityKind::L2Tx
+            } else {
+                ActivityKind::PriorityOp
+            },
+            block_index: record.block_index as u32,
+        }))
+    }
+
+    /// Returns the number of operations in the activity feed of the account.
+    pub async fn get_account_activity_count(&mut self, address: Address) -> QueryResult<u32> {
+        let start = Instant::now();
+        let count = sqlx::query!(
+            r#"
+                WITH filters AS (
+                    SELECT DISTINCT sequence_number, is_priority FROM tx_filters
+                    WHERE address = $1
+                ), activity AS (
+                    SELECT executed_transactions.sequence_number
+                    FROM filters INNER JOIN executed_transactions
+                        ON filters.sequence_number = executed_transactions.sequence_number
+                    WHERE NOT filters.is_priority AND block_index IS NOT NULL
+                    UNION ALL
+                    SELECT executed_priority_operations.sequence_number
+                    FROM filters INNER JOIN executed_priority_operations
+                        ON filters.sequence_number = executed_priority_operations.sequence_number
+                    WHERE filters.is_priority
+                )
+                SELECT COUNT(*) as "count!" FROM activity
+            "#,
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .count;
+
+        metrics::histogram!(
+            "sql.chain.operations_ext.get_account_activity_count",
+            start.elapsed()
+        );
+        Ok(count as u32)
+    }
+
     /// Returns `created_at` for `block_number` fields for transaction with given hash.
     pub async fn get_tx_sequence_number_for_block(
         &mut self,
@@ -1506,8 +1983,19 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 .map(|tx| TxHashSerializeWrapper(TxHash::from_slice(&tx.tx_hash).unwrap()))
                 .collect();
             let block_number = BlockNumber(batch_data[0].block_number as u32);
-            let batch_status = if batch_data[0].success {
-                if let Some(op) = transaction
+            // Status is derived the same way as for the receipts of the single transactions.
+            let batch_status = if !batch_data.iter().all(|tx| tx.success) {
+                BatchStatus {
+                    updated_at: created_at,
+                    last_state: TxInBlockStatus::Rejected,
+                }
+            } else if transaction
+                .chain()
+                .block_schema()
+                .is_block_finalized(block_number)
+                .await?
+            {
+                let updated_at = transaction
                     .chain()
                     .operations_schema()
                     .get_stored_aggregated_operation(
@@ -1515,21 +2003,16 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                         AggregatedActionType::ExecuteBlocks,
                     )
                     .await
-                {
-                    BatchStatus {
-                        updated_at: op.created_at,
-                        last_state: TxInBlockStatus::Finalized,
-                    }
-                } else {
-                    BatchStatus {
-                        updated_at: created_at,
-                        last_state: TxInBlockStatus::Committed,
-                    }
+                    .map(|op| op.created_at)
+                    .unwrap_or(created_at);
+                BatchStatus {
+                    updated_at,
+                    last_state: TxInBlockStatus::Finalized,
                 }
             } else {
                 BatchStatus {
                     updated_at: created_at,
-                    last_state: TxInBlockStatus::Rejected,
+                    last_state: TxInBlockStatus::Committed,
                 }
             };
             Some(ApiTxBatch {
@@ -1537,6 +2020,7 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
                 transaction_hashes,
                 created_at,
                 batch_status,
+                block_number: Some(block_number),
             })
         } else {
             None
@@ -1547,23 +2031,41 @@ impl<'a, 'c> OperationsExtSchema<'a, 'c> {
         Ok(result)
     }
 
+    /// Loads the batch by its hash, `None` if the batch is unknown.
+    /// The batch is resolved even if only a part of its transactions is executed while the rest
+    /// are still in the mempool: such a batch is reported as queued, unless the executed part failed.
     pub async fn get_batch_info(&mut self, batch_hash: TxHash) -> QueryResult<Option<ApiTxBatch>> {
         let start = Instant::now();
         let mut transaction = self.0.start_transaction().await?;
 
-        let result = if let Some(batch_info) = transaction
+        let in_block = transaction
             .chain()
             .operations_ext_schema()
             .get_in_block_batch_info(batch_hash)
-            .await?
-        {
-            Some(batch_info)
-        } else {
-            transaction
-                .chain()
-                .mempool_schema()
-                .get_queued_batch_info(batch_hash)
-                .await?
+            .await?;
+        let queued = transaction
+            .chain()
+            .mempool_schema()
+            .get_queued_batch_info(batch_hash)
+            .await?;
+        let result = match (in_block, queued) {
+            (Some(mut batch), Some(queued)) => {
+                // Executed transactions stay in the mempool until it's cleaned up,
+                // so only the ones which are not executed yet are taken from there.
+                let pending: Vec<_> = queued
+                    .transaction_hashes
+                    .into_iter()
+                    .filter(|tx_hash| !batch.transaction_hashes.contains(tx_hash))
+                    .collect();
+                batch.created_at = batch.created_at.min(queued.created_at);
+                if !pending.is_empty() && batch.batch_status.last_state != TxInBlockStatus::Rejected
+                {
+                    batch.batch_status = queued.batch_status;
+                }
+                batch.transaction_hashes.extend(pending);
+                Some(batch)
+            }
+            (in_block, queued) => in_block.or(queued),
         };
         transaction.commit().await?;
 
@@ -2028,10 +2530,53 @@ async fn tx_data_from_storage(
     let complete_withdrawals_tx_hash = complete_withdrawals_tx_hash(transaction, &data).await?;
 
     let is_block_finalized = is_block_finalized(transaction, data.block_number).await?;
+    let priority_op = executed_priority_op(transaction, data.priority_op_serialid).await?;
+    let nft = minted_nft(transaction, &data).await?;
 
     Ok(StorageTxData::data_from_storage_data(
         data,
         is_block_finalized,
         complete_withdrawals_tx_hash,
+        priority_op.as_ref(),
+        nft,
     ))
 }
+
+/// Loads the token produced by the successfully executed `MintNFT` transaction.
+async fn minted_nft(
+    transaction: &mut StorageProcessor<'_>,
+    data: &StorageTxData,
+) -> QueryResult<Option<MintedNFT>> {
+    let is_executed = data.block_number.is_some() && data.success == Some(true);
+    let is_mint_nft = data.op.get("type").and_then(|tx_type| tx_type.as_str()) == Some("MintNFT");
+    if !is_executed || !is_mint_nft {
+        return Ok(None);
+    }
+
+    let tx = match serde_json::from_value(data.op.clone()) {
+        Ok(ZkSyncTx::MintNFT(tx)) => tx,
+        _ => return Ok(None),
+    };
+    // The token is recorded along with the nonce of the creator that minted it.
+    let nft = transaction
+        .chain()
+        .state_schema()
+        .get_mint_nft_update_by_creator_and_nonce(tx.creator_address, tx.nonce)
+        .await?;
+    Ok(nft.map(MintedNFT::from))
+}
+
+async fn executed_priority_op(
+    transaction: &mut StorageProcessor<'_>,
+    serial_id: Option<i64>,
+) -> QueryResult<Option<StoredExecutedPriorityOperation>> {
+    if let Some(serial_id) = serial_id {
+        transaction
+            .chain()
+            .operations_schema()
+            .get_executed_priority_operation(serial_id as u32)
+            .await
+    } else {
+        Ok(None)
+    }
+}
@@ -155,10 +155,17 @@ pub struct InBlockBatchTx {
 pub struct StorageTxReceipt {
     pub tx_hash: Vec<u8>,
     pub block_number: Option<i64>,
+    pub block_index: Option<i32>,
     pub success: Option<bool>,
     pub fail_reason: Option<String>,
     pub eth_block: Option<i64>,
     pub priority_op_serialid: Option<i64>,
+    pub batch_id: Option<i64>,
+    /// Position of the transaction within the batch, only meaningful if `batch_id` is set.
+    pub batch_index: Option<i64>,
+    /// Submission time and Ethereum signature, only set for the transactions in the mempool.
+    pub created_at: Option<DateTime<Utc>>,
+    pub eth_sign_data: Option<Value>,
 }
 
 #[derive(Debug, FromRow, PartialEq)]
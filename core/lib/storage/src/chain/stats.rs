@@ -1,12 +1,24 @@
 use std::cmp::max;
 // Built-in deps
-use std::time::Instant;
+use std::{collections::HashMap, time::Instant};
 // External imports
-
+use chrono::{DateTime, TimeZone, Utc};
+use num::{bigint::ToBigInt, BigInt, BigUint, Zero};
+use sqlx::types::BigDecimal;
 // Workspace imports
-use zksync_types::{BlockNumber, SequentialTxId};
+use zksync_types::{BlockNumber, ExecutedOperations, SequentialTxId, TokenId, ZkSyncOp, ZkSyncTx};
 // Local imports
-use crate::{QueryResult, StorageProcessor};
+use crate::{chain::block::BlockSchema, QueryResult, StorageProcessor};
+
+/// Length of the period the token volume is aggregated over, in seconds.
+const VOLUME_PERIOD_SECS: i64 = 3600;
+
+/// Transfer and withdraw volume of the token.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenVolume {
+    pub transfer: BigUint,
+    pub withdraw: BigUint,
+}
 
 /// Auxiliary schema encapsulating the stats counting logic for the storage tables.
 #[derive(Debug)]
@@ -61,4 +73,266 @@ impl<'a, 'c> StatsSchema<'a, 'c> {
             ) as u64),
         ))
     }
+
+    /// Adds the volume of the newly committed block to the token volume stats.
+    ///
+    /// Blocks are aggregated strictly in order: if some of the previous blocks are not aggregated yet
+    /// (e.g. the backfill is still in progress), the block is skipped and will be picked up by the backfill.
+    /// Returns whether the block was aggregated.
+    pub async fn accumulate_block_token_volume(
+        &mut self,
+        block_number: BlockNumber,
+        timestamp: Option<u64>,
+        ops: &[ExecutedOperations],
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let last_block = StatsSchema(&mut transaction)
+            .lock_token_volume_progress()
+            .await?;
+        let accumulated = last_block + 1 == block_number;
+        if accumulated {
+            StatsSchema(&mut transaction)
+                .apply_block_token_volume(timestamp, ops, false)
+                .await?;
+            StatsSchema(&mut transaction)
+                .set_token_volume_progress(block_number)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.chain.stats.accumulate_block_token_volume",
+            start.elapsed()
+        );
+        Ok(accumulated)
+    }
+
+    /// Aggregates the volume of at most `max_blocks` committed blocks that are not accounted
+    /// in the token volume stats yet. Returns the number of the aggregated blocks, so the caller
+    /// can repeat the call until there is nothing left to aggregate.
+    pub async fn backfill_token_volume(&mut self, max_blocks: u32) -> QueryResult<u32> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let last_block = StatsSchema(&mut transaction)
+            .lock_token_volume_progress()
+            .await?;
+        let blocks = sqlx::query!(
+            "SELECT number, timestamp FROM blocks WHERE number > $1 ORDER BY number LIMIT $2",
+            i64::from(*last_block),
+            i64::from(max_blocks)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        let mut aggregated = 0;
+        for block in blocks {
+            let block_number = BlockNumber(block.number as u32);
+            let ops = BlockSchema(&mut transaction)
+                .get_block_executed_ops(block_number)
+                .await?;
+            StatsSchema(&mut transaction)
+                .apply_block_token_volume(block.timestamp.map(|ts| ts as u64), &ops, false)
+                .await?;
+            StatsSchema(&mut transaction)
+                .set_token_volume_progress(block_number)
+                .await?;
+            aggregated += 1;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.stats.backfill_token_volume", start.elapsed());
+        Ok(aggregated)
+    }
+
+    /// Subtracts the volume of the blocks with number greater than `last_block` from the token volume stats.
+    /// Must be called before the executed operations of the blocks are removed.
+    pub async fn revert_token_volume(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        let last_aggregated_block = StatsSchema(&mut transaction)
+            .lock_token_volume_progress()
+            .await?;
+        let blocks = sqlx::query!(
+            "SELECT number, timestamp FROM blocks WHERE number > $1 AND number <= $2",
+            i64::from(*last_block),
+            i64::from(*last_aggregated_block)
+        )
+        .fetch_all(transaction.conn())
+        .await?;
+
+        for block in blocks {
+            let ops = BlockSchema(&mut transaction)
+                .get_block_executed_ops(BlockNumber(block.number as u32))
+                .await?;
+            StatsSchema(&mut transaction)
+                .apply_block_token_volume(block.timestamp.map(|ts| ts as u64), &ops, true)
+                .await?;
+        }
+        if last_aggregated_block > last_block {
+            StatsSchema(&mut transaction)
+                .set_token_volume_progress(last_block)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        metrics::histogram!("sql.chain.stats.revert_token_volume", start.elapsed());
+        Ok(())
+    }
+
+    /// Returns the volume of the token in the blocks created since the start of the hour of `since`.
+    pub async fn load_token_volume(
+        &mut self,
+        token_id: TokenId,
+        since: DateTime<Utc>,
+    ) -> QueryResult<TokenVolume> {
+        let start = Instant::now();
+        let volume = sqlx::query!(
+            r#"
+            SELECT SUM(transfer_volume) as transfer_volume, SUM(withdraw_volume) as withdraw_volume
+            FROM token_volume_stats
+            WHERE token_id = $1 AND hour >= $2
+            "#,
+            *token_id as i32,
+            volume_period_start(since)
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        let to_biguint = |amount: Option<BigDecimal>| {
+            amount
+                .and_then(|amount| amount.to_bigint())
+                .and_then(|amount| amount.to_biguint())
+                .unwrap_or_default()
+        };
+        metrics::histogram!("sql.chain.stats.load_token_volume", start.elapsed());
+        Ok(TokenVolume {
+            transfer: to_biguint(volume.transfer_volume),
+            withdraw: to_biguint(volume.withdraw_volume),
+        })
+    }
+
+    /// Returns the last block accounted in the token volume stats, locking the progress
+    /// until the end of the transaction.
+    async fn lock_token_volume_progress(&mut self) -> QueryResult<BlockNumber> {
+        let last_block =
+            sqlx::query!("SELECT last_block FROM token_volume_stats_progress FOR UPDATE")
+                .fetch_one(self.0.conn())
+                .await?
+                .last_block;
+        Ok(BlockNumber(last_block as u32))
+    }
+
+    async fn set_token_volume_progress(&mut self, last_block: BlockNumber) -> QueryResult<()> {
+        sqlx::query!(
+            "UPDATE token_volume_stats_progress SET last_block = $1",
+            i64::from(*last_block)
+        )
+        .execute(self.0.conn())
+        .await?;
+        Ok(())
+    }
+
+    async fn apply_block_token_volume(
+        &mut self,
+        timestamp: Option<u64>,
+        ops: &[ExecutedOperations],
+        revert: bool,
+    ) -> QueryResult<()> {
+        let volumes = block_token_volume(ops);
+        if volumes.is_empty() {
+            return Ok(());
+        }
+        // Blocks that were created before the block timestamps were introduced
+        // are attributed to the time of their operations.
+        let block_time = timestamp
+            .filter(|timestamp| *timestamp != 0)
+            .map(|timestamp| Utc.timestamp(timestamp as i64, 0))
+            .or_else(|| ops.iter().map(executed_op_time).min())
+            .unwrap_or_else(Utc::now);
+        let hour = volume_period_start(block_time);
+
+        let to_big_decimal = |amount: BigUint| {
+            let amount = BigInt::from(amount);
+            BigDecimal::from(if revert { -amount } else { amount })
+        };
+        for (token_id, volume) in volumes {
+            sqlx::query!(
+                r#"
+                INSERT INTO token_volume_stats (token_id, hour, transfer_volume, withdraw_volume)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (token_id, hour)
+                DO UPDATE SET
+                    transfer_volume = token_volume_stats.transfer_volume + EXCLUDED.transfer_volume,
+                    withdraw_volume = token_volume_stats.withdraw_volume + EXCLUDED.withdraw_volume
+                "#,
+                *token_id as i32,
+                hour,
+                to_big_decimal(volume.transfer),
+                to_big_decimal(volume.withdraw),
+            )
+            .execute(self.0.conn())
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the start of the aggregation period the given time belongs to.
+pub fn volume_period_start(time: DateTime<Utc>) -> DateTime<Utc> {
+    let timestamp = time.timestamp();
+    Utc.timestamp(timestamp - timestamp.rem_euclid(VOLUME_PERIOD_SECS), 0)
+}
+
+fn executed_op_time(op: &ExecutedOperations) -> DateTime<Utc> {
+    match op {
+        ExecutedOperations::Tx(tx) => tx.created_at,
+        ExecutedOperations::PriorityOp(op) => op.created_at,
+    }
+}
+
+/// Sums up the transfer and withdraw amounts of the successfully executed operations per token.
+fn block_token_volume(ops: &[ExecutedOperations]) -> HashMap<TokenId, TokenVolume> {
+    let mut volumes: HashMap<TokenId, TokenVolume> = HashMap::new();
+    for op in ops {
+        let (token, transfer, withdraw) = match op {
+            ExecutedOperations::Tx(tx) if tx.success => match (&tx.signed_tx.tx, &tx.op) {
+                (ZkSyncTx::Transfer(transfer), _) => {
+                    (transfer.token, transfer.amount.clone(), BigUint::zero())
+                }
+                (ZkSyncTx::Withdraw(withdraw), _) => {
+                    (withdraw.token, BigUint::zero(), withdraw.amount.clone())
+                }
+                (_, Some(ZkSyncOp::ForcedExit(forced_exit))) => {
+                    match &forced_exit.withdraw_amount {
+                        Some(amount) => (forced_exit.tx.token, BigUint::zero(), amount.0.clone()),
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            },
+            ExecutedOperations::PriorityOp(op) => match &op.op {
+                ZkSyncOp::FullExit(full_exit) => match &full_exit.withdraw_amount {
+                    Some(amount) => (
+                        full_exit.priority_op.token,
+                        BigUint::zero(),
+                        amount.0.clone(),
+                    ),
+                    None => continue,
+                },
+                _ => continue,
+            },
+            _ => continue,
+        };
+        if transfer.is_zero() && withdraw.is_zero() {
+            continue;
+        }
+        let volume = volumes.entry(token).or_default();
+        volume.transfer += transfer;
+        volume.withdraw += withdraw;
+    }
+    volumes
 }
@@ -2,9 +2,10 @@
 use std::time::Instant;
 // External imports
 // Workspace imports
-use zksync_types::Address;
+use zksync_types::{Address, ContractUpgradeEvent};
 // Local imports
-use self::records::ServerConfig;
+use self::records::{ServerConfig, StoredContractUpgrade};
+use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use crate::{QueryResult, StorageProcessor};
 
 pub mod records;
@@ -14,6 +15,9 @@ pub mod records;
 /// isn't expected to be writable within application.
 ///
 /// Currently config is added to ZKSync by the `db-insert-contract.sh` script.
+///
+/// Besides, the schema keeps track of the contract upgrades observed on-chain,
+/// so the current state of the contracts is available without the server restart.
 #[derive(Debug)]
 pub struct ConfigSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
 
@@ -29,6 +33,65 @@ impl<'a, 'c> ConfigSchema<'a, 'c> {
         Ok(config)
     }
 
+    /// Stores the completed contract upgrade. Already stored upgrades are ignored.
+    pub async fn store_contract_upgrade(
+        &mut self,
+        event: &ContractUpgradeEvent,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+
+        sqlx::query!(
+            "INSERT INTO contract_upgrades (version_id, zksync_target_addr, eth_block)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (version_id) DO NOTHING",
+            event.version_id as i64,
+            event
+                .zksync_target()
+                .map(|target| address_to_stored_string(&target)),
+            event.eth_block as i64,
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.store_contract_upgrade", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the latest completed contract upgrade, if there were any.
+    pub async fn load_last_contract_upgrade(
+        &mut self,
+    ) -> QueryResult<Option<StoredContractUpgrade>> {
+        let start = Instant::now();
+        let upgrade = sqlx::query_as!(
+            StoredContractUpgrade,
+            "SELECT * FROM contract_upgrades ORDER BY version_id DESC LIMIT 1"
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.load_last_contract_upgrade", start.elapsed());
+        Ok(upgrade)
+    }
+
+    /// Loads the current target of the main contract as set by the latest upgrade.
+    /// Returns `None` if the main contract has never been upgraded since the watching started.
+    pub async fn load_zksync_target(&mut self) -> QueryResult<Option<Address>> {
+        let start = Instant::now();
+        let target = sqlx::query!(
+            "SELECT zksync_target_addr FROM contract_upgrades
+            WHERE zksync_target_addr IS NOT NULL
+            ORDER BY version_id DESC
+            LIMIT 1"
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|record| record.zksync_target_addr)
+        .map(|target| stored_str_address_to_address(&target));
+
+        metrics::histogram!("sql.load_zksync_target", start.elapsed());
+        Ok(target)
+    }
+
     // Stores the server configuration for tests.
     #[doc(hidden)]
     #[allow(dead_code)]
@@ -1,4 +1,5 @@
 // External imports
+use chrono::{DateTime, Utc};
 use sqlx::FromRow;
 // Workspace imports
 // Local imports
@@ -10,3 +11,13 @@ pub struct ServerConfig {
     pub gov_contract_addr: Option<String>,
     pub nft_factory_addr: Option<String>,
 }
+
+/// Completed upgrade of the contracts observed on-chain.
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredContractUpgrade {
+    pub version_id: i64,
+    /// New target of the main contract, `None` if it wasn't upgraded.
+    pub zksync_target_addr: Option<String>,
+    pub eth_block: i64,
+    pub created_at: DateTime<Utc>,
+}
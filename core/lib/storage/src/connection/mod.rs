@@ -88,6 +88,14 @@ impl ConnectionPool {
 
         Self { pool }
     }
+
+    /// Creates a `ConnectionPool` object for the database at the given URL.
+    pub fn new_with_url(database_url: impl Into<String>, pool_max_size: u32) -> Self {
+        let pool = DbPool::create(database_url, pool_max_size as usize);
+
+        Self { pool }
+    }
+
     /// Creates a `StorageProcessor` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -125,4 +133,17 @@ impl ConnectionPool {
         // Attempting to get the pooled connection for the last time
         self.pool.get().await.unwrap()
     }
+
+    /// Same as `access_storage`, but fails without retries if the connection
+    /// cannot be acquired within the timeout.
+    pub async fn try_access_storage(
+        &self,
+        timeout: Duration,
+    ) -> anyhow::Result<StorageProcessor<'_>> {
+        let connection = time::timeout(timeout, self.pool.get())
+            .await
+            .map_err(|_| anyhow::format_err!("Timed out acquiring the connection to db"))?
+            .map_err(|err| anyhow::format_err!("Failed to get connection to db: {}", err))?;
+        Ok(StorageProcessor::from_pool(connection))
+    }
 }
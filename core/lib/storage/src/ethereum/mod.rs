@@ -673,10 +673,50 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         Ok(created_at)
     }
 
+    /// Returns the hash of the confirmed Ethereum transaction sent for the aggregated operation.
+    pub async fn get_aggregated_op_confirmed_hash(
+        &mut self,
+        op_id: i64,
+    ) -> QueryResult<Option<H256>> {
+        let start = Instant::now();
+        let final_hash = sqlx::query!(
+            "SELECT eth_operations.final_hash FROM eth_aggregated_ops_binding
+                INNER JOIN eth_operations ON eth_operations.id = eth_aggregated_ops_binding.eth_op_id
+            WHERE eth_aggregated_ops_binding.op_id = $1 AND eth_operations.confirmed = true
+            LIMIT 1",
+            op_id
+        )
+        .fetch_optional(self.0.conn())
+        .await?
+        .and_then(|row| row.final_hash)
+        .map(|hash| H256::from_slice(&hash));
+
+        metrics::histogram!(
+            "sql.ethereum.get_aggregated_op_confirmed_hash",
+            start.elapsed()
+        );
+        Ok(final_hash)
+    }
+
     pub async fn aggregated_op_final_hash(
         &mut self,
         block_number: BlockNumber,
     ) -> QueryResult<Option<H256>> {
+        let eth_operation = self.confirmed_execute_operation(block_number).await?;
+
+        let final_hash = eth_operation
+            .map(|eth_operation| eth_operation.final_hash.map(|hash| H256::from_slice(&hash)))
+            .flatten();
+
+        Ok(final_hash)
+    }
+
+    /// Loads the confirmed Ethereum operation which has executed the block.
+    pub async fn confirmed_execute_operation(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> QueryResult<Option<StorageETHOperation>> {
+        let start = Instant::now();
         let eth_operation = sqlx::query_as!(
             StorageETHOperation,
             "SELECT eth_operations.* FROM aggregate_operations
@@ -691,11 +731,8 @@ impl<'a, 'c> EthereumSchema<'a, 'c> {
         .fetch_optional(self.0.conn())
         .await?;
 
-        let final_hash = eth_operation
-            .map(|eth_operation| eth_operation.final_hash.map(|hash| H256::from_slice(&hash)))
-            .flatten();
-
-        Ok(final_hash)
+        metrics::histogram!("sql.ethereum.confirmed_execute_operation", start.elapsed());
+        Ok(eth_operation)
     }
 
     // Updates eth_parameters with given nonce and last block.
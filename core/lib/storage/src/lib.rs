@@ -32,11 +32,15 @@
 //!
 //! There are the following sets of schemas:
 //!
+//! - api_keys, for the API keys of the hosted API and their usage.
 //! - config, for the server config.
 //! - data_restore, for the data_restore crate.
 //! - ethereum, for the data associated with the Ethereum blockchain.
+//! - maintenance, for the fee-exempted maintenance accounts and their transactions.
 //! - prover, for the data on prover jobs, proofs, etc.
+//! - subsidy_campaigns, for the partners' fee subsidy campaigns and their budgets.
 //! - tokens, for storing and loading known tokens.
+//! - webhooks, for the webhooks notified about the account events and their deliveries.
 //! - chain - the biggest one, which includes several schemas for the ZKSync sidechain itself.
 //!
 //! The chain module includes the following schemas:
@@ -87,6 +91,7 @@ use crate::connection::{holder::ConnectionHolder, PooledConnection};
 #[cfg(test)]
 mod tests;
 
+pub mod api_keys;
 pub mod chain;
 pub mod config;
 pub mod connection;
@@ -96,11 +101,14 @@ pub mod ethereum;
 pub mod event;
 pub mod forced_exit_requests;
 pub mod listener;
+pub mod maintenance;
 pub mod misc;
 pub mod prover;
+pub mod subsidy_campaigns;
 pub mod test_data;
 pub mod tokens;
 pub mod utils;
+pub mod webhooks;
 
 use forced_exit_requests::ForcedExitRequestsSchema;
 
@@ -206,6 +214,11 @@ impl<'a> StorageProcessor<'a> {
         chain::ChainIntermediator(self)
     }
 
+    /// Gains access to the `ApiKeys` schema.
+    pub fn api_keys_schema(&mut self) -> api_keys::ApiKeysSchema<'_, 'a> {
+        api_keys::ApiKeysSchema(self)
+    }
+
     /// Gains access to the `Config` schema.
     pub fn config_schema(&mut self) -> config::ConfigSchema<'_, 'a> {
         config::ConfigSchema(self)
@@ -221,11 +234,23 @@ impl<'a> StorageProcessor<'a> {
         ethereum::EthereumSchema(self)
     }
 
+    /// Gains access to the `Maintenance` schema.
+    pub fn maintenance_schema(&mut self) -> maintenance::MaintenanceSchema<'_, 'a> {
+        maintenance::MaintenanceSchema(self)
+    }
+
     /// Gains access to the `Prover` schema.
     pub fn prover_schema(&mut self) -> prover::ProverSchema<'_, 'a> {
         prover::ProverSchema(self)
     }
 
+    /// Gains access to the `SubsidyCampaigns` schema.
+    pub fn subsidy_campaigns_schema(
+        &mut self,
+    ) -> subsidy_campaigns::SubsidyCampaignsSchema<'_, 'a> {
+        subsidy_campaigns::SubsidyCampaignsSchema(self)
+    }
+
     /// Gains access to the `Tokens` schema.
     pub fn tokens_schema(&mut self) -> tokens::TokensSchema<'_, 'a> {
         tokens::TokensSchema(self)
@@ -243,6 +268,11 @@ impl<'a> StorageProcessor<'a> {
         misc::MiscSchema(self)
     }
 
+    /// Gains access to the `Webhooks` schema.
+    pub fn webhooks_schema(&mut self) -> webhooks::WebhooksSchema<'_, 'a> {
+        webhooks::WebhooksSchema(self)
+    }
+
     fn conn(&mut self) -> &mut PgConnection {
         match &mut self.conn {
             ConnectionHolder::Pooled(conn) => conn,
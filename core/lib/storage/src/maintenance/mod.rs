@@ -0,0 +1,194 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use num::{BigInt, BigUint};
+use sqlx::types::BigDecimal;
+// Workspace imports
+use zksync_api_types::{FeeExemption, MaintenanceMode};
+use zksync_types::{tx::TxHash, Address};
+// Local imports
+use self::records::StorageFeeExemption;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Maintenance schema is capable of storing the protocol maintenance accounts that are
+/// exempted from paying fees, the journal of the transactions accepted without the fee,
+/// and the maintenance mode flag shared by all the API servers.
+#[derive(Debug)]
+pub struct MaintenanceSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> MaintenanceSchema<'a, 'c> {
+    /// Adds the address to the maintenance accounts. Returns `false` if it's already there.
+    pub async fn add_maintenance_account(&mut self, address: Address) -> QueryResult<bool> {
+        let start = Instant::now();
+        let added = sqlx::query!(
+            r#"
+            INSERT INTO maintenance_accounts (address, created_at)
+            VALUES ($1, now())
+            ON CONFLICT (address) DO NOTHING
+            "#,
+            address.as_bytes()
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.maintenance.add_maintenance_account", start.elapsed());
+        Ok(added)
+    }
+
+    /// Removes the address from the maintenance accounts. Returns `false` if it wasn't there.
+    pub async fn remove_maintenance_account(&mut self, address: Address) -> QueryResult<bool> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "DELETE FROM maintenance_accounts WHERE address = $1",
+            address.as_bytes()
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!(
+            "sql.maintenance.remove_maintenance_account",
+            start.elapsed()
+        );
+        Ok(removed)
+    }
+
+    /// Loads the maintenance accounts added through the admin API.
+    pub async fn load_maintenance_accounts(&mut self) -> QueryResult<Vec<Address>> {
+        let start = Instant::now();
+        let accounts = sqlx::query!("SELECT address FROM maintenance_accounts ORDER BY created_at")
+            .fetch_all(self.0.conn())
+            .await?
+            .into_iter()
+            .map(|row| Address::from_slice(&row.address))
+            .collect();
+
+        metrics::histogram!("sql.maintenance.load_maintenance_accounts", start.elapsed());
+        Ok(accounts)
+    }
+
+    /// Checks whether the address was added to the maintenance accounts.
+    pub async fn is_maintenance_account(&mut self, address: Address) -> QueryResult<bool> {
+        let start = Instant::now();
+        let exists = sqlx::query!(
+            r#"SELECT EXISTS(SELECT 1 FROM maintenance_accounts WHERE address = $1) as "exists!""#,
+            address.as_bytes()
+        )
+        .fetch_one(self.0.conn())
+        .await?
+        .exists;
+
+        metrics::histogram!("sql.maintenance.is_maintenance_account", start.elapsed());
+        Ok(exists)
+    }
+
+    /// Records that the transaction was accepted without paying the required fee.
+    pub async fn record_fee_exemption(
+        &mut self,
+        tx_hash: TxHash,
+        address: Address,
+        tx_type: &str,
+        required_fee: BigUint,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO fee_exemptions (tx_hash, address, tx_type, required_fee, created_at)
+            VALUES ($1, $2, $3, $4, now())
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            tx_hash.as_ref(),
+            address.as_bytes(),
+            tx_type,
+            BigDecimal::from(BigInt::from(required_fee))
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.maintenance.record_fee_exemption", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the latest fee exemptions, newest first.
+    pub async fn load_fee_exemptions(&mut self, limit: u32) -> QueryResult<Vec<FeeExemption>> {
+        let start = Instant::now();
+        let exemptions = sqlx::query_as!(
+            StorageFeeExemption,
+            "SELECT * FROM fee_exemptions ORDER BY id DESC LIMIT $1",
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(FeeExemption::from)
+        .collect();
+
+        metrics::histogram!("sql.maintenance.load_fee_exemptions", start.elapsed());
+        Ok(exemptions)
+    }
+
+    /// Filters the given transactions down to the ones accepted without paying the required fee.
+    pub async fn filter_fee_exempted_txs(
+        &mut self,
+        tx_hashes: &[TxHash],
+    ) -> QueryResult<Vec<TxHash>> {
+        let start = Instant::now();
+        let tx_hashes: Vec<_> = tx_hashes
+            .iter()
+            .map(|hash| hash.as_ref().to_vec())
+            .collect();
+        let exempted = sqlx::query!(
+            "SELECT tx_hash FROM fee_exemptions WHERE tx_hash = ANY($1)",
+            &tx_hashes
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .filter_map(|row| TxHash::from_slice(&row.tx_hash))
+        .collect();
+
+        metrics::histogram!("sql.maintenance.filter_fee_exempted_txs", start.elapsed());
+        Ok(exempted)
+    }
+
+    /// Enables or disables the maintenance mode.
+    pub async fn set_maintenance_mode(&mut self, mode: &MaintenanceMode) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO maintenance_mode (enabled, reason, updated_at)
+            VALUES ($1, $2, now())
+            ON CONFLICT (id) DO UPDATE
+            SET enabled = $1, reason = $2, updated_at = now()
+            "#,
+            mode.enabled,
+            mode.reason
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.maintenance.set_maintenance_mode", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the maintenance mode, which is disabled unless it was ever set.
+    pub async fn load_maintenance_mode(&mut self) -> QueryResult<MaintenanceMode> {
+        let start = Instant::now();
+        let mode = sqlx::query!("SELECT enabled, reason FROM maintenance_mode")
+            .fetch_optional(self.0.conn())
+            .await?
+            .map(|row| MaintenanceMode {
+                enabled: row.enabled,
+                reason: row.reason,
+            })
+            .unwrap_or_default();
+
+        metrics::histogram!("sql.maintenance.load_maintenance_mode", start.elapsed());
+        Ok(mode)
+    }
+}
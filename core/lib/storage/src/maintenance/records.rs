@@ -0,0 +1,37 @@
+// External imports
+use chrono::{DateTime, Utc};
+use num::bigint::ToBigInt;
+use sqlx::{types::BigDecimal, FromRow};
+// Workspace imports
+use zksync_api_types::FeeExemption;
+use zksync_types::{tx::TxHash, Address};
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageFeeExemption {
+    pub id: i64,
+    pub tx_hash: Vec<u8>,
+    pub address: Vec<u8>,
+    pub tx_type: String,
+    pub required_fee: BigDecimal,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StorageFeeExemption> for FeeExemption {
+    fn from(val: StorageFeeExemption) -> Self {
+        let required_fee = val
+            .required_fee
+            .to_bigint()
+            .and_then(|int| int.to_biguint())
+            // Only the non-negative integer fees are stored.
+            .expect("Invalid fee exemption has been stored");
+
+        Self {
+            id: val.id,
+            tx_hash: TxHash::from_slice(&val.tx_hash).expect("Invalid fee exemption tx hash"),
+            address: Address::from_slice(&val.address),
+            tx_type: val.tx_type,
+            required_fee,
+            created_at: val.created_at,
+        }
+    }
+}
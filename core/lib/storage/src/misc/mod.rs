@@ -4,9 +4,11 @@ use std::time::Instant;
 use sqlx::types::BigDecimal;
 // Local imports
 
-use self::records::Subsidy;
+use self::records::{StorageBootstrapMarker, Subsidy};
 use crate::{QueryResult, StorageProcessor};
 use num::ToPrimitive;
+use serde_json::Value;
+use zksync_types::Address;
 
 pub mod records;
 
@@ -64,4 +66,46 @@ impl<'a, 'c> MiscSchema<'a, 'c> {
         metrics::histogram!("sql.token.get_total_used_subsidy_for_type", start.elapsed());
         Ok(sum)
     }
+
+    /// Loads the genesis parameters the database was bootstrapped with, if any.
+    pub async fn load_bootstrap_marker(&mut self) -> QueryResult<Option<StorageBootstrapMarker>> {
+        let start = Instant::now();
+        let marker = sqlx::query_as!(
+            StorageBootstrapMarker,
+            r#"
+            SELECT genesis_root, fee_account_address, tokens, created_at
+            FROM server_bootstrap
+            "#
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.misc.load_bootstrap_marker", start.elapsed());
+        Ok(marker)
+    }
+
+    /// Stores the genesis parameters the database was bootstrapped with.
+    /// There can be only one marker, so the second call will fail.
+    pub async fn store_bootstrap_marker(
+        &mut self,
+        genesis_root: &[u8],
+        fee_account_address: Address,
+        tokens: Value,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO server_bootstrap ( genesis_root, fee_account_address, tokens )
+            VALUES ( $1, $2, $3 )
+            "#,
+            genesis_root,
+            fee_account_address.as_bytes(),
+            tokens
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.misc.store_bootstrap_marker", start.elapsed());
+        Ok(())
+    }
 }
@@ -1,5 +1,7 @@
 // External imports
-use sqlx::types::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::{types::BigDecimal, FromRow};
 // Workspace imports
 // Local imports
 use zksync_types::{tx::TxHash, TokenId};
@@ -13,3 +15,12 @@ pub struct Subsidy {
     pub full_cost_token: BigDecimal,
     pub subsidy_type: String,
 }
+
+/// Genesis parameters the database was bootstrapped with.
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageBootstrapMarker {
+    pub genesis_root: Vec<u8>,
+    pub fee_account_address: Vec<u8>,
+    pub tokens: Value,
+    pub created_at: DateTime<Utc>,
+}
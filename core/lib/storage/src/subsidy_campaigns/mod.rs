@@ -0,0 +1,266 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+// Workspace imports
+use zksync_api_types::{NewSubsidyCampaign, SubsidyCampaign};
+use zksync_types::tx::TxHash;
+// Local imports
+use self::records::StorageSubsidyCampaign;
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Subsidy campaigns schema stores the partners' fee subsidy campaigns and the subsidies
+/// of the accepted transactions reserved in their budgets.
+#[derive(Debug)]
+pub struct SubsidyCampaignsSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> SubsidyCampaignsSchema<'a, 'c> {
+    /// Stores the new campaign, it's enabled right away.
+    pub async fn create_campaign(
+        &mut self,
+        campaign: &NewSubsidyCampaign,
+    ) -> QueryResult<SubsidyCampaign> {
+        let start = Instant::now();
+        let campaign = sqlx::query_as!(
+            StorageSubsidyCampaign,
+            r#"
+            INSERT INTO subsidy_campaigns (partner, fee_types, budget_usd_scale6, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, now())
+            RETURNING *
+            "#,
+            campaign.partner,
+            serde_json::to_value(&campaign.fee_types).expect("Fee types are serializable"),
+            campaign.budget_usd_scaled as i64,
+            campaign.expires_at
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.subsidy_campaigns.create_campaign", start.elapsed());
+        Ok(campaign.into())
+    }
+
+    /// Increases the budget of the campaign. Returns `None` if there is no campaign with such id.
+    pub async fn top_up_campaign(
+        &mut self,
+        id: i64,
+        amount_usd_scaled: u64,
+    ) -> QueryResult<Option<SubsidyCampaign>> {
+        let start = Instant::now();
+        let campaign = sqlx::query_as!(
+            StorageSubsidyCampaign,
+            r#"
+            UPDATE subsidy_campaigns SET budget_usd_scale6 = budget_usd_scale6 + $2
+            WHERE id = $1
+            RETURNING *
+            "#,
+            id,
+            amount_usd_scaled as i64
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.subsidy_campaigns.top_up_campaign", start.elapsed());
+        Ok(campaign.map(Into::into))
+    }
+
+    /// Disables the campaign, so its budget is no longer spent. The subsidies of the transactions
+    /// accepted before are still charged once executed.
+    /// Returns `None` if there is no campaign with such id.
+    pub async fn disable_campaign(&mut self, id: i64) -> QueryResult<Option<SubsidyCampaign>> {
+        let start = Instant::now();
+        let campaign = sqlx::query_as!(
+            StorageSubsidyCampaign,
+            "UPDATE subsidy_campaigns SET enabled = false WHERE id = $1 RETURNING *",
+            id
+        )
+        .fetch_optional(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.subsidy_campaigns.disable_campaign", start.elapsed());
+        Ok(campaign.map(Into::into))
+    }
+
+    /// Loads all the campaigns, including the disabled and the expired ones.
+    pub async fn load_campaigns(&mut self) -> QueryResult<Vec<SubsidyCampaign>> {
+        let start = Instant::now();
+        let campaigns = sqlx::query_as!(
+            StorageSubsidyCampaign,
+            "SELECT * FROM subsidy_campaigns ORDER BY id ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.subsidy_campaigns.load_campaigns", start.elapsed());
+        Ok(campaigns.into_iter().map(Into::into).collect())
+    }
+
+    /// Loads the campaigns which are enabled, not expired at `now` and have some budget left
+    /// besides the reserved one.
+    pub async fn load_active_campaigns(
+        &mut self,
+        now: DateTime<Utc>,
+    ) -> QueryResult<Vec<SubsidyCampaign>> {
+        let start = Instant::now();
+        let campaigns = sqlx::query_as!(
+            StorageSubsidyCampaign,
+            r#"
+            SELECT * FROM subsidy_campaigns
+            WHERE enabled AND expires_at > $1
+                AND spent_usd_scale6 + reserved_usd_scale6 < budget_usd_scale6
+            ORDER BY id ASC
+            "#,
+            now
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.subsidy_campaigns.load_active_campaigns",
+            start.elapsed()
+        );
+        Ok(campaigns.into_iter().map(Into::into).collect())
+    }
+
+    /// Reserves the subsidy of the transaction being accepted in the campaign budget, it's charged
+    /// to the budget once the transaction with `tx_hash` is executed. The previous reservation for
+    /// the same transaction is released first.
+    /// Returns `false` without reserving anything if the campaign is not active at the moment
+    /// or its budget left besides the reserved one doesn't cover the subsidy.
+    pub async fn reserve_campaign_charge(
+        &mut self,
+        tx_hash: &TxHash,
+        campaign_id: i64,
+        usd_amount_scaled: u64,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+        transaction
+            .subsidy_campaigns_schema()
+            .settle_campaign_charges(tx_hash.as_ref(), false)
+            .await?;
+
+        // The budget is checked and reserved by a single statement, so the concurrent requests
+        // can't reserve more than the budget left.
+        let reserved = sqlx::query!(
+            r#"
+            UPDATE subsidy_campaigns SET reserved_usd_scale6 = reserved_usd_scale6 + $2
+            WHERE id = $1 AND enabled AND expires_at > now()
+                AND budget_usd_scale6 - spent_usd_scale6 - reserved_usd_scale6 >= $2
+            RETURNING id
+            "#,
+            campaign_id,
+            usd_amount_scaled as i64
+        )
+        .fetch_optional(transaction.conn())
+        .await?
+        .is_some();
+
+        // Otherwise the transaction is rolled back, keeping the previous reservation.
+        if reserved {
+            sqlx::query!(
+                r#"
+                INSERT INTO subsidy_campaign_charges (tx_hash, campaign_id, usd_amount_scale6, created_at)
+                VALUES ($1, $2, $3, now())
+                "#,
+                tx_hash.as_ref(),
+                campaign_id,
+                usd_amount_scaled as i64
+            )
+            .execute(transaction.conn())
+            .await?;
+            transaction.commit().await?;
+        }
+
+        metrics::histogram!(
+            "sql.subsidy_campaigns.reserve_campaign_charge",
+            start.elapsed()
+        );
+        Ok(reserved)
+    }
+
+    /// Releases the subsidies reserved for the transaction with `tx_hash`, e.g. if it's rejected.
+    pub async fn release_campaign_charges(&mut self, tx_hash: &TxHash) -> QueryResult<()> {
+        self.settle_campaign_charges(tx_hash.as_ref(), false).await
+    }
+
+    /// Releases the subsidies reserved before `created_before` for the transactions which are
+    /// neither in the mempool nor executed, i.e. were rejected, replaced or removed from the mempool.
+    /// Returns the number of the released reservations.
+    pub async fn release_stale_campaign_charges(
+        &mut self,
+        created_before: DateTime<Utc>,
+    ) -> QueryResult<u64> {
+        let start = Instant::now();
+        // Executed transactions are removed from the mempool along with settling their charges.
+        let released = sqlx::query!(
+            r#"
+            WITH charges AS (
+                DELETE FROM subsidy_campaign_charges
+                WHERE created_at < $1 AND NOT EXISTS (
+                    SELECT 1 FROM mempool_txs
+                    WHERE mempool_txs.tx_hash = encode(subsidy_campaign_charges.tx_hash, 'hex')
+                )
+                RETURNING campaign_id, usd_amount_scale6
+            ), released AS (
+                SELECT campaign_id, SUM(usd_amount_scale6)::BIGINT AS amount, COUNT(*) AS count
+                FROM charges
+                GROUP BY campaign_id
+            )
+            UPDATE subsidy_campaigns SET reserved_usd_scale6 = reserved_usd_scale6 - released.amount
+            FROM released
+            WHERE subsidy_campaigns.id = released.campaign_id
+            RETURNING released.count AS "count!"
+            "#,
+            created_before
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|row| row.count as u64)
+        .sum();
+
+        metrics::histogram!(
+            "sql.subsidy_campaigns.release_stale_campaign_charges",
+            start.elapsed()
+        );
+        Ok(released)
+    }
+
+    /// Settles the charges of the executed transaction: they are moved from the reserved budget
+    /// of the campaigns to the spent one if the transaction succeeded and are released otherwise.
+    /// Every charge is settled once, so the repeated executions of the transaction are not charged.
+    pub(crate) async fn settle_campaign_charges(
+        &mut self,
+        tx_hash: &[u8],
+        success: bool,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            WITH charges AS (
+                DELETE FROM subsidy_campaign_charges WHERE tx_hash = $1
+                RETURNING campaign_id, usd_amount_scale6
+            )
+            UPDATE subsidy_campaigns SET
+                reserved_usd_scale6 = reserved_usd_scale6 - charges.usd_amount_scale6,
+                spent_usd_scale6 = spent_usd_scale6
+                    + CASE WHEN $2 THEN charges.usd_amount_scale6 ELSE 0 END
+            FROM charges
+            WHERE subsidy_campaigns.id = charges.campaign_id
+            "#,
+            tx_hash,
+            success
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.subsidy_campaigns.settle_campaign_charges",
+            start.elapsed()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+use zksync_api_types::SubsidyCampaign;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageSubsidyCampaign {
+    pub id: i64,
+    pub partner: String,
+    pub fee_types: Value,
+    pub budget_usd_scale6: i64,
+    pub spent_usd_scale6: i64,
+    pub reserved_usd_scale6: i64,
+    pub expires_at: DateTime<Utc>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StorageSubsidyCampaign> for SubsidyCampaign {
+    fn from(val: StorageSubsidyCampaign) -> Self {
+        Self {
+            id: val.id,
+            partner: val.partner,
+            fee_types: serde_json::from_value(val.fee_types)
+                .expect("Invalid subsidy campaign fee types in the database"),
+            budget_usd_scaled: val.budget_usd_scale6 as u64,
+            spent_usd_scaled: val.spent_usd_scale6 as u64,
+            reserved_usd_scaled: val.reserved_usd_scale6 as u64,
+            expires_at: val.expires_at,
+            enabled: val.enabled,
+            created_at: val.created_at,
+        }
+    }
+}
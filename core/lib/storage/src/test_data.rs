@@ -1,7 +1,7 @@
 //! Utilities used to generate test data for tests that involve database interaction.
 
 // Built-in uses
-use std::ops::Deref;
+use std::{env, ops::Deref};
 
 // External imports
 use num::{BigUint, Zero};
@@ -10,7 +10,11 @@ use parity_crypto::publickey::{Generator, Random};
 // Workspace imports
 use zksync_crypto::params::{max_account_id, MIN_NFT_TOKEN_ID};
 use zksync_crypto::proof::{AggregatedProof, PrecomputedSampleProofs, SingleProof};
-use zksync_crypto::{ff::PrimeField, rand::Rng, Fr};
+use zksync_crypto::{
+    ff::PrimeField,
+    rand::{thread_rng, Rng, SeedableRng, XorShiftRng},
+    Fr,
+};
 use zksync_prover_utils::fs_utils::load_precomputed_proofs;
 use zksync_types::block::{IncompleteBlock, PendingBlock};
 use zksync_types::{
@@ -23,7 +27,8 @@ use zksync_types::{
     Action, Address, Operation, H256, NFT,
     {
         block::{Block, ExecutedOperations},
-        AccountId, AccountUpdate, BlockNumber, Nonce, PubKeyHash, TokenId,
+        AccountId, AccountMap, AccountUpdate, AccountUpdates, BlockNumber, Nonce, PubKeyHash,
+        TokenId,
     },
 };
 // Local imports
@@ -42,9 +47,219 @@ pub fn get_sample_aggregated_proof() -> AggregatedProof {
 /// Block size used for tests
 pub const BLOCK_SIZE_CHUNKS: usize = 100;
 
+/// Environment variable pinning the seed of the test data RNG, e.g. to reproduce a failed test.
+pub const TEST_SEED_ENV: &str = "ZKSYNC_TEST_SEED";
+
+/// Seeded RNG used to generate the test data.
+///
+/// The seed is printed once the RNG is created, so the output of a failed test contains
+/// the seed required to reproduce the same test data.
+#[derive(Debug, Clone)]
+pub struct TestRng {
+    seed: [u32; 4],
+    rng: XorShiftRng,
+}
+
+impl TestRng {
+    pub fn from_seed(seed: [u32; 4]) -> Self {
+        Self {
+            seed,
+            rng: XorShiftRng::from_seed(seed),
+        }
+    }
+
+    /// Creates the RNG with the seed taken from the `ZKSYNC_TEST_SEED` variable, or a random one if it's not set.
+    pub fn from_env() -> Self {
+        let seed = match env::var(TEST_SEED_ENV) {
+            Ok(seed) => Self::parse_seed(&seed)
+                .unwrap_or_else(|| panic!("{} should be 16 bytes in hex", TEST_SEED_ENV)),
+            // XorShift can't be seeded with zeroes.
+            Err(_) => loop {
+                let seed: [u32; 4] = thread_rng().gen();
+                if seed != [0; 4] {
+                    break seed;
+                }
+            },
+        };
+        let rng = Self::from_seed(seed);
+        println!(
+            "Test data seed: {seed}, set {env}={seed} to reproduce",
+            seed = rng.seed_hex(),
+            env = TEST_SEED_ENV
+        );
+        rng
+    }
+
+    pub fn seed(&self) -> [u32; 4] {
+        self.seed
+    }
+
+    pub fn seed_hex(&self) -> String {
+        let bytes: Vec<u8> = self
+            .seed
+            .iter()
+            .flat_map(|word| word.to_be_bytes().to_vec())
+            .collect();
+        hex::encode(bytes)
+    }
+
+    pub(crate) fn parse_seed(seed_hex: &str) -> Option<[u32; 4]> {
+        let bytes = hex::decode(seed_hex).ok()?;
+        if bytes.len() != 16 {
+            return None;
+        }
+        let mut seed = [0u32; 4];
+        for (word, chunk) in seed.iter_mut().zip(bytes.chunks(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Some(seed).filter(|seed| *seed != [0; 4])
+    }
+}
+
+impl Rng for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+}
+
+/// Relative weights of the account update kinds produced by [`gen_random_updates`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateMix {
+    /// New accounts.
+    pub creates: u32,
+    /// Balance changes of the existing accounts.
+    pub balance_updates: u32,
+    /// Public key hash changes of the existing accounts.
+    pub pubkey_changes: u32,
+    /// NFTs minted by the existing accounts.
+    pub nft_mints: u32,
+}
+
+impl UpdateMix {
+    /// Mix with no updates, meant to be combined with the struct update syntax.
+    pub fn none() -> Self {
+        Self {
+            creates: 0,
+            balance_updates: 0,
+            pubkey_changes: 0,
+            nft_mints: 0,
+        }
+    }
+}
+
+impl Default for UpdateMix {
+    fn default() -> Self {
+        Self {
+            creates: 1,
+            balance_updates: 1,
+            pubkey_changes: 1,
+            nft_mints: 1,
+        }
+    }
+}
+
+/// Generates `count` random updates consistent with the provided accounts state.
+/// The kinds of the updates are chosen according to the mix. Updates of the existing
+/// accounts fall back to creating an account while there are no accounts yet.
+pub fn gen_random_updates(
+    accounts: &AccountMap,
+    count: usize,
+    mix: UpdateMix,
+    rng: &mut TestRng,
+) -> AccountUpdates {
+    let total_weight = mix.creates + mix.balance_updates + mix.pubkey_changes + mix.nft_mints;
+    assert!(
+        total_weight > 0,
+        "Update mix should allow at least one kind of updates"
+    );
+
+    let mut accounts = accounts.clone();
+    let mut next_nft_id = accounts
+        .values()
+        .flat_map(|account| account.minted_nfts.keys())
+        .map(|token_id| token_id.0 + 1)
+        .max()
+        .unwrap_or(MIN_NFT_TOKEN_ID);
+    let mut updates = AccountUpdates::new();
+
+    for _ in 0..count {
+        // Account map iteration order is not defined, so the accounts are sorted to stay reproducible.
+        let mut account_ids: Vec<_> = accounts.keys().copied().collect();
+        account_ids.sort();
+
+        let mut choice = rng.gen_range(0, total_weight);
+        let new_updates = if account_ids.is_empty() || choice < mix.creates {
+            gen_account_create(&accounts, rng)
+        } else {
+            let account_id = account_ids[rng.gen_range(0, account_ids.len())];
+            let account = &accounts[&account_id];
+            choice -= mix.creates;
+            if choice < mix.balance_updates {
+                let token = TokenId(rng.gen_range(0, 4));
+                let new_balance = BigUint::from(rng.gen::<u64>());
+                vec![(
+                    account_id,
+                    AccountUpdate::UpdateBalance {
+                        old_nonce: account.nonce,
+                        new_nonce: account.nonce + 1,
+                        balance_update: (token, account.get_balance(token), new_balance),
+                    },
+                )]
+            } else if choice < mix.balance_updates + mix.pubkey_changes {
+                vec![(
+                    account_id,
+                    AccountUpdate::ChangePubKeyHash {
+                        old_nonce: account.nonce,
+                        old_pub_key_hash: account.pub_key_hash,
+                        new_nonce: account.nonce + 1,
+                        new_pub_key_hash: PubKeyHash { data: rng.gen() },
+                    },
+                )]
+            } else {
+                let number = next_nft_id - MIN_NFT_TOKEN_ID;
+                next_nft_id += 1;
+                generate_nft(account_id, account, number, rng)
+            }
+        };
+
+        for (account_id, update) in &new_updates {
+            let account = accounts.remove(account_id);
+            if let Some(account) = Account::apply_update(account, update.clone()) {
+                accounts.insert(*account_id, account);
+            }
+        }
+        updates.extend(new_updates);
+    }
+    updates
+}
+
+fn gen_account_create(accounts: &AccountMap, rng: &mut TestRng) -> AccountUpdates {
+    let id = loop {
+        let id = AccountId(rng.gen::<u32>() % max_account_id().0);
+        if !accounts.contains_key(&id) {
+            break id;
+        }
+    };
+    vec![(
+        id,
+        AccountUpdate::Create {
+            nonce: Nonce(rng.gen_range(0, 1_000)),
+            address: rng.gen::<[u8; 20]>().into(),
+        },
+    )]
+}
+
 /// Generates a random account with a set of changes.
-pub fn gen_acc_random_updates<R: Rng>(
-    rng: &mut R,
+pub fn gen_acc_random_updates(
+    rng: &mut TestRng,
 ) -> impl Iterator<Item = (AccountId, AccountUpdate)> {
     let id: AccountId = AccountId(rng.gen::<u32>() % max_account_id().0);
     let balance = u128::from(rng.gen::<u64>());
@@ -91,11 +306,11 @@ pub fn gen_acc_random_updates<R: Rng>(
 }
 
 /// Generates one nft.
-pub fn generate_nft<R: Rng>(
+pub fn generate_nft(
     account_id: AccountId,
     account: &Account,
     number: u32,
-    rng: &mut R,
+    rng: &mut TestRng,
 ) -> Vec<(AccountId, AccountUpdate)> {
     let nft = NFT::new(
         TokenId(MIN_NFT_TOKEN_ID + number),
@@ -0,0 +1,124 @@
+// External imports
+use chrono::NaiveDate;
+// Workspace imports
+use zksync_api_types::{ApiKeyUsage, NewApiKey};
+use zksync_types::{api_keys::ApiKeyScope, H256};
+// Local imports
+use crate::tests::db_test;
+use crate::{api_keys::ApiKeysSchema, QueryResult, StorageProcessor};
+
+fn new_api_key(scopes: Vec<ApiKeyScope>) -> NewApiKey {
+    NewApiKey {
+        name: "customer".to_string(),
+        scopes,
+        requests_per_minute: 60,
+    }
+}
+
+/// Checks that the API keys can be stored, found by the hash and revoked.
+#[db_test]
+async fn api_keys(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let read_only = ApiKeysSchema(&mut storage)
+        .create_api_key(H256::repeat_byte(1), &new_api_key(vec![ApiKeyScope::Read]))
+        .await?;
+    let full = ApiKeysSchema(&mut storage)
+        .create_api_key(
+            H256::repeat_byte(2),
+            &new_api_key(vec![ApiKeyScope::Read, ApiKeyScope::Submit]),
+        )
+        .await?;
+    assert!(read_only.allows(ApiKeyScope::Read));
+    assert!(!read_only.allows(ApiKeyScope::Submit));
+    assert!(full.allows(ApiKeyScope::Submit));
+
+    // Keys are found by the hash only.
+    assert_eq!(
+        ApiKeysSchema(&mut storage)
+            .load_api_key_by_hash(H256::repeat_byte(1))
+            .await?,
+        Some(read_only.clone())
+    );
+    assert_eq!(
+        ApiKeysSchema(&mut storage)
+            .load_api_key_by_hash(H256::repeat_byte(3))
+            .await?,
+        None
+    );
+    assert_eq!(
+        ApiKeysSchema(&mut storage).load_api_keys().await?,
+        vec![read_only.clone(), full.clone()]
+    );
+
+    // Revoked key doesn't give access to any scope, the key can't be revoked twice.
+    assert!(ApiKeysSchema(&mut storage).revoke_api_key(full.id).await?);
+    assert!(!ApiKeysSchema(&mut storage).revoke_api_key(full.id).await?);
+    let revoked = ApiKeysSchema(&mut storage)
+        .load_api_key(full.id)
+        .await?
+        .expect("key is not found");
+    assert!(revoked.revoked_at.is_some());
+    assert!(!revoked.allows(ApiKeyScope::Read));
+    Ok(())
+}
+
+/// Checks that the requests are accounted per key and day.
+#[db_test]
+async fn api_key_daily_usage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let first = ApiKeysSchema(&mut storage)
+        .create_api_key(H256::repeat_byte(1), &new_api_key(vec![ApiKeyScope::Read]))
+        .await?;
+    let second = ApiKeysSchema(&mut storage)
+        .create_api_key(H256::repeat_byte(2), &new_api_key(vec![ApiKeyScope::Read]))
+        .await?;
+    let day = |day| NaiveDate::from_ymd(2022, 8, day);
+
+    // Usage is added up with the previously recorded one.
+    ApiKeysSchema(&mut storage)
+        .record_api_key_requests(day(1), &[(first.id, 2), (second.id, 1)])
+        .await?;
+    ApiKeysSchema(&mut storage)
+        .record_api_key_requests(day(1), &[(first.id, 1)])
+        .await?;
+    ApiKeysSchema(&mut storage)
+        .record_api_key_requests(day(3), &[(first.id, 1)])
+        .await?;
+    // Empty batches are fine.
+    ApiKeysSchema(&mut storage)
+        .record_api_key_requests(day(3), &[])
+        .await?;
+
+    assert_eq!(
+        ApiKeysSchema(&mut storage)
+            .load_api_key_usage(first.id, day(1), day(3))
+            .await?,
+        vec![
+            ApiKeyUsage {
+                day: day(1),
+                requests: 3
+            },
+            ApiKeyUsage {
+                day: day(3),
+                requests: 1
+            },
+        ]
+    );
+    assert_eq!(
+        ApiKeysSchema(&mut storage)
+            .load_api_key_usage(first.id, day(2), day(3))
+            .await?,
+        vec![ApiKeyUsage {
+            day: day(3),
+            requests: 1
+        }]
+    );
+    assert_eq!(
+        ApiKeysSchema(&mut storage)
+            .load_api_key_usage(second.id, day(1), day(3))
+            .await?,
+        vec![ApiKeyUsage {
+            day: day(1),
+            requests: 1
+        }]
+    );
+    Ok(())
+}
@@ -1,10 +1,12 @@
 // External imports
+use chrono::{Duration, Utc};
 use num::{BigUint, Zero};
 // Workspace imports
+use zksync_api_types::v02::pagination::{PaginationDirection, PaginationQuery};
 use zksync_crypto::params::{MIN_NFT_TOKEN_ID, NFT_TOKEN_ID};
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, helpers::apply_updates, AccountId, AccountMap,
-    AccountUpdate, Address, BlockNumber, Nonce, Token, TokenId, TokenKind,
+    aggregated_operations::AggregatedActionType, helpers::apply_updates, Account, AccountId,
+    AccountMap, AccountUpdate, Address, BlockNumber, Nonce, Token, TokenId, TokenKind,
 };
 // Local imports
 use super::block::apply_random_updates;
@@ -41,6 +43,76 @@ async fn eth_account_type(mut storage: StorageProcessor<'_>) -> QueryResult<()>
     Ok(())
 }
 
+/// Checks that the requests disabling 2FA can't be replayed.
+#[db_test]
+async fn toggle_account_type(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let account_id = AccountId(19);
+    let now = Utc::now();
+
+    assert!(
+        AccountSchema(&mut storage)
+            .toggle_account_type(account_id, EthAccountType::No2FA(None), now)
+            .await?
+    );
+    assert!(matches!(
+        AccountSchema(&mut storage)
+            .account_type_by_id(account_id)
+            .await?,
+        Some(EthAccountType::No2FA(None))
+    ));
+
+    // Enabling 2FA is accepted even with the older timestamp.
+    assert!(
+        AccountSchema(&mut storage)
+            .toggle_account_type(
+                account_id,
+                EthAccountType::Owned,
+                now - Duration::minutes(1)
+            )
+            .await?
+    );
+    assert!(matches!(
+        AccountSchema(&mut storage)
+            .account_type_by_id(account_id)
+            .await?,
+        Some(EthAccountType::Owned)
+    ));
+
+    // The same request disabling 2FA is rejected, as well as the older one.
+    for toggled_at in [now, now - Duration::minutes(2)] {
+        assert!(
+            !AccountSchema(&mut storage)
+                .toggle_account_type(account_id, EthAccountType::No2FA(None), toggled_at)
+                .await?
+        );
+    }
+    assert!(matches!(
+        AccountSchema(&mut storage)
+            .account_type_by_id(account_id)
+            .await?,
+        Some(EthAccountType::Owned)
+    ));
+
+    // The newer one is accepted.
+    assert!(
+        AccountSchema(&mut storage)
+            .toggle_account_type(
+                account_id,
+                EthAccountType::No2FA(None),
+                now + Duration::minutes(1)
+            )
+            .await?
+    );
+    assert!(matches!(
+        AccountSchema(&mut storage)
+            .account_type_by_id(account_id)
+            .await?,
+        Some(EthAccountType::No2FA(None))
+    ));
+
+    Ok(())
+}
+
 /// Checks that stored accounts can be obtained once they're committed.
 #[db_test]
 async fn stored_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -534,3 +606,116 @@ async fn test_get_nft_owner(mut storage: StorageProcessor<'_>) -> QueryResult<()
 
     Ok(())
 }
+
+/// Checks that the NFTs owned by the account are loaded from the committed state
+/// and paginated by the token id.
+#[db_test]
+async fn test_load_account_nft_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let _lock = ACCOUNT_MUTEX.lock().await;
+    let mut rng = create_rng();
+    let account_id1 = AccountId(1);
+    let account_id2 = AccountId(2);
+    let account1 = Account::default_with_address(&Address::random());
+    let account2 = Account::default_with_address(&Address::random());
+
+    // Checks that nonexistent account has no nfts.
+    let (count, last_token_id) = AccountSchema(&mut storage)
+        .get_account_nfts_summary(account_id1)
+        .await?;
+    assert_eq!(count, 0);
+    assert_eq!(last_token_id, None);
+
+    let mut updates1 = vec![
+        (
+            account_id1,
+            AccountUpdate::Create {
+                address: account1.address,
+                nonce: Nonce(0),
+            },
+        ),
+        (
+            account_id2,
+            AccountUpdate::Create {
+                address: account2.address,
+                nonce: Nonce(0),
+            },
+        ),
+    ];
+    for number in 0..3 {
+        updates1.append(&mut generate_nft(account_id1, &account1, number, &mut rng));
+    }
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(1), &updates1, 0)
+        .await?;
+    StateSchema(&mut storage)
+        .apply_state_update(BlockNumber(1))
+        .await?;
+
+    // The second block is only committed, the nft is transferred to the second account.
+    let nft_id = |number: u32| TokenId(MIN_NFT_TOKEN_ID + number);
+    let updates2 = vec![
+        (
+            account_id1,
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(1),
+                balance_update: (nft_id(1), BigUint::from(1u32), BigUint::zero()),
+            },
+        ),
+        (
+            account_id2,
+            AccountUpdate::UpdateBalance {
+                old_nonce: Nonce(0),
+                new_nonce: Nonce(0),
+                balance_update: (nft_id(1), BigUint::zero(), BigUint::from(1u32)),
+            },
+        ),
+    ];
+    StateSchema(&mut storage)
+        .commit_state_update(BlockNumber(2), &updates2, updates1.len())
+        .await?;
+
+    let (count, last_token_id) = AccountSchema(&mut storage)
+        .get_account_nfts_summary(account_id1)
+        .await?;
+    assert_eq!(count, 2);
+    assert_eq!(last_token_id, Some(nft_id(2)));
+
+    let query = PaginationQuery {
+        from: TokenId(0),
+        limit: 10,
+        direction: PaginationDirection::Newer,
+    };
+    let nfts = AccountSchema(&mut storage)
+        .load_account_nft_page(account_id1, &query)
+        .await?;
+    let ids: Vec<_> = nfts.iter().map(|nft| nft.id).collect();
+    assert_eq!(ids, vec![nft_id(0), nft_id(2)]);
+    assert_eq!(nfts[0].creator_id, account_id1);
+    assert_eq!(nfts[0].creator_address, account1.address);
+
+    let query = PaginationQuery {
+        from: nft_id(2),
+        limit: 1,
+        direction: PaginationDirection::Older,
+    };
+    let nfts = AccountSchema(&mut storage)
+        .load_account_nft_page(account_id1, &query)
+        .await?;
+    let ids: Vec<_> = nfts.iter().map(|nft| nft.id).collect();
+    assert_eq!(ids, vec![nft_id(2)]);
+
+    let query = PaginationQuery {
+        from: TokenId(0),
+        limit: 10,
+        direction: PaginationDirection::Newer,
+    };
+    let nfts = AccountSchema(&mut storage)
+        .load_account_nft_page(account_id2, &query)
+        .await?;
+    let ids: Vec<_> = nfts.iter().map(|nft| nft.id).collect();
+    assert_eq!(ids, vec![nft_id(1)]);
+    assert_eq!(nfts[0].creator_id, account_id1);
+
+    Ok(())
+}
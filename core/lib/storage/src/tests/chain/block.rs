@@ -1,9 +1,16 @@
 // External imports
 // Workspace imports
-use zksync_api_types::v02::pagination::{
-    ApiEither, BlockAndTxHash, PaginationDirection, PaginationQuery,
+use zksync_api_types::{
+    v02::{
+        pagination::{
+            ApiEither, BlockAndTxHash, BlockAndTxIndex, BlockTimestamp, Latest,
+            PaginationDirection, PaginationQuery,
+        },
+        transaction::{Receipt, TxInBlockStatus, TxType},
+    },
+    Either,
 };
-use zksync_crypto::{convert::FeConvert, rand::XorShiftRng};
+use zksync_crypto::convert::FeConvert;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::Block,
@@ -13,7 +20,8 @@ use zksync_types::{
 };
 // Local imports
 use super::operations_ext::{
-    commit_block, commit_schema_data, setup::TransactionsHistoryTestSetup, verify_block,
+    commit_block, commit_block_at, commit_schema_data, setup::TransactionsHistoryTestSetup,
+    verify_block,
 };
 use crate::{
     chain::{
@@ -25,9 +33,9 @@ use crate::{
     test_data::{
         dummy_ethereum_tx_hash, dummy_root_hash_for_block, gen_acc_random_updates,
         gen_sample_block, gen_sample_incomplete_block, gen_sample_pending_block,
-        gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS,
+        gen_unique_aggregated_operation, TestRng, BLOCK_SIZE_CHUNKS,
     },
-    tests::{create_rng, db_test},
+    tests::db_test,
     QueryResult, StorageProcessor,
 };
 
@@ -36,7 +44,7 @@ use crate::{
 /// of generated updates.
 pub fn apply_random_updates(
     mut accounts: AccountMap,
-    rng: &mut XorShiftRng,
+    rng: &mut TestRng,
 ) -> (AccountMap, Vec<(AccountId, AccountUpdate)>) {
     let updates = (0..3)
         .flat_map(|_| gen_acc_random_updates(rng))
@@ -49,9 +57,10 @@ pub fn apply_random_updates(
 /// We apply updates for blocks 1,2 (verify 2 blocks)
 /// Make sure that we can get state for all blocks.
 #[db_test]
-async fn test_commit_rewind(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
-    let mut rng = create_rng();
-
+async fn test_commit_rewind(
+    mut storage: StorageProcessor<'_>,
+    mut rng: TestRng,
+) -> QueryResult<()> {
     // Create the input data for three blocks.
     // Data for the next block is based on previous block data.
     let (accounts_block_1, updates_block_1) = apply_random_updates(AccountMap::default(), &mut rng);
@@ -161,7 +170,10 @@ async fn test_commit_rewind(mut storage: StorageProcessor<'_>) -> QueryResult<()
 /// to load the block details by either its height, hash of the included
 /// transaction, or the root hash of the block.
 #[db_test]
-async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+async fn test_find_block_by_height_or_hash(
+    mut storage: StorageProcessor<'_>,
+    mut rng: TestRng,
+) -> QueryResult<()> {
     /// The actual test check. It obtains the block details using
     /// the `find_block_by_height_or_hash` method with different types of query,
     /// and compares them against the provided sample.
@@ -212,8 +224,6 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
     // Below the initialization of the data for the test and collecting
     // the reference block detail samples.
 
-    let mut rng = create_rng();
-
     // Required since we use `EthereumSchema` in this test.
     EthereumSchema(&mut storage).initialize_eth_data().await?;
 
@@ -351,7 +361,7 @@ async fn test_find_block_by_height_or_hash(mut storage: StorageProcessor<'_>) ->
 
 /// Checks that `load_block_page` method loads the range of blocks correctly.
 #[db_test]
-async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+async fn test_block_page(mut storage: StorageProcessor<'_>, mut rng: TestRng) -> QueryResult<()> {
     /// Loads the block range in desc order and checks that every block in the response is
     /// equal to the one obtained from `find_block_by_height_or_hash` method.
     async fn check_block_range_desc(
@@ -436,8 +446,6 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     // Below lies the initialization of the data for the test.
 
-    let mut rng = create_rng();
-
     // Required since we use `EthereumSchema` in this test.
     EthereumSchema(&mut storage).initialize_eth_data().await?;
 
@@ -564,13 +572,163 @@ async fn test_block_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the last committed block, which the `latest` pagination cursor is resolved to,
+/// gives an empty page rather than an error if there are no blocks.
+#[db_test]
+async fn block_page_of_empty_chain(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let last_block = BlockSchema(&mut storage)
+        .get_last_committed_confirmed_block()
+        .await?;
+    assert_eq!(last_block, BlockNumber(0));
+
+    for direction in vec![PaginationDirection::Older, PaginationDirection::Newer] {
+        let page = BlockSchema(&mut storage)
+            .load_block_page(&PaginationQuery {
+                from: last_block,
+                limit: 10,
+                direction,
+            })
+            .await?;
+        assert!(page.is_empty());
+    }
+
+    Ok(())
+}
+
+/// Checks that the blocks are paginated by their timestamps,
+/// and the blocks created within the same second are ordered by their numbers.
+#[db_test]
+async fn block_page_by_timestamp(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // Blocks 2, 3 and 4 are created within the same second.
+    let timestamps = [100, 200, 200, 200, 300];
+    for (block_number, timestamp) in (1..).zip(timestamps.iter()) {
+        commit_block_at(&mut storage, BlockNumber(block_number), *timestamp).await?;
+    }
+    let cursor = |timestamp, block_number: Option<u32>| BlockTimestamp {
+        timestamp,
+        block_number: block_number.map(BlockNumber),
+    };
+
+    let test_data = vec![
+        // Pages across the tie go through the blocks with the same timestamp by their numbers.
+        (
+            cursor(300, Some(5)),
+            3,
+            PaginationDirection::Older,
+            vec![5, 4, 3],
+        ),
+        (
+            cursor(200, Some(3)),
+            3,
+            PaginationDirection::Older,
+            vec![3, 2, 1],
+        ),
+        (
+            cursor(100, Some(1)),
+            3,
+            PaginationDirection::Newer,
+            vec![1, 2, 3],
+        ),
+        (
+            cursor(200, Some(4)),
+            3,
+            PaginationDirection::Newer,
+            vec![4, 5],
+        ),
+        // The cursor without the block number starts from the edge of the second.
+        (cursor(200, None), 2, PaginationDirection::Older, vec![4, 3]),
+        (cursor(200, None), 2, PaginationDirection::Newer, vec![2, 3]),
+        // The cursor between the timestamps of the blocks.
+        (
+            cursor(250, None),
+            10,
+            PaginationDirection::Older,
+            vec![4, 3, 2, 1],
+        ),
+        (
+            cursor(150, Some(1)),
+            10,
+            PaginationDirection::Newer,
+            vec![2, 3, 4, 5],
+        ),
+        (
+            cursor(u64::MAX, None),
+            1,
+            PaginationDirection::Older,
+            vec![5],
+        ),
+        (
+            cursor(u64::MAX, None),
+            1,
+            PaginationDirection::Newer,
+            vec![],
+        ),
+    ];
+    for (from, limit, direction, expected) in test_data {
+        let page = BlockSchema(&mut storage)
+            .load_block_page_by_timestamp(&PaginationQuery {
+                from,
+                limit,
+                direction,
+            })
+            .await?;
+        let actual: Vec<_> = page
+            .iter()
+            .map(|(details, _)| details.block_number as u32)
+            .collect();
+        assert_eq!(actual, expected, "from {}, direction {:?}", from, direction);
+
+        // Every block comes with its exact cursor.
+        for (details, block_cursor) in page {
+            let block_number = details.block_number as u32;
+            assert_eq!(
+                *block_cursor.block_number.unwrap(),
+                block_number,
+                "from {}, direction {:?}",
+                from,
+                direction
+            );
+            assert_eq!(
+                block_cursor.timestamp,
+                timestamps[block_number as usize - 1]
+            );
+        }
+    }
+
+    // Blocks without the timestamp are neither on the pages nor counted.
+    assert_eq!(
+        BlockSchema(&mut storage).count_timestamped_blocks().await?,
+        5
+    );
+    commit_block_at(&mut storage, BlockNumber(6), 400).await?;
+    sqlx::query("UPDATE blocks SET timestamp = NULL WHERE number = $1")
+        .bind(6i64)
+        .execute(storage.conn())
+        .await?;
+    let page = BlockSchema(&mut storage)
+        .load_block_page_by_timestamp(&PaginationQuery {
+            from: cursor(u64::MAX, None),
+            limit: 10,
+            direction: PaginationDirection::Older,
+        })
+        .await?;
+    assert_eq!(page.len(), 5);
+    assert_eq!(
+        BlockSchema(&mut storage).count_timestamped_blocks().await?,
+        5
+    );
+
+    Ok(())
+}
+
 /// Checks the correctness of the processing of committed unconfirmed transactions.
 #[db_test]
-async fn unconfirmed_transaction(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+async fn unconfirmed_transaction(
+    mut storage: StorageProcessor<'_>,
+    mut rng: TestRng,
+) -> QueryResult<()> {
     // Below lies the initialization of the data for the test.
 
-    let mut rng = create_rng();
-
     // Required since we use `EthereumSchema` in this test.
     EthereumSchema(&mut storage).initialize_eth_data().await?;
 
@@ -1225,6 +1383,7 @@ async fn test_get_block_transactions_page(mut storage: StorageProcessor<'_>) ->
                 from: BlockAndTxHash {
                     block_number: BlockNumber(1),
                     tx_hash: ApiEither::from(tx_hash),
+                    tx_type: None,
                 },
                 limit,
                 direction,
@@ -1246,6 +1405,7 @@ async fn test_get_block_transactions_page(mut storage: StorageProcessor<'_>) ->
             from: BlockAndTxHash {
                 block_number: BlockNumber(3),
                 tx_hash: ApiEither::from(setup.get_tx_hash(2, 0)),
+                tx_type: None,
             },
             limit: 1,
             direction: PaginationDirection::Newer,
@@ -1261,6 +1421,7 @@ async fn test_get_block_transactions_page(mut storage: StorageProcessor<'_>) ->
             from: BlockAndTxHash {
                 block_number: BlockNumber(2),
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 0)),
+                tx_type: None,
             },
             limit: 1,
             direction: PaginationDirection::Newer,
@@ -1268,6 +1429,252 @@ async fn test_get_block_transactions_page(mut storage: StorageProcessor<'_>) ->
         .await?;
     assert!(result.is_none());
 
+    // Check that only the operations of the requested type are returned, both L1 and L2 ones.
+    // The cursor doesn't have to be of the requested type.
+    for (tx_hash, tx_type, direction, limit, expected) in vec![
+        (
+            tx_hashes[0],
+            TxType::Transfer,
+            PaginationDirection::Newer,
+            10,
+            vec![tx_hashes[1], tx_hashes[2]],
+        ),
+        (
+            tx_hashes[9],
+            TxType::Transfer,
+            PaginationDirection::Older,
+            1,
+            vec![tx_hashes[2]],
+        ),
+        (
+            tx_hashes[3],
+            TxType::FullExit,
+            PaginationDirection::Newer,
+            10,
+            vec![tx_hashes[9]],
+        ),
+        (
+            tx_hashes[9],
+            TxType::Deposit,
+            PaginationDirection::Older,
+            10,
+            vec![tx_hashes[0]],
+        ),
+        (
+            tx_hashes[0],
+            TxType::ForcedExit,
+            PaginationDirection::Newer,
+            10,
+            vec![],
+        ),
+    ] {
+        let actual: Vec<TxHash> = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_page(&PaginationQuery {
+                from: BlockAndTxHash {
+                    block_number: BlockNumber(1),
+                    tx_hash: ApiEither::from(tx_hash),
+                    tx_type: Some(tx_type),
+                },
+                limit,
+                direction,
+            })
+            .await?
+            .unwrap()
+            .into_iter()
+            .map(|tx| tx.tx_hash)
+            .collect();
+        assert_eq!(actual, expected, "{:?} {:?}", tx_type, direction);
+    }
+
+    for (tx_type, expected_count) in vec![
+        (TxType::Transfer, 2),
+        (TxType::Deposit, 1),
+        (TxType::MintNFT, 1),
+        (TxType::ForcedExit, 0),
+    ] {
+        let count = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_count_by_type(BlockNumber(1), tx_type)
+            .await?;
+        assert_eq!(count, expected_count, "{:?}", tx_type);
+    }
+
+    Ok(())
+}
+
+/// Checks that `get_block_transactions_page_by_index` method works correctly.
+#[db_test]
+async fn test_get_block_transactions_page_by_index(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    setup.add_block(2);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let tx_hashes: Vec<_> = (0..10).map(|index| setup.get_tx_hash(0, index)).collect();
+    let latest = ApiEither {
+        inner: Either::Right(Latest),
+    };
+
+    for (from, limit, direction, expected, test_name) in vec![
+        (
+            ApiEither::from(0),
+            5,
+            PaginationDirection::Newer,
+            tx_hashes[0..5].to_vec(),
+            "First 5 txs",
+        ),
+        (
+            ApiEither::from(3),
+            4,
+            PaginationDirection::Newer,
+            tx_hashes[3..7].to_vec(),
+            "Middle 4 txs (newer)",
+        ),
+        (
+            ApiEither::from(6),
+            100,
+            PaginationDirection::Newer,
+            tx_hashes[6..].to_vec(),
+            "Big limit (newer)",
+        ),
+        (
+            ApiEither::from(5),
+            3,
+            PaginationDirection::Older,
+            tx_hashes[3..=5].iter().rev().cloned().collect(),
+            "Middle 3 txs (older)",
+        ),
+        (
+            latest,
+            2,
+            PaginationDirection::Older,
+            tx_hashes[8..].iter().rev().cloned().collect(),
+            "Last 2 txs",
+        ),
+        (
+            ApiEither::from(10),
+            5,
+            PaginationDirection::Newer,
+            Vec::new(),
+            "Index out of the block",
+        ),
+    ] {
+        let actual: Vec<TxHash> = storage
+            .chain()
+            .block_schema()
+            .get_block_transactions_page_by_index(&PaginationQuery {
+                from: BlockAndTxIndex {
+                    block_number: BlockNumber(1),
+                    block_index: from,
+                },
+                limit,
+                direction,
+            })
+            .await?
+            .into_iter()
+            .map(|tx| tx.tx_hash)
+            .collect();
+        assert_eq!(actual, expected, "\"{}\", failed", test_name);
+    }
+
+    Ok(())
+}
+
+/// Checks that `get_block_range_transactions_chunk` reads the whole block range by chunks.
+#[db_test]
+async fn test_get_block_range_transactions_chunk(
+    mut storage: StorageProcessor<'_>,
+) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    setup.add_block(2);
+    setup.add_block(3);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    // Operations of the blocks outside of the range are not loaded.
+    let expected: Vec<_> = (1..3)
+        .flat_map(|block| (0..10).map(move |index| (block, index)))
+        .map(|(block, index)| setup.get_tx_hash(block, index))
+        .collect();
+
+    let mut actual = Vec::new();
+    let mut cursor = None;
+    loop {
+        let (txs, next_cursor) = storage
+            .chain()
+            .block_schema()
+            .get_block_range_transactions_chunk(BlockNumber(2), BlockNumber(3), cursor, 7)
+            .await?;
+        if txs.is_empty() {
+            // Cursor stays the same once the range is over.
+            assert_eq!(next_cursor, cursor);
+            break;
+        }
+        assert!(txs.len() <= 7);
+        actual.extend(txs.into_iter().map(|tx| tx.tx_hash));
+        cursor = next_cursor;
+    }
+    assert_eq!(actual, expected);
+
+    Ok(())
+}
+
+/// Checks that every operation in the sealed block gets a dense index,
+/// with the failed transactions placed after the successful ones.
+#[db_test]
+async fn test_sealed_block_dense_indexes(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block_with_rejected_op(1);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let block_number = BlockNumber(1);
+    let incomplete_block = gen_sample_incomplete_block(block_number, BLOCK_SIZE_CHUNKS, Vec::new());
+    storage
+        .chain()
+        .block_schema()
+        .save_incomplete_block(&incomplete_block)
+        .await?;
+
+    let txs = storage
+        .chain()
+        .block_schema()
+        .get_block_transactions_page_by_index(&PaginationQuery {
+            from: BlockAndTxIndex {
+                block_number,
+                block_index: ApiEither::from(0),
+            },
+            limit: 100,
+            direction: PaginationDirection::Newer,
+        })
+        .await?;
+    assert_eq!(
+        txs.iter().map(|tx| tx.block_index).collect::<Vec<_>>(),
+        (0..10).map(Some).collect::<Vec<_>>()
+    );
+    // The rejected transaction is the last one in the block.
+    let rejected_tx = txs.last().unwrap();
+    assert_eq!(rejected_tx.tx_hash, setup.get_tx_hash(0, 2));
+
+    // The index is also exposed in the receipt.
+    let receipt = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_receipt_api_v02(rejected_tx.tx_hash.as_ref())
+        .await?;
+    match receipt {
+        Some(Receipt::L2(receipt)) => {
+            assert_eq!(receipt.status, TxInBlockStatus::Rejected);
+            assert_eq!(receipt.rollup_block, Some(block_number));
+            assert_eq!(receipt.block_index, Some(9));
+        }
+        receipt => panic!("Unexpected receipt: {:?}", receipt),
+    }
+
     Ok(())
 }
 
@@ -1,12 +1,16 @@
 // External imports
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
 // Workspace imports
+use zksync_api_types::v02::{
+    pagination::PaginationDirection,
+    transaction::{Receipt, TxInBlockStatus},
+};
 use zksync_crypto::rand::{Rng, SeedableRng, XorShiftRng};
 use zksync_types::{
     block::{Block, ExecutedOperations},
     mempool::SignedTxVariant,
     priority_ops::FullExit,
-    tx::{ChangePubKey, Transfer, TxHash, Withdraw},
+    tx::{ChangePubKey, TimeRange, Transfer, TxHash, Withdraw},
     AccountId, Address, BlockNumber, ExecutedPriorityOp, ExecutedTx, FullExitOp, Nonce, PriorityOp,
     SignedZkSyncTx, TokenId, ZkSyncOp, ZkSyncPriorityOp, ZkSyncTx, H256,
 };
@@ -126,6 +130,27 @@ fn gen_transfers(n: usize) -> Vec<SignedZkSyncTx> {
         .collect()
 }
 
+/// Generates a transfer transaction of the account with the given nonce.
+fn gen_transfer(account_id: AccountId, nonce: Nonce) -> SignedZkSyncTx {
+    let transfer = Transfer::new(
+        account_id,
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        100u32.into(),
+        10u32.into(),
+        nonce,
+        Default::default(),
+        None,
+    );
+
+    SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(transfer)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    }
+}
+
 /// Gets a single transaction from a `SignedTxVariant`. Panics if variant is a batch.
 fn unwrap_tx(tx: SignedTxVariant) -> SignedZkSyncTx {
     match tx {
@@ -165,6 +190,48 @@ async fn store_load(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the mempool statistics count the pending transactions of every type.
+#[db_test]
+async fn mempool_stats(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let stats = MempoolSchema(&mut storage).get_mempool_stats().await?;
+    assert!(stats.is_empty());
+
+    let oldest_created_at = Utc.timestamp(1_660_730_400, 0);
+    let mut txs = zksync_txs();
+    txs[1].created_at = oldest_created_at;
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+
+    let stats = MempoolSchema(&mut storage).get_mempool_stats().await?;
+    let counts: Vec<_> = stats
+        .iter()
+        .map(|stats| (stats.tx_type.as_str(), stats.count))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![("ChangePubKey", 1), ("Transfer", 2), ("Withdraw", 1)]
+    );
+    assert_eq!(stats[1].oldest_created_at, oldest_created_at);
+
+    // Parked transactions are not pending, so they are not counted.
+    MempoolSchema(&mut storage)
+        .park_tx(txs[1].hash(), "test")
+        .await?;
+    let stats = MempoolSchema(&mut storage).get_mempool_stats().await?;
+    let counts: Vec<_> = stats
+        .iter()
+        .map(|stats| (stats.tx_type.as_str(), stats.count))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![("ChangePubKey", 1), ("Transfer", 1), ("Withdraw", 1)]
+    );
+    assert_ne!(stats[1].oldest_created_at, oldest_created_at);
+
+    Ok(())
+}
+
 /// Checks the save&load routine for mempool schema.
 #[db_test]
 async fn store_load_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -288,6 +355,7 @@ async fn collect_garbage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         batch_id: None,
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
+        fee_only_token: None,
     };
     OperationsSchema(&mut storage)
         .store_executed_tx(executed_tx)
@@ -356,6 +424,610 @@ async fn contains_and_get_tx(mut storage: StorageProcessor<'_>) -> QueryResult<(
     Ok(())
 }
 
+/// Checks that `filter_known_txs` returns only the hashes of transactions stored in the mempool.
+#[db_test]
+async fn filter_known_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(3);
+    let hashes: Vec<TxHash> = txs.iter().map(|tx| tx.hash()).collect();
+
+    // Nothing is known while the mempool is empty.
+    assert!(MempoolSchema(&mut storage)
+        .filter_known_txs(&hashes)
+        .await?
+        .is_empty());
+
+    // Only the submitted transaction should be reported.
+    MempoolSchema(&mut storage).insert_tx(&txs[0]).await?;
+    let known = MempoolSchema(&mut storage)
+        .filter_known_txs(&hashes)
+        .await?;
+    assert_eq!(known, vec![hashes[0]]);
+
+    Ok(())
+}
+
+/// Checks that the queued transaction is replaced atomically, and is not replaced once executed.
+#[db_test]
+async fn replace_queued_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let original = gen_transfer(AccountId(1), Nonce(5));
+    MempoolSchema(&mut storage).insert_tx(&original).await?;
+    // Transactions of the batches can't be replaced.
+    let batch_tx = gen_transfer(AccountId(2), Nonce(0));
+    MempoolSchema(&mut storage)
+        .insert_batch(&[batch_tx], Vec::new())
+        .await?;
+
+    let queued = MempoolSchema(&mut storage)
+        .get_queued_tx_by_nonce(AccountId(1), Nonce(5))
+        .await?;
+    assert_eq!(queued.map(|tx| tx.hash()), Some(original.hash()));
+    assert!(MempoolSchema(&mut storage)
+        .get_queued_tx_by_nonce(AccountId(1), Nonce(6))
+        .await?
+        .is_none());
+    assert!(MempoolSchema(&mut storage)
+        .get_queued_tx_by_nonce(AccountId(2), Nonce(0))
+        .await?
+        .is_none());
+
+    let replacement = gen_transfer(AccountId(1), Nonce(5));
+    assert!(
+        MempoolSchema(&mut storage)
+            .replace_tx(original.hash(), &replacement)
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(original.hash())
+            .await?
+    );
+    let queued = MempoolSchema(&mut storage)
+        .get_queued_tx_by_nonce(AccountId(1), Nonce(5))
+        .await?;
+    assert_eq!(queued.map(|tx| tx.hash()), Some(replacement.hash()));
+
+    // Transaction that is no longer queued can't be replaced.
+    let second_replacement = gen_transfer(AccountId(1), Nonce(5));
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(original.hash(), &second_replacement)
+            .await?
+    );
+
+    // The replaced transaction gets executed in the pending block before it's removed from the mempool.
+    let executed_tx = NewExecutedTransaction {
+        block_number: 1,
+        tx_hash: replacement.hash().as_ref().to_vec(),
+        tx: Default::default(),
+        operation: Default::default(),
+        from_account: Default::default(),
+        to_account: None,
+        success: true,
+        fail_reason: None,
+        block_index: None,
+        primary_account_address: Default::default(),
+        nonce: Default::default(),
+        created_at: chrono::Utc::now(),
+        eth_sign_data: None,
+        batch_id: None,
+        affected_accounts: Vec::new(),
+        used_tokens: Vec::new(),
+        fee_only_token: None,
+    };
+    OperationsSchema(&mut storage)
+        .store_executed_tx(executed_tx)
+        .await?;
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(replacement.hash(), &second_replacement)
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .contains_tx(replacement.hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(second_replacement.hash())
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the transactions picked into the pending block can't be replaced until the markers are reset.
+#[db_test]
+async fn picked_txs_are_not_replaced(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let picked_tx = gen_transfer(AccountId(1), Nonce(0));
+    let queued_tx = gen_transfer(AccountId(2), Nonce(0));
+    MempoolSchema(&mut storage).insert_tx(&picked_tx).await?;
+    MempoolSchema(&mut storage).insert_tx(&queued_tx).await?;
+
+    // Transaction replaced after being loaded for the proposal is not in the mempool anymore.
+    let replaced_tx = gen_transfer(AccountId(3), Nonce(0));
+    let picked = MempoolSchema(&mut storage)
+        .mark_txs_picked(&[picked_tx.hash(), replaced_tx.hash()])
+        .await?;
+    assert_eq!(picked, vec![picked_tx.hash()]);
+    assert!(
+        MempoolSchema(&mut storage)
+            .is_tx_picked(picked_tx.hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .is_tx_picked(queued_tx.hash())
+            .await?
+    );
+
+    let replacement = gen_transfer(AccountId(1), Nonce(0));
+    assert!(
+        !MempoolSchema(&mut storage)
+            .replace_tx(picked_tx.hash(), &replacement)
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .contains_tx(picked_tx.hash())
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(replacement.hash())
+            .await?
+    );
+
+    // Transactions dropped from the proposal may be replaced again.
+    let next_tx = gen_transfer(AccountId(1), Nonce(1));
+    MempoolSchema(&mut storage).insert_tx(&next_tx).await?;
+    MempoolSchema(&mut storage)
+        .mark_txs_picked(&[next_tx.hash()])
+        .await?;
+    MempoolSchema(&mut storage)
+        .unmark_txs_picked(&[next_tx.hash()])
+        .await?;
+    assert!(
+        !MempoolSchema(&mut storage)
+            .is_tx_picked(next_tx.hash())
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .is_tx_picked(picked_tx.hash())
+            .await?
+    );
+
+    // The in-memory pending block is lost on restart, so its transactions may be replaced again.
+    MempoolSchema(&mut storage).reset_picked_txs().await?;
+    assert!(
+        !MempoolSchema(&mut storage)
+            .is_tx_picked(picked_tx.hash())
+            .await?
+    );
+    assert!(
+        MempoolSchema(&mut storage)
+            .replace_tx(picked_tx.hash(), &replacement)
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that parked transactions are excluded from the queue and can be returned back or expired.
+#[db_test]
+async fn park_and_unpark_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let txs = gen_transfers(2);
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    let parked_hash = txs[0].hash();
+
+    // Parked transaction is not proposed for execution, but is still re-validated.
+    MempoolSchema(&mut storage)
+        .park_tx(parked_hash, "Transaction fee is too low")
+        .await?;
+    let queued: Vec<TxHash> = MempoolSchema(&mut storage)
+        .load_txs(&[])
+        .await?
+        .iter()
+        .flat_map(|tx| tx.hashes())
+        .collect();
+    assert_eq!(queued, vec![txs[1].hash()]);
+
+    let revalidated = MempoolSchema(&mut storage)
+        .load_txs_for_revalidation(10)
+        .await?;
+    assert_eq!(revalidated.len(), 2);
+    for (tx, parked_at) in revalidated {
+        assert_eq!(parked_at.is_some(), tx.hash() == parked_hash);
+    }
+
+    // Unparked transaction returns to the queue.
+    MempoolSchema(&mut storage)
+        .unpark_txs(&[parked_hash])
+        .await?;
+    let queued = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(queued.len(), 2);
+
+    // Transaction parked for too long is removed from the mempool.
+    MempoolSchema(&mut storage)
+        .park_tx(parked_hash, "Transaction fee is too low")
+        .await?;
+    let expired = MempoolSchema(&mut storage)
+        .remove_expired_parked_txs(Utc::now() + chrono::Duration::seconds(1))
+        .await?;
+    assert_eq!(expired, vec![parked_hash]);
+    assert!(!MempoolSchema(&mut storage).contains_tx(parked_hash).await?);
+    assert!(
+        MempoolSchema(&mut storage)
+            .contains_tx(txs[1].hash())
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Generates a transfer transaction which becomes valid at the given time.
+fn gen_deferred_transfer(account_id: AccountId, valid_from: u64) -> SignedZkSyncTx {
+    let transfer = Transfer::new(
+        account_id,
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        100u32.into(),
+        10u32.into(),
+        Nonce(0),
+        TimeRange::new(valid_from, u64::MAX),
+        None,
+    );
+
+    SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(transfer)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    }
+}
+
+/// Checks that transactions valid in the future are queued, but not proposed for execution
+/// until they become valid.
+#[db_test]
+async fn deferred_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let valid_from = Utc::now().timestamp() as u64 + 60;
+    let deferred = gen_deferred_transfer(AccountId(1), valid_from);
+    let ready = gen_transfer(AccountId(2), Nonce(0));
+    MempoolSchema(&mut storage).insert_tx(&deferred).await?;
+    MempoolSchema(&mut storage).insert_tx(&ready).await?;
+    // The batch is deferred as a whole.
+    let batch = vec![
+        gen_transfer(AccountId(3), Nonce(0)),
+        gen_deferred_transfer(AccountId(3), valid_from),
+    ];
+    MempoolSchema(&mut storage)
+        .insert_batch(&batch, Vec::new())
+        .await?;
+
+    let queued: Vec<TxHash> = MempoolSchema(&mut storage)
+        .load_txs(&[])
+        .await?
+        .iter()
+        .flat_map(|tx| tx.hashes())
+        .collect();
+    assert_eq!(queued, vec![ready.hash()]);
+
+    // Deferred transaction is reported as queued.
+    let receipt = OperationsExtSchema(&mut storage)
+        .tx_receipt_api_v02(deferred.hash().as_ref())
+        .await?;
+    match receipt {
+        Some(Receipt::L2(receipt)) => assert_eq!(receipt.status, TxInBlockStatus::Queued),
+        other => panic!("Unexpected receipt: {:?}", other),
+    }
+
+    // A minute later the transactions become valid.
+    sqlx::query("UPDATE mempool_txs SET deferred_until = deferred_until - interval '1 minute'")
+        .execute(storage.conn())
+        .await?;
+    let queued = MempoolSchema(&mut storage).load_txs(&[]).await?;
+    assert_eq!(queued.len(), 3);
+
+    Ok(())
+}
+
+/// Checks that the queued transactions are found by the address of the account that initiated them
+/// and are paginated in the order of their nonces, while the parked ones are not listed.
+#[db_test]
+async fn account_queued_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::random();
+    let received_at = Utc::now() - chrono::Duration::minutes(1);
+    let txs = vec![
+        ZkSyncTx::Transfer(Box::new(Transfer::new(
+            AccountId(1),
+            address,
+            Address::random(),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(0),
+            Default::default(),
+            None,
+        ))),
+        ZkSyncTx::Withdraw(Box::new(Withdraw::new(
+            AccountId(1),
+            address,
+            Address::random(),
+            TokenId(0),
+            100u32.into(),
+            10u32.into(),
+            Nonce(1),
+            Default::default(),
+            None,
+        ))),
+        ZkSyncTx::ChangePubKey(Box::new(ChangePubKey::new(
+            AccountId(1),
+            address,
+            Default::default(),
+            TokenId(0),
+            Default::default(),
+            Nonce(2),
+            Default::default(),
+            None,
+            None,
+        ))),
+    ];
+    // Transactions are received in the reverse order of their nonces.
+    let txs: Vec<_> = txs
+        .into_iter()
+        .enumerate()
+        .map(|(idx, tx)| SignedZkSyncTx {
+            tx,
+            eth_sign_data: None,
+            created_at: received_at - chrono::Duration::seconds(idx as i64),
+        })
+        .collect();
+    for tx in &txs {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    // Transactions of the other accounts are not listed.
+    MempoolSchema(&mut storage)
+        .insert_tx(&gen_transfer(AccountId(2), Nonce(0)))
+        .await?;
+    MempoolSchema(&mut storage)
+        .park_tx(txs[1].hash(), "Transaction fee is too low")
+        .await?;
+
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_account_queued_txs_count(address)
+            .await?,
+        2
+    );
+    let latest = MempoolSchema(&mut storage)
+        .get_account_last_queued_tx_position(address)
+        .await?
+        .expect("account has queued transactions");
+    assert_eq!(latest.nonce, Nonce(2));
+    assert_eq!(latest.tx_hash, txs[2].hash());
+
+    let older = MempoolSchema(&mut storage)
+        .get_account_queued_txs(address, latest, 10, PaginationDirection::Older)
+        .await?;
+    let hashes: Vec<_> = older.iter().map(|(_, tx)| tx.tx_hash).collect();
+    assert_eq!(hashes, vec![txs[2].hash(), txs[0].hash()]);
+    assert!(older
+        .iter()
+        .all(|(_, tx)| tx.status == TxInBlockStatus::Queued));
+
+    let first = older[1].0;
+    assert_eq!(first.nonce, Nonce(0));
+    let newer = MempoolSchema(&mut storage)
+        .get_account_queued_txs(address, first, 2, PaginationDirection::Newer)
+        .await?;
+    let hashes: Vec<_> = newer.iter().map(|(_, tx)| tx.tx_hash).collect();
+    assert_eq!(hashes, vec![txs[0].hash(), txs[2].hash()]);
+
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_account_last_queued_tx_position(Address::random())
+            .await?,
+        None
+    );
+
+    Ok(())
+}
+
+/// Checks that a transaction is dead-lettered exactly when its failed execution attempts
+/// reach the threshold, and that it can be resurrected afterwards.
+#[db_test]
+async fn dead_letter_failed_txs(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    const MAX_ATTEMPTS: u32 = 3;
+    let tx = gen_transfers(1).pop().unwrap();
+    let tx_hash = tx.hash();
+
+    let failed_at = Utc::now();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let fail_reason = format!("Not enough balance, attempt {}", attempt);
+        let failed_at = failed_at + chrono::Duration::seconds(attempt.into());
+        let dead_lettered = MempoolSchema(&mut storage)
+            .record_failed_execution(&tx, &fail_reason, failed_at, MAX_ATTEMPTS)
+            .await?;
+        assert_eq!(dead_lettered, attempt == MAX_ATTEMPTS);
+
+        // Recording the same attempt twice doesn't count it again.
+        let dead_lettered = MempoolSchema(&mut storage)
+            .record_failed_execution(&tx, &fail_reason, failed_at, MAX_ATTEMPTS)
+            .await?;
+        assert!(!dead_lettered);
+
+        let stored = MempoolSchema(&mut storage)
+            .get_dead_lettered_tx(tx_hash)
+            .await?;
+        assert_eq!(stored.is_some(), attempt == MAX_ATTEMPTS);
+    }
+
+    let dead_lettered = MempoolSchema(&mut storage).load_dead_lettered_txs().await?;
+    assert_eq!(dead_lettered.len(), 1);
+    assert_eq!(dead_lettered[0].tx_hash, tx_hash);
+    assert_eq!(dead_lettered[0].attempts, MAX_ATTEMPTS);
+    assert_eq!(
+        dead_lettered[0].last_fail_reason,
+        format!("Not enough balance, attempt {}", MAX_ATTEMPTS)
+    );
+
+    // Dead-lettered transaction is not proposed for execution even if it gets to the mempool.
+    MempoolSchema(&mut storage).insert_tx(&tx).await?;
+    assert!(MempoolSchema(&mut storage).load_txs(&[]).await?.is_empty());
+    MempoolSchema(&mut storage)
+        .remove_tx(tx_hash.as_ref())
+        .await?;
+
+    // Resurrected transaction returns to the queue with the attempts reset.
+    assert!(
+        MempoolSchema(&mut storage)
+            .resurrect_dead_lettered_tx(tx_hash)
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .resurrect_dead_lettered_tx(tx_hash)
+            .await?
+    );
+    let queued: Vec<TxHash> = MempoolSchema(&mut storage)
+        .load_txs(&[])
+        .await?
+        .iter()
+        .flat_map(|tx| tx.hashes())
+        .collect();
+    assert_eq!(queued, vec![tx_hash]);
+    assert!(MempoolSchema(&mut storage)
+        .load_dead_lettered_txs()
+        .await?
+        .is_empty());
+
+    let dead_lettered = MempoolSchema(&mut storage)
+        .record_failed_execution(&tx, "Not enough balance", Utc::now(), MAX_ATTEMPTS)
+        .await?;
+    assert!(!dead_lettered);
+
+    Ok(())
+}
+
+/// Checks that the accounts whose lowest queued nonce is parked or failing are detected as stuck,
+/// and that dead-lettering the blocking transaction resolves the deadlock.
+#[db_test]
+async fn stuck_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    // The lowest nonce is parked, the next one is queued behind it.
+    let parked = gen_transfer(AccountId(1), Nonce(0));
+    let parked_next = gen_transfer(AccountId(1), Nonce(1));
+    // The lowest nonce keeps failing, two more nonces are queued behind it.
+    let failing = gen_transfer(AccountId(2), Nonce(5));
+    let failing_next = vec![
+        gen_transfer(AccountId(2), Nonce(6)),
+        gen_transfer(AccountId(2), Nonce(7)),
+    ];
+    // Parked transaction with nothing queued behind it doesn't block anything.
+    let parked_alone = gen_transfer(AccountId(3), Nonce(0));
+
+    for tx in std::iter::once(&parked)
+        .chain(std::iter::once(&parked_next))
+        .chain(std::iter::once(&failing))
+        .chain(&failing_next)
+        .chain(std::iter::once(&parked_alone))
+    {
+        MempoolSchema(&mut storage).insert_tx(tx).await?;
+    }
+    MempoolSchema(&mut storage)
+        .park_tx(parked.hash(), "Transaction fee is too low")
+        .await?;
+    MempoolSchema(&mut storage)
+        .park_tx(parked_alone.hash(), "Transaction fee is too low")
+        .await?;
+    MempoolSchema(&mut storage)
+        .record_failed_execution(&failing, "Not enough balance", Utc::now(), 10)
+        .await?;
+
+    // Accounts are not reported until they are blocked for longer than the threshold.
+    let stuck = MempoolSchema(&mut storage)
+        .refresh_stuck_accounts(Utc::now() - chrono::Duration::hours(1))
+        .await?;
+    assert!(stuck.is_empty());
+
+    let mut stuck = MempoolSchema(&mut storage)
+        .refresh_stuck_accounts(Utc::now() + chrono::Duration::seconds(1))
+        .await?;
+    stuck.sort_by_key(|account| account.account_id);
+    assert_eq!(stuck.len(), 2);
+    assert_eq!(stuck[0].account_id, AccountId(1));
+    assert_eq!(stuck[0].blocking_tx_hash, parked.hash());
+    assert_eq!(stuck[0].blocking_nonce, Nonce(0));
+    assert_eq!(stuck[0].reason, "Transaction fee is too low");
+    assert_eq!(stuck[0].queued_txs, 1);
+    assert_eq!(stuck[1].account_id, AccountId(2));
+    assert_eq!(stuck[1].blocking_tx_hash, failing.hash());
+    assert_eq!(stuck[1].blocking_nonce, Nonce(5));
+    assert_eq!(stuck[1].reason, "Not enough balance");
+    assert_eq!(stuck[1].queued_txs, 2);
+
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .load_stuck_accounts()
+            .await?
+            .len(),
+        2
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_stuck_account(AccountId(1))
+            .await?,
+        Some(stuck[0].clone())
+    );
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_stuck_account(AccountId(3))
+            .await?,
+        None
+    );
+
+    // Dead-lettered blocker leaves the mempool, so the account is not stuck anymore.
+    assert!(
+        MempoolSchema(&mut storage)
+            .dead_letter_tx(parked.hash(), "Account was blocked for too long")
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .dead_letter_tx(parked.hash(), "Account was blocked for too long")
+            .await?
+    );
+    assert!(
+        !MempoolSchema(&mut storage)
+            .contains_tx(parked.hash())
+            .await?
+    );
+    let dead_lettered = MempoolSchema(&mut storage)
+        .get_dead_lettered_tx(parked.hash())
+        .await?
+        .unwrap();
+    assert_eq!(
+        dead_lettered.last_fail_reason,
+        "Account was blocked for too long"
+    );
+
+    let stuck = MempoolSchema(&mut storage)
+        .refresh_stuck_accounts(Utc::now() + chrono::Duration::seconds(1))
+        .await?;
+    assert_eq!(stuck.len(), 1);
+    assert_eq!(stuck[0].account_id, AccountId(2));
+    assert_eq!(
+        MempoolSchema(&mut storage)
+            .get_stuck_account(AccountId(1))
+            .await?,
+        None
+    );
+
+    Ok(())
+}
+
 /// Checks that batch is got from mempool correctly
 #[db_test]
 async fn test_get_batch_info_from_mempool(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -402,6 +1074,8 @@ async fn test_return_executed_txs_to_mempool(mut storage: StorageProcessor<'_>)
             eth_hash: H256::zero(),
             eth_block: 0,
             eth_block_index: None,
+            eth_block_hash: None,
+            l1_surplus: None,
         },
         op: ZkSyncOp::FullExit(Box::new(FullExitOp {
             priority_op: priority_op.clone(),
@@ -1,20 +1,30 @@
 // External imports
 use chrono::{Duration, Utc};
+use sqlx::types::BigDecimal;
 // Workspace imports
+use zksync_api_types::{
+    v02::pagination::{PaginationDirection, PaginationQuery},
+    PriorityOpSurplus,
+};
 use zksync_types::{
-    aggregated_operations::AggregatedActionType, Address, BlockNumber, Deposit, SequentialTxId,
-    ZkSyncPriorityOp, H256,
+    aggregated_operations::AggregatedActionType, AccountId, Address, BlockNumber, Deposit,
+    DepositOp, SequentialTxId, TokenId, ZkSyncOp, ZkSyncPriorityOp, H256,
 };
+use zksync_utils::BigUintSerdeWrapper;
 // Local imports
 use crate::chain::mempool::MempoolSchema;
 use crate::{
     chain::{
         block::BlockSchema,
         operations::{
-            records::{NewExecutedPriorityOperation, NewExecutedTransaction},
+            records::{
+                NewExecutedPriorityOperation, NewExecutedTransaction,
+                StoredExecutedPriorityOperation,
+            },
             OperationsSchema,
         },
     },
+    ethereum::EthereumSchema,
     test_data::gen_unique_aggregated_operation,
     tests::db_test,
     QueryResult, StorageProcessor,
@@ -46,6 +56,84 @@ async fn aggregated_operations(mut storage: StorageProcessor<'_>) -> QueryResult
     Ok(())
 }
 
+/// Checks that the aggregated operations of the block are loaded with the hashes of their L1 transactions.
+#[db_test]
+async fn block_aggregated_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let block_number = BlockNumber(1);
+    assert!(OperationsSchema(&mut storage)
+        .get_block_aggregated_operations(block_number)
+        .await?
+        .is_empty());
+
+    for action_type in [
+        AggregatedActionType::CommitBlocks,
+        AggregatedActionType::ExecuteBlocks,
+    ] {
+        OperationsSchema(&mut storage)
+            .store_aggregated_action(gen_unique_aggregated_operation(
+                block_number,
+                action_type,
+                100,
+            ))
+            .await?;
+    }
+    // Only the commit operation is sent, and it was resent once.
+    let (id, op) = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, block_number)
+        .await?
+        .unwrap();
+    let response = EthereumSchema(&mut storage)
+        .save_new_eth_tx(
+            AggregatedActionType::CommitBlocks,
+            Some((id, op)),
+            100,
+            100u32.into(),
+            Default::default(),
+        )
+        .await?;
+    let (first_hash, second_hash) = (H256::repeat_byte(0x01), H256::repeat_byte(0x02));
+    for hash in [&first_hash, &second_hash] {
+        EthereumSchema(&mut storage)
+            .add_hash_entry(response.id, hash)
+            .await?;
+    }
+
+    let operations = OperationsSchema(&mut storage)
+        .get_block_aggregated_operations(block_number)
+        .await?;
+    assert_eq!(operations.len(), 2);
+    let commit = &operations[0];
+    assert_eq!(commit.action_type, "CommitBlocks");
+    assert_eq!((commit.from_block, commit.to_block), (1, 1));
+    assert!(!commit.confirmed);
+    // The last sent transaction is reported until one of them is confirmed.
+    assert_eq!(commit.eth_tx_hash, Some(second_hash.as_bytes().to_vec()));
+    let execute = &operations[1];
+    assert_eq!(execute.action_type, "ExecuteBlocks");
+    assert!(!execute.confirmed);
+    assert_eq!(execute.eth_tx_hash, None);
+
+    EthereumSchema(&mut storage)
+        .confirm_eth_tx(&first_hash)
+        .await?;
+    let operations = OperationsSchema(&mut storage)
+        .get_block_aggregated_operations(block_number)
+        .await?;
+    assert!(operations[0].confirmed);
+    assert_eq!(
+        operations[0].eth_tx_hash,
+        Some(first_hash.as_bytes().to_vec())
+    );
+
+    // Other blocks are not affected.
+    assert!(OperationsSchema(&mut storage)
+        .get_block_aggregated_operations(BlockNumber(2))
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
 /// Checks the save&load routine for executed operations.
 #[db_test]
 async fn executed_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -66,6 +154,7 @@ async fn executed_operations(mut storage: StorageProcessor<'_>) -> QueryResult<(
         batch_id: Some(10),
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
+        fee_only_token: None,
     };
 
     OperationsSchema(&mut storage)
@@ -112,6 +201,8 @@ async fn executed_priority_operations(mut storage: StorageProcessor<'_>) -> Quer
         created_at: chrono::Utc::now(),
         tx_hash: Default::default(),
         eth_block_index: Some(1),
+        eth_block_hash: None,
+        l1_surplus: None,
         affected_accounts: Default::default(),
         token: Default::default(),
     };
@@ -139,6 +230,111 @@ async fn executed_priority_operations(mut storage: StorageProcessor<'_>) -> Quer
     Ok(())
 }
 
+/// Checks that the priority operations executed after the block are loaded for the address they target.
+#[db_test]
+async fn executed_priority_ops_after_block(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(0x11);
+    let other_address = Address::repeat_byte(0x22);
+    let ops = [(1, address), (2, other_address), (2, address), (3, address)];
+    for (serial_id, (block_number, to_account)) in ops.iter().enumerate() {
+        let executed_op = NewExecutedPriorityOperation {
+            block_number: *block_number,
+            block_index: serial_id as i32,
+            operation: Default::default(),
+            from_account: other_address.as_bytes().to_vec(),
+            to_account: to_account.as_bytes().to_vec(),
+            priority_op_serialid: serial_id as i64,
+            deadline_block: 100,
+            eth_hash: vec![serial_id as u8; 32],
+            eth_block: 10,
+            created_at: chrono::Utc::now(),
+            tx_hash: vec![serial_id as u8; 32],
+            eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: None,
+            affected_accounts: Default::default(),
+            token: Default::default(),
+        };
+        OperationsSchema(&mut storage)
+            .store_executed_priority_op(executed_op)
+            .await?;
+    }
+
+    let serial_ids = |ops: Vec<StoredExecutedPriorityOperation>| {
+        ops.into_iter()
+            .map(|op| op.priority_op_serialid)
+            .collect::<Vec<_>>()
+    };
+    let executed = OperationsSchema(&mut storage)
+        .get_executed_priority_ops_after_block(address, BlockNumber(1))
+        .await?;
+    assert_eq!(serial_ids(executed), vec![2, 3]);
+    let executed = OperationsSchema(&mut storage)
+        .get_executed_priority_ops_after_block(address, BlockNumber(3))
+        .await?;
+    assert!(executed.is_empty());
+
+    Ok(())
+}
+
+/// Checks that the executed priority operations are paginated by the serial id, skipping the gaps.
+#[db_test]
+async fn executed_priority_ops_page(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    for serial_id in [1i64, 2, 5, 6] {
+        let executed_op = NewExecutedPriorityOperation {
+            block_number: serial_id,
+            block_index: 0,
+            operation: Default::default(),
+            from_account: Address::default().as_bytes().to_vec(),
+            to_account: Address::default().as_bytes().to_vec(),
+            priority_op_serialid: serial_id,
+            deadline_block: 100,
+            eth_hash: vec![serial_id as u8; 32],
+            eth_block: 10,
+            created_at: chrono::Utc::now(),
+            tx_hash: vec![serial_id as u8; 32],
+            eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: None,
+            affected_accounts: Default::default(),
+            token: Default::default(),
+        };
+        OperationsSchema(&mut storage)
+            .store_executed_priority_op(executed_op)
+            .await?;
+    }
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .get_executed_priority_ops_count()
+            .await?,
+        4
+    );
+
+    let cases: [(u64, u32, PaginationDirection, Vec<i64>); 5] = [
+        (1, 3, PaginationDirection::Newer, vec![1, 2, 5]),
+        (3, 2, PaginationDirection::Newer, vec![5, 6]),
+        (7, 2, PaginationDirection::Newer, vec![]),
+        (4, 3, PaginationDirection::Older, vec![2, 1]),
+        (u64::MAX, 1, PaginationDirection::Older, vec![6]),
+    ];
+    for (from, limit, direction, expected) in cases {
+        let query = PaginationQuery {
+            from,
+            limit,
+            direction,
+        };
+        let serial_ids: Vec<_> = OperationsSchema(&mut storage)
+            .load_executed_priority_ops_page(&query)
+            .await?
+            .into_iter()
+            .map(|op| op.priority_op_serialid)
+            .collect();
+        assert_eq!(serial_ids, expected, "{:?}", query);
+    }
+
+    Ok(())
+}
+
 /// Checks that attempt to save the duplicate txs is ignored by the DB.
 #[db_test]
 async fn duplicated_operations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -161,6 +357,7 @@ async fn duplicated_operations(mut storage: StorageProcessor<'_>) -> QueryResult
         batch_id: None,
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
+        fee_only_token: None,
     };
 
     let executed_priority_op = NewExecutedPriorityOperation {
@@ -176,6 +373,8 @@ async fn duplicated_operations(mut storage: StorageProcessor<'_>) -> QueryResult
         created_at: chrono::Utc::now(),
         tx_hash: Default::default(),
         eth_block_index: Some(1),
+        eth_block_hash: None,
+        l1_surplus: None,
         affected_accounts: Default::default(),
         token: Default::default(),
     };
@@ -236,6 +435,7 @@ async fn transaction_resent(mut storage: StorageProcessor<'_>) -> QueryResult<()
         batch_id: None,
         affected_accounts: Vec::new(),
         used_tokens: Vec::new(),
+        fee_only_token: None,
     };
 
     // Save the failed operation.
@@ -316,6 +516,7 @@ async fn remove_rejected_transactions(mut storage: StorageProcessor<'_>) -> Quer
         batch_id: None,
         affected_accounts: vec![Address::zero().as_bytes().to_vec()],
         used_tokens: vec![0],
+        fee_only_token: None,
     };
     let timestamp_2 = timestamp_1 - Duration::weeks(1);
     let mut executed_tx_2 = executed_tx_1.clone();
@@ -369,7 +570,7 @@ async fn remove_rejected_transactions(mut storage: StorageProcessor<'_>) -> Quer
     let count_tx_filters = storage
         .chain()
         .operations_ext_schema()
-        .get_account_transactions_count(Default::default(), None, None)
+        .get_account_transactions_count(Default::default(), None, false, None)
         .await?;
     assert_eq!(count, 4);
     assert_eq!(count_tx_filters, 4);
@@ -388,7 +589,7 @@ async fn remove_rejected_transactions(mut storage: StorageProcessor<'_>) -> Quer
     let count_tx_filters = storage
         .chain()
         .operations_ext_schema()
-        .get_account_transactions_count(Default::default(), None, None)
+        .get_account_transactions_count(Default::default(), None, false, None)
         .await?;
     assert_eq!(count, 2);
     assert_eq!(count_tx_filters, 2);
@@ -406,7 +607,7 @@ async fn remove_rejected_transactions(mut storage: StorageProcessor<'_>) -> Quer
     let count_tx_filters = storage
         .chain()
         .operations_ext_schema()
-        .get_account_transactions_count(Default::default(), None, None)
+        .get_account_transactions_count(Default::default(), None, false, None)
         .await?;
     assert_eq!(count, 1);
     assert_eq!(count_tx_filters, 1);
@@ -419,7 +620,7 @@ async fn remove_rejected_transactions(mut storage: StorageProcessor<'_>) -> Quer
     let count_tx_filters = storage
         .chain()
         .operations_ext_schema()
-        .get_account_transactions_count(Default::default(), None, None)
+        .get_account_transactions_count(Default::default(), None, false, None)
         .await?;
     assert_eq!(count, 1);
     assert_eq!(count_tx_filters, 1);
@@ -443,6 +644,8 @@ async fn priority_ops_hashes(mut storage: StorageProcessor<'_>) -> QueryResult<(
         created_at: chrono::Utc::now(),
         tx_hash: vec![0xBB, 0xBB, 0xBB, 0xBB],
         eth_block_index: Some(1),
+        eth_block_hash: None,
+        l1_surplus: None,
         affected_accounts: Default::default(),
         token: Default::default(),
     };
@@ -498,6 +701,8 @@ async fn test_remove_executed_priority_operations(
             eth_block: 10,
             created_at: chrono::Utc::now(),
             eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: None,
             tx_hash: H256::zero().as_bytes().to_vec(),
             affected_accounts: Default::default(),
             token: Default::default(),
@@ -530,6 +735,98 @@ async fn test_remove_executed_priority_operations(
     Ok(())
 }
 
+/// Checks that the surplus of the executed priority operations is summed up per day,
+/// and is subtracted back once the operations are returned to the mempool.
+#[db_test]
+async fn priority_op_surplus(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let created_at = Utc::now();
+    let day = created_at.date().naive_utc();
+    let token = TokenId(1);
+
+    // Deposits with the surplus, with the zero surplus and with the unknown one.
+    for (block_number, surplus) in vec![(1, Some(10u32)), (2, Some(0)), (3, None)] {
+        let deposit = ZkSyncOp::Deposit(Box::new(DepositOp {
+            priority_op: Deposit {
+                from: Address::zero(),
+                token,
+                amount: 90u32.into(),
+                to: Address::zero(),
+            },
+            account_id: AccountId(1),
+        }));
+        let executed_priority_op = NewExecutedPriorityOperation {
+            block_number,
+            block_index: 1,
+            operation: serde_json::to_value(deposit).unwrap(),
+            from_account: Address::zero().as_bytes().to_vec(),
+            to_account: Address::zero().as_bytes().to_vec(),
+            priority_op_serialid: block_number,
+            deadline_block: 100,
+            eth_hash: H256::from_low_u64_be(block_number as u64)
+                .as_bytes()
+                .to_vec(),
+            eth_block: 10,
+            created_at,
+            eth_block_index: Some(1),
+            eth_block_hash: None,
+            l1_surplus: surplus.map(BigDecimal::from),
+            tx_hash: H256::from_low_u64_be(block_number as u64)
+                .as_bytes()
+                .to_vec(),
+            affected_accounts: Default::default(),
+            token: *token as i32,
+        };
+        // The surplus of the operation stored twice is accounted only once.
+        for _ in 0..2 {
+            OperationsSchema(&mut storage)
+                .store_executed_priority_op(executed_priority_op.clone())
+                .await?;
+        }
+    }
+
+    let stored_op = OperationsSchema(&mut storage)
+        .get_executed_priority_operation(1)
+        .await?
+        .unwrap();
+    assert_eq!(stored_op.l1_surplus, Some(BigDecimal::from(10)));
+    let surplus = OperationsSchema(&mut storage)
+        .load_priority_op_surplus(day, day)
+        .await?;
+    assert_eq!(
+        surplus,
+        vec![PriorityOpSurplus {
+            day,
+            token_id: token,
+            surplus: 10u32.into(),
+            ops_count: 2,
+        }]
+    );
+
+    // Revert the operations from the 2nd block onwards.
+    OperationsSchema(&mut storage)
+        .return_executed_priority_operations_to_mempool(BlockNumber(1))
+        .await?;
+    let surplus = OperationsSchema(&mut storage)
+        .load_priority_op_surplus(day, day)
+        .await?;
+    assert_eq!(surplus[0].surplus, 10u32.into());
+    assert_eq!(surplus[0].ops_count, 1);
+
+    // Operations are returned to the mempool along with their surplus.
+    let mempool_surplus: Vec<_> = MempoolSchema(&mut storage)
+        .get_confirmed_priority_ops()
+        .await?
+        .into_iter()
+        .map(|op| op.l1_surplus)
+        .collect();
+    assert_eq!(
+        mempool_surplus,
+        vec![Some(BigUintSerdeWrapper(0u32.into())), None]
+    );
+
+    Ok(())
+}
+
 /// Checks if ethereum unprocessed aggregated operations are removed correctly.
 #[db_test]
 async fn test_remove_eth_unprocessed_aggregated_ops(
@@ -1,17 +1,22 @@
 // Built-in imports
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 // External imports
 // Workspace imports
 use zksync_api_types::v02::{
-    pagination::{AccountTxsRequest, ApiEither, PaginationDirection, PaginationQuery},
-    transaction::{Receipt, TxInBlockStatus},
+    account::AccountActivity,
+    pagination::{
+        AccountActivityRequest, AccountTxsRequest, ActivityKind, ActivityPosition, ApiEither,
+        PaginationDirection, PaginationQuery,
+    },
+    transaction::{EthSignatureType, MintedNFT, Receipt, TxInBlockStatus},
 };
 use zksync_crypto::{franklin_crypto::bellman::pairing::ff::Field, Fr};
 use zksync_types::{
     aggregated_operations::{AggregatedActionType, AggregatedOperation},
     block::Block,
     tx::TxHash,
-    AccountId, AccountUpdate, BlockNumber, ExecutedOperations, Nonce, ZkSyncOp, H256,
+    AccountId, AccountUpdate, Address, BlockNumber, EthBlockId, ExecutedOperations, Nonce, TokenId,
+    ZkSyncOp, ZkSyncTx, H256, NFT,
 };
 // Local imports
 use self::setup::TransactionsHistoryTestSetup;
@@ -127,16 +132,21 @@ async fn confirm_eth_op(
 pub async fn commit_block(
     storage: &mut StorageProcessor<'_>,
     block_number: BlockNumber,
+) -> QueryResult<()> {
+    commit_block_at(storage, block_number, 0).await
+}
+
+/// Same as `commit_block`, but the block gets the given timestamp.
+pub async fn commit_block_at(
+    storage: &mut StorageProcessor<'_>,
+    block_number: BlockNumber,
+    timestamp: u64,
 ) -> QueryResult<()> {
     // Required since we use `EthereumSchema` in this test.
     storage.ethereum_schema().initialize_eth_data().await?;
-    BlockSchema(storage)
-        .save_full_block(gen_sample_block(
-            block_number,
-            BLOCK_SIZE_CHUNKS,
-            Default::default(),
-        ))
-        .await?;
+    let mut block = gen_sample_block(block_number, BLOCK_SIZE_CHUNKS, Default::default());
+    block.timestamp = timestamp;
+    BlockSchema(storage).save_full_block(block).await?;
     OperationsSchema(storage)
         .store_aggregated_action(gen_unique_aggregated_operation(
             block_number,
@@ -385,6 +395,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 address: from,
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 0)),
                 token: None,
+                include_fee_token: false,
                 second_address: None,
             },
             limit: 1,
@@ -513,6 +524,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                     address: from,
                     tx_hash: ApiEither::from(request.tx_hash),
                     token: None,
+                    include_fee_token: false,
                     second_address: None,
                 },
                 limit: request.limit,
@@ -532,6 +544,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 address: from,
                 tx_hash: ApiEither::from(setup.get_tx_hash(1, 2)),
                 token: None,
+                include_fee_token: false,
                 second_address: None,
             },
             limit: 1,
@@ -550,6 +563,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 address: from,
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 9)),
                 token: None,
+                include_fee_token: false,
                 second_address: None,
             },
             limit: 2,
@@ -569,6 +583,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 address: from,
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 2)),
                 token: None,
+                include_fee_token: false,
                 second_address: None,
             },
             limit: 1,
@@ -584,6 +599,7 @@ async fn get_account_transactions(mut storage: StorageProcessor<'_>) -> QueryRes
                 address: to,
                 tx_hash: ApiEither::from(setup.get_tx_hash(0, 2)),
                 token: None,
+                include_fee_token: false,
                 second_address: None,
             },
             limit: 1,
@@ -710,6 +726,7 @@ async fn get_batch_info(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     assert_eq!(batch_info.batch_hash, batch_hash);
     assert_eq!(actual_tx_hashes, tx_hashes);
     assert_eq!(batch_info.batch_status.last_state, TxInBlockStatus::Queued);
+    assert_eq!(batch_info.block_number, None);
 
     // Get batch from queued block.
     commit_schema_data(&mut storage, &setup).await?;
@@ -733,6 +750,7 @@ async fn get_batch_info(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         batch_info.batch_status.last_state,
         TxInBlockStatus::Committed
     );
+    assert_eq!(batch_info.block_number, Some(BlockNumber(1)));
 
     // Get batch from committed block.
     commit_block(&mut storage, BlockNumber(1)).await?;
@@ -782,6 +800,64 @@ async fn get_batch_info(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the batch which is partially executed is resolved with the transactions
+/// from the mempool, and that the unknown batch is not found.
+#[db_test]
+async fn get_partially_executed_batch_info(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block_with_batch(1, true);
+
+    let tx_hashes = vec![
+        setup.get_tx_hash(0, 0),
+        setup.get_tx_hash(0, 1),
+        setup.get_tx_hash(0, 2),
+    ];
+    let batch_hash = TxHash::batch_hash(&tx_hashes);
+    let txs: Vec<_> = setup.blocks[0]
+        .block_transactions
+        .iter()
+        .map(|tx| tx.get_executed_tx().unwrap().signed_tx.clone())
+        .collect();
+    let batch_id = storage
+        .chain()
+        .mempool_schema()
+        .insert_batch(&txs, Vec::new())
+        .await?;
+
+    // Only the first transaction of the batch is executed.
+    setup.blocks[0].block_transactions.truncate(1);
+    match &mut setup.blocks[0].block_transactions[0] {
+        ExecutedOperations::Tx(tx) => tx.batch_id = Some(batch_id),
+        _ => unreachable!(),
+    }
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let batch_info = storage
+        .chain()
+        .operations_ext_schema()
+        .get_batch_info(batch_hash)
+        .await?
+        .unwrap();
+    let actual_tx_hashes: Vec<TxHash> = batch_info
+        .transaction_hashes
+        .into_iter()
+        .map(|tx_hash| tx_hash.0)
+        .collect();
+    assert_eq!(actual_tx_hashes, tx_hashes);
+    assert_eq!(batch_info.batch_status.last_state, TxInBlockStatus::Queued);
+    assert_eq!(batch_info.block_number, Some(BlockNumber(1)));
+
+    let unknown_batch_hash = TxHash::batch_hash(&tx_hashes[1..]);
+    let batch_info = storage
+        .chain()
+        .operations_ext_schema()
+        .get_batch_info(unknown_batch_hash)
+        .await?;
+    assert!(batch_info.is_none());
+
+    Ok(())
+}
+
 /// Test `get_account_transactions_count` method
 #[db_test]
 async fn account_transactions_count(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -792,7 +868,7 @@ async fn account_transactions_count(mut storage: StorageProcessor<'_>) -> QueryR
     let count_after_saving = storage
         .chain()
         .operations_ext_schema()
-        .get_account_transactions_count(setup.from_zksync_account.address, None, None)
+        .get_account_transactions_count(setup.from_zksync_account.address, None, false, None)
         .await?;
     assert_eq!(count_after_saving, 10);
 
@@ -825,7 +901,175 @@ async fn account_last_tx_hash(mut storage: StorageProcessor<'_>) -> QueryResult<
     Ok(())
 }
 
-/// Test `get_block_last_tx_hash` method
+/// Test `is_account_tx` method
+#[db_test]
+async fn is_account_tx(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let tx_hash = setup.get_tx_hash(0, 9);
+    assert!(
+        storage
+            .chain()
+            .operations_ext_schema()
+            .is_account_tx(setup.from_zksync_account.address, tx_hash)
+            .await?
+    );
+    assert!(
+        !storage
+            .chain()
+            .operations_ext_schema()
+            .is_account_tx(Address::repeat_byte(0x42), tx_hash)
+            .await?
+    );
+    assert!(
+        !storage
+            .chain()
+            .operations_ext_schema()
+            .is_account_tx(setup.from_zksync_account.address, TxHash::default())
+            .await?
+    );
+
+    Ok(())
+}
+
+/// Checks that the account activity feed merges L2 transactions with priority operations
+/// and keeps the order by the position in block across page boundaries.
+#[db_test]
+async fn get_account_activity(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    let address = setup.from_zksync_account.address;
+    setup.add_block(1);
+    setup.add_block(2);
+
+    // Empty feed for the account without operations.
+    let activity = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_activity(&PaginationQuery {
+            from: AccountActivityRequest {
+                address,
+                position: ApiEither::from_str("latest").unwrap(),
+            },
+            limit: 10,
+            direction: PaginationDirection::Older,
+        })
+        .await?;
+    assert!(activity.is_empty());
+
+    commit_schema_data(&mut storage, &setup).await?;
+
+    // Every block starts with a deposit and ends with a full exit, both addressed to the account,
+    // with L2 transactions in between.
+    let mut expected = Vec::new();
+    for (block, block_number) in [(0, BlockNumber(1)), (1, BlockNumber(2))] {
+        for block_index in 0..10 {
+            let kind = if block_index == 0 || block_index == 9 {
+                ActivityKind::PriorityOp
+            } else {
+                ActivityKind::L2Tx
+            };
+            expected.push((
+                ActivityPosition {
+                    block_number,
+                    kind,
+                    block_index: block_index as u32,
+                },
+                setup.get_tx_hash(block, block_index),
+            ));
+        }
+    }
+
+    let count = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_activity_count(address)
+        .await?;
+    assert_eq!(count as usize, expected.len());
+
+    let last_position = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_last_activity_position(address)
+        .await?;
+    assert_eq!(last_position, Some(expected.last().unwrap().0));
+
+    let to_pairs = |activity: Vec<AccountActivity>| -> Vec<(ActivityPosition, TxHash)> {
+        activity
+            .into_iter()
+            .map(|item| (item.position().unwrap(), item.tx().tx_hash))
+            .collect()
+    };
+
+    // Walk through the whole feed page by page, the next page starts right after the last
+    // element of the previous one.
+    let limit = 3;
+    let mut from = expected[0].0;
+    let mut actual = Vec::new();
+    loop {
+        let page = storage
+            .chain()
+            .operations_ext_schema()
+            .get_account_activity(&PaginationQuery {
+                from: AccountActivityRequest {
+                    address,
+                    position: ApiEither::from(from),
+                },
+                limit,
+                direction: PaginationDirection::Newer,
+            })
+            .await?;
+        let mut page = to_pairs(page);
+        // `from` is inclusive, so every page except the first one repeats the last element.
+        if !actual.is_empty() {
+            assert_eq!(page.first(), actual.last());
+            page.remove(0);
+        }
+        if page.is_empty() {
+            break;
+        }
+        from = page.last().unwrap().0;
+        actual.extend(page);
+    }
+    assert_eq!(actual, expected);
+
+    // Page crossing the block boundary in the reverse order.
+    let page = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_activity(&PaginationQuery {
+            from: AccountActivityRequest {
+                address,
+                position: ApiEither::from(expected[11].0),
+            },
+            limit: 4,
+            direction: PaginationDirection::Older,
+        })
+        .await?;
+    let expected_page: Vec<_> = expected[8..12].iter().rev().cloned().collect();
+    assert_eq!(to_pairs(page), expected_page);
+
+    // `latest` starts from the last operation.
+    let page = storage
+        .chain()
+        .operations_ext_schema()
+        .get_account_activity(&PaginationQuery {
+            from: AccountActivityRequest {
+                address,
+                position: ApiEither::from_str("latest").unwrap(),
+            },
+            limit: 2,
+            direction: PaginationDirection::Older,
+        })
+        .await?;
+    let expected_page: Vec<_> = expected[18..].iter().rev().cloned().collect();
+    assert_eq!(to_pairs(page), expected_page);
+
+    Ok(())
+}
+
+/// Test `get_block_first_tx_hash` and `get_block_last_tx_hash` methods
 #[db_test]
 async fn block_last_tx_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     let mut setup = TransactionsHistoryTestSetup::new();
@@ -847,6 +1091,18 @@ async fn block_last_tx_hash(mut storage: StorageProcessor<'_>) -> QueryResult<()
         .get_block_last_tx_hash(BlockNumber(1))
         .await?;
     assert_eq!(last_tx_hash, Some(setup.get_tx_hash(0, 9)));
+    let first_tx_hash = storage
+        .chain()
+        .operations_ext_schema()
+        .get_block_first_tx_hash(BlockNumber(1))
+        .await?;
+    assert_eq!(first_tx_hash, Some(setup.get_tx_hash(0, 0)));
+    assert!(storage
+        .chain()
+        .operations_ext_schema()
+        .get_block_first_tx_hash(BlockNumber(2))
+        .await?
+        .is_none());
     Ok(())
 }
 
@@ -911,6 +1167,9 @@ async fn tx_receipt(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     match l2_receipt.unwrap() {
         Receipt::L2(receipt) => {
             assert_eq!(receipt.tx_hash, setup.get_tx_hash(0, 2));
+            assert_eq!(receipt.batch_id, None);
+            assert_eq!(receipt.batch_index, None);
+            assert_eq!(receipt.created_at, None);
         }
         Receipt::L1(_) => {
             panic!("Should be L2 receipt");
@@ -934,6 +1193,9 @@ async fn tx_receipt(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     match l2_receipt.unwrap() {
         Receipt::L2(receipt) => {
             assert_eq!(receipt.tx_hash, tx.hash());
+            assert_eq!(receipt.batch_id, None);
+            assert!(receipt.created_at.is_some());
+            assert_eq!(receipt.eth_signature_type, Some(EthSignatureType::None));
         }
         Receipt::L1(_) => {
             panic!("Should be L2 receipt");
@@ -943,6 +1205,195 @@ async fn tx_receipt(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the receipts of the batched transactions carry the batch and the position within it,
+/// both while the batch is in the mempool and after it's executed.
+#[db_test]
+async fn tx_receipt_in_batch(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block_with_batch(1, true);
+
+    let txs: Vec<_> = setup.blocks[0]
+        .block_transactions
+        .iter()
+        .map(|tx| tx.get_executed_tx().unwrap().signed_tx.clone())
+        .collect();
+    let batch_id = storage
+        .chain()
+        .mempool_schema()
+        .insert_batch(&txs, Vec::new())
+        .await?;
+
+    for (index, tx) in txs.iter().enumerate() {
+        let receipt = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(tx.hash().as_ref())
+            .await?;
+        match receipt.unwrap() {
+            Receipt::L2(receipt) => {
+                assert_eq!(receipt.status, TxInBlockStatus::Queued);
+                assert_eq!(receipt.batch_id, Some(batch_id as u32));
+                assert_eq!(receipt.batch_index, Some(index as u32));
+            }
+            Receipt::L1(_) => {
+                panic!("Should be L2 receipt");
+            }
+        }
+    }
+
+    for tx in &mut setup.blocks[0].block_transactions {
+        match tx {
+            ExecutedOperations::Tx(tx) => tx.batch_id = Some(batch_id),
+            _ => unreachable!(),
+        }
+    }
+    commit_schema_data(&mut storage, &setup).await?;
+    storage.chain().mempool_schema().collect_garbage().await?;
+
+    for (index, tx) in txs.iter().enumerate() {
+        let receipt = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(tx.hash().as_ref())
+            .await?;
+        match receipt.unwrap() {
+            Receipt::L2(receipt) => {
+                assert_eq!(receipt.rollup_block, Some(BlockNumber(1)));
+                assert_eq!(receipt.batch_id, Some(batch_id as u32));
+                assert_eq!(receipt.batch_index, Some(index as u32));
+            }
+            Receipt::L1(_) => {
+                panic!("Should be L2 receipt");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the receipts still resolve after the payloads of the aggregated operations
+/// are archived or truncated, and the archived payloads can be restored.
+#[db_test]
+async fn aggregated_operations_archival(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    for block_number in 1..=3 {
+        setup.add_block(block_number);
+    }
+    commit_schema_data(&mut storage, &setup).await?;
+    for block_number in 1..=3 {
+        commit_block(&mut storage, BlockNumber(block_number)).await?;
+        verify_block(&mut storage, BlockNumber(block_number)).await?;
+    }
+
+    let tx_hashes = [setup.get_tx_hash(0, 0), setup.get_tx_hash(0, 2)];
+    let mut receipts = Vec::new();
+    for tx_hash in &tx_hashes {
+        let receipt = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(tx_hash.as_ref())
+            .await?;
+        receipts.push(receipt.unwrap());
+    }
+    let (commit_id, commit_op) = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, BlockNumber(1))
+        .await?
+        .unwrap();
+    // Aggregated operations are compared by their JSON representation.
+    let commit_op = serde_json::to_value(commit_op).unwrap();
+
+    // Operations for the first two blocks are archived in batches of one operation.
+    let mut archived = 0;
+    loop {
+        let processed = OperationsSchema(&mut storage)
+            .archive_aggregated_operations(1, 1, true)
+            .await?;
+        assert!(processed <= 1);
+        if processed == 0 {
+            break;
+        }
+        archived += processed;
+    }
+    assert_eq!(archived, 4);
+
+    for (tx_hash, expected) in tx_hashes.iter().zip(&receipts) {
+        let receipt = storage
+            .chain()
+            .operations_ext_schema()
+            .tx_receipt_api_v02(tx_hash.as_ref())
+            .await?;
+        assert_eq!(receipt.as_ref(), Some(expected));
+    }
+    let stored_operation = OperationsSchema(&mut storage)
+        .get_stored_aggregated_operation(BlockNumber(1), AggregatedActionType::CommitBlocks)
+        .await
+        .unwrap();
+    assert!(stored_operation.arguments.is_null());
+    assert!(stored_operation.confirmed);
+    // Archived payload is still available without being restored.
+    let (id, archived_op) = OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::CommitBlocks, BlockNumber(1))
+        .await?
+        .unwrap();
+    assert_eq!(id, commit_id);
+    assert_eq!(serde_json::to_value(archived_op).unwrap(), commit_op);
+
+    // Restore round trip.
+    assert!(
+        OperationsSchema(&mut storage)
+            .restore_aggregated_operation(commit_id)
+            .await?
+    );
+    assert!(
+        !OperationsSchema(&mut storage)
+            .restore_aggregated_operation(commit_id)
+            .await?
+    );
+    let stored_operation = OperationsSchema(&mut storage)
+        .get_stored_aggregated_operation(BlockNumber(1), AggregatedActionType::CommitBlocks)
+        .await
+        .unwrap();
+    assert_eq!(stored_operation.arguments, commit_op);
+
+    // The restored operation is archived again, the third block is still within the retention.
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .archive_aggregated_operations(1, 10, true)
+            .await?,
+        1
+    );
+
+    // Truncated payloads can't be restored, but the receipts still resolve.
+    assert_eq!(
+        OperationsSchema(&mut storage)
+            .archive_aggregated_operations(0, 10, false)
+            .await?,
+        2
+    );
+    let execute_id = OperationsSchema(&mut storage)
+        .get_stored_aggregated_operation(BlockNumber(3), AggregatedActionType::ExecuteBlocks)
+        .await
+        .unwrap()
+        .id;
+    assert!(
+        !OperationsSchema(&mut storage)
+            .restore_aggregated_operation(execute_id)
+            .await?
+    );
+    assert!(OperationsSchema(&mut storage)
+        .get_aggregated_op_that_affects_block(AggregatedActionType::ExecuteBlocks, BlockNumber(3))
+        .await
+        .is_err());
+    let receipt = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_receipt_api_v02(setup.get_tx_hash(2, 2).as_ref())
+        .await?;
+    assert!(receipt.is_some());
+
+    Ok(())
+}
+
 /// Test `tx_data_api_v02` method
 #[db_test]
 async fn tx_data(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -1014,6 +1465,162 @@ async fn tx_data(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that `tx_data_api_v02` and `tx_receipt_api_v02` return the context of the L1 transaction
+/// for priority operations and tolerate rows stored without the L1 block hash.
+#[db_test]
+async fn tx_data_l1_context(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let priority_op = match setup.blocks[0].block_transactions[0].clone() {
+        ExecutedOperations::PriorityOp(op) => op.priority_op,
+        ExecutedOperations::Tx(_) => {
+            panic!("Should be L1 op")
+        }
+    };
+    let deposit = priority_op.data.try_get_deposit().unwrap();
+    assert!(priority_op.eth_block_hash.is_some());
+
+    let l1_context = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_data_api_v02(setup.get_tx_hash(0, 0).as_ref())
+        .await?
+        .unwrap()
+        .l1_context
+        .expect("L1 op must have the L1 context");
+    assert_eq!(l1_context.eth_sender, deposit.from);
+    assert_eq!(l1_context.eth_block, EthBlockId(priority_op.eth_block));
+    assert_eq!(l1_context.eth_block_hash, priority_op.eth_block_hash);
+    assert_eq!(l1_context.token_id, deposit.token);
+    assert_eq!(l1_context.amount, Some(deposit.amount.clone().into()));
+
+    let receipt = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_receipt_api_v02(setup.get_tx_hash(0, 0).as_ref())
+        .await?;
+    if let Some(Receipt::L1(receipt)) = receipt {
+        assert_eq!(receipt.eth_sender, Some(deposit.from));
+        assert_eq!(receipt.eth_block_hash, priority_op.eth_block_hash);
+    } else {
+        panic!("Should be L1 receipt");
+    }
+
+    // Rows stored before the block hash was recorded must be serialized with `null` instead.
+    sqlx::query(
+        "UPDATE executed_priority_operations SET eth_block_hash = NULL WHERE priority_op_serialid = $1",
+    )
+    .bind(priority_op.serial_id as i64)
+    .execute(storage.conn())
+    .await?;
+    let l1_context = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_data_api_v02(setup.get_tx_hash(0, 0).as_ref())
+        .await?
+        .unwrap()
+        .l1_context
+        .expect("L1 op must have the L1 context");
+    assert_eq!(l1_context.eth_sender, deposit.from);
+    assert!(l1_context.eth_block_hash.is_none());
+    let serialized = serde_json::to_value(&l1_context).unwrap();
+    assert!(serialized["ethBlockHash"].is_null());
+
+    // L2 transactions don't have the L1 context.
+    let l2_data = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_data_api_v02(setup.get_tx_hash(0, 2).as_ref())
+        .await?;
+    assert!(l2_data.unwrap().l1_context.is_none());
+
+    Ok(())
+}
+
+/// Checks that `tx_data_api_v02` reports the token produced by the executed `MintNFT` transaction.
+#[db_test]
+async fn tx_data_minted_nft(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let mut setup = TransactionsHistoryTestSetup::new();
+    setup.add_block(1);
+    commit_schema_data(&mut storage, &setup).await?;
+
+    let mint_nft = match &setup.blocks[0].block_transactions[6] {
+        ExecutedOperations::Tx(tx) => match &tx.signed_tx.tx {
+            ZkSyncTx::MintNFT(tx) => tx.as_ref().clone(),
+            _ => panic!("Should be MintNFT"),
+        },
+        ExecutedOperations::PriorityOp(_) => panic!("Should be L2 tx"),
+    };
+    let tx_hash = setup.get_tx_hash(0, 6);
+
+    let nft = NFT::new(
+        TokenId(65_536),
+        3,
+        mint_nft.creator_id,
+        mint_nft.creator_address,
+        Address::repeat_byte(0x11),
+        None,
+        mint_nft.content_hash,
+    );
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(
+            BlockNumber(1),
+            &[(
+                mint_nft.creator_id,
+                AccountUpdate::MintNFT {
+                    token: nft.clone(),
+                    nonce: mint_nft.nonce,
+                },
+            )],
+            0,
+        )
+        .await?;
+
+    let minted_nft = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_data_api_v02(tx_hash.as_ref())
+        .await?
+        .unwrap()
+        .nft
+        .expect("Executed MintNFT must have the token");
+    assert_eq!(
+        minted_nft,
+        MintedNFT {
+            token_id: nft.id,
+            serial_id: nft.serial_id,
+            content_hash: nft.content_hash,
+            address: nft.address,
+        }
+    );
+
+    // Other transactions don't produce tokens.
+    let transfer_data = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_data_api_v02(setup.get_tx_hash(0, 2).as_ref())
+        .await?;
+    assert!(transfer_data.unwrap().nft.is_none());
+
+    // Failed MintNFT doesn't produce the token.
+    sqlx::query("UPDATE executed_transactions SET success = false WHERE tx_hash = $1")
+        .bind(tx_hash.as_ref())
+        .execute(storage.conn())
+        .await?;
+    let failed_data = storage
+        .chain()
+        .operations_ext_schema()
+        .tx_data_api_v02(tx_hash.as_ref())
+        .await?;
+    assert!(failed_data.unwrap().nft.is_none());
+
+    Ok(())
+}
+
 /// Test `tx_data_for_web3` method
 #[db_test]
 async fn tx_data_for_web3(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -1233,6 +1840,7 @@ async fn test_getting_swap_for_acc(mut storage: StorageProcessor<'_>) -> QueryRe
                     address: *address,
                     tx_hash: ApiEither::from(tx_hash),
                     token: None,
+                    include_fee_token: false,
                     second_address: None,
                 },
                 limit: 1,
@@ -209,6 +209,8 @@ impl TransactionsHistoryTestSetup {
                 ),
                 eth_block: 10,
                 eth_block_index: Some(1),
+                eth_block_hash: Some(H256::from_low_u64_be(u64::from(block))),
+                l1_surplus: None,
             },
             op: deposit_op,
             block_index,
@@ -248,6 +250,8 @@ impl TransactionsHistoryTestSetup {
                 ),
                 eth_block: 11,
                 eth_block_index: Some(1),
+                eth_block_hash: None,
+                l1_surplus: None,
             },
             op: full_exit_op,
             block_index,
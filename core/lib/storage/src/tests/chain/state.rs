@@ -9,8 +9,10 @@ use zksync_types::{
 use super::block::apply_random_updates;
 use crate::{
     chain::{operations::OperationsSchema, state::StateSchema},
-    test_data::{gen_unique_aggregated_operation, BLOCK_SIZE_CHUNKS},
-    tests::{create_rng, db_test},
+    test_data::{
+        gen_random_updates, gen_unique_aggregated_operation, TestRng, UpdateMix, BLOCK_SIZE_CHUNKS,
+    },
+    tests::db_test,
     QueryResult, StorageProcessor,
 };
 
@@ -21,9 +23,10 @@ use crate::{
 /// `load_committed_state(None)` will not work (since this method will attempt to
 /// look into `blocks` table to get the most recent block number.)
 #[db_test]
-async fn low_level_commit_verify_state(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
-    let mut rng = create_rng();
-
+async fn low_level_commit_verify_state(
+    mut storage: StorageProcessor<'_>,
+    mut rng: TestRng,
+) -> QueryResult<()> {
     // Create the input data for three blocks.
     // Data for the next block is based on previous block data.
     let (accounts_block_1, updates_block_1) = apply_random_updates(AccountMap::default(), &mut rng);
@@ -147,7 +150,7 @@ async fn low_level_commit_verify_state(mut storage: StorageProcessor<'_>) -> Que
 }
 
 #[db_test]
-async fn state_diff(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+async fn state_diff(mut storage: StorageProcessor<'_>, mut rng: TestRng) -> QueryResult<()> {
     async fn check_diff_applying(
         storage: &mut StorageProcessor<'_>,
         start_block: BlockNumber,
@@ -173,8 +176,6 @@ async fn state_diff(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
         Ok(())
     }
 
-    let mut rng = create_rng();
-
     let block_size = 100;
     let mut accounts_map = AccountMap::default();
     let blocks_amount = 5;
@@ -222,9 +223,10 @@ async fn state_diff(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
 /// Checks if account updates are removed correctly.
 #[db_test]
-async fn test_remove_account_updates(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
-    let mut rng = create_rng();
-
+async fn test_remove_account_updates(
+    mut storage: StorageProcessor<'_>,
+    mut rng: TestRng,
+) -> QueryResult<()> {
     let (accounts_block_1, updates_block_1) = apply_random_updates(AccountMap::default(), &mut rng);
     let (accounts_block_2, updates_block_2) =
         apply_random_updates(accounts_block_1.clone(), &mut rng);
@@ -328,3 +330,43 @@ async fn test_get_mint_nft_update(mut storage: StorageProcessor<'_>) -> QueryRes
 
     Ok(())
 }
+
+/// Checks that the NFTs minted in random updates can be loaded back.
+#[db_test]
+async fn test_random_nft_mints(
+    mut storage: StorageProcessor<'_>,
+    mut rng: TestRng,
+) -> QueryResult<()> {
+    let mix = UpdateMix {
+        creates: 1,
+        nft_mints: 4,
+        ..UpdateMix::none()
+    };
+    let updates = gen_random_updates(&AccountMap::default(), 20, mix, &mut rng);
+    storage
+        .chain()
+        .state_schema()
+        .commit_state_update(BlockNumber(1), &updates, 0)
+        .await?;
+
+    let minted_nfts: Vec<_> = updates
+        .iter()
+        .filter_map(|(_, update)| match update {
+            AccountUpdate::MintNFT { token, .. } => Some(token),
+            _ => None,
+        })
+        .collect();
+    assert!(!minted_nfts.is_empty());
+    for token in minted_nfts {
+        let nft = storage
+            .chain()
+            .state_schema()
+            .get_mint_nft_update(token.id)
+            .await?
+            .expect("Minted NFT is not found");
+        assert_eq!(nft.creator_address, token.creator_address);
+        assert_eq!(nft.content_hash, token.content_hash);
+    }
+
+    Ok(())
+}
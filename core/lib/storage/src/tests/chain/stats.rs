@@ -0,0 +1,176 @@
+// External imports
+use chrono::{TimeZone, Utc};
+use num::BigUint;
+// Workspace imports
+use zksync_types::{
+    block::ExecutedOperations,
+    tx::{Transfer, Withdraw},
+    AccountId, Address, BlockNumber, ExecutedTx, Nonce, TokenId, ZkSyncTx,
+};
+// Local imports
+use crate::{
+    chain::stats::TokenVolume,
+    test_data::{gen_sample_block, BLOCK_SIZE_CHUNKS},
+    tests::db_test,
+    QueryResult, StorageProcessor,
+};
+
+/// Start of some hour, the blocks of the test are created around it.
+const HOUR: i64 = 1_600_000_000 - 1_600_000_000 % 3600;
+
+fn executed_tx(tx: ZkSyncTx, success: bool) -> ExecutedOperations {
+    ExecutedOperations::Tx(Box::new(ExecutedTx {
+        signed_tx: tx.into(),
+        success,
+        op: None,
+        fail_reason: None,
+        block_index: if success { Some(0) } else { None },
+        created_at: Utc::now(),
+        batch_id: None,
+    }))
+}
+
+fn transfer(token: u32, amount: u32, nonce: u32, success: bool) -> ExecutedOperations {
+    let transfer = Transfer::new(
+        AccountId(1),
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        TokenId(token),
+        amount.into(),
+        0u32.into(),
+        Nonce(nonce),
+        Default::default(),
+        None,
+    );
+    executed_tx(ZkSyncTx::Transfer(Box::new(transfer)), success)
+}
+
+fn withdraw(token: u32, amount: u32, nonce: u32) -> ExecutedOperations {
+    let withdraw = Withdraw::new(
+        AccountId(1),
+        Address::repeat_byte(1),
+        Address::repeat_byte(2),
+        TokenId(token),
+        amount.into(),
+        0u32.into(),
+        Nonce(nonce),
+        Default::default(),
+        None,
+    );
+    executed_tx(ZkSyncTx::Withdraw(Box::new(withdraw)), true)
+}
+
+fn volume(transfer: u32, withdraw: u32) -> TokenVolume {
+    TokenVolume {
+        transfer: BigUint::from(transfer),
+        withdraw: BigUint::from(withdraw),
+    }
+}
+
+async fn token_volume(
+    storage: &mut StorageProcessor<'_>,
+    token: u32,
+    since: i64,
+) -> QueryResult<TokenVolume> {
+    storage
+        .chain()
+        .stats_schema()
+        .load_token_volume(TokenId(token), Utc.timestamp(since, 0))
+        .await
+}
+
+/// Commits the blocks of the test and checks the trailing sums of the token volume.
+async fn check_volume(storage: &mut StorageProcessor<'_>) -> QueryResult<()> {
+    // Trailing 24 hours from the middle of the next hour include the current hour, but not the block from 30 hours ago.
+    assert_eq!(
+        token_volume(storage, 0, HOUR + 3600 + 1800 - 24 * 3600).await?,
+        volume(500, 50)
+    );
+    // Only the next hour.
+    assert_eq!(
+        token_volume(storage, 0, HOUR + 3600 + 1800).await?,
+        volume(300, 0)
+    );
+    // The whole week.
+    assert_eq!(
+        token_volume(storage, 0, HOUR + 3600 - 7 * 24 * 3600).await?,
+        volume(600, 50)
+    );
+    // Other token is accounted separately.
+    assert_eq!(
+        token_volume(storage, 1, HOUR - 7 * 24 * 3600).await?,
+        volume(7, 0)
+    );
+    // Token without transfers.
+    assert_eq!(
+        token_volume(storage, 2, HOUR - 7 * 24 * 3600).await?,
+        volume(0, 0)
+    );
+    Ok(())
+}
+
+/// Checks that the token volume is accumulated as the blocks are committed,
+/// rebuilt by the backfill and reverted together with the blocks.
+#[db_test]
+async fn token_volume_stats(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let blocks = vec![
+        // 30 hours ago.
+        (HOUR - 30 * 3600, vec![transfer(0, 100, 0, true)]),
+        // The last second of the hour.
+        (
+            HOUR + 3599,
+            vec![transfer(0, 200, 1, true), withdraw(0, 50, 2)],
+        ),
+        // The next hour.
+        (
+            HOUR + 3600 + 10,
+            vec![
+                transfer(0, 300, 3, true),
+                transfer(1, 7, 4, true),
+                // Failed transactions are not accounted.
+                transfer(0, 1000, 5, false),
+            ],
+        ),
+    ];
+    for (block_number, (timestamp, txs)) in blocks.into_iter().enumerate() {
+        let mut block =
+            gen_sample_block(BlockNumber(block_number as u32 + 1), BLOCK_SIZE_CHUNKS, txs);
+        block.timestamp = timestamp as u64;
+        storage
+            .chain()
+            .block_schema()
+            .save_full_block(block)
+            .await
+            .unwrap();
+    }
+    check_volume(&mut storage).await?;
+
+    // Rebuild the stats from scratch in chunks.
+    sqlx::query!("DELETE FROM token_volume_stats")
+        .execute(storage.conn())
+        .await?;
+    sqlx::query!("UPDATE token_volume_stats_progress SET last_block = 0")
+        .execute(storage.conn())
+        .await?;
+    let mut stats = storage.chain().stats_schema();
+    assert_eq!(stats.backfill_token_volume(2).await?, 2);
+    assert_eq!(stats.backfill_token_volume(2).await?, 1);
+    assert_eq!(stats.backfill_token_volume(2).await?, 0);
+    check_volume(&mut storage).await?;
+
+    // Revert the last block.
+    storage
+        .chain()
+        .stats_schema()
+        .revert_token_volume(BlockNumber(2))
+        .await?;
+    assert_eq!(
+        token_volume(&mut storage, 0, HOUR - 7 * 24 * 3600).await?,
+        volume(300, 50)
+    );
+    assert_eq!(
+        token_volume(&mut storage, 1, HOUR - 7 * 24 * 3600).await?,
+        volume(0, 0)
+    );
+    Ok(())
+}
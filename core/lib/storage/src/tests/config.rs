@@ -1,5 +1,6 @@
 // External imports
 // Workspace imports
+use zksync_types::{Address, ContractUpgradeEvent};
 // Local imports
 use crate::tests::db_test;
 use crate::{QueryResult, StorageProcessor};
@@ -17,3 +18,60 @@ async fn test_load_config(mut storage: StorageProcessor<'_>) -> QueryResult<()>
 
     Ok(())
 }
+
+/// Checks that the contract upgrades are stored and the current main contract target is tracked.
+#[db_test]
+async fn test_contract_upgrades(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(storage
+        .config_schema()
+        .load_last_contract_upgrade()
+        .await?
+        .is_none());
+    assert!(storage
+        .config_schema()
+        .load_zksync_target()
+        .await?
+        .is_none());
+
+    let zksync_target = Address::repeat_byte(0x11);
+    let upgrade = ContractUpgradeEvent {
+        version_id: 1,
+        new_targets: vec![Address::zero(), Address::zero(), zksync_target],
+        eth_block: 10,
+    };
+    storage
+        .config_schema()
+        .store_contract_upgrade(&upgrade)
+        .await?;
+    // Storing the same upgrade twice is not an error.
+    storage
+        .config_schema()
+        .store_contract_upgrade(&upgrade)
+        .await?;
+
+    // The next upgrade doesn't touch the main contract.
+    let upgrade = ContractUpgradeEvent {
+        version_id: 2,
+        new_targets: vec![Address::repeat_byte(0x22), Address::zero(), Address::zero()],
+        eth_block: 20,
+    };
+    storage
+        .config_schema()
+        .store_contract_upgrade(&upgrade)
+        .await?;
+
+    let last_upgrade = storage
+        .config_schema()
+        .load_last_contract_upgrade()
+        .await?
+        .expect("upgrade should be stored");
+    assert_eq!(last_upgrade.version_id, 2);
+    assert_eq!(last_upgrade.eth_block, 20);
+    assert!(last_upgrade.zksync_target_addr.is_none());
+    assert_eq!(
+        storage.config_schema().load_zksync_target().await?,
+        Some(zksync_target)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+// External imports
+use num::BigUint;
+// Workspace imports
+use zksync_api_types::MaintenanceMode;
+use zksync_types::{tx::TxHash, Address};
+// Local imports
+use crate::tests::db_test;
+use crate::{maintenance::MaintenanceSchema, QueryResult, StorageProcessor};
+
+/// Checks that the maintenance accounts can be added, loaded and removed.
+#[db_test]
+async fn maintenance_accounts(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let first = Address::repeat_byte(1);
+    let second = Address::repeat_byte(2);
+
+    assert!(
+        MaintenanceSchema(&mut storage)
+            .add_maintenance_account(first)
+            .await?
+    );
+    assert!(
+        !MaintenanceSchema(&mut storage)
+            .add_maintenance_account(first)
+            .await?
+    );
+    assert!(
+        MaintenanceSchema(&mut storage)
+            .add_maintenance_account(second)
+            .await?
+    );
+    let mut accounts = MaintenanceSchema(&mut storage)
+        .load_maintenance_accounts()
+        .await?;
+    accounts.sort();
+    assert_eq!(accounts, vec![first, second]);
+    assert!(
+        MaintenanceSchema(&mut storage)
+            .is_maintenance_account(first)
+            .await?
+    );
+
+    assert!(
+        MaintenanceSchema(&mut storage)
+            .remove_maintenance_account(first)
+            .await?
+    );
+    assert!(
+        !MaintenanceSchema(&mut storage)
+            .remove_maintenance_account(first)
+            .await?
+    );
+    assert!(
+        !MaintenanceSchema(&mut storage)
+            .is_maintenance_account(first)
+            .await?
+    );
+    assert_eq!(
+        MaintenanceSchema(&mut storage)
+            .load_maintenance_accounts()
+            .await?,
+        vec![second]
+    );
+
+    Ok(())
+}
+
+/// Checks that the fee exemptions are journaled and can be looked up by the transaction hash.
+#[db_test]
+async fn fee_exemptions(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let address = Address::repeat_byte(1);
+    let exempted = TxHash::from_slice(&[1; 32]).unwrap();
+    let regular = TxHash::from_slice(&[2; 32]).unwrap();
+
+    MaintenanceSchema(&mut storage)
+        .record_fee_exemption(exempted, address, "ChangePubKey", BigUint::from(1000u32))
+        .await?;
+    // Recording the same transaction twice doesn't duplicate the entry.
+    MaintenanceSchema(&mut storage)
+        .record_fee_exemption(exempted, address, "ChangePubKey", BigUint::from(1000u32))
+        .await?;
+
+    let exemptions = MaintenanceSchema(&mut storage)
+        .load_fee_exemptions(10)
+        .await?;
+    assert_eq!(exemptions.len(), 1);
+    assert_eq!(exemptions[0].tx_hash, exempted);
+    assert_eq!(exemptions[0].address, address);
+    assert_eq!(exemptions[0].tx_type, "ChangePubKey");
+    assert_eq!(exemptions[0].required_fee, BigUint::from(1000u32));
+
+    assert_eq!(
+        MaintenanceSchema(&mut storage)
+            .filter_fee_exempted_txs(&[exempted, regular])
+            .await?,
+        vec![exempted]
+    );
+
+    Ok(())
+}
+
+/// Checks that the maintenance mode is disabled by default and can be toggled.
+#[db_test]
+async fn maintenance_mode(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert_eq!(
+        MaintenanceSchema(&mut storage)
+            .load_maintenance_mode()
+            .await?,
+        MaintenanceMode::default()
+    );
+
+    let enabled = MaintenanceMode {
+        enabled: true,
+        reason: Some("Contract upgrade".to_string()),
+    };
+    MaintenanceSchema(&mut storage)
+        .set_maintenance_mode(&enabled)
+        .await?;
+    assert_eq!(
+        MaintenanceSchema(&mut storage)
+            .load_maintenance_mode()
+            .await?,
+        enabled
+    );
+
+    let disabled = MaintenanceMode {
+        enabled: false,
+        reason: None,
+    };
+    MaintenanceSchema(&mut storage)
+        .set_maintenance_mode(&disabled)
+        .await?;
+    assert_eq!(
+        MaintenanceSchema(&mut storage)
+            .load_maintenance_mode()
+            .await?,
+        disabled
+    );
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use sqlx::types::BigDecimal;
-use zksync_types::TokenId;
+use zksync_types::{Address, TokenId};
 
 use crate::tests::db_test;
 use crate::{misc::records::Subsidy, misc::MiscSchema};
@@ -54,3 +54,35 @@ async fn stored_subsidy(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
 
     Ok(())
 }
+
+/// Checks that the bootstrap marker can be stored only once and is loaded back intact.
+#[db_test]
+async fn stored_bootstrap_marker(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    assert!(MiscSchema(&mut storage)
+        .load_bootstrap_marker()
+        .await?
+        .is_none());
+
+    let genesis_root = vec![1u8; 32];
+    let fee_account_address = Address::repeat_byte(0x42);
+    let tokens = serde_json::json!([{ "address": "0x0000000000000000000000000000000000000001", "decimals": 18, "symbol": "DAI" }]);
+    MiscSchema(&mut storage)
+        .store_bootstrap_marker(&genesis_root, fee_account_address, tokens.clone())
+        .await?;
+
+    let marker = MiscSchema(&mut storage)
+        .load_bootstrap_marker()
+        .await?
+        .expect("marker must be stored");
+    assert_eq!(marker.genesis_root, genesis_root);
+    assert_eq!(marker.fee_account_address, fee_account_address.as_bytes());
+    assert_eq!(marker.tokens, tokens);
+
+    // The marker is a singleton.
+    assert!(MiscSchema(&mut storage)
+        .store_bootstrap_marker(&genesis_root, fee_account_address, tokens)
+        .await
+        .is_err());
+
+    Ok(())
+}
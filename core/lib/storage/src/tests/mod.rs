@@ -15,29 +15,38 @@
 //! This procedure macro implicitly creates a database connection and starts
 //! new transactions which will be dropped when the method returns.
 //!
+//! Tests that need random data may take a second `mut rng: TestRng` argument.
+//! The RNG seed is printed by the test, and it can be pinned with the `ZKSYNC_TEST_SEED`
+//! environment variable to reproduce the same data, e.g. after a test failure.
+//!
 //! The file hierarchy is designed to mirror the actual project structure.
 
 // External imports
 use once_cell::sync::Lazy;
 use tokio::sync::Mutex;
-// Workspace imports
-use zksync_crypto::rand::{SeedableRng, XorShiftRng};
+// Local imports
+use crate::test_data::TestRng;
 
+mod api_keys;
 pub(crate) mod chain;
 mod config;
 mod data_restore;
 mod ethereum;
 mod event;
 mod forced_exit_requests;
+mod maintenance;
 mod misc;
 mod prover;
+mod subsidy_campaigns;
+mod test_data;
 mod tokens;
+mod webhooks;
 
 pub use db_test_macro::test as db_test;
 
 /// Creates a fixed-seed RNG for tests.
-pub fn create_rng() -> XorShiftRng {
-    XorShiftRng::from_seed([0, 1, 2, 3])
+pub fn create_rng() -> TestRng {
+    TestRng::from_seed([0, 1, 2, 3])
 }
 
 /// Mutex that's used to avoid database deadlock when accessing
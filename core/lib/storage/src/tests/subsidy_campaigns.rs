@@ -0,0 +1,253 @@
+// External imports
+use chrono::{Duration, Utc};
+// Workspace imports
+use zksync_api_types::NewSubsidyCampaign;
+use zksync_types::{
+    tokens::ChangePubKeyFeeTypeArg, tx::ChangePubKeyType, tx::TxHash, AccountId, Address, Nonce,
+    OutputFeeType, SignedZkSyncTx, TokenId, Transfer, ZkSyncTx,
+};
+// Local imports
+use crate::tests::db_test;
+use crate::{
+    chain::{
+        mempool::MempoolSchema,
+        operations::{records::NewExecutedTransaction, OperationsSchema},
+    },
+    subsidy_campaigns::SubsidyCampaignsSchema,
+    QueryResult, StorageProcessor,
+};
+
+fn new_campaign(partner: &str, budget_usd_scaled: u64) -> NewSubsidyCampaign {
+    NewSubsidyCampaign {
+        partner: partner.to_string(),
+        fee_types: vec![OutputFeeType::ChangePubKey(
+            ChangePubKeyFeeTypeArg::ContractsV4Version(ChangePubKeyType::ECDSA),
+        )],
+        budget_usd_scaled,
+        expires_at: Utc::now() + Duration::days(1),
+    }
+}
+
+fn executed_tx(tx_hash: &TxHash, success: bool) -> NewExecutedTransaction {
+    NewExecutedTransaction {
+        block_number: 1,
+        tx_hash: tx_hash.as_ref().to_vec(),
+        tx: Default::default(),
+        operation: Default::default(),
+        from_account: Default::default(),
+        to_account: None,
+        success,
+        fail_reason: None,
+        block_index: None,
+        primary_account_address: Default::default(),
+        nonce: Default::default(),
+        created_at: Utc::now(),
+        eth_sign_data: None,
+        batch_id: None,
+        affected_accounts: Vec::new(),
+        used_tokens: Vec::new(),
+        fee_only_token: None,
+    }
+}
+
+fn transfer() -> SignedZkSyncTx {
+    let transfer = Transfer::new(
+        AccountId(1),
+        Address::random(),
+        Address::random(),
+        TokenId(0),
+        100u32.into(),
+        10u32.into(),
+        Nonce(0),
+        Default::default(),
+        None,
+    );
+
+    SignedZkSyncTx {
+        tx: ZkSyncTx::Transfer(Box::new(transfer)),
+        eth_sign_data: None,
+        created_at: Utc::now(),
+    }
+}
+
+/// Returns the spent and the reserved budget of the campaign.
+async fn charged(storage: &mut StorageProcessor<'_>, id: i64) -> QueryResult<(u64, u64)> {
+    let campaigns = SubsidyCampaignsSchema(storage).load_campaigns().await?;
+    let campaign = campaigns
+        .into_iter()
+        .find(|campaign| campaign.id == id)
+        .unwrap();
+    Ok((campaign.spent_usd_scaled, campaign.reserved_usd_scaled))
+}
+
+/// Checks that the campaigns can be created, topped up and disabled,
+/// and only the active ones are loaded for the fee calculation.
+#[db_test]
+async fn subsidy_campaigns(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let first = SubsidyCampaignsSchema(&mut storage)
+        .create_campaign(&new_campaign("first", 1_000_000))
+        .await?;
+    let mut expired = new_campaign("expired", 1_000_000);
+    expired.expires_at = Utc::now() - Duration::seconds(1);
+    let expired = SubsidyCampaignsSchema(&mut storage)
+        .create_campaign(&expired)
+        .await?;
+    let empty = SubsidyCampaignsSchema(&mut storage)
+        .create_campaign(&new_campaign("empty", 0))
+        .await?;
+    assert!(first.enabled);
+    assert_eq!(first.spent_usd_scaled, 0);
+
+    let active = SubsidyCampaignsSchema(&mut storage)
+        .load_active_campaigns(Utc::now())
+        .await?;
+    assert_eq!(active, vec![first.clone()]);
+
+    let empty = SubsidyCampaignsSchema(&mut storage)
+        .top_up_campaign(empty.id, 500_000)
+        .await?
+        .unwrap();
+    assert_eq!(empty.budget_usd_scaled, 500_000);
+    let first = SubsidyCampaignsSchema(&mut storage)
+        .disable_campaign(first.id)
+        .await?
+        .unwrap();
+    assert!(!first.enabled);
+    let active = SubsidyCampaignsSchema(&mut storage)
+        .load_active_campaigns(Utc::now())
+        .await?;
+    assert_eq!(active, vec![empty.clone()]);
+
+    assert_eq!(
+        SubsidyCampaignsSchema(&mut storage)
+            .load_campaigns()
+            .await?,
+        vec![first, expired, empty]
+    );
+    assert!(SubsidyCampaignsSchema(&mut storage)
+        .top_up_campaign(-1, 1)
+        .await?
+        .is_none());
+    assert!(SubsidyCampaignsSchema(&mut storage)
+        .disable_campaign(-1)
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Checks that the subsidy reserved in the campaign budget is charged once the transaction is
+/// executed successfully, and is charged only once.
+#[db_test]
+async fn subsidy_campaign_charges(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let campaign = SubsidyCampaignsSchema(&mut storage)
+        .create_campaign(&new_campaign("partner", 1_000_000))
+        .await?;
+    let succeeded = TxHash::from_slice(&[1; 32]).unwrap();
+    let failed = TxHash::from_slice(&[2; 32]).unwrap();
+    assert!(
+        SubsidyCampaignsSchema(&mut storage)
+            .reserve_campaign_charge(&succeeded, campaign.id, 300_000)
+            .await?
+    );
+    assert!(
+        SubsidyCampaignsSchema(&mut storage)
+            .reserve_campaign_charge(&failed, campaign.id, 200_000)
+            .await?
+    );
+
+    // Nothing is spent until the transactions are executed.
+    assert_eq!(charged(&mut storage, campaign.id).await?, (0, 500_000));
+
+    OperationsSchema(&mut storage)
+        .store_executed_tx(executed_tx(&failed, false))
+        .await?;
+    assert_eq!(charged(&mut storage, campaign.id).await?, (0, 300_000));
+
+    OperationsSchema(&mut storage)
+        .store_executed_tx(executed_tx(&succeeded, true))
+        .await?;
+    assert_eq!(charged(&mut storage, campaign.id).await?, (300_000, 0));
+
+    // Storing the executed transaction again doesn't charge the subsidy twice.
+    OperationsSchema(&mut storage)
+        .store_executed_tx(executed_tx(&succeeded, true))
+        .await?;
+    assert_eq!(charged(&mut storage, campaign.id).await?, (300_000, 0));
+
+    Ok(())
+}
+
+/// Checks that the transactions submitted before the previous ones are executed can't reserve
+/// more than the campaign budget, and the budget of the rejected ones is released.
+#[db_test]
+async fn subsidy_campaign_reservations(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let campaign = SubsidyCampaignsSchema(&mut storage)
+        .create_campaign(&new_campaign("partner", 1_000_000))
+        .await?;
+    let tx_hashes: Vec<_> = (1..=4)
+        .map(|i| TxHash::from_slice(&[i; 32]).unwrap())
+        .collect();
+
+    for tx_hash in &tx_hashes[..3] {
+        assert!(
+            SubsidyCampaignsSchema(&mut storage)
+                .reserve_campaign_charge(tx_hash, campaign.id, 300_000)
+                .await?
+        );
+    }
+    // Only 100_000 is left besides the reserved budget.
+    assert!(
+        !SubsidyCampaignsSchema(&mut storage)
+            .reserve_campaign_charge(&tx_hashes[3], campaign.id, 300_000)
+            .await?
+    );
+    assert_eq!(charged(&mut storage, campaign.id).await?, (0, 900_000));
+    let active = SubsidyCampaignsSchema(&mut storage)
+        .load_active_campaigns(Utc::now())
+        .await?;
+    assert_eq!(active[0].remaining_usd_scaled(), 100_000);
+
+    // Reserving the subsidy of the resubmitted transaction again replaces the previous reservation.
+    assert!(
+        SubsidyCampaignsSchema(&mut storage)
+            .reserve_campaign_charge(&tx_hashes[0], campaign.id, 400_000)
+            .await?
+    );
+    assert_eq!(charged(&mut storage, campaign.id).await?, (0, 1_000_000));
+    assert!(SubsidyCampaignsSchema(&mut storage)
+        .load_active_campaigns(Utc::now())
+        .await?
+        .is_empty());
+
+    // The rejected transaction releases its subsidy.
+    SubsidyCampaignsSchema(&mut storage)
+        .release_campaign_charges(&tx_hashes[0])
+        .await?;
+    assert_eq!(charged(&mut storage, campaign.id).await?, (0, 600_000));
+
+    // The stale reservations are released unless their transactions are in the mempool.
+    let tx = transfer();
+    let in_mempool = tx.hash();
+    assert!(
+        SubsidyCampaignsSchema(&mut storage)
+            .reserve_campaign_charge(&in_mempool, campaign.id, 100_000)
+            .await?
+    );
+    MempoolSchema(&mut storage).insert_tx(&tx).await?;
+    assert_eq!(
+        SubsidyCampaignsSchema(&mut storage)
+            .release_stale_campaign_charges(Utc::now() - Duration::minutes(1))
+            .await?,
+        0
+    );
+    assert_eq!(
+        SubsidyCampaignsSchema(&mut storage)
+            .release_stale_campaign_charges(Utc::now())
+            .await?,
+        2
+    );
+    assert_eq!(charged(&mut storage, campaign.id).await?, (0, 100_000));
+
+    Ok(())
+}
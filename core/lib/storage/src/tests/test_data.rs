@@ -0,0 +1,52 @@
+// Workspace imports
+use zksync_types::{AccountMap, AccountUpdate};
+// Local imports
+use crate::test_data::{gen_random_updates, TestRng, UpdateMix};
+
+/// Checks that the same seed produces exactly the same test data, and the different seeds don't.
+#[test]
+fn same_seed_same_updates() {
+    let generate = |seed| {
+        let mut rng = TestRng::from_seed(seed);
+        let updates =
+            gen_random_updates(&AccountMap::default(), 50, UpdateMix::default(), &mut rng);
+        serde_json::to_vec(&updates).unwrap()
+    };
+
+    assert_eq!(generate([1, 2, 3, 4]), generate([1, 2, 3, 4]));
+    assert_ne!(generate([1, 2, 3, 4]), generate([4, 3, 2, 1]));
+}
+
+/// Checks that the seed printed by the RNG can be used to recreate it.
+#[test]
+fn seed_hex_roundtrip() {
+    let rng = TestRng::from_seed([0xdead_beef, 1, 2, 3]);
+    assert_eq!(rng.seed_hex(), "deadbeef000000010000000200000003");
+    assert_eq!(TestRng::parse_seed(&rng.seed_hex()), Some(rng.seed()));
+
+    assert_eq!(TestRng::parse_seed("deadbeef"), None);
+    assert_eq!(TestRng::parse_seed(&"0".repeat(32)), None);
+}
+
+/// Checks that only the kinds of updates allowed by the mix are generated.
+#[test]
+fn update_mix() {
+    let mut rng = TestRng::from_seed([1, 2, 3, 4]);
+    let mix = UpdateMix {
+        creates: 1,
+        pubkey_changes: 3,
+        ..UpdateMix::none()
+    };
+    let updates = gen_random_updates(&AccountMap::default(), 100, mix, &mut rng);
+
+    assert_eq!(updates.len(), 100);
+    // There are no accounts yet, so the first update has to create one.
+    assert!(matches!(updates[0].1, AccountUpdate::Create { .. }));
+    assert!(updates.iter().all(|(_, update)| matches!(
+        update,
+        AccountUpdate::Create { .. } | AccountUpdate::ChangePubKeyHash { .. }
+    )));
+    assert!(updates
+        .iter()
+        .any(|(_, update)| matches!(update, AccountUpdate::ChangePubKeyHash { .. })));
+}
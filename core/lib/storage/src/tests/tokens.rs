@@ -4,10 +4,12 @@ use std::str::FromStr;
 use chrono::Utc;
 use num::{rational::Ratio, BigUint};
 // Workspace imports
+use zksync_api_types::TokenListingStatus;
 use zksync_test_account::ZkSyncAccount;
 use zksync_types::{
-    tokens::TokenMarketVolume, AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx,
-    Token, TokenId, TokenKind, TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
+    tokens::{NewTokenEvent, TokenMarketVolume},
+    AccountId, Address, BlockNumber, ExecutedOperations, ExecutedTx, Token, TokenId, TokenKind,
+    TokenLike, TokenPrice, WithdrawNFTOp, ZkSyncOp, H256,
 };
 use zksync_utils::{big_decimal_to_ratio, ratio_to_big_decimal};
 // Local imports
@@ -135,6 +137,20 @@ async fn tokens_storage(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
     Ok(())
 }
 
+/// Checks that the max token ids are `0` rather than an error if there are no tokens at all,
+/// so that the `latest` pagination cursor resolves to an empty page.
+#[db_test]
+async fn max_token_id_without_tokens(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    sqlx::query("DELETE FROM tokens")
+        .execute(storage.conn())
+        .await?;
+    assert_eq!(storage.tokens_schema().get_count().await?, 0);
+    assert_eq!(storage.tokens_schema().get_max_token_id().await?, 0);
+    assert_eq!(storage.tokens_schema().get_max_erc20_token_id().await?, 0);
+
+    Ok(())
+}
+
 /// Checks the store/load routine for `ticker_price` table.
 #[db_test]
 async fn test_ticker_price(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
@@ -342,3 +358,98 @@ async fn test_nfts_with_factories(mut storage: StorageProcessor<'_>) -> QueryRes
 
     Ok(())
 }
+
+/// Checks the review flow of the token listings held back by the listing policy.
+#[db_test]
+async fn rejected_token_listings(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let events: Vec<_> = (1..=2)
+        .map(|id| NewTokenEvent {
+            eth_block_number: 10,
+            address: Address::from_low_u64_be(id),
+            id: TokenId(id as u32),
+            eth_tx_hash: Some(H256::from_low_u64_be(id)),
+        })
+        .collect();
+    for event in &events {
+        storage
+            .tokens_schema()
+            .record_rejected_token_listing(event, "Insufficient listing fee")
+            .await?;
+    }
+    // Recording the same listing again doesn't change it.
+    storage
+        .tokens_schema()
+        .record_rejected_token_listing(&events[0], "Other reason")
+        .await?;
+
+    let listings = storage
+        .tokens_schema()
+        .load_rejected_token_listings()
+        .await?;
+    assert_eq!(listings.len(), 2);
+    assert_eq!(listings[0].token_id, TokenId(1));
+    assert_eq!(listings[0].address, events[0].address);
+    assert_eq!(listings[0].eth_tx_hash, events[0].eth_tx_hash);
+    assert_eq!(listings[0].reason, "Insufficient listing fee");
+    assert!(listings
+        .iter()
+        .all(|listing| listing.status == TokenListingStatus::Pending));
+    assert!(storage
+        .tokens_schema()
+        .load_approved_token_listings()
+        .await?
+        .is_empty());
+
+    // Approve the first listing and reject the second one.
+    assert!(
+        storage
+            .tokens_schema()
+            .review_token_listing(TokenId(1), TokenListingStatus::Approved)
+            .await?
+    );
+    assert!(
+        storage
+            .tokens_schema()
+            .review_token_listing(TokenId(2), TokenListingStatus::Rejected)
+            .await?
+    );
+    // Unknown listings can't be reviewed.
+    assert!(
+        !storage
+            .tokens_schema()
+            .review_token_listing(TokenId(3), TokenListingStatus::Approved)
+            .await?
+    );
+
+    let approved = storage
+        .tokens_schema()
+        .load_approved_token_listings()
+        .await?;
+    assert_eq!(approved.len(), 1);
+    assert_eq!(approved[0].id, TokenId(1));
+    assert_eq!(approved[0].address, events[0].address);
+
+    // Approved listing can't be rejected anymore.
+    assert!(
+        !storage
+            .tokens_schema()
+            .review_token_listing(TokenId(1), TokenListingStatus::Rejected)
+            .await?
+    );
+
+    // Once the token is registered, the listing is removed.
+    storage
+        .tokens_schema()
+        .remove_token_listing(TokenId(1))
+        .await?;
+    let listings = storage
+        .tokens_schema()
+        .load_rejected_token_listings()
+        .await?;
+    assert_eq!(listings.len(), 1);
+    assert_eq!(listings[0].token_id, TokenId(2));
+    assert_eq!(listings[0].status, TokenListingStatus::Rejected);
+    assert!(listings[0].reviewed_at.is_some());
+
+    Ok(())
+}
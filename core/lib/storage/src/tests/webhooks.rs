@@ -0,0 +1,154 @@
+// External imports
+use chrono::{Duration, Utc};
+use serde_json::json;
+// Workspace imports
+use zksync_api_types::{WebhookDeliveryAttempt, WebhookDeliveryStatus};
+use zksync_types::event::EventId;
+// Local imports
+use crate::tests::db_test;
+use crate::{webhooks::WebhooksSchema, QueryResult, StorageProcessor};
+
+/// Checks that the webhooks can be stored, loaded and removed.
+#[db_test]
+async fn webhooks(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let filter = json!({ "accounts": [1, 2] });
+    let first = WebhooksSchema(&mut storage)
+        .create_webhook("http://localhost:1/first", "secret", &filter)
+        .await?;
+    let second = WebhooksSchema(&mut storage)
+        .create_webhook("http://localhost:1/second", "secret", &json!({}))
+        .await?;
+    assert_eq!(first.filter, filter);
+    assert_eq!(
+        WebhooksSchema(&mut storage).load_webhook(first.id).await?,
+        Some(first.clone())
+    );
+    assert_eq!(
+        WebhooksSchema(&mut storage).load_active_webhooks().await?,
+        vec![first.clone(), second.clone()]
+    );
+
+    assert!(
+        WebhooksSchema(&mut storage)
+            .remove_webhook(first.id)
+            .await?
+    );
+    assert!(
+        !WebhooksSchema(&mut storage)
+            .remove_webhook(first.id)
+            .await?
+    );
+    assert_eq!(
+        WebhooksSchema(&mut storage).load_active_webhooks().await?,
+        vec![second]
+    );
+    // Removed webhooks are kept.
+    let removed = WebhooksSchema(&mut storage)
+        .load_webhook(first.id)
+        .await?
+        .unwrap();
+    assert!(removed.removed_at.is_some());
+
+    Ok(())
+}
+
+/// Checks the lifecycle of the webhook deliveries.
+#[db_test]
+async fn webhook_deliveries(mut storage: StorageProcessor<'_>) -> QueryResult<()> {
+    let webhook = WebhooksSchema(&mut storage)
+        .create_webhook("http://localhost:1/", "secret", &json!({}))
+        .await?;
+    let deliveries = vec![
+        (webhook.id, EventId(1), json!({ "id": 1 })),
+        (webhook.id, EventId(2), json!({ "id": 2 })),
+    ];
+    WebhooksSchema(&mut storage)
+        .enqueue_webhook_deliveries(&deliveries)
+        .await?;
+    // Events are queued only once.
+    WebhooksSchema(&mut storage)
+        .enqueue_webhook_deliveries(&deliveries[..1])
+        .await?;
+
+    let claimed_until = Utc::now() + Duration::minutes(1);
+    let due = WebhooksSchema(&mut storage)
+        .claim_due_webhook_deliveries(Utc::now(), claimed_until, 10)
+        .await?;
+    assert_eq!(due.len(), 2);
+    assert_eq!(due[0].url, webhook.url);
+    assert_eq!(due[0].secret, "secret");
+    assert_eq!(due[0].payload, json!({ "id": 1 }));
+    assert_eq!(due[0].attempts, 0);
+    // Claimed deliveries are not handed out again until the claim expires.
+    assert!(WebhooksSchema(&mut storage)
+        .claim_due_webhook_deliveries(Utc::now(), claimed_until, 10)
+        .await?
+        .is_empty());
+
+    // The first delivery fails and is retried later, the second one succeeds.
+    let failed = WebhookDeliveryAttempt {
+        attempt: 1,
+        response_status: Some(500),
+        error: None,
+        attempted_at: Utc::now(),
+    };
+    let next_attempt_at = Utc::now() + Duration::minutes(2);
+    WebhooksSchema(&mut storage)
+        .record_webhook_delivery_attempt(
+            due[0].id,
+            &failed,
+            WebhookDeliveryStatus::Pending,
+            next_attempt_at,
+        )
+        .await?;
+    let succeeded = WebhookDeliveryAttempt {
+        attempt: 1,
+        response_status: Some(200),
+        error: None,
+        attempted_at: Utc::now(),
+    };
+    WebhooksSchema(&mut storage)
+        .record_webhook_delivery_attempt(
+            due[1].id,
+            &succeeded,
+            WebhookDeliveryStatus::Delivered,
+            Utc::now(),
+        )
+        .await?;
+
+    assert!(WebhooksSchema(&mut storage)
+        .claim_due_webhook_deliveries(claimed_until, claimed_until, 10)
+        .await?
+        .is_empty());
+    let due = WebhooksSchema(&mut storage)
+        .claim_due_webhook_deliveries(next_attempt_at, next_attempt_at, 10)
+        .await?;
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].attempts, 1);
+
+    let deliveries = WebhooksSchema(&mut storage)
+        .load_webhook_deliveries(webhook.id, 10)
+        .await?;
+    assert_eq!(deliveries.len(), 2);
+    assert_eq!(deliveries[0].event_id, 2);
+    assert_eq!(deliveries[0].status, WebhookDeliveryStatus::Delivered);
+    assert_eq!(deliveries[1].status, WebhookDeliveryStatus::Pending);
+    assert_eq!(deliveries[1].attempts, 1);
+    let journal = WebhooksSchema(&mut storage)
+        .load_webhook_delivery_attempts(deliveries[1].id)
+        .await?;
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal[0].attempt, 1);
+    assert_eq!(journal[0].response_status, Some(500));
+
+    // Deliveries of the removed webhooks are not attempted.
+    WebhooksSchema(&mut storage)
+        .remove_webhook(webhook.id)
+        .await?;
+    assert!(WebhooksSchema(&mut storage)
+        .claim_due_webhook_deliveries(next_attempt_at, next_attempt_at, 10)
+        .await?
+        .is_empty());
+
+    Ok(())
+}
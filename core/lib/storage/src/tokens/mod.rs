@@ -2,18 +2,27 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 // External imports
+use chrono::Utc;
 use num::{rational::Ratio, BigUint};
 
 use thiserror::Error;
 // Workspace imports
-use zksync_api_types::v02::{
-    pagination::{PaginationDirection, PaginationQuery},
-    token::ApiNFT,
+use zksync_api_types::{
+    v02::{
+        pagination::{PaginationDirection, PaginationQuery},
+        token::ApiNFT,
+    },
+    RejectedTokenListing, TokenListingStatus,
+};
+use zksync_types::{
+    tokens::NewTokenEvent, AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT,
 };
-use zksync_types::{AccountId, Address, Token, TokenId, TokenLike, TokenPrice, NFT};
 use zksync_utils::ratio_to_big_decimal;
 // Local imports
-use self::records::{DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT, TokenKind};
+use self::records::{
+    token_listing_status_to_str, DBMarketVolume, DbTickerPrice, DbToken, StorageApiNFT, StorageNFT,
+    StoredRejectedTokenListing, TokenKind,
+};
 
 use crate::utils::address_to_stored_string;
 use crate::{QueryResult, StorageProcessor};
@@ -321,14 +330,15 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     /// Get the max token ID of ERC20 tokens from Database
     pub async fn get_max_erc20_token_id(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
+        // `max` is `NULL` if there are no tokens.
         let last_token_id = sqlx::query!(
             r#"
-            SELECT max(id) as "id!" FROM tokens WHERE kind = 'ERC20'::token_kind
+            SELECT max(id) as "id?" FROM tokens WHERE kind = 'ERC20'::token_kind
             "#,
         )
-        .fetch_optional(self.0.conn())
+        .fetch_one(self.0.conn())
         .await?
-        .map(|token| token.id)
+        .id
         .unwrap_or(0);
 
         metrics::histogram!("sql.token.get_max_erc20_token_id", start.elapsed());
@@ -338,14 +348,15 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
     /// Get the max token ID of non-NFT tokens from Database
     pub async fn get_max_token_id(&mut self) -> QueryResult<u32> {
         let start = Instant::now();
+        // `max` is `NULL` if there are no tokens.
         let last_token_id = sqlx::query!(
             r#"
-            SELECT max(id) as "id!" FROM tokens WHERE kind != 'NFT'::token_kind
+            SELECT max(id) as "id?" FROM tokens WHERE kind != 'NFT'::token_kind
             "#,
         )
-        .fetch_optional(self.0.conn())
+        .fetch_one(self.0.conn())
         .await?
-        .map(|token| token.id)
+        .id
         .unwrap_or(0);
 
         metrics::histogram!("sql.token.get_max_token_id", start.elapsed());
@@ -582,4 +593,114 @@ impl<'a, 'c> TokensSchema<'a, 'c> {
         metrics::histogram!("sql.token.store_nft_factory", start.elapsed());
         Ok(())
     }
+
+    /// Records the token listing that wasn't registered because of the listing policy.
+    /// The listing awaits the operator review, already recorded listings are left intact.
+    pub async fn record_rejected_token_listing(
+        &mut self,
+        event: &NewTokenEvent,
+        reason: &str,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            r#"
+            INSERT INTO rejected_token_listings ( token_id, address, eth_block_number, eth_tx_hash, reason, created_at )
+            VALUES ( $1, $2, $3, $4, $5, $6 )
+            ON CONFLICT ( token_id )
+            DO NOTHING
+            "#,
+            *event.id as i32,
+            address_to_stored_string(&event.address),
+            event.eth_block_number as i64,
+            event.eth_tx_hash.map(|hash| hash.as_bytes().to_vec()),
+            reason,
+            Utc::now(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.record_rejected_token_listing", start.elapsed());
+        Ok(())
+    }
+
+    /// Loads the token listings that weren't registered because of the listing policy.
+    pub async fn load_rejected_token_listings(&mut self) -> QueryResult<Vec<RejectedTokenListing>> {
+        let start = Instant::now();
+        let listings = sqlx::query_as!(
+            StoredRejectedTokenListing,
+            "SELECT * FROM rejected_token_listings ORDER BY token_id"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(RejectedTokenListing::from)
+        .collect();
+
+        metrics::histogram!("sql.token.load_rejected_token_listings", start.elapsed());
+        Ok(listings)
+    }
+
+    /// Sets the status of the token listing that wasn't approved yet.
+    /// Returns `false` if there is no such listing.
+    pub async fn review_token_listing(
+        &mut self,
+        token_id: TokenId,
+        status: TokenListingStatus,
+    ) -> QueryResult<bool> {
+        let start = Instant::now();
+        let result = sqlx::query!(
+            r#"
+            UPDATE rejected_token_listings
+            SET status = $2, reviewed_at = $3
+            WHERE token_id = $1 AND status != 'approved'
+            "#,
+            *token_id as i32,
+            token_listing_status_to_str(status),
+            Utc::now(),
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.review_token_listing", start.elapsed());
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Loads the approved token listings that are yet to be registered.
+    pub async fn load_approved_token_listings(&mut self) -> QueryResult<Vec<NewTokenEvent>> {
+        let start = Instant::now();
+        let listings = sqlx::query_as!(
+            StoredRejectedTokenListing,
+            "SELECT * FROM rejected_token_listings WHERE status = 'approved' ORDER BY token_id"
+        )
+        .fetch_all(self.0.conn())
+        .await?
+        .into_iter()
+        .map(|listing| {
+            let listing = RejectedTokenListing::from(listing);
+            NewTokenEvent {
+                eth_block_number: listing.eth_block_number,
+                address: listing.address,
+                id: listing.token_id,
+                eth_tx_hash: listing.eth_tx_hash,
+            }
+        })
+        .collect();
+
+        metrics::histogram!("sql.token.load_approved_token_listings", start.elapsed());
+        Ok(listings)
+    }
+
+    /// Removes the listing once the token is registered.
+    pub async fn remove_token_listing(&mut self, token_id: TokenId) -> QueryResult<()> {
+        let start = Instant::now();
+        sqlx::query!(
+            "DELETE FROM rejected_token_listings WHERE token_id = $1",
+            *token_id as i32
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.token.remove_token_listing", start.elapsed());
+        Ok(())
+    }
 }
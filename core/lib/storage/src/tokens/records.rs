@@ -7,7 +7,7 @@ use sqlx::{types::BigDecimal, FromRow};
 // Local imports
 use crate::utils::{address_to_stored_string, stored_str_address_to_address};
 use chrono::{DateTime, Utc};
-use zksync_api_types::v02::token::ApiNFT;
+use zksync_api_types::{v02::token::ApiNFT, RejectedTokenListing, TokenListingStatus};
 use zksync_types::{
     tokens::{TokenMarketVolume, TokenPrice},
     AccountId, Address, Token, TokenId, H256, NFT,
@@ -168,3 +168,44 @@ impl From<DBMarketVolume> for TokenMarketVolume {
         }
     }
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StoredRejectedTokenListing {
+    pub token_id: i32,
+    pub address: String,
+    pub eth_block_number: i64,
+    pub eth_tx_hash: Option<Vec<u8>>,
+    pub reason: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+pub(crate) fn token_listing_status_to_str(status: TokenListingStatus) -> &'static str {
+    match status {
+        TokenListingStatus::Pending => "pending",
+        TokenListingStatus::Approved => "approved",
+        TokenListingStatus::Rejected => "rejected",
+    }
+}
+
+impl From<StoredRejectedTokenListing> for RejectedTokenListing {
+    fn from(val: StoredRejectedTokenListing) -> Self {
+        let status = match val.status.as_str() {
+            "pending" => TokenListingStatus::Pending,
+            "approved" => TokenListingStatus::Approved,
+            "rejected" => TokenListingStatus::Rejected,
+            status => panic!("Unknown token listing status: {}", status),
+        };
+        Self {
+            token_id: TokenId(val.token_id as u32),
+            address: stored_str_address_to_address(&val.address),
+            eth_block_number: val.eth_block_number as u64,
+            eth_tx_hash: val.eth_tx_hash.map(|hash| H256::from_slice(&hash)),
+            reason: val.reason,
+            status,
+            created_at: val.created_at,
+            reviewed_at: val.reviewed_at,
+        }
+    }
+}
@@ -1,5 +1,8 @@
 use crate::{QueryResult, StorageProcessor};
+use num::{bigint::ToBigInt, BigInt};
+use sqlx::types::BigDecimal;
 use zksync_types::{Address, ZkSyncTx};
+use zksync_utils::BigUintSerdeWrapper;
 
 pub(crate) fn address_to_stored_string(address: &Address) -> String {
     format!("0x{:x}", address)
@@ -12,6 +15,23 @@ pub(crate) fn stored_str_address_to_address(address: &str) -> Address {
         .expect("failed to parse stored db address")
 }
 
+pub(crate) fn l1_surplus_to_stored(surplus: &Option<BigUintSerdeWrapper>) -> Option<BigDecimal> {
+    surplus
+        .as_ref()
+        .map(|surplus| BigDecimal::from(BigInt::from(surplus.0.clone())))
+}
+
+pub(crate) fn stored_l1_surplus(surplus: Option<BigDecimal>) -> Option<BigUintSerdeWrapper> {
+    surplus.map(|surplus| {
+        let surplus = surplus
+            .to_bigint()
+            .and_then(|int| int.to_biguint())
+            // Only the non-negative integer surpluses are stored.
+            .expect("Invalid L1 surplus has been stored");
+        BigUintSerdeWrapper(surplus)
+    })
+}
+
 pub(crate) async fn affected_accounts(
     tx: &ZkSyncTx,
     storage: &mut StorageProcessor<'_>,
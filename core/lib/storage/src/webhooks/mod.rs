@@ -0,0 +1,265 @@
+// Built-in deps
+use std::time::Instant;
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+// Workspace imports
+use zksync_api_types::{
+    WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryStatus, WebhookInfo,
+};
+use zksync_types::event::EventId;
+// Local imports
+use self::records::{
+    DueWebhookDelivery, StorageWebhook, StorageWebhookDelivery, StorageWebhookDeliveryAttempt,
+};
+use crate::{QueryResult, StorageProcessor};
+
+pub mod records;
+
+/// Webhooks schema stores the HTTP endpoints notified about the account events,
+/// the queue of the event deliveries and the journal of the delivery attempts.
+#[derive(Debug)]
+pub struct WebhooksSchema<'a, 'c>(pub &'a mut StorageProcessor<'c>);
+
+impl<'a, 'c> WebhooksSchema<'a, 'c> {
+    /// Stores the new webhook.
+    pub async fn create_webhook(
+        &mut self,
+        url: &str,
+        secret: &str,
+        filter: &Value,
+    ) -> QueryResult<WebhookInfo> {
+        let start = Instant::now();
+        let webhook = sqlx::query_as!(
+            StorageWebhook,
+            r#"
+            INSERT INTO webhooks (url, secret, filter, created_at)
+            VALUES ($1, $2, $3, now())
+            RETURNING *
+            "#,
+            url,
+            secret,
+            filter
+        )
+        .fetch_one(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.create_webhook", start.elapsed());
+        Ok(webhook.into())
+    }
+
+    /// Removes the webhook, so it's no longer notified. The webhook itself is kept for the journal.
+    /// Returns `false` if there is no active webhook with such id.
+    pub async fn remove_webhook(&mut self, id: i64) -> QueryResult<bool> {
+        let start = Instant::now();
+        let removed = sqlx::query!(
+            "UPDATE webhooks SET removed_at = now() WHERE id = $1 AND removed_at IS NULL",
+            id
+        )
+        .execute(self.0.conn())
+        .await?
+        .rows_affected()
+            > 0;
+
+        metrics::histogram!("sql.webhooks.remove_webhook", start.elapsed());
+        Ok(removed)
+    }
+
+    /// Loads the webhook by its id.
+    pub async fn load_webhook(&mut self, id: i64) -> QueryResult<Option<WebhookInfo>> {
+        let start = Instant::now();
+        let webhook = sqlx::query_as!(StorageWebhook, "SELECT * FROM webhooks WHERE id = $1", id)
+            .fetch_optional(self.0.conn())
+            .await?;
+
+        metrics::histogram!("sql.webhooks.load_webhook", start.elapsed());
+        Ok(webhook.map(Into::into))
+    }
+
+    /// Loads all the webhooks that weren't removed.
+    pub async fn load_active_webhooks(&mut self) -> QueryResult<Vec<WebhookInfo>> {
+        let start = Instant::now();
+        let webhooks = sqlx::query_as!(
+            StorageWebhook,
+            "SELECT * FROM webhooks WHERE removed_at IS NULL ORDER BY id ASC"
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.load_active_webhooks", start.elapsed());
+        Ok(webhooks.into_iter().map(Into::into).collect())
+    }
+
+    /// Queues the events for the delivery. Each entry is the webhook id, the event id
+    /// and the payload to be sent. Events already queued for the webhook are skipped.
+    pub async fn enqueue_webhook_deliveries(
+        &mut self,
+        deliveries: &[(i64, EventId, Value)],
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        if deliveries.is_empty() {
+            return Ok(());
+        }
+
+        let mut webhook_ids = Vec::with_capacity(deliveries.len());
+        let mut event_ids = Vec::with_capacity(deliveries.len());
+        let mut payloads = Vec::with_capacity(deliveries.len());
+        for (webhook_id, event_id, payload) in deliveries {
+            webhook_ids.push(*webhook_id);
+            event_ids.push(**event_id as i64);
+            payloads.push(payload.clone());
+        }
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_deliveries (webhook_id, event_id, payload, status, attempts, next_attempt_at, created_at)
+            SELECT u.webhook_id, u.event_id, u.payload, 'pending', 0, now(), now()
+                FROM UNNEST ($1::bigint[], $2::bigint[], $3::jsonb[])
+                AS u(webhook_id, event_id, payload)
+            ON CONFLICT (webhook_id, event_id) DO NOTHING
+            "#,
+            &webhook_ids,
+            &event_ids,
+            &payloads
+        )
+        .execute(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.enqueue_webhook_deliveries", start.elapsed());
+        Ok(())
+    }
+
+    /// Claims the pending deliveries of the active webhooks which should be attempted at `now`.
+    /// The claimed deliveries are not due again until `claimed_until`, so the concurrent dispatchers
+    /// don't attempt them twice. If the attempt is never recorded, the delivery is retried afterwards.
+    pub async fn claim_due_webhook_deliveries(
+        &mut self,
+        now: DateTime<Utc>,
+        claimed_until: DateTime<Utc>,
+        limit: u32,
+    ) -> QueryResult<Vec<DueWebhookDelivery>> {
+        let start = Instant::now();
+        let mut deliveries = sqlx::query_as!(
+            DueWebhookDelivery,
+            r#"
+            WITH due AS (
+                SELECT webhook_deliveries.id
+                FROM webhook_deliveries
+                INNER JOIN webhooks ON webhooks.id = webhook_deliveries.webhook_id
+                WHERE status = 'pending' AND next_attempt_at <= $1 AND webhooks.removed_at IS NULL
+                ORDER BY webhook_deliveries.id ASC
+                LIMIT $3
+                FOR UPDATE OF webhook_deliveries SKIP LOCKED
+            )
+            UPDATE webhook_deliveries SET next_attempt_at = $2
+            FROM due, webhooks
+            WHERE webhook_deliveries.id = due.id AND webhooks.id = webhook_deliveries.webhook_id
+            RETURNING webhook_deliveries.id, webhook_id, event_id, url, secret, payload, attempts
+            "#,
+            now,
+            claimed_until,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+        deliveries.sort_unstable_by_key(|delivery| delivery.id);
+
+        metrics::histogram!("sql.webhooks.claim_due_webhook_deliveries", start.elapsed());
+        Ok(deliveries)
+    }
+
+    /// Journals the delivery attempt and updates the state of the delivery.
+    /// `next_attempt_at` only matters for the deliveries that are still pending.
+    pub async fn record_webhook_delivery_attempt(
+        &mut self,
+        delivery_id: i64,
+        attempt: &WebhookDeliveryAttempt,
+        status: WebhookDeliveryStatus,
+        next_attempt_at: DateTime<Utc>,
+    ) -> QueryResult<()> {
+        let start = Instant::now();
+        let mut transaction = self.0.start_transaction().await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO webhook_delivery_attempts (delivery_id, attempt, response_status, error, attempted_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+            delivery_id,
+            attempt.attempt as i32,
+            attempt.response_status.map(i32::from),
+            attempt.error,
+            attempt.attempted_at
+        )
+        .execute(transaction.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            UPDATE webhook_deliveries SET status = $2, attempts = $3, next_attempt_at = $4
+            WHERE id = $1
+            "#,
+            delivery_id,
+            status.as_str(),
+            attempt.attempt as i32,
+            next_attempt_at
+        )
+        .execute(transaction.conn())
+        .await?;
+        transaction.commit().await?;
+
+        metrics::histogram!(
+            "sql.webhooks.record_webhook_delivery_attempt",
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Loads the latest deliveries of the webhook, newest first.
+    pub async fn load_webhook_deliveries(
+        &mut self,
+        webhook_id: i64,
+        limit: u32,
+    ) -> QueryResult<Vec<WebhookDelivery>> {
+        let start = Instant::now();
+        let deliveries = sqlx::query_as!(
+            StorageWebhookDelivery,
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY id DESC
+            LIMIT $2
+            "#,
+            webhook_id,
+            i64::from(limit)
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!("sql.webhooks.load_webhook_deliveries", start.elapsed());
+        Ok(deliveries.into_iter().map(Into::into).collect())
+    }
+
+    /// Loads the journal of the delivery attempts, in the order they were made.
+    pub async fn load_webhook_delivery_attempts(
+        &mut self,
+        delivery_id: i64,
+    ) -> QueryResult<Vec<WebhookDeliveryAttempt>> {
+        let start = Instant::now();
+        let attempts = sqlx::query_as!(
+            StorageWebhookDeliveryAttempt,
+            r#"
+            SELECT * FROM webhook_delivery_attempts
+            WHERE delivery_id = $1
+            ORDER BY attempt ASC
+            "#,
+            delivery_id
+        )
+        .fetch_all(self.0.conn())
+        .await?;
+
+        metrics::histogram!(
+            "sql.webhooks.load_webhook_delivery_attempts",
+            start.elapsed()
+        );
+        Ok(attempts.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,89 @@
+// External imports
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::FromRow;
+// Workspace imports
+use zksync_api_types::{WebhookDelivery, WebhookDeliveryAttempt, WebhookInfo};
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageWebhook {
+    pub id: i64,
+    pub url: String,
+    pub secret: String,
+    pub filter: Value,
+    pub created_at: DateTime<Utc>,
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+impl From<StorageWebhook> for WebhookInfo {
+    fn from(val: StorageWebhook) -> Self {
+        Self {
+            id: val.id,
+            url: val.url,
+            filter: val.filter,
+            created_at: val.created_at,
+            removed_at: val.removed_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageWebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: i64,
+    pub payload: Value,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<StorageWebhookDelivery> for WebhookDelivery {
+    fn from(val: StorageWebhookDelivery) -> Self {
+        Self {
+            id: val.id,
+            webhook_id: val.webhook_id,
+            event_id: val.event_id as u64,
+            status: val
+                .status
+                .parse()
+                .expect("Invalid webhook delivery status in the database"),
+            attempts: val.attempts as u32,
+            next_attempt_at: val.next_attempt_at,
+            created_at: val.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct StorageWebhookDeliveryAttempt {
+    pub delivery_id: i64,
+    pub attempt: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+impl From<StorageWebhookDeliveryAttempt> for WebhookDeliveryAttempt {
+    fn from(val: StorageWebhookDeliveryAttempt) -> Self {
+        Self {
+            attempt: val.attempt as u32,
+            response_status: val.response_status.map(|status| status as u16),
+            error: val.error,
+            attempted_at: val.attempted_at,
+        }
+    }
+}
+
+/// Pending delivery which is due to be attempted, together with the webhook it's addressed to.
+#[derive(Debug, Clone, FromRow)]
+pub struct DueWebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event_id: i64,
+    pub url: String,
+    pub secret: String,
+    pub payload: Value,
+    pub attempts: i32,
+}
@@ -0,0 +1,38 @@
+//! API keys issued to the customers of the hosted zkSync API.
+//!
+//! Keys are never stored as is, only their hashes are persisted.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use zksync_basic_types::H256;
+
+/// Part of the API the key gives access to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApiKeyScope {
+    /// Querying the network state.
+    Read,
+    /// Submitting the transactions.
+    Submit,
+}
+
+impl fmt::Display for ApiKeyScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Submit => write!(f, "submit"),
+        }
+    }
+}
+
+/// Generates a new random API key.
+pub fn generate_api_key() -> String {
+    format!("{:x}", H256::random())
+}
+
+/// Returns the hash under which the API key is stored.
+pub fn api_key_hash(key: &str) -> H256 {
+    H256::from(tiny_keccak::keccak256(key.as_bytes()))
+}
@@ -0,0 +1,140 @@
+use std::convert::TryFrom;
+
+use ethabi::{decode, ParamType};
+use thiserror::Error;
+
+use zksync_basic_types::{Log, U256};
+
+use crate::Address;
+
+/// Index of the main zkSync contract among the contracts managed by the upgrade gatekeeper.
+/// Managed contracts are registered in the following order: governance, verifier, zkSync.
+const ZKSYNC_TARGET_INDEX: usize = 2;
+
+#[derive(Debug, Error)]
+#[allow(clippy::large_enum_variant)]
+pub enum ContractUpgradeEventParseError {
+    #[error("Cannot parse log for Upgrade Complete Event {0:?}")]
+    ParseLogError(Log),
+    #[error("Cannot parse log for Upgrade Complete Event {0:?}")]
+    ParseError(ethabi::Error),
+}
+
+/// Event emitted by the upgrade gatekeeper contract once the upgrade is finished.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractUpgradeEvent {
+    /// Version of the contracts after the upgrade.
+    pub version_id: u64,
+    /// New targets of the managed contracts.
+    /// Zero address means that the corresponding contract was not upgraded.
+    pub new_targets: Vec<Address>,
+    pub eth_block: u64,
+}
+
+impl ContractUpgradeEvent {
+    /// Returns the new target of the main zkSync contract, if it was upgraded.
+    pub fn zksync_target(&self) -> Option<Address> {
+        self.new_targets
+            .get(ZKSYNC_TARGET_INDEX)
+            .copied()
+            .filter(|target| !target.is_zero())
+    }
+}
+
+impl TryFrom<Log> for ContractUpgradeEvent {
+    type Error = ContractUpgradeEventParseError;
+
+    fn try_from(event: Log) -> Result<Self, Self::Error> {
+        let eth_block = match event.block_number {
+            Some(block_number) => block_number.as_u64(),
+            None => return Err(ContractUpgradeEventParseError::ParseLogError(event)),
+        };
+
+        let version_id = match event.topics.get(1) {
+            Some(topic) => U256::from_big_endian(topic.as_bytes()).as_u64(),
+            None => return Err(ContractUpgradeEventParseError::ParseLogError(event)),
+        };
+
+        let mut decoded_event = decode(
+            &[
+                ParamType::Array(Box::new(ParamType::Address)), // newTargets
+            ],
+            &event.data.0,
+        )
+        .map_err(ContractUpgradeEventParseError::ParseError)?;
+        let new_targets = decoded_event.remove(0).into_array().and_then(|targets| {
+            targets
+                .into_iter()
+                .map(|target| target.into_address())
+                .collect::<Option<Vec<_>>>()
+        });
+        let new_targets = match new_targets {
+            Some(new_targets) => new_targets,
+            None => return Err(ContractUpgradeEventParseError::ParseLogError(event)),
+        };
+
+        Ok(Self {
+            version_id,
+            new_targets,
+            eth_block,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethabi::{encode, Token};
+    use web3::types::Bytes;
+    use zksync_basic_types::H256;
+
+    fn upgrade_log(topics: Vec<H256>, data: Vec<u8>) -> Log {
+        Log {
+            address: Address::zero(),
+            topics,
+            data: Bytes(data),
+            block_hash: None,
+            block_number: Some(10.into()),
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: None,
+            removed: None,
+        }
+    }
+
+    #[test]
+    fn parse_upgrade_log() {
+        let targets = vec![Address::zero(), Address::zero(), Address::repeat_byte(1)];
+        let data = encode(&[Token::Array(
+            targets.iter().copied().map(Token::Address).collect(),
+        )]);
+        let version_topic = H256::from_low_u64_be(3);
+
+        let event = ContractUpgradeEvent::try_from(upgrade_log(
+            vec![H256::zero(), version_topic],
+            data.clone(),
+        ))
+        .unwrap();
+        assert_eq!(
+            event,
+            ContractUpgradeEvent {
+                version_id: 3,
+                new_targets: targets,
+                eth_block: 10,
+            }
+        );
+        assert_eq!(event.zksync_target(), Some(Address::repeat_byte(1)));
+
+        // Malformed logs are reported as errors instead of panicking.
+        assert!(matches!(
+            ContractUpgradeEvent::try_from(upgrade_log(vec![H256::zero()], data)),
+            Err(ContractUpgradeEventParseError::ParseLogError(_))
+        ));
+        assert!(matches!(
+            ContractUpgradeEvent::try_from(upgrade_log(vec![H256::zero(), version_topic], vec![1])),
+            Err(ContractUpgradeEventParseError::ParseError(_))
+        ));
+    }
+}
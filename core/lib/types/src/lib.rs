@@ -38,7 +38,9 @@
 
 pub mod account;
 pub mod aggregated_operations;
+pub mod api_keys;
 pub mod block;
+pub mod contract_upgrade;
 pub mod ethereum;
 pub mod event;
 pub mod fee;
@@ -60,6 +62,7 @@ mod tests;
 
 pub use self::account::{Account, AccountUpdate, PubKeyHash};
 pub use self::block::{ExecutedOperations, ExecutedPriorityOp, ExecutedTx};
+pub use self::contract_upgrade::ContractUpgradeEvent;
 pub use self::fee::{BatchFee, Fee, OutputFeeType, TotalFee};
 pub use self::operations::{
     ChangePubKeyOp, DepositOp, ForcedExitOp, FullExitOp, MintNFTOp, SwapOp, TransferOp,
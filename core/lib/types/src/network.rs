@@ -14,6 +14,7 @@ use serde::{Deserialize, Serialize};
 /// Network to be used for a zkSync client.
 ///
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "camelCase")]
 pub enum Network {
     /// Ethereum Mainnet.
@@ -10,7 +10,7 @@ use zksync_crypto::params::{
     ACCOUNT_ID_BIT_WIDTH, BALANCE_BIT_WIDTH, CONTENT_HASH_WIDTH, ETH_ADDRESS_BIT_WIDTH,
     FR_ADDRESS_LEN, LEGACY_TOKEN_BIT_WIDTH, SERIAL_ID_WIDTH, TOKEN_BIT_WIDTH, TX_TYPE_BIT_WIDTH,
 };
-use zksync_utils::BigUintSerdeAsRadix10Str;
+use zksync_utils::{BigUintSerdeAsRadix10Str, BigUintSerdeWrapper};
 
 use super::{
     operations::{DepositOp, FullExitOp},
@@ -402,6 +402,14 @@ pub struct PriorityOp {
     /// Transaction index in Ethereum block.
     /// This field must be optional because of backward compatibility.
     pub eth_block_index: Option<u64>,
+    /// Hash of the Ethereum block in which transaction was included.
+    /// This field must be optional because of backward compatibility.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eth_block_hash: Option<H256>,
+    /// Part of the amount paid on L1 that was not credited on L2 (e.g. because of the token
+    /// transfer fee). `None` if not applicable to the operation or not known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_surplus: Option<BigUintSerdeWrapper>,
 }
 
 impl TryFrom<Log> for PriorityOp {
@@ -473,6 +481,9 @@ impl TryFrom<Log> for PriorityOp {
                 .expect("Event block number is missing")
                 .as_u64(),
             eth_block_index: event.transaction_index.map(|index| index.as_u64()),
+            eth_block_hash: event.block_hash,
+            // Not present in the event, has to be derived from the L1 transaction.
+            l1_surplus: None,
         })
     }
 }
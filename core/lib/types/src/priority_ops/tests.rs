@@ -46,6 +46,7 @@ mod backward_compatibility {
         assert_eq!(old_value.deadline_block, new_value.deadline_block);
         assert_eq!(old_value.eth_hash, new_value.eth_hash.as_bytes().to_vec());
         assert_eq!(old_value.eth_block, new_value.eth_block);
+        assert!(new_value.eth_block_hash.is_none());
     }
 
     #[test]
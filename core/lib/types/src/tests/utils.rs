@@ -17,6 +17,8 @@ pub fn create_full_exit_op() -> ExecutedOperations {
             eth_hash: H256::zero(),
             eth_block: 0,
             eth_block_index: None,
+            eth_block_hash: None,
+            l1_surplus: None,
         },
         op: ZkSyncOp::FullExit(Box::new(FullExitOp {
             priority_op,
@@ -19,12 +19,13 @@ pub enum NewTokenEventParseError {
 // Order of the fields is important (from more specific types to less specific types)
 /// Set of values that can be interpreted as a token descriptor.
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[serde(untagged, rename_all = "camelCase")]
 pub enum TokenLike {
     /// ID of the token in the zkSync network.
     Id(TokenId),
     /// Address of the token in the L1.
-    Address(Address),
+    Address(#[cfg_attr(feature = "json_schema", schemars(with = "String"))] Address),
     /// Symbol associated with token, e.g. "ETH".
     Symbol(String),
 }
@@ -187,6 +188,9 @@ pub struct NewTokenEvent {
     pub eth_block_number: u64,
     pub address: Address,
     pub id: TokenId,
+    /// Hash of the transaction that listed the token.
+    #[serde(default)]
+    pub eth_tx_hash: Option<H256>,
 }
 
 impl TryFrom<Log> for NewTokenEvent {
@@ -210,6 +214,7 @@ impl TryFrom<Log> for NewTokenEvent {
             eth_block_number,
             address: Address::from_slice(&event.topics[1].as_fixed_bytes()[12..]),
             id: TokenId(U256::from_big_endian(&event.topics[2].as_fixed_bytes()[..]).as_u32()),
+            eth_tx_hash: event.transaction_hash,
         })
     }
 }
@@ -242,6 +247,7 @@ pub struct TokenMarketVolume {
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Hash, Eq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum ChangePubKeyFeeTypeArg {
     PreContracts4Version {
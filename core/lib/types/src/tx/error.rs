@@ -31,6 +31,9 @@ pub enum TxAddError {
     #[error("EIP1271 signature could not be verified")]
     EIP1271SignatureVerificationFail,
 
+    #[error("Smart contract wallet is not deployed yet, it should be deployed before signing with EIP1271")]
+    EIP1271ContractNotDeployed,
+
     #[error("MissingEthSignature")]
     MissingEthSignature,
 
@@ -57,6 +60,17 @@ pub enum TxAddError {
 
     #[error("Too many Ethereum signatures provided")]
     EthSignaturesLimitExceeded,
+
+    #[error("Transaction failed too many times and is not accepted anymore")]
+    AttemptsExhausted,
+
+    #[error("Transaction with the same nonce is already queued, replacement must pay a higher fee in the same token")]
+    ReplacementFeeTooLow,
+
+    #[error(
+        "Transaction with the same nonce is already picked into a block and can't be replaced"
+    )]
+    ReplacedTxNotQueued,
 }
 
 #[derive(Error, Debug, Copy, Clone, Serialize, Deserialize)]
@@ -81,6 +95,38 @@ pub enum TransactionError {
     CloseError(#[from] close::TransactionError),
 }
 
+impl TransactionError {
+    /// Whether the transaction is rejected because of the incorrect L2 signature.
+    pub fn is_wrong_signature(&self) -> bool {
+        matches!(
+            self,
+            Self::WithdrawError(withdraw::TransactionError::WrongSignature)
+                | Self::TransferError(transfer::TransactionError::WrongSignature)
+                | Self::MintNFTError(mint_nft::TransactionError::WrongSignature)
+                | Self::WithdrawNFTError(withdraw_nft::TransactionError::WrongSignature)
+                | Self::ChangePubKeyError(change_pubkey::TransactionError::WrongSignature)
+                | Self::SwapError(swap::TransactionError::WrongSignature)
+                | Self::ForcedExitError(forced_exit::TransactionError::WrongSignature)
+                | Self::CloseError(close::TransactionError::WrongSignature)
+        )
+    }
+
+    /// Whether the transaction is rejected because its fee is paid in the token
+    /// which is not allowed for paying fees.
+    pub fn is_wrong_fee_token(&self) -> bool {
+        matches!(
+            self,
+            Self::WithdrawError(withdraw::TransactionError::WrongTokenForPayingFee)
+                | Self::TransferError(transfer::TransactionError::WrongTokenForPayingFee)
+                | Self::MintNFTError(mint_nft::TransactionError::WrongFeeToken)
+                | Self::WithdrawNFTError(withdraw_nft::TransactionError::WrongFeeToken)
+                | Self::ChangePubKeyError(change_pubkey::TransactionError::WrongFeeToken)
+                | Self::SwapError(swap::TransactionError::WrongFeeToken)
+                | Self::ForcedExitError(forced_exit::TransactionError::WrongTokenForPayingFee)
+        )
+    }
+}
+
 pub const WRONG_AMOUNT_ERROR: &str = "Specified amount is greater than maximum supported amount";
 pub const WRONG_FEE_ERROR: &str = "Specified fee amount is greater than maximum supported fee";
 pub const FEE_AMOUNT_IS_NOT_PACKABLE: &str = "Specified fee is not packable";
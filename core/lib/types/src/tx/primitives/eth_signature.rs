@@ -26,6 +26,11 @@ pub enum TxEthSignatureVariant {
         Option<TxEthSignature>,
         Option<TxEthSignature>,
     ),
+    /// This is used for transactions with several possible signers (e.g. multisig wallets):
+    /// any signature of the account owner is sufficient.
+    /// Lists of 3 signatures are deserialized as `Triple`, and are treated the same way
+    /// for all transactions except swaps.
+    Multiple(Vec<Option<TxEthSignature>>),
 }
 
 impl Default for TxEthSignatureVariant {
@@ -43,17 +48,30 @@ impl TxEthSignatureVariant {
         match self {
             Self::Single(sig) => sig,
             Self::Triple(sig, _, _) => sig,
+            Self::Multiple(sigs) => sigs.first().unwrap_or(&None),
         }
     }
 
+    /// Returns all the provided signatures, any of which may belong to the transaction signer.
+    /// Not applicable to swaps, since the signatures of the orders are provided along with the transaction one.
+    pub fn signer_signatures(&self) -> Vec<TxEthSignature> {
+        let signatures = match self {
+            Self::Single(sig) => vec![sig],
+            Self::Triple(sig, sig_1, sig_2) => vec![sig, sig_1, sig_2],
+            Self::Multiple(sigs) => sigs.iter().collect(),
+        };
+        signatures.into_iter().flatten().cloned().collect()
+    }
+
     pub fn exists(&self) -> bool {
         self.tx_signature().is_some()
     }
 
-    pub fn orders_signatures(&self) -> (&Option<TxEthSignature>, &Option<TxEthSignature>) {
+    /// Returns the signatures of the swap orders, which are provided only in the `Triple` variant.
+    pub fn orders_signatures(&self) -> Option<(&Option<TxEthSignature>, &Option<TxEthSignature>)> {
         match self {
-            Self::Single(_) => panic!("called orders_signatures() on a Single variant"),
-            Self::Triple(_, order0, order1) => (order0, order1),
+            Self::Triple(_, order0, order1) => Some((order0, order1)),
+            Self::Single(_) | Self::Multiple(_) => None,
         }
     }
 }
@@ -1,5 +1,6 @@
 use std::str::FromStr;
 // External uses
+use parity_crypto::digest::sha256;
 // Workspace uses
 use zksync_basic_types::Address;
 use zksync_utils::format_units;
@@ -54,6 +55,26 @@ fn test_empty_batch() {
     assert!(EthBatchSignData::new(Vec::new(), Vec::new()).is_err());
 }
 
+/// Batch hash returned on submission and stored with the batch is the hash of the concatenated
+/// hashes of its transactions, so the clients can compute it on their own.
+#[test]
+fn test_batch_hash() {
+    let tx_hashes = vec![
+        ZkSyncTx::from(get_transfer()).hash(),
+        ZkSyncTx::from(get_withdraw()).hash(),
+    ];
+    let bytes: Vec<u8> = tx_hashes
+        .iter()
+        .flat_map(|hash| hash.as_ref().to_vec())
+        .collect();
+    let expected = TxHash::from_slice(&*sha256(&bytes)).unwrap();
+    assert_eq!(TxHash::batch_hash(&tx_hashes), expected);
+
+    // The order of the transactions matters.
+    let reversed: Vec<_> = tx_hashes.iter().rev().cloned().collect();
+    assert_ne!(TxHash::batch_hash(&reversed), expected);
+}
+
 /// Checks the correctness of the message `EthBatchSignData::new()` returns.
 #[test]
 fn test_batch_message() {
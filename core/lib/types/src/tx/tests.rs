@@ -575,6 +575,53 @@ fn eth_sign_data_compatibility() {
     assert_eq!(deserialized.message, eth_sign_data.message);
 }
 
+/// Checks that the transaction signature may be provided either as a single value or as a list.
+#[test]
+fn eth_signature_variant_deserialization() {
+    let private_key = "0b43c0f5b5a13a7047408d1f8c8ad32ba5879902ea6212184e0a5d1157281d76"
+        .parse()
+        .unwrap();
+    let signature = TxEthSignature::EthereumSignature(
+        PackedEthSignature::sign(&private_key, b"Sample text").unwrap(),
+    );
+    let value = serde_json::to_value(&signature).unwrap();
+
+    let variant: TxEthSignatureVariant = serde_json::from_value(serde_json::Value::Null).unwrap();
+    assert_eq!(variant, TxEthSignatureVariant::Single(None));
+    assert!(variant.signer_signatures().is_empty());
+
+    let variant: TxEthSignatureVariant = serde_json::from_value(value.clone()).unwrap();
+    assert_eq!(
+        variant,
+        TxEthSignatureVariant::Single(Some(signature.clone()))
+    );
+    assert_eq!(variant.signer_signatures(), vec![signature.clone()]);
+
+    let variant: TxEthSignatureVariant = serde_json::from_value(serde_json::json!([])).unwrap();
+    assert_eq!(variant, TxEthSignatureVariant::Multiple(vec![]));
+    assert_eq!(variant.tx_signature(), &None);
+
+    let variant: TxEthSignatureVariant =
+        serde_json::from_value(serde_json::json!([null, value.clone()])).unwrap();
+    assert_eq!(variant.tx_signature(), &None);
+    assert_eq!(variant.signer_signatures(), vec![signature.clone()]);
+    // Swaps require exactly 3 signatures, so other lists don't provide the orders signatures.
+    assert!(variant.orders_signatures().is_none());
+
+    // Lists of 3 signatures are taken for the swap signatures, but may be used as a list as well.
+    let variant: TxEthSignatureVariant =
+        serde_json::from_value(serde_json::json!([value.clone(), null, value])).unwrap();
+    assert!(matches!(variant, TxEthSignatureVariant::Triple(..)));
+    assert_eq!(
+        variant.orders_signatures(),
+        Some((&None, &Some(signature.clone())))
+    );
+    assert_eq!(
+        variant.signer_signatures(),
+        vec![signature.clone(), signature]
+    );
+}
+
 #[test]
 fn test_check_signature() {
     let (pk, msg) = gen_pk_and_msg();
@@ -184,6 +184,27 @@ impl ZkSyncTx {
         tokens
     }
 
+    /// Returns the token the transaction uses only to pay the fee with,
+    /// if it doesn't transfer or exchange the same token.
+    pub fn fee_only_token(&self) -> Option<TokenId> {
+        let fee_only = match self {
+            ZkSyncTx::ChangePubKey(_) | ZkSyncTx::MintNFT(_) => true,
+            ZkSyncTx::Swap(tx) => {
+                tx.fee_token != tx.orders.0.token_buy && tx.fee_token != tx.orders.0.token_sell
+            }
+            ZkSyncTx::WithdrawNFT(tx) => tx.fee_token != tx.token,
+            ZkSyncTx::Transfer(_)
+            | ZkSyncTx::Withdraw(_)
+            | ZkSyncTx::ForcedExit(_)
+            | ZkSyncTx::Close(_) => false,
+        };
+        if fee_only {
+            Some(self.token_id())
+        } else {
+            None
+        }
+    }
+
     pub fn account_id(&self) -> Result<AccountId, CloseOperationsDisabled> {
         match self {
             ZkSyncTx::Transfer(tx) => Ok(tx.account_id),
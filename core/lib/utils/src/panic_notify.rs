@@ -19,7 +19,8 @@ pub fn spawn_panic_handler() -> (JoinHandle<()>, mpsc::Sender<bool>) {
     let (panic_sender, mut panic_receiver) = mpsc::channel(1);
 
     let handler = tokio::spawn(async move {
-        panic_receiver.next().await.unwrap();
+        // The sender is dropped without a message if the thread is stopped gracefully.
+        panic_receiver.next().await;
     });
     (handler, panic_sender)
 }
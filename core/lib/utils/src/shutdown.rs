@@ -0,0 +1,87 @@
+//! Graceful shutdown of the HTTP servers.
+//!
+//! Once the shutdown is requested, every server stops accepting new requests and lets the in-flight
+//! ones finish. The controller waits for the servers to stop, so the process exits only after
+//! the responses are sent (or the grace period is over).
+
+// Built-in deps
+use std::time::Duration;
+// External uses
+use tokio::sync::{mpsc, watch};
+// Local uses
+
+/// Signal telling the server to shut down.
+///
+/// The controller considers the server stopped once all the clones of its signal are dropped,
+/// so the server should keep the signal until it stops serving the requests.
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal {
+    requested: watch::Receiver<bool>,
+    _stopped: mpsc::Sender<()>,
+}
+
+impl ShutdownSignal {
+    /// Resolves once the shutdown is requested.
+    pub async fn requested(mut self) {
+        loop {
+            if *self.requested.borrow() {
+                return;
+            }
+            // The controller is only dropped when the process is exiting anyway.
+            if self.requested.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Requests the shutdown of the servers and waits for them to stop.
+#[derive(Debug)]
+pub struct ShutdownController {
+    requested: watch::Sender<bool>,
+    signal: ShutdownSignal,
+    stopped: mpsc::Receiver<()>,
+}
+
+impl Default for ShutdownController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownController {
+    pub fn new() -> Self {
+        let (requested_sender, requested) = watch::channel(false);
+        let (stopped_sender, stopped) = mpsc::channel(1);
+        Self {
+            requested: requested_sender,
+            signal: ShutdownSignal {
+                requested,
+                _stopped: stopped_sender,
+            },
+            stopped,
+        }
+    }
+
+    /// Creates the signal for a new server.
+    pub fn signal(&self) -> ShutdownSignal {
+        self.signal.clone()
+    }
+
+    /// Requests the shutdown and waits until all the servers are stopped, but no longer than
+    /// the grace period. Returns `false` if some of the servers are still running.
+    pub async fn shutdown(self, grace_period: Duration) -> bool {
+        let Self {
+            requested,
+            signal,
+            mut stopped,
+        } = self;
+        drop(signal);
+        // Servers may have stopped already, so the signal may have no receivers.
+        requested.send(true).ok();
+        // Nothing is ever sent, the channel is closed once the last signal is dropped.
+        tokio::time::timeout(grace_period, stopped.recv())
+            .await
+            .is_ok()
+    }
+}
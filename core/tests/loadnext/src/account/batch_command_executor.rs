@@ -1,4 +1,4 @@
-use zksync::{error::ClientError, operations::SyncTransactionHandle};
+use zksync::{error::ClientError, provider::Provider};
 
 use crate::{
     account::AccountLifespan,
@@ -58,10 +58,9 @@ impl AccountLifespan {
                     .unwrap_or(IncorrectnessModifier::None)
             });
 
-        let provider = self.wallet.provider.clone();
-        self.submit(modifier, || async {
-            self.wallet.provider.send_txs_batch(batch, None).await?;
-            Ok(SyncTransactionHandle::new(main_hash, provider))
+        let provider = &self.wallet.provider;
+        self.submit(modifier, main_hash, || {
+            provider.send_txs_batch(batch.clone(), None)
         })
         .await
     }
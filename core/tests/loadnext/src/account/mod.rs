@@ -5,15 +5,16 @@ use std::{
 
 use futures::{channel::mpsc::Sender, SinkExt};
 
-use zksync::{error::ClientError, operations::SyncTransactionHandle, RpcProvider, Wallet};
+use zksync::{error::ClientError, operations::SyncTransactionHandle, provider::Provider, Wallet};
 use zksync_eth_signer::PrivateKeySigner;
-use zksync_types::{Token, H256};
+use zksync_types::{tx::TxHash, Token, H256};
 
 use crate::{
     account_pool::{AddressPool, TestWallet},
+    chaos::{is_chaos_error, ChaosProvider},
     command::{Command, ExpectedOutcome, IncorrectnessModifier, TxCommand},
     config::LoadtestConfig,
-    constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
+    constants::{COMMIT_TIMEOUT, MAX_POLL_RESUMPTIONS, MAX_RESUBMISSIONS, POLLING_INTERVAL},
     report::{Report, ReportBuilder, ReportLabel},
     rng::LoadtestRng,
 };
@@ -30,7 +31,7 @@ mod tx_command_executor;
 #[derive(Debug)]
 pub struct AccountLifespan {
     /// Wallet used to perform the test.
-    pub wallet: Wallet<PrivateKeySigner, RpcProvider>,
+    pub wallet: Wallet<PrivateKeySigner, ChaosProvider>,
     /// Ethereum private key of the used wallet.
     /// zkSync private key can be obtained from it using `private_key_from_seed` function.
     eth_pk: H256,
@@ -96,6 +97,9 @@ impl AccountLifespan {
             }
         }
 
+        // The account is ready, from now on its requests may suffer from the chaos layer.
+        self.wallet.provider.activate();
+
         let command_sequence = self.generate_commands();
         for command in command_sequence {
             self.execute_command(command).await;
@@ -134,11 +138,13 @@ impl AccountLifespan {
                     }
 
                     // We reached the maximum amount of retries.
-                    let error = format!(
-                        "Retries limit reached. Latest error: {}",
-                        result.unwrap_err()
-                    );
-                    ReportLabel::failed(&error)
+                    let err = result.unwrap_err();
+                    let error = format!("Retries limit reached. Latest error: {}", err);
+                    if is_chaos_error(&err) {
+                        ReportLabel::chaos_failed(&error)
+                    } else {
+                        ReportLabel::failed(&error)
+                    }
                 }
                 Err(err) => {
                     // Other kinds of errors should not be handled, we will just report them.
@@ -179,36 +185,44 @@ impl AccountLifespan {
     }
 
     /// Generic sumbitter for zkSync network: it can operate both individual transactions and
-    /// batches, as long as we can provide a `SyncTransactionHandle` to wait for the commitment and the
-    /// execution result.
+    /// batches, as long as we can provide a hash to wait for the commitment and the execution result.
+    /// A batch is identified by the hash of any of its transactions.
+    ///
+    /// The `send` closure must submit exactly the same data each time it's called: if the response to
+    /// the submission is lost, the transaction is resubmitted rather than rebuilt.
+    ///
     /// Once result is obtained, it's compared to the expected operation outcome in order to check whether
     /// command was completed as planned.
-    async fn submit<F, Fut>(
+    async fn submit<F, Fut, T>(
         &self,
         modifier: IncorrectnessModifier,
+        tx_hash: TxHash,
         send: F,
     ) -> Result<ReportLabel, ClientError>
     where
-        F: FnOnce() -> Fut,
-        Fut: Future<Output = Result<SyncTransactionHandle<RpcProvider>, ClientError>>,
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
     {
         let expected_outcome = modifier.expected_outcome();
 
-        let send_result = send().await;
-        let mut handle = match (expected_outcome, send_result) {
-            (ExpectedOutcome::ApiRequestFailed, Ok(_handle)) => {
+        let send_result = send_with_resubmission(&self.wallet.provider, tx_hash, send).await;
+        match (expected_outcome, send_result) {
+            (ExpectedOutcome::ApiRequestFailed, Ok(())) => {
                 // Transaction got accepted, but should have not been.
                 let error = "Tx/batch was accepted, but should have not been";
                 return Ok(ReportLabel::failed(error));
             }
-            (_, Ok(handle)) => {
+            (_, Ok(())) => {
                 // Transaction should have been accepted by API and it was; now wait for the commitment.
-                handle
             }
             (ExpectedOutcome::ApiRequestFailed, Err(_error)) => {
                 // Transaction was expected to be rejected and it was.
                 return Ok(ReportLabel::done());
             }
+            (_, Err(error @ ClientError::NetworkError(_))) => {
+                // We don't know whether the transaction was accepted, so the operation will be retried.
+                return Err(error);
+            }
             (_, Err(_error)) => {
                 // Transaction was expected to be accepted, but was rejected.
                 let error = "Tx/batch should have been accepted, but got rejected";
@@ -216,11 +230,10 @@ impl AccountLifespan {
             }
         };
 
+        let mut handle = SyncTransactionHandle::new(tx_hash, self.wallet.provider.clone());
         handle.polling_interval(POLLING_INTERVAL).unwrap();
-        let transaction_receipt = handle
-            .commit_timeout(COMMIT_TIMEOUT)
-            .wait_for_commit()
-            .await?;
+        let handle = handle.commit_timeout(COMMIT_TIMEOUT);
+        let transaction_receipt = resume_on_lost_response(|| handle.wait_for_commit()).await?;
 
         match expected_outcome {
             ExpectedOutcome::TxSucceed if transaction_receipt.fail_reason.is_none() => {
@@ -257,3 +270,55 @@ impl AccountLifespan {
         commands
     }
 }
+
+/// Submits the transaction or batch identified by `tx_hash`, resubmitting exactly the same data
+/// if the response to the submission was lost.
+///
+/// A lost submission could have been processed by the server; in that case a resubmission may be rejected
+/// (e.g. because of the nonce of the already executed transaction). Such a rejection is not considered
+/// a failure if the server knows the transaction as executed.
+pub(crate) async fn send_with_resubmission<P, F, Fut, T>(
+    provider: &P,
+    tx_hash: TxHash,
+    send: F,
+) -> Result<(), ClientError>
+where
+    P: Provider,
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+{
+    let mut resubmissions = 0;
+    loop {
+        match send().await {
+            Ok(_) => return Ok(()),
+            Err(ClientError::NetworkError(_)) if resubmissions < MAX_RESUBMISSIONS => {
+                resubmissions += 1;
+            }
+            Err(err) if resubmissions > 0 => {
+                let executed = resume_on_lost_response(|| provider.tx_info(tx_hash))
+                    .await
+                    .map(|info| info.executed)
+                    .unwrap_or(false);
+                return if executed { Ok(()) } else { Err(err) };
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Repeats the status request if the response to it was lost.
+pub(crate) async fn resume_on_lost_response<F, Fut, T>(request: F) -> Result<T, ClientError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ClientError>>,
+{
+    let mut resumptions = 0;
+    loop {
+        match request().await {
+            Err(ClientError::NetworkError(_)) if resumptions < MAX_POLL_RESUMPTIONS => {
+                resumptions += 1;
+            }
+            result => return result,
+        }
+    }
+}
@@ -1,14 +1,11 @@
 use std::convert::TryInto;
 
 use num::{BigUint, Zero};
-use zksync::{
-    error::ClientError, ethereum::PriorityOpHolder, operations::SyncTransactionHandle,
-    provider::Provider,
-};
+use zksync::{error::ClientError, ethereum::PriorityOpHolder, provider::Provider};
 use zksync_types::{tokens::ETH_TOKEN_ID, tx::PackedEthSignature, Nonce, ZkSyncTx, H256};
 
 use crate::{
-    account::AccountLifespan,
+    account::{resume_on_lost_response, AccountLifespan},
     command::{IncorrectnessModifier, TxCommand, TxType},
     constants::{COMMIT_TIMEOUT, POLLING_INTERVAL},
     corrupted_tx::Corrupted,
@@ -175,23 +172,30 @@ impl AccountLifespan {
         priority_op_handle
             .polling_interval(POLLING_INTERVAL)
             .unwrap();
-        priority_op_handle
-            .commit_timeout(COMMIT_TIMEOUT)
-            .wait_for_commit()
-            .await?;
+        let priority_op_handle = priority_op_handle.commit_timeout(COMMIT_TIMEOUT);
+        resume_on_lost_response(|| priority_op_handle.wait_for_commit()).await?;
 
         Ok(ReportLabel::done())
     }
 
+    /// Submits a single transaction, see `AccountLifespan::submit` for details.
+    async fn submit_tx(
+        &self,
+        modifier: IncorrectnessModifier,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> Result<ReportLabel, ClientError> {
+        let provider = &self.wallet.provider;
+        self.submit(modifier, tx.hash(), || {
+            provider.send_tx(tx.clone(), eth_signature.clone())
+        })
+        .await
+    }
+
     async fn execute_change_pubkey(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
         let (tx, eth_signature) = self.build_change_pubkey(command, None).await?;
 
-        let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
-        })
-        .await
+        self.submit_tx(command.modifier, tx, eth_signature).await
     }
 
     pub(super) async fn build_change_pubkey(
@@ -217,12 +221,7 @@ impl AccountLifespan {
     async fn execute_transfer(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
         let (tx, eth_signature) = self.build_transfer(command, None).await?;
 
-        let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
-        })
-        .await
+        self.submit_tx(command.modifier, tx, eth_signature).await
     }
 
     pub(super) async fn build_transfer(
@@ -250,12 +249,7 @@ impl AccountLifespan {
     async fn execute_withdraw(&self, command: &TxCommand) -> Result<ReportLabel, ClientError> {
         let (tx, eth_signature) = self.build_withdraw(command, None).await?;
 
-        let provider = self.wallet.provider.clone();
-        self.submit(command.modifier, || async {
-            let tx_hash = provider.send_tx(tx, eth_signature).await?;
-            Ok(SyncTransactionHandle::new(tx_hash, provider))
-        })
-        .await
+        self.submit_tx(command.modifier, tx, eth_signature).await
     }
 
     pub(super) async fn build_withdraw(
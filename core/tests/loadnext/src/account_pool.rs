@@ -10,6 +10,7 @@ use zksync_eth_signer::PrivateKeySigner;
 use zksync_types::{tx::PackedEthSignature, Address, H256};
 
 use crate::{
+    chaos::{ChaosProvider, ChaosStats},
     config::LoadtestConfig,
     rng::{LoadtestRng, Random},
 };
@@ -56,7 +57,8 @@ impl Random for AccountCredentials {
 #[derive(Debug)]
 pub struct TestWallet {
     /// Pre-initialized wallet object.
-    pub wallet: Wallet<PrivateKeySigner, RpcProvider>,
+    /// Its provider injects the failures simulating a flaky client, if the chaos layer is enabled.
+    pub wallet: Wallet<PrivateKeySigner, ChaosProvider>,
     /// Ethereum private key of the wallet.
     /// We have to collect private keys, since `Wallet` doesn't expose it, and we may need it to resign transactions
     /// (for example, if we want to create a corrupted transaction: `zksync` library won't allow us to do it, thus
@@ -77,6 +79,8 @@ pub struct AccountPool {
     pub accounts: VecDeque<TestWallet>,
     /// Pool of addresses of the test accounts.
     pub addresses: AddressPool,
+    /// Failures injected by the chaos layer into the requests of all the test wallets.
+    pub chaos_stats: Arc<ChaosStats>,
 }
 
 impl AccountPool {
@@ -116,6 +120,12 @@ impl AccountPool {
                 .expect("Can't create a wallet")
         };
 
+        let chaos_config = config.chaos_config();
+        if chaos_config.is_enabled() {
+            vlog::info!("Chaos layer is enabled: {:?}", chaos_config);
+        }
+        let chaos_stats = Arc::new(ChaosStats::default());
+
         let mut accounts = VecDeque::with_capacity(config.accounts_amount);
         let mut addresses = Vec::with_capacity(config.accounts_amount);
 
@@ -129,7 +139,17 @@ impl AccountPool {
                 Some(eth_credentials.eth_pk),
             );
 
-            let wallet = Wallet::new(provider.clone(), wallet_credentials)
+            let account_rng = rng.derive(eth_credentials.eth_pk);
+            // Chaos decisions use their own RNG, so that enabling the chaos layer doesn't change
+            // the sequence of operations generated for the account.
+            let chaos_rng = account_rng.derive(eth_credentials.eth_pk);
+            let chaos_provider = ChaosProvider::new(
+                provider.clone(),
+                &chaos_config,
+                chaos_rng,
+                chaos_stats.clone(),
+            );
+            let wallet = Wallet::new(chaos_provider, wallet_credentials)
                 .await
                 .expect("Can't create a wallet");
 
@@ -137,7 +157,7 @@ impl AccountPool {
             let account = TestWallet {
                 wallet,
                 eth_pk: eth_credentials.eth_pk,
-                rng: rng.derive(eth_credentials.eth_pk),
+                rng: account_rng,
             };
             accounts.push_back(account);
         }
@@ -146,6 +166,7 @@ impl AccountPool {
             master_wallet,
             accounts,
             addresses: AddressPool::new(addresses),
+            chaos_stats,
         })
     }
 }
@@ -0,0 +1,566 @@
+//! Chaos layer simulating the flaky clients.
+//!
+//! Real users of zkSync have unstable connections: their requests are delayed, responses get lost after
+//! the server has already processed the request, and long polls are cut off. The `ChaosProvider` wraps
+//! the actual provider and injects such failures, so that the loadtest checks whether the server copes
+//! with the retries and duplicate submissions caused by them.
+//!
+//! All the decisions are made by the RNG derived from the account one, so the injected failures are
+//! reproducible with the same master seed. Errors produced by the chaos layer are marked, so that the
+//! report can separate them from the genuine ones.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use num::BigUint;
+use rand::Rng;
+
+use zksync::{
+    error::ClientError,
+    provider::{Provider, ResponseResult},
+    types::{AccountInfo, ContractAddress, EthOpInfo, Fee, NFTInfo, Tokens, TransactionInfo},
+    Network, RpcProvider,
+};
+use zksync_types::{
+    tx::{PackedEthSignature, TxHash},
+    AccountId, Address, TokenId, TokenLike, TxFeeTypes, ZkSyncTx,
+};
+
+use crate::rng::LoadtestRng;
+
+/// Prefix of the messages of the network errors produced by the chaos layer.
+pub const CHAOS_ERROR_PREFIX: &str = "[chaos]";
+
+/// Checks whether the error was produced by the chaos layer rather than by the server.
+pub fn is_chaos_error(error: &ClientError) -> bool {
+    matches!(error, ClientError::NetworkError(message) if message.starts_with(CHAOS_ERROR_PREFIX))
+}
+
+fn chaos_error(reason: &str) -> ClientError {
+    ClientError::NetworkError(format!("{} {}", CHAOS_ERROR_PREFIX, reason))
+}
+
+/// Probabilities of the failures injected by the chaos layer.
+/// All the probabilities are zero by default, i.e. the chaos layer is disabled.
+#[derive(Debug, Clone, Default)]
+pub struct ChaosConfig {
+    /// Probability of delaying a request.
+    pub delay_probability: f64,
+    /// Maximum delay of a request, the actual delay is chosen uniformly.
+    pub max_delay: Duration,
+    /// Probability of dropping the response to a submitted transaction or batch.
+    /// The submission itself reaches the server.
+    pub drop_response_probability: f64,
+    /// Probability of cutting off a request polling the status of an operation.
+    pub truncate_poll_probability: f64,
+}
+
+impl ChaosConfig {
+    /// Returns `true` if any kind of failures is going to be injected.
+    pub fn is_enabled(&self) -> bool {
+        (self.delay_probability > 0.0 && self.max_delay > Duration::from_millis(0))
+            || self.drop_response_probability > 0.0
+            || self.truncate_poll_probability > 0.0
+    }
+}
+
+/// Random event happening with the fixed probability.
+#[derive(Debug, Clone, Copy)]
+pub struct ChaosGate {
+    probability: f64,
+}
+
+impl ChaosGate {
+    pub fn new(probability: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "Chaos probability must be within [0, 1], got {}",
+            probability
+        );
+        Self { probability }
+    }
+
+    /// Decides whether the event happens this time.
+    /// The gate with zero probability never fires and doesn't consume the randomness.
+    pub fn fires(&self, rng: &mut impl Rng) -> bool {
+        self.probability > 0.0 && rng.gen_bool(self.probability)
+    }
+}
+
+/// Counters of the failures injected by the chaos layer.
+#[derive(Debug, Default)]
+pub struct ChaosStats {
+    delayed: AtomicU64,
+    dropped_responses: AtomicU64,
+    truncated_polls: AtomicU64,
+}
+
+impl ChaosStats {
+    pub fn delayed(&self) -> u64 {
+        self.delayed.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped_responses(&self) -> u64 {
+        self.dropped_responses.load(Ordering::Relaxed)
+    }
+
+    pub fn truncated_polls(&self) -> u64 {
+        self.truncated_polls.load(Ordering::Relaxed)
+    }
+
+    pub fn report(&self) {
+        vlog::info!(
+            "Chaos layer: {} requests delayed, {} responses dropped, {} polls truncated.",
+            self.delayed(),
+            self.dropped_responses(),
+            self.truncated_polls()
+        );
+    }
+}
+
+#[derive(Debug)]
+struct ChaosState {
+    delay: ChaosGate,
+    max_delay: Duration,
+    drop_response: ChaosGate,
+    truncate_poll: ChaosGate,
+    rng: Mutex<LoadtestRng>,
+    active: AtomicBool,
+    stats: Arc<ChaosStats>,
+}
+
+/// Provider injecting the failures into the requests of the wrapped provider.
+///
+/// The chaos layer is inactive after creation, so that the wallet can be initialized reliably.
+/// Call `activate` once the account is ready to be tested.
+#[derive(Debug, Clone)]
+pub struct ChaosProvider<P = RpcProvider> {
+    inner: P,
+    state: Arc<ChaosState>,
+}
+
+impl<P: Provider> ChaosProvider<P> {
+    pub fn new(inner: P, config: &ChaosConfig, rng: LoadtestRng, stats: Arc<ChaosStats>) -> Self {
+        let state = ChaosState {
+            delay: ChaosGate::new(config.delay_probability),
+            max_delay: config.max_delay,
+            drop_response: ChaosGate::new(config.drop_response_probability),
+            truncate_poll: ChaosGate::new(config.truncate_poll_probability),
+            rng: Mutex::new(rng),
+            active: AtomicBool::new(false),
+            stats,
+        };
+
+        Self {
+            inner,
+            state: Arc::new(state),
+        }
+    }
+
+    /// Starts injecting the failures.
+    pub fn activate(&self) {
+        self.state.active.store(true, Ordering::SeqCst);
+    }
+
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    fn roll(&self, gate: ChaosGate) -> bool {
+        if !self.state.active.load(Ordering::SeqCst) {
+            return false;
+        }
+        gate.fires(&mut *self.state.rng.lock().unwrap())
+    }
+
+    /// Delays the request, if the chaos layer decides so.
+    async fn maybe_delay(&self) {
+        if !self.roll(self.state.delay) {
+            return;
+        }
+        let max_delay = self.state.max_delay.as_millis() as u64;
+        let delay = self.state.rng.lock().unwrap().gen_range(0..=max_delay);
+        self.state.stats.delayed.fetch_add(1, Ordering::Relaxed);
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+    }
+
+    /// Cuts off the polling request before it reaches the server, if the chaos layer decides so.
+    async fn maybe_truncate_poll(&self) -> ResponseResult<()> {
+        self.maybe_delay().await;
+        if self.roll(self.state.truncate_poll) {
+            self.state
+                .stats
+                .truncated_polls
+                .fetch_add(1, Ordering::Relaxed);
+            return Err(chaos_error("poll truncated"));
+        }
+        Ok(())
+    }
+
+    /// Drops the response to the processed submission, if the chaos layer decides so.
+    fn maybe_drop_response<T>(&self, response: ResponseResult<T>) -> ResponseResult<T> {
+        // Only the successful responses are dropped: a lost rejection doesn't lead to duplicate submissions.
+        if response.is_ok() && self.roll(self.state.drop_response) {
+            self.state
+                .stats
+                .dropped_responses
+                .fetch_add(1, Ordering::Relaxed);
+            return Err(chaos_error("response dropped"));
+        }
+        response
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync> Provider for ChaosProvider<P> {
+    async fn account_info(&self, address: Address) -> ResponseResult<AccountInfo> {
+        self.maybe_delay().await;
+        self.inner.account_info(address).await
+    }
+
+    async fn tokens(&self) -> ResponseResult<Tokens> {
+        self.maybe_delay().await;
+        self.inner.tokens().await
+    }
+
+    async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
+        self.maybe_truncate_poll().await?;
+        self.inner.tx_info(tx_hash).await
+    }
+
+    async fn get_tx_fee(
+        &self,
+        tx_type: TxFeeTypes,
+        address: Address,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<Fee> {
+        self.maybe_delay().await;
+        self.inner.get_tx_fee(tx_type, address, token).await
+    }
+
+    async fn get_txs_batch_fee(
+        &self,
+        tx_types: Vec<TxFeeTypes>,
+        addresses: Vec<Address>,
+        token: impl Into<TokenLike> + Send + 'async_trait,
+    ) -> ResponseResult<BigUint> {
+        self.maybe_delay().await;
+        self.inner
+            .get_txs_batch_fee(tx_types, addresses, token)
+            .await
+    }
+
+    async fn ethop_info(&self, serial_id: u32) -> ResponseResult<EthOpInfo> {
+        self.maybe_truncate_poll().await?;
+        self.inner.ethop_info(serial_id).await
+    }
+
+    async fn get_eth_tx_for_withdrawal(
+        &self,
+        withdrawal_hash: TxHash,
+    ) -> ResponseResult<Option<String>> {
+        self.maybe_delay().await;
+        self.inner.get_eth_tx_for_withdrawal(withdrawal_hash).await
+    }
+
+    async fn contract_address(&self) -> ResponseResult<ContractAddress> {
+        self.maybe_delay().await;
+        self.inner.contract_address().await
+    }
+
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        self.maybe_delay().await;
+        self.inner.get_nft(token_id).await
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        self.maybe_delay().await;
+        self.inner.get_nft_owner(token_id).await
+    }
+
+    async fn send_tx(
+        &self,
+        tx: ZkSyncTx,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<TxHash> {
+        self.maybe_delay().await;
+        let response = self.inner.send_tx(tx, eth_signature).await;
+        self.maybe_drop_response(response)
+    }
+
+    async fn send_txs_batch(
+        &self,
+        txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+        eth_signature: Option<PackedEthSignature>,
+    ) -> ResponseResult<Vec<TxHash>> {
+        self.maybe_delay().await;
+        let response = self.inner.send_txs_batch(txs_signed, eth_signature).await;
+        self.maybe_drop_response(response)
+    }
+
+    fn network(&self) -> Network {
+        self.inner.network()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use zksync::{
+        types::{BlockInfo, TxInfoStatus},
+        utils::private_key_from_seed,
+    };
+    use zksync_test_account::ZkSyncAccount;
+    use zksync_types::{AccountId, Nonce, PubKeyHash, TokenId};
+
+    use super::*;
+    use crate::account::send_with_resubmission;
+
+    const SEED: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    fn rng() -> LoadtestRng {
+        LoadtestRng::new_generic(Some(SEED.into()))
+    }
+
+    #[test]
+    fn zero_probability_gate_never_fires() {
+        let gate = ChaosGate::new(0.0);
+        let mut rng = rng();
+        assert!((0..1000).all(|_| !gate.fires(&mut rng)));
+    }
+
+    #[test]
+    fn certain_gate_always_fires() {
+        let gate = ChaosGate::new(1.0);
+        let mut rng = rng();
+        assert!((0..1000).all(|_| gate.fires(&mut rng)));
+    }
+
+    #[test]
+    fn gate_fires_with_configured_frequency() {
+        let gate = ChaosGate::new(0.25);
+        let mut rng = rng();
+        let fired = (0..10_000).filter(|_| gate.fires(&mut rng)).count();
+        assert!((2_250..=2_750).contains(&fired), "fired {} times", fired);
+    }
+
+    #[test]
+    fn gate_is_deterministic_under_seed() {
+        let gate = ChaosGate::new(0.5);
+        let decisions =
+            |mut rng: LoadtestRng| (0..100).map(|_| gate.fires(&mut rng)).collect::<Vec<_>>();
+        assert_eq!(decisions(rng()), decisions(rng()));
+        assert_ne!(
+            decisions(rng()),
+            decisions(rng().derive(Default::default()))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn gate_rejects_invalid_probability() {
+        ChaosGate::new(1.5);
+    }
+
+    #[test]
+    fn chaos_errors_are_distinguishable() {
+        assert!(is_chaos_error(&chaos_error("response dropped")));
+        assert!(!is_chaos_error(&ClientError::NetworkError(
+            "connection reset".into()
+        )));
+        assert!(!is_chaos_error(&ClientError::OperationTimeout));
+    }
+
+    /// Server applying each transaction on the first submission and rejecting the duplicates
+    /// of the already applied ones, like the zkSync mempool does for the executed transactions.
+    #[derive(Debug, Clone, Default)]
+    struct MockServer {
+        applied: Arc<Mutex<HashMap<TxHash, usize>>>,
+    }
+
+    impl MockServer {
+        fn apply(&self, tx_hash: TxHash) -> ResponseResult<()> {
+            let mut applied = self.applied.lock().unwrap();
+            let applications = applied.entry(tx_hash).or_default();
+            if *applications > 0 {
+                // Any error other than the network one works here.
+                return Err(ClientError::MalformedResponse("Nonce mismatch".into()));
+            }
+            *applications += 1;
+            Ok(())
+        }
+
+        /// Response to the methods the tests don't rely on.
+        fn unsupported<T>() -> ResponseResult<T> {
+            Err(ClientError::MalformedResponse(
+                "Method is not supported by the mock server".into(),
+            ))
+        }
+    }
+
+    #[async_trait]
+    impl Provider for MockServer {
+        async fn account_info(&self, _address: Address) -> ResponseResult<AccountInfo> {
+            Self::unsupported()
+        }
+
+        async fn tokens(&self) -> ResponseResult<Tokens> {
+            Self::unsupported()
+        }
+
+        async fn tx_info(&self, tx_hash: TxHash) -> ResponseResult<TransactionInfo> {
+            let executed = self.applied.lock().unwrap().contains_key(&tx_hash);
+            Ok(TransactionInfo {
+                status: Some(if executed {
+                    TxInfoStatus::Executed
+                } else {
+                    TxInfoStatus::NotFound
+                }),
+                executed,
+                success: Some(executed),
+                fail_reason: None,
+                block: Some(BlockInfo {
+                    block_number: 1,
+                    committed: executed,
+                    verified: false,
+                })
+                .filter(|_| executed),
+            })
+        }
+
+        async fn get_tx_fee(
+            &self,
+            _tx_type: TxFeeTypes,
+            _address: Address,
+            _token: impl Into<TokenLike> + Send + 'async_trait,
+        ) -> ResponseResult<Fee> {
+            Self::unsupported()
+        }
+
+        async fn get_txs_batch_fee(
+            &self,
+            _tx_types: Vec<TxFeeTypes>,
+            _addresses: Vec<Address>,
+            _token: impl Into<TokenLike> + Send + 'async_trait,
+        ) -> ResponseResult<BigUint> {
+            Self::unsupported()
+        }
+
+        async fn ethop_info(&self, _serial_id: u32) -> ResponseResult<EthOpInfo> {
+            Self::unsupported()
+        }
+
+        async fn get_eth_tx_for_withdrawal(
+            &self,
+            _withdrawal_hash: TxHash,
+        ) -> ResponseResult<Option<String>> {
+            Self::unsupported()
+        }
+
+        async fn contract_address(&self) -> ResponseResult<ContractAddress> {
+            Self::unsupported()
+        }
+
+        async fn get_nft(&self, _token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+            Self::unsupported()
+        }
+
+        async fn get_nft_owner(&self, _token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+            Self::unsupported()
+        }
+
+        async fn send_tx(
+            &self,
+            tx: ZkSyncTx,
+            _eth_signature: Option<PackedEthSignature>,
+        ) -> ResponseResult<TxHash> {
+            self.apply(tx.hash())?;
+            Ok(tx.hash())
+        }
+
+        async fn send_txs_batch(
+            &self,
+            txs_signed: Vec<(ZkSyncTx, Option<PackedEthSignature>)>,
+            _eth_signature: Option<PackedEthSignature>,
+        ) -> ResponseResult<Vec<TxHash>> {
+            // Batches are applied atomically, thus the first transaction identifies the whole batch.
+            self.apply(txs_signed[0].0.hash())?;
+            Ok(txs_signed.iter().map(|(tx, _)| tx.hash()).collect())
+        }
+
+        fn network(&self) -> Network {
+            Network::Localhost
+        }
+    }
+
+    fn create_transfers(amount: u32) -> Vec<ZkSyncTx> {
+        let mut account = ZkSyncAccount::rand();
+        account.set_account_id(Some(AccountId(1)));
+        let eth_pk = account.eth_account_data.unwrap_eoa_pk();
+        account.private_key = private_key_from_seed(eth_pk.as_bytes()).unwrap();
+        account.pubkey_hash = PubKeyHash::from_privkey(&account.private_key);
+
+        (0..amount)
+            .map(|nonce| {
+                let (transfer, _) = account.sign_transfer(
+                    TokenId(0),
+                    "ETH",
+                    100u64.into(),
+                    100u64.into(),
+                    &Address::repeat_byte(0x7e),
+                    Some(Nonce(nonce)),
+                    false,
+                    Default::default(),
+                );
+                ZkSyncTx::from(transfer)
+            })
+            .collect()
+    }
+
+    /// Runs the submissions through the chaos layer dropping most of the responses, and checks that
+    /// every transaction is reported as accepted while being applied by the server exactly once.
+    #[tokio::test]
+    async fn lost_responses_do_not_cause_duplicate_mutations() {
+        let server = MockServer::default();
+        let config = ChaosConfig {
+            delay_probability: 0.5,
+            max_delay: Duration::from_millis(5),
+            drop_response_probability: 0.5,
+            truncate_poll_probability: 0.3,
+        };
+        let stats = Arc::new(ChaosStats::default());
+        let provider = ChaosProvider::new(server.clone(), &config, rng(), stats.clone());
+        provider.activate();
+
+        let txs = create_transfers(40);
+        for tx in &txs {
+            let tx_hash = tx.hash();
+            send_with_resubmission(&provider, tx_hash, || provider.send_tx(tx.clone(), None))
+                .await
+                .expect("Lost responses should be recovered");
+        }
+
+        let batch: Vec<_> = create_transfers(5)
+            .into_iter()
+            .map(|tx| (tx, None))
+            .collect();
+        let batch_hash = batch[0].0.hash();
+        send_with_resubmission(&provider, batch_hash, || {
+            provider.send_txs_batch(batch.clone(), None)
+        })
+        .await
+        .expect("Lost responses should be recovered");
+
+        let applied = server.applied.lock().unwrap();
+        assert_eq!(applied.len(), txs.len() + 1);
+        assert!(applied.values().all(|&applications| applications == 1));
+        assert!(stats.dropped_responses() > 0, "Chaos layer was not active");
+    }
+}
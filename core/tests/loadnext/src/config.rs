@@ -1,5 +1,9 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
+use crate::chaos::ChaosConfig;
+
 /// Configuration for the loadtest.
 ///
 /// This structure is meant to provide the least possible amount of parameters:
@@ -45,12 +49,35 @@ pub struct LoadtestConfig {
     pub seed: Option<String>,
     /// Allowed percent of failed transactions
     pub allowed_percent: u8,
+
+    /// Probability of delaying a request to the zkSync node by the chaos layer.
+    #[serde(default)]
+    pub chaos_delay_probability: f64,
+    /// Maximum delay of a request injected by the chaos layer, in milliseconds.
+    #[serde(default)]
+    pub chaos_max_delay_ms: u64,
+    /// Probability of dropping the response to a submitted transaction or batch by the chaos layer.
+    #[serde(default)]
+    pub chaos_drop_response_probability: f64,
+    /// Probability of cutting off a request polling the status of an operation by the chaos layer.
+    #[serde(default)]
+    pub chaos_truncate_poll_probability: f64,
 }
 
 impl LoadtestConfig {
     pub fn from_env() -> envy::Result<Self> {
         envy::from_env()
     }
+
+    /// Returns the configuration of the chaos layer simulating the flaky clients.
+    pub fn chaos_config(&self) -> ChaosConfig {
+        ChaosConfig {
+            delay_probability: self.chaos_delay_probability,
+            max_delay: Duration::from_millis(self.chaos_max_delay_ms),
+            drop_response_probability: self.chaos_drop_response_probability,
+            truncate_poll_probability: self.chaos_truncate_poll_probability,
+        }
+    }
 }
 
 impl Default for LoadtestConfig {
@@ -69,6 +96,10 @@ impl Default for LoadtestConfig {
             main_token: "DAI".into(),
             seed: None,
             allowed_percent: 10,
+            chaos_delay_probability: 0.0,
+            chaos_max_delay_ms: 0,
+            chaos_drop_response_probability: 0.0,
+            chaos_truncate_poll_probability: 0.0,
         }
     }
 }
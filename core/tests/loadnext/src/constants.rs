@@ -10,6 +10,11 @@ pub const COMMIT_TIMEOUT: Duration = Duration::from_secs(600);
 /// We don't want to overload the server with too many requests; given the fact that blocks are expected to be created
 /// every couple of seconds, chosen value seems to be adequate to provide the result in one or two calls at average.
 pub const POLLING_INTERVAL: Duration = Duration::from_secs(3);
+/// Amount of times the same transaction or batch is resubmitted if the response to the submission was lost.
+pub const MAX_RESUBMISSIONS: usize = 3;
+/// Amount of times the polling is resumed if the response to the status request was lost.
+/// Polling is cheap, so it's fine to be more persistent here than with the resubmissions.
+pub const MAX_POLL_RESUMPTIONS: usize = 10;
 
 // TODO (ZKS-623): This value is not the greatest batch size zkSync supports.
 // However, choosing the bigger value (e.g. 40) causes server to fail with error "Error communicating core server".
@@ -56,6 +56,9 @@ impl Executor {
         self.set_signing_key().await?;
         let (executor_future, account_futures) = self.send_initial_transfers().await?;
         self.wait_account_routines(account_futures).await;
+        if self.config.chaos_config().is_enabled() {
+            self.pool.chaos_stats.report();
+        }
 
         let final_resultion = executor_future.await.unwrap_or(LoadtestResult::TestFailed);
 
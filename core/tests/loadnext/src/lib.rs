@@ -1,6 +1,7 @@
 pub mod account;
 pub mod account_pool;
 pub mod all;
+pub mod chaos;
 pub mod command;
 pub mod config;
 pub mod constants;
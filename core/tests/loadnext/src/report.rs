@@ -88,8 +88,16 @@ impl ReportBuilder {
 #[derive(Debug, Clone)]
 pub enum ReportLabel {
     ActionDone,
-    ActionSkipped { reason: String },
-    ActionFailed { error: String },
+    ActionSkipped {
+        reason: String,
+    },
+    ActionFailed {
+        error: String,
+    },
+    /// Action failed only because of the failures injected by the chaos layer.
+    ActionChaosFailed {
+        error: String,
+    },
 }
 
 impl ReportLabel {
@@ -108,6 +116,12 @@ impl ReportLabel {
             error: error.into(),
         }
     }
+
+    pub fn chaos_failed(error: &str) -> Self {
+        Self::ActionChaosFailed {
+            error: error.into(),
+        }
+    }
 }
 
 /// Denotes the type of executed transaction.
@@ -25,8 +25,8 @@ pub enum LoadtestResult {
 /// Currently, only the following collectors are used:
 ///
 /// - MetricsCollector, which builds time distribution histograms for each kind of performed action.
-/// - OperationResultsCollector, a primitive collector that counts the amount of failures and decides whether
-///   test is passed.
+/// - OperationResultsCollector, a primitive collector that counts the amount of failures (separating the ones
+///   caused by the chaos layer) and decides whether test is passed.
 ///
 /// Other possible collectors that can be implemented:
 ///
@@ -73,8 +73,14 @@ impl ReportCollector {
             self.operations_results_collector.add_status(&report.label);
 
             // Report failure, if it exists.
-            if let ReportLabel::ActionFailed { error } = &report.label {
-                vlog::warn!("Operation failed: {}", error);
+            match &report.label {
+                ReportLabel::ActionFailed { error } => {
+                    vlog::warn!("Operation failed: {}", error);
+                }
+                ReportLabel::ActionChaosFailed { error } => {
+                    vlog::info!("Operation failed because of the chaos layer: {}", error);
+                }
+                _ => {}
             }
         }
 
@@ -86,6 +92,8 @@ impl ReportCollector {
         self.final_resolution()
     }
 
+    /// Decides whether the test passed. Only the genuine failures are taken into account:
+    /// failures caused by the chaos layer are expected.
     fn final_resolution(&self) -> LoadtestResult {
         let failure_percent = (self.operations_results_collector.failures() as f64
             / self.operations_results_collector.total() as f64)
@@ -2,11 +2,14 @@ use crate::report::ReportLabel;
 
 /// Collector that analyzes the outcomes of the performed operations.
 /// Currently it's solely capable of deciding whether test was failed or not.
+///
+/// Failures caused by the chaos layer are counted separately from the genuine ones.
 #[derive(Debug, Clone, Default)]
 pub struct OperationResultsCollector {
     successes: u64,
     skipped: u64,
     failures: u64,
+    chaos_failures: u64,
 }
 
 impl OperationResultsCollector {
@@ -19,6 +22,7 @@ impl OperationResultsCollector {
             ReportLabel::ActionDone => self.successes += 1,
             ReportLabel::ActionSkipped { .. } => self.skipped += 1,
             ReportLabel::ActionFailed { .. } => self.failures += 1,
+            ReportLabel::ActionChaosFailed { .. } => self.chaos_failures += 1,
         }
     }
 
@@ -34,16 +38,21 @@ impl OperationResultsCollector {
         self.failures
     }
 
+    pub fn chaos_failures(&self) -> u64 {
+        self.chaos_failures
+    }
+
     pub fn total(&self) -> u64 {
-        self.successes + self.skipped + self.failures
+        self.successes + self.skipped + self.failures + self.chaos_failures
     }
 
     pub fn report(&self) {
         vlog::info!(
-            "Loadtest status: {} successful operations, {} skipped, {} failures. {} actions total.",
+            "Loadtest status: {} successful operations, {} skipped, {} failures, {} failures caused by chaos. {} actions total.",
             self.successes(),
             self.skipped(),
             self.failures(),
+            self.chaos_failures(),
             self.total()
         );
     }
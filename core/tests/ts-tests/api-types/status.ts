@@ -12,4 +12,5 @@ export type CoreStatus = {
     main_database_available: boolean;
     replica_database_available: boolean;
     web3_available: boolean;
+    contract_config_outdated: boolean;
 };
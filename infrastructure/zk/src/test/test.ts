@@ -29,6 +29,21 @@ export async function db(reset: boolean, ...args: string[]) {
         `cargo test --release -p zksync_storage --lib -- --ignored --nocapture --test-threads=1
         ${args.join(' ')}`
     );
+    await serverDb(false, ...args);
+}
+
+// Server components that talk to the database directly and have their own `db_test`s.
+const DB_TEST_CRATES = ['core/bin/zksync_core', 'core/bin/zksync_event_listener'];
+
+export async function serverDb(reset: boolean, ...args: string[]) {
+    // `--features` applies to the package in the current directory, so the tests are run from the crate directory.
+    for (const dir of DB_TEST_CRATES) {
+        await runOnTestDb(
+            reset,
+            dir,
+            `cargo test --release --features db_test --lib -- --nocapture --test-threads=1 ${args.join(' ')}`
+        );
+    }
 }
 
 export async function rustApi(reset: boolean, ...args: string[]) {
@@ -107,6 +122,15 @@ command
         await db(cmd.reset, ...(options || []));
     });
 
+command
+    .command('server-db')
+    .description('run unit-tests of the server components using the database')
+    .option('--no-reset', 'do not reset the database before test starting')
+    .allowUnknownOption()
+    .action(async (cmd: Command, options: string[] | undefined) => {
+        await serverDb(cmd.reset, ...(options || []));
+    });
+
 command
     .command('rust-api')
     .description('run unit-tests for the REST API')
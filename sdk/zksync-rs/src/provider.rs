@@ -13,7 +13,7 @@ use num::BigUint;
 use zksync_types::{
     network::Network,
     tx::{PackedEthSignature, TxHash, ZkSyncTx},
-    Address, TokenLike, TxFeeTypes,
+    AccountId, Address, TokenId, TokenLike, TxFeeTypes,
 };
 
 // Local uses
@@ -77,6 +77,13 @@ pub trait Provider {
     /// Requests and returns a smart contract address (for Ethereum network associated with network specified in `Provider`).
     async fn contract_address(&self) -> ResponseResult<ContractAddress>;
 
+    /// Requests and returns information about an NFT given its token ID.
+    /// Returns `None` if the NFT wasn't minted in the committed state.
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>>;
+
+    /// Requests and returns the ID of the account owning the NFT in the committed state.
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>>;
+
     /// Submits a transaction to the zkSync network.
     /// Returns the hash of the created transaction.
     async fn send_tx(
@@ -164,6 +171,16 @@ impl Provider for RpcProvider {
         self.send_and_deserialize(&msg).await
     }
 
+    async fn get_nft(&self, token_id: TokenId) -> ResponseResult<Option<NFTInfo>> {
+        let msg = JsonRpcRequest::get_nft(token_id);
+        self.send_and_deserialize(&msg).await
+    }
+
+    async fn get_nft_owner(&self, token_id: TokenId) -> ResponseResult<Option<AccountId>> {
+        let msg = JsonRpcRequest::get_nft_owner(token_id);
+        self.send_and_deserialize(&msg).await
+    }
+
     async fn send_tx(
         &self,
         tx: ZkSyncTx,
@@ -324,7 +341,7 @@ mod messages {
     use serde::Serialize;
     use zksync_types::{
         tx::{PackedEthSignature, TxEthSignature, TxHash, ZkSyncTx},
-        Address, TokenLike, TxFeeTypes,
+        Address, TokenId, TokenLike, TxFeeTypes,
     };
 
     #[derive(Debug, Serialize)]
@@ -412,6 +429,14 @@ mod messages {
             Self::create("get_eth_tx_for_withdrawal", json_values![withdrawal_hash])
         }
 
+        pub fn get_nft(token_id: TokenId) -> Self {
+            Self::create("get_nft", json_values![token_id])
+        }
+
+        pub fn get_nft_owner(token_id: TokenId) -> Self {
+            Self::create("get_nft_owner", json_values![token_id])
+        }
+
         pub fn get_tx_fee(tx_type: TxFeeTypes, address: Address, token_symbol: TokenLike) -> Self {
             let params = json_values![tx_type, address, token_symbol];
             Self::create("get_tx_fee", params)
@@ -17,6 +17,22 @@ pub struct NFT {
     pub content_hash: H256,
 }
 
+/// NFT as it was minted, along with the factories able to withdraw it to L1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NFTInfo {
+    pub id: TokenId,
+    pub serial_id: u32,
+    pub creator_id: AccountId,
+    pub creator_address: Address,
+    pub content_hash: H256,
+    /// L2 address of the NFT, computed from the creator and the content hash.
+    pub address: Address,
+    pub symbol: String,
+    pub current_factory: Address,
+    pub withdrawn_factory: Option<Address>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountState {
@@ -32,6 +48,8 @@ pub struct DepositingFunds {
     #[serde(with = "BigUintSerdeAsRadix10Str")]
     amount: BigUint,
     expected_accept_block: u64,
+    #[serde(default)]
+    confirmations_left: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -52,7 +70,8 @@ pub enum BlockStatus {
 pub struct AccountInfo {
     pub address: Address,
     pub id: Option<AccountId>,
-    pub depositing: DepositingAccountBalances,
+    /// `None` if the server couldn't get the pending deposits from the Ethereum watcher.
+    pub depositing: Option<DepositingAccountBalances>,
     pub committed: AccountState,
     pub verified: AccountState,
 }
@@ -65,9 +84,24 @@ pub struct BlockInfo {
     pub verified: bool,
 }
 
+/// Whether the transaction is known to the server and how far it has got.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TxInfoStatus {
+    /// The transaction was never seen by the server.
+    NotFound,
+    /// The transaction is in the mempool waiting to be executed.
+    Queued,
+    /// The transaction was executed, successfully or not.
+    Executed,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionInfo {
+    /// Absent in the responses of the older servers.
+    #[serde(default)]
+    pub status: Option<TxInfoStatus>,
     pub executed: bool,
     pub success: Option<bool>,
     pub fail_reason: Option<String>,
@@ -100,6 +134,15 @@ impl EthOpInfo {
 pub struct ContractAddress {
     pub main_contract: String,
     pub gov_contract: String,
+    /// Address of the default NFT factory, `None` if it's not known to the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nft_factory_contract: Option<String>,
+    /// Current target of the main contract, `None` if the contract has never been upgraded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_target: Option<String>,
+    /// Version of the contracts set by the latest upgrade, `None` if the contracts have never been upgraded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract_upgrade_version: Option<u64>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
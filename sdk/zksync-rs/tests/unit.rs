@@ -514,8 +514,8 @@ mod wallet_tests {
         provider::Provider,
         signer::Signer,
         types::{
-            AccountInfo, AccountState, BlockStatus, ContractAddress, EthOpInfo, Fee, Tokens,
-            TransactionInfo,
+            AccountInfo, AccountState, BlockStatus, ContractAddress, EthOpInfo, Fee, NFTInfo,
+            Tokens, TransactionInfo,
         },
         Network, Wallet, WalletCredentials,
     };
@@ -640,9 +640,23 @@ mod wallet_tests {
             Ok(ContractAddress {
                 main_contract: "0x000102030405060708090a0b0c0d0e0f10111213".to_string(),
                 gov_contract: "".to_string(),
+                nft_factory_contract: None,
+                contract_target: None,
+                contract_upgrade_version: None,
             })
         }
 
+        async fn get_nft(&self, _token_id: TokenId) -> Result<Option<NFTInfo>, ClientError> {
+            unreachable!()
+        }
+
+        async fn get_nft_owner(
+            &self,
+            _token_id: TokenId,
+        ) -> Result<Option<AccountId>, ClientError> {
+            unreachable!()
+        }
+
         async fn send_tx(
             &self,
             _tx: ZkSyncTx,
@@ -8,7 +8,8 @@ import {
     TxEthSignature,
     ChangePubkeyTypes,
     TotalFee,
-    Order
+    Order,
+    SubmissionLimits
 } from './types';
 import { MAX_TIMESTAMP } from './utils';
 import { AbstractWallet } from './abstract-wallet';
@@ -36,6 +37,29 @@ export interface BatchBuilderInternalTx {
     alreadySigned?: boolean;
 }
 
+/**
+ * Minimal amount of chunks occupied by each transaction type in the block.
+ * Transfers to the new accounts take more chunks, so the actual amount may be higher.
+ */
+const MIN_TX_CHUNKS: { [type in BatchBuilderInternalTx['type']]: number } = {
+    Transfer: 2,
+    Withdraw: 6,
+    ChangePubKey: 6,
+    ForcedExit: 6,
+    Swap: 5,
+    MintNFT: 5,
+    WithdrawNFT: 10
+};
+
+/**
+ * Thrown by `BatchBuilder` when the batch exceeds one of the server submission limits.
+ */
+export class SubmissionLimitError extends Error {
+    constructor(public limit: keyof SubmissionLimits, public value: number, public max: number) {
+        super(`Batch exceeds the submission limit ${limit}: ${value} > ${max}`);
+    }
+}
+
 /**
  * Provides interface for constructing batches of transactions.
  */
@@ -65,6 +89,7 @@ export class BatchBuilder {
         if (feeToken != undefined) {
             await this.setFeeToken(feeToken);
         }
+        await this.checkSubmissionLimits();
         // Gather total fee for every token.
         const totalFee: TotalFee = new Map();
         for (const tx of this.txs) {
@@ -87,6 +112,29 @@ export class BatchBuilder {
         };
     }
 
+    /**
+     * Checks the batch against the server submission limits, so it's rejected before being signed.
+     * The amount of chunks is a lower bound, so the server may still reject the batch.
+     */
+    private async checkSubmissionLimits() {
+        const limits = await this.wallet.provider.getSubmissionLimits();
+        if (limits == undefined) {
+            return;
+        }
+        const withdrawals = this.txs.filter((tx) => ['Withdraw', 'ForcedExit', 'WithdrawNFT'].includes(tx.type)).length;
+        const chunks = this.txs.reduce((total, tx) => total + MIN_TX_CHUNKS[tx.type], 0);
+        const checks: [keyof SubmissionLimits, number][] = [
+            ['maxTransactionsPerBatch', this.txs.length],
+            ['maxWithdrawalsPerBatch', withdrawals],
+            ['maxChunksPerBatch', chunks]
+        ];
+        for (const [limit, value] of checks) {
+            if (value > limits[limit]) {
+                throw new SubmissionLimitError(limit, value, limits[limit]);
+            }
+        }
+    }
+
     private async setFeeToken(feeToken: TokenLike) {
         // If user specified a token he wants to pay with, we expect all fees to be zero
         // and no signed transactions in the batch.
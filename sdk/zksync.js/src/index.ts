@@ -6,6 +6,7 @@ export { SyncProvider } from './provider-interface';
 export { Signer, Create2WalletSigner, No2FAWalletSigner } from './signer';
 export { closestPackableTransactionAmount, closestPackableTransactionFee } from './utils';
 export { EthMessageSigner } from './eth-message-signer';
+export { SubmissionLimitError } from './batch-builder';
 
 export * as wallet from './wallet';
 export * as types from './types';
@@ -12,7 +12,8 @@ import {
     TxEthSignatureVariant,
     NFTInfo,
     Toggle2FARequest,
-    Network
+    Network,
+    SubmissionLimits
 } from './types';
 import { BigNumber } from 'ethers';
 import { TokenSet, isNFT } from './utils';
@@ -65,5 +66,12 @@ export abstract class SyncProvider {
         }
         return this.tokenSet.resolveTokenSymbol(token);
     }
+    /**
+     * Returns the limits the server applies to the submitted transactions,
+     * or `undefined` if the provider doesn't expose them.
+     */
+    async getSubmissionLimits(): Promise<SubmissionLimits | undefined> {
+        return undefined;
+    }
     async disconnect() {}
 }
@@ -48,6 +48,7 @@ export interface Error {
 
 export interface Response<T> {
     request: Request;
+    requestId: string;
     status: 'success' | 'error';
     error?: Error;
     result?: T;
@@ -179,6 +180,50 @@ export class RestProvider extends SyncProvider {
         return this.parseResponse(await this.accountPendingTxsDetailed(idOrAddress, paginationQuery));
     }
 
+    async accountPendingPriorityOpsDetailed(
+        idOrAddress: number | types.Address
+    ): Promise<Response<types.ApiPendingPriorityOp[]>> {
+        return await this.get(`${this.address}/accounts/${idOrAddress}/pending_priority_ops`);
+    }
+
+    async accountPendingPriorityOps(idOrAddress: number | types.Address): Promise<types.ApiPendingPriorityOp[]> {
+        return this.parseResponse(await this.accountPendingPriorityOpsDetailed(idOrAddress));
+    }
+
+    async accountQueuedTxsDetailed(
+        idOrAddress: number | types.Address,
+        paginationQuery: types.PaginationQuery<string>
+    ): Promise<Response<types.Paginated<types.ApiTransaction, string>>> {
+        return await this.get(
+            `${this.address}/accounts/${idOrAddress}/pending?from=${paginationQuery.from}` +
+                `&limit=${paginationQuery.limit}&direction=${paginationQuery.direction}`
+        );
+    }
+
+    async accountQueuedTxs(
+        idOrAddress: number | types.Address,
+        paginationQuery: types.PaginationQuery<string>
+    ): Promise<types.Paginated<types.ApiTransaction, string>> {
+        return this.parseResponse(await this.accountQueuedTxsDetailed(idOrAddress, paginationQuery));
+    }
+
+    async accountNFTsDetailed(
+        idOrAddress: number | types.Address,
+        paginationQuery: types.PaginationQuery<number>
+    ): Promise<Response<types.Paginated<types.NFT, number>>> {
+        return await this.get(
+            `${this.address}/accounts/${idOrAddress}/nfts?from=${paginationQuery.from}` +
+                `&limit=${paginationQuery.limit}&direction=${paginationQuery.direction}`
+        );
+    }
+
+    async accountNFTs(
+        idOrAddress: number | types.Address,
+        paginationQuery: types.PaginationQuery<number>
+    ): Promise<types.Paginated<types.NFT, number>> {
+        return this.parseResponse(await this.accountNFTsDetailed(idOrAddress, paginationQuery));
+    }
+
     async blockPaginationDetailed(
         paginationQuery: types.PaginationQuery<number>
     ): Promise<Response<types.Paginated<types.ApiBlockInfo, number>>> {
@@ -204,19 +249,22 @@ export class RestProvider extends SyncProvider {
 
     async blockTransactionsDetailed(
         blockPosition: types.BlockPosition,
-        paginationQuery: types.PaginationQuery<string>
+        paginationQuery: types.PaginationQuery<string>,
+        txType?: types.ApiTxType
     ): Promise<Response<types.Paginated<types.ApiTransaction, string>>> {
-        return await this.get(
+        let url =
             `${this.address}/blocks/${blockPosition}/transactions?from=${paginationQuery.from}` +
-                `&limit=${paginationQuery.limit}&direction=${paginationQuery.direction}`
-        );
+            `&limit=${paginationQuery.limit}&direction=${paginationQuery.direction}`;
+        if (txType) url += `&txType=${txType}`;
+        return await this.get(url);
     }
 
     async blockTransactions(
         blockPosition: types.BlockPosition,
-        paginationQuery: types.PaginationQuery<string>
+        paginationQuery: types.PaginationQuery<string>,
+        txType?: types.ApiTxType
     ): Promise<types.Paginated<types.ApiTransaction, string>> {
-        return this.parseResponse(await this.blockTransactionsDetailed(blockPosition, paginationQuery));
+        return this.parseResponse(await this.blockTransactionsDetailed(blockPosition, paginationQuery, txType));
     }
 
     async configDetailed(): Promise<Response<types.ApiConfig>> {
@@ -227,12 +275,20 @@ export class RestProvider extends SyncProvider {
         return this.parseResponse(await this.configDetailed());
     }
 
+    async submissionLimitsDetailed(): Promise<Response<types.SubmissionLimits>> {
+        return await this.get(`${this.address}/transactions/limits`);
+    }
+
+    async submissionLimits(): Promise<types.SubmissionLimits> {
+        return this.parseResponse(await this.submissionLimitsDetailed());
+    }
+
     async getTransactionFeeDetailed(
         txType: types.IncomingTxFeeType,
         address: types.Address,
         tokenLike: types.TokenLike
     ): Promise<Response<types.FeeRest>> {
-        const rawFee = await this.post<{ gasFee: string; zkpFee: string; totalFee: string }>(`${this.address}/fee`, {
+        const rawFee = await this.post<types.RawFeeRest>(`${this.address}/fee`, {
             txType,
             address,
             tokenLike
@@ -246,7 +302,9 @@ export class RestProvider extends SyncProvider {
                 result: {
                     gasFee: BigNumber.from(rawFee.result.gasFee),
                     zkpFee: BigNumber.from(rawFee.result.zkpFee),
-                    totalFee: BigNumber.from(rawFee.result.totalFee)
+                    totalFee: BigNumber.from(rawFee.result.totalFee),
+                    gasPriceWei: BigNumber.from(rawFee.result.gasPriceWei),
+                    totalFeeUsd: rawFee.result.totalFeeUsd
                 }
             };
         } else {
@@ -275,10 +333,7 @@ export class RestProvider extends SyncProvider {
         }[],
         tokenLike: types.TokenLike
     ): Promise<Response<types.FeeRest>> {
-        const rawFee = await this.post<{ gasFee: string; zkpFee: string; totalFee: string }>(
-            `${this.address}/fee/batch`,
-            { transactions, tokenLike }
-        );
+        const rawFee = await this.post<types.RawFeeRest>(`${this.address}/fee/batch`, { transactions, tokenLike });
         let fee: Response<types.FeeRest>;
         if (rawFee.status === 'success') {
             fee = {
@@ -288,7 +343,9 @@ export class RestProvider extends SyncProvider {
                 result: {
                     gasFee: BigNumber.from(rawFee.result.gasFee),
                     zkpFee: BigNumber.from(rawFee.result.zkpFee),
-                    totalFee: BigNumber.from(rawFee.result.totalFee)
+                    totalFee: BigNumber.from(rawFee.result.totalFee),
+                    gasPriceWei: BigNumber.from(rawFee.result.gasPriceWei),
+                    totalFeeUsd: rawFee.result.totalFeeUsd
                 }
             };
         } else {
@@ -320,6 +377,14 @@ export class RestProvider extends SyncProvider {
         return this.parseResponse(await this.networkStatusDetailed());
     }
 
+    async searchDetailed(query: string): Promise<Response<types.ApiSearchMatch[]>> {
+        return await this.get(`${this.address}/search?query=${encodeURIComponent(query)}`);
+    }
+
+    async search(query: string): Promise<types.ApiSearchMatch[]> {
+        return this.parseResponse(await this.searchDetailed(query));
+    }
+
     async tokenPaginationDetailed(
         paginationQuery: types.PaginationQuery<number>
     ): Promise<Response<types.Paginated<types.TokenInfo, number>>> {
@@ -345,21 +410,24 @@ export class RestProvider extends SyncProvider {
 
     async tokenPriceInfoDetailed(
         tokenLike: types.TokenLike,
-        tokenIdOrUsd: number | 'usd'
+        currency: types.TokenLike | 'usd'
     ): Promise<Response<types.TokenPriceInfo>> {
-        return await this.get(`${this.address}/tokens/${tokenLike}/priceIn/${tokenIdOrUsd}`);
+        return await this.get(`${this.address}/tokens/${tokenLike}/priceIn/${currency}`);
     }
 
-    async tokenPriceInfo(tokenLike: types.TokenLike, tokenIdOrUsd: number | 'usd'): Promise<types.TokenPriceInfo> {
-        return this.parseResponse(await this.tokenPriceInfoDetailed(tokenLike, tokenIdOrUsd));
+    async tokenPriceInfo(tokenLike: types.TokenLike, currency: types.TokenLike | 'usd'): Promise<types.TokenPriceInfo> {
+        return this.parseResponse(await this.tokenPriceInfoDetailed(tokenLike, currency));
     }
 
-    async submitTxNewDetailed(tx: types.L2Tx, signature?: types.TxEthSignatureVariant): Promise<Response<string>> {
+    async submitTxNewDetailed(
+        tx: types.L2Tx,
+        signature?: types.TxEthSignatureVariant
+    ): Promise<Response<types.SubmitTxResponse>> {
         return await this.post(`${this.address}/transactions`, { tx, signature });
     }
 
     async submitTxNew(tx: types.L2Tx, signature?: types.TxEthSignatureVariant): Promise<string> {
-        return this.parseResponse(await this.submitTxNewDetailed(tx, signature));
+        return this.parseResponse(await this.submitTxNewDetailed(tx, signature)).txHash;
     }
 
     /**
@@ -382,6 +450,14 @@ export class RestProvider extends SyncProvider {
         return this.parseResponse(await this.txStatusDetailed(txHash));
     }
 
+    async txStatusesDetailed(txHashes: string[]): Promise<Response<types.ApiTxStatuses>> {
+        return await this.post(`${this.address}/transactions/status`, txHashes);
+    }
+
+    async txStatuses(txHashes: string[]): Promise<types.ApiTxStatuses> {
+        return this.parseResponse(await this.txStatusesDetailed(txHashes));
+    }
+
     async txDataDetailed(txHash: string): Promise<Response<types.ApiSignedTx>> {
         return await this.get(`${this.address}/transactions/${txHash}/data`);
     }
@@ -390,6 +466,14 @@ export class RestProvider extends SyncProvider {
         return this.parseResponse(await this.txDataDetailed(txHash));
     }
 
+    async txFinalityDetailed(txHash: string): Promise<Response<types.ApiTxFinality>> {
+        return await this.get(`${this.address}/transactions/${txHash}/finality`);
+    }
+
+    async txFinality(txHash: string): Promise<types.ApiTxFinality> {
+        return this.parseResponse(await this.txFinalityDetailed(txHash));
+    }
+
     async submitTxsBatchNewDetailed(
         txs: { tx: any; signature?: types.TxEthSignatureVariant }[],
         signature?: types.TxEthSignature | types.TxEthSignature[]
@@ -586,6 +670,10 @@ export class RestProvider extends SyncProvider {
         return config.depositConfirmations;
     }
 
+    override async getSubmissionLimits(): Promise<types.SubmissionLimits> {
+        return await this.submissionLimits();
+    }
+
     async getTransactionsBatchFee(
         txTypes: types.IncomingTxFeeType[],
         addresses: types.Address[],
@@ -78,6 +78,8 @@ export interface Depositing {
             // Value denoting the block number when the funds are expected
             // to be received by zkSync network.
             expectedAcceptBlock: number;
+            // Number of Ethereum blocks left until the last of the deposits is accepted.
+            confirmationsLeft?: number;
         };
     };
 }
@@ -86,7 +88,8 @@ export interface AccountState {
     address: Address;
     id?: number;
     accountType?: EthAccountType;
-    depositing: Depositing;
+    // `null` if the server couldn't get the pending deposits from the Ethereum watcher.
+    depositing: Depositing | null;
     committed: {
         balances: {
             // Token are indexed by their symbol (e.g. "ETH")
@@ -448,19 +451,53 @@ export interface ApiAccountFullInfo {
     finalized: ApiAccountInfo;
 }
 
+export interface ApiPendingPriorityOp {
+    serialId: number;
+    opType: 'Deposit' | 'FullExit';
+    tokenId: number;
+    amount?: string;
+    ethHash: string;
+    ethBlock: number;
+    confirmationsLeft: number;
+    status: 'unconfirmed' | 'confirmed' | 'executed';
+}
+
 export interface ApiConfig {
     network: Network;
     contract: Address;
     govContract: Address;
     depositConfirmations: number;
     zksyncVersion: 'contractV4';
+    submissionLimits: SubmissionLimits;
+    nftFactory?: Address;
+    maxBlockChunks: number;
     // TODO: server_version (ZKS-627)
 }
 
+export interface SubmissionLimits {
+    maxTransactionsPerBatch: number;
+    maxAuthorsPerBatch: number;
+    maxChunksPerBatch: number;
+    maxWithdrawalsPerBatch: number;
+    nonceAheadWindow: number;
+}
+
 export interface FeeRest {
     gasFee: BigNumber;
     zkpFee: BigNumber;
     totalFee: BigNumber;
+    // Gas price used to calculate the gas part of fee (in wei)
+    gasPriceWei: BigNumber;
+    // Total fee amount in USD
+    totalFeeUsd: string;
+}
+
+export interface RawFeeRest {
+    gasFee: string;
+    zkpFee: string;
+    totalFee: string;
+    gasPriceWei: string;
+    totalFeeUsd: string;
 }
 
 export interface NetworkStatus {
@@ -486,6 +523,12 @@ export interface TokenPriceInfo {
     price: string;
 }
 
+export interface SubmitTxResponse {
+    txHash: string;
+    // Hash of the queued transaction with the same nonce replaced by the submitted one.
+    replacedTxHash: string | null;
+}
+
 export interface SubmitBatchResponse {
     transactionHashes: string[];
     batchHash: string;
@@ -500,15 +543,34 @@ export interface ApiL1TxReceipt {
 
 export type L2TxStatus = 'queued' | 'committed' | 'finalized' | 'rejected';
 
+export type FailReasonCode =
+    | 'insufficientBalance'
+    | 'nonceMismatch'
+    | 'invalidSignature'
+    | 'expiredTimeRange'
+    | 'other';
+
+export type ApiEthSignatureType = 'ethereum' | 'eip1271' | 'none';
+
 export interface ApiL2TxReceipt {
     txHash: string;
     rollupBlock?: number;
+    blockIndex?: number;
     status: L2TxStatus;
     failReason?: string;
+    failReasonCode?: FailReasonCode;
+    batchId?: number;
+    batchIndex?: number;
+    // Only set for the transactions that are still queued.
+    createdAt?: string;
+    ethSignatureType?: ApiEthSignatureType;
 }
 
 export type ApiTxReceipt = ApiL1TxReceipt | ApiL2TxReceipt;
 
+// Receipts of the requested transactions, `null` for the unknown ones.
+export type ApiTxStatuses = { [txHash: string]: ApiTxReceipt | null };
+
 export interface WithdrawData {
     type: 'Withdraw';
     accountId: number;
@@ -587,19 +649,57 @@ export type L2TxData =
 
 export type TransactionData = L2TxData | ApiDeposit | ApiFullExit;
 
+export type ApiTxType =
+    | 'Transfer'
+    | 'Withdraw'
+    | 'ChangePubKey'
+    | 'Deposit'
+    | 'FullExit'
+    | 'ForcedExit'
+    | 'MintNFT'
+    | 'WithdrawNFT'
+    | 'Swap';
+
 export interface ApiTransaction {
     txHash: string;
     blockNumber?: number;
     op: TransactionData;
     status: L2TxStatus;
     failReason?: string;
+    failReasonCode?: FailReasonCode;
     createdAt?: string;
     batchId?: number;
 }
 
+export type ApiSearchMatch =
+    | { kind: 'block'; blockNumber: number; path: string }
+    | { kind: 'transaction'; txHash: string; path: string }
+    | { kind: 'account'; accountId: number; address: Address; path: string };
+
+export interface ApiMintedNFT {
+    tokenId: number;
+    serialId: number;
+    contentHash: string;
+    address: string;
+}
+
 export interface ApiSignedTx {
     tx: ApiTransaction;
     ethSignature?: string;
+    nft?: ApiMintedNFT;
+}
+
+export interface ApiFinalityStage {
+    ethTxHash: string;
+    confirmations: number;
+}
+
+export interface ApiTxFinality {
+    txHash: string;
+    rollupBlock: number | null;
+    commit: ApiFinalityStage | null;
+    prove: ApiFinalityStage | null;
+    execute: ApiFinalityStage | null;
 }
 
 export interface ApiBatchStatus {
@@ -612,6 +712,7 @@ export interface ApiBatchData {
     transactionHashes: string[];
     createdAt: string;
     batchStatus: ApiBatchStatus;
+    blockNumber?: number;
 }
 
 export interface Toggle2FARequest {
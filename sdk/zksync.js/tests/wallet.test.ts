@@ -5,6 +5,7 @@ import { getTokens } from 'reading-tool';
 
 import { Provider } from '../src/provider';
 import { Network } from '../src/types';
+import { SubmissionLimitError } from '../src/batch-builder';
 
 describe('Wallet with mock provider', function () {
     async function getWallet(ethPrivateKey: Uint8Array, network: Network): Promise<Wallet> {
@@ -88,4 +89,33 @@ describe('Wallet with mock provider', function () {
         const wallet = await getWallet(key, 'mainnet');
         expect(await wallet.isSigningKeySet()).eq(true, "Wallet's signing key is unset");
     });
+
+    it('Batch exceeding the submission limits is rejected before signing', async function () {
+        const key = new Uint8Array(new Array(32).fill(70));
+        const wallet = await getWallet(key, 'mainnet');
+        wallet.provider.getSubmissionLimits = async () => ({
+            maxTransactionsPerBatch: 10,
+            maxAuthorsPerBatch: 10,
+            maxChunksPerBatch: 100,
+            maxWithdrawalsPerBatch: 1,
+            nonceAheadWindow: 10
+        });
+
+        const batch = wallet.batchBuilder();
+        for (let i = 0; i < 2; i++) {
+            batch.addWithdraw({ ethAddress: wallet.address(), token: 'DAI', amount: 1 });
+        }
+
+        let error: any;
+        try {
+            await batch.build();
+        } catch (e) {
+            error = e;
+        }
+
+        expect(error, 'build call was expected to throw an exception').to.be.instanceOf(SubmissionLimitError);
+        expect(error.limit).eq('maxWithdrawalsPerBatch');
+        expect(error.value).eq(2);
+        expect(error.max).eq(1);
+    });
 });